@@ -0,0 +1,64 @@
+// Database benchmarks against a seeded PostgreSQL database
+//
+//   cargo run --bin historical-import -- seed --gauges 5 --seed 42 -y
+//   DATABASE_URL=postgres://... cargo bench --bench database
+//
+// Bulk inserts are rolled back, so the database is unchanged afterwards. Without
+// DATABASE_URL the benchmarks are skipped.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use rain_tracker_service::services::bench_service::{
+    self, BenchService, DEFAULT_BENCH_STATION, DEFAULT_BULK_INSERT_READINGS,
+};
+use sqlx::postgres::PgPoolOptions;
+use tokio::runtime::Runtime;
+
+fn database(c: &mut Criterion) {
+    let Ok(url) = std::env::var("DATABASE_URL") else {
+        eprintln!("DATABASE_URL is not set; skipping database benchmarks");
+        return;
+    };
+    let rt = Runtime::new().expect("tokio runtime");
+    let pool = rt
+        .block_on(PgPoolOptions::new().max_connections(2).connect(&url))
+        .expect("Failed to connect to DATABASE_URL");
+    let service = BenchService::new(pool);
+    let water_year = service.default_water_year();
+
+    let seeded = rt
+        .block_on(service.water_year_summary(DEFAULT_BENCH_STATION, water_year))
+        .expect("water year query failed");
+    if seeded == 0 {
+        eprintln!(
+            "Station {DEFAULT_BENCH_STATION} has no readings in water year {water_year}; run `historical-import seed` first"
+        );
+        return;
+    }
+
+    let station_id = DEFAULT_BENCH_STATION.parse().unwrap();
+    let readings = bench_service::synthetic_readings(&station_id, DEFAULT_BULK_INSERT_READINGS);
+    c.bench_function("bulk_insert", |b| {
+        b.to_async(&rt).iter(|| async {
+            service
+                .bulk_insert_rolled_back(DEFAULT_BENCH_STATION, &readings)
+                .await
+                .unwrap()
+        })
+    });
+
+    c.bench_function("water_year_query", |b| {
+        b.to_async(&rt).iter(|| async {
+            service
+                .water_year_summary(DEFAULT_BENCH_STATION, water_year)
+                .await
+                .unwrap()
+        })
+    });
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(20);
+    targets = database
+}
+criterion_main!(benches);
@@ -0,0 +1,30 @@
+// Parser benchmarks on the checked-in sample workbooks
+//
+//   cargo bench --bench parsers
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use rain_tracker_service::services::bench_service;
+
+const WATER_YEAR_FILE: &str = "sample-data-files/pcp_WY_2023.xlsx";
+const FOPR_FILE: &str = "sample-data-files/59700_FOPR.xlsx";
+
+fn excel_parse(c: &mut Criterion) {
+    c.bench_function("excel_parse", |b| {
+        b.iter(|| bench_service::parse_water_year_excel(WATER_YEAR_FILE, 2023).unwrap())
+    });
+}
+
+fn fopr_parse(c: &mut Criterion) {
+    let station_id = "59700".parse().unwrap();
+    c.bench_function("fopr_parse", |b| {
+        b.iter(|| bench_service::parse_fopr_daily(FOPR_FILE, &station_id).unwrap())
+    });
+}
+
+criterion_group! {
+    name = benches;
+    // Each iteration opens and parses a whole workbook
+    config = Criterion::default().sample_size(20);
+    targets = excel_parse, fopr_parse
+}
+criterion_main!(benches);
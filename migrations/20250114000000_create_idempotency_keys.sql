@@ -0,0 +1,29 @@
+-- Stored responses for requests sent with an Idempotency-Key header
+--
+-- A retried POST with the same key and body replays the stored response instead of
+-- running the operation again. A row with NULL status_code is a request still in
+-- flight. Keys are scoped per method and path, and expire after 24 hours.
+
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    idempotency_key VARCHAR(255) NOT NULL,
+    method VARCHAR(10) NOT NULL,
+    path TEXT NOT NULL,
+
+    -- SHA-256 of method, path, and body; a reused key with a different request is rejected
+    request_hash CHAR(64) NOT NULL,
+
+    -- Stored response (NULL until the request completes)
+    status_code SMALLINT,
+    content_type TEXT,
+    response_body BYTEA,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ,
+
+    PRIMARY KEY (idempotency_key, method, path)
+);
+
+CREATE INDEX idx_idempotency_keys_created_at ON idempotency_keys(created_at);
+
+COMMENT ON TABLE idempotency_keys IS
+'Responses replayed for retried requests with the same Idempotency-Key (24h retention)';
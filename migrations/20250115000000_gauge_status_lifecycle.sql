@@ -0,0 +1,31 @@
+-- Gauge status lifecycle
+--
+-- gauges.status moves between Active, Inactive, and Decommissioned. Each transition is
+-- recorded in gauge_status_history with the date it took effect, either set by an admin
+-- or detected automatically when a gauge drops out of the scraped gauge list.
+-- Decommissioned gauges are soft-deleted: hidden from listings by default, but their
+-- readings and summaries are kept.
+
+UPDATE gauges SET status = 'Active' WHERE status IS NULL;
+
+ALTER TABLE gauges
+    ALTER COLUMN status SET NOT NULL,
+    ADD CONSTRAINT chk_gauges_status CHECK (status IN ('Active', 'Inactive', 'Decommissioned')),
+    ADD COLUMN status_effective_date DATE;
+
+CREATE TABLE IF NOT EXISTS gauge_status_history (
+    id BIGSERIAL PRIMARY KEY,
+    station_id VARCHAR(20) NOT NULL REFERENCES gauges(station_id) ON DELETE CASCADE,
+    previous_status VARCHAR(50) NOT NULL,
+    status VARCHAR(50) NOT NULL,
+    effective_date DATE NOT NULL,
+    reason TEXT,
+    changed_by VARCHAR(50) NOT NULL,          -- 'admin' or 'auto_detection'
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_gauge_status_history_station
+    ON gauge_status_history(station_id, created_at DESC);
+
+COMMENT ON COLUMN gauges.status_effective_date IS 'Date the current status took effect (NULL = since import)';
+COMMENT ON TABLE gauge_status_history IS 'Audit trail of gauge status transitions';
@@ -0,0 +1,22 @@
+-- Gauge attachments (site photos, FOPR PDFs)
+--
+-- File contents live in the object store (ATTACHMENT_STORAGE_DIR); this table holds the
+-- metadata and the object key. Keys are content-addressed per gauge
+-- (`gauges/<station_id>/<sha256>`), so re-uploading the same file reuses the object.
+
+CREATE TABLE IF NOT EXISTS gauge_attachments (
+    id BIGSERIAL PRIMARY KEY,
+    station_id VARCHAR(20) NOT NULL REFERENCES gauges(station_id) ON DELETE CASCADE,
+    filename VARCHAR(255) NOT NULL,
+    content_type VARCHAR(100) NOT NULL,
+    size_bytes BIGINT NOT NULL CHECK (size_bytes > 0),
+    sha256 CHAR(64) NOT NULL,
+    storage_key TEXT NOT NULL,
+    description TEXT,
+    uploaded_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_gauge_attachments_station
+    ON gauge_attachments(station_id, uploaded_at DESC);
+
+COMMENT ON TABLE gauge_attachments IS 'Files associated with a gauge; contents are in the object store';
@@ -0,0 +1,25 @@
+-- Gauge annotations
+--
+-- Free-text notes that give unusual data context ("sensor clogged 3/2-3/5"). A note
+-- covers an inclusive date range, is open-ended when end_date is NULL (still ongoing),
+-- or applies to the gauge as a whole when both dates are NULL. Notes overlapping a
+-- water or calendar year are returned with that year's readings.
+
+CREATE TABLE IF NOT EXISTS gauge_annotations (
+    id BIGSERIAL PRIMARY KEY,
+    station_id VARCHAR(20) NOT NULL REFERENCES gauges(station_id) ON DELETE CASCADE,
+    start_date DATE,
+    end_date DATE,
+    note TEXT NOT NULL,
+    author VARCHAR(100) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT chk_gauge_annotations_range CHECK (
+        end_date IS NULL OR (start_date IS NOT NULL AND end_date >= start_date)
+    )
+);
+
+CREATE INDEX IF NOT EXISTS idx_gauge_annotations_station
+    ON gauge_annotations(station_id, start_date);
+
+COMMENT ON TABLE gauge_annotations IS 'Notes explaining unusual gauge data, optionally for a date range';
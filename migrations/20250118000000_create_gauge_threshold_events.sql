@@ -0,0 +1,27 @@
+-- Rainfall threshold crossings
+--
+-- After each gauge list scrape, a gauge's 24-hour total is compared against the
+-- configured thresholds (RAINFALL_THRESHOLDS_INCHES, default 0.5/1/2 inches). An event
+-- opens when the total first reaches a threshold, tracks the peak while it stays at or
+-- above it, and closes (ended_at) on the first scrape below it. station_id has no
+-- foreign key: gauges in the scraped list may not have FOPR metadata yet.
+
+CREATE TABLE IF NOT EXISTS gauge_threshold_events (
+    id BIGSERIAL PRIMARY KEY,
+    station_id VARCHAR(20) NOT NULL,
+    threshold_inches DOUBLE PRECISION NOT NULL,
+    crossed_at TIMESTAMPTZ NOT NULL,
+    ended_at TIMESTAMPTZ,                              -- NULL while still at or above
+    rainfall_24h_inches DOUBLE PRECISION NOT NULL,     -- 24h total when first detected
+    peak_rainfall_24h_inches DOUBLE PRECISION NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_threshold_events_station
+    ON gauge_threshold_events(station_id, crossed_at DESC);
+
+-- At most one open event per gauge and threshold
+CREATE UNIQUE INDEX IF NOT EXISTS idx_threshold_events_open
+    ON gauge_threshold_events(station_id, threshold_inches)
+    WHERE ended_at IS NULL;
+
+COMMENT ON TABLE gauge_threshold_events IS 'Times a gauge''s 24h rainfall reached a configured threshold';
@@ -0,0 +1,2 @@
+-- Revert 20250119000000: the table is rebuilt by the schedulers, so nothing is lost
+DROP TABLE IF EXISTS current_conditions;
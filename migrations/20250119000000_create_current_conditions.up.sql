@@ -0,0 +1,30 @@
+-- Precomputed current conditions for the dashboard
+--
+-- The dashboard needs every gauge with its latest totals and water-year-to-date
+-- rainfall, which otherwise means joining gauge_summaries, gauges, rain_readings, and
+-- monthly_rainfall_summary on every request. The schedulers rebuild this table after
+-- each scrape so GET /api/v1/current is a single-table read. Rows mirror the gauge
+-- list: a gauge that drops out of gauge_summaries is removed on the next refresh.
+
+CREATE TABLE IF NOT EXISTS current_conditions (
+    station_id VARCHAR(20) PRIMARY KEY,
+    gauge_name VARCHAR(255) NOT NULL,
+    city_town VARCHAR(255),
+    status VARCHAR(50) NOT NULL,
+    latitude DOUBLE PRECISION,
+    longitude DOUBLE PRECISION,
+    elevation_ft INTEGER,
+    rainfall_past_6h_inches DOUBLE PRECISION,
+    rainfall_past_24h_inches DOUBLE PRECISION,
+    latest_reading_at TIMESTAMPTZ,
+    latest_cumulative_inches DOUBLE PRECISION,
+    water_year INTEGER NOT NULL,
+    water_year_to_date_inches DOUBLE PRECISION NOT NULL,
+    last_scraped_at TIMESTAMPTZ NOT NULL,
+    refreshed_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_current_conditions_order
+    ON current_conditions(city_town, gauge_name);
+
+COMMENT ON TABLE current_conditions IS 'Per-gauge dashboard snapshot, rebuilt by the schedulers after each scrape';
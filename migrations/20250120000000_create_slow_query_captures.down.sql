@@ -0,0 +1,2 @@
+-- Revert 20250120000000: captures are diagnostics only
+DROP TABLE IF EXISTS slow_query_captures;
@@ -0,0 +1,22 @@
+-- EXPLAIN plans of slow queries
+--
+-- With SLOW_QUERY_EXPLAIN=true, a watched query that takes longer than
+-- SLOW_QUERY_THRESHOLD_MS is re-run under EXPLAIN (ANALYZE, BUFFERS, FORMAT JSON) in a
+-- rolled-back transaction and its plan is stored here for GET /api/v1/admin/slow-queries.
+-- Bound parameter values are never stored, only how many there were.
+
+CREATE TABLE IF NOT EXISTS slow_query_captures (
+    id BIGSERIAL PRIMARY KEY,
+    query_name VARCHAR(100) NOT NULL,   -- Repository method that ran the query
+    sql TEXT NOT NULL,                  -- With $n placeholders
+    param_count INTEGER NOT NULL,
+    duration_ms DOUBLE PRECISION NOT NULL,
+    plan JSONB,                         -- NULL when EXPLAIN itself failed
+    explain_error TEXT,
+    captured_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_slow_query_captures_captured_at
+    ON slow_query_captures(captured_at DESC);
+
+COMMENT ON TABLE slow_query_captures IS 'EXPLAIN ANALYZE plans of queries slower than SLOW_QUERY_THRESHOLD_MS';
@@ -0,0 +1,4 @@
+-- Revert 20250121000000: month_start is derived from year and month
+DROP INDEX IF EXISTS idx_monthly_summary_month_start;
+DROP INDEX IF EXISTS idx_monthly_summary_station_month_start;
+ALTER TABLE monthly_rainfall_summary DROP COLUMN IF EXISTS month_start;
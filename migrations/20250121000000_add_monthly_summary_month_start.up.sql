@@ -0,0 +1,19 @@
+-- First day of each summary's month, for range queries
+--
+-- Summary range queries compared (year, month) pairs with EXTRACT or make_timestamptz
+-- expressions, which no index can serve, so every read filtered all of a station's rows
+-- (or, for rankings, the whole table). month_start is generated from year and month,
+-- so writers are unchanged, and ranges become plain index range scans.
+
+ALTER TABLE monthly_rainfall_summary
+    ADD COLUMN IF NOT EXISTS month_start DATE
+    GENERATED ALWAYS AS (make_date(year, month, 1)) STORED;
+
+CREATE INDEX IF NOT EXISTS idx_monthly_summary_station_month_start
+    ON monthly_rainfall_summary(station_id, month_start);
+
+-- Rankings read one period across every station
+CREATE INDEX IF NOT EXISTS idx_monthly_summary_month_start
+    ON monthly_rainfall_summary(month_start);
+
+COMMENT ON COLUMN monthly_rainfall_summary.month_start IS 'make_date(year, month, 1); use for date range predicates';
@@ -0,0 +1,3 @@
+-- Revert 20250122000000: water_year is derived from reading_datetime
+DROP INDEX IF EXISTS idx_rain_readings_station_water_year;
+ALTER TABLE rain_readings DROP COLUMN IF EXISTS water_year;
@@ -0,0 +1,20 @@
+-- Water year of each reading, for per-water-year reads
+--
+-- A water year runs Oct 1 through Sep 30 and is named for the year it ends in, so
+-- 2024-10-01 00:00 UTC starts water year 2025. Water-year reads filtered
+-- reading_datetime against bounds computed per request; water_year is generated from
+-- reading_datetime (in UTC, like every other period boundary), so writers are unchanged
+-- and a station's water year is a single index lookup.
+--
+-- Adding a stored generated column rewrites rain_readings; run it during a quiet period.
+
+ALTER TABLE rain_readings
+    ADD COLUMN IF NOT EXISTS water_year INTEGER
+    GENERATED ALWAYS AS (
+        EXTRACT(YEAR FROM (reading_datetime AT TIME ZONE 'UTC') + INTERVAL '3 months')::INTEGER
+    ) STORED;
+
+CREATE INDEX IF NOT EXISTS idx_rain_readings_station_water_year
+    ON rain_readings(station_id, water_year);
+
+COMMENT ON COLUMN rain_readings.water_year IS 'Oct-Sep water year of reading_datetime (UTC), named for the ending year';
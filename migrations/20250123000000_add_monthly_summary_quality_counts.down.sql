@@ -0,0 +1,5 @@
+-- Revert 20250123000000: the counts are derived from rain_readings.import_metadata
+ALTER TABLE monthly_rainfall_summary
+    DROP COLUMN IF EXISTS footnoted_count,
+    DROP COLUMN IF EXISTS estimated_count,
+    DROP COLUMN IF EXISTS flagged_count;
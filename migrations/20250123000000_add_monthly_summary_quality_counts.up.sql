@@ -0,0 +1,37 @@
+-- Reading quality counts on monthly summaries
+--
+-- A month with a low total may be dry, or the gauge may have been offline with the gap
+-- patched by estimates. Each summary now counts its readings whose import_metadata marks
+-- them as:
+-- - flagged:   a non-empty `flags` array (quality problems noted at import)
+-- - estimated: `"estimated": true` (value filled in rather than measured)
+-- - footnoted: a `footnote_marker` (the source report attached a note)
+-- The service grades each month A/B/C from these counts. NULL means the counts are
+-- unknown, e.g. a summary restored from a backup taken before this migration; the next
+-- recalculation fills them in.
+
+ALTER TABLE monthly_rainfall_summary
+    ADD COLUMN IF NOT EXISTS flagged_count INTEGER,
+    ADD COLUMN IF NOT EXISTS estimated_count INTEGER,
+    ADD COLUMN IF NOT EXISTS footnoted_count INTEGER;
+
+UPDATE monthly_rainfall_summary s
+SET flagged_count = q.flagged,
+    estimated_count = q.estimated,
+    footnoted_count = q.footnoted
+FROM (
+    SELECT station_id,
+           date_part('year', reading_datetime AT TIME ZONE 'UTC')::INT AS year,
+           date_part('month', reading_datetime AT TIME ZONE 'UTC')::INT AS month,
+           COUNT(*) FILTER (WHERE jsonb_typeof(import_metadata->'flags') = 'array'
+                              AND import_metadata->'flags' <> '[]'::jsonb)::INT AS flagged,
+           COUNT(*) FILTER (WHERE import_metadata->'estimated' = 'true'::jsonb)::INT AS estimated,
+           COUNT(*) FILTER (WHERE import_metadata ? 'footnote_marker')::INT AS footnoted
+    FROM rain_readings
+    GROUP BY 1, 2, 3
+) q
+WHERE s.station_id = q.station_id AND s.year = q.year AND s.month = q.month;
+
+COMMENT ON COLUMN monthly_rainfall_summary.flagged_count IS 'Readings with a non-empty import_metadata.flags array; NULL if unknown';
+COMMENT ON COLUMN monthly_rainfall_summary.estimated_count IS 'Readings with import_metadata.estimated = true; NULL if unknown';
+COMMENT ON COLUMN monthly_rainfall_summary.footnoted_count IS 'Readings with an import_metadata.footnote_marker; NULL if unknown';
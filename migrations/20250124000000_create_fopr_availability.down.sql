@@ -0,0 +1,2 @@
+-- Revert 20250124000000: availability is re-probed on demand
+DROP TABLE IF EXISTS fopr_availability;
@@ -0,0 +1,20 @@
+-- FOPR file availability per gauge
+--
+-- Filled by `historical-import probe fopr`, which sends a HEAD request for each gauge's
+-- {station_id}_FOPR.xlsx on the MCFCD site. Bulk imports skip gauges whose file is
+-- missing, and changed_at tells scheduled refreshes which files were republished.
+
+CREATE TABLE IF NOT EXISTS fopr_availability (
+    station_id VARCHAR(20) PRIMARY KEY,
+    available BOOLEAN NOT NULL,
+    http_status INTEGER NOT NULL,       -- Status of the last HEAD request
+    last_modified TIMESTAMPTZ,          -- Last-Modified header, when sent
+    content_length BIGINT,              -- Content-Length header, when sent
+    checked_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()  -- Last time available or last_modified changed
+);
+
+CREATE INDEX IF NOT EXISTS idx_fopr_availability_changed_at
+    ON fopr_availability(changed_at DESC);
+
+COMMENT ON TABLE fopr_availability IS 'Whether each gauge has an FOPR file on the MCFCD site, from HEAD probes';
@@ -0,0 +1,3 @@
+-- Revert 20250125000000: the unrounded totals aren't kept, and recalculating a month
+-- rounds it again, so there is nothing to undo
+SELECT 1;
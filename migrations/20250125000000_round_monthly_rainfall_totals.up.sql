@@ -0,0 +1,10 @@
+-- Round stored monthly totals to hundredths of an inch
+--
+-- Totals summed from float readings picked up drift (5.299999999 for 5.3) that leaked
+-- into API responses. Recalculation now rounds each total as it is stored; this fixes
+-- the rows written before that. Min/max cumulative values are single readings and
+-- carry no drift.
+
+UPDATE monthly_rainfall_summary
+SET total_rainfall_inches = ROUND(total_rainfall_inches::numeric, 2)::double precision
+WHERE total_rainfall_inches <> ROUND(total_rainfall_inches::numeric, 2)::double precision;
@@ -0,0 +1,2 @@
+-- Revert 20250126000000: quarantined readings are dropped with the table
+DROP TABLE IF EXISTS quarantined_readings;
@@ -0,0 +1,24 @@
+-- Readings rejected by the ingest guards
+--
+-- The live fetcher and the Excel/FOPR importers store a reading here instead of in
+-- rain_readings when it is negative, exceeds the configured maximum (MAX_READING_INCHES),
+-- or its cumulative total falls below an earlier reading from the same day. Nothing reads
+-- these rows into summaries; they are kept for review. A reading re-fetched or re-imported
+-- from the same source is recorded once.
+
+CREATE TABLE IF NOT EXISTS quarantined_readings (
+    id BIGSERIAL PRIMARY KEY,
+    station_id VARCHAR(20) NOT NULL,
+    reading_datetime TIMESTAMPTZ NOT NULL,
+    cumulative_inches DOUBLE PRECISION,         -- NULL for daily totals
+    incremental_inches DOUBLE PRECISION NOT NULL,
+    data_source VARCHAR(50) NOT NULL,
+    reason VARCHAR(30) NOT NULL,                -- negative_rainfall, cumulative_decrease, exceeds_maximum
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (station_id, reading_datetime, data_source)
+);
+
+CREATE INDEX IF NOT EXISTS idx_quarantined_readings_created_at
+    ON quarantined_readings(created_at DESC);
+
+COMMENT ON TABLE quarantined_readings IS 'Readings rejected at ingest, kept out of rain_readings for review';
@@ -0,0 +1,3 @@
+-- Revert 20250127000000: the back-filled totals are derived from incremental_inches, and
+-- re-importing computes them again, so there is nothing to undo
+SELECT 1;
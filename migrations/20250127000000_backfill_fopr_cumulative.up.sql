@@ -0,0 +1,37 @@
+-- Back-fill cumulative_inches of FOPR readings with water year running totals
+--
+-- FOPR files only carry daily incremental amounts, and imports stored cumulative_inches
+-- as 0.0. Imports now re-derive the running total per station and water year; this fixes
+-- the rows written before that, then the min/max cumulative of the affected monthly
+-- summaries.
+
+UPDATE rain_readings r
+SET cumulative_inches = t.running_total
+FROM (
+    SELECT id,
+           ROUND(SUM(incremental_inches) OVER (
+               PARTITION BY station_id, data_source, water_year
+               ORDER BY reading_datetime
+           )::numeric, 2)::double precision AS running_total
+    FROM rain_readings
+    WHERE data_source LIKE 'fopr\_import\_%'
+) t
+WHERE r.id = t.id AND r.cumulative_inches <> t.running_total;
+
+UPDATE monthly_rainfall_summary m
+SET min_cumulative_inches = a.min_cumulative,
+    max_cumulative_inches = a.max_cumulative,
+    updated_at = NOW()
+FROM (
+    SELECT station_id,
+           EXTRACT(YEAR FROM reading_datetime AT TIME ZONE 'UTC')::int AS year,
+           EXTRACT(MONTH FROM reading_datetime AT TIME ZONE 'UTC')::int AS month,
+           MIN(cumulative_inches) AS min_cumulative,
+           MAX(cumulative_inches) AS max_cumulative
+    FROM rain_readings
+    WHERE station_id IN (
+        SELECT DISTINCT station_id FROM rain_readings WHERE data_source LIKE 'fopr\_import\_%'
+    )
+    GROUP BY 1, 2, 3
+) a
+WHERE m.station_id = a.station_id AND m.year = a.year AND m.month = a.month;
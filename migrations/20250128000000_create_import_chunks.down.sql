@@ -0,0 +1,2 @@
+-- Revert 20250128000000: resume information is dropped with the table
+DROP TABLE IF EXISTS import_chunks;
@@ -0,0 +1,20 @@
+-- Station-months committed by historical imports
+--
+-- Excel and FOPR imports commit each station-month in one transaction: the readings, the
+-- month's summary (and, for FOPR, the water year's cumulative totals), and a row here. A
+-- crash leaves whole months loaded, never half of one. An interrupted import that is run
+-- again skips months already committed from the same source with the same number of
+-- readings, and re-commits any month the source has since added readings to.
+
+CREATE TABLE IF NOT EXISTS import_chunks (
+    station_id VARCHAR(50) NOT NULL REFERENCES gauges(station_id) ON DELETE CASCADE,
+    data_source VARCHAR(50) NOT NULL,
+    year INT NOT NULL,
+    month INT NOT NULL CHECK (month >= 1 AND month <= 12),
+    reading_count INT NOT NULL,                 -- readings in the month as parsed
+    readings_inserted INT NOT NULL,             -- readings new to rain_readings
+    committed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (station_id, data_source, year, month)
+);
+
+COMMENT ON TABLE import_chunks IS 'Station-months committed by historical imports, for resuming interrupted imports';
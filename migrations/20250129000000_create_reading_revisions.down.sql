@@ -0,0 +1,2 @@
+-- Revert 20250129000000: the revision history is dropped with the table
+DROP TABLE IF EXISTS reading_revisions;
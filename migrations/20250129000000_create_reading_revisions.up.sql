@@ -0,0 +1,24 @@
+-- Previous values of readings overwritten by re-imports
+--
+-- `import ... --on-conflict update` lets a corrected upstream file overwrite readings
+-- already stored for the same gauge and time, instead of skipping them. Each overwrite
+-- records the values it replaced here, so a bad correction can be traced and undone.
+
+CREATE TABLE IF NOT EXISTS reading_revisions (
+    id BIGSERIAL PRIMARY KEY,
+    reading_id BIGINT NOT NULL REFERENCES rain_readings(id) ON DELETE CASCADE,
+    station_id VARCHAR(50) NOT NULL,
+    reading_datetime TIMESTAMPTZ NOT NULL,
+    previous_incremental_inches DOUBLE PRECISION NOT NULL,
+    previous_cumulative_inches DOUBLE PRECISION NOT NULL,
+    previous_data_source VARCHAR(50) NOT NULL,
+    previous_import_metadata JSONB,
+    new_incremental_inches DOUBLE PRECISION NOT NULL,
+    new_data_source VARCHAR(50) NOT NULL,
+    revised_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_reading_revisions_station_datetime
+    ON reading_revisions(station_id, reading_datetime);
+
+COMMENT ON TABLE reading_revisions IS 'Values replaced when a re-import overwrote a stored reading';
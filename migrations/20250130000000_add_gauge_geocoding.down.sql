@@ -0,0 +1,5 @@
+-- Revert 20250130000000: geocoded cities written into gauges.city are kept
+ALTER TABLE gauges
+    DROP COLUMN IF EXISTS geocoded_at,
+    DROP COLUMN IF EXISTS geocode_source,
+    DROP COLUMN IF EXISTS nearest_place;
@@ -0,0 +1,15 @@
+-- Reverse geocoding results on gauges
+--
+-- Many gauges have coordinates but no city. The optional geocoding job looks up each
+-- such gauge's coordinates with a provider (a Nominatim-compatible HTTP endpoint or an
+-- offline places file), stores the nearest named place, and fills `city` when it is
+-- empty. A city from FOPR metadata or a manual edit is never overwritten.
+
+ALTER TABLE gauges
+    ADD COLUMN IF NOT EXISTS nearest_place VARCHAR(255),
+    ADD COLUMN IF NOT EXISTS geocode_source VARCHAR(255),
+    ADD COLUMN IF NOT EXISTS geocoded_at TIMESTAMPTZ;
+
+COMMENT ON COLUMN gauges.nearest_place IS 'Nearest named place to the gauge, from reverse geocoding';
+COMMENT ON COLUMN gauges.geocode_source IS 'Provider of the last reverse geocoding lookup (e.g. nominatim, places:az_places.csv)';
+COMMENT ON COLUMN gauges.geocoded_at IS 'When the gauge was last reverse geocoded; NULL if never';
@@ -0,0 +1,4 @@
+-- Revert 20250131000000: sampled elevations written into gauges.elevation_ft are kept
+ALTER TABLE gauges
+    DROP COLUMN IF EXISTS elevation_sampled_at,
+    DROP COLUMN IF EXISTS elevation_source;
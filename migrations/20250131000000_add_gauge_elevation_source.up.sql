@@ -0,0 +1,13 @@
+-- Elevation provenance on gauges
+--
+-- Some gauges have coordinates but no elevation_ft. The optional elevation job samples a
+-- digital elevation model at each such gauge's coordinates (the USGS 3DEP elevation point
+-- query service or an offline DEM grid) and fills elevation_ft. An elevation from FOPR
+-- metadata or a manual edit is never overwritten.
+
+ALTER TABLE gauges
+    ADD COLUMN IF NOT EXISTS elevation_source VARCHAR(255),
+    ADD COLUMN IF NOT EXISTS elevation_sampled_at TIMESTAMPTZ;
+
+COMMENT ON COLUMN gauges.elevation_source IS 'DEM elevation_ft was sampled from (e.g. usgs-3dep, dem:maricopa.asc); NULL when it came from metadata';
+COMMENT ON COLUMN gauges.elevation_sampled_at IS 'When the DEM was last sampled for the gauge; NULL if never';
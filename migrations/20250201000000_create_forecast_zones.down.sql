@@ -0,0 +1,4 @@
+-- Revert 20250201000000
+DROP INDEX IF EXISTS idx_gauges_forecast_zone;
+ALTER TABLE gauges DROP COLUMN IF EXISTS forecast_zone;
+DROP TABLE IF EXISTS forecast_zones;
@@ -0,0 +1,29 @@
+-- MSP forecast zone polygons and geometric zone assignment of gauges
+--
+-- The gauge list scrape names each gauge's forecast zone as free text, and often says
+-- "None". `historical-import zones import` loads the zone polygons from GeoJSON, and each
+-- gauge with coordinates is assigned the zone containing it (point in polygon), which
+-- rankings and zone rainfall totals prefer over the scraped text.
+
+CREATE TABLE IF NOT EXISTS forecast_zones (
+    zone_id VARCHAR(50) PRIMARY KEY,
+    name VARCHAR(255),
+    -- GeoJSON Polygon or MultiPolygon geometry, WGS 84 longitude/latitude
+    geometry JSONB NOT NULL,
+    -- Bounding box, to skip polygons that cannot contain a gauge
+    min_latitude DOUBLE PRECISION NOT NULL,
+    max_latitude DOUBLE PRECISION NOT NULL,
+    min_longitude DOUBLE PRECISION NOT NULL,
+    max_longitude DOUBLE PRECISION NOT NULL,
+    source VARCHAR(255),
+    imported_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE gauges
+    ADD COLUMN IF NOT EXISTS forecast_zone VARCHAR(50)
+        REFERENCES forecast_zones(zone_id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_gauges_forecast_zone ON gauges(forecast_zone);
+
+COMMENT ON TABLE forecast_zones IS 'MSP forecast zone polygons imported from GeoJSON';
+COMMENT ON COLUMN gauges.forecast_zone IS 'Forecast zone whose polygon contains the gauge; NULL outside every zone or without coordinates';
@@ -0,0 +1,2 @@
+-- Revert 20250202000000: estimates are re-sampled from the QPE grids on demand
+DROP TABLE IF EXISTS radar_estimates;
@@ -0,0 +1,21 @@
+-- MRMS radar-estimated rainfall per gauge and storm window
+--
+-- `historical-import radar import` samples MRMS quantitative precipitation estimate grids
+-- at each gauge's coordinates and stores the total for the window; the radar comparison
+-- endpoint sets it against the gauge's readings for the same window. Re-importing a
+-- window and product replaces its estimates. PostgreSQL only.
+
+CREATE TABLE IF NOT EXISTS radar_estimates (
+    station_id VARCHAR(50) NOT NULL REFERENCES gauges(station_id) ON DELETE CASCADE,
+    storm_start TIMESTAMPTZ NOT NULL,
+    storm_end TIMESTAMPTZ NOT NULL,
+    product VARCHAR(100) NOT NULL,      -- MRMS product, e.g. MultiSensor_QPE_01H_Pass2
+    radar_inches DOUBLE PRECISION NOT NULL,
+    source TEXT NOT NULL,               -- Grid file names
+    sampled_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (storm_start, storm_end, product, station_id),
+    CHECK (storm_end > storm_start),
+    CHECK (radar_inches >= 0)
+);
+
+COMMENT ON TABLE radar_estimates IS 'MRMS radar rainfall estimates sampled at gauge locations for storm windows';
@@ -0,0 +1,2 @@
+-- Revert 20250203000000: the weather job refetches recent days on its next run
+DROP TABLE IF EXISTS gauge_daily_weather;
@@ -0,0 +1,20 @@
+-- Daily temperature and reference evapotranspiration per gauge
+--
+-- The optional weather job fills one row per gauge and day from AZMET observations (the
+-- nearest station) or NWS gridpoint forecasts (with ET estimated by Hargreaves); the
+-- gauge weather endpoint sets it beside the gauge's daily rainfall. Each run replaces
+-- the days it fetched. PostgreSQL only.
+
+CREATE TABLE IF NOT EXISTS gauge_daily_weather (
+    station_id VARCHAR(50) NOT NULL REFERENCES gauges(station_id) ON DELETE CASCADE,
+    date DATE NOT NULL,
+    max_temp_f DOUBLE PRECISION,
+    min_temp_f DOUBLE PRECISION,
+    et_inches DOUBLE PRECISION,         -- Reference evapotranspiration
+    source VARCHAR(100) NOT NULL,       -- e.g. azmet:az06 or nws:PSR/158,56
+    fetched_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (station_id, date),
+    CHECK (et_inches IS NULL OR et_inches >= 0)
+);
+
+COMMENT ON TABLE gauge_daily_weather IS 'Daily temperature and reference ET at gauge locations from AZMET or NWS';
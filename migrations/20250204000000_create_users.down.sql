@@ -0,0 +1,4 @@
+-- Revert 20250204000000: drops every user, favorite, and saved view
+DROP TABLE IF EXISTS saved_views;
+DROP TABLE IF EXISTS user_favorites;
+DROP TABLE IF EXISTS users;
@@ -0,0 +1,36 @@
+-- User accounts with favorite gauges and saved views
+--
+-- Admins create users; each gets a random key, shown once and stored only as its SHA-256
+-- hash. Clients send the key in X-User-Key to read and change the user's favorites and
+-- saved views (a named gauge set and period), so a frontend can sync dashboards across
+-- devices. PostgreSQL only.
+
+CREATE TABLE IF NOT EXISTS users (
+    id BIGSERIAL PRIMARY KEY,
+    name VARCHAR(100) NOT NULL UNIQUE,
+    key_hash CHAR(64) NOT NULL UNIQUE,  -- Hex SHA-256 of the user's key
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMPTZ            -- Last request made with the key
+);
+
+CREATE TABLE IF NOT EXISTS user_favorites (
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    station_id VARCHAR(50) NOT NULL REFERENCES gauges(station_id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, station_id)
+);
+
+CREATE TABLE IF NOT EXISTS saved_views (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    station_ids VARCHAR(50)[] NOT NULL, -- In display order
+    period VARCHAR(20) NOT NULL CHECK (period IN ('24h', 'month', 'water-year')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, name)
+);
+
+COMMENT ON TABLE users IS 'API users identified by a hashed X-User-Key';
+COMMENT ON TABLE user_favorites IS 'Gauges each user has marked as a favorite';
+COMMENT ON TABLE saved_views IS 'Named gauge sets and periods saved by each user';
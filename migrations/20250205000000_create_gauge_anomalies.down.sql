@@ -0,0 +1,2 @@
+-- Revert 20250205000000: drops every recorded anomaly and its review
+DROP TABLE IF EXISTS gauge_anomalies;
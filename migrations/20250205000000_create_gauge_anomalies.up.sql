@@ -0,0 +1,29 @@
+-- Suspected gauge malfunctions found by the anomaly job
+--
+-- kind is flatline (weeks of zeros while neighbors recorded rain), spike (one impossibly
+-- large reading), or reset (cumulative dropped mid water year). Findings are keyed by
+-- gauge, kind, and start so each run extends an ongoing flat-line instead of adding a
+-- row, and review status survives re-detection. PostgreSQL only.
+
+CREATE TABLE IF NOT EXISTS gauge_anomalies (
+    id BIGSERIAL PRIMARY KEY,
+    station_id VARCHAR(50) NOT NULL REFERENCES gauges(station_id) ON DELETE CASCADE,
+    kind VARCHAR(20) NOT NULL CHECK (kind IN ('flatline', 'spike', 'reset')),
+    started_at TIMESTAMPTZ NOT NULL,
+    ended_at TIMESTAMPTZ,               -- Last zero reading of a flat-line
+    magnitude_inches DOUBLE PRECISION NOT NULL,
+    detail TEXT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'open'
+        CHECK (status IN ('open', 'confirmed', 'dismissed')),
+    review_note TEXT,
+    reviewed_by VARCHAR(100),
+    reviewed_at TIMESTAMPTZ,
+    first_detected_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_detected_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (station_id, kind, started_at)
+);
+
+CREATE INDEX IF NOT EXISTS idx_gauge_anomalies_status
+    ON gauge_anomalies(status, first_detected_at DESC);
+
+COMMENT ON TABLE gauge_anomalies IS 'Suspected gauge malfunctions awaiting or after admin review';
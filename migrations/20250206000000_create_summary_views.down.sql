@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS summary_views;
+DROP MATERIALIZED VIEW IF EXISTS gauge_period_rainfall;
@@ -0,0 +1,53 @@
+-- Materialized rainfall totals for the heavy ranking and zone aggregate queries.
+-- Rankings and zone rainfall read gauge_period_rainfall instead of summing monthly
+-- summaries while summary_views says it is fresh, and fall back to the live query
+-- otherwise. Bulk summary changes (recalculations, imports) flag it stale until the
+-- next refresh.
+
+-- Each gauge's rainfall in the month and water year in progress at refresh time (UTC),
+-- matching the live month and water-year ranking windows
+CREATE MATERIALIZED VIEW gauge_period_rainfall AS
+WITH bounds AS (
+    SELECT (NOW() AT TIME ZONE 'UTC') AS now_utc,
+           date_trunc('month', NOW() AT TIME ZONE 'UTC') AS month_start,
+           make_timestamp(
+               EXTRACT(YEAR FROM (NOW() AT TIME ZONE 'UTC') + INTERVAL '3 months')::INT - 1,
+               10, 1, 0, 0, 0
+           ) AS water_year_start
+)
+SELECT m.station_id,
+       g.gauge_name,
+       g.city_town,
+       COALESCE(gs.forecast_zone, g.msp_forecast_zone) AS msp_forecast_zone,
+       g.general_location,
+       COALESCE(gs.status, 'Active') AS status,
+       SUM(m.total_rainfall_inches) FILTER (WHERE m.month_start >= b.month_start) AS month_inches,
+       SUM(m.reading_count) FILTER (WHERE m.month_start >= b.month_start)::BIGINT AS month_reading_count,
+       SUM(m.total_rainfall_inches) AS water_year_inches,
+       SUM(m.reading_count)::BIGINT AS water_year_reading_count
+FROM monthly_rainfall_summary m
+CROSS JOIN bounds b
+JOIN gauge_summaries g ON g.station_id = m.station_id
+LEFT JOIN gauges gs ON gs.station_id = m.station_id
+WHERE m.month_start >= b.water_year_start
+  AND m.month_start < b.now_utc
+GROUP BY m.station_id, g.gauge_name, g.city_town, g.msp_forecast_zone, g.general_location,
+         gs.forecast_zone, gs.status
+WITH NO DATA;
+
+-- Required by REFRESH MATERIALIZED VIEW CONCURRENTLY
+CREATE UNIQUE INDEX idx_gauge_period_rainfall_station ON gauge_period_rainfall (station_id);
+
+-- Refresh state of each summary view
+CREATE TABLE summary_views (
+    view_name VARCHAR(63) PRIMARY KEY,
+    refreshed_at TIMESTAMPTZ,
+    refresh_duration_ms BIGINT,
+    -- Set by bulk summary changes, cleared by a refresh that started after them
+    stale BOOLEAN NOT NULL DEFAULT TRUE,
+    stale_reason TEXT,
+    marked_stale_at TIMESTAMPTZ
+);
+
+INSERT INTO summary_views (view_name, stale_reason, marked_stale_at)
+VALUES ('gauge_period_rainfall', 'Not yet refreshed', NOW());
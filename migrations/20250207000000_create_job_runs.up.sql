@@ -0,0 +1,28 @@
+-- History of background job runs
+--
+-- One row per reading fetch, gauge list scrape, and FOPR import attempt, recorded when
+-- the run ends. Unlike fopr_import_jobs, whose rows are updated in place as a job is
+-- retried, rows here are never changed, so operators can chart throughput and MCFCD
+-- response times over time. Rows older than JOB_RUN_RETENTION_DAYS are deleted daily.
+-- fopr_job_id is kept without a foreign key so history outlives pruned jobs.
+-- PostgreSQL only.
+
+CREATE TABLE IF NOT EXISTS job_runs (
+    id BIGSERIAL PRIMARY KEY,
+    job VARCHAR(20) NOT NULL CHECK (job IN ('fetch', 'gauge_list', 'fopr_import')),
+    station_id VARCHAR(50),             -- FOPR imports only
+    fopr_job_id INTEGER,                -- FOPR imports only
+    started_at TIMESTAMPTZ NOT NULL,
+    duration_ms DOUBLE PRECISION NOT NULL,
+    row_count BIGINT NOT NULL DEFAULT 0, -- Readings stored or gauges upserted
+    outcome VARCHAR(20) NOT NULL CHECK (outcome IN ('succeeded', 'failed')),
+    error TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_job_runs_job_started_at
+    ON job_runs(job, started_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_job_runs_started_at
+    ON job_runs(started_at);
+
+COMMENT ON TABLE job_runs IS 'Append-only history of background job runs, pruned after JOB_RUN_RETENTION_DAYS';
@@ -0,0 +1,31 @@
+-- Hourly rainfall totals per gauge, for intensity charts
+--
+-- Built from sub-daily readings only (live scrapes today): a daily import total would
+-- land in a single hour and read as a cloudburst. Kept current by the fetch scheduler
+-- and rebuilt with the monthly summaries by the admin recalculation. Hours without
+-- readings have no row.
+
+CREATE TABLE IF NOT EXISTS hourly_rainfall_summary (
+    station_id VARCHAR(20) NOT NULL,
+    hour_start TIMESTAMPTZ NOT NULL,
+    total_rainfall_inches DOUBLE PRECISION NOT NULL,
+    max_incremental_inches DOUBLE PRECISION NOT NULL,
+    reading_count INT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (station_id, hour_start)
+);
+
+INSERT INTO hourly_rainfall_summary
+    (station_id, hour_start, total_rainfall_inches, max_incremental_inches, reading_count)
+SELECT station_id,
+       date_trunc('hour', reading_datetime AT TIME ZONE 'UTC') AT TIME ZONE 'UTC',
+       ROUND(SUM(incremental_inches) * 100) / 100,
+       MAX(incremental_inches),
+       COUNT(*)
+FROM rain_readings
+WHERE data_source = 'live_scrape'
+GROUP BY 1, 2
+ON CONFLICT (station_id, hour_start) DO NOTHING;
+
+COMMENT ON TABLE hourly_rainfall_summary IS 'Pre-aggregated hourly rainfall per gauge from sub-daily readings';
+COMMENT ON COLUMN hourly_rainfall_summary.max_incremental_inches IS 'Largest single reading in the hour, a proxy for peak intensity';
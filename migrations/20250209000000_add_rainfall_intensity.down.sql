@@ -0,0 +1,5 @@
+ALTER TABLE gauge_threshold_events
+    DROP COLUMN IF EXISTS max_15min_intensity_in_per_hr,
+    DROP COLUMN IF EXISTS max_1h_intensity_in_per_hr;
+
+DROP TABLE IF EXISTS daily_rainfall_intensity;
@@ -0,0 +1,72 @@
+-- Peak rainfall intensity per gauge-day and per threshold event
+--
+-- Intensity is the most rain in a sliding window ending at a reading: 15 minutes
+-- (scaled to inches per hour) and 1 hour. Like the hourly summaries it needs
+-- sub-daily readings (live scrapes today), so days and events without any have no
+-- value. Daily rows are kept current with the hourly summaries; an event's peaks cover
+-- the 24 hours before its crossing through its end and are refreshed on each gauge
+-- list scrape while it is open.
+
+CREATE TABLE IF NOT EXISTS daily_rainfall_intensity (
+    station_id VARCHAR(20) NOT NULL,
+    date DATE NOT NULL,
+    max_15min_intensity_in_per_hr DOUBLE PRECISION NOT NULL,
+    max_1h_intensity_in_per_hr DOUBLE PRECISION NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (station_id, date)
+);
+
+ALTER TABLE gauge_threshold_events
+    ADD COLUMN IF NOT EXISTS max_15min_intensity_in_per_hr DOUBLE PRECISION,
+    ADD COLUMN IF NOT EXISTS max_1h_intensity_in_per_hr DOUBLE PRECISION;
+
+INSERT INTO daily_rainfall_intensity
+    (station_id, date, max_15min_intensity_in_per_hr, max_1h_intensity_in_per_hr)
+SELECT station_id,
+       (reading_datetime AT TIME ZONE 'UTC')::DATE,
+       ROUND(MAX(rain_15min) * 4 * 100) / 100,
+       ROUND(MAX(rain_1h) * 100) / 100
+FROM (
+    SELECT station_id, reading_datetime,
+           SUM(incremental_inches) OVER (
+               PARTITION BY station_id ORDER BY reading_datetime
+               RANGE BETWEEN INTERVAL '15 minutes' - INTERVAL '1 microsecond' PRECEDING
+                     AND CURRENT ROW
+           ) AS rain_15min,
+           SUM(incremental_inches) OVER (
+               PARTITION BY station_id ORDER BY reading_datetime
+               RANGE BETWEEN INTERVAL '1 hour' - INTERVAL '1 microsecond' PRECEDING
+                     AND CURRENT ROW
+           ) AS rain_1h
+    FROM rain_readings
+    WHERE data_source = 'live_scrape'
+) windows
+GROUP BY 1, 2
+ON CONFLICT (station_id, date) DO NOTHING;
+
+UPDATE gauge_threshold_events e
+SET (max_15min_intensity_in_per_hr, max_1h_intensity_in_per_hr) = (
+    SELECT ROUND(MAX(rain_15min) * 4 * 100) / 100, ROUND(MAX(rain_1h) * 100) / 100
+    FROM (
+        SELECT reading_datetime,
+               SUM(incremental_inches) OVER (
+                   ORDER BY reading_datetime
+                   RANGE BETWEEN INTERVAL '15 minutes' - INTERVAL '1 microsecond' PRECEDING
+                         AND CURRENT ROW
+               ) AS rain_15min,
+               SUM(incremental_inches) OVER (
+                   ORDER BY reading_datetime
+                   RANGE BETWEEN INTERVAL '1 hour' - INTERVAL '1 microsecond' PRECEDING
+                         AND CURRENT ROW
+               ) AS rain_1h
+        FROM rain_readings r
+        WHERE r.station_id = e.station_id
+          AND r.reading_datetime >= e.crossed_at - INTERVAL '25 hours'
+          AND r.reading_datetime <= COALESCE(e.ended_at, NOW())
+          AND r.data_source = 'live_scrape'
+    ) windows
+    WHERE reading_datetime >= e.crossed_at - INTERVAL '24 hours'
+);
+
+COMMENT ON TABLE daily_rainfall_intensity IS 'Peak 15-minute and 1-hour rainfall intensity per gauge and UTC day from sub-daily readings';
+COMMENT ON COLUMN gauge_threshold_events.max_15min_intensity_in_per_hr IS 'Most rain in 15 minutes from 24h before the crossing to the end, in inches per hour';
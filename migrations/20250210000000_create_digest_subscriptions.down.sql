@@ -0,0 +1,2 @@
+-- Revert 20250210000000: drops every digest subscription
+DROP TABLE IF EXISTS digest_subscriptions;
@@ -0,0 +1,22 @@
+-- Daily and weekly rainfall digests for users
+--
+-- A user subscribes with a frequency and an email address, a webhook URL, or both; each
+-- digest summarizes the user's favorite gauges over the last complete UTC day or
+-- Monday-to-Sunday week. last_sent_through records the last day delivered, so the digest
+-- job sends each period once and retries one whose delivery failed. PostgreSQL only.
+
+CREATE TABLE IF NOT EXISTS digest_subscriptions (
+    user_id BIGINT PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    frequency VARCHAR(10) NOT NULL CHECK (frequency IN ('daily', 'weekly')),
+    email VARCHAR(254),
+    webhook_url VARCHAR(2000),
+    last_sent_through DATE,             -- Last day covered by a delivered digest
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (email IS NOT NULL OR webhook_url IS NOT NULL)
+);
+
+CREATE INDEX IF NOT EXISTS idx_digest_subscriptions_due
+    ON digest_subscriptions(frequency, last_sent_through);
+
+COMMENT ON TABLE digest_subscriptions IS 'Users receiving daily or weekly rainfall digests for their favorite gauges';
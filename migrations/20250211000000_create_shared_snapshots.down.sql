@@ -0,0 +1,2 @@
+-- Revert 20250211000000: every share link stops resolving
+DROP TABLE IF EXISTS shared_snapshots;
@@ -0,0 +1,20 @@
+-- Public shareable snapshot links
+--
+-- A user captures a gauge set and period; the rainfall totals at that moment are stored
+-- here and served to anyone holding the signed link until expires_at, so recipients see
+-- exactly what was shared even after later readings or revisions. Expired rows are
+-- deleted as new links are created. PostgreSQL only.
+
+CREATE TABLE IF NOT EXISTS shared_snapshots (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    station_ids VARCHAR(50)[] NOT NULL, -- As requested, in display order
+    period VARCHAR(20) NOT NULL CHECK (period IN ('24h', 'month', 'water-year')),
+    snapshot JSONB NOT NULL,            -- The response served at /share/{token}
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_shared_snapshots_expires_at ON shared_snapshots(expires_at);
+
+COMMENT ON TABLE shared_snapshots IS 'Frozen rainfall results behind signed share links';
@@ -0,0 +1,5 @@
+-- Revert 20250212000000: drops usage history and every user's quotas
+DROP TABLE IF EXISTS user_usage;
+ALTER TABLE users
+    DROP COLUMN IF EXISTS monthly_byte_quota,
+    DROP COLUMN IF EXISTS monthly_request_quota;
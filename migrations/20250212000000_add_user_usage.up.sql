@@ -0,0 +1,20 @@
+-- Per-user API usage and monthly quotas
+--
+-- Requests made with a user's X-User-Key are counted per UTC month with the response
+-- bytes sent. A user may have a monthly request quota, a byte quota, or both (NULL means
+-- unlimited); once either is used up, requests are refused with 429 until the next month.
+-- PostgreSQL only.
+
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS monthly_request_quota BIGINT CHECK (monthly_request_quota > 0),
+    ADD COLUMN IF NOT EXISTS monthly_byte_quota BIGINT CHECK (monthly_byte_quota > 0);
+
+CREATE TABLE IF NOT EXISTS user_usage (
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    month DATE NOT NULL,                -- First day of the UTC month
+    request_count BIGINT NOT NULL DEFAULT 0,
+    response_bytes BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (user_id, month)
+);
+
+COMMENT ON TABLE user_usage IS 'Requests and response bytes per user per UTC month';
@@ -0,0 +1,187 @@
+-- SQLite schema, equivalent to the PostgreSQL migrations through 20250119000000
+--
+-- Used by builds with the `sqlite` feature (DATABASE_URL=sqlite:...). Timestamps are TEXT in
+-- the RFC 3339 form sqlx writes for DateTime<Utc> ("2025-01-01T00:00:00+00:00"), dates are
+-- TEXT "YYYY-MM-DD", arrays and JSONB are JSON text. There is no fopr_import_jobs table:
+-- FOPR imports run only on PostgreSQL.
+--
+-- Schema changes to migrations/ need a matching migration here.
+
+CREATE TABLE IF NOT EXISTS gauges (
+    station_id TEXT PRIMARY KEY,
+    station_name TEXT,
+    station_type TEXT DEFAULT 'Rain',
+    previous_station_ids TEXT,                 -- JSON array of strings
+    latitude REAL,
+    longitude REAL,
+    elevation_ft INTEGER,
+    county TEXT DEFAULT 'Maricopa',
+    city TEXT,
+    location_description TEXT,
+    installation_date TEXT,
+    data_begins_date TEXT,
+    data_ends_date TEXT,
+    status TEXT NOT NULL DEFAULT 'Active'
+        CHECK (status IN ('Active', 'Inactive', 'Decommissioned')),
+    status_effective_date TEXT,
+    avg_annual_precipitation_inches REAL,
+    complete_years_count INTEGER,
+    incomplete_months_count INTEGER DEFAULT 0,
+    missing_months_count INTEGER DEFAULT 0,
+    data_quality_remarks TEXT,
+    fopr_metadata TEXT,                        -- JSON object
+    metadata_source TEXT DEFAULT 'fopr_import',
+    metadata_updated_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    fopr_available INTEGER DEFAULT 1,
+    fopr_last_import_date TEXT,
+    fopr_last_checked_date TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_gauges_latitude ON gauges(latitude);
+CREATE INDEX IF NOT EXISTS idx_gauges_longitude ON gauges(longitude);
+CREATE INDEX IF NOT EXISTS idx_gauges_city ON gauges(city);
+CREATE INDEX IF NOT EXISTS idx_gauges_status ON gauges(status);
+
+CREATE TABLE IF NOT EXISTS gauge_summaries (
+    id INTEGER PRIMARY KEY,
+    station_id TEXT NOT NULL UNIQUE REFERENCES gauges(station_id) ON DELETE CASCADE,
+    gauge_name TEXT NOT NULL,
+    city_town TEXT,
+    elevation_ft INTEGER,
+    general_location TEXT,
+    msp_forecast_zone TEXT,
+    rainfall_past_6h_inches REAL,
+    rainfall_past_24h_inches REAL,
+    last_scraped_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_gauge_city_town ON gauge_summaries(city_town);
+
+CREATE TABLE IF NOT EXISTS rain_readings (
+    id INTEGER PRIMARY KEY,
+    reading_datetime TEXT NOT NULL,
+    cumulative_inches REAL NOT NULL,
+    incremental_inches REAL NOT NULL,
+    station_id TEXT NOT NULL DEFAULT '59700' REFERENCES gauges(station_id) ON DELETE RESTRICT,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    data_source TEXT NOT NULL DEFAULT 'live_scrape',
+    import_metadata TEXT,                      -- JSON object
+    UNIQUE(reading_datetime, station_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_station_datetime ON rain_readings(station_id, reading_datetime DESC);
+CREATE INDEX IF NOT EXISTS idx_rain_readings_data_source ON rain_readings(data_source);
+
+CREATE TABLE IF NOT EXISTS monthly_rainfall_summary (
+    id INTEGER PRIMARY KEY,
+    station_id TEXT NOT NULL REFERENCES gauges(station_id) ON DELETE CASCADE,
+    year INTEGER NOT NULL,
+    month INTEGER NOT NULL CHECK (month >= 1 AND month <= 12),
+    total_rainfall_inches REAL NOT NULL DEFAULT 0.0,
+    reading_count INTEGER NOT NULL DEFAULT 0,
+    first_reading_date TEXT,
+    last_reading_date TEXT,
+    min_cumulative_inches REAL,
+    max_cumulative_inches REAL,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    UNIQUE(station_id, year, month)
+);
+
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    idempotency_key TEXT NOT NULL,
+    method TEXT NOT NULL,
+    path TEXT NOT NULL,
+    request_hash TEXT NOT NULL,
+    status_code INTEGER,
+    content_type TEXT,
+    response_body BLOB,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    completed_at TEXT,
+    PRIMARY KEY (idempotency_key, method, path)
+);
+
+CREATE INDEX IF NOT EXISTS idx_idempotency_keys_created_at ON idempotency_keys(created_at);
+
+CREATE TABLE IF NOT EXISTS gauge_status_history (
+    id INTEGER PRIMARY KEY,
+    station_id TEXT NOT NULL REFERENCES gauges(station_id) ON DELETE CASCADE,
+    previous_status TEXT NOT NULL,
+    status TEXT NOT NULL,
+    effective_date TEXT NOT NULL,
+    reason TEXT,
+    changed_by TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_gauge_status_history_station
+    ON gauge_status_history(station_id, created_at DESC);
+
+CREATE TABLE IF NOT EXISTS gauge_attachments (
+    id INTEGER PRIMARY KEY,
+    station_id TEXT NOT NULL REFERENCES gauges(station_id) ON DELETE CASCADE,
+    filename TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    size_bytes INTEGER NOT NULL CHECK (size_bytes > 0),
+    sha256 TEXT NOT NULL,
+    storage_key TEXT NOT NULL,
+    description TEXT,
+    uploaded_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_gauge_attachments_station
+    ON gauge_attachments(station_id, uploaded_at DESC);
+
+CREATE TABLE IF NOT EXISTS gauge_annotations (
+    id INTEGER PRIMARY KEY,
+    station_id TEXT NOT NULL REFERENCES gauges(station_id) ON DELETE CASCADE,
+    start_date TEXT,
+    end_date TEXT,
+    note TEXT NOT NULL,
+    author TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    CHECK (end_date IS NULL OR (start_date IS NOT NULL AND end_date >= start_date))
+);
+
+CREATE INDEX IF NOT EXISTS idx_gauge_annotations_station
+    ON gauge_annotations(station_id, start_date);
+
+CREATE TABLE IF NOT EXISTS gauge_threshold_events (
+    id INTEGER PRIMARY KEY,
+    station_id TEXT NOT NULL,
+    threshold_inches REAL NOT NULL,
+    crossed_at TEXT NOT NULL,
+    ended_at TEXT,
+    rainfall_24h_inches REAL NOT NULL,
+    peak_rainfall_24h_inches REAL NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_threshold_events_station
+    ON gauge_threshold_events(station_id, crossed_at DESC);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_threshold_events_open
+    ON gauge_threshold_events(station_id, threshold_inches)
+    WHERE ended_at IS NULL;
+
+CREATE TABLE IF NOT EXISTS current_conditions (
+    station_id TEXT PRIMARY KEY,
+    gauge_name TEXT NOT NULL,
+    city_town TEXT,
+    status TEXT NOT NULL,
+    latitude REAL,
+    longitude REAL,
+    elevation_ft INTEGER,
+    rainfall_past_6h_inches REAL,
+    rainfall_past_24h_inches REAL,
+    latest_reading_at TEXT,
+    latest_cumulative_inches REAL,
+    water_year INTEGER NOT NULL,
+    water_year_to_date_inches REAL NOT NULL,
+    last_scraped_at TEXT NOT NULL,
+    refreshed_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_current_conditions_order
+    ON current_conditions(city_town, gauge_name);
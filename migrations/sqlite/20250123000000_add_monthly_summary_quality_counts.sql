@@ -0,0 +1,5 @@
+-- Reading quality counts on monthly summaries; see the PostgreSQL migration of the same
+-- version. NULL means unknown until the summary is next recalculated.
+ALTER TABLE monthly_rainfall_summary ADD COLUMN flagged_count INTEGER;
+ALTER TABLE monthly_rainfall_summary ADD COLUMN estimated_count INTEGER;
+ALTER TABLE monthly_rainfall_summary ADD COLUMN footnoted_count INTEGER;
@@ -0,0 +1,5 @@
+-- Round stored monthly totals to hundredths of an inch; see the PostgreSQL migration of
+-- the same version
+UPDATE monthly_rainfall_summary
+SET total_rainfall_inches = ROUND(total_rainfall_inches, 2)
+WHERE total_rainfall_inches <> ROUND(total_rainfall_inches, 2);
@@ -0,0 +1,15 @@
+-- Readings rejected by the ingest guards; see the PostgreSQL migration of the same version
+CREATE TABLE IF NOT EXISTS quarantined_readings (
+    id INTEGER PRIMARY KEY,
+    station_id TEXT NOT NULL,
+    reading_datetime TEXT NOT NULL,
+    cumulative_inches REAL,                    -- NULL for daily totals
+    incremental_inches REAL NOT NULL,
+    data_source TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    UNIQUE (station_id, reading_datetime, data_source)
+);
+
+CREATE INDEX IF NOT EXISTS idx_quarantined_readings_created_at
+    ON quarantined_readings(created_at DESC);
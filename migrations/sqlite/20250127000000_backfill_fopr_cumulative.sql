@@ -0,0 +1,35 @@
+-- Back-fill cumulative_inches of FOPR readings with water year running totals; see the
+-- PostgreSQL migration of the same version. The water year is the year three months on.
+UPDATE rain_readings
+SET cumulative_inches = t.running_total
+FROM (
+    SELECT id,
+           ROUND(SUM(incremental_inches) OVER (
+               PARTITION BY station_id, data_source, strftime('%Y', reading_datetime, '+3 months')
+               ORDER BY reading_datetime
+           ), 2) AS running_total
+    FROM rain_readings
+    WHERE data_source LIKE 'fopr\_import\_%' ESCAPE '\'
+) t
+WHERE rain_readings.id = t.id AND rain_readings.cumulative_inches <> t.running_total;
+
+UPDATE monthly_rainfall_summary
+SET min_cumulative_inches = a.min_cumulative,
+    max_cumulative_inches = a.max_cumulative,
+    updated_at = strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')
+FROM (
+    SELECT station_id,
+           CAST(strftime('%Y', reading_datetime) AS INTEGER) AS year,
+           CAST(strftime('%m', reading_datetime) AS INTEGER) AS month,
+           MIN(cumulative_inches) AS min_cumulative,
+           MAX(cumulative_inches) AS max_cumulative
+    FROM rain_readings
+    WHERE station_id IN (
+        SELECT DISTINCT station_id FROM rain_readings
+        WHERE data_source LIKE 'fopr\_import\_%' ESCAPE '\'
+    )
+    GROUP BY 1, 2, 3
+) a
+WHERE monthly_rainfall_summary.station_id = a.station_id
+  AND monthly_rainfall_summary.year = a.year
+  AND monthly_rainfall_summary.month = a.month;
@@ -0,0 +1,12 @@
+-- Station-months committed by historical imports; see the PostgreSQL migration of the same
+-- version
+CREATE TABLE IF NOT EXISTS import_chunks (
+    station_id TEXT NOT NULL REFERENCES gauges(station_id) ON DELETE CASCADE,
+    data_source TEXT NOT NULL,
+    year INTEGER NOT NULL,
+    month INTEGER NOT NULL CHECK (month >= 1 AND month <= 12),
+    reading_count INTEGER NOT NULL,
+    readings_inserted INTEGER NOT NULL,
+    committed_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    PRIMARY KEY (station_id, data_source, year, month)
+);
@@ -0,0 +1,18 @@
+-- Previous values of readings overwritten by re-imports; see the PostgreSQL migration of
+-- the same version
+CREATE TABLE IF NOT EXISTS reading_revisions (
+    id INTEGER PRIMARY KEY,
+    reading_id INTEGER NOT NULL REFERENCES rain_readings(id) ON DELETE CASCADE,
+    station_id TEXT NOT NULL,
+    reading_datetime TEXT NOT NULL,
+    previous_incremental_inches REAL NOT NULL,
+    previous_cumulative_inches REAL NOT NULL,
+    previous_data_source TEXT NOT NULL,
+    previous_import_metadata TEXT,             -- JSON object
+    new_incremental_inches REAL NOT NULL,
+    new_data_source TEXT NOT NULL,
+    revised_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_reading_revisions_station_datetime
+    ON reading_revisions(station_id, reading_datetime);
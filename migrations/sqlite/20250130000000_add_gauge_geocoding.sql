@@ -0,0 +1,4 @@
+-- Reverse geocoding results on gauges; see the PostgreSQL migration of the same version
+ALTER TABLE gauges ADD COLUMN nearest_place TEXT;
+ALTER TABLE gauges ADD COLUMN geocode_source TEXT;
+ALTER TABLE gauges ADD COLUMN geocoded_at TEXT;
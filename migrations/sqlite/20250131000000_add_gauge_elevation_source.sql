@@ -0,0 +1,3 @@
+-- Elevation provenance on gauges; see the PostgreSQL migration of the same version
+ALTER TABLE gauges ADD COLUMN elevation_source TEXT;
+ALTER TABLE gauges ADD COLUMN elevation_sampled_at TEXT;
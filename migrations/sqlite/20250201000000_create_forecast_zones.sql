@@ -0,0 +1,17 @@
+-- MSP forecast zone polygons and geometric zone assignment of gauges; see the PostgreSQL
+-- migration of the same version
+CREATE TABLE IF NOT EXISTS forecast_zones (
+    zone_id TEXT PRIMARY KEY,
+    name TEXT,
+    geometry TEXT NOT NULL,                    -- GeoJSON geometry
+    min_latitude REAL NOT NULL,
+    max_latitude REAL NOT NULL,
+    min_longitude REAL NOT NULL,
+    max_longitude REAL NOT NULL,
+    source TEXT,
+    imported_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
+
+ALTER TABLE gauges ADD COLUMN forecast_zone TEXT REFERENCES forecast_zones(zone_id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_gauges_forecast_zone ON gauges(forecast_zone);
@@ -0,0 +1,22 @@
+-- Hourly rainfall totals per gauge from sub-daily readings; see the PostgreSQL migration
+-- of the same version
+CREATE TABLE IF NOT EXISTS hourly_rainfall_summary (
+    station_id TEXT NOT NULL,
+    hour_start TEXT NOT NULL,
+    total_rainfall_inches REAL NOT NULL,
+    max_incremental_inches REAL NOT NULL,
+    reading_count INTEGER NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    PRIMARY KEY (station_id, hour_start)
+);
+
+INSERT OR IGNORE INTO hourly_rainfall_summary
+    (station_id, hour_start, total_rainfall_inches, max_incremental_inches, reading_count)
+SELECT station_id,
+       substr(reading_datetime, 1, 13) || ':00:00+00:00',
+       ROUND(SUM(incremental_inches), 2),
+       MAX(incremental_inches),
+       COUNT(*)
+FROM rain_readings
+WHERE data_source = 'live_scrape'
+GROUP BY 1, 2;
@@ -0,0 +1,34 @@
+-- Peak rainfall intensity per gauge-day and per threshold event; see the PostgreSQL
+-- migration of the same version. Windows are in whole seconds since the epoch.
+CREATE TABLE IF NOT EXISTS daily_rainfall_intensity (
+    station_id TEXT NOT NULL,
+    date TEXT NOT NULL,
+    max_15min_intensity_in_per_hr REAL NOT NULL,
+    max_1h_intensity_in_per_hr REAL NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    PRIMARY KEY (station_id, date)
+);
+
+ALTER TABLE gauge_threshold_events ADD COLUMN max_15min_intensity_in_per_hr REAL;
+ALTER TABLE gauge_threshold_events ADD COLUMN max_1h_intensity_in_per_hr REAL;
+
+INSERT OR IGNORE INTO daily_rainfall_intensity
+    (station_id, date, max_15min_intensity_in_per_hr, max_1h_intensity_in_per_hr)
+SELECT station_id,
+       substr(reading_datetime, 1, 10),
+       ROUND(MAX(rain_15min) * 4, 2),
+       ROUND(MAX(rain_1h), 2)
+FROM (
+    SELECT station_id, reading_datetime,
+           SUM(incremental_inches) OVER (
+               PARTITION BY station_id ORDER BY CAST(strftime('%s', reading_datetime) AS INTEGER)
+               RANGE BETWEEN 899 PRECEDING AND CURRENT ROW
+           ) AS rain_15min,
+           SUM(incremental_inches) OVER (
+               PARTITION BY station_id ORDER BY CAST(strftime('%s', reading_datetime) AS INTEGER)
+               RANGE BETWEEN 3599 PRECEDING AND CURRENT ROW
+           ) AS rain_1h
+    FROM rain_readings
+    WHERE data_source = 'live_scrape'
+)
+GROUP BY 1, 2;
@@ -0,0 +1,807 @@
+// Analytical export of readings, monthly summaries, and gauges as Parquet
+//
+// The analytics export job publishes a full copy of the rainfall data under
+// ANALYTICS_EXPORT_DIR so analysts can run ad-hoc SQL (DuckDB, pandas, Spark) against a
+// local download instead of the production database:
+//   <dir>/LATEST                                   ID of the newest complete export
+//   <dir>/<export_id>/manifest.json                tables, files, and row counts
+//   <dir>/<export_id>/gauges.parquet
+//   <dir>/<export_id>/monthly_summaries.parquet
+//   <dir>/<export_id>/readings/water_year=<wy>/readings.parquet
+//
+// Readings are partitioned by water year in Hive style, so DuckDB reads them with
+// `read_parquet('readings/*/*.parquet', hive_partitioning = true)` and gets a
+// `water_year` column. Files are zstd-compressed; timestamps are UTC.
+//
+// An export is written to a hidden staging directory and renamed into place before
+// LATEST moves to it, so readers never see a partial export. The previous export is
+// kept so downloads that started from its manifest can finish.
+
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use arrow_array::builder::{
+    ArrayBuilder, Date32Builder, Float64Builder, Int32Builder, StringBuilder,
+    TimestampMicrosecondBuilder,
+};
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef, TimeUnit};
+use chrono::{DateTime, NaiveDate, Utc};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::{Compression, ZstdLevel};
+use parquet::errors::ParquetError;
+use parquet::file::properties::WriterProperties;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+use utoipa::ToSchema;
+
+use crate::db::{DbError, ExportReading, GisGauge, MonthlyRainfallSummary};
+use crate::services::bootstrap_service::water_year_of;
+use crate::storage::ObjectStore;
+
+/// Version of the export layout; bump when files or columns change
+pub const ANALYTICS_FORMAT_VERSION: u32 = 1;
+
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// File at the export root naming the newest complete export
+pub const LATEST_FILE: &str = "LATEST";
+
+pub const GAUGES_TABLE: &str = "gauges";
+pub const MONTHLY_SUMMARIES_TABLE: &str = "monthly_summaries";
+pub const READINGS_TABLE: &str = "readings";
+
+pub const DEFAULT_ANALYTICS_EXPORT_INTERVAL_MINUTES: u64 = 1440;
+
+/// Complete exports kept on disk: the latest and the one before it
+const KEPT_EXPORTS: usize = 2;
+
+/// Prefix of staging directories; exports in progress are never listed or served
+const STAGING_PREFIX: &str = ".staging-";
+
+/// Rows buffered before they are handed to the Parquet writer
+const BATCH_ROWS: usize = 8192;
+
+/// Settings for the analytics export job
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnalyticsExportConfig {
+    /// ANALYTICS_EXPORT_DIR
+    pub dir: PathBuf,
+    /// ANALYTICS_EXPORT_INTERVAL_MINUTES, default 1440
+    pub interval_minutes: u64,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AnalyticsError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Parquet error: {0}")]
+    Parquet(#[from] ParquetError),
+
+    #[error("Arrow error: {0}")]
+    Arrow(#[from] ArrowError),
+
+    #[error("Invalid export manifest: {0}")]
+    Manifest(#[from] serde_json::Error),
+
+    #[error("Analytics exports are disabled (ANALYTICS_EXPORT_DIR is not set)")]
+    Disabled,
+
+    #[error("No analytics export has been published yet")]
+    NotPublished,
+
+    #[error("Export {export_id} has no file {path}")]
+    FileNotFound { export_id: String, path: String },
+
+    #[error(transparent)]
+    Database(#[from] DbError),
+}
+
+/// Contents of `manifest.json`, also served by GET /api/v1/exports/analytics
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AnalyticsManifest {
+    #[schema(example = 1)]
+    pub format_version: u32,
+    /// Directory of this export; files download from
+    /// `/api/v1/exports/analytics/{export_id}/{path}`
+    #[schema(example = "20250215T060000Z")]
+    pub export_id: String,
+    #[schema(example = "2025-02-15T06:00:00Z")]
+    pub created_at: DateTime<Utc>,
+    /// Backend the data was read from ("postgres" or "sqlite")
+    #[schema(example = "postgres")]
+    pub backend: String,
+    /// rain-tracker-service version that wrote the export
+    #[schema(example = "0.3.0")]
+    pub tool_version: String,
+    pub tables: Vec<AnalyticsTable>,
+}
+
+impl AnalyticsManifest {
+    /// Whether `path` is one of the export's files (or the manifest itself)
+    pub fn lists(&self, path: &str) -> bool {
+        path == MANIFEST_FILE
+            || self
+                .tables
+                .iter()
+                .flat_map(|table| &table.files)
+                .any(|file| file.path == path)
+    }
+}
+
+/// One table of an export, split across one or more Parquet files
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AnalyticsTable {
+    #[schema(example = "readings")]
+    pub name: String,
+    #[schema(example = 1843210)]
+    pub rows: u64,
+    pub files: Vec<AnalyticsFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AnalyticsFile {
+    /// Path relative to the export directory
+    #[schema(example = "readings/water_year=2025/readings.parquet")]
+    pub path: String,
+    #[schema(example = 96412)]
+    pub rows: u64,
+    #[schema(example = 412733)]
+    pub bytes: u64,
+}
+
+/// ID of an export created at `created_at`; IDs sort in creation order
+pub fn export_id(created_at: DateTime<Utc>) -> String {
+    created_at.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Columns of one table, buffered until they are written as a record batch
+trait ColumnBuilder: Default {
+    type Row;
+
+    fn schema() -> SchemaRef;
+    fn append(&mut self, row: &Self::Row);
+    fn len(&self) -> usize;
+    fn finish(&mut self) -> Vec<ArrayRef>;
+}
+
+/// A Parquet file being written, flushed every BATCH_ROWS rows
+struct ParquetTable<B: ColumnBuilder> {
+    path: String,
+    full_path: PathBuf,
+    writer: ArrowWriter<File>,
+    columns: B,
+    rows: u64,
+}
+
+impl<B: ColumnBuilder> ParquetTable<B> {
+    /// Create `path` (relative to `dir`), with its parent directories
+    fn create(dir: &Path, path: &str) -> Result<Self, AnalyticsError> {
+        let full_path = dir.join(path);
+        if let Some(parent) = full_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::ZSTD(ZstdLevel::default()))
+            .build();
+        let writer =
+            ArrowWriter::try_new(File::create(&full_path)?, B::schema(), Some(properties))?;
+
+        Ok(Self {
+            path: path.to_string(),
+            full_path,
+            writer,
+            columns: B::default(),
+            rows: 0,
+        })
+    }
+
+    fn push(&mut self, row: &B::Row) -> Result<(), AnalyticsError> {
+        self.columns.append(row);
+        self.rows += 1;
+        if self.columns.len() >= BATCH_ROWS {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), AnalyticsError> {
+        if self.columns.len() > 0 {
+            let batch = RecordBatch::try_new(B::schema(), self.columns.finish())?;
+            self.writer.write(&batch)?;
+        }
+        Ok(())
+    }
+
+    fn close(mut self) -> Result<AnalyticsFile, AnalyticsError> {
+        self.flush()?;
+        self.writer.close()?;
+        Ok(AnalyticsFile {
+            bytes: fs::metadata(&self.full_path)?.len(),
+            path: self.path,
+            rows: self.rows,
+        })
+    }
+}
+
+/// Write every row to one Parquet file, `path` relative to `dir`
+fn write_table<B: ColumnBuilder>(
+    dir: &Path,
+    path: &str,
+    rows: &[B::Row],
+) -> Result<AnalyticsFile, AnalyticsError> {
+    let mut table = ParquetTable::<B>::create(dir, path)?;
+    for row in rows {
+        table.push(row)?;
+    }
+    table.close()
+}
+
+pub fn write_gauges(dir: &Path, gauges: &[GisGauge]) -> Result<AnalyticsFile, AnalyticsError> {
+    write_table::<GaugeColumns>(dir, &format!("{GAUGES_TABLE}.parquet"), gauges)
+}
+
+pub fn write_monthly_summaries(
+    dir: &Path,
+    summaries: &[MonthlyRainfallSummary],
+) -> Result<AnalyticsFile, AnalyticsError> {
+    write_table::<MonthlyColumns>(
+        dir,
+        &format!("{MONTHLY_SUMMARIES_TABLE}.parquet"),
+        summaries,
+    )
+}
+
+/// Readings split into one file per water year
+///
+/// Readings must arrive oldest first, so each water year's file is opened once.
+pub struct ReadingPartitions {
+    dir: PathBuf,
+    current: Option<(i32, ParquetTable<ReadingColumns>)>,
+    files: Vec<AnalyticsFile>,
+}
+
+impl ReadingPartitions {
+    pub fn new(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+            current: None,
+            files: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, reading: &ExportReading) -> Result<(), AnalyticsError> {
+        let water_year = water_year_of(reading.reading_datetime.date_naive());
+        match &mut self.current {
+            Some((current, table)) if *current == water_year => table.push(reading),
+            _ => {
+                self.close_current()?;
+                let path = format!("{READINGS_TABLE}/water_year={water_year}/readings.parquet");
+                let table = ParquetTable::create(&self.dir, &path)?;
+                let (_, table) = self.current.insert((water_year, table));
+                table.push(reading)
+            }
+        }
+    }
+
+    /// Close the last file, returning every file written in water year order
+    pub fn finish(mut self) -> Result<Vec<AnalyticsFile>, AnalyticsError> {
+        self.close_current()?;
+        Ok(self.files)
+    }
+
+    fn close_current(&mut self) -> Result<(), AnalyticsError> {
+        if let Some((_, table)) = self.current.take() {
+            self.files.push(table.close()?);
+        }
+        Ok(())
+    }
+}
+
+/// A table made of `files`, with their rows summed
+pub fn table(name: &str, files: Vec<AnalyticsFile>) -> AnalyticsTable {
+    AnalyticsTable {
+        name: name.to_string(),
+        rows: files.iter().map(|file| file.rows).sum(),
+        files,
+    }
+}
+
+fn utc_timestamp() -> DataType {
+    DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()))
+}
+
+fn timestamp_builder() -> TimestampMicrosecondBuilder {
+    TimestampMicrosecondBuilder::new().with_timezone("UTC")
+}
+
+/// Days since 1970-01-01, Parquet's DATE
+fn days_since_epoch(date: NaiveDate) -> i32 {
+    (date - DateTime::UNIX_EPOCH.date_naive()).num_days() as i32
+}
+
+struct ReadingColumns {
+    station_id: StringBuilder,
+    reading_datetime: TimestampMicrosecondBuilder,
+    cumulative_inches: Float64Builder,
+    incremental_inches: Float64Builder,
+    data_source: StringBuilder,
+}
+
+impl Default for ReadingColumns {
+    fn default() -> Self {
+        Self {
+            station_id: StringBuilder::new(),
+            reading_datetime: timestamp_builder(),
+            cumulative_inches: Float64Builder::new(),
+            incremental_inches: Float64Builder::new(),
+            data_source: StringBuilder::new(),
+        }
+    }
+}
+
+impl ColumnBuilder for ReadingColumns {
+    type Row = ExportReading;
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("station_id", DataType::Utf8, false),
+            Field::new("reading_datetime", utc_timestamp(), false),
+            Field::new("cumulative_inches", DataType::Float64, false),
+            Field::new("incremental_inches", DataType::Float64, false),
+            Field::new("data_source", DataType::Utf8, false),
+        ]))
+    }
+
+    fn append(&mut self, row: &ExportReading) {
+        self.station_id.append_value(&row.station_id);
+        self.reading_datetime
+            .append_value(row.reading_datetime.timestamp_micros());
+        self.cumulative_inches.append_value(row.cumulative_inches);
+        self.incremental_inches.append_value(row.incremental_inches);
+        self.data_source.append_value(&row.data_source);
+    }
+
+    fn len(&self) -> usize {
+        self.station_id.len()
+    }
+
+    fn finish(&mut self) -> Vec<ArrayRef> {
+        vec![
+            Arc::new(self.station_id.finish()),
+            Arc::new(self.reading_datetime.finish()),
+            Arc::new(self.cumulative_inches.finish()),
+            Arc::new(self.incremental_inches.finish()),
+            Arc::new(self.data_source.finish()),
+        ]
+    }
+}
+
+struct MonthlyColumns {
+    station_id: StringBuilder,
+    year: Int32Builder,
+    month: Int32Builder,
+    total_rainfall_inches: Float64Builder,
+    reading_count: Int32Builder,
+    first_reading_date: TimestampMicrosecondBuilder,
+    last_reading_date: TimestampMicrosecondBuilder,
+    min_cumulative_inches: Float64Builder,
+    max_cumulative_inches: Float64Builder,
+    flagged_count: Int32Builder,
+    estimated_count: Int32Builder,
+    footnoted_count: Int32Builder,
+    updated_at: TimestampMicrosecondBuilder,
+}
+
+impl Default for MonthlyColumns {
+    fn default() -> Self {
+        Self {
+            station_id: StringBuilder::new(),
+            year: Int32Builder::new(),
+            month: Int32Builder::new(),
+            total_rainfall_inches: Float64Builder::new(),
+            reading_count: Int32Builder::new(),
+            first_reading_date: timestamp_builder(),
+            last_reading_date: timestamp_builder(),
+            min_cumulative_inches: Float64Builder::new(),
+            max_cumulative_inches: Float64Builder::new(),
+            flagged_count: Int32Builder::new(),
+            estimated_count: Int32Builder::new(),
+            footnoted_count: Int32Builder::new(),
+            updated_at: timestamp_builder(),
+        }
+    }
+}
+
+impl ColumnBuilder for MonthlyColumns {
+    type Row = MonthlyRainfallSummary;
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("station_id", DataType::Utf8, false),
+            Field::new("year", DataType::Int32, false),
+            Field::new("month", DataType::Int32, false),
+            Field::new("total_rainfall_inches", DataType::Float64, false),
+            Field::new("reading_count", DataType::Int32, false),
+            Field::new("first_reading_date", utc_timestamp(), true),
+            Field::new("last_reading_date", utc_timestamp(), true),
+            Field::new("min_cumulative_inches", DataType::Float64, true),
+            Field::new("max_cumulative_inches", DataType::Float64, true),
+            Field::new("flagged_count", DataType::Int32, true),
+            Field::new("estimated_count", DataType::Int32, true),
+            Field::new("footnoted_count", DataType::Int32, true),
+            Field::new("updated_at", utc_timestamp(), false),
+        ]))
+    }
+
+    fn append(&mut self, row: &MonthlyRainfallSummary) {
+        self.station_id.append_value(&row.station_id);
+        self.year.append_value(row.year);
+        self.month.append_value(row.month);
+        self.total_rainfall_inches
+            .append_value(row.total_rainfall_inches);
+        self.reading_count.append_value(row.reading_count);
+        self.first_reading_date
+            .append_option(row.first_reading_date.map(|t| t.timestamp_micros()));
+        self.last_reading_date
+            .append_option(row.last_reading_date.map(|t| t.timestamp_micros()));
+        self.min_cumulative_inches
+            .append_option(row.min_cumulative_inches);
+        self.max_cumulative_inches
+            .append_option(row.max_cumulative_inches);
+        self.flagged_count.append_option(row.flagged_count);
+        self.estimated_count.append_option(row.estimated_count);
+        self.footnoted_count.append_option(row.footnoted_count);
+        self.updated_at
+            .append_value(row.updated_at.timestamp_micros());
+    }
+
+    fn len(&self) -> usize {
+        self.station_id.len()
+    }
+
+    fn finish(&mut self) -> Vec<ArrayRef> {
+        vec![
+            Arc::new(self.station_id.finish()),
+            Arc::new(self.year.finish()),
+            Arc::new(self.month.finish()),
+            Arc::new(self.total_rainfall_inches.finish()),
+            Arc::new(self.reading_count.finish()),
+            Arc::new(self.first_reading_date.finish()),
+            Arc::new(self.last_reading_date.finish()),
+            Arc::new(self.min_cumulative_inches.finish()),
+            Arc::new(self.max_cumulative_inches.finish()),
+            Arc::new(self.flagged_count.finish()),
+            Arc::new(self.estimated_count.finish()),
+            Arc::new(self.footnoted_count.finish()),
+            Arc::new(self.updated_at.finish()),
+        ]
+    }
+}
+
+#[derive(Default)]
+struct GaugeColumns {
+    station_id: StringBuilder,
+    station_name: StringBuilder,
+    city: StringBuilder,
+    county: StringBuilder,
+    location_description: StringBuilder,
+    latitude: Float64Builder,
+    longitude: Float64Builder,
+    elevation_ft: Int32Builder,
+    status: StringBuilder,
+    data_begins_date: Date32Builder,
+    data_ends_date: Date32Builder,
+    forecast_zone: StringBuilder,
+}
+
+impl ColumnBuilder for GaugeColumns {
+    type Row = GisGauge;
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("station_id", DataType::Utf8, false),
+            Field::new("station_name", DataType::Utf8, true),
+            Field::new("city", DataType::Utf8, true),
+            Field::new("county", DataType::Utf8, true),
+            Field::new("location_description", DataType::Utf8, true),
+            Field::new("latitude", DataType::Float64, true),
+            Field::new("longitude", DataType::Float64, true),
+            Field::new("elevation_ft", DataType::Int32, true),
+            Field::new("status", DataType::Utf8, true),
+            Field::new("data_begins_date", DataType::Date32, true),
+            Field::new("data_ends_date", DataType::Date32, true),
+            Field::new("forecast_zone", DataType::Utf8, true),
+        ]))
+    }
+
+    fn append(&mut self, row: &GisGauge) {
+        self.station_id.append_value(&row.station_id);
+        self.station_name.append_option(row.station_name.as_deref());
+        self.city.append_option(row.city.as_deref());
+        self.county.append_option(row.county.as_deref());
+        self.location_description
+            .append_option(row.location_description.as_deref());
+        self.latitude.append_option(row.latitude);
+        self.longitude.append_option(row.longitude);
+        self.elevation_ft.append_option(row.elevation_ft);
+        self.status.append_option(row.status.as_deref());
+        self.data_begins_date
+            .append_option(row.data_begins_date.map(days_since_epoch));
+        self.data_ends_date
+            .append_option(row.data_ends_date.map(days_since_epoch));
+        self.forecast_zone
+            .append_option(row.forecast_zone.as_deref());
+    }
+
+    fn len(&self) -> usize {
+        self.station_id.len()
+    }
+
+    fn finish(&mut self) -> Vec<ArrayRef> {
+        vec![
+            Arc::new(self.station_id.finish()),
+            Arc::new(self.station_name.finish()),
+            Arc::new(self.city.finish()),
+            Arc::new(self.county.finish()),
+            Arc::new(self.location_description.finish()),
+            Arc::new(self.latitude.finish()),
+            Arc::new(self.longitude.finish()),
+            Arc::new(self.elevation_ft.finish()),
+            Arc::new(self.status.finish()),
+            Arc::new(self.data_begins_date.finish()),
+            Arc::new(self.data_ends_date.finish()),
+            Arc::new(self.forecast_zone.finish()),
+        ]
+    }
+}
+
+/// Published exports under ANALYTICS_EXPORT_DIR
+#[derive(Debug, Clone)]
+pub struct AnalyticsStore {
+    objects: ObjectStore,
+}
+
+impl AnalyticsStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            objects: ObjectStore::new(root),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        self.objects.root()
+    }
+
+    /// An empty staging directory for `export_id`, after removing any left behind by
+    /// interrupted exports
+    pub fn stage(&self, export_id: &str) -> io::Result<PathBuf> {
+        fs::create_dir_all(self.root())?;
+        for entry in fs::read_dir(self.root())? {
+            let entry = entry?;
+            if entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with(STAGING_PREFIX)
+            {
+                debug!("Removing abandoned export {}", entry.path().display());
+                fs::remove_dir_all(entry.path())?;
+            }
+        }
+
+        let staging = self.root().join(format!("{STAGING_PREFIX}{export_id}"));
+        fs::create_dir_all(&staging)?;
+        Ok(staging)
+    }
+
+    /// Write the manifest into `staging`, move it into place, and point LATEST at it
+    ///
+    /// Exports older than the previous one are removed afterwards.
+    pub async fn publish(
+        &self,
+        staging: &Path,
+        manifest: &AnalyticsManifest,
+    ) -> Result<(), AnalyticsError> {
+        let file = File::create(staging.join(MANIFEST_FILE))?;
+        serde_json::to_writer_pretty(file, manifest)?;
+
+        let target = self.root().join(&manifest.export_id);
+        if target.exists() {
+            fs::remove_dir_all(&target)?;
+        }
+        fs::rename(staging, &target)?;
+        self.objects
+            .put(LATEST_FILE, manifest.export_id.as_bytes())
+            .await?;
+        info!(
+            "Published analytics export {} to {}",
+            manifest.export_id,
+            target.display()
+        );
+
+        self.prune()?;
+        Ok(())
+    }
+
+    /// Manifest of the newest complete export; None before the first is published
+    pub async fn latest(&self) -> Result<Option<AnalyticsManifest>, AnalyticsError> {
+        let Some(export_id) = self.objects.get(LATEST_FILE).await? else {
+            return Ok(None);
+        };
+        let export_id = String::from_utf8_lossy(&export_id).trim().to_string();
+        let Some(manifest) = self
+            .objects
+            .get(&format!("{export_id}/{MANIFEST_FILE}"))
+            .await?
+        else {
+            return Ok(None);
+        };
+        Ok(Some(serde_json::from_slice(&manifest)?))
+    }
+
+    /// Contents of a published export's file; None for unknown exports and files
+    pub async fn read(
+        &self,
+        export_id: &str,
+        path: &str,
+    ) -> Result<Option<Vec<u8>>, AnalyticsError> {
+        if export_id.starts_with('.') || export_id.contains('/') {
+            return Ok(None);
+        }
+        match self.objects.get(&format!("{export_id}/{path}")).await {
+            Ok(bytes) => Ok(bytes),
+            // Keys that could escape the export directory name nothing that exists
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Remove all but the newest KEPT_EXPORTS exports
+    fn prune(&self) -> io::Result<()> {
+        let mut exports: Vec<PathBuf> = fs::read_dir(self.root())?
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+            .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+            .map(|entry| entry.path())
+            .collect();
+        exports.sort();
+
+        let stale = exports.len().saturating_sub(KEPT_EXPORTS);
+        for export in &exports[..stale] {
+            info!("Removing old analytics export {}", export.display());
+            fs::remove_dir_all(export)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+
+    fn reading(station_id: &str, datetime: DateTime<Utc>, inches: f64) -> ExportReading {
+        ExportReading {
+            station_id: station_id.to_string(),
+            reading_datetime: datetime,
+            cumulative_inches: inches,
+            incremental_inches: inches,
+            data_source: "live_scrape".to_string(),
+        }
+    }
+
+    fn row_count(path: &Path) -> i64 {
+        let reader = SerializedFileReader::new(File::open(path).unwrap()).unwrap();
+        reader.metadata().file_metadata().num_rows()
+    }
+
+    #[test]
+    fn test_readings_are_partitioned_by_water_year() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut partitions = ReadingPartitions::new(dir.path());
+        for reading in [
+            reading(
+                "59700",
+                Utc.with_ymd_and_hms(2024, 9, 30, 23, 0, 0).unwrap(),
+                0.1,
+            ),
+            reading(
+                "59700",
+                Utc.with_ymd_and_hms(2024, 10, 1, 1, 0, 0).unwrap(),
+                0.2,
+            ),
+            reading(
+                "41200",
+                Utc.with_ymd_and_hms(2025, 2, 14, 6, 0, 0).unwrap(),
+                0.3,
+            ),
+        ] {
+            partitions.push(&reading).unwrap();
+        }
+
+        let files = partitions.finish().unwrap();
+        let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "readings/water_year=2024/readings.parquet",
+                "readings/water_year=2025/readings.parquet"
+            ]
+        );
+        assert_eq!(files[1].rows, 2);
+        assert_eq!(row_count(&dir.path().join(&files[1].path)), 2);
+        assert_eq!(table(READINGS_TABLE, files).rows, 3);
+    }
+
+    #[test]
+    fn test_write_gauges_keeps_missing_values() {
+        let dir = tempfile::tempdir().unwrap();
+        let gauge = GisGauge {
+            station_id: "59700".to_string(),
+            station_name: Some("Aztec Park".to_string()),
+            city: None,
+            county: None,
+            location_description: None,
+            latitude: None,
+            longitude: None,
+            elevation_ft: Some(1465),
+            status: Some("Active".to_string()),
+            data_begins_date: NaiveDate::from_ymd_opt(1998, 6, 1),
+            data_ends_date: None,
+            forecast_zone: None,
+        };
+
+        let file = write_gauges(dir.path(), &[gauge]).unwrap();
+        assert_eq!(file.path, "gauges.parquet");
+        assert_eq!(file.rows, 1);
+        assert_eq!(row_count(&dir.path().join("gauges.parquet")), 1);
+        assert_eq!(
+            days_since_epoch(NaiveDate::from_ymd_opt(1970, 1, 2).unwrap()),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_publish_keeps_latest_two_exports() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = AnalyticsStore::new(dir.path());
+        assert_eq!(store.latest().await.unwrap(), None);
+
+        for day in 1..=3 {
+            let created_at = Utc.with_ymd_and_hms(2025, 2, day, 6, 0, 0).unwrap();
+            let id = export_id(created_at);
+            let staging = store.stage(&id).unwrap();
+            let file = write_gauges(&staging, &[]).unwrap();
+            let manifest = AnalyticsManifest {
+                format_version: ANALYTICS_FORMAT_VERSION,
+                export_id: id,
+                created_at,
+                backend: "postgres".to_string(),
+                tool_version: env!("CARGO_PKG_VERSION").to_string(),
+                tables: vec![table(GAUGES_TABLE, vec![file])],
+            };
+            store.publish(&staging, &manifest).await.unwrap();
+        }
+
+        let latest = store.latest().await.unwrap().unwrap();
+        assert_eq!(latest.export_id, "20250203T060000Z");
+        assert!(latest.lists("gauges.parquet"));
+        assert!(!dir.path().join("20250201T060000Z").exists());
+        assert!(dir.path().join("20250202T060000Z").exists());
+
+        let bytes = store.read("20250203T060000Z", "gauges.parquet").await;
+        assert!(bytes.unwrap().is_some());
+        assert_eq!(
+            store.read("20250203T060000Z", "../LATEST").await.unwrap(),
+            None
+        );
+        assert_eq!(
+            store.read(".staging-x", "gauges.parquet").await.unwrap(),
+            None
+        );
+    }
+}
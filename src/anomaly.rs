@@ -0,0 +1,390 @@
+// Gauge malfunction detection
+//
+// The anomaly job looks for three signs of a broken gauge and records each finding in
+// `gauge_anomalies` for an admin to confirm or dismiss:
+// - Flat-line: a gauge that keeps reporting exact zeros for FLATLINE_DAYS or more while
+//   at least MIN_WET_NEIGHBORS gauges within NEIGHBOR_MILES recorded NEIGHBOR_WET_INCHES
+//   over the same stretch (a clogged funnel or stuck tipping bucket). Gauges that stop
+//   reporting altogether are offline, not flat-lined, and are left to the gauge status
+//   lifecycle.
+// - Spike: a single reading of SPIKE_INCHES or more, beyond what a storm delivers in one
+//   reporting interval (usually an electrical fault or a tampered bucket).
+// - Reset: cumulative rainfall dropping mid water year for one data source (a logger
+//   reboot or swapped logger).
+//
+// A finding is keyed by gauge, kind, and start time, so later runs extend an ongoing
+// flat-line instead of duplicating it, and never reopen one an admin dismissed.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+use utoipa::ToSchema;
+
+use crate::db::{GaugeAnomaly, GaugeLocation};
+use crate::geocode::distance_miles;
+
+/// How often the anomaly job runs
+pub const DEFAULT_ANOMALY_INTERVAL_MINUTES: u64 = 1440;
+
+/// Days of readings checked for spikes and resets each run
+pub const DEFAULT_ANOMALY_LOOKBACK_DAYS: u32 = 30;
+
+/// Shortest run of zero readings reported as a flat-line
+pub const DEFAULT_FLATLINE_DAYS: u32 = 14;
+
+/// Smallest single reading reported as a spike
+pub const DEFAULT_SPIKE_INCHES: f64 = 3.0;
+
+/// Gauges silent for longer than this are offline rather than flat-lined
+pub const OFFLINE_AFTER_DAYS: i64 = 2;
+
+/// Gauges this close are compared with a flat-lined gauge
+pub const NEIGHBOR_MILES: f64 = 10.0;
+
+/// Rainfall over the dry stretch at which a neighbor counts as wet
+pub const NEIGHBOR_WET_INCHES: f64 = 0.5;
+
+/// Wet neighbors needed before a dry stretch is suspicious rather than local
+pub const MIN_WET_NEIGHBORS: usize = 2;
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Settings for the anomaly job
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnomalyConfig {
+    /// ANOMALY_INTERVAL_MINUTES, default 1440
+    pub interval_minutes: u64,
+    pub rules: AnomalyRules,
+    /// ANOMALY_WEBHOOK_URL: new findings are POSTed here as JSON when set
+    pub webhook_url: Option<String>,
+}
+
+impl Default for AnomalyConfig {
+    fn default() -> Self {
+        Self {
+            interval_minutes: DEFAULT_ANOMALY_INTERVAL_MINUTES,
+            rules: AnomalyRules::default(),
+            webhook_url: None,
+        }
+    }
+}
+
+/// Thresholds the anomaly job applies
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnomalyRules {
+    /// ANOMALY_LOOKBACK_DAYS, default 30
+    pub lookback_days: u32,
+    /// ANOMALY_FLATLINE_DAYS, default 14
+    pub flatline_days: u32,
+    /// ANOMALY_SPIKE_INCHES, default 3.0
+    pub spike_inches: f64,
+}
+
+impl Default for AnomalyRules {
+    fn default() -> Self {
+        Self {
+            lookback_days: DEFAULT_ANOMALY_LOOKBACK_DAYS,
+            flatline_days: DEFAULT_FLATLINE_DAYS,
+            spike_inches: DEFAULT_SPIKE_INCHES,
+        }
+    }
+}
+
+/// Kind of malfunction a finding points to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyKind {
+    /// Weeks of exact zeros while neighbors recorded rain
+    Flatline,
+    /// A single reading too large to be real
+    Spike,
+    /// Cumulative rainfall dropped mid water year
+    Reset,
+}
+
+impl AnomalyKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AnomalyKind::Flatline => "flatline",
+            AnomalyKind::Spike => "spike",
+            AnomalyKind::Reset => "reset",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "flatline" => Some(AnomalyKind::Flatline),
+            "spike" => Some(AnomalyKind::Spike),
+            "reset" => Some(AnomalyKind::Reset),
+            _ => None,
+        }
+    }
+}
+
+/// Where a finding stands in admin review
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyStatus {
+    /// Not reviewed yet
+    Open,
+    /// A real malfunction; field staff should visit
+    Confirmed,
+    /// Not a malfunction; later runs leave it dismissed
+    Dismissed,
+}
+
+impl AnomalyStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AnomalyStatus::Open => "open",
+            AnomalyStatus::Confirmed => "confirmed",
+            AnomalyStatus::Dismissed => "dismissed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "open" => Some(AnomalyStatus::Open),
+            "confirmed" => Some(AnomalyStatus::Confirmed),
+            "dismissed" => Some(AnomalyStatus::Dismissed),
+            _ => None,
+        }
+    }
+}
+
+/// A suspected malfunction found by one run
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnomalyFinding {
+    pub station_id: String,
+    pub kind: AnomalyKind,
+    pub started_at: DateTime<Utc>,
+    /// Last zero reading of a flat-line; None for single-reading findings
+    pub ended_at: Option<DateTime<Utc>>,
+    /// Neighbors' median rainfall, the spike reading, or the cumulative drop
+    pub magnitude_inches: f64,
+    pub detail: String,
+}
+
+/// A gauge reporting only zeros since `dry_since`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DryRun {
+    pub station_id: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// First reading after the gauge's last non-zero one
+    pub dry_since: DateTime<Utc>,
+    pub last_reading: DateTime<Utc>,
+}
+
+/// Station IDs of the other gauges within NEIGHBOR_MILES of a dry gauge
+pub fn neighbors(run: &DryRun, gauges: &[GaugeLocation]) -> Vec<String> {
+    gauges
+        .iter()
+        .filter(|g| g.station_id != run.station_id)
+        .filter(|g| {
+            distance_miles(run.latitude, run.longitude, g.latitude, g.longitude) <= NEIGHBOR_MILES
+        })
+        .map(|g| g.station_id.clone())
+        .collect()
+}
+
+/// A flat-line finding when enough neighbors were wet over the dry stretch
+///
+/// `neighbor_totals` is each neighbor's rainfall over the stretch; neighbors without
+/// readings may be left out.
+pub fn flatline_finding(run: &DryRun, neighbor_totals: &[f64]) -> Option<AnomalyFinding> {
+    let mut wet: Vec<f64> = neighbor_totals
+        .iter()
+        .copied()
+        .filter(|inches| *inches >= NEIGHBOR_WET_INCHES)
+        .collect();
+    if wet.len() < MIN_WET_NEIGHBORS {
+        return None;
+    }
+    wet.sort_by(f64::total_cmp);
+    let median = if wet.len().is_multiple_of(2) {
+        (wet[wet.len() / 2 - 1] + wet[wet.len() / 2]) / 2.0
+    } else {
+        wet[wet.len() / 2]
+    };
+
+    let days = (run.last_reading - run.dry_since).num_days();
+    Some(AnomalyFinding {
+        station_id: run.station_id.clone(),
+        kind: AnomalyKind::Flatline,
+        started_at: run.dry_since,
+        ended_at: Some(run.last_reading),
+        magnitude_inches: median,
+        detail: format!(
+            "Only zeros for {days} days while {} of {} gauges within {NEIGHBOR_MILES} mi \
+             recorded {NEIGHBOR_WET_INCHES}+ in (median {median:.2} in)",
+            wet.len(),
+            neighbor_totals.len(),
+        ),
+    })
+}
+
+/// A spike finding for one reading of at least `limit_inches`
+pub fn spike_finding(
+    station_id: String,
+    reading_datetime: DateTime<Utc>,
+    inches: f64,
+    limit_inches: f64,
+) -> AnomalyFinding {
+    AnomalyFinding {
+        station_id,
+        kind: AnomalyKind::Spike,
+        started_at: reading_datetime,
+        ended_at: None,
+        magnitude_inches: inches,
+        detail: format!("{inches:.2} in in a single reading (limit {limit_inches:.2} in)"),
+    }
+}
+
+/// A reset finding for a cumulative drop between consecutive readings
+pub fn reset_finding(
+    station_id: String,
+    reading_datetime: DateTime<Utc>,
+    previous_cumulative: f64,
+    cumulative: f64,
+) -> AnomalyFinding {
+    AnomalyFinding {
+        station_id,
+        kind: AnomalyKind::Reset,
+        started_at: reading_datetime,
+        ended_at: None,
+        magnitude_inches: previous_cumulative - cumulative,
+        detail: format!(
+            "Cumulative rainfall dropped from {previous_cumulative:.2} to {cumulative:.2} in \
+             mid water year"
+        ),
+    }
+}
+
+/// POSTs newly found anomalies to a webhook
+///
+/// Delivery is best effort: failures are logged and the findings stay in the review
+/// queue either way.
+#[derive(Debug, Clone)]
+pub struct AnomalyAlerter {
+    client: Client,
+    url: String,
+}
+
+#[derive(Serialize)]
+struct AlertBody<'a> {
+    anomalies: &'a [GaugeAnomaly],
+}
+
+impl AnomalyAlerter {
+    pub fn new(url: impl Into<String>) -> Self {
+        let client = Client::builder()
+            .timeout(WEBHOOK_TIMEOUT)
+            .build()
+            .expect("Failed to create HTTP client");
+        Self {
+            client,
+            url: url.into(),
+        }
+    }
+
+    pub async fn send(&self, anomalies: &[GaugeAnomaly]) {
+        if anomalies.is_empty() {
+            return;
+        }
+        let body = match serde_json::to_vec(&AlertBody { anomalies }) {
+            Ok(body) => body,
+            Err(e) => {
+                warn!(error = %e, "Failed to serialize anomaly alert");
+                return;
+            }
+        };
+
+        let result = self
+            .client
+            .post(&self.url)
+            .header(CONTENT_TYPE, "application/json")
+            .body(body)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+
+        match result {
+            Ok(_) => debug!("Sent {} anomaly alerts", anomalies.len()),
+            Err(e) => warn!(url = %self.url, error = %e, "Anomaly webhook delivery failed"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn run() -> DryRun {
+        DryRun {
+            station_id: "59700".to_string(),
+            latitude: 33.5,
+            longitude: -112.0,
+            dry_since: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+            last_reading: Utc.with_ymd_and_hms(2025, 1, 22, 6, 0, 0).unwrap(),
+        }
+    }
+
+    fn gauge(station_id: &str, latitude: f64) -> GaugeLocation {
+        GaugeLocation {
+            station_id: station_id.to_string(),
+            latitude,
+            longitude: -112.0,
+        }
+    }
+
+    #[test]
+    fn test_neighbors_within_radius() {
+        // 0.1° of latitude is about 6.9 miles
+        let gauges = [
+            gauge("59700", 33.5),
+            gauge("11000", 33.6),
+            gauge("12000", 33.3),
+        ];
+        assert_eq!(neighbors(&run(), &gauges), vec!["11000"]);
+    }
+
+    #[test]
+    fn test_flatline_needs_wet_neighbors() {
+        assert_eq!(flatline_finding(&run(), &[0.0, 1.2, 0.3]), None);
+
+        let finding = flatline_finding(&run(), &[0.0, 1.2, 0.8, 2.0]).unwrap();
+        assert_eq!(finding.kind, AnomalyKind::Flatline);
+        assert_eq!(finding.started_at, run().dry_since);
+        assert_eq!(finding.ended_at, Some(run().last_reading));
+        assert!((finding.magnitude_inches - 1.2).abs() < 1e-9);
+        assert!(finding
+            .detail
+            .starts_with("Only zeros for 21 days while 3 of 4"));
+    }
+
+    #[test]
+    fn test_kind_and_status_round_trip() {
+        for kind in [
+            AnomalyKind::Flatline,
+            AnomalyKind::Spike,
+            AnomalyKind::Reset,
+        ] {
+            assert_eq!(AnomalyKind::parse(kind.as_str()), Some(kind));
+        }
+        for status in [
+            AnomalyStatus::Open,
+            AnomalyStatus::Confirmed,
+            AnomalyStatus::Dismissed,
+        ] {
+            assert_eq!(AnomalyStatus::parse(status.as_str()), Some(status));
+        }
+        assert_eq!(AnomalyKind::parse("drift"), None);
+    }
+}
@@ -1,78 +1,559 @@
+pub mod admin;
+pub mod annotations;
+pub mod anomalies;
+pub mod attachments;
+pub mod error;
+pub mod exports;
+pub mod idempotency;
+pub mod methods;
+pub mod ndjson;
+pub mod pagination;
+pub mod share;
+pub mod stats;
+pub mod usage;
+pub mod users;
+pub mod validation;
+
 use axum::response::Html;
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
-    response::IntoResponse,
-    routing::get,
+    extract::{OriginalUri, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{delete, get, post, put},
     Json, Router,
 };
 use serde::Serialize;
+use tower_http::cors::CorsLayer;
 use tracing::{debug, error, info, instrument, warn};
-use utoipa::{OpenApi, ToSchema};
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, SecurityScheme};
+use utoipa::{Modify, OpenApi, ToSchema};
 
-use crate::db::Reading;
-use crate::services::gauge_service::PaginationParams;
-use crate::services::{GaugeService, ReadingService};
+use crate::analytics::{AnalyticsFile, AnalyticsManifest, AnalyticsTable};
+use crate::api::error::{ApiError, ApiPath, ErrorCode, FieldError, ProblemDetails};
+use crate::api::methods::LastModified;
+use crate::api::pagination::PageLinks;
+use crate::api::validation::{
+    parse_year, StationPath, StationYearPath, StormPath, ValidatedPath, ValidatedQuery,
+};
+use crate::breaker::{BreakerState, UpstreamStatus};
+use crate::db::{FoprAvailability, RadarStorm, Reading, SlowQueryCapture};
+use crate::forecast::{ForecastError, ForecastPeriod, GridCell};
+use crate::leader::{LeaderStatus, Leadership};
+use crate::metrics::{Metrics, RouteSummary, StationReads, StatsSummary};
+use crate::pagination::PageInfo;
+use crate::radar::RadarAgreement;
+use crate::readiness::{CheckState, CheckStatus, Readiness, ReadinessCheck, ReadinessReport};
+use crate::services::gauge_service::{
+    GaugeFilterParams, GaugeIdsParams, GaugeIncludeParams, GaugeStatusUpdate, PaginationParams,
+};
+use crate::services::historical_import_service::{WaterYearGauge, WaterYearGauges};
+use crate::services::radar_service::{
+    GaugeRadarComparison, RadarComparison, RadarComparisonParams, RadarStormPage, RadarStormParams,
+};
+use crate::services::reading_service::{
+    CalendarParams, HistogramParams, RankingParams, ReadingPageParams, ReadingRangeParams,
+    YearSummaryParams, ZoneRainfallParams,
+};
+use crate::services::summary_service::{RecalcScope, RecalcStats};
+use crate::services::summary_view_service::{SummaryViewReport, SummaryViewStatus};
+use crate::services::threshold_service::{StormError, StormTimelineParams, ThresholdEventParams};
+use crate::services::zone_service::{ZoneCollection, ZoneFeature, ZoneProperties};
+use crate::services::{
+    AnalyticsExportService, AnnotationService, AnomalyService, AttachmentService,
+    CurrentConditionsService, DigestService, FoprAvailabilityService, ForecastService,
+    GaugeService, HistoricalImportService, IdempotencyService, JobRunService, RadarService,
+    ReadingQueryError, ReadingService, ShareService, SlowQueryService, SummaryService,
+    SummaryViewService, ThresholdService, UsageService, UserService, WeatherService, ZoneService,
+};
+use crate::tiles::{TileCoord, MAX_ZOOM};
 
 #[derive(Clone)]
 pub struct AppState {
     pub reading_service: ReadingService,
     pub gauge_service: GaugeService,
+    pub summary_service: SummaryService,
+    pub idempotency_service: IdempotencyService,
+    pub attachment_service: AttachmentService,
+    pub annotation_service: AnnotationService,
+    pub threshold_service: ThresholdService,
+    pub current_conditions_service: CurrentConditionsService,
+    /// Forecast zone polygons imported by `zones import`
+    pub zone_service: ZoneService,
+    /// NWS precipitation forecasts for gauges, cached per grid cell
+    pub forecast_service: ForecastService,
+    /// MRMS radar estimates imported by `radar import`
+    pub radar_service: RadarService,
+    /// Daily temperature and ET stored by the weather job
+    pub weather_service: WeatherService,
+    /// Users with their favorite gauges and saved views
+    pub user_service: UserService,
+    /// Users' daily and weekly digest subscriptions
+    pub digest_service: DigestService,
+    /// Signed share links to frozen rainfall snapshots
+    pub share_service: ShareService,
+    /// Per-user monthly request and byte counts, checked against quotas
+    pub usage_service: UsageService,
+    pub anomaly_service: AnomalyService,
+    pub summary_view_service: SummaryViewService,
+    pub slow_query_service: SlowQueryService,
+    /// Fetch, gauge list, and FOPR import run history
+    pub job_run_service: JobRunService,
+    /// Downloads water year files for admin gauge discovery
+    pub historical_import_service: HistoricalImportService,
+    /// FOPR file availability recorded by `historical-import probe fopr`
+    pub fopr_availability_service: FoprAvailabilityService,
+    /// Parquet exports of readings, summaries, and gauges for offline analysis
+    pub analytics_export_service: AnalyticsExportService,
+    /// Key required by /admin routes; admin API is disabled when None
+    pub admin_api_key: Option<String>,
+    /// Serve the Swagger UI at /docs/try
+    pub swagger_ui_enabled: bool,
+    /// Answer CORS requests from any origin; no CORS headers are sent otherwise
+    pub cors_allow_any_origin: bool,
+    /// Startup checks reported by /health/ready
+    pub readiness: Readiness,
+    /// Scheduler leader election reported by /health
+    pub leadership: Leadership,
+    /// Request counters for /metrics and /admin/stats
+    pub metrics: Metrics,
 }
 
 #[derive(Serialize, ToSchema)]
 pub struct HealthResponse {
-    pub status: String,
+    pub status: HealthStatus,
+    /// This replica's scheduler leadership; present only with LEADER_ELECTION_ENABLED
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub leader_election: Option<LeaderStatus>,
+    /// Circuit breakers of upstream hosts this process has requested, by host; omitted
+    /// until one has been requested
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub upstreams: Vec<UpstreamStatus>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Healthy,
 }
 
 pub fn create_router(state: AppState) -> Router {
+    let admin_routes = Router::new()
+        .route("/recalculate", post(admin::recalculate_summaries))
+        .route("/reconciliation", get(admin::get_reconciliation_report))
+        .route("/stats", get(stats::get_stats))
+        .route("/slow-queries", get(admin::get_slow_queries))
+        .route("/job-runs", get(admin::get_job_runs))
+        .route("/job-runs/trend", get(admin::get_job_run_trend))
+        .route("/summary-views", get(admin::get_summary_views))
+        .route("/summary-views/refresh", post(admin::refresh_summary_views))
+        .route(
+            "/water-years/{year}/gauges",
+            get(admin::get_water_year_gauges),
+        )
+        .route("/fopr-availability", get(admin::get_fopr_availability))
+        .route(
+            "/gauges/{station_id}/status",
+            post(admin::change_gauge_status),
+        )
+        .route(
+            "/gauges/{station_id}/attachments",
+            post(attachments::upload_gauge_attachment),
+        )
+        .route(
+            "/gauges/{station_id}/annotations",
+            post(annotations::create_gauge_annotation),
+        )
+        .route(
+            "/gauges/{station_id}/annotations/{annotation_id}",
+            delete(annotations::delete_gauge_annotation),
+        )
+        .route("/anomalies", get(anomalies::list_anomalies))
+        .route(
+            "/anomalies/{anomaly_id}/review",
+            post(anomalies::review_anomaly),
+        )
+        // Layers run bottom-up: the key check rejects before a key is claimed
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            idempotency::idempotency,
+        ))
+        // Added after the idempotency layer so stored responses never hold a user key
+        .route("/users", get(users::list_users).post(users::create_user))
+        .route("/users/{user_id}", delete(users::delete_user))
+        .route("/users/{user_id}/quota", put(users::set_user_quota))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            admin::require_admin_key,
+        ));
+
+    let user_routes = Router::new()
+        .route("/", get(users::get_me))
+        .route("/favorites", get(users::list_favorites))
+        .route(
+            "/favorites/{station_id}",
+            put(users::add_favorite).delete(users::remove_favorite),
+        )
+        .route("/views", get(users::list_views).post(users::create_view))
+        .route(
+            "/views/{view_id}",
+            put(users::update_view).delete(users::delete_view),
+        )
+        .route(
+            "/digest",
+            get(users::get_digest)
+                .put(users::subscribe_digest)
+                .delete(users::unsubscribe_digest),
+        )
+        .route("/digest/preview", get(users::preview_digest))
+        .route("/usage", get(usage::get_usage))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            users::require_user_key,
+        ));
+
     let api_routes = Router::new()
         .route("/health", get(health))
+        .route("/health/ready", get(health_ready))
         .route(
             "/readings/{station_id}/water-year/{year}",
-            get(get_water_year),
+            get(get_water_year).head(head_water_year),
         )
         .route(
             "/readings/{station_id}/calendar-year/{year}",
-            get(get_calendar_year),
+            get(get_calendar_year).head(head_calendar_year),
         )
+        .route("/readings/{station_id}", get(get_readings))
         .route("/readings/{station_id}/latest", get(get_latest))
+        .route("/readings/{station_id}/histogram", get(get_histogram))
+        .route("/readings/{station_id}/calendar", get(get_calendar))
+        .route("/readings/{station_id}/hourly", get(get_hourly))
+        .route("/rankings", get(get_rankings))
+        .route(
+            "/current",
+            get(get_current_conditions).head(head_current_conditions),
+        )
+        .route("/zones", get(get_zones))
+        .route("/zones/rainfall", get(get_zone_rainfall))
+        .route("/radar/storms", get(get_radar_storms))
+        .route("/radar/comparison", get(get_radar_comparison))
+        .route("/storms/{storm_id}/footprint", get(get_storm_footprint))
+        .route("/storms/{storm_id}/timeline", get(get_storm_timeline))
         .route("/gauges", get(get_all_gauges))
         .route("/gauges/{station_id}", get(get_gauge_by_id))
-        .with_state(state);
+        .route("/gauges/{station_id}/full", get(get_gauge_full))
+        .route("/gauges/{station_id}/coverage", get(get_gauge_coverage))
+        .route(
+            "/gauges/{station_id}/status-history",
+            get(get_gauge_status_history),
+        )
+        .route("/gauges/{station_id}/normals", get(get_gauge_normals))
+        .route("/gauges/{station_id}/forecast", get(get_gauge_forecast))
+        .route("/gauges/{station_id}/weather", get(get_gauge_weather))
+        .route(
+            "/gauges/{station_id}/attachments",
+            get(attachments::list_gauge_attachments),
+        )
+        .route(
+            "/gauges/{station_id}/attachments/{attachment_id}",
+            get(attachments::download_gauge_attachment),
+        )
+        .route(
+            "/gauges/{station_id}/annotations",
+            get(annotations::list_gauge_annotations),
+        )
+        .route(
+            "/gauges/{station_id}/threshold-events",
+            get(get_gauge_threshold_events),
+        )
+        .route(
+            "/share",
+            post(share::create_share_link).route_layer(middleware::from_fn_with_state(
+                state.clone(),
+                users::require_user_key,
+            )),
+        )
+        .route("/exports/analytics", get(exports::get_analytics_export))
+        .route(
+            "/exports/analytics/{export_id}/{*path}",
+            get(exports::download_analytics_file),
+        )
+        .nest("/admin", admin_routes)
+        .nest("/me", user_routes)
+        // Outside every other layer so refused and rejected requests are counted too
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            usage::track_usage,
+        ))
+        .with_state(state.clone());
 
-    Router::new()
+    let mut router = Router::new()
         .nest("/api/v1", api_routes)
         .route("/api-docs/openapi.json", get(openapi_spec))
-        .route("/docs", get(redoc_ui))
+        .route("/docs", get(redoc_ui));
+
+    if state.swagger_ui_enabled {
+        router = router.route("/docs/try", get(swagger_ui));
+    }
+
+    let metrics = state.metrics.clone();
+    let cors_allow_any_origin = state.cors_allow_any_origin;
+    let router = router
+        .route(
+            "/share/{token}",
+            get(share::get_shared_snapshot).with_state(state.clone()),
+        )
+        .route(
+            "/tiles/gauges/{z}/{x}/{y}",
+            get(get_gauge_tile).with_state(state),
+        )
+        .route(
+            "/metrics",
+            get(stats::prometheus_metrics).with_state(metrics.clone()),
+        )
+        // After every route is added: only routes present at this point get it
+        .method_not_allowed_fallback(methods::method_not_allowed)
+        .fallback(error::route_not_found)
+        .layer(middleware::from_fn_with_state(
+            metrics,
+            stats::track_requests,
+        ));
+
+    // Outermost, so preflight requests are answered before routing
+    if cors_allow_any_origin {
+        router.layer(CorsLayer::permissive())
+    } else {
+        router
+    }
+}
+
+/// Health, readiness, and /metrics only, for worker and scheduler processes that serve
+/// no API but still answer orchestrator probes and metric scrapes
+pub fn create_ops_router(state: AppState) -> Router {
+    let metrics = state.metrics.clone();
+    Router::new()
+        .route("/api/v1/health", get(health))
+        .route("/api/v1/health/ready", get(health_ready))
+        .with_state(state)
+        .route(
+            "/metrics",
+            get(stats::prometheus_metrics).with_state(metrics.clone()),
+        )
+        .method_not_allowed_fallback(methods::method_not_allowed)
+        .fallback(error::route_not_found)
+        .layer(middleware::from_fn_with_state(
+            metrics,
+            stats::track_requests,
+        ))
 }
 
 #[derive(utoipa::OpenApi)]
 #[openapi(
     paths(
         health,
+        health_ready,
         get_water_year,
+        head_water_year,
         get_calendar_year,
+        head_calendar_year,
+        get_readings,
         get_latest,
+        get_histogram,
+        get_calendar,
+        get_hourly,
+        get_rankings,
+        get_zones,
+        get_zone_rainfall,
+        get_radar_storms,
+        get_radar_comparison,
+        get_storm_footprint,
+        get_storm_timeline,
+        get_current_conditions,
+        head_current_conditions,
         get_all_gauges,
         get_gauge_by_id,
+        get_gauge_full,
+        get_gauge_coverage,
+        get_gauge_status_history,
+        get_gauge_normals,
+        get_gauge_forecast,
+        get_gauge_weather,
+        attachments::list_gauge_attachments,
+        attachments::download_gauge_attachment,
+        annotations::list_gauge_annotations,
+        get_gauge_threshold_events,
+        get_gauge_tile,
+        admin::recalculate_summaries,
+        admin::get_reconciliation_report,
+        stats::get_stats,
+        admin::get_slow_queries,
+        admin::get_job_runs,
+        admin::get_job_run_trend,
+        admin::get_summary_views,
+        admin::refresh_summary_views,
+        admin::get_water_year_gauges,
+        admin::get_fopr_availability,
+        admin::change_gauge_status,
+        attachments::upload_gauge_attachment,
+        annotations::create_gauge_annotation,
+        annotations::delete_gauge_annotation,
+        anomalies::list_anomalies,
+        anomalies::review_anomaly,
+        users::create_user,
+        users::list_users,
+        users::set_user_quota,
+        users::delete_user,
+        users::get_me,
+        users::list_favorites,
+        users::add_favorite,
+        users::remove_favorite,
+        users::list_views,
+        users::create_view,
+        users::update_view,
+        users::delete_view,
+        users::get_digest,
+        users::subscribe_digest,
+        users::unsubscribe_digest,
+        users::preview_digest,
+        usage::get_usage,
+        share::create_share_link,
+        share::get_shared_snapshot,
+        exports::get_analytics_export,
+        exports::download_analytics_file,
     ),
     components(
         schemas(
             HealthResponse,
+            HealthStatus,
+            LeaderStatus,
+            UpstreamStatus,
+            BreakerState,
+            ReadinessReport,
+            CheckStatus,
+            ReadinessCheck,
+            CheckState,
             Reading,
             WaterYearSummary,
             CalendarYearSummary,
+            ReadingRange,
             MonthlySummary,
+            MonthFill,
+            QualityGrade,
             GaugeSummary,
             GaugeListResponse,
+            PageInfo,
+            GaugeListItem,
+            CurrentConditionsResponse,
+            CurrentCondition,
+            GaugeDetail,
+            GaugeMetadata,
+            GaugeFullDetail,
+            WaterYearTotal,
+            GaugeStatus,
+            GaugeStatusChange,
+            GaugeStatusUpdate,
+            GaugeAttachment,
+            GaugeAnnotation,
+            NewAnnotation,
+            GaugeThresholdEvent,
+            ThresholdEventPage,
+            RainfallHistogram,
+            HistogramBin,
+            RainfallCalendar,
+            CalendarDay,
+            HourlyRainfall,
+            HourlyRainfallSummary,
+            RankingResponse,
+            GaugeRanking,
+            ZoneCollection,
+            ZoneFeature,
+            ZoneProperties,
+            ZoneRainfallResponse,
+            ZoneRainfall,
+            RadarStorm,
+            RadarStormPage,
+            RadarComparison,
+            GaugeRadarComparison,
+            RadarAgreement,
+            StormFootprint,
+            StormFootprintGauge,
+            StormTimeline,
+            StormTimelineFrame,
+            StormTimelineGauge,
+            GaugeCoverage,
+            SourceCoverage,
+            YearCoverage,
+            MonthCoverage,
+            MonthlyNormals,
+            MonthlyNormal,
+            GaugeForecast,
+            GridCell,
+            ForecastPeriod,
+            GaugeWeather,
+            GaugeWeatherDay,
+            RecalcScope,
+            RecalcStats,
+            GaugeReconciliationReport,
+            GaugeMismatch,
+            GaugeMismatchKind,
+            StatsSummary,
+            RouteSummary,
+            StationReads,
+            SlowQueryCapture,
+            JobRun,
+            JobRunPage,
+            JobRunKind,
+            JobRunOutcome,
+            JobRunTrend,
+            SummaryViewReport,
+            SummaryViewStatus,
+            SummaryView,
+            WaterYearGauges,
+            WaterYearGauge,
+            FoprAvailability,
+            User,
+            CreatedUser,
+            NewUser,
+            UserQuotas,
+            UsageReport,
+            FavoriteGauge,
+            SavedView,
+            SavedViewRequest,
+            RankingPeriod,
+            DigestSubscription,
+            DigestSubscriptionRequest,
+            DigestFrequency,
+            Digest,
+            DigestGauge,
+            ShareRequest,
+            ShareLink,
+            SharedSnapshot,
+            AnalyticsManifest,
+            AnalyticsTable,
+            AnalyticsFile,
+            GaugeAnomaly,
+            AnomalyKind,
+            AnomalyStatus,
+            AnomalyReview,
+            ProblemDetails,
+            FieldError,
+            ErrorCode,
         )
     ),
     tags(
         (name = "health", description = "Health check endpoints"),
         (name = "readings", description = "Rain gauge reading endpoints"),
-        (name = "gauges", description = "Gauge information endpoints")
+        (name = "gauges", description = "Gauge information endpoints"),
+        (name = "zones", description = "MSP forecast zone polygons and zone rainfall"),
+        (name = "radar", description = "Gauge readings compared with MRMS radar rainfall estimates"),
+        (name = "storms", description = "Storms detected as rainfall threshold events"),
+        (name = "tiles", description = "Mapbox Vector Tiles for map frontends"),
+        (name = "users", description = "A user's favorite gauges, saved views, digests, and usage (require X-User-Key)"),
+        (name = "share", description = "Public links to frozen rainfall snapshots (creating one requires X-User-Key)"),
+        (name = "exports", description = "Periodic Parquet exports of readings, summaries, and gauges for offline analysis"),
+        (name = "admin", description = "Maintenance endpoints (require X-Admin-Key)")
     ),
+    modifiers(&KeySecurity),
     info(
         title = "Rain Tracker Service API",
         version = "0.3.0",
@@ -84,8 +565,62 @@ pub fn create_router(state: AppState) -> Router {
 )]
 struct ApiDoc;
 
-use crate::db::{CalendarYearSummary, GaugeSummary, MonthlySummary, WaterYearSummary};
-use crate::services::gauge_service::GaugeListResponse;
+/// Name of the security scheme for the `X-Admin-Key` header
+const ADMIN_KEY_SECURITY: &str = "admin_key";
+
+/// Name of the security scheme for the `X-User-Key` header
+const USER_KEY_SECURITY: &str = "user_key";
+
+/// Registers the key headers so Swagger UI's "Authorize" dialog can send them
+struct KeySecurity;
+
+impl Modify for KeySecurity {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            ADMIN_KEY_SECURITY,
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::with_description(
+                "X-Admin-Key",
+                "Admin API key (ADMIN_API_KEY)",
+            ))),
+        );
+        components.add_security_scheme(
+            USER_KEY_SECURITY,
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::with_description(
+                "X-User-Key",
+                "User key returned when an admin creates the user",
+            ))),
+        );
+    }
+}
+
+use crate::anomaly::{AnomalyKind, AnomalyStatus};
+use crate::db::{
+    CalendarDay, CalendarYearSummary, CurrentCondition, Digest, DigestGauge, DigestSubscription,
+    FavoriteGauge, GaugeAnnotation, GaugeAnomaly, GaugeAttachment, GaugeCoverage, GaugeDetail,
+    GaugeFullDetail, GaugeMetadata, GaugeRanking, GaugeStatus, GaugeStatusChange, GaugeSummary,
+    GaugeThresholdEvent, GaugeWeatherDay, HistogramBin, HourlyRainfall, HourlyRainfallSummary,
+    JobRun, JobRunKind, JobRunOutcome, JobRunTrend, MonthCoverage, MonthFill, MonthlyNormal,
+    MonthlyNormals, MonthlySummary, QualityGrade, RainfallCalendar, RainfallHistogram,
+    RankingPeriod, RankingResponse, ReadingRange, SavedView, ShareLink, SharedSnapshot,
+    SourceCoverage, StormFootprint, StormFootprintGauge, StormTimeline, StormTimelineFrame,
+    StormTimelineGauge, SummaryView, UsageReport, User, WaterYearSummary, WaterYearTotal,
+    YearCoverage, ZoneRainfall, ZoneRainfallResponse,
+};
+use crate::digest::DigestFrequency;
+use crate::services::annotation_service::NewAnnotation;
+use crate::services::anomaly_service::AnomalyReview;
+use crate::services::current_conditions_service::CurrentConditionsResponse;
+use crate::services::digest_service::DigestSubscriptionRequest;
+use crate::services::forecast_service::{ForecastServiceError, GaugeForecast};
+use crate::services::gauge_service::{
+    GaugeListItem, GaugeListResponse, GaugeMismatch, GaugeMismatchKind, GaugeReconciliationReport,
+};
+use crate::services::job_run_service::JobRunPage;
+use crate::services::share_service::ShareRequest;
+use crate::services::threshold_service::ThresholdEventPage;
+use crate::services::user_service::{CreatedUser, NewUser, SavedViewRequest, UserQuotas};
+use crate::services::weather_service::GaugeWeather;
 
 /// Generate the OpenAPI specification
 /// utoipa 4.2 natively generates OpenAPI 3.0.x for better Rust tooling compatibility
@@ -121,56 +656,134 @@ async fn redoc_ui() -> Html<&'static str> {
     )
 }
 
+/// Interactive docs for trying requests (with auth headers) from the browser
+async fn swagger_ui() -> Html<&'static str> {
+    Html(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+    <title>Rain Tracker API - Try It</title>
+    <meta charset="utf-8"/>
+    <meta name="viewport" content="width=device-width, initial-scale=1">
+    <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css">
+</head>
+<body>
+    <div id="swagger-ui"></div>
+    <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js"> </script>
+    <script>
+      window.ui = SwaggerUIBundle({
+        url: '/api-docs/openapi.json',
+        dom_id: '#swagger-ui',
+        tryItOutEnabled: true,
+        persistAuthorization: true
+      });
+    </script>
+</body>
+</html>"#,
+    )
+}
+
 #[utoipa::path(
     get,
     path = "/api/v1/health",
     tag = "health",
     responses(
-        (status = 200, description = "Service is healthy", body = HealthResponse)
+        (status = 200, description = "Service is healthy; `leader_election` shows whether this replica's schedulers lead, and `upstreams` which upstream hosts are paused after repeated failures", body = HealthResponse)
     )
 )]
-#[instrument(skip(_state))]
-async fn health(State(_state): State<AppState>) -> impl IntoResponse {
+#[instrument(skip(state))]
+async fn health(State(state): State<AppState>) -> impl IntoResponse {
     debug!("Health check requested");
     info!("Health check successful");
     let response = HealthResponse {
-        status: "healthy".to_string(),
+        status: HealthStatus::Healthy,
+        leader_election: state.leadership.status(),
+        upstreams: state.metrics.upstreams(),
     };
     (StatusCode::OK, Json(response))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/health/ready",
+    tag = "health",
+    responses(
+        (status = 200, description = "Startup checks passed; ready for traffic", body = ReadinessReport),
+        (status = 503, description = "Still starting, or a startup check failed (code `not_ready`); `errors` has one entry per outstanding check, with `field` the check and `rule` its state", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state))]
+async fn health_ready(State(state): State<AppState>) -> Result<Json<ReadinessReport>, ApiError> {
+    let report = state.readiness.report();
+    if report.ready {
+        return Ok(Json(report));
+    }
+
+    let outstanding: Vec<FieldError> = report
+        .checks
+        .iter()
+        .filter(|c| c.state != CheckState::Passed)
+        .map(|c| FieldError {
+            field: Some(c.check.as_str().to_string()),
+            rule: c.state.as_str().to_string(),
+            message: c
+                .message
+                .clone()
+                .unwrap_or_else(|| "has not run yet".to_string()),
+        })
+        .collect();
+    debug!("Readiness check: {} checks outstanding", outstanding.len());
+    let names: Vec<&str> = outstanding
+        .iter()
+        .filter_map(|e| e.field.as_deref())
+        .collect();
+    Err(ApiError::new(
+        ErrorCode::NotReady,
+        format!("Service is not ready: waiting on {}", names.join(", ")),
+    )
+    .with_errors(outstanding))
+}
+
 #[utoipa::path(
     get,
     path = "/api/v1/readings/{station_id}/water-year/{year}",
     tag = "readings",
     params(
-        ("station_id" = String, Path, description = "Rain gauge station ID"),
-        ("year" = i32, Path, description = "Water year (Oct 1 of year-1 through Sep 30 of year)")
+        ("station_id" = String, Path, description = "Rain gauge station ID", example = "59700"),
+        ("year" = i32, Path, description = "Water year (Oct 1 of year-1 through Sep 30 of year)", minimum = 1900, maximum = 2200, example = 2025),
+        YearSummaryParams
     ),
     responses(
         (status = 200, description = "Water year summary retrieved successfully", body = WaterYearSummary),
-        (status = 500, description = "Internal server error")
+        (status = 400, description = "Year is not a number or outside 1900-2200 (code `invalid_water_year`), or invalid station ID or `fill` (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 413, description = "The year holds more readings than one response may return (code `too_many_rows`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
     )
 )]
 #[instrument(skip(state), fields(station_id = %station_id, year = %year))]
 async fn get_water_year(
     State(state): State<AppState>,
-    Path((station_id, year)): Path<(String, i32)>,
-) -> Result<Json<crate::db::WaterYearSummary>, StatusCode> {
+    ValidatedPath(StationYearPath { station_id, year }): ValidatedPath<StationYearPath>,
+    ValidatedQuery(params): ValidatedQuery<YearSummaryParams>,
+) -> Result<(LastModified, Json<crate::db::WaterYearSummary>), ApiError> {
     debug!(
         "Fetching rain year readings for gauge {} year {}",
         station_id, year
     );
+    let year = parse_year(&year, ErrorCode::InvalidWaterYear, "water year").inspect_err(|_| {
+        warn!("Invalid water year {}", year);
+    })?;
+
     let summary = state
         .reading_service
-        .get_water_year_summary(&station_id, year)
+        .get_water_year_summary(&station_id, year, &params)
         .await
         .map_err(|e| {
-            error!(
-                "Failed to fetch rain year readings for gauge {} year {}: {}",
-                station_id, year, e
-            );
-            StatusCode::INTERNAL_SERVER_ERROR
+            reading_query_error(
+                e,
+                &station_id,
+                &format!("rain year readings for gauge {station_id} year {year}"),
+            )
         })?;
 
     info!(
@@ -178,7 +791,47 @@ async fn get_water_year(
         summary.total_readings, station_id, year, summary.total_rainfall_inches
     );
 
-    Ok(Json(summary))
+    let last_modified = summary.readings.iter().map(|r| r.created_at).max();
+    Ok((LastModified(last_modified), Json(summary)))
+}
+
+#[utoipa::path(
+    head,
+    path = "/api/v1/readings/{station_id}/water-year/{year}",
+    tag = "readings",
+    params(
+        ("station_id" = String, Path, description = "Rain gauge station ID", example = "59700"),
+        ("year" = i32, Path, description = "Water year (Oct 1 of year-1 through Sep 30 of year)", minimum = 1900, maximum = 2200, example = 2025)
+    ),
+    responses(
+        (status = 200, description = "Headers only: `Last-Modified` is when the year's readings last changed (absent when there are none)"),
+        (status = 400, description = "Year is not a number or outside 1900-2200 (code `invalid_water_year`), or invalid station ID (code `invalid_parameter`); body omitted", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`); body omitted", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state), fields(station_id = %station_id, year = %year))]
+async fn head_water_year(
+    State(state): State<AppState>,
+    ValidatedPath(StationYearPath { station_id, year }): ValidatedPath<StationYearPath>,
+) -> Result<impl IntoResponse, ApiError> {
+    let year = parse_year(&year, ErrorCode::InvalidWaterYear, "water year")?;
+
+    let last_modified = state
+        .reading_service
+        .get_water_year_last_modified(&station_id, year)
+        .await
+        .map_err(|e| {
+            error!(
+                "Failed to check rain year freshness for gauge {} year {}: {}",
+                station_id, year, e
+            );
+            ApiError::internal()
+        })?;
+
+    Ok((
+        LastModified(last_modified),
+        [(header::CONTENT_TYPE, "application/json")],
+    ))
 }
 
 #[utoipa::path(
@@ -186,33 +839,42 @@ async fn get_water_year(
     path = "/api/v1/readings/{station_id}/calendar-year/{year}",
     tag = "readings",
     params(
-        ("station_id" = String, Path, description = "Rain gauge station ID"),
-        ("year" = i32, Path, description = "Calendar year (Jan 1 through Dec 31)")
+        ("station_id" = String, Path, description = "Rain gauge station ID", example = "59700"),
+        ("year" = i32, Path, description = "Calendar year (Jan 1 through Dec 31)", minimum = 1900, maximum = 2200, example = 2024),
+        YearSummaryParams
     ),
     responses(
         (status = 200, description = "Calendar year summary retrieved successfully", body = CalendarYearSummary),
-        (status = 500, description = "Internal server error")
+        (status = 400, description = "Year is not a number or outside 1900-2200 (code `invalid_calendar_year`), or invalid station ID or `fill` (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 413, description = "The year holds more readings than one response may return (code `too_many_rows`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
     )
 )]
 #[instrument(skip(state), fields(station_id = %station_id, year = %year))]
 async fn get_calendar_year(
     State(state): State<AppState>,
-    Path((station_id, year)): Path<(String, i32)>,
-) -> Result<Json<crate::db::CalendarYearSummary>, StatusCode> {
+    ValidatedPath(StationYearPath { station_id, year }): ValidatedPath<StationYearPath>,
+    ValidatedQuery(params): ValidatedQuery<YearSummaryParams>,
+) -> Result<(LastModified, Json<crate::db::CalendarYearSummary>), ApiError> {
     debug!(
         "Fetching calendar year readings for gauge {} year {}",
         station_id, year
     );
+    let year =
+        parse_year(&year, ErrorCode::InvalidCalendarYear, "calendar year").inspect_err(|_| {
+            warn!("Invalid calendar year {}", year);
+        })?;
+
     let summary = state
         .reading_service
-        .get_calendar_year_summary(&station_id, year)
+        .get_calendar_year_summary(&station_id, year, &params)
         .await
         .map_err(|e| {
-            error!(
-                "Failed to fetch calendar year readings for gauge {} year {}: {}",
-                station_id, year, e
-            );
-            StatusCode::INTERNAL_SERVER_ERROR
+            reading_query_error(
+                e,
+                &station_id,
+                &format!("calendar year readings for gauge {station_id} year {year}"),
+            )
         })?;
 
     info!(
@@ -220,7 +882,102 @@ async fn get_calendar_year(
         summary.total_readings, station_id, year, summary.year_to_date_rainfall_inches
     );
 
-    Ok(Json(summary))
+    let last_modified = summary.readings.iter().map(|r| r.created_at).max();
+    Ok((LastModified(last_modified), Json(summary)))
+}
+
+#[utoipa::path(
+    head,
+    path = "/api/v1/readings/{station_id}/calendar-year/{year}",
+    tag = "readings",
+    params(
+        ("station_id" = String, Path, description = "Rain gauge station ID", example = "59700"),
+        ("year" = i32, Path, description = "Calendar year (Jan 1 through Dec 31)", minimum = 1900, maximum = 2200, example = 2024)
+    ),
+    responses(
+        (status = 200, description = "Headers only: `Last-Modified` is when the year's readings last changed (absent when there are none)"),
+        (status = 400, description = "Year is not a number or outside 1900-2200 (code `invalid_calendar_year`), or invalid station ID (code `invalid_parameter`); body omitted", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`); body omitted", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state), fields(station_id = %station_id, year = %year))]
+async fn head_calendar_year(
+    State(state): State<AppState>,
+    ValidatedPath(StationYearPath { station_id, year }): ValidatedPath<StationYearPath>,
+) -> Result<impl IntoResponse, ApiError> {
+    let year = parse_year(&year, ErrorCode::InvalidCalendarYear, "calendar year")?;
+
+    let last_modified = state
+        .reading_service
+        .get_calendar_year_last_modified(&station_id, year)
+        .await
+        .map_err(|e| {
+            error!(
+                "Failed to check calendar year freshness for gauge {} year {}: {}",
+                station_id, year, e
+            );
+            ApiError::internal()
+        })?;
+
+    Ok((
+        LastModified(last_modified),
+        [(header::CONTENT_TYPE, "application/json")],
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/readings/{station_id}",
+    tag = "readings",
+    params(
+        ("station_id" = String, Path, description = "Rain gauge station ID", example = "59700"),
+        ReadingRangeParams,
+        ReadingPageParams
+    ),
+    responses(
+        (status = 200, description = "Readings in the range, or the requested page of them, oldest first. With `Accept: application/x-ndjson` the whole range is streamed one JSON object per line instead, without the row cap or paging", content(
+            ("application/json" = ReadingRange),
+            ("application/x-ndjson" = Reading)
+        ), headers(
+            ("Link" = String, description = "RFC 5988 links to the first, prev, next, and last pages (JSON only)")
+        )),
+        (status = 400, description = "Invalid station ID, missing dates, inverted date range, or page or page_size out of range (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 413, description = "The range holds more readings than one JSON response may return; page through it, or use NDJSON or an aggregated endpoint (code `too_many_rows`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Date range is longer than allowed (code `range_too_large`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state, headers), fields(station_id = %station_id))]
+async fn get_readings(
+    State(state): State<AppState>,
+    ValidatedPath(StationPath { station_id }): ValidatedPath<StationPath>,
+    OriginalUri(uri): OriginalUri,
+    ValidatedQuery(params): ValidatedQuery<ReadingRangeParams>,
+    ValidatedQuery(page): ValidatedQuery<ReadingPageParams>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let context = format!(
+        "readings for gauge {} from {} to {}",
+        station_id, params.start, params.end
+    );
+
+    if ndjson::wants_ndjson(&headers) {
+        let rows = state
+            .reading_service
+            .stream_reading_range(&station_id, &params)
+            .map_err(|e| reading_query_error(e, &station_id, &context))?;
+        info!("Streaming {}", context);
+        return Ok(ndjson::ndjson_response(rows));
+    }
+
+    let range = state
+        .reading_service
+        .get_reading_range(&station_id, &params, &page)
+        .await
+        .map_err(|e| reading_query_error(e, &station_id, &context))?;
+
+    info!("Retrieved {} {}", range.total_readings, context);
+    Ok((PageLinks::new(uri, range.pagination), Json(range)).into_response())
 }
 
 #[utoipa::path(
@@ -228,19 +985,20 @@ async fn get_calendar_year(
     path = "/api/v1/readings/{station_id}/latest",
     tag = "readings",
     params(
-        ("station_id" = String, Path, description = "Rain gauge station ID")
+        ("station_id" = String, Path, description = "Rain gauge station ID", example = "59700")
     ),
     responses(
         (status = 200, description = "Latest reading retrieved successfully", body = Reading),
-        (status = 404, description = "No readings found for this gauge"),
-        (status = 500, description = "Internal server error")
+        (status = 400, description = "Invalid station ID (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "No readings found for this gauge (code `reading_not_found`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
     )
 )]
 #[instrument(skip(state), fields(station_id = %station_id))]
 async fn get_latest(
     State(state): State<AppState>,
-    Path(station_id): Path<String>,
-) -> Result<Json<Reading>, StatusCode> {
+    ValidatedPath(StationPath { station_id }): ValidatedPath<StationPath>,
+) -> Result<Json<Reading>, ApiError> {
     debug!("Fetching latest reading for gauge {}", station_id);
     let reading = state
         .reading_service
@@ -251,11 +1009,14 @@ async fn get_latest(
                 "Failed to fetch latest reading for gauge {}: {}",
                 station_id, e
             );
-            StatusCode::INTERNAL_SERVER_ERROR
+            ApiError::internal()
         })?
         .ok_or_else(|| {
             warn!("No readings found for gauge {}", station_id);
-            StatusCode::NOT_FOUND
+            ApiError::new(
+                ErrorCode::ReadingNotFound,
+                format!("No readings found for gauge {station_id}"),
+            )
         })?;
 
     info!(
@@ -268,79 +1029,1077 @@ async fn get_latest(
 
 #[utoipa::path(
     get,
-    path = "/api/v1/gauges",
-    tag = "gauges",
+    path = "/api/v1/readings/{station_id}/histogram",
+    tag = "readings",
     params(
-        PaginationParams
+        ("station_id" = String, Path, description = "Rain gauge station ID", example = "59700"),
+        HistogramParams
     ),
     responses(
-        (status = 200, description = "Paginated list of gauges retrieved successfully", body = GaugeListResponse),
-        (status = 500, description = "Internal server error")
+        (status = 200, description = "Distribution of daily rainfall totals", body = RainfallHistogram),
+        (status = 400, description = "Invalid station ID, bin width, or date range (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Date range (open ends measured to the gauge's first or last reading) is longer than allowed (code `range_too_large`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
     )
 )]
-#[instrument(skip(state))]
-async fn get_all_gauges(
+#[instrument(skip(state), fields(station_id = %station_id))]
+async fn get_histogram(
     State(state): State<AppState>,
-    Query(params): Query<PaginationParams>,
-) -> Result<Json<crate::services::gauge_service::GaugeListResponse>, StatusCode> {
+    ValidatedPath(StationPath { station_id }): ValidatedPath<StationPath>,
+    ValidatedQuery(params): ValidatedQuery<HistogramParams>,
+) -> Result<Json<RainfallHistogram>, ApiError> {
     debug!(
-        "Fetching gauge summaries (page={}, page_size={})",
-        params.page, params.page_size
+        "Fetching daily rainfall histogram for gauge {} (bin={})",
+        station_id, params.bin
     );
 
-    let response = state
-        .gauge_service
-        .get_gauges_paginated(&params)
+    let histogram = state
+        .reading_service
+        .get_daily_histogram(&station_id, &params)
         .await
         .map_err(|e| {
-            error!("Failed to fetch gauges: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
+            reading_query_error(e, &station_id, &format!("histogram for gauge {station_id}"))
         })?;
 
     info!(
-        "Retrieved {} gauge summaries (page {}/{}, total={})",
-        response.gauges.len(),
-        response.page,
-        response.total_pages,
-        response.total_gauges
+        "Built histogram for gauge {}: {} days in {} bins",
+        station_id,
+        histogram.total_days,
+        histogram.bins.len()
     );
 
-    Ok(Json(response))
+    Ok(Json(histogram))
 }
 
 #[utoipa::path(
     get,
-    path = "/api/v1/gauges/{station_id}",
-    tag = "gauges",
+    path = "/api/v1/readings/{station_id}/calendar",
+    tag = "readings",
     params(
-        ("station_id" = String, Path, description = "Rain gauge station ID")
+        ("station_id" = String, Path, description = "Rain gauge station ID", example = "59700"),
+        CalendarParams
     ),
     responses(
-        (status = 200, description = "Gauge details retrieved successfully", body = GaugeSummary),
-        (status = 404, description = "Gauge not found"),
-        (status = 500, description = "Internal server error")
+        (status = 200, description = "Daily rainfall and peak intensity for every day of the year, zero-filled, laid out for a calendar heat map", body = RainfallCalendar),
+        (status = 400, description = "Invalid station ID, or year missing or outside 1900-2200 (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
     )
 )]
 #[instrument(skip(state), fields(station_id = %station_id))]
-async fn get_gauge_by_id(
+async fn get_calendar(
     State(state): State<AppState>,
-    Path(station_id): Path<String>,
-) -> Result<Json<crate::db::GaugeSummary>, StatusCode> {
-    debug!("Fetching gauge summary for station {}", station_id);
+    ValidatedPath(StationPath { station_id }): ValidatedPath<StationPath>,
+    ValidatedQuery(params): ValidatedQuery<CalendarParams>,
+) -> Result<Json<RainfallCalendar>, ApiError> {
+    debug!(
+        "Fetching rainfall calendar for gauge {} year {}",
+        station_id, params.year
+    );
 
-    let gauge = state
-        .gauge_service
-        .get_gauge_by_id(&station_id)
+    let calendar = state
+        .reading_service
+        .get_rainfall_calendar(&station_id, &params)
         .await
         .map_err(|e| {
-            error!("Failed to fetch gauge {}: {}", station_id, e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?
-        .ok_or_else(|| {
-            warn!("Gauge {} not found", station_id);
-            StatusCode::NOT_FOUND
+            error!(
+                "Failed to build rainfall calendar for gauge {}: {}",
+                station_id, e
+            );
+            ApiError::internal()
         })?;
 
-    info!("Retrieved gauge summary for station {}", station_id);
-    Ok(Json(gauge))
+    info!(
+        "Built rainfall calendar for gauge {} year {}: {} rainy days",
+        station_id, calendar.year, calendar.rainy_days
+    );
+
+    Ok(Json(calendar))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/readings/{station_id}/hourly",
+    tag = "readings",
+    params(
+        ("station_id" = String, Path, description = "Rain gauge station ID", example = "59700"),
+        ReadingRangeParams
+    ),
+    responses(
+        (status = 200, description = "Hourly rainfall from sub-daily readings in the range, oldest first; hours without readings are omitted", body = HourlyRainfall),
+        (status = 400, description = "Invalid station ID, missing dates, or inverted date range (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Date range is longer than allowed (code `range_too_large`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state), fields(station_id = %station_id))]
+async fn get_hourly(
+    State(state): State<AppState>,
+    ValidatedPath(StationPath { station_id }): ValidatedPath<StationPath>,
+    ValidatedQuery(params): ValidatedQuery<ReadingRangeParams>,
+) -> Result<Json<HourlyRainfall>, ApiError> {
+    debug!(
+        "Fetching hourly rainfall for gauge {} from {} to {}",
+        station_id, params.start, params.end
+    );
+
+    let hourly = state
+        .reading_service
+        .get_hourly_rainfall(&station_id, &params)
+        .await
+        .map_err(|e| {
+            reading_query_error(
+                e,
+                &station_id,
+                &format!("hourly rainfall for gauge {station_id}"),
+            )
+        })?;
+
+    info!(
+        "Retrieved {} hourly summaries for gauge {}",
+        hourly.hours.len(),
+        station_id
+    );
+
+    Ok(Json(hourly))
+}
+
+/// Map a refused or failed raw-readings query, pointing clients at the aggregated endpoints
+fn reading_query_error(e: ReadingQueryError, station_id: &str, context: &str) -> ApiError {
+    match e {
+        ReadingQueryError::SpanTooLarge { .. } => {
+            warn!("Refused {}: {}", context, e);
+            ApiError::new(
+                ErrorCode::RangeTooLarge,
+                format!(
+                    "{e}; request a shorter range, or use /api/v1/gauges/{station_id}/normals \
+                     for long-term monthly statistics"
+                ),
+            )
+        }
+        ReadingQueryError::TooManyRows { .. } => {
+            warn!("Refused {}: {}", context, e);
+            ApiError::new(
+                ErrorCode::TooManyRows,
+                format!(
+                    "{e}; stream the readings from /api/v1/readings/{station_id}?start=&end= \
+                     with Accept: application/x-ndjson, or use the aggregated \
+                     /api/v1/gauges/{station_id}/normals endpoint"
+                ),
+            )
+        }
+        ReadingQueryError::Database(e) => {
+            error!("Failed to fetch {}: {}", context, e);
+            ApiError::internal()
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/rankings",
+    tag = "gauges",
+    params(
+        RankingParams
+    ),
+    responses(
+        (status = 200, description = "Gauges ranked by rainfall for the period", body = RankingResponse),
+        (status = 400, description = "Invalid period, order, or limit (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state))]
+async fn get_rankings(
+    State(state): State<AppState>,
+    ValidatedQuery(params): ValidatedQuery<RankingParams>,
+) -> Result<Json<RankingResponse>, ApiError> {
+    debug!(
+        "Ranking gauges (period={}, order={:?}, limit={})",
+        params.period.as_str(),
+        params.order,
+        params.limit()
+    );
+
+    let response = state
+        .reading_service
+        .get_rankings(&params)
+        .await
+        .map_err(|e| {
+            error!("Failed to rank gauges: {}", e);
+            ApiError::internal()
+        })?;
+
+    info!(
+        "Ranked {} gauges for period {}",
+        response.rankings.len(),
+        response.period.as_str()
+    );
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/zones",
+    tag = "zones",
+    responses(
+        (status = 200, description = "Imported forecast zones as a GeoJSON FeatureCollection (empty until zones are imported)", body = ZoneCollection, content_type = "application/geo+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state))]
+async fn get_zones(State(state): State<AppState>) -> Result<Response, ApiError> {
+    let zones = state.zone_service.zone_collection().await.map_err(|e| {
+        error!("Failed to fetch forecast zones: {}", e);
+        ApiError::internal()
+    })?;
+
+    debug!("Returning {} forecast zones", zones.features.len());
+    Ok((
+        [(header::CONTENT_TYPE, "application/geo+json")],
+        Json(zones),
+    )
+        .into_response())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/zones/rainfall",
+    tag = "zones",
+    params(
+        ZoneRainfallParams
+    ),
+    responses(
+        (status = 200, description = "Rainfall for the period aggregated by forecast zone", body = ZoneRainfallResponse),
+        (status = 400, description = "Invalid period (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state))]
+async fn get_zone_rainfall(
+    State(state): State<AppState>,
+    ValidatedQuery(params): ValidatedQuery<ZoneRainfallParams>,
+) -> Result<Json<ZoneRainfallResponse>, ApiError> {
+    let response = state
+        .reading_service
+        .get_zone_rainfall(&params)
+        .await
+        .map_err(|e| {
+            error!("Failed to aggregate zone rainfall: {}", e);
+            ApiError::internal()
+        })?;
+
+    info!(
+        "Aggregated rainfall for {} zones for period {}",
+        response.zones.len(),
+        response.period.as_str()
+    );
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/radar/storms",
+    tag = "radar",
+    params(
+        RadarStormParams
+    ),
+    responses(
+        (status = 200, description = "A page of storm windows with MRMS radar estimates imported by `radar import`, newest first", body = RadarStormPage, headers(
+            ("Link" = String, description = "RFC 5988 links to the first, prev, next, and last pages")
+        )),
+        (status = 400, description = "page or page_size out of range (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state))]
+async fn get_radar_storms(
+    State(state): State<AppState>,
+    OriginalUri(uri): OriginalUri,
+    ValidatedQuery(params): ValidatedQuery<RadarStormParams>,
+) -> Result<(PageLinks, Json<RadarStormPage>), ApiError> {
+    let page = state.radar_service.storms(&params).await.map_err(|e| {
+        error!("Failed to fetch radar storms: {}", e);
+        ApiError::internal()
+    })?;
+
+    debug!(
+        "Returning {} radar storms (page {}/{})",
+        page.storms.len(),
+        page.pagination.page,
+        page.pagination.total_pages
+    );
+    Ok((PageLinks::new(uri, page.pagination), Json(page)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/radar/comparison",
+    tag = "radar",
+    params(
+        RadarComparisonParams
+    ),
+    responses(
+        (status = 200, description = "Each gauge's storm total against its MRMS radar estimate, suspect gauges first", body = RadarComparison),
+        (status = 400, description = "Invalid storm window (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "No radar estimates imported for the window and product (code `radar_storm_not_found`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state))]
+async fn get_radar_comparison(
+    State(state): State<AppState>,
+    ValidatedQuery(params): ValidatedQuery<RadarComparisonParams>,
+) -> Result<Json<RadarComparison>, ApiError> {
+    let comparison = state
+        .radar_service
+        .compare(&params)
+        .await
+        .map_err(|e| {
+            error!("Failed to compare gauges with radar: {}", e);
+            ApiError::internal()
+        })?
+        .ok_or_else(|| {
+            warn!("No radar estimates for {} to {}", params.start, params.end);
+            ApiError::new(
+                ErrorCode::RadarStormNotFound,
+                format!(
+                    "No radar estimates imported for {} to {}",
+                    params.start, params.end
+                ),
+            )
+        })?;
+
+    info!(
+        "Compared {} gauges with radar ({} suspect)",
+        comparison.gauges.len(),
+        comparison.suspect_count
+    );
+    Ok(Json(comparison))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/current",
+    tag = "gauges",
+    params(
+        GaugeFilterParams
+    ),
+    responses(
+        (status = 200, description = "Every gauge with its latest totals and water-year-to-date rainfall, as of the last scheduler refresh", body = CurrentConditionsResponse),
+        (status = 400, description = "Unknown status (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state))]
+async fn get_current_conditions(
+    State(state): State<AppState>,
+    ValidatedQuery(filter): ValidatedQuery<GaugeFilterParams>,
+) -> Result<(LastModified, Json<CurrentConditionsResponse>), ApiError> {
+    debug!("Fetching current conditions (status={:?})", filter.status);
+
+    let response = state
+        .current_conditions_service
+        .get_current(&filter)
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch current conditions: {}", e);
+            ApiError::internal()
+        })?;
+
+    info!(
+        "Retrieved current conditions for {} gauges",
+        response.total_gauges
+    );
+    Ok((LastModified(response.refreshed_at), Json(response)))
+}
+
+#[utoipa::path(
+    head,
+    path = "/api/v1/current",
+    tag = "gauges",
+    params(
+        GaugeFilterParams
+    ),
+    responses(
+        (status = 200, description = "Headers only: `Last-Modified` is the response's `refreshed_at` (absent before the first refresh)"),
+        (status = 400, description = "Unknown status (code `invalid_parameter`); body omitted", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`); body omitted", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state))]
+async fn head_current_conditions(
+    State(state): State<AppState>,
+    ValidatedQuery(filter): ValidatedQuery<GaugeFilterParams>,
+) -> Result<impl IntoResponse, ApiError> {
+    let refreshed_at = state
+        .current_conditions_service
+        .get_refreshed_at(&filter)
+        .await
+        .map_err(|e| {
+            error!("Failed to check current conditions freshness: {}", e);
+            ApiError::internal()
+        })?;
+
+    Ok((
+        LastModified(refreshed_at),
+        [(header::CONTENT_TYPE, "application/json")],
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/gauges",
+    tag = "gauges",
+    params(
+        PaginationParams,
+        GaugeFilterParams,
+        GaugeIdsParams,
+        GaugeIncludeParams
+    ),
+    responses(
+        (status = 200, description = "Paginated list of gauges retrieved successfully; with `ids`, the requested gauges in request order on one page (unknown IDs are left out)", body = GaugeListResponse, headers(
+            ("Link" = String, description = "RFC 5988 links to the first, prev, next, and last pages")
+        )),
+        (status = 400, description = "page or page_size out of range, malformed or too many ids, or unknown include (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state))]
+async fn get_all_gauges(
+    State(state): State<AppState>,
+    OriginalUri(uri): OriginalUri,
+    ValidatedQuery(params): ValidatedQuery<PaginationParams>,
+    ValidatedQuery(filter): ValidatedQuery<GaugeFilterParams>,
+    ValidatedQuery(ids): ValidatedQuery<GaugeIdsParams>,
+    ValidatedQuery(include): ValidatedQuery<GaugeIncludeParams>,
+) -> Result<
+    (
+        LastModified,
+        PageLinks,
+        Json<crate::services::gauge_service::GaugeListResponse>,
+    ),
+    ApiError,
+> {
+    debug!(
+        "Fetching gauge summaries (page={}, page_size={}, status={:?}, ids={:?}, include={:?})",
+        params.page, params.page_size, filter.status, ids.ids, include.include
+    );
+
+    let response = match ids.station_ids() {
+        Some(station_ids) => {
+            state
+                .gauge_service
+                .get_gauges_by_ids(&station_ids, &filter, &include)
+                .await
+        }
+        None => {
+            state
+                .gauge_service
+                .get_gauges_paginated(&params, &filter, &include)
+                .await
+        }
+    }
+    .map_err(|e| {
+        error!("Failed to fetch gauges: {}", e);
+        ApiError::internal()
+    })?;
+
+    info!(
+        "Retrieved {} gauge summaries (page {}/{}, total={})",
+        response.gauges.len(),
+        response.pagination.page,
+        response.pagination.total_pages,
+        response.pagination.total_items
+    );
+
+    Ok((
+        LastModified(response.last_scraped_at),
+        PageLinks::new(uri, response.pagination),
+        Json(response),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/gauges/{station_id}",
+    tag = "gauges",
+    params(
+        ("station_id" = String, Path, description = "Rain gauge station ID", example = "59700")
+    ),
+    responses(
+        (status = 200, description = "Gauge details retrieved successfully", body = GaugeSummary),
+        (status = 400, description = "Invalid station ID (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Gauge not found (code `gauge_not_found`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state), fields(station_id = %station_id))]
+async fn get_gauge_by_id(
+    State(state): State<AppState>,
+    ValidatedPath(StationPath { station_id }): ValidatedPath<StationPath>,
+) -> Result<Json<crate::db::GaugeSummary>, ApiError> {
+    debug!("Fetching gauge summary for station {}", station_id);
+
+    let gauge = state
+        .gauge_service
+        .get_gauge_by_id(&station_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch gauge {}: {}", station_id, e);
+            ApiError::internal()
+        })?
+        .ok_or_else(|| {
+            warn!("Gauge {} not found", station_id);
+            ApiError::gauge_not_found(&station_id)
+        })?;
+
+    info!("Retrieved gauge summary for station {}", station_id);
+    Ok(Json(gauge))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/gauges/{station_id}/full",
+    tag = "gauges",
+    params(
+        ("station_id" = String, Path, description = "Rain gauge station ID", example = "59700")
+    ),
+    responses(
+        (status = 200, description = "Merged gauge metadata, latest reading, water-year total, and data coverage", body = GaugeFullDetail),
+        (status = 400, description = "Invalid station ID (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Gauge not found (code `gauge_not_found`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state), fields(station_id = %station_id))]
+async fn get_gauge_full(
+    State(state): State<AppState>,
+    ValidatedPath(StationPath { station_id }): ValidatedPath<StationPath>,
+) -> Result<Json<GaugeFullDetail>, ApiError> {
+    debug!("Fetching full gauge detail for station {}", station_id);
+
+    let gauge = state
+        .gauge_service
+        .get_gauge_detail(&station_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch gauge {}: {}", station_id, e);
+            ApiError::internal()
+        })?
+        .ok_or_else(|| {
+            warn!("Gauge {} not found", station_id);
+            ApiError::gauge_not_found(&station_id)
+        })?;
+
+    let water_year = state.reading_service.current_water_year();
+    let (metadata, latest_reading, current_water_year, coverage) = tokio::try_join!(
+        state.gauge_service.get_gauge_metadata(&station_id),
+        state.reading_service.get_latest_reading(&station_id),
+        state
+            .reading_service
+            .get_water_year_total(&station_id, water_year),
+        state.reading_service.get_coverage(&station_id),
+    )
+    .map_err(|e| {
+        error!(
+            "Failed to fetch full detail for gauge {}: {}",
+            station_id, e
+        );
+        ApiError::internal()
+    })?;
+
+    info!(
+        "Retrieved full detail for station {}: WY {} total {:.2} in",
+        station_id, water_year, current_water_year.total_rainfall_inches
+    );
+    Ok(Json(GaugeFullDetail {
+        gauge,
+        metadata,
+        latest_reading,
+        current_water_year,
+        coverage,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/gauges/{station_id}/status-history",
+    tag = "gauges",
+    params(
+        ("station_id" = String, Path, description = "Rain gauge station ID", example = "59700")
+    ),
+    responses(
+        (status = 200, description = "Status transitions, newest first (empty if never changed)", body = [GaugeStatusChange]),
+        (status = 400, description = "Invalid station ID (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Gauge not found (code `gauge_not_found`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state), fields(station_id = %station_id))]
+async fn get_gauge_status_history(
+    State(state): State<AppState>,
+    ValidatedPath(StationPath { station_id }): ValidatedPath<StationPath>,
+) -> Result<Json<Vec<GaugeStatusChange>>, ApiError> {
+    debug!("Fetching status history for station {}", station_id);
+
+    state
+        .gauge_service
+        .get_gauge_detail(&station_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch gauge {}: {}", station_id, e);
+            ApiError::internal()
+        })?
+        .ok_or_else(|| {
+            warn!("Gauge {} not found", station_id);
+            ApiError::gauge_not_found(&station_id)
+        })?;
+
+    let history = state
+        .gauge_service
+        .get_status_history(&station_id)
+        .await
+        .map_err(|e| {
+            error!(
+                "Failed to fetch status history for gauge {}: {}",
+                station_id, e
+            );
+            ApiError::internal()
+        })?;
+
+    info!(
+        "Retrieved {} status changes for station {}",
+        history.len(),
+        station_id
+    );
+    Ok(Json(history))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/gauges/{station_id}/threshold-events",
+    tag = "gauges",
+    params(
+        ("station_id" = String, Path, description = "Rain gauge station ID", example = "59700"),
+        ThresholdEventParams
+    ),
+    responses(
+        (status = 200, description = "A page of the times the gauge's 24h rainfall reached a configured threshold, with peak intensities, newest first", body = ThresholdEventPage, headers(
+            ("Link" = String, description = "RFC 5988 links to the first, prev, next, and last pages")
+        )),
+        (status = 400, description = "Invalid station ID, threshold, page, or page_size (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Gauge not found (code `gauge_not_found`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state), fields(station_id = %station_id))]
+async fn get_gauge_threshold_events(
+    State(state): State<AppState>,
+    ValidatedPath(StationPath { station_id }): ValidatedPath<StationPath>,
+    OriginalUri(uri): OriginalUri,
+    ValidatedQuery(params): ValidatedQuery<ThresholdEventParams>,
+) -> Result<(PageLinks, Json<ThresholdEventPage>), ApiError> {
+    debug!("Fetching threshold events for station {}", station_id);
+
+    state
+        .gauge_service
+        .get_gauge_detail(&station_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch gauge {}: {}", station_id, e);
+            ApiError::internal()
+        })?
+        .ok_or_else(|| {
+            warn!("Gauge {} not found", station_id);
+            ApiError::gauge_not_found(&station_id)
+        })?;
+
+    let page = state
+        .threshold_service
+        .get_events(&station_id, &params)
+        .await
+        .map_err(|e| {
+            error!(
+                "Failed to fetch threshold events for gauge {}: {}",
+                station_id, e
+            );
+            ApiError::internal()
+        })?;
+
+    info!(
+        "Retrieved {} threshold events for station {}",
+        page.events.len(),
+        station_id
+    );
+    Ok((PageLinks::new(uri, page.pagination), Json(page)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/storms/{storm_id}/footprint",
+    tag = "storms",
+    params(
+        ("storm_id" = i64, Path, description = "Threshold event ID of the storm", example = 41)
+    ),
+    responses(
+        (status = 200, description = "Every gauge that recorded rain during the storm window, wettest first, with the convex hull of their locations", body = StormFootprint),
+        (status = 400, description = "Invalid storm ID (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "No threshold event with the ID (code `storm_not_found`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state), fields(storm_id = path.storm_id))]
+async fn get_storm_footprint(
+    State(state): State<AppState>,
+    ValidatedPath(path): ValidatedPath<StormPath>,
+) -> Result<Json<StormFootprint>, ApiError> {
+    let footprint = state
+        .threshold_service
+        .get_footprint(path.storm_id, chrono::Utc::now())
+        .await
+        .map_err(storm_error)?;
+
+    debug!(
+        "Storm {} footprint covers {} gauges",
+        path.storm_id, footprint.gauge_count
+    );
+    Ok(Json(footprint))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/storms/{storm_id}/timeline",
+    tag = "storms",
+    params(
+        ("storm_id" = i64, Path, description = "Threshold event ID of the storm", example = 41),
+        StormTimelineParams
+    ),
+    responses(
+        (status = 200, description = "Each gauge's rain per step across the storm window, for animated map playback", body = StormTimeline),
+        (status = 400, description = "Invalid storm ID or step (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "No threshold event with the ID (code `storm_not_found`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "The step would split the window into too many frames (code `range_too_large`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state), fields(storm_id = path.storm_id))]
+async fn get_storm_timeline(
+    State(state): State<AppState>,
+    ValidatedPath(path): ValidatedPath<StormPath>,
+    ValidatedQuery(params): ValidatedQuery<StormTimelineParams>,
+) -> Result<Json<StormTimeline>, ApiError> {
+    // Validation already rejected unparseable steps
+    let step = params
+        .step()
+        .ok_or_else(|| ApiError::invalid_parameter("Invalid step"))?;
+    let timeline = state
+        .threshold_service
+        .get_timeline(path.storm_id, step, chrono::Utc::now())
+        .await
+        .map_err(storm_error)?;
+
+    debug!(
+        "Storm {} timeline has {} frames",
+        path.storm_id,
+        timeline.frames.len()
+    );
+    Ok(Json(timeline))
+}
+
+fn storm_error(e: StormError) -> ApiError {
+    match e {
+        StormError::StormNotFound(id) => {
+            warn!("Storm {} not found", id);
+            ApiError::new(ErrorCode::StormNotFound, format!("Storm {id} not found"))
+        }
+        StormError::TooManyFrames { .. } => {
+            warn!("Refused storm timeline: {}", e);
+            ApiError::new(
+                ErrorCode::RangeTooLarge,
+                format!("{e}; request a longer step"),
+            )
+        }
+        StormError::Database(e) => {
+            error!("Failed to fetch storm: {}", e);
+            ApiError::internal()
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/gauges/{station_id}/coverage",
+    tag = "gauges",
+    params(
+        ("station_id" = String, Path, description = "Rain gauge station ID", example = "59700")
+    ),
+    responses(
+        (status = 200, description = "Reading counts by year/month and data source", body = GaugeCoverage),
+        (status = 400, description = "Invalid station ID (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Gauge not found (code `gauge_not_found`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state), fields(station_id = %station_id))]
+async fn get_gauge_coverage(
+    State(state): State<AppState>,
+    ValidatedPath(StationPath { station_id }): ValidatedPath<StationPath>,
+) -> Result<Json<GaugeCoverage>, ApiError> {
+    debug!("Fetching data coverage for station {}", station_id);
+
+    state
+        .gauge_service
+        .get_gauge_by_id(&station_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch gauge {}: {}", station_id, e);
+            ApiError::internal()
+        })?
+        .ok_or_else(|| {
+            warn!("Gauge {} not found", station_id);
+            ApiError::gauge_not_found(&station_id)
+        })?;
+
+    let coverage = state
+        .reading_service
+        .get_coverage(&station_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch coverage for gauge {}: {}", station_id, e);
+            ApiError::internal()
+        })?;
+
+    info!(
+        "Retrieved coverage for station {}: {} readings across {} years",
+        station_id,
+        coverage.total_readings,
+        coverage.years.len()
+    );
+    Ok(Json(coverage))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/gauges/{station_id}/normals",
+    tag = "gauges",
+    params(
+        ("station_id" = String, Path, description = "Rain gauge station ID", example = "59700")
+    ),
+    responses(
+        (status = 200, description = "Per-month percentiles of historical monthly totals", body = MonthlyNormals),
+        (status = 400, description = "Invalid station ID (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Gauge not found (code `gauge_not_found`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state), fields(station_id = %station_id))]
+async fn get_gauge_normals(
+    State(state): State<AppState>,
+    ValidatedPath(StationPath { station_id }): ValidatedPath<StationPath>,
+) -> Result<Json<MonthlyNormals>, ApiError> {
+    debug!("Fetching monthly normals for station {}", station_id);
+
+    state
+        .gauge_service
+        .get_gauge_by_id(&station_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch gauge {}: {}", station_id, e);
+            ApiError::internal()
+        })?
+        .ok_or_else(|| {
+            warn!("Gauge {} not found", station_id);
+            ApiError::gauge_not_found(&station_id)
+        })?;
+
+    let normals = state
+        .reading_service
+        .get_monthly_normals(&station_id)
+        .await
+        .map_err(|e| {
+            error!(
+                "Failed to compute monthly normals for gauge {}: {}",
+                station_id, e
+            );
+            ApiError::internal()
+        })?;
+
+    info!(
+        "Computed monthly normals for station {} ({} months with history)",
+        station_id,
+        normals.months.len()
+    );
+    Ok(Json(normals))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/gauges/{station_id}/forecast",
+    tag = "gauges",
+    params(
+        ("station_id" = String, Path, description = "Rain gauge station ID", example = "59700")
+    ),
+    responses(
+        (status = 200, description = "Observed water-year-to-date rainfall combined with the NWS precipitation forecast for the next 72 hours", body = GaugeForecast),
+        (status = 400, description = "Invalid station ID (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Gauge not found (code `gauge_not_found`), or it has no coordinates or is outside NWS coverage (code `forecast_not_found`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 502, description = "The NWS forecast API failed (code `forecast_unavailable`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state), fields(station_id = %station_id))]
+async fn get_gauge_forecast(
+    State(state): State<AppState>,
+    ValidatedPath(StationPath { station_id }): ValidatedPath<StationPath>,
+) -> Result<Json<GaugeForecast>, ApiError> {
+    debug!("Fetching forecast for station {}", station_id);
+
+    let forecast = state
+        .forecast_service
+        .get_gauge_forecast(&station_id)
+        .await
+        .map_err(|e| match e {
+            ForecastServiceError::NoCoordinates(_)
+            | ForecastServiceError::Forecast(ForecastError::OutsideCoverage) => {
+                warn!("No forecast for gauge {}: {}", station_id, e);
+                ApiError::new(
+                    ErrorCode::ForecastNotFound,
+                    format!("No NWS forecast covers gauge {station_id}"),
+                )
+            }
+            ForecastServiceError::Forecast(e) => {
+                error!("NWS forecast failed for gauge {}: {}", station_id, e);
+                ApiError::new(
+                    ErrorCode::ForecastUnavailable,
+                    "The NWS forecast API is unavailable",
+                )
+            }
+            ForecastServiceError::Database(e) => {
+                error!("Failed to fetch forecast for gauge {}: {}", station_id, e);
+                ApiError::internal()
+            }
+        })?
+        .ok_or_else(|| {
+            warn!("Gauge {} not found", station_id);
+            ApiError::gauge_not_found(&station_id)
+        })?;
+
+    info!(
+        "Forecast for station {}: {:.2} in over {} periods",
+        station_id,
+        forecast.forecast_inches,
+        forecast.periods.len()
+    );
+    Ok(Json(forecast))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/gauges/{station_id}/weather",
+    tag = "gauges",
+    params(
+        ("station_id" = String, Path, description = "Rain gauge station ID", example = "59700"),
+        ReadingRangeParams
+    ),
+    responses(
+        (status = 200, description = "Daily rainfall beside the daily high, low, and reference evapotranspiration stored by the weather job (null for days it has not fetched)", body = GaugeWeather),
+        (status = 400, description = "Invalid station ID, missing dates, or inverted date range (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Gauge not found (code `gauge_not_found`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Date range is longer than allowed (code `range_too_large`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state), fields(station_id = %station_id))]
+async fn get_gauge_weather(
+    State(state): State<AppState>,
+    ValidatedPath(StationPath { station_id }): ValidatedPath<StationPath>,
+    ValidatedQuery(params): ValidatedQuery<ReadingRangeParams>,
+) -> Result<Json<GaugeWeather>, ApiError> {
+    let context = format!(
+        "weather for gauge {} from {} to {}",
+        station_id, params.start, params.end
+    );
+
+    let weather = state
+        .weather_service
+        .get_gauge_weather(&station_id, &params)
+        .await
+        .map_err(|e| reading_query_error(e, &station_id, &context))?
+        .ok_or_else(|| {
+            warn!("Gauge {} not found", station_id);
+            ApiError::gauge_not_found(&station_id)
+        })?;
+
+    info!("Retrieved {} ({} days with ET)", context, weather.et_days);
+    Ok(Json(weather))
+}
+
+/// Media type for Mapbox Vector Tiles
+const MVT_CONTENT_TYPE: &str = "application/vnd.mapbox-vector-tile";
+
+/// Tiles carry current 24h totals, so cache only briefly
+const TILE_CACHE_CONTROL: &str = "public, max-age=60";
+
+#[utoipa::path(
+    get,
+    path = "/tiles/gauges/{z}/{x}/{y}.pbf",
+    tag = "tiles",
+    params(
+        ("z" = u32, Path, description = "Zoom level (0-22)"),
+        ("x" = u32, Path, description = "Tile column"),
+        ("y" = u32, Path, description = "Tile row (XYZ scheme, origin top-left)")
+    ),
+    responses(
+        (status = 200, description = "Mapbox Vector Tile with a `gauges` point layer (empty body when no gauges fall in the tile)", content_type = "application/vnd.mapbox-vector-tile", body = Vec<u8>),
+        (status = 400, description = "Tile coordinates out of range (code `invalid_tile`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state))]
+async fn get_gauge_tile(
+    State(state): State<AppState>,
+    ApiPath((z, x, y)): ApiPath<(u32, u32, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    // The router can't match a parameter with a static suffix, so strip `.pbf` here
+    let y: u32 = y.strip_suffix(".pbf").unwrap_or(&y).parse().map_err(|_| {
+        warn!("Invalid tile row {}", y);
+        ApiError::new(
+            ErrorCode::InvalidTile,
+            format!("'{y}' is not a valid tile row"),
+        )
+    })?;
+
+    let tile = TileCoord::new(z, x, y).ok_or_else(|| {
+        warn!(
+            "Tile {}/{}/{} out of range (max zoom {})",
+            z, x, y, MAX_ZOOM
+        );
+        ApiError::new(
+            ErrorCode::InvalidTile,
+            format!("Tile {z}/{x}/{y} is outside the grid (zoom 0-{MAX_ZOOM})"),
+        )
+    })?;
+
+    let body = state
+        .gauge_service
+        .get_gauge_tile(tile)
+        .await
+        .map_err(|e| {
+            error!("Failed to render gauge tile {}/{}/{}: {}", z, x, y, e);
+            ApiError::internal()
+        })?;
+
+    debug!(
+        "Rendered gauge tile {}/{}/{} ({} bytes)",
+        z,
+        x,
+        y,
+        body.len()
+    );
+    Ok((
+        [
+            (header::CONTENT_TYPE, MVT_CONTENT_TYPE),
+            (header::CACHE_CONTROL, TILE_CACHE_CONTROL),
+        ],
+        body,
+    ))
 }
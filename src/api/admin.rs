@@ -0,0 +1,460 @@
+// Admin endpoints
+//
+// Maintenance operations (summary recalculation, etc.) exposed under /api/v1/admin.
+// Every admin route requires the `X-Admin-Key` header to match ADMIN_API_KEY;
+// when no key is configured the admin API is disabled entirely.
+
+use axum::{
+    extract::{OriginalUri, Request, State},
+    middleware::Next,
+    response::Response,
+    Json,
+};
+use tracing::{error, info, instrument, warn};
+
+use crate::api::error::{ApiError, ApiJson, ApiPath, ErrorCode};
+use crate::api::pagination::PageLinks;
+use crate::api::validation::{parse_year, StationPath, ValidatedPath, ValidatedQuery};
+use crate::api::AppState;
+use crate::db::{FoprAvailability, GaugeStatusChange, JobRunTrend, SlowQueryCapture};
+use crate::services::fopr_availability_service::FoprAvailabilityParams;
+use crate::services::gauge_service::{
+    GaugeReconciliationReport, GaugeStatusError, GaugeStatusUpdate, ADMIN,
+};
+use crate::services::historical_import_service::{HistoricalImportError, WaterYearGauges};
+use crate::services::job_run_service::{JobRunPage, JobRunParams, JobRunTrendParams};
+use crate::services::slow_query_service::SlowQueryParams;
+use crate::services::summary_service::{RecalcScope, RecalcStats};
+use crate::services::summary_view_service::SummaryViewReport;
+
+/// Header carrying the admin API key
+pub const ADMIN_KEY_HEADER: &str = "x-admin-key";
+
+/// Middleware rejecting requests without a valid admin key
+pub async fn require_admin_key(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let Some(expected) = state.admin_api_key.as_deref() else {
+        warn!("Admin request rejected: ADMIN_API_KEY is not configured");
+        return Err(ApiError::new(
+            ErrorCode::AdminDisabled,
+            "Admin API is disabled: ADMIN_API_KEY is not configured",
+        ));
+    };
+
+    let provided = request
+        .headers()
+        .get(ADMIN_KEY_HEADER)
+        .and_then(|v| v.to_str().ok());
+
+    match provided {
+        Some(key) if constant_time_eq(key.as_bytes(), expected.as_bytes()) => {
+            Ok(next.run(request).await)
+        }
+        _ => {
+            warn!("Admin request rejected: missing or invalid admin key");
+            Err(ApiError::new(
+                ErrorCode::Unauthorized,
+                "Missing or invalid X-Admin-Key header",
+            ))
+        }
+    }
+}
+
+/// Compare secrets without short-circuiting on the first differing byte
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/recalculate",
+    tag = "admin",
+    request_body = RecalcScope,
+    security(
+        ("admin_key" = [])
+    ),
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Replay the stored response when a request is retried with the same key and body (24h retention)")
+    ),
+    responses(
+        (status = 200, description = "Summaries recalculated", body = RecalcStats),
+        (status = 400, description = "Malformed body or start_date after end_date (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid admin key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Admin API disabled (code `admin_disabled`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Same Idempotency-Key still in progress (code `idempotency_key_in_use`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Idempotency-Key reused with a different body (code `idempotency_key_mismatch`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state))]
+pub async fn recalculate_summaries(
+    State(state): State<AppState>,
+    ApiJson(scope): ApiJson<RecalcScope>,
+) -> Result<Json<RecalcStats>, ApiError> {
+    if let (Some(start), Some(end)) = (scope.start_date, scope.end_date) {
+        if start > end {
+            warn!(
+                "Rejected recalculation: start_date {} after end_date {}",
+                start, end
+            );
+            return Err(ApiError::invalid_parameter(format!(
+                "start_date {start} is after end_date {end}"
+            )));
+        }
+    }
+
+    let stats = state
+        .summary_service
+        .recalculate(&scope, |_, _| {})
+        .await
+        .map_err(|e| {
+            error!("Failed to recalculate summaries: {}", e);
+            ApiError::internal()
+        })?;
+
+    info!(
+        "Recalculated {} monthly summaries ({} orphaned deleted) and {} hourly summaries",
+        stats.months_recalculated, stats.orphaned_summaries_deleted, stats.hours_recalculated
+    );
+
+    Ok(Json(stats))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/reconciliation",
+    tag = "admin",
+    security(
+        ("admin_key" = [])
+    ),
+    responses(
+        (status = 200, description = "Differences between the gauge list scrape (gauge_summaries) and FOPR metadata (gauges)", body = GaugeReconciliationReport),
+        (status = 401, description = "Missing or invalid admin key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Admin API disabled (code `admin_disabled`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state))]
+pub async fn get_reconciliation_report(
+    State(state): State<AppState>,
+) -> Result<Json<GaugeReconciliationReport>, ApiError> {
+    let report = state
+        .gauge_service
+        .reconciliation_report()
+        .await
+        .map_err(|e| {
+            error!("Failed to build gauge reconciliation report: {}", e);
+            ApiError::internal()
+        })?;
+
+    info!(
+        "Gauge reconciliation report: {} mismatches, {} matched",
+        report.mismatches.len(),
+        report.matched_count
+    );
+
+    Ok(Json(report))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/slow-queries",
+    tag = "admin",
+    security(
+        ("admin_key" = [])
+    ),
+    params(SlowQueryParams),
+    responses(
+        (status = 200, description = "EXPLAIN ANALYZE plans of summary queries slower than SLOW_QUERY_THRESHOLD_MS, newest first (captured only with SLOW_QUERY_EXPLAIN=true)", body = [SlowQueryCapture]),
+        (status = 400, description = "Invalid limit (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid admin key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Admin API disabled (code `admin_disabled`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state))]
+pub async fn get_slow_queries(
+    State(state): State<AppState>,
+    ValidatedQuery(params): ValidatedQuery<SlowQueryParams>,
+) -> Result<Json<Vec<SlowQueryCapture>>, ApiError> {
+    let captures = state
+        .slow_query_service
+        .recent(&params)
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch slow query captures: {}", e);
+            ApiError::internal()
+        })?;
+
+    Ok(Json(captures))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/job-runs",
+    tag = "admin",
+    security(
+        ("admin_key" = [])
+    ),
+    params(JobRunParams),
+    responses(
+        (status = 200, description = "A page of finished reading fetch, gauge list, and FOPR import runs with duration, rows, and outcome, newest first (PostgreSQL only)", body = JobRunPage, headers(
+            ("Link" = String, description = "RFC 5988 links to the first, prev, next, and last pages")
+        )),
+        (status = 400, description = "Invalid job, station ID, page, or page_size (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid admin key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Admin API disabled (code `admin_disabled`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state))]
+pub async fn get_job_runs(
+    State(state): State<AppState>,
+    OriginalUri(uri): OriginalUri,
+    ValidatedQuery(params): ValidatedQuery<JobRunParams>,
+) -> Result<(PageLinks, Json<JobRunPage>), ApiError> {
+    let page = state.job_run_service.recent(&params).await.map_err(|e| {
+        error!("Failed to fetch job runs: {}", e);
+        ApiError::internal()
+    })?;
+
+    Ok((PageLinks::new(uri, page.pagination), Json(page)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/job-runs/trend",
+    tag = "admin",
+    security(
+        ("admin_key" = [])
+    ),
+    params(JobRunTrendParams),
+    responses(
+        (status = 200, description = "Runs, failures, rows, run times, and throughput per job for each UTC day with runs, oldest first (PostgreSQL only)", body = [JobRunTrend]),
+        (status = 400, description = "Invalid job or days (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid admin key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Admin API disabled (code `admin_disabled`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state))]
+pub async fn get_job_run_trend(
+    State(state): State<AppState>,
+    ValidatedQuery(params): ValidatedQuery<JobRunTrendParams>,
+) -> Result<Json<Vec<JobRunTrend>>, ApiError> {
+    let trend = state.job_run_service.trend(&params).await.map_err(|e| {
+        error!("Failed to fetch job run trend: {}", e);
+        ApiError::internal()
+    })?;
+
+    Ok(Json(trend))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/summary-views",
+    tag = "admin",
+    security(
+        ("admin_key" = [])
+    ),
+    responses(
+        (status = 200, description = "Refresh state of the materialized views behind month and water-year rankings and zone rainfall, and whether queries are reading them (PostgreSQL only)", body = SummaryViewReport),
+        (status = 401, description = "Missing or invalid admin key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Admin API disabled (code `admin_disabled`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state))]
+pub async fn get_summary_views(
+    State(state): State<AppState>,
+) -> Result<Json<SummaryViewReport>, ApiError> {
+    let report = state.summary_view_service.status().await.map_err(|e| {
+        error!("Failed to fetch summary view state: {}", e);
+        ApiError::internal()
+    })?;
+
+    Ok(Json(report))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/summary-views/refresh",
+    tag = "admin",
+    security(
+        ("admin_key" = [])
+    ),
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Replay the stored response when a request is retried with the same key and body (24h retention)")
+    ),
+    responses(
+        (status = 200, description = "Views recomputed now, clearing stale flags set before the refresh started (PostgreSQL only)", body = SummaryViewReport),
+        (status = 401, description = "Missing or invalid admin key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Admin API disabled (code `admin_disabled`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Same Idempotency-Key still in progress (code `idempotency_key_in_use`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Idempotency-Key reused with a different body (code `idempotency_key_mismatch`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state))]
+pub async fn refresh_summary_views(
+    State(state): State<AppState>,
+) -> Result<Json<SummaryViewReport>, ApiError> {
+    let report = state.summary_view_service.refresh().await.map_err(|e| {
+        error!("Failed to refresh summary views: {}", e);
+        ApiError::internal()
+    })?;
+
+    info!("Summary views refreshed on request");
+    Ok(Json(report))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/fopr-availability",
+    tag = "admin",
+    security(
+        ("admin_key" = [])
+    ),
+    params(FoprAvailabilityParams),
+    responses(
+        (status = 200, description = "Per-gauge FOPR file availability and Last-Modified from the last `historical-import probe fopr` run, ordered by station", body = [FoprAvailability]),
+        (status = 400, description = "Invalid filter (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid admin key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Admin API disabled (code `admin_disabled`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state))]
+pub async fn get_fopr_availability(
+    State(state): State<AppState>,
+    ValidatedQuery(params): ValidatedQuery<FoprAvailabilityParams>,
+) -> Result<Json<Vec<FoprAvailability>>, ApiError> {
+    let gauges = state
+        .fopr_availability_service
+        .list(&params)
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch FOPR availability: {}", e);
+            ApiError::internal()
+        })?;
+
+    Ok(Json(gauges))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/gauges/{station_id}/status",
+    tag = "admin",
+    request_body = GaugeStatusUpdate,
+    security(
+        ("admin_key" = [])
+    ),
+    params(
+        ("station_id" = String, Path, description = "Rain gauge station ID", example = "59700"),
+        ("Idempotency-Key" = Option<String>, Header, description = "Replay the stored response when a request is retried with the same key and body (24h retention)")
+    ),
+    responses(
+        (status = 200, description = "Status changed; the recorded transition", body = GaugeStatusChange),
+        (status = 400, description = "Invalid station ID or body (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid admin key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Admin API disabled (code `admin_disabled`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Gauge has no metadata row (code `gauge_not_found`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Transition not allowed from the current status (code `invalid_status_transition`), or Idempotency-Key in progress (code `idempotency_key_in_use`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Idempotency-Key reused with a different body (code `idempotency_key_mismatch`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state, update), fields(station_id = %station_id))]
+pub async fn change_gauge_status(
+    State(state): State<AppState>,
+    ValidatedPath(StationPath { station_id }): ValidatedPath<StationPath>,
+    ApiJson(update): ApiJson<GaugeStatusUpdate>,
+) -> Result<Json<GaugeStatusChange>, ApiError> {
+    let change = state
+        .gauge_service
+        .change_status(&station_id, &update, ADMIN)
+        .await
+        .map_err(|e| match e {
+            GaugeStatusError::GaugeNotFound(_) => {
+                warn!("Gauge {} not found", station_id);
+                ApiError::gauge_not_found(&station_id)
+            }
+            GaugeStatusError::InvalidTransition { .. } => {
+                warn!("Rejected status change for {}: {}", station_id, e);
+                ApiError::new(ErrorCode::InvalidStatusTransition, e.to_string())
+            }
+            GaugeStatusError::Database(e) => {
+                error!("Failed to change status of gauge {}: {}", station_id, e);
+                ApiError::internal()
+            }
+        })?;
+
+    Ok(Json(change))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/water-years/{year}/gauges",
+    tag = "admin",
+    security(
+        ("admin_key" = [])
+    ),
+    params(
+        ("year" = i32, Path, description = "Water year (Oct 1 of year-1 through Sep 30 of year)", example = 2019)
+    ),
+    responses(
+        (status = 200, description = "Gauges listed in any month sheet of the water year's MCFCD Excel file (downloaded on each request)", body = WaterYearGauges),
+        (status = 400, description = "Invalid water year (code `invalid_water_year`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid admin key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Admin API disabled (code `admin_disabled`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "MCFCD has no file for the water year (code `water_year_file_not_found`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Download or parse failure, or database error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state))]
+pub async fn get_water_year_gauges(
+    State(state): State<AppState>,
+    ApiPath(year): ApiPath<String>,
+) -> Result<Json<WaterYearGauges>, ApiError> {
+    let water_year = parse_year(&year, ErrorCode::InvalidWaterYear, "water year")?;
+
+    let gauges = state
+        .historical_import_service
+        .discover_water_year_gauges(water_year)
+        .await
+        .map_err(|e| match e {
+            HistoricalImportError::NotPublished(_) => {
+                warn!("No water year {} file published", water_year);
+                ApiError::new(ErrorCode::WaterYearFileNotFound, e.to_string())
+            }
+            e => {
+                error!(
+                    "Failed to discover gauges for water year {}: {}",
+                    water_year, e
+                );
+                ApiError::internal()
+            }
+        })?;
+
+    info!(
+        "Water year {} file lists {} gauges",
+        water_year, gauges.gauge_count
+    );
+
+    Ok(Json(gauges))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret2"));
+    }
+}
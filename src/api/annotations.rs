@@ -0,0 +1,141 @@
+// Gauge annotation endpoints
+//
+// Anyone can read annotations; they are also embedded in water-year and calendar-year
+// responses. Creating and deleting them are admin routes.
+
+use axum::{extract::State, http::StatusCode, Json};
+use tracing::{error, info, instrument, warn};
+use validator::Validate;
+
+use crate::api::error::{ApiError, ApiJson, ErrorCode};
+use crate::api::validation::{validation_error, AnnotationPath, StationPath, ValidatedPath};
+use crate::api::AppState;
+use crate::db::GaugeAnnotation;
+use crate::services::annotation_service::{AnnotationError, NewAnnotation};
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/gauges/{station_id}/annotations",
+    tag = "gauges",
+    params(
+        ("station_id" = String, Path, description = "Rain gauge station ID", example = "59700")
+    ),
+    responses(
+        (status = 200, description = "Annotations, newest first", body = [GaugeAnnotation]),
+        (status = 400, description = "Invalid station ID (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Gauge has no metadata row (code `gauge_not_found`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state), fields(station_id = %station_id))]
+pub async fn list_gauge_annotations(
+    State(state): State<AppState>,
+    ValidatedPath(StationPath { station_id }): ValidatedPath<StationPath>,
+) -> Result<Json<Vec<GaugeAnnotation>>, ApiError> {
+    let annotations = state
+        .annotation_service
+        .list(&station_id)
+        .await
+        .map_err(annotation_error)?;
+
+    info!(
+        "Retrieved {} annotations for station {}",
+        annotations.len(),
+        station_id
+    );
+    Ok(Json(annotations))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/gauges/{station_id}/annotations",
+    tag = "admin",
+    request_body = NewAnnotation,
+    security(
+        ("admin_key" = [])
+    ),
+    params(
+        ("station_id" = String, Path, description = "Rain gauge station ID", example = "59700"),
+        ("Idempotency-Key" = Option<String>, Header, description = "Replay the stored response when a request is retried with the same key and body (24h retention)")
+    ),
+    responses(
+        (status = 201, description = "Annotation created", body = GaugeAnnotation),
+        (status = 400, description = "Invalid station ID, malformed body, or end_date before start_date (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid admin key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Admin API disabled (code `admin_disabled`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Gauge has no metadata row (code `gauge_not_found`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Same Idempotency-Key still in progress (code `idempotency_key_in_use`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Idempotency-Key reused with a different body (code `idempotency_key_mismatch`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state, annotation), fields(station_id = %station_id))]
+pub async fn create_gauge_annotation(
+    State(state): State<AppState>,
+    ValidatedPath(StationPath { station_id }): ValidatedPath<StationPath>,
+    ApiJson(annotation): ApiJson<NewAnnotation>,
+) -> Result<(StatusCode, Json<GaugeAnnotation>), ApiError> {
+    annotation.validate().map_err(validation_error)?;
+
+    let created = state
+        .annotation_service
+        .create(&station_id, &annotation)
+        .await
+        .map_err(annotation_error)?;
+
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/gauges/{station_id}/annotations/{annotation_id}",
+    tag = "admin",
+    security(
+        ("admin_key" = [])
+    ),
+    params(
+        ("station_id" = String, Path, description = "Rain gauge station ID", example = "59700"),
+        ("annotation_id" = i64, Path, description = "Annotation ID", example = 3)
+    ),
+    responses(
+        (status = 204, description = "Annotation deleted"),
+        (status = 400, description = "Invalid station or annotation ID (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid admin key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Admin API disabled (code `admin_disabled`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "No such annotation for the gauge (code `annotation_not_found`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state), fields(station_id = %path.station_id, annotation_id = path.annotation_id))]
+pub async fn delete_gauge_annotation(
+    State(state): State<AppState>,
+    ValidatedPath(path): ValidatedPath<AnnotationPath>,
+) -> Result<StatusCode, ApiError> {
+    state
+        .annotation_service
+        .delete(&path.station_id, path.annotation_id)
+        .await
+        .map_err(annotation_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn annotation_error(e: AnnotationError) -> ApiError {
+    match e {
+        AnnotationError::GaugeNotFound(station_id) => {
+            warn!("Gauge {} not found", station_id);
+            ApiError::gauge_not_found(&station_id)
+        }
+        AnnotationError::AnnotationNotFound(id) => {
+            warn!("Annotation {} not found", id);
+            ApiError::new(
+                ErrorCode::AnnotationNotFound,
+                format!("Annotation {id} not found"),
+            )
+        }
+        AnnotationError::Database(e) => {
+            error!("Annotation operation failed: {}", e);
+            ApiError::internal()
+        }
+    }
+}
@@ -0,0 +1,100 @@
+// Gauge anomaly review endpoints
+//
+// The anomaly job (see `crate::anomaly`) records suspected malfunctions; admins list them
+// here and mark each one confirmed or dismissed. Both are admin routes.
+
+use axum::{extract::State, Json};
+use tracing::{error, info, instrument, warn};
+use validator::Validate;
+
+use crate::api::error::{ApiError, ApiJson, ErrorCode};
+use crate::api::validation::{validation_error, AnomalyPath, ValidatedPath, ValidatedQuery};
+use crate::api::AppState;
+use crate::db::GaugeAnomaly;
+use crate::services::anomaly_service::{AnomalyError, AnomalyParams, AnomalyReview};
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/anomalies",
+    tag = "admin",
+    security(
+        ("admin_key" = [])
+    ),
+    params(AnomalyParams),
+    responses(
+        (status = 200, description = "Suspected gauge malfunctions (flat-lines, spikes, cumulative resets), newest first", body = [GaugeAnomaly]),
+        (status = 400, description = "Invalid filter or limit (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid admin key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Admin API disabled (code `admin_disabled`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state))]
+pub async fn list_anomalies(
+    State(state): State<AppState>,
+    ValidatedQuery(params): ValidatedQuery<AnomalyParams>,
+) -> Result<Json<Vec<GaugeAnomaly>>, ApiError> {
+    let anomalies = state.anomaly_service.list(&params).await.map_err(|e| {
+        error!("Failed to fetch gauge anomalies: {}", e);
+        ApiError::internal()
+    })?;
+
+    info!("Retrieved {} gauge anomalies", anomalies.len());
+    Ok(Json(anomalies))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/anomalies/{anomaly_id}/review",
+    tag = "admin",
+    request_body = AnomalyReview,
+    security(
+        ("admin_key" = [])
+    ),
+    params(
+        ("anomaly_id" = i64, Path, description = "Anomaly ID", example = 12),
+        ("Idempotency-Key" = Option<String>, Header, description = "Replay the stored response when a request is retried with the same key and body (24h retention)")
+    ),
+    responses(
+        (status = 200, description = "Review recorded", body = GaugeAnomaly),
+        (status = 400, description = "Invalid anomaly ID or malformed body (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid admin key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Admin API disabled (code `admin_disabled`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "No such anomaly (code `anomaly_not_found`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Same Idempotency-Key still in progress (code `idempotency_key_in_use`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Idempotency-Key reused with a different body (code `idempotency_key_mismatch`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state, review), fields(anomaly_id = path.anomaly_id))]
+pub async fn review_anomaly(
+    State(state): State<AppState>,
+    ValidatedPath(path): ValidatedPath<AnomalyPath>,
+    ApiJson(review): ApiJson<AnomalyReview>,
+) -> Result<Json<GaugeAnomaly>, ApiError> {
+    review.validate().map_err(validation_error)?;
+
+    let reviewed = state
+        .anomaly_service
+        .review(path.anomaly_id, &review)
+        .await
+        .map_err(anomaly_error)?;
+
+    Ok(Json(reviewed))
+}
+
+fn anomaly_error(e: AnomalyError) -> ApiError {
+    match e {
+        AnomalyError::AnomalyNotFound(id) => {
+            warn!("Anomaly {} not found", id);
+            ApiError::new(
+                ErrorCode::AnomalyNotFound,
+                format!("Anomaly {id} not found"),
+            )
+        }
+        AnomalyError::Database(e) => {
+            error!("Anomaly review failed: {}", e);
+            ApiError::internal()
+        }
+    }
+}
@@ -0,0 +1,228 @@
+// Gauge attachment endpoints
+//
+// Listing and downloading are public. Uploading is an admin route: the request body is
+// the raw file, its media type comes from the Content-Type header, and the file name
+// and caption are query parameters.
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use tracing::{error, info, instrument, warn};
+
+use crate::api::error::{ApiError, ErrorCode};
+use crate::api::validation::{AttachmentPath, StationPath, ValidatedPath, ValidatedQuery};
+use crate::api::AppState;
+use crate::db::GaugeAttachment;
+use crate::services::attachment_service::{
+    AttachmentError, AttachmentUpload, AttachmentUploadParams, ALLOWED_CONTENT_TYPES,
+    MAX_ATTACHMENT_BYTES,
+};
+
+/// Attachments never change once uploaded (a new upload gets a new ID)
+const ATTACHMENT_CACHE_CONTROL: &str = "public, max-age=86400, immutable";
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/gauges/{station_id}/attachments",
+    tag = "gauges",
+    params(
+        ("station_id" = String, Path, description = "Rain gauge station ID", example = "59700")
+    ),
+    responses(
+        (status = 200, description = "Attachments, newest first; download each from `/api/v1/gauges/{station_id}/attachments/{id}`", body = [GaugeAttachment]),
+        (status = 400, description = "Invalid station ID (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Gauge has no metadata row (code `gauge_not_found`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state), fields(station_id = %station_id))]
+pub async fn list_gauge_attachments(
+    State(state): State<AppState>,
+    ValidatedPath(StationPath { station_id }): ValidatedPath<StationPath>,
+) -> Result<Json<Vec<GaugeAttachment>>, ApiError> {
+    let attachments = state
+        .attachment_service
+        .list(&station_id)
+        .await
+        .map_err(attachment_error)?;
+
+    info!(
+        "Retrieved {} attachments for station {}",
+        attachments.len(),
+        station_id
+    );
+    Ok(Json(attachments))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/gauges/{station_id}/attachments/{attachment_id}",
+    tag = "gauges",
+    params(
+        ("station_id" = String, Path, description = "Rain gauge station ID", example = "59700"),
+        ("attachment_id" = i64, Path, description = "Attachment ID", example = 7)
+    ),
+    responses(
+        (status = 200, description = "File contents with the uploaded Content-Type", content_type = "application/octet-stream", body = Vec<u8>),
+        (status = 400, description = "Invalid station or attachment ID (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "No such attachment for the gauge (code `attachment_not_found`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state), fields(station_id = %path.station_id, attachment_id = path.attachment_id))]
+pub async fn download_gauge_attachment(
+    State(state): State<AppState>,
+    ValidatedPath(path): ValidatedPath<AttachmentPath>,
+) -> Result<impl IntoResponse, ApiError> {
+    let (attachment, bytes) = state
+        .attachment_service
+        .download(&path.station_id, path.attachment_id)
+        .await
+        .map_err(attachment_error)?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, attachment.content_type),
+            (
+                header::CONTENT_DISPOSITION,
+                content_disposition(&attachment.filename),
+            ),
+            (header::CACHE_CONTROL, ATTACHMENT_CACHE_CONTROL.to_string()),
+        ],
+        bytes,
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/gauges/{station_id}/attachments",
+    tag = "admin",
+    request_body(content = Vec<u8>, description = "Raw file contents (JPEG, PNG, WebP, or PDF; max 10 MiB)", content_type = "application/octet-stream"),
+    security(
+        ("admin_key" = [])
+    ),
+    params(
+        ("station_id" = String, Path, description = "Rain gauge station ID", example = "59700"),
+        AttachmentUploadParams,
+        ("Idempotency-Key" = Option<String>, Header, description = "Replay the stored response when a request is retried with the same key and body (24h retention)")
+    ),
+    responses(
+        (status = 201, description = "Attachment stored", body = GaugeAttachment),
+        (status = 400, description = "Invalid station ID, file name, or empty body (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid admin key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Admin API disabled (code `admin_disabled`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Gauge has no metadata row (code `gauge_not_found`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Same Idempotency-Key still in progress (code `idempotency_key_in_use`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 413, description = "File larger than 10 MiB (code `payload_too_large`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 415, description = "Content-Type is not an accepted image or PDF type (code `unsupported_media_type`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Idempotency-Key reused with a different body (code `idempotency_key_mismatch`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state, headers, body), fields(station_id = %station_id))]
+pub async fn upload_gauge_attachment(
+    State(state): State<AppState>,
+    ValidatedPath(StationPath { station_id }): ValidatedPath<StationPath>,
+    ValidatedQuery(params): ValidatedQuery<AttachmentUploadParams>,
+    headers: HeaderMap,
+    body: Body,
+) -> Result<(StatusCode, Json<GaugeAttachment>), ApiError> {
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+
+    let bytes = to_bytes(body, MAX_ATTACHMENT_BYTES).await.map_err(|_| {
+        warn!("Rejected attachment for {}: body too large", station_id);
+        ApiError::new(
+            ErrorCode::PayloadTooLarge,
+            format!(
+                "Attachments are limited to {} MiB",
+                MAX_ATTACHMENT_BYTES / (1024 * 1024)
+            ),
+        )
+    })?;
+
+    let upload = AttachmentUpload {
+        filename: params.filename,
+        content_type,
+        description: params.description,
+        bytes: bytes.to_vec(),
+    };
+    let attachment = state
+        .attachment_service
+        .upload(&station_id, upload)
+        .await
+        .map_err(attachment_error)?;
+
+    Ok((StatusCode::CREATED, Json(attachment)))
+}
+
+fn attachment_error(e: AttachmentError) -> ApiError {
+    match e {
+        AttachmentError::GaugeNotFound(station_id) => {
+            warn!("Gauge {} not found", station_id);
+            ApiError::gauge_not_found(&station_id)
+        }
+        AttachmentError::AttachmentNotFound(id) => {
+            warn!("Attachment {} not found", id);
+            ApiError::new(
+                ErrorCode::AttachmentNotFound,
+                format!("Attachment {id} not found"),
+            )
+        }
+        AttachmentError::UnsupportedContentType(_) => {
+            warn!("Rejected attachment: {}", e);
+            ApiError::new(
+                ErrorCode::UnsupportedMediaType,
+                format!("{e}; expected one of {}", ALLOWED_CONTENT_TYPES.join(", ")),
+            )
+        }
+        AttachmentError::Empty => ApiError::invalid_parameter("Attachment body is empty"),
+        AttachmentError::Storage(_) | AttachmentError::Database(_) => {
+            error!("Attachment operation failed: {}", e);
+            ApiError::internal()
+        }
+    }
+}
+
+/// `inline` disposition with an ASCII fallback name plus the RFC 5987 UTF-8 name
+fn content_disposition(filename: &str) -> String {
+    let ascii: String = filename
+        .chars()
+        .map(|c| if c.is_ascii() { c } else { '_' })
+        .collect();
+    let encoded: String = filename
+        .bytes()
+        .map(|b| {
+            if b.is_ascii_alphanumeric() || b"-._~".contains(&b) {
+                (b as char).to_string()
+            } else {
+                format!("%{b:02X}")
+            }
+        })
+        .collect();
+    format!("inline; filename=\"{ascii}\"; filename*=UTF-8''{encoded}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_disposition() {
+        assert_eq!(
+            content_disposition("site north.jpg"),
+            "inline; filename=\"site north.jpg\"; filename*=UTF-8''site%20north.jpg"
+        );
+        assert_eq!(
+            content_disposition("año.pdf"),
+            "inline; filename=\"a_o.pdf\"; filename*=UTF-8''a%C3%B1o.pdf"
+        );
+    }
+}
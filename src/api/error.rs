@@ -0,0 +1,419 @@
+// RFC 7807 problem details for API errors
+//
+// Every error response is `application/problem+json` with a stable machine-readable
+// `code`, so clients can branch on the code instead of parsing `detail` text.
+// Extractor rejections (malformed paths, query strings, and bodies) go through the
+// Api* extractor wrappers below so they are reported the same way.
+
+use axum::{
+    extract::{
+        path::ErrorKind, rejection::PathRejection, FromRequest, FromRequestParts, Path, Query,
+        Request,
+    },
+    http::{header, request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Media type for problem detail responses
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+/// Prefix for problem `type` URIs; the error code is appended
+const PROBLEM_TYPE_PREFIX: &str = "urn:rain-tracker:problem:";
+
+/// Machine-readable error codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// No gauge with the requested station ID (404)
+    GaugeNotFound,
+    /// The gauge exists but has no readings (404)
+    ReadingNotFound,
+    /// No attachment with the requested ID for the gauge (404)
+    AttachmentNotFound,
+    /// No annotation with the requested ID for the gauge (404)
+    AnnotationNotFound,
+    /// MCFCD has not published a file for the requested water year (404)
+    WaterYearFileNotFound,
+    /// The gauge has no coordinates or is outside NWS forecast coverage (404)
+    ForecastNotFound,
+    /// No radar estimates were imported for the storm window and product (404)
+    RadarStormNotFound,
+    /// No threshold event has the requested storm ID (404)
+    StormNotFound,
+    /// No user with the requested ID (404)
+    UserNotFound,
+    /// The user has no saved view with the requested ID (404)
+    SavedViewNotFound,
+    /// No recorded gauge anomaly has the requested ID (404)
+    AnomalyNotFound,
+    /// The user has no digest subscription (404)
+    DigestNotFound,
+    /// The share link is malformed, its signature does not match, or its snapshot is gone
+    /// (404)
+    ShareNotFound,
+    /// No analytics export has been published, or the export or file does not exist (404)
+    ExportNotFound,
+    /// No route matches the request path (404)
+    NotFound,
+    /// The path exists but not for this method; see the Allow header (405)
+    MethodNotAllowed,
+    /// Water year path segment is not a valid year (400)
+    InvalidWaterYear,
+    /// Calendar year path segment is not a valid year (400)
+    InvalidCalendarYear,
+    /// A path, query, or body parameter is malformed or out of range (400)
+    InvalidParameter,
+    /// Tile coordinates are outside the zoom level's grid (400)
+    InvalidTile,
+    /// Request body exceeds the endpoint's size limit (413)
+    PayloadTooLarge,
+    /// The query would return more raw readings than one response may hold (413)
+    TooManyRows,
+    /// Request Content-Type is not accepted by the endpoint (415)
+    UnsupportedMediaType,
+    /// Admin or user key missing or wrong (401)
+    Unauthorized,
+    /// Admin API is disabled because no key is configured (403)
+    AdminDisabled,
+    /// Share links are disabled because no signing key is configured (403)
+    SharingDisabled,
+    /// Analytics exports are disabled because no export directory is configured (403)
+    ExportsDisabled,
+    /// The gauge cannot move from its current status to the requested one (409)
+    InvalidStatusTransition,
+    /// A request with the same Idempotency-Key is still running (409)
+    IdempotencyKeyInUse,
+    /// Another user, or another of the user's saved views, already has the name (409)
+    NameTaken,
+    /// Idempotency-Key was already used for a different request (422)
+    IdempotencyKeyMismatch,
+    /// Requested date range is longer than raw-readings queries allow (422)
+    RangeTooLarge,
+    /// The share link has expired (410)
+    ShareExpired,
+    /// Too many requests; retry later (429)
+    RateLimited,
+    /// The user's monthly request or byte quota is used up; see Retry-After (429)
+    QuotaExceeded,
+    /// Unexpected server-side failure (500)
+    InternalError,
+    /// The NWS forecast API failed or returned unusable data (502)
+    ForecastUnavailable,
+    /// Startup checks have not all passed; `errors` lists the outstanding ones (503)
+    NotReady,
+}
+
+impl ErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::GaugeNotFound => "gauge_not_found",
+            ErrorCode::ReadingNotFound => "reading_not_found",
+            ErrorCode::AttachmentNotFound => "attachment_not_found",
+            ErrorCode::AnnotationNotFound => "annotation_not_found",
+            ErrorCode::WaterYearFileNotFound => "water_year_file_not_found",
+            ErrorCode::ForecastNotFound => "forecast_not_found",
+            ErrorCode::RadarStormNotFound => "radar_storm_not_found",
+            ErrorCode::StormNotFound => "storm_not_found",
+            ErrorCode::UserNotFound => "user_not_found",
+            ErrorCode::SavedViewNotFound => "saved_view_not_found",
+            ErrorCode::AnomalyNotFound => "anomaly_not_found",
+            ErrorCode::DigestNotFound => "digest_not_found",
+            ErrorCode::ShareNotFound => "share_not_found",
+            ErrorCode::ExportNotFound => "export_not_found",
+            ErrorCode::NotFound => "not_found",
+            ErrorCode::MethodNotAllowed => "method_not_allowed",
+            ErrorCode::InvalidWaterYear => "invalid_water_year",
+            ErrorCode::InvalidCalendarYear => "invalid_calendar_year",
+            ErrorCode::InvalidParameter => "invalid_parameter",
+            ErrorCode::InvalidTile => "invalid_tile",
+            ErrorCode::PayloadTooLarge => "payload_too_large",
+            ErrorCode::TooManyRows => "too_many_rows",
+            ErrorCode::UnsupportedMediaType => "unsupported_media_type",
+            ErrorCode::Unauthorized => "unauthorized",
+            ErrorCode::AdminDisabled => "admin_disabled",
+            ErrorCode::SharingDisabled => "sharing_disabled",
+            ErrorCode::ExportsDisabled => "exports_disabled",
+            ErrorCode::InvalidStatusTransition => "invalid_status_transition",
+            ErrorCode::IdempotencyKeyInUse => "idempotency_key_in_use",
+            ErrorCode::NameTaken => "name_taken",
+            ErrorCode::IdempotencyKeyMismatch => "idempotency_key_mismatch",
+            ErrorCode::RangeTooLarge => "range_too_large",
+            ErrorCode::ShareExpired => "share_expired",
+            ErrorCode::RateLimited => "rate_limited",
+            ErrorCode::QuotaExceeded => "quota_exceeded",
+            ErrorCode::InternalError => "internal_error",
+            ErrorCode::ForecastUnavailable => "forecast_unavailable",
+            ErrorCode::NotReady => "not_ready",
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ErrorCode::GaugeNotFound
+            | ErrorCode::ReadingNotFound
+            | ErrorCode::AttachmentNotFound
+            | ErrorCode::AnnotationNotFound
+            | ErrorCode::WaterYearFileNotFound
+            | ErrorCode::ForecastNotFound
+            | ErrorCode::RadarStormNotFound
+            | ErrorCode::StormNotFound
+            | ErrorCode::UserNotFound
+            | ErrorCode::SavedViewNotFound
+            | ErrorCode::AnomalyNotFound
+            | ErrorCode::DigestNotFound
+            | ErrorCode::ShareNotFound
+            | ErrorCode::ExportNotFound
+            | ErrorCode::NotFound => StatusCode::NOT_FOUND,
+            ErrorCode::MethodNotAllowed => StatusCode::METHOD_NOT_ALLOWED,
+            ErrorCode::InvalidWaterYear
+            | ErrorCode::InvalidCalendarYear
+            | ErrorCode::InvalidParameter
+            | ErrorCode::InvalidTile => StatusCode::BAD_REQUEST,
+            ErrorCode::PayloadTooLarge | ErrorCode::TooManyRows => StatusCode::PAYLOAD_TOO_LARGE,
+            ErrorCode::UnsupportedMediaType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ErrorCode::Unauthorized => StatusCode::UNAUTHORIZED,
+            ErrorCode::AdminDisabled | ErrorCode::SharingDisabled | ErrorCode::ExportsDisabled => {
+                StatusCode::FORBIDDEN
+            }
+            ErrorCode::InvalidStatusTransition
+            | ErrorCode::IdempotencyKeyInUse
+            | ErrorCode::NameTaken => StatusCode::CONFLICT,
+            ErrorCode::IdempotencyKeyMismatch | ErrorCode::RangeTooLarge => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            ErrorCode::ShareExpired => StatusCode::GONE,
+            ErrorCode::RateLimited | ErrorCode::QuotaExceeded => StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorCode::ForecastUnavailable => StatusCode::BAD_GATEWAY,
+            ErrorCode::NotReady => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+}
+
+/// RFC 7807 problem details body
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ProblemDetails {
+    /// URI identifying the problem type (`urn:rain-tracker:problem:<code>`)
+    #[serde(rename = "type")]
+    #[schema(example = "urn:rain-tracker:problem:gauge_not_found")]
+    pub problem_type: String,
+    /// Short summary of the problem type (the HTTP reason phrase)
+    #[schema(example = "Not Found")]
+    pub title: String,
+    /// HTTP status code
+    #[schema(example = 404)]
+    pub status: u16,
+    /// Explanation specific to this occurrence
+    #[schema(example = "Gauge 59700 not found")]
+    pub detail: String,
+    pub code: ErrorCode,
+    /// Per-field validation failures (omitted when empty)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+}
+
+/// A single failed validation rule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct FieldError {
+    /// Parameter name; omitted for rules spanning several fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "page_size")]
+    pub field: Option<String>,
+    /// Rule that failed (e.g. `range`, `station_id_format`, `date_order`)
+    #[schema(example = "range")]
+    pub rule: String,
+    #[schema(example = "must be between 1 and 100")]
+    pub message: String,
+}
+
+/// An error response rendered as problem+json
+#[derive(Debug, Clone)]
+pub struct ApiError {
+    pub code: ErrorCode,
+    pub detail: String,
+    pub errors: Vec<FieldError>,
+}
+
+impl ApiError {
+    pub fn new(code: ErrorCode, detail: impl Into<String>) -> Self {
+        Self {
+            code,
+            detail: detail.into(),
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn with_errors(mut self, errors: Vec<FieldError>) -> Self {
+        self.errors = errors;
+        self
+    }
+
+    pub fn gauge_not_found(station_id: &str) -> Self {
+        Self::new(
+            ErrorCode::GaugeNotFound,
+            format!("Gauge {station_id} not found"),
+        )
+    }
+
+    pub fn invalid_parameter(detail: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidParameter, detail)
+    }
+
+    /// Internal failure; details stay in the logs rather than the response
+    pub fn internal() -> Self {
+        Self::new(
+            ErrorCode::InternalError,
+            "An unexpected error occurred while processing the request",
+        )
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.code.status()
+    }
+
+    pub fn to_problem(&self) -> ProblemDetails {
+        let status = self.status();
+        ProblemDetails {
+            problem_type: format!("{PROBLEM_TYPE_PREFIX}{}", self.code.as_str()),
+            title: status.canonical_reason().unwrap_or("Error").to_string(),
+            status: status.as_u16(),
+            detail: self.detail.clone(),
+            code: self.code,
+            errors: self.errors.clone(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (
+            self.status(),
+            [(header::CONTENT_TYPE, PROBLEM_JSON)],
+            Json(self.to_problem()),
+        )
+            .into_response()
+    }
+}
+
+/// Fallback for requests that match no route
+pub async fn route_not_found() -> ApiError {
+    ApiError::new(ErrorCode::NotFound, "No route matches the request path")
+}
+
+/// `Path` extractor that rejects with problem+json
+///
+/// Segments whose type validates while deserializing (such as `StationId`) are reported
+/// as a field error with a `<field>_format` rule, like the Validated* extractors do.
+pub struct ApiPath<T>(pub T);
+
+impl<S, T> FromRequestParts<S> for ApiPath<T>
+where
+    T: DeserializeOwned + Send,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        Path::<T>::from_request_parts(parts, state)
+            .await
+            .map(|Path(value)| ApiPath(value))
+            .map_err(path_rejection)
+    }
+}
+
+fn path_rejection(rejection: PathRejection) -> ApiError {
+    if let PathRejection::FailedToDeserializePathParams(err) = &rejection {
+        if let ErrorKind::DeserializeError { key, message, .. } = err.kind() {
+            return ApiError::invalid_parameter(format!("Invalid parameters: {key}")).with_errors(
+                vec![FieldError {
+                    field: Some(key.clone()),
+                    rule: format!("{key}_format"),
+                    message: message.clone(),
+                }],
+            );
+        }
+    }
+    ApiError::invalid_parameter(rejection.body_text())
+}
+
+/// `Query` extractor that rejects with problem+json
+pub struct ApiQuery<T>(pub T);
+
+impl<S, T> FromRequestParts<S> for ApiQuery<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        Query::<T>::from_request_parts(parts, state)
+            .await
+            .map(|Query(value)| ApiQuery(value))
+            .map_err(|rejection| ApiError::invalid_parameter(rejection.body_text()))
+    }
+}
+
+/// `Json` body extractor that rejects with problem+json
+pub struct ApiJson<T>(pub T);
+
+impl<S, T> FromRequest<S> for ApiJson<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        Json::<T>::from_request(request, state)
+            .await
+            .map(|Json(value)| ApiJson(value))
+            .map_err(|rejection| ApiError::invalid_parameter(rejection.body_text()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_problem_from_error_code() {
+        let problem = ApiError::gauge_not_found("59700").to_problem();
+
+        assert_eq!(problem.status, 404);
+        assert_eq!(problem.title, "Not Found");
+        assert_eq!(problem.code, ErrorCode::GaugeNotFound);
+        assert_eq!(
+            problem.problem_type,
+            "urn:rain-tracker:problem:gauge_not_found"
+        );
+        assert_eq!(problem.detail, "Gauge 59700 not found");
+    }
+
+    #[test]
+    fn test_error_code_serializes_as_str() {
+        for code in [
+            ErrorCode::InvalidWaterYear,
+            ErrorCode::RateLimited,
+            ErrorCode::InternalError,
+        ] {
+            let json = serde_json::to_value(code).unwrap();
+            assert_eq!(json, code.as_str());
+        }
+    }
+
+    #[test]
+    fn test_into_response_sets_problem_content_type() {
+        let response = ApiError::new(ErrorCode::RateLimited, "Slow down").into_response();
+
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            PROBLEM_JSON
+        );
+    }
+}
@@ -0,0 +1,116 @@
+// Analytics export endpoints
+//
+// The scheduled analytics export (see `crate::analytics`) writes readings, monthly
+// summaries, and gauges as Parquet under ANALYTICS_EXPORT_DIR. GET /api/v1/exports/analytics
+// returns the newest export's manifest; each file it lists downloads from
+// /api/v1/exports/analytics/{export_id}/{path}. Both return 403 without an export directory.
+
+use axum::{
+    extract::State,
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use tracing::{error, instrument, warn};
+
+use crate::analytics::{AnalyticsError, AnalyticsManifest};
+use crate::api::error::{ApiError, ApiPath, ErrorCode};
+use crate::api::AppState;
+
+/// An export's files never change once published (a new export gets a new ID)
+const EXPORT_FILE_CACHE_CONTROL: &str = "public, max-age=86400, immutable";
+
+/// Media type registered for Apache Parquet files
+const PARQUET_CONTENT_TYPE: &str = "application/vnd.apache.parquet";
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/exports/analytics",
+    tag = "exports",
+    responses(
+        (status = 200, description = "Manifest of the newest export; download each listed file from `/api/v1/exports/analytics/{export_id}/{path}`", body = AnalyticsManifest),
+        (status = 403, description = "ANALYTICS_EXPORT_DIR is not set (code `exports_disabled`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "No export has been published yet (code `export_not_found`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state))]
+pub async fn get_analytics_export(
+    State(state): State<AppState>,
+) -> Result<Json<AnalyticsManifest>, ApiError> {
+    let manifest = state
+        .analytics_export_service
+        .latest()
+        .await
+        .map_err(analytics_error)?;
+
+    Ok(Json(manifest))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/exports/analytics/{export_id}/{path}",
+    tag = "exports",
+    params(
+        ("export_id" = String, Path, description = "`export_id` from the manifest", example = "20250215T060000Z"),
+        ("path" = String, Path, description = "File path from the manifest", example = "readings/water_year=2025/readings.parquet")
+    ),
+    responses(
+        (status = 200, description = "Parquet file, or the export's manifest.json", content_type = "application/vnd.apache.parquet", body = Vec<u8>),
+        (status = 403, description = "ANALYTICS_EXPORT_DIR is not set (code `exports_disabled`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "No such export, or no such file in it; older exports are removed (code `export_not_found`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state))]
+pub async fn download_analytics_file(
+    State(state): State<AppState>,
+    ApiPath((export_id, path)): ApiPath<(String, String)>,
+) -> Result<Response, ApiError> {
+    let bytes = state
+        .analytics_export_service
+        .file(&export_id, &path)
+        .await
+        .map_err(analytics_error)?;
+
+    let content_type = if path.ends_with(".json") {
+        "application/json"
+    } else {
+        PARQUET_CONTENT_TYPE
+    };
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type),
+            (header::CACHE_CONTROL, EXPORT_FILE_CACHE_CONTROL),
+        ],
+        bytes,
+    )
+        .into_response())
+}
+
+fn analytics_error(e: AnalyticsError) -> ApiError {
+    match e {
+        AnalyticsError::Disabled => {
+            warn!("Analytics export request rejected: ANALYTICS_EXPORT_DIR is not set");
+            ApiError::new(
+                ErrorCode::ExportsDisabled,
+                "Analytics exports are disabled on this server",
+            )
+        }
+        AnalyticsError::NotPublished => ApiError::new(
+            ErrorCode::ExportNotFound,
+            "No analytics export has been published yet",
+        ),
+        AnalyticsError::FileNotFound { export_id, path } => {
+            warn!("Analytics export file not found: {}/{}", export_id, path);
+            ApiError::new(
+                ErrorCode::ExportNotFound,
+                format!("Export {export_id} has no file {path}"),
+            )
+        }
+        e => {
+            error!("Failed to read analytics export: {}", e);
+            ApiError::internal()
+        }
+    }
+}
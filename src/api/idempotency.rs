@@ -0,0 +1,223 @@
+// Idempotency-Key support for POST endpoints
+//
+// A POST carrying an `Idempotency-Key` header is recorded with a hash of the request.
+// Retrying with the same key and body replays the stored response (marked with
+// `Idempotency-Replayed: true`) instead of running the operation again. Server errors
+// are not stored, so a retry after a 5xx runs the operation again. A request that never
+// finishes (client disconnect, timeout, handler panic) releases its key as it is dropped,
+// so retries aren't refused as in progress until the key expires.
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{OriginalUri, Request, State},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use tracing::{error, info, warn};
+
+use crate::api::error::{ApiError, ErrorCode};
+use crate::api::AppState;
+use crate::services::idempotency_service::{
+    IdempotencyOutcome, StoredResponse, MAX_IDEMPOTENCY_KEY_LEN,
+};
+use crate::services::IdempotencyService;
+
+/// Request header carrying the client's key
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Response header set on replayed responses
+pub const IDEMPOTENCY_REPLAYED_HEADER: &str = "idempotency-replayed";
+
+/// Largest request or response body buffered for hashing and replay
+const MAX_BODY_BYTES: usize = 10 * 1024 * 1024;
+
+/// Middleware applying Idempotency-Key semantics to POST requests
+pub async fn idempotency(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    if request.method() != Method::POST {
+        return Ok(next.run(request).await);
+    }
+
+    let Some(key) = request.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(next.run(request).await);
+    };
+    let key = key
+        .to_str()
+        .ok()
+        .filter(|k| IdempotencyService::is_valid_key(k))
+        .ok_or_else(|| {
+            ApiError::invalid_parameter(format!(
+                "Idempotency-Key must be 1-{MAX_IDEMPOTENCY_KEY_LEN} visible ASCII characters"
+            ))
+        })?
+        .to_string();
+
+    let (parts, body) = request.into_parts();
+    let body = to_bytes(body, MAX_BODY_BYTES)
+        .await
+        .map_err(|_| ApiError::invalid_parameter("Request body too large"))?;
+
+    // Nested routers see a stripped path; scope keys by the full one
+    let path = parts
+        .extensions
+        .get::<OriginalUri>()
+        .map(|uri| uri.path().to_string())
+        .unwrap_or_else(|| parts.uri.path().to_string());
+    let method = parts.method.to_string();
+
+    let service = &state.idempotency_service;
+    let outcome = service
+        .begin(&key, &method, &path, &body)
+        .await
+        .map_err(|e| {
+            error!("Failed to check idempotency key {}: {}", key, e);
+            ApiError::internal()
+        })?;
+
+    let reservation = match outcome {
+        IdempotencyOutcome::Reserved => Reservation {
+            service: service.clone(),
+            key: key.clone(),
+            method: method.clone(),
+            path: path.clone(),
+            settled: false,
+        },
+        IdempotencyOutcome::Replay(stored) => {
+            info!("Replaying stored response for idempotency key {}", key);
+            return Ok(replay(stored));
+        }
+        IdempotencyOutcome::InProgress => {
+            warn!("Idempotency key {} is still in progress", key);
+            return Err(ApiError::new(
+                ErrorCode::IdempotencyKeyInUse,
+                "A request with this Idempotency-Key is still being processed",
+            ));
+        }
+        IdempotencyOutcome::Mismatch => {
+            warn!("Idempotency key {} reused for a different request", key);
+            return Err(ApiError::new(
+                ErrorCode::IdempotencyKeyMismatch,
+                "Idempotency-Key was already used with a different request body",
+            ));
+        }
+    };
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(e) => {
+            error!(
+                "Failed to buffer response for idempotency key {}: {}",
+                key, e
+            );
+            reservation.release().await;
+            return Err(ApiError::internal());
+        }
+    };
+
+    if parts.status.is_server_error() {
+        reservation.release().await;
+    } else {
+        let stored = StoredResponse {
+            status_code: parts.status.as_u16(),
+            content_type: parts
+                .headers
+                .get(header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string),
+            body: body.to_vec(),
+        };
+        if let Err(e) = service.complete(&key, &method, &path, &stored).await {
+            // The operation already ran; a failed store only loses replay for retries
+            error!(
+                "Failed to store response for idempotency key {}: {}",
+                key, e
+            );
+        }
+        reservation.settle();
+    }
+
+    Ok(Response::from_parts(parts, Body::from(body)))
+}
+
+/// A reserved key, released on drop unless the request settled it
+///
+/// The middleware future is dropped mid-request when the client disconnects, a timeout
+/// fires, or the handler panics; without this the key would stay in progress until it
+/// expires.
+struct Reservation {
+    service: IdempotencyService,
+    key: String,
+    method: String,
+    path: String,
+    settled: bool,
+}
+
+impl Reservation {
+    /// Delete the reservation so a retry runs the operation again
+    async fn release(mut self) {
+        self.settled = true;
+        release(&self.service, &self.key, &self.method, &self.path).await;
+    }
+
+    /// Keep the row; its response has been stored
+    fn settle(mut self) {
+        self.settled = true;
+    }
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        if self.settled {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            error!(
+                "Idempotency key {} abandoned outside a runtime; it stays in progress until it expires",
+                self.key
+            );
+            return;
+        };
+
+        warn!(
+            "Request with idempotency key {} ended before responding; releasing the key",
+            self.key
+        );
+        let service = self.service.clone();
+        let (key, method, path) = (
+            std::mem::take(&mut self.key),
+            std::mem::take(&mut self.method),
+            std::mem::take(&mut self.path),
+        );
+        runtime.spawn(async move { release(&service, &key, &method, &path).await });
+    }
+}
+
+async fn release(service: &IdempotencyService, key: &str, method: &str, path: &str) {
+    if let Err(e) = service.release(key, method, path).await {
+        error!("Failed to release idempotency key {}: {}", key, e);
+    }
+}
+
+fn replay(stored: StoredResponse) -> Response {
+    let mut response = Response::new(Body::from(stored.body));
+    *response.status_mut() = StatusCode::from_u16(stored.status_code).unwrap_or(StatusCode::OK);
+
+    let headers = response.headers_mut();
+    if let Some(content_type) = stored
+        .content_type
+        .and_then(|ct| HeaderValue::from_str(&ct).ok())
+    {
+        headers.insert(header::CONTENT_TYPE, content_type);
+    }
+    headers.insert(
+        IDEMPOTENCY_REPLAYED_HEADER,
+        HeaderValue::from_static("true"),
+    );
+    response
+}
@@ -0,0 +1,116 @@
+// HEAD and OPTIONS handling
+//
+// Axum answers HEAD on any GET route by running the GET handler and dropping the body.
+// The heavy endpoints register their own HEAD handlers that only look up freshness, so
+// monitoring can poll `Last-Modified` cheaply. Requests whose path matches but whose
+// method doesn't reach `method_not_allowed`: OPTIONS gets 204 and anything else a
+// problem+json 405. Axum adds the route's methods as the `Allow` header to both.
+
+use axum::{
+    http::{header, HeaderValue, Method, StatusCode},
+    response::{IntoResponse, IntoResponseParts, Response, ResponseParts},
+};
+use chrono::{DateTime, Utc};
+
+use crate::api::error::{ApiError, ErrorCode};
+
+/// `Last-Modified` header for a response; omitted when there is no data yet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LastModified(pub Option<DateTime<Utc>>);
+
+impl LastModified {
+    /// IMF-fixdate (RFC 9110), e.g. `Wed, 15 Jan 2025 14:30:00 GMT`
+    fn http_date(at: DateTime<Utc>) -> String {
+        at.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+    }
+}
+
+impl IntoResponseParts for LastModified {
+    type Error = std::convert::Infallible;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        if let Some(at) = self.0 {
+            if let Ok(value) = HeaderValue::from_str(&Self::http_date(at)) {
+                res.headers_mut().insert(header::LAST_MODIFIED, value);
+            }
+        }
+        Ok(res)
+    }
+}
+
+/// Fallback for a known path with an unhandled method (see `Router::method_not_allowed_fallback`)
+pub async fn method_not_allowed(method: Method) -> Response {
+    if method == Method::OPTIONS {
+        return StatusCode::NO_CONTENT.into_response();
+    }
+    ApiError::new(
+        ErrorCode::MethodNotAllowed,
+        format!("{method} is not supported for this path; see the Allow header"),
+    )
+    .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, extract::Request, routing::get, Router};
+    use chrono::TimeZone;
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        Router::new()
+            .route("/things", get(|| async { "things" }).post(|| async { "" }))
+            .method_not_allowed_fallback(method_not_allowed)
+    }
+
+    async fn send(method: Method, uri: &str) -> Response {
+        app()
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn test_http_date() {
+        let at = Utc.with_ymd_and_hms(2025, 1, 15, 14, 30, 0).unwrap();
+        assert_eq!(LastModified::http_date(at), "Wed, 15 Jan 2025 14:30:00 GMT");
+    }
+
+    #[tokio::test]
+    async fn test_options_lists_route_methods() {
+        let response = send(Method::OPTIONS, "/things").await;
+
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            response.headers().get(header::ALLOW).unwrap(),
+            "GET,HEAD,POST"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_method_not_allowed_is_problem_json() {
+        let response = send(Method::DELETE, "/things").await;
+
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(
+            response.headers().get(header::ALLOW).unwrap(),
+            "GET,HEAD,POST"
+        );
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            crate::api::error::PROBLEM_JSON
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unknown_path_is_untouched() {
+        let response = send(Method::OPTIONS, "/nothing").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
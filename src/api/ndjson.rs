@@ -0,0 +1,120 @@
+// Newline-delimited JSON responses
+//
+// Endpoints that can return very large result sets also answer `Accept: application/x-ndjson`
+// with one JSON object per line, written as rows arrive instead of collected into a Vec.
+// Rows come from a bounded channel, so a slow client slows the database cursor rather
+// than growing a buffer.
+
+use axum::{
+    body::{Body, Bytes},
+    http::{header, HeaderMap},
+    response::{IntoResponse, Response},
+    BoxError,
+};
+use futures::stream;
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tracing::error;
+
+pub const NDJSON: &str = "application/x-ndjson";
+
+/// Whether any `Accept` entry names NDJSON
+pub fn wants_ndjson(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|media| {
+            media
+                .split(';')
+                .next()
+                .is_some_and(|media_type| media_type.trim().eq_ignore_ascii_case(NDJSON))
+        })
+}
+
+/// Stream `rx` as an NDJSON body
+///
+/// The status line is already sent when an item fails, so an error ends the body early
+/// and the client sees a truncated response.
+pub fn ndjson_response<T, E>(rx: mpsc::Receiver<Result<T, E>>) -> Response
+where
+    T: Serialize + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let lines = stream::unfold(rx, |mut rx| async move {
+        let item = rx.recv().await?;
+        Some((encode_line(item), rx))
+    });
+
+    ([(header::CONTENT_TYPE, NDJSON)], Body::from_stream(lines)).into_response()
+}
+
+fn encode_line<T, E>(item: Result<T, E>) -> Result<Bytes, BoxError>
+where
+    T: Serialize,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let item = item.inspect_err(|e| error!("NDJSON stream failed: {}", e))?;
+    let mut line = serde_json::to_vec(&item)?;
+    line.push(b'\n');
+    Ok(Bytes::from(line))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use http_body_util::BodyExt;
+    use serde_json::json;
+
+    fn accept(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ACCEPT, HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn test_wants_ndjson() {
+        assert!(wants_ndjson(&accept("application/x-ndjson")));
+        assert!(wants_ndjson(&accept(
+            "application/json;q=0.5, application/x-ndjson"
+        )));
+        assert!(wants_ndjson(&accept("Application/X-NDJSON; charset=utf-8")));
+        assert!(!wants_ndjson(&accept("application/json")));
+        assert!(!wants_ndjson(&accept("*/*")));
+        assert!(!wants_ndjson(&HeaderMap::new()));
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_response_writes_one_line_per_item() {
+        let (tx, rx) = mpsc::channel::<Result<_, std::io::Error>>(1);
+        tokio::spawn(async move {
+            for id in 1..=3 {
+                tx.send(Ok(json!({ "id": id }))).await.unwrap();
+            }
+        });
+
+        let response = ndjson_response(rx);
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            NDJSON
+        );
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body, "{\"id\":1}\n{\"id\":2}\n{\"id\":3}\n");
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_response_ends_early_on_error() {
+        let (tx, rx) = mpsc::channel(2);
+        tx.send(Ok(json!({ "id": 1 }))).await.unwrap();
+        tx.send(Err(std::io::Error::other("cursor failed")))
+            .await
+            .unwrap();
+        drop(tx);
+
+        let result = ndjson_response(rx).into_body().collect().await;
+        assert!(result.is_err());
+    }
+}
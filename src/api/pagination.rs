@@ -0,0 +1,108 @@
+// RFC 5988 `Link` headers for paginated responses
+//
+// Each link repeats the request's path and query with only `page` replaced, so filters
+// and `page_size` carry over. Links are relative, resolved against the request URL.
+
+use axum::{
+    http::{header, HeaderValue, Uri},
+    response::{IntoResponseParts, ResponseParts},
+};
+
+use crate::pagination::PageInfo;
+
+/// `Link` header with rel first, prev, next, and last for a page; prev and next are
+/// omitted on the first and last pages
+#[derive(Debug, Clone)]
+pub struct PageLinks {
+    uri: Uri,
+    page: PageInfo,
+}
+
+impl PageLinks {
+    /// `uri` is the request's full URI (`OriginalUri`, not the nested router's)
+    pub fn new(uri: Uri, page: PageInfo) -> Self {
+        Self { uri, page }
+    }
+
+    fn header_value(&self) -> String {
+        let page = &self.page;
+        let mut rels = vec![("first", 1)];
+        if page.has_prev_page {
+            rels.push(("prev", page.page.saturating_sub(1).min(page.total_pages)));
+        }
+        if page.has_next_page {
+            rels.push(("next", page.page + 1));
+        }
+        rels.push(("last", page.total_pages));
+
+        rels.into_iter()
+            .map(|(rel, number)| format!("<{}>; rel=\"{rel}\"", self.page_uri(number)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// The request URI with `page` set to `number`
+    fn page_uri(&self, number: u32) -> String {
+        let page = format!("page={number}");
+        let query: Vec<&str> = self
+            .uri
+            .query()
+            .unwrap_or_default()
+            .split('&')
+            .filter(|pair| !pair.is_empty() && pair.split('=').next() != Some("page"))
+            .chain([page.as_str()])
+            .collect();
+        format!("{}?{}", self.uri.path(), query.join("&"))
+    }
+}
+
+impl IntoResponseParts for PageLinks {
+    type Error = std::convert::Infallible;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        if let Ok(value) = HeaderValue::from_str(&self.header_value()) {
+            res.headers_mut().insert(header::LINK, value);
+        }
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn links(uri: &str, page: PageInfo) -> String {
+        PageLinks::new(uri.parse().unwrap(), page).header_value()
+    }
+
+    #[test]
+    fn test_middle_page_links() {
+        assert_eq!(
+            links(
+                "/api/v1/gauges?status=active&page=2&page_size=50",
+                PageInfo::new(2, 50, 352)
+            ),
+            concat!(
+                "</api/v1/gauges?status=active&page_size=50&page=1>; rel=\"first\", ",
+                "</api/v1/gauges?status=active&page_size=50&page=1>; rel=\"prev\", ",
+                "</api/v1/gauges?status=active&page_size=50&page=3>; rel=\"next\", ",
+                "</api/v1/gauges?status=active&page_size=50&page=8>; rel=\"last\""
+            )
+        );
+    }
+
+    #[test]
+    fn test_first_and_only_page_links() {
+        assert_eq!(
+            links("/api/v1/gauges", PageInfo::new(1, 50, 10)),
+            "</api/v1/gauges?page=1>; rel=\"first\", </api/v1/gauges?page=1>; rel=\"last\""
+        );
+    }
+
+    #[test]
+    fn test_page_past_the_end_links_back_to_last() {
+        let value = links("/api/v1/gauges?page=20", PageInfo::new(20, 50, 352));
+        assert!(value.contains("</api/v1/gauges?page=8>; rel=\"prev\""));
+        assert!(!value.contains("rel=\"next\""));
+    }
+}
@@ -0,0 +1,114 @@
+// Share link endpoints
+//
+// POST /api/v1/share (X-User-Key) freezes a gauge set's rainfall over a period and returns
+// a signed link; GET /share/{token} serves that snapshot to anyone until it expires (see
+// `crate::share`). Both need SHARE_SIGNING_KEY. PostgreSQL only.
+
+use axum::{extract::State, http::StatusCode, Extension, Json};
+use tracing::{error, instrument, warn};
+use validator::Validate;
+
+use crate::api::error::{ApiError, ApiJson, ApiPath, ErrorCode, FieldError};
+use crate::api::validation::validation_error;
+use crate::api::AppState;
+use crate::db::{ShareLink, User};
+use crate::services::share_service::{ShareError, ShareRequest};
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/share",
+    tag = "share",
+    request_body = ShareRequest,
+    security(
+        ("user_key" = [])
+    ),
+    responses(
+        (status = 201, description = "Snapshot taken; the link serves it until `expires_at`", body = ShareLink),
+        (status = 400, description = "Malformed body, bad gauge list or expiry, or unknown gauges (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid user key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "SHARE_SIGNING_KEY is not set (code `sharing_disabled`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state, user, request), fields(user_id = user.id))]
+pub async fn create_share_link(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    ApiJson(request): ApiJson<ShareRequest>,
+) -> Result<(StatusCode, Json<ShareLink>), ApiError> {
+    request.validate().map_err(validation_error)?;
+
+    let link = state
+        .share_service
+        .create(&user, &request)
+        .await
+        .map_err(share_error)?;
+
+    Ok((StatusCode::CREATED, Json(link)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/share/{token}",
+    tag = "share",
+    params(
+        ("token" = String, Path, description = "Token from POST /api/v1/share")
+    ),
+    responses(
+        (status = 200, description = "The rainfall snapshot as it was when the link was created", body = SharedSnapshot),
+        (status = 403, description = "SHARE_SIGNING_KEY is not set (code `sharing_disabled`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Malformed or forged token, or the snapshot was deleted with its user (code `share_not_found`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 410, description = "The link has expired (code `share_expired`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state, token))]
+pub async fn get_shared_snapshot(
+    State(state): State<AppState>,
+    ApiPath(token): ApiPath<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let snapshot = state
+        .share_service
+        .open(&token)
+        .await
+        .map_err(share_error)?;
+
+    Ok(Json(snapshot))
+}
+
+fn share_error(e: ShareError) -> ApiError {
+    match e {
+        ShareError::Disabled => {
+            warn!("Share link request rejected: SHARE_SIGNING_KEY is not set");
+            ApiError::new(
+                ErrorCode::SharingDisabled,
+                "Share links are disabled on this server",
+            )
+        }
+        ShareError::GaugesNotFound(station_ids) => {
+            warn!("Share lists unknown gauges: {}", station_ids.join(", "));
+            ApiError::invalid_parameter("Invalid parameters: station_ids").with_errors(vec![
+                FieldError {
+                    field: Some("station_ids".to_string()),
+                    rule: "gauge_exists".to_string(),
+                    message: format!("no gauge for {}", station_ids.join(", ")),
+                },
+            ])
+        }
+        ShareError::NotFound => {
+            warn!("Share link not found or signature mismatch");
+            ApiError::new(ErrorCode::ShareNotFound, "Share link not found")
+        }
+        ShareError::Expired(expires_at) => {
+            warn!("Share link expired at {}", expires_at);
+            ApiError::new(
+                ErrorCode::ShareExpired,
+                format!("Share link expired at {}", expires_at.to_rfc3339()),
+            )
+        }
+        ShareError::Database(e) => {
+            error!("Share link operation failed: {}", e);
+            ApiError::internal()
+        }
+    }
+}
@@ -0,0 +1,89 @@
+// Request metrics endpoints
+//
+// `track_requests` wraps every route and feeds crate::metrics; the counters are
+// scraped from /metrics and summarised at /api/v1/admin/stats.
+
+use std::time::Instant;
+
+use axum::{
+    extract::{MatchedPath, RawPathParams, Request, State},
+    http::{header, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use tracing::instrument;
+
+use crate::api::error::ApiError;
+use crate::api::AppState;
+use crate::metrics::{Metrics, StatsSummary, UNMATCHED_ROUTE};
+
+/// Stations listed in the admin stats summary
+pub const TOP_STATIONS: usize = 20;
+
+/// Path parameter naming the station a route reads
+const STATION_PARAM: &str = "station_id";
+
+/// Middleware recording latency per route and successful reads per station
+pub async fn track_requests(
+    State(metrics): State<Metrics>,
+    matched_path: Option<MatchedPath>,
+    params: Result<RawPathParams, axum::extract::rejection::RawPathParamsRejection>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().clone();
+    let started = Instant::now();
+    let response = next.run(request).await;
+
+    let route = matched_path
+        .as_ref()
+        .map_or(UNMATCHED_ROUTE, MatchedPath::as_str);
+    let status = response.status();
+    metrics.record_request(&method, route, status, started.elapsed());
+
+    // A 304 still served the station to a client holding a cached copy
+    if (method == Method::GET || method == Method::HEAD)
+        && (status.is_success() || status == StatusCode::NOT_MODIFIED)
+    {
+        if let Some((_, station_id)) = params
+            .iter()
+            .flat_map(|p| p.iter())
+            .find(|(name, _)| *name == STATION_PARAM)
+        {
+            metrics.record_station_read(station_id);
+        }
+    }
+
+    response
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/stats",
+    tag = "admin",
+    security(
+        ("admin_key" = [])
+    ),
+    responses(
+        (status = 200, description = "Request counts and latency per route, and the most-read stations, since process start", body = StatsSummary),
+        (status = 401, description = "Missing or invalid admin key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Admin API disabled (code `admin_disabled`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state))]
+pub async fn get_stats(State(state): State<AppState>) -> Result<Json<StatsSummary>, ApiError> {
+    Ok(Json(state.metrics.summary(TOP_STATIONS)))
+}
+
+/// Prometheus scrape endpoint; not part of the versioned API
+pub async fn prometheus_metrics(State(metrics): State<Metrics>) -> impl IntoResponse {
+    (
+        [(
+            header::CONTENT_TYPE,
+            "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        metrics.render_prometheus(),
+    )
+}
@@ -0,0 +1,186 @@
+// Per-user usage tracking and monthly quotas
+//
+// `track_usage` wraps every /api/v1 route. A request carrying `X-User-Key` is counted
+// against that user's current UTC month, along with the bytes of its response body, and
+// refused with 429 `quota_exceeded` once their request or byte quota is used up. Requests
+// without a key are not tracked. GET /api/v1/me/usage reports the month so far.
+// PostgreSQL only.
+
+use axum::{
+    body::{Body, HttpBody},
+    extract::{Request, State},
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use chrono::{NaiveDate, Utc};
+use futures::TryStreamExt;
+use tracing::{error, instrument, warn};
+
+use crate::api::error::{ApiError, ErrorCode};
+use crate::api::users::USER_KEY_HEADER;
+use crate::api::AppState;
+use crate::db::{UsageReport, User};
+use crate::services::usage_service::UsageError;
+use crate::services::UsageService;
+
+/// Middleware counting requests and response bytes per user key and enforcing quotas
+///
+/// The resolved user is stored as `Extension<User>`, so `require_user_key` further in
+/// does not look the key up again. If usage cannot be read or written the request is
+/// let through untracked rather than failing.
+pub async fn track_usage(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let Some(key) = request.headers().get(USER_KEY_HEADER) else {
+        return Ok(next.run(request).await);
+    };
+
+    let user = match key.to_str() {
+        Ok(key) => state.user_service.authenticate(key).await,
+        Err(_) => Ok(None),
+    };
+    let user = match user {
+        Ok(Some(user)) => user,
+        Ok(None) => {
+            warn!("Request rejected: invalid user key");
+            return Err(ApiError::new(
+                ErrorCode::Unauthorized,
+                "Missing or invalid X-User-Key header",
+            ));
+        }
+        Err(e) => {
+            error!("Failed to look up user key for usage tracking: {}", e);
+            return Ok(next.run(request).await);
+        }
+    };
+
+    let month = match state.usage_service.claim(&user).await {
+        Ok(month) => month,
+        Err(UsageError::QuotaExceeded(report)) => return Ok(quota_exceeded(&report)),
+        Err(UsageError::Database(e)) => {
+            error!("Failed to count request for user {}: {}", user.id, e);
+            request.extensions_mut().insert(user);
+            return Ok(next.run(request).await);
+        }
+    };
+
+    let user_id = user.id;
+    request.extensions_mut().insert(user);
+    let response = next.run(request).await;
+
+    // Most responses are buffered; count those now, and streamed ones as they are sent
+    if let Some(bytes) = response.body().size_hint().exact() {
+        if bytes > 0 {
+            if let Err(e) = state
+                .usage_service
+                .record_bytes(user_id, month, bytes)
+                .await
+            {
+                error!(
+                    "Failed to record {} bytes for user {}: {}",
+                    bytes, user_id, e
+                );
+            }
+        }
+        return Ok(response);
+    }
+    let mut recorder = BytesRecorder {
+        service: state.usage_service.clone(),
+        user_id,
+        month,
+        bytes: 0,
+    };
+    let (parts, body) = response.into_parts();
+    let body = body.into_data_stream().inspect_ok(move |chunk| {
+        recorder.count(chunk.len());
+    });
+    Ok(Response::from_parts(parts, Body::from_stream(body)))
+}
+
+/// 429 with Retry-After set to the seconds until the month rolls over
+fn quota_exceeded(report: &UsageReport) -> Response {
+    let retry_after = (report.resets_at - Utc::now()).num_seconds().max(1);
+    let mut response = ApiError::new(
+        ErrorCode::QuotaExceeded,
+        format!(
+            "Monthly quota exceeded: {} requests and {} response bytes used; resets at {}",
+            report.request_count,
+            report.response_bytes,
+            report.resets_at.to_rfc3339()
+        ),
+    )
+    .into_response();
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+    response
+}
+
+/// Adds the bytes counted for one response to the user's month when dropped, after
+/// the body has been sent or abandoned
+struct BytesRecorder {
+    service: UsageService,
+    user_id: i64,
+    month: NaiveDate,
+    bytes: u64,
+}
+
+impl BytesRecorder {
+    // A method rather than `recorder.bytes += ..` in the closure, which would capture only
+    // the field and drop the recorder before the body is sent
+    fn count(&mut self, bytes: usize) {
+        self.bytes += bytes as u64;
+    }
+}
+
+impl Drop for BytesRecorder {
+    fn drop(&mut self) {
+        if self.bytes == 0 {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let service = self.service.clone();
+        let (user_id, month, bytes) = (self.user_id, self.month, self.bytes);
+        runtime.spawn(async move {
+            if let Err(e) = service.record_bytes(user_id, month, bytes).await {
+                error!(
+                    "Failed to record {} bytes for user {}: {}",
+                    bytes, user_id, e
+                );
+            }
+        });
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/me/usage",
+    tag = "users",
+    security(
+        ("user_key" = [])
+    ),
+    responses(
+        (status = 200, description = "Requests and response bytes this UTC month, counting this request, with the user's quotas", body = UsageReport),
+        (status = 401, description = "Missing or invalid user key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 429, description = "A monthly quota is used up; Retry-After gives the seconds until it resets (code `quota_exceeded`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state, user), fields(user_id = user.id))]
+pub async fn get_usage(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+) -> Result<Json<UsageReport>, ApiError> {
+    let report = state.usage_service.usage(&user).await.map_err(|e| {
+        error!("Failed to read usage for user {}: {}", user.id, e);
+        ApiError::internal()
+    })?;
+
+    Ok(Json(report))
+}
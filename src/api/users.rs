@@ -0,0 +1,612 @@
+// User endpoints: favorite gauges, saved views, and digests
+//
+// Admins create users under /api/v1/admin/users; each gets a key shown once. Routes under
+// /api/v1/me require the `X-User-Key` header and act on that user's favorites and saved
+// views, so a frontend can sync dashboards across devices, and on their daily or weekly
+// digest of rainfall at those favorites. Admins can also cap a user's monthly requests
+// and response bytes (see `crate::api::usage`). PostgreSQL only.
+
+use axum::{
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::Response,
+    Extension, Json,
+};
+use tracing::{error, info, instrument, warn};
+use validator::Validate;
+
+use crate::api::error::{ApiError, ApiJson, ErrorCode, FieldError};
+use crate::api::validation::{
+    validation_error, SavedViewPath, StationPath, UserPath, ValidatedPath, ValidatedQuery,
+};
+use crate::api::AppState;
+use crate::db::{Digest, DigestSubscription, FavoriteGauge, SavedView, User};
+use crate::services::digest_service::{
+    DigestError, DigestPreviewParams, DigestSubscriptionRequest,
+};
+use crate::services::user_service::{
+    CreatedUser, NewUser, SavedViewRequest, UserError, UserQuotas,
+};
+
+/// Header carrying a user's key
+pub const USER_KEY_HEADER: &str = "x-user-key";
+
+/// Middleware resolving `X-User-Key` to its user, available to handlers as
+/// `Extension<User>`
+pub async fn require_user_key(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    // Already resolved by `track_usage`
+    if request.extensions().get::<User>().is_some() {
+        return Ok(next.run(request).await);
+    }
+
+    let unauthorized = || {
+        warn!("User request rejected: missing or invalid user key");
+        ApiError::new(
+            ErrorCode::Unauthorized,
+            "Missing or invalid X-User-Key header",
+        )
+    };
+
+    let Some(key) = request
+        .headers()
+        .get(USER_KEY_HEADER)
+        .and_then(|v| v.to_str().ok())
+    else {
+        return Err(unauthorized());
+    };
+
+    let user = state
+        .user_service
+        .authenticate(key)
+        .await
+        .map_err(user_error)?
+        .ok_or_else(unauthorized)?;
+
+    request.extensions_mut().insert(user);
+    Ok(next.run(request).await)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/users",
+    tag = "admin",
+    request_body = NewUser,
+    security(
+        ("admin_key" = [])
+    ),
+    responses(
+        (status = 201, description = "User created; the key is not shown again", body = CreatedUser),
+        (status = 400, description = "Malformed body or name (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid admin key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Admin API disabled (code `admin_disabled`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Another user has the name (code `name_taken`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state, new_user))]
+pub async fn create_user(
+    State(state): State<AppState>,
+    ApiJson(new_user): ApiJson<NewUser>,
+) -> Result<(StatusCode, Json<CreatedUser>), ApiError> {
+    new_user.validate().map_err(validation_error)?;
+
+    let created = state
+        .user_service
+        .create(&new_user)
+        .await
+        .map_err(user_error)?;
+
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/users",
+    tag = "admin",
+    security(
+        ("admin_key" = [])
+    ),
+    responses(
+        (status = 200, description = "Users, ordered by name", body = [User]),
+        (status = 401, description = "Missing or invalid admin key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Admin API disabled (code `admin_disabled`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state))]
+pub async fn list_users(State(state): State<AppState>) -> Result<Json<Vec<User>>, ApiError> {
+    let users = state.user_service.list().await.map_err(user_error)?;
+
+    info!("Retrieved {} users", users.len());
+    Ok(Json(users))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/users/{user_id}/quota",
+    tag = "admin",
+    request_body = UserQuotas,
+    security(
+        ("admin_key" = [])
+    ),
+    params(
+        ("user_id" = i64, Path, description = "User ID", example = 7)
+    ),
+    responses(
+        (status = 200, description = "Quotas replaced; they apply from the user's next request", body = User),
+        (status = 400, description = "Malformed body, invalid user ID, or a quota below 1 (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid admin key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Admin API disabled (code `admin_disabled`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "No such user (code `user_not_found`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state, quotas), fields(user_id = path.user_id))]
+pub async fn set_user_quota(
+    State(state): State<AppState>,
+    ValidatedPath(path): ValidatedPath<UserPath>,
+    ApiJson(quotas): ApiJson<UserQuotas>,
+) -> Result<Json<User>, ApiError> {
+    quotas.validate().map_err(validation_error)?;
+
+    let user = state
+        .user_service
+        .set_quotas(path.user_id, &quotas)
+        .await
+        .map_err(user_error)?;
+
+    Ok(Json(user))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/users/{user_id}",
+    tag = "admin",
+    security(
+        ("admin_key" = [])
+    ),
+    params(
+        ("user_id" = i64, Path, description = "User ID", example = 7)
+    ),
+    responses(
+        (status = 204, description = "User deleted with their favorites and saved views"),
+        (status = 400, description = "Invalid user ID (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid admin key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Admin API disabled (code `admin_disabled`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "No such user (code `user_not_found`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state), fields(user_id = path.user_id))]
+pub async fn delete_user(
+    State(state): State<AppState>,
+    ValidatedPath(path): ValidatedPath<UserPath>,
+) -> Result<StatusCode, ApiError> {
+    state
+        .user_service
+        .delete(path.user_id)
+        .await
+        .map_err(user_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/me",
+    tag = "users",
+    security(
+        ("user_key" = [])
+    ),
+    responses(
+        (status = 200, description = "The user holding the key", body = User),
+        (status = 401, description = "Missing or invalid user key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+pub async fn get_me(Extension(user): Extension<User>) -> Json<User> {
+    Json(user)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/me/favorites",
+    tag = "users",
+    security(
+        ("user_key" = [])
+    ),
+    responses(
+        (status = 200, description = "Favorite gauges, most recently added first", body = [FavoriteGauge]),
+        (status = 401, description = "Missing or invalid user key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state, user), fields(user_id = user.id))]
+pub async fn list_favorites(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+) -> Result<Json<Vec<FavoriteGauge>>, ApiError> {
+    let favorites = state
+        .user_service
+        .favorites(&user)
+        .await
+        .map_err(user_error)?;
+
+    Ok(Json(favorites))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/me/favorites/{station_id}",
+    tag = "users",
+    security(
+        ("user_key" = [])
+    ),
+    params(
+        ("station_id" = String, Path, description = "Rain gauge station ID", example = "59700")
+    ),
+    responses(
+        (status = 204, description = "Gauge is a favorite (adding one twice is not an error)"),
+        (status = 400, description = "Invalid station ID (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid user key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Gauge has no metadata row (code `gauge_not_found`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state, user), fields(user_id = user.id, station_id = %station_id))]
+pub async fn add_favorite(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    ValidatedPath(StationPath { station_id }): ValidatedPath<StationPath>,
+) -> Result<StatusCode, ApiError> {
+    state
+        .user_service
+        .add_favorite(&user, &station_id)
+        .await
+        .map_err(user_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/me/favorites/{station_id}",
+    tag = "users",
+    security(
+        ("user_key" = [])
+    ),
+    params(
+        ("station_id" = String, Path, description = "Rain gauge station ID", example = "59700")
+    ),
+    responses(
+        (status = 204, description = "Gauge is not a favorite (removing one twice is not an error)"),
+        (status = 400, description = "Invalid station ID (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid user key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state, user), fields(user_id = user.id, station_id = %station_id))]
+pub async fn remove_favorite(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    ValidatedPath(StationPath { station_id }): ValidatedPath<StationPath>,
+) -> Result<StatusCode, ApiError> {
+    state
+        .user_service
+        .remove_favorite(&user, &station_id)
+        .await
+        .map_err(user_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/me/views",
+    tag = "users",
+    security(
+        ("user_key" = [])
+    ),
+    responses(
+        (status = 200, description = "Saved views, ordered by name", body = [SavedView]),
+        (status = 401, description = "Missing or invalid user key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state, user), fields(user_id = user.id))]
+pub async fn list_views(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+) -> Result<Json<Vec<SavedView>>, ApiError> {
+    let views = state.user_service.views(&user).await.map_err(user_error)?;
+
+    Ok(Json(views))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/me/views",
+    tag = "users",
+    request_body = SavedViewRequest,
+    security(
+        ("user_key" = [])
+    ),
+    responses(
+        (status = 201, description = "View saved", body = SavedView),
+        (status = 400, description = "Malformed body, or a station ID without a gauge (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid user key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "The user already has a view with the name (code `name_taken`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state, user, request), fields(user_id = user.id))]
+pub async fn create_view(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    ApiJson(request): ApiJson<SavedViewRequest>,
+) -> Result<(StatusCode, Json<SavedView>), ApiError> {
+    request.validate().map_err(validation_error)?;
+
+    let view = state
+        .user_service
+        .create_view(&user, &request)
+        .await
+        .map_err(user_error)?;
+
+    Ok((StatusCode::CREATED, Json(view)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/me/views/{view_id}",
+    tag = "users",
+    request_body = SavedViewRequest,
+    security(
+        ("user_key" = [])
+    ),
+    params(
+        ("view_id" = i64, Path, description = "Saved view ID", example = 12)
+    ),
+    responses(
+        (status = 200, description = "View replaced", body = SavedView),
+        (status = 400, description = "Invalid view ID, malformed body, or a station ID without a gauge (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid user key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "The user has no such view (code `saved_view_not_found`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "The user already has another view with the name (code `name_taken`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state, user, request), fields(user_id = user.id, view_id = path.view_id))]
+pub async fn update_view(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    ValidatedPath(path): ValidatedPath<SavedViewPath>,
+    ApiJson(request): ApiJson<SavedViewRequest>,
+) -> Result<Json<SavedView>, ApiError> {
+    request.validate().map_err(validation_error)?;
+
+    let view = state
+        .user_service
+        .update_view(&user, path.view_id, &request)
+        .await
+        .map_err(user_error)?;
+
+    Ok(Json(view))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/me/views/{view_id}",
+    tag = "users",
+    security(
+        ("user_key" = [])
+    ),
+    params(
+        ("view_id" = i64, Path, description = "Saved view ID", example = 12)
+    ),
+    responses(
+        (status = 204, description = "View deleted"),
+        (status = 400, description = "Invalid view ID (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid user key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "The user has no such view (code `saved_view_not_found`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state, user), fields(user_id = user.id, view_id = path.view_id))]
+pub async fn delete_view(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    ValidatedPath(path): ValidatedPath<SavedViewPath>,
+) -> Result<StatusCode, ApiError> {
+    state
+        .user_service
+        .delete_view(&user, path.view_id)
+        .await
+        .map_err(user_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/me/digest",
+    tag = "users",
+    security(
+        ("user_key" = [])
+    ),
+    responses(
+        (status = 200, description = "The user's digest subscription", body = DigestSubscription),
+        (status = 401, description = "Missing or invalid user key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "The user has no digest subscription (code `digest_not_found`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state, user), fields(user_id = user.id))]
+pub async fn get_digest(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+) -> Result<Json<DigestSubscription>, ApiError> {
+    let subscription = state
+        .digest_service
+        .subscription(&user)
+        .await
+        .map_err(digest_error)?;
+
+    Ok(Json(subscription))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/me/digest",
+    tag = "users",
+    request_body = DigestSubscriptionRequest,
+    security(
+        ("user_key" = [])
+    ),
+    responses(
+        (status = 200, description = "Subscription created or replaced; changing the frequency starts over from the latest period", body = DigestSubscription),
+        (status = 400, description = "Malformed body, no channel, or email while email digests are not enabled (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid user key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state, user, request), fields(user_id = user.id))]
+pub async fn subscribe_digest(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    ApiJson(request): ApiJson<DigestSubscriptionRequest>,
+) -> Result<Json<DigestSubscription>, ApiError> {
+    request.validate().map_err(validation_error)?;
+
+    let subscription = state
+        .digest_service
+        .subscribe(&user, &request)
+        .await
+        .map_err(digest_error)?;
+
+    Ok(Json(subscription))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/me/digest",
+    tag = "users",
+    security(
+        ("user_key" = [])
+    ),
+    responses(
+        (status = 204, description = "No digests are sent (unsubscribing twice is not an error)"),
+        (status = 401, description = "Missing or invalid user key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state, user), fields(user_id = user.id))]
+pub async fn unsubscribe_digest(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+) -> Result<StatusCode, ApiError> {
+    state
+        .digest_service
+        .unsubscribe(&user)
+        .await
+        .map_err(digest_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/me/digest/preview",
+    tag = "users",
+    params(DigestPreviewParams),
+    security(
+        ("user_key" = [])
+    ),
+    responses(
+        (status = 200, description = "The digest for the latest complete day or week, as a webhook would receive it; nothing is sent", body = Digest),
+        (status = 400, description = "Invalid frequency (code `invalid_parameter`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid user key (code `unauthorized`)", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Internal server error (code `internal_error`)", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+#[instrument(skip(state, user), fields(user_id = user.id, frequency = ?params.frequency))]
+pub async fn preview_digest(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    ValidatedQuery(params): ValidatedQuery<DigestPreviewParams>,
+) -> Result<Json<Digest>, ApiError> {
+    let digest = state
+        .digest_service
+        .preview(&user, &params)
+        .await
+        .map_err(digest_error)?;
+
+    Ok(Json(digest))
+}
+
+fn digest_error(e: DigestError) -> ApiError {
+    match e {
+        DigestError::NotSubscribed(user_id) => {
+            warn!("User {} has no digest subscription", user_id);
+            ApiError::new(ErrorCode::DigestNotFound, "You have no digest subscription")
+        }
+        DigestError::EmailDisabled => {
+            warn!("Email digest requested but DIGEST_SMTP_URL is not set");
+            ApiError::invalid_parameter("Invalid parameters: email").with_errors(vec![FieldError {
+                field: Some("email".to_string()),
+                rule: "email_enabled".to_string(),
+                message: "email digests are not enabled; use webhook_url".to_string(),
+            }])
+        }
+        DigestError::Database(e) => {
+            error!("Digest operation failed: {}", e);
+            ApiError::internal()
+        }
+    }
+}
+
+fn user_error(e: UserError) -> ApiError {
+    match e {
+        UserError::GaugeNotFound(station_id) => {
+            warn!("Gauge {} not found", station_id);
+            ApiError::gauge_not_found(&station_id)
+        }
+        UserError::ViewGaugesNotFound(station_ids) => {
+            warn!("View lists unknown gauges: {}", station_ids.join(", "));
+            ApiError::invalid_parameter("Invalid parameters: station_ids").with_errors(vec![
+                FieldError {
+                    field: Some("station_ids".to_string()),
+                    rule: "gauge_exists".to_string(),
+                    message: format!("no gauge for {}", station_ids.join(", ")),
+                },
+            ])
+        }
+        UserError::UserNotFound(id) => {
+            warn!("User {} not found", id);
+            ApiError::new(ErrorCode::UserNotFound, format!("User {id} not found"))
+        }
+        UserError::ViewNotFound(id) => {
+            warn!("Saved view {} not found", id);
+            ApiError::new(
+                ErrorCode::SavedViewNotFound,
+                format!("Saved view {id} not found"),
+            )
+        }
+        UserError::NameTaken(name) => {
+            warn!("Name {} already in use", name);
+            ApiError::new(
+                ErrorCode::NameTaken,
+                format!("The name '{name}' is already in use"),
+            )
+        }
+        UserError::Database(e) => {
+            error!("User operation failed: {}", e);
+            ApiError::internal()
+        }
+    }
+}
@@ -0,0 +1,215 @@
+// Request validation for API path and query parameters
+//
+// Parameter structs derive `validator::Validate`. The Validated* extractors run those
+// rules after deserialization and reject with a 400 problem+json listing every failed
+// field, so bad input never reaches the services (or gets silently defaulted).
+
+use axum::{extract::FromRequestParts, http::request::Parts};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use validator::{Validate, ValidationErrors, ValidationErrorsKind};
+
+use crate::api::error::{ApiError, ApiPath, ApiQuery, ErrorCode, FieldError};
+use crate::station_id::StationId;
+
+/// Oldest year accepted in water/calendar year routes
+pub const MIN_YEAR: i32 = 1900;
+
+/// Latest year accepted in water/calendar year routes
+pub const MAX_YEAR: i32 = 2200;
+
+/// Key validator uses for struct-level (multi-field) rules
+const SCHEMA_ERRORS_KEY: &str = "__all__";
+
+/// `/{station_id}` path segment
+///
+/// `StationId` validates while the path deserializes; ApiPath reports a malformed ID
+/// as a `station_id_format` field error.
+#[derive(Debug, Deserialize, Validate)]
+pub struct StationPath {
+    pub station_id: StationId,
+}
+
+/// `/{station_id}/attachments/{attachment_id}` path segments
+#[derive(Debug, Deserialize, Validate)]
+pub struct AttachmentPath {
+    pub station_id: StationId,
+    #[validate(range(min = 1, message = "must be a positive ID"))]
+    pub attachment_id: i64,
+}
+
+/// `/{station_id}/annotations/{annotation_id}` path segments
+#[derive(Debug, Deserialize, Validate)]
+pub struct AnnotationPath {
+    pub station_id: StationId,
+    #[validate(range(min = 1, message = "must be a positive ID"))]
+    pub annotation_id: i64,
+}
+
+/// `/users/{user_id}` path segment
+#[derive(Debug, Deserialize, Validate)]
+pub struct UserPath {
+    #[validate(range(min = 1, message = "must be a positive ID"))]
+    pub user_id: i64,
+}
+
+/// `/me/views/{view_id}` path segment
+#[derive(Debug, Deserialize, Validate)]
+pub struct SavedViewPath {
+    #[validate(range(min = 1, message = "must be a positive ID"))]
+    pub view_id: i64,
+}
+
+/// `/anomalies/{anomaly_id}/review` path segment
+#[derive(Debug, Deserialize, Validate)]
+pub struct AnomalyPath {
+    #[validate(range(min = 1, message = "must be a positive ID"))]
+    pub anomaly_id: i64,
+}
+
+/// `/storms/{storm_id}/...` path segment
+#[derive(Debug, Deserialize, Validate)]
+pub struct StormPath {
+    #[validate(range(min = 1, message = "must be a positive ID"))]
+    pub storm_id: i64,
+}
+
+/// `/{station_id}/.../{year}` path segments
+///
+/// The year stays a string here so a malformed year can be reported with the
+/// route-specific error code (see [`parse_year`]).
+#[derive(Debug, Deserialize, Validate)]
+pub struct StationYearPath {
+    pub station_id: StationId,
+    pub year: String,
+}
+
+/// Parse and range-check a year path segment, reporting failures with `code`
+pub fn parse_year(raw: &str, code: ErrorCode, kind: &str) -> Result<i32, ApiError> {
+    let year_error = |rule: &str, message: String| {
+        ApiError::new(code, format!("'{raw}' is not a valid {kind}")).with_errors(vec![
+            FieldError {
+                field: Some("year".to_string()),
+                rule: rule.to_string(),
+                message,
+            },
+        ])
+    };
+
+    let year: i32 = raw
+        .parse()
+        .map_err(|_| year_error("integer", "must be a whole number".to_string()))?;
+
+    if !(MIN_YEAR..=MAX_YEAR).contains(&year) {
+        return Err(year_error(
+            "range",
+            format!("must be between {MIN_YEAR} and {MAX_YEAR}"),
+        ));
+    }
+
+    Ok(year)
+}
+
+/// Flatten validator output into problem+json field errors
+pub fn validation_error(errors: ValidationErrors) -> ApiError {
+    let mut field_errors: Vec<FieldError> = errors
+        .into_errors()
+        .into_iter()
+        .flat_map(|(field, kind)| {
+            let failures = match kind {
+                ValidationErrorsKind::Field(failures) => failures,
+                // Parameter structs are flat, so nested kinds never occur
+                ValidationErrorsKind::Struct(_) | ValidationErrorsKind::List(_) => Vec::new(),
+            };
+            let field = (field != SCHEMA_ERRORS_KEY).then(|| field.to_string());
+            failures.into_iter().map(move |failure| FieldError {
+                field: field.clone(),
+                rule: failure.code.to_string(),
+                message: failure
+                    .message
+                    .map(|m| m.to_string())
+                    .unwrap_or_else(|| format!("failed the {} rule", failure.code)),
+            })
+        })
+        .collect();
+    field_errors.sort_by(|a, b| (&a.field, &a.rule).cmp(&(&b.field, &b.rule)));
+
+    let fields: Vec<&str> = field_errors
+        .iter()
+        .map(|e| e.field.as_deref().unwrap_or("request"))
+        .collect();
+    let detail = format!("Invalid parameters: {}", fields.join(", "));
+
+    ApiError::new(ErrorCode::InvalidParameter, detail).with_errors(field_errors)
+}
+
+/// `Path` extractor that also runs the type's validation rules
+pub struct ValidatedPath<T>(pub T);
+
+impl<S, T> FromRequestParts<S> for ValidatedPath<T>
+where
+    T: DeserializeOwned + Validate + Send,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let ApiPath(value) = ApiPath::<T>::from_request_parts(parts, state).await?;
+        value.validate().map_err(validation_error)?;
+        Ok(ValidatedPath(value))
+    }
+}
+
+/// `Query` extractor that also runs the type's validation rules
+pub struct ValidatedQuery<T>(pub T);
+
+impl<S, T> FromRequestParts<S> for ValidatedQuery<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let ApiQuery(value) = ApiQuery::<T>::from_request_parts(parts, state).await?;
+        value.validate().map_err(validation_error)?;
+        Ok(ValidatedQuery(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_year() {
+        assert_eq!(
+            parse_year("2024", ErrorCode::InvalidWaterYear, "water year").unwrap(),
+            2024
+        );
+
+        let err = parse_year("abc", ErrorCode::InvalidWaterYear, "water year").unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidWaterYear);
+        assert_eq!(err.errors[0].rule, "integer");
+
+        let err = parse_year("99999", ErrorCode::InvalidCalendarYear, "calendar year").unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidCalendarYear);
+        assert_eq!(err.errors[0].field.as_deref(), Some("year"));
+        assert_eq!(err.errors[0].rule, "range");
+    }
+
+    #[test]
+    fn test_validation_error_lists_fields() {
+        let path = AttachmentPath {
+            station_id: "59700".parse().unwrap(),
+            attachment_id: 0,
+        };
+        let err = validation_error(path.validate().unwrap_err());
+
+        assert_eq!(err.code, ErrorCode::InvalidParameter);
+        assert_eq!(err.detail, "Invalid parameters: attachment_id");
+        assert_eq!(err.errors.len(), 1);
+        assert_eq!(err.errors[0].field.as_deref(), Some("attachment_id"));
+        assert_eq!(err.errors[0].rule, "range");
+    }
+}
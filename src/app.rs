@@ -1,28 +1,150 @@
-use sqlx::PgPool;
+use std::time::Duration;
+
+use backon::Retryable;
+use chrono::Utc;
+use log::LevelFilter;
 use tokio::task::JoinHandle;
 use tower_http::trace::TraceLayer;
-use tracing::info;
+use tracing::{error, info, warn};
 
-use crate::api::{create_router, AppState};
-use crate::config::Config;
+use crate::analytics::AnalyticsStore;
+use crate::anomaly::AnomalyAlerter;
+use crate::api::{create_ops_router, create_router, AppState};
+use crate::clock;
+use crate::config::{Config, DatabaseConfig};
 use crate::db::fopr_import_job_repository::FoprImportJobRepository;
-use crate::db::{GaugeRepository, MonthlyRainfallRepository, ReadingRepository};
+use crate::db::{
+    AnnotationRepository, AttachmentRepository, CurrentConditionsRepository, DbError, DbPool,
+    GaugeRepository, HourlyRainfallRepository, IdempotencyRepository, MonthlyRainfallRepository,
+    QuarantineRepository, ReadingRepository, SlowQueryLog, SlowQueryRepository,
+    SummaryViewRepository, ThresholdEventRepository,
+};
+use crate::digest::DigestSender;
+use crate::elevation::ElevationSampler;
+use crate::error::Error;
 use crate::fetcher::RainGaugeFetcher;
 use crate::gauge_list_fetcher::GaugeListFetcher;
-use crate::scheduler;
+use crate::geocode::ReverseGeocoder;
+use crate::importers::downloader::McfcdDownloader;
+use crate::job_notify::JobNotifier;
+use crate::leader::{self, Leadership};
+use crate::metrics::Metrics;
+use crate::payload_archive::PayloadArchive;
+use crate::politeness::Politeness;
+use crate::readiness::{Readiness, ReadinessCheck};
+use crate::scheduler::{self, JobGate};
 use crate::services::fopr_import_service::FoprImportService;
-use crate::services::{GaugeService, ReadingService};
+use crate::services::{
+    AnalyticsExportService, AnnotationService, AnomalyService, AttachmentService,
+    CurrentConditionsService, DigestService, ElevationService, FoprAvailabilityService,
+    ForecastService, GaugeService, GeocodeService, HistoricalImportService, IdempotencyService,
+    JobRunService, RadarService, ReadingService, ShareService, SlowQueryService, SummaryService,
+    SummaryViewService, ThresholdService, UsageService, UserService, WeatherService, ZoneService,
+};
+use crate::share::ShareSigner;
+use crate::storage::ObjectStore;
+use crate::weather::WeatherSource;
 use crate::workers::fopr_import_worker::FoprImportWorker;
 
+/// Delay between attempts at a failed startup check
+const STARTUP_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Connections in the service's database pool
+const DB_MAX_CONNECTIONS: u32 = 5;
+
+/// Which parts of the service a process runs (SERVICE_ROLE or `--role`, default `all`)
+///
+/// Every role shares one config and database, so the HTTP API can be scaled apart from
+/// the background work. Run a single `scheduler` process: each one scrapes on its own
+/// timer. `worker`s can scale with the FOPR import backlog, since each job is claimed by
+/// one worker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ServiceRole {
+    /// API, schedulers, and workers in one process
+    #[default]
+    All,
+    /// HTTP API only
+    Api,
+    /// FOPR import workers only
+    Worker,
+    /// Scrape and maintenance schedulers only
+    Scheduler,
+}
+
+impl ServiceRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ServiceRole::All => "all",
+            ServiceRole::Api => "api",
+            ServiceRole::Worker => "worker",
+            ServiceRole::Scheduler => "scheduler",
+        }
+    }
+
+    /// The full API router; other roles serve only health, readiness, and /metrics
+    pub fn serves_api(&self) -> bool {
+        matches!(self, ServiceRole::All | ServiceRole::Api)
+    }
+
+    pub fn runs_schedulers(&self) -> bool {
+        matches!(self, ServiceRole::All | ServiceRole::Scheduler)
+    }
+
+    pub fn runs_workers(&self) -> bool {
+        matches!(self, ServiceRole::All | ServiceRole::Worker)
+    }
+}
+
+impl std::fmt::Display for ServiceRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ServiceRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        <Self as clap::ValueEnum>::from_str(s, true)
+    }
+}
+
 /// Application with all spawned background tasks and server
 ///
 /// This struct holds handles to all running tasks, allowing graceful
 /// shutdown if needed. For now, tasks run indefinitely.
 pub struct Application {
     pub server_handle: JoinHandle<Result<(), std::io::Error>>,
-    pub reading_scheduler_handle: JoinHandle<()>,
-    pub gauge_list_scheduler_handle: JoinHandle<()>,
+    /// Scheduler handles are `None` in snapshot mode, where nothing is scraped
+    pub reading_scheduler_handle: Option<JoinHandle<()>>,
+    pub gauge_list_scheduler_handle: Option<JoinHandle<()>>,
+    pub reconciliation_scheduler_handle: Option<JoinHandle<()>>,
+    /// Also `None` unless a geocoding provider is configured
+    pub geocode_scheduler_handle: Option<JoinHandle<()>>,
+    /// Also `None` unless an elevation model is configured
+    pub elevation_scheduler_handle: Option<JoinHandle<()>>,
+    /// Also `None` unless a weather source is configured
+    pub weather_scheduler_handle: Option<JoinHandle<()>>,
+    /// Also `None` on backends other than PostgreSQL
+    pub anomaly_scheduler_handle: Option<JoinHandle<()>>,
+    /// Also `None` unless summary views are enabled (PostgreSQL only)
+    pub summary_view_scheduler_handle: Option<JoinHandle<()>>,
+    /// Also `None` with JOB_RUN_RETENTION_DAYS=0 or on backends other than PostgreSQL
+    pub job_run_retention_handle: Option<JoinHandle<()>>,
+    /// Also `None` without PAYLOAD_ARCHIVE_DIR or with PAYLOAD_ARCHIVE_DAYS=0
+    pub payload_archive_retention_handle: Option<JoinHandle<()>>,
+    /// Also `None` on backends other than PostgreSQL
+    pub digest_scheduler_handle: Option<JoinHandle<()>>,
+    /// Also `None` without ANALYTICS_EXPORT_DIR
+    pub analytics_export_scheduler_handle: Option<JoinHandle<()>>,
+    /// Scheduler leader election; `None` unless enabled for a process running schedulers
+    pub leader_election_handle: Option<JoinHandle<()>>,
     pub fopr_worker_handles: Vec<JoinHandle<()>>,
+    /// Startup checks behind /api/v1/health/ready
+    pub readiness: Readiness,
+    pub startup_checks_handle: JoinHandle<()>,
+    /// Periodic database health check feeding the db_* metrics
+    pub db_health_handle: JoinHandle<()>,
 }
 
 impl Application {
@@ -32,76 +154,514 @@ impl Application {
     /// - HTTP API server (Axum)
     /// - Reading scheduler (15 min interval)
     /// - Gauge list scheduler (60 min interval)
-    /// - FOPR import workers (configurable concurrency, default 10)
-    pub async fn build(config: Config, pool: PgPool) -> Result<Self, Box<dyn std::error::Error>> {
-        info!("Initializing application components");
+    /// - Gauge reconciliation scheduler (6 hour interval)
+    /// - Reverse geocoding scheduler (daily, only with a provider configured)
+    /// - Elevation sampling scheduler (daily, only with an elevation model configured)
+    /// - Weather scheduler (daily, only with a weather source configured; PostgreSQL only)
+    /// - Anomaly detection scheduler (daily; PostgreSQL only)
+    /// - Summary view refresh scheduler (every 15 min, only with summary views enabled;
+    ///   PostgreSQL only)
+    /// - Job run history pruning (daily; PostgreSQL only)
+    /// - Payload archive pruning (daily, only with PAYLOAD_ARCHIVE_DIR set)
+    /// - Digest delivery (hourly; PostgreSQL only)
+    /// - Analytics export (daily, only with ANALYTICS_EXPORT_DIR set)
+    /// - FOPR import workers (configurable concurrency, default 10; PostgreSQL only)
+    /// - Scheduler leader election (only when enabled; PostgreSQL only)
+    /// - Database health check (every 30 s by default)
+    ///
+    /// `config.server.role` narrows this to the API, the schedulers, or the workers (see
+    /// [`ServiceRole`]); every role keeps the HTTP server for probes and metrics.
+    ///
+    /// With `config.database.snapshot_dir` set, `pool` holds a loaded snapshot and only the
+    /// API server runs, without the admin endpoints.
+    ///
+    /// The server starts before the service is ready: `readiness` flips once the config
+    /// validates, the schema is confirmed migrated, and current conditions are warmed.
+    /// `metrics` already holds the startup connection attempts from [`connect_database`].
+    pub async fn build(config: Config, pool: DbPool, metrics: Metrics) -> Result<Self, Error> {
+        info!(
+            "Initializing application components ({} backend)",
+            pool.backend()
+        );
+
+        let readiness = Readiness::new();
+        match config.validate() {
+            Ok(()) => readiness.pass(ReadinessCheck::Config),
+            Err(problems) => {
+                for problem in &problems {
+                    error!("Invalid configuration: {}", problem);
+                }
+                readiness.fail(ReadinessCheck::Config, problems.join("; "));
+            }
+        }
 
         // Create repositories
         let reading_repo = ReadingRepository::new(pool.clone());
         let gauge_repo = GaugeRepository::new(pool.clone());
-        let monthly_rainfall_repo = MonthlyRainfallRepository::new(pool.clone());
+        let monthly_rainfall_repo = MonthlyRainfallRepository::new(pool.clone())
+            .with_slow_query_log(SlowQueryLog::new(config.database.slow_query));
+        let hourly_rainfall_repo = HourlyRainfallRepository::new(pool.clone());
         let job_repo = FoprImportJobRepository::new(pool.clone());
 
         // Create services
-        let reading_service =
-            ReadingService::new(reading_repo.clone(), monthly_rainfall_repo.clone());
+        let clock = clock::system_clock();
+        // Fetch, gauge list, and FOPR import history; recorded on PostgreSQL only
+        let job_run_service = JobRunService::new(pool.clone()).with_clock(clock.clone());
+        // Summary views only exist in the PostgreSQL schema
+        let summary_view_config = config
+            .database
+            .summary_views
+            .filter(|_| pool.postgres().is_ok());
+        if config.database.summary_views.is_some() && summary_view_config.is_none() {
+            info!("Summary views disabled on the {} backend", pool.backend());
+        }
+        let summary_view_service =
+            SummaryViewService::new(pool.clone(), summary_view_config).with_clock(clock.clone());
+        let reading_service = ReadingService::new(
+            reading_repo.clone(),
+            monthly_rainfall_repo.clone(),
+            hourly_rainfall_repo.clone(),
+            AnnotationRepository::new(pool.clone()),
+            gauge_repo.clone(),
+        )
+        .with_query_limits(config.server.reading_query_limits)
+        .with_clock(clock.clone())
+        .with_summary_views(summary_view_service.clone());
         let gauge_service = GaugeService::new(gauge_repo.clone(), job_repo.clone());
-        let fopr_import_service = FoprImportService::new(pool.clone());
+        let summary_service = SummaryService::new(monthly_rainfall_repo.clone())
+            .with_hourly(hourly_rainfall_repo.clone())
+            .with_views(SummaryViewRepository::new(pool.clone()));
+        let idempotency_service = IdempotencyService::new(IdempotencyRepository::new(pool.clone()));
+        let attachment_store = ObjectStore::new(&config.server.attachment_storage_dir);
+        info!(
+            "Gauge attachments stored under {}",
+            attachment_store.root().display()
+        );
+        let attachment_service =
+            AttachmentService::new(AttachmentRepository::new(pool.clone()), attachment_store);
+        let mut analytics_export_service =
+            AnalyticsExportService::new(pool.clone()).with_clock(clock.clone());
+        if let Some(export) = &config.scheduler.analytics_export {
+            let store = AnalyticsStore::new(&export.dir);
+            info!(
+                "Analytics exports published under {}",
+                store.root().display()
+            );
+            analytics_export_service = analytics_export_service.with_store(store);
+        }
+        let annotation_service = AnnotationService::new(AnnotationRepository::new(pool.clone()));
+        let threshold_service = ThresholdService::new(
+            ThresholdEventRepository::new(pool.clone()),
+            &config.alerts.rainfall_thresholds_inches,
+        );
+        let current_conditions_service =
+            CurrentConditionsService::new(CurrentConditionsRepository::new(pool.clone()));
+        let zone_service = ZoneService::new(pool.clone());
+        let politeness = Politeness::new(&config.fetcher.politeness);
+        metrics.watch_breakers(politeness.breakers());
+        let forecast_service = ForecastService::new(
+            &config.enrichment.forecast,
+            gauge_repo.clone(),
+            reading_service.clone(),
+        )
+        .with_clock(clock.clone())
+        .with_politeness(politeness.clone());
+        let mut fopr_downloader = McfcdDownloader::new().with_politeness(politeness.clone());
+        if let Some(dir) = &config.fetcher.fopr_partial_dir {
+            fopr_downloader = fopr_downloader.with_partial_dir(dir);
+        }
+        let fopr_import_service = FoprImportService::new(pool.clone())
+            .with_downloader(fopr_downloader)
+            .with_validation_bounds(config.fetcher.validation_bounds.clone())
+            .with_ingest_limits(config.fetcher.ingest_limits);
 
         // Create fetchers
-        let reading_fetcher = RainGaugeFetcher::new(config.gauge_url.clone());
-        let gauge_list_fetcher = GaugeListFetcher::new(config.gauge_list_url.clone());
+        let payload_archive = config
+            .fetcher
+            .payload_archive_dir
+            .as_ref()
+            .map(|dir| PayloadArchive::new(dir, config.fetcher.payload_archive_days));
+        let mut reading_fetcher = RainGaugeFetcher::new(config.fetcher.gauge_url.clone())
+            .with_limits(config.fetcher.ingest_limits)
+            .with_politeness(politeness.clone());
+        let mut gauge_list_fetcher = GaugeListFetcher::new(config.fetcher.gauge_list_url.clone())
+            .with_politeness(politeness.clone());
+        if let Some(archive) = &payload_archive {
+            reading_fetcher = reading_fetcher.with_archive(archive.clone());
+            gauge_list_fetcher = gauge_list_fetcher.with_archive(archive.clone());
+        }
 
         // Spawn background tasks
-        info!("Spawning background schedulers and workers");
+        let read_only = config.database.snapshot_dir.is_some();
+        let role = config.server.role;
+        let run_schedulers = !read_only && role.runs_schedulers();
+        let run_workers = !read_only && role.runs_workers();
+        info!(
+            "Profile {}: auto-migrate {}, Swagger UI {}, SQL log {}, any-origin CORS {}",
+            config.server.profile,
+            on_off(config.database.auto_migrate),
+            on_off(config.server.swagger_ui_enabled),
+            on_off(config.database.log_statements),
+            on_off(config.server.cors_allow_any_origin)
+        );
+        if read_only {
+            info!("Snapshot mode: serving read-only data, schedulers and workers disabled");
+        } else {
+            info!(
+                "Process role {}: API {}, schedulers {}, workers {}",
+                role,
+                on_off(role.serves_api()),
+                on_off(run_schedulers),
+                on_off(run_workers)
+            );
+        }
+        // The FOPR job queue only exists in the PostgreSQL schema
+        let fopr_worker_concurrency = if !run_workers {
+            0
+        } else if pool.postgres().is_ok() {
+            config.scheduler.fopr_worker_concurrency
+        } else {
+            info!(
+                "FOPR import workers disabled on the {} backend",
+                pool.backend()
+            );
+            0
+        };
         info!(
             "FOPR worker concurrency: {} workers",
-            config.fopr_worker_concurrency
+            fopr_worker_concurrency
+        );
+
+        // With leader election, only the elected replica's schedulers do work
+        let (leadership, leader_election_handle) = match &config.scheduler.leader_election {
+            Some(election) if run_schedulers && pool.postgres().is_ok() => {
+                let leadership = Leadership::elected(&election.instance_id);
+                let handle = tokio::spawn(leader::run_election(
+                    config.database.url.clone(),
+                    election.clone(),
+                    leadership.clone(),
+                ));
+                (leadership, Some(handle))
+            }
+            Some(_) if run_schedulers => {
+                info!("Leader election disabled on the {} backend", pool.backend());
+                (Leadership::always(), None)
+            }
+            _ => (Leadership::always(), None),
+        };
+
+        if config.scheduler.job_windows.is_restricted() {
+            info!("Job windows: {}", config.scheduler.job_windows);
+        }
+        let gate = JobGate::new(
+            leadership.clone(),
+            config.scheduler.job_windows.clone(),
+            clock.clone(),
         );
 
         // Scheduler 1: Individual gauge readings (15 min interval)
-        let reading_scheduler_handle = {
+        let reading_scheduler_handle = run_schedulers.then(|| {
             let reading_repo_clone = reading_repo.clone();
+            let quarantine_repo = QuarantineRepository::new(pool.clone());
             let monthly_repo_clone = monthly_rainfall_repo.clone();
+            let hourly_repo_clone = hourly_rainfall_repo.clone();
             let reading_fetcher_clone = reading_fetcher.clone();
-            let reading_interval = config.fetch_interval_minutes;
+            let current_conditions_clone = current_conditions_service.clone();
+            let job_runs = job_run_service.clone();
+            let clock_clone = clock.clone();
+            let reading_interval = config.scheduler.fetch_interval_minutes;
+            let late_data_window_hours = config.fetcher.late_data_window_hours;
+            let gate = gate.clone();
 
             tokio::spawn(async move {
                 scheduler::start_fetch_scheduler(
                     reading_fetcher_clone,
                     reading_repo_clone,
+                    quarantine_repo,
                     monthly_repo_clone,
+                    hourly_repo_clone,
+                    current_conditions_clone,
+                    job_runs,
+                    clock_clone,
                     reading_interval,
+                    late_data_window_hours,
+                    gate,
                 )
                 .await;
             })
-        };
+        });
 
         // Scheduler 2: Gauge list/summaries (60 min interval)
-        let gauge_list_scheduler_handle = {
+        let gauge_list_scheduler_handle = run_schedulers.then(|| {
             let gauge_service_clone = gauge_service.clone();
+            let threshold_service_clone = threshold_service.clone();
+            let current_conditions_clone = current_conditions_service.clone();
             let gauge_list_fetcher_clone = gauge_list_fetcher.clone();
-            let gauge_list_interval = config.gauge_list_interval_minutes;
+            let job_runs = job_run_service.clone();
+            let clock_clone = clock.clone();
+            let gauge_list_interval = config.scheduler.gauge_list_interval_minutes;
+            let inactive_after_days = config.fetcher.gauge_inactive_after_days;
+            let gauge_list_drift = config.fetcher.gauge_list_drift;
+            let metrics = metrics.clone();
+            let gate = gate.clone();
 
             tokio::spawn(async move {
                 scheduler::start_gauge_list_scheduler(
                     gauge_list_fetcher_clone,
                     gauge_service_clone,
+                    threshold_service_clone,
+                    current_conditions_clone,
+                    job_runs,
+                    metrics,
+                    clock_clone,
                     gauge_list_interval,
+                    inactive_after_days,
+                    gauge_list_drift,
+                    gate,
                 )
                 .await;
             })
+        });
+
+        // Scheduler 3: Reconcile gauge_summaries with gauges (6 hour interval)
+        let reconciliation_scheduler_handle = run_schedulers.then(|| {
+            let gauge_service_clone = gauge_service.clone();
+            let zone_service_clone = zone_service.clone();
+            let reconciliation_interval = config.scheduler.reconciliation_interval_minutes;
+            let gate = gate.clone();
+
+            tokio::spawn(async move {
+                scheduler::start_reconciliation_scheduler(
+                    gauge_service_clone,
+                    zone_service_clone,
+                    reconciliation_interval,
+                    gate,
+                )
+                .await;
+            })
+        });
+
+        // Scheduler 4: Reverse geocode gauges without a city (optional, daily)
+        let geocode_scheduler_handle = config
+            .enrichment
+            .geocode
+            .as_ref()
+            .filter(|_| run_schedulers)
+            .and_then(|geocode| {
+                let geocoder = match ReverseGeocoder::from_provider(&geocode.provider) {
+                    Ok(geocoder) => geocoder,
+                    Err(e) => {
+                        error!(error = %e, "Reverse geocoding disabled");
+                        return None;
+                    }
+                };
+                let geocode_service = GeocodeService::new(
+                    GaugeRepository::new(pool.clone()),
+                    geocoder.with_politeness(&politeness),
+                    geocode.batch_size,
+                );
+                let geocode_interval = geocode.interval_minutes;
+                let gate = gate.clone();
+
+                Some(tokio::spawn(async move {
+                    scheduler::start_geocode_scheduler(geocode_service, geocode_interval, gate)
+                        .await;
+                }))
+            });
+
+        // Scheduler 5: Sample elevations of gauges without one (optional, daily)
+        let elevation_scheduler_handle = config
+            .enrichment
+            .elevation
+            .as_ref()
+            .filter(|_| run_schedulers)
+            .and_then(|elevation| {
+                let sampler = match ElevationSampler::from_provider(&elevation.provider) {
+                    Ok(sampler) => sampler,
+                    Err(e) => {
+                        error!(error = %e, "Elevation sampling disabled");
+                        return None;
+                    }
+                };
+                let elevation_service = ElevationService::new(
+                    GaugeRepository::new(pool.clone()),
+                    sampler.with_politeness(&politeness),
+                    elevation.batch_size,
+                );
+                let elevation_interval = elevation.interval_minutes;
+                let gate = gate.clone();
+
+                Some(tokio::spawn(async move {
+                    scheduler::start_elevation_scheduler(
+                        elevation_service,
+                        elevation_interval,
+                        gate,
+                    )
+                    .await;
+                }))
+            });
+
+        // Scheduler 6: Daily temperature and ET for gauges (optional, daily)
+        let weather_scheduler_handle = config
+            .enrichment
+            .weather
+            .as_ref()
+            .filter(|_| run_schedulers)
+            .and_then(|weather| {
+                // gauge_daily_weather only exists in the PostgreSQL schema
+                if pool.postgres().is_err() {
+                    info!("Weather job disabled on the {} backend", pool.backend());
+                    return None;
+                }
+                let source = match WeatherSource::from_provider(
+                    &weather.provider,
+                    &config.enrichment.forecast.api_url,
+                ) {
+                    Ok(source) => source.with_politeness(&politeness),
+                    Err(e) => {
+                        error!(error = %e, "Weather job disabled");
+                        return None;
+                    }
+                };
+                let weather_service = WeatherService::new(pool.clone());
+                let (history_days, weather_interval) =
+                    (weather.history_days, weather.interval_minutes);
+                let gate = gate.clone();
+
+                Some(tokio::spawn(async move {
+                    scheduler::start_weather_scheduler(
+                        weather_service,
+                        source,
+                        history_days,
+                        weather_interval,
+                        gate,
+                    )
+                    .await;
+                }))
+            });
+
+        // Scheduler 7: Detect gauge malfunctions (daily)
+        // gauge_anomalies only exists in the PostgreSQL schema
+        let anomaly_scheduler_handle = if !run_schedulers {
+            None
+        } else if pool.postgres().is_err() {
+            info!("Anomaly job disabled on the {} backend", pool.backend());
+            None
+        } else {
+            let anomaly = &config.alerts.anomaly;
+            let mut anomaly_service = AnomalyService::new(pool.clone());
+            if let Some(url) = &anomaly.webhook_url {
+                anomaly_service = anomaly_service.with_alerter(AnomalyAlerter::new(url));
+            }
+            let (rules, anomaly_interval) = (anomaly.rules, anomaly.interval_minutes);
+            let gate = gate.clone();
+
+            Some(tokio::spawn(async move {
+                scheduler::start_anomaly_scheduler(anomaly_service, rules, anomaly_interval, gate)
+                    .await;
+            }))
+        };
+
+        // Scheduler 8: Refresh summary materialized views (optional, every 15 min)
+        let summary_view_scheduler_handle =
+            summary_view_config.filter(|_| run_schedulers).map(|views| {
+                let service = summary_view_service.clone();
+                let gate = gate.clone();
+                tokio::spawn(async move {
+                    scheduler::start_summary_view_scheduler(service, views.refresh_minutes, gate)
+                        .await;
+                })
+            });
+
+        // Scheduler 9: Prune job run history (daily)
+        // job_runs only exists in the PostgreSQL schema
+        let job_run_retention_handle = (run_schedulers
+            && config.scheduler.job_run_retention_days > 0
+            && pool.postgres().is_ok())
+        .then(|| {
+            let job_runs = job_run_service.clone();
+            let retention_days = config.scheduler.job_run_retention_days;
+            let gate = gate.clone();
+            tokio::spawn(async move {
+                scheduler::start_job_run_retention_scheduler(job_runs, retention_days, gate).await;
+            })
+        });
+
+        // Scheduler 10: Prune archived upstream payloads (daily)
+        let payload_archive_retention_handle = payload_archive
+            .filter(|archive| run_schedulers && archive.retention_days() > 0)
+            .map(|archive| {
+                let gate = gate.clone();
+                tokio::spawn(async move {
+                    scheduler::start_payload_archive_retention_scheduler(archive, gate).await;
+                })
+            });
+
+        // Scheduler 11: Deliver daily and weekly digests (hourly)
+        // digest_subscriptions only exists in the PostgreSQL schema
+        let digest_sender = match DigestSender::from_config(&config.alerts.digest) {
+            Ok(sender) => sender,
+            Err(e) => {
+                error!(error = %e, "Email digests disabled");
+                DigestSender::default()
+            }
         };
+        let digest_service = DigestService::new(pool.clone()).with_sender(digest_sender);
+        let digest_scheduler_handle = if !run_schedulers {
+            None
+        } else if pool.postgres().is_err() {
+            info!("Digest job disabled on the {} backend", pool.backend());
+            None
+        } else {
+            let service = digest_service.clone();
+            let digest_interval = config.alerts.digest.interval_minutes;
+            let gate = gate.clone();
+            Some(tokio::spawn(async move {
+                scheduler::start_digest_scheduler(service, digest_interval, gate).await;
+            }))
+        };
+
+        // Scheduler 12: Publish the Parquet analytics export (optional, daily)
+        let analytics_export_scheduler_handle = config
+            .scheduler
+            .analytics_export
+            .as_ref()
+            .filter(|_| run_schedulers)
+            .map(|export| {
+                let service = analytics_export_service.clone();
+                let export_interval = export.interval_minutes;
+                let gate = gate.clone();
+                tokio::spawn(async move {
+                    scheduler::start_analytics_export_scheduler(service, export_interval, gate)
+                        .await;
+                })
+            });
 
         // Workers: FOPR import workers (spawn multiple for concurrent processing)
+        let job_notifier = if fopr_worker_concurrency == 0 {
+            None
+        } else {
+            match JobNotifier::from_config(&config.alerts.job_notify) {
+                Ok(notifier) => notifier,
+                Err(e) => {
+                    error!(error = %e, "FOPR job notifications disabled");
+                    None
+                }
+            }
+        };
         let mut fopr_worker_handles = Vec::new();
-        for worker_id in 0..config.fopr_worker_concurrency {
-            let worker = FoprImportWorker::new(
+        for worker_id in 0..fopr_worker_concurrency {
+            let mut worker = FoprImportWorker::new(
                 job_repo.clone(),
                 fopr_import_service.clone(),
                 30, // Poll every 30 seconds
                 worker_id,
-            );
+            )
+            .with_job_windows(config.scheduler.job_windows.clone())
+            .with_job_runs(job_run_service.clone());
+            if let Some(notifier) = &job_notifier {
+                worker = worker.with_notifier(notifier.clone());
+            }
 
             let handle = tokio::spawn(async move {
                 worker.run().await;
@@ -110,12 +670,61 @@ impl Application {
             fopr_worker_handles.push(handle);
         }
 
+        // Share links need SHARE_SIGNING_KEY; POST /api/v1/share is refused without it
+        let mut share_service =
+            ShareService::new(pool.clone(), reading_service.clone()).with_clock(clock.clone());
+        if let Some(key) = &config.auth.share_signing_key {
+            share_service = share_service.with_signer(ShareSigner::new(key.expose()));
+        }
+
         // Create API router
+        let warm_up_service = current_conditions_service.clone();
         let app_state = AppState {
             reading_service,
             gauge_service,
+            summary_service,
+            idempotency_service,
+            attachment_service,
+            annotation_service,
+            threshold_service,
+            current_conditions_service,
+            zone_service,
+            forecast_service,
+            radar_service: RadarService::new(pool.clone()),
+            weather_service: WeatherService::new(pool.clone())
+                .with_query_limits(config.server.reading_query_limits),
+            user_service: UserService::new(pool.clone()),
+            anomaly_service: AnomalyService::new(pool.clone()),
+            digest_service,
+            share_service,
+            usage_service: UsageService::new(pool.clone()).with_clock(clock.clone()),
+            summary_view_service,
+            analytics_export_service,
+            slow_query_service: SlowQueryService::new(SlowQueryRepository::new(pool.clone())),
+            job_run_service,
+            historical_import_service: HistoricalImportService::new(pool.clone())
+                .with_downloader(McfcdDownloader::new().with_politeness(politeness.clone())),
+            fopr_availability_service: FoprAvailabilityService::new(pool.clone())
+                .with_downloader(McfcdDownloader::new().with_politeness(politeness.clone())),
+            admin_api_key: config
+                .auth
+                .admin_api_key
+                .as_ref()
+                .filter(|_| !read_only)
+                .map(|k| k.expose().to_string()),
+            swagger_ui_enabled: config.server.swagger_ui_enabled,
+            cors_allow_any_origin: config.server.cors_allow_any_origin,
+            readiness: readiness.clone(),
+            leadership,
+            metrics: metrics.clone(),
         };
-        let app = create_router(app_state).layer(TraceLayer::new_for_http());
+        // Worker and scheduler processes still answer health probes and metric scrapes
+        let app = if role.serves_api() {
+            create_router(app_state)
+        } else {
+            create_ops_router(app_state)
+        }
+        .layer(TraceLayer::new_for_http());
 
         // Spawn server
         let addr = config.server_addr();
@@ -126,23 +735,191 @@ impl Application {
             axum::serve(listener, app).await
         });
 
+        let startup_checks_handle = tokio::spawn(run_startup_checks(
+            pool.clone(),
+            role.serves_api().then_some(warm_up_service),
+            readiness.clone(),
+        ));
+
+        let db_health_handle = tokio::spawn(scheduler::start_db_health_monitor(
+            pool.clone(),
+            metrics,
+            config.database.health_check_secs,
+        ));
+
         info!("Application initialized successfully");
 
         Ok(Self {
             server_handle,
             reading_scheduler_handle,
             gauge_list_scheduler_handle,
+            reconciliation_scheduler_handle,
+            geocode_scheduler_handle,
+            elevation_scheduler_handle,
+            weather_scheduler_handle,
+            anomaly_scheduler_handle,
+            summary_view_scheduler_handle,
+            job_run_retention_handle,
+            payload_archive_retention_handle,
+            digest_scheduler_handle,
+            analytics_export_scheduler_handle,
+            leader_election_handle,
             fopr_worker_handles,
+            readiness,
+            startup_checks_handle,
+            db_health_handle,
         })
     }
 
     /// Run until the server stops (which runs indefinitely unless error)
     ///
     /// Background schedulers and workers also run indefinitely.
-    pub async fn run_until_stopped(self) -> Result<(), Box<dyn std::error::Error>> {
+    pub async fn run_until_stopped(self) -> Result<(), Error> {
         // Wait for server (the main task)
         // Schedulers and worker run indefinitely in background
-        self.server_handle.await??;
+        self.server_handle
+            .await
+            .map_err(|e| Error::Internal(format!("HTTP server task failed: {e}")))??;
         Ok(())
     }
 }
+
+fn on_off(enabled: bool) -> &'static str {
+    if enabled {
+        "on"
+    } else {
+        "off"
+    }
+}
+
+/// Connect to the database and apply (or verify) its migrations, retrying while it is
+/// unreachable
+///
+/// Under orchestration the service often starts before its database accepts
+/// connections. Transient failures (see [`DbError::is_transient`]) are retried with
+/// exponential backoff up to `config.database.connect.max_attempts` times; anything else,
+/// such as bad credentials or pending migrations with AUTO_MIGRATE=false, fails at once.
+pub async fn connect_database(config: &Config, metrics: &Metrics) -> Result<DbPool, DbError> {
+    let attempt = || async move {
+        info!("Connecting to database...");
+        let result = connect_and_migrate(&config.database).await;
+        metrics.record_db_connect(result.is_ok());
+        result
+    };
+
+    attempt
+        .retry(config.database.connect.backoff())
+        .when(DbError::is_transient)
+        .notify(|e, delay| {
+            warn!(
+                "Database unavailable ({}); retrying in {:.1}s",
+                e,
+                delay.as_secs_f64()
+            )
+        })
+        .await
+}
+
+async fn connect_and_migrate(config: &DatabaseConfig) -> Result<DbPool, DbError> {
+    let statement_level = if config.log_statements {
+        LevelFilter::Info
+    } else {
+        LevelFilter::Debug
+    };
+    let pool = DbPool::connect_with_statement_log(&config.url, DB_MAX_CONNECTIONS, statement_level)
+        .await?;
+    info!("Database connection established ({})", pool.backend());
+
+    if config.auto_migrate {
+        info!("Running database migrations...");
+        pool.migrate().await?;
+        info!("Database migrations completed");
+    } else {
+        // Change-controlled deployments apply migrations out of band
+        pool.ensure_migrated().await?;
+        info!("Database schema is up to date (AUTO_MIGRATE=false)");
+    }
+
+    Ok(pool)
+}
+
+/// Pass the database readiness checks, retrying each failure until it succeeds
+///
+/// Migrations were applied (or verified) before `build`; this confirms the schema still
+/// matches, then rebuilds current conditions so the dashboard is populated before traffic
+/// arrives. Processes that serve no API pass `None` and skip the warm-up. The config check
+/// is not retried: fixing it needs a restart.
+async fn run_startup_checks(
+    pool: DbPool,
+    current_conditions: Option<CurrentConditionsService>,
+    readiness: Readiness,
+) {
+    if current_conditions.is_none() {
+        readiness.pass(ReadinessCheck::GaugeCache);
+    }
+
+    loop {
+        if !readiness.is_passed(ReadinessCheck::Migrations) {
+            match pool.ensure_migrated().await {
+                Ok(()) => readiness.pass(ReadinessCheck::Migrations),
+                Err(e) => {
+                    warn!("Readiness: migration check failed: {}", e);
+                    readiness.fail(ReadinessCheck::Migrations, e.to_string());
+                }
+            }
+        }
+
+        if let Some(current_conditions) = current_conditions
+            .as_ref()
+            .filter(|_| readiness.is_passed(ReadinessCheck::Migrations))
+            .filter(|_| !readiness.is_passed(ReadinessCheck::GaugeCache))
+        {
+            match current_conditions.refresh(Utc::now()).await {
+                Ok(_) => readiness.pass(ReadinessCheck::GaugeCache),
+                Err(e) => {
+                    warn!("Readiness: warming current conditions failed: {}", e);
+                    readiness.fail(ReadinessCheck::GaugeCache, e.to_string());
+                }
+            }
+        }
+
+        if readiness.is_passed(ReadinessCheck::GaugeCache) {
+            break;
+        }
+        tokio::time::sleep(STARTUP_RETRY_INTERVAL).await;
+    }
+
+    if readiness.is_ready() {
+        info!("Startup checks passed; service is ready");
+    } else {
+        error!("Startup checks finished but the configuration is invalid; service stays unready");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_service_role() {
+        assert_eq!("Worker".parse(), Ok(ServiceRole::Worker));
+        assert!("web".parse::<ServiceRole>().is_err());
+
+        let roles = [
+            ServiceRole::All,
+            ServiceRole::Api,
+            ServiceRole::Worker,
+            ServiceRole::Scheduler,
+        ];
+        let parts = roles.map(|r| (r.serves_api(), r.runs_schedulers(), r.runs_workers()));
+        assert_eq!(
+            parts,
+            [
+                (true, true, true),
+                (true, false, false),
+                (false, false, true),
+                (false, true, false),
+            ]
+        );
+    }
+}
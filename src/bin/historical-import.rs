@@ -0,0 +1,40 @@
+use std::process::ExitCode;
+
+use clap::Parser;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+
+use rain_tracker_service::cli::{self, Cli};
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    // Load environment variables before parsing so DATABASE_URL can come from .env
+    dotenvy::dotenv().ok();
+
+    let cli = Cli::parse();
+    let json = cli.json || cli.non_interactive;
+
+    // Logs go to stderr so stdout stays clean for reports and exports
+    tracing_subscriber::registry()
+        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn")))
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+        .init();
+
+    match cli::run(cli).await {
+        Ok(code) => code,
+        Err(e) => {
+            // Automation parses stdout, so failures get a JSON summary there too
+            if json {
+                println!(
+                    "{}",
+                    serde_json::json!({
+                        "status": "error",
+                        "class": e.class().as_str(),
+                        "error": e.to_string(),
+                    })
+                );
+            }
+            eprintln!("Error: {e}");
+            e.exit_code()
+        }
+    }
+}
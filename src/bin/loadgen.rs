@@ -0,0 +1,31 @@
+use std::process::ExitCode;
+
+use clap::Parser;
+
+use rain_tracker_service::loadgen::{self, LoadgenArgs};
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = LoadgenArgs::parse();
+
+    match loadgen::run(&args).await {
+        Ok(report) => {
+            if args.json {
+                match serde_json::to_string_pretty(&report) {
+                    Ok(json) => println!("{json}"),
+                    Err(e) => {
+                        eprintln!("Error: {e}");
+                        return ExitCode::FAILURE;
+                    }
+                }
+            } else {
+                println!("{report}");
+            }
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,314 @@
+// Circuit breakers for upstream hosts
+//
+// Politeness records the outcome of every outbound request against the host it went to.
+// A network error or a 5xx, 429, or 408 response is a failure; any other response resets
+// the host's count. After FETCH_BREAKER_THRESHOLD consecutive failures (default 5; 0 turns breaking
+// off) the host's breaker opens and requests to it fail at once, without touching the
+// network, for FETCH_BREAKER_COOLDOWN_SECS (default 60). Once the cooldown has passed one
+// trial request goes through: success closes the breaker, failure reopens it with the
+// cooldown doubled, up to FETCH_BREAKER_MAX_COOLDOWN_SECS (default 3600).
+//
+// Breaker state is shared by every client, scheduler, and worker in the process and is
+// reported by /api/v1/health and as rain_tracker_upstream_* metrics.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::time::Instant;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+pub const DEFAULT_BREAKER_THRESHOLD: u32 = 5;
+pub const DEFAULT_BREAKER_COOLDOWN_SECS: u64 = 60;
+pub const DEFAULT_BREAKER_MAX_COOLDOWN_SECS: u64 = 3600;
+
+/// When a host's breaker opens and for how long; the default never opens
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BreakerConfig {
+    /// Consecutive failures that open the breaker; 0 disables breaking
+    pub threshold: u32,
+    /// Cooldown after the first trip
+    pub cooldown: Duration,
+    /// Longest cooldown after repeated trips
+    pub max_cooldown: Duration,
+}
+
+impl BreakerConfig {
+    /// Cooldown after `trips` consecutive trips: doubled each time, capped
+    fn cooldown_after(&self, trips: u32) -> Duration {
+        let factor = 1u32
+            .checked_shl(trips.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        self.cooldown
+            .checked_mul(factor)
+            .unwrap_or(self.max_cooldown)
+            .min(self.max_cooldown)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakerState {
+    /// Requests go through
+    Closed,
+    /// Requests fail without being sent until `retry_at`
+    Open,
+    /// The cooldown has passed; the next request, or the one in flight, is a trial
+    HalfOpen,
+}
+
+impl BreakerState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BreakerState::Closed => "closed",
+            BreakerState::Open => "open",
+            BreakerState::HalfOpen => "half_open",
+        }
+    }
+}
+
+/// One upstream host's breaker, as reported by /api/v1/health
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct UpstreamStatus {
+    #[schema(example = "alert.fcd.maricopa.gov")]
+    pub host: String,
+    pub state: BreakerState,
+    /// Failures since the last successful request
+    #[schema(example = 0)]
+    pub consecutive_failures: u32,
+    /// Times the breaker has opened since process start
+    #[schema(example = 1)]
+    pub trips: u64,
+    /// When the next trial request may be sent; present while open
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default)]
+struct HostBreaker {
+    consecutive_failures: u32,
+    /// Trips since the last success, for the cooldown
+    consecutive_trips: u32,
+    total_trips: u64,
+    /// Start and length of the current cooldown
+    open: Option<(Instant, Duration)>,
+    /// A trial request was let through and has not finished
+    probing: bool,
+}
+
+impl HostBreaker {
+    fn state(&self, now: Instant) -> BreakerState {
+        match self.open {
+            None => BreakerState::Closed,
+            Some(_) if self.probing => BreakerState::HalfOpen,
+            Some((at, cooldown)) if now < at + cooldown => BreakerState::Open,
+            Some(_) => BreakerState::HalfOpen,
+        }
+    }
+}
+
+/// Shared per-host breakers, cloned into Politeness and Metrics
+#[derive(Debug, Clone, Default)]
+pub struct CircuitBreakers {
+    config: BreakerConfig,
+    hosts: Arc<Mutex<HashMap<String, HostBreaker>>>,
+}
+
+impl CircuitBreakers {
+    pub fn new(config: BreakerConfig) -> Self {
+        Self {
+            config,
+            hosts: Arc::default(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, HostBreaker>> {
+        self.hosts.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Whether a request to `host` may be sent now
+    ///
+    /// After the cooldown the first caller is let through as the trial and the cooldown
+    /// restarts, so a trial that never reports back only delays the next one.
+    pub fn allow(&self, host: &str) -> bool {
+        if self.config.threshold == 0 {
+            return true;
+        }
+        let mut hosts = self.lock();
+        let Some(breaker) = hosts.get_mut(&host.to_ascii_lowercase()) else {
+            return true;
+        };
+        let now = Instant::now();
+        match breaker.open {
+            None => true,
+            Some((at, cooldown)) if now < at + cooldown => false,
+            Some((_, cooldown)) => {
+                breaker.open = Some((now, cooldown));
+                breaker.probing = true;
+                info!(host, "Sending trial request after breaker cooldown");
+                true
+            }
+        }
+    }
+
+    /// Record a request to `host` that got a response other than 5xx, 429, or 408
+    pub fn record_success(&self, host: &str) {
+        if self.config.threshold == 0 {
+            return;
+        }
+        let mut hosts = self.lock();
+        let breaker = hosts.entry(host.to_ascii_lowercase()).or_default();
+        if breaker.open.is_some() {
+            info!(host, "Upstream recovered; closing breaker");
+        }
+        breaker.consecutive_failures = 0;
+        breaker.consecutive_trips = 0;
+        breaker.open = None;
+        breaker.probing = false;
+    }
+
+    /// Record a request to `host` that failed to connect or got a 5xx, 429, or 408
+    pub fn record_failure(&self, host: &str) {
+        if self.config.threshold == 0 {
+            return;
+        }
+        let mut hosts = self.lock();
+        let breaker = hosts.entry(host.to_ascii_lowercase()).or_default();
+        breaker.consecutive_failures = breaker.consecutive_failures.saturating_add(1);
+
+        // Requests already in flight when the breaker opened do not trip it again
+        let trips = breaker.probing
+            || breaker.open.is_none() && breaker.consecutive_failures >= self.config.threshold;
+        if trips {
+            breaker.consecutive_trips = breaker.consecutive_trips.saturating_add(1);
+            breaker.total_trips += 1;
+            let cooldown = self.config.cooldown_after(breaker.consecutive_trips);
+            breaker.open = Some((Instant::now(), cooldown));
+            breaker.probing = false;
+            warn!(
+                host,
+                failures = breaker.consecutive_failures,
+                cooldown_secs = cooldown.as_secs(),
+                "Upstream failing; opening breaker"
+            );
+        }
+    }
+
+    /// Every host requested so far, by name
+    pub fn statuses(&self) -> Vec<UpstreamStatus> {
+        let hosts = self.lock();
+        let now = Instant::now();
+        let wall_now = Utc::now();
+        let mut statuses: Vec<UpstreamStatus> = hosts
+            .iter()
+            .map(|(host, breaker)| {
+                let state = breaker.state(now);
+                let retry_at = match (state, breaker.open) {
+                    (BreakerState::Open, Some((at, cooldown))) => {
+                        let remaining = (at + cooldown).saturating_duration_since(now);
+                        chrono::Duration::from_std(remaining)
+                            .ok()
+                            .map(|remaining| wall_now + remaining)
+                    }
+                    _ => None,
+                };
+                UpstreamStatus {
+                    host: host.clone(),
+                    state,
+                    consecutive_failures: breaker.consecutive_failures,
+                    trips: breaker.total_trips,
+                    retry_at,
+                }
+            })
+            .collect();
+        statuses.sort_by(|a, b| a.host.cmp(&b.host));
+        statuses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOST: &str = "alert.fcd.maricopa.gov";
+
+    fn breakers(cooldown: Duration) -> CircuitBreakers {
+        CircuitBreakers::new(BreakerConfig {
+            threshold: 3,
+            cooldown,
+            max_cooldown: cooldown * 3,
+        })
+    }
+
+    #[test]
+    fn test_cooldown_doubles_up_to_max() {
+        let config = BreakerConfig {
+            threshold: 1,
+            cooldown: Duration::from_secs(60),
+            max_cooldown: Duration::from_secs(300),
+        };
+        let cooldowns: Vec<u64> = (1..=5)
+            .map(|trips| config.cooldown_after(trips).as_secs())
+            .collect();
+        assert_eq!(cooldowns, [60, 120, 240, 300, 300]);
+        assert_eq!(config.cooldown_after(200).as_secs(), 300);
+    }
+
+    #[test]
+    fn test_opens_after_consecutive_failures() {
+        let breakers = breakers(Duration::from_secs(60));
+        breakers.record_failure(HOST);
+        breakers.record_failure(HOST);
+        breakers.record_success(HOST);
+        breakers.record_failure(HOST);
+        breakers.record_failure(HOST);
+        assert!(breakers.allow(HOST), "a success resets the count");
+
+        breakers.record_failure(HOST);
+        assert!(!breakers.allow(HOST));
+        assert!(
+            breakers.allow("api.weather.gov"),
+            "other hosts are unaffected"
+        );
+
+        let status = &breakers.statuses()[0];
+        assert_eq!(status.state, BreakerState::Open);
+        assert_eq!(status.consecutive_failures, 3);
+        assert_eq!(status.trips, 1);
+        assert!(status.retry_at.is_some_and(|at| at > Utc::now()));
+    }
+
+    #[test]
+    fn test_trial_after_cooldown() {
+        let breakers = breakers(Duration::ZERO);
+        for _ in 0..3 {
+            breakers.record_failure(HOST);
+        }
+
+        // Cooldown passed: one trial, which fails and reopens the breaker
+        assert!(breakers.allow(HOST));
+        assert_eq!(breakers.statuses()[0].state, BreakerState::HalfOpen);
+        breakers.record_failure(HOST);
+        assert_eq!(breakers.statuses()[0].trips, 2);
+
+        assert!(breakers.allow(HOST));
+        breakers.record_success(HOST);
+        let status = &breakers.statuses()[0];
+        assert_eq!(status.state, BreakerState::Closed);
+        assert_eq!(status.consecutive_failures, 0);
+        assert_eq!(status.retry_at, None);
+    }
+
+    #[test]
+    fn test_default_never_opens() {
+        let breakers = CircuitBreakers::default();
+        for _ in 0..100 {
+            breakers.record_failure(HOST);
+        }
+        assert!(breakers.allow(HOST));
+        assert!(breakers.statuses().is_empty());
+    }
+}
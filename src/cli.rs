@@ -0,0 +1,1310 @@
+// Command-line interface for the historical-import tool
+//
+// Each operation is a clap subcommand with its own required arguments:
+// - import excel / import bulk / import fopr: Load historical data into the database
+// - download water-year: Fetch MCFCD source files without importing
+// - probe fopr: Record which gauges have FOPR files (HEAD requests, rate limited)
+// - recalc: Rebuild monthly summaries for a station, date range, or the whole database
+// - verify: Compare monthly summaries against raw readings
+// - export: Dump a gauge's readings as CSV or JSON
+// - export gis: Write gauges (and forecast zones) as a Shapefile or GeoPackage for GIS tools
+// - gauges export / import: Bulk-edit gauge names, locations, and coordinates as CSV
+// - zones import / assign: Load forecast zone polygons (GeoJSON) and assign gauges to them
+// - radar import: Sample MRMS radar rainfall grids at gauges for a storm window
+// - backup / restore: Portable tar.zst archive of gauges, readings, summaries, and jobs
+// - migrate status / up / down: Inspect and apply schema migrations (for AUTO_MIGRATE=false)
+// - seed: Fill a development database with synthetic gauges and rainfall
+// - bench: Time parsing, bulk insert, and water year queries against a seeded database
+// - bootstrap: Backfill a new deployment (gauge list, FOPR, water years, summaries), resumable
+// - replay: Re-parse archived gauge list and gauge page payloads with the current parsers
+//
+// Global `--json` switches every command to a machine-readable report on stdout.
+// Global `--non-interactive` never reads stdin (for cron and CI): confirmations are
+// assumed yes, bulk import failures follow `--on-error`, and output is JSON.
+// Excel and FOPR imports publish progress events to tracing and, with `--progress-webhook`, to a URL.
+
+pub mod backup;
+pub mod bench;
+pub mod bootstrap;
+pub mod download;
+pub mod export;
+pub mod gauges;
+pub mod import;
+pub mod migrate;
+pub mod output;
+pub mod probe;
+pub mod radar;
+pub mod recalc;
+pub mod replay;
+pub mod seed;
+pub mod verify;
+pub mod zones;
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::{ArgGroup, Args, Parser, Subcommand, ValueEnum};
+
+use crate::config;
+use crate::db::{
+    BackupRepository, ConflictPolicy, DbPool, HourlyRainfallRepository, MonthlyRainfallRepository,
+    SummaryViewRepository,
+};
+use crate::error::Error;
+use crate::gis::GisFormat;
+use crate::importers::downloader::McfcdDownloader;
+use crate::importers::progress::ProgressReporter;
+use crate::payload_archive::PayloadSource;
+use crate::politeness::Politeness;
+use crate::radar::DEFAULT_RADAR_PRODUCT;
+use crate::services::backup_service::BackupService;
+use crate::services::bench_service::{
+    BenchService, DEFAULT_BENCH_STATION, DEFAULT_BULK_INSERT_READINGS,
+};
+use crate::services::bootstrap_service::{BootstrapService, DEFAULT_FIRST_WATER_YEAR};
+use crate::services::fopr_availability_service::{FoprAvailabilityService, DEFAULT_PROBE_RATE};
+use crate::services::fopr_import_service::FoprImportService;
+use crate::services::gauge_edit_service::GaugeEditService;
+use crate::services::gis_export_service::GisExportService;
+use crate::services::historical_import_service::HistoricalImportService;
+use crate::services::radar_service::RadarService;
+use crate::services::seed_service::{SeedService, MAX_SEED_GAUGES};
+use crate::services::summary_service::{SummaryService, DEFAULT_RECALC_CONCURRENCY};
+use crate::services::ZoneService;
+use crate::station_id::StationId;
+use crate::zones::{DEFAULT_ZONE_ID_PROPERTY, DEFAULT_ZONE_NAME_PROPERTY};
+
+pub type CliResult<T> = crate::error::Result<T>;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "historical-import",
+    version,
+    about = "Import and maintain historical MCFCD rainfall data"
+)]
+pub struct Cli {
+    /// Database connection string (postgres://..., or sqlite:... in `sqlite` builds)
+    #[arg(long, env = "DATABASE_URL", global = true, hide_env_values = true)]
+    pub database_url: Option<String>,
+
+    /// Print a machine-readable JSON report to stdout instead of human-readable output
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// Never prompt: assume yes for confirmations, follow --on-error for failures, print JSON
+    #[arg(long, global = true)]
+    pub non_interactive: bool,
+
+    /// POST import progress events as JSON to this URL (best effort)
+    #[arg(long, env = "IMPORT_PROGRESS_WEBHOOK", global = true)]
+    pub progress_webhook: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Import historical data into the database
+    #[command(subcommand)]
+    Import(ImportCommand),
+
+    /// Download MCFCD source files without importing them
+    #[command(subcommand)]
+    Download(DownloadCommand),
+
+    /// Check which MCFCD files exist without downloading them
+    #[command(subcommand)]
+    Probe(ProbeCommand),
+
+    /// Recalculate monthly summaries from raw readings
+    Recalc(RecalcArgs),
+
+    /// Compare monthly summaries against raw readings
+    Verify(VerifyArgs),
+
+    /// Export a gauge's readings for a water year, or the gauge network for GIS (`export gis`)
+    Export(ExportCommand),
+
+    /// Export gauge metadata to CSV, or apply an edited CSV after previewing the changes
+    #[command(subcommand)]
+    Gauges(GaugesCommand),
+
+    /// Import MSP forecast zone polygons and assign gauges to the zone containing them
+    #[command(subcommand)]
+    Zones(ZonesCommand),
+
+    /// Sample MRMS radar rainfall estimates at gauges for comparison with their readings
+    #[command(subcommand)]
+    Radar(RadarCommand),
+
+    /// Generate synthetic gauges and rainfall for development and load testing
+    Seed(SeedArgs),
+
+    /// Time parsing, bulk insert, and water year queries against a seeded database
+    Bench(BenchArgs),
+
+    /// Backfill a new deployment's full history; resumes from its checkpoint when re-run
+    Bootstrap(BootstrapArgs),
+
+    /// Write gauges, readings, summaries, and FOPR jobs to a portable archive
+    Backup(BackupArgs),
+
+    /// Load a backup archive into the database (applies pending migrations first)
+    Restore(RestoreArgs),
+
+    /// Show, apply, or revert schema migrations
+    #[command(subcommand)]
+    Migrate(MigrateCommand),
+
+    /// Re-parse archived upstream payloads (PAYLOAD_ARCHIVE_DIR) with the current parsers
+    Replay(ReplayArgs),
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ImportCommand {
+    /// Import a water year Excel file (pcp_WY_YYYY.xlsx)
+    Excel(ExcelImportArgs),
+
+    /// Download and import a range of water years
+    Bulk(BulkImportArgs),
+
+    /// Import FOPR (Full Operational Period of Record) files for specific gauges
+    Fopr(FoprImportArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ExcelImportArgs {
+    /// Water year to import (Oct 1 of year-1 through Sep 30 of year)
+    #[arg(short, long)]
+    pub water_year: i32,
+
+    /// Local Excel file; downloaded from MCFCD when omitted
+    #[arg(short, long)]
+    pub file: Option<PathBuf>,
+
+    /// What to do with readings already stored for the same gauge and day
+    #[arg(long, value_enum, default_value_t = OnConflict::Skip)]
+    pub on_conflict: OnConflict,
+
+    /// Skip the confirmation prompt
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct BulkImportArgs {
+    /// First water year to import
+    #[arg(long)]
+    pub start_year: i32,
+
+    /// Last water year to import (inclusive)
+    #[arg(long)]
+    pub end_year: i32,
+
+    /// What to do when a water year fails; prompts when omitted (aborts with --non-interactive)
+    #[arg(long, value_enum)]
+    pub on_error: Option<OnError>,
+
+    /// What to do with readings already stored for the same gauge and day
+    #[arg(long, value_enum, default_value_t = OnConflict::Skip)]
+    pub on_conflict: OnConflict,
+
+    /// Skip the confirmation prompt
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+/// Failure policy for multi-step imports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OnError {
+    /// Record the failure and move on to the next item
+    Continue,
+    /// Stop at the first failure
+    Abort,
+}
+
+/// What an import does with readings that are already stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OnConflict {
+    /// Keep the stored reading
+    Skip,
+    /// Overwrite it with the file's value, recording the old one in reading_revisions
+    Update,
+}
+
+impl From<OnConflict> for ConflictPolicy {
+    fn from(on_conflict: OnConflict) -> Self {
+        match on_conflict {
+            OnConflict::Skip => ConflictPolicy::Skip,
+            OnConflict::Update => ConflictPolicy::Update,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct FoprImportArgs {
+    /// Station IDs to import
+    #[arg(required = true, num_args = 1..)]
+    pub station_ids: Vec<StationId>,
+
+    /// What to do with readings already stored for the same gauge and day
+    #[arg(long, value_enum, default_value_t = OnConflict::Skip)]
+    pub on_conflict: OnConflict,
+
+    /// Keep interrupted downloads here and resume them on the next run
+    #[arg(long, env = "FOPR_PARTIAL_DIR")]
+    pub partial_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DownloadCommand {
+    /// Download a water year Excel file
+    WaterYear(DownloadWaterYearArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct DownloadWaterYearArgs {
+    /// Water year to download
+    #[arg(short, long)]
+    pub water_year: i32,
+
+    /// Directory to write pcp_WY_YYYY.xlsx into
+    #[arg(short, long, default_value = ".")]
+    pub output_dir: PathBuf,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ProbeCommand {
+    /// Record which gauges have FOPR files, and when each file last changed
+    Fopr(ProbeFoprArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ProbeFoprArgs {
+    /// Station IDs to probe; every gauge in the database when omitted
+    pub station_ids: Vec<StationId>,
+
+    /// Maximum HEAD requests per second
+    #[arg(long, default_value_t = DEFAULT_PROBE_RATE, value_parser = parse_rate)]
+    pub rate: f64,
+}
+
+fn parse_rate(raw: &str) -> Result<f64, String> {
+    match raw.parse::<f64>() {
+        Ok(rate) if rate > 0.0 && rate.is_finite() => Ok(rate),
+        _ => Err("must be a positive number".to_string()),
+    }
+}
+
+#[derive(Debug, Args)]
+#[command(group(
+    ArgGroup::new("scope")
+        .required(true)
+        .multiple(true)
+        .args(["station", "water_year", "from", "to", "all"])
+))]
+pub struct RecalcArgs {
+    /// Restrict to one station
+    #[arg(short, long)]
+    pub station: Option<StationId>,
+
+    /// Restrict to one water year
+    #[arg(short, long, conflicts_with_all = ["from", "to"])]
+    pub water_year: Option<i32>,
+
+    /// First day to include (YYYY-MM-DD)
+    #[arg(long)]
+    pub from: Option<NaiveDate>,
+
+    /// Last day to include (YYYY-MM-DD, inclusive)
+    #[arg(long)]
+    pub to: Option<NaiveDate>,
+
+    /// Recalculate every station and month in the database
+    #[arg(long, conflicts_with_all = ["station", "water_year", "from", "to"])]
+    pub all: bool,
+
+    /// Stations recalculated concurrently
+    #[arg(long, default_value_t = DEFAULT_RECALC_CONCURRENCY)]
+    pub concurrency: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct VerifyArgs {
+    /// Water year to verify
+    #[arg(short, long)]
+    pub water_year: i32,
+
+    /// Restrict verification to one station
+    #[arg(short, long)]
+    pub station: Option<StationId>,
+}
+
+/// `export -s <station> -w <year>` for readings, or an export mode such as `export gis`
+#[derive(Debug, Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct ExportCommand {
+    #[command(subcommand)]
+    pub mode: Option<ExportMode>,
+
+    #[command(flatten)]
+    pub readings: Option<ExportArgs>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ExportMode {
+    /// Write every gauge with coordinates as a Shapefile or GeoPackage point layer
+    Gis(GisExportArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct GisExportArgs {
+    /// Output format; GeoPackage needs a build with `--features sqlite`
+    #[arg(long, value_enum, default_value_t = GisFormat::Shapefile)]
+    pub format: GisFormat,
+
+    /// Output file; the format's extension (.shp or .gpkg) is added when missing
+    #[arg(short, long)]
+    pub output: PathBuf,
+
+    /// Add each gauge's forecast zone and a layer of the zone polygons
+    #[arg(long)]
+    pub zones: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    /// Station ID to export
+    #[arg(short, long)]
+    pub station: StationId,
+
+    /// Water year to export
+    #[arg(short, long)]
+    pub water_year: i32,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = ExportFormat::Csv)]
+    pub format: ExportFormat,
+
+    /// Output file; writes to stdout when omitted
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum GaugesCommand {
+    /// Write every gauge's name, city, county, location, and coordinates as CSV
+    Export(GaugesExportArgs),
+
+    /// Validate an edited CSV, show the changes, and apply them
+    Import(GaugesImportArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct GaugesExportArgs {
+    /// Output file; writes to stdout when omitted
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct GaugesImportArgs {
+    /// CSV file in the `gauges export` format; gauges missing from it are left alone
+    #[arg(short, long)]
+    pub file: PathBuf,
+
+    /// Show the changes without applying them
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Skip the confirmation prompt
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ZonesCommand {
+    /// Replace the stored zones with a GeoJSON FeatureCollection and reassign gauges
+    Import(ZonesImportArgs),
+
+    /// Reassign every gauge to the zone containing its coordinates
+    Assign,
+}
+
+#[derive(Debug, Args)]
+pub struct ZonesImportArgs {
+    /// GeoJSON FeatureCollection of Polygon or MultiPolygon zones; zones missing from it
+    /// are deleted
+    #[arg(short, long)]
+    pub file: PathBuf,
+
+    /// Feature property holding the zone ID
+    #[arg(long, default_value = DEFAULT_ZONE_ID_PROPERTY)]
+    pub id_property: String,
+
+    /// Feature property holding the zone name
+    #[arg(long, default_value = DEFAULT_ZONE_NAME_PROPERTY)]
+    pub name_property: String,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum RadarCommand {
+    /// Sum QPE grids covering a storm window at each gauge, replacing earlier estimates
+    /// for the same window and product
+    Import(RadarImportArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct RadarImportArgs {
+    /// MRMS QPE grid in millimeters, converted to ESRI ASCII with
+    /// `gdal_translate -of AAIGrid`; list every consecutive grid covering the window
+    #[arg(short, long = "file", required = true, num_args = 1..)]
+    pub files: Vec<PathBuf>,
+
+    /// Storm window start (RFC 3339)
+    #[arg(long)]
+    pub start: DateTime<Utc>,
+
+    /// Storm window end (RFC 3339)
+    #[arg(long)]
+    pub end: DateTime<Utc>,
+
+    /// MRMS product the grids came from
+    #[arg(long, default_value = DEFAULT_RADAR_PRODUCT)]
+    pub product: String,
+}
+
+#[derive(Debug, Args)]
+pub struct SeedArgs {
+    /// Number of synthetic gauges (station IDs 99001 and up)
+    #[arg(long, default_value_t = 50, value_parser = clap::value_parser!(u32).range(1..=MAX_SEED_GAUGES as i64))]
+    pub gauges: u32,
+
+    /// Water years of daily history, ending with the current water year
+    #[arg(long, default_value_t = 5, value_parser = clap::value_parser!(u32).range(1..=100))]
+    pub years: u32,
+
+    /// RNG seed for reproducible data; random when omitted
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Skip the confirmation prompt
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct BenchArgs {
+    /// Timed runs per workload, after one warm-up run
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..=10_000))]
+    pub iterations: u32,
+
+    /// Gauge to insert into and query (the first `seed` gauge by default)
+    #[arg(long, default_value = DEFAULT_BENCH_STATION)]
+    pub station: StationId,
+
+    /// Water year to query; defaults to the previous water year
+    #[arg(long)]
+    pub water_year: Option<i32>,
+
+    /// Readings per bulk insert run (rolled back afterwards)
+    #[arg(long, default_value_t = DEFAULT_BULK_INSERT_READINGS)]
+    pub readings: usize,
+
+    /// Water year workbook to parse; skipped when missing
+    #[arg(long, default_value = "sample-data-files/pcp_WY_2023.xlsx")]
+    pub excel_file: PathBuf,
+
+    /// Water year of --excel-file
+    #[arg(long, default_value_t = 2023)]
+    pub excel_water_year: i32,
+
+    /// FOPR workbook to parse (station ID taken from the file name); skipped when missing
+    #[arg(long, default_value = "sample-data-files/59700_FOPR.xlsx")]
+    pub fopr_file: PathBuf,
+
+    /// Append results to this JSON Lines file and compare with its last entry
+    #[arg(long)]
+    pub history: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct BootstrapArgs {
+    /// Gauge list to scrape for station IDs
+    #[arg(
+        long,
+        env = "GAUGE_LIST_URL",
+        default_value = "https://alert.fcd.maricopa.gov/alert/Rain/ev_rain.txt"
+    )]
+    pub gauge_list_url: String,
+
+    /// Progress file; completed gauges and water years in it are skipped
+    #[arg(long, default_value = "bootstrap-checkpoint.json")]
+    pub checkpoint: PathBuf,
+
+    /// Ignore an existing checkpoint and start over
+    #[arg(long)]
+    pub restart: bool,
+
+    /// Earliest water year to backfill from water year workbooks
+    #[arg(long, default_value_t = DEFAULT_FIRST_WATER_YEAR)]
+    pub first_water_year: i32,
+
+    /// Latest water year to backfill; defaults to the previous water year
+    #[arg(long)]
+    pub last_water_year: Option<i32>,
+
+    /// What to do when a gauge or water year fails (failures are retried on the next run)
+    #[arg(long, value_enum, default_value_t = OnError::Continue)]
+    pub on_error: OnError,
+
+    /// Stations recalculated concurrently
+    #[arg(long, default_value_t = DEFAULT_RECALC_CONCURRENCY)]
+    pub concurrency: usize,
+
+    /// Skip the confirmation prompt
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct BackupArgs {
+    /// Archive to write (zstd-compressed tar, e.g. backup.tar.zst)
+    #[arg(short, long)]
+    pub out: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct RestoreArgs {
+    /// Archive written by `backup`
+    pub file: PathBuf,
+
+    /// Restore into a database that already has data, skipping rows that exist
+    #[arg(long)]
+    pub force: bool,
+
+    /// Skip the confirmation prompt
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum MigrateCommand {
+    /// List migrations and whether each is applied (exits non-zero when any are pending)
+    Status,
+
+    /// Apply pending migrations
+    Up(MigrateUpArgs),
+
+    /// Revert migrations newer than a version (only migrations with down scripts)
+    Down(MigrateDownArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct MigrateUpArgs {
+    /// Stop after this migration version; applies everything when omitted
+    #[arg(long)]
+    pub to: Option<i64>,
+}
+
+#[derive(Debug, Args)]
+pub struct MigrateDownArgs {
+    /// Migration version to go back to (kept applied); 0 reverts everything
+    #[arg(long)]
+    pub to: i64,
+
+    /// Skip the confirmation prompt
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ReplayArgs {
+    /// Payload archive directory
+    #[arg(long, env = "PAYLOAD_ARCHIVE_DIR", default_value = "./data/payloads")]
+    pub dir: PathBuf,
+
+    /// Only payloads from this scrape
+    #[arg(long, value_enum)]
+    pub source: Option<PayloadSource>,
+
+    /// Only payloads fetched on or after this date (YYYY-MM-DD, UTC)
+    #[arg(long)]
+    pub since: Option<NaiveDate>,
+
+    /// Only the newest N matching payloads
+    #[arg(long)]
+    pub last: Option<usize>,
+
+    /// Print every parsed gauge or reading
+    #[arg(long)]
+    pub show_rows: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+/// Run a parsed command line, returning the process exit code
+pub async fn run(cli: Cli) -> CliResult<ExitCode> {
+    let json = cli.json || cli.non_interactive;
+    let interactive = !cli.non_interactive;
+    let progress = match &cli.progress_webhook {
+        Some(url) => ProgressReporter::new().with_webhook(url),
+        None => ProgressReporter::new(),
+    };
+    let politeness = Politeness::new(&config::politeness_config_from_env());
+    let downloader = || McfcdDownloader::new().with_politeness(politeness.clone());
+
+    match cli.command {
+        Command::Download(DownloadCommand::WaterYear(args)) => {
+            let report = download::download_water_year(&downloader(), &args).await?;
+            output::emit(&report, json)?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Probe(ProbeCommand::Fopr(args)) => {
+            let pool = connect(&cli.database_url).await?;
+            let service = FoprAvailabilityService::new(pool.postgres()?.clone())
+                .with_downloader(downloader())
+                .with_rate(args.rate);
+            let report = probe::probe_fopr(&service, &args, json).await?;
+            output::emit(&report, json)?;
+            Ok(exit_code(report.failed.is_empty()))
+        }
+        Command::Import(ImportCommand::Excel(mut args)) => {
+            args.yes |= !interactive;
+            let service = HistoricalImportService::new(connect(&cli.database_url).await?)
+                .with_downloader(downloader())
+                .with_conflict_policy(args.on_conflict.into());
+            let report = import::import_excel(&service, &args, json, &progress).await?;
+            output::emit(&report, json)?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Import(ImportCommand::Bulk(mut args)) => {
+            args.yes |= !interactive;
+            if !interactive {
+                args.on_error.get_or_insert(OnError::Abort);
+            }
+            let service = HistoricalImportService::new(connect(&cli.database_url).await?)
+                .with_downloader(downloader())
+                .with_conflict_policy(args.on_conflict.into());
+            let report = import::load_bulk_years(&service, &args, json, &progress).await?;
+            output::emit(&report, json)?;
+            Ok(exit_code(report.failed_years.is_empty()))
+        }
+        Command::Import(ImportCommand::Fopr(args)) => {
+            let mut fopr_downloader = downloader();
+            if let Some(dir) = &args.partial_dir {
+                fopr_downloader = fopr_downloader.with_partial_dir(dir);
+            }
+            let service = FoprImportService::new(connect(&cli.database_url).await?)
+                .with_downloader(fopr_downloader)
+                .with_conflict_policy(args.on_conflict.into());
+            let report = import::import_fopr(&service, &args, json, &progress).await;
+            output::emit(&report, json)?;
+            Ok(exit_code(report.failed() == 0))
+        }
+        Command::Recalc(args) => {
+            let pool = connect(&cli.database_url).await?;
+            let service = SummaryService::new(MonthlyRainfallRepository::new(pool.clone()))
+                .with_hourly(HourlyRainfallRepository::new(pool.clone()))
+                .with_views(SummaryViewRepository::new(pool))
+                .with_concurrency(args.concurrency);
+            let report = recalc::recalc(&service, &args, json).await?;
+            output::emit(&report, json)?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Verify(args) => {
+            let service = HistoricalImportService::new(connect(&cli.database_url).await?);
+            let report = verify::verify(&service, &args).await?;
+            output::emit(&report, json)?;
+            Ok(exit_code(report.discrepancies.is_empty()))
+        }
+        Command::Export(ExportCommand {
+            mode: Some(ExportMode::Gis(args)),
+            ..
+        }) => {
+            let service = GisExportService::new(connect(&cli.database_url).await?);
+            let report = export::export_gis(&service, &args).await?;
+            output::emit(&report, json)?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Export(ExportCommand {
+            readings: Some(args),
+            ..
+        }) => {
+            let service = HistoricalImportService::new(connect(&cli.database_url).await?);
+            if let Some(report) = export::export(&service, &args).await? {
+                output::emit(&report, json)?;
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Export(_) => Err(Error::Usage(
+            "export needs --station and --water-year, or a mode such as `export gis`".to_string(),
+        )),
+        Command::Gauges(GaugesCommand::Export(args)) => {
+            let service = GaugeEditService::new(connect(&cli.database_url).await?);
+            if let Some(report) = gauges::export(&service, &args).await? {
+                output::emit(&report, json)?;
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Gauges(GaugesCommand::Import(mut args)) => {
+            args.yes |= !interactive;
+            let service = GaugeEditService::new(connect(&cli.database_url).await?);
+            let report = gauges::import(&service, &args, json).await?;
+            output::emit(&report, json)?;
+            Ok(exit_code(report.plan.is_valid()))
+        }
+        Command::Zones(ZonesCommand::Import(args)) => {
+            let service = ZoneService::new(connect(&cli.database_url).await?);
+            let report = zones::import(&service, &args).await?;
+            output::emit(&report, json)?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Zones(ZonesCommand::Assign) => {
+            let service = ZoneService::new(connect(&cli.database_url).await?);
+            let report = zones::assign(&service).await?;
+            output::emit(&report, json)?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Radar(RadarCommand::Import(args)) => {
+            let pool = connect(&cli.database_url).await?;
+            let service = RadarService::new(pool.postgres()?.clone());
+            let report = radar::import(&service, &args).await?;
+            output::emit(&report, json)?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Seed(mut args) => {
+            args.yes |= !interactive;
+            let pool = connect(&cli.database_url).await?;
+            let service = SeedService::new(pool.postgres()?.clone());
+            let report = seed::seed(&service, &args, json).await?;
+            output::emit(&report, json)?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Bench(args) => {
+            let pool = connect(&cli.database_url).await?;
+            let service = BenchService::new(pool.postgres()?.clone());
+            let report = bench::bench(&service, &args, json).await?;
+            output::emit(&report, json)?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Bootstrap(mut args) => {
+            args.yes |= !interactive;
+            let pool = connect(&cli.database_url).await?;
+            let service = BootstrapService::new(pool, &args.gauge_list_url)
+                .with_politeness(politeness.clone())
+                .with_recalc_concurrency(args.concurrency);
+            let report = bootstrap::bootstrap(&service, &args, json, &progress).await?;
+            output::emit(&report, json)?;
+            Ok(exit_code(report.is_complete()))
+        }
+        Command::Backup(args) => {
+            let pool = connect(&cli.database_url).await?;
+            let service = BackupService::new(BackupRepository::new(pool));
+            let report = backup::backup(&service, &args, json).await?;
+            output::emit(&report, json)?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Restore(mut args) => {
+            args.yes |= !interactive;
+            let pool = connect(&cli.database_url).await?;
+            pool.migrate().await?;
+            let service = BackupService::new(BackupRepository::new(pool));
+            let report = backup::restore(&service, &args, json).await?;
+            output::emit(&report, json)?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Replay(args) => {
+            let report = replay::replay(&args, json).await?;
+            output::emit(&report, json)?;
+            Ok(exit_code(report.failed() == 0))
+        }
+        Command::Migrate(MigrateCommand::Status) => {
+            let pool = connect(&cli.database_url).await?;
+            let report = migrate::status(&pool).await?;
+            output::emit(&report, json)?;
+            Ok(exit_code(report.is_current()))
+        }
+        Command::Migrate(MigrateCommand::Up(args)) => {
+            let pool = connect(&cli.database_url).await?;
+            let report = migrate::up(&pool, &args, json).await?;
+            output::emit(&report, json)?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Migrate(MigrateCommand::Down(mut args)) => {
+            args.yes |= !interactive;
+            let pool = connect(&cli.database_url).await?;
+            let report = migrate::down(&pool, &args, json).await?;
+            output::emit(&report, json)?;
+            Ok(ExitCode::SUCCESS)
+        }
+    }
+}
+
+async fn connect(database_url: &Option<String>) -> CliResult<DbPool> {
+    let url = database_url.as_deref().ok_or_else(|| {
+        Error::Config(
+            "DATABASE_URL is required (pass --database-url or set DATABASE_URL)".to_string(),
+        )
+    })?;
+
+    Ok(DbPool::connect(url, 5).await?)
+}
+
+fn exit_code(success: bool) -> ExitCode {
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_parse_replay() {
+        let cli = Cli::try_parse_from([
+            "historical-import",
+            "replay",
+            "--dir",
+            "/var/lib/rain-tracker/payloads",
+            "--source",
+            "gauge-list",
+            "--since",
+            "2025-02-01",
+            "--last",
+            "5",
+        ])
+        .unwrap();
+
+        match cli.command {
+            Command::Replay(args) => {
+                assert_eq!(args.dir, PathBuf::from("/var/lib/rain-tracker/payloads"));
+                assert_eq!(args.source, Some(PayloadSource::GaugeList));
+                assert_eq!(args.since, NaiveDate::from_ymd_opt(2025, 2, 1));
+                assert_eq!(args.last, Some(5));
+                assert!(!args.show_rows);
+            }
+            other => panic!("Expected replay, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_import_excel() {
+        let cli = Cli::try_parse_from([
+            "historical-import",
+            "--database-url",
+            "postgres://localhost/test",
+            "import",
+            "excel",
+            "-f",
+            "plans/pcp_WY_2023.xlsx",
+            "-w",
+            "2023",
+            "-y",
+        ])
+        .unwrap();
+
+        match cli.command {
+            Command::Import(ImportCommand::Excel(args)) => {
+                assert_eq!(args.water_year, 2023);
+                assert_eq!(args.file, Some(PathBuf::from("plans/pcp_WY_2023.xlsx")));
+                assert!(args.yes);
+                assert_eq!(args.on_conflict, OnConflict::Skip);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_import_fopr_on_conflict_update() {
+        let cli = Cli::try_parse_from([
+            "historical-import",
+            "import",
+            "fopr",
+            "59700",
+            "--on-conflict",
+            "update",
+        ])
+        .unwrap();
+
+        match cli.command {
+            Command::Import(ImportCommand::Fopr(args)) => {
+                assert_eq!(args.on_conflict, OnConflict::Update);
+                assert_eq!(
+                    ConflictPolicy::from(args.on_conflict),
+                    ConflictPolicy::Update
+                );
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_import_excel_requires_water_year() {
+        let result = Cli::try_parse_from(["historical-import", "import", "excel", "-f", "x.xlsx"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_import_fopr_requires_station() {
+        let result = Cli::try_parse_from(["historical-import", "import", "fopr"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_probe_fopr_rate() {
+        let cli = Cli::try_parse_from(["historical-import", "probe", "fopr"]).unwrap();
+        match cli.command {
+            Command::Probe(ProbeCommand::Fopr(args)) => {
+                assert!(args.station_ids.is_empty());
+                assert_eq!(args.rate, DEFAULT_PROBE_RATE);
+            }
+            _ => panic!("Expected probe fopr command"),
+        }
+
+        for rate in ["0", "-1", "NaN"] {
+            let result =
+                Cli::try_parse_from(["historical-import", "probe", "fopr", "--rate", rate]);
+            assert!(result.is_err(), "rate {rate} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_json_flag_is_global() {
+        let cli = Cli::try_parse_from([
+            "historical-import",
+            "verify",
+            "--water-year",
+            "2024",
+            "--json",
+        ])
+        .unwrap();
+        assert!(cli.json);
+    }
+
+    #[test]
+    fn test_export_format_defaults_to_csv() {
+        let cli = Cli::try_parse_from(["historical-import", "export", "-s", "59700", "-w", "2024"])
+            .unwrap();
+
+        match cli.command {
+            Command::Export(ExportCommand {
+                readings: Some(args),
+                mode: None,
+            }) => assert_eq!(args.format, ExportFormat::Csv),
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_export_gis() {
+        let cli = Cli::try_parse_from([
+            "historical-import",
+            "export",
+            "gis",
+            "-o",
+            "gauges.gpkg",
+            "--format",
+            "geopackage",
+            "--zones",
+        ])
+        .unwrap();
+
+        match cli.command {
+            Command::Export(ExportCommand {
+                mode: Some(ExportMode::Gis(args)),
+                readings: None,
+            }) => {
+                assert_eq!(args.format, GisFormat::Geopackage);
+                assert_eq!(args.output, PathBuf::from("gauges.gpkg"));
+                assert!(args.zones);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+
+        // Reading export options do not mix with a mode, and still need both values
+        let mixed = [
+            "historical-import",
+            "export",
+            "-s",
+            "59700",
+            "gis",
+            "-o",
+            "g",
+        ];
+        assert!(Cli::try_parse_from(mixed).is_err());
+        assert!(Cli::try_parse_from(["historical-import", "export", "-s", "59700"]).is_err());
+    }
+
+    #[test]
+    fn test_recalc_requires_scope() {
+        assert!(Cli::try_parse_from(["historical-import", "recalc"]).is_err());
+        assert!(Cli::try_parse_from(["historical-import", "recalc", "--all"]).is_ok());
+    }
+
+    #[test]
+    fn test_bench_defaults_to_seeded_gauge() {
+        let cli = Cli::try_parse_from(["historical-import", "bench", "--iterations", "3"]).unwrap();
+
+        match cli.command {
+            Command::Bench(args) => {
+                assert_eq!(args.iterations, 3);
+                assert_eq!(args.station, "99001");
+                assert_eq!(args.readings, 1000);
+                assert!(args.water_year.is_none());
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+        assert!(Cli::try_parse_from(["historical-import", "bench", "--iterations", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_bootstrap() {
+        let cli = Cli::try_parse_from([
+            "historical-import",
+            "bootstrap",
+            "--checkpoint",
+            "/tmp/bootstrap.json",
+            "--last-water-year",
+            "2024",
+            "--on-error",
+            "abort",
+        ])
+        .unwrap();
+
+        match cli.command {
+            Command::Bootstrap(args) => {
+                assert_eq!(args.checkpoint, PathBuf::from("/tmp/bootstrap.json"));
+                assert_eq!(args.first_water_year, DEFAULT_FIRST_WATER_YEAR);
+                assert_eq!(args.last_water_year, Some(2024));
+                assert_eq!(args.on_error, OnError::Abort);
+                assert!(!args.restart);
+                assert!(!args.yes);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_recalc_all_conflicts_with_station() {
+        let result = Cli::try_parse_from(["historical-import", "recalc", "--all", "-s", "59700"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_recalc_date_range() {
+        let cli = Cli::try_parse_from([
+            "historical-import",
+            "recalc",
+            "--from",
+            "2024-01-01",
+            "--to",
+            "2024-03-31",
+        ])
+        .unwrap();
+
+        match cli.command {
+            Command::Recalc(args) => {
+                assert_eq!(args.from, NaiveDate::from_ymd_opt(2024, 1, 1));
+                assert_eq!(args.to, NaiveDate::from_ymd_opt(2024, 3, 31));
+                assert_eq!(args.concurrency, DEFAULT_RECALC_CONCURRENCY);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_seed_defaults() {
+        let cli = Cli::try_parse_from(["historical-import", "seed"]).unwrap();
+
+        match cli.command {
+            Command::Seed(args) => {
+                assert_eq!(args.gauges, 50);
+                assert_eq!(args.years, 5);
+                assert_eq!(args.seed, None);
+                assert!(!args.yes);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_seed_rejects_too_many_gauges() {
+        let result = Cli::try_parse_from(["historical-import", "seed", "--gauges", "1000"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_migrate_commands() {
+        let cli = Cli::try_parse_from(["historical-import", "migrate", "up"]).unwrap();
+        match cli.command {
+            Command::Migrate(MigrateCommand::Up(args)) => assert_eq!(args.to, None),
+            other => panic!("unexpected command: {other:?}"),
+        }
+
+        let cli = Cli::try_parse_from([
+            "historical-import",
+            "migrate",
+            "down",
+            "--to",
+            "20250118000000",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Migrate(MigrateCommand::Down(args)) => {
+                assert_eq!(args.to, 20250118000000);
+                assert!(!args.yes);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+
+        // Reverting needs an explicit target
+        assert!(Cli::try_parse_from(["historical-import", "migrate", "down"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_gauges_import() {
+        let cli = Cli::try_parse_from([
+            "historical-import",
+            "gauges",
+            "import",
+            "-f",
+            "gauges.csv",
+            "--dry-run",
+        ])
+        .unwrap();
+
+        match cli.command {
+            Command::Gauges(GaugesCommand::Import(args)) => {
+                assert_eq!(args.file, PathBuf::from("gauges.csv"));
+                assert!(args.dry_run);
+                assert!(!args.yes);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+
+        // The edited file is required
+        assert!(Cli::try_parse_from(["historical-import", "gauges", "import"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_zones_import() {
+        let cli = Cli::try_parse_from([
+            "historical-import",
+            "zones",
+            "import",
+            "--file",
+            "msp_zones.geojson",
+            "--id-property",
+            "ZONE_ID",
+        ])
+        .unwrap();
+
+        match cli.command {
+            Command::Zones(ZonesCommand::Import(args)) => {
+                assert_eq!(args.file, PathBuf::from("msp_zones.geojson"));
+                assert_eq!(args.id_property, "ZONE_ID");
+                assert_eq!(args.name_property, "name");
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_radar_import() {
+        let cli = Cli::try_parse_from([
+            "historical-import",
+            "radar",
+            "import",
+            "-f",
+            "qpe_01.asc",
+            "qpe_02.asc",
+            "--start",
+            "2025-01-06T00:00:00Z",
+            "--end",
+            "2025-01-06T02:00:00Z",
+        ])
+        .unwrap();
+
+        match cli.command {
+            Command::Radar(RadarCommand::Import(args)) => {
+                assert_eq!(
+                    args.files,
+                    [PathBuf::from("qpe_01.asc"), PathBuf::from("qpe_02.asc")]
+                );
+                assert_eq!(args.end - args.start, chrono::Duration::hours(2));
+                assert_eq!(args.product, DEFAULT_RADAR_PRODUCT);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+
+        // At least one grid is required
+        assert!(Cli::try_parse_from([
+            "historical-import",
+            "radar",
+            "import",
+            "--start",
+            "2025-01-06T00:00:00Z",
+            "--end",
+            "2025-01-06T02:00:00Z",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_non_interactive_bulk_import() {
+        let cli = Cli::try_parse_from([
+            "historical-import",
+            "import",
+            "bulk",
+            "--start-year",
+            "2020",
+            "--end-year",
+            "2024",
+            "--on-error",
+            "continue",
+            "--non-interactive",
+        ])
+        .unwrap();
+
+        assert!(cli.non_interactive);
+        match cli.command {
+            Command::Import(ImportCommand::Bulk(args)) => {
+                assert_eq!(args.on_error, Some(OnError::Continue));
+                assert!(!args.yes);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_on_error_rejects_unknown_policy() {
+        let result = Cli::try_parse_from([
+            "historical-import",
+            "import",
+            "bulk",
+            "--start-year",
+            "2020",
+            "--end-year",
+            "2024",
+            "--on-error",
+            "retry",
+        ]);
+        assert!(result.is_err());
+    }
+}
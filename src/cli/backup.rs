@@ -0,0 +1,112 @@
+// Backup and restore commands: portable tar.zst archives of the database
+
+use std::fmt;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::cli::output;
+use crate::cli::{BackupArgs, CliResult, RestoreArgs};
+use crate::error::Error;
+use crate::services::backup_service::{BackupManifest, BackupService, RestoreStats};
+
+/// Summary of a backup run
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupReport {
+    pub path: PathBuf,
+    #[serde(flatten)]
+    pub manifest: BackupManifest,
+}
+
+impl fmt::Display for BackupReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "✓ Backed up {} database to {}",
+            self.manifest.backend,
+            self.path.display()
+        )?;
+        for table in &self.manifest.tables {
+            writeln!(f, "  {}: {} rows", table.name, table.rows)?;
+        }
+        write!(
+            f,
+            "Format version {}, schema version {}",
+            self.manifest.format_version,
+            self.manifest
+                .schema_version
+                .map_or("none".to_string(), |v| v.to_string())
+        )
+    }
+}
+
+/// Summary of a restore run
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreReport {
+    pub path: PathBuf,
+    #[serde(flatten)]
+    pub stats: RestoreStats,
+}
+
+impl fmt::Display for RestoreReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "✓ Restored {} in {:.1}s",
+            self.path.display(),
+            self.stats.duration_secs
+        )?;
+        for table in &self.stats.tables {
+            writeln!(
+                f,
+                "  {}: {} of {} rows inserted",
+                table.name, table.inserted, table.rows
+            )?;
+        }
+        if !self.stats.skipped_tables.is_empty() {
+            write!(
+                f,
+                "Skipped (not supported by this backend): {}",
+                self.stats.skipped_tables.join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+pub async fn backup(
+    service: &BackupService,
+    args: &BackupArgs,
+    json: bool,
+) -> CliResult<BackupReport> {
+    output::status(json, format!("Writing backup to {}...", args.out.display()));
+    let manifest = service.backup(&args.out).await?;
+
+    Ok(BackupReport {
+        path: args.out.clone(),
+        manifest,
+    })
+}
+
+pub async fn restore(
+    service: &BackupService,
+    args: &RestoreArgs,
+    json: bool,
+) -> CliResult<RestoreReport> {
+    if !args.yes
+        && !output::confirm(&format!(
+            "Restore {} into this database?",
+            args.file.display()
+        ))
+    {
+        return Err(Error::Cancelled("Restore"));
+    }
+
+    output::status(json, format!("Restoring {}...", args.file.display()));
+    let stats = service.restore(&args.file, args.force).await?;
+
+    Ok(RestoreReport {
+        path: args.file.clone(),
+        stats,
+    })
+}
@@ -0,0 +1,288 @@
+// Bench command: time the import and query paths against a seeded database
+//
+// Runs each workload once to warm up, then `--iterations` timed runs. With `--history`,
+// the report is appended to a JSON Lines file and each median is compared with the
+// previous entry, so performance changes can be tracked across commits.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::future::Future;
+use std::io::Write;
+use std::path::Path;
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::cli::output;
+use crate::cli::{BenchArgs, CliResult};
+use crate::error::Error;
+use crate::services::bench_service::{self, BenchService, BenchTiming};
+use crate::station_id::StationId;
+
+/// Timings from one bench run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchReport {
+    pub recorded_at: DateTime<Utc>,
+    pub version: String,
+    pub station_id: String,
+    pub water_year: i32,
+    /// Timings by workload: excel_parse, fopr_parse, bulk_insert, water_year_query
+    pub workloads: BTreeMap<String, BenchTiming>,
+    /// Workloads not run, with the reason
+    #[serde(default)]
+    pub skipped: BTreeMap<String, String>,
+    /// Percent change in median from the previous `--history` entry, by workload
+    #[serde(default)]
+    pub median_change_pct: BTreeMap<String, f64>,
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Benchmarks for station {} (water year {}):",
+            self.station_id, self.water_year
+        )?;
+        for (name, timing) in &self.workloads {
+            write!(
+                f,
+                "  {name:<18} median {:>9.2} ms  p95 {:>9.2} ms  min {:>9.2} ms  ({} runs)",
+                timing.median_ms, timing.p95_ms, timing.min_ms, timing.iterations
+            )?;
+            if let Some(change) = self.median_change_pct.get(name) {
+                write!(f, "  {change:+.1}% vs previous")?;
+            }
+            writeln!(f)?;
+        }
+        for (name, reason) in &self.skipped {
+            writeln!(f, "  {name:<18} skipped: {reason}")?;
+        }
+        Ok(())
+    }
+}
+
+pub async fn bench(service: &BenchService, args: &BenchArgs, json: bool) -> CliResult<BenchReport> {
+    let water_year = args
+        .water_year
+        .unwrap_or_else(|| service.default_water_year());
+    let mut workloads = BTreeMap::new();
+    let mut skipped = BTreeMap::new();
+
+    match args
+        .excel_file
+        .to_str()
+        .filter(|_| args.excel_file.exists())
+    {
+        Some(path) => {
+            output::status(json, "Timing water year Excel parsing...");
+            let timing = time(args.iterations, || async {
+                Ok(bench_service::parse_water_year_excel(
+                    path,
+                    args.excel_water_year,
+                )?)
+            })
+            .await?;
+            workloads.insert("excel_parse".to_string(), timing);
+        }
+        None => {
+            skipped.insert("excel_parse".to_string(), missing(&args.excel_file));
+        }
+    }
+
+    match args.fopr_file.to_str().filter(|_| args.fopr_file.exists()) {
+        Some(path) => {
+            let station_id = fopr_station_id(&args.fopr_file);
+            output::status(json, "Timing FOPR parsing...");
+            let timing = time(args.iterations, || async {
+                Ok(bench_service::parse_fopr_daily(path, &station_id)?)
+            })
+            .await?;
+            workloads.insert("fopr_parse".to_string(), timing);
+        }
+        None => {
+            skipped.insert("fopr_parse".to_string(), missing(&args.fopr_file));
+        }
+    }
+
+    if service
+        .water_year_summary(&args.station, water_year)
+        .await?
+        == 0
+    {
+        return Err(Error::Usage(format!(
+            "Station {} has no readings in water year {water_year}; run `seed` first or pass --station/--water-year",
+            args.station
+        )));
+    }
+
+    output::status(
+        json,
+        format!("Timing bulk insert of {} readings...", args.readings),
+    );
+    let readings = bench_service::synthetic_readings(&args.station, args.readings);
+    let timing = time(args.iterations, || async {
+        Ok(service
+            .bulk_insert_rolled_back(&args.station, &readings)
+            .await?)
+    })
+    .await?;
+    workloads.insert("bulk_insert".to_string(), timing);
+
+    output::status(json, "Timing water year query...");
+    let timing = time(args.iterations, || async {
+        Ok(service
+            .water_year_summary(&args.station, water_year)
+            .await?)
+    })
+    .await?;
+    workloads.insert("water_year_query".to_string(), timing);
+
+    let mut report = BenchReport {
+        recorded_at: Utc::now(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        station_id: args.station.to_string(),
+        water_year,
+        workloads,
+        skipped,
+        median_change_pct: BTreeMap::new(),
+    };
+
+    if let Some(history) = &args.history {
+        if let Some(previous) = last_entry(history)? {
+            report.median_change_pct = median_changes(&previous, &report);
+        }
+        append_entry(history, &report)?;
+    }
+
+    Ok(report)
+}
+
+/// One warm-up run, then `iterations` timed runs
+async fn time<F, Fut, T>(iterations: u32, mut run: F) -> CliResult<BenchTiming>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = CliResult<T>>,
+{
+    run().await?;
+
+    let mut samples = Vec::with_capacity(iterations as usize);
+    for _ in 0..iterations {
+        let start = Instant::now();
+        run().await?;
+        samples.push(start.elapsed());
+    }
+    BenchTiming::from_samples(&samples)
+        .ok_or_else(|| Error::Usage("--iterations must be at least 1".to_string()))
+}
+
+fn missing(path: &Path) -> String {
+    format!("{} not found", path.display())
+}
+
+/// Station ID from an FOPR file name like 59700_FOPR.xlsx
+fn fopr_station_id(path: &Path) -> StationId {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .and_then(|stem| StationId::extract(&stem.replace('_', " ")).ok())
+        .unwrap_or_else(|| {
+            StationId::parse(bench_service::DEFAULT_BENCH_STATION).expect("valid station ID")
+        })
+}
+
+fn last_entry(history: &Path) -> CliResult<Option<BenchReport>> {
+    if !history.exists() {
+        return Ok(None);
+    }
+    let contents = fs::read_to_string(history)?;
+    match contents.lines().rev().find(|line| !line.trim().is_empty()) {
+        Some(line) => Ok(Some(serde_json::from_str(line)?)),
+        None => Ok(None),
+    }
+}
+
+fn append_entry(history: &Path, report: &BenchReport) -> CliResult<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(history)?;
+    writeln!(file, "{}", serde_json::to_string(report)?)?;
+    Ok(())
+}
+
+/// Percent change in median per workload present in both runs
+fn median_changes(previous: &BenchReport, current: &BenchReport) -> BTreeMap<String, f64> {
+    current
+        .workloads
+        .iter()
+        .filter_map(|(name, timing)| {
+            let before = previous.workloads.get(name)?.median_ms;
+            (before > 0.0).then(|| (name.clone(), (timing.median_ms / before - 1.0) * 100.0))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn timing(median_ms: f64) -> BenchTiming {
+        BenchTiming {
+            iterations: 10,
+            min_ms: median_ms,
+            median_ms,
+            p95_ms: median_ms,
+            mean_ms: median_ms,
+        }
+    }
+
+    fn report(workloads: &[(&str, f64)]) -> BenchReport {
+        BenchReport {
+            recorded_at: Utc::now(),
+            version: "0.0.0".to_string(),
+            station_id: "99001".to_string(),
+            water_year: 2024,
+            workloads: workloads
+                .iter()
+                .map(|(name, ms)| (name.to_string(), timing(*ms)))
+                .collect(),
+            skipped: BTreeMap::new(),
+            median_change_pct: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_median_changes_compare_shared_workloads() {
+        let previous = report(&[("bulk_insert", 200.0), ("excel_parse", 50.0)]);
+        let current = report(&[("bulk_insert", 50.0), ("water_year_query", 5.0)]);
+
+        let changes = median_changes(&previous, &current);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes["bulk_insert"], -75.0);
+    }
+
+    #[test]
+    fn test_history_round_trip() {
+        let path = std::env::temp_dir().join(format!("bench-history-{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        assert!(last_entry(&path).unwrap().is_none());
+        append_entry(&path, &report(&[("bulk_insert", 200.0)])).unwrap();
+        append_entry(&path, &report(&[("bulk_insert", 100.0)])).unwrap();
+
+        let last = last_entry(&path).unwrap().unwrap();
+        assert_eq!(last.workloads["bulk_insert"].median_ms, 100.0);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_fopr_station_id_from_file_name() {
+        assert_eq!(
+            fopr_station_id(&PathBuf::from("sample-data-files/59700_FOPR.xlsx")),
+            "59700"
+        );
+        assert_eq!(
+            fopr_station_id(&PathBuf::from("other.xlsx")),
+            bench_service::DEFAULT_BENCH_STATION
+        );
+    }
+}
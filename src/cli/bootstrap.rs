@@ -0,0 +1,467 @@
+// Bootstrap command: backfill a new deployment's full history in one run
+//
+// Stages run in order: scrape the gauge list, import FOPR for every gauge, import water
+// year workbooks for the years FOPR doesn't reach, rebuild monthly summaries, and report
+// coverage. Progress is written to a JSON checkpoint after every gauge and water year, so
+// an interrupted or partly failed run picks up where it stopped when started again.
+// Completed items are skipped on resume; failed ones are retried. Gauges that the last
+// `probe fopr` found without an FOPR file are skipped rather than imported to a 404.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+use crate::cli::import;
+use crate::cli::output;
+use crate::cli::{BootstrapArgs, CliResult, ExcelImportArgs, OnConflict, OnError};
+use crate::error::Error;
+use crate::importers::progress::ProgressReporter;
+use crate::services::bootstrap_service::{self, BootstrapService, StationCoverage};
+
+/// Progress saved between bootstrap runs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BootstrapCheckpoint {
+    pub started_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    /// Gauges found by the gauge list scrape; None until that stage completes
+    pub stations: Option<Vec<String>>,
+    /// Readings imported by station, for FOPR imports that succeeded
+    #[serde(default)]
+    pub fopr_imported: BTreeMap<String, i64>,
+    /// Last error by station, for FOPR imports that failed
+    #[serde(default)]
+    pub fopr_failed: BTreeMap<String, String>,
+    /// Readings inserted by water year, for workbook imports that succeeded
+    #[serde(default)]
+    pub water_years_imported: BTreeMap<i32, usize>,
+    /// Last error by water year, for workbook imports that failed
+    #[serde(default)]
+    pub water_years_failed: BTreeMap<i32, String>,
+    /// Set once summaries were rebuilt after the latest import
+    pub months_recalculated: Option<usize>,
+}
+
+impl BootstrapCheckpoint {
+    /// Read a checkpoint; a missing file means a fresh start
+    pub fn load(path: &Path) -> CliResult<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let contents = fs::read_to_string(path)?;
+        let checkpoint = serde_json::from_str(&contents)
+            .map_err(|e| Error::Data(format!("Invalid checkpoint {}: {e}", path.display())))?;
+        Ok(Some(checkpoint))
+    }
+
+    /// Write the checkpoint through a temp file so a crash never leaves it half written
+    pub fn save(&mut self, path: &Path) -> CliResult<()> {
+        self.updated_at = Some(Utc::now());
+        let temp = path.with_extension("tmp");
+        fs::write(&temp, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&temp, path)?;
+        Ok(())
+    }
+
+    /// Stations whose FOPR import hasn't succeeded yet
+    pub fn pending_fopr(&self) -> Vec<String> {
+        self.stations
+            .iter()
+            .flatten()
+            .filter(|station| !self.fopr_imported.contains_key(*station))
+            .cloned()
+            .collect()
+    }
+
+    /// Planned water years whose import hasn't succeeded yet
+    pub fn pending_water_years(&self, planned: &[i32]) -> Vec<i32> {
+        planned
+            .iter()
+            .filter(|year| !self.water_years_imported.contains_key(year))
+            .copied()
+            .collect()
+    }
+
+    fn record_fopr(&mut self, station_id: &str, result: Result<i64, String>) {
+        match result {
+            Ok(readings) => {
+                self.fopr_failed.remove(station_id);
+                self.fopr_imported.insert(station_id.to_string(), readings);
+            }
+            Err(e) => {
+                self.fopr_failed.insert(station_id.to_string(), e);
+            }
+        }
+        self.months_recalculated = None;
+    }
+
+    fn record_water_year(&mut self, water_year: i32, result: Result<usize, String>) {
+        match result {
+            Ok(inserted) => {
+                self.water_years_failed.remove(&water_year);
+                self.water_years_imported.insert(water_year, inserted);
+            }
+            Err(e) => {
+                self.water_years_failed.insert(water_year, e);
+            }
+        }
+        self.months_recalculated = None;
+    }
+}
+
+/// Summary of a bootstrap run, including work done by earlier runs it resumed
+#[derive(Debug, Clone, Serialize)]
+pub struct BootstrapReport {
+    pub checkpoint: String,
+    pub resumed: bool,
+    pub gauges: usize,
+    pub fopr_imported: usize,
+    pub fopr_readings: i64,
+    pub fopr_failed: BTreeMap<String, String>,
+    /// Gauges skipped because the last probe found no FOPR file
+    pub fopr_skipped: Vec<String>,
+    /// Water years FOPR doesn't reach, imported from water year workbooks
+    pub water_years: Vec<i32>,
+    pub water_years_imported: BTreeMap<i32, usize>,
+    pub water_years_failed: BTreeMap<i32, String>,
+    pub months_recalculated: Option<usize>,
+    /// True when the run stopped at a failure (--on-error abort)
+    pub aborted: bool,
+    pub coverage: Vec<StationCoverage>,
+    pub stations_without_readings: Vec<String>,
+    pub duration_secs: f64,
+}
+
+impl BootstrapReport {
+    /// True when every stage finished without failures
+    pub fn is_complete(&self) -> bool {
+        !self.aborted && self.fopr_failed.is_empty() && self.water_years_failed.is_empty()
+    }
+}
+
+impl fmt::Display for BootstrapReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Bootstrap {} in {:.1}s ({} gauges)",
+            if self.is_complete() {
+                "completed"
+            } else {
+                "incomplete"
+            },
+            self.duration_secs,
+            self.gauges
+        )?;
+        writeln!(
+            f,
+            "  FOPR: {} gauges imported ({} readings), {} failed",
+            self.fopr_imported,
+            self.fopr_readings,
+            self.fopr_failed.len()
+        )?;
+        for (station_id, error) in &self.fopr_failed {
+            writeln!(f, "    ✗ {station_id}: {error}")?;
+        }
+        if !self.fopr_skipped.is_empty() {
+            writeln!(
+                f,
+                "    - No FOPR file (skipped): {}",
+                self.fopr_skipped.join(", ")
+            )?;
+        }
+        writeln!(
+            f,
+            "  Water years: {} of {:?} imported, {} failed",
+            self.water_years_imported.len(),
+            self.water_years,
+            self.water_years_failed.len()
+        )?;
+        for (water_year, error) in &self.water_years_failed {
+            writeln!(f, "    ✗ WY {water_year}: {error}")?;
+        }
+        match self.months_recalculated {
+            Some(months) => writeln!(f, "  Summaries: {months} station-months recalculated")?,
+            None => writeln!(f, "  Summaries: not recalculated")?,
+        }
+
+        if !self.coverage.is_empty() {
+            writeln!(f, "Coverage:")?;
+            for station in &self.coverage {
+                writeln!(
+                    f,
+                    "  {:<8} {} to {}  {:>7} readings  {:>3} water years  {}",
+                    station.station_id,
+                    station.first_reading,
+                    station.last_reading,
+                    station.readings,
+                    station.water_years,
+                    station.data_sources.join(", ")
+                )?;
+            }
+        }
+        if !self.stations_without_readings.is_empty() {
+            writeln!(
+                f,
+                "⚠ No readings for: {}",
+                self.stations_without_readings.join(", ")
+            )?;
+        }
+        if !self.is_complete() {
+            write!(f, "Run bootstrap again to resume from {}", self.checkpoint)?;
+        }
+        Ok(())
+    }
+}
+
+pub async fn bootstrap(
+    service: &BootstrapService,
+    args: &BootstrapArgs,
+    json: bool,
+    progress: &ProgressReporter,
+) -> CliResult<BootstrapReport> {
+    let last_year = args
+        .last_water_year
+        .unwrap_or_else(|| bootstrap_service::water_year_of(Utc::now().date_naive()) - 1);
+    if args.first_water_year > last_year {
+        return Err(Error::Usage(format!(
+            "--first-water-year ({}) must not be after the last water year ({last_year})",
+            args.first_water_year
+        )));
+    }
+
+    let existing = if args.restart {
+        None
+    } else {
+        BootstrapCheckpoint::load(&args.checkpoint)?
+    };
+    let resumed = existing.is_some();
+
+    let prompt = if resumed {
+        format!("Resume bootstrap from {}?", args.checkpoint.display())
+    } else {
+        format!(
+            "Bootstrap the database (gauge list, FOPR for every gauge, water years {}-{last_year})?",
+            args.first_water_year
+        )
+    };
+    if !args.yes && !output::confirm(&prompt) {
+        return Err(Error::Cancelled("Bootstrap"));
+    }
+
+    let start = Instant::now();
+    let mut checkpoint = existing.unwrap_or_else(|| BootstrapCheckpoint {
+        started_at: Some(Utc::now()),
+        ..Default::default()
+    });
+    let keep_going = args.on_error == OnError::Continue;
+    let mut aborted = false;
+
+    // Stage 1: gauge list
+    if checkpoint.stations.is_none() {
+        output::status(json, "Scraping gauge list...");
+        let gauges = service.discover_gauges().await?;
+        let mut stations: Vec<String> = gauges
+            .into_iter()
+            .map(|g| g.station_id.into_string())
+            .collect();
+        stations.sort();
+        stations.dedup();
+        output::status(json, format!("✓ Found {} gauges", stations.len()));
+        checkpoint.stations = Some(stations);
+        checkpoint.save(&args.checkpoint)?;
+    }
+    let stations = checkpoint.stations.clone().unwrap_or_default();
+
+    // Stage 2: FOPR for each gauge with a file
+    let unavailable = service.fopr_unavailable().await?;
+    let (fopr_skipped, pending): (Vec<String>, Vec<String>) = checkpoint
+        .pending_fopr()
+        .into_iter()
+        .partition(|station_id| unavailable.contains(station_id));
+    if !fopr_skipped.is_empty() {
+        output::status(
+            json,
+            format!(
+                "Skipping {} gauges without an FOPR file",
+                fopr_skipped.len()
+            ),
+        );
+    }
+    if !pending.is_empty() {
+        output::status(
+            json,
+            format!("Importing FOPR files for {} gauges...", pending.len()),
+        );
+        let bar = output::progress_bar(pending.len() as u64, json);
+        for station_id in &pending {
+            bar.set_message(station_id.clone());
+            let result = service
+                .import_fopr(station_id)
+                .await
+                .map(|stats| stats.readings_imported)
+                .map_err(|e| {
+                    error!(station_id = %station_id, error = %e, "FOPR import failed");
+                    e.to_string()
+                });
+            let failed = result.is_err();
+            checkpoint.record_fopr(station_id, result);
+            checkpoint.save(&args.checkpoint)?;
+            bar.inc(1);
+
+            if failed && !keep_going {
+                aborted = true;
+                break;
+            }
+        }
+        bar.finish_and_clear();
+    }
+
+    // Stage 3: water year workbooks for the years FOPR doesn't reach
+    let mut water_years = Vec::new();
+    if !aborted {
+        let mut last_readings = Vec::with_capacity(stations.len());
+        for station_id in &stations {
+            let coverage = service.station_coverage(station_id).await?;
+            last_readings.push(coverage.map(|c| c.last_reading));
+        }
+        water_years = bootstrap_service::missing_water_years(
+            &last_readings,
+            args.first_water_year,
+            last_year,
+        );
+
+        for water_year in checkpoint.pending_water_years(&water_years) {
+            output::status(json, format!("=== Water Year {water_year} ==="));
+            let year_args = ExcelImportArgs {
+                water_year,
+                file: None,
+                on_conflict: OnConflict::Skip,
+                yes: true,
+            };
+            let result = import::import_excel(service.historical(), &year_args, json, progress)
+                .await
+                .map(|report| report.readings_inserted)
+                .map_err(|e| {
+                    error!(water_year = water_year, error = %e, "Water year import failed");
+                    e.to_string()
+                });
+            let failed = result.is_err();
+            checkpoint.record_water_year(water_year, result);
+            checkpoint.save(&args.checkpoint)?;
+
+            if failed && !keep_going {
+                aborted = true;
+                break;
+            }
+        }
+    }
+
+    // Stage 4: summaries
+    if !aborted && checkpoint.months_recalculated.is_none() {
+        output::status(json, "Recalculating monthly summaries...");
+        let bar = output::progress_bar(0, json);
+        let stats = service
+            .recalculate(|done, total| {
+                bar.set_length(total as u64);
+                bar.set_position(done as u64);
+            })
+            .await?;
+        bar.finish_and_clear();
+        checkpoint.months_recalculated = Some(stats.months_recalculated);
+        checkpoint.save(&args.checkpoint)?;
+    }
+
+    // Stage 5: coverage
+    let mut coverage = Vec::with_capacity(stations.len());
+    let mut stations_without_readings = Vec::new();
+    for station_id in &stations {
+        match service.station_coverage(station_id).await? {
+            Some(station) => coverage.push(station),
+            None => stations_without_readings.push(station_id.clone()),
+        }
+    }
+
+    Ok(BootstrapReport {
+        checkpoint: args.checkpoint.display().to_string(),
+        resumed,
+        gauges: stations.len(),
+        fopr_imported: checkpoint.fopr_imported.len(),
+        fopr_readings: checkpoint.fopr_imported.values().sum(),
+        fopr_failed: checkpoint.fopr_failed,
+        fopr_skipped,
+        water_years,
+        water_years_imported: checkpoint.water_years_imported,
+        water_years_failed: checkpoint.water_years_failed,
+        months_recalculated: checkpoint.months_recalculated,
+        aborted,
+        coverage,
+        stations_without_readings,
+        duration_secs: start.elapsed().as_secs_f64(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkpoint(stations: &[&str]) -> BootstrapCheckpoint {
+        BootstrapCheckpoint {
+            stations: Some(stations.iter().map(|s| s.to_string()).collect()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_pending_fopr_retries_failures_and_skips_successes() {
+        let mut checkpoint = checkpoint(&["100", "200", "300"]);
+        checkpoint.record_fopr("100", Ok(42));
+        checkpoint.record_fopr("200", Err("404 Not Found".to_string()));
+
+        assert_eq!(checkpoint.pending_fopr(), vec!["200", "300"]);
+
+        checkpoint.record_fopr("200", Ok(7));
+        assert_eq!(checkpoint.pending_fopr(), vec!["300"]);
+        assert!(checkpoint.fopr_failed.is_empty());
+        assert!(BootstrapCheckpoint::default().pending_fopr().is_empty());
+    }
+
+    #[test]
+    fn test_imports_invalidate_recalculation() {
+        let mut checkpoint = checkpoint(&["100"]);
+        checkpoint.months_recalculated = Some(12);
+        checkpoint.record_water_year(2024, Ok(500));
+        assert_eq!(checkpoint.months_recalculated, None);
+
+        checkpoint.record_water_year(2025, Err("download failed".to_string()));
+        assert_eq!(
+            checkpoint.pending_water_years(&[2023, 2024, 2025]),
+            vec![2023, 2025]
+        );
+    }
+
+    #[test]
+    fn test_checkpoint_round_trip() {
+        let path = std::env::temp_dir().join(format!("bootstrap-{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+        assert!(BootstrapCheckpoint::load(&path).unwrap().is_none());
+
+        let mut saved = checkpoint(&["100", "200"]);
+        saved.record_fopr("100", Ok(42));
+        saved.record_water_year(2024, Ok(500));
+        saved.months_recalculated = Some(3);
+        saved.save(&path).unwrap();
+
+        let loaded = BootstrapCheckpoint::load(&path).unwrap().unwrap();
+        assert_eq!(loaded, saved);
+        assert!(loaded.updated_at.is_some());
+
+        fs::write(&path, "not json").unwrap();
+        assert!(BootstrapCheckpoint::load(&path).is_err());
+        fs::remove_file(&path).unwrap();
+    }
+}
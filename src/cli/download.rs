@@ -0,0 +1,49 @@
+// Download commands: fetch MCFCD source files without importing them
+
+use std::fmt;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::cli::{CliResult, DownloadWaterYearArgs};
+use crate::importers::downloader::McfcdDownloader;
+
+/// Summary of a downloaded file
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadReport {
+    pub water_year: i32,
+    pub path: PathBuf,
+    pub bytes: usize,
+}
+
+impl fmt::Display for DownloadReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "✓ Downloaded water year {} ({} bytes) to {}",
+            self.water_year,
+            self.bytes,
+            self.path.display()
+        )
+    }
+}
+
+/// Download pcp_WY_YYYY.xlsx into the output directory
+pub async fn download_water_year(
+    downloader: &McfcdDownloader,
+    args: &DownloadWaterYearArgs,
+) -> CliResult<DownloadReport> {
+    let bytes = downloader.download_excel(args.water_year).await?;
+
+    std::fs::create_dir_all(&args.output_dir)?;
+    let path = args
+        .output_dir
+        .join(format!("pcp_WY_{}.xlsx", args.water_year));
+    std::fs::write(&path, &bytes)?;
+
+    Ok(DownloadReport {
+        water_year: args.water_year,
+        path,
+        bytes: bytes.len(),
+    })
+}
@@ -0,0 +1,176 @@
+// Export command: dump a gauge's readings for a water year as CSV or JSON, or write the
+// gauge network as GIS layers (`export gis`)
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::cli::{CliResult, ExportArgs, ExportFormat, GisExportArgs};
+use crate::db::Reading;
+use crate::gis::{GisFormat, GisOutput};
+use crate::services::gis_export_service::GisExportService;
+use crate::services::historical_import_service::HistoricalImportService;
+
+/// Summary printed when exporting to a file
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportReport {
+    pub station_id: String,
+    pub water_year: i32,
+    pub rows: usize,
+    pub path: PathBuf,
+}
+
+impl fmt::Display for ExportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "✓ Exported {} readings for station {} (water year {}) to {}",
+            self.rows,
+            self.station_id,
+            self.water_year,
+            self.path.display()
+        )
+    }
+}
+
+/// Summary of a GIS export
+#[derive(Debug, Clone, Serialize)]
+pub struct GisExportReport {
+    pub format: GisFormat,
+    #[serde(flatten)]
+    pub output: GisOutput,
+}
+
+impl fmt::Display for GisExportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let output = &self.output;
+        let format = match self.format {
+            GisFormat::Shapefile => "Shapefile",
+            GisFormat::Geopackage => "GeoPackage",
+        };
+        write!(f, "✓ Exported {} gauges", output.gauges)?;
+        if let Some(zones) = output.zones {
+            write!(f, " and {zones} forecast zones")?;
+        }
+        if let Some(path) = output.files.first() {
+            write!(f, " as {format} to {}", path.display())?;
+        }
+        if output.gauges_without_coordinates > 0 {
+            write!(
+                f,
+                "\n  {} gauges without coordinates were left out",
+                output.gauges_without_coordinates
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Write the gauge network as GIS layers
+pub async fn export_gis(
+    service: &GisExportService,
+    args: &GisExportArgs,
+) -> CliResult<GisExportReport> {
+    let output = service
+        .export(args.format, &args.output, args.zones)
+        .await?;
+    Ok(GisExportReport {
+        format: args.format,
+        output,
+    })
+}
+
+/// Export readings; returns a report only when writing to a file
+///
+/// When writing to stdout the data itself is the output, so no report is produced.
+pub async fn export(
+    service: &HistoricalImportService,
+    args: &ExportArgs,
+) -> CliResult<Option<ExportReport>> {
+    let readings = service
+        .export_water_year(&args.station, args.water_year)
+        .await?;
+
+    match &args.output {
+        Some(path) => {
+            let mut writer = BufWriter::new(File::create(path)?);
+            write_readings(&mut writer, &readings, args.format)?;
+            writer.flush()?;
+
+            Ok(Some(ExportReport {
+                station_id: args.station.to_string(),
+                water_year: args.water_year,
+                rows: readings.len(),
+                path: path.clone(),
+            }))
+        }
+        None => {
+            let stdout = io::stdout();
+            let mut writer = stdout.lock();
+            write_readings(&mut writer, &readings, args.format)?;
+            writer.flush()?;
+            Ok(None)
+        }
+    }
+}
+
+/// Write readings in the requested format
+pub fn write_readings<W: Write>(
+    writer: &mut W,
+    readings: &[Reading],
+    format: ExportFormat,
+) -> CliResult<()> {
+    match format {
+        ExportFormat::Csv => {
+            writeln!(
+                writer,
+                "station_id,reading_datetime,cumulative_inches,incremental_inches"
+            )?;
+            for r in readings {
+                writeln!(
+                    writer,
+                    "{},{},{},{}",
+                    r.station_id,
+                    r.reading_datetime.to_rfc3339(),
+                    r.cumulative_inches.value(),
+                    r.incremental_inches.value()
+                )?;
+            }
+        }
+        ExportFormat::Json => {
+            serde_json::to_writer_pretty(&mut *writer, readings)?;
+            writeln!(writer)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::units::Inches;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_write_readings_csv() {
+        let readings = vec![Reading {
+            id: 1,
+            reading_datetime: Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap(),
+            cumulative_inches: Inches::new(0.0).unwrap(),
+            incremental_inches: Inches::new(0.12).unwrap(),
+            station_id: "59700".to_string(),
+            created_at: Utc::now(),
+        }];
+
+        let mut buf = Vec::new();
+        write_readings(&mut buf, &readings, ExportFormat::Csv).unwrap();
+
+        let csv = String::from_utf8(buf).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1], "59700,2024-10-01T00:00:00+00:00,0,0.12");
+    }
+}
@@ -0,0 +1,125 @@
+// Gauges command: export gauge metadata to CSV and apply an operator's edits
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::cli::output;
+use crate::cli::{CliResult, GaugesExportArgs, GaugesImportArgs};
+use crate::error::Error;
+use crate::services::gauge_edit_service::{GaugeEditPlan, GaugeEditService};
+
+/// Summary printed when exporting to a file
+#[derive(Debug, Clone, Serialize)]
+pub struct GaugeExportReport {
+    pub gauges: usize,
+    pub path: PathBuf,
+}
+
+impl fmt::Display for GaugeExportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "✓ Exported metadata for {} gauges to {}",
+            self.gauges,
+            self.path.display()
+        )
+    }
+}
+
+/// Export gauge metadata; returns a report only when writing to a file
+pub async fn export(
+    service: &GaugeEditService,
+    args: &GaugesExportArgs,
+) -> CliResult<Option<GaugeExportReport>> {
+    match &args.output {
+        Some(path) => {
+            let mut writer = BufWriter::new(File::create(path)?);
+            let gauges = service.export_csv(&mut writer).await?;
+            writer.flush()?;
+            Ok(Some(GaugeExportReport {
+                gauges,
+                path: path.clone(),
+            }))
+        }
+        None => {
+            service.export_csv(io::stdout().lock()).await?;
+            Ok(None)
+        }
+    }
+}
+
+/// Result of applying (or previewing) an edited metadata file
+#[derive(Debug, Clone, Serialize)]
+pub struct GaugeEditReport {
+    #[serde(flatten)]
+    pub plan: GaugeEditPlan,
+    pub dry_run: bool,
+    pub gauges_updated: u64,
+}
+
+impl fmt::Display for GaugeEditReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plan = &self.plan;
+        if !plan.is_valid() {
+            write!(f, "{plan}")?;
+            return write!(
+                f,
+                "✗ {} problems in {} rows; no gauges were updated",
+                plan.issues.len(),
+                plan.rows_read
+            );
+        }
+        if self.dry_run {
+            write!(f, "{plan}")?;
+            return write!(
+                f,
+                "Dry run: {} gauges would be updated ({} unchanged)",
+                plan.edits.len(),
+                plan.unchanged
+            );
+        }
+        write!(
+            f,
+            "✓ Updated {} gauges ({} unchanged)",
+            self.gauges_updated, plan.unchanged
+        )
+    }
+}
+
+/// Validate an edited file, preview its changes, and apply them after confirmation
+///
+/// Nothing is written when any row fails validation.
+pub async fn import(
+    service: &GaugeEditService,
+    args: &GaugesImportArgs,
+    json: bool,
+) -> CliResult<GaugeEditReport> {
+    let plan = service
+        .plan(BufReader::new(File::open(&args.file)?))
+        .await?;
+    let mut report = GaugeEditReport {
+        plan,
+        dry_run: args.dry_run,
+        gauges_updated: 0,
+    };
+    if !report.plan.is_valid() || args.dry_run || report.plan.edits.is_empty() {
+        return Ok(report);
+    }
+
+    output::status(json, &report.plan);
+    if !args.yes
+        && !output::confirm(&format!(
+            "Update metadata for {} gauges?",
+            report.plan.edits.len()
+        ))
+    {
+        return Err(Error::Cancelled("Import"));
+    }
+
+    report.gauges_updated = service.apply(&report.plan).await?;
+    Ok(report)
+}
@@ -0,0 +1,456 @@
+// Import commands: water year Excel files (single and bulk) and FOPR files
+
+use std::fmt;
+use std::io::Write;
+use std::time::Instant;
+
+use serde::Serialize;
+use tracing::error;
+
+use crate::cli::output;
+use crate::cli::{BulkImportArgs, CliResult, ExcelImportArgs, FoprImportArgs, OnError};
+use crate::error::Error;
+use crate::importers::progress::{ImportPhase, ImportProgressEvent, ProgressReporter};
+use crate::services::fopr_import_service::FoprImportService;
+use crate::services::historical_import_service::HistoricalImportService;
+
+/// Summary of a single water year Excel import
+#[derive(Debug, Clone, Serialize)]
+pub struct ExcelImportReport {
+    pub water_year: i32,
+    pub data_source: String,
+    pub readings_parsed: usize,
+    /// Values kept out of rain_readings by the ingest guards
+    pub readings_quarantined: usize,
+    pub readings_inserted: usize,
+    /// Stored readings overwritten with the file's values (`--on-conflict update`)
+    pub readings_updated: usize,
+    pub duplicates_skipped: usize,
+    pub stations_imported: usize,
+    /// Stations present in the file but missing from the gauges table
+    pub stations_skipped: Vec<String>,
+    /// Station-months committed, each with its recalculated summary
+    pub months_recalculated: usize,
+    /// Station-months an interrupted earlier run already committed
+    pub months_resumed: usize,
+    pub duration_secs: f64,
+}
+
+impl fmt::Display for ExcelImportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "✓ Inserted {} new readings, {} duplicates skipped",
+            self.readings_inserted, self.duplicates_skipped
+        )?;
+        if self.readings_updated > 0 {
+            writeln!(
+                f,
+                "✎ Updated {} changed readings (previous values saved in reading_revisions)",
+                self.readings_updated
+            )?;
+        }
+        if self.readings_quarantined > 0 {
+            writeln!(
+                f,
+                "⚠ Quarantined {} negative or oversized readings",
+                self.readings_quarantined
+            )?;
+        }
+        if !self.stations_skipped.is_empty() {
+            writeln!(
+                f,
+                "⚠ Skipped {} unknown stations: {}",
+                self.stations_skipped.len(),
+                self.stations_skipped.join(", ")
+            )?;
+        }
+        if self.months_resumed > 0 {
+            writeln!(
+                f,
+                "↻ Resumed: {} station-months were already committed",
+                self.months_resumed
+            )?;
+        }
+        write!(
+            f,
+            "Water year {} import completed in {:.1}s ({} stations, {} station-months committed)",
+            self.water_year, self.duration_secs, self.stations_imported, self.months_recalculated
+        )
+    }
+}
+
+/// Stations inserted between `inserting` progress events
+const STATIONS_PER_PROGRESS_EVENT: usize = 25;
+
+/// Import a water year Excel file, downloading it when no local file is given
+///
+/// Publishes progress events for each phase, and a `failed` event on error.
+pub async fn import_excel(
+    service: &HistoricalImportService,
+    args: &ExcelImportArgs,
+    json: bool,
+    progress: &ProgressReporter,
+) -> CliResult<ExcelImportReport> {
+    let mut event = progress.start(
+        HistoricalImportService::excel_data_source(args.water_year),
+        Some(args.water_year),
+    );
+
+    match run_excel_import(service, args, json, progress, &mut event).await {
+        Ok(report) => Ok(report),
+        Err(e) => {
+            event.error = Some(e.to_string());
+            progress.phase(&mut event, ImportPhase::Failed).await;
+            Err(e)
+        }
+    }
+}
+
+async fn run_excel_import(
+    service: &HistoricalImportService,
+    args: &ExcelImportArgs,
+    json: bool,
+    progress: &ProgressReporter,
+    event: &mut ImportProgressEvent,
+) -> CliResult<ExcelImportReport> {
+    let start = Instant::now();
+
+    // calamine needs a file path, so downloads go through a temp file
+    let mut temp_file = None;
+    let path = match &args.file {
+        Some(path) => path.to_string_lossy().to_string(),
+        None => {
+            progress.phase(event, ImportPhase::Downloading).await;
+            output::status(
+                json,
+                format!(
+                    "Downloading Excel file for water year {}...",
+                    args.water_year
+                ),
+            );
+            let bytes = service.download_water_year(args.water_year).await?;
+            let mut file = tempfile::NamedTempFile::new()?;
+            file.write_all(&bytes)?;
+            let path = file.path().to_string_lossy().to_string();
+            temp_file = Some(file);
+            path
+        }
+    };
+
+    progress.phase(event, ImportPhase::Parsing).await;
+    output::status(
+        json,
+        format!("Parsing Excel file for water year {}...", args.water_year),
+    );
+    let screened = service.parse_excel(&path, args.water_year)?;
+    drop(temp_file);
+    let readings = screened.accepted;
+    let readings_parsed = readings.len();
+    event.readings_parsed = readings_parsed;
+    output::status(json, format!("✓ Parsed {readings_parsed} readings"));
+
+    if !args.yes
+        && !output::confirm(&format!(
+            "Insert {readings_parsed} readings for water year {}?",
+            args.water_year
+        ))
+    {
+        return Err(Error::Cancelled("Import"));
+    }
+
+    let data_source = HistoricalImportService::excel_data_source(args.water_year);
+    service
+        .quarantine_readings(&data_source, &screened.quarantined)
+        .await?;
+    let by_station = HistoricalImportService::group_by_station(readings);
+    event.stations_total = by_station.len();
+    progress.phase(event, ImportPhase::Inserting).await;
+
+    output::status(
+        json,
+        format!("Inserting {readings_parsed} readings into database..."),
+    );
+    let bar = output::progress_bar(readings_parsed as u64, json);
+
+    let mut report = ExcelImportReport {
+        water_year: args.water_year,
+        data_source: data_source.clone(),
+        readings_parsed,
+        readings_quarantined: screened.quarantined.len(),
+        readings_inserted: 0,
+        readings_updated: 0,
+        duplicates_skipped: 0,
+        stations_imported: 0,
+        stations_skipped: Vec::new(),
+        months_recalculated: 0,
+        months_resumed: 0,
+        duration_secs: 0.0,
+    };
+
+    for (station_id, station_readings) in &by_station {
+        let result = service
+            .import_station_readings(station_id, &data_source, station_readings)
+            .await?;
+
+        if result.skipped {
+            report.stations_skipped.push(station_id.to_string());
+        } else {
+            report.stations_imported += 1;
+            report.readings_inserted += result.inserted;
+            report.readings_updated += result.updated;
+            report.duplicates_skipped += result.duplicates;
+            report.months_recalculated += result.affected_months.len();
+            report.months_resumed += result.months_resumed;
+        }
+        bar.inc(station_readings.len() as u64);
+
+        event.stations_processed += 1;
+        event.readings_inserted = report.readings_inserted;
+        event.duplicates_skipped = report.duplicates_skipped;
+        if event
+            .stations_processed
+            .is_multiple_of(STATIONS_PER_PROGRESS_EVENT)
+        {
+            progress.emit(event).await;
+        }
+    }
+    bar.finish();
+
+    report.duration_secs = start.elapsed().as_secs_f64();
+
+    progress.phase(event, ImportPhase::Completed).await;
+    Ok(report)
+}
+
+/// Outcome of a water year that failed during a bulk import
+#[derive(Debug, Clone, Serialize)]
+pub struct YearFailure {
+    pub water_year: i32,
+    pub error: String,
+}
+
+/// Summary of a multi-year bulk import
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkImportReport {
+    pub start_year: i32,
+    pub end_year: i32,
+    pub readings_inserted: usize,
+    pub imported: Vec<ExcelImportReport>,
+    pub failed_years: Vec<YearFailure>,
+    /// True when the run stopped early after a failure
+    pub aborted: bool,
+    /// Years never attempted because the run was aborted
+    pub skipped_years: Vec<i32>,
+    pub duration_secs: f64,
+}
+
+impl fmt::Display for BulkImportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Bulk import {}-{}: {} years imported, {} failed, {} readings inserted in {:.1}s",
+            self.start_year,
+            self.end_year,
+            self.imported.len(),
+            self.failed_years.len(),
+            self.readings_inserted,
+            self.duration_secs
+        )?;
+        for failure in &self.failed_years {
+            writeln!(f, "  ✗ WY {}: {}", failure.water_year, failure.error)?;
+        }
+        if self.aborted {
+            writeln!(f, "  Aborted; skipped water years {:?}", self.skipped_years)?;
+        }
+        Ok(())
+    }
+}
+
+/// Download and import each water year in the range
+///
+/// When a year fails, `args.on_error` decides whether to continue with the rest;
+/// without a policy the operator is asked on stdin.
+pub async fn load_bulk_years(
+    service: &HistoricalImportService,
+    args: &BulkImportArgs,
+    json: bool,
+    progress: &ProgressReporter,
+) -> CliResult<BulkImportReport> {
+    if args.start_year > args.end_year {
+        return Err(Error::Usage(format!(
+            "--start-year ({}) must not be after --end-year ({})",
+            args.start_year, args.end_year
+        )));
+    }
+
+    if !args.yes
+        && !output::confirm(&format!(
+            "Import water years {} through {}?",
+            args.start_year, args.end_year
+        ))
+    {
+        return Err(Error::Cancelled("Import"));
+    }
+
+    let start = Instant::now();
+    let mut report = BulkImportReport {
+        start_year: args.start_year,
+        end_year: args.end_year,
+        readings_inserted: 0,
+        imported: Vec::new(),
+        failed_years: Vec::new(),
+        aborted: false,
+        skipped_years: Vec::new(),
+        duration_secs: 0.0,
+    };
+
+    for water_year in args.start_year..=args.end_year {
+        output::status(json, format!("=== Water Year {water_year} ==="));
+        let year_args = ExcelImportArgs {
+            water_year,
+            file: None,
+            on_conflict: args.on_conflict,
+            yes: true,
+        };
+
+        match import_excel(service, &year_args, json, progress).await {
+            Ok(year_report) => {
+                report.readings_inserted += year_report.readings_inserted;
+                report.imported.push(year_report);
+            }
+            Err(e) => {
+                error!(water_year = water_year, error = %e, "Water year import failed");
+                report.failed_years.push(YearFailure {
+                    water_year,
+                    error: e.to_string(),
+                });
+
+                let keep_going = match args.on_error {
+                    Some(OnError::Continue) => true,
+                    Some(OnError::Abort) => false,
+                    None => {
+                        water_year == args.end_year
+                            || output::confirm(&format!(
+                            "Water year {water_year} failed: {e}. Continue with remaining years?"
+                        ))
+                    }
+                };
+
+                if !keep_going {
+                    report.aborted = water_year < args.end_year;
+                    report.skipped_years = (water_year + 1..=args.end_year).collect();
+                    break;
+                }
+            }
+        }
+    }
+
+    report.duration_secs = start.elapsed().as_secs_f64();
+    Ok(report)
+}
+
+/// Result of importing one gauge's FOPR file
+#[derive(Debug, Clone, Serialize)]
+pub struct FoprStationResult {
+    pub station_id: String,
+    pub readings_imported: Option<i64>,
+    /// Stored readings overwritten (`--on-conflict update`)
+    pub readings_updated: i64,
+    pub error: Option<String>,
+}
+
+/// Summary of FOPR imports
+#[derive(Debug, Clone, Serialize)]
+pub struct FoprImportReport {
+    pub stations: Vec<FoprStationResult>,
+}
+
+impl FoprImportReport {
+    pub fn failed(&self) -> usize {
+        self.stations.iter().filter(|s| s.error.is_some()).count()
+    }
+}
+
+impl fmt::Display for FoprImportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for station in &self.stations {
+            match (&station.readings_imported, &station.error) {
+                (_, Some(error)) => writeln!(f, "✗ {}: {}", station.station_id, error)?,
+                (Some(count), None) if station.readings_updated > 0 => writeln!(
+                    f,
+                    "✓ {}: {} readings imported, {} updated",
+                    station.station_id, count, station.readings_updated
+                )?,
+                (Some(count), None) => {
+                    writeln!(f, "✓ {}: {} readings imported", station.station_id, count)?
+                }
+                (None, None) => writeln!(f, "? {}", station.station_id)?,
+            }
+        }
+        write!(
+            f,
+            "FOPR import finished: {} succeeded, {} failed",
+            self.stations.len() - self.failed(),
+            self.failed()
+        )
+    }
+}
+
+/// `source` of the progress events for a FOPR import run
+pub const FOPR_PROGRESS_SOURCE: &str = "fopr_import";
+
+/// Import FOPR files for each station, continuing past failures
+///
+/// Publishes a start event, an `inserting` event after each station, and a `completed`
+/// event, or `failed` when any station failed.
+pub async fn import_fopr(
+    service: &FoprImportService,
+    args: &FoprImportArgs,
+    json: bool,
+    progress: &ProgressReporter,
+) -> FoprImportReport {
+    let mut event = progress.start(FOPR_PROGRESS_SOURCE, None);
+    event.stations_total = args.station_ids.len();
+    progress.emit(&mut event).await;
+
+    let bar = output::progress_bar(args.station_ids.len() as u64, json);
+    let mut stations = Vec::with_capacity(args.station_ids.len());
+
+    for station_id in &args.station_ids {
+        bar.set_message(station_id.to_string());
+        let result = match service.import_fopr(station_id).await {
+            Ok(stats) => FoprStationResult {
+                station_id: station_id.to_string(),
+                readings_imported: Some(stats.readings_imported),
+                readings_updated: stats.readings_updated,
+                error: None,
+            },
+            Err(e) => FoprStationResult {
+                station_id: station_id.to_string(),
+                readings_imported: None,
+                readings_updated: 0,
+                error: Some(e.to_string()),
+            },
+        };
+        event.stations_processed += 1;
+        event.readings_inserted += result.readings_imported.unwrap_or(0) as usize;
+        progress.phase(&mut event, ImportPhase::Inserting).await;
+        stations.push(result);
+        bar.inc(1);
+    }
+    bar.finish_and_clear();
+
+    let report = FoprImportReport { stations };
+    let failed = report.failed();
+    if failed > 0 {
+        event.error = Some(format!(
+            "{failed} of {} stations failed",
+            report.stations.len()
+        ));
+        progress.phase(&mut event, ImportPhase::Failed).await;
+    } else {
+        progress.phase(&mut event, ImportPhase::Completed).await;
+    }
+    report
+}
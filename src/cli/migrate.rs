@@ -0,0 +1,126 @@
+// Migrate command: inspect and apply schema migrations under operator control
+
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::cli::output;
+use crate::cli::{CliResult, MigrateDownArgs, MigrateUpArgs};
+use crate::db::{DbPool, MigrationStatus};
+use crate::error::Error;
+
+/// Every migration in this build and whether the database has it
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationStatusReport {
+    pub backend: &'static str,
+    pub migrations: Vec<MigrationStatus>,
+}
+
+impl MigrationStatusReport {
+    pub fn pending(&self) -> usize {
+        self.migrations.iter().filter(|m| !m.applied).count()
+    }
+
+    /// Nothing pending and no applied migration modified since
+    pub fn is_current(&self) -> bool {
+        self.migrations
+            .iter()
+            .all(|m| m.applied && !m.checksum_mismatch)
+    }
+}
+
+impl fmt::Display for MigrationStatusReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Migrations ({}):", self.backend)?;
+        for m in &self.migrations {
+            let state = match (m.applied, m.checksum_mismatch) {
+                (true, true) => "modified",
+                (true, false) => "applied",
+                (false, _) => "pending",
+            };
+            let reversible = if m.reversible { " (reversible)" } else { "" };
+            writeln!(
+                f,
+                "  {}  {:<8}  {}{}",
+                m.version, state, m.description, reversible
+            )?;
+        }
+        write!(
+            f,
+            "{} applied, {} pending",
+            self.migrations.len() - self.pending(),
+            self.pending()
+        )
+    }
+}
+
+/// Migrations applied or reverted by `migrate up` / `migrate down`
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrateReport {
+    pub backend: &'static str,
+    /// "up" or "down"
+    pub direction: &'static str,
+    /// Versions in the order they ran
+    pub versions: Vec<i64>,
+}
+
+impl fmt::Display for MigrateReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.versions.is_empty() {
+            return write!(f, "Nothing to do; the {} schema is unchanged", self.backend);
+        }
+
+        let verb = if self.direction == "up" {
+            "Applied"
+        } else {
+            "Reverted"
+        };
+        writeln!(
+            f,
+            "✓ {} {} migration(s) on {}",
+            verb,
+            self.versions.len(),
+            self.backend
+        )?;
+        let versions: Vec<String> = self.versions.iter().map(|v| v.to_string()).collect();
+        write!(f, "  {}", versions.join(", "))
+    }
+}
+
+pub async fn status(pool: &DbPool) -> CliResult<MigrationStatusReport> {
+    Ok(MigrationStatusReport {
+        backend: pool.backend(),
+        migrations: pool.migration_status().await?,
+    })
+}
+
+pub async fn up(pool: &DbPool, args: &MigrateUpArgs, json: bool) -> CliResult<MigrateReport> {
+    match args.to {
+        Some(version) => output::status(json, format!("Applying migrations up to {version}...")),
+        None => output::status(json, "Applying pending migrations..."),
+    }
+
+    Ok(MigrateReport {
+        backend: pool.backend(),
+        direction: "up",
+        versions: pool.migrate_up(args.to).await?,
+    })
+}
+
+pub async fn down(pool: &DbPool, args: &MigrateDownArgs, json: bool) -> CliResult<MigrateReport> {
+    if !args.yes
+        && !output::confirm(&format!(
+            "Revert every migration after {}? Reverted tables and their data are dropped",
+            args.to
+        ))
+    {
+        return Err(Error::Cancelled("Migration"));
+    }
+
+    output::status(json, format!("Reverting migrations after {}...", args.to));
+    Ok(MigrateReport {
+        backend: pool.backend(),
+        direction: "down",
+        versions: pool.migrate_down(args.to).await?,
+    })
+}
@@ -0,0 +1,58 @@
+// Output helpers shared by CLI commands
+//
+// Human-readable reports and progress bars go to stdout/stderr respectively.
+// In `--json` mode progress bars are hidden and reports are printed as JSON.
+
+use std::fmt::Display;
+use std::io::{self, BufRead, Write};
+
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::Serialize;
+
+use crate::cli::CliResult;
+
+/// Print a command report as JSON or via its Display impl
+pub fn emit<T: Serialize + Display>(report: &T, json: bool) -> CliResult<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(report)?);
+    } else {
+        println!("{report}");
+    }
+    Ok(())
+}
+
+/// Progress bar on stderr, hidden in JSON mode
+pub fn progress_bar(len: u64, json: bool) -> ProgressBar {
+    if json {
+        return ProgressBar::hidden();
+    }
+
+    let bar = ProgressBar::new(len);
+    bar.set_style(
+        ProgressStyle::with_template("[{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len} {msg}")
+            .expect("valid progress template")
+            .progress_chars("██░"),
+    );
+    bar
+}
+
+/// Print a status line to stderr unless in JSON mode
+pub fn status(json: bool, message: impl Display) {
+    if !json {
+        eprintln!("{message}");
+    }
+}
+
+/// Ask a yes/no question on stderr and read the answer from stdin
+///
+/// Anything other than "y"/"yes" (including EOF) is treated as no.
+pub fn confirm(prompt: &str) -> bool {
+    eprint!("{prompt} [y/N] ");
+    let _ = io::stderr().flush();
+
+    let mut answer = String::new();
+    if io::stdin().lock().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
@@ -0,0 +1,106 @@
+// Probe commands: check which MCFCD files exist without downloading them
+
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::cli::output;
+use crate::cli::{CliResult, ProbeFoprArgs};
+use crate::error::Error;
+use crate::services::fopr_availability_service::{FoprAvailabilityService, ProbeOutcome};
+
+/// Summary of an FOPR availability probe
+#[derive(Debug, Clone, Serialize)]
+pub struct FoprProbeReport {
+    pub probed: usize,
+    pub available: usize,
+    /// Stations without an FOPR file; bulk imports skip these
+    pub unavailable: Vec<String>,
+    /// Stations whose availability or Last-Modified changed since the previous probe
+    pub changed: Vec<String>,
+    pub failed: Vec<ProbeOutcome>,
+    pub results: Vec<ProbeOutcome>,
+}
+
+impl fmt::Display for FoprProbeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.unavailable.is_empty() {
+            writeln!(f, "✗ No FOPR file: {}", self.unavailable.join(", "))?;
+        }
+        if !self.changed.is_empty() {
+            writeln!(f, "↻ Changed since last probe: {}", self.changed.join(", "))?;
+        }
+        for outcome in &self.failed {
+            writeln!(
+                f,
+                "? {}: {}",
+                outcome.station_id,
+                outcome.error.as_deref().unwrap_or("unknown error")
+            )?;
+        }
+        write!(
+            f,
+            "Probed {} gauges: {} available, {} unavailable, {} failed",
+            self.probed,
+            self.available,
+            self.unavailable.len(),
+            self.failed.len()
+        )
+    }
+}
+
+/// HEAD each station's FOPR file (every known station when none are given)
+pub async fn probe_fopr(
+    service: &FoprAvailabilityService,
+    args: &ProbeFoprArgs,
+    json: bool,
+) -> CliResult<FoprProbeReport> {
+    let station_ids = if args.station_ids.is_empty() {
+        service.known_station_ids().await?
+    } else {
+        args.station_ids.iter().map(ToString::to_string).collect()
+    };
+    if station_ids.is_empty() {
+        return Err(Error::Usage(
+            "No stations to probe; pass station IDs or scrape the gauge list first".to_string(),
+        ));
+    }
+
+    output::status(
+        json,
+        format!(
+            "Probing FOPR files for {} gauges at up to {} requests/s...",
+            station_ids.len(),
+            args.rate
+        ),
+    );
+    let bar = output::progress_bar(station_ids.len() as u64, json);
+    let results = service
+        .probe(&station_ids, |outcome| {
+            bar.set_message(outcome.station_id.clone());
+            bar.inc(1);
+        })
+        .await?;
+    bar.finish_and_clear();
+
+    Ok(FoprProbeReport {
+        probed: results.len(),
+        available: results.iter().filter(|r| r.available == Some(true)).count(),
+        unavailable: results
+            .iter()
+            .filter(|r| r.available == Some(false))
+            .map(|r| r.station_id.clone())
+            .collect(),
+        changed: results
+            .iter()
+            .filter(|r| r.changed)
+            .map(|r| r.station_id.clone())
+            .collect(),
+        failed: results
+            .iter()
+            .filter(|r| r.error.is_some())
+            .cloned()
+            .collect(),
+        results,
+    })
+}
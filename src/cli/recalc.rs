@@ -0,0 +1,85 @@
+// Recalc command: rebuild monthly summaries from raw readings
+
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::cli::output;
+use crate::cli::{CliResult, RecalcArgs};
+use crate::services::summary_service::{RecalcScope, RecalcStats, SummaryService};
+
+/// Summary of a summary recalculation
+#[derive(Debug, Clone, Serialize)]
+pub struct RecalcReport {
+    pub station_id: Option<String>,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    #[serde(flatten)]
+    pub stats: RecalcStats,
+}
+
+impl fmt::Display for RecalcReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let station = self.station_id.as_deref().unwrap_or("all stations");
+        let range = match (&self.start_date, &self.end_date) {
+            (None, None) => "all dates".to_string(),
+            (start, end) => format!(
+                "{} to {}",
+                start.as_deref().unwrap_or("beginning"),
+                end.as_deref().unwrap_or("today")
+            ),
+        };
+
+        write!(
+            f,
+            "✓ Recalculated {} monthly and {} hourly summaries for {station} ({range}) in {:.1}s, {} orphaned summaries deleted",
+            self.stats.months_recalculated,
+            self.stats.hours_recalculated,
+            self.stats.duration_secs,
+            self.stats.orphaned_summaries_deleted
+        )
+    }
+}
+
+/// Build the recalculation scope from CLI arguments
+pub fn scope_from_args(args: &RecalcArgs) -> RecalcScope {
+    if args.all {
+        return RecalcScope::default();
+    }
+
+    let station_id = args.station.as_ref().map(ToString::to_string);
+    match args.water_year {
+        Some(water_year) => RecalcScope::water_year(station_id, water_year),
+        None => RecalcScope {
+            station_id,
+            start_date: args.from,
+            end_date: args.to,
+        },
+    }
+}
+
+pub async fn recalc(
+    service: &SummaryService,
+    args: &RecalcArgs,
+    json: bool,
+) -> CliResult<RecalcReport> {
+    let scope = scope_from_args(args);
+
+    output::status(json, "Recalculating monthly summaries...");
+
+    let bar = output::progress_bar(0, json);
+    let stats = service
+        .recalculate(&scope, |done, total| {
+            bar.set_length(total as u64);
+            bar.set_position(done as u64);
+        })
+        .await?;
+    bar.finish();
+
+    Ok(RecalcReport {
+        station_id: scope.station_id,
+        start_date: scope.start_date.map(|d| d.to_string()),
+        end_date: scope.end_date.map(|d| d.to_string()),
+        stats,
+    })
+}
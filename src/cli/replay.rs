@@ -0,0 +1,158 @@
+// Replay command: re-parse archived upstream payloads with the current parsers
+
+use std::fmt;
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::cli::{output, CliResult, ReplayArgs};
+use crate::fetcher::RainGaugeFetcher;
+use crate::gauge_list_fetcher::GaugeListFetcher;
+use crate::payload_archive::{PayloadArchive, PayloadSource, StoredPayload};
+
+/// Outcome of parsing one archived payload
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayedPayload {
+    pub source: PayloadSource,
+    pub fetched_at: DateTime<Utc>,
+    pub sha256: String,
+    pub path: PathBuf,
+    /// Gauges or readings the parser produced
+    pub parsed: usize,
+    /// Readings the ingest guards would have quarantined
+    pub quarantined: usize,
+    pub error: Option<String>,
+    /// Parsed gauges or readings, with `--show-rows`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rows: Option<serde_json::Value>,
+}
+
+/// Replay result; the command exits non-zero when any payload fails to parse
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayReport {
+    pub payloads: Vec<ReplayedPayload>,
+}
+
+impl ReplayReport {
+    pub fn failed(&self) -> usize {
+        self.payloads.iter().filter(|p| p.error.is_some()).count()
+    }
+}
+
+impl fmt::Display for ReplayReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.payloads.is_empty() {
+            return write!(f, "No archived payloads match");
+        }
+
+        for p in &self.payloads {
+            let hash = &p.sha256[..12];
+            match &p.error {
+                Some(e) => writeln!(f, "✗ {} {} {hash}: {e}", p.fetched_at, p.source)?,
+                None => {
+                    let unit = match p.source {
+                        PayloadSource::GaugeList => "gauges",
+                        PayloadSource::Readings => "readings",
+                    };
+                    write!(
+                        f,
+                        "✓ {} {} {hash}: {} {unit}",
+                        p.fetched_at, p.source, p.parsed
+                    )?;
+                    if p.quarantined > 0 {
+                        write!(f, " ({} quarantined)", p.quarantined)?;
+                    }
+                    writeln!(f)?;
+                }
+            }
+            if let Some(rows) = &p.rows {
+                writeln!(
+                    f,
+                    "{}",
+                    serde_json::to_string_pretty(rows).map_err(|_| fmt::Error)?
+                )?;
+            }
+        }
+        write!(
+            f,
+            "Replayed {} payloads, {} failed",
+            self.payloads.len(),
+            self.failed()
+        )
+    }
+}
+
+/// Parse each matching archived payload as the scrapers would
+pub async fn replay(args: &ReplayArgs, json: bool) -> CliResult<ReplayReport> {
+    let archive = PayloadArchive::new(&args.dir, 0);
+    let since = args
+        .since
+        .map(|d| d.and_hms_opt(0, 0, 0).unwrap().and_utc());
+    let mut stored = archive.list(args.source, since).await?;
+    if let Some(last) = args.last {
+        stored.drain(..stored.len().saturating_sub(last));
+    }
+    output::status(
+        json,
+        format!(
+            "Replaying {} payloads from {}",
+            stored.len(),
+            args.dir.display()
+        ),
+    );
+
+    let mut payloads = Vec::with_capacity(stored.len());
+    for payload in &stored {
+        payloads.push(replay_one(&archive, payload, args.show_rows).await);
+    }
+    Ok(ReplayReport { payloads })
+}
+
+async fn replay_one(
+    archive: &PayloadArchive,
+    payload: &StoredPayload,
+    show_rows: bool,
+) -> ReplayedPayload {
+    let meta = &payload.meta;
+    let mut replayed = ReplayedPayload {
+        source: meta.source,
+        fetched_at: meta.fetched_at,
+        sha256: meta.sha256.clone(),
+        path: payload.path.clone(),
+        parsed: 0,
+        quarantined: 0,
+        error: None,
+        rows: None,
+    };
+
+    let body = match archive.load(payload).await {
+        Ok(body) => body,
+        Err(e) => {
+            replayed.error = Some(e.to_string());
+            return replayed;
+        }
+    };
+
+    let rows = match meta.source {
+        PayloadSource::GaugeList => GaugeListFetcher::new(meta.url.clone())
+            .parse_body(meta.content_type.as_deref(), &body)
+            .map(|gauges| {
+                replayed.parsed = gauges.len();
+                serde_json::to_value(gauges)
+            }),
+        PayloadSource::Readings => RainGaugeFetcher::new(meta.url.clone())
+            .parse_html(&body)
+            .map(|screened| {
+                replayed.parsed = screened.accepted.len();
+                replayed.quarantined = screened.quarantined.len();
+                serde_json::to_value(screened.accepted)
+            }),
+    };
+    match rows {
+        Ok(rows) if show_rows => replayed.rows = rows.ok(),
+        Ok(_) => {}
+        Err(e) => replayed.error = Some(e.to_string()),
+    }
+    replayed
+}
@@ -0,0 +1,78 @@
+// Seed command: fill a development database with synthetic gauges and rainfall
+
+use std::fmt;
+use std::time::Instant;
+
+use serde::Serialize;
+
+use crate::cli::output;
+use crate::cli::{CliResult, SeedArgs};
+use crate::error::Error;
+use crate::services::seed_service::{SeedOptions, SeedService, SeedStats, SyntheticDataGenerator};
+
+/// Summary of a seed run
+#[derive(Debug, Clone, Serialize)]
+pub struct SeedReport {
+    /// RNG seed used; pass it to `--seed` to regenerate the same data
+    pub rng_seed: u64,
+    pub years: u32,
+    pub start_date: String,
+    pub end_date: String,
+    #[serde(flatten)]
+    pub stats: SeedStats,
+    pub duration_secs: f64,
+}
+
+impl fmt::Display for SeedReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "✓ Seeded {} synthetic gauges with {} readings ({} to {})",
+            self.stats.gauges_created, self.stats.readings_inserted, self.start_date, self.end_date
+        )?;
+        write!(
+            f,
+            "Seed completed in {:.1}s ({} duplicates skipped, {} station-months summarized, --seed {})",
+            self.duration_secs,
+            self.stats.duplicates_skipped,
+            self.stats.months_summarized,
+            self.rng_seed
+        )
+    }
+}
+
+pub async fn seed(service: &SeedService, args: &SeedArgs, json: bool) -> CliResult<SeedReport> {
+    let start = Instant::now();
+    let rng_seed = args.seed.unwrap_or_else(rand::random);
+    let options = SeedOptions::new(args.gauges, args.years, rng_seed);
+
+    if !args.yes
+        && !output::confirm(&format!(
+            "Insert {} synthetic gauges with {} water years of readings? Only do this on a development database.",
+            args.gauges, args.years
+        ))
+    {
+        return Err(Error::Cancelled("Seed"));
+    }
+
+    output::status(json, "Generating synthetic data...");
+    let gauges = SyntheticDataGenerator::new(options.clone()).generate();
+
+    output::status(json, format!("Inserting {} gauges...", gauges.len()));
+    let bar = output::progress_bar(gauges.len() as u64, json);
+    let mut stats = SeedStats::default();
+    for gauge in &gauges {
+        stats.add(&service.seed_gauge(gauge).await?);
+        bar.inc(1);
+    }
+    bar.finish();
+
+    Ok(SeedReport {
+        rng_seed,
+        years: args.years,
+        start_date: options.start_date().to_string(),
+        end_date: options.end_date.to_string(),
+        stats,
+        duration_secs: start.elapsed().as_secs_f64(),
+    })
+}
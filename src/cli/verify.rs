@@ -0,0 +1,77 @@
+// Verify command: compare monthly summaries against raw readings
+
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::cli::{CliResult, VerifyArgs};
+use crate::db::SummaryDiscrepancy;
+use crate::services::historical_import_service::HistoricalImportService;
+
+/// Verification result; the command exits non-zero when discrepancies exist
+#[derive(Debug, Clone, Serialize)]
+pub struct VerifyReport {
+    pub water_year: i32,
+    pub station_id: Option<String>,
+    pub discrepancies: Vec<SummaryDiscrepancy>,
+}
+
+impl fmt::Display for VerifyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scope = self
+            .station_id
+            .as_deref()
+            .map(|id| format!("station {id}"))
+            .unwrap_or_else(|| "all stations".to_string());
+
+        if self.discrepancies.is_empty() {
+            return write!(
+                f,
+                "✓ Monthly summaries match readings for water year {} ({scope})",
+                self.water_year
+            );
+        }
+
+        writeln!(
+            f,
+            "✗ {} discrepancies for water year {} ({scope}):",
+            self.discrepancies.len(),
+            self.water_year
+        )?;
+        for d in &self.discrepancies {
+            writeln!(
+                f,
+                "  {} {}-{:02}: summary {} in / {} readings, actual {} in / {} readings",
+                d.station_id,
+                d.year,
+                d.month,
+                fmt_opt(d.summary_total_inches),
+                fmt_opt(d.summary_reading_count),
+                fmt_opt(d.readings_total_inches),
+                fmt_opt(d.actual_reading_count),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn fmt_opt<T: fmt::Display>(value: Option<T>) -> String {
+    value
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+pub async fn verify(
+    service: &HistoricalImportService,
+    args: &VerifyArgs,
+) -> CliResult<VerifyReport> {
+    let discrepancies = service
+        .verify_water_year(args.station.as_deref(), args.water_year)
+        .await?;
+
+    Ok(VerifyReport {
+        water_year: args.water_year,
+        station_id: args.station.as_ref().map(ToString::to_string),
+        discrepancies,
+    })
+}
@@ -0,0 +1,89 @@
+// Zones command: import forecast zone polygons and assign gauges to them
+
+use std::fmt;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::cli::{CliResult, ZonesImportArgs};
+use crate::error::Error;
+use crate::services::zone_service::{ZoneAssignment, ZoneImportReport, ZoneService};
+
+/// Summary of a zone import
+#[derive(Debug, Clone, Serialize)]
+pub struct ZonesImportReport {
+    pub file: PathBuf,
+    #[serde(flatten)]
+    pub import: ZoneImportReport,
+}
+
+impl fmt::Display for ZonesImportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "✓ Imported {} forecast zones from {} ({} removed)",
+            self.import.zones_imported,
+            self.file.display(),
+            self.import.zones_removed
+        )?;
+        write!(f, "{}", ZonesAssignReport::from(&self.import.assignment))
+    }
+}
+
+/// Summary of a gauge reassignment
+#[derive(Debug, Clone, Serialize)]
+pub struct ZonesAssignReport {
+    #[serde(flatten)]
+    pub assignment: ZoneAssignment,
+}
+
+impl From<&ZoneAssignment> for ZonesAssignReport {
+    fn from(assignment: &ZoneAssignment) -> Self {
+        Self {
+            assignment: assignment.clone(),
+        }
+    }
+}
+
+impl fmt::Display for ZonesAssignReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let assignment = &self.assignment;
+        write!(
+            f,
+            "✓ {} of {} gauges with coordinates are inside one of {} zones ({} reassigned)",
+            assignment.gauges_in_zones,
+            assignment.gauges_located,
+            assignment.zones,
+            assignment.gauges_changed
+        )
+    }
+}
+
+/// Replace the stored zones with a GeoJSON file and reassign gauges
+pub async fn import(service: &ZoneService, args: &ZonesImportArgs) -> CliResult<ZonesImportReport> {
+    let geojson = std::fs::read_to_string(&args.file)?;
+    let source = args
+        .file
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let import = service
+        .import_geojson(&geojson, &args.id_property, &args.name_property, &source)
+        .await?;
+    Ok(ZonesImportReport {
+        file: args.file.clone(),
+        import,
+    })
+}
+
+/// Reassign every gauge to the zone containing it
+pub async fn assign(service: &ZoneService) -> CliResult<ZonesAssignReport> {
+    let assignment = service.assign_gauges().await?;
+    if assignment.zones == 0 {
+        return Err(Error::Usage(
+            "No forecast zones imported; run `zones import` first".to_string(),
+        ));
+    }
+    Ok(ZonesAssignReport { assignment })
+}
@@ -0,0 +1,55 @@
+// Time source for "current" calculations
+//
+// The current water year, partial months, and ranking windows all depend on the time.
+// Services and schedulers read it through a Clock instead of calling Utc::now(), so
+// tests can pin the date (e.g. across the Sep 30 -> Oct 1 water year rollover).
+
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+
+pub trait Clock: Debug + Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Shared handle, cloned into each service and scheduler that reads the time
+pub type SharedClock = Arc<dyn Clock>;
+
+/// Wall-clock time
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Always reports the same instant
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+pub fn system_clock() -> SharedClock {
+    Arc::new(SystemClock)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_fixed_clock_is_pinned() {
+        let at = Utc.with_ymd_and_hms(2024, 9, 30, 23, 59, 59).unwrap();
+        let clock: SharedClock = Arc::new(FixedClock(at));
+        assert_eq!(clock.now(), at);
+        assert_eq!(clock.clone().now(), at);
+    }
+}
@@ -1,15 +1,67 @@
+// Without the `sqlite` feature the repositories' backend `match`es have a single arm
+#![cfg_attr(not(feature = "sqlite"), allow(clippy::infallible_destructuring_match))]
+
+pub mod annotation_repository;
+pub mod anomaly_repository;
+pub mod attachment_repository;
+pub mod backup_repository;
+pub mod current_conditions_repository;
+pub mod daily_weather_repository;
+pub mod digest_repository;
 pub mod error;
+pub mod fopr_availability_repository;
 pub mod fopr_import_job_repository;
+pub mod forecast_zone_repository;
 pub mod gauge_repository;
+pub mod hourly_rainfall_repository;
+pub mod idempotency_repository;
+pub mod import_chunk_repository;
+pub mod job_run_repository;
+pub mod leader_lock;
+pub mod migrations;
 pub mod models;
 pub mod monthly_rainfall_repository;
 pub mod pool;
+pub mod quarantine_repository;
+pub mod radar_estimate_repository;
 pub mod reading_repository;
+pub mod share_repository;
+pub mod slow_query;
+pub mod slow_query_repository;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod summary_view_repository;
+pub mod threshold_event_repository;
+pub mod usage_repository;
+pub mod user_repository;
 
+pub use annotation_repository::AnnotationRepository;
+pub use anomaly_repository::AnomalyRepository;
+pub use attachment_repository::AttachmentRepository;
+pub use backup_repository::BackupRepository;
+pub use current_conditions_repository::CurrentConditionsRepository;
+pub use daily_weather_repository::DailyWeatherRepository;
+pub use digest_repository::DigestRepository;
 pub use error::DbError;
+pub use fopr_availability_repository::FoprAvailabilityRepository;
 pub use fopr_import_job_repository::FoprImportJobRepository;
+pub use forecast_zone_repository::ForecastZoneRepository;
 pub use gauge_repository::GaugeRepository;
+pub use hourly_rainfall_repository::HourlyRainfallRepository;
+pub use idempotency_repository::IdempotencyRepository;
+pub use import_chunk_repository::{ConflictPolicy, ImportChunkRepository};
+pub use job_run_repository::{JobRunRepository, NewJobRun};
+pub use migrations::MigrationStatus;
 pub use models::*;
 pub use monthly_rainfall_repository::MonthlyRainfallRepository;
-pub use pool::DbPool;
+pub use pool::{ConnectRetry, DbPool, PoolStats};
+pub use quarantine_repository::QuarantineRepository;
+pub use radar_estimate_repository::RadarEstimateRepository;
 pub use reading_repository::ReadingRepository;
+pub use share_repository::ShareRepository;
+pub use slow_query::{SlowQueryConfig, SlowQueryLog};
+pub use slow_query_repository::SlowQueryRepository;
+pub use summary_view_repository::SummaryViewRepository;
+pub use threshold_event_repository::ThresholdEventRepository;
+pub use usage_repository::{MonthlyUsage, UsageRepository};
+pub use user_repository::UserRepository;
@@ -0,0 +1,164 @@
+use chrono::NaiveDate;
+use tracing::{debug, instrument};
+
+#[cfg(feature = "sqlite")]
+use crate::db::sqlite;
+use crate::db::{DbError, DbPool, GaugeAnnotation};
+
+#[derive(Clone)]
+pub struct AnnotationRepository {
+    db: DbPool,
+}
+
+impl AnnotationRepository {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        Self { db: pool.into() }
+    }
+
+    /// Whether the gauge has a metadata row (annotations reference `gauges`)
+    #[instrument(skip(self))]
+    pub async fn gauge_exists(&self, station_id: &str) -> Result<bool, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::annotations::gauge_exists(pool, station_id).await
+            }
+        };
+        let exists = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM gauges WHERE station_id = $1) AS "exists!""#,
+            station_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(exists)
+    }
+
+    /// Record an annotation; None when the gauge does not exist
+    #[instrument(skip(self, note))]
+    pub async fn insert(
+        &self,
+        station_id: &str,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+        note: &str,
+        author: &str,
+    ) -> Result<Option<GaugeAnnotation>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::annotations::insert(
+                    pool, station_id, start_date, end_date, note, author,
+                )
+                .await
+            }
+        };
+        let inserted = sqlx::query_as!(
+            GaugeAnnotation,
+            r#"
+            INSERT INTO gauge_annotations (station_id, start_date, end_date, note, author)
+            SELECT g.station_id, $2, $3, $4, $5
+            FROM gauges g
+            WHERE g.station_id = $1
+            RETURNING id, station_id, start_date, end_date, note, author, created_at
+            "#,
+            station_id,
+            start_date,
+            end_date,
+            note,
+            author
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        if let Some(ref row) = inserted {
+            debug!("Recorded annotation {} for gauge {}", row.id, station_id);
+        }
+        Ok(inserted)
+    }
+
+    /// All annotations for a gauge, newest first
+    #[instrument(skip(self))]
+    pub async fn find_by_station(&self, station_id: &str) -> Result<Vec<GaugeAnnotation>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::annotations::find_by_station(pool, station_id).await
+            }
+        };
+        let annotations = sqlx::query_as!(
+            GaugeAnnotation,
+            r#"
+            SELECT id, station_id, start_date, end_date, note, author, created_at
+            FROM gauge_annotations
+            WHERE station_id = $1
+            ORDER BY created_at DESC, id DESC
+            "#,
+            station_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(annotations)
+    }
+
+    /// Annotations overlapping `first..=last`, including whole-gauge notes
+    ///
+    /// Ordered by start date, whole-gauge notes first.
+    #[instrument(skip(self))]
+    pub async fn find_overlapping(
+        &self,
+        station_id: &str,
+        first: NaiveDate,
+        last: NaiveDate,
+    ) -> Result<Vec<GaugeAnnotation>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::annotations::find_overlapping(pool, station_id, first, last).await
+            }
+        };
+        let annotations = sqlx::query_as!(
+            GaugeAnnotation,
+            r#"
+            SELECT id, station_id, start_date, end_date, note, author, created_at
+            FROM gauge_annotations
+            WHERE station_id = $1
+              AND (start_date IS NULL
+                   OR (start_date <= $3 AND (end_date IS NULL OR end_date >= $2)))
+            ORDER BY start_date NULLS FIRST, id
+            "#,
+            station_id,
+            first,
+            last
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(annotations)
+    }
+
+    /// Delete an annotation; false when no such annotation exists for the gauge
+    #[instrument(skip(self))]
+    pub async fn delete(&self, station_id: &str, id: i64) -> Result<bool, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => return sqlite::annotations::delete(pool, station_id, id).await,
+        };
+        let deleted = sqlx::query!(
+            "DELETE FROM gauge_annotations WHERE station_id = $1 AND id = $2",
+            station_id,
+            id
+        )
+        .execute(pool)
+        .await?
+        .rows_affected();
+
+        Ok(deleted > 0)
+    }
+}
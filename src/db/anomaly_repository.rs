@@ -0,0 +1,330 @@
+use chrono::{DateTime, Utc};
+use tracing::{debug, instrument};
+
+use crate::anomaly::{
+    reset_finding, spike_finding, AnomalyFinding, AnomalyKind, AnomalyStatus, DryRun,
+};
+use crate::db::{DbError, DbPool, GaugeAnomaly};
+
+/// Suspected gauge malfunctions and the readings they are found in; PostgreSQL only
+#[derive(Clone)]
+pub struct AnomalyRepository {
+    db: DbPool,
+}
+
+/// A gauge_anomalies row before its kind and status are parsed
+struct AnomalyRow {
+    id: i64,
+    station_id: String,
+    station_name: Option<String>,
+    kind: String,
+    started_at: DateTime<Utc>,
+    ended_at: Option<DateTime<Utc>>,
+    magnitude_inches: f64,
+    detail: String,
+    status: String,
+    review_note: Option<String>,
+    reviewed_by: Option<String>,
+    reviewed_at: Option<DateTime<Utc>>,
+    first_detected_at: DateTime<Utc>,
+    last_detected_at: DateTime<Utc>,
+}
+
+impl From<AnomalyRow> for GaugeAnomaly {
+    fn from(row: AnomalyRow) -> Self {
+        Self {
+            id: row.id,
+            station_id: row.station_id,
+            station_name: row.station_name,
+            // The columns' CHECK constraints only admit known values
+            kind: AnomalyKind::parse(&row.kind).unwrap_or(AnomalyKind::Spike),
+            started_at: row.started_at,
+            ended_at: row.ended_at,
+            magnitude_inches: row.magnitude_inches,
+            detail: row.detail,
+            status: AnomalyStatus::parse(&row.status).unwrap_or(AnomalyStatus::Open),
+            review_note: row.review_note,
+            reviewed_by: row.reviewed_by,
+            reviewed_at: row.reviewed_at,
+            first_detected_at: row.first_detected_at,
+            last_detected_at: row.last_detected_at,
+        }
+    }
+}
+
+impl AnomalyRepository {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        Self { db: pool.into() }
+    }
+
+    /// Located gauges still reporting at `reporting_since` whose readings have all been
+    /// zero for at least `min_days`
+    #[instrument(skip(self))]
+    pub async fn find_dry_runs(
+        &self,
+        reporting_since: DateTime<Utc>,
+        min_days: i32,
+    ) -> Result<Vec<DryRun>, DbError> {
+        let runs = sqlx::query_as!(
+            DryRun,
+            r#"
+            SELECT g.station_id,
+                   g.latitude::FLOAT8 AS "latitude!",
+                   g.longitude::FLOAT8 AS "longitude!",
+                   d.dry_since AS "dry_since!",
+                   l.last_reading AS "last_reading!"
+            FROM gauges g
+            CROSS JOIN LATERAL (
+                SELECT MAX(reading_datetime) AS last_reading
+                FROM rain_readings r
+                WHERE r.station_id = g.station_id
+            ) l
+            LEFT JOIN LATERAL (
+                SELECT reading_datetime AS at
+                FROM rain_readings r
+                WHERE r.station_id = g.station_id AND r.incremental_inches > 0
+                ORDER BY reading_datetime DESC
+                LIMIT 1
+            ) w ON TRUE
+            CROSS JOIN LATERAL (
+                SELECT MIN(reading_datetime) AS dry_since
+                FROM rain_readings r
+                WHERE r.station_id = g.station_id
+                  AND (w.at IS NULL OR r.reading_datetime > w.at)
+            ) d
+            WHERE g.latitude IS NOT NULL AND g.longitude IS NOT NULL
+              AND l.last_reading >= $1
+              AND d.dry_since <= l.last_reading - make_interval(days => $2)
+            ORDER BY g.station_id
+            "#,
+            reporting_since,
+            min_days
+        )
+        .fetch_all(self.db.postgres()?)
+        .await?;
+
+        Ok(runs)
+    }
+
+    /// Rainfall of each gauge with readings in `[start, end]`
+    pub async fn find_rainfall_totals(
+        &self,
+        station_ids: &[String],
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<f64>, DbError> {
+        let totals = sqlx::query_scalar!(
+            r#"
+            SELECT SUM(incremental_inches)::FLOAT8 AS "inches!"
+            FROM rain_readings
+            WHERE station_id = ANY($1) AND reading_datetime >= $2 AND reading_datetime <= $3
+            GROUP BY station_id
+            ORDER BY station_id
+            "#,
+            station_ids,
+            start,
+            end
+        )
+        .fetch_all(self.db.postgres()?)
+        .await?;
+
+        Ok(totals)
+    }
+
+    /// Readings since `since` of at least `min_inches`
+    #[instrument(skip(self))]
+    pub async fn find_spikes(
+        &self,
+        since: DateTime<Utc>,
+        min_inches: f64,
+    ) -> Result<Vec<AnomalyFinding>, DbError> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT station_id, reading_datetime, incremental_inches::FLOAT8 AS "inches!"
+            FROM rain_readings
+            WHERE reading_datetime >= $1 AND incremental_inches >= $2
+            ORDER BY station_id, reading_datetime
+            "#,
+            since,
+            min_inches
+        )
+        .fetch_all(self.db.postgres()?)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| spike_finding(row.station_id, row.reading_datetime, row.inches, min_inches))
+            .collect())
+    }
+
+    /// Readings since `since` whose cumulative total is below the previous reading's
+    /// from the same source and water year
+    #[instrument(skip(self))]
+    pub async fn find_resets(&self, since: DateTime<Utc>) -> Result<Vec<AnomalyFinding>, DbError> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT station_id AS "station_id!", reading_datetime AS "reading_datetime!",
+                   previous AS "previous!", cumulative AS "cumulative!"
+            FROM (
+                SELECT station_id, reading_datetime,
+                       cumulative_inches::FLOAT8 AS cumulative,
+                       LAG(cumulative_inches::FLOAT8) OVER (
+                           PARTITION BY station_id, data_source, water_year
+                           ORDER BY reading_datetime
+                       ) AS previous
+                FROM rain_readings
+                WHERE reading_datetime >= $1
+            ) r
+            WHERE cumulative < previous
+            ORDER BY station_id, reading_datetime
+            "#,
+            since
+        )
+        .fetch_all(self.db.postgres()?)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                reset_finding(
+                    row.station_id,
+                    row.reading_datetime,
+                    row.previous,
+                    row.cumulative,
+                )
+            })
+            .collect())
+    }
+
+    /// Record findings, extending ones already recorded; returns the new ones
+    ///
+    /// A re-detected finding keeps its review status.
+    #[instrument(skip(self, findings), fields(findings = findings.len()))]
+    pub async fn upsert_findings(
+        &self,
+        findings: &[AnomalyFinding],
+    ) -> Result<Vec<GaugeAnomaly>, DbError> {
+        let (mut ids, mut kinds, mut starts, mut ends, mut magnitudes, mut details) = (
+            Vec::new(),
+            Vec::new(),
+            Vec::new(),
+            Vec::new(),
+            Vec::new(),
+            Vec::new(),
+        );
+        for finding in findings {
+            ids.push(finding.station_id.clone());
+            kinds.push(finding.kind.as_str().to_string());
+            starts.push(finding.started_at);
+            ends.push(finding.ended_at);
+            magnitudes.push(finding.magnitude_inches);
+            details.push(finding.detail.clone());
+        }
+
+        let rows = sqlx::query_as!(
+            AnomalyRow,
+            r#"
+            WITH upserted AS (
+                INSERT INTO gauge_anomalies
+                    (station_id, kind, started_at, ended_at, magnitude_inches, detail)
+                SELECT * FROM UNNEST(
+                    $1::VARCHAR[], $2::VARCHAR[], $3::TIMESTAMPTZ[], $4::TIMESTAMPTZ[],
+                    $5::FLOAT8[], $6::TEXT[]
+                )
+                ON CONFLICT (station_id, kind, started_at) DO UPDATE SET
+                    ended_at = EXCLUDED.ended_at,
+                    magnitude_inches = EXCLUDED.magnitude_inches,
+                    detail = EXCLUDED.detail,
+                    last_detected_at = NOW()
+                RETURNING *, (xmax = 0) AS inserted
+            )
+            SELECT u.id, u.station_id, g.station_name, u.kind, u.started_at, u.ended_at,
+                   u.magnitude_inches, u.detail, u.status, u.review_note, u.reviewed_by,
+                   u.reviewed_at, u.first_detected_at, u.last_detected_at
+            FROM upserted u
+            JOIN gauges g ON g.station_id = u.station_id
+            WHERE u.inserted
+            ORDER BY u.station_id, u.started_at
+            "#,
+            &ids,
+            &kinds,
+            &starts,
+            &ends as &[Option<DateTime<Utc>>],
+            &magnitudes,
+            &details
+        )
+        .fetch_all(self.db.postgres()?)
+        .await?;
+
+        debug!("Recorded {} findings, {} new", findings.len(), rows.len());
+        Ok(rows.into_iter().map(GaugeAnomaly::from).collect())
+    }
+
+    /// Recorded anomalies matching every given filter, newest first
+    pub async fn find(
+        &self,
+        status: Option<AnomalyStatus>,
+        kind: Option<AnomalyKind>,
+        station_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<GaugeAnomaly>, DbError> {
+        let rows = sqlx::query_as!(
+            AnomalyRow,
+            r#"
+            SELECT a.id, a.station_id, g.station_name, a.kind, a.started_at, a.ended_at,
+                   a.magnitude_inches, a.detail, a.status, a.review_note, a.reviewed_by,
+                   a.reviewed_at, a.first_detected_at, a.last_detected_at
+            FROM gauge_anomalies a
+            JOIN gauges g ON g.station_id = a.station_id
+            WHERE ($1::VARCHAR IS NULL OR a.status = $1)
+              AND ($2::VARCHAR IS NULL OR a.kind = $2)
+              AND ($3::VARCHAR IS NULL OR a.station_id = $3)
+            ORDER BY a.first_detected_at DESC, a.id DESC
+            LIMIT $4
+            "#,
+            status.map(|s| s.as_str()),
+            kind.map(|k| k.as_str()),
+            station_id,
+            limit
+        )
+        .fetch_all(self.db.postgres()?)
+        .await?;
+
+        Ok(rows.into_iter().map(GaugeAnomaly::from).collect())
+    }
+
+    /// Record an admin's review; None when no such anomaly
+    #[instrument(skip(self, note))]
+    pub async fn review(
+        &self,
+        id: i64,
+        status: AnomalyStatus,
+        note: Option<&str>,
+        reviewed_by: &str,
+    ) -> Result<Option<GaugeAnomaly>, DbError> {
+        let row = sqlx::query_as!(
+            AnomalyRow,
+            r#"
+            WITH reviewed AS (
+                UPDATE gauge_anomalies
+                SET status = $2, review_note = $3, reviewed_by = $4, reviewed_at = NOW()
+                WHERE id = $1
+                RETURNING *
+            )
+            SELECT r.id, r.station_id, g.station_name, r.kind, r.started_at, r.ended_at,
+                   r.magnitude_inches, r.detail, r.status, r.review_note, r.reviewed_by,
+                   r.reviewed_at, r.first_detected_at, r.last_detected_at
+            FROM reviewed r
+            JOIN gauges g ON g.station_id = r.station_id
+            "#,
+            id,
+            status.as_str(),
+            note,
+            reviewed_by
+        )
+        .fetch_optional(self.db.postgres()?)
+        .await?;
+
+        Ok(row.map(GaugeAnomaly::from))
+    }
+}
@@ -0,0 +1,145 @@
+use tracing::{debug, instrument};
+
+#[cfg(feature = "sqlite")]
+use crate::db::sqlite;
+use crate::db::{DbError, DbPool, GaugeAttachment};
+
+/// Metadata for a new attachment (the contents are already in the object store)
+#[derive(Debug, Clone)]
+pub struct NewGaugeAttachment<'a> {
+    pub station_id: &'a str,
+    pub filename: &'a str,
+    pub content_type: &'a str,
+    pub size_bytes: i64,
+    pub sha256: &'a str,
+    pub storage_key: &'a str,
+    pub description: Option<&'a str>,
+}
+
+#[derive(Clone)]
+pub struct AttachmentRepository {
+    db: DbPool,
+}
+
+impl AttachmentRepository {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        Self { db: pool.into() }
+    }
+
+    /// Whether the gauge has a metadata row (attachments reference `gauges`)
+    #[instrument(skip(self))]
+    pub async fn gauge_exists(&self, station_id: &str) -> Result<bool, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::attachments::gauge_exists(pool, station_id).await
+            }
+        };
+        let exists = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM gauges WHERE station_id = $1) AS "exists!""#,
+            station_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(exists)
+    }
+
+    /// Record an attachment; None when the gauge does not exist
+    #[instrument(skip(self, attachment), fields(station_id = %attachment.station_id))]
+    pub async fn insert(
+        &self,
+        attachment: &NewGaugeAttachment<'_>,
+    ) -> Result<Option<GaugeAttachment>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => return sqlite::attachments::insert(pool, attachment).await,
+        };
+        let inserted = sqlx::query_as!(
+            GaugeAttachment,
+            r#"
+            INSERT INTO gauge_attachments
+                (station_id, filename, content_type, size_bytes, sha256, storage_key, description)
+            SELECT g.station_id, $2, $3, $4, $5, $6, $7
+            FROM gauges g
+            WHERE g.station_id = $1
+            RETURNING id, station_id, filename, content_type, size_bytes, sha256,
+                      storage_key, description, uploaded_at
+            "#,
+            attachment.station_id,
+            attachment.filename,
+            attachment.content_type,
+            attachment.size_bytes,
+            attachment.sha256,
+            attachment.storage_key,
+            attachment.description
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        if let Some(ref row) = inserted {
+            debug!(
+                "Recorded attachment {} for gauge {}",
+                row.id, row.station_id
+            );
+        }
+        Ok(inserted)
+    }
+
+    /// Attachments for a gauge, newest first
+    #[instrument(skip(self))]
+    pub async fn find_by_station(&self, station_id: &str) -> Result<Vec<GaugeAttachment>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::attachments::find_by_station(pool, station_id).await
+            }
+        };
+        let attachments = sqlx::query_as!(
+            GaugeAttachment,
+            r#"
+            SELECT id, station_id, filename, content_type, size_bytes, sha256,
+                   storage_key, description, uploaded_at
+            FROM gauge_attachments
+            WHERE station_id = $1
+            ORDER BY uploaded_at DESC, id DESC
+            "#,
+            station_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(attachments)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn find(
+        &self,
+        station_id: &str,
+        id: i64,
+    ) -> Result<Option<GaugeAttachment>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => return sqlite::attachments::find(pool, station_id, id).await,
+        };
+        let attachment = sqlx::query_as!(
+            GaugeAttachment,
+            r#"
+            SELECT id, station_id, filename, content_type, size_bytes, sha256,
+                   storage_key, description, uploaded_at
+            FROM gauge_attachments
+            WHERE station_id = $1 AND id = $2
+            "#,
+            station_id,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(attachment)
+    }
+}
@@ -0,0 +1,395 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use futures::TryStreamExt;
+use serde_json::{Map, Value};
+use tracing::instrument;
+
+#[cfg(feature = "sqlite")]
+use crate::db::sqlite;
+use crate::db::{DbError, DbPool};
+
+/// How a column's JSON value is normalized so backups are identical across backends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackupColumn {
+    /// Strings and numbers, stored as-is
+    Plain,
+    /// RFC 3339 in UTC ("2025-01-01T00:00:00+00:00")
+    Timestamp,
+    /// true/false (SQLite stores 1/0)
+    Bool,
+    /// Arrays and objects (SQLite stores them as JSON text)
+    Json,
+}
+
+/// A table included in backups; surrogate ids are left out so rows restore by natural key
+#[derive(Debug)]
+pub struct BackupTable {
+    pub name: &'static str,
+    pub columns: &'static [(&'static str, BackupColumn)],
+    /// Sort order for deterministic output
+    pub order_by: &'static str,
+    /// Only exists in the PostgreSQL schema
+    pub postgres_only: bool,
+}
+
+use BackupColumn::{Bool, Json, Plain, Timestamp};
+
+/// Tables in backup (and restore) order: gauges before the tables that reference them
+pub const BACKUP_TABLES: &[BackupTable] = &[
+    BackupTable {
+        name: "gauges",
+        columns: &[
+            ("station_id", Plain),
+            ("station_name", Plain),
+            ("station_type", Plain),
+            ("previous_station_ids", Json),
+            ("latitude", Plain),
+            ("longitude", Plain),
+            ("elevation_ft", Plain),
+            ("county", Plain),
+            ("city", Plain),
+            ("location_description", Plain),
+            ("installation_date", Plain),
+            ("data_begins_date", Plain),
+            ("data_ends_date", Plain),
+            ("status", Plain),
+            ("status_effective_date", Plain),
+            ("avg_annual_precipitation_inches", Plain),
+            ("complete_years_count", Plain),
+            ("incomplete_months_count", Plain),
+            ("missing_months_count", Plain),
+            ("data_quality_remarks", Plain),
+            ("fopr_metadata", Json),
+            ("metadata_source", Plain),
+            ("metadata_updated_at", Timestamp),
+            ("created_at", Timestamp),
+            ("fopr_available", Bool),
+            ("fopr_last_import_date", Plain),
+            ("fopr_last_checked_date", Plain),
+            ("nearest_place", Plain),
+            ("geocode_source", Plain),
+            ("geocoded_at", Timestamp),
+            ("elevation_source", Plain),
+            ("elevation_sampled_at", Timestamp),
+        ],
+        order_by: "station_id",
+        postgres_only: false,
+    },
+    BackupTable {
+        name: "gauge_summaries",
+        columns: &[
+            ("station_id", Plain),
+            ("gauge_name", Plain),
+            ("city_town", Plain),
+            ("elevation_ft", Plain),
+            ("general_location", Plain),
+            ("msp_forecast_zone", Plain),
+            ("rainfall_past_6h_inches", Plain),
+            ("rainfall_past_24h_inches", Plain),
+            ("last_scraped_at", Timestamp),
+            ("created_at", Timestamp),
+            ("updated_at", Timestamp),
+        ],
+        order_by: "station_id",
+        postgres_only: false,
+    },
+    BackupTable {
+        name: "rain_readings",
+        columns: &[
+            ("station_id", Plain),
+            ("reading_datetime", Timestamp),
+            ("cumulative_inches", Plain),
+            ("incremental_inches", Plain),
+            ("data_source", Plain),
+            ("import_metadata", Json),
+            ("created_at", Timestamp),
+        ],
+        order_by: "station_id, reading_datetime",
+        postgres_only: false,
+    },
+    BackupTable {
+        name: "monthly_rainfall_summary",
+        columns: &[
+            ("station_id", Plain),
+            ("year", Plain),
+            ("month", Plain),
+            ("total_rainfall_inches", Plain),
+            ("reading_count", Plain),
+            ("first_reading_date", Timestamp),
+            ("last_reading_date", Timestamp),
+            ("min_cumulative_inches", Plain),
+            ("max_cumulative_inches", Plain),
+            ("flagged_count", Plain),
+            ("estimated_count", Plain),
+            ("footnoted_count", Plain),
+            ("created_at", Timestamp),
+            ("updated_at", Timestamp),
+        ],
+        order_by: "station_id, year, month",
+        postgres_only: false,
+    },
+    BackupTable {
+        name: "hourly_rainfall_summary",
+        columns: &[
+            ("station_id", Plain),
+            ("hour_start", Timestamp),
+            ("total_rainfall_inches", Plain),
+            ("max_incremental_inches", Plain),
+            ("reading_count", Plain),
+            ("updated_at", Timestamp),
+        ],
+        order_by: "station_id, hour_start",
+        postgres_only: false,
+    },
+    BackupTable {
+        name: "daily_rainfall_intensity",
+        columns: &[
+            ("station_id", Plain),
+            ("date", Plain),
+            ("max_15min_intensity_in_per_hr", Plain),
+            ("max_1h_intensity_in_per_hr", Plain),
+            ("updated_at", Timestamp),
+        ],
+        order_by: "station_id, date",
+        postgres_only: false,
+    },
+    BackupTable {
+        name: "fopr_import_jobs",
+        columns: &[
+            ("station_id", Plain),
+            ("status", Plain),
+            ("priority", Plain),
+            ("created_at", Timestamp),
+            ("started_at", Timestamp),
+            ("completed_at", Timestamp),
+            ("error_message", Plain),
+            ("error_history", Json),
+            ("retry_count", Plain),
+            ("max_retries", Plain),
+            ("next_retry_at", Timestamp),
+            ("source", Plain),
+            ("gauge_summary", Json),
+            ("import_stats", Json),
+        ],
+        order_by: "created_at, station_id",
+        postgres_only: true,
+    },
+    // gauges.forecast_zone is left out of the gauges columns: it references this table,
+    // and `zones assign` (or the reconciliation job) recomputes it from coordinates
+    BackupTable {
+        name: "forecast_zones",
+        columns: &[
+            ("zone_id", Plain),
+            ("name", Plain),
+            ("geometry", Json),
+            ("min_latitude", Plain),
+            ("max_latitude", Plain),
+            ("min_longitude", Plain),
+            ("max_longitude", Plain),
+            ("source", Plain),
+            ("imported_at", Timestamp),
+        ],
+        order_by: "zone_id",
+        postgres_only: false,
+    },
+];
+
+impl BackupTable {
+    pub fn column_list(&self) -> String {
+        self.columns
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Normalize a row read from either backend to the backup representation
+    pub fn normalize_row(&self, row: &mut Map<String, Value>) {
+        for (name, column) in self.columns {
+            let Some(value) = row.get_mut(*name) else {
+                continue;
+            };
+            match (column, &*value) {
+                (Timestamp, Value::String(s)) => {
+                    if let Ok(parsed) = DateTime::parse_from_rfc3339(s) {
+                        *value = Value::String(
+                            parsed
+                                .with_timezone(&Utc)
+                                .to_rfc3339_opts(SecondsFormat::AutoSi, false),
+                        );
+                    }
+                }
+                (Bool, Value::Number(n)) => *value = Value::Bool(n.as_i64() != Some(0)),
+                (Json, Value::String(s)) => {
+                    if let Ok(parsed) = serde_json::from_str(s) {
+                        *value = parsed;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Bulk export and import of whole tables for the backup and restore commands
+#[derive(Clone)]
+pub struct BackupRepository {
+    db: DbPool,
+}
+
+impl BackupRepository {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        Self { db: pool.into() }
+    }
+
+    pub fn backend(&self) -> &'static str {
+        self.db.backend()
+    }
+
+    /// Whether `table` exists on this backend
+    pub fn supports(&self, table: &BackupTable) -> bool {
+        !table.postgres_only || self.db.postgres().is_ok()
+    }
+
+    /// Latest successfully applied migration version
+    pub async fn schema_version(&self) -> Result<Option<i64>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => return sqlite::backup::schema_version(pool).await,
+        };
+        let version = sqlx::query_scalar("SELECT MAX(version) FROM _sqlx_migrations WHERE success")
+            .fetch_one(pool)
+            .await?;
+
+        Ok(version)
+    }
+
+    pub async fn count_rows(&self, table: &BackupTable) -> Result<i64, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => return sqlite::backup::count_rows(pool, table).await,
+        };
+        let count = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table.name))
+            .fetch_one(pool)
+            .await?;
+
+        Ok(count)
+    }
+
+    /// Call `on_row` with each row of `table` as normalized JSON, in `order_by` order
+    ///
+    /// Rows are streamed from a cursor, so tables larger than memory can be exported.
+    #[instrument(skip(self, table, on_row), fields(table = table.name))]
+    pub async fn for_each_row<F, E>(&self, table: &BackupTable, mut on_row: F) -> Result<u64, E>
+    where
+        F: FnMut(Map<String, Value>) -> Result<(), E>,
+        E: From<DbError>,
+    {
+        let sql = match &self.db {
+            DbPool::Postgres(_) => format!(
+                "SELECT row_to_json(t)::text FROM (SELECT {} FROM {} ORDER BY {}) t",
+                table.column_list(),
+                table.name,
+                table.order_by
+            ),
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(_) => sqlite::backup::export_sql(table),
+        };
+
+        let mut rows = match &self.db {
+            DbPool::Postgres(pool) => sqlx::query_scalar::<_, String>(&sql).fetch(pool),
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => sqlx::query_scalar::<_, String>(&sql).fetch(pool),
+        };
+
+        let mut count = 0;
+        while let Some(text) = rows.try_next().await.map_err(DbError::from)? {
+            let mut row: Map<String, Value> = serde_json::from_str(&text)
+                .map_err(|e| DbError::from(sqlx::Error::Decode(Box::new(e))))?;
+            table.normalize_row(&mut row);
+            on_row(row)?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    /// Insert backup rows into `table`, skipping rows whose natural key already exists
+    ///
+    /// Returns the number of rows inserted.
+    #[instrument(skip(self, table, rows), fields(table = table.name, count = rows.len()))]
+    pub async fn insert_rows(
+        &self,
+        table: &BackupTable,
+        rows: &[Map<String, Value>],
+    ) -> Result<u64, DbError> {
+        let json = Value::Array(rows.iter().cloned().map(Value::Object).collect()).to_string();
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => return sqlite::backup::insert_rows(pool, table, &json).await,
+        };
+        let columns = table.column_list();
+        let sql = format!(
+            r#"
+            INSERT INTO {table} ({columns})
+            SELECT {columns} FROM json_populate_recordset(NULL::{table}, $1::json)
+            ON CONFLICT DO NOTHING
+            "#,
+            table = table.name,
+        );
+        let inserted = sqlx::query(&sql)
+            .bind(json)
+            .execute(pool)
+            .await?
+            .rows_affected();
+
+        Ok(inserted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn table(name: &str) -> &'static BackupTable {
+        BACKUP_TABLES.iter().find(|t| t.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_normalize_row_matches_across_backends() {
+        // As returned by PostgreSQL row_to_json in a non-UTC session
+        let mut postgres = json!({
+            "station_id": "59700",
+            "previous_station_ids": ["4695"],
+            "fopr_available": true,
+            "created_at": "2024-11-02T23:00:00.5-07:00",
+        });
+        // As returned by SQLite json_object
+        let mut sqlite = json!({
+            "station_id": "59700",
+            "previous_station_ids": "[\"4695\"]",
+            "fopr_available": 1,
+            "created_at": "2024-11-03T06:00:00.500+00:00",
+        });
+
+        let gauges = table("gauges");
+        gauges.normalize_row(postgres.as_object_mut().unwrap());
+        gauges.normalize_row(sqlite.as_object_mut().unwrap());
+
+        assert_eq!(postgres, sqlite);
+        assert_eq!(postgres["created_at"], "2024-11-03T06:00:00.500+00:00");
+    }
+
+    #[test]
+    fn test_normalize_row_keeps_whole_second_timestamps_short() {
+        let mut row =
+            json!({ "reading_datetime": "2024-11-03T06:00:00Z", "import_metadata": null });
+        table("rain_readings").normalize_row(row.as_object_mut().unwrap());
+
+        assert_eq!(row["reading_datetime"], "2024-11-03T06:00:00+00:00");
+        assert_eq!(row["import_metadata"], Value::Null);
+    }
+}
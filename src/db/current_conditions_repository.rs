@@ -0,0 +1,171 @@
+use chrono::{DateTime, Utc};
+use tracing::{debug, instrument};
+
+#[cfg(feature = "sqlite")]
+use crate::db::sqlite;
+use crate::db::{CurrentCondition, DbError, DbPool};
+
+#[derive(Clone)]
+pub struct CurrentConditionsRepository {
+    db: DbPool,
+}
+
+impl CurrentConditionsRepository {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        Self { db: pool.into() }
+    }
+
+    /// Rebuild every row from the source tables in one transaction
+    ///
+    /// Water-year-to-date totals sum the monthly summaries from October of
+    /// `water_year - 1` through September of `water_year`. Rows for gauges no longer in
+    /// the gauge list are removed. Returns the number of rows written.
+    #[instrument(skip(self))]
+    pub async fn refresh(
+        &self,
+        water_year: i32,
+        refreshed_at: DateTime<Utc>,
+    ) -> Result<u64, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::current_conditions::refresh(pool, water_year, refreshed_at).await
+            }
+        };
+        let mut tx = pool.begin().await?;
+
+        let written = sqlx::query!(
+            r#"
+            INSERT INTO current_conditions (
+                station_id, gauge_name, city_town, status, latitude, longitude, elevation_ft,
+                rainfall_past_6h_inches, rainfall_past_24h_inches,
+                latest_reading_at, latest_cumulative_inches,
+                water_year, water_year_to_date_inches, last_scraped_at, refreshed_at
+            )
+            SELECT s.station_id,
+                   s.gauge_name,
+                   COALESCE(s.city_town, g.city),
+                   COALESCE(g.status, 'Active'),
+                   g.latitude::FLOAT8,
+                   g.longitude::FLOAT8,
+                   COALESCE(s.elevation_ft, g.elevation_ft),
+                   s.rainfall_past_6h_inches,
+                   s.rainfall_past_24h_inches,
+                   r.reading_datetime,
+                   r.cumulative_inches,
+                   $1,
+                   COALESCE(m.total, 0),
+                   s.last_scraped_at,
+                   $2
+            FROM gauge_summaries s
+            LEFT JOIN gauges g ON g.station_id = s.station_id
+            LEFT JOIN LATERAL (
+                SELECT reading_datetime, cumulative_inches
+                FROM rain_readings
+                WHERE station_id = s.station_id
+                ORDER BY reading_datetime DESC
+                LIMIT 1
+            ) r ON TRUE
+            LEFT JOIN LATERAL (
+                SELECT SUM(total_rainfall_inches) AS total
+                FROM monthly_rainfall_summary
+                WHERE station_id = s.station_id
+                  AND month_start >= make_date($1 - 1, 10, 1)
+                  AND month_start < make_date($1, 10, 1)
+            ) m ON TRUE
+            ON CONFLICT (station_id) DO UPDATE SET
+                gauge_name = EXCLUDED.gauge_name,
+                city_town = EXCLUDED.city_town,
+                status = EXCLUDED.status,
+                latitude = EXCLUDED.latitude,
+                longitude = EXCLUDED.longitude,
+                elevation_ft = EXCLUDED.elevation_ft,
+                rainfall_past_6h_inches = EXCLUDED.rainfall_past_6h_inches,
+                rainfall_past_24h_inches = EXCLUDED.rainfall_past_24h_inches,
+                latest_reading_at = EXCLUDED.latest_reading_at,
+                latest_cumulative_inches = EXCLUDED.latest_cumulative_inches,
+                water_year = EXCLUDED.water_year,
+                water_year_to_date_inches = EXCLUDED.water_year_to_date_inches,
+                last_scraped_at = EXCLUDED.last_scraped_at,
+                refreshed_at = EXCLUDED.refreshed_at
+            "#,
+            water_year,
+            refreshed_at
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        let removed = sqlx::query!(
+            "DELETE FROM current_conditions WHERE refreshed_at <> $1",
+            refreshed_at
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        tx.commit().await?;
+
+        debug!(
+            "Refreshed {} current condition rows ({} removed)",
+            written, removed
+        );
+        Ok(written)
+    }
+
+    /// Oldest refresh among rows with one of `statuses` (None when there are none)
+    #[instrument(skip(self))]
+    pub async fn find_refreshed_at(
+        &self,
+        statuses: &[String],
+    ) -> Result<Option<DateTime<Utc>>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::current_conditions::find_refreshed_at(pool, statuses).await
+            }
+        };
+        let refreshed_at = sqlx::query_scalar!(
+            "SELECT MIN(refreshed_at) FROM current_conditions WHERE status = ANY($1)",
+            statuses
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(refreshed_at)
+    }
+
+    /// Rows for gauges with one of `statuses`, in gauge list order
+    #[instrument(skip(self))]
+    pub async fn find_by_status(
+        &self,
+        statuses: &[String],
+    ) -> Result<Vec<CurrentCondition>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::current_conditions::find_by_status(pool, statuses).await
+            }
+        };
+        let rows = sqlx::query_as!(
+            CurrentCondition,
+            r#"
+            SELECT station_id, gauge_name, city_town, status, latitude, longitude, elevation_ft,
+                   rainfall_past_6h_inches, rainfall_past_24h_inches,
+                   latest_reading_at, latest_cumulative_inches,
+                   water_year, water_year_to_date_inches, last_scraped_at, refreshed_at
+            FROM current_conditions
+            WHERE status = ANY($1)
+            ORDER BY city_town, gauge_name
+            "#,
+            statuses
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows)
+    }
+}
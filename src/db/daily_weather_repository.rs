@@ -0,0 +1,113 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use tracing::{debug, instrument};
+
+use crate::db::{DbError, DbPool, GaugeWeatherDay};
+use crate::weather::DailyWeather;
+
+/// Daily temperature and ET per gauge; PostgreSQL only
+#[derive(Clone)]
+pub struct DailyWeatherRepository {
+    db: DbPool,
+}
+
+impl DailyWeatherRepository {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        Self { db: pool.into() }
+    }
+
+    /// Store the same days for each gauge, replacing days already stored
+    ///
+    /// Returns the number of gauge-days written.
+    #[instrument(skip(self, station_ids, days), fields(gauges = station_ids.len(), days = days.len()))]
+    pub async fn upsert_days(
+        &self,
+        station_ids: &[String],
+        days: &[DailyWeather],
+        source: &str,
+    ) -> Result<u64, DbError> {
+        let rows = station_ids
+            .iter()
+            .flat_map(|id| days.iter().map(move |d| (id, d)));
+        let (mut ids, mut dates, mut max_temps, mut min_temps, mut ets) =
+            (Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        for (station_id, day) in rows {
+            ids.push(station_id.clone());
+            dates.push(day.date);
+            max_temps.push(day.max_temp_f);
+            min_temps.push(day.min_temp_f);
+            ets.push(day.et_inches);
+        }
+
+        let stored = sqlx::query!(
+            r#"
+            INSERT INTO gauge_daily_weather
+                (station_id, date, max_temp_f, min_temp_f, et_inches, source)
+            SELECT station_id, date, max_temp_f, min_temp_f, et_inches, $6
+            FROM UNNEST($1::VARCHAR[], $2::DATE[], $3::FLOAT8[], $4::FLOAT8[], $5::FLOAT8[])
+                AS w(station_id, date, max_temp_f, min_temp_f, et_inches)
+            ON CONFLICT (station_id, date) DO UPDATE SET
+                max_temp_f = EXCLUDED.max_temp_f,
+                min_temp_f = EXCLUDED.min_temp_f,
+                et_inches = EXCLUDED.et_inches,
+                source = EXCLUDED.source,
+                fetched_at = NOW()
+            "#,
+            &ids,
+            &dates,
+            &max_temps as &[Option<f64>],
+            &min_temps as &[Option<f64>],
+            &ets as &[Option<f64>],
+            source
+        )
+        .execute(self.db.postgres()?)
+        .await?
+        .rows_affected();
+
+        debug!("Stored {} gauge-days of weather from {}", stored, source);
+        Ok(stored)
+    }
+
+    /// Each day of `[start_date, end_date]` with the gauge's rainfall and weather
+    ///
+    /// `start` and `end` bound the readings: UTC midnight starting `start_date` and the
+    /// day after `end_date`.
+    #[instrument(skip(self))]
+    pub async fn find_days(
+        &self,
+        station_id: &str,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<GaugeWeatherDay>, DbError> {
+        let days = sqlx::query_as!(
+            GaugeWeatherDay,
+            r#"
+            SELECT d.day::DATE AS "date!",
+                   COALESCE(r.inches, 0)::FLOAT8 AS "rainfall_inches!",
+                   w.max_temp_f, w.min_temp_f, w.et_inches,
+                   w.source AS "source?"
+            FROM generate_series($2::DATE, $3::DATE, INTERVAL '1 day') AS d(day)
+            LEFT JOIN (
+                SELECT (reading_datetime AT TIME ZONE 'UTC')::DATE AS day,
+                       SUM(incremental_inches) AS inches
+                FROM rain_readings
+                WHERE station_id = $1 AND reading_datetime >= $4 AND reading_datetime < $5
+                GROUP BY 1
+            ) r ON r.day = d.day::DATE
+            LEFT JOIN gauge_daily_weather w
+                ON w.station_id = $1 AND w.date = d.day::DATE
+            ORDER BY 1
+            "#,
+            station_id,
+            start_date,
+            end_date,
+            start,
+            end
+        )
+        .fetch_all(self.db.postgres()?)
+        .await?;
+
+        Ok(days)
+    }
+}
@@ -0,0 +1,250 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use tracing::instrument;
+
+use crate::db::{DbError, DbPool, DigestGauge, DigestSubscription, GaugeThresholdEvent};
+use crate::digest::DigestFrequency;
+
+/// Digest subscriptions and the summaries digests are built from; PostgreSQL only
+#[derive(Clone)]
+pub struct DigestRepository {
+    db: DbPool,
+}
+
+/// A digest_subscriptions row before its frequency is parsed
+struct DigestSubscriptionRow {
+    user_id: i64,
+    frequency: String,
+    email: Option<String>,
+    webhook_url: Option<String>,
+    last_sent_through: Option<NaiveDate>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<DigestSubscriptionRow> for DigestSubscription {
+    fn from(row: DigestSubscriptionRow) -> Self {
+        Self {
+            user_id: row.user_id,
+            // The column's CHECK constraint only admits known frequencies
+            frequency: DigestFrequency::parse(&row.frequency).unwrap_or(DigestFrequency::Daily),
+            email: row.email,
+            webhook_url: row.webhook_url,
+            last_sent_through: row.last_sent_through,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+/// A gauge's hourly summaries over a period, before data gaps are counted
+struct DigestGaugeRow {
+    station_id: String,
+    station_name: Option<String>,
+    total_inches: f64,
+    wettest_hour_inches: Option<f64>,
+    max_1h_intensity_in_per_hr: Option<f64>,
+    reported_hours: i64,
+}
+
+impl DigestRepository {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        Self { db: pool.into() }
+    }
+
+    /// A user's subscription, if they have one
+    pub async fn find(&self, user_id: i64) -> Result<Option<DigestSubscription>, DbError> {
+        let row = sqlx::query_as!(
+            DigestSubscriptionRow,
+            r#"
+            SELECT user_id, frequency, email, webhook_url, last_sent_through,
+                   created_at, updated_at
+            FROM digest_subscriptions
+            WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .fetch_optional(self.db.postgres()?)
+        .await?;
+
+        Ok(row.map(Into::into))
+    }
+
+    /// Create or replace a user's subscription
+    ///
+    /// Changing the frequency starts over from the latest period; otherwise the periods
+    /// already delivered are kept so none is sent twice.
+    #[instrument(skip(self, email, webhook_url))]
+    pub async fn upsert(
+        &self,
+        user_id: i64,
+        frequency: DigestFrequency,
+        email: Option<&str>,
+        webhook_url: Option<&str>,
+    ) -> Result<DigestSubscription, DbError> {
+        let row = sqlx::query_as!(
+            DigestSubscriptionRow,
+            r#"
+            INSERT INTO digest_subscriptions (user_id, frequency, email, webhook_url)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_id) DO UPDATE SET
+                frequency = EXCLUDED.frequency,
+                email = EXCLUDED.email,
+                webhook_url = EXCLUDED.webhook_url,
+                last_sent_through = CASE
+                    WHEN digest_subscriptions.frequency = EXCLUDED.frequency
+                        THEN digest_subscriptions.last_sent_through
+                END,
+                updated_at = NOW()
+            RETURNING user_id, frequency, email, webhook_url, last_sent_through,
+                      created_at, updated_at
+            "#,
+            user_id,
+            frequency.as_str(),
+            email,
+            webhook_url
+        )
+        .fetch_one(self.db.postgres()?)
+        .await?;
+
+        Ok(row.into())
+    }
+
+    /// Remove a user's subscription; false when there was none
+    #[instrument(skip(self))]
+    pub async fn delete(&self, user_id: i64) -> Result<bool, DbError> {
+        let result = sqlx::query!(
+            "DELETE FROM digest_subscriptions WHERE user_id = $1",
+            user_id
+        )
+        .execute(self.db.postgres()?)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Subscriptions at `frequency` not yet delivered through `last_day`
+    pub async fn find_due(
+        &self,
+        frequency: DigestFrequency,
+        last_day: NaiveDate,
+    ) -> Result<Vec<DigestSubscription>, DbError> {
+        let rows = sqlx::query_as!(
+            DigestSubscriptionRow,
+            r#"
+            SELECT user_id, frequency, email, webhook_url, last_sent_through,
+                   created_at, updated_at
+            FROM digest_subscriptions
+            WHERE frequency = $1
+              AND (last_sent_through IS NULL OR last_sent_through < $2)
+            ORDER BY user_id
+            "#,
+            frequency.as_str(),
+            last_day
+        )
+        .fetch_all(self.db.postgres()?)
+        .await?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Record that a user's digest through `last_day` was delivered
+    #[instrument(skip(self))]
+    pub async fn mark_sent(&self, user_id: i64, last_day: NaiveDate) -> Result<(), DbError> {
+        sqlx::query!(
+            r#"
+            UPDATE digest_subscriptions
+            SET last_sent_through = $2
+            WHERE user_id = $1
+            "#,
+            user_id,
+            last_day
+        )
+        .execute(self.db.postgres()?)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Each of a user's favorite gauges with its hourly summaries from `first_day`
+    /// through `last_day`, wettest first
+    #[instrument(skip(self))]
+    pub async fn find_gauges(
+        &self,
+        user_id: i64,
+        first_day: NaiveDate,
+        last_day: NaiveDate,
+    ) -> Result<Vec<DigestGauge>, DbError> {
+        let hours = (last_day - first_day).num_days() * 24 + 24;
+        let rows = sqlx::query_as!(
+            DigestGaugeRow,
+            r#"
+            SELECT f.station_id AS "station_id!",
+                   g.station_name,
+                   COALESCE(SUM(h.total_rainfall_inches), 0)::FLOAT8 AS "total_inches!",
+                   MAX(h.total_rainfall_inches)::FLOAT8 AS wettest_hour_inches,
+                   (SELECT MAX(i.max_1h_intensity_in_per_hr)
+                    FROM daily_rainfall_intensity i
+                    WHERE i.station_id = f.station_id
+                      AND i.date BETWEEN $2 AND $3)::FLOAT8 AS max_1h_intensity_in_per_hr,
+                   COUNT(h.hour_start) AS "reported_hours!"
+            FROM user_favorites f
+            JOIN gauges g ON g.station_id = f.station_id
+            LEFT JOIN hourly_rainfall_summary h
+                   ON h.station_id = f.station_id
+                  AND h.hour_start >= $2::DATE::TIMESTAMP AT TIME ZONE 'UTC'
+                  AND h.hour_start < ($3::DATE + 1)::TIMESTAMP AT TIME ZONE 'UTC'
+            WHERE f.user_id = $1
+            GROUP BY f.station_id, g.station_name
+            ORDER BY 3 DESC, 1
+            "#,
+            user_id,
+            first_day,
+            last_day
+        )
+        .fetch_all(self.db.postgres()?)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| DigestGauge {
+                station_id: row.station_id,
+                station_name: row.station_name,
+                total_inches: row.total_inches,
+                wettest_hour_inches: row.wettest_hour_inches,
+                max_1h_intensity_in_per_hr: row.max_1h_intensity_in_per_hr,
+                reported_hours: row.reported_hours,
+                missing_hours: (hours - row.reported_hours).max(0),
+            })
+            .collect())
+    }
+
+    /// Threshold events at a user's favorite gauges that started from `first_day`
+    /// through `last_day`, oldest first
+    pub async fn find_events(
+        &self,
+        user_id: i64,
+        first_day: NaiveDate,
+        last_day: NaiveDate,
+    ) -> Result<Vec<GaugeThresholdEvent>, DbError> {
+        let events = sqlx::query_as!(
+            GaugeThresholdEvent,
+            r#"
+            SELECT e.id, e.station_id, e.threshold_inches, e.crossed_at, e.ended_at,
+                   e.rainfall_24h_inches, e.peak_rainfall_24h_inches,
+                   e.max_15min_intensity_in_per_hr, e.max_1h_intensity_in_per_hr
+            FROM gauge_threshold_events e
+            JOIN user_favorites f ON f.station_id = e.station_id AND f.user_id = $1
+            WHERE e.crossed_at >= $2::DATE::TIMESTAMP AT TIME ZONE 'UTC'
+              AND e.crossed_at < ($3::DATE + 1)::TIMESTAMP AT TIME ZONE 'UTC'
+            ORDER BY e.crossed_at, e.station_id, e.threshold_inches
+            "#,
+            user_id,
+            first_day,
+            last_day
+        )
+        .fetch_all(self.db.postgres()?)
+        .await?;
+
+        Ok(events)
+    }
+}
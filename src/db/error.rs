@@ -2,4 +2,65 @@
 pub enum DbError {
     #[error("Database error: {0}")]
     SqlxError(#[from] sqlx::Error),
+    /// The query has no implementation for the connected backend (PostgreSQL-only)
+    #[error("Operation requires PostgreSQL; not supported by the {0} backend")]
+    Unsupported(&'static str),
+    /// `migrate down` would revert a migration that has no `.down.sql` script
+    #[error("Migration {0} has no down script and cannot be reverted")]
+    Irreversible(i64),
+    /// AUTO_MIGRATE=false and the database is behind this build
+    #[error("{count} pending migration(s) up to {latest}; apply them with `historical-import migrate up`")]
+    PendingMigrations { count: usize, latest: i64 },
+}
+
+impl DbError {
+    /// Whether the database may simply not be reachable yet, so retrying can succeed
+    ///
+    /// Covers refused or dropped connections, pool timeouts, and a server that is starting
+    /// up or shutting down. Bad credentials, a missing database, and migration problems
+    /// need an operator and are not retried.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            DbError::SqlxError(e) => is_transient_sqlx(e),
+            _ => false,
+        }
+    }
+}
+
+fn is_transient_sqlx(e: &sqlx::Error) -> bool {
+    match e {
+        sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed => true,
+        // Class 08 is connection_exception; class 57 includes cannot_connect_now (57P03)
+        // while the server starts and admin_shutdown (57P01) while it stops
+        sqlx::Error::Database(db) => db
+            .code()
+            .is_some_and(|code| code.starts_with("08") || code.starts_with("57P")),
+        sqlx::Error::Migrate(m) => match m.as_ref() {
+            sqlx::migrate::MigrateError::Execute(e) => is_transient_sqlx(e),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_transient() {
+        let refused = std::io::Error::from(std::io::ErrorKind::ConnectionRefused);
+        assert!(DbError::from(sqlx::Error::Io(refused)).is_transient());
+        assert!(DbError::from(sqlx::Error::PoolTimedOut).is_transient());
+
+        let migrate = sqlx::migrate::MigrateError::Execute(sqlx::Error::PoolTimedOut);
+        assert!(DbError::from(sqlx::Error::from(migrate)).is_transient());
+
+        assert!(!DbError::from(sqlx::Error::Configuration("bad url".into())).is_transient());
+        assert!(!DbError::PendingMigrations {
+            count: 1,
+            latest: 20250101000000
+        }
+        .is_transient());
+    }
 }
@@ -0,0 +1,101 @@
+use chrono::{DateTime, Utc};
+use tracing::instrument;
+
+use crate::db::{DbError, DbPool, FoprAvailability};
+use crate::importers::downloader::RemoteFileStatus;
+
+/// FOPR file probe results; PostgreSQL only, like the FOPR import queue
+#[derive(Clone)]
+pub struct FoprAvailabilityRepository {
+    db: DbPool,
+}
+
+impl FoprAvailabilityRepository {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        Self { db: pool.into() }
+    }
+
+    /// Record a probe, returning true when availability or Last-Modified changed
+    /// (or the gauge was never probed before)
+    #[instrument(skip(self, status))]
+    pub async fn record(
+        &self,
+        station_id: &str,
+        status: &RemoteFileStatus,
+    ) -> Result<bool, DbError> {
+        let changed = sqlx::query_scalar!(
+            r#"
+            INSERT INTO fopr_availability
+                (station_id, available, http_status, last_modified, content_length)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (station_id) DO UPDATE SET
+                available = EXCLUDED.available,
+                http_status = EXCLUDED.http_status,
+                last_modified = EXCLUDED.last_modified,
+                content_length = EXCLUDED.content_length,
+                checked_at = NOW(),
+                changed_at = CASE
+                    WHEN fopr_availability.available IS DISTINCT FROM EXCLUDED.available
+                      OR fopr_availability.last_modified IS DISTINCT FROM EXCLUDED.last_modified
+                    THEN NOW()
+                    ELSE fopr_availability.changed_at
+                END
+            RETURNING changed_at = checked_at AS "changed!"
+            "#,
+            station_id,
+            status.available,
+            status.http_status as i32,
+            status.last_modified,
+            status.content_length
+        )
+        .fetch_one(self.db.postgres()?)
+        .await?;
+
+        Ok(changed)
+    }
+
+    /// Probe results ordered by station, optionally filtered
+    #[instrument(skip(self))]
+    pub async fn find_all(
+        &self,
+        available: Option<bool>,
+        changed_since: Option<DateTime<Utc>>,
+    ) -> Result<Vec<FoprAvailability>, DbError> {
+        let rows = sqlx::query_as!(
+            FoprAvailability,
+            r#"
+            SELECT station_id, available, http_status, last_modified, content_length,
+                   checked_at, changed_at
+            FROM fopr_availability
+            WHERE ($1::BOOLEAN IS NULL OR available = $1)
+              AND ($2::TIMESTAMPTZ IS NULL OR changed_at >= $2)
+            ORDER BY station_id
+            "#,
+            available,
+            changed_since
+        )
+        .fetch_all(self.db.postgres()?)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Stations whose last probe found no FOPR file
+    ///
+    /// Empty on backends without the table, so callers can always consult it.
+    #[instrument(skip(self))]
+    pub async fn find_unavailable_station_ids(&self) -> Result<Vec<String>, DbError> {
+        let pool = match self.db.postgres() {
+            Ok(pool) => pool,
+            Err(DbError::Unsupported(_)) => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        let ids = sqlx::query_scalar!(
+            "SELECT station_id FROM fopr_availability WHERE NOT available ORDER BY station_id"
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(ids)
+    }
+}
@@ -1,9 +1,9 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{PgPool, Postgres, Transaction};
+use sqlx::{Postgres, Transaction};
 use tracing::{debug, info, instrument};
 
-use crate::db::DbError;
+use crate::db::{DbError, DbPool};
 use crate::gauge_list_fetcher::GaugeSummary as FetchedGauge;
 
 /// Job status for FOPR imports
@@ -53,19 +53,30 @@ pub struct ErrorHistoryEntry {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImportStats {
     pub readings_imported: i64,
+    /// Stored readings overwritten by an `--on-conflict update` import
+    #[serde(default)]
+    pub readings_updated: i64,
+    /// Daily totals the ingest guards kept out (absent from jobs completed before the guards)
+    #[serde(default)]
+    pub readings_quarantined: i64,
     pub start_date: Option<String>,
     pub end_date: Option<String>,
     pub duration_secs: f64,
 }
 
+/// Seconds of waiting that raise a job's effective priority by one when claiming
+///
+/// A default-priority (10) job overtakes a fresh priority-100 job after 7.5 hours.
+pub const PRIORITY_AGING_SECS: f64 = 300.0;
+
 #[derive(Clone)]
 pub struct FoprImportJobRepository {
-    pool: PgPool,
+    db: DbPool,
 }
 
 impl FoprImportJobRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        Self { db: pool.into() }
     }
 
     /// Create a new import job
@@ -96,7 +107,7 @@ impl FoprImportJobRepository {
             source,
             gauge_summary_json
         )
-        .fetch_one(&self.pool)
+        .fetch_one(self.db.postgres()?)
         .await?;
 
         info!(
@@ -109,7 +120,9 @@ impl FoprImportJobRepository {
     /// Atomically claim the next job to process
     ///
     /// This uses FOR UPDATE SKIP LOCKED to safely handle concurrent workers.
-    /// Returns the next pending job or a failed job ready for retry.
+    /// Returns the next pending job or a failed job ready for retry, highest effective
+    /// priority first: a job's priority plus one for every [`PRIORITY_AGING_SECS`] it has
+    /// waited, so a steady stream of urgent jobs cannot starve older low-priority ones.
     #[instrument(skip(self))]
     pub async fn claim_next_job(&self) -> Result<Option<FoprImportJob>, DbError> {
         debug!("Attempting to claim next job");
@@ -125,7 +138,8 @@ impl FoprImportJobRepository {
                 FROM fopr_import_jobs
                 WHERE status = 'pending'
                    OR (status = 'failed' AND retry_count < max_retries AND next_retry_at <= NOW())
-                ORDER BY priority DESC, created_at ASC
+                ORDER BY priority + EXTRACT(EPOCH FROM NOW() - created_at)::float8 / $1::float8 DESC,
+                         created_at ASC
                 LIMIT 1
                 FOR UPDATE SKIP LOCKED
             )
@@ -135,8 +149,9 @@ impl FoprImportJobRepository {
                 error_message, error_history, retry_count, max_retries, next_retry_at,
                 source, gauge_summary, import_stats
             "#,
+            PRIORITY_AGING_SECS,
         )
-        .fetch_optional(&self.pool)
+        .fetch_optional(self.db.postgres()?)
         .await?;
 
         if let Some(ref j) = job {
@@ -167,7 +182,7 @@ impl FoprImportJobRepository {
             job_id,
             stats_json
         )
-        .execute(&self.pool)
+        .execute(self.db.postgres()?)
         .await?;
 
         info!("Job {} marked as completed", job_id);
@@ -207,7 +222,7 @@ impl FoprImportJobRepository {
             retry_count,
             next_retry_at
         )
-        .execute(&self.pool)
+        .execute(self.db.postgres()?)
         .await?;
 
         info!(
@@ -231,7 +246,7 @@ impl FoprImportJobRepository {
             "#,
             station_id
         )
-        .fetch_one(&self.pool)
+        .fetch_one(self.db.postgres()?)
         .await?
         .unwrap_or(false);
 
@@ -255,7 +270,7 @@ impl FoprImportJobRepository {
             "#,
             job_id
         )
-        .fetch_optional(&self.pool)
+        .fetch_optional(self.db.postgres()?)
         .await?;
 
         Ok(job)
@@ -277,7 +292,7 @@ impl FoprImportJobRepository {
             ORDER BY priority DESC, created_at ASC
             "#
         )
-        .fetch_all(&self.pool)
+        .fetch_all(self.db.postgres()?)
         .await?;
 
         debug!("Found {} pending jobs", jobs.len());
@@ -346,7 +361,8 @@ impl FoprImportJobRepository {
                 FROM fopr_import_jobs
                 WHERE status = 'pending'
                    OR (status = 'failed' AND retry_count < max_retries AND next_retry_at <= NOW())
-                ORDER BY priority DESC, created_at ASC
+                ORDER BY priority + EXTRACT(EPOCH FROM NOW() - created_at)::float8 / $1::float8 DESC,
+                         created_at ASC
                 LIMIT 1
                 FOR UPDATE SKIP LOCKED
             )
@@ -356,6 +372,7 @@ impl FoprImportJobRepository {
                 error_message, error_history, retry_count, max_retries, next_retry_at,
                 source, gauge_summary, import_stats
             "#,
+            PRIORITY_AGING_SECS,
         )
         .fetch_optional(&mut **tx)
         .await?;
@@ -0,0 +1,167 @@
+use tracing::{info, instrument};
+
+#[cfg(feature = "sqlite")]
+use crate::db::sqlite;
+use crate::db::{DbError, DbPool, ForecastZoneRecord, GaugeZoneLocation};
+use crate::zones::ZonePolygon;
+
+/// Forecast zone polygons and the zone assigned to each gauge
+#[derive(Clone)]
+pub struct ForecastZoneRepository {
+    db: DbPool,
+}
+
+impl ForecastZoneRepository {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        Self { db: pool.into() }
+    }
+
+    /// Replace the stored zones with `zones` in one transaction
+    ///
+    /// Zones missing from `zones` are deleted, which clears them from their gauges.
+    /// Returns the number of zones deleted.
+    #[instrument(skip(self, zones), fields(count = zones.len()))]
+    pub async fn replace_all(&self, zones: &[ZonePolygon], source: &str) -> Result<u64, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::forecast_zones::replace_all(pool, zones, source).await
+            }
+        };
+        let mut tx = pool.begin().await?;
+
+        for zone in zones {
+            sqlx::query!(
+                r#"
+                INSERT INTO forecast_zones
+                    (zone_id, name, geometry, min_latitude, max_latitude, min_longitude,
+                     max_longitude, source)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                ON CONFLICT (zone_id) DO UPDATE SET
+                    name = EXCLUDED.name,
+                    geometry = EXCLUDED.geometry,
+                    min_latitude = EXCLUDED.min_latitude,
+                    max_latitude = EXCLUDED.max_latitude,
+                    min_longitude = EXCLUDED.min_longitude,
+                    max_longitude = EXCLUDED.max_longitude,
+                    source = EXCLUDED.source,
+                    imported_at = NOW()
+                "#,
+                zone.zone_id,
+                zone.name,
+                zone.geometry,
+                zone.bounds.min_latitude,
+                zone.bounds.max_latitude,
+                zone.bounds.min_longitude,
+                zone.bounds.max_longitude,
+                source
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let zone_ids: Vec<String> = zones.iter().map(|zone| zone.zone_id.clone()).collect();
+        let deleted = sqlx::query!(
+            "DELETE FROM forecast_zones WHERE zone_id <> ALL($1)",
+            &zone_ids
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        tx.commit().await?;
+        info!(
+            "Stored {} forecast zones ({} removed)",
+            zones.len(),
+            deleted
+        );
+        Ok(deleted)
+    }
+
+    /// Every zone with its gauge count, ordered by zone ID
+    #[instrument(skip(self))]
+    pub async fn find_all(&self) -> Result<Vec<ForecastZoneRecord>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => return sqlite::forecast_zones::find_all(pool).await,
+        };
+        let zones = sqlx::query_as!(
+            ForecastZoneRecord,
+            r#"
+            SELECT z.zone_id, z.name, z.geometry, z.source, z.imported_at,
+                   COUNT(g.station_id) AS "gauge_count!"
+            FROM forecast_zones z
+            LEFT JOIN gauges g ON g.forecast_zone = z.zone_id
+            GROUP BY z.zone_id
+            ORDER BY z.zone_id
+            "#
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(zones)
+    }
+
+    /// Coordinates and assigned zone of every gauge
+    #[instrument(skip(self))]
+    pub async fn find_gauge_locations(&self) -> Result<Vec<GaugeZoneLocation>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::forecast_zones::find_gauge_locations(pool).await
+            }
+        };
+        let gauges = sqlx::query_as!(
+            GaugeZoneLocation,
+            r#"
+            SELECT station_id,
+                   latitude::FLOAT8 AS latitude,
+                   longitude::FLOAT8 AS longitude,
+                   forecast_zone
+            FROM gauges
+            ORDER BY station_id
+            "#
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(gauges)
+    }
+
+    /// Set the zone of each gauge in one transaction, returning the number updated
+    #[instrument(skip(self, assignments), fields(count = assignments.len()))]
+    pub async fn assign_gauges(
+        &self,
+        assignments: &[(String, Option<String>)],
+    ) -> Result<u64, DbError> {
+        if assignments.is_empty() {
+            return Ok(0);
+        }
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::forecast_zones::assign_gauges(pool, assignments).await
+            }
+        };
+        let mut tx = pool.begin().await?;
+        let mut updated = 0;
+
+        for (station_id, zone_id) in assignments {
+            updated += sqlx::query!(
+                "UPDATE gauges SET forecast_zone = $2 WHERE station_id = $1",
+                station_id,
+                zone_id.as_deref()
+            )
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        }
+
+        tx.commit().await?;
+        Ok(updated)
+    }
+}
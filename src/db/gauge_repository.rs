@@ -1,27 +1,46 @@
-use sqlx::{PgPool, Postgres, Transaction};
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::{Postgres, Transaction};
 use tracing::{debug, error, info, instrument};
 
-use crate::db::{DbError, GaugeSummary};
+#[cfg(feature = "sqlite")]
+use crate::db::sqlite;
+use crate::db::{
+    DbError, DbPool, EditableGaugeMetadata, ElevationCandidate, GaugeDetail, GaugeLastSeen,
+    GaugeLocation, GaugeMapPoint, GaugeMetadata, GaugeSourcePair, GaugeStatusChange, GaugeSummary,
+    GaugeWaterYearToDate, GeocodeCandidate, GisGauge,
+};
 use crate::fopr::MetaStatsData;
 use crate::gauge_list_fetcher::GaugeSummary as FetchedGauge;
+use crate::units::Inches;
+use crate::utils;
+
+/// `metadata_source` of gauges corrected through the CSV metadata editor
+///
+/// FOPR metadata upserts keep the edited columns of these gauges.
+pub const MANUAL_EDIT_SOURCE: &str = "manual_edit";
 
 #[derive(Clone)]
 pub struct GaugeRepository {
-    pool: PgPool,
+    db: DbPool,
 }
 
 impl GaugeRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        Self { db: pool.into() }
     }
 
     #[instrument(skip(self, summaries), fields(count = summaries.len()))]
     pub async fn upsert_summaries(&self, summaries: &[FetchedGauge]) -> Result<usize, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => return sqlite::gauges::upsert_summaries(pool, summaries).await,
+        };
         debug!(
             "Beginning transaction to upsert {} gauge summaries",
             summaries.len()
         );
-        let mut tx = self.pool.begin().await?;
+        let mut tx = pool.begin().await?;
         let mut upserted = 0;
 
         for summary in summaries {
@@ -45,14 +64,14 @@ impl GaugeRepository {
                     last_scraped_at = NOW(),
                     updated_at = NOW()
                 "#,
-                summary.station_id,
+                summary.station_id.as_str(),
                 summary.gauge_name,
                 summary.city_town,
                 summary.elevation_ft,
                 summary.general_location,
                 summary.msp_forecast_zone,
-                summary.rainfall_past_6h_inches,
-                summary.rainfall_past_24h_inches
+                summary.rainfall_past_6h_inches.map(Inches::value),
+                summary.rainfall_past_24h_inches.map(Inches::value)
             )
             .execute(&mut *tx)
             .await
@@ -76,8 +95,13 @@ impl GaugeRepository {
 
     #[instrument(skip(self))]
     pub async fn count(&self) -> Result<usize, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => return sqlite::gauges::count(pool).await,
+        };
         let count = sqlx::query_scalar!("SELECT COUNT(*) FROM gauge_summaries")
-            .fetch_one(&self.pool)
+            .fetch_one(pool)
             .await?;
 
         Ok(count.unwrap_or(0) as usize)
@@ -89,46 +113,238 @@ impl GaugeRepository {
         offset: i64,
         limit: i64,
     ) -> Result<Vec<GaugeSummary>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::gauges::find_paginated(pool, offset, limit).await
+            }
+        };
         debug!("Querying gauges with offset={}, limit={}", offset, limit);
 
         let gauges = sqlx::query_as!(
             GaugeSummary,
             r#"
-            SELECT id, station_id, gauge_name, city_town, elevation_ft,
-                   general_location, msp_forecast_zone,
-                   rainfall_past_6h_inches, rainfall_past_24h_inches,
-                   last_scraped_at, created_at, updated_at
-            FROM gauge_summaries
-            ORDER BY city_town, gauge_name
+            SELECT s.id, s.station_id, s.gauge_name, s.city_town, s.elevation_ft,
+                   s.general_location, s.msp_forecast_zone,
+                   s.rainfall_past_6h_inches, s.rainfall_past_24h_inches,
+                   COALESCE(g.status, 'Active') AS "status!",
+                   g.status_effective_date AS "status_effective_date?",
+                   s.last_scraped_at, s.created_at, s.updated_at
+            FROM gauge_summaries s
+            LEFT JOIN gauges g ON g.station_id = s.station_id
+            ORDER BY s.city_town, s.gauge_name
             LIMIT $1 OFFSET $2
             "#,
             limit,
             offset
         )
-        .fetch_all(&self.pool)
+        .fetch_all(pool)
         .await?;
 
         debug!("Found {} gauges", gauges.len());
         Ok(gauges)
     }
 
+    /// Count gauge list entries whose gauge has one of `statuses`
+    ///
+    /// Entries without a gauges row count as Active.
+    #[instrument(skip(self))]
+    pub async fn count_by_status(&self, statuses: &[String]) -> Result<usize, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => return sqlite::gauges::count_by_status(pool, statuses).await,
+        };
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*)
+            FROM gauge_summaries s
+            LEFT JOIN gauges g ON g.station_id = s.station_id
+            WHERE COALESCE(g.status, 'Active') = ANY($1)
+            "#,
+            statuses
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count.unwrap_or(0) as usize)
+    }
+
+    /// Water-year-to-date totals for the given gauges as of (`year`, `month`)
+    ///
+    /// Months of `water_year` before `month` are summed from monthly summaries and
+    /// `month` itself from raw readings. Every requested gauge gets a row.
+    #[instrument(skip(self, station_ids), fields(count = station_ids.len()))]
+    pub async fn find_water_year_to_date(
+        &self,
+        station_ids: &[String],
+        water_year: i32,
+        year: i32,
+        month: u32,
+    ) -> Result<Vec<GaugeWaterYearToDate>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::gauges::find_water_year_to_date(
+                    pool,
+                    station_ids,
+                    water_year,
+                    year,
+                    month,
+                )
+                .await
+            }
+        };
+        let (month_start, month_end) = utils::month_date_range(year, month);
+        let totals = sqlx::query_as!(
+            GaugeWaterYearToDate,
+            r#"
+            SELECT ids.station_id AS "station_id!",
+                   COALESCE(m.total, 0) + COALESCE(r.total, 0) AS "total_rainfall_inches!",
+                   COALESCE(m.readings, 0) + COALESCE(r.readings, 0) AS "total_readings!",
+                   GREATEST(m.last_reading, r.last_reading) AS as_of
+            FROM UNNEST($1::TEXT[]) AS ids(station_id)
+            LEFT JOIN LATERAL (
+                SELECT SUM(total_rainfall_inches) AS total,
+                       SUM(reading_count)::BIGINT AS readings,
+                       MAX(last_reading_date) AS last_reading
+                FROM monthly_rainfall_summary
+                WHERE station_id = ids.station_id
+                  AND month_start >= make_date($2 - 1, 10, 1)
+                  AND month_start < make_date($3, $4, 1)
+            ) m ON TRUE
+            LEFT JOIN LATERAL (
+                SELECT SUM(incremental_inches) AS total, COUNT(*) AS readings,
+                       MAX(reading_datetime) AS last_reading
+                FROM rain_readings
+                WHERE station_id = ids.station_id
+                  AND reading_datetime >= $5
+                  AND reading_datetime < $6
+            ) r ON TRUE
+            "#,
+            station_ids,
+            water_year,
+            year,
+            month as i32,
+            month_start,
+            month_end
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(totals)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn find_paginated_by_status(
+        &self,
+        statuses: &[String],
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<GaugeSummary>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::gauges::find_paginated_by_status(pool, statuses, offset, limit)
+                    .await
+            }
+        };
+        let gauges = sqlx::query_as!(
+            GaugeSummary,
+            r#"
+            SELECT s.id, s.station_id, s.gauge_name, s.city_town, s.elevation_ft,
+                   s.general_location, s.msp_forecast_zone,
+                   s.rainfall_past_6h_inches, s.rainfall_past_24h_inches,
+                   COALESCE(g.status, 'Active') AS "status!",
+                   g.status_effective_date AS "status_effective_date?",
+                   s.last_scraped_at, s.created_at, s.updated_at
+            FROM gauge_summaries s
+            LEFT JOIN gauges g ON g.station_id = s.station_id
+            WHERE COALESCE(g.status, 'Active') = ANY($1)
+            ORDER BY s.city_town, s.gauge_name
+            LIMIT $2 OFFSET $3
+            "#,
+            statuses,
+            limit,
+            offset
+        )
+        .fetch_all(pool)
+        .await?;
+
+        debug!("Found {} gauges", gauges.len());
+        Ok(gauges)
+    }
+
+    /// Gauge list entries for `station_ids` whose gauge has one of `statuses`, in no
+    /// particular order
+    #[instrument(skip(self, station_ids), fields(count = station_ids.len()))]
+    pub async fn find_by_station_ids(
+        &self,
+        station_ids: &[String],
+        statuses: &[String],
+    ) -> Result<Vec<GaugeSummary>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::gauges::find_by_station_ids(pool, station_ids, statuses).await
+            }
+        };
+        // Overrides: without ORDER BY the plan can hide gauge_summaries' NOT NULLs
+        let gauges = sqlx::query_as!(
+            GaugeSummary,
+            r#"
+            SELECT s.id AS "id!", s.station_id AS "station_id!", s.gauge_name AS "gauge_name!",
+                   s.city_town, s.elevation_ft,
+                   s.general_location, s.msp_forecast_zone,
+                   s.rainfall_past_6h_inches, s.rainfall_past_24h_inches,
+                   COALESCE(g.status, 'Active') AS "status!",
+                   g.status_effective_date AS "status_effective_date?",
+                   s.last_scraped_at AS "last_scraped_at!", s.created_at AS "created_at!",
+                   s.updated_at AS "updated_at!"
+            FROM gauge_summaries s
+            LEFT JOIN gauges g ON g.station_id = s.station_id
+            WHERE s.station_id = ANY($1)
+              AND COALESCE(g.status, 'Active') = ANY($2)
+            "#,
+            station_ids,
+            statuses
+        )
+        .fetch_all(pool)
+        .await?;
+
+        debug!("Found {} of {} gauges", gauges.len(), station_ids.len());
+        Ok(gauges)
+    }
+
     #[instrument(skip(self), fields(station_id = %station_id))]
     pub async fn find_by_id(&self, station_id: &str) -> Result<Option<GaugeSummary>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => return sqlite::gauges::find_by_id(pool, station_id).await,
+        };
         debug!("Querying gauge by station_id");
 
         let gauge = sqlx::query_as!(
             GaugeSummary,
             r#"
-            SELECT id, station_id, gauge_name, city_town, elevation_ft,
-                   general_location, msp_forecast_zone,
-                   rainfall_past_6h_inches, rainfall_past_24h_inches,
-                   last_scraped_at, created_at, updated_at
-            FROM gauge_summaries
-            WHERE station_id = $1
+            SELECT s.id, s.station_id, s.gauge_name, s.city_town, s.elevation_ft,
+                   s.general_location, s.msp_forecast_zone,
+                   s.rainfall_past_6h_inches, s.rainfall_past_24h_inches,
+                   COALESCE(g.status, 'Active') AS "status!",
+                   g.status_effective_date AS "status_effective_date?",
+                   s.last_scraped_at, s.created_at, s.updated_at
+            FROM gauge_summaries s
+            LEFT JOIN gauges g ON g.station_id = s.station_id
+            WHERE s.station_id = $1
             "#,
             station_id
         )
-        .fetch_optional(&self.pool)
+        .fetch_optional(pool)
         .await?;
 
         if gauge.is_some() {
@@ -140,12 +356,689 @@ impl GaugeRepository {
         Ok(gauge)
     }
 
+    /// Gauges with coordinates inside a bounding box, with current rainfall totals
+    ///
+    /// Positions come from the gauges table (FOPR metadata); names and rainfall
+    /// from the latest gauge list scrape when available.
+    #[instrument(skip(self))]
+    pub async fn find_map_points(
+        &self,
+        min_lat: f64,
+        max_lat: f64,
+        min_lon: f64,
+        max_lon: f64,
+    ) -> Result<Vec<GaugeMapPoint>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::gauges::find_map_points(pool, min_lat, max_lat, min_lon, max_lon)
+                    .await
+            }
+        };
+        let points = sqlx::query_as!(
+            GaugeMapPoint,
+            r#"
+            SELECT g.station_id,
+                   COALESCE(s.gauge_name, g.station_name, g.station_id) AS "gauge_name!",
+                   COALESCE(s.city_town, g.city) AS city_town,
+                   g.latitude::FLOAT8 AS "latitude!",
+                   g.longitude::FLOAT8 AS "longitude!",
+                   COALESCE(s.elevation_ft, g.elevation_ft) AS elevation_ft,
+                   s.rainfall_past_6h_inches AS "rainfall_past_6h_inches?",
+                   s.rainfall_past_24h_inches AS "rainfall_past_24h_inches?"
+            FROM gauges g
+            LEFT JOIN gauge_summaries s ON s.station_id = g.station_id
+            WHERE g.latitude::FLOAT8 BETWEEN $1 AND $2
+              AND g.longitude::FLOAT8 BETWEEN $3 AND $4
+            ORDER BY g.station_id
+            "#,
+            min_lat,
+            max_lat,
+            min_lon,
+            max_lon
+        )
+        .fetch_all(pool)
+        .await?;
+
+        debug!("Found {} gauges in bounds", points.len());
+        Ok(points)
+    }
+
+    /// Merged view of one gauge from gauge_summaries and gauges
+    ///
+    /// Returns None only when the station is in neither table.
+    #[instrument(skip(self), fields(station_id = %station_id))]
+    pub async fn find_detail(&self, station_id: &str) -> Result<Option<GaugeDetail>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => return sqlite::gauges::find_detail(pool, station_id).await,
+        };
+        let detail = sqlx::query_as!(
+            GaugeDetail,
+            r#"
+            SELECT COALESCE(s.station_id, g.station_id) AS "station_id!",
+                   COALESCE(s.gauge_name, g.station_name, COALESCE(s.station_id, g.station_id))
+                       AS "gauge_name!",
+                   COALESCE(s.city_town, g.city) AS city_town,
+                   COALESCE(s.elevation_ft, g.elevation_ft) AS elevation_ft,
+                   COALESCE(s.general_location, g.location_description) AS general_location,
+                   s.msp_forecast_zone AS "msp_forecast_zone?",
+                   g.latitude::FLOAT8 AS "latitude?",
+                   g.longitude::FLOAT8 AS "longitude?",
+                   g.status AS "status?",
+                   s.rainfall_past_6h_inches AS "rainfall_past_6h_inches?",
+                   s.rainfall_past_24h_inches AS "rainfall_past_24h_inches?",
+                   s.last_scraped_at AS "last_scraped_at?",
+                   g.metadata_updated_at AS "metadata_updated_at?",
+                   s.station_id IS NOT NULL AS "in_gauge_list!",
+                   g.station_id IS NOT NULL AS "has_metadata!"
+            FROM (SELECT * FROM gauge_summaries WHERE station_id = $1) s
+            FULL OUTER JOIN (SELECT * FROM gauges WHERE station_id = $1) g
+                ON g.station_id = s.station_id
+            "#,
+            station_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(detail)
+    }
+
+    /// FOPR metadata and statistics for one gauge
+    #[instrument(skip(self), fields(station_id = %station_id))]
+    pub async fn find_metadata(&self, station_id: &str) -> Result<Option<GaugeMetadata>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => return sqlite::gauges::find_metadata(pool, station_id).await,
+        };
+        let metadata = sqlx::query_as!(
+            GaugeMetadata,
+            r#"
+            SELECT station_type, county, previous_station_ids,
+                   installation_date, data_begins_date, data_ends_date,
+                   avg_annual_precipitation_inches::FLOAT8 AS avg_annual_precipitation_inches,
+                   complete_years_count, incomplete_months_count, missing_months_count,
+                   data_quality_remarks, fopr_available, fopr_last_import_date
+            FROM gauges
+            WHERE station_id = $1
+            "#,
+            station_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(metadata)
+    }
+
+    /// Current lifecycle status (None if the gauge has no metadata row)
+    #[instrument(skip(self), fields(station_id = %station_id))]
+    pub async fn find_status(&self, station_id: &str) -> Result<Option<String>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => return sqlite::gauges::find_status(pool, station_id).await,
+        };
+        let status = sqlx::query_scalar!(
+            "SELECT status FROM gauges WHERE station_id = $1",
+            station_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(status)
+    }
+
+    /// Move a gauge from `from` to `to` and record the transition
+    ///
+    /// Returns None when the gauge's status is no longer `from` (a concurrent change).
+    #[instrument(skip(self, reason))]
+    pub async fn change_status(
+        &self,
+        station_id: &str,
+        from: &str,
+        to: &str,
+        effective_date: NaiveDate,
+        reason: Option<&str>,
+        changed_by: &str,
+    ) -> Result<Option<GaugeStatusChange>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::gauges::change_status(
+                    pool,
+                    station_id,
+                    from,
+                    to,
+                    effective_date,
+                    reason,
+                    changed_by,
+                )
+                .await
+            }
+        };
+        let mut tx = pool.begin().await?;
+
+        let updated = sqlx::query!(
+            r#"
+            UPDATE gauges SET status = $3, status_effective_date = $4
+            WHERE station_id = $1 AND status = $2
+            "#,
+            station_id,
+            from,
+            to,
+            effective_date
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if updated.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        let change = sqlx::query_as!(
+            GaugeStatusChange,
+            r#"
+            INSERT INTO gauge_status_history (
+                station_id, previous_status, status, effective_date, reason, changed_by
+            )
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, station_id, previous_status, status, effective_date, reason,
+                      changed_by, created_at
+            "#,
+            station_id,
+            from,
+            to,
+            effective_date,
+            reason,
+            changed_by
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(Some(change))
+    }
+
+    /// Status transitions for a gauge, newest first
+    #[instrument(skip(self), fields(station_id = %station_id))]
+    pub async fn find_status_history(
+        &self,
+        station_id: &str,
+    ) -> Result<Vec<GaugeStatusChange>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::gauges::find_status_history(pool, station_id).await
+            }
+        };
+        let history = sqlx::query_as!(
+            GaugeStatusChange,
+            r#"
+            SELECT id, station_id, previous_status, status, effective_date, reason,
+                   changed_by, created_at
+            FROM gauge_status_history
+            WHERE station_id = $1
+            ORDER BY created_at DESC, id DESC
+            "#,
+            station_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(history)
+    }
+
+    /// Active gauges whose last appearance in the gauge list is before `cutoff`
+    ///
+    /// Gauges that were never in the gauge list are not included.
+    #[instrument(skip(self))]
+    pub async fn find_active_not_seen_since(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> Result<Vec<GaugeLastSeen>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::gauges::find_active_not_seen_since(pool, cutoff).await
+            }
+        };
+        let gauges = sqlx::query_as!(
+            GaugeLastSeen,
+            r#"
+            SELECT g.station_id, s.last_scraped_at
+            FROM gauges g
+            JOIN gauge_summaries s ON s.station_id = g.station_id
+            WHERE g.status = 'Active' AND s.last_scraped_at < $1
+            ORDER BY g.station_id
+            "#,
+            cutoff
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(gauges)
+    }
+
+    /// Gauges made Inactive by `changed_by` that have appeared in the gauge list since `cutoff`
+    #[instrument(skip(self))]
+    pub async fn find_inactive_seen_since(
+        &self,
+        cutoff: DateTime<Utc>,
+        changed_by: &str,
+    ) -> Result<Vec<GaugeLastSeen>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::gauges::find_inactive_seen_since(pool, cutoff, changed_by).await
+            }
+        };
+        let gauges = sqlx::query_as!(
+            GaugeLastSeen,
+            r#"
+            SELECT g.station_id, s.last_scraped_at
+            FROM gauges g
+            JOIN gauge_summaries s ON s.station_id = g.station_id
+            WHERE g.status = 'Inactive'
+              AND s.last_scraped_at >= $1
+              AND (
+                  SELECT h.changed_by FROM gauge_status_history h
+                  WHERE h.station_id = g.station_id
+                  ORDER BY h.created_at DESC, h.id DESC
+                  LIMIT 1
+              ) = $2
+            ORDER BY g.station_id
+            "#,
+            cutoff,
+            changed_by
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(gauges)
+    }
+
+    /// Every station in either gauge_summaries or gauges, with both sides' values
+    #[instrument(skip(self))]
+    pub async fn find_source_pairs(&self) -> Result<Vec<GaugeSourcePair>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => return sqlite::gauges::find_source_pairs(pool).await,
+        };
+        let pairs = sqlx::query_as!(
+            GaugeSourcePair,
+            r#"
+            SELECT COALESCE(s.station_id, g.station_id) AS "station_id!",
+                   s.station_id IS NOT NULL AS "in_gauge_list!",
+                   g.station_id IS NOT NULL AS "has_metadata!",
+                   s.gauge_name AS "summary_name?",
+                   g.station_name AS "metadata_name?",
+                   s.city_town AS "summary_city?",
+                   g.city AS "metadata_city?",
+                   s.elevation_ft AS "summary_elevation_ft?",
+                   g.elevation_ft AS "metadata_elevation_ft?"
+            FROM gauge_summaries s
+            FULL OUTER JOIN gauges g ON g.station_id = s.station_id
+            ORDER BY 1
+            "#
+        )
+        .fetch_all(pool)
+        .await?;
+
+        debug!("Loaded {} gauge source pairs", pairs.len());
+        Ok(pairs)
+    }
+
+    /// Fill empty name, city, and elevation in gauges from the gauge list scrape
+    ///
+    /// Only NULL columns are written; values from FOPR metadata are never overwritten.
+    /// Returns the number of gauges updated.
+    #[instrument(skip(self))]
+    pub async fn backfill_metadata_from_summaries(&self) -> Result<u64, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::gauges::backfill_metadata_from_summaries(pool).await
+            }
+        };
+        let result = sqlx::query!(
+            r#"
+            UPDATE gauges g SET
+                station_name = COALESCE(g.station_name, s.gauge_name),
+                city = COALESCE(g.city, LEFT(s.city_town, 100)),
+                elevation_ft = COALESCE(g.elevation_ft, s.elevation_ft)
+            FROM gauge_summaries s
+            WHERE s.station_id = g.station_id
+              AND (g.station_name IS NULL
+                   OR (g.city IS NULL AND s.city_town IS NOT NULL)
+                   OR (g.elevation_ft IS NULL AND s.elevation_ft IS NOT NULL))
+            "#
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// The editable metadata of every gauge, ordered by station ID
+    #[instrument(skip(self))]
+    pub async fn find_editable_metadata(&self) -> Result<Vec<EditableGaugeMetadata>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => return sqlite::gauges::find_editable_metadata(pool).await,
+        };
+        let gauges = sqlx::query_as!(
+            EditableGaugeMetadata,
+            r#"
+            SELECT station_id, station_name, city, county, location_description,
+                   latitude::FLOAT8 AS latitude, longitude::FLOAT8 AS longitude, elevation_ft
+            FROM gauges
+            ORDER BY station_id
+            "#
+        )
+        .fetch_all(pool)
+        .await?;
+
+        debug!("Loaded editable metadata for {} gauges", gauges.len());
+        Ok(gauges)
+    }
+
+    /// Metadata and forecast zone of every gauge, ordered by station ID
+    #[instrument(skip(self))]
+    pub async fn find_gis_gauges(&self) -> Result<Vec<GisGauge>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => return sqlite::gauges::find_gis_gauges(pool).await,
+        };
+        let gauges = sqlx::query_as!(
+            GisGauge,
+            r#"
+            SELECT station_id, station_name, city, county, location_description,
+                   latitude::FLOAT8 AS latitude, longitude::FLOAT8 AS longitude, elevation_ft,
+                   status, data_begins_date, data_ends_date, forecast_zone
+            FROM gauges
+            ORDER BY station_id
+            "#
+        )
+        .fetch_all(pool)
+        .await?;
+
+        debug!("Loaded {} gauges for GIS export", gauges.len());
+        Ok(gauges)
+    }
+
+    /// Every gauge with both coordinates, ordered by station
+    #[instrument(skip(self))]
+    pub async fn find_locations(&self) -> Result<Vec<GaugeLocation>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => return sqlite::gauges::find_locations(pool).await,
+        };
+        let gauges = sqlx::query_as!(
+            GaugeLocation,
+            r#"
+            SELECT station_id,
+                   latitude::FLOAT8 AS "latitude!",
+                   longitude::FLOAT8 AS "longitude!"
+            FROM gauges
+            WHERE latitude IS NOT NULL AND longitude IS NOT NULL
+            ORDER BY station_id
+            "#
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(gauges)
+    }
+
+    /// Write operator corrections to gauges in one transaction
+    ///
+    /// The gauges are marked with MANUAL_EDIT_SOURCE so later FOPR imports keep the
+    /// corrected columns. Returns the number of gauges updated.
+    #[instrument(skip(self, edits), fields(count = edits.len()))]
+    pub async fn update_editable_metadata(
+        &self,
+        edits: &[EditableGaugeMetadata],
+    ) -> Result<u64, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::gauges::update_editable_metadata(pool, edits).await
+            }
+        };
+        let mut tx = pool.begin().await?;
+        let mut updated = 0;
+
+        for edit in edits {
+            let result = sqlx::query!(
+                r#"
+                UPDATE gauges SET
+                    station_name = $2,
+                    city = $3,
+                    county = $4,
+                    location_description = $5,
+                    latitude = $6::FLOAT8,
+                    longitude = $7::FLOAT8,
+                    elevation_ft = $8,
+                    metadata_source = 'manual_edit',
+                    metadata_updated_at = NOW()
+                WHERE station_id = $1
+                "#,
+                edit.station_id,
+                edit.station_name,
+                edit.city,
+                edit.county,
+                edit.location_description,
+                edit.latitude,
+                edit.longitude,
+                edit.elevation_ft
+            )
+            .execute(&mut *tx)
+            .await?;
+            updated += result.rows_affected();
+        }
+
+        tx.commit().await?;
+        info!("Applied metadata edits to {} gauges", updated);
+        Ok(updated)
+    }
+
+    /// Gauges with coordinates still missing a city or nearest place, ordered by station ID
+    ///
+    /// Gauges already geocoded are skipped, so a place the provider could not name is
+    /// not looked up again every run.
+    #[instrument(skip(self))]
+    pub async fn find_geocode_candidates(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<GeocodeCandidate>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::gauges::find_geocode_candidates(pool, limit).await
+            }
+        };
+        let gauges = sqlx::query_as!(
+            GeocodeCandidate,
+            r#"
+            SELECT station_id,
+                   latitude::FLOAT8 AS "latitude!",
+                   longitude::FLOAT8 AS "longitude!",
+                   city
+            FROM gauges
+            WHERE latitude IS NOT NULL
+              AND longitude IS NOT NULL
+              AND geocoded_at IS NULL
+              AND (city IS NULL OR nearest_place IS NULL)
+            ORDER BY station_id
+            LIMIT $1
+            "#,
+            limit
+        )
+        .fetch_all(pool)
+        .await?;
+
+        debug!("Found {} gauges to geocode", gauges.len());
+        Ok(gauges)
+    }
+
+    /// Record a reverse geocoding result, marking the gauge with its source
+    ///
+    /// An existing city is never overwritten; the nearest place is written when found.
+    #[instrument(skip(self))]
+    pub async fn record_geocode(
+        &self,
+        station_id: &str,
+        city: Option<&str>,
+        nearest_place: Option<&str>,
+        source: &str,
+    ) -> Result<(), DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::gauges::record_geocode(
+                    pool,
+                    station_id,
+                    city,
+                    nearest_place,
+                    source,
+                )
+                .await
+            }
+        };
+        sqlx::query!(
+            r#"
+            UPDATE gauges SET
+                city = COALESCE(city, LEFT($2, 100)),
+                nearest_place = COALESCE(LEFT($3, 255), nearest_place),
+                geocode_source = $4,
+                geocoded_at = NOW()
+            WHERE station_id = $1
+            "#,
+            station_id,
+            city,
+            nearest_place,
+            source
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Gauges with coordinates but no elevation, ordered by station ID
+    ///
+    /// Gauges already sampled are skipped, so a point outside the DEM is not sampled
+    /// again every run.
+    #[instrument(skip(self))]
+    pub async fn find_elevation_candidates(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<ElevationCandidate>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::gauges::find_elevation_candidates(pool, limit).await
+            }
+        };
+        let gauges = sqlx::query_as!(
+            ElevationCandidate,
+            r#"
+            SELECT station_id,
+                   latitude::FLOAT8 AS "latitude!",
+                   longitude::FLOAT8 AS "longitude!"
+            FROM gauges
+            WHERE latitude IS NOT NULL
+              AND longitude IS NOT NULL
+              AND elevation_ft IS NULL
+              AND elevation_sampled_at IS NULL
+            ORDER BY station_id
+            LIMIT $1
+            "#,
+            limit
+        )
+        .fetch_all(pool)
+        .await?;
+
+        debug!("Found {} gauges without elevation", gauges.len());
+        Ok(gauges)
+    }
+
+    /// Record a DEM sample; the elevation is only written when the gauge still has none
+    ///
+    /// `elevation_source` is set only when the sampled elevation is stored, so it always
+    /// describes the elevation in the row.
+    #[instrument(skip(self))]
+    pub async fn record_elevation_sample(
+        &self,
+        station_id: &str,
+        elevation_ft: Option<i32>,
+        source: &str,
+    ) -> Result<(), DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::gauges::record_elevation_sample(
+                    pool,
+                    station_id,
+                    elevation_ft,
+                    source,
+                )
+                .await
+            }
+        };
+        sqlx::query!(
+            r#"
+            UPDATE gauges SET
+                elevation_source = CASE
+                    WHEN elevation_ft IS NULL AND $2::INT4 IS NOT NULL THEN $3
+                    ELSE elevation_source
+                END,
+                elevation_ft = COALESCE(elevation_ft, $2),
+                elevation_sampled_at = NOW()
+            WHERE station_id = $1
+            "#,
+            station_id,
+            elevation_ft,
+            source
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
     /// Upsert gauge metadata from FOPR Meta_Stats sheet
     ///
     /// This inserts a new gauge or updates existing gauge metadata.
     /// Used during FOPR imports to ensure gauge exists before importing readings.
+    /// The status is only set on insert; afterwards it follows the status lifecycle.
+    /// Name, location, and coordinates corrected through the metadata editor are kept.
     #[instrument(skip(self, metadata), fields(station_id = %metadata.station_id))]
     pub async fn upsert_gauge_metadata(&self, metadata: &MetaStatsData) -> Result<(), DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::gauges::upsert_gauge_metadata(pool, metadata).await
+            }
+        };
         info!(
             "Upserting gauge metadata for station {}",
             metadata.station_id
@@ -164,25 +1057,32 @@ impl GaugeRepository {
             )
             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, 'fopr_import', NOW())
             ON CONFLICT (station_id) DO UPDATE SET
-                station_name = EXCLUDED.station_name,
+                station_name = CASE WHEN gauges.metadata_source = 'manual_edit'
+                    THEN gauges.station_name ELSE EXCLUDED.station_name END,
                 station_type = EXCLUDED.station_type,
                 previous_station_ids = EXCLUDED.previous_station_ids,
-                latitude = EXCLUDED.latitude,
-                longitude = EXCLUDED.longitude,
-                elevation_ft = EXCLUDED.elevation_ft,
-                county = EXCLUDED.county,
-                city = EXCLUDED.city,
-                location_description = EXCLUDED.location_description,
+                latitude = CASE WHEN gauges.metadata_source = 'manual_edit'
+                    THEN gauges.latitude ELSE EXCLUDED.latitude END,
+                longitude = CASE WHEN gauges.metadata_source = 'manual_edit'
+                    THEN gauges.longitude ELSE EXCLUDED.longitude END,
+                elevation_ft = CASE WHEN gauges.metadata_source = 'manual_edit'
+                    THEN gauges.elevation_ft ELSE EXCLUDED.elevation_ft END,
+                county = CASE WHEN gauges.metadata_source = 'manual_edit'
+                    THEN gauges.county ELSE EXCLUDED.county END,
+                city = CASE WHEN gauges.metadata_source = 'manual_edit'
+                    THEN gauges.city ELSE EXCLUDED.city END,
+                location_description = CASE WHEN gauges.metadata_source = 'manual_edit'
+                    THEN gauges.location_description ELSE EXCLUDED.location_description END,
                 installation_date = EXCLUDED.installation_date,
                 data_begins_date = EXCLUDED.data_begins_date,
-                status = EXCLUDED.status,
                 avg_annual_precipitation_inches = EXCLUDED.avg_annual_precipitation_inches,
                 complete_years_count = EXCLUDED.complete_years_count,
                 incomplete_months_count = EXCLUDED.incomplete_months_count,
                 missing_months_count = EXCLUDED.missing_months_count,
                 data_quality_remarks = EXCLUDED.data_quality_remarks,
                 fopr_metadata = EXCLUDED.fopr_metadata,
-                metadata_source = 'fopr_import',
+                metadata_source = CASE WHEN gauges.metadata_source = 'manual_edit'
+                    THEN 'manual_edit' ELSE 'fopr_import' END,
                 metadata_updated_at = NOW()
             "#
         )
@@ -205,7 +1105,7 @@ impl GaugeRepository {
         .bind(metadata.missing_months_count)
         .bind(&metadata.data_quality_remarks)
         .bind(serde_json::to_value(&metadata.fopr_metadata).unwrap())
-        .execute(&self.pool)
+        .execute(pool)
         .await?;
 
         if result.rows_affected() > 0 {
@@ -221,16 +1121,53 @@ impl GaugeRepository {
     /// Check if a gauge exists by station_id
     #[instrument(skip(self), fields(station_id = %station_id))]
     pub async fn gauge_exists(&self, station_id: &str) -> Result<bool, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => return sqlite::gauges::gauge_exists(pool, station_id).await,
+        };
         let count = sqlx::query_scalar!(
             r#"SELECT COUNT(*) FROM gauges WHERE station_id = $1"#,
             station_id
         )
-        .fetch_one(&self.pool)
+        .fetch_one(pool)
         .await?;
 
         Ok(count.unwrap_or(0) > 0)
     }
 
+    /// Add a gauge known only from the gauge list, without FOPR metadata
+    ///
+    /// Used where there is no FOPR import queue (SQLite) so the gauge's list entry and
+    /// readings can be stored. Existing gauges are untouched; returns whether one was added.
+    #[instrument(skip(self, gauge), fields(station_id = %gauge.station_id))]
+    pub async fn register_from_list(&self, gauge: &FetchedGauge) -> Result<bool, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => return sqlite::gauges::register_from_list(pool, gauge).await,
+        };
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO gauges (
+                station_id, station_name, city, elevation_ft, location_description,
+                metadata_source, metadata_updated_at
+            )
+            VALUES ($1, $2, LEFT($3, 100), $4, $5, 'gauge_list', NOW())
+            ON CONFLICT (station_id) DO NOTHING
+            "#,
+            gauge.station_id.as_str(),
+            gauge.gauge_name,
+            gauge.city_town,
+            gauge.elevation_ft,
+            gauge.general_location
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
     // ============================================================
     // Transaction-aware methods for testing
     // ============================================================
@@ -259,25 +1196,32 @@ impl GaugeRepository {
             )
             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, 'fopr_import', NOW())
             ON CONFLICT (station_id) DO UPDATE SET
-                station_name = EXCLUDED.station_name,
+                station_name = CASE WHEN gauges.metadata_source = 'manual_edit'
+                    THEN gauges.station_name ELSE EXCLUDED.station_name END,
                 station_type = EXCLUDED.station_type,
                 previous_station_ids = EXCLUDED.previous_station_ids,
-                latitude = EXCLUDED.latitude,
-                longitude = EXCLUDED.longitude,
-                elevation_ft = EXCLUDED.elevation_ft,
-                county = EXCLUDED.county,
-                city = EXCLUDED.city,
-                location_description = EXCLUDED.location_description,
+                latitude = CASE WHEN gauges.metadata_source = 'manual_edit'
+                    THEN gauges.latitude ELSE EXCLUDED.latitude END,
+                longitude = CASE WHEN gauges.metadata_source = 'manual_edit'
+                    THEN gauges.longitude ELSE EXCLUDED.longitude END,
+                elevation_ft = CASE WHEN gauges.metadata_source = 'manual_edit'
+                    THEN gauges.elevation_ft ELSE EXCLUDED.elevation_ft END,
+                county = CASE WHEN gauges.metadata_source = 'manual_edit'
+                    THEN gauges.county ELSE EXCLUDED.county END,
+                city = CASE WHEN gauges.metadata_source = 'manual_edit'
+                    THEN gauges.city ELSE EXCLUDED.city END,
+                location_description = CASE WHEN gauges.metadata_source = 'manual_edit'
+                    THEN gauges.location_description ELSE EXCLUDED.location_description END,
                 installation_date = EXCLUDED.installation_date,
                 data_begins_date = EXCLUDED.data_begins_date,
-                status = EXCLUDED.status,
                 avg_annual_precipitation_inches = EXCLUDED.avg_annual_precipitation_inches,
                 complete_years_count = EXCLUDED.complete_years_count,
                 incomplete_months_count = EXCLUDED.incomplete_months_count,
                 missing_months_count = EXCLUDED.missing_months_count,
                 data_quality_remarks = EXCLUDED.data_quality_remarks,
                 fopr_metadata = EXCLUDED.fopr_metadata,
-                metadata_source = 'fopr_import',
+                metadata_source = CASE WHEN gauges.metadata_source = 'manual_edit'
+                    THEN 'manual_edit' ELSE 'fopr_import' END,
                 metadata_updated_at = NOW()
             "#
         )
@@ -325,12 +1269,15 @@ impl GaugeRepository {
         let gauge = sqlx::query_as!(
             GaugeSummary,
             r#"
-            SELECT id, station_id, gauge_name, city_town, elevation_ft,
-                   general_location, msp_forecast_zone,
-                   rainfall_past_6h_inches, rainfall_past_24h_inches,
-                   last_scraped_at, created_at, updated_at
-            FROM gauge_summaries
-            WHERE station_id = $1
+            SELECT s.id, s.station_id, s.gauge_name, s.city_town, s.elevation_ft,
+                   s.general_location, s.msp_forecast_zone,
+                   s.rainfall_past_6h_inches, s.rainfall_past_24h_inches,
+                   COALESCE(g.status, 'Active') AS "status!",
+                   g.status_effective_date AS "status_effective_date?",
+                   s.last_scraped_at, s.created_at, s.updated_at
+            FROM gauge_summaries s
+            LEFT JOIN gauges g ON g.station_id = s.station_id
+            WHERE s.station_id = $1
             "#,
             station_id
         )
@@ -386,12 +1333,15 @@ impl GaugeRepository {
         let gauges = sqlx::query_as!(
             GaugeSummary,
             r#"
-            SELECT id, station_id, gauge_name, city_town, elevation_ft,
-                   general_location, msp_forecast_zone,
-                   rainfall_past_6h_inches, rainfall_past_24h_inches,
-                   last_scraped_at, created_at, updated_at
-            FROM gauge_summaries
-            ORDER BY city_town, gauge_name
+            SELECT s.id, s.station_id, s.gauge_name, s.city_town, s.elevation_ft,
+                   s.general_location, s.msp_forecast_zone,
+                   s.rainfall_past_6h_inches, s.rainfall_past_24h_inches,
+                   COALESCE(g.status, 'Active') AS "status!",
+                   g.status_effective_date AS "status_effective_date?",
+                   s.last_scraped_at, s.created_at, s.updated_at
+            FROM gauge_summaries s
+            LEFT JOIN gauges g ON g.station_id = s.station_id
+            ORDER BY s.city_town, s.gauge_name
             LIMIT $1 OFFSET $2
             "#,
             limit,
@@ -0,0 +1,275 @@
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+use sqlx::{Postgres, Transaction};
+use tracing::{debug, instrument};
+
+#[cfg(feature = "sqlite")]
+use crate::db::sqlite;
+use crate::db::{DailyRainfallIntensity, DbError, DbPool, HourlyRainfallSummary};
+use crate::fetcher::LIVE_DATA_SOURCE;
+
+/// `data_source` values whose readings come more than once a day
+///
+/// Only these are bucketed by hour: a daily import total would land in a single hour.
+/// Add interval sources here as they are imported.
+pub const SUB_DAILY_SOURCES: &[&str] = &[LIVE_DATA_SOURCE];
+
+/// UTC days whose peak intensity can change with readings in `[start, end)`
+///
+/// A reading counts toward windows up to an hour after it, so a reading late in a day
+/// also touches the next one. Returns midnight bounds, the end exclusive.
+fn intensity_days(
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+) -> (Option<DateTime<Utc>>, Option<DateTime<Utc>>) {
+    let midnight = |t: DateTime<Utc>| t.date_naive().and_time(Default::default()).and_utc();
+    (
+        start.map(midnight),
+        end.map(|end| midnight(end + TimeDelta::hours(1)) + TimeDelta::days(1)),
+    )
+}
+
+#[derive(Clone)]
+pub struct HourlyRainfallRepository {
+    db: DbPool,
+}
+
+impl HourlyRainfallRepository {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        Self { db: pool.into() }
+    }
+
+    /// Hourly summaries of one station in `[start, end)`, oldest first
+    #[instrument(skip(self))]
+    pub async fn get_summaries_by_date_range(
+        &self,
+        station_id: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<HourlyRainfallSummary>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::hourly_rainfall::get_summaries_by_date_range(
+                    pool, station_id, start, end,
+                )
+                .await
+            }
+        };
+        let hours = sqlx::query_as!(
+            HourlyRainfallSummary,
+            r#"
+            SELECT hour_start, total_rainfall_inches, max_incremental_inches, reading_count
+            FROM hourly_rainfall_summary
+            WHERE station_id = $1 AND hour_start >= $2 AND hour_start < $3
+            ORDER BY hour_start
+            "#,
+            station_id,
+            start,
+            end
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(hours)
+    }
+
+    /// Peak intensities of one station for the days in `[first, last]`, oldest first
+    ///
+    /// Days without sub-daily readings have no row.
+    #[instrument(skip(self))]
+    pub async fn get_daily_intensity(
+        &self,
+        station_id: &str,
+        first: NaiveDate,
+        last: NaiveDate,
+    ) -> Result<Vec<DailyRainfallIntensity>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::hourly_rainfall::get_daily_intensity(pool, station_id, first, last)
+                    .await
+            }
+        };
+        let days = sqlx::query_as!(
+            DailyRainfallIntensity,
+            r#"
+            SELECT date, max_15min_intensity_in_per_hr, max_1h_intensity_in_per_hr
+            FROM daily_rainfall_intensity
+            WHERE station_id = $1 AND date >= $2 AND date <= $3
+            ORDER BY date
+            "#,
+            station_id,
+            first,
+            last
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(days)
+    }
+
+    /// Rebuild hourly summaries from sub-daily readings
+    ///
+    /// All filters are optional; `start` and `end` should fall on hour boundaries, and
+    /// `end` is exclusive. Summaries of hours left without readings are deleted. The
+    /// daily peak intensities of the days those readings reach are rebuilt too. Returns
+    /// the number of hours summarized.
+    #[instrument(skip(self))]
+    pub async fn recalculate_hours(
+        &self,
+        station_id: Option<&str>,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<u64, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::hourly_rainfall::recalculate_hours(
+                    pool,
+                    station_id,
+                    start,
+                    end,
+                    intensity_days(start, end),
+                    SUB_DAILY_SOURCES,
+                )
+                .await
+            }
+        };
+        let mut tx = pool.begin().await?;
+        let hours = self
+            .recalculate_hours_tx(&mut tx, station_id, start, end)
+            .await?;
+        tx.commit().await?;
+        Ok(hours)
+    }
+
+    /// Rebuild hourly summaries using a transaction; see `recalculate_hours`
+    #[instrument(skip(self, tx))]
+    pub async fn recalculate_hours_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        station_id: Option<&str>,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<u64, DbError> {
+        sqlx::query!(
+            r#"
+            DELETE FROM hourly_rainfall_summary
+            WHERE ($1::VARCHAR IS NULL OR station_id = $1)
+              AND ($2::TIMESTAMPTZ IS NULL OR hour_start >= $2)
+              AND ($3::TIMESTAMPTZ IS NULL OR hour_start < $3)
+            "#,
+            station_id,
+            start,
+            end
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        let sources: Vec<String> = SUB_DAILY_SOURCES.iter().map(|s| s.to_string()).collect();
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO hourly_rainfall_summary
+                (station_id, hour_start, total_rainfall_inches, max_incremental_inches,
+                 reading_count)
+            SELECT station_id,
+                   date_trunc('hour', reading_datetime AT TIME ZONE 'UTC') AT TIME ZONE 'UTC',
+                   ROUND(SUM(incremental_inches) * 100) / 100,
+                   MAX(incremental_inches),
+                   COUNT(*)
+            FROM rain_readings
+            WHERE ($1::VARCHAR IS NULL OR station_id = $1)
+              AND ($2::TIMESTAMPTZ IS NULL OR reading_datetime >= $2)
+              AND ($3::TIMESTAMPTZ IS NULL OR reading_datetime < $3)
+              AND data_source = ANY($4)
+            GROUP BY 1, 2
+            "#,
+            station_id,
+            start,
+            end,
+            &sources
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        debug!("Recalculated {} hourly summaries", result.rows_affected());
+        self.recalculate_intensity_tx(tx, station_id, start, end)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Rebuild the daily peak intensities that readings in `[start, end)` feed
+    ///
+    /// Each reading closes a 15-minute and a 1-hour window; a day's peak is its largest
+    /// window total, the 15-minute one scaled to inches per hour.
+    async fn recalculate_intensity_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        station_id: Option<&str>,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<u64, DbError> {
+        let (day_start, day_end) = intensity_days(start, end);
+        sqlx::query!(
+            r#"
+            DELETE FROM daily_rainfall_intensity
+            WHERE ($1::VARCHAR IS NULL OR station_id = $1)
+              AND ($2::TIMESTAMPTZ IS NULL OR date >= ($2 AT TIME ZONE 'UTC')::DATE)
+              AND ($3::TIMESTAMPTZ IS NULL OR date < ($3 AT TIME ZONE 'UTC')::DATE)
+            "#,
+            station_id,
+            day_start,
+            day_end
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        // Readings from the hour before the first day fill that day's early windows
+        let sources: Vec<String> = SUB_DAILY_SOURCES.iter().map(|s| s.to_string()).collect();
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO daily_rainfall_intensity
+                (station_id, date, max_15min_intensity_in_per_hr, max_1h_intensity_in_per_hr)
+            SELECT station_id,
+                   (reading_datetime AT TIME ZONE 'UTC')::DATE,
+                   ROUND(MAX(rain_15min) * 4 * 100) / 100,
+                   ROUND(MAX(rain_1h) * 100) / 100
+            FROM (
+                SELECT station_id, reading_datetime,
+                       SUM(incremental_inches) OVER (
+                           PARTITION BY station_id ORDER BY reading_datetime
+                           RANGE BETWEEN INTERVAL '15 minutes' - INTERVAL '1 microsecond'
+                                 PRECEDING AND CURRENT ROW
+                       ) AS rain_15min,
+                       SUM(incremental_inches) OVER (
+                           PARTITION BY station_id ORDER BY reading_datetime
+                           RANGE BETWEEN INTERVAL '1 hour' - INTERVAL '1 microsecond'
+                                 PRECEDING AND CURRENT ROW
+                       ) AS rain_1h
+                FROM rain_readings
+                WHERE ($1::VARCHAR IS NULL OR station_id = $1)
+                  AND ($2::TIMESTAMPTZ IS NULL OR reading_datetime >= $2 - INTERVAL '1 hour')
+                  AND ($3::TIMESTAMPTZ IS NULL OR reading_datetime < $3)
+                  AND data_source = ANY($4)
+            ) windows
+            WHERE $2::TIMESTAMPTZ IS NULL OR reading_datetime >= $2
+            GROUP BY 1, 2
+            "#,
+            station_id,
+            day_start,
+            day_end,
+            &sources
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        debug!(
+            "Recalculated {} daily peak intensities",
+            result.rows_affected()
+        );
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,163 @@
+use chrono::{DateTime, Utc};
+use tracing::{debug, instrument};
+
+#[cfg(feature = "sqlite")]
+use crate::db::sqlite;
+use crate::db::{DbError, DbPool, IdempotencyRecord};
+
+#[derive(Clone)]
+pub struct IdempotencyRepository {
+    db: DbPool,
+}
+
+impl IdempotencyRepository {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        Self { db: pool.into() }
+    }
+
+    /// Claim a key for a new request
+    ///
+    /// Keys created before `expired_before` are purged first. Returns None when the key
+    /// was claimed, or the existing record when the key is already in use.
+    #[instrument(skip(self, request_hash))]
+    pub async fn reserve(
+        &self,
+        key: &str,
+        method: &str,
+        path: &str,
+        request_hash: &str,
+        expired_before: DateTime<Utc>,
+    ) -> Result<Option<IdempotencyRecord>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::idempotency::reserve(
+                    pool,
+                    key,
+                    method,
+                    path,
+                    request_hash,
+                    expired_before,
+                )
+                .await
+            }
+        };
+        let purged = sqlx::query!(
+            "DELETE FROM idempotency_keys WHERE created_at < $1",
+            expired_before
+        )
+        .execute(pool)
+        .await?
+        .rows_affected();
+        if purged > 0 {
+            debug!("Purged {} expired idempotency keys", purged);
+        }
+
+        let inserted = sqlx::query!(
+            r#"
+            INSERT INTO idempotency_keys (idempotency_key, method, path, request_hash)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (idempotency_key, method, path) DO NOTHING
+            "#,
+            key,
+            method,
+            path,
+            request_hash
+        )
+        .execute(pool)
+        .await?
+        .rows_affected();
+
+        if inserted == 1 {
+            return Ok(None);
+        }
+
+        let existing = sqlx::query_as!(
+            IdempotencyRecord,
+            r#"
+            SELECT request_hash, status_code, content_type, response_body, created_at
+            FROM idempotency_keys
+            WHERE idempotency_key = $1 AND method = $2 AND path = $3
+            "#,
+            key,
+            method,
+            path
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(existing)
+    }
+
+    /// Store the response for a claimed key
+    #[instrument(skip(self, body))]
+    pub async fn complete(
+        &self,
+        key: &str,
+        method: &str,
+        path: &str,
+        status_code: i16,
+        content_type: Option<&str>,
+        body: &[u8],
+    ) -> Result<(), DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::idempotency::complete(
+                    pool,
+                    key,
+                    method,
+                    path,
+                    status_code,
+                    content_type,
+                    body,
+                )
+                .await
+            }
+        };
+        sqlx::query!(
+            r#"
+            UPDATE idempotency_keys
+            SET status_code = $4, content_type = $5, response_body = $6, completed_at = NOW()
+            WHERE idempotency_key = $1 AND method = $2 AND path = $3
+            "#,
+            key,
+            method,
+            path,
+            status_code,
+            content_type,
+            body
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Drop an in-flight claim so the request can be retried
+    #[instrument(skip(self))]
+    pub async fn release(&self, key: &str, method: &str, path: &str) -> Result<(), DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::idempotency::release(pool, key, method, path).await
+            }
+        };
+        sqlx::query!(
+            r#"
+            DELETE FROM idempotency_keys
+            WHERE idempotency_key = $1 AND method = $2 AND path = $3 AND status_code IS NULL
+            "#,
+            key,
+            method,
+            path
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use chrono::Datelike;
+use tracing::{debug, info, instrument};
+
+#[cfg(feature = "sqlite")]
+use crate::db::sqlite;
+use crate::db::{DbError, DbPool, MonthlyRainfallRepository, ReadingRepository};
+use crate::importers::excel_importer::HistoricalReading;
+use crate::utils;
+
+/// What an import does with a reading already stored for the same gauge and time
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Keep the stored reading (ON CONFLICT DO NOTHING)
+    #[default]
+    Skip,
+    /// Overwrite it when the amount or metadata differs, recording the previous values
+    /// in `reading_revisions`; live scraper readings are still kept
+    Update,
+}
+
+/// What committing one station-month did
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MonthCommit {
+    pub inserted: usize,
+    /// Stored readings overwritten (ConflictPolicy::Update only)
+    pub updated: usize,
+    pub duplicates: usize,
+}
+
+/// Totals of a station's month-by-month import
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChunkedImport {
+    pub inserted: usize,
+    pub updated: usize,
+    pub duplicates: usize,
+    /// (year, month) pairs committed in this run, oldest first
+    pub months_committed: Vec<(i32, u32)>,
+    /// Months skipped because an earlier run already committed the same readings
+    pub months_resumed: usize,
+}
+
+/// Historical imports committed one station-month at a time
+///
+/// Each month's readings, its monthly summary, and an `import_chunks` row recording the
+/// commit go in one transaction, so an interrupted import never leaves a month half
+/// loaded or its summary stale, and a rerun picks up after the last committed month.
+#[derive(Clone)]
+pub struct ImportChunkRepository {
+    db: DbPool,
+    derive_cumulative: bool,
+    on_conflict: ConflictPolicy,
+}
+
+impl ImportChunkRepository {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        Self {
+            db: pool.into(),
+            derive_cumulative: false,
+            on_conflict: ConflictPolicy::Skip,
+        }
+    }
+
+    /// Back-fill water year cumulative totals in each commit (for sources, like FOPR
+    /// files, that only carry incremental amounts)
+    pub fn with_derived_cumulative(mut self) -> Self {
+        self.derive_cumulative = true;
+        self
+    }
+
+    /// Choose what happens to readings that are already stored (defaults to Skip)
+    pub fn with_conflict_policy(mut self, on_conflict: ConflictPolicy) -> Self {
+        self.on_conflict = on_conflict;
+        self
+    }
+
+    /// Readings per month committed from a source, keyed by (year, month)
+    #[instrument(skip(self))]
+    pub async fn committed_months(
+        &self,
+        station_id: &str,
+        data_source: &str,
+    ) -> Result<HashMap<(i32, u32), usize>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::import_chunks::committed_months(pool, station_id, data_source).await
+            }
+        };
+        let rows = sqlx::query!(
+            r#"
+            SELECT year, month, reading_count
+            FROM import_chunks
+            WHERE station_id = $1 AND data_source = $2
+            "#,
+            station_id,
+            data_source
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ((row.year, row.month as u32), row.reading_count as usize))
+            .collect())
+    }
+
+    /// Insert one station-month of readings and update its summary in a transaction
+    ///
+    /// With derived cumulative totals, the months whose totals the back-fill changed
+    /// (later months of the water year, when an earlier day arrives late) are
+    /// resummarized in the same transaction.
+    #[instrument(skip(self, readings), fields(count = readings.len()))]
+    pub async fn commit_month(
+        &self,
+        station_id: &str,
+        data_source: &str,
+        (year, month): (i32, u32),
+        readings: &[HistoricalReading],
+    ) -> Result<MonthCommit, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::import_chunks::commit_month(
+                    pool,
+                    station_id,
+                    data_source,
+                    (year, month),
+                    readings,
+                    self.derive_cumulative,
+                    self.on_conflict,
+                )
+                .await
+            }
+        };
+        let reading_repo = ReadingRepository::new(self.db.clone());
+        let monthly_repo = MonthlyRainfallRepository::new(self.db.clone());
+
+        let mut tx = pool.begin().await?;
+        let commit = match self.on_conflict {
+            ConflictPolicy::Skip => {
+                let (inserted, duplicates, _) = reading_repo
+                    .bulk_insert_historical_readings_tx(&mut tx, station_id, data_source, readings)
+                    .await?;
+                MonthCommit {
+                    inserted,
+                    updated: 0,
+                    duplicates,
+                }
+            }
+            ConflictPolicy::Update => {
+                let upsert = reading_repo
+                    .upsert_historical_readings_tx(&mut tx, station_id, data_source, readings)
+                    .await?;
+                MonthCommit {
+                    inserted: upsert.inserted,
+                    updated: upsert.updated,
+                    duplicates: upsert.unchanged,
+                }
+            }
+        };
+
+        let mut months = BTreeSet::from([(year, month)]);
+        if self.derive_cumulative {
+            let water_year = if month >= 10 { year + 1 } else { year };
+            months.extend(
+                reading_repo
+                    .backfill_cumulative_tx(&mut tx, station_id, data_source, water_year)
+                    .await?,
+            );
+        }
+        // Months in between are unchanged but cheaper to resummarize in the same statement
+        let (first, last) = (months.first().unwrap(), months.last().unwrap());
+        monthly_repo
+            .recalculate_station_summaries_tx(
+                &mut tx,
+                station_id,
+                utils::month_date_range(first.0, first.1).0,
+                utils::month_date_range(last.0, last.1).1,
+            )
+            .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO import_chunks
+                (station_id, data_source, year, month, reading_count, readings_inserted)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (station_id, data_source, year, month)
+            DO UPDATE SET
+                reading_count = EXCLUDED.reading_count,
+                readings_inserted = EXCLUDED.readings_inserted,
+                committed_at = NOW()
+            "#,
+            station_id,
+            data_source,
+            year,
+            month as i32,
+            readings.len() as i32,
+            commit.inserted as i32
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        debug!(
+            "Committed {}-{:02} for station {}: {} inserted, {} updated, {} duplicates",
+            year, month, station_id, commit.inserted, commit.updated, commit.duplicates
+        );
+        Ok(commit)
+    }
+
+    /// Import a station's readings month by month, oldest first
+    ///
+    /// Months committed by an earlier run with the same number of readings are skipped;
+    /// a month the source has added readings to since is committed again (existing
+    /// readings count as duplicates). With ConflictPolicy::Update nothing is skipped, since
+    /// a corrected file has the same readings with different values.
+    #[instrument(skip(self, readings), fields(count = readings.len()))]
+    pub async fn import_by_month(
+        &self,
+        station_id: &str,
+        data_source: &str,
+        readings: &[HistoricalReading],
+    ) -> Result<ChunkedImport, DbError> {
+        let mut by_month: BTreeMap<(i32, u32), Vec<HistoricalReading>> = BTreeMap::new();
+        for reading in readings {
+            let date = reading.reading_date;
+            by_month
+                .entry((date.year(), date.month()))
+                .or_default()
+                .push(reading.clone());
+        }
+
+        let committed = self.committed_months(station_id, data_source).await?;
+        let mut result = ChunkedImport::default();
+        for (month, month_readings) in by_month {
+            let resumable = self.on_conflict == ConflictPolicy::Skip
+                && committed.get(&month) == Some(&month_readings.len());
+            if resumable {
+                result.months_resumed += 1;
+                continue;
+            }
+            let commit = self
+                .commit_month(station_id, data_source, month, &month_readings)
+                .await?;
+            result.inserted += commit.inserted;
+            result.updated += commit.updated;
+            result.duplicates += commit.duplicates;
+            result.months_committed.push(month);
+        }
+
+        if result.months_resumed > 0 {
+            info!(
+                "Resumed import of station {} from {}: {} months already committed",
+                station_id, data_source, result.months_resumed
+            );
+        }
+        Ok(result)
+    }
+}
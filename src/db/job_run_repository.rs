@@ -0,0 +1,215 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use tracing::instrument;
+
+use crate::db::{DbError, DbPool, JobRun, JobRunKind, JobRunOutcome, JobRunTrend};
+
+/// A finished run, before it is stored
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewJobRun {
+    pub job: JobRunKind,
+    pub station_id: Option<String>,
+    pub fopr_job_id: Option<i32>,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: f64,
+    pub row_count: i64,
+    /// None when the run succeeded
+    pub error: Option<String>,
+}
+
+/// A job_runs row before its job and outcome are parsed
+struct JobRunRow {
+    id: i64,
+    job: String,
+    station_id: Option<String>,
+    fopr_job_id: Option<i32>,
+    started_at: DateTime<Utc>,
+    duration_ms: f64,
+    row_count: i64,
+    outcome: String,
+    error: Option<String>,
+}
+
+impl From<JobRunRow> for JobRun {
+    fn from(row: JobRunRow) -> Self {
+        Self {
+            id: row.id,
+            // The columns' CHECK constraints only admit known values
+            job: JobRunKind::parse(&row.job).unwrap_or(JobRunKind::Fetch),
+            station_id: row.station_id,
+            fopr_job_id: row.fopr_job_id,
+            started_at: row.started_at,
+            duration_ms: row.duration_ms,
+            row_count: row.row_count,
+            outcome: JobRunOutcome::parse(&row.outcome).unwrap_or(JobRunOutcome::Failed),
+            error: row.error,
+        }
+    }
+}
+
+/// One day's aggregate before its job is parsed
+struct JobRunTrendRow {
+    day: NaiveDate,
+    job: String,
+    runs: i64,
+    failures: i64,
+    row_count: i64,
+    avg_duration_ms: f64,
+    p95_duration_ms: f64,
+    rows_per_second: Option<f64>,
+}
+
+impl From<JobRunTrendRow> for JobRunTrend {
+    fn from(row: JobRunTrendRow) -> Self {
+        Self {
+            day: row.day,
+            job: JobRunKind::parse(&row.job).unwrap_or(JobRunKind::Fetch),
+            runs: row.runs,
+            failures: row.failures,
+            row_count: row.row_count,
+            avg_duration_ms: row.avg_duration_ms,
+            p95_duration_ms: row.p95_duration_ms,
+            rows_per_second: row.rows_per_second,
+        }
+    }
+}
+
+/// Append-only job run history; PostgreSQL only, like the FOPR job queue
+#[derive(Clone)]
+pub struct JobRunRepository {
+    db: DbPool,
+}
+
+impl JobRunRepository {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        Self { db: pool.into() }
+    }
+
+    #[instrument(skip(self, run), fields(job = run.job.as_str()))]
+    pub async fn insert(&self, run: &NewJobRun) -> Result<i64, DbError> {
+        let outcome = if run.error.is_some() {
+            JobRunOutcome::Failed
+        } else {
+            JobRunOutcome::Succeeded
+        };
+        let id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO job_runs
+                (job, station_id, fopr_job_id, started_at, duration_ms, row_count, outcome, error)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id
+            "#,
+            run.job.as_str(),
+            run.station_id,
+            run.fopr_job_id,
+            run.started_at,
+            run.duration_ms,
+            run.row_count,
+            outcome.as_str(),
+            run.error
+        )
+        .fetch_one(self.db.postgres()?)
+        .await?;
+
+        Ok(id)
+    }
+
+    /// Most recent runs first, skipping the newest `offset`
+    #[instrument(skip(self))]
+    pub async fn find_recent(
+        &self,
+        job: Option<JobRunKind>,
+        station_id: Option<&str>,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<JobRun>, DbError> {
+        let rows = sqlx::query_as!(
+            JobRunRow,
+            r#"
+            SELECT id, job, station_id, fopr_job_id, started_at, duration_ms, row_count,
+                   outcome, error
+            FROM job_runs
+            WHERE ($1::VARCHAR IS NULL OR job = $1)
+              AND ($2::VARCHAR IS NULL OR station_id = $2)
+            ORDER BY started_at DESC, id DESC
+            LIMIT $3 OFFSET $4
+            "#,
+            job.map(|j| j.as_str()),
+            station_id,
+            limit,
+            offset
+        )
+        .fetch_all(self.db.postgres()?)
+        .await?;
+
+        Ok(rows.into_iter().map(JobRun::from).collect())
+    }
+
+    /// Number of runs matching the `find_recent` filters
+    #[instrument(skip(self))]
+    pub async fn count(
+        &self,
+        job: Option<JobRunKind>,
+        station_id: Option<&str>,
+    ) -> Result<i64, DbError> {
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "count!"
+            FROM job_runs
+            WHERE ($1::VARCHAR IS NULL OR job = $1)
+              AND ($2::VARCHAR IS NULL OR station_id = $2)
+            "#,
+            job.map(|j| j.as_str()),
+            station_id
+        )
+        .fetch_one(self.db.postgres()?)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Per-job totals for each UTC day with runs since `since`, oldest first
+    #[instrument(skip(self))]
+    pub async fn daily_trend(
+        &self,
+        job: Option<JobRunKind>,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<JobRunTrend>, DbError> {
+        let rows = sqlx::query_as!(
+            JobRunTrendRow,
+            r#"
+            SELECT
+                (started_at AT TIME ZONE 'UTC')::date AS "day!",
+                job,
+                COUNT(*) AS "runs!",
+                COUNT(*) FILTER (WHERE outcome = 'failed') AS "failures!",
+                COALESCE(SUM(row_count), 0)::BIGINT AS "row_count!",
+                AVG(duration_ms) AS "avg_duration_ms!",
+                PERCENTILE_CONT(0.95) WITHIN GROUP (ORDER BY duration_ms) AS "p95_duration_ms!",
+                SUM(row_count) FILTER (WHERE outcome = 'succeeded')::FLOAT8
+                    / NULLIF(SUM(duration_ms) FILTER (WHERE outcome = 'succeeded'), 0)
+                    * 1000 AS rows_per_second
+            FROM job_runs
+            WHERE started_at >= $1
+              AND ($2::VARCHAR IS NULL OR job = $2)
+            GROUP BY 1, job
+            ORDER BY 1, job
+            "#,
+            since,
+            job.map(|j| j.as_str())
+        )
+        .fetch_all(self.db.postgres()?)
+        .await?;
+
+        Ok(rows.into_iter().map(JobRunTrend::from).collect())
+    }
+
+    /// Delete runs that started before `cutoff`; returns how many were deleted
+    #[instrument(skip(self))]
+    pub async fn delete_before(&self, cutoff: DateTime<Utc>) -> Result<u64, DbError> {
+        let result = sqlx::query!("DELETE FROM job_runs WHERE started_at < $1", cutoff)
+            .execute(self.db.postgres()?)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
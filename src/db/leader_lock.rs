@@ -0,0 +1,74 @@
+// PostgreSQL advisory lock behind scheduler leader election (see `crate::leader`)
+//
+// The lock is session-level and held by one dedicated connection rather than a pooled
+// one, so it lives exactly as long as that connection. The connection's application_name
+// carries the replica's identity, which lets followers see who holds the lock.
+
+use std::str::FromStr;
+
+use sqlx::postgres::{PgConnectOptions, PgConnection};
+use sqlx::{ConnectOptions, Connection};
+
+use crate::db::DbError;
+
+/// Advisory lock key shared by every replica ("raintrkr")
+pub const LEADER_LOCK_KEY: i64 = 0x7261_696e_7472_6b72;
+
+/// application_name prefix of election connections
+const APPLICATION_NAME_PREFIX: &str = "rain-tracker-leader:";
+
+pub struct LeaderLock {
+    conn: PgConnection,
+    key: i64,
+}
+
+impl LeaderLock {
+    /// Open the dedicated election connection for `instance_id`
+    pub async fn connect(url: &str, instance_id: &str, key: i64) -> Result<Self, DbError> {
+        let conn = PgConnectOptions::from_str(url)?
+            .application_name(&format!("{APPLICATION_NAME_PREFIX}{instance_id}"))
+            .connect()
+            .await?;
+        Ok(Self { conn, key })
+    }
+
+    /// Take the lock if no other session holds it; true once held
+    ///
+    /// Session advisory locks stack, so a holder must not call this again.
+    pub async fn try_acquire(&mut self) -> Result<bool, DbError> {
+        let locked =
+            sqlx::query_scalar!(r#"SELECT pg_try_advisory_lock($1) AS "locked!""#, self.key)
+                .fetch_one(&mut self.conn)
+                .await?;
+        Ok(locked)
+    }
+
+    /// Confirm the connection, and with it any lock it holds, is still alive
+    pub async fn ping(&mut self) -> Result<(), DbError> {
+        self.conn.ping().await?;
+        Ok(())
+    }
+
+    /// Identity of the replica holding the lock, if any
+    pub async fn holder(&mut self) -> Result<Option<String>, DbError> {
+        let application_name = sqlx::query_scalar!(
+            r#"
+            SELECT a.application_name AS "application_name!"
+            FROM pg_locks l
+            JOIN pg_stat_activity a ON a.pid = l.pid
+            WHERE l.locktype = 'advisory'
+              AND l.granted
+              AND l.classid = ($1::bigint >> 32)::oid
+              AND l.objid = ($1::bigint & 4294967295)::oid
+              AND l.objsubid = 1
+            LIMIT 1
+            "#,
+            self.key
+        )
+        .fetch_optional(&mut self.conn)
+        .await?;
+
+        Ok(application_name
+            .and_then(|name| name.strip_prefix(APPLICATION_NAME_PREFIX).map(String::from)))
+    }
+}
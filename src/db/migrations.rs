@@ -0,0 +1,238 @@
+// Migration status and controlled migration runs
+//
+// Startup applies pending migrations by default (DbPool::migrate). Operators who need
+// change control set AUTO_MIGRATE=false so the service refuses to start with pending
+// migrations, and apply them with `historical-import migrate up`. Migrations shipped with a
+// `.down.sql` script can be reverted with `migrate down --to <version>`; older migrations
+// are plain `.sql` files and cannot.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use sqlx::migrate::{AppliedMigration, Migrate, MigrateError, Migration, Migrator};
+
+use crate::db::{DbError, DbPool};
+
+pub(crate) static POSTGRES_MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+#[cfg(feature = "sqlite")]
+pub(crate) static SQLITE_MIGRATOR: Migrator = sqlx::migrate!("./migrations/sqlite");
+
+/// One migration known to this build and whether the database has it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub applied: bool,
+    /// Applied, but the migration file has changed since
+    pub checksum_mismatch: bool,
+    /// Has a down script, so `migrate down` can revert it
+    pub reversible: bool,
+}
+
+impl DbPool {
+    pub(crate) fn migrator(&self) -> &'static Migrator {
+        match self {
+            DbPool::Postgres(_) => &POSTGRES_MIGRATOR,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(_) => &SQLITE_MIGRATOR,
+        }
+    }
+
+    /// Every migration in this build, oldest first, with its state in the database
+    pub async fn migration_status(&self) -> Result<Vec<MigrationStatus>, DbError> {
+        let migrator = self.migrator();
+        let applied = match self {
+            DbPool::Postgres(pool) => list_applied(&mut *pool.acquire().await?).await?,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => list_applied(&mut *pool.acquire().await?).await?,
+        };
+
+        Ok(up_migrations(migrator)
+            .map(|m| {
+                let applied = applied.get(&m.version);
+                MigrationStatus {
+                    version: m.version,
+                    description: m.description.to_string(),
+                    applied: applied.is_some(),
+                    checksum_mismatch: applied.is_some_and(|a| a.checksum != m.checksum),
+                    reversible: down_migration(migrator, m.version).is_some(),
+                }
+            })
+            .collect())
+    }
+
+    /// Fail unless every migration is applied unmodified (startup with AUTO_MIGRATE=false)
+    pub async fn ensure_migrated(&self) -> Result<(), DbError> {
+        let status = self.migration_status().await?;
+        if let Some(changed) = status.iter().find(|m| m.checksum_mismatch) {
+            return Err(migrate_error(MigrateError::VersionMismatch(
+                changed.version,
+            )));
+        }
+
+        let pending: Vec<i64> = status
+            .iter()
+            .filter(|m| !m.applied)
+            .map(|m| m.version)
+            .collect();
+        match pending.last() {
+            Some(&latest) => Err(DbError::PendingMigrations {
+                count: pending.len(),
+                latest,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Apply pending migrations up to and including `target` (all when `None`)
+    ///
+    /// Returns the versions applied, oldest first.
+    pub async fn migrate_up(&self, target: Option<i64>) -> Result<Vec<i64>, DbError> {
+        let migrator = self.migrator();
+        if let Some(target) = target {
+            check_known(migrator, target)?;
+        }
+
+        match self {
+            DbPool::Postgres(pool) => run_up(&mut *pool.acquire().await?, migrator, target).await,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => run_up(&mut *pool.acquire().await?, migrator, target).await,
+        }
+    }
+
+    /// Revert applied migrations newer than `target` (0 reverts everything)
+    ///
+    /// Nothing is reverted unless every migration involved has a down script. Returns the
+    /// versions reverted, newest first.
+    pub async fn migrate_down(&self, target: i64) -> Result<Vec<i64>, DbError> {
+        let migrator = self.migrator();
+        if target != 0 {
+            check_known(migrator, target)?;
+        }
+
+        match self {
+            DbPool::Postgres(pool) => run_down(&mut *pool.acquire().await?, migrator, target).await,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => run_down(&mut *pool.acquire().await?, migrator, target).await,
+        }
+    }
+}
+
+fn migrate_error(e: MigrateError) -> DbError {
+    DbError::from(sqlx::Error::from(e))
+}
+
+fn up_migrations(migrator: &Migrator) -> impl Iterator<Item = &Migration> {
+    migrator
+        .iter()
+        .filter(|m| m.migration_type.is_up_migration())
+}
+
+fn down_migration(migrator: &Migrator, version: i64) -> Option<&Migration> {
+    migrator
+        .iter()
+        .find(|m| m.version == version && m.migration_type.is_down_migration())
+}
+
+fn check_known(migrator: &Migrator, version: i64) -> Result<(), DbError> {
+    if migrator.version_exists(version) {
+        Ok(())
+    } else {
+        Err(migrate_error(MigrateError::VersionNotPresent(version)))
+    }
+}
+
+async fn list_applied<C: Migrate>(conn: &mut C) -> Result<HashMap<i64, AppliedMigration>, DbError> {
+    conn.ensure_migrations_table()
+        .await
+        .map_err(migrate_error)?;
+    let applied = conn
+        .list_applied_migrations()
+        .await
+        .map_err(migrate_error)?;
+
+    Ok(applied.into_iter().map(|m| (m.version, m)).collect())
+}
+
+/// Load the applied migrations, checking they match this build
+async fn load_applied<C: Migrate>(
+    conn: &mut C,
+    migrator: &Migrator,
+) -> Result<HashMap<i64, AppliedMigration>, DbError> {
+    let applied = list_applied(conn).await?;
+    if let Some(version) = conn.dirty_version().await.map_err(migrate_error)? {
+        return Err(migrate_error(MigrateError::Dirty(version)));
+    }
+
+    for (version, migration) in &applied {
+        match up_migrations(migrator).find(|m| m.version == *version) {
+            None => return Err(migrate_error(MigrateError::VersionMissing(*version))),
+            Some(m) if m.checksum != migration.checksum => {
+                return Err(migrate_error(MigrateError::VersionMismatch(*version)))
+            }
+            Some(_) => {}
+        }
+    }
+    Ok(applied)
+}
+
+async fn run_up<C: Migrate>(
+    conn: &mut C,
+    migrator: &Migrator,
+    target: Option<i64>,
+) -> Result<Vec<i64>, DbError> {
+    conn.lock().await.map_err(migrate_error)?;
+    let result = apply_up(conn, migrator, target).await;
+    // Release the lock even on failure; the connection goes back to the pool
+    conn.unlock().await.map_err(migrate_error)?;
+    result
+}
+
+async fn apply_up<C: Migrate>(
+    conn: &mut C,
+    migrator: &Migrator,
+    target: Option<i64>,
+) -> Result<Vec<i64>, DbError> {
+    let applied = load_applied(conn, migrator).await?;
+
+    let mut versions = Vec::new();
+    for migration in up_migrations(migrator)
+        .filter(|m| !applied.contains_key(&m.version))
+        .filter(|m| target.is_none_or(|t| m.version <= t))
+    {
+        conn.apply(migration).await.map_err(migrate_error)?;
+        versions.push(migration.version);
+    }
+    Ok(versions)
+}
+
+async fn run_down<C: Migrate>(
+    conn: &mut C,
+    migrator: &Migrator,
+    target: i64,
+) -> Result<Vec<i64>, DbError> {
+    conn.lock().await.map_err(migrate_error)?;
+    let result = apply_down(conn, migrator, target).await;
+    conn.unlock().await.map_err(migrate_error)?;
+    result
+}
+
+async fn apply_down<C: Migrate>(
+    conn: &mut C,
+    migrator: &Migrator,
+    target: i64,
+) -> Result<Vec<i64>, DbError> {
+    let applied = load_applied(conn, migrator).await?;
+
+    let mut to_revert: Vec<i64> = applied.keys().copied().filter(|v| *v > target).collect();
+    to_revert.sort_unstable_by(|a, b| b.cmp(a));
+    let down: Vec<&Migration> = to_revert
+        .iter()
+        .map(|v| down_migration(migrator, *v).ok_or(DbError::Irreversible(*v)))
+        .collect::<Result<_, _>>()?;
+
+    for migration in down {
+        conn.revert(migration).await.map_err(migrate_error)?;
+    }
+    Ok(to_revert)
+}
@@ -1,60 +1,1205 @@
 use chrono::{DateTime, Utc};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use utoipa::ToSchema;
 
+use crate::anomaly::{AnomalyKind, AnomalyStatus};
+use crate::digest::DigestFrequency;
+use crate::pagination::PageInfo;
+use crate::units::Inches;
+
 // Database entity models
+/// A single rain gauge reading
 #[derive(Debug, Clone, FromRow, Serialize, ToSchema)]
 pub struct Reading {
+    #[schema(example = 1042)]
     pub id: i64,
+    #[schema(example = "2025-01-15T14:30:00Z")]
     pub reading_datetime: DateTime<Utc>,
-    pub cumulative_inches: f64,
-    pub incremental_inches: f64,
+    /// Running water-year total at the time of the reading
+    #[schema(value_type = f64, example = 2.36)]
+    pub cumulative_inches: Inches,
+    /// Rainfall since the previous reading
+    #[schema(value_type = f64, example = 0.04)]
+    pub incremental_inches: Inches,
+    #[schema(example = "59700")]
     pub station_id: String,
+    #[schema(example = "2025-01-15T14:35:12Z")]
     pub created_at: DateTime<Utc>,
 }
 
+/// A reading with where it came from, for the analytics export
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct ExportReading {
+    pub station_id: String,
+    pub reading_datetime: DateTime<Utc>,
+    pub cumulative_inches: f64,
+    pub incremental_inches: f64,
+    /// `live_scrape`, or the file a historical import read the reading from
+    pub data_source: String,
+}
+
+/// Current state of a gauge from the latest gauge list scrape
 #[derive(Debug, Clone, FromRow, Serialize, ToSchema)]
 pub struct GaugeSummary {
+    #[schema(example = 17)]
     pub id: i64,
+    #[schema(example = "59700")]
     pub station_id: String,
+    #[schema(example = "Aztec Park")]
     pub gauge_name: String,
+    #[schema(example = "Scottsdale")]
     pub city_town: Option<String>,
+    #[schema(example = 1465)]
     pub elevation_ft: Option<i32>,
+    #[schema(example = "Near Thunderbird & Frank Lloyd Wright")]
     pub general_location: Option<String>,
+    #[schema(example = "E1")]
     pub msp_forecast_zone: Option<String>,
+    #[schema(example = 0.0)]
     pub rainfall_past_6h_inches: Option<f64>,
+    #[schema(example = 0.28)]
     pub rainfall_past_24h_inches: Option<f64>,
+    /// Lifecycle status: Active, Inactive, or Decommissioned
+    #[schema(example = "Active")]
+    pub status: String,
+    /// Date the current status took effect (null if unchanged since import)
+    #[schema(example = "2024-06-01")]
+    pub status_effective_date: Option<chrono::NaiveDate>,
+    #[schema(example = "2025-01-15T14:30:00Z")]
     pub last_scraped_at: DateTime<Utc>,
+    #[schema(example = "2024-10-01T00:00:00Z")]
+    pub created_at: DateTime<Utc>,
+    #[schema(example = "2025-01-15T14:30:00Z")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A gauge position with its current rainfall, for map tiles
+#[derive(Debug, Clone, FromRow)]
+pub struct GaugeMapPoint {
+    pub station_id: String,
+    pub gauge_name: String,
+    pub city_town: Option<String>,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub elevation_ft: Option<i32>,
+    pub rainfall_past_6h_inches: Option<f64>,
+    pub rainfall_past_24h_inches: Option<f64>,
+}
+
+/// A gauge merged from the gauge list scrape (gauge_summaries) and FOPR metadata (gauges)
+///
+/// Scraped values win where both tables have one, since the gauge list is refreshed
+/// hourly; location and status only exist in the metadata.
+#[derive(Debug, Clone, FromRow, Serialize, ToSchema)]
+pub struct GaugeDetail {
+    #[schema(example = "59700")]
+    pub station_id: String,
+    #[schema(example = "Aztec Park")]
+    pub gauge_name: String,
+    #[schema(example = "Scottsdale")]
+    pub city_town: Option<String>,
+    #[schema(example = 1465)]
+    pub elevation_ft: Option<i32>,
+    #[schema(example = "Near Thunderbird & Frank Lloyd Wright")]
+    pub general_location: Option<String>,
+    #[schema(example = "E1")]
+    pub msp_forecast_zone: Option<String>,
+    #[schema(example = 33.61006)]
+    pub latitude: Option<f64>,
+    #[schema(example = -111.86545)]
+    pub longitude: Option<f64>,
+    #[schema(example = "Active")]
+    pub status: Option<String>,
+    #[schema(example = 0.0)]
+    pub rainfall_past_6h_inches: Option<f64>,
+    #[schema(example = 0.28)]
+    pub rainfall_past_24h_inches: Option<f64>,
+    #[schema(example = "2025-01-15T14:30:00Z")]
+    pub last_scraped_at: Option<DateTime<Utc>>,
+    #[schema(example = "2024-11-02T08:00:00Z")]
+    pub metadata_updated_at: Option<DateTime<Utc>>,
+    /// Present in the latest gauge list scrape
+    #[schema(example = true)]
+    pub in_gauge_list: bool,
+    /// Has FOPR metadata in the gauges table
+    #[schema(example = true)]
+    pub has_metadata: bool,
+}
+
+/// One recorded gauge status transition
+#[derive(Debug, Clone, FromRow, Serialize, ToSchema)]
+pub struct GaugeStatusChange {
+    #[schema(example = 12)]
+    pub id: i64,
+    #[schema(example = "59700")]
+    pub station_id: String,
+    #[schema(example = "Active")]
+    pub previous_status: String,
+    #[schema(example = "Inactive")]
+    pub status: String,
+    #[schema(example = "2025-01-02")]
+    pub effective_date: chrono::NaiveDate,
+    #[schema(example = "Not in gauge list for 14 days")]
+    pub reason: Option<String>,
+    /// `admin` or `auto_detection`
+    #[schema(example = "auto_detection")]
+    pub changed_by: String,
+    #[schema(example = "2025-01-16T00:00:00Z")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// A file (site photo, FOPR PDF) attached to a gauge
+#[derive(Debug, Clone, FromRow, Serialize, ToSchema)]
+pub struct GaugeAttachment {
+    #[schema(example = 7)]
+    pub id: i64,
+    #[schema(example = "59700")]
+    pub station_id: String,
+    #[schema(example = "site-north.jpg")]
+    pub filename: String,
+    #[schema(example = "image/jpeg")]
+    pub content_type: String,
+    #[schema(example = 482113)]
+    pub size_bytes: i64,
+    /// Hex SHA-256 of the file contents
+    #[schema(example = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08")]
+    pub sha256: String,
+    /// Object store key; not exposed (download via the attachment URL)
+    #[serde(skip)]
+    pub storage_key: String,
+    #[schema(example = "Gauge mast looking north, 2024 site visit")]
+    pub description: Option<String>,
+    #[schema(example = "2025-01-16T00:00:00Z")]
+    pub uploaded_at: DateTime<Utc>,
+}
+
+/// A note giving context to a gauge's data, optionally for a date range
+#[derive(Debug, Clone, FromRow, Serialize, ToSchema)]
+pub struct GaugeAnnotation {
+    #[schema(example = 3)]
+    pub id: i64,
+    #[schema(example = "59700")]
+    pub station_id: String,
+    /// First day covered (inclusive); null for notes about the gauge as a whole
+    #[schema(example = "2025-03-02")]
+    pub start_date: Option<chrono::NaiveDate>,
+    /// Last day covered (inclusive); null when ongoing or about the gauge as a whole
+    #[schema(example = "2025-03-05")]
+    pub end_date: Option<chrono::NaiveDate>,
+    #[schema(example = "Sensor clogged with debris; readings undercount")]
+    pub note: String,
+    #[schema(example = "jsmith")]
+    pub author: String,
+    #[schema(example = "2025-03-06T17:20:00Z")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// An API user; requests made with the user's key act on their favorites and views
+#[derive(Debug, Clone, FromRow, Serialize, ToSchema)]
+pub struct User {
+    #[schema(example = 7)]
+    pub id: i64,
+    #[schema(example = "jsmith")]
+    pub name: String,
+    #[schema(example = "2025-02-04T16:00:00Z")]
+    pub created_at: DateTime<Utc>,
+    /// Last request made with the user's key; null until first use
+    #[schema(example = "2025-02-05T08:12:00Z")]
+    pub last_seen_at: Option<DateTime<Utc>>,
+    /// Requests allowed per UTC month; null means unlimited
+    #[schema(example = 10000)]
+    pub monthly_request_quota: Option<i64>,
+    /// Response bytes allowed per UTC month; null means unlimited
+    #[schema(example = 1073741824)]
+    pub monthly_byte_quota: Option<i64>,
+}
+
+/// A user's API usage this month against their quotas
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UsageReport {
+    /// First day of the UTC month counted
+    #[schema(example = "2025-02-01")]
+    pub month: chrono::NaiveDate,
+    /// Requests made with the key this month, including this one
+    #[schema(example = 1234)]
+    pub request_count: i64,
+    /// Response body bytes sent this month
+    #[schema(example = 5242880)]
+    pub response_bytes: i64,
+    #[schema(example = 10000)]
+    pub monthly_request_quota: Option<i64>,
+    #[schema(example = 1073741824)]
+    pub monthly_byte_quota: Option<i64>,
+    /// When the counts start over
+    #[schema(example = "2025-03-01T00:00:00Z")]
+    pub resets_at: DateTime<Utc>,
+}
+
+/// A gauge a user has marked as a favorite
+#[derive(Debug, Clone, FromRow, Serialize, ToSchema)]
+pub struct FavoriteGauge {
+    #[schema(example = "59700")]
+    pub station_id: String,
+    #[schema(example = "Aztec Park")]
+    pub station_name: Option<String>,
+    #[schema(example = "2025-02-05T08:12:00Z")]
+    pub added_at: DateTime<Utc>,
+}
+
+/// A named set of gauges and a period, saved so dashboards sync across devices
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SavedView {
+    #[schema(example = 12)]
+    pub id: i64,
+    #[schema(example = "North Scottsdale")]
+    pub name: String,
+    /// Gauges in display order
+    #[schema(example = json!(["59700", "11000"]))]
+    pub station_ids: Vec<String>,
+    pub period: RankingPeriod,
+    #[schema(example = "2025-02-05T08:12:00Z")]
+    pub created_at: DateTime<Utc>,
+    #[schema(example = "2025-02-05T08:12:00Z")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A user's daily or weekly digest subscription
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DigestSubscription {
+    #[schema(example = 7)]
+    pub user_id: i64,
+    pub frequency: DigestFrequency,
+    #[schema(example = "jsmith@example.com")]
+    pub email: Option<String>,
+    #[schema(example = "https://hooks.example.com/rain")]
+    pub webhook_url: Option<String>,
+    /// Last day covered by a delivered digest; null until the first is sent
+    #[schema(example = "2025-02-09")]
+    pub last_sent_through: Option<chrono::NaiveDate>,
+    #[schema(example = "2025-02-05T08:12:00Z")]
     pub created_at: DateTime<Utc>,
+    #[schema(example = "2025-02-05T08:12:00Z")]
     pub updated_at: DateTime<Utc>,
 }
 
+/// One favorite gauge's rainfall over a digest period
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct DigestGauge {
+    #[schema(example = "59700")]
+    pub station_id: String,
+    #[schema(example = "Aztec Park")]
+    pub station_name: Option<String>,
+    #[schema(example = 1.24)]
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub total_inches: f64,
+    /// Largest hourly total; null without readings in the period
+    #[schema(example = 0.4)]
+    #[serde(serialize_with = "crate::units::serialize_rounded_opt")]
+    pub wettest_hour_inches: Option<f64>,
+    /// Most rain in any hour, in inches per hour; null without sub-daily readings
+    #[schema(example = 0.52)]
+    pub max_1h_intensity_in_per_hr: Option<f64>,
+    /// Hours in the period with at least one reading
+    #[schema(example = 156)]
+    pub reported_hours: i64,
+    /// Hours in the period without a reading (data gaps)
+    #[schema(example = 12)]
+    pub missing_hours: i64,
+}
+
+/// Rainfall across a user's favorite gauges for one day or week
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Digest {
+    pub frequency: DigestFrequency,
+    /// First day covered (UTC, inclusive)
+    #[schema(example = "2025-02-03")]
+    pub first_day: chrono::NaiveDate,
+    /// Last day covered (UTC, inclusive)
+    #[schema(example = "2025-02-09")]
+    pub last_day: chrono::NaiveDate,
+    /// Favorite gauges, wettest first
+    pub gauges: Vec<DigestGauge>,
+    /// Threshold crossings at the gauges that started in the period, oldest first
+    pub events: Vec<GaugeThresholdEvent>,
+    #[schema(example = "2025-02-10T00:05:00Z")]
+    pub generated_at: DateTime<Utc>,
+}
+
+/// A period during which a gauge's 24h rainfall was at or above a threshold
+#[derive(Debug, Clone, FromRow, Serialize, ToSchema)]
+pub struct GaugeThresholdEvent {
+    #[schema(example = 41)]
+    pub id: i64,
+    #[schema(example = "59700")]
+    pub station_id: String,
+    #[schema(example = 1.0)]
+    pub threshold_inches: f64,
+    /// Scrape time at which the 24h total first reached the threshold
+    #[schema(example = "2025-02-13T06:15:00Z")]
+    pub crossed_at: DateTime<Utc>,
+    /// First scrape back below the threshold; null while still at or above it
+    #[schema(example = "2025-02-14T09:15:00Z")]
+    pub ended_at: Option<DateTime<Utc>>,
+    /// 24h total when the crossing was detected
+    #[schema(example = 1.06)]
+    pub rainfall_24h_inches: f64,
+    /// Highest 24h total seen during the event
+    #[schema(example = 1.57)]
+    pub peak_rainfall_24h_inches: f64,
+    /// Most rain in any 15 minutes from 24h before the crossing to the end (or the
+    /// latest scrape), in inches per hour; null without sub-daily readings
+    #[schema(example = 3.2)]
+    pub max_15min_intensity_in_per_hr: Option<f64>,
+    /// Most rain in any hour over the same window; null without sub-daily readings
+    #[schema(example = 1.44)]
+    pub max_1h_intensity_in_per_hr: Option<f64>,
+}
+
+/// A gauge that recorded rain during a storm's window
+#[derive(Debug, Clone, PartialEq, FromRow, Serialize, ToSchema)]
+pub struct StormFootprintGauge {
+    #[schema(example = "59700")]
+    pub station_id: String,
+    #[schema(example = "Aztec Park")]
+    pub station_name: Option<String>,
+    /// Null when the gauge has no coordinates; such gauges are left out of the hull
+    #[schema(example = 33.6119)]
+    pub latitude: Option<f64>,
+    #[schema(example = -111.9144)]
+    pub longitude: Option<f64>,
+    /// Sum of the gauge's readings in the window
+    #[schema(example = 1.22)]
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub rainfall_inches: f64,
+    #[schema(example = 9)]
+    pub reading_count: i64,
+}
+
+/// Every gauge that recorded rain while a storm was under way, and the area they cover
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StormFootprint {
+    /// The threshold event the storm was detected by
+    pub storm: GaugeThresholdEvent,
+    /// 24 hours before the crossing, when the rain that crossed the threshold could start
+    #[schema(example = "2025-02-12T06:15:00Z")]
+    pub window_start: DateTime<Utc>,
+    /// The event's end, or now while it is open (inclusive)
+    #[schema(example = "2025-02-14T09:15:00Z")]
+    pub window_end: DateTime<Utc>,
+    #[schema(example = 27)]
+    pub gauge_count: usize,
+    /// Wettest gauges first
+    pub gauges: Vec<StormFootprintGauge>,
+    /// Convex hull of the gauges with coordinates as a GeoJSON Polygon (longitude,
+    /// latitude), or a Point/LineString when they enclose no area; null without any
+    #[schema(value_type = Option<Object>)]
+    pub hull: Option<serde_json::Value>,
+}
+
+/// A gauge's rain in one step of a storm timeline, as queried
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct StormBucketRow {
+    pub station_id: String,
+    pub bucket_start: DateTime<Utc>,
+    pub rainfall_inches: f64,
+}
+
+/// A gauge's rain in one frame of a storm timeline
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct StormTimelineGauge {
+    #[schema(example = "59700")]
+    pub station_id: String,
+    /// Rain in this frame
+    #[schema(example = 0.16)]
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub rainfall_inches: f64,
+    /// Rain since the window started, through this frame
+    #[schema(example = 0.63)]
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub cumulative_inches: f64,
+}
+
+/// One step of a storm timeline
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct StormTimelineFrame {
+    #[schema(example = "2025-02-13T05:45:00Z")]
+    pub start: DateTime<Utc>,
+    /// Gauges with rain in the frame by station ID; empty for a dry frame
+    pub gauges: Vec<StormTimelineGauge>,
+}
+
+/// Per-gauge rainfall in fixed steps across a storm, for animated map playback
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StormTimeline {
+    /// The threshold event the storm was detected by
+    pub storm: GaugeThresholdEvent,
+    #[schema(example = "2025-02-12T06:15:00Z")]
+    pub window_start: DateTime<Utc>,
+    #[schema(example = "2025-02-14T09:15:00Z")]
+    pub window_end: DateTime<Utc>,
+    #[schema(example = 15)]
+    pub step_minutes: i64,
+    /// Every gauge in any frame, with its location and storm total, wettest first
+    pub gauges: Vec<StormFootprintGauge>,
+    /// Consecutive frames covering the window, oldest first
+    pub frames: Vec<StormTimelineFrame>,
+}
+
+/// Open threshold event, as tracked between scrapes
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct OpenThresholdEvent {
+    pub id: i64,
+    pub station_id: String,
+    pub threshold_inches: f64,
+    pub peak_rainfall_24h_inches: f64,
+}
+
+/// Precomputed dashboard row for one gauge in the gauge list
+#[derive(Debug, Clone, FromRow, Serialize, ToSchema)]
+pub struct CurrentCondition {
+    #[schema(example = "59700")]
+    pub station_id: String,
+    #[schema(example = "Aztec Park")]
+    pub gauge_name: String,
+    #[schema(example = "Scottsdale")]
+    pub city_town: Option<String>,
+    #[schema(example = "Active")]
+    pub status: String,
+    /// Null until the gauge's FOPR metadata has been imported
+    #[schema(example = 33.61006)]
+    pub latitude: Option<f64>,
+    #[schema(example = -111.86545)]
+    pub longitude: Option<f64>,
+    #[schema(example = 1465)]
+    pub elevation_ft: Option<i32>,
+    #[schema(example = 0.0)]
+    pub rainfall_past_6h_inches: Option<f64>,
+    #[schema(example = 0.28)]
+    pub rainfall_past_24h_inches: Option<f64>,
+    /// Most recent stored reading; null when the gauge has no readings
+    #[schema(example = "2025-01-15T14:15:00Z")]
+    pub latest_reading_at: Option<DateTime<Utc>>,
+    #[schema(example = 2.36)]
+    pub latest_cumulative_inches: Option<f64>,
+    #[schema(example = 2025)]
+    pub water_year: i32,
+    /// Sum of the water year's monthly summaries
+    #[schema(example = 2.36)]
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub water_year_to_date_inches: f64,
+    #[schema(example = "2025-01-15T14:30:00Z")]
+    pub last_scraped_at: DateTime<Utc>,
+    /// When this row was rebuilt
+    #[schema(example = "2025-01-15T14:31:02Z")]
+    pub refreshed_at: DateTime<Utc>,
+}
+
+/// Water-year-to-date rainfall for one gauge, before it is wrapped in a WaterYearTotal
+#[derive(Debug, Clone, FromRow)]
+pub struct GaugeWaterYearToDate {
+    pub station_id: String,
+    pub total_rainfall_inches: f64,
+    pub total_readings: i64,
+    pub as_of: Option<DateTime<Utc>>,
+}
+
+/// When a gauge was last seen in the scraped gauge list
+#[derive(Debug, Clone, FromRow)]
+pub struct GaugeLastSeen {
+    pub station_id: String,
+    pub last_scraped_at: DateTime<Utc>,
+}
+
+/// FOPR operational metadata and climate statistics for a gauge
+#[derive(Debug, Clone, FromRow, Serialize, ToSchema)]
+pub struct GaugeMetadata {
+    #[schema(example = "Rain")]
+    pub station_type: Option<String>,
+    #[schema(example = "Maricopa")]
+    pub county: Option<String>,
+    /// Station IDs this gauge reported under before
+    #[schema(example = json!(["4695"]))]
+    pub previous_station_ids: Option<Vec<String>>,
+    #[schema(example = "1998-06-01")]
+    pub installation_date: Option<chrono::NaiveDate>,
+    #[schema(example = "1998-06-01")]
+    pub data_begins_date: Option<chrono::NaiveDate>,
+    /// None while the gauge is still reporting
+    pub data_ends_date: Option<chrono::NaiveDate>,
+    #[schema(example = 7.48)]
+    pub avg_annual_precipitation_inches: Option<f64>,
+    #[schema(example = 26)]
+    pub complete_years_count: Option<i32>,
+    #[schema(example = 0)]
+    pub incomplete_months_count: Option<i32>,
+    #[schema(example = 0)]
+    pub missing_months_count: Option<i32>,
+    #[schema(example = "Records Good")]
+    pub data_quality_remarks: Option<String>,
+    /// False when the gauge has no FOPR file
+    #[schema(example = true)]
+    pub fopr_available: Option<bool>,
+    #[schema(example = "2024-11-02")]
+    pub fopr_last_import_date: Option<chrono::NaiveDate>,
+}
+
+/// A station's values from gauge_summaries and gauges side by side, for reconciliation
+#[derive(Debug, Clone, FromRow)]
+pub struct GaugeSourcePair {
+    pub station_id: String,
+    pub in_gauge_list: bool,
+    pub has_metadata: bool,
+    pub summary_name: Option<String>,
+    pub metadata_name: Option<String>,
+    pub summary_city: Option<String>,
+    pub metadata_city: Option<String>,
+    pub summary_elevation_ft: Option<i32>,
+    pub metadata_elevation_ft: Option<i32>,
+}
+
+/// Gauge columns an operator can correct through the CSV metadata editor
+///
+/// Field order is the CSV column order.
+#[derive(Debug, Clone, PartialEq, FromRow, Serialize, Deserialize)]
+pub struct EditableGaugeMetadata {
+    pub station_id: String,
+    pub station_name: Option<String>,
+    pub city: Option<String>,
+    pub county: Option<String>,
+    pub location_description: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub elevation_ft: Option<i32>,
+}
+
+/// A gauge's metadata and assigned forecast zone, for the GIS and analytics exports
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct GisGauge {
+    pub station_id: String,
+    pub station_name: Option<String>,
+    pub city: Option<String>,
+    pub county: Option<String>,
+    pub location_description: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub elevation_ft: Option<i32>,
+    pub status: Option<String>,
+    pub data_begins_date: Option<chrono::NaiveDate>,
+    pub data_ends_date: Option<chrono::NaiveDate>,
+    pub forecast_zone: Option<String>,
+}
+
+/// A gauge with coordinates but no city or nearest place, awaiting reverse geocoding
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct GeocodeCandidate {
+    pub station_id: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub city: Option<String>,
+}
+
+/// A gauge with coordinates but no elevation, awaiting a DEM sample
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct ElevationCandidate {
+    pub station_id: String,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// An imported forecast zone polygon
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForecastZoneRecord {
+    pub zone_id: String,
+    pub name: Option<String>,
+    /// GeoJSON Polygon or MultiPolygon geometry
+    pub geometry: serde_json::Value,
+    pub source: Option<String>,
+    pub imported_at: DateTime<Utc>,
+    /// Gauges assigned to the zone
+    pub gauge_count: i64,
+}
+
+/// A gauge's coordinates and assigned forecast zone
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct GaugeZoneLocation {
+    pub station_id: String,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub forecast_zone: Option<String>,
+}
+
+/// A station-month where the stored monthly summary disagrees with the raw readings
+///
+/// `None` on the summary side means the summary row is missing; `None` on the
+/// readings side means a summary exists without any backing readings.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct SummaryDiscrepancy {
+    pub station_id: String,
+    pub year: i32,
+    pub month: i32,
+    #[serde(serialize_with = "crate::units::serialize_rounded_opt")]
+    pub summary_total_inches: Option<f64>,
+    #[serde(serialize_with = "crate::units::serialize_rounded_opt")]
+    pub readings_total_inches: Option<f64>,
+    pub summary_reading_count: Option<i32>,
+    pub actual_reading_count: Option<i64>,
+}
+
+/// Readings for one station-month from one data source
+#[derive(Debug, Clone, FromRow)]
+pub struct CoverageRow {
+    pub year: i32,
+    pub month: i32,
+    pub data_source: String,
+    pub reading_count: i64,
+    pub first_reading: DateTime<Utc>,
+    pub last_reading: DateTime<Utc>,
+}
+
+/// Distribution of one calendar month's totals across a station's history
+#[derive(Debug, Clone, FromRow)]
+pub struct MonthPercentileRow {
+    pub month: i32,
+    pub years_of_record: i64,
+    pub mean_inches: f64,
+    pub min_inches: f64,
+    pub p10_inches: f64,
+    pub p25_inches: f64,
+    pub p50_inches: f64,
+    pub p75_inches: f64,
+    pub p90_inches: f64,
+    pub max_inches: f64,
+}
+
+/// A gauge's rainfall total over a ranking period
+#[derive(Debug, Clone, FromRow)]
+pub struct RankingRow {
+    pub station_id: String,
+    pub gauge_name: String,
+    pub city_town: Option<String>,
+    pub msp_forecast_zone: Option<String>,
+    pub general_location: Option<String>,
+    pub rainfall_inches: f64,
+    pub reading_count: i64,
+}
+
+/// Stored state of an Idempotency-Key (status_code is None while in flight)
+#[derive(Debug, Clone, FromRow)]
+pub struct IdempotencyRecord {
+    pub request_hash: String,
+    pub status_code: Option<i16>,
+    pub content_type: Option<String>,
+    pub response_body: Option<Vec<u8>>,
+    pub created_at: DateTime<Utc>,
+}
+
 // API response DTOs (to avoid circular dependency between services and api modules)
+/// Readings and total for one water year (Oct 1 - Sep 30)
 #[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct WaterYearSummary {
+    /// Named for the year it ends in (WY 2025 = Oct 2024 - Sep 2025)
+    #[schema(example = 2025)]
     pub water_year: i32,
+    #[schema(example = 412)]
     pub total_readings: usize,
+    #[schema(example = 7.48)]
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
     pub total_rainfall_inches: f64,
+    /// Worst grade among the months with summary rows (null when none is graded)
+    pub quality_grade: Option<QualityGrade>,
+    /// Latest reading counted in the totals (null when the year has none); for a water
+    /// year still in progress, totals run through this moment rather than the year's end
+    #[schema(example = "2025-03-14T18:45:00Z")]
+    pub as_of: Option<DateTime<Utc>>,
+    /// The water year has not ended yet
+    #[schema(example = false)]
+    pub is_partial: bool,
+    /// Months with summary rows, Oct through Sep; `fill` lists all 12
+    pub monthly_summaries: Vec<MonthlySummary>,
+    pub readings: Vec<Reading>,
+    /// Annotations overlapping the water year
+    pub annotations: Vec<GaugeAnnotation>,
+}
+
+/// Readings for an arbitrary date range, or one page of them, oldest first
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ReadingRange {
+    #[schema(example = "59700")]
+    pub station_id: String,
+    #[schema(example = "2021-10-01")]
+    pub start_date: chrono::NaiveDate,
+    #[schema(example = "2025-09-30")]
+    pub end_date: chrono::NaiveDate,
+    /// Readings in this response
+    #[schema(example = 1650)]
+    pub total_readings: usize,
+    pub pagination: PageInfo,
     pub readings: Vec<Reading>,
 }
 
+/// Readings and month-by-month totals for one calendar year
 #[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct CalendarYearSummary {
+    #[schema(example = 2024)]
     pub calendar_year: i32,
+    #[schema(example = 388)]
     pub total_readings: usize,
+    #[schema(example = 6.91)]
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
     pub year_to_date_rainfall_inches: f64,
+    /// Worst grade among the months with summary rows (null when none is graded)
+    pub quality_grade: Option<QualityGrade>,
+    /// Latest reading counted in the year-to-date total (null when the year has none)
+    #[schema(example = "2024-12-30T07:15:00Z")]
+    pub as_of: Option<DateTime<Utc>>,
+    /// The calendar year has not ended yet
+    #[schema(example = false)]
+    pub is_partial: bool,
+    /// Months with summary rows, Jan through Dec; `fill` lists all 12
     pub monthly_summaries: Vec<MonthlySummary>,
     pub readings: Vec<Reading>,
+    /// Annotations overlapping the calendar year
+    pub annotations: Vec<GaugeAnnotation>,
+}
+
+/// Water-year-to-date total from monthly summaries
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WaterYearTotal {
+    #[schema(example = 2025)]
+    pub water_year: i32,
+    #[schema(example = 2.36)]
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub total_rainfall_inches: f64,
+    #[schema(example = 97)]
+    pub total_readings: i64,
+    /// Latest reading counted in the total (null when the water year has none)
+    #[schema(example = "2024-12-02T14:30:00Z")]
+    pub as_of: Option<DateTime<Utc>>,
+}
+
+/// Everything a gauge detail page needs in one response
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct GaugeFullDetail {
+    pub gauge: GaugeDetail,
+    /// None until the gauge's FOPR file has been imported
+    pub metadata: Option<GaugeMetadata>,
+    pub latest_reading: Option<Reading>,
+    pub current_water_year: WaterYearTotal,
+    pub coverage: GaugeCoverage,
+}
+
+/// What data is stored for a gauge, by data source and by year/month
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct GaugeCoverage {
+    pub station_id: String,
+    pub total_readings: i64,
+    pub earliest_reading: Option<DateTime<Utc>>,
+    pub latest_reading: Option<DateTime<Utc>>,
+    /// Data sources present (e.g. live_scrape, excel_WY_2023, fopr_import)
+    pub data_sources: Vec<SourceCoverage>,
+    /// Calendar years with readings, oldest first
+    pub years: Vec<YearCoverage>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SourceCoverage {
+    pub data_source: String,
+    pub reading_count: i64,
+    pub earliest_reading: DateTime<Utc>,
+    pub latest_reading: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct YearCoverage {
+    pub year: i32,
+    pub reading_count: i64,
+    /// Only months with at least one reading are listed
+    pub months: Vec<MonthCoverage>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MonthCoverage {
+    pub month: u32,
+    pub reading_count: i64,
+    pub earliest_reading: DateTime<Utc>,
+    pub latest_reading: DateTime<Utc>,
+    pub data_sources: Vec<String>,
+}
+
+/// Gauge lifecycle status
+///
+/// Active gauges report normally. Inactive gauges have stopped reporting (detected
+/// automatically or set by an admin) and may come back. Decommissioned gauges are
+/// soft-deleted: hidden by default, with their history kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+pub enum GaugeStatus {
+    #[serde(alias = "active")]
+    Active,
+    #[serde(alias = "inactive")]
+    Inactive,
+    #[serde(alias = "decommissioned")]
+    Decommissioned,
+}
+
+impl GaugeStatus {
+    /// Value stored in gauges.status
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GaugeStatus::Active => "Active",
+            GaugeStatus::Inactive => "Inactive",
+            GaugeStatus::Decommissioned => "Decommissioned",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "Active" => Some(GaugeStatus::Active),
+            "Inactive" => Some(GaugeStatus::Inactive),
+            "Decommissioned" => Some(GaugeStatus::Decommissioned),
+            _ => None,
+        }
+    }
+
+    /// Allowed transitions; a decommissioned gauge can only be recommissioned
+    pub fn can_transition_to(&self, next: GaugeStatus) -> bool {
+        matches!(
+            (self, next),
+            (GaugeStatus::Active, GaugeStatus::Inactive)
+                | (GaugeStatus::Active, GaugeStatus::Decommissioned)
+                | (GaugeStatus::Inactive, GaugeStatus::Active)
+                | (GaugeStatus::Inactive, GaugeStatus::Decommissioned)
+                | (GaugeStatus::Decommissioned, GaugeStatus::Active)
+        )
+    }
+}
+
+/// Time window for gauge rankings, ending now
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum RankingPeriod {
+    #[serde(rename = "24h")]
+    Last24Hours,
+    /// Current calendar month to date
+    #[serde(rename = "month")]
+    Month,
+    /// Current water year to date
+    #[serde(rename = "water-year")]
+    WaterYear,
+}
+
+impl RankingPeriod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RankingPeriod::Last24Hours => "24h",
+            RankingPeriod::Month => "month",
+            RankingPeriod::WaterYear => "water-year",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "24h" => Some(RankingPeriod::Last24Hours),
+            "month" => Some(RankingPeriod::Month),
+            "water-year" => Some(RankingPeriod::WaterYear),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RankingOrder {
+    #[default]
+    Wettest,
+    Driest,
+}
+
+/// Gauges ranked by rainfall over a period
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RankingResponse {
+    pub period: RankingPeriod,
+    pub order: RankingOrder,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub rankings: Vec<GaugeRanking>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct GaugeRanking {
+    /// 1-based position in this ranking
+    #[schema(example = 1)]
+    pub rank: usize,
+    #[schema(example = "59700")]
+    pub station_id: String,
+    #[schema(example = "Aztec Park")]
+    pub gauge_name: String,
+    #[schema(example = "Scottsdale")]
+    pub city_town: Option<String>,
+    /// Forecast zone whose polygon contains the gauge; the zone named by the gauge list
+    /// when the gauge has no assigned zone
+    #[schema(example = "E1")]
+    pub msp_forecast_zone: Option<String>,
+    #[schema(example = "Near Thunderbird & Frank Lloyd Wright")]
+    pub general_location: Option<String>,
+    #[schema(example = 1.57)]
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub rainfall_inches: f64,
+    #[schema(example = 24)]
+    pub reading_count: i64,
+}
+
+/// Rainfall at a shared gauge set, frozen when the share link was created
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SharedSnapshot {
+    /// Gauges as shared, in display order
+    #[schema(example = json!(["59700", "11000"]))]
+    pub station_ids: Vec<String>,
+    pub period: RankingPeriod,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Shared gauges with readings in the period, wettest first; ranks are within the set
+    pub rankings: Vec<GaugeRanking>,
+    /// When the snapshot was taken
+    #[schema(example = "2025-02-05T08:12:00Z")]
+    pub created_at: DateTime<Utc>,
+    /// After this the link no longer resolves
+    #[schema(example = "2025-02-06T08:12:00Z")]
+    pub expires_at: DateTime<Utc>,
+}
+
+/// A signed link to a shared snapshot; anyone holding it can read the snapshot
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ShareLink {
+    #[schema(example = "42.1738829520.3f1c9a…")]
+    pub token: String,
+    /// Path serving the snapshot, relative to this service
+    #[schema(example = "/share/42.1738829520.3f1c9a…")]
+    pub path: String,
+    #[schema(example = "2025-02-06T08:12:00Z")]
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Rainfall over a period aggregated by forecast zone
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ZoneRainfallResponse {
+    pub period: RankingPeriod,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Zones with at least one reporting gauge, wettest first
+    pub zones: Vec<ZoneRainfall>,
+    /// Reporting gauges without a forecast zone
+    #[schema(example = 3)]
+    pub unzoned_gauges: usize,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ZoneRainfall {
+    #[schema(example = "E1")]
+    pub zone_id: String,
+    /// Gauges in the zone that reported during the period
+    #[schema(example = 12)]
+    pub gauge_count: usize,
+    #[schema(example = 0.84)]
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub mean_rainfall_inches: f64,
+    #[schema(example = 1.57)]
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub max_rainfall_inches: f64,
+    /// Gauge that recorded the most rainfall
+    #[schema(example = "59700")]
+    pub wettest_station_id: String,
+}
+
+/// Climatological envelope for each calendar month of a gauge
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MonthlyNormals {
+    pub station_id: String,
+    /// Year whose monthly totals are reported as `current_year_inches`
+    pub current_year: i32,
+    /// Latest reading counted in `current_year_inches` (null when the year has none)
+    pub as_of: Option<DateTime<Utc>>,
+    /// Months with at least one complete month of history, January first
+    pub months: Vec<MonthlyNormal>,
 }
 
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MonthlyNormal {
+    pub month: u32,
+    pub month_name: String,
+    /// Complete past months contributing to the statistics
+    pub years_of_record: i64,
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub mean_inches: f64,
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub min_inches: f64,
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub p10_inches: f64,
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub p25_inches: f64,
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub p50_inches: f64,
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub p75_inches: f64,
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub p90_inches: f64,
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub max_inches: f64,
+    /// This month's total in `current_year` (month-to-date for the current month)
+    #[serde(serialize_with = "crate::units::serialize_rounded_opt")]
+    pub current_year_inches: Option<f64>,
+    /// This is the current month, so `current_year_inches` only runs through `as_of`
+    pub is_partial: bool,
+}
+
+/// Distribution of daily rainfall totals for a gauge
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RainfallHistogram {
+    pub station_id: String,
+    pub bin_width_inches: f64,
+    pub start_date: Option<chrono::NaiveDate>,
+    pub end_date: Option<chrono::NaiveDate>,
+    /// Days with at least one reading in range
+    pub total_days: i64,
+    /// Contiguous bins from 0 up to the wettest day
+    pub bins: Vec<HistogramBin>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct HistogramBin {
+    /// Inclusive lower bound
+    pub min_inches: f64,
+    /// Exclusive upper bound
+    pub max_inches: f64,
+    pub day_count: i64,
+}
+
+/// One calendar year of daily rainfall totals, zero-filled, for heat-map rendering
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RainfallCalendar {
+    pub station_id: String,
+    pub year: i32,
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub total_inches: f64,
+    /// Wettest day's total, the top of the `level` scale
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub max_daily_inches: f64,
+    /// Days with measurable rain
+    pub rainy_days: i64,
+    /// Every day of the year in date order (365 or 366)
+    pub days: Vec<CalendarDay>,
+}
+
+/// One cell of a rainfall calendar heat map
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct CalendarDay {
+    pub date: chrono::NaiveDate,
+    /// Total of the day's readings (UTC day); 0 when none were stored
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub rainfall_inches: f64,
+    /// Color step: 0 for a dry day, otherwise 1-4 by quarter of `max_daily_inches`
+    pub level: u8,
+    /// Column in a GitHub-style grid: weeks start on Sunday, and the week holding
+    /// January 1 is 0
+    pub week: u32,
+    /// Row in the grid, 0 (Sunday) through 6 (Saturday)
+    pub weekday: u32,
+    /// Most rain in any 15 minutes of the day, in inches per hour; null without
+    /// sub-daily readings
+    #[schema(example = 2.4)]
+    #[serde(serialize_with = "crate::units::serialize_rounded_opt")]
+    pub max_15min_intensity_in_per_hr: Option<f64>,
+    /// Most rain in any hour of the day; null without sub-daily readings
+    #[schema(example = 1.12)]
+    #[serde(serialize_with = "crate::units::serialize_rounded_opt")]
+    pub max_1h_intensity_in_per_hr: Option<f64>,
+}
+
+/// How year summaries list months that have no summary rows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum MonthFill {
+    /// List the month with zero readings and rainfall
+    Zero,
+    /// List the month with null readings and rainfall
+    Null,
+}
+
+/// One month within a calendar or water year summary
 #[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct MonthlySummary {
+    /// Calendar year the month falls in (a water year spans two)
+    #[schema(example = 2024)]
+    pub year: i32,
+    /// Calendar month, 1-12
+    #[schema(example = 8)]
     pub month: u32,
+    #[schema(example = "August")]
     pub month_name: String,
-    pub readings_count: usize,
-    pub monthly_rainfall_inches: f64,
+    /// Null for a month without summary rows under `fill=null`
+    #[schema(example = 31)]
+    pub readings_count: Option<usize>,
+    /// Null for a month without summary rows under `fill=null`
+    #[schema(example = 1.22)]
+    #[serde(serialize_with = "crate::units::serialize_rounded_opt")]
+    pub monthly_rainfall_inches: Option<f64>,
+    /// Running total for the requested year through the end of this month
+    #[schema(example = 3.87)]
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
     pub cumulative_ytd_inches: f64,
+    /// Readings flagged at import; null when unknown or the month has no summary rows
+    #[schema(example = 0)]
+    pub flagged_count: Option<i32>,
+    /// Estimated readings; null when unknown or the month has no summary rows
+    #[schema(example = 0)]
+    pub estimated_count: Option<i32>,
+    /// Footnoted readings; null when unknown or the month has no summary rows
+    #[schema(example = 1)]
+    pub footnoted_count: Option<i32>,
+    /// Null when the counts are unknown or the month has no readings
+    pub quality_grade: Option<QualityGrade>,
+    /// The month has ended, lies within the gauge's record, and either has readings or
+    /// belongs to a gauge whose FOPR record reports no missing months (so no readings
+    /// means no rain)
+    #[schema(example = true)]
+    pub is_complete: bool,
+    /// The month is still in progress; its totals only run through the year's `as_of`
+    #[schema(example = false)]
+    pub is_partial: bool,
+}
+
+/// One hour of a gauge's sub-daily readings
+#[derive(Debug, Clone, PartialEq, FromRow, Serialize, ToSchema)]
+pub struct HourlyRainfallSummary {
+    /// Start of the UTC hour
+    pub hour_start: DateTime<Utc>,
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub total_rainfall_inches: f64,
+    /// Largest single reading in the hour
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub max_incremental_inches: f64,
+    pub reading_count: i32,
+}
+
+/// Peak rainfall intensity of one gauge over a UTC day, from sub-daily readings
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct DailyRainfallIntensity {
+    pub date: chrono::NaiveDate,
+    /// Most rain in any 15 minutes, in inches per hour
+    pub max_15min_intensity_in_per_hr: f64,
+    /// Most rain in any hour
+    pub max_1h_intensity_in_per_hr: f64,
+}
+
+/// Hourly rainfall for a gauge over a date range
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct HourlyRainfall {
+    pub station_id: String,
+    pub start_date: chrono::NaiveDate,
+    pub end_date: chrono::NaiveDate,
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub total_inches: f64,
+    /// Hours with readings, oldest first; hours without readings are omitted
+    pub hours: Vec<HourlyRainfallSummary>,
 }
 
 #[derive(Debug, Clone, FromRow, Serialize, ToSchema)]
@@ -63,12 +1208,326 @@ pub struct MonthlyRainfallSummary {
     pub station_id: String,
     pub year: i32,
     pub month: i32,
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
     pub total_rainfall_inches: f64,
     pub reading_count: i32,
     pub first_reading_date: Option<DateTime<Utc>>,
     pub last_reading_date: Option<DateTime<Utc>>,
+    #[serde(serialize_with = "crate::units::serialize_rounded_opt")]
     pub min_cumulative_inches: Option<f64>,
+    #[serde(serialize_with = "crate::units::serialize_rounded_opt")]
     pub max_cumulative_inches: Option<f64>,
+    /// Readings flagged at import; None until the summary is recalculated
+    pub flagged_count: Option<i32>,
+    /// Estimated readings; None until the summary is recalculated
+    pub estimated_count: Option<i32>,
+    /// Footnoted readings; None until the summary is recalculated
+    pub footnoted_count: Option<i32>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
+
+impl MonthlyRainfallSummary {
+    /// Grade of the month's readings (None while the quality counts are unknown)
+    pub fn quality_grade(&self) -> Option<QualityGrade> {
+        QualityGrade::from_counts(
+            self.reading_count,
+            self.flagged_count?,
+            self.estimated_count?,
+            self.footnoted_count?,
+        )
+    }
+}
+
+/// How far a month's readings can be trusted, worst last
+///
+/// - A: no flagged or estimated readings, and at most 10% footnoted
+/// - B: at most 25% of readings flagged, estimated, or footnoted
+/// - C: anything worse; a low total may mean the gauge was offline rather than a dry month
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, ToSchema)]
+pub enum QualityGrade {
+    A,
+    B,
+    C,
+}
+
+impl QualityGrade {
+    pub fn from_counts(
+        reading_count: i32,
+        flagged: i32,
+        estimated: i32,
+        footnoted: i32,
+    ) -> Option<Self> {
+        if reading_count <= 0 {
+            return None;
+        }
+        let share = |count: i32| count as f64 / reading_count as f64;
+        Some(
+            if flagged == 0 && estimated == 0 && share(footnoted) <= 0.10 {
+                Self::A
+            } else if share(flagged + estimated + footnoted) <= 0.25 {
+                Self::B
+            } else {
+                Self::C
+            },
+        )
+    }
+}
+
+/// EXPLAIN ANALYZE of a query that exceeded SLOW_QUERY_THRESHOLD_MS
+#[derive(Debug, Clone, FromRow, Serialize, ToSchema)]
+pub struct SlowQueryCapture {
+    #[schema(example = 12)]
+    pub id: i64,
+    /// Repository method that ran the query
+    #[schema(example = "get_summaries_by_date_range")]
+    pub query_name: String,
+    /// Query text with `$n` placeholders; bound values are never recorded
+    pub sql: String,
+    #[schema(example = 3)]
+    pub param_count: i32,
+    /// Duration of the original (slow) execution
+    #[schema(example = 812.4)]
+    pub duration_ms: f64,
+    /// `EXPLAIN (ANALYZE, BUFFERS, FORMAT JSON)` output; null when EXPLAIN failed
+    #[schema(value_type = Option<Object>)]
+    pub plan: Option<serde_json::Value>,
+    /// Why EXPLAIN failed
+    pub explain_error: Option<String>,
+    #[schema(example = "2025-02-13T06:15:00Z")]
+    pub captured_at: DateTime<Utc>,
+}
+
+/// Whether a gauge's FOPR file exists on the MCFCD site, from the last HEAD probe
+#[derive(Debug, Clone, FromRow, Serialize, ToSchema)]
+pub struct FoprAvailability {
+    #[schema(example = "59700")]
+    pub station_id: String,
+    pub available: bool,
+    /// Status of the last HEAD request
+    #[schema(example = 200)]
+    pub http_status: i32,
+    /// Last-Modified of the file, when the server sent one
+    #[schema(example = "2025-01-06T17:42:00Z")]
+    pub last_modified: Option<DateTime<Utc>>,
+    #[schema(example = 326073)]
+    pub content_length: Option<i64>,
+    #[schema(example = "2025-02-13T06:15:00Z")]
+    pub checked_at: DateTime<Utc>,
+    /// Last time `available` or `last_modified` changed
+    #[schema(example = "2025-01-07T06:00:00Z")]
+    pub changed_at: DateTime<Utc>,
+}
+
+/// Refresh state of a summary materialized view
+#[derive(Debug, Clone, FromRow, Serialize, ToSchema)]
+pub struct SummaryView {
+    #[schema(example = "gauge_period_rainfall")]
+    pub view_name: String,
+    /// When the view's contents were computed; null until the first refresh
+    #[schema(example = "2025-02-13T06:15:00Z")]
+    pub refreshed_at: Option<DateTime<Utc>>,
+    #[schema(example = 412)]
+    pub refresh_duration_ms: Option<i64>,
+    /// Summaries changed in bulk since the last refresh; a stale view is not read
+    pub stale: bool,
+    #[schema(example = "Monthly summaries recalculated")]
+    pub stale_reason: Option<String>,
+    pub marked_stale_at: Option<DateTime<Utc>>,
+}
+
+/// A suspected gauge malfunction found by the anomaly job
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct GaugeAnomaly {
+    #[schema(example = 18)]
+    pub id: i64,
+    #[schema(example = "59700")]
+    pub station_id: String,
+    #[schema(example = "Aztec Park")]
+    pub station_name: Option<String>,
+    pub kind: AnomalyKind,
+    /// First zero reading of a flat-line, or the spike or reset reading
+    #[schema(example = "2025-01-02T00:15:00Z")]
+    pub started_at: DateTime<Utc>,
+    /// Last zero reading of a flat-line; null for spikes and resets
+    #[schema(example = "2025-01-23T06:00:00Z")]
+    pub ended_at: Option<DateTime<Utc>>,
+    /// Neighbors' median rainfall (flatline), the reading (spike), or the drop (reset)
+    #[schema(example = 1.24)]
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub magnitude_inches: f64,
+    #[schema(
+        example = "Only zeros for 21 days while 3 of 4 gauges within 10 mi recorded 0.5+ in (median 1.24 in)"
+    )]
+    pub detail: String,
+    pub status: AnomalyStatus,
+    #[schema(example = "Funnel packed with leaves; cleaned 1/24")]
+    pub review_note: Option<String>,
+    #[schema(example = "jsmith")]
+    pub reviewed_by: Option<String>,
+    #[schema(example = "2025-01-24T17:00:00Z")]
+    pub reviewed_at: Option<DateTime<Utc>>,
+    #[schema(example = "2025-01-16T00:00:00Z")]
+    pub first_detected_at: DateTime<Utc>,
+    #[schema(example = "2025-01-23T00:00:00Z")]
+    pub last_detected_at: DateTime<Utc>,
+}
+
+/// A gauge with coordinates, for sampling gridded data such as radar estimates
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct GaugeLocation {
+    pub station_id: String,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// A storm window with radar estimates imported by `radar import`
+#[derive(Debug, Clone, FromRow, Serialize, ToSchema)]
+pub struct RadarStorm {
+    #[schema(example = "2025-01-06T00:00:00Z")]
+    pub storm_start: DateTime<Utc>,
+    #[schema(example = "2025-01-07T00:00:00Z")]
+    pub storm_end: DateTime<Utc>,
+    /// MRMS product the grids came from
+    #[schema(example = "MultiSensor_QPE_01H_Pass2")]
+    pub product: String,
+    /// Gauges with an estimate
+    #[schema(example = 342)]
+    pub gauge_count: i64,
+    #[schema(example = "2025-01-07T06:00:00Z")]
+    pub sampled_at: DateTime<Utc>,
+}
+
+/// A gauge's radar estimate alongside its readings for the same storm window
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct RadarComparisonRow {
+    pub station_id: String,
+    pub station_name: Option<String>,
+    pub radar_inches: f64,
+    /// Sum of incremental readings in `[storm_start, storm_end)`
+    pub gauge_inches: f64,
+    pub reading_count: i64,
+}
+
+/// A gauge's rainfall for one UTC day beside the day's temperature and ET
+#[derive(Debug, Clone, PartialEq, FromRow, Serialize, ToSchema)]
+pub struct GaugeWeatherDay {
+    #[schema(example = "2025-01-15")]
+    pub date: chrono::NaiveDate,
+    /// Sum of the gauge's readings for the day
+    #[schema(example = 0.12)]
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub rainfall_inches: f64,
+    #[schema(example = 68.2)]
+    pub max_temp_f: Option<f64>,
+    #[schema(example = 41.5)]
+    pub min_temp_f: Option<f64>,
+    /// Reference evapotranspiration; null until the weather job has the day
+    #[schema(example = 0.08)]
+    #[serde(serialize_with = "crate::units::serialize_rounded_opt")]
+    pub et_inches: Option<f64>,
+    /// Where the weather came from, e.g. azmet:az06 or nws:PSR/158,56
+    #[schema(example = "azmet:az06")]
+    pub source: Option<String>,
+}
+
+/// Background job recorded in `job_runs`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JobRunKind {
+    /// Reading scrape from the MCFCD gauge page
+    Fetch,
+    /// Gauge list scrape from MCFCD
+    GaugeList,
+    /// One FOPR import attempt by a worker
+    FoprImport,
+}
+
+impl JobRunKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobRunKind::Fetch => "fetch",
+            JobRunKind::GaugeList => "gauge_list",
+            JobRunKind::FoprImport => "fopr_import",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "fetch" => Some(JobRunKind::Fetch),
+            "gauge_list" => Some(JobRunKind::GaugeList),
+            "fopr_import" => Some(JobRunKind::FoprImport),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JobRunOutcome {
+    Succeeded,
+    Failed,
+}
+
+impl JobRunOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobRunOutcome::Succeeded => "succeeded",
+            JobRunOutcome::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "succeeded" => Some(JobRunOutcome::Succeeded),
+            "failed" => Some(JobRunOutcome::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// One finished background job run
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct JobRun {
+    #[schema(example = 4821)]
+    pub id: i64,
+    pub job: JobRunKind,
+    /// Gauge of a FOPR import
+    #[schema(example = "59700")]
+    pub station_id: Option<String>,
+    /// fopr_import_jobs row of a FOPR import; one job may have several failed runs
+    #[schema(example = 312)]
+    pub fopr_job_id: Option<i32>,
+    #[schema(example = "2025-02-13T06:15:00Z")]
+    pub started_at: DateTime<Utc>,
+    #[schema(example = 2140.5)]
+    pub duration_ms: f64,
+    /// Readings stored (fetch, FOPR import) or gauges upserted (gauge list)
+    #[schema(example = 96)]
+    pub row_count: i64,
+    pub outcome: JobRunOutcome,
+    #[schema(example = "error sending request for url")]
+    pub error: Option<String>,
+}
+
+/// A job's runs over one UTC day
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct JobRunTrend {
+    #[schema(example = "2025-02-13")]
+    pub day: chrono::NaiveDate,
+    pub job: JobRunKind,
+    #[schema(example = 96)]
+    pub runs: i64,
+    #[schema(example = 2)]
+    pub failures: i64,
+    #[schema(example = 4310)]
+    pub row_count: i64,
+    #[schema(example = 1875.2)]
+    pub avg_duration_ms: f64,
+    /// 95th percentile run time; a rising value suggests MCFCD is slowing down
+    #[schema(example = 4120.0)]
+    pub p95_duration_ms: f64,
+    /// Rows per second of run time across the day's successful runs
+    #[schema(example = 24.6)]
+    pub rows_per_second: Option<f64>,
+}
@@ -1,17 +1,68 @@
 use chrono::{DateTime, Utc};
-use sqlx::{PgPool, Postgres, Transaction};
+use sqlx::{Postgres, Transaction};
 use tracing::{debug, instrument};
 
-use crate::db::{DbError, MonthlyRainfallSummary, Reading};
+#[cfg(feature = "sqlite")]
+use crate::db::sqlite;
+use crate::db::{
+    DbError, DbPool, MonthPercentileRow, MonthlyRainfallSummary, RankingRow, Reading, SlowQueryLog,
+    SummaryDiscrepancy,
+};
+use crate::units::{self, Inches};
+use crate::utils;
+
+/// Monthly summary values computed from one month's readings
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonthAggregates {
+    pub total_rainfall: f64,
+    pub reading_count: i32,
+    pub first_reading_date: Option<DateTime<Utc>>,
+    pub last_reading_date: Option<DateTime<Utc>>,
+    pub min_cumulative: f64,
+    pub max_cumulative: f64,
+}
+
+impl MonthAggregates {
+    pub fn from_readings(readings: &[Reading]) -> Self {
+        Self {
+            total_rainfall: units::round_inches(
+                readings.iter().map(|r| r.incremental_inches.value()).sum(),
+            ),
+            reading_count: readings.len() as i32,
+            first_reading_date: readings.iter().map(|r| r.reading_datetime).min(),
+            last_reading_date: readings.iter().map(|r| r.reading_datetime).max(),
+            min_cumulative: readings
+                .iter()
+                .map(|r| r.cumulative_inches.value())
+                .min_by(|a, b| a.partial_cmp(b).unwrap())
+                .unwrap_or(0.0),
+            max_cumulative: readings
+                .iter()
+                .map(|r| r.cumulative_inches.value())
+                .max_by(|a, b| a.partial_cmp(b).unwrap())
+                .unwrap_or(0.0),
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct MonthlyRainfallRepository {
-    pool: PgPool,
+    db: DbPool,
+    slow_queries: SlowQueryLog,
 }
 
 impl MonthlyRainfallRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        Self {
+            db: pool.into(),
+            slow_queries: SlowQueryLog::default(),
+        }
+    }
+
+    /// Log (and optionally EXPLAIN) slow summary reads per `log`'s settings
+    pub fn with_slow_query_log(mut self, log: SlowQueryLog) -> Self {
+        self.slow_queries = log;
+        self
     }
 
     /// Upsert monthly rainfall summary for a specific month
@@ -29,64 +80,69 @@ impl MonthlyRainfallRepository {
             return Ok(());
         }
 
-        // Calculate aggregates from readings
-        let total_rainfall: f64 = readings.iter().map(|r| r.incremental_inches).sum();
-        let reading_count = readings.len() as i32;
-
-        let first_reading_date = readings
-            .iter()
-            .min_by_key(|r| r.reading_datetime)
-            .map(|r| r.reading_datetime);
-
-        let last_reading_date = readings
-            .iter()
-            .max_by_key(|r| r.reading_datetime)
-            .map(|r| r.reading_datetime);
-
-        let min_cumulative = readings
-            .iter()
-            .map(|r| r.cumulative_inches)
-            .min_by(|a, b| a.partial_cmp(b).unwrap())
-            .unwrap_or(0.0);
-
-        let max_cumulative = readings
-            .iter()
-            .map(|r| r.cumulative_inches)
-            .max_by(|a, b| a.partial_cmp(b).unwrap())
-            .unwrap_or(0.0);
-
-        sqlx::query!(
-            r#"
-            INSERT INTO monthly_rainfall_summary
-                (station_id, year, month, total_rainfall_inches, reading_count,
-                 first_reading_date, last_reading_date, min_cumulative_inches, max_cumulative_inches)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
-            ON CONFLICT (station_id, year, month)
-            DO UPDATE SET
-                total_rainfall_inches = EXCLUDED.total_rainfall_inches,
-                reading_count = EXCLUDED.reading_count,
-                first_reading_date = EXCLUDED.first_reading_date,
-                last_reading_date = EXCLUDED.last_reading_date,
-                min_cumulative_inches = EXCLUDED.min_cumulative_inches,
-                max_cumulative_inches = EXCLUDED.max_cumulative_inches,
-                updated_at = NOW()
-            "#,
-            station_id,
-            year,
-            month,
-            total_rainfall,
-            reading_count,
-            first_reading_date,
-            last_reading_date,
-            min_cumulative,
-            max_cumulative
-        )
-        .execute(&self.pool)
-        .await?;
+        let aggregates = MonthAggregates::from_readings(readings);
+        // Quality counts come from the stored readings' import_metadata
+        let (start, end) = utils::month_date_range(year, month as u32);
+        match &self.db {
+            DbPool::Postgres(pool) => {
+                sqlx::query!(
+                    r#"
+                    INSERT INTO monthly_rainfall_summary
+                        (station_id, year, month, total_rainfall_inches, reading_count,
+                         first_reading_date, last_reading_date, min_cumulative_inches, max_cumulative_inches,
+                         flagged_count, estimated_count, footnoted_count)
+                    SELECT $1::VARCHAR, $2, $3, $4, $5, $6, $7, $8, $9,
+                           COUNT(*) FILTER (WHERE jsonb_typeof(import_metadata->'flags') = 'array'
+                                              AND import_metadata->'flags' <> '[]'::jsonb),
+                           COUNT(*) FILTER (WHERE import_metadata->'estimated' = 'true'::jsonb),
+                           COUNT(*) FILTER (WHERE import_metadata ? 'footnote_marker')
+                    FROM rain_readings
+                    WHERE station_id = $1::VARCHAR AND reading_datetime >= $10 AND reading_datetime < $11
+                    ON CONFLICT (station_id, year, month)
+                    DO UPDATE SET
+                        total_rainfall_inches = EXCLUDED.total_rainfall_inches,
+                        reading_count = EXCLUDED.reading_count,
+                        first_reading_date = EXCLUDED.first_reading_date,
+                        last_reading_date = EXCLUDED.last_reading_date,
+                        min_cumulative_inches = EXCLUDED.min_cumulative_inches,
+                        max_cumulative_inches = EXCLUDED.max_cumulative_inches,
+                        flagged_count = EXCLUDED.flagged_count,
+                        estimated_count = EXCLUDED.estimated_count,
+                        footnoted_count = EXCLUDED.footnoted_count,
+                        updated_at = NOW()
+                    "#,
+                    station_id,
+                    year,
+                    month,
+                    aggregates.total_rainfall,
+                    aggregates.reading_count,
+                    aggregates.first_reading_date,
+                    aggregates.last_reading_date,
+                    aggregates.min_cumulative,
+                    aggregates.max_cumulative,
+                    start,
+                    end
+                )
+                .execute(pool)
+                .await?;
+            }
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                sqlite::monthly_rainfall::upsert_monthly_summary(
+                    pool,
+                    station_id,
+                    year,
+                    month,
+                    &aggregates,
+                    (start, end),
+                )
+                .await?;
+            }
+        }
 
         debug!(
             "Upserted monthly summary for {} {}-{:02}: {} inches from {} readings",
-            station_id, year, month, total_rainfall, reading_count
+            station_id, year, month, aggregates.total_rainfall, aggregates.reading_count
         );
 
         Ok(())
@@ -103,28 +159,67 @@ impl MonthlyRainfallRepository {
         start: DateTime<Utc>,
         end: DateTime<Utc>,
     ) -> Result<Vec<MonthlyRainfallSummary>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::monthly_rainfall::get_summaries_by_date_range(
+                    pool, station_id, start, end,
+                )
+                .await
+            }
+        };
+        let summaries = self
+            .slow_queries
+            .observe(
+                "get_summaries_by_date_range",
+                pool,
+                || {
+                    sqlx::query_as!(
+                        MonthlyRainfallSummary,
+                        r#"
+                        SELECT id, station_id, year, month, total_rainfall_inches, reading_count,
+                               first_reading_date, last_reading_date, min_cumulative_inches, max_cumulative_inches,
+                               flagged_count, estimated_count, footnoted_count, created_at, updated_at
+                        FROM monthly_rainfall_summary
+                        WHERE station_id = $1
+                          AND month_start >= date_trunc('month', $2::timestamptz AT TIME ZONE 'UTC')
+                          AND month_start < date_trunc('month', $3::timestamptz AT TIME ZONE 'UTC')
+                        ORDER BY year ASC, month ASC
+                        "#,
+                        station_id,
+                        start,
+                        end
+                    )
+                },
+                |query| query.fetch_all(pool),
+            )
+            .await?;
+
+        Ok(summaries)
+    }
+
+    /// Every monthly summary, ordered by station and month
+    #[instrument(skip(self))]
+    pub async fn find_all_summaries(&self) -> Result<Vec<MonthlyRainfallSummary>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::monthly_rainfall::find_all_summaries(pool).await
+            }
+        };
         let summaries = sqlx::query_as!(
             MonthlyRainfallSummary,
             r#"
             SELECT id, station_id, year, month, total_rainfall_inches, reading_count,
                    first_reading_date, last_reading_date, min_cumulative_inches, max_cumulative_inches,
-                   created_at, updated_at
+                   flagged_count, estimated_count, footnoted_count, created_at, updated_at
             FROM monthly_rainfall_summary
-            WHERE station_id = $1
-              AND (
-                (year > EXTRACT(YEAR FROM $2::timestamptz) OR
-                 (year = EXTRACT(YEAR FROM $2::timestamptz) AND month >= EXTRACT(MONTH FROM $2::timestamptz)))
-                AND
-                (year < EXTRACT(YEAR FROM $3::timestamptz) OR
-                 (year = EXTRACT(YEAR FROM $3::timestamptz) AND month < EXTRACT(MONTH FROM $3::timestamptz)))
-              )
-            ORDER BY year ASC, month ASC
-            "#,
-            station_id,
-            start,
-            end
+            ORDER BY station_id ASC, year ASC, month ASC
+            "#
         )
-        .fetch_all(&self.pool)
+        .fetch_all(pool)
         .await?;
 
         Ok(summaries)
@@ -148,24 +243,361 @@ impl MonthlyRainfallRepository {
             station_id, year, month
         );
 
-        let readings = sqlx::query_as!(
-            Reading,
+        let readings = match &self.db {
+            DbPool::Postgres(pool) => {
+                sqlx::query_as!(
+                    Reading,
+                    r#"
+                    SELECT id, reading_datetime, cumulative_inches as "cumulative_inches!: Inches",
+                           incremental_inches as "incremental_inches!: Inches", station_id, created_at
+                    FROM rain_readings
+                    WHERE station_id = $1 AND reading_datetime >= $2 AND reading_datetime < $3
+                    ORDER BY reading_datetime ASC
+                    "#,
+                    station_id,
+                    start,
+                    end
+                )
+                .fetch_all(pool)
+                .await?
+            }
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                sqlite::readings::find_by_date_range(pool, station_id, start, end).await?
+            }
+        };
+
+        self.upsert_monthly_summary(station_id, year, month, &readings)
+            .await
+    }
+
+    /// Recalculate every monthly summary of one station's readings in a range
+    ///
+    /// One set-based statement on PostgreSQL instead of a read and an upsert per month.
+    /// `start` and `end` should fall on month boundaries; months without readings are
+    /// left alone. Returns the number of months summarized.
+    #[instrument(skip(self))]
+    pub async fn recalculate_station_summaries(
+        &self,
+        station_id: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<usize, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                let months = sqlite::monthly_rainfall::find_station_months(
+                    pool,
+                    Some(station_id),
+                    Some(start),
+                    Some(end),
+                )
+                .await?;
+                for (station_id, year, month) in &months {
+                    let (start, end) = utils::month_date_range(*year, *month);
+                    self.recalculate_monthly_summary(station_id, *year, *month as i32, start, end)
+                        .await?;
+                }
+                return Ok(months.len());
+            }
+        };
+
+        let mut tx = pool.begin().await?;
+        let months = self
+            .recalculate_station_summaries_tx(&mut tx, station_id, start, end)
+            .await?;
+        tx.commit().await?;
+        Ok(months)
+    }
+
+    /// List distinct station-months that have raw readings
+    ///
+    /// All filters are optional; `end` is exclusive. Used to drive bulk summary
+    /// recalculation for a station, a date range, or the whole database.
+    #[instrument(skip(self))]
+    pub async fn find_station_months(
+        &self,
+        station_id: Option<&str>,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<Vec<(String, i32, u32)>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::monthly_rainfall::find_station_months(pool, station_id, start, end)
+                    .await
+            }
+        };
+        let rows = sqlx::query!(
             r#"
-            SELECT id, reading_datetime, cumulative_inches as "cumulative_inches!",
-                   incremental_inches as "incremental_inches!", station_id, created_at
+            SELECT DISTINCT station_id,
+                   EXTRACT(YEAR FROM reading_datetime AT TIME ZONE 'UTC')::INT AS "year!",
+                   EXTRACT(MONTH FROM reading_datetime AT TIME ZONE 'UTC')::INT AS "month!"
             FROM rain_readings
-            WHERE station_id = $1 AND reading_datetime >= $2 AND reading_datetime < $3
-            ORDER BY reading_datetime ASC
+            WHERE ($1::TEXT IS NULL OR station_id = $1)
+              AND ($2::TIMESTAMPTZ IS NULL OR reading_datetime >= $2)
+              AND ($3::TIMESTAMPTZ IS NULL OR reading_datetime < $3)
+            ORDER BY 1, 2, 3
             "#,
             station_id,
             start,
             end
         )
-        .fetch_all(&self.pool)
+        .fetch_all(pool)
         .await?;
 
-        self.upsert_monthly_summary(station_id, year, month, &readings)
-            .await
+        Ok(rows
+            .into_iter()
+            .map(|r| (r.station_id, r.year, r.month as u32))
+            .collect())
+    }
+
+    /// Delete monthly summaries that no longer have any readings backing them
+    ///
+    /// Happens after readings are rolled back or corrected. Filters match
+    /// `find_station_months`; a summary is in range when its month overlaps it.
+    #[instrument(skip(self))]
+    pub async fn delete_orphaned_summaries(
+        &self,
+        station_id: Option<&str>,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<u64, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::monthly_rainfall::delete_orphaned_summaries(
+                    pool, station_id, start, end,
+                )
+                .await
+            }
+        };
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM monthly_rainfall_summary s
+            WHERE ($1::TEXT IS NULL OR s.station_id = $1)
+              AND ($2::TIMESTAMPTZ IS NULL OR make_timestamptz(s.year, s.month, 1, 0, 0, 0, 'UTC') + INTERVAL '1 month' > $2)
+              AND ($3::TIMESTAMPTZ IS NULL OR make_timestamptz(s.year, s.month, 1, 0, 0, 0, 'UTC') < $3)
+              AND NOT EXISTS (
+                  SELECT 1 FROM rain_readings r
+                  WHERE r.station_id = s.station_id
+                    AND r.reading_datetime >= make_timestamptz(s.year, s.month, 1, 0, 0, 0, 'UTC')
+                    AND r.reading_datetime < make_timestamptz(s.year, s.month, 1, 0, 0, 0, 'UTC') + INTERVAL '1 month'
+              )
+            "#,
+            station_id,
+            start,
+            end
+        )
+        .execute(pool)
+        .await?;
+
+        debug!(
+            "Deleted {} orphaned monthly summaries",
+            result.rows_affected()
+        );
+        Ok(result.rows_affected())
+    }
+
+    /// Find station-months where monthly summaries disagree with raw readings
+    ///
+    /// Compares total rainfall and reading counts per station-month within the date
+    /// range, including months with readings but no summary and vice versa.
+    /// Optionally restricted to a single station.
+    #[instrument(skip(self))]
+    pub async fn find_summary_discrepancies(
+        &self,
+        station_id: Option<&str>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<SummaryDiscrepancy>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::monthly_rainfall::find_summary_discrepancies(
+                    pool, station_id, start, end,
+                )
+                .await
+            }
+        };
+        let discrepancies = self
+            .slow_queries
+            .observe(
+                "find_summary_discrepancies",
+                pool,
+                || {
+                    sqlx::query_as!(
+                        SummaryDiscrepancy,
+                        r#"
+                        WITH actual AS (
+                            SELECT station_id,
+                                   EXTRACT(YEAR FROM reading_datetime AT TIME ZONE 'UTC')::INT AS year,
+                                   EXTRACT(MONTH FROM reading_datetime AT TIME ZONE 'UTC')::INT AS month,
+                                   SUM(incremental_inches) AS total_inches,
+                                   COUNT(*) AS reading_count
+                            FROM rain_readings
+                            WHERE reading_datetime >= $1 AND reading_datetime < $2
+                              AND ($3::TEXT IS NULL OR station_id = $3)
+                            GROUP BY 1, 2, 3
+                        ),
+                        summarized AS (
+                            SELECT station_id, year, month, total_rainfall_inches, reading_count
+                            FROM monthly_rainfall_summary
+                            WHERE month_start >= ($1::timestamptz AT TIME ZONE 'UTC')
+                              AND month_start < ($2::timestamptz AT TIME ZONE 'UTC')
+                              AND ($3::TEXT IS NULL OR station_id = $3)
+                        )
+                        SELECT COALESCE(s.station_id, a.station_id) AS "station_id!",
+                               COALESCE(s.year, a.year) AS "year!",
+                               COALESCE(s.month, a.month) AS "month!",
+                               s.total_rainfall_inches AS "summary_total_inches?",
+                               a.total_inches AS "readings_total_inches?",
+                               s.reading_count AS "summary_reading_count?",
+                               a.reading_count AS "actual_reading_count?"
+                        FROM summarized s
+                        FULL OUTER JOIN actual a
+                          ON s.station_id = a.station_id AND s.year = a.year AND s.month = a.month
+                        WHERE s.station_id IS NULL
+                           OR a.station_id IS NULL
+                           OR s.reading_count <> a.reading_count
+                           OR ABS(s.total_rainfall_inches - a.total_inches) > 0.001
+                        ORDER BY 1, 2, 3
+                        "#,
+                        start,
+                        end,
+                        station_id
+                    )
+                },
+                |query| query.fetch_all(pool),
+            )
+            .await?;
+
+        Ok(discrepancies)
+    }
+
+    /// Percentiles of monthly totals per calendar month for a station
+    ///
+    /// Only months starting before `before` are included, so the current partial
+    /// month can be excluded from the statistics.
+    #[instrument(skip(self))]
+    pub async fn monthly_percentiles(
+        &self,
+        station_id: &str,
+        before: DateTime<Utc>,
+    ) -> Result<Vec<MonthPercentileRow>, DbError> {
+        let pool = self.db.postgres()?;
+        let rows = self
+            .slow_queries
+            .observe(
+                "monthly_percentiles",
+                pool,
+                || {
+                    sqlx::query_as!(
+                        MonthPercentileRow,
+                        r#"
+                        SELECT month,
+                               COUNT(*) AS "years_of_record!",
+                               AVG(total_rainfall_inches) AS "mean_inches!",
+                               MIN(total_rainfall_inches) AS "min_inches!",
+                               percentile_cont(0.10) WITHIN GROUP (ORDER BY total_rainfall_inches) AS "p10_inches!",
+                               percentile_cont(0.25) WITHIN GROUP (ORDER BY total_rainfall_inches) AS "p25_inches!",
+                               percentile_cont(0.50) WITHIN GROUP (ORDER BY total_rainfall_inches) AS "p50_inches!",
+                               percentile_cont(0.75) WITHIN GROUP (ORDER BY total_rainfall_inches) AS "p75_inches!",
+                               percentile_cont(0.90) WITHIN GROUP (ORDER BY total_rainfall_inches) AS "p90_inches!",
+                               MAX(total_rainfall_inches) AS "max_inches!"
+                        FROM monthly_rainfall_summary
+                        WHERE station_id = $1
+                          AND month_start < ($2::timestamptz AT TIME ZONE 'UTC')
+                        GROUP BY month
+                        ORDER BY month
+                        "#,
+                        station_id,
+                        before
+                    )
+                },
+                |query| query.fetch_all(pool),
+            )
+            .await?;
+
+        debug!(
+            "Computed percentiles for {} calendar months for station {}",
+            rows.len(),
+            station_id
+        );
+        Ok(rows)
+    }
+
+    /// Rank gauges by the sum of their monthly totals for months starting in `[start, end)`
+    ///
+    /// Faster than ranking raw readings for month and water year periods. Only gauges
+    /// listed in gauge_summaries with a summary in range are ranked, and only Active
+    /// ones unless `include_inactive` is set.
+    #[instrument(skip(self))]
+    pub async fn rank_stations_by_monthly_totals(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        driest_first: bool,
+        include_inactive: bool,
+        limit: i64,
+    ) -> Result<Vec<RankingRow>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::monthly_rainfall::rank_stations_by_monthly_totals(
+                    pool,
+                    start,
+                    end,
+                    driest_first,
+                    include_inactive,
+                    limit,
+                )
+                .await
+            }
+        };
+        let rows = self
+            .slow_queries
+            .observe(
+                "rank_stations_by_monthly_totals",
+                pool,
+                || {
+                    sqlx::query_as!(
+                        RankingRow,
+                        r#"
+                        SELECT m.station_id, g.gauge_name, g.city_town,
+                               COALESCE(gs.forecast_zone, g.msp_forecast_zone) AS msp_forecast_zone, g.general_location,
+                               SUM(m.total_rainfall_inches) AS "rainfall_inches!",
+                               SUM(m.reading_count)::BIGINT AS "reading_count!"
+                        FROM monthly_rainfall_summary m
+                        JOIN gauge_summaries g ON g.station_id = m.station_id
+                        LEFT JOIN gauges gs ON gs.station_id = m.station_id
+                        WHERE m.month_start >= ($1::timestamptz AT TIME ZONE 'UTC')
+                          AND m.month_start < ($2::timestamptz AT TIME ZONE 'UTC')
+                          AND ($5 OR COALESCE(gs.status, 'Active') = 'Active')
+                        GROUP BY m.station_id, g.gauge_name, g.city_town, g.msp_forecast_zone, g.general_location,
+                                 gs.forecast_zone
+                        ORDER BY CASE WHEN $3 THEN SUM(m.total_rainfall_inches) END ASC,
+                                 CASE WHEN NOT $3 THEN SUM(m.total_rainfall_inches) END DESC,
+                                 m.station_id
+                        LIMIT $4
+                        "#,
+                        start,
+                        end,
+                        driest_first,
+                        limit,
+                        include_inactive
+                    )
+                },
+                |query| query.fetch_all(pool),
+            )
+            .await?;
+
+        Ok(rows)
     }
 
     // ============================================================
@@ -187,7 +619,8 @@ impl MonthlyRainfallRepository {
             return Ok(());
         }
 
-        let total_rainfall: f64 = readings.iter().map(|r| r.incremental_inches).sum();
+        let total_rainfall =
+            units::round_inches(readings.iter().map(|r| r.incremental_inches.value()).sum());
         let reading_count = readings.len() as i32;
 
         let first_reading_date = readings
@@ -202,22 +635,30 @@ impl MonthlyRainfallRepository {
 
         let min_cumulative = readings
             .iter()
-            .map(|r| r.cumulative_inches)
+            .map(|r| r.cumulative_inches.value())
             .min_by(|a, b| a.partial_cmp(b).unwrap())
             .unwrap_or(0.0);
 
         let max_cumulative = readings
             .iter()
-            .map(|r| r.cumulative_inches)
+            .map(|r| r.cumulative_inches.value())
             .max_by(|a, b| a.partial_cmp(b).unwrap())
             .unwrap_or(0.0);
 
+        let (start, end) = utils::month_date_range(year, month as u32);
         sqlx::query!(
             r#"
             INSERT INTO monthly_rainfall_summary
                 (station_id, year, month, total_rainfall_inches, reading_count,
-                 first_reading_date, last_reading_date, min_cumulative_inches, max_cumulative_inches)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                 first_reading_date, last_reading_date, min_cumulative_inches, max_cumulative_inches,
+                 flagged_count, estimated_count, footnoted_count)
+            SELECT $1::VARCHAR, $2, $3, $4, $5, $6, $7, $8, $9,
+                   COUNT(*) FILTER (WHERE jsonb_typeof(import_metadata->'flags') = 'array'
+                                      AND import_metadata->'flags' <> '[]'::jsonb),
+                   COUNT(*) FILTER (WHERE import_metadata->'estimated' = 'true'::jsonb),
+                   COUNT(*) FILTER (WHERE import_metadata ? 'footnote_marker')
+            FROM rain_readings
+            WHERE station_id = $1::VARCHAR AND reading_datetime >= $10 AND reading_datetime < $11
             ON CONFLICT (station_id, year, month)
             DO UPDATE SET
                 total_rainfall_inches = EXCLUDED.total_rainfall_inches,
@@ -226,6 +667,9 @@ impl MonthlyRainfallRepository {
                 last_reading_date = EXCLUDED.last_reading_date,
                 min_cumulative_inches = EXCLUDED.min_cumulative_inches,
                 max_cumulative_inches = EXCLUDED.max_cumulative_inches,
+                flagged_count = EXCLUDED.flagged_count,
+                estimated_count = EXCLUDED.estimated_count,
+                footnoted_count = EXCLUDED.footnoted_count,
                 updated_at = NOW()
             "#,
             station_id,
@@ -236,7 +680,9 @@ impl MonthlyRainfallRepository {
             first_reading_date,
             last_reading_date,
             min_cumulative,
-            max_cumulative
+            max_cumulative,
+            start,
+            end
         )
         .execute(&mut **tx)
         .await?;
@@ -263,16 +709,11 @@ impl MonthlyRainfallRepository {
             r#"
             SELECT id, station_id, year, month, total_rainfall_inches, reading_count,
                    first_reading_date, last_reading_date, min_cumulative_inches, max_cumulative_inches,
-                   created_at, updated_at
+                   flagged_count, estimated_count, footnoted_count, created_at, updated_at
             FROM monthly_rainfall_summary
             WHERE station_id = $1
-              AND (
-                (year > EXTRACT(YEAR FROM $2::timestamptz) OR
-                 (year = EXTRACT(YEAR FROM $2::timestamptz) AND month >= EXTRACT(MONTH FROM $2::timestamptz)))
-                AND
-                (year < EXTRACT(YEAR FROM $3::timestamptz) OR
-                 (year = EXTRACT(YEAR FROM $3::timestamptz) AND month < EXTRACT(MONTH FROM $3::timestamptz)))
-              )
+              AND month_start >= date_trunc('month', $2::timestamptz AT TIME ZONE 'UTC')
+              AND month_start < date_trunc('month', $3::timestamptz AT TIME ZONE 'UTC')
             ORDER BY year ASC, month ASC
             "#,
             station_id,
@@ -304,8 +745,8 @@ impl MonthlyRainfallRepository {
         let readings = sqlx::query_as!(
             Reading,
             r#"
-            SELECT id, reading_datetime, cumulative_inches as "cumulative_inches!",
-                   incremental_inches as "incremental_inches!", station_id, created_at
+            SELECT id, reading_datetime, cumulative_inches as "cumulative_inches!: Inches",
+                   incremental_inches as "incremental_inches!: Inches", station_id, created_at
             FROM rain_readings
             WHERE station_id = $1 AND reading_datetime >= $2 AND reading_datetime < $3
             ORDER BY reading_datetime ASC
@@ -320,4 +761,66 @@ impl MonthlyRainfallRepository {
         self.upsert_monthly_summary_tx(tx, station_id, year, month, &readings)
             .await
     }
+
+    /// Recalculate every monthly summary of one station's readings in a range using a
+    /// transaction
+    ///
+    /// Totals are rounded like `MonthAggregates`; see `recalculate_station_summaries`.
+    #[instrument(skip(self, tx))]
+    pub async fn recalculate_station_summaries_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        station_id: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<usize, DbError> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO monthly_rainfall_summary
+                (station_id, year, month, total_rainfall_inches, reading_count,
+                 first_reading_date, last_reading_date, min_cumulative_inches, max_cumulative_inches,
+                 flagged_count, estimated_count, footnoted_count)
+            SELECT $1::VARCHAR,
+                   EXTRACT(YEAR FROM reading_datetime AT TIME ZONE 'UTC')::INT,
+                   EXTRACT(MONTH FROM reading_datetime AT TIME ZONE 'UTC')::INT,
+                   ROUND(SUM(incremental_inches) * 100) / 100,
+                   COUNT(*),
+                   MIN(reading_datetime),
+                   MAX(reading_datetime),
+                   MIN(cumulative_inches),
+                   MAX(cumulative_inches),
+                   COUNT(*) FILTER (WHERE jsonb_typeof(import_metadata->'flags') = 'array'
+                                      AND import_metadata->'flags' <> '[]'::jsonb),
+                   COUNT(*) FILTER (WHERE import_metadata->'estimated' = 'true'::jsonb),
+                   COUNT(*) FILTER (WHERE import_metadata ? 'footnote_marker')
+            FROM rain_readings
+            WHERE station_id = $1::VARCHAR AND reading_datetime >= $2 AND reading_datetime < $3
+            GROUP BY 2, 3
+            ON CONFLICT (station_id, year, month)
+            DO UPDATE SET
+                total_rainfall_inches = EXCLUDED.total_rainfall_inches,
+                reading_count = EXCLUDED.reading_count,
+                first_reading_date = EXCLUDED.first_reading_date,
+                last_reading_date = EXCLUDED.last_reading_date,
+                min_cumulative_inches = EXCLUDED.min_cumulative_inches,
+                max_cumulative_inches = EXCLUDED.max_cumulative_inches,
+                flagged_count = EXCLUDED.flagged_count,
+                estimated_count = EXCLUDED.estimated_count,
+                footnoted_count = EXCLUDED.footnoted_count,
+                updated_at = NOW()
+            "#,
+            station_id,
+            start,
+            end
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        debug!(
+            "Recalculated {} monthly summaries for {}",
+            result.rows_affected(),
+            station_id
+        );
+        Ok(result.rows_affected() as usize)
+    }
 }
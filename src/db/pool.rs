@@ -1,16 +1,206 @@
-use sqlx::PgPool;
+// Database connection facade
+//
+// PostgreSQL is the primary backend. Builds with the `sqlite` feature can instead run on a
+// SQLite file (DATABASE_URL=sqlite:...) for small offline deployments such as a Raspberry Pi.
+// Repositories hold a DbPool and send each query to its backend's SQL; the SQLite versions
+// live in `db::sqlite`. Queries with no SQLite version (FOPR import jobs, monthly normals,
+// the `_tx` test helpers) return `DbError::Unsupported` there.
 
-#[derive(Clone)]
-pub struct DbPool {
-    pool: PgPool,
+use std::time::Duration;
+
+use backon::ExponentialBuilder;
+use log::LevelFilter;
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+#[cfg(feature = "sqlite")]
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqlitePoolOptions};
+use sqlx::{ConnectOptions, PgPool};
+
+use crate::db::DbError;
+
+/// How long a query waits for a pooled connection, which also bounds each startup
+/// connection attempt while the server is down (sqlx keeps redialing until then)
+const ACQUIRE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Connection attempts before startup gives up
+pub const DEFAULT_CONNECT_MAX_ATTEMPTS: usize = 10;
+
+/// Delay before the second connection attempt; later delays double up to the max
+pub const DEFAULT_CONNECT_INITIAL_BACKOFF_MS: u64 = 500;
+
+pub const DEFAULT_CONNECT_MAX_BACKOFF_SECS: u64 = 30;
+
+/// How startup retries the initial connection and migration run while the database
+/// is unreachable (see [`DbError::is_transient`])
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectRetry {
+    /// DB_CONNECT_MAX_ATTEMPTS, default 10
+    pub max_attempts: usize,
+    /// DB_CONNECT_INITIAL_BACKOFF_MS, default 500
+    pub initial_backoff_ms: u64,
+    /// DB_CONNECT_MAX_BACKOFF_SECS, default 30
+    pub max_backoff_secs: u64,
+}
+
+impl Default for ConnectRetry {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_CONNECT_MAX_ATTEMPTS,
+            initial_backoff_ms: DEFAULT_CONNECT_INITIAL_BACKOFF_MS,
+            max_backoff_secs: DEFAULT_CONNECT_MAX_BACKOFF_SECS,
+        }
+    }
+}
+
+impl ConnectRetry {
+    /// Exponential backoff with jitter between attempts
+    pub fn backoff(&self) -> ExponentialBuilder {
+        ExponentialBuilder::default()
+            .with_min_delay(Duration::from_millis(self.initial_backoff_ms))
+            .with_max_delay(Duration::from_secs(self.max_backoff_secs))
+            .with_factor(2.0)
+            .with_max_times(self.max_attempts.saturating_sub(1))
+            .with_jitter()
+    }
+}
+
+/// Connections held by a pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolStats {
+    pub size: u32,
+    pub idle: u32,
+}
+
+#[derive(Debug, Clone)]
+pub enum DbPool {
+    Postgres(PgPool),
+    #[cfg(feature = "sqlite")]
+    Sqlite(SqlitePool),
 }
 
 impl DbPool {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    /// Connect to `url`; `sqlite:` URLs select the SQLite backend
+    pub async fn connect(url: &str, max_connections: u32) -> Result<Self, DbError> {
+        // sqlx's own default
+        Self::connect_with_statement_log(url, max_connections, LevelFilter::Debug).await
+    }
+
+    /// Connect to `url`, logging every statement at `statement_level` (SQL_LOG_STATEMENTS
+    /// raises it to info so the default log filter shows them)
+    pub async fn connect_with_statement_log(
+        url: &str,
+        max_connections: u32,
+        statement_level: LevelFilter,
+    ) -> Result<Self, DbError> {
+        if url.starts_with("sqlite:") {
+            return Self::connect_sqlite(url, max_connections, statement_level).await;
+        }
+
+        let options = url
+            .parse::<PgConnectOptions>()?
+            .log_statements(statement_level);
+        let pool = PgPoolOptions::new()
+            .max_connections(max_connections)
+            .acquire_timeout(ACQUIRE_TIMEOUT)
+            .connect_with(options)
+            .await?;
+        Ok(DbPool::Postgres(pool))
     }
 
-    pub fn pool(&self) -> &PgPool {
-        &self.pool
+    #[cfg(feature = "sqlite")]
+    async fn connect_sqlite(
+        url: &str,
+        max_connections: u32,
+        statement_level: LevelFilter,
+    ) -> Result<Self, DbError> {
+        use std::str::FromStr;
+
+        // WAL lets API reads proceed while a scheduler writes
+        let options = SqliteConnectOptions::from_str(url)?
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal)
+            .log_statements(statement_level);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(max_connections)
+            .connect_with(options)
+            .await?;
+        Ok(DbPool::Sqlite(pool))
+    }
+
+    #[cfg(not(feature = "sqlite"))]
+    async fn connect_sqlite(
+        _url: &str,
+        _max_connections: u32,
+        _statement_level: LevelFilter,
+    ) -> Result<Self, DbError> {
+        Err(sqlx::Error::Configuration(
+            "SQLite support is not compiled in; rebuild with `--features sqlite`".into(),
+        )
+        .into())
+    }
+
+    /// Apply pending migrations: `migrations/` for PostgreSQL, `migrations/sqlite/` for SQLite
+    pub async fn migrate(&self) -> Result<(), DbError> {
+        match self {
+            DbPool::Postgres(pool) => self.migrator().run(pool).await,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => self.migrator().run(pool).await,
+        }
+        .map_err(sqlx::Error::from)?;
+        Ok(())
+    }
+
+    /// Round-trip a trivial query; the pool replaces dead connections as it goes
+    pub async fn ping(&self) -> Result<(), DbError> {
+        match self {
+            DbPool::Postgres(pool) => sqlx::query("SELECT 1").execute(pool).await.map(|_| ()),
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => sqlx::query("SELECT 1").execute(pool).await.map(|_| ()),
+        }?;
+        Ok(())
+    }
+
+    pub fn stats(&self) -> PoolStats {
+        match self {
+            DbPool::Postgres(pool) => PoolStats {
+                size: pool.size(),
+                idle: pool.num_idle() as u32,
+            },
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => PoolStats {
+                size: pool.size(),
+                idle: pool.num_idle() as u32,
+            },
+        }
+    }
+
+    /// Backend name for logs and errors
+    pub fn backend(&self) -> &'static str {
+        match self {
+            DbPool::Postgres(_) => "postgres",
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(_) => "sqlite",
+        }
+    }
+
+    /// The PostgreSQL pool, for queries that only exist for PostgreSQL
+    pub fn postgres(&self) -> Result<&PgPool, DbError> {
+        match self {
+            DbPool::Postgres(pool) => Ok(pool),
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(_) => Err(DbError::Unsupported(self.backend())),
+        }
+    }
+}
+
+impl From<PgPool> for DbPool {
+    fn from(pool: PgPool) -> Self {
+        DbPool::Postgres(pool)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl From<SqlitePool> for DbPool {
+    fn from(pool: SqlitePool) -> Self {
+        DbPool::Sqlite(pool)
     }
 }
@@ -0,0 +1,70 @@
+use tracing::{instrument, warn};
+
+#[cfg(feature = "sqlite")]
+use crate::db::sqlite;
+use crate::db::{DbError, DbPool};
+use crate::ingest_guard::QuarantinedReading;
+
+/// Readings the ingest guards kept out of `rain_readings`
+#[derive(Clone)]
+pub struct QuarantineRepository {
+    db: DbPool,
+}
+
+impl QuarantineRepository {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        Self { db: pool.into() }
+    }
+
+    /// Record rejected readings in a transaction, returning how many were new
+    ///
+    /// A reading already quarantined from the same source (a live row seen on every
+    /// scrape, a re-imported file) is skipped.
+    #[instrument(skip(self, readings), fields(count = readings.len()))]
+    pub async fn insert(
+        &self,
+        data_source: &str,
+        readings: &[QuarantinedReading],
+    ) -> Result<usize, DbError> {
+        if readings.is_empty() {
+            return Ok(0);
+        }
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::quarantine::insert(pool, data_source, readings).await
+            }
+        };
+        let mut tx = pool.begin().await?;
+        let mut inserted = 0;
+
+        for reading in readings {
+            let result = sqlx::query!(
+                r#"
+                INSERT INTO quarantined_readings
+                    (station_id, reading_datetime, cumulative_inches, incremental_inches,
+                     data_source, reason)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                ON CONFLICT (station_id, reading_datetime, data_source) DO NOTHING
+                "#,
+                reading.station_id,
+                reading.reading_datetime,
+                reading.cumulative_inches,
+                reading.incremental_inches,
+                data_source,
+                reading.reason.as_str()
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            inserted += result.rows_affected() as usize;
+        }
+
+        tx.commit().await?;
+        if inserted > 0 {
+            warn!("Quarantined {} new readings from {}", inserted, data_source);
+        }
+        Ok(inserted)
+    }
+}
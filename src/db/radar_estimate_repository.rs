@@ -0,0 +1,144 @@
+use chrono::{DateTime, Utc};
+use tracing::{info, instrument};
+
+use crate::db::{DbError, DbPool, RadarComparisonRow, RadarStorm};
+
+/// MRMS radar estimates per gauge and storm window; PostgreSQL only
+#[derive(Clone)]
+pub struct RadarEstimateRepository {
+    db: DbPool,
+}
+
+impl RadarEstimateRepository {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        Self { db: pool.into() }
+    }
+
+    /// Replace the estimates for a storm window and product in one transaction
+    ///
+    /// Returns the number of estimates stored.
+    #[instrument(skip(self, estimates), fields(count = estimates.len()))]
+    pub async fn replace_storm(
+        &self,
+        storm_start: DateTime<Utc>,
+        storm_end: DateTime<Utc>,
+        product: &str,
+        source: &str,
+        estimates: &[(String, f64)],
+    ) -> Result<u64, DbError> {
+        let mut tx = self.db.postgres()?.begin().await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM radar_estimates
+            WHERE storm_start = $1 AND storm_end = $2 AND product = $3
+            "#,
+            storm_start,
+            storm_end,
+            product
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let (station_ids, radar_inches): (Vec<String>, Vec<f64>) =
+            estimates.iter().cloned().unzip();
+        let stored = sqlx::query!(
+            r#"
+            INSERT INTO radar_estimates
+                (station_id, storm_start, storm_end, product, radar_inches, source)
+            SELECT station_id, $3, $4, $5, radar_inches, $6
+            FROM UNNEST($1::VARCHAR[], $2::FLOAT8[]) AS e(station_id, radar_inches)
+            "#,
+            &station_ids,
+            &radar_inches,
+            storm_start,
+            storm_end,
+            product,
+            source
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        tx.commit().await?;
+        info!(
+            "Stored {} radar estimates for {} to {} ({})",
+            stored, storm_start, storm_end, product
+        );
+        Ok(stored)
+    }
+
+    /// Imported storm windows, newest first
+    #[instrument(skip(self))]
+    pub async fn find_storms(&self, offset: i64, limit: i64) -> Result<Vec<RadarStorm>, DbError> {
+        let storms = sqlx::query_as!(
+            RadarStorm,
+            r#"
+            SELECT storm_start, storm_end, product,
+                   COUNT(*) AS "gauge_count!",
+                   MAX(sampled_at) AS "sampled_at!"
+            FROM radar_estimates
+            GROUP BY storm_start, storm_end, product
+            ORDER BY storm_start DESC, storm_end DESC, product
+            LIMIT $1 OFFSET $2
+            "#,
+            limit,
+            offset
+        )
+        .fetch_all(self.db.postgres()?)
+        .await?;
+
+        Ok(storms)
+    }
+
+    /// Number of imported storm windows, counting each product separately
+    #[instrument(skip(self))]
+    pub async fn count_storms(&self) -> Result<i64, DbError> {
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "count!"
+            FROM (SELECT DISTINCT storm_start, storm_end, product FROM radar_estimates) storms
+            "#
+        )
+        .fetch_one(self.db.postgres()?)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Each gauge's estimate for the storm with its readings in `[storm_start, storm_end)`
+    ///
+    /// Empty when the storm window and product were never imported.
+    #[instrument(skip(self))]
+    pub async fn find_comparison(
+        &self,
+        storm_start: DateTime<Utc>,
+        storm_end: DateTime<Utc>,
+        product: &str,
+    ) -> Result<Vec<RadarComparisonRow>, DbError> {
+        let rows = sqlx::query_as!(
+            RadarComparisonRow,
+            r#"
+            SELECT e.station_id, g.station_name, e.radar_inches,
+                   COALESCE(SUM(r.incremental_inches), 0)::FLOAT8 AS "gauge_inches!",
+                   COUNT(r.id) AS "reading_count!"
+            FROM radar_estimates e
+            JOIN gauges g ON g.station_id = e.station_id
+            LEFT JOIN rain_readings r
+                ON r.station_id = e.station_id
+               AND r.reading_datetime >= e.storm_start
+               AND r.reading_datetime < e.storm_end
+            WHERE e.storm_start = $1 AND e.storm_end = $2 AND e.product = $3
+            GROUP BY e.station_id, g.station_name, e.radar_inches
+            ORDER BY e.station_id
+            "#,
+            storm_start,
+            storm_end,
+            product
+        )
+        .fetch_all(self.db.postgres()?)
+        .await?;
+
+        Ok(rows)
+    }
+}
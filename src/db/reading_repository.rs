@@ -1,128 +1,429 @@
-use chrono::{DateTime, Datelike, TimeZone, Utc};
-use sqlx::{PgPool, Postgres, Transaction};
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Utc};
+use futures::stream::{BoxStream, StreamExt, TryStreamExt};
+use sqlx::{Postgres, Transaction};
 use tracing::{debug, info, instrument};
 
-use crate::db::{DbError, Reading};
-use crate::fetcher::RainReading;
+#[cfg(feature = "sqlite")]
+use crate::db::sqlite;
+use crate::db::{CoverageRow, DbError, DbPool, ExportReading, RankingRow, Reading};
+use crate::fetcher::{RainReading, LIVE_DATA_SOURCE, LIVE_STATION_ID};
 use crate::importers::excel_importer::HistoricalReading;
+use crate::units::Inches;
+use crate::utils;
+
+/// Readings written per multi-row INSERT
+const INSERT_BATCH_SIZE: usize = 1000;
+
+/// Counts from writing historical readings that may already be stored
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HistoricalUpsert {
+    pub inserted: usize,
+    /// Stored readings overwritten with a different amount or metadata
+    pub updated: usize,
+    /// Stored readings left as they were: identical, or from the live scraper
+    pub unchanged: usize,
+}
+
+/// Counts from writing scraped readings with late-data revision
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LiveUpsert {
+    pub inserted: usize,
+    /// Stored live readings inside the late-data window replaced by revised values
+    pub updated: usize,
+    /// Times of the inserted and updated readings, oldest first
+    pub changed: Vec<DateTime<Utc>>,
+}
+
+/// Column arrays of scraped readings for UNNEST: datetimes, cumulative, incremental
+fn reading_columns(readings: &[RainReading]) -> (Vec<DateTime<Utc>>, Vec<f64>, Vec<f64>) {
+    (
+        readings.iter().map(|r| r.reading_datetime).collect(),
+        readings
+            .iter()
+            .map(|r| r.cumulative_inches.value())
+            .collect(),
+        readings
+            .iter()
+            .map(|r| r.incremental_inches.value())
+            .collect(),
+    )
+}
+
+/// Column arrays of historical readings for UNNEST: midnight UTC datetimes, incremental
+/// amounts, and footnote markers
+fn historical_columns(
+    readings: &[HistoricalReading],
+) -> (Vec<DateTime<Utc>>, Vec<f64>, Vec<Option<String>>) {
+    (
+        readings
+            .iter()
+            .map(|r| Utc.from_utc_datetime(&r.reading_date.and_hms_opt(0, 0, 0).unwrap()))
+            .collect(),
+        readings.iter().map(|r| r.rainfall_inches.value()).collect(),
+        readings.iter().map(|r| r.footnote_marker.clone()).collect(),
+    )
+}
 
 #[derive(Clone)]
 pub struct ReadingRepository {
-    pool: PgPool,
+    db: DbPool,
 }
 
 impl ReadingRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        Self { db: pool.into() }
     }
 
     /// Insert multiple readings in a transaction
     #[instrument(skip(self, readings), fields(count = readings.len()))]
     pub async fn insert_readings(&self, readings: &[RainReading]) -> Result<usize, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => return sqlite::readings::insert_readings(pool, readings).await,
+        };
         debug!(
             "Beginning transaction to insert {} readings",
             readings.len()
         );
-        let mut tx = self.pool.begin().await?;
+        let mut tx = pool.begin().await?;
         let mut inserted = 0;
-        let mut duplicates = 0;
 
-        for reading in readings {
+        for batch in readings.chunks(INSERT_BATCH_SIZE) {
+            let (datetimes, cumulative, incremental) = reading_columns(batch);
             let result = sqlx::query!(
                 r#"
                 INSERT INTO rain_readings (reading_datetime, cumulative_inches, incremental_inches)
-                VALUES ($1, $2, $3)
+                SELECT * FROM UNNEST($1::TIMESTAMPTZ[], $2::FLOAT8[], $3::FLOAT8[])
                 ON CONFLICT (reading_datetime, station_id) DO NOTHING
                 "#,
-                reading.reading_datetime,
-                reading.cumulative_inches,
-                reading.incremental_inches
+                &datetimes,
+                &cumulative,
+                &incremental
             )
             .execute(&mut *tx)
             .await?;
 
-            if result.rows_affected() > 0 {
-                inserted += 1;
-            } else {
-                duplicates += 1;
-            }
+            inserted += result.rows_affected() as usize;
         }
 
         tx.commit().await?;
         info!(
             "Inserted {} new readings, {} duplicates skipped",
-            inserted, duplicates
+            inserted,
+            readings.len() - inserted
         );
         Ok(inserted)
     }
 
-    /// Insert historical readings (from FOPR imports, Excel files, etc.) in bulk
+    /// Insert scraped readings, revising stored ones the gauge has since corrected
     ///
-    /// This is a data access method - all business logic should be in the service layer.
-    /// Returns (inserted_count, duplicate_count, affected_months) where affected_months
-    /// contains (year, month) tuples for months that had new data inserted.
+    /// Live readings at or after `revisable_from` whose amounts differ from the scrape are
+    /// updated, with the replaced values kept in reading_revisions; older ones and
+    /// readings from imports are left alone as `insert_readings` would.
+    #[instrument(skip(self, readings), fields(count = readings.len()))]
+    pub async fn upsert_readings(
+        &self,
+        readings: &[RainReading],
+        revisable_from: DateTime<Utc>,
+    ) -> Result<LiveUpsert, DbError> {
+        // One statement cannot update a row twice; the last copy of a time wins
+        let readings: Vec<RainReading> = readings
+            .iter()
+            .map(|r| (r.reading_datetime, r.clone()))
+            .collect::<BTreeMap<_, _>>()
+            .into_values()
+            .collect();
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::readings::upsert_readings(pool, &readings, revisable_from).await
+            }
+        };
+        let mut tx = pool.begin().await?;
+        let mut result = LiveUpsert::default();
+
+        for batch in readings.chunks(INSERT_BATCH_SIZE) {
+            let (datetimes, cumulative, incremental) = reading_columns(batch);
+            // xmax is 0 only for rows this statement inserted; rows left alone by the
+            // DO UPDATE condition are not returned
+            let written = sqlx::query!(
+                r#"
+                WITH incoming AS (
+                    SELECT * FROM UNNEST($1::TIMESTAMPTZ[], $2::FLOAT8[], $3::FLOAT8[])
+                        AS t(reading_datetime, cumulative_inches, incremental_inches)
+                ),
+                revisions AS (
+                    INSERT INTO reading_revisions
+                        (reading_id, station_id, reading_datetime, previous_incremental_inches,
+                         previous_cumulative_inches, previous_data_source, previous_import_metadata,
+                         new_incremental_inches, new_data_source)
+                    SELECT r.id, r.station_id, r.reading_datetime, r.incremental_inches,
+                           r.cumulative_inches, r.data_source, r.import_metadata,
+                           i.incremental_inches, r.data_source
+                    FROM rain_readings r
+                    JOIN incoming i ON i.reading_datetime = r.reading_datetime
+                    WHERE r.station_id = $5
+                      AND r.data_source = $6
+                      AND r.reading_datetime >= $4
+                      AND (r.cumulative_inches <> i.cumulative_inches
+                           OR r.incremental_inches <> i.incremental_inches)
+                )
+                INSERT INTO rain_readings (reading_datetime, cumulative_inches, incremental_inches)
+                SELECT reading_datetime, cumulative_inches, incremental_inches FROM incoming
+                ON CONFLICT (reading_datetime, station_id) DO UPDATE
+                SET cumulative_inches = EXCLUDED.cumulative_inches,
+                    incremental_inches = EXCLUDED.incremental_inches
+                WHERE rain_readings.data_source = $6
+                  AND rain_readings.reading_datetime >= $4
+                  AND (rain_readings.cumulative_inches <> EXCLUDED.cumulative_inches
+                       OR rain_readings.incremental_inches <> EXCLUDED.incremental_inches)
+                RETURNING reading_datetime, (xmax = 0) AS "inserted!"
+                "#,
+                &datetimes,
+                &cumulative,
+                &incremental,
+                revisable_from,
+                LIVE_STATION_ID,
+                LIVE_DATA_SOURCE
+            )
+            .fetch_all(&mut *tx)
+            .await?;
+
+            for row in written {
+                if row.inserted {
+                    result.inserted += 1;
+                } else {
+                    result.updated += 1;
+                }
+                result.changed.push(row.reading_datetime);
+            }
+        }
+
+        tx.commit().await?;
+        result.changed.sort();
+        info!(
+            "Inserted {} new readings, revised {}, {} unchanged",
+            result.inserted,
+            result.updated,
+            readings.len() - result.inserted - result.updated
+        );
+        Ok(result)
+    }
+
+    /// Insert readings for a given station in a transaction, tagged with their source
+    ///
+    /// Unlike `insert_readings` (which stores live scrapes for the default gauge), this
+    /// keeps the station and cumulative values of readings restored from a snapshot.
     #[instrument(skip(self, readings), fields(station_id = %station_id, count = readings.len()))]
-    #[allow(clippy::type_complexity)]
-    pub async fn bulk_insert_historical_readings(
+    pub async fn insert_station_readings(
         &self,
         station_id: &str,
         data_source: &str,
-        readings: &[HistoricalReading],
-    ) -> Result<(usize, usize, Vec<(i32, u32)>), DbError> {
-        debug!(
-            "Bulk inserting {} historical readings for station {} from source {}",
-            readings.len(),
-            station_id,
-            data_source
-        );
-
+        readings: &[RainReading],
+    ) -> Result<usize, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::readings::insert_station_readings(
+                    pool,
+                    station_id,
+                    data_source,
+                    readings,
+                )
+                .await
+            }
+        };
+        let mut tx = pool.begin().await?;
         let mut inserted = 0;
-        let mut duplicates = 0;
-        let mut affected_months = Vec::new();
-
-        for reading in readings {
-            let import_metadata = reading.footnote_marker.as_ref().map(|marker| {
-                serde_json::json!({
-                    "footnote_marker": marker
-                })
-            });
-
-            // Convert NaiveDate to DateTime<Utc> for midnight
-            let reading_datetime =
-                Utc.from_utc_datetime(&reading.reading_date.and_hms_opt(0, 0, 0).unwrap());
 
+        for batch in readings.chunks(INSERT_BATCH_SIZE) {
+            let (datetimes, cumulative, incremental) = reading_columns(batch);
             let result = sqlx::query!(
                 r#"
-                INSERT INTO rain_readings (station_id, reading_datetime, cumulative_inches, incremental_inches, data_source, import_metadata)
-                VALUES ($1, $2, $3, $4, $5, $6)
+                INSERT INTO rain_readings (station_id, reading_datetime, cumulative_inches, incremental_inches, data_source)
+                SELECT $1, t.reading_datetime, t.cumulative_inches, t.incremental_inches, $2
+                FROM UNNEST($3::TIMESTAMPTZ[], $4::FLOAT8[], $5::FLOAT8[])
+                    AS t(reading_datetime, cumulative_inches, incremental_inches)
                 ON CONFLICT (reading_datetime, station_id) DO NOTHING
                 "#,
                 station_id,
-                reading_datetime,
-                0.0, // FOPR files only have incremental, cumulative is calculated separately
-                reading.rainfall_inches,
                 data_source,
-                import_metadata as _
+                &datetimes,
+                &cumulative,
+                &incremental
             )
-            .execute(&self.pool)
+            .execute(&mut *tx)
             .await?;
 
-            if result.rows_affected() > 0 {
-                inserted += 1;
-                let year = reading.reading_date.year();
-                let month = reading.reading_date.month();
-                affected_months.push((year, month));
-            } else {
-                duplicates += 1;
-            }
+            inserted += result.rows_affected() as usize;
         }
 
-        info!(
-            "Bulk insert complete: {} inserted, {} duplicates for station {}",
-            inserted, duplicates, station_id
+        tx.commit().await?;
+        debug!(
+            "Inserted {} of {} readings for station {}",
+            inserted,
+            readings.len(),
+            station_id
         );
+        Ok(inserted)
+    }
 
-        Ok((inserted, duplicates, affected_months))
+    /// Insert historical readings (from FOPR imports, Excel files, etc.) in bulk
+    ///
+    /// This is a data access method - all business logic should be in the service layer.
+    /// Returns (inserted_count, duplicate_count, affected_months) where affected_months
+    /// contains (year, month) tuples for months that had new data inserted. Readings are
+    /// written in multi-row batches in one transaction.
+    #[instrument(skip(self, readings), fields(station_id = %station_id, count = readings.len()))]
+    #[allow(clippy::type_complexity)]
+    pub async fn bulk_insert_historical_readings(
+        &self,
+        station_id: &str,
+        data_source: &str,
+        readings: &[HistoricalReading],
+    ) -> Result<(usize, usize, Vec<(i32, u32)>), DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::readings::bulk_insert_historical_readings(
+                    pool,
+                    station_id,
+                    data_source,
+                    readings,
+                )
+                .await
+            }
+        };
+        let mut tx = pool.begin().await?;
+        let result = self
+            .bulk_insert_historical_readings_tx(&mut tx, station_id, data_source, readings)
+            .await?;
+        tx.commit().await?;
+        Ok(result)
+    }
+
+    /// Set cumulative_inches of a source's readings to their water year running total
+    ///
+    /// Daily totals from FOPR files are inserted with cumulative_inches = 0 because the
+    /// files only carry incremental amounts. This re-derives the whole water year, so
+    /// readings inserted out of order or in later imports end up with the right totals.
+    /// Returns the (year, month) pairs, oldest first, of readings whose value changed;
+    /// their monthly summaries' min/max cumulative values are stale.
+    #[instrument(skip(self))]
+    pub async fn backfill_cumulative(
+        &self,
+        station_id: &str,
+        data_source: &str,
+        water_year: i32,
+    ) -> Result<Vec<(i32, u32)>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::readings::backfill_cumulative(
+                    pool,
+                    station_id,
+                    data_source,
+                    water_year,
+                )
+                .await
+            }
+        };
+        let changed = sqlx::query_scalar!(
+            r#"
+            UPDATE rain_readings r
+            SET cumulative_inches = t.running_total
+            FROM (
+                SELECT id,
+                       ROUND(SUM(incremental_inches) OVER (ORDER BY reading_datetime)::numeric, 2)::double precision
+                           AS running_total
+                FROM rain_readings
+                WHERE station_id = $1 AND data_source = $2 AND water_year = $3
+            ) t
+            WHERE r.id = t.id AND r.cumulative_inches <> t.running_total
+            RETURNING r.reading_datetime
+            "#,
+            station_id,
+            data_source,
+            water_year
+        )
+        .fetch_all(pool)
+        .await?;
+
+        debug!(
+            "Back-filled cumulative totals of {} readings for station {} water year {}",
+            changed.len(),
+            station_id,
+            water_year
+        );
+        Ok(utils::distinct_months(changed))
+    }
+
+    /// When readings in a date range were last inserted or updated (None if there are none)
+    #[instrument(skip(self))]
+    pub async fn find_last_modified(
+        &self,
+        station_id: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Option<DateTime<Utc>>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::readings::find_last_modified(pool, station_id, start, end).await
+            }
+        };
+        let last_modified = sqlx::query_scalar!(
+            r#"
+            SELECT MAX(created_at)
+            FROM rain_readings
+            WHERE station_id = $1 AND reading_datetime >= $2 AND reading_datetime < $3
+            "#,
+            station_id,
+            start,
+            end
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(last_modified)
+    }
+
+    /// First and last UTC days with readings for a gauge (None if it has no readings)
+    #[instrument(skip(self))]
+    pub async fn find_date_extent(
+        &self,
+        station_id: &str,
+    ) -> Result<Option<(NaiveDate, NaiveDate)>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::readings::find_date_extent(pool, station_id).await
+            }
+        };
+        let extent = sqlx::query!(
+            r#"
+            SELECT MIN(reading_datetime) AS first, MAX(reading_datetime) AS last
+            FROM rain_readings
+            WHERE station_id = $1
+            "#,
+            station_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(extent
+            .first
+            .zip(extent.last)
+            .map(|(first, last)| (first.date_naive(), last.date_naive())))
     }
 
     /// Generic query to find readings within a date range for a specific gauge
@@ -134,6 +435,13 @@ impl ReadingRepository {
         start: DateTime<Utc>,
         end: DateTime<Utc>,
     ) -> Result<Vec<Reading>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::readings::find_by_date_range(pool, station_id, start, end).await
+            }
+        };
         debug!(
             "Querying readings for gauge {} from {} to {}",
             station_id, start, end
@@ -142,8 +450,8 @@ impl ReadingRepository {
         let readings = sqlx::query_as!(
             Reading,
             r#"
-            SELECT id, reading_datetime, cumulative_inches as "cumulative_inches!",
-                   incremental_inches as "incremental_inches!", station_id, created_at
+            SELECT id, reading_datetime, cumulative_inches as "cumulative_inches!: Inches",
+                   incremental_inches as "incremental_inches!: Inches", station_id, created_at
             FROM rain_readings
             WHERE station_id = $1 AND reading_datetime >= $2 AND reading_datetime < $3
             ORDER BY reading_datetime DESC
@@ -152,23 +460,230 @@ impl ReadingRepository {
             start,
             end
         )
-        .fetch_all(&self.pool)
+        .fetch_all(pool)
         .await?;
 
         debug!("Found {} readings for gauge {}", readings.len(), station_id);
         Ok(readings)
     }
 
+    /// One page of the readings within a date range, oldest first
+    #[instrument(skip(self))]
+    pub async fn find_page_by_date_range(
+        &self,
+        station_id: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<Reading>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::readings::find_page_by_date_range(
+                    pool, station_id, start, end, offset, limit,
+                )
+                .await
+            }
+        };
+
+        let readings = sqlx::query_as!(
+            Reading,
+            r#"
+            SELECT id, reading_datetime, cumulative_inches as "cumulative_inches!: Inches",
+                   incremental_inches as "incremental_inches!: Inches", station_id, created_at
+            FROM rain_readings
+            WHERE station_id = $1 AND reading_datetime >= $2 AND reading_datetime < $3
+            ORDER BY reading_datetime ASC, id ASC
+            LIMIT $4 OFFSET $5
+            "#,
+            station_id,
+            start,
+            end,
+            limit,
+            offset
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(readings)
+    }
+
+    /// Readings of one water year for a gauge, newest first
+    ///
+    /// Reads the stored `water_year` column on PostgreSQL; SQLite has no such column and
+    /// falls back to the water year's date range.
+    #[instrument(skip(self))]
+    pub async fn find_by_water_year(
+        &self,
+        station_id: &str,
+        water_year: i32,
+    ) -> Result<Vec<Reading>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                let (start, end) = crate::utils::water_year_date_range(water_year);
+                return sqlite::readings::find_by_date_range(pool, station_id, start, end).await;
+            }
+        };
+
+        let readings = sqlx::query_as!(
+            Reading,
+            r#"
+            SELECT id, reading_datetime, cumulative_inches as "cumulative_inches!: Inches",
+                   incremental_inches as "incremental_inches!: Inches", station_id, created_at
+            FROM rain_readings
+            WHERE station_id = $1 AND water_year = $2
+            ORDER BY reading_datetime DESC
+            "#,
+            station_id,
+            water_year
+        )
+        .fetch_all(pool)
+        .await?;
+
+        debug!(
+            "Found {} readings for gauge {} in water year {}",
+            readings.len(),
+            station_id,
+            water_year
+        );
+        Ok(readings)
+    }
+
+    /// When a water year's readings were last inserted or updated (None if there are none)
+    #[instrument(skip(self))]
+    pub async fn find_water_year_last_modified(
+        &self,
+        station_id: &str,
+        water_year: i32,
+    ) -> Result<Option<DateTime<Utc>>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                let (start, end) = crate::utils::water_year_date_range(water_year);
+                return sqlite::readings::find_last_modified(pool, station_id, start, end).await;
+            }
+        };
+        let last_modified = sqlx::query_scalar!(
+            r#"
+            SELECT MAX(created_at)
+            FROM rain_readings
+            WHERE station_id = $1 AND water_year = $2
+            "#,
+            station_id,
+            water_year
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(last_modified)
+    }
+
+    /// Number of readings within a date range, without loading them
+    #[instrument(skip(self))]
+    pub async fn count_by_date_range(
+        &self,
+        station_id: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<i64, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::readings::count_by_date_range(pool, station_id, start, end).await
+            }
+        };
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "count!"
+            FROM rain_readings
+            WHERE station_id = $1 AND reading_datetime >= $2 AND reading_datetime < $3
+            "#,
+            station_id,
+            start,
+            end
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Readings within a date range, oldest first, fetched row by row from a cursor
+    pub fn stream_by_date_range<'a>(
+        &'a self,
+        station_id: &'a str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> BoxStream<'a, Result<Reading, DbError>> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::readings::stream_by_date_range(pool, station_id, start, end)
+            }
+        };
+        sqlx::query_as!(
+            Reading,
+            r#"
+            SELECT id, reading_datetime, cumulative_inches as "cumulative_inches!: Inches",
+                   incremental_inches as "incremental_inches!: Inches", station_id, created_at
+            FROM rain_readings
+            WHERE station_id = $1 AND reading_datetime >= $2 AND reading_datetime < $3
+            ORDER BY reading_datetime ASC
+            "#,
+            station_id,
+            start,
+            end
+        )
+        .fetch(pool)
+        .map_err(DbError::from)
+        .boxed()
+    }
+
+    /// Stream every reading of every gauge with its data source, oldest first
+    ///
+    /// Rows are fetched through a cursor, so the whole table is never held in memory.
+    pub fn stream_all_for_export(&self) -> BoxStream<'_, Result<ExportReading, DbError>> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => return sqlite::readings::stream_all_for_export(pool),
+        };
+        sqlx::query_as!(
+            ExportReading,
+            r#"
+            SELECT station_id, reading_datetime, cumulative_inches::FLOAT8 AS "cumulative_inches!",
+                   incremental_inches::FLOAT8 AS "incremental_inches!", data_source
+            FROM rain_readings
+            ORDER BY reading_datetime ASC, station_id ASC
+            "#
+        )
+        .fetch(pool)
+        .map_err(DbError::from)
+        .boxed()
+    }
+
     /// Find the most recent reading for a specific gauge
     #[instrument(skip(self))]
     pub async fn find_latest(&self, station_id: &str) -> Result<Option<Reading>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => return sqlite::readings::find_latest(pool, station_id).await,
+        };
         debug!("Querying for latest reading for gauge {}", station_id);
 
         let reading = sqlx::query_as!(
             Reading,
             r#"
-            SELECT id, reading_datetime, cumulative_inches as "cumulative_inches!",
-                   incremental_inches as "incremental_inches!", station_id, created_at
+            SELECT id, reading_datetime, cumulative_inches as "cumulative_inches!: Inches",
+                   incremental_inches as "incremental_inches!: Inches", station_id, created_at
             FROM rain_readings
             WHERE station_id = $1
             ORDER BY reading_datetime DESC
@@ -176,7 +691,7 @@ impl ReadingRepository {
             "#,
             station_id
         )
-        .fetch_optional(&self.pool)
+        .fetch_optional(pool)
         .await?;
 
         if reading.is_some() {
@@ -188,6 +703,200 @@ impl ReadingRepository {
         Ok(reading)
     }
 
+    /// Count readings per station-month and data source, oldest month first
+    #[instrument(skip(self))]
+    pub async fn find_coverage(&self, station_id: &str) -> Result<Vec<CoverageRow>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => return sqlite::readings::find_coverage(pool, station_id).await,
+        };
+        let rows = sqlx::query_as!(
+            CoverageRow,
+            r#"
+            SELECT EXTRACT(YEAR FROM reading_datetime AT TIME ZONE 'UTC')::INT AS "year!",
+                   EXTRACT(MONTH FROM reading_datetime AT TIME ZONE 'UTC')::INT AS "month!",
+                   data_source,
+                   COUNT(*) AS "reading_count!",
+                   MIN(reading_datetime) AS "first_reading!",
+                   MAX(reading_datetime) AS "last_reading!"
+            FROM rain_readings
+            WHERE station_id = $1
+            GROUP BY 1, 2, 3
+            ORDER BY 1, 2, 3
+            "#,
+            station_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        debug!(
+            "Found {} coverage rows for gauge {}",
+            rows.len(),
+            station_id
+        );
+        Ok(rows)
+    }
+
+    /// Histogram of daily rainfall totals (UTC days) for a gauge
+    ///
+    /// Returns `(bin_index, day_count)` for non-empty bins, where bin `i` covers
+    /// `[i * bin_width, (i + 1) * bin_width)` inches. Only days with readings count.
+    /// Both bounds are optional; `end` is exclusive.
+    #[instrument(skip(self))]
+    pub async fn daily_total_histogram(
+        &self,
+        station_id: &str,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+        bin_width: f64,
+    ) -> Result<Vec<(i32, i64)>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::readings::daily_total_histogram(
+                    pool, station_id, start, end, bin_width,
+                )
+                .await
+            }
+        };
+        // The epsilon keeps totals like 0.3 out of the 0.2 bin despite float error
+        let rows = sqlx::query!(
+            r#"
+            WITH daily AS (
+                SELECT (reading_datetime AT TIME ZONE 'UTC')::DATE AS day,
+                       SUM(incremental_inches) AS total_inches
+                FROM rain_readings
+                WHERE station_id = $1
+                  AND ($2::TIMESTAMPTZ IS NULL OR reading_datetime >= $2)
+                  AND ($3::TIMESTAMPTZ IS NULL OR reading_datetime < $3)
+                GROUP BY 1
+            )
+            SELECT GREATEST(FLOOR(total_inches / $4 + 1e-9), 0)::INT AS "bin_index!",
+                   COUNT(*) AS "day_count!"
+            FROM daily
+            GROUP BY 1
+            ORDER BY 1
+            "#,
+            station_id,
+            start,
+            end,
+            bin_width
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| (r.bin_index, r.day_count))
+            .collect())
+    }
+
+    /// Daily rainfall totals (UTC days) for every day from `first` through `last`
+    ///
+    /// Days without readings come back as 0, so the result has one row per day in order.
+    #[instrument(skip(self))]
+    pub async fn daily_totals(
+        &self,
+        station_id: &str,
+        first: NaiveDate,
+        last: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, f64)>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::readings::daily_totals(pool, station_id, first, last).await
+            }
+        };
+        let rows = sqlx::query!(
+            r#"
+            WITH daily AS (
+                SELECT (reading_datetime AT TIME ZONE 'UTC')::DATE AS day,
+                       SUM(incremental_inches) AS total_inches
+                FROM rain_readings
+                WHERE station_id = $1
+                  AND reading_datetime >= $2::DATE::TIMESTAMP AT TIME ZONE 'UTC'
+                  AND reading_datetime < ($3::DATE + 1)::TIMESTAMP AT TIME ZONE 'UTC'
+                GROUP BY 1
+            )
+            SELECT days.day::DATE AS "day!",
+                   COALESCE(daily.total_inches, 0) AS "total_inches!"
+            FROM generate_series($2::DATE::TIMESTAMP, $3::DATE::TIMESTAMP, INTERVAL '1 day')
+                AS days(day)
+            LEFT JOIN daily ON daily.day = days.day::DATE
+            ORDER BY 1
+            "#,
+            station_id,
+            first,
+            last
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|r| (r.day, r.total_inches)).collect())
+    }
+
+    /// Rank gauges by total rainfall from raw readings in `[start, end)`
+    ///
+    /// Only gauges listed in gauge_summaries with readings in range are ranked, and
+    /// only Active ones unless `include_inactive` is set.
+    #[instrument(skip(self))]
+    pub async fn rank_stations_by_rainfall(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        driest_first: bool,
+        include_inactive: bool,
+        limit: i64,
+    ) -> Result<Vec<RankingRow>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::readings::rank_stations_by_rainfall(
+                    pool,
+                    start,
+                    end,
+                    driest_first,
+                    include_inactive,
+                    limit,
+                )
+                .await
+            }
+        };
+        let rows = sqlx::query_as!(
+            RankingRow,
+            r#"
+            SELECT r.station_id, g.gauge_name, g.city_town,
+                   COALESCE(gs.forecast_zone, g.msp_forecast_zone) AS msp_forecast_zone, g.general_location,
+                   SUM(r.incremental_inches) AS "rainfall_inches!",
+                   COUNT(*) AS "reading_count!"
+            FROM rain_readings r
+            JOIN gauge_summaries g ON g.station_id = r.station_id
+            LEFT JOIN gauges gs ON gs.station_id = r.station_id
+            WHERE r.reading_datetime >= $1 AND r.reading_datetime < $2
+              AND ($5 OR COALESCE(gs.status, 'Active') = 'Active')
+            GROUP BY r.station_id, g.gauge_name, g.city_town, g.msp_forecast_zone, g.general_location,
+                     gs.forecast_zone
+            ORDER BY CASE WHEN $3 THEN SUM(r.incremental_inches) END ASC,
+                     CASE WHEN NOT $3 THEN SUM(r.incremental_inches) END DESC,
+                     r.station_id
+            LIMIT $4
+            "#,
+            start,
+            end,
+            driest_first,
+            limit,
+            include_inactive
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows)
+    }
+
     // ============================================================
     // Transaction-aware methods for testing
     // ============================================================
@@ -210,44 +919,45 @@ impl ReadingRepository {
         );
 
         let mut inserted = 0;
-        let mut duplicates = 0;
         let mut affected_months = Vec::new();
 
-        for reading in readings {
-            let import_metadata = reading.footnote_marker.as_ref().map(|marker| {
-                serde_json::json!({
-                    "footnote_marker": marker
-                })
-            });
-
-            let reading_datetime =
-                Utc.from_utc_datetime(&reading.reading_date.and_hms_opt(0, 0, 0).unwrap());
-
-            let result = sqlx::query!(
+        for batch in readings.chunks(INSERT_BATCH_SIZE) {
+            let (datetimes, incremental, footnotes) = historical_columns(batch);
+            // DO NOTHING returns only the rows it inserted
+            let inserted_datetimes = sqlx::query_scalar!(
                 r#"
                 INSERT INTO rain_readings (station_id, reading_datetime, cumulative_inches, incremental_inches, data_source, import_metadata)
-                VALUES ($1, $2, $3, $4, $5, $6)
+                SELECT $1, t.reading_datetime, 0.0, t.incremental_inches, $2,
+                       CASE WHEN t.footnote_marker IS NOT NULL
+                            THEN jsonb_build_object('footnote_marker', t.footnote_marker) END
+                FROM UNNEST($3::TIMESTAMPTZ[], $4::FLOAT8[], $5::TEXT[])
+                    AS t(reading_datetime, incremental_inches, footnote_marker)
                 ON CONFLICT (reading_datetime, station_id) DO NOTHING
+                RETURNING reading_datetime
                 "#,
                 station_id,
-                reading_datetime,
-                0.0,
-                reading.rainfall_inches,
                 data_source,
-                import_metadata as _
+                &datetimes,
+                &incremental,
+                &footnotes as _
             )
-            .execute(&mut **tx)
+            .fetch_all(&mut **tx)
             .await?;
 
-            if result.rows_affected() > 0 {
-                inserted += 1;
-                let year = reading.reading_date.year();
-                let month = reading.reading_date.month();
-                affected_months.push((year, month));
-            } else {
-                duplicates += 1;
-            }
+            debug!(
+                "Inserted {} of {} readings in batch for station {}",
+                inserted_datetimes.len(),
+                batch.len(),
+                station_id
+            );
+            inserted += inserted_datetimes.len();
+            affected_months.extend(
+                inserted_datetimes
+                    .iter()
+                    .map(|datetime| (datetime.year(), datetime.month())),
+            );
         }
+        let duplicates = readings.len() - inserted;
 
         info!(
             "Bulk insert complete: {} inserted, {} duplicates for station {}",
@@ -257,6 +967,130 @@ impl ReadingRepository {
         Ok((inserted, duplicates, affected_months))
     }
 
+    /// Insert historical readings, overwriting stored ones that differ, using a transaction
+    ///
+    /// Each overwrite records the replaced values in `reading_revisions` and bumps the
+    /// reading's created_at so cached responses for its range are invalidated. Readings
+    /// from the live scraper are never overwritten by an import. Readings are written in
+    /// multi-row batches; when a day appears twice, the last reading wins.
+    #[instrument(skip(self, tx, readings), fields(station_id = %station_id, count = readings.len()))]
+    pub async fn upsert_historical_readings_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        station_id: &str,
+        data_source: &str,
+        readings: &[HistoricalReading],
+    ) -> Result<HistoricalUpsert, DbError> {
+        // One statement cannot update a row twice
+        let by_date: BTreeMap<NaiveDate, HistoricalReading> = readings
+            .iter()
+            .map(|r| (r.reading_date, r.clone()))
+            .collect();
+        // Earlier readings of a repeated day count as unchanged
+        let mut result = HistoricalUpsert {
+            unchanged: readings.len() - by_date.len(),
+            ..Default::default()
+        };
+        let readings: Vec<HistoricalReading> = by_date.into_values().collect();
+
+        for batch in readings.chunks(INSERT_BATCH_SIZE) {
+            let (datetimes, incremental, footnotes) = historical_columns(batch);
+            // xmax is 0 only for rows this statement inserted; rows left alone by the
+            // DO UPDATE condition are not returned
+            let written = sqlx::query_scalar!(
+                r#"
+                WITH incoming AS (
+                    SELECT t.reading_datetime, t.incremental_inches,
+                           CASE WHEN t.footnote_marker IS NOT NULL
+                                THEN jsonb_build_object('footnote_marker', t.footnote_marker) END
+                               AS import_metadata
+                    FROM UNNEST($3::TIMESTAMPTZ[], $4::FLOAT8[], $5::TEXT[])
+                        AS t(reading_datetime, incremental_inches, footnote_marker)
+                ),
+                revisions AS (
+                    INSERT INTO reading_revisions
+                        (reading_id, station_id, reading_datetime, previous_incremental_inches,
+                         previous_cumulative_inches, previous_data_source, previous_import_metadata,
+                         new_incremental_inches, new_data_source)
+                    SELECT r.id, r.station_id, r.reading_datetime, r.incremental_inches,
+                           r.cumulative_inches, r.data_source, r.import_metadata,
+                           i.incremental_inches, $2
+                    FROM rain_readings r
+                    JOIN incoming i ON i.reading_datetime = r.reading_datetime
+                    WHERE r.station_id = $1
+                      AND r.data_source IS DISTINCT FROM $6
+                      AND (r.incremental_inches <> i.incremental_inches
+                           OR r.import_metadata IS DISTINCT FROM i.import_metadata)
+                )
+                INSERT INTO rain_readings (station_id, reading_datetime, cumulative_inches, incremental_inches, data_source, import_metadata)
+                SELECT $1, reading_datetime, 0.0, incremental_inches, $2, import_metadata
+                FROM incoming
+                ON CONFLICT (reading_datetime, station_id) DO UPDATE
+                SET incremental_inches = EXCLUDED.incremental_inches,
+                    data_source = EXCLUDED.data_source,
+                    import_metadata = EXCLUDED.import_metadata,
+                    created_at = NOW()
+                WHERE rain_readings.data_source IS DISTINCT FROM $6
+                  AND (rain_readings.incremental_inches <> EXCLUDED.incremental_inches
+                       OR rain_readings.import_metadata IS DISTINCT FROM EXCLUDED.import_metadata)
+                RETURNING (xmax = 0) AS "inserted!"
+                "#,
+                station_id,
+                data_source,
+                &datetimes,
+                &incremental,
+                &footnotes as _,
+                LIVE_DATA_SOURCE
+            )
+            .fetch_all(&mut **tx)
+            .await?;
+
+            let inserted = written.iter().filter(|inserted| **inserted).count();
+            result.inserted += inserted;
+            result.updated += written.len() - inserted;
+            result.unchanged += batch.len() - written.len();
+        }
+
+        info!(
+            "Upsert complete: {} inserted, {} updated, {} unchanged for station {}",
+            result.inserted, result.updated, result.unchanged, station_id
+        );
+        Ok(result)
+    }
+
+    /// Back-fill water year cumulative totals using a transaction
+    #[instrument(skip(self, tx))]
+    pub async fn backfill_cumulative_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        station_id: &str,
+        data_source: &str,
+        water_year: i32,
+    ) -> Result<Vec<(i32, u32)>, DbError> {
+        let changed = sqlx::query_scalar!(
+            r#"
+            UPDATE rain_readings r
+            SET cumulative_inches = t.running_total
+            FROM (
+                SELECT id,
+                       ROUND(SUM(incremental_inches) OVER (ORDER BY reading_datetime)::numeric, 2)::double precision
+                           AS running_total
+                FROM rain_readings
+                WHERE station_id = $1 AND data_source = $2 AND water_year = $3
+            ) t
+            WHERE r.id = t.id AND r.cumulative_inches <> t.running_total
+            RETURNING r.reading_datetime
+            "#,
+            station_id,
+            data_source,
+            water_year
+        )
+        .fetch_all(&mut **tx)
+        .await?;
+
+        Ok(utils::distinct_months(changed))
+    }
+
     /// Find readings by date range using a transaction (for testing)
     #[instrument(skip(self, tx))]
     pub async fn find_by_date_range_tx(
@@ -274,8 +1108,8 @@ impl ReadingRepository {
         let readings = sqlx::query_as!(
             Reading,
             r#"
-            SELECT id, reading_datetime, cumulative_inches as "cumulative_inches!",
-                   incremental_inches as "incremental_inches!", station_id, created_at
+            SELECT id, reading_datetime, cumulative_inches as "cumulative_inches!: Inches",
+                   incremental_inches as "incremental_inches!: Inches", station_id, created_at
             FROM rain_readings
             WHERE station_id = $1 AND reading_datetime >= $2 AND reading_datetime < $3
             ORDER BY reading_datetime DESC
@@ -303,8 +1137,8 @@ impl ReadingRepository {
         let reading = sqlx::query_as!(
             Reading,
             r#"
-            SELECT id, reading_datetime, cumulative_inches as "cumulative_inches!",
-                   incremental_inches as "incremental_inches!", station_id, created_at
+            SELECT id, reading_datetime, cumulative_inches as "cumulative_inches!: Inches",
+                   incremental_inches as "incremental_inches!: Inches", station_id, created_at
             FROM rain_readings
             WHERE station_id = $1
             ORDER BY reading_datetime DESC
@@ -0,0 +1,75 @@
+use chrono::{DateTime, Utc};
+use tracing::instrument;
+
+use crate::db::{DbError, DbPool, RankingPeriod};
+
+/// Frozen results behind share links; PostgreSQL only
+#[derive(Clone)]
+pub struct ShareRepository {
+    db: DbPool,
+}
+
+impl ShareRepository {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        Self { db: pool.into() }
+    }
+
+    /// Store a snapshot for `user_id`'s share link; returns its ID
+    #[instrument(skip(self, station_ids, snapshot))]
+    pub async fn insert(
+        &self,
+        user_id: i64,
+        station_ids: &[String],
+        period: RankingPeriod,
+        snapshot: &serde_json::Value,
+        expires_at: DateTime<Utc>,
+    ) -> Result<i64, DbError> {
+        let id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO shared_snapshots (user_id, station_ids, period, snapshot, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id
+            "#,
+            user_id,
+            station_ids,
+            period.as_str(),
+            snapshot,
+            expires_at
+        )
+        .fetch_one(self.db.postgres()?)
+        .await?;
+
+        Ok(id)
+    }
+
+    /// A snapshot that has not expired by `now`
+    pub async fn find(
+        &self,
+        id: i64,
+        now: DateTime<Utc>,
+    ) -> Result<Option<serde_json::Value>, DbError> {
+        let snapshot = sqlx::query_scalar!(
+            r#"
+            SELECT snapshot
+            FROM shared_snapshots
+            WHERE id = $1 AND expires_at > $2
+            "#,
+            id,
+            now
+        )
+        .fetch_optional(self.db.postgres()?)
+        .await?;
+
+        Ok(snapshot)
+    }
+
+    /// Delete snapshots that expired by `now`; returns how many
+    #[instrument(skip(self))]
+    pub async fn delete_expired(&self, now: DateTime<Utc>) -> Result<u64, DbError> {
+        let result = sqlx::query!("DELETE FROM shared_snapshots WHERE expires_at <= $1", now)
+            .execute(self.db.postgres()?)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
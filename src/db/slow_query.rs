@@ -0,0 +1,209 @@
+// Slow query logging
+//
+// Repositories run the queries worth tuning (the monthly summary reads) through
+// SlowQueryLog::observe. One that takes longer than SLOW_QUERY_THRESHOLD_MS is logged
+// with its SQL and parameter count; bound values are never logged, since the SQL only
+// carries `$n` placeholders. With SLOW_QUERY_EXPLAIN=true the query is also re-run in the
+// background under EXPLAIN (ANALYZE, BUFFERS, FORMAT JSON), inside a transaction that is
+// rolled back, and the plan is stored in slow_query_captures for
+// GET /api/v1/admin/slow-queries. Each query is captured at most once per
+// CAPTURE_COOLDOWN so an already slow query isn't run twice on every request.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use sqlx::postgres::PgArguments;
+use sqlx::{Arguments, Execute, PgPool, Postgres};
+use tracing::{debug, error, warn};
+
+use crate::db::slow_query_repository::{NewSlowQueryCapture, SlowQueryRepository};
+
+/// Minimum time between two EXPLAIN captures of the same query
+pub const CAPTURE_COOLDOWN: Duration = Duration::from_secs(10 * 60);
+
+/// Longest an EXPLAIN ANALYZE re-run may take
+const EXPLAIN_TIMEOUT: &str = "60s";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlowQueryConfig {
+    /// Log watched queries taking at least this long (SLOW_QUERY_THRESHOLD_MS); 0 logs all
+    pub threshold_ms: u64,
+    /// Store EXPLAIN ANALYZE plans of slow queries (SLOW_QUERY_EXPLAIN)
+    pub capture_explain: bool,
+}
+
+impl Default for SlowQueryConfig {
+    fn default() -> Self {
+        Self {
+            threshold_ms: 500,
+            capture_explain: false,
+        }
+    }
+}
+
+/// Times watched queries; cloned into each repository that uses it
+#[derive(Debug, Clone, Default)]
+pub struct SlowQueryLog {
+    config: SlowQueryConfig,
+    last_capture: Arc<Mutex<HashMap<&'static str, Instant>>>,
+}
+
+impl SlowQueryLog {
+    pub fn new(config: SlowQueryConfig) -> Self {
+        Self {
+            config,
+            last_capture: Arc::default(),
+        }
+    }
+
+    /// Run `build()` through `run`, logging (and maybe capturing) it when slow
+    ///
+    /// `build` is called again to recover the SQL and arguments after a slow run, so pass
+    /// a closure around the `query!` macro rather than a built query.
+    pub async fn observe<'q, Q, F, Fut, T>(
+        &self,
+        name: &'static str,
+        pool: &PgPool,
+        build: impl Fn() -> Q,
+        run: F,
+    ) -> Result<T, sqlx::Error>
+    where
+        Q: Execute<'q, Postgres>,
+        F: FnOnce(Q) -> Fut,
+        Fut: Future<Output = Result<T, sqlx::Error>>,
+    {
+        let started = Instant::now();
+        let result = run(build()).await;
+        let elapsed = started.elapsed();
+        if elapsed < Duration::from_millis(self.config.threshold_ms) {
+            return result;
+        }
+
+        let mut query = build();
+        let sql = query.sql();
+        let arguments = query.take_arguments().ok().flatten();
+        let param_count = arguments.as_ref().map_or(0, |a| a.len());
+        let duration_ms = elapsed.as_secs_f64() * 1000.0;
+        warn!(
+            query = name,
+            duration_ms,
+            param_count,
+            sql = %collapse_whitespace(sql),
+            "Slow query"
+        );
+
+        if self.config.capture_explain && self.claim_capture(name) {
+            let capture = Capture {
+                name,
+                sql: sql.to_string(),
+                arguments: arguments.unwrap_or_default(),
+                param_count,
+                duration_ms,
+            };
+            tokio::spawn(capture.run(pool.clone()));
+        }
+
+        result
+    }
+
+    /// Whether `name` is due for a capture; records the attempt if so
+    fn claim_capture(&self, name: &'static str) -> bool {
+        let mut last = self.last_capture.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        match last.get(name) {
+            Some(at) if now.duration_since(*at) < CAPTURE_COOLDOWN => false,
+            _ => {
+                last.insert(name, now);
+                true
+            }
+        }
+    }
+}
+
+struct Capture {
+    name: &'static str,
+    sql: String,
+    arguments: PgArguments,
+    param_count: usize,
+    duration_ms: f64,
+}
+
+impl Capture {
+    async fn run(self, pool: PgPool) {
+        let plan = explain_analyze(&pool, &self.sql, self.arguments)
+            .await
+            .map_err(|e| e.to_string());
+        if let Err(e) = &plan {
+            warn!("EXPLAIN of slow query {} failed: {}", self.name, e);
+        }
+
+        let capture = NewSlowQueryCapture {
+            query_name: self.name.to_string(),
+            sql: self.sql,
+            param_count: self.param_count as i32,
+            duration_ms: self.duration_ms,
+            plan,
+        };
+        match SlowQueryRepository::new(pool).insert(&capture).await {
+            Ok(id) => debug!("Stored slow query capture {} for {}", id, self.name),
+            Err(e) => error!(
+                "Failed to store slow query capture for {}: {}",
+                self.name, e
+            ),
+        }
+    }
+}
+
+/// EXPLAIN ANALYZE executes the statement, so run it in a transaction that is rolled back
+async fn explain_analyze(
+    pool: &PgPool,
+    sql: &str,
+    arguments: PgArguments,
+) -> Result<serde_json::Value, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    sqlx::query(&format!(
+        "SET LOCAL statement_timeout = '{EXPLAIN_TIMEOUT}'"
+    ))
+    .execute(&mut *tx)
+    .await?;
+    let explain = format!("EXPLAIN (ANALYZE, BUFFERS, FORMAT JSON) {sql}");
+    let plan = sqlx::query_scalar_with::<_, serde_json::Value, _>(&explain, arguments)
+        .persistent(false)
+        .fetch_one(&mut *tx)
+        .await;
+    tx.rollback().await?;
+    plan
+}
+
+/// Single-line SQL for log output
+fn collapse_whitespace(sql: &str) -> String {
+    sql.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collapse_whitespace() {
+        assert_eq!(
+            collapse_whitespace("\n  SELECT id\n  FROM t\n  WHERE a = $1\n"),
+            "SELECT id FROM t WHERE a = $1"
+        );
+    }
+
+    #[test]
+    fn test_capture_cooldown_per_query() {
+        let log = SlowQueryLog::new(SlowQueryConfig {
+            threshold_ms: 0,
+            capture_explain: true,
+        });
+        assert!(log.claim_capture("a"));
+        assert!(!log.claim_capture("a"));
+        assert!(log.claim_capture("b"));
+        // Clones share the cooldown
+        assert!(!log.clone().claim_capture("b"));
+    }
+}
@@ -0,0 +1,70 @@
+use tracing::instrument;
+
+use crate::db::{DbError, DbPool, SlowQueryCapture};
+
+/// A slow query and its EXPLAIN outcome, before it is stored
+#[derive(Debug, Clone)]
+pub struct NewSlowQueryCapture {
+    pub query_name: String,
+    pub sql: String,
+    pub param_count: i32,
+    pub duration_ms: f64,
+    pub plan: Result<serde_json::Value, String>,
+}
+
+/// Stored EXPLAIN captures; PostgreSQL only, like the queries they describe
+#[derive(Clone)]
+pub struct SlowQueryRepository {
+    db: DbPool,
+}
+
+impl SlowQueryRepository {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        Self { db: pool.into() }
+    }
+
+    #[instrument(skip(self, capture), fields(query_name = %capture.query_name))]
+    pub async fn insert(&self, capture: &NewSlowQueryCapture) -> Result<i64, DbError> {
+        let (plan, explain_error) = match &capture.plan {
+            Ok(plan) => (Some(plan), None),
+            Err(e) => (None, Some(e.as_str())),
+        };
+        let id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO slow_query_captures
+                (query_name, sql, param_count, duration_ms, plan, explain_error)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id
+            "#,
+            capture.query_name,
+            capture.sql,
+            capture.param_count,
+            capture.duration_ms,
+            plan,
+            explain_error
+        )
+        .fetch_one(self.db.postgres()?)
+        .await?;
+
+        Ok(id)
+    }
+
+    /// Most recent captures first
+    #[instrument(skip(self))]
+    pub async fn find_recent(&self, limit: i64) -> Result<Vec<SlowQueryCapture>, DbError> {
+        let captures = sqlx::query_as!(
+            SlowQueryCapture,
+            r#"
+            SELECT id, query_name, sql, param_count, duration_ms, plan, explain_error, captured_at
+            FROM slow_query_captures
+            ORDER BY captured_at DESC, id DESC
+            LIMIT $1
+            "#,
+            limit
+        )
+        .fetch_all(self.db.postgres()?)
+        .await?;
+
+        Ok(captures)
+    }
+}
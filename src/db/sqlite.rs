@@ -0,0 +1,28 @@
+// SQLite versions of the repository queries (`sqlite` feature)
+//
+// One module per repository, with a free function per method taking the pool followed by
+// the method's arguments; the repositories dispatch to these when holding a SQLite pool.
+// The schema is in migrations/sqlite. Timestamps are stored as the RFC 3339 text sqlx
+// writes for DateTime<Utc>, so they order and compare correctly as strings. "Now" is bound
+// from Rust rather than taken from SQLite's clock so every timestamp has that format.
+
+pub mod annotations;
+pub mod attachments;
+pub mod backup;
+pub mod current_conditions;
+pub mod forecast_zones;
+pub mod gauges;
+pub mod hourly_rainfall;
+pub mod idempotency;
+pub mod import_chunks;
+pub mod monthly_rainfall;
+pub mod quarantine;
+pub mod readings;
+pub mod threshold_events;
+
+use serde::Serialize;
+
+/// JSON array bound for `IN (SELECT value FROM json_each($n))`, SQLite's `= ANY($n)`
+fn json_list<T: Serialize>(values: &[T]) -> String {
+    serde_json::to_string(values).expect("a slice of strings or numbers serializes")
+}
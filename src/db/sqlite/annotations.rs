@@ -0,0 +1,92 @@
+use chrono::{NaiveDate, Utc};
+use sqlx::SqlitePool;
+
+use crate::db::{DbError, GaugeAnnotation};
+
+pub async fn gauge_exists(pool: &SqlitePool, station_id: &str) -> Result<bool, DbError> {
+    super::gauges::gauge_exists(pool, station_id).await
+}
+
+pub async fn insert(
+    pool: &SqlitePool,
+    station_id: &str,
+    start_date: Option<NaiveDate>,
+    end_date: Option<NaiveDate>,
+    note: &str,
+    author: &str,
+) -> Result<Option<GaugeAnnotation>, DbError> {
+    let inserted = sqlx::query_as(
+        r#"
+        INSERT INTO gauge_annotations (station_id, start_date, end_date, note, author, created_at)
+        SELECT g.station_id, $2, $3, $4, $5, $6
+        FROM gauges g
+        WHERE g.station_id = $1
+        RETURNING id, station_id, start_date, end_date, note, author, created_at
+        "#,
+    )
+    .bind(station_id)
+    .bind(start_date)
+    .bind(end_date)
+    .bind(note)
+    .bind(author)
+    .bind(Utc::now())
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(inserted)
+}
+
+pub async fn find_by_station(
+    pool: &SqlitePool,
+    station_id: &str,
+) -> Result<Vec<GaugeAnnotation>, DbError> {
+    let annotations = sqlx::query_as(
+        r#"
+        SELECT id, station_id, start_date, end_date, note, author, created_at
+        FROM gauge_annotations
+        WHERE station_id = $1
+        ORDER BY created_at DESC, id DESC
+        "#,
+    )
+    .bind(station_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(annotations)
+}
+
+pub async fn find_overlapping(
+    pool: &SqlitePool,
+    station_id: &str,
+    first: NaiveDate,
+    last: NaiveDate,
+) -> Result<Vec<GaugeAnnotation>, DbError> {
+    let annotations = sqlx::query_as(
+        r#"
+        SELECT id, station_id, start_date, end_date, note, author, created_at
+        FROM gauge_annotations
+        WHERE station_id = $1
+          AND (start_date IS NULL
+               OR (start_date <= $3 AND (end_date IS NULL OR end_date >= $2)))
+        ORDER BY start_date NULLS FIRST, id
+        "#,
+    )
+    .bind(station_id)
+    .bind(first)
+    .bind(last)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(annotations)
+}
+
+pub async fn delete(pool: &SqlitePool, station_id: &str, id: i64) -> Result<bool, DbError> {
+    let deleted = sqlx::query("DELETE FROM gauge_annotations WHERE station_id = $1 AND id = $2")
+        .bind(station_id)
+        .bind(id)
+        .execute(pool)
+        .await?
+        .rows_affected();
+
+    Ok(deleted > 0)
+}
@@ -0,0 +1,80 @@
+use chrono::Utc;
+use sqlx::SqlitePool;
+
+use crate::db::attachment_repository::NewGaugeAttachment;
+use crate::db::{DbError, GaugeAttachment};
+
+pub async fn gauge_exists(pool: &SqlitePool, station_id: &str) -> Result<bool, DbError> {
+    super::gauges::gauge_exists(pool, station_id).await
+}
+
+pub async fn insert(
+    pool: &SqlitePool,
+    attachment: &NewGaugeAttachment<'_>,
+) -> Result<Option<GaugeAttachment>, DbError> {
+    let inserted = sqlx::query_as(
+        r#"
+        INSERT INTO gauge_attachments
+            (station_id, filename, content_type, size_bytes, sha256, storage_key, description,
+             uploaded_at)
+        SELECT g.station_id, $2, $3, $4, $5, $6, $7, $8
+        FROM gauges g
+        WHERE g.station_id = $1
+        RETURNING id, station_id, filename, content_type, size_bytes, sha256,
+                  storage_key, description, uploaded_at
+        "#,
+    )
+    .bind(attachment.station_id)
+    .bind(attachment.filename)
+    .bind(attachment.content_type)
+    .bind(attachment.size_bytes)
+    .bind(attachment.sha256)
+    .bind(attachment.storage_key)
+    .bind(attachment.description)
+    .bind(Utc::now())
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(inserted)
+}
+
+pub async fn find_by_station(
+    pool: &SqlitePool,
+    station_id: &str,
+) -> Result<Vec<GaugeAttachment>, DbError> {
+    let attachments = sqlx::query_as(
+        r#"
+        SELECT id, station_id, filename, content_type, size_bytes, sha256,
+               storage_key, description, uploaded_at
+        FROM gauge_attachments
+        WHERE station_id = $1
+        ORDER BY uploaded_at DESC, id DESC
+        "#,
+    )
+    .bind(station_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(attachments)
+}
+
+pub async fn find(
+    pool: &SqlitePool,
+    station_id: &str,
+    id: i64,
+) -> Result<Option<GaugeAttachment>, DbError> {
+    let attachment = sqlx::query_as(
+        r#"
+        SELECT id, station_id, filename, content_type, size_bytes, sha256,
+               storage_key, description, uploaded_at
+        FROM gauge_attachments
+        WHERE station_id = $1 AND id = $2
+        "#,
+    )
+    .bind(station_id)
+    .bind(id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(attachment)
+}
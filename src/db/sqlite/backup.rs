@@ -0,0 +1,63 @@
+use sqlx::SqlitePool;
+
+use crate::db::backup_repository::BackupTable;
+use crate::db::DbError;
+
+pub async fn schema_version(pool: &SqlitePool) -> Result<Option<i64>, DbError> {
+    let version = sqlx::query_scalar("SELECT MAX(version) FROM _sqlx_migrations WHERE success")
+        .fetch_one(pool)
+        .await?;
+
+    Ok(version)
+}
+
+pub async fn count_rows(pool: &SqlitePool, table: &BackupTable) -> Result<i64, DbError> {
+    let count = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table.name))
+        .fetch_one(pool)
+        .await?;
+
+    Ok(count)
+}
+
+/// One JSON object per row; timestamps, booleans, and JSON text are normalized by the caller
+pub fn export_sql(table: &BackupTable) -> String {
+    let fields = table
+        .columns
+        .iter()
+        .map(|(name, _)| format!("'{name}', {name}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!(
+        "SELECT json_object({fields}) FROM {} ORDER BY {}",
+        table.name, table.order_by
+    )
+}
+
+/// Insert rows from a JSON array; arrays and objects are stored as JSON text, booleans as 1/0
+pub async fn insert_rows(
+    pool: &SqlitePool,
+    table: &BackupTable,
+    json: &str,
+) -> Result<u64, DbError> {
+    let values = table
+        .columns
+        .iter()
+        .map(|(name, _)| format!("json_extract(value, '$.{name}')"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    // `WHERE true` keeps SQLite from reading ON CONFLICT as part of the join
+    let sql = format!(
+        "INSERT INTO {} ({}) SELECT {values} FROM json_each($1) WHERE true ON CONFLICT DO NOTHING",
+        table.name,
+        table.column_list()
+    );
+    let inserted = sqlx::query(&sql)
+        .bind(json)
+        .execute(pool)
+        .await?
+        .rows_affected();
+
+    Ok(inserted)
+}
@@ -0,0 +1,115 @@
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+
+use super::json_list;
+use crate::db::{CurrentCondition, DbError};
+
+pub async fn refresh(
+    pool: &SqlitePool,
+    water_year: i32,
+    refreshed_at: DateTime<Utc>,
+) -> Result<u64, DbError> {
+    let mut tx = pool.begin().await?;
+
+    // `WHERE true` keeps SQLite from reading ON CONFLICT as part of the join
+    let written = sqlx::query(
+        r#"
+        INSERT INTO current_conditions (
+            station_id, gauge_name, city_town, status, latitude, longitude, elevation_ft,
+            rainfall_past_6h_inches, rainfall_past_24h_inches,
+            latest_reading_at, latest_cumulative_inches,
+            water_year, water_year_to_date_inches, last_scraped_at, refreshed_at
+        )
+        SELECT s.station_id,
+               s.gauge_name,
+               COALESCE(s.city_town, g.city),
+               COALESCE(g.status, 'Active'),
+               g.latitude,
+               g.longitude,
+               COALESCE(s.elevation_ft, g.elevation_ft),
+               s.rainfall_past_6h_inches,
+               s.rainfall_past_24h_inches,
+               (SELECT MAX(reading_datetime) FROM rain_readings WHERE station_id = s.station_id),
+               (SELECT cumulative_inches FROM rain_readings
+                WHERE station_id = s.station_id
+                ORDER BY reading_datetime DESC
+                LIMIT 1),
+               $1,
+               (SELECT TOTAL(total_rainfall_inches) FROM monthly_rainfall_summary
+                WHERE station_id = s.station_id
+                  AND ((year = $1 - 1 AND month >= 10) OR (year = $1 AND month <= 9))),
+               s.last_scraped_at,
+               $2
+        FROM gauge_summaries s
+        LEFT JOIN gauges g ON g.station_id = s.station_id
+        WHERE true
+        ON CONFLICT (station_id) DO UPDATE SET
+            gauge_name = excluded.gauge_name,
+            city_town = excluded.city_town,
+            status = excluded.status,
+            latitude = excluded.latitude,
+            longitude = excluded.longitude,
+            elevation_ft = excluded.elevation_ft,
+            rainfall_past_6h_inches = excluded.rainfall_past_6h_inches,
+            rainfall_past_24h_inches = excluded.rainfall_past_24h_inches,
+            latest_reading_at = excluded.latest_reading_at,
+            latest_cumulative_inches = excluded.latest_cumulative_inches,
+            water_year = excluded.water_year,
+            water_year_to_date_inches = excluded.water_year_to_date_inches,
+            last_scraped_at = excluded.last_scraped_at,
+            refreshed_at = excluded.refreshed_at
+        "#,
+    )
+    .bind(water_year)
+    .bind(refreshed_at)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    sqlx::query("DELETE FROM current_conditions WHERE refreshed_at <> $1")
+        .bind(refreshed_at)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(written)
+}
+
+pub async fn find_refreshed_at(
+    pool: &SqlitePool,
+    statuses: &[String],
+) -> Result<Option<DateTime<Utc>>, DbError> {
+    let refreshed_at = sqlx::query_scalar(
+        r#"
+        SELECT MIN(refreshed_at) FROM current_conditions
+        WHERE status IN (SELECT value FROM json_each($1))
+        "#,
+    )
+    .bind(json_list(statuses))
+    .fetch_one(pool)
+    .await?;
+
+    Ok(refreshed_at)
+}
+
+pub async fn find_by_status(
+    pool: &SqlitePool,
+    statuses: &[String],
+) -> Result<Vec<CurrentCondition>, DbError> {
+    let rows = sqlx::query_as(
+        r#"
+        SELECT station_id, gauge_name, city_town, status, latitude, longitude, elevation_ft,
+               rainfall_past_6h_inches, rainfall_past_24h_inches,
+               latest_reading_at, latest_cumulative_inches,
+               water_year, water_year_to_date_inches, last_scraped_at, refreshed_at
+        FROM current_conditions
+        WHERE status IN (SELECT value FROM json_each($1))
+        ORDER BY city_town, gauge_name
+        "#,
+    )
+    .bind(json_list(statuses))
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
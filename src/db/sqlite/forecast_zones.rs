@@ -0,0 +1,122 @@
+use chrono::Utc;
+use sqlx::{Row, SqlitePool};
+
+use super::json_list;
+use crate::db::{DbError, ForecastZoneRecord, GaugeZoneLocation};
+use crate::zones::ZonePolygon;
+
+pub async fn replace_all(
+    pool: &SqlitePool,
+    zones: &[ZonePolygon],
+    source: &str,
+) -> Result<u64, DbError> {
+    let now = Utc::now();
+    let mut tx = pool.begin().await?;
+
+    for zone in zones {
+        sqlx::query(
+            r#"
+            INSERT INTO forecast_zones
+                (zone_id, name, geometry, min_latitude, max_latitude, min_longitude,
+                 max_longitude, source, imported_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (zone_id) DO UPDATE SET
+                name = excluded.name,
+                geometry = excluded.geometry,
+                min_latitude = excluded.min_latitude,
+                max_latitude = excluded.max_latitude,
+                min_longitude = excluded.min_longitude,
+                max_longitude = excluded.max_longitude,
+                source = excluded.source,
+                imported_at = excluded.imported_at
+            "#,
+        )
+        .bind(&zone.zone_id)
+        .bind(&zone.name)
+        .bind(zone.geometry.to_string())
+        .bind(zone.bounds.min_latitude)
+        .bind(zone.bounds.max_latitude)
+        .bind(zone.bounds.min_longitude)
+        .bind(zone.bounds.max_longitude)
+        .bind(source)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    let zone_ids: Vec<&str> = zones.iter().map(|zone| zone.zone_id.as_str()).collect();
+    let deleted = sqlx::query(
+        "DELETE FROM forecast_zones WHERE zone_id NOT IN (SELECT value FROM json_each($1))",
+    )
+    .bind(json_list(&zone_ids))
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    tx.commit().await?;
+    Ok(deleted)
+}
+
+pub async fn find_all(pool: &SqlitePool) -> Result<Vec<ForecastZoneRecord>, DbError> {
+    let rows = sqlx::query(
+        r#"
+        SELECT z.zone_id, z.name, z.geometry, z.source, z.imported_at,
+               COUNT(g.station_id) AS gauge_count
+        FROM forecast_zones z
+        LEFT JOIN gauges g ON g.forecast_zone = z.zone_id
+        GROUP BY z.zone_id
+        ORDER BY z.zone_id
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter()
+        .map(|row| {
+            let geometry: String = row.try_get("geometry")?;
+            Ok(ForecastZoneRecord {
+                zone_id: row.try_get("zone_id")?,
+                name: row.try_get("name")?,
+                geometry: serde_json::from_str(&geometry)
+                    .map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+                source: row.try_get("source")?,
+                imported_at: row.try_get("imported_at")?,
+                gauge_count: row.try_get("gauge_count")?,
+            })
+        })
+        .collect()
+}
+
+pub async fn find_gauge_locations(pool: &SqlitePool) -> Result<Vec<GaugeZoneLocation>, DbError> {
+    let gauges = sqlx::query_as(
+        r#"
+        SELECT station_id, latitude, longitude, forecast_zone
+        FROM gauges
+        ORDER BY station_id
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(gauges)
+}
+
+pub async fn assign_gauges(
+    pool: &SqlitePool,
+    assignments: &[(String, Option<String>)],
+) -> Result<u64, DbError> {
+    let mut tx = pool.begin().await?;
+    let mut updated = 0;
+
+    for (station_id, zone_id) in assignments {
+        updated += sqlx::query("UPDATE gauges SET forecast_zone = $2 WHERE station_id = $1")
+            .bind(station_id)
+            .bind(zone_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+    }
+
+    tx.commit().await?;
+    Ok(updated)
+}
@@ -0,0 +1,835 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::{Row, SqlitePool};
+
+use super::json_list;
+use crate::db::{
+    DbError, EditableGaugeMetadata, ElevationCandidate, GaugeDetail, GaugeLastSeen, GaugeLocation,
+    GaugeMapPoint, GaugeMetadata, GaugeSourcePair, GaugeStatusChange, GaugeSummary,
+    GaugeWaterYearToDate, GeocodeCandidate, GisGauge,
+};
+use crate::fopr::MetaStatsData;
+use crate::gauge_list_fetcher::GaugeSummary as FetchedGauge;
+use crate::utils;
+
+pub async fn upsert_summaries(
+    pool: &SqlitePool,
+    summaries: &[FetchedGauge],
+) -> Result<usize, DbError> {
+    let now = Utc::now();
+    let mut tx = pool.begin().await?;
+    let mut upserted = 0;
+
+    for summary in summaries {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO gauge_summaries (
+                station_id, gauge_name, city_town, elevation_ft,
+                general_location, msp_forecast_zone,
+                rainfall_past_6h_inches, rainfall_past_24h_inches,
+                last_scraped_at, created_at, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $9, $9)
+            ON CONFLICT (station_id) DO UPDATE SET
+                gauge_name = excluded.gauge_name,
+                city_town = excluded.city_town,
+                elevation_ft = excluded.elevation_ft,
+                general_location = excluded.general_location,
+                msp_forecast_zone = excluded.msp_forecast_zone,
+                rainfall_past_6h_inches = excluded.rainfall_past_6h_inches,
+                rainfall_past_24h_inches = excluded.rainfall_past_24h_inches,
+                last_scraped_at = excluded.last_scraped_at,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(&summary.station_id)
+        .bind(&summary.gauge_name)
+        .bind(&summary.city_town)
+        .bind(summary.elevation_ft)
+        .bind(&summary.general_location)
+        .bind(&summary.msp_forecast_zone)
+        .bind(summary.rainfall_past_6h_inches)
+        .bind(summary.rainfall_past_24h_inches)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+
+        upserted += result.rows_affected() as usize;
+    }
+
+    tx.commit().await?;
+    Ok(upserted)
+}
+
+pub async fn count(pool: &SqlitePool) -> Result<usize, DbError> {
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM gauge_summaries")
+        .fetch_one(pool)
+        .await?;
+
+    Ok(count as usize)
+}
+
+pub async fn find_paginated(
+    pool: &SqlitePool,
+    offset: i64,
+    limit: i64,
+) -> Result<Vec<GaugeSummary>, DbError> {
+    let gauges = sqlx::query_as(
+        r#"
+        SELECT s.id, s.station_id, s.gauge_name, s.city_town, s.elevation_ft,
+               s.general_location, s.msp_forecast_zone,
+               s.rainfall_past_6h_inches, s.rainfall_past_24h_inches,
+               COALESCE(g.status, 'Active') AS status,
+               g.status_effective_date,
+               s.last_scraped_at, s.created_at, s.updated_at
+        FROM gauge_summaries s
+        LEFT JOIN gauges g ON g.station_id = s.station_id
+        ORDER BY s.city_town, s.gauge_name
+        LIMIT $1 OFFSET $2
+        "#,
+    )
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(gauges)
+}
+
+pub async fn count_by_status(pool: &SqlitePool, statuses: &[String]) -> Result<usize, DbError> {
+    let count: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*)
+        FROM gauge_summaries s
+        LEFT JOIN gauges g ON g.station_id = s.station_id
+        WHERE COALESCE(g.status, 'Active') IN (SELECT value FROM json_each($1))
+        "#,
+    )
+    .bind(json_list(statuses))
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count as usize)
+}
+
+pub async fn find_water_year_to_date(
+    pool: &SqlitePool,
+    station_ids: &[String],
+    water_year: i32,
+    year: i32,
+    month: u32,
+) -> Result<Vec<GaugeWaterYearToDate>, DbError> {
+    let (month_start, month_end) = utils::month_date_range(year, month);
+    let totals = sqlx::query_as(
+        r#"
+        SELECT ids.value AS station_id,
+               (SELECT TOTAL(total_rainfall_inches)
+                FROM monthly_rainfall_summary
+                WHERE station_id = ids.value
+                  AND year * 12 + month >= ($2 - 1) * 12 + 10
+                  AND year * 12 + month < $3 * 12 + $4)
+               + (SELECT TOTAL(incremental_inches)
+                  FROM rain_readings
+                  WHERE station_id = ids.value
+                    AND reading_datetime >= $5
+                    AND reading_datetime < $6) AS total_rainfall_inches,
+               (SELECT COALESCE(SUM(reading_count), 0)
+                FROM monthly_rainfall_summary
+                WHERE station_id = ids.value
+                  AND year * 12 + month >= ($2 - 1) * 12 + 10
+                  AND year * 12 + month < $3 * 12 + $4)
+               + (SELECT COUNT(*)
+                  FROM rain_readings
+                  WHERE station_id = ids.value
+                    AND reading_datetime >= $5
+                    AND reading_datetime < $6) AS total_readings,
+               (SELECT MAX(last_reading)
+                FROM (SELECT MAX(last_reading_date) AS last_reading
+                      FROM monthly_rainfall_summary
+                      WHERE station_id = ids.value
+                        AND year * 12 + month >= ($2 - 1) * 12 + 10
+                        AND year * 12 + month < $3 * 12 + $4
+                      UNION ALL
+                      SELECT MAX(reading_datetime)
+                      FROM rain_readings
+                      WHERE station_id = ids.value
+                        AND reading_datetime >= $5
+                        AND reading_datetime < $6)) AS as_of
+        FROM json_each($1) AS ids
+        "#,
+    )
+    .bind(json_list(station_ids))
+    .bind(water_year)
+    .bind(year)
+    .bind(month as i32)
+    .bind(month_start)
+    .bind(month_end)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(totals)
+}
+
+pub async fn find_paginated_by_status(
+    pool: &SqlitePool,
+    statuses: &[String],
+    offset: i64,
+    limit: i64,
+) -> Result<Vec<GaugeSummary>, DbError> {
+    let gauges = sqlx::query_as(
+        r#"
+        SELECT s.id, s.station_id, s.gauge_name, s.city_town, s.elevation_ft,
+               s.general_location, s.msp_forecast_zone,
+               s.rainfall_past_6h_inches, s.rainfall_past_24h_inches,
+               COALESCE(g.status, 'Active') AS status,
+               g.status_effective_date,
+               s.last_scraped_at, s.created_at, s.updated_at
+        FROM gauge_summaries s
+        LEFT JOIN gauges g ON g.station_id = s.station_id
+        WHERE COALESCE(g.status, 'Active') IN (SELECT value FROM json_each($1))
+        ORDER BY s.city_town, s.gauge_name
+        LIMIT $2 OFFSET $3
+        "#,
+    )
+    .bind(json_list(statuses))
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(gauges)
+}
+
+pub async fn find_by_station_ids(
+    pool: &SqlitePool,
+    station_ids: &[String],
+    statuses: &[String],
+) -> Result<Vec<GaugeSummary>, DbError> {
+    let gauges = sqlx::query_as(
+        r#"
+        SELECT s.id, s.station_id, s.gauge_name, s.city_town, s.elevation_ft,
+               s.general_location, s.msp_forecast_zone,
+               s.rainfall_past_6h_inches, s.rainfall_past_24h_inches,
+               COALESCE(g.status, 'Active') AS status,
+               g.status_effective_date,
+               s.last_scraped_at, s.created_at, s.updated_at
+        FROM gauge_summaries s
+        LEFT JOIN gauges g ON g.station_id = s.station_id
+        WHERE s.station_id IN (SELECT value FROM json_each($1))
+          AND COALESCE(g.status, 'Active') IN (SELECT value FROM json_each($2))
+        "#,
+    )
+    .bind(json_list(station_ids))
+    .bind(json_list(statuses))
+    .fetch_all(pool)
+    .await?;
+
+    Ok(gauges)
+}
+
+pub async fn find_by_id(
+    pool: &SqlitePool,
+    station_id: &str,
+) -> Result<Option<GaugeSummary>, DbError> {
+    let gauge = sqlx::query_as(
+        r#"
+        SELECT s.id, s.station_id, s.gauge_name, s.city_town, s.elevation_ft,
+               s.general_location, s.msp_forecast_zone,
+               s.rainfall_past_6h_inches, s.rainfall_past_24h_inches,
+               COALESCE(g.status, 'Active') AS status,
+               g.status_effective_date,
+               s.last_scraped_at, s.created_at, s.updated_at
+        FROM gauge_summaries s
+        LEFT JOIN gauges g ON g.station_id = s.station_id
+        WHERE s.station_id = $1
+        "#,
+    )
+    .bind(station_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(gauge)
+}
+
+pub async fn find_map_points(
+    pool: &SqlitePool,
+    min_lat: f64,
+    max_lat: f64,
+    min_lon: f64,
+    max_lon: f64,
+) -> Result<Vec<GaugeMapPoint>, DbError> {
+    let points = sqlx::query_as(
+        r#"
+        SELECT g.station_id,
+               COALESCE(s.gauge_name, g.station_name, g.station_id) AS gauge_name,
+               COALESCE(s.city_town, g.city) AS city_town,
+               g.latitude,
+               g.longitude,
+               COALESCE(s.elevation_ft, g.elevation_ft) AS elevation_ft,
+               s.rainfall_past_6h_inches,
+               s.rainfall_past_24h_inches
+        FROM gauges g
+        LEFT JOIN gauge_summaries s ON s.station_id = g.station_id
+        WHERE g.latitude BETWEEN $1 AND $2
+          AND g.longitude BETWEEN $3 AND $4
+        ORDER BY g.station_id
+        "#,
+    )
+    .bind(min_lat)
+    .bind(max_lat)
+    .bind(min_lon)
+    .bind(max_lon)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(points)
+}
+
+pub async fn find_detail(
+    pool: &SqlitePool,
+    station_id: &str,
+) -> Result<Option<GaugeDetail>, DbError> {
+    let detail = sqlx::query_as(
+        r#"
+        SELECT COALESCE(s.station_id, g.station_id) AS station_id,
+               COALESCE(s.gauge_name, g.station_name, COALESCE(s.station_id, g.station_id))
+                   AS gauge_name,
+               COALESCE(s.city_town, g.city) AS city_town,
+               COALESCE(s.elevation_ft, g.elevation_ft) AS elevation_ft,
+               COALESCE(s.general_location, g.location_description) AS general_location,
+               s.msp_forecast_zone,
+               g.latitude,
+               g.longitude,
+               g.status,
+               s.rainfall_past_6h_inches,
+               s.rainfall_past_24h_inches,
+               s.last_scraped_at,
+               g.metadata_updated_at,
+               s.station_id IS NOT NULL AS in_gauge_list,
+               g.station_id IS NOT NULL AS has_metadata
+        FROM (SELECT * FROM gauge_summaries WHERE station_id = $1) s
+        FULL OUTER JOIN (SELECT * FROM gauges WHERE station_id = $1) g
+            ON g.station_id = s.station_id
+        "#,
+    )
+    .bind(station_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(detail)
+}
+
+pub async fn find_metadata(
+    pool: &SqlitePool,
+    station_id: &str,
+) -> Result<Option<GaugeMetadata>, DbError> {
+    let row = sqlx::query(
+        r#"
+        SELECT station_type, county, previous_station_ids,
+               installation_date, data_begins_date, data_ends_date,
+               avg_annual_precipitation_inches,
+               complete_years_count, incomplete_months_count, missing_months_count,
+               data_quality_remarks, fopr_available, fopr_last_import_date
+        FROM gauges
+        WHERE station_id = $1
+        "#,
+    )
+    .bind(station_id)
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(row) = row else {
+        return Ok(None);
+    };
+
+    // Stored as a JSON array; PostgreSQL has TEXT[]
+    let previous_station_ids = row
+        .try_get::<Option<String>, _>("previous_station_ids")?
+        .map(|json| serde_json::from_str(&json))
+        .transpose()
+        .map_err(|e| sqlx::Error::Decode(e.into()))?;
+
+    Ok(Some(GaugeMetadata {
+        station_type: row.try_get("station_type")?,
+        county: row.try_get("county")?,
+        previous_station_ids,
+        installation_date: row.try_get("installation_date")?,
+        data_begins_date: row.try_get("data_begins_date")?,
+        data_ends_date: row.try_get("data_ends_date")?,
+        avg_annual_precipitation_inches: row.try_get("avg_annual_precipitation_inches")?,
+        complete_years_count: row.try_get("complete_years_count")?,
+        incomplete_months_count: row.try_get("incomplete_months_count")?,
+        missing_months_count: row.try_get("missing_months_count")?,
+        data_quality_remarks: row.try_get("data_quality_remarks")?,
+        fopr_available: row.try_get("fopr_available")?,
+        fopr_last_import_date: row.try_get("fopr_last_import_date")?,
+    }))
+}
+
+pub async fn find_status(pool: &SqlitePool, station_id: &str) -> Result<Option<String>, DbError> {
+    let status = sqlx::query_scalar("SELECT status FROM gauges WHERE station_id = $1")
+        .bind(station_id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(status)
+}
+
+pub async fn change_status(
+    pool: &SqlitePool,
+    station_id: &str,
+    from: &str,
+    to: &str,
+    effective_date: NaiveDate,
+    reason: Option<&str>,
+    changed_by: &str,
+) -> Result<Option<GaugeStatusChange>, DbError> {
+    let mut tx = pool.begin().await?;
+
+    let updated = sqlx::query(
+        r#"
+        UPDATE gauges SET status = $3, status_effective_date = $4
+        WHERE station_id = $1 AND status = $2
+        "#,
+    )
+    .bind(station_id)
+    .bind(from)
+    .bind(to)
+    .bind(effective_date)
+    .execute(&mut *tx)
+    .await?;
+
+    if updated.rows_affected() == 0 {
+        return Ok(None);
+    }
+
+    let change = sqlx::query_as(
+        r#"
+        INSERT INTO gauge_status_history (
+            station_id, previous_status, status, effective_date, reason, changed_by, created_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING id, station_id, previous_status, status, effective_date, reason,
+                  changed_by, created_at
+        "#,
+    )
+    .bind(station_id)
+    .bind(from)
+    .bind(to)
+    .bind(effective_date)
+    .bind(reason)
+    .bind(changed_by)
+    .bind(Utc::now())
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(Some(change))
+}
+
+pub async fn find_status_history(
+    pool: &SqlitePool,
+    station_id: &str,
+) -> Result<Vec<GaugeStatusChange>, DbError> {
+    let history = sqlx::query_as(
+        r#"
+        SELECT id, station_id, previous_status, status, effective_date, reason,
+               changed_by, created_at
+        FROM gauge_status_history
+        WHERE station_id = $1
+        ORDER BY created_at DESC, id DESC
+        "#,
+    )
+    .bind(station_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(history)
+}
+
+pub async fn find_active_not_seen_since(
+    pool: &SqlitePool,
+    cutoff: DateTime<Utc>,
+) -> Result<Vec<GaugeLastSeen>, DbError> {
+    let gauges = sqlx::query_as(
+        r#"
+        SELECT g.station_id, s.last_scraped_at
+        FROM gauges g
+        JOIN gauge_summaries s ON s.station_id = g.station_id
+        WHERE g.status = 'Active' AND s.last_scraped_at < $1
+        ORDER BY g.station_id
+        "#,
+    )
+    .bind(cutoff)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(gauges)
+}
+
+pub async fn find_inactive_seen_since(
+    pool: &SqlitePool,
+    cutoff: DateTime<Utc>,
+    changed_by: &str,
+) -> Result<Vec<GaugeLastSeen>, DbError> {
+    let gauges = sqlx::query_as(
+        r#"
+        SELECT g.station_id, s.last_scraped_at
+        FROM gauges g
+        JOIN gauge_summaries s ON s.station_id = g.station_id
+        WHERE g.status = 'Inactive'
+          AND s.last_scraped_at >= $1
+          AND (
+              SELECT h.changed_by FROM gauge_status_history h
+              WHERE h.station_id = g.station_id
+              ORDER BY h.created_at DESC, h.id DESC
+              LIMIT 1
+          ) = $2
+        ORDER BY g.station_id
+        "#,
+    )
+    .bind(cutoff)
+    .bind(changed_by)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(gauges)
+}
+
+pub async fn find_source_pairs(pool: &SqlitePool) -> Result<Vec<GaugeSourcePair>, DbError> {
+    let pairs = sqlx::query_as(
+        r#"
+        SELECT COALESCE(s.station_id, g.station_id) AS station_id,
+               s.station_id IS NOT NULL AS in_gauge_list,
+               g.station_id IS NOT NULL AS has_metadata,
+               s.gauge_name AS summary_name,
+               g.station_name AS metadata_name,
+               s.city_town AS summary_city,
+               g.city AS metadata_city,
+               s.elevation_ft AS summary_elevation_ft,
+               g.elevation_ft AS metadata_elevation_ft
+        FROM gauge_summaries s
+        FULL OUTER JOIN gauges g ON g.station_id = s.station_id
+        ORDER BY 1
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(pairs)
+}
+
+pub async fn backfill_metadata_from_summaries(pool: &SqlitePool) -> Result<u64, DbError> {
+    let result = sqlx::query(
+        r#"
+        UPDATE gauges AS g SET
+            station_name = COALESCE(g.station_name, s.gauge_name),
+            city = COALESCE(g.city, substr(s.city_town, 1, 100)),
+            elevation_ft = COALESCE(g.elevation_ft, s.elevation_ft)
+        FROM gauge_summaries s
+        WHERE s.station_id = g.station_id
+          AND (g.station_name IS NULL
+               OR (g.city IS NULL AND s.city_town IS NOT NULL)
+               OR (g.elevation_ft IS NULL AND s.elevation_ft IS NOT NULL))
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+pub async fn find_editable_metadata(
+    pool: &SqlitePool,
+) -> Result<Vec<EditableGaugeMetadata>, DbError> {
+    let gauges = sqlx::query_as(
+        r#"
+        SELECT station_id, station_name, city, county, location_description,
+               latitude, longitude, elevation_ft
+        FROM gauges
+        ORDER BY station_id
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(gauges)
+}
+
+pub async fn find_gis_gauges(pool: &SqlitePool) -> Result<Vec<GisGauge>, DbError> {
+    let gauges = sqlx::query_as(
+        r#"
+        SELECT station_id, station_name, city, county, location_description,
+               latitude, longitude, elevation_ft,
+               status, data_begins_date, data_ends_date, forecast_zone
+        FROM gauges
+        ORDER BY station_id
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(gauges)
+}
+
+pub async fn find_locations(pool: &SqlitePool) -> Result<Vec<GaugeLocation>, DbError> {
+    let gauges = sqlx::query_as(
+        r#"
+        SELECT station_id, latitude, longitude
+        FROM gauges
+        WHERE latitude IS NOT NULL AND longitude IS NOT NULL
+        ORDER BY station_id
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(gauges)
+}
+
+pub async fn update_editable_metadata(
+    pool: &SqlitePool,
+    edits: &[EditableGaugeMetadata],
+) -> Result<u64, DbError> {
+    let now = Utc::now();
+    let mut tx = pool.begin().await?;
+    let mut updated = 0;
+
+    for edit in edits {
+        let result = sqlx::query(
+            r#"
+            UPDATE gauges SET
+                station_name = $2,
+                city = $3,
+                county = $4,
+                location_description = $5,
+                latitude = $6,
+                longitude = $7,
+                elevation_ft = $8,
+                metadata_source = 'manual_edit',
+                metadata_updated_at = $9
+            WHERE station_id = $1
+            "#,
+        )
+        .bind(&edit.station_id)
+        .bind(&edit.station_name)
+        .bind(&edit.city)
+        .bind(&edit.county)
+        .bind(&edit.location_description)
+        .bind(edit.latitude)
+        .bind(edit.longitude)
+        .bind(edit.elevation_ft)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+        updated += result.rows_affected();
+    }
+
+    tx.commit().await?;
+    Ok(updated)
+}
+
+pub async fn find_geocode_candidates(
+    pool: &SqlitePool,
+    limit: i64,
+) -> Result<Vec<GeocodeCandidate>, DbError> {
+    let gauges = sqlx::query_as(
+        r#"
+        SELECT station_id, latitude, longitude, city
+        FROM gauges
+        WHERE latitude IS NOT NULL
+          AND longitude IS NOT NULL
+          AND geocoded_at IS NULL
+          AND (city IS NULL OR nearest_place IS NULL)
+        ORDER BY station_id
+        LIMIT $1
+        "#,
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(gauges)
+}
+
+pub async fn record_geocode(
+    pool: &SqlitePool,
+    station_id: &str,
+    city: Option<&str>,
+    nearest_place: Option<&str>,
+    source: &str,
+) -> Result<(), DbError> {
+    sqlx::query(
+        r#"
+        UPDATE gauges SET
+            city = COALESCE(city, substr($2, 1, 100)),
+            nearest_place = COALESCE(substr($3, 1, 255), nearest_place),
+            geocode_source = $4,
+            geocoded_at = $5
+        WHERE station_id = $1
+        "#,
+    )
+    .bind(station_id)
+    .bind(city)
+    .bind(nearest_place)
+    .bind(source)
+    .bind(Utc::now())
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn find_elevation_candidates(
+    pool: &SqlitePool,
+    limit: i64,
+) -> Result<Vec<ElevationCandidate>, DbError> {
+    let gauges = sqlx::query_as(
+        r#"
+        SELECT station_id, latitude, longitude
+        FROM gauges
+        WHERE latitude IS NOT NULL
+          AND longitude IS NOT NULL
+          AND elevation_ft IS NULL
+          AND elevation_sampled_at IS NULL
+        ORDER BY station_id
+        LIMIT $1
+        "#,
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(gauges)
+}
+
+pub async fn record_elevation_sample(
+    pool: &SqlitePool,
+    station_id: &str,
+    elevation_ft: Option<i32>,
+    source: &str,
+) -> Result<(), DbError> {
+    sqlx::query(
+        r#"
+        UPDATE gauges SET
+            elevation_source = CASE
+                WHEN elevation_ft IS NULL AND $2 IS NOT NULL THEN $3
+                ELSE elevation_source
+            END,
+            elevation_ft = COALESCE(elevation_ft, $2),
+            elevation_sampled_at = $4
+        WHERE station_id = $1
+        "#,
+    )
+    .bind(station_id)
+    .bind(elevation_ft)
+    .bind(source)
+    .bind(Utc::now())
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn upsert_gauge_metadata(
+    pool: &SqlitePool,
+    metadata: &MetaStatsData,
+) -> Result<(), DbError> {
+    sqlx::query(
+        r#"
+        INSERT INTO gauges (
+            station_id, station_name, station_type, previous_station_ids,
+            latitude, longitude, elevation_ft, county, city, location_description,
+            installation_date, data_begins_date, status,
+            avg_annual_precipitation_inches, complete_years_count,
+            incomplete_months_count, missing_months_count, data_quality_remarks,
+            fopr_metadata, metadata_source, metadata_updated_at, created_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, 'fopr_import', $20, $20)
+        ON CONFLICT (station_id) DO UPDATE SET
+            station_name = CASE WHEN gauges.metadata_source = 'manual_edit'
+                THEN gauges.station_name ELSE excluded.station_name END,
+            station_type = excluded.station_type,
+            previous_station_ids = excluded.previous_station_ids,
+            latitude = CASE WHEN gauges.metadata_source = 'manual_edit'
+                THEN gauges.latitude ELSE excluded.latitude END,
+            longitude = CASE WHEN gauges.metadata_source = 'manual_edit'
+                THEN gauges.longitude ELSE excluded.longitude END,
+            elevation_ft = CASE WHEN gauges.metadata_source = 'manual_edit'
+                THEN gauges.elevation_ft ELSE excluded.elevation_ft END,
+            county = CASE WHEN gauges.metadata_source = 'manual_edit'
+                THEN gauges.county ELSE excluded.county END,
+            city = CASE WHEN gauges.metadata_source = 'manual_edit'
+                THEN gauges.city ELSE excluded.city END,
+            location_description = CASE WHEN gauges.metadata_source = 'manual_edit'
+                THEN gauges.location_description ELSE excluded.location_description END,
+            installation_date = excluded.installation_date,
+            data_begins_date = excluded.data_begins_date,
+            avg_annual_precipitation_inches = excluded.avg_annual_precipitation_inches,
+            complete_years_count = excluded.complete_years_count,
+            incomplete_months_count = excluded.incomplete_months_count,
+            missing_months_count = excluded.missing_months_count,
+            data_quality_remarks = excluded.data_quality_remarks,
+            fopr_metadata = excluded.fopr_metadata,
+            metadata_source = CASE WHEN gauges.metadata_source = 'manual_edit'
+                THEN 'manual_edit' ELSE 'fopr_import' END,
+            metadata_updated_at = excluded.metadata_updated_at
+        "#,
+    )
+    .bind(&metadata.station_id)
+    .bind(&metadata.station_name)
+    .bind(&metadata.station_type)
+    .bind(json_list(&metadata.previous_station_ids))
+    .bind(metadata.latitude)
+    .bind(metadata.longitude)
+    .bind(metadata.elevation_ft)
+    .bind(&metadata.county)
+    .bind(&metadata.city)
+    .bind(&metadata.location_description)
+    .bind(metadata.installation_date)
+    .bind(metadata.data_begins_date)
+    .bind(&metadata.status)
+    .bind(metadata.avg_annual_precipitation_inches)
+    .bind(metadata.complete_years_count)
+    .bind(metadata.incomplete_months_count)
+    .bind(metadata.missing_months_count)
+    .bind(&metadata.data_quality_remarks)
+    .bind(serde_json::to_string(&metadata.fopr_metadata).unwrap())
+    .bind(Utc::now())
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn register_from_list(pool: &SqlitePool, gauge: &FetchedGauge) -> Result<bool, DbError> {
+    let now = Utc::now();
+    let result = sqlx::query(
+        r#"
+        INSERT INTO gauges (
+            station_id, station_name, city, elevation_ft, location_description,
+            metadata_source, metadata_updated_at, created_at
+        )
+        VALUES ($1, $2, substr($3, 1, 100), $4, $5, 'gauge_list', $6, $6)
+        ON CONFLICT (station_id) DO NOTHING
+        "#,
+    )
+    .bind(&gauge.station_id)
+    .bind(&gauge.gauge_name)
+    .bind(&gauge.city_town)
+    .bind(gauge.elevation_ft)
+    .bind(&gauge.general_location)
+    .bind(now)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn gauge_exists(pool: &SqlitePool, station_id: &str) -> Result<bool, DbError> {
+    let exists = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM gauges WHERE station_id = $1)")
+        .bind(station_id)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(exists)
+}
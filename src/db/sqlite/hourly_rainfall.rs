@@ -0,0 +1,165 @@
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+use sqlx::SqlitePool;
+
+use super::json_list;
+use crate::db::{DailyRainfallIntensity, DbError, HourlyRainfallSummary};
+
+pub async fn get_summaries_by_date_range(
+    pool: &SqlitePool,
+    station_id: &str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Vec<HourlyRainfallSummary>, DbError> {
+    let hours = sqlx::query_as(
+        r#"
+        SELECT hour_start, total_rainfall_inches, max_incremental_inches, reading_count
+        FROM hourly_rainfall_summary
+        WHERE station_id = $1 AND hour_start >= $2 AND hour_start < $3
+        ORDER BY hour_start
+        "#,
+    )
+    .bind(station_id)
+    .bind(start)
+    .bind(end)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(hours)
+}
+
+pub async fn get_daily_intensity(
+    pool: &SqlitePool,
+    station_id: &str,
+    first: NaiveDate,
+    last: NaiveDate,
+) -> Result<Vec<DailyRainfallIntensity>, DbError> {
+    let days = sqlx::query_as(
+        r#"
+        SELECT date, max_15min_intensity_in_per_hr, max_1h_intensity_in_per_hr
+        FROM daily_rainfall_intensity
+        WHERE station_id = $1 AND date >= $2 AND date <= $3
+        ORDER BY date
+        "#,
+    )
+    .bind(station_id)
+    .bind(first)
+    .bind(last)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(days)
+}
+
+/// `days` are the midnight bounds of the daily peak intensities to rebuild
+pub async fn recalculate_hours(
+    pool: &SqlitePool,
+    station_id: Option<&str>,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    days: (Option<DateTime<Utc>>, Option<DateTime<Utc>>),
+    sources: &[&str],
+) -> Result<u64, DbError> {
+    let mut tx = pool.begin().await?;
+    sqlx::query(
+        r#"
+        DELETE FROM hourly_rainfall_summary
+        WHERE ($1 IS NULL OR station_id = $1)
+          AND ($2 IS NULL OR hour_start >= $2)
+          AND ($3 IS NULL OR hour_start < $3)
+        "#,
+    )
+    .bind(station_id)
+    .bind(start)
+    .bind(end)
+    .execute(&mut *tx)
+    .await?;
+
+    // Timestamps are RFC 3339 text, so the hour is the first 13 characters
+    let result = sqlx::query(
+        r#"
+        INSERT INTO hourly_rainfall_summary
+            (station_id, hour_start, total_rainfall_inches, max_incremental_inches,
+             reading_count, updated_at)
+        SELECT station_id,
+               substr(reading_datetime, 1, 13) || ':00:00+00:00',
+               ROUND(SUM(incremental_inches), 2),
+               MAX(incremental_inches),
+               COUNT(*),
+               $5
+        FROM rain_readings
+        WHERE ($1 IS NULL OR station_id = $1)
+          AND ($2 IS NULL OR reading_datetime >= $2)
+          AND ($3 IS NULL OR reading_datetime < $3)
+          AND data_source IN (SELECT value FROM json_each($4))
+        GROUP BY 1, 2
+        "#,
+    )
+    .bind(station_id)
+    .bind(start)
+    .bind(end)
+    .bind(json_list(sources))
+    .bind(Utc::now())
+    .execute(&mut *tx)
+    .await?;
+
+    let (day_start, day_end) = days;
+    sqlx::query(
+        r#"
+        DELETE FROM daily_rainfall_intensity
+        WHERE ($1 IS NULL OR station_id = $1)
+          AND ($2 IS NULL OR date >= substr($2, 1, 10))
+          AND ($3 IS NULL OR date < substr($3, 1, 10))
+        "#,
+    )
+    .bind(station_id)
+    .bind(day_start)
+    .bind(day_end)
+    .execute(&mut *tx)
+    .await?;
+
+    // Windows run over whole seconds since the epoch; readings from the hour before the
+    // first day fill that day's early windows
+    sqlx::query(
+        r#"
+        INSERT INTO daily_rainfall_intensity
+            (station_id, date, max_15min_intensity_in_per_hr, max_1h_intensity_in_per_hr,
+             updated_at)
+        SELECT station_id,
+               substr(reading_datetime, 1, 10),
+               ROUND(MAX(rain_15min) * 4, 2),
+               ROUND(MAX(rain_1h), 2),
+               $6
+        FROM (
+            SELECT station_id, reading_datetime,
+                   SUM(incremental_inches) OVER (
+                       PARTITION BY station_id
+                       ORDER BY CAST(strftime('%s', reading_datetime) AS INTEGER)
+                       RANGE BETWEEN 899 PRECEDING AND CURRENT ROW
+                   ) AS rain_15min,
+                   SUM(incremental_inches) OVER (
+                       PARTITION BY station_id
+                       ORDER BY CAST(strftime('%s', reading_datetime) AS INTEGER)
+                       RANGE BETWEEN 3599 PRECEDING AND CURRENT ROW
+                   ) AS rain_1h
+            FROM rain_readings
+            WHERE ($1 IS NULL OR station_id = $1)
+              AND ($4 IS NULL OR reading_datetime >= $4)
+              AND ($3 IS NULL OR reading_datetime < $3)
+              AND data_source IN (SELECT value FROM json_each($5))
+        )
+        WHERE $2 IS NULL OR reading_datetime >= $2
+        GROUP BY 1, 2
+        "#,
+    )
+    .bind(station_id)
+    .bind(day_start)
+    .bind(day_end)
+    .bind(day_start.map(|start| start - TimeDelta::hours(1)))
+    .bind(json_list(sources))
+    .bind(Utc::now())
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(result.rows_affected())
+}
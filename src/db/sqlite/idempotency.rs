@@ -0,0 +1,103 @@
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+
+use crate::db::{DbError, IdempotencyRecord};
+
+pub async fn reserve(
+    pool: &SqlitePool,
+    key: &str,
+    method: &str,
+    path: &str,
+    request_hash: &str,
+    expired_before: DateTime<Utc>,
+) -> Result<Option<IdempotencyRecord>, DbError> {
+    sqlx::query("DELETE FROM idempotency_keys WHERE created_at < $1")
+        .bind(expired_before)
+        .execute(pool)
+        .await?;
+
+    let inserted = sqlx::query(
+        r#"
+        INSERT INTO idempotency_keys (idempotency_key, method, path, request_hash, created_at)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (idempotency_key, method, path) DO NOTHING
+        "#,
+    )
+    .bind(key)
+    .bind(method)
+    .bind(path)
+    .bind(request_hash)
+    .bind(Utc::now())
+    .execute(pool)
+    .await?
+    .rows_affected();
+
+    if inserted == 1 {
+        return Ok(None);
+    }
+
+    let existing = sqlx::query_as(
+        r#"
+        SELECT request_hash, status_code, content_type, response_body, created_at
+        FROM idempotency_keys
+        WHERE idempotency_key = $1 AND method = $2 AND path = $3
+        "#,
+    )
+    .bind(key)
+    .bind(method)
+    .bind(path)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(existing)
+}
+
+pub async fn complete(
+    pool: &SqlitePool,
+    key: &str,
+    method: &str,
+    path: &str,
+    status_code: i16,
+    content_type: Option<&str>,
+    body: &[u8],
+) -> Result<(), DbError> {
+    sqlx::query(
+        r#"
+        UPDATE idempotency_keys
+        SET status_code = $4, content_type = $5, response_body = $6, completed_at = $7
+        WHERE idempotency_key = $1 AND method = $2 AND path = $3
+        "#,
+    )
+    .bind(key)
+    .bind(method)
+    .bind(path)
+    .bind(status_code)
+    .bind(content_type)
+    .bind(body)
+    .bind(Utc::now())
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn release(
+    pool: &SqlitePool,
+    key: &str,
+    method: &str,
+    path: &str,
+) -> Result<(), DbError> {
+    sqlx::query(
+        r#"
+        DELETE FROM idempotency_keys
+        WHERE idempotency_key = $1 AND method = $2 AND path = $3 AND status_code IS NULL
+        "#,
+    )
+    .bind(key)
+    .bind(method)
+    .bind(path)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
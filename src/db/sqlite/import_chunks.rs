@@ -0,0 +1,125 @@
+use std::collections::{BTreeSet, HashMap};
+
+use chrono::Utc;
+use sqlx::{Row, SqlitePool};
+
+use crate::db::import_chunk_repository::{ConflictPolicy, MonthCommit};
+use crate::db::monthly_rainfall_repository::MonthAggregates;
+use crate::db::sqlite::{monthly_rainfall, readings as sqlite_readings};
+use crate::db::DbError;
+use crate::importers::excel_importer::HistoricalReading;
+use crate::utils;
+
+pub async fn committed_months(
+    pool: &SqlitePool,
+    station_id: &str,
+    data_source: &str,
+) -> Result<HashMap<(i32, u32), usize>, DbError> {
+    let rows = sqlx::query(
+        r#"
+        SELECT year, month, reading_count
+        FROM import_chunks
+        WHERE station_id = $1 AND data_source = $2
+        "#,
+    )
+    .bind(station_id)
+    .bind(data_source)
+    .fetch_all(pool)
+    .await?;
+
+    let months = rows
+        .into_iter()
+        .map(|row| {
+            let year: i32 = row.try_get("year")?;
+            let month: i32 = row.try_get("month")?;
+            let count: i64 = row.try_get("reading_count")?;
+            Ok(((year, month as u32), count as usize))
+        })
+        .collect::<Result<_, sqlx::Error>>()?;
+    Ok(months)
+}
+
+pub async fn commit_month(
+    pool: &SqlitePool,
+    station_id: &str,
+    data_source: &str,
+    (year, month): (i32, u32),
+    readings: &[HistoricalReading],
+    derive_cumulative: bool,
+    on_conflict: ConflictPolicy,
+) -> Result<MonthCommit, DbError> {
+    let mut tx = pool.begin().await?;
+    let commit = match on_conflict {
+        ConflictPolicy::Skip => {
+            let (inserted, duplicates, _) =
+                sqlite_readings::insert_historical_rows(&mut tx, station_id, data_source, readings)
+                    .await?;
+            MonthCommit {
+                inserted,
+                updated: 0,
+                duplicates,
+            }
+        }
+        ConflictPolicy::Update => {
+            let upsert =
+                sqlite_readings::upsert_historical_rows(&mut tx, station_id, data_source, readings)
+                    .await?;
+            MonthCommit {
+                inserted: upsert.inserted,
+                updated: upsert.updated,
+                duplicates: upsert.unchanged,
+            }
+        }
+    };
+
+    let mut months = BTreeSet::from([(year, month)]);
+    if derive_cumulative {
+        let water_year = if month >= 10 { year + 1 } else { year };
+        months.extend(
+            sqlite_readings::backfill_cumulative(&mut *tx, station_id, data_source, water_year)
+                .await?,
+        );
+    }
+    for (year, month) in months {
+        let (start, end) = utils::month_date_range(year, month);
+        let month_readings =
+            sqlite_readings::find_by_date_range(&mut *tx, station_id, start, end).await?;
+        if month_readings.is_empty() {
+            continue;
+        }
+        monthly_rainfall::upsert_monthly_summary(
+            &mut *tx,
+            station_id,
+            year,
+            month as i32,
+            &MonthAggregates::from_readings(&month_readings),
+            (start, end),
+        )
+        .await?;
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO import_chunks
+            (station_id, data_source, year, month, reading_count, readings_inserted, committed_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (station_id, data_source, year, month)
+        DO UPDATE SET
+            reading_count = excluded.reading_count,
+            readings_inserted = excluded.readings_inserted,
+            committed_at = excluded.committed_at
+        "#,
+    )
+    .bind(station_id)
+    .bind(data_source)
+    .bind(year)
+    .bind(month as i32)
+    .bind(readings.len() as i64)
+    .bind(commit.inserted as i64)
+    .bind(Utc::now())
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(commit)
+}
@@ -0,0 +1,251 @@
+use chrono::{DateTime, Datelike, Utc};
+use sqlx::{SqliteExecutor, SqlitePool};
+
+use crate::db::monthly_rainfall_repository::MonthAggregates;
+use crate::db::{DbError, MonthlyRainfallSummary, RankingRow, SummaryDiscrepancy};
+
+pub async fn upsert_monthly_summary(
+    executor: impl SqliteExecutor<'_>,
+    station_id: &str,
+    year: i32,
+    month: i32,
+    aggregates: &MonthAggregates,
+    (start, end): (DateTime<Utc>, DateTime<Utc>),
+) -> Result<(), DbError> {
+    sqlx::query(
+        r#"
+        INSERT INTO monthly_rainfall_summary
+            (station_id, year, month, total_rainfall_inches, reading_count,
+             first_reading_date, last_reading_date, min_cumulative_inches, max_cumulative_inches,
+             flagged_count, estimated_count, footnoted_count, created_at, updated_at)
+        SELECT $1, $2, $3, $4, $5, $6, $7, $8, $9,
+               COUNT(*) FILTER (WHERE json_type(import_metadata, '$.flags') = 'array'
+                                  AND json_array_length(import_metadata, '$.flags') > 0),
+               COUNT(*) FILTER (WHERE json_type(import_metadata, '$.estimated') = 'true'),
+               COUNT(*) FILTER (WHERE json_extract(import_metadata, '$.footnote_marker') IS NOT NULL),
+               $10, $10
+        FROM rain_readings
+        WHERE station_id = $1 AND reading_datetime >= $11 AND reading_datetime < $12
+        ON CONFLICT (station_id, year, month)
+        DO UPDATE SET
+            total_rainfall_inches = excluded.total_rainfall_inches,
+            reading_count = excluded.reading_count,
+            first_reading_date = excluded.first_reading_date,
+            last_reading_date = excluded.last_reading_date,
+            min_cumulative_inches = excluded.min_cumulative_inches,
+            max_cumulative_inches = excluded.max_cumulative_inches,
+            flagged_count = excluded.flagged_count,
+            estimated_count = excluded.estimated_count,
+            footnoted_count = excluded.footnoted_count,
+            updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(station_id)
+    .bind(year)
+    .bind(month)
+    .bind(aggregates.total_rainfall)
+    .bind(aggregates.reading_count)
+    .bind(aggregates.first_reading_date)
+    .bind(aggregates.last_reading_date)
+    .bind(aggregates.min_cumulative)
+    .bind(aggregates.max_cumulative)
+    .bind(Utc::now())
+    .bind(start)
+    .bind(end)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_summaries_by_date_range(
+    pool: &SqlitePool,
+    station_id: &str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Vec<MonthlyRainfallSummary>, DbError> {
+    // Months are compared as year * 12 + month, from start's month up to end's (exclusive)
+    let summaries = sqlx::query_as(
+        r#"
+        SELECT id, station_id, year, month, total_rainfall_inches, reading_count,
+               first_reading_date, last_reading_date, min_cumulative_inches, max_cumulative_inches,
+               flagged_count, estimated_count, footnoted_count, created_at, updated_at
+        FROM monthly_rainfall_summary
+        WHERE station_id = $1 AND year * 12 + month >= $2 AND year * 12 + month < $3
+        ORDER BY year ASC, month ASC
+        "#,
+    )
+    .bind(station_id)
+    .bind(start.year() * 12 + start.month() as i32)
+    .bind(end.year() * 12 + end.month() as i32)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(summaries)
+}
+
+pub async fn find_all_summaries(pool: &SqlitePool) -> Result<Vec<MonthlyRainfallSummary>, DbError> {
+    let summaries = sqlx::query_as(
+        r#"
+        SELECT id, station_id, year, month, total_rainfall_inches, reading_count,
+               first_reading_date, last_reading_date, min_cumulative_inches, max_cumulative_inches,
+               flagged_count, estimated_count, footnoted_count, created_at, updated_at
+        FROM monthly_rainfall_summary
+        ORDER BY station_id ASC, year ASC, month ASC
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(summaries)
+}
+
+pub async fn find_station_months(
+    pool: &SqlitePool,
+    station_id: Option<&str>,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+) -> Result<Vec<(String, i32, u32)>, DbError> {
+    let rows: Vec<(String, i32, i32)> = sqlx::query_as(
+        r#"
+        SELECT DISTINCT station_id,
+               CAST(substr(reading_datetime, 1, 4) AS INTEGER),
+               CAST(substr(reading_datetime, 6, 2) AS INTEGER)
+        FROM rain_readings
+        WHERE ($1 IS NULL OR station_id = $1)
+          AND ($2 IS NULL OR reading_datetime >= $2)
+          AND ($3 IS NULL OR reading_datetime < $3)
+        ORDER BY 1, 2, 3
+        "#,
+    )
+    .bind(station_id)
+    .bind(start)
+    .bind(end)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(station_id, year, month)| (station_id, year, month as u32))
+        .collect())
+}
+
+pub async fn delete_orphaned_summaries(
+    pool: &SqlitePool,
+    station_id: Option<&str>,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+) -> Result<u64, DbError> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM monthly_rainfall_summary AS s
+        WHERE ($1 IS NULL OR s.station_id = $1)
+          AND ($2 IS NULL OR printf('%04d-%02d-01T00:00:00+00:00', s.year + s.month / 12, s.month % 12 + 1) > $2)
+          AND ($3 IS NULL OR printf('%04d-%02d-01T00:00:00+00:00', s.year, s.month) < $3)
+          AND NOT EXISTS (
+              SELECT 1 FROM rain_readings r
+              WHERE r.station_id = s.station_id
+                AND r.reading_datetime >= printf('%04d-%02d-01T00:00:00+00:00', s.year, s.month)
+                AND r.reading_datetime < printf('%04d-%02d-01T00:00:00+00:00', s.year + s.month / 12, s.month % 12 + 1)
+          )
+        "#,
+    )
+    .bind(station_id)
+    .bind(start)
+    .bind(end)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+pub async fn find_summary_discrepancies(
+    pool: &SqlitePool,
+    station_id: Option<&str>,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Vec<SummaryDiscrepancy>, DbError> {
+    let discrepancies = sqlx::query_as(
+        r#"
+        WITH actual AS (
+            SELECT station_id,
+                   CAST(substr(reading_datetime, 1, 4) AS INTEGER) AS year,
+                   CAST(substr(reading_datetime, 6, 2) AS INTEGER) AS month,
+                   TOTAL(incremental_inches) AS total_inches,
+                   COUNT(*) AS reading_count
+            FROM rain_readings
+            WHERE reading_datetime >= $1 AND reading_datetime < $2
+              AND ($3 IS NULL OR station_id = $3)
+            GROUP BY 1, 2, 3
+        ),
+        summarized AS (
+            SELECT station_id, year, month, total_rainfall_inches, reading_count
+            FROM monthly_rainfall_summary
+            WHERE printf('%04d-%02d-01T00:00:00+00:00', year, month) >= $1
+              AND printf('%04d-%02d-01T00:00:00+00:00', year, month) < $2
+              AND ($3 IS NULL OR station_id = $3)
+        )
+        SELECT COALESCE(s.station_id, a.station_id) AS station_id,
+               COALESCE(s.year, a.year) AS year,
+               COALESCE(s.month, a.month) AS month,
+               s.total_rainfall_inches AS summary_total_inches,
+               a.total_inches AS readings_total_inches,
+               s.reading_count AS summary_reading_count,
+               a.reading_count AS actual_reading_count
+        FROM summarized s
+        FULL OUTER JOIN actual a
+          ON s.station_id = a.station_id AND s.year = a.year AND s.month = a.month
+        WHERE s.station_id IS NULL
+           OR a.station_id IS NULL
+           OR s.reading_count <> a.reading_count
+           OR ABS(s.total_rainfall_inches - a.total_inches) > 0.001
+        ORDER BY 1, 2, 3
+        "#,
+    )
+    .bind(start)
+    .bind(end)
+    .bind(station_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(discrepancies)
+}
+
+pub async fn rank_stations_by_monthly_totals(
+    pool: &SqlitePool,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    driest_first: bool,
+    include_inactive: bool,
+    limit: i64,
+) -> Result<Vec<RankingRow>, DbError> {
+    let rows = sqlx::query_as(
+        r#"
+        SELECT m.station_id, g.gauge_name, g.city_town,
+               COALESCE(gs.forecast_zone, g.msp_forecast_zone) AS msp_forecast_zone, g.general_location,
+               TOTAL(m.total_rainfall_inches) AS rainfall_inches,
+               SUM(m.reading_count) AS reading_count
+        FROM monthly_rainfall_summary m
+        JOIN gauge_summaries g ON g.station_id = m.station_id
+        LEFT JOIN gauges gs ON gs.station_id = m.station_id
+        WHERE printf('%04d-%02d-01T00:00:00+00:00', m.year, m.month) >= $1
+          AND printf('%04d-%02d-01T00:00:00+00:00', m.year, m.month) < $2
+          AND ($5 OR COALESCE(gs.status, 'Active') = 'Active')
+        GROUP BY m.station_id, g.gauge_name, g.city_town, g.msp_forecast_zone, g.general_location,
+                 gs.forecast_zone
+        ORDER BY CASE WHEN $3 THEN TOTAL(m.total_rainfall_inches) END ASC,
+                 CASE WHEN NOT $3 THEN TOTAL(m.total_rainfall_inches) END DESC,
+                 m.station_id
+        LIMIT $4
+        "#,
+    )
+    .bind(start)
+    .bind(end)
+    .bind(driest_first)
+    .bind(limit)
+    .bind(include_inactive)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
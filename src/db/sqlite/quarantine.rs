@@ -0,0 +1,38 @@
+use sqlx::SqlitePool;
+
+use crate::db::DbError;
+use crate::ingest_guard::QuarantinedReading;
+
+pub async fn insert(
+    pool: &SqlitePool,
+    data_source: &str,
+    readings: &[QuarantinedReading],
+) -> Result<usize, DbError> {
+    let mut tx = pool.begin().await?;
+    let mut inserted = 0;
+
+    for reading in readings {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO quarantined_readings
+                (station_id, reading_datetime, cumulative_inches, incremental_inches,
+                 data_source, reason)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (station_id, reading_datetime, data_source) DO NOTHING
+            "#,
+        )
+        .bind(&reading.station_id)
+        .bind(reading.reading_datetime)
+        .bind(reading.cumulative_inches)
+        .bind(reading.incremental_inches)
+        .bind(data_source)
+        .bind(reading.reason.as_str())
+        .execute(&mut *tx)
+        .await?;
+
+        inserted += result.rows_affected() as usize;
+    }
+
+    tx.commit().await?;
+    Ok(inserted)
+}
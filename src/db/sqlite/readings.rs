@@ -0,0 +1,670 @@
+use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Utc};
+use futures::stream::{BoxStream, StreamExt, TryStreamExt};
+use sqlx::{Row, SqliteConnection, SqliteExecutor, SqlitePool};
+use tracing::info;
+
+use crate::db::reading_repository::{HistoricalUpsert, LiveUpsert};
+use crate::db::{CoverageRow, DbError, ExportReading, RankingRow, Reading};
+use crate::fetcher::{RainReading, LIVE_DATA_SOURCE, LIVE_STATION_ID};
+use crate::importers::excel_importer::HistoricalReading;
+use crate::utils;
+
+pub async fn insert_readings(
+    pool: &SqlitePool,
+    readings: &[RainReading],
+) -> Result<usize, DbError> {
+    let mut tx = pool.begin().await?;
+    let mut inserted = 0;
+
+    for reading in readings {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO rain_readings (reading_datetime, cumulative_inches, incremental_inches)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (reading_datetime, station_id) DO NOTHING
+            "#,
+        )
+        .bind(reading.reading_datetime)
+        .bind(reading.cumulative_inches)
+        .bind(reading.incremental_inches)
+        .execute(&mut *tx)
+        .await?;
+
+        inserted += result.rows_affected() as usize;
+    }
+
+    tx.commit().await?;
+    info!(
+        "Inserted {} new readings, {} duplicates skipped",
+        inserted,
+        readings.len() - inserted
+    );
+    Ok(inserted)
+}
+
+pub async fn upsert_readings(
+    pool: &SqlitePool,
+    readings: &[RainReading],
+    revisable_from: DateTime<Utc>,
+) -> Result<LiveUpsert, DbError> {
+    let mut tx = pool.begin().await?;
+    let mut result = LiveUpsert::default();
+
+    for reading in readings {
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO rain_readings (reading_datetime, cumulative_inches, incremental_inches)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (reading_datetime, station_id) DO NOTHING
+            "#,
+        )
+        .bind(reading.reading_datetime)
+        .bind(reading.cumulative_inches)
+        .bind(reading.incremental_inches)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            > 0;
+        if inserted {
+            result.inserted += 1;
+            result.changed.push(reading.reading_datetime);
+            continue;
+        }
+        if reading.reading_datetime < revisable_from {
+            continue;
+        }
+
+        let existing = sqlx::query(
+            r#"
+            SELECT id, cumulative_inches, incremental_inches, import_metadata
+            FROM rain_readings
+            WHERE station_id = $1 AND reading_datetime = $2 AND data_source = $3
+            "#,
+        )
+        .bind(LIVE_STATION_ID)
+        .bind(reading.reading_datetime)
+        .bind(LIVE_DATA_SOURCE)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(existing) = existing else {
+            continue;
+        };
+        let previous_cumulative: f64 = existing.try_get("cumulative_inches")?;
+        let previous_incremental: f64 = existing.try_get("incremental_inches")?;
+        if previous_cumulative == reading.cumulative_inches.value()
+            && previous_incremental == reading.incremental_inches.value()
+        {
+            continue;
+        }
+
+        let id: i64 = existing.try_get("id")?;
+        sqlx::query(
+            r#"
+            INSERT INTO reading_revisions
+                (reading_id, station_id, reading_datetime, previous_incremental_inches,
+                 previous_cumulative_inches, previous_data_source, previous_import_metadata,
+                 new_incremental_inches, new_data_source, revised_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $6, $9)
+            "#,
+        )
+        .bind(id)
+        .bind(LIVE_STATION_ID)
+        .bind(reading.reading_datetime)
+        .bind(previous_incremental)
+        .bind(previous_cumulative)
+        .bind(LIVE_DATA_SOURCE)
+        .bind(existing.try_get::<Option<String>, _>("import_metadata")?)
+        .bind(reading.incremental_inches)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            "UPDATE rain_readings SET cumulative_inches = $2, incremental_inches = $3 WHERE id = $1",
+        )
+        .bind(id)
+        .bind(reading.cumulative_inches)
+        .bind(reading.incremental_inches)
+        .execute(&mut *tx)
+        .await?;
+        result.updated += 1;
+        result.changed.push(reading.reading_datetime);
+    }
+
+    tx.commit().await?;
+    info!(
+        "Inserted {} new readings, revised {}, {} unchanged",
+        result.inserted,
+        result.updated,
+        readings.len() - result.inserted - result.updated
+    );
+    Ok(result)
+}
+
+pub async fn insert_station_readings(
+    pool: &SqlitePool,
+    station_id: &str,
+    data_source: &str,
+    readings: &[RainReading],
+) -> Result<usize, DbError> {
+    let mut tx = pool.begin().await?;
+    let mut inserted = 0;
+
+    for reading in readings {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO rain_readings (station_id, reading_datetime, cumulative_inches, incremental_inches, data_source)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (reading_datetime, station_id) DO NOTHING
+            "#,
+        )
+        .bind(station_id)
+        .bind(reading.reading_datetime)
+        .bind(reading.cumulative_inches)
+        .bind(reading.incremental_inches)
+        .bind(data_source)
+        .execute(&mut *tx)
+        .await?;
+
+        inserted += result.rows_affected() as usize;
+    }
+
+    tx.commit().await?;
+    Ok(inserted)
+}
+
+#[allow(clippy::type_complexity)]
+pub async fn bulk_insert_historical_readings(
+    pool: &SqlitePool,
+    station_id: &str,
+    data_source: &str,
+    readings: &[HistoricalReading],
+) -> Result<(usize, usize, Vec<(i32, u32)>), DbError> {
+    // One transaction instead of one per row: SQLite commits are fsyncs
+    let mut tx = pool.begin().await?;
+    let result = insert_historical_rows(&mut tx, station_id, data_source, readings).await?;
+    tx.commit().await?;
+
+    info!(
+        "Bulk insert complete: {} inserted, {} duplicates for station {}",
+        result.0, result.1, station_id
+    );
+    Ok(result)
+}
+
+/// Insert historical readings on a connection the caller holds a transaction on
+#[allow(clippy::type_complexity)]
+pub async fn insert_historical_rows(
+    conn: &mut SqliteConnection,
+    station_id: &str,
+    data_source: &str,
+    readings: &[HistoricalReading],
+) -> Result<(usize, usize, Vec<(i32, u32)>), DbError> {
+    let mut inserted = 0;
+    let mut duplicates = 0;
+    let mut affected_months = Vec::new();
+
+    for reading in readings {
+        let import_metadata = reading
+            .footnote_marker
+            .as_ref()
+            .map(|marker| serde_json::json!({ "footnote_marker": marker }).to_string());
+        let reading_datetime =
+            Utc.from_utc_datetime(&reading.reading_date.and_hms_opt(0, 0, 0).unwrap());
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO rain_readings (station_id, reading_datetime, cumulative_inches, incremental_inches, data_source, import_metadata)
+            VALUES ($1, $2, 0.0, $3, $4, $5)
+            ON CONFLICT (reading_datetime, station_id) DO NOTHING
+            "#,
+        )
+        .bind(station_id)
+        .bind(reading_datetime)
+        .bind(reading.rainfall_inches)
+        .bind(data_source)
+        .bind(import_metadata)
+        .execute(&mut *conn)
+        .await?;
+
+        if result.rows_affected() > 0 {
+            inserted += 1;
+            affected_months.push((reading.reading_date.year(), reading.reading_date.month()));
+        } else {
+            duplicates += 1;
+        }
+    }
+
+    Ok((inserted, duplicates, affected_months))
+}
+
+pub async fn upsert_historical_rows(
+    conn: &mut SqliteConnection,
+    station_id: &str,
+    data_source: &str,
+    readings: &[HistoricalReading],
+) -> Result<HistoricalUpsert, DbError> {
+    let mut result = HistoricalUpsert::default();
+
+    for reading in readings {
+        let import_metadata = reading
+            .footnote_marker
+            .as_ref()
+            .map(|marker| serde_json::json!({ "footnote_marker": marker }));
+        let reading_datetime =
+            Utc.from_utc_datetime(&reading.reading_date.and_hms_opt(0, 0, 0).unwrap());
+
+        let existing = sqlx::query(
+            r#"
+            SELECT id, cumulative_inches, incremental_inches, data_source, import_metadata
+            FROM rain_readings
+            WHERE station_id = $1 AND reading_datetime = $2
+            "#,
+        )
+        .bind(station_id)
+        .bind(reading_datetime)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        let Some(existing) = existing else {
+            sqlx::query(
+                r#"
+                INSERT INTO rain_readings (station_id, reading_datetime, cumulative_inches, incremental_inches, data_source, import_metadata)
+                VALUES ($1, $2, 0.0, $3, $4, $5)
+                "#,
+            )
+            .bind(station_id)
+            .bind(reading_datetime)
+            .bind(reading.rainfall_inches)
+            .bind(data_source)
+            .bind(import_metadata.as_ref().map(|m| m.to_string()))
+            .execute(&mut *conn)
+            .await?;
+            result.inserted += 1;
+            continue;
+        };
+
+        let id: i64 = existing.try_get("id")?;
+        let previous_incremental: f64 = existing.try_get("incremental_inches")?;
+        let previous_source: String = existing.try_get("data_source")?;
+        let previous_metadata: Option<String> = existing.try_get("import_metadata")?;
+        let previous_metadata_json = previous_metadata
+            .as_deref()
+            .and_then(|m| serde_json::from_str::<serde_json::Value>(m).ok());
+
+        let same = previous_incremental == reading.rainfall_inches.value()
+            && previous_metadata_json == import_metadata;
+        if same || previous_source == LIVE_DATA_SOURCE {
+            result.unchanged += 1;
+            continue;
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO reading_revisions
+                (reading_id, station_id, reading_datetime, previous_incremental_inches,
+                 previous_cumulative_inches, previous_data_source, previous_import_metadata,
+                 new_incremental_inches, new_data_source, revised_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            "#,
+        )
+        .bind(id)
+        .bind(station_id)
+        .bind(reading_datetime)
+        .bind(previous_incremental)
+        .bind(existing.try_get::<f64, _>("cumulative_inches")?)
+        .bind(&previous_source)
+        .bind(&previous_metadata)
+        .bind(reading.rainfall_inches)
+        .bind(data_source)
+        .bind(Utc::now())
+        .execute(&mut *conn)
+        .await?;
+        sqlx::query(
+            r#"
+            UPDATE rain_readings
+            SET incremental_inches = $2, data_source = $3, import_metadata = $4, created_at = $5
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(reading.rainfall_inches)
+        .bind(data_source)
+        .bind(import_metadata.as_ref().map(|m| m.to_string()))
+        .bind(Utc::now())
+        .execute(&mut *conn)
+        .await?;
+        result.updated += 1;
+    }
+
+    Ok(result)
+}
+
+pub async fn backfill_cumulative(
+    executor: impl SqliteExecutor<'_>,
+    station_id: &str,
+    data_source: &str,
+    water_year: i32,
+) -> Result<Vec<(i32, u32)>, DbError> {
+    let (start, end) = utils::water_year_date_range(water_year);
+    let changed: Vec<DateTime<Utc>> = sqlx::query_scalar(
+        r#"
+        UPDATE rain_readings
+        SET cumulative_inches = t.running_total
+        FROM (
+            SELECT id, ROUND(SUM(incremental_inches) OVER (ORDER BY reading_datetime), 2) AS running_total
+            FROM rain_readings
+            WHERE station_id = $1 AND data_source = $2
+              AND reading_datetime >= $3 AND reading_datetime < $4
+        ) t
+        WHERE rain_readings.id = t.id AND rain_readings.cumulative_inches <> t.running_total
+        RETURNING rain_readings.reading_datetime
+        "#,
+    )
+    .bind(station_id)
+    .bind(data_source)
+    .bind(start)
+    .bind(end)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(utils::distinct_months(changed))
+}
+
+pub async fn find_last_modified(
+    pool: &SqlitePool,
+    station_id: &str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Option<DateTime<Utc>>, DbError> {
+    let last_modified = sqlx::query_scalar(
+        r#"
+        SELECT MAX(created_at)
+        FROM rain_readings
+        WHERE station_id = $1 AND reading_datetime >= $2 AND reading_datetime < $3
+        "#,
+    )
+    .bind(station_id)
+    .bind(start)
+    .bind(end)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(last_modified)
+}
+
+pub async fn find_date_extent(
+    pool: &SqlitePool,
+    station_id: &str,
+) -> Result<Option<(NaiveDate, NaiveDate)>, DbError> {
+    let (first, last): (Option<DateTime<Utc>>, Option<DateTime<Utc>>) = sqlx::query_as(
+        "SELECT MIN(reading_datetime), MAX(reading_datetime) FROM rain_readings WHERE station_id = $1",
+    )
+    .bind(station_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(first
+        .zip(last)
+        .map(|(first, last)| (first.date_naive(), last.date_naive())))
+}
+
+pub async fn find_by_date_range(
+    executor: impl SqliteExecutor<'_>,
+    station_id: &str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Vec<Reading>, DbError> {
+    let readings = sqlx::query_as(
+        r#"
+        SELECT id, reading_datetime, cumulative_inches, incremental_inches, station_id, created_at
+        FROM rain_readings
+        WHERE station_id = $1 AND reading_datetime >= $2 AND reading_datetime < $3
+        ORDER BY reading_datetime DESC
+        "#,
+    )
+    .bind(station_id)
+    .bind(start)
+    .bind(end)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(readings)
+}
+
+pub async fn find_page_by_date_range(
+    pool: &SqlitePool,
+    station_id: &str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    offset: i64,
+    limit: i64,
+) -> Result<Vec<Reading>, DbError> {
+    let readings = sqlx::query_as(
+        r#"
+        SELECT id, reading_datetime, cumulative_inches, incremental_inches, station_id, created_at
+        FROM rain_readings
+        WHERE station_id = $1 AND reading_datetime >= $2 AND reading_datetime < $3
+        ORDER BY reading_datetime ASC, id ASC
+        LIMIT $4 OFFSET $5
+        "#,
+    )
+    .bind(station_id)
+    .bind(start)
+    .bind(end)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(readings)
+}
+
+pub async fn count_by_date_range(
+    pool: &SqlitePool,
+    station_id: &str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<i64, DbError> {
+    let count = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*)
+        FROM rain_readings
+        WHERE station_id = $1 AND reading_datetime >= $2 AND reading_datetime < $3
+        "#,
+    )
+    .bind(station_id)
+    .bind(start)
+    .bind(end)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count)
+}
+
+pub fn stream_by_date_range<'a>(
+    pool: &'a SqlitePool,
+    station_id: &'a str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> BoxStream<'a, Result<Reading, DbError>> {
+    sqlx::query_as(
+        r#"
+        SELECT id, reading_datetime, cumulative_inches, incremental_inches, station_id, created_at
+        FROM rain_readings
+        WHERE station_id = $1 AND reading_datetime >= $2 AND reading_datetime < $3
+        ORDER BY reading_datetime ASC
+        "#,
+    )
+    .bind(station_id)
+    .bind(start)
+    .bind(end)
+    .fetch(pool)
+    .map_err(DbError::from)
+    .boxed()
+}
+
+pub fn stream_all_for_export(pool: &SqlitePool) -> BoxStream<'_, Result<ExportReading, DbError>> {
+    sqlx::query_as(
+        r#"
+        SELECT station_id, reading_datetime, cumulative_inches, incremental_inches, data_source
+        FROM rain_readings
+        ORDER BY reading_datetime ASC, station_id ASC
+        "#,
+    )
+    .fetch(pool)
+    .map_err(DbError::from)
+    .boxed()
+}
+
+pub async fn find_latest(pool: &SqlitePool, station_id: &str) -> Result<Option<Reading>, DbError> {
+    let reading = sqlx::query_as(
+        r#"
+        SELECT id, reading_datetime, cumulative_inches, incremental_inches, station_id, created_at
+        FROM rain_readings
+        WHERE station_id = $1
+        ORDER BY reading_datetime DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(station_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(reading)
+}
+
+pub async fn find_coverage(
+    pool: &SqlitePool,
+    station_id: &str,
+) -> Result<Vec<CoverageRow>, DbError> {
+    let rows = sqlx::query_as(
+        r#"
+        SELECT CAST(substr(reading_datetime, 1, 4) AS INTEGER) AS year,
+               CAST(substr(reading_datetime, 6, 2) AS INTEGER) AS month,
+               data_source,
+               COUNT(*) AS reading_count,
+               MIN(reading_datetime) AS first_reading,
+               MAX(reading_datetime) AS last_reading
+        FROM rain_readings
+        WHERE station_id = $1
+        GROUP BY 1, 2, 3
+        ORDER BY 1, 2, 3
+        "#,
+    )
+    .bind(station_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+pub async fn daily_total_histogram(
+    pool: &SqlitePool,
+    station_id: &str,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    bin_width: f64,
+) -> Result<Vec<(i32, i64)>, DbError> {
+    // CAST truncates toward zero, which matches FLOOR for the non-negative quotients kept
+    let rows = sqlx::query_as(
+        r#"
+        WITH daily AS (
+            SELECT substr(reading_datetime, 1, 10) AS day,
+                   SUM(incremental_inches) AS total_inches
+            FROM rain_readings
+            WHERE station_id = $1
+              AND ($2 IS NULL OR reading_datetime >= $2)
+              AND ($3 IS NULL OR reading_datetime < $3)
+            GROUP BY 1
+        )
+        SELECT MAX(CAST(total_inches / $4 + 1e-9 AS INTEGER), 0) AS bin_index,
+               COUNT(*) AS day_count
+        FROM daily
+        GROUP BY 1
+        ORDER BY 1
+        "#,
+    )
+    .bind(station_id)
+    .bind(start)
+    .bind(end)
+    .bind(bin_width)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+pub async fn daily_totals(
+    pool: &SqlitePool,
+    station_id: &str,
+    first: NaiveDate,
+    last: NaiveDate,
+) -> Result<Vec<(NaiveDate, f64)>, DbError> {
+    let rows = sqlx::query_as(
+        r#"
+        WITH RECURSIVE days(day) AS (
+            SELECT $2
+            UNION ALL
+            SELECT date(day, '+1 day') FROM days WHERE day < $3
+        ),
+        daily AS (
+            SELECT substr(reading_datetime, 1, 10) AS day,
+                   SUM(incremental_inches) AS total_inches
+            FROM rain_readings
+            WHERE station_id = $1
+              AND reading_datetime >= $2
+              AND reading_datetime < date($3, '+1 day')
+            GROUP BY 1
+        )
+        SELECT days.day, COALESCE(daily.total_inches, 0.0)
+        FROM days
+        LEFT JOIN daily ON daily.day = days.day
+        ORDER BY 1
+        "#,
+    )
+    .bind(station_id)
+    .bind(first)
+    .bind(last)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+pub async fn rank_stations_by_rainfall(
+    pool: &SqlitePool,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    driest_first: bool,
+    include_inactive: bool,
+    limit: i64,
+) -> Result<Vec<RankingRow>, DbError> {
+    let rows = sqlx::query_as(
+        r#"
+        SELECT r.station_id, g.gauge_name, g.city_town,
+               COALESCE(gs.forecast_zone, g.msp_forecast_zone) AS msp_forecast_zone, g.general_location,
+               TOTAL(r.incremental_inches) AS rainfall_inches,
+               COUNT(*) AS reading_count
+        FROM rain_readings r
+        JOIN gauge_summaries g ON g.station_id = r.station_id
+        LEFT JOIN gauges gs ON gs.station_id = r.station_id
+        WHERE r.reading_datetime >= $1 AND r.reading_datetime < $2
+          AND ($5 OR COALESCE(gs.status, 'Active') = 'Active')
+        GROUP BY r.station_id, g.gauge_name, g.city_town, g.msp_forecast_zone, g.general_location,
+                 gs.forecast_zone
+        ORDER BY CASE WHEN $3 THEN TOTAL(r.incremental_inches) END ASC,
+                 CASE WHEN NOT $3 THEN TOTAL(r.incremental_inches) END DESC,
+                 r.station_id
+        LIMIT $4
+        "#,
+    )
+    .bind(start)
+    .bind(end)
+    .bind(driest_first)
+    .bind(limit)
+    .bind(include_inactive)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
@@ -0,0 +1,245 @@
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+
+use super::json_list;
+use crate::db::hourly_rainfall_repository::SUB_DAILY_SOURCES;
+use crate::db::threshold_event_repository::ThresholdChanges;
+use crate::db::{
+    DbError, GaugeThresholdEvent, OpenThresholdEvent, StormBucketRow, StormFootprintGauge,
+};
+
+pub async fn find_open(
+    pool: &SqlitePool,
+    station_ids: &[String],
+) -> Result<Vec<OpenThresholdEvent>, DbError> {
+    let events = sqlx::query_as(
+        r#"
+        SELECT id, station_id, threshold_inches, peak_rainfall_24h_inches
+        FROM gauge_threshold_events
+        WHERE ended_at IS NULL AND station_id IN (SELECT value FROM json_each($1))
+        "#,
+    )
+    .bind(json_list(station_ids))
+    .fetch_all(pool)
+    .await?;
+
+    Ok(events)
+}
+
+pub async fn apply(
+    pool: &SqlitePool,
+    changes: &ThresholdChanges,
+    observed_at: DateTime<Utc>,
+) -> Result<(), DbError> {
+    let mut tx = pool.begin().await?;
+
+    for event in &changes.opened {
+        sqlx::query(
+            r#"
+            INSERT INTO gauge_threshold_events
+                (station_id, threshold_inches, crossed_at,
+                 rainfall_24h_inches, peak_rainfall_24h_inches)
+            VALUES ($1, $2, $3, $4, $4)
+            ON CONFLICT (station_id, threshold_inches) WHERE ended_at IS NULL DO NOTHING
+            "#,
+        )
+        .bind(&event.station_id)
+        .bind(event.threshold_inches)
+        .bind(observed_at)
+        .bind(event.rainfall_24h_inches)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    for (id, peak) in &changes.raised_peaks {
+        sqlx::query(
+            r#"
+            UPDATE gauge_threshold_events
+            SET peak_rainfall_24h_inches = MAX(peak_rainfall_24h_inches, $2)
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(peak)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    if !changes.closed.is_empty() {
+        sqlx::query(
+            r#"
+            UPDATE gauge_threshold_events SET ended_at = $2
+            WHERE id IN (SELECT value FROM json_each($1)) AND ended_at IS NULL
+            "#,
+        )
+        .bind(json_list(&changes.closed))
+        .bind(observed_at)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    // 90000 and 86400 seconds are 25 and 24 hours before the crossing
+    sqlx::query(
+        r#"
+        UPDATE gauge_threshold_events AS e
+        SET (max_15min_intensity_in_per_hr, max_1h_intensity_in_per_hr) = (
+            SELECT ROUND(MAX(rain_15min) * 4, 2), ROUND(MAX(rain_1h), 2)
+            FROM (
+                SELECT epoch,
+                       SUM(incremental_inches) OVER (
+                           ORDER BY epoch RANGE BETWEEN 899 PRECEDING AND CURRENT ROW
+                       ) AS rain_15min,
+                       SUM(incremental_inches) OVER (
+                           ORDER BY epoch RANGE BETWEEN 3599 PRECEDING AND CURRENT ROW
+                       ) AS rain_1h
+                FROM (
+                    SELECT CAST(strftime('%s', reading_datetime) AS INTEGER) AS epoch,
+                           incremental_inches
+                    FROM rain_readings r
+                    WHERE r.station_id = e.station_id
+                      AND r.reading_datetime <= COALESCE(e.ended_at, $2)
+                      AND r.data_source IN (SELECT value FROM json_each($3))
+                )
+                WHERE epoch >= CAST(strftime('%s', e.crossed_at) AS INTEGER) - 90000
+            )
+            WHERE epoch >= CAST(strftime('%s', e.crossed_at) AS INTEGER) - 86400
+        )
+        WHERE e.ended_at IS NULL OR e.id IN (SELECT value FROM json_each($1))
+        "#,
+    )
+    .bind(json_list(&changes.closed))
+    .bind(observed_at)
+    .bind(json_list(SUB_DAILY_SOURCES))
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+pub async fn find_by_station(
+    pool: &SqlitePool,
+    station_id: &str,
+    threshold_inches: Option<f64>,
+    offset: i64,
+    limit: i64,
+) -> Result<Vec<GaugeThresholdEvent>, DbError> {
+    let events = sqlx::query_as(
+        r#"
+        SELECT id, station_id, threshold_inches, crossed_at, ended_at,
+               rainfall_24h_inches, peak_rainfall_24h_inches,
+               max_15min_intensity_in_per_hr, max_1h_intensity_in_per_hr
+        FROM gauge_threshold_events
+        WHERE station_id = $1
+          AND ($2 IS NULL OR ABS(threshold_inches - $2) < 0.0001)
+        ORDER BY crossed_at DESC, threshold_inches DESC
+        LIMIT $3 OFFSET $4
+        "#,
+    )
+    .bind(station_id)
+    .bind(threshold_inches)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(events)
+}
+
+pub async fn count_by_station(
+    pool: &SqlitePool,
+    station_id: &str,
+    threshold_inches: Option<f64>,
+) -> Result<i64, DbError> {
+    let count = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*)
+        FROM gauge_threshold_events
+        WHERE station_id = $1
+          AND ($2 IS NULL OR ABS(threshold_inches - $2) < 0.0001)
+        "#,
+    )
+    .bind(station_id)
+    .bind(threshold_inches)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count)
+}
+
+pub async fn find_by_id(
+    pool: &SqlitePool,
+    id: i64,
+) -> Result<Option<GaugeThresholdEvent>, DbError> {
+    let event = sqlx::query_as(
+        r#"
+        SELECT id, station_id, threshold_inches, crossed_at, ended_at,
+               rainfall_24h_inches, peak_rainfall_24h_inches,
+               max_15min_intensity_in_per_hr, max_1h_intensity_in_per_hr
+        FROM gauge_threshold_events
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(event)
+}
+
+pub async fn find_footprint(
+    pool: &SqlitePool,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Vec<StormFootprintGauge>, DbError> {
+    let gauges = sqlx::query_as(
+        r#"
+        SELECT r.station_id, g.station_name, g.latitude, g.longitude,
+               SUM(r.incremental_inches) AS rainfall_inches,
+               COUNT(*) AS reading_count
+        FROM rain_readings r
+        LEFT JOIN gauges g ON g.station_id = r.station_id
+        WHERE r.reading_datetime >= $1 AND r.reading_datetime <= $2
+        GROUP BY r.station_id, g.station_name, g.latitude, g.longitude
+        HAVING SUM(r.incremental_inches) > 0
+        ORDER BY 5 DESC, r.station_id
+        "#,
+    )
+    .bind(start)
+    .bind(end)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(gauges)
+}
+
+pub async fn find_timeline_buckets(
+    pool: &SqlitePool,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    step_seconds: i64,
+) -> Result<Vec<StormBucketRow>, DbError> {
+    let buckets = sqlx::query_as(
+        r#"
+        SELECT station_id,
+               strftime(
+                   '%Y-%m-%dT%H:%M:%S+00:00',
+                   CAST(strftime('%s', reading_datetime) AS INTEGER) / $3 * $3,
+                   'unixepoch'
+               ) AS bucket_start,
+               SUM(incremental_inches) AS rainfall_inches
+        FROM rain_readings
+        WHERE reading_datetime >= $1 AND reading_datetime <= $2
+        GROUP BY 1, 2
+        HAVING SUM(incremental_inches) > 0
+        ORDER BY 2, 1
+        "#,
+    )
+    .bind(start)
+    .bind(end)
+    .bind(step_seconds)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(buckets)
+}
@@ -0,0 +1,168 @@
+use std::time::Instant;
+
+use tracing::{debug, instrument};
+
+use crate::db::{DbError, DbPool, RankingRow, SummaryView};
+
+/// Summary materialized views and their refresh state; PostgreSQL only
+#[derive(Clone)]
+pub struct SummaryViewRepository {
+    db: DbPool,
+}
+
+impl SummaryViewRepository {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        Self { db: pool.into() }
+    }
+
+    /// Every summary view, ordered by name
+    pub async fn find_all(&self) -> Result<Vec<SummaryView>, DbError> {
+        let views = sqlx::query_as!(
+            SummaryView,
+            r#"
+            SELECT view_name, refreshed_at, refresh_duration_ms, stale, stale_reason,
+                   marked_stale_at
+            FROM summary_views
+            ORDER BY view_name
+            "#
+        )
+        .fetch_all(self.db.postgres()?)
+        .await?;
+
+        Ok(views)
+    }
+
+    /// The gauge_period_rainfall view's refresh state
+    pub async fn find_period_rainfall(&self) -> Result<SummaryView, DbError> {
+        let view = sqlx::query_as!(
+            SummaryView,
+            r#"
+            SELECT view_name, refreshed_at, refresh_duration_ms, stale, stale_reason,
+                   marked_stale_at
+            FROM summary_views
+            WHERE view_name = 'gauge_period_rainfall'
+            "#
+        )
+        .fetch_one(self.db.postgres()?)
+        .await?;
+
+        Ok(view)
+    }
+
+    /// Flag every view stale until its next refresh
+    ///
+    /// A no-op on backends without summary views.
+    #[instrument(skip(self))]
+    pub async fn mark_stale(&self, reason: &str) -> Result<(), DbError> {
+        let Ok(pool) = self.db.postgres() else {
+            return Ok(());
+        };
+        sqlx::query!(
+            r#"
+            UPDATE summary_views
+            SET stale = TRUE, stale_reason = $1, marked_stale_at = NOW()
+            "#,
+            reason
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Recompute gauge_period_rainfall and clear its stale flag
+    ///
+    /// Readers keep the old contents until the refresh commits (except on the first
+    /// refresh, which cannot run concurrently). A flag set after the refresh started is
+    /// kept, since the refresh may not include the change behind it.
+    #[instrument(skip(self))]
+    pub async fn refresh_period_rainfall(&self) -> Result<SummaryView, DbError> {
+        let mut tx = self.db.postgres()?.begin().await?;
+        let populated = sqlx::query_scalar!(
+            r#"
+            SELECT ispopulated AS "populated!"
+            FROM pg_matviews
+            WHERE matviewname = 'gauge_period_rainfall'
+            "#
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let started = Instant::now();
+        if populated {
+            sqlx::query!("REFRESH MATERIALIZED VIEW CONCURRENTLY gauge_period_rainfall")
+                .execute(&mut *tx)
+                .await?;
+        } else {
+            sqlx::query!("REFRESH MATERIALIZED VIEW gauge_period_rainfall")
+                .execute(&mut *tx)
+                .await?;
+        }
+        let duration_ms = started.elapsed().as_millis() as i64;
+
+        // NOW() is the transaction's start, which the view's periods were computed from
+        let view = sqlx::query_as!(
+            SummaryView,
+            r#"
+            UPDATE summary_views
+            SET refreshed_at = NOW(),
+                refresh_duration_ms = $1,
+                stale = COALESCE(marked_stale_at > NOW(), FALSE),
+                stale_reason = CASE WHEN marked_stale_at > NOW() THEN stale_reason END,
+                marked_stale_at = CASE WHEN marked_stale_at > NOW() THEN marked_stale_at END
+            WHERE view_name = 'gauge_period_rainfall'
+            RETURNING view_name, refreshed_at, refresh_duration_ms, stale AS "stale!",
+                      stale_reason, marked_stale_at
+            "#,
+            duration_ms
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        debug!(duration_ms, populated, "Refreshed gauge_period_rainfall");
+        Ok(view)
+    }
+
+    /// Rank gauges by their month-to-date (`month`) or water-year-to-date rainfall as of
+    /// the last refresh
+    pub async fn rank_stations_by_period(
+        &self,
+        month: bool,
+        driest_first: bool,
+        include_inactive: bool,
+        limit: i64,
+    ) -> Result<Vec<RankingRow>, DbError> {
+        let rows = sqlx::query_as!(
+            RankingRow,
+            r#"
+            SELECT station_id AS "station_id!", gauge_name AS "gauge_name!", city_town,
+                   msp_forecast_zone, general_location,
+                   rainfall_inches AS "rainfall_inches!", reading_count AS "reading_count!"
+            FROM (
+                SELECT station_id, gauge_name, city_town, msp_forecast_zone, general_location,
+                       status,
+                       CASE WHEN $1 THEN month_inches ELSE water_year_inches END
+                           AS rainfall_inches,
+                       CASE WHEN $1 THEN month_reading_count ELSE water_year_reading_count END
+                           AS reading_count
+                FROM gauge_period_rainfall
+            ) p
+            WHERE rainfall_inches IS NOT NULL
+              AND ($4 OR status = 'Active')
+            ORDER BY CASE WHEN $2 THEN rainfall_inches END ASC,
+                     CASE WHEN NOT $2 THEN rainfall_inches END DESC,
+                     station_id
+            LIMIT $3
+            "#,
+            month,
+            driest_first,
+            limit,
+            include_inactive
+        )
+        .fetch_all(self.db.postgres()?)
+        .await?;
+
+        Ok(rows)
+    }
+}
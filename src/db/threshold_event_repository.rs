@@ -0,0 +1,372 @@
+use chrono::{DateTime, Utc};
+use tracing::{debug, instrument};
+
+use crate::db::hourly_rainfall_repository::SUB_DAILY_SOURCES;
+#[cfg(feature = "sqlite")]
+use crate::db::sqlite;
+use crate::db::{
+    DbError, DbPool, GaugeThresholdEvent, OpenThresholdEvent, StormBucketRow, StormFootprintGauge,
+};
+
+/// A gauge that reached a threshold it was below on the previous scrape
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewThresholdEvent {
+    pub station_id: String,
+    pub threshold_inches: f64,
+    pub rainfall_24h_inches: f64,
+}
+
+/// Changes to threshold events from one scrape
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ThresholdChanges {
+    pub opened: Vec<NewThresholdEvent>,
+    /// Open events with a new peak: (event id, 24h total)
+    pub raised_peaks: Vec<(i64, f64)>,
+    /// Open events whose gauge dropped below the threshold
+    pub closed: Vec<i64>,
+}
+
+impl ThresholdChanges {
+    pub fn is_empty(&self) -> bool {
+        self.opened.is_empty() && self.raised_peaks.is_empty() && self.closed.is_empty()
+    }
+}
+
+#[derive(Clone)]
+pub struct ThresholdEventRepository {
+    db: DbPool,
+}
+
+impl ThresholdEventRepository {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        Self { db: pool.into() }
+    }
+
+    /// Open events for the given gauges
+    #[instrument(skip(self, station_ids), fields(count = station_ids.len()))]
+    pub async fn find_open(
+        &self,
+        station_ids: &[String],
+    ) -> Result<Vec<OpenThresholdEvent>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::threshold_events::find_open(pool, station_ids).await
+            }
+        };
+        let events = sqlx::query_as!(
+            OpenThresholdEvent,
+            r#"
+            SELECT id, station_id, threshold_inches, peak_rainfall_24h_inches
+            FROM gauge_threshold_events
+            WHERE ended_at IS NULL AND station_id = ANY($1)
+            "#,
+            station_ids
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(events)
+    }
+
+    /// Apply one scrape's changes atomically
+    ///
+    /// Open and just-closed events also get their peak intensities refreshed from
+    /// sub-daily readings, from 24h before the crossing through `observed_at`.
+    #[instrument(skip(self, changes))]
+    pub async fn apply(
+        &self,
+        changes: &ThresholdChanges,
+        observed_at: DateTime<Utc>,
+    ) -> Result<(), DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::threshold_events::apply(pool, changes, observed_at).await
+            }
+        };
+        let mut tx = pool.begin().await?;
+
+        for event in &changes.opened {
+            sqlx::query!(
+                r#"
+                INSERT INTO gauge_threshold_events
+                    (station_id, threshold_inches, crossed_at,
+                     rainfall_24h_inches, peak_rainfall_24h_inches)
+                VALUES ($1, $2, $3, $4, $4)
+                ON CONFLICT (station_id, threshold_inches) WHERE ended_at IS NULL DO NOTHING
+                "#,
+                event.station_id,
+                event.threshold_inches,
+                observed_at,
+                event.rainfall_24h_inches
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for (id, peak) in &changes.raised_peaks {
+            sqlx::query!(
+                r#"
+                UPDATE gauge_threshold_events
+                SET peak_rainfall_24h_inches = GREATEST(peak_rainfall_24h_inches, $2)
+                WHERE id = $1
+                "#,
+                id,
+                peak
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        if !changes.closed.is_empty() {
+            sqlx::query!(
+                "UPDATE gauge_threshold_events SET ended_at = $2 WHERE id = ANY($1) AND ended_at IS NULL",
+                &changes.closed,
+                observed_at
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let sources: Vec<String> = SUB_DAILY_SOURCES.iter().map(|s| s.to_string()).collect();
+        sqlx::query!(
+            r#"
+            UPDATE gauge_threshold_events e
+            SET (max_15min_intensity_in_per_hr, max_1h_intensity_in_per_hr) = (
+                SELECT ROUND(MAX(rain_15min) * 4 * 100) / 100, ROUND(MAX(rain_1h) * 100) / 100
+                FROM (
+                    SELECT reading_datetime,
+                           SUM(incremental_inches) OVER (
+                               ORDER BY reading_datetime
+                               RANGE BETWEEN INTERVAL '15 minutes' - INTERVAL '1 microsecond'
+                                     PRECEDING AND CURRENT ROW
+                           ) AS rain_15min,
+                           SUM(incremental_inches) OVER (
+                               ORDER BY reading_datetime
+                               RANGE BETWEEN INTERVAL '1 hour' - INTERVAL '1 microsecond'
+                                     PRECEDING AND CURRENT ROW
+                           ) AS rain_1h
+                    FROM rain_readings r
+                    WHERE r.station_id = e.station_id
+                      AND r.reading_datetime >= e.crossed_at - INTERVAL '25 hours'
+                      AND r.reading_datetime <= COALESCE(e.ended_at, $2)
+                      AND r.data_source = ANY($3)
+                ) windows
+                WHERE reading_datetime >= e.crossed_at - INTERVAL '24 hours'
+            )
+            WHERE e.ended_at IS NULL OR e.id = ANY($1)
+            "#,
+            &changes.closed,
+            observed_at,
+            &sources
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        debug!(
+            "Threshold events: {} opened, {} peaks raised, {} closed",
+            changes.opened.len(),
+            changes.raised_peaks.len(),
+            changes.closed.len()
+        );
+        Ok(())
+    }
+
+    /// Events for a gauge, newest first, optionally for a single threshold, skipping the
+    /// newest `offset`
+    #[instrument(skip(self))]
+    pub async fn find_by_station(
+        &self,
+        station_id: &str,
+        threshold_inches: Option<f64>,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<GaugeThresholdEvent>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::threshold_events::find_by_station(
+                    pool,
+                    station_id,
+                    threshold_inches,
+                    offset,
+                    limit,
+                )
+                .await
+            }
+        };
+        let events = sqlx::query_as!(
+            GaugeThresholdEvent,
+            r#"
+            SELECT id, station_id, threshold_inches, crossed_at, ended_at,
+                   rainfall_24h_inches, peak_rainfall_24h_inches,
+                   max_15min_intensity_in_per_hr, max_1h_intensity_in_per_hr
+            FROM gauge_threshold_events
+            WHERE station_id = $1
+              AND ($2::FLOAT8 IS NULL OR ABS(threshold_inches - $2) < 0.0001)
+            ORDER BY crossed_at DESC, threshold_inches DESC
+            LIMIT $3 OFFSET $4
+            "#,
+            station_id,
+            threshold_inches,
+            limit,
+            offset
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(events)
+    }
+
+    /// Number of events `find_by_station` pages through
+    #[instrument(skip(self))]
+    pub async fn count_by_station(
+        &self,
+        station_id: &str,
+        threshold_inches: Option<f64>,
+    ) -> Result<i64, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::threshold_events::count_by_station(
+                    pool,
+                    station_id,
+                    threshold_inches,
+                )
+                .await
+            }
+        };
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "count!"
+            FROM gauge_threshold_events
+            WHERE station_id = $1
+              AND ($2::FLOAT8 IS NULL OR ABS(threshold_inches - $2) < 0.0001)
+            "#,
+            station_id,
+            threshold_inches
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// One event by ID
+    #[instrument(skip(self))]
+    pub async fn find_by_id(&self, id: i64) -> Result<Option<GaugeThresholdEvent>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => return sqlite::threshold_events::find_by_id(pool, id).await,
+        };
+        let event = sqlx::query_as!(
+            GaugeThresholdEvent,
+            r#"
+            SELECT id, station_id, threshold_inches, crossed_at, ended_at,
+                   rainfall_24h_inches, peak_rainfall_24h_inches,
+                   max_15min_intensity_in_per_hr, max_1h_intensity_in_per_hr
+            FROM gauge_threshold_events
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(event)
+    }
+
+    /// Gauges with rain in `[start, end]`, wettest first
+    ///
+    /// Totals cover readings from every source; gauges whose readings sum to zero are
+    /// left out.
+    #[instrument(skip(self))]
+    pub async fn find_footprint(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<StormFootprintGauge>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::threshold_events::find_footprint(pool, start, end).await
+            }
+        };
+        let gauges = sqlx::query_as!(
+            StormFootprintGauge,
+            r#"
+            SELECT r.station_id, g.station_name,
+                   g.latitude::FLOAT8 AS latitude, g.longitude::FLOAT8 AS longitude,
+                   SUM(r.incremental_inches)::FLOAT8 AS "rainfall_inches!",
+                   COUNT(*) AS "reading_count!"
+            FROM rain_readings r
+            LEFT JOIN gauges g ON g.station_id = r.station_id
+            WHERE r.reading_datetime >= $1 AND r.reading_datetime <= $2
+            GROUP BY r.station_id, g.station_name, g.latitude, g.longitude
+            HAVING SUM(r.incremental_inches) > 0
+            ORDER BY 5 DESC, r.station_id
+            "#,
+            start,
+            end
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(gauges)
+    }
+
+    /// Each gauge's rain in `[start, end]` per `step_seconds` bucket since the Unix epoch
+    ///
+    /// Only buckets with rain are returned, oldest first.
+    #[instrument(skip(self))]
+    pub async fn find_timeline_buckets(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        step_seconds: i64,
+    ) -> Result<Vec<StormBucketRow>, DbError> {
+        let pool = match &self.db {
+            DbPool::Postgres(pool) => pool,
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(pool) => {
+                return sqlite::threshold_events::find_timeline_buckets(
+                    pool,
+                    start,
+                    end,
+                    step_seconds,
+                )
+                .await
+            }
+        };
+        let buckets = sqlx::query_as!(
+            StormBucketRow,
+            r#"
+            SELECT station_id,
+                   to_timestamp(
+                       FLOOR(EXTRACT(EPOCH FROM reading_datetime) / $3) * $3
+                   ) AS "bucket_start!",
+                   SUM(incremental_inches)::FLOAT8 AS "rainfall_inches!"
+            FROM rain_readings
+            WHERE reading_datetime >= $1 AND reading_datetime <= $2
+            GROUP BY 1, 2
+            HAVING SUM(incremental_inches) > 0
+            ORDER BY 2, 1
+            "#,
+            start,
+            end,
+            step_seconds as f64
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(buckets)
+    }
+}
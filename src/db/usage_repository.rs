@@ -0,0 +1,100 @@
+use chrono::NaiveDate;
+use tracing::instrument;
+
+use crate::db::{DbError, DbPool};
+
+/// A user's counts for one month
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MonthlyUsage {
+    pub request_count: i64,
+    pub response_bytes: i64,
+}
+
+/// Per-user request and byte counts by UTC month; PostgreSQL only
+#[derive(Clone)]
+pub struct UsageRepository {
+    db: DbPool,
+}
+
+impl UsageRepository {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        Self { db: pool.into() }
+    }
+
+    /// Count a request against `month` unless a quota is already used up; returns the
+    /// counts including this request, or None when the request was refused
+    ///
+    /// Checking and counting happen in one statement, so concurrent requests cannot
+    /// overshoot the request quota. The byte quota is only checked here: a response that
+    /// crosses it still completes, and the next request is refused.
+    #[instrument(skip(self))]
+    pub async fn claim(
+        &self,
+        user_id: i64,
+        month: NaiveDate,
+        request_quota: Option<i64>,
+        byte_quota: Option<i64>,
+    ) -> Result<Option<MonthlyUsage>, DbError> {
+        // Quotas are at least 1, so the month's first request always fits
+        let usage = sqlx::query_as!(
+            MonthlyUsage,
+            r#"
+            INSERT INTO user_usage (user_id, month, request_count)
+            VALUES ($1, $2, 1)
+            ON CONFLICT (user_id, month) DO UPDATE
+            SET request_count = user_usage.request_count + 1
+            WHERE ($3::BIGINT IS NULL OR user_usage.request_count < $3)
+              AND ($4::BIGINT IS NULL OR user_usage.response_bytes < $4)
+            RETURNING request_count, response_bytes
+            "#,
+            user_id,
+            month,
+            request_quota,
+            byte_quota
+        )
+        .fetch_optional(self.db.postgres()?)
+        .await?;
+
+        Ok(usage)
+    }
+
+    /// Add response bytes to a month already claimed
+    pub async fn add_bytes(
+        &self,
+        user_id: i64,
+        month: NaiveDate,
+        bytes: i64,
+    ) -> Result<(), DbError> {
+        sqlx::query!(
+            r#"
+            UPDATE user_usage SET response_bytes = response_bytes + $3
+            WHERE user_id = $1 AND month = $2
+            "#,
+            user_id,
+            month,
+            bytes
+        )
+        .execute(self.db.postgres()?)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Counts for a month; zeros when the user made no requests in it
+    pub async fn find(&self, user_id: i64, month: NaiveDate) -> Result<MonthlyUsage, DbError> {
+        let usage = sqlx::query_as!(
+            MonthlyUsage,
+            r#"
+            SELECT request_count, response_bytes
+            FROM user_usage
+            WHERE user_id = $1 AND month = $2
+            "#,
+            user_id,
+            month
+        )
+        .fetch_optional(self.db.postgres()?)
+        .await?;
+
+        Ok(usage.unwrap_or_default())
+    }
+}
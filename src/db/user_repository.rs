@@ -0,0 +1,308 @@
+use chrono::{DateTime, Utc};
+use tracing::instrument;
+
+use crate::db::{DbError, DbPool, FavoriteGauge, RankingPeriod, SavedView, User};
+
+/// Users, their favorite gauges, and their saved views; PostgreSQL only
+#[derive(Clone)]
+pub struct UserRepository {
+    db: DbPool,
+}
+
+/// A saved_views row before its period is parsed
+struct SavedViewRow {
+    id: i64,
+    name: String,
+    station_ids: Vec<String>,
+    period: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<SavedViewRow> for SavedView {
+    fn from(row: SavedViewRow) -> Self {
+        Self {
+            id: row.id,
+            name: row.name,
+            station_ids: row.station_ids,
+            // The column's CHECK constraint only admits known periods
+            period: RankingPeriod::parse(&row.period).unwrap_or(RankingPeriod::Month),
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+impl UserRepository {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        Self { db: pool.into() }
+    }
+
+    /// Insert a user; fails with a unique violation when the name is taken
+    #[instrument(skip(self, key_hash))]
+    pub async fn insert(
+        &self,
+        name: &str,
+        key_hash: &str,
+        monthly_request_quota: Option<i64>,
+        monthly_byte_quota: Option<i64>,
+    ) -> Result<User, DbError> {
+        let user = sqlx::query_as!(
+            User,
+            r#"
+            INSERT INTO users (name, key_hash, monthly_request_quota, monthly_byte_quota)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, name, created_at, last_seen_at, monthly_request_quota, monthly_byte_quota
+            "#,
+            name,
+            key_hash,
+            monthly_request_quota,
+            monthly_byte_quota
+        )
+        .fetch_one(self.db.postgres()?)
+        .await?;
+
+        Ok(user)
+    }
+
+    /// Every user, ordered by name
+    pub async fn find_all(&self) -> Result<Vec<User>, DbError> {
+        let users = sqlx::query_as!(
+            User,
+            r#"
+            SELECT id, name, created_at, last_seen_at, monthly_request_quota, monthly_byte_quota
+            FROM users
+            ORDER BY name
+            "#
+        )
+        .fetch_all(self.db.postgres()?)
+        .await?;
+
+        Ok(users)
+    }
+
+    /// Replace a user's quotas; None when no such user
+    #[instrument(skip(self))]
+    pub async fn set_quotas(
+        &self,
+        id: i64,
+        monthly_request_quota: Option<i64>,
+        monthly_byte_quota: Option<i64>,
+    ) -> Result<Option<User>, DbError> {
+        let user = sqlx::query_as!(
+            User,
+            r#"
+            UPDATE users SET monthly_request_quota = $2, monthly_byte_quota = $3
+            WHERE id = $1
+            RETURNING id, name, created_at, last_seen_at, monthly_request_quota, monthly_byte_quota
+            "#,
+            id,
+            monthly_request_quota,
+            monthly_byte_quota
+        )
+        .fetch_optional(self.db.postgres()?)
+        .await?;
+
+        Ok(user)
+    }
+
+    /// Delete a user with their favorites and views; false when no such user
+    #[instrument(skip(self))]
+    pub async fn delete(&self, id: i64) -> Result<bool, DbError> {
+        let deleted = sqlx::query!(r#"DELETE FROM users WHERE id = $1"#, id)
+            .execute(self.db.postgres()?)
+            .await?
+            .rows_affected();
+
+        Ok(deleted > 0)
+    }
+
+    /// The user holding a key, recording the request as their last activity
+    pub async fn authenticate(&self, key_hash: &str) -> Result<Option<User>, DbError> {
+        let user = sqlx::query_as!(
+            User,
+            r#"
+            UPDATE users SET last_seen_at = NOW()
+            WHERE key_hash = $1
+            RETURNING id, name, created_at, last_seen_at, monthly_request_quota, monthly_byte_quota
+            "#,
+            key_hash
+        )
+        .fetch_optional(self.db.postgres()?)
+        .await?;
+
+        Ok(user)
+    }
+
+    /// A user's favorite gauges, most recently added first
+    pub async fn find_favorites(&self, user_id: i64) -> Result<Vec<FavoriteGauge>, DbError> {
+        let favorites = sqlx::query_as!(
+            FavoriteGauge,
+            r#"
+            SELECT f.station_id, g.station_name, f.created_at AS added_at
+            FROM user_favorites f
+            JOIN gauges g ON g.station_id = f.station_id
+            WHERE f.user_id = $1
+            ORDER BY f.created_at DESC, f.station_id
+            "#,
+            user_id
+        )
+        .fetch_all(self.db.postgres()?)
+        .await?;
+
+        Ok(favorites)
+    }
+
+    /// Mark a gauge as a favorite; false when the gauge does not exist
+    ///
+    /// Adding a gauge that is already a favorite succeeds and keeps its original date.
+    #[instrument(skip(self))]
+    pub async fn add_favorite(&self, user_id: i64, station_id: &str) -> Result<bool, DbError> {
+        let pool = self.db.postgres()?;
+        let exists = sqlx::query_scalar!(
+            r#"
+            WITH added AS (
+                INSERT INTO user_favorites (user_id, station_id)
+                SELECT $1, station_id FROM gauges WHERE station_id = $2
+                ON CONFLICT (user_id, station_id) DO NOTHING
+            )
+            SELECT EXISTS (SELECT 1 FROM gauges WHERE station_id = $2) AS "exists!"
+            "#,
+            user_id,
+            station_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(exists)
+    }
+
+    /// Unmark a favorite; a gauge that was not a favorite is left as is
+    #[instrument(skip(self))]
+    pub async fn remove_favorite(&self, user_id: i64, station_id: &str) -> Result<(), DbError> {
+        sqlx::query!(
+            r#"DELETE FROM user_favorites WHERE user_id = $1 AND station_id = $2"#,
+            user_id,
+            station_id
+        )
+        .execute(self.db.postgres()?)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Station IDs from `station_ids` that have no gauge row, in the order given
+    pub async fn find_missing_gauges(
+        &self,
+        station_ids: &[String],
+    ) -> Result<Vec<String>, DbError> {
+        let missing = sqlx::query_scalar!(
+            r#"
+            SELECT s.station_id AS "station_id!"
+            FROM UNNEST($1::VARCHAR[]) WITH ORDINALITY AS s(station_id, position)
+            WHERE NOT EXISTS (SELECT 1 FROM gauges g WHERE g.station_id = s.station_id)
+            ORDER BY s.position
+            "#,
+            station_ids
+        )
+        .fetch_all(self.db.postgres()?)
+        .await?;
+
+        Ok(missing)
+    }
+
+    /// A user's saved views, ordered by name
+    pub async fn find_views(&self, user_id: i64) -> Result<Vec<SavedView>, DbError> {
+        let rows = sqlx::query_as!(
+            SavedViewRow,
+            r#"
+            SELECT id, name, station_ids AS "station_ids: Vec<String>", period,
+                   created_at, updated_at
+            FROM saved_views
+            WHERE user_id = $1
+            ORDER BY name
+            "#,
+            user_id
+        )
+        .fetch_all(self.db.postgres()?)
+        .await?;
+
+        Ok(rows.into_iter().map(SavedView::from).collect())
+    }
+
+    /// Save a new view; fails with a unique violation when the user has one of that name
+    #[instrument(skip(self, station_ids))]
+    pub async fn insert_view(
+        &self,
+        user_id: i64,
+        name: &str,
+        station_ids: &[String],
+        period: RankingPeriod,
+    ) -> Result<SavedView, DbError> {
+        let row = sqlx::query_as!(
+            SavedViewRow,
+            r#"
+            INSERT INTO saved_views (user_id, name, station_ids, period)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, name, station_ids AS "station_ids: Vec<String>", period,
+                      created_at, updated_at
+            "#,
+            user_id,
+            name,
+            station_ids as &[String],
+            period.as_str()
+        )
+        .fetch_one(self.db.postgres()?)
+        .await?;
+
+        Ok(row.into())
+    }
+
+    /// Replace a view's contents; None when the user has no such view
+    ///
+    /// Fails with a unique violation when renaming onto another of the user's views.
+    #[instrument(skip(self, station_ids))]
+    pub async fn update_view(
+        &self,
+        user_id: i64,
+        id: i64,
+        name: &str,
+        station_ids: &[String],
+        period: RankingPeriod,
+    ) -> Result<Option<SavedView>, DbError> {
+        let row = sqlx::query_as!(
+            SavedViewRow,
+            r#"
+            UPDATE saved_views
+            SET name = $3, station_ids = $4, period = $5, updated_at = NOW()
+            WHERE user_id = $1 AND id = $2
+            RETURNING id, name, station_ids AS "station_ids: Vec<String>", period,
+                      created_at, updated_at
+            "#,
+            user_id,
+            id,
+            name,
+            station_ids as &[String],
+            period.as_str()
+        )
+        .fetch_optional(self.db.postgres()?)
+        .await?;
+
+        Ok(row.map(SavedView::from))
+    }
+
+    /// Delete a view; false when the user has no such view
+    #[instrument(skip(self))]
+    pub async fn delete_view(&self, user_id: i64, id: i64) -> Result<bool, DbError> {
+        let deleted = sqlx::query!(
+            r#"DELETE FROM saved_views WHERE user_id = $1 AND id = $2"#,
+            user_id,
+            id
+        )
+        .execute(self.db.postgres()?)
+        .await?
+        .rows_affected();
+
+        Ok(deleted > 0)
+    }
+}
@@ -0,0 +1,377 @@
+// Daily and weekly rainfall digests
+//
+// Users subscribe under /api/v1/me/digest with a frequency and an email address, a
+// webhook URL, or both. The digest job checks every DIGEST_INTERVAL_MINUTES (default 60)
+// for subscriptions whose last complete period has not been delivered yet and sends a
+// summary of the user's favorite gauges over it:
+// - Daily digests cover the previous UTC day; weekly digests the previous Monday to
+//   Sunday
+// - Each gauge's total, wettest hour, and peak 1-hour intensity, from the hourly summaries
+// - Threshold crossings that started during the period
+// - Data gaps: hours in the period without a reading from the gauge
+//
+// Webhooks receive the digest as JSON. Email needs DIGEST_SMTP_URL and DIGEST_EMAIL_FROM;
+// without them, subscriptions can only use webhooks. A period whose delivery fails is
+// retried at the next check. Webhook URLs come from users, so only public hosts are
+// accepted and redirects are not followed (see notify).
+
+use chrono::{Datelike, Days, NaiveDate};
+use reqwest::redirect::Policy;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::config::Secret;
+use crate::db::Digest;
+use crate::notify::{self, EmailSender};
+
+/// How often the digest job looks for undelivered periods
+pub const DEFAULT_DIGEST_INTERVAL_MINUTES: u64 = 60;
+
+/// Settings for the digest job
+#[derive(Debug, Clone)]
+pub struct DigestConfig {
+    /// DIGEST_INTERVAL_MINUTES, default 60
+    pub interval_minutes: u64,
+    /// DIGEST_SMTP_URL; may carry credentials. Email digests are refused without it
+    pub smtp_url: Option<Secret>,
+    /// DIGEST_EMAIL_FROM, e.g. `Rain Tracker <rain@example.com>`
+    pub email_from: Option<String>,
+}
+
+impl Default for DigestConfig {
+    fn default() -> Self {
+        Self {
+            interval_minutes: DEFAULT_DIGEST_INTERVAL_MINUTES,
+            smtp_url: None,
+            email_from: None,
+        }
+    }
+}
+
+impl DigestConfig {
+    /// Settings that cannot work, named by environment variable
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.interval_minutes == 0 {
+            problems.push("DIGEST_INTERVAL_MINUTES must be at least 1".into());
+        }
+        if let Some(url) = &self.smtp_url {
+            problems.extend(notify::smtp_problems(
+                "DIGEST_SMTP_URL",
+                url,
+                "DIGEST_EMAIL_FROM",
+                self.email_from.as_deref(),
+            ));
+        }
+        problems
+    }
+}
+
+/// How often a user receives a digest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DigestFrequency {
+    /// The previous UTC day
+    Daily,
+    /// The previous Monday to Sunday (UTC)
+    Weekly,
+}
+
+impl DigestFrequency {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DigestFrequency::Daily => "daily",
+            DigestFrequency::Weekly => "weekly",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "daily" => Some(DigestFrequency::Daily),
+            "weekly" => Some(DigestFrequency::Weekly),
+            _ => None,
+        }
+    }
+
+    /// First and last day (inclusive) of the latest period to end before `today`
+    pub fn last_period(&self, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+        match self {
+            DigestFrequency::Daily => {
+                let day = today - Days::new(1);
+                (day, day)
+            }
+            DigestFrequency::Weekly => {
+                let monday = today - Days::new(today.weekday().num_days_from_monday() as u64);
+                (monday - Days::new(7), monday - Days::new(1))
+            }
+        }
+    }
+}
+
+/// Email subject for a digest
+pub fn email_subject(digest: &Digest) -> String {
+    match digest.frequency {
+        DigestFrequency::Daily => format!("Rainfall digest for {}", digest.first_day),
+        DigestFrequency::Weekly => format!(
+            "Weekly rainfall digest for {} to {}",
+            digest.first_day, digest.last_day
+        ),
+    }
+}
+
+/// Plain-text email body: gauge totals, then threshold crossings, then data gaps
+pub fn email_body(digest: &Digest) -> String {
+    let label = |station_id: &str| {
+        digest
+            .gauges
+            .iter()
+            .find(|g| g.station_id == station_id)
+            .and_then(|g| g.station_name.as_deref())
+            .map(|name| format!("{station_id} {name}"))
+            .unwrap_or_else(|| station_id.to_string())
+    };
+
+    let mut body = if digest.first_day == digest.last_day {
+        format!("Rainfall on {} (UTC)", digest.first_day)
+    } else {
+        format!(
+            "Rainfall from {} to {} (UTC)",
+            digest.first_day, digest.last_day
+        )
+    };
+    if digest.gauges.is_empty() {
+        body.push_str(": you have no favorite gauges to summarize.\n");
+        return body;
+    }
+    body.push_str(&format!(
+        " at your {} favorite gauges:\n\n",
+        digest.gauges.len()
+    ));
+    for gauge in &digest.gauges {
+        body.push_str(&format!(
+            "  {:<32} {:>6.2} in",
+            label(&gauge.station_id),
+            gauge.total_inches
+        ));
+        if let Some(wettest) = gauge.wettest_hour_inches.filter(|inches| *inches > 0.0) {
+            body.push_str(&format!("  (wettest hour {wettest:.2} in)"));
+        }
+        body.push('\n');
+    }
+
+    if !digest.events.is_empty() {
+        body.push_str("\nThreshold crossings:\n");
+        for event in &digest.events {
+            body.push_str(&format!(
+                "  {} reached {:.2} in / 24h at {} (peak {:.2} in)\n",
+                label(&event.station_id),
+                event.threshold_inches,
+                event.crossed_at.format("%Y-%m-%d %H:%M UTC"),
+                event.peak_rainfall_24h_inches
+            ));
+        }
+    }
+
+    let gaps: Vec<_> = digest
+        .gauges
+        .iter()
+        .filter(|g| g.missing_hours > 0)
+        .collect();
+    if !gaps.is_empty() {
+        body.push_str("\nData gaps:\n");
+        for gauge in gaps {
+            body.push_str(&format!(
+                "  {}: no readings for {} of {} hours\n",
+                label(&gauge.station_id),
+                gauge.missing_hours,
+                gauge.missing_hours + gauge.reported_hours
+            ));
+        }
+    }
+    body
+}
+
+/// Delivers digests to a subscriber's webhook and mailbox; the default sends webhooks only
+#[derive(Clone, Default)]
+pub struct DigestSender {
+    email: Option<EmailSender>,
+    /// Skip the public-address check, for webhooks on a local test server
+    private_webhooks: bool,
+}
+
+impl DigestSender {
+    /// Err describes settings that cannot work
+    pub fn from_config(config: &DigestConfig) -> Result<Self, String> {
+        let problems = config.problems();
+        if !problems.is_empty() {
+            return Err(problems.join("; "));
+        }
+
+        let email = match &config.smtp_url {
+            Some(url) => Some(EmailSender::new(
+                url,
+                config.email_from.as_deref().unwrap_or_default(),
+            )?),
+            None => None,
+        };
+        Ok(Self {
+            email,
+            ..Self::default()
+        })
+    }
+
+    /// Also deliver webhooks to loopback and private hosts, e.g. a local test server
+    pub fn with_private_webhooks(mut self) -> Self {
+        self.private_webhooks = true;
+        self
+    }
+
+    /// Whether email digests can be sent
+    pub fn email_enabled(&self) -> bool {
+        self.email.is_some()
+    }
+
+    /// Send `digest` to each given channel; Err lists the channels that failed
+    pub async fn deliver(
+        &self,
+        digest: &Digest,
+        webhook_url: Option<&str>,
+        email: Option<&str>,
+    ) -> Result<(), String> {
+        let mut failures = Vec::new();
+        if let Some(url) = webhook_url {
+            if let Err(e) = self.post(url, digest).await {
+                failures.push(format!("webhook: {e}"));
+            }
+        }
+        if let Some(to) = email {
+            if let Err(e) = self.send_email(to, digest).await {
+                failures.push(format!("email: {e}"));
+            }
+        }
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(failures.join("; "))
+        }
+    }
+
+    /// POST without following redirects, connecting only to the addresses checked by
+    /// `notify::resolve_public`
+    async fn post(&self, url: &str, digest: &Digest) -> Result<(), String> {
+        let mut builder = notify::client_builder().redirect(Policy::none());
+        if !self.private_webhooks {
+            let parsed = reqwest::Url::parse(url).map_err(|e| e.to_string())?;
+            let addrs = notify::resolve_public(&parsed).await?;
+            if let Some(host) = parsed.host_str() {
+                builder = builder.resolve_to_addrs(host, &addrs);
+            }
+        }
+        let client = builder.build().map_err(|e| e.to_string())?;
+        notify::post_json(&client, url, digest).await
+    }
+
+    async fn send_email(&self, to: &str, digest: &Digest) -> Result<(), String> {
+        let sender = self.email.as_ref().ok_or("DIGEST_SMTP_URL is not set")?;
+        let to = notify::parse_mailbox(to)?;
+        sender
+            .send(&[to], email_subject(digest), email_body(digest))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use crate::db::{DigestGauge, GaugeThresholdEvent};
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_last_period() {
+        // 2025-02-12 is a Wednesday
+        assert_eq!(
+            DigestFrequency::Daily.last_period(date(2, 12)),
+            (date(2, 11), date(2, 11))
+        );
+        assert_eq!(
+            DigestFrequency::Weekly.last_period(date(2, 12)),
+            (date(2, 3), date(2, 9))
+        );
+        // On a Monday the week that just ended is complete
+        assert_eq!(
+            DigestFrequency::Weekly.last_period(date(2, 10)),
+            (date(2, 3), date(2, 9))
+        );
+        assert_eq!(
+            DigestFrequency::Daily.last_period(date(3, 1)),
+            (date(2, 28), date(2, 28))
+        );
+    }
+
+    #[test]
+    fn test_email_body_lists_events_and_gaps() {
+        let digest = Digest {
+            frequency: DigestFrequency::Weekly,
+            first_day: date(2, 3),
+            last_day: date(2, 9),
+            gauges: vec![
+                DigestGauge {
+                    station_id: "59700".to_string(),
+                    station_name: Some("Aztec Park".to_string()),
+                    total_inches: 1.24,
+                    wettest_hour_inches: Some(0.4),
+                    max_1h_intensity_in_per_hr: Some(0.52),
+                    reported_hours: 168,
+                    missing_hours: 0,
+                },
+                DigestGauge {
+                    station_id: "11000".to_string(),
+                    station_name: None,
+                    total_inches: 0.0,
+                    wettest_hour_inches: None,
+                    max_1h_intensity_in_per_hr: None,
+                    reported_hours: 156,
+                    missing_hours: 12,
+                },
+            ],
+            events: vec![GaugeThresholdEvent {
+                id: 41,
+                station_id: "59700".to_string(),
+                threshold_inches: 1.0,
+                crossed_at: Utc.with_ymd_and_hms(2025, 2, 4, 6, 15, 0).unwrap(),
+                ended_at: None,
+                rainfall_24h_inches: 1.06,
+                peak_rainfall_24h_inches: 1.18,
+                max_15min_intensity_in_per_hr: None,
+                max_1h_intensity_in_per_hr: None,
+            }],
+            generated_at: Utc.with_ymd_and_hms(2025, 2, 10, 0, 5, 0).unwrap(),
+        };
+
+        assert_eq!(
+            email_subject(&digest),
+            "Weekly rainfall digest for 2025-02-03 to 2025-02-09"
+        );
+        let body = email_body(&digest);
+        assert!(body.starts_with("Rainfall from 2025-02-03 to 2025-02-09 (UTC) at your 2"));
+        assert!(body.contains("(wettest hour 0.40 in)"));
+        assert!(body.contains(
+            "59700 Aztec Park reached 1.00 in / 24h at 2025-02-04 06:15 UTC (peak 1.18 in)"
+        ));
+        assert!(body.contains("11000: no readings for 12 of 168 hours"));
+    }
+
+    #[test]
+    fn test_frequency_round_trip() {
+        for frequency in [DigestFrequency::Daily, DigestFrequency::Weekly] {
+            assert_eq!(DigestFrequency::parse(frequency.as_str()), Some(frequency));
+        }
+        assert_eq!(DigestFrequency::parse("monthly"), None);
+    }
+}
@@ -0,0 +1,247 @@
+// Elevation sampling for gauges missing elevation_ft
+//
+// Two digital elevation model sources can fill the gap:
+// - The USGS 3DEP Elevation Point Query Service (ELEVATION_URL, e.g.
+//   https://epqs.nationalmap.gov/v1/json), asked for feet at each gauge's coordinates.
+// - An offline DEM grid (ELEVATION_DEM_FILE) in ESRI ASCII format (`.asc`, as exported by
+//   GDAL or the National Map downloader) with cell values in meters, sampled at the cell
+//   containing the gauge.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Deserialize;
+use tracing::{debug, instrument};
+
+use crate::grid::AsciiGrid;
+use crate::politeness::{PoliteError, Politeness, DEFAULT_USER_AGENT};
+
+/// How often the elevation job looks for gauges to sample
+pub const DEFAULT_ELEVATION_INTERVAL_MINUTES: u64 = 1440;
+
+/// Gauges sampled per run
+pub const DEFAULT_ELEVATION_BATCH_SIZE: usize = 50;
+
+/// `elevation_source` of elevations sampled from the 3DEP point query service
+pub const USGS_3DEP_SOURCE: &str = "usgs-3dep";
+
+/// Value EPQS returns for points outside its coverage
+const EPQS_NO_DATA: f64 = -1_000_000.0;
+
+const FEET_PER_METER: f64 = 3.280_84;
+
+/// Spacing between requests to the point query service
+const REMOTE_REQUEST_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Debug, thiserror::Error)]
+pub enum ElevationError {
+    #[error("HTTP request failed: {0}")]
+    Request(#[from] reqwest::Error),
+
+    #[error("Disallowed by robots.txt: {0}")]
+    Disallowed(String),
+
+    #[error("Paused after repeated failures from {0}")]
+    CircuitOpen(String),
+
+    #[error("Elevation service returned HTTP {0}")]
+    Status(u16),
+
+    #[error("Invalid elevation service response: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Failed to read DEM file {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Invalid DEM file {}: {message}", path.display())]
+    Grid { path: PathBuf, message: String },
+}
+
+impl From<PoliteError> for ElevationError {
+    fn from(e: PoliteError) -> Self {
+        match e {
+            PoliteError::Request(e) => ElevationError::Request(e),
+            PoliteError::Disallowed(url) => ElevationError::Disallowed(url),
+            PoliteError::CircuitOpen(host) => ElevationError::CircuitOpen(host),
+        }
+    }
+}
+
+/// Where the elevation job samples elevations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ElevationProvider {
+    /// USGS 3DEP Elevation Point Query Service (or a compatible endpoint)
+    Epqs { url: String },
+    /// Offline ESRI ASCII grid in meters
+    Dem { path: PathBuf },
+}
+
+/// Settings for the optional elevation job (disabled unless a provider is configured)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElevationConfig {
+    pub provider: ElevationProvider,
+    /// ELEVATION_INTERVAL_MINUTES, default 1440
+    pub interval_minutes: u64,
+    /// ELEVATION_BATCH_SIZE, default 50
+    pub batch_size: usize,
+}
+
+/// Elevation sampler for one configured provider
+#[derive(Clone)]
+pub enum ElevationSampler {
+    Epqs(EpqsClient),
+    Dem(Arc<AsciiGrid>),
+}
+
+impl ElevationSampler {
+    /// Build the sampler, loading the grid for the offline provider
+    pub fn from_provider(provider: &ElevationProvider) -> Result<Self, ElevationError> {
+        match provider {
+            ElevationProvider::Epqs { url } => Ok(Self::Epqs(EpqsClient::new(url))),
+            ElevationProvider::Dem { path } => Ok(Self::Dem(Arc::new(load_dem(path)?))),
+        }
+    }
+
+    /// Send remote samples through the shared `politeness`
+    pub fn with_politeness(self, politeness: &Politeness) -> Self {
+        match self {
+            Self::Epqs(client) => Self::Epqs(client.with_politeness(politeness.clone())),
+            dem => dem,
+        }
+    }
+
+    /// Value recorded in `gauges.elevation_source`
+    pub fn source(&self) -> String {
+        match self {
+            Self::Epqs(_) => USGS_3DEP_SOURCE.to_string(),
+            Self::Dem(grid) => format!("dem:{}", grid.name()),
+        }
+    }
+
+    /// Minimum spacing between samples, for providers with a usage limit
+    pub fn request_interval(&self) -> Option<Duration> {
+        match self {
+            Self::Epqs(_) => Some(REMOTE_REQUEST_INTERVAL),
+            Self::Dem(_) => None,
+        }
+    }
+
+    /// Elevation in whole feet; Ok(None) outside the model's coverage
+    pub async fn sample(
+        &self,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<Option<i32>, ElevationError> {
+        let feet = match self {
+            Self::Epqs(client) => client.sample_feet(latitude, longitude).await?,
+            Self::Dem(grid) => grid
+                .sample(latitude, longitude)
+                .map(|meters| meters * FEET_PER_METER),
+        };
+        Ok(feet.map(|feet| feet.round() as i32))
+    }
+}
+
+/// Load an ESRI ASCII elevation grid in meters
+fn load_dem(path: &Path) -> Result<AsciiGrid, ElevationError> {
+    let text = std::fs::read_to_string(path).map_err(|source| ElevationError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    AsciiGrid::parse(name, &text).map_err(|message| ElevationError::Grid {
+        path: path.to_path_buf(),
+        message,
+    })
+}
+
+/// Client for the USGS Elevation Point Query Service
+#[derive(Clone)]
+pub struct EpqsClient {
+    client: reqwest::Client,
+    url: String,
+    politeness: Politeness,
+}
+
+#[derive(Debug, Deserialize)]
+struct EpqsResponse {
+    /// A number, or a numeric string in older versions of the service
+    value: serde_json::Value,
+}
+
+impl EpqsClient {
+    pub fn new(url: &str) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(30))
+                .user_agent(DEFAULT_USER_AGENT)
+                .build()
+                .expect("Failed to create HTTP client"),
+            url: url.to_string(),
+            politeness: Politeness::default(),
+        }
+    }
+
+    /// Send requests with the shared User-Agent, robots.txt checks, and host delay
+    pub fn with_politeness(mut self, politeness: Politeness) -> Self {
+        self.politeness = politeness;
+        self
+    }
+
+    #[instrument(skip(self))]
+    pub async fn sample_feet(
+        &self,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<Option<f64>, ElevationError> {
+        let request = self.client.get(&self.url).query(&[
+            ("x", longitude.to_string().as_str()),
+            ("y", latitude.to_string().as_str()),
+            ("wkid", "4326"),
+            ("units", "Feet"),
+            ("includeDate", "false"),
+        ]);
+        let response = self.politeness.send(&self.client, request).await?;
+        if !response.status().is_success() {
+            return Err(ElevationError::Status(response.status().as_u16()));
+        }
+
+        let body = response.text().await?;
+        debug!("Elevation service response: {} bytes", body.len());
+        parse_epqs(&body)
+    }
+}
+
+/// Read an EPQS JSON response
+fn parse_epqs(body: &str) -> Result<Option<f64>, ElevationError> {
+    let response: EpqsResponse = serde_json::from_str(body)?;
+    let value = match &response.value {
+        serde_json::Value::Number(n) => n.as_f64(),
+        serde_json::Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    };
+    Ok(value.filter(|feet| feet.is_finite() && *feet > EPQS_NO_DATA))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_epqs() {
+        assert_eq!(
+            parse_epqs(r#"{"location": {"x": -112.0, "y": 33.5}, "value": 1139.9}"#).unwrap(),
+            Some(1139.9)
+        );
+        assert_eq!(parse_epqs(r#"{"value": "1139.9"}"#).unwrap(), Some(1139.9));
+        assert_eq!(parse_epqs(r#"{"value": -1000000}"#).unwrap(), None);
+    }
+}
@@ -0,0 +1,302 @@
+// Crate-level error for the service and historical-import binaries
+//
+// Every failure that ends a process is converted into `Error`, which keeps the original
+// error as its source and sorts it into an `ErrorClass`. Each class exits with its own
+// status so wrappers, cron jobs, and CI can tell a database outage from a changed MCFCD
+// file layout without parsing messages:
+//
+//   1  failure: a command finished but reported failed items, or an unexpected error
+//   2  usage: invalid arguments or options (also clap's own argument errors)
+//   3  config: missing or invalid configuration
+//   4  database: unreachable, failing, or migrations pending
+//   5  upstream: MCFCD or a third-party API unreachable, erroring, or refusing requests
+//   6  data: an input file, download, or page that could not be parsed
+//   7  io: reading or writing a local file failed
+//   8  cancelled: a confirmation prompt was declined
+
+use std::process::ExitCode;
+
+use serde_json::error::Category;
+
+use crate::db::DbError;
+use crate::fetch_error::FetchError;
+use crate::fopr::FoprParseError;
+use crate::gis::GisError;
+use crate::importers::downloader::DownloadError;
+use crate::importers::excel_importer::ExcelImportError;
+use crate::payload_archive::ArchiveError;
+use crate::radar::RadarError;
+use crate::services::backup_service::BackupError;
+use crate::services::bootstrap_service::DiscoveryError;
+use crate::services::gauge_edit_service::GaugeEditError;
+use crate::services::historical_import_service::HistoricalImportError;
+use crate::services::reading_service::ReadingQueryError;
+use crate::services::zone_service::ZoneImportError;
+#[cfg(feature = "sqlite")]
+use crate::snapshot::SnapshotError;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Broad kind of failure, which decides the process exit status
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    Failure,
+    Usage,
+    Config,
+    Database,
+    Upstream,
+    Data,
+    Io,
+    Cancelled,
+}
+
+impl ErrorClass {
+    pub fn exit_status(self) -> u8 {
+        match self {
+            ErrorClass::Failure => 1,
+            ErrorClass::Usage => 2,
+            ErrorClass::Config => 3,
+            ErrorClass::Database => 4,
+            ErrorClass::Upstream => 5,
+            ErrorClass::Data => 6,
+            ErrorClass::Io => 7,
+            ErrorClass::Cancelled => 8,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorClass::Failure => "failure",
+            ErrorClass::Usage => "usage",
+            ErrorClass::Config => "config",
+            ErrorClass::Database => "database",
+            ErrorClass::Upstream => "upstream",
+            ErrorClass::Data => "data",
+            ErrorClass::Io => "io",
+            ErrorClass::Cancelled => "cancelled",
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Arguments that cannot work together, or a command run before its prerequisites
+    #[error("{0}")]
+    Usage(String),
+
+    #[error("{0} cancelled")]
+    Cancelled(&'static str),
+
+    #[error("Configuration error: {0}")]
+    Config(String),
+
+    /// Local input, such as a checkpoint file, that could not be parsed
+    #[error("{0}")]
+    Data(String),
+
+    #[error(transparent)]
+    Database(#[from] DbError),
+
+    #[error(transparent)]
+    Fetch(#[from] FetchError),
+
+    #[error(transparent)]
+    Download(#[from] DownloadError),
+
+    #[error(transparent)]
+    Import(#[from] HistoricalImportError),
+
+    #[error(transparent)]
+    Excel(#[from] ExcelImportError),
+
+    #[error(transparent)]
+    Fopr(#[from] FoprParseError),
+
+    #[error(transparent)]
+    Discovery(#[from] DiscoveryError),
+
+    #[error(transparent)]
+    Backup(#[from] BackupError),
+
+    #[error(transparent)]
+    GaugeEdit(#[from] GaugeEditError),
+
+    #[error(transparent)]
+    Radar(#[from] RadarError),
+
+    #[error(transparent)]
+    Zones(#[from] ZoneImportError),
+
+    #[error(transparent)]
+    Gis(#[from] GisError),
+
+    #[error(transparent)]
+    ReadingQuery(#[from] ReadingQueryError),
+
+    #[error(transparent)]
+    Archive(#[from] ArchiveError),
+
+    #[cfg(feature = "sqlite")]
+    #[error(transparent)]
+    Snapshot(#[from] SnapshotError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// A background task panicked or was cancelled
+    #[error("{0}")]
+    Internal(String),
+}
+
+fn fetch_class(e: &FetchError) -> ErrorClass {
+    match e {
+        FetchError::LayoutChanged(_) | FetchError::Value { .. } => ErrorClass::Data,
+        FetchError::Network(_)
+        | FetchError::HttpStatus { .. }
+        | FetchError::Disallowed(_)
+        | FetchError::CircuitOpen(_) => ErrorClass::Upstream,
+    }
+}
+
+fn json_class(e: &serde_json::Error) -> ErrorClass {
+    match e.classify() {
+        Category::Io => ErrorClass::Io,
+        Category::Syntax | Category::Data | Category::Eof => ErrorClass::Data,
+    }
+}
+
+impl Error {
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            Error::Usage(_) => ErrorClass::Usage,
+            Error::Cancelled(_) => ErrorClass::Cancelled,
+            Error::Config(_) => ErrorClass::Config,
+            Error::Data(_) => ErrorClass::Data,
+            Error::Database(_) => ErrorClass::Database,
+            Error::Fetch(e) => fetch_class(e),
+            Error::Download(DownloadError::InvalidUrl(_)) => ErrorClass::Config,
+            Error::Download(_) => ErrorClass::Upstream,
+            Error::Import(e) => match e {
+                HistoricalImportError::Download(_) | HistoricalImportError::NotPublished(_) => {
+                    ErrorClass::Upstream
+                }
+                HistoricalImportError::Parse(_) => ErrorClass::Data,
+                HistoricalImportError::Database(_) => ErrorClass::Database,
+                HistoricalImportError::Io(_) => ErrorClass::Io,
+            },
+            Error::Excel(_) | Error::Fopr(_) => ErrorClass::Data,
+            Error::Discovery(e) => match e {
+                DiscoveryError::Fetch(e) => fetch_class(e),
+                DiscoveryError::Database(_) => ErrorClass::Database,
+            },
+            Error::Backup(e) => match e {
+                BackupError::Io(_) => ErrorClass::Io,
+                BackupError::Json(e) => json_class(e),
+                BackupError::Database(_) | BackupError::SchemaTooNew { .. } => ErrorClass::Database,
+                BackupError::MissingManifest | BackupError::UnsupportedFormat(_) => {
+                    ErrorClass::Data
+                }
+                BackupError::NotEmpty(_) => ErrorClass::Usage,
+            },
+            Error::GaugeEdit(e) => match e {
+                GaugeEditError::Csv(_) | GaugeEditError::Invalid(_) => ErrorClass::Data,
+                GaugeEditError::Io(_) => ErrorClass::Io,
+                GaugeEditError::Database(_) => ErrorClass::Database,
+            },
+            Error::Radar(e) => match e {
+                RadarError::Io { .. } => ErrorClass::Io,
+                RadarError::Grid { .. } => ErrorClass::Data,
+                RadarError::NoGrids => ErrorClass::Usage,
+            },
+            Error::Zones(e) => match e {
+                ZoneImportError::Zones(_) => ErrorClass::Data,
+                ZoneImportError::Database(_) => ErrorClass::Database,
+            },
+            Error::Gis(e) => match e {
+                GisError::Io(_)
+                | GisError::Shapefile(_)
+                | GisError::Attributes(_)
+                | GisError::GeoPackage(_) => ErrorClass::Io,
+                GisError::Unsupported => ErrorClass::Usage,
+                GisError::Geometry { .. } => ErrorClass::Data,
+                GisError::Database(_) => ErrorClass::Database,
+            },
+            Error::ReadingQuery(e) => match e {
+                ReadingQueryError::SpanTooLarge { .. } | ReadingQueryError::TooManyRows { .. } => {
+                    ErrorClass::Usage
+                }
+                ReadingQueryError::Database(_) => ErrorClass::Database,
+            },
+            Error::Archive(e) => match e {
+                ArchiveError::Io(_) => ErrorClass::Io,
+                ArchiveError::Metadata(_) | ArchiveError::Corrupt(_) => ErrorClass::Data,
+            },
+            #[cfg(feature = "sqlite")]
+            Error::Snapshot(e) => match e {
+                SnapshotError::Csv { .. } | SnapshotError::UnknownStation(_) => ErrorClass::Data,
+                SnapshotError::Database(_) => ErrorClass::Database,
+            },
+            Error::Io(_) => ErrorClass::Io,
+            Error::Json(e) => json_class(e),
+            Error::Internal(_) => ErrorClass::Failure,
+        }
+    }
+
+    /// Process exit status for this error's class
+    pub fn exit_code(&self) -> ExitCode {
+        ExitCode::from(self.class().exit_status())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_errors_exit_with_their_class() {
+        let cases = [
+            (Error::Usage("--start must be before --end".into()), 2),
+            (Error::Config("DATABASE_URL must be set".into()), 3),
+            (
+                Error::Database(DbError::PendingMigrations {
+                    count: 1,
+                    latest: 20250212000000,
+                }),
+                4,
+            ),
+            (Error::Download(DownloadError::ServerError("503".into())), 5),
+            (
+                Error::Fetch(FetchError::LayoutChanged("no data table".into())),
+                6,
+            ),
+            (
+                Error::Import(HistoricalImportError::Parse("bad sheet".into())),
+                6,
+            ),
+            (Error::Io(std::io::Error::other("disk full")), 7),
+            (Error::Cancelled("Import"), 8),
+            (Error::Internal("task panicked".into()), 1),
+        ];
+        for (error, status) in cases {
+            assert_eq!(error.class().exit_status(), status, "{error}");
+        }
+    }
+
+    #[test]
+    fn test_sources_classify_through_wrappers() {
+        let discovery = Error::Discovery(DiscoveryError::Fetch(FetchError::CircuitOpen(
+            "alert.fcd.maricopa.gov".into(),
+        )));
+        assert_eq!(discovery.class(), ErrorClass::Upstream);
+        assert_eq!(
+            discovery.to_string(),
+            "Failed to fetch gauge list: Paused after repeated failures from alert.fcd.maricopa.gov"
+        );
+
+        let json = serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+        assert_eq!(Error::from(json).class(), ErrorClass::Data);
+    }
+}
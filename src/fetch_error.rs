@@ -1,11 +1,214 @@
+// Errors from scraping the MCFCD gauge page and gauge list
+//
+// Each error says whether trying again can help. Network failures, 5xx, 429, and 408
+// responses are transient: the schedulers retry them a few times before giving up until
+// the next run, and the circuit breaker counts them against the host. A changed page
+// layout, an unparseable value, a robots.txt refusal, or any other HTTP status fails the
+// same way on every attempt and is reported at once. A host paused by its breaker is not
+// retried either; the breaker decides when the next request goes out.
+
+use std::future::Future;
+use std::time::Duration;
+
+use reqwest::{Response, StatusCode};
+use tracing::warn;
+
+use crate::politeness::PoliteError;
+
 #[derive(Debug, thiserror::Error)]
 pub enum FetchError {
-    #[error("HTTP request failed: {0}")]
-    Request(#[from] reqwest::Error),
-    #[error("Failed to parse HTML data")]
-    ParseError,
-    #[error("Failed to parse date/time: {0}")]
-    DateTimeError(String),
-    #[error("Failed to parse number: {0}")]
-    NumberError(String),
+    #[error("Network error: {0}")]
+    Network(#[source] reqwest::Error),
+    #[error("HTTP {status} from {url}")]
+    HttpStatus { status: StatusCode, url: String },
+    #[error("Disallowed by robots.txt: {0}")]
+    Disallowed(String),
+    #[error("Paused after repeated failures from {0}")]
+    CircuitOpen(String),
+    #[error("Page layout changed: {0}")]
+    LayoutChanged(String),
+    #[error("Line {line}: {source}")]
+    Value {
+        line: usize,
+        #[source]
+        source: ValueError,
+    },
+}
+
+/// A field that could not be parsed, before the line it came from is known
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("invalid {field} {value:?}: {reason}")]
+pub struct ValueError {
+    pub field: &'static str,
+    pub value: String,
+    pub reason: String,
+}
+
+impl ValueError {
+    pub fn new(field: &'static str, value: &str, reason: impl ToString) -> Self {
+        Self {
+            field,
+            value: value.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    /// Attach the 1-based line (or table row) of the payload the value came from
+    pub fn at_line(self, line: usize) -> FetchError {
+        FetchError::Value { line, source: self }
+    }
+}
+
+/// Whether a response with `status` may succeed if the request is sent again
+pub fn is_transient_status(status: StatusCode) -> bool {
+    status.is_server_error()
+        || status == StatusCode::TOO_MANY_REQUESTS
+        || status == StatusCode::REQUEST_TIMEOUT
+}
+
+impl FetchError {
+    /// Whether sending the same request again may succeed
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            // A request that could not be built fails the same way every time
+            FetchError::Network(e) => !e.is_builder(),
+            FetchError::HttpStatus { status, .. } => is_transient_status(*status),
+            FetchError::Disallowed(_)
+            | FetchError::CircuitOpen(_)
+            | FetchError::LayoutChanged(_)
+            | FetchError::Value { .. } => false,
+        }
+    }
+
+    /// Short name of the failure class, for logs
+    pub fn kind(&self) -> &'static str {
+        match self {
+            FetchError::Network(_) => "network",
+            FetchError::HttpStatus { .. } => "http_status",
+            FetchError::Disallowed(_) => "disallowed",
+            FetchError::CircuitOpen(_) => "circuit_open",
+            FetchError::LayoutChanged(_) => "layout_changed",
+            FetchError::Value { .. } => "value",
+        }
+    }
+
+    /// `response` if its status is a success, otherwise the status as an error
+    pub fn check_status(response: Response) -> Result<Response, FetchError> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        Err(FetchError::HttpStatus {
+            status,
+            url: response.url().to_string(),
+        })
+    }
+}
+
+impl From<reqwest::Error> for FetchError {
+    fn from(e: reqwest::Error) -> Self {
+        FetchError::Network(e)
+    }
+}
+
+impl From<PoliteError> for FetchError {
+    fn from(e: PoliteError) -> Self {
+        match e {
+            PoliteError::Request(e) => FetchError::Network(e),
+            PoliteError::Disallowed(url) => FetchError::Disallowed(url),
+            PoliteError::CircuitOpen(host) => FetchError::CircuitOpen(host),
+        }
+    }
+}
+
+/// Run `fetch` up to `attempts` times while it fails with a retryable error, waiting
+/// `delay` before the first retry and doubling it before each one after
+pub async fn retry_transient<T, F, Fut>(
+    attempts: u32,
+    delay: Duration,
+    mut fetch: F,
+) -> Result<T, FetchError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, FetchError>>,
+{
+    let mut delay = delay;
+    let mut attempt = 1;
+    loop {
+        match fetch().await {
+            Err(e) if e.is_retryable() && attempt < attempts => {
+                warn!(
+                    error = %e,
+                    kind = e.kind(),
+                    attempt,
+                    retry_in_secs = delay.as_secs(),
+                    "Transient fetch failure; retrying"
+                );
+                tokio::time::sleep(delay).await;
+                delay = delay.saturating_mul(2);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn status(status: StatusCode) -> FetchError {
+        FetchError::HttpStatus {
+            status,
+            url: "https://alert.fcd.maricopa.gov/".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_retryability() {
+        assert!(status(StatusCode::SERVICE_UNAVAILABLE).is_retryable());
+        assert!(status(StatusCode::TOO_MANY_REQUESTS).is_retryable());
+        assert!(!status(StatusCode::NOT_FOUND).is_retryable());
+        assert!(!FetchError::LayoutChanged("no data table".to_string()).is_retryable());
+        assert!(!FetchError::CircuitOpen("alert.fcd.maricopa.gov".to_string()).is_retryable());
+        assert!(!ValueError::new("time", "25:00:00", "out of range")
+            .at_line(3)
+            .is_retryable());
+    }
+
+    #[test]
+    fn test_value_error_names_line_and_field() {
+        let e = ValueError::new("incremental inches", "0.O4", "invalid float literal").at_line(7);
+        assert_eq!(
+            e.to_string(),
+            "Line 7: invalid incremental inches \"0.O4\": invalid float literal"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retry_transient_stops_on_permanent_error() {
+        let calls = AtomicU32::new(0);
+        let result: Result<(), _> = retry_transient(3, Duration::ZERO, || async {
+            match calls.fetch_add(1, Ordering::SeqCst) {
+                0 => Err(status(StatusCode::BAD_GATEWAY)),
+                _ => Err(FetchError::LayoutChanged("no data table".to_string())),
+            }
+        })
+        .await;
+        assert!(matches!(result, Err(FetchError::LayoutChanged(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_retry_transient_gives_up_after_attempts() {
+        let calls = AtomicU32::new(0);
+        let result: Result<(), _> = retry_transient(3, Duration::ZERO, || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(status(StatusCode::SERVICE_UNAVAILABLE))
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
 }
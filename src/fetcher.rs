@@ -1,21 +1,44 @@
 use chrono::{DateTime, NaiveDateTime, Utc};
 use scraper::{Html, Selector};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use tracing::{debug, error, instrument, warn};
 
-use crate::fetch_error::FetchError;
+use crate::fetch_error::{FetchError, ValueError};
+use crate::ingest_guard::{self, IngestLimits, QuarantineReason, QuarantinedReading, Screened};
+use crate::payload_archive::{PayloadArchive, PayloadSource};
+use crate::politeness::Politeness;
+use crate::units::Inches;
 
-#[derive(Debug, Clone, Deserialize)]
+/// Gauge the live fetcher reads; its rows are stored under the `rain_readings.station_id`
+/// column default
+pub const LIVE_STATION_ID: &str = "59700";
+
+/// `data_source` of live readings (the `rain_readings.data_source` column default)
+pub const LIVE_DATA_SOURCE: &str = "live_scrape";
+
+/// Hours a stored live reading stays open to correction by later scrapes
+pub const DEFAULT_LATE_DATA_WINDOW_HOURS: u32 = 24;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RainReading {
     pub reading_datetime: DateTime<Utc>,
-    pub cumulative_inches: f64,
-    pub incremental_inches: f64,
+    pub cumulative_inches: Inches,
+    pub incremental_inches: Inches,
+}
+
+/// A data row that parsed, before the cumulative check
+enum ParsedRow {
+    Reading(RainReading),
+    Quarantined(QuarantinedReading),
 }
 
 #[derive(Clone)]
 pub struct RainGaugeFetcher {
     client: reqwest::Client,
     url: String,
+    limits: IngestLimits,
+    politeness: Politeness,
+    archive: Option<PayloadArchive>,
 }
 
 impl RainGaugeFetcher {
@@ -23,23 +46,54 @@ impl RainGaugeFetcher {
         Self {
             client: reqwest::Client::new(),
             url,
+            limits: IngestLimits::default(),
+            politeness: Politeness::default(),
+            archive: None,
         }
     }
 
+    pub fn with_limits(mut self, limits: IngestLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Send requests with the shared User-Agent, robots.txt checks, and host delay
+    pub fn with_politeness(mut self, politeness: Politeness) -> Self {
+        self.politeness = politeness;
+        self
+    }
+
+    /// Keep each fetched page in `archive` for replay
+    pub fn with_archive(mut self, archive: PayloadArchive) -> Self {
+        self.archive = Some(archive);
+        self
+    }
+
+    /// Readings from the gauge page, oldest first, and the rows the ingest guards rejected
     #[instrument(skip(self), fields(url = %self.url))]
-    pub async fn fetch_readings(&self) -> Result<Vec<RainReading>, FetchError> {
+    pub async fn fetch_readings(&self) -> Result<Screened<RainReading>, FetchError> {
         debug!("Sending HTTP request to rain gauge");
-        let response = self.client.get(&self.url).send().await?;
+        let response = self
+            .politeness
+            .send(&self.client, self.client.get(&self.url))
+            .await?;
         debug!("Received HTTP response with status: {}", response.status());
+        let response = FetchError::check_status(response)?;
 
         let html = response.text().await?;
         debug!("Retrieved HTML content, size: {} bytes", html.len());
+        if let Some(archive) = &self.archive {
+            archive
+                .record(PayloadSource::Readings, &self.url, None, &html)
+                .await;
+        }
 
         self.parse_html(&html)
     }
 
+    /// Readings from a gauge page body, e.g. one replayed from the payload archive
     #[instrument(skip(self, html), fields(html_size = html.len()))]
-    fn parse_html(&self, html: &str) -> Result<Vec<RainReading>, FetchError> {
+    pub fn parse_html(&self, html: &str) -> Result<Screened<RainReading>, FetchError> {
         debug!("Parsing HTML document");
         let document = Html::parse_document(html);
         let pre_selector = Selector::parse("pre").unwrap();
@@ -57,18 +111,21 @@ impl RainGaugeFetcher {
                     "HTML preview (first 500 chars): {}",
                     &html.chars().take(500).collect::<String>()
                 );
-                FetchError::ParseError
+                FetchError::LayoutChanged(
+                    "no <pre> block with Date, Time, and inches columns".to_string(),
+                )
             })?;
 
         debug!("Found data PRE element");
         let pre_text = pre_element.text().collect::<String>();
 
         let mut readings = Vec::new();
+        let mut quarantined = Vec::new();
         let mut skipped_rows = 0;
         let mut row_count = 0;
 
         // Parse each line of the PRE content
-        for line in pre_text.lines() {
+        for (line_idx, line) in pre_text.lines().enumerate() {
             let trimmed = line.trim();
 
             // Skip empty lines and header lines
@@ -99,14 +156,26 @@ impl RainGaugeFetcher {
                 );
 
                 match self.parse_reading(date_str, time_str, cumulative_str, incremental_str) {
-                    Ok(reading) => {
+                    Ok(ParsedRow::Reading(reading)) => {
                         debug!("Successfully parsed row {}", row_count);
                         readings.push(reading);
                     }
+                    Ok(ParsedRow::Quarantined(reading)) => {
+                        warn!(
+                            "Quarantining row {}: {} (cumulative='{}', incremental='{}')",
+                            row_count, reading.reason, cumulative_str, incremental_str
+                        );
+                        quarantined.push(reading);
+                    }
                     Err(e) => {
                         warn!(
                             "Failed to parse row {}: {} (date='{}', time='{}', cumulative='{}', incremental='{}')",
-                            row_count, e, date_str, time_str, cumulative_str, incremental_str
+                            row_count,
+                            e.at_line(line_idx + 1),
+                            date_str,
+                            time_str,
+                            cumulative_str,
+                            incremental_str
                         );
                         skipped_rows += 1;
                     }
@@ -133,7 +202,15 @@ impl RainGaugeFetcher {
             row_count
         );
 
-        Ok(readings)
+        let mut screened = ingest_guard::screen_cumulative(LIVE_STATION_ID, readings);
+        if !screened.quarantined.is_empty() {
+            warn!(
+                "Quarantined {} readings whose cumulative total decreased within a day",
+                screened.quarantined.len()
+            );
+        }
+        screened.quarantined.extend(quarantined);
+        Ok(screened)
     }
 
     fn parse_reading(
@@ -142,29 +219,57 @@ impl RainGaugeFetcher {
         time_str: &str,
         cumulative_str: &str,
         incremental_str: &str,
-    ) -> Result<RainReading, FetchError> {
+    ) -> Result<ParsedRow, ValueError> {
         let datetime_str = format!("{date_str} {time_str}");
         let naive_dt = NaiveDateTime::parse_from_str(&datetime_str, "%m/%d/%Y %H:%M:%S")
-            .map_err(|e| FetchError::DateTimeError(e.to_string()))?;
+            .map_err(|e| ValueError::new("date/time", &datetime_str, e))?;
 
         let reading_datetime = DateTime::<Utc>::from_naive_utc_and_offset(naive_dt, Utc);
 
-        let cumulative_inches = cumulative_str
-            .parse::<f64>()
-            .map_err(|e| FetchError::NumberError(e.to_string()))?;
-
-        let incremental_inches = incremental_str
-            .parse::<f64>()
-            .map_err(|e| FetchError::NumberError(e.to_string()))?;
-
-        Ok(RainReading {
-            reading_datetime,
-            cumulative_inches,
-            incremental_inches,
+        let cumulative = parse_number("cumulative inches", cumulative_str)?;
+        let incremental = parse_number("incremental inches", incremental_str)?;
+
+        // The cumulative total runs all water year, so only the increment has a maximum
+        let checked = Inches::new(cumulative)
+            .map_err(|_| QuarantineReason::NegativeRainfall)
+            .and_then(|cumulative_inches| {
+                Ok(RainReading {
+                    reading_datetime,
+                    cumulative_inches,
+                    incremental_inches: self.limits.check_amount(incremental)?,
+                })
+            });
+
+        Ok(match checked {
+            Ok(reading) => ParsedRow::Reading(reading),
+            Err(reason) => ParsedRow::Quarantined(QuarantinedReading {
+                station_id: LIVE_STATION_ID.to_string(),
+                reading_datetime,
+                cumulative_inches: Some(cumulative),
+                incremental_inches: incremental,
+                reason,
+            }),
         })
     }
 }
 
+/// Parse a number, rejecting the "NaN"/"inf" spellings f64 parsing accepts
+fn parse_number(field: &'static str, value: &str) -> Result<f64, ValueError> {
+    let number = value
+        .parse::<f64>()
+        .map_err(|e| ValueError::new(field, value, e))?;
+    if number.is_finite() {
+        Ok(number)
+    } else {
+        Err(ValueError::new(field, value, "not finite"))
+    }
+}
+
+/// Parse a rainfall amount, rejecting negatives and non-finite values
+pub(crate) fn parse_inches(field: &'static str, value: &str) -> Result<Inches, ValueError> {
+    Inches::new(parse_number(field, value)?).map_err(|e| ValueError::new(field, value, e))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -173,11 +278,45 @@ mod tests {
     fn test_parse_reading() {
         let fetcher = RainGaugeFetcher::new("".to_string());
         let result = fetcher.parse_reading("10/14/2025", "06:00:00", "1.85", "0.00");
-        assert!(result.is_ok());
 
-        let reading = result.unwrap();
-        assert_eq!(reading.cumulative_inches, 1.85);
-        assert_eq!(reading.incremental_inches, 0.0);
+        let Ok(ParsedRow::Reading(reading)) = result else {
+            panic!("expected a reading");
+        };
+        assert_eq!(reading.cumulative_inches.value(), 1.85);
+        assert_eq!(reading.incremental_inches.value(), 0.0);
+    }
+
+    #[test]
+    fn test_parse_reading_quarantines_negative_rainfall() {
+        let fetcher = RainGaugeFetcher::new("".to_string());
+        let result = fetcher.parse_reading("10/14/2025", "06:00:00", "1.85", "-0.04");
+
+        let Ok(ParsedRow::Quarantined(reading)) = result else {
+            panic!("expected a quarantined reading");
+        };
+        assert_eq!(reading.reason, QuarantineReason::NegativeRainfall);
+        assert_eq!(reading.station_id, LIVE_STATION_ID);
+        assert_eq!(reading.cumulative_inches, Some(1.85));
+        assert_eq!(reading.incremental_inches, -0.04);
+    }
+
+    #[test]
+    fn test_parse_reading_quarantines_amount_over_limit() {
+        let fetcher = RainGaugeFetcher::new("".to_string()).with_limits(IngestLimits {
+            max_reading_inches: 2.0,
+        });
+        let result = fetcher.parse_reading("10/14/2025", "06:00:00", "9.85", "8.00");
+
+        assert!(matches!(
+            result,
+            Ok(ParsedRow::Quarantined(QuarantinedReading {
+                reason: QuarantineReason::ExceedsMaximum,
+                ..
+            }))
+        ));
+        assert!(fetcher
+            .parse_reading("10/14/2025", "06:00:00", "NaN", "0.00")
+            .is_err());
     }
 
     #[test]
@@ -201,12 +340,13 @@ Date       Time      inches   inches
         let result = fetcher.parse_html(html);
         assert!(result.is_ok());
 
-        let readings = result.unwrap();
+        // Oldest first
+        let readings = result.unwrap().accepted;
         assert_eq!(readings.len(), 4);
-        assert_eq!(readings[0].cumulative_inches, 1.85);
-        assert_eq!(readings[0].incremental_inches, 0.00);
-        assert_eq!(readings[3].cumulative_inches, 1.81);
-        assert_eq!(readings[3].incremental_inches, 0.04);
+        assert_eq!(readings[0].cumulative_inches.value(), 1.81);
+        assert_eq!(readings[0].incremental_inches.value(), 0.04);
+        assert_eq!(readings[3].cumulative_inches.value(), 1.85);
+        assert_eq!(readings[3].incremental_inches.value(), 0.00);
     }
 
     #[test]
@@ -229,10 +369,36 @@ Date       Time      inches   inches
         let result = fetcher.parse_html(html);
         assert!(result.is_ok());
 
-        let readings = result.unwrap();
+        let readings = result.unwrap().accepted;
         assert_eq!(readings.len(), 2);
     }
 
+    #[test]
+    fn test_parse_html_quarantines_bad_rows() {
+        let html = r#"
+            <PRE>
+Date       Time      inches   inches
+10/14/2025 12:00:00    0.20     0.00
+10/14/2025 09:00:00    1.85    -0.04
+10/14/2025 06:00:00    1.85     0.00
+            </PRE>
+        "#;
+
+        let fetcher = RainGaugeFetcher::new("".to_string());
+        let screened = fetcher.parse_html(html).unwrap();
+
+        assert_eq!(screened.accepted.len(), 1);
+        let reasons: Vec<QuarantineReason> =
+            screened.quarantined.iter().map(|q| q.reason).collect();
+        assert_eq!(
+            reasons,
+            [
+                QuarantineReason::CumulativeDecrease,
+                QuarantineReason::NegativeRainfall
+            ]
+        );
+    }
+
     #[test]
     fn test_parse_html_no_pre_tag() {
         let html = r#"
@@ -246,7 +412,7 @@ Date       Time      inches   inches
         let fetcher = RainGaugeFetcher::new("".to_string());
         let result = fetcher.parse_html(html);
         assert!(result.is_err());
-        assert!(matches!(result, Err(FetchError::ParseError)));
+        assert!(matches!(result, Err(FetchError::LayoutChanged(_))));
     }
 
     #[test]
@@ -257,7 +423,9 @@ Date       Time      inches   inches
         let result = fetcher.parse_html(html);
         assert!(result.is_ok());
 
-        let readings = result.unwrap();
+        let screened = result.unwrap();
+        assert!(screened.quarantined.is_empty());
+        let readings = screened.accepted;
         // The sample file has 200 data rows
         assert!(
             readings.len() > 100,
@@ -265,15 +433,67 @@ Date       Time      inches   inches
             readings.len()
         );
 
-        // Verify first reading
-        assert_eq!(readings[0].cumulative_inches, 1.85);
-        assert_eq!(readings[0].incremental_inches, 0.00);
+        // Verify the latest reading
+        let latest = readings.last().unwrap();
+        assert_eq!(latest.cumulative_inches.value(), 1.85);
+        assert_eq!(latest.incremental_inches.value(), 0.00);
 
         // Verify some parsing accuracy
-        let reading_with_increment = readings.iter().find(|r| r.incremental_inches == 0.04);
+        let reading_with_increment = readings
+            .iter()
+            .find(|r| r.incremental_inches.value() == 0.04);
         assert!(
             reading_with_increment.is_some(),
             "Should have readings with 0.04 incremental"
         );
     }
+
+    mod properties {
+        use super::*;
+        use chrono::NaiveDate;
+        use proptest::prelude::*;
+        use std::sync::LazyLock;
+
+        // Building a reqwest client per case dominates the run time otherwise
+        static FETCHER: LazyLock<RainGaugeFetcher> =
+            LazyLock::new(|| RainGaugeFetcher::new(String::new()));
+
+        proptest! {
+            #[test]
+            fn parse_reading_never_panics(
+                date in "\\PC{0,12}",
+                time in "\\PC{0,10}",
+                cumulative in "\\PC{0,8}",
+                incremental in "\\PC{0,8}",
+            ) {
+                if let Ok(ParsedRow::Reading(reading)) = FETCHER.parse_reading(&date, &time, &cumulative, &incremental) {
+                    prop_assert!(reading.cumulative_inches.value().is_finite());
+                    prop_assert!(reading.incremental_inches.value().is_finite());
+                }
+            }
+
+            #[test]
+            fn parse_reading_rejects_impossible_dates(
+                month in 0u32..100,
+                day in 0u32..100,
+                year in 0u32..10000,
+                hour in 0u32..100,
+            ) {
+                let date = format!("{month:02}/{day:02}/{year:04}");
+                let time = format!("{hour:02}:00:00");
+                let valid = NaiveDate::from_ymd_opt(year as i32, month, day).is_some() && hour < 24;
+                let result = FETCHER.parse_reading(&date, &time, "1.00", "0.00");
+                prop_assert_eq!(result.is_ok(), valid);
+            }
+
+            #[test]
+            fn parse_html_never_panics(rows in prop::collection::vec("[^<&\\p{C}]{0,60}", 0..20)) {
+                let html = format!(
+                    "<PRE>\nDate       Time      inches   inches\n{}\n</PRE>",
+                    rows.join("\n")
+                );
+                prop_assert!(FETCHER.parse_html(&html).is_ok());
+            }
+        }
+    }
 }
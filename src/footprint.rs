@@ -0,0 +1,114 @@
+// Storm footprints
+//
+// The area a storm covered is drawn as the convex hull of the gauges that recorded rain
+// during it, as a GeoJSON geometry (WGS 84 longitude/latitude). Fewer than three gauges,
+// or gauges in a line, cannot enclose an area, so they come back as a Point or
+// LineString instead.
+
+use serde_json::{json, Value};
+
+/// Convex hull of (longitude, latitude) points, counterclockwise from the westernmost
+///
+/// Duplicate and collinear points are dropped, so fewer than three points come back
+/// when the input does not enclose an area.
+pub fn convex_hull(points: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    let mut points: Vec<(f64, f64)> = points
+        .iter()
+        .copied()
+        .filter(|(x, y)| x.is_finite() && y.is_finite())
+        .collect();
+    points.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+
+    // Andrew's monotone chain: lower hull left to right, then upper hull back
+    let cross = |o: (f64, f64), a: (f64, f64), b: (f64, f64)| {
+        (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+    };
+    let reversed: Vec<(f64, f64)> = points.iter().rev().copied().collect();
+    let mut hull: Vec<(f64, f64)> = Vec::with_capacity(points.len() * 2);
+    for pass in [&points, &reversed] {
+        let floor = hull.len();
+        for &p in pass.iter() {
+            while hull.len() >= floor + 2
+                && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0.0
+            {
+                hull.pop();
+            }
+            hull.push(p);
+        }
+        // Each chain ends where the other starts
+        hull.pop();
+    }
+    hull
+}
+
+/// GeoJSON geometry covering the points; None without any
+///
+/// A Polygon when the hull encloses an area (a closed, counterclockwise ring),
+/// otherwise a LineString between the two extreme points or a single Point.
+pub fn hull_geometry(points: &[(f64, f64)]) -> Option<Value> {
+    let hull = convex_hull(points);
+    let position = |&(lon, lat): &(f64, f64)| json!([lon, lat]);
+    match hull.as_slice() {
+        [] => None,
+        [point] => Some(json!({ "type": "Point", "coordinates": position(point) })),
+        [_, _] => Some(json!({
+            "type": "LineString",
+            "coordinates": hull.iter().map(position).collect::<Vec<_>>(),
+        })),
+        [first, ..] => {
+            let ring: Vec<Value> = hull.iter().chain([first]).map(position).collect();
+            Some(json!({ "type": "Polygon", "coordinates": [ring] }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convex_hull_drops_interior_and_collinear_points() {
+        let points = [
+            (0.0, 0.0),
+            (2.0, 0.0),
+            (1.0, 0.0), // on an edge
+            (2.0, 2.0),
+            (0.0, 2.0),
+            (1.0, 1.0), // inside
+            (0.0, 0.0), // duplicate
+        ];
+        assert_eq!(
+            convex_hull(&points),
+            vec![(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)]
+        );
+    }
+
+    #[test]
+    fn test_convex_hull_of_a_line_keeps_its_ends() {
+        let points = [(0.0, 0.0), (1.0, 1.0), (2.0, 2.0)];
+        assert_eq!(convex_hull(&points), vec![(0.0, 0.0), (2.0, 2.0)]);
+    }
+
+    #[test]
+    fn test_hull_geometry_types() {
+        assert_eq!(hull_geometry(&[]), None);
+        assert_eq!(
+            hull_geometry(&[(-112.0, 33.5), (-112.0, 33.5)]).unwrap()["type"],
+            "Point"
+        );
+        assert_eq!(
+            hull_geometry(&[(-112.0, 33.5), (-111.9, 33.6)]).unwrap()["type"],
+            "LineString"
+        );
+
+        let polygon = hull_geometry(&[(-112.0, 33.5), (-111.9, 33.5), (-111.9, 33.6)]).unwrap();
+        assert_eq!(polygon["type"], "Polygon");
+        let ring = polygon["coordinates"][0].as_array().unwrap();
+        assert_eq!(ring.len(), 4);
+        assert_eq!(ring.first(), ring.last());
+    }
+}
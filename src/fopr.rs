@@ -7,6 +7,8 @@
 
 pub mod daily_data_parser;
 pub mod metadata_parser;
+pub mod validation;
 
 pub use daily_data_parser::{FoprDailyDataParser, FoprParseError};
 pub use metadata_parser::{MetaStatsData, ParseError};
+pub use validation::ValidationBounds;
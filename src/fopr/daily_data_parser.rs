@@ -10,6 +10,8 @@ use tracing::{debug, info, warn};
 
 use crate::fopr::metadata_parser::excel_serial_to_date;
 use crate::importers::excel_importer::HistoricalReading;
+use crate::ingest_guard::{IngestLimits, Screened};
+use crate::station_id::StationId;
 
 #[derive(Error, Debug)]
 pub enum FoprParseError {
@@ -32,7 +34,8 @@ pub enum FoprParseError {
 /// Parser for FOPR daily rainfall data
 pub struct FoprDailyDataParser {
     workbook_path: String,
-    station_id: String,
+    station_id: StationId,
+    limits: IngestLimits,
 }
 
 impl FoprDailyDataParser {
@@ -41,19 +44,33 @@ impl FoprDailyDataParser {
     /// # Arguments
     /// * `workbook_path` - Path to the FOPR Excel file (e.g., "59700_FOPR.xlsx")
     /// * `station_id` - Station ID for the gauge (e.g., "59700")
-    pub fn new(workbook_path: impl Into<String>, station_id: impl Into<String>) -> Self {
+    pub fn new(workbook_path: impl Into<String>, station_id: StationId) -> Self {
         Self {
             workbook_path: workbook_path.into(),
-            station_id: station_id.into(),
+            station_id,
+            limits: IngestLimits::default(),
         }
     }
 
+    /// Override the ingest limits daily totals are screened against
+    pub fn with_limits(mut self, limits: IngestLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Parse all year sheets in the FOPR file, keeping only readings that pass the
+    /// ingest guards (see `screen_all_years` for the rejects)
+    pub fn parse_all_years(&self) -> Result<Vec<HistoricalReading>, FoprParseError> {
+        Ok(self.screen_all_years()?.accepted)
+    }
+
     /// Parse all year sheets in the FOPR file
     ///
-    /// Returns a Vec of HistoricalReading for all years found in the file.
+    /// Returns the readings for all years found in the file, and the daily totals the
+    /// ingest guards quarantined (negative or over the maximum).
     /// Year sheets are identified by numeric names (e.g., "2024", "2023").
     /// Skips non-year sheets like "Meta_Stats", "AnnualTables", etc.
-    pub fn parse_all_years(&self) -> Result<Vec<HistoricalReading>, FoprParseError> {
+    pub fn screen_all_years(&self) -> Result<Screened<HistoricalReading>, FoprParseError> {
         info!("Parsing FOPR file: {}", self.workbook_path);
 
         // Open workbook
@@ -65,7 +82,7 @@ impl FoprDailyDataParser {
         let sheet_names = workbook.sheet_names().to_owned();
         debug!("Found {} total sheets", sheet_names.len());
 
-        let mut all_readings = Vec::new();
+        let mut all_readings = Screened::default();
         let mut year_sheets_found = 0;
 
         // Find and parse year sheets
@@ -79,7 +96,11 @@ impl FoprDailyDataParser {
 
                     match self.parse_year_sheet(&mut workbook, &sheet_name, year) {
                         Ok(readings) => {
-                            info!("✓ Parsed {} readings from year {}", readings.len(), year);
+                            info!(
+                                "✓ Parsed {} readings from year {}",
+                                readings.accepted.len(),
+                                year
+                            );
                             all_readings.extend(readings);
                         }
                         Err(e) => {
@@ -103,9 +124,10 @@ impl FoprDailyDataParser {
         }
 
         info!(
-            "Parsed {} year sheets, total {} readings",
+            "Parsed {} year sheets, total {} readings ({} quarantined)",
             year_sheets_found,
-            all_readings.len()
+            all_readings.accepted.len(),
+            all_readings.quarantined.len()
         );
 
         Ok(all_readings)
@@ -123,13 +145,13 @@ impl FoprDailyDataParser {
         workbook: &mut Xlsx<BufReader<File>>,
         sheet_name: &str,
         _year: i32,
-    ) -> Result<Vec<HistoricalReading>, FoprParseError> {
+    ) -> Result<Screened<HistoricalReading>, FoprParseError> {
         let range = match workbook.worksheet_range(sheet_name) {
             Ok(range) => range,
             Err(_) => return Err(FoprParseError::SheetNotFound(sheet_name.to_string())),
         };
 
-        let mut readings = Vec::new();
+        let mut readings = Screened::default();
         let (row_count, _col_count) = range.get_size();
 
         debug!("Year sheet '{}' has {} rows", sheet_name, row_count);
@@ -173,15 +195,6 @@ impl FoprDailyDataParser {
                 None => 0.0, // Missing value = no rain
             };
 
-            // Validate rainfall value
-            if !(0.0..=20.0).contains(&rainfall) {
-                warn!(
-                    "Suspicious rainfall value at row {}: {} inches (skipping)",
-                    row_idx, rainfall
-                );
-                continue;
-            }
-
             // Convert Excel date serial to NaiveDate
             let date = match excel_serial_to_date(date_serial) {
                 Some(d) => d,
@@ -207,7 +220,20 @@ impl FoprDailyDataParser {
                 continue;
             }
 
-            readings.push(HistoricalReading {
+            // Negative and oversized totals are data entry errors
+            let rainfall = match self.limits.check_daily(&self.station_id, date, rainfall) {
+                Ok(inches) => inches,
+                Err(quarantined) => {
+                    warn!(
+                        "Quarantining rainfall value at row {}: {} inches ({})",
+                        row_idx, rainfall, quarantined.reason
+                    );
+                    readings.quarantined.push(quarantined);
+                    continue;
+                }
+            };
+
+            readings.accepted.push(HistoricalReading {
                 station_id: self.station_id.clone(),
                 reading_date: date,
                 rainfall_inches: rainfall,
@@ -215,7 +241,10 @@ impl FoprDailyDataParser {
             });
         }
 
-        debug!("Extracted {} non-zero readings from sheet", readings.len());
+        debug!(
+            "Extracted {} non-zero readings from sheet",
+            readings.accepted.len()
+        );
 
         Ok(readings)
     }
@@ -255,7 +284,10 @@ mod tests {
     #[test]
     fn test_get_available_years() {
         // This test requires the sample file to exist
-        let parser = FoprDailyDataParser::new("sample-data-files/59700_FOPR.xlsx", "59700");
+        let parser = FoprDailyDataParser::new(
+            "sample-data-files/59700_FOPR.xlsx",
+            "59700".parse().unwrap(),
+        );
 
         match parser.get_available_years() {
             Ok(years) => {
@@ -272,7 +304,10 @@ mod tests {
     #[test]
     fn test_parse_all_years() {
         // This test requires the sample file to exist
-        let parser = FoprDailyDataParser::new("sample-data-files/59700_FOPR.xlsx", "59700");
+        let parser = FoprDailyDataParser::new(
+            "sample-data-files/59700_FOPR.xlsx",
+            "59700".parse().unwrap(),
+        );
 
         match parser.parse_all_years() {
             Ok(readings) => {
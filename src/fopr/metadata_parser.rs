@@ -8,15 +8,16 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 
-use crate::utils;
+use crate::fopr::validation::ValidationBounds;
+use crate::station_id::StationId;
 
 /// Gauge metadata extracted from FOPR Meta_Stats sheet
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetaStatsData {
     // Identification
-    pub station_id: String,
+    pub station_id: StationId,
     pub station_name: String,
-    pub previous_station_ids: Vec<String>,
+    pub previous_station_ids: Vec<StationId>,
     pub station_type: String,
 
     // Location
@@ -48,8 +49,8 @@ pub struct MetaStatsData {
 /// Gage ID with historical ID tracking
 #[derive(Debug, Clone)]
 struct GageIdHistory {
-    current_id: String,
-    previous_ids: Vec<String>,
+    current_id: StationId,
+    previous_ids: Vec<StationId>,
 }
 
 /// Parse errors
@@ -66,8 +67,16 @@ pub enum ParseError {
 }
 
 impl MetaStatsData {
-    /// Parse metadata from Meta_Stats worksheet range
+    /// Parse metadata from Meta_Stats worksheet range using the default (MCFCD) bounds
     pub fn from_worksheet_range(range: &Range<Data>) -> Result<Self, ParseError> {
+        Self::from_worksheet_range_with_bounds(range, &ValidationBounds::default())
+    }
+
+    /// Parse metadata from Meta_Stats worksheet range, validating against the given bounds
+    pub fn from_worksheet_range_with_bounds(
+        range: &Range<Data>,
+        bounds: &ValidationBounds,
+    ) -> Result<Self, ParseError> {
         // Helper to get cell value safely (0-indexed)
         let get_cell = |row: usize, col: usize| -> Option<String> {
             range.get((row, col)).and_then(|v| match v {
@@ -101,7 +110,7 @@ impl MetaStatsData {
 
         // Parse Gage ID History (Row 4, Col B = index 3, 1)
         let gage_history_str = get_cell(3, 1).ok_or(ParseError::MissingField("Gage ID History"))?;
-        let gage_history = parse_gage_id_history(&gage_history_str);
+        let gage_history = parse_gage_id_history(&gage_history_str)?;
 
         // Extract station name (Row 3, Col B)
         let station_name = get_cell(2, 1).ok_or(ParseError::MissingField("Station Name"))?;
@@ -111,16 +120,16 @@ impl MetaStatsData {
 
         // Extract latitude (Row 11, Col C = index 10, 2)
         let latitude = get_float(10, 2).ok_or(ParseError::MissingField("Latitude"))?;
-        validate_latitude(latitude)?;
+        bounds.validate_latitude(latitude)?;
 
         // Extract longitude (Row 12, Col C = index 11, 2)
         let longitude = get_float(11, 2).ok_or(ParseError::MissingField("Longitude"))?;
-        validate_longitude(longitude)?;
+        bounds.validate_longitude(longitude)?;
 
         // Extract elevation (Row 13, Col B)
         let elevation_ft = get_cell(12, 1).and_then(|s| parse_elevation(&s));
         if let Some(elev) = elevation_ft {
-            validate_elevation(elev)?;
+            bounds.validate_elevation(elev)?;
         }
 
         // Extract city (Row 9, Col B)
@@ -145,7 +154,7 @@ impl MetaStatsData {
         // Parse climate stats
         let avg_annual_precipitation_inches = get_float(14, 3);
         if let Some(precip) = avg_annual_precipitation_inches {
-            validate_precipitation(precip)?;
+            bounds.validate_precipitation(precip)?;
         }
 
         let complete_years_count = get_cell(14, 0) // Column A label
@@ -222,59 +231,63 @@ impl MetaStatsData {
 
 /// Parse gage ID history: "59700; 4695 prior to 2/20/2018" or "40700 since 6/30/20"
 /// Extracts only numeric IDs (4-5 digits), ignoring text like "since" or "prior to"
-fn parse_gage_id_history(value: &str) -> GageIdHistory {
+fn parse_gage_id_history(value: &str) -> Result<GageIdHistory, ParseError> {
     let parts: Vec<&str> = value.split(';').map(|s| s.trim()).collect();
 
-    // Extract current ID (first part before semicolon, take only the numeric portion)
-    let current_id = extract_numeric_id(parts[0]);
+    // Current ID is the first part; a bare non-numeric ID (test gauges) is taken as is
+    let current_id = StationId::extract(parts[0])
+        .or_else(|_| StationId::parse(parts[0]))
+        .map_err(|e| ParseError::InvalidFormat(format!("Gage ID History {value:?}: {e}")))?;
 
-    // Extract previous IDs from subsequent parts
+    // Previous IDs from "4695 prior to 2/20/2018" parts; anything else is a note
     let previous_ids = parts[1..]
         .iter()
-        .filter_map(|part| {
-            // Extract ID from "4695 prior to 2/20/2018" format
-            let id = extract_numeric_id(part);
-            if id.is_empty() {
-                None
-            } else {
-                Some(id)
-            }
-        })
+        .filter_map(|part| StationId::extract(part).ok())
         .collect();
 
-    GageIdHistory {
+    Ok(GageIdHistory {
         current_id,
         previous_ids,
-    }
+    })
 }
 
-/// Extract 4-5 digit numeric ID from a string that may contain additional text
-/// Delegates to shared utils::extract_station_id()
-fn extract_numeric_id(value: &str) -> String {
-    utils::extract_station_id(value).unwrap_or_else(|_| value.to_string())
-}
+/// Largest serial Excel displays as a date (9999-12-31)
+const MAX_EXCEL_SERIAL: f64 = 2_958_465.0;
 
 /// Convert Excel date serial to NaiveDate
 ///
 /// This is a fallback for when we get a raw f64 value instead of ExcelDateTime.
 /// Excel stores dates as integers (serial numbers) since Dec 31, 1899.
+/// Serials Excel can't display (negative, NaN, past 9999) come only from corrupt
+/// cells and return None.
 /// Note: Prefer using ExcelDateTime::as_datetime() when available.
 pub fn excel_serial_to_date(serial: f64) -> Option<NaiveDate> {
+    if !(0.0..=MAX_EXCEL_SERIAL).contains(&serial) {
+        return None;
+    }
     // Excel epoch: 1899-12-30 (adjusted for Excel's off-by-one bug)
     let epoch = NaiveDate::from_ymd_opt(1899, 12, 30)?;
     epoch.checked_add_signed(Duration::days(serial as i64))
 }
 
 /// Convert ExcelDateTime to NaiveDate using calamine's built-in conversion
-fn excel_datetime_to_date(dt: &calamine::ExcelDateTime) -> Option<NaiveDate> {
+///
+/// calamine panics on serials far outside Excel's range, so those return None.
+pub fn excel_datetime_to_date(dt: &calamine::ExcelDateTime) -> Option<NaiveDate> {
+    if !(0.0..=MAX_EXCEL_SERIAL).contains(&dt.as_f64()) {
+        return None;
+    }
     dt.as_datetime().map(|chrono_dt| chrono_dt.date())
 }
 
 /// Calculate installation date from years since installation
 fn calculate_installation_date(years_since: f64, reference_serial: f64) -> Option<NaiveDate> {
     let reference_date = excel_serial_to_date(reference_serial)?;
+    if !years_since.is_finite() {
+        return None;
+    }
     let days_offset = (years_since * 365.25) as i64;
-    reference_date.checked_sub_signed(Duration::days(days_offset))
+    reference_date.checked_sub_signed(Duration::try_days(days_offset)?)
 }
 
 /// Extract complete years count from label text
@@ -329,72 +342,19 @@ fn add_frequency_stat<F, D>(
     }
 }
 
-/// Validate latitude is within Arizona state bounds
-///
-/// Arizona extends from approximately 31.3°N (southern border with Mexico)
-/// to 37.0°N (northern border with Utah). We use slightly wider bounds
-/// to account for partnership gauges near state borders.
-fn validate_latitude(lat: f64) -> Result<(), ParseError> {
-    if (31.0..=37.5).contains(&lat) {
-        Ok(())
-    } else {
-        Err(ParseError::ValidationError(format!(
-            "Latitude {lat} outside Arizona state range (31.0 - 37.5)"
-        )))
-    }
-}
-
-/// Validate longitude is within Arizona state bounds
-///
-/// Arizona extends from approximately -114.8°W (western border with California/Nevada)
-/// to -109.0°W (eastern border with New Mexico). We use slightly wider bounds
-/// to account for partnership gauges near state borders.
-fn validate_longitude(lon: f64) -> Result<(), ParseError> {
-    if (-115.0..=-108.5).contains(&lon) {
-        Ok(())
-    } else {
-        Err(ParseError::ValidationError(format!(
-            "Longitude {lon} outside Arizona state range (-115.0 - -108.5)"
-        )))
-    }
-}
-
-/// Validate elevation is within Arizona state range
-///
-/// Arizona elevations range from approximately 70 feet (Colorado River at
-/// Mexican border) to 12,637 feet (Humphreys Peak near Flagstaff). We use
-/// a slightly wider range to account for partnership gauges and measurement
-/// variations.
-fn validate_elevation(elev: i32) -> Result<(), ParseError> {
-    if (0..=13000).contains(&elev) {
-        Ok(())
-    } else {
-        Err(ParseError::ValidationError(format!(
-            "Elevation {elev} outside Arizona range (0 - 13,000 ft)"
-        )))
-    }
-}
-
-/// Validate precipitation is within reasonable range
-fn validate_precipitation(inches: f64) -> Result<(), ParseError> {
-    if (0.0..=20.0).contains(&inches) {
-        Ok(())
-    } else {
-        Err(ParseError::ValidationError(format!(
-            "Precipitation {inches} outside reasonable range (0.0 - 20.0 inches)"
-        )))
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use chrono::Datelike;
 
+    fn bounds() -> ValidationBounds {
+        ValidationBounds::default()
+    }
+
     #[test]
     fn test_parse_gage_id_history_with_previous() {
         let input = "59700; 4695 prior to 2/20/2018";
-        let result = parse_gage_id_history(input);
+        let result = parse_gage_id_history(input).unwrap();
         assert_eq!(result.current_id, "59700");
         assert_eq!(result.previous_ids, vec!["4695"]);
     }
@@ -402,7 +362,7 @@ mod tests {
     #[test]
     fn test_parse_gage_id_history_no_previous() {
         let input = "11000";
-        let result = parse_gage_id_history(input);
+        let result = parse_gage_id_history(input).unwrap();
         assert_eq!(result.current_id, "11000");
         assert!(result.previous_ids.is_empty());
     }
@@ -410,7 +370,7 @@ mod tests {
     #[test]
     fn test_parse_gage_id_history_multiple_previous() {
         let input = "59700; 4695 prior to 2/20/2018; 1234 prior to 1/1/2010";
-        let result = parse_gage_id_history(input);
+        let result = parse_gage_id_history(input).unwrap();
         assert_eq!(result.current_id, "59700");
         assert_eq!(result.previous_ids, vec!["4695", "1234"]);
     }
@@ -418,7 +378,7 @@ mod tests {
     #[test]
     fn test_parse_gage_id_history_with_since() {
         let input = "40700 since 6/30/20";
-        let result = parse_gage_id_history(input);
+        let result = parse_gage_id_history(input).unwrap();
         assert_eq!(result.current_id, "40700");
         assert!(result.previous_ids.is_empty());
     }
@@ -426,31 +386,21 @@ mod tests {
     #[test]
     fn test_parse_gage_id_history_with_installation() {
         let input = "37300 since installation";
-        let result = parse_gage_id_history(input);
+        let result = parse_gage_id_history(input).unwrap();
         assert_eq!(result.current_id, "37300");
         assert!(result.previous_ids.is_empty());
     }
 
     #[test]
-    fn test_extract_numeric_id_clean() {
-        assert_eq!(extract_numeric_id("29200"), "29200");
-        assert_eq!(extract_numeric_id("1800"), "1800");
-    }
-
-    #[test]
-    fn test_extract_numeric_id_with_since() {
-        assert_eq!(extract_numeric_id("40700 since 6/30/20"), "40700");
-        assert_eq!(extract_numeric_id("29200 since 03/09/18"), "29200");
-    }
-
-    #[test]
-    fn test_extract_numeric_id_with_installation() {
-        assert_eq!(extract_numeric_id("37300 since installation"), "37300");
-    }
+    fn test_parse_gage_id_history_normalizes_and_rejects() {
+        let result = parse_gage_id_history("59700.0; 01800 prior to 2015").unwrap();
+        assert_eq!(result.current_id, "59700");
+        assert_eq!(result.previous_ids, vec!["1800"]);
 
-    #[test]
-    fn test_extract_numeric_id_4digit() {
-        assert_eq!(extract_numeric_id("1800 since 03/27/18"), "1800");
+        assert!(matches!(
+            parse_gage_id_history("see notes; 4695 prior to 2/20/2018"),
+            Err(ParseError::InvalidFormat(_))
+        ));
     }
 
     #[test]
@@ -506,63 +456,63 @@ mod tests {
 
     #[test]
     fn test_validate_latitude_valid() {
-        assert!(validate_latitude(33.61006).is_ok());
+        assert!(bounds().validate_latitude(33.61006).is_ok());
     }
 
     #[test]
     fn test_validate_latitude_too_far_north() {
-        assert!(validate_latitude(40.0).is_err());
+        assert!(bounds().validate_latitude(40.0).is_err());
     }
 
     #[test]
     fn test_validate_latitude_too_far_south() {
-        assert!(validate_latitude(30.0).is_err());
+        assert!(bounds().validate_latitude(30.0).is_err());
     }
 
     #[test]
     fn test_validate_longitude_valid() {
-        assert!(validate_longitude(-111.86545).is_ok());
+        assert!(bounds().validate_longitude(-111.86545).is_ok());
     }
 
     #[test]
     fn test_validate_longitude_too_far_east() {
-        assert!(validate_longitude(-100.0).is_err());
+        assert!(bounds().validate_longitude(-100.0).is_err());
     }
 
     #[test]
     fn test_validate_longitude_too_far_west() {
-        assert!(validate_longitude(-116.0).is_err()); // Beyond AZ western border
+        assert!(bounds().validate_longitude(-116.0).is_err()); // Beyond AZ western border
     }
 
     #[test]
     fn test_validate_elevation_valid() {
-        assert!(validate_elevation(1465).is_ok()); // Phoenix area
-        assert!(validate_elevation(5205).is_ok()); // Northern AZ (the one that was failing)
+        assert!(bounds().validate_elevation(1465).is_ok()); // Phoenix area
+        assert!(bounds().validate_elevation(5205).is_ok()); // Northern AZ (the one that was failing)
     }
 
     #[test]
     fn test_validate_elevation_too_low() {
-        assert!(validate_elevation(-10).is_err()); // Below sea level (invalid for AZ)
+        assert!(bounds().validate_elevation(-10).is_err()); // Below sea level (invalid for AZ)
     }
 
     #[test]
     fn test_validate_elevation_too_high() {
-        assert!(validate_elevation(14000).is_err()); // Above Humphreys Peak (12,637 ft)
+        assert!(bounds().validate_elevation(14000).is_err()); // Above Humphreys Peak (12,637 ft)
     }
 
     #[test]
     fn test_validate_precipitation_valid() {
-        assert!(validate_precipitation(7.48).is_ok());
+        assert!(bounds().validate_precipitation(7.48).is_ok());
     }
 
     #[test]
     fn test_validate_precipitation_negative() {
-        assert!(validate_precipitation(-1.0).is_err());
+        assert!(bounds().validate_precipitation(-1.0).is_err());
     }
 
     #[test]
     fn test_validate_precipitation_too_high() {
-        assert!(validate_precipitation(25.0).is_err());
+        assert!(bounds().validate_precipitation(25.0).is_err());
     }
 
     #[test]
@@ -575,4 +525,92 @@ mod tests {
         // Allow Jan-Mar range due to calculation method
         assert!(install_date.month() >= 1 && install_date.month() <= 3);
     }
+
+    mod properties {
+        use super::*;
+        use calamine::{ExcelDateTime, ExcelDateTimeType};
+        use proptest::prelude::*;
+
+        /// Includes the values a corrupt workbook can hold but a real one won't
+        fn any_float() -> impl Strategy<Value = f64> {
+            prop_oneof![
+                any::<f64>(),
+                -1.0e6..1.0e6,
+                Just(f64::NAN),
+                Just(f64::INFINITY),
+                Just(f64::NEG_INFINITY),
+                Just(f64::MAX),
+                Just(f64::MIN),
+            ]
+        }
+
+        fn any_cell() -> impl Strategy<Value = Data> {
+            prop_oneof![
+                Just(Data::Empty),
+                any_float().prop_map(Data::Float),
+                any::<i64>().prop_map(Data::Int),
+                any::<bool>().prop_map(Data::Bool),
+                "\\PC{0,40}".prop_map(Data::String),
+                (any_float(), any::<bool>()).prop_map(|(value, is_1904)| {
+                    Data::DateTime(ExcelDateTime::new(
+                        value,
+                        ExcelDateTimeType::DateTime,
+                        is_1904,
+                    ))
+                }),
+            ]
+        }
+
+        /// A Meta_Stats-sized sheet of arbitrary cells, optionally with the required
+        /// fields valid so parsing reaches the optional ones
+        fn meta_stats_sheet() -> impl Strategy<Value = Range<Data>> {
+            (prop::collection::vec(any_cell(), 40 * 4), any::<bool>()).prop_map(
+                |(cells, anchored)| {
+                    let mut range = Range::new((0, 0), (39, 3));
+                    for (i, cell) in cells.into_iter().enumerate() {
+                        range.set_value(((i / 4) as u32, (i % 4) as u32), cell);
+                    }
+                    if anchored {
+                        range.set_value((2, 1), Data::String("Test Wash".to_string()));
+                        range.set_value((3, 1), Data::String("59700".to_string()));
+                        range.set_value((10, 2), Data::Float(33.5));
+                        range.set_value((11, 2), Data::Float(-112.0));
+                        range.set_value((12, 1), Data::Empty);
+                        range.set_value((14, 3), Data::Empty);
+                    }
+                    range
+                },
+            )
+        }
+
+        proptest! {
+            #[test]
+            fn excel_serial_to_date_never_panics(serial in any_float()) {
+                let _ = excel_serial_to_date(serial);
+            }
+
+            #[test]
+            fn calculate_installation_date_never_panics(
+                years in any_float(),
+                reference in any_float(),
+            ) {
+                let _ = calculate_installation_date(years, reference);
+            }
+
+            #[test]
+            fn parse_gage_id_history_never_panics(value in "\\PC{0,60}") {
+                if let Ok(history) = parse_gage_id_history(&value) {
+                    prop_assert!(history.previous_ids.iter().all(|id| !id.is_empty()));
+                }
+            }
+
+            #[test]
+            fn from_worksheet_range_never_panics(range in meta_stats_sheet()) {
+                if let Ok(meta) = MetaStatsData::from_worksheet_range(&range) {
+                    prop_assert!(meta.latitude.is_finite());
+                    prop_assert!(meta.longitude.is_finite());
+                }
+            }
+        }
+    }
 }
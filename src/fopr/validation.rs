@@ -0,0 +1,157 @@
+/// Validation bounds for FOPR gauge metadata
+///
+/// MetaStatsData rejects coordinates, elevations, and precipitation averages that
+/// fall outside a plausible range for the gauge network. The defaults describe the
+/// MCFCD network (Arizona state bounds, slightly widened for partnership gauges near
+/// state borders). Other networks can supply their own bounds through Config.
+use crate::fopr::metadata_parser::ParseError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationBounds {
+    /// Network label used in validation error messages (e.g., "MCFCD")
+    pub network: String,
+    pub min_latitude: f64,
+    pub max_latitude: f64,
+    pub min_longitude: f64,
+    pub max_longitude: f64,
+    pub min_elevation_ft: i32,
+    pub max_elevation_ft: i32,
+    pub min_annual_precipitation_inches: f64,
+    pub max_annual_precipitation_inches: f64,
+}
+
+impl ValidationBounds {
+    /// Bounds for the Maricopa County Flood Control District network
+    ///
+    /// - Latitude: Arizona spans ~31.3°N to 37.0°N
+    /// - Longitude: Arizona spans ~-114.8°W to -109.0°W
+    /// - Elevation: ~70 ft (Colorado River) to 12,637 ft (Humphreys Peak)
+    /// - Average annual precipitation: 0 - 20 inches
+    pub fn mcfcd() -> Self {
+        Self {
+            network: "MCFCD".to_string(),
+            min_latitude: 31.0,
+            max_latitude: 37.5,
+            min_longitude: -115.0,
+            max_longitude: -108.5,
+            min_elevation_ft: 0,
+            max_elevation_ft: 13000,
+            min_annual_precipitation_inches: 0.0,
+            max_annual_precipitation_inches: 20.0,
+        }
+    }
+
+    /// Look up the built-in bounds for a network by name (case-insensitive)
+    pub fn for_network(network: &str) -> Option<Self> {
+        match network.to_ascii_lowercase().as_str() {
+            "mcfcd" | "maricopa" => Some(Self::mcfcd()),
+            _ => None,
+        }
+    }
+
+    pub fn validate_latitude(&self, lat: f64) -> Result<(), ParseError> {
+        if (self.min_latitude..=self.max_latitude).contains(&lat) {
+            Ok(())
+        } else {
+            Err(ParseError::ValidationError(format!(
+                "Latitude {lat} outside {} range ({} - {})",
+                self.network, self.min_latitude, self.max_latitude
+            )))
+        }
+    }
+
+    pub fn validate_longitude(&self, lon: f64) -> Result<(), ParseError> {
+        if (self.min_longitude..=self.max_longitude).contains(&lon) {
+            Ok(())
+        } else {
+            Err(ParseError::ValidationError(format!(
+                "Longitude {lon} outside {} range ({} - {})",
+                self.network, self.min_longitude, self.max_longitude
+            )))
+        }
+    }
+
+    pub fn validate_elevation(&self, elev: i32) -> Result<(), ParseError> {
+        if (self.min_elevation_ft..=self.max_elevation_ft).contains(&elev) {
+            Ok(())
+        } else {
+            Err(ParseError::ValidationError(format!(
+                "Elevation {elev} outside {} range ({} - {} ft)",
+                self.network, self.min_elevation_ft, self.max_elevation_ft
+            )))
+        }
+    }
+
+    pub fn validate_precipitation(&self, inches: f64) -> Result<(), ParseError> {
+        if (self.min_annual_precipitation_inches..=self.max_annual_precipitation_inches)
+            .contains(&inches)
+        {
+            Ok(())
+        } else {
+            Err(ParseError::ValidationError(format!(
+                "Precipitation {inches} outside {} range ({} - {} inches)",
+                self.network,
+                self.min_annual_precipitation_inches,
+                self.max_annual_precipitation_inches
+            )))
+        }
+    }
+}
+
+impl Default for ValidationBounds {
+    fn default() -> Self {
+        Self::mcfcd()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_is_mcfcd() {
+        assert_eq!(ValidationBounds::default(), ValidationBounds::mcfcd());
+    }
+
+    #[test]
+    fn test_for_network_lookup() {
+        assert_eq!(
+            ValidationBounds::for_network("MCFCD"),
+            Some(ValidationBounds::mcfcd())
+        );
+        assert!(ValidationBounds::for_network("unknown").is_none());
+    }
+
+    #[test]
+    fn test_custom_bounds_accept_other_region() {
+        // Bounds for a hypothetical Colorado network
+        let bounds = ValidationBounds {
+            network: "CO".to_string(),
+            min_latitude: 36.9,
+            max_latitude: 41.1,
+            min_longitude: -109.1,
+            max_longitude: -102.0,
+            min_elevation_ft: 3000,
+            max_elevation_ft: 14500,
+            min_annual_precipitation_inches: 0.0,
+            max_annual_precipitation_inches: 60.0,
+        };
+
+        assert!(bounds.validate_latitude(39.74).is_ok());
+        assert!(bounds.validate_longitude(-104.99).is_ok());
+        assert!(bounds.validate_elevation(14000).is_ok());
+        assert!(bounds.validate_precipitation(35.0).is_ok());
+
+        // Phoenix is outside these bounds
+        assert!(bounds.validate_latitude(33.45).is_err());
+    }
+
+    #[test]
+    fn test_error_message_names_network() {
+        let err = ValidationBounds::mcfcd()
+            .validate_latitude(40.0)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("MCFCD"), "unexpected message: {err}");
+    }
+}
@@ -0,0 +1,530 @@
+// NWS gridpoint precipitation forecasts
+//
+// The National Weather Service API maps a latitude/longitude to a forecast office grid
+// cell (`/points/{lat},{lon}`). The cell's raw gridpoint data
+// (`/gridpoints/{office}/{x},{y}`) includes `quantitativePrecipitation` (QPF): forecast
+// liquid precipitation for consecutive ISO 8601 intervals such as
+// `2025-01-15T12:00:00+00:00/PT6H`, in millimeters. Gauges in the same cell share one
+// forecast, so forecasts are cached per cell. The same data carries daily high and low
+// temperatures (`maxTemperature`, `minTemperature`), used by the optional weather job.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+use utoipa::ToSchema;
+
+use crate::politeness::{PoliteError, Politeness, DEFAULT_USER_AGENT};
+
+/// NWS API base URL when NWS_API_URL is unset
+pub const DEFAULT_NWS_URL: &str = "https://api.weather.gov";
+
+/// How long a grid cell's forecast is reused before it is fetched again
+pub const DEFAULT_FORECAST_CACHE_MINUTES: u64 = 60;
+
+/// Hours of forecast combined with observed rainfall
+pub const FORECAST_HOURS: i64 = 72;
+
+const MM_PER_INCH: f64 = 25.4;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ForecastError {
+    #[error("HTTP request failed: {0}")]
+    Request(#[from] reqwest::Error),
+
+    #[error("Disallowed by robots.txt: {0}")]
+    Disallowed(String),
+
+    #[error("Paused after repeated failures from {0}")]
+    CircuitOpen(String),
+
+    #[error("NWS API returned HTTP {0}")]
+    Status(u16),
+
+    #[error("Invalid NWS API response: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Invalid NWS API response: {0}")]
+    Invalid(String),
+
+    /// The point is outside the NWS forecast grid (e.g. offshore or outside the US)
+    #[error("Location is outside NWS forecast coverage")]
+    OutsideCoverage,
+}
+
+impl From<PoliteError> for ForecastError {
+    fn from(e: PoliteError) -> Self {
+        match e {
+            PoliteError::Request(e) => ForecastError::Request(e),
+            PoliteError::Disallowed(url) => ForecastError::Disallowed(url),
+            PoliteError::CircuitOpen(host) => ForecastError::CircuitOpen(host),
+        }
+    }
+}
+
+/// Settings for the gauge forecast endpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForecastConfig {
+    /// NWS_API_URL, default https://api.weather.gov
+    pub api_url: String,
+    /// FORECAST_CACHE_MINUTES, default 60
+    pub cache_minutes: u64,
+}
+
+impl Default for ForecastConfig {
+    fn default() -> Self {
+        Self {
+            api_url: DEFAULT_NWS_URL.to_string(),
+            cache_minutes: DEFAULT_FORECAST_CACHE_MINUTES,
+        }
+    }
+}
+
+/// An NWS forecast grid cell
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, ToSchema)]
+pub struct GridCell {
+    /// Forecast office (e.g. PSR for Phoenix)
+    #[schema(example = "PSR")]
+    pub office: String,
+    #[schema(example = 158)]
+    pub x: i32,
+    #[schema(example = 56)]
+    pub y: i32,
+}
+
+/// Forecast precipitation over one interval
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct ForecastPeriod {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    #[schema(example = 0.12)]
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub inches: f64,
+}
+
+/// A grid cell's quantitative precipitation forecast
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridForecast {
+    /// When NWS last updated the cell's forecast
+    pub updated_at: Option<DateTime<Utc>>,
+    /// Consecutive intervals in time order
+    pub periods: Vec<ForecastPeriod>,
+}
+
+impl GridForecast {
+    /// Periods overlapping `[start, end)`, clipped to it
+    ///
+    /// A clipped period keeps the share of its precipitation proportional to the overlap.
+    pub fn window(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<ForecastPeriod> {
+        self.periods
+            .iter()
+            .filter(|p| p.end > start && p.start < end)
+            .map(|p| {
+                let clipped_start = p.start.max(start);
+                let clipped_end = p.end.min(end);
+                let share = (clipped_end - clipped_start).num_seconds() as f64
+                    / (p.end - p.start).num_seconds() as f64;
+                ForecastPeriod {
+                    start: clipped_start,
+                    end: clipped_end,
+                    inches: p.inches * share,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Forecast high or low temperature over one interval
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemperaturePeriod {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub fahrenheit: f64,
+}
+
+/// A grid cell's daily high and low temperature forecasts
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GridTemperatures {
+    /// Daytime highs in time order
+    pub highs: Vec<TemperaturePeriod>,
+    /// Overnight lows in time order
+    pub lows: Vec<TemperaturePeriod>,
+}
+
+/// Client for the NWS API
+#[derive(Clone)]
+pub struct NwsClient {
+    client: reqwest::Client,
+    url: String,
+    politeness: Politeness,
+}
+
+impl NwsClient {
+    pub fn new(url: &str) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(30))
+                // NWS rejects requests without an identifying User-Agent
+                .user_agent(DEFAULT_USER_AGENT)
+                .build()
+                .expect("Failed to create HTTP client"),
+            url: url.trim_end_matches('/').to_string(),
+            politeness: Politeness::default(),
+        }
+    }
+
+    /// Send requests with the shared User-Agent, robots.txt checks, and host delay
+    pub fn with_politeness(mut self, politeness: Politeness) -> Self {
+        self.politeness = politeness;
+        self
+    }
+
+    /// The grid cell containing a point
+    #[instrument(skip(self))]
+    pub async fn grid_cell(
+        &self,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<GridCell, ForecastError> {
+        // NWS redirects requests with more than four decimal places
+        let url = format!("{}/points/{latitude:.4},{longitude:.4}", self.url);
+        let body = self.get(&url).await?;
+        parse_point(&body)
+    }
+
+    /// The cell's quantitative precipitation forecast
+    #[instrument(skip(self))]
+    pub async fn precipitation(&self, cell: &GridCell) -> Result<GridForecast, ForecastError> {
+        let url = format!(
+            "{}/gridpoints/{}/{},{}",
+            self.url, cell.office, cell.x, cell.y
+        );
+        let body = self.get(&url).await?;
+        parse_gridpoint(&body)
+    }
+
+    /// The cell's daily high and low temperature forecasts
+    #[instrument(skip(self))]
+    pub async fn temperatures(&self, cell: &GridCell) -> Result<GridTemperatures, ForecastError> {
+        let url = format!(
+            "{}/gridpoints/{}/{},{}",
+            self.url, cell.office, cell.x, cell.y
+        );
+        let body = self.get(&url).await?;
+        parse_temperatures(&body)
+    }
+
+    async fn get(&self, url: &str) -> Result<String, ForecastError> {
+        let request = self
+            .client
+            .get(url)
+            .header(reqwest::header::ACCEPT, "application/geo+json");
+        let response = self.politeness.send(&self.client, request).await?;
+        match response.status() {
+            status if status.is_success() => {}
+            reqwest::StatusCode::NOT_FOUND => return Err(ForecastError::OutsideCoverage),
+            status => return Err(ForecastError::Status(status.as_u16())),
+        }
+
+        let body = response.text().await?;
+        debug!("NWS API response from {}: {} bytes", url, body.len());
+        Ok(body)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PointResponse {
+    properties: PointProperties,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PointProperties {
+    grid_id: Option<String>,
+    grid_x: Option<i32>,
+    grid_y: Option<i32>,
+}
+
+/// Read the grid cell from a `/points` response
+fn parse_point(body: &str) -> Result<GridCell, ForecastError> {
+    let point: PointResponse = serde_json::from_str(body)?;
+    match point.properties {
+        PointProperties {
+            grid_id: Some(office),
+            grid_x: Some(x),
+            grid_y: Some(y),
+        } => Ok(GridCell { office, x, y }),
+        // Marine and some territory points have no land forecast grid
+        _ => Err(ForecastError::OutsideCoverage),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GridpointResponse {
+    properties: GridpointProperties,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GridpointProperties {
+    update_time: Option<DateTime<Utc>>,
+    quantitative_precipitation: Option<GridLayer>,
+    max_temperature: Option<GridLayer>,
+    min_temperature: Option<GridLayer>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GridLayer {
+    uom: Option<String>,
+    #[serde(default)]
+    values: Vec<GridValue>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GridValue {
+    valid_time: String,
+    value: Option<f64>,
+}
+
+/// Read the QPF layer from a `/gridpoints` response
+fn parse_gridpoint(body: &str) -> Result<GridForecast, ForecastError> {
+    let gridpoint: GridpointResponse = serde_json::from_str(body)?;
+    let properties = gridpoint.properties;
+    let Some(layer) = properties.quantitative_precipitation else {
+        return Ok(GridForecast {
+            updated_at: properties.update_time,
+            periods: Vec::new(),
+        });
+    };
+    let to_inches = match layer.uom.as_deref() {
+        None | Some("wmoUnit:mm") => 1.0 / MM_PER_INCH,
+        Some("wmoUnit:in") => 1.0,
+        Some(other) => {
+            return Err(ForecastError::Invalid(format!(
+                "unexpected precipitation unit {other}"
+            )))
+        }
+    };
+
+    let mut periods = layer
+        .values
+        .iter()
+        .map(|value| {
+            let (start, end) = parse_valid_time(&value.valid_time).ok_or_else(|| {
+                ForecastError::Invalid(format!("invalid validTime {:?}", value.valid_time))
+            })?;
+            Ok(ForecastPeriod {
+                start,
+                end,
+                inches: value.value.unwrap_or(0.0).max(0.0) * to_inches,
+            })
+        })
+        .collect::<Result<Vec<_>, ForecastError>>()?;
+    periods.sort_by_key(|p| p.start);
+
+    Ok(GridForecast {
+        updated_at: properties.update_time,
+        periods,
+    })
+}
+
+/// Read the max and min temperature layers from a `/gridpoints` response
+fn parse_temperatures(body: &str) -> Result<GridTemperatures, ForecastError> {
+    let gridpoint: GridpointResponse = serde_json::from_str(body)?;
+    let properties = gridpoint.properties;
+    Ok(GridTemperatures {
+        highs: temperature_periods(properties.max_temperature)?,
+        lows: temperature_periods(properties.min_temperature)?,
+    })
+}
+
+fn temperature_periods(layer: Option<GridLayer>) -> Result<Vec<TemperaturePeriod>, ForecastError> {
+    let Some(layer) = layer else {
+        return Ok(Vec::new());
+    };
+    let to_fahrenheit: fn(f64) -> f64 = match layer.uom.as_deref() {
+        None | Some("wmoUnit:degC") => |c| c * 9.0 / 5.0 + 32.0,
+        Some("wmoUnit:degF") => |f| f,
+        Some(other) => {
+            return Err(ForecastError::Invalid(format!(
+                "unexpected temperature unit {other}"
+            )))
+        }
+    };
+
+    let mut periods = Vec::new();
+    for value in &layer.values {
+        let (start, end) = parse_valid_time(&value.valid_time).ok_or_else(|| {
+            ForecastError::Invalid(format!("invalid validTime {:?}", value.valid_time))
+        })?;
+        // Unlike precipitation, a missing temperature is not zero
+        if let Some(temperature) = value.value {
+            periods.push(TemperaturePeriod {
+                start,
+                end,
+                fahrenheit: (to_fahrenheit(temperature) * 10.0).round() / 10.0,
+            });
+        }
+    }
+    periods.sort_by_key(|p| p.start);
+    Ok(periods)
+}
+
+/// Parse an ISO 8601 `start/duration` interval
+fn parse_valid_time(valid_time: &str) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let (start, duration) = valid_time.split_once('/')?;
+    let start = DateTime::parse_from_rfc3339(start)
+        .ok()?
+        .with_timezone(&Utc);
+    let end = start + parse_duration(duration)?;
+    (end > start).then_some((start, end))
+}
+
+/// Parse an ISO 8601 duration of days, hours, and minutes (`PT6H`, `P1DT12H`)
+fn parse_duration(duration: &str) -> Option<chrono::Duration> {
+    let rest = duration.strip_prefix('P')?;
+    let (days, time) = rest.split_once('T').unwrap_or((rest, ""));
+    let mut total = chrono::Duration::zero();
+    for (part, units) in [
+        (days, &[('D', 86_400)][..]),
+        (time, &[('H', 3_600), ('M', 60)][..]),
+    ] {
+        let mut number = String::new();
+        for c in part.chars() {
+            if c.is_ascii_digit() {
+                number.push(c);
+                continue;
+            }
+            let seconds = units.iter().find(|(unit, _)| *unit == c)?.1;
+            total += chrono::Duration::seconds(number.parse::<i64>().ok()? * seconds);
+            number.clear();
+        }
+        if !number.is_empty() {
+            return None;
+        }
+    }
+    Some(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_point() {
+        let body = r#"{"properties": {"gridId": "PSR", "gridX": 158, "gridY": 56,
+            "forecastGridData": "https://api.weather.gov/gridpoints/PSR/158,56"}}"#;
+        assert_eq!(
+            parse_point(body).unwrap(),
+            GridCell {
+                office: "PSR".to_string(),
+                x: 158,
+                y: 56
+            }
+        );
+        assert!(matches!(
+            parse_point(r#"{"properties": {"gridId": null}}"#),
+            Err(ForecastError::OutsideCoverage)
+        ));
+    }
+
+    #[test]
+    fn test_parse_gridpoint_converts_mm_to_inches() {
+        let body = r#"{"properties": {
+            "updateTime": "2025-01-15T10:21:44+00:00",
+            "quantitativePrecipitation": {"uom": "wmoUnit:mm", "values": [
+                {"validTime": "2025-01-15T18:00:00+00:00/PT6H", "value": 2.54},
+                {"validTime": "2025-01-15T12:00:00+00:00/PT6H", "value": 0},
+                {"validTime": "2025-01-16T00:00:00+00:00/P1DT6H", "value": 25.4}
+            ]}
+        }}"#;
+        let forecast = parse_gridpoint(body).unwrap();
+
+        let at = |d, h| Utc.with_ymd_and_hms(2025, 1, d, h, 0, 0).unwrap();
+        assert_eq!(
+            forecast.updated_at,
+            Some(Utc.with_ymd_and_hms(2025, 1, 15, 10, 21, 44).unwrap())
+        );
+        let periods: Vec<_> = forecast.periods.iter().map(|p| (p.start, p.end)).collect();
+        assert_eq!(
+            periods,
+            [
+                (at(15, 12), at(15, 18)),
+                (at(15, 18), at(16, 0)),
+                (at(16, 0), at(17, 6))
+            ]
+        );
+        assert!((forecast.periods[1].inches - 0.1).abs() < 1e-9);
+        assert!((forecast.periods[2].inches - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_parse_temperatures_converts_celsius() {
+        let body = r#"{"properties": {
+            "maxTemperature": {"uom": "wmoUnit:degC", "values": [
+                {"validTime": "2025-01-15T14:00:00+00:00/PT11H", "value": 20}
+            ]},
+            "minTemperature": {"uom": "wmoUnit:degC", "values": [
+                {"validTime": "2025-01-16T02:00:00+00:00/PT14H", "value": null},
+                {"validTime": "2025-01-15T02:00:00+00:00/PT14H", "value": 5}
+            ]}
+        }}"#;
+        let temperatures = parse_temperatures(body).unwrap();
+
+        assert_eq!(temperatures.highs.len(), 1);
+        assert!((temperatures.highs[0].fahrenheit - 68.0).abs() < 1e-9);
+        assert_eq!(temperatures.lows.len(), 1, "missing values are skipped");
+        assert!((temperatures.lows[0].fahrenheit - 41.0).abs() < 1e-9);
+        assert_eq!(
+            temperatures.lows[0].start,
+            Utc.with_ymd_and_hms(2025, 1, 15, 2, 0, 0).unwrap()
+        );
+        assert_eq!(
+            parse_temperatures(r#"{"properties": {}}"#).unwrap(),
+            GridTemperatures::default()
+        );
+    }
+
+    #[test]
+    fn test_window_prorates_partial_periods() {
+        let at = |h| Utc.with_ymd_and_hms(2025, 1, 15, h, 0, 0).unwrap();
+        let forecast = GridForecast {
+            updated_at: None,
+            periods: vec![
+                ForecastPeriod {
+                    start: at(0),
+                    end: at(6),
+                    inches: 0.6,
+                },
+                ForecastPeriod {
+                    start: at(6),
+                    end: at(12),
+                    inches: 0.3,
+                },
+            ],
+        };
+
+        let window = forecast.window(at(3), at(8));
+        assert_eq!(window.len(), 2);
+        assert_eq!((window[0].start, window[0].end), (at(3), at(6)));
+        assert!((window[0].inches - 0.3).abs() < 1e-9);
+        assert!((window[1].inches - 0.1).abs() < 1e-9);
+        assert!(forecast.window(at(12), at(18)).is_empty());
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("PT6H"), Some(chrono::Duration::hours(6)));
+        assert_eq!(parse_duration("P1DT12H"), Some(chrono::Duration::hours(36)));
+        assert_eq!(
+            parse_duration("PT1H30M"),
+            Some(chrono::Duration::minutes(90))
+        );
+        assert_eq!(parse_duration("P2D"), Some(chrono::Duration::days(2)));
+        assert_eq!(parse_duration("6H"), None);
+        assert_eq!(parse_duration("PT6"), None);
+    }
+}
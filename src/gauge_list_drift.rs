@@ -0,0 +1,232 @@
+// Gauge list parser drift detection
+//
+// When MCFCD changes the gauge list layout, the parser rarely fails outright: it tends
+// to find a handful of rows, or rows with every rainfall column empty. Storing that
+// scrape would overwrite good summaries and make most gauges look missing. Each scrape's
+// gauge count is therefore compared with the trailing average of recent accepted
+// scrapes; a count below GAUGE_LIST_DRIFT_RATIO of it is suspect and is not stored.
+//
+// Suspect counts never join the baseline, so a broken parser can't drag the average
+// down to meet it. A genuine shrink of the network looks the same as drift at first;
+// after GAUGE_LIST_DRIFT_ACCEPT_AFTER consecutive suspect scrapes the lower count is
+// accepted and becomes the new baseline.
+
+use std::collections::VecDeque;
+
+/// Accepted scrapes averaged into the baseline
+pub const DEFAULT_DRIFT_WINDOW: usize = 12;
+/// Fraction of the trailing average below which a scrape is suspect
+pub const DEFAULT_DRIFT_RATIO: f64 = 0.5;
+/// Consecutive suspect scrapes after which the lower count is accepted
+pub const DEFAULT_DRIFT_ACCEPT_AFTER: u32 = 24;
+/// Accepted scrapes needed before counts are compared
+const MIN_BASELINE: usize = 3;
+
+/// Thresholds for gauge list drift detection
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DriftConfig {
+    /// GAUGE_LIST_DRIFT_WINDOW, default 12
+    pub window: usize,
+    /// GAUGE_LIST_DRIFT_RATIO, default 0.5; 0 turns detection off
+    pub ratio: f64,
+    /// GAUGE_LIST_DRIFT_ACCEPT_AFTER, default 24
+    pub accept_after: u32,
+}
+
+impl Default for DriftConfig {
+    fn default() -> Self {
+        Self {
+            window: DEFAULT_DRIFT_WINDOW,
+            ratio: DEFAULT_DRIFT_RATIO,
+            accept_after: DEFAULT_DRIFT_ACCEPT_AFTER,
+        }
+    }
+}
+
+/// How one scrape's gauge count compares with recent scrapes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DriftVerdict {
+    /// Too few accepted scrapes to compare against yet
+    Learning,
+    Normal {
+        trailing_average: f64,
+    },
+    /// Far fewer gauges than usual; the scrape should not be stored
+    Suspect {
+        trailing_average: f64,
+        /// Suspect scrapes in a row, this one included
+        consecutive: u32,
+    },
+    /// The count stayed low for `accept_after` scrapes and is now the baseline
+    Accepted {
+        previous_average: f64,
+    },
+}
+
+impl DriftVerdict {
+    pub fn is_suspect(&self) -> bool {
+        matches!(self, DriftVerdict::Suspect { .. })
+    }
+}
+
+/// Trailing gauge counts of accepted scrapes
+#[derive(Debug, Clone)]
+pub struct DriftMonitor {
+    config: DriftConfig,
+    /// Oldest first, at most `config.window` long
+    history: VecDeque<usize>,
+    consecutive_suspect: u32,
+}
+
+impl DriftMonitor {
+    pub fn new(config: DriftConfig) -> Self {
+        Self {
+            config,
+            history: VecDeque::with_capacity(config.window),
+            consecutive_suspect: 0,
+        }
+    }
+
+    /// Start from earlier accepted counts (oldest first), e.g. recorded job runs, so a
+    /// restart doesn't have to relearn the baseline
+    pub fn with_history(mut self, counts: impl IntoIterator<Item = usize>) -> Self {
+        for count in counts {
+            self.push(count);
+        }
+        self
+    }
+
+    /// Average gauge count of the accepted scrapes, once there are enough of them
+    pub fn trailing_average(&self) -> Option<f64> {
+        if self.history.len() < MIN_BASELINE {
+            return None;
+        }
+        Some(self.history.iter().sum::<usize>() as f64 / self.history.len() as f64)
+    }
+
+    /// Judge one scrape's gauge count; anything but Suspect joins the baseline
+    pub fn observe(&mut self, count: usize) -> DriftVerdict {
+        let Some(trailing_average) = self.trailing_average() else {
+            self.push(count);
+            return DriftVerdict::Learning;
+        };
+
+        if (count as f64) >= trailing_average * self.config.ratio {
+            self.consecutive_suspect = 0;
+            self.push(count);
+            return DriftVerdict::Normal { trailing_average };
+        }
+
+        self.consecutive_suspect += 1;
+        if self.config.accept_after > 0 && self.consecutive_suspect >= self.config.accept_after {
+            self.consecutive_suspect = 0;
+            self.history.clear();
+            self.push(count);
+            return DriftVerdict::Accepted {
+                previous_average: trailing_average,
+            };
+        }
+        DriftVerdict::Suspect {
+            trailing_average,
+            consecutive: self.consecutive_suspect,
+        }
+    }
+
+    fn push(&mut self, count: usize) {
+        if self.config.window == 0 {
+            return;
+        }
+        while self.history.len() >= self.config.window {
+            self.history.pop_front();
+        }
+        self.history.push_back(count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(history: &[usize]) -> DriftMonitor {
+        DriftMonitor::new(DriftConfig {
+            window: 4,
+            ratio: 0.5,
+            accept_after: 3,
+        })
+        .with_history(history.iter().copied())
+    }
+
+    #[test]
+    fn test_learns_before_judging() {
+        let mut monitor = monitor(&[]);
+        assert_eq!(monitor.observe(300), DriftVerdict::Learning);
+        assert_eq!(monitor.observe(10), DriftVerdict::Learning);
+        assert_eq!(monitor.trailing_average(), None);
+        assert_eq!(monitor.observe(290), DriftVerdict::Learning);
+        assert_eq!(monitor.trailing_average(), Some(200.0));
+    }
+
+    #[test]
+    fn test_sharp_drop_is_suspect_and_kept_out_of_baseline() {
+        let mut monitor = monitor(&[300, 300, 300]);
+        assert_eq!(
+            monitor.observe(280),
+            DriftVerdict::Normal {
+                trailing_average: 300.0
+            }
+        );
+        assert_eq!(monitor.trailing_average(), Some(295.0));
+
+        let verdict = monitor.observe(12);
+        assert_eq!(
+            verdict,
+            DriftVerdict::Suspect {
+                trailing_average: 295.0,
+                consecutive: 1
+            }
+        );
+        assert!(verdict.is_suspect());
+        assert_eq!(monitor.trailing_average(), Some(295.0));
+
+        // A good scrape resets the streak
+        assert!(!monitor.observe(300).is_suspect());
+        assert!(matches!(
+            monitor.observe(0),
+            DriftVerdict::Suspect { consecutive: 1, .. }
+        ));
+    }
+
+    #[test]
+    fn test_persistent_drop_is_accepted() {
+        let mut monitor = monitor(&[300, 300, 300]);
+        assert!(monitor.observe(100).is_suspect());
+        assert!(monitor.observe(100).is_suspect());
+        assert_eq!(
+            monitor.observe(100),
+            DriftVerdict::Accepted {
+                previous_average: 300.0
+            }
+        );
+        // The new baseline has to be learned again
+        assert_eq!(monitor.trailing_average(), None);
+        monitor.observe(100);
+        monitor.observe(100);
+        assert!(!monitor.observe(90).is_suspect());
+    }
+
+    #[test]
+    fn test_window_drops_oldest_counts() {
+        let monitor = monitor(&[100, 300, 300, 300, 300]);
+        assert_eq!(monitor.trailing_average(), Some(300.0));
+    }
+
+    #[test]
+    fn test_zero_ratio_disables_detection() {
+        let mut monitor = DriftMonitor::new(DriftConfig {
+            ratio: 0.0,
+            ..DriftConfig::default()
+        })
+        .with_history([300, 300, 300]);
+        assert!(!monitor.observe(0).is_suspect());
+    }
+}
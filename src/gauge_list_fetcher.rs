@@ -1,20 +1,27 @@
+use std::fmt;
+
+use scraper::{ElementRef, Html, Selector};
 use serde::{Deserialize, Serialize};
-use tracing::{debug, instrument, warn};
+use tracing::{debug, error, instrument, warn};
 
-use crate::fetch_error::FetchError;
-use crate::utils;
+use crate::fetch_error::{FetchError, ValueError};
+use crate::fetcher::parse_inches;
+use crate::payload_archive::{PayloadArchive, PayloadSource};
+use crate::politeness::Politeness;
+use crate::station_id::StationId;
+use crate::units::Inches;
 
 // Note: This is the "fetcher" version of GaugeSummary (before being persisted)
 // The DB model GaugeSummary (in db/models.rs) includes id, timestamps, etc.
 // In the repository, import as: use crate::gauge_list_fetcher::GaugeSummary as FetchedGauge;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GaugeSummary {
-    pub station_id: String,
+    pub station_id: StationId,
     pub gauge_name: String,
     pub city_town: Option<String>,
     pub elevation_ft: Option<i32>,
-    pub rainfall_past_6h_inches: Option<f64>,
-    pub rainfall_past_24h_inches: Option<f64>,
+    pub rainfall_past_6h_inches: Option<Inches>,
+    pub rainfall_past_24h_inches: Option<Inches>,
     pub msp_forecast_zone: Option<String>,
     pub general_location: Option<String>,
 }
@@ -23,12 +30,117 @@ pub struct GaugeSummary {
 pub struct GaugeListFetcher {
     client: reqwest::Client,
     url: String,
+    politeness: Politeness,
+    archive: Option<PayloadArchive>,
+}
+
+/// Layout of a gauge list response
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ListFormat {
+    /// Fixed-width plain text report (ev_rain.txt)
+    Text,
+    /// The same report rendered as an HTML table
+    Html,
+}
+
+impl fmt::Display for ListFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ListFormat::Text => "text",
+            ListFormat::Html => "HTML",
+        })
+    }
+}
+
+impl ListFormat {
+    /// HTML when the Content-Type says so or the body looks like markup, otherwise text
+    fn detect(content_type: Option<&str>, body: &str) -> Self {
+        if content_type.is_some_and(|ct| ct.to_ascii_lowercase().contains("html")) {
+            return ListFormat::Html;
+        }
+        let start: String = body.trim_start().chars().take(256).collect();
+        let start = start.to_ascii_lowercase();
+        if start.starts_with("<!doctype html")
+            || start.starts_with("<html")
+            || start.contains("<table")
+        {
+            ListFormat::Html
+        } else {
+            ListFormat::Text
+        }
+    }
+
+    fn other(self) -> Self {
+        match self {
+            ListFormat::Text => ListFormat::Html,
+            ListFormat::Html => ListFormat::Text,
+        }
+    }
+}
+
+/// Column positions of an HTML gauge table, found from its header text
+#[derive(Debug, Default)]
+struct HtmlColumns {
+    name: Option<usize>,
+    city: Option<usize>,
+    id: Option<usize>,
+    elevation: Option<usize>,
+    past_6h: Option<usize>,
+    past_24h: Option<usize>,
+    zone: Option<usize>,
+    location: Option<usize>,
+}
+
+impl HtmlColumns {
+    /// Match header labels (each column's header rows joined) to fields
+    fn from_labels(labels: &[String]) -> Self {
+        let mut columns = Self::default();
+        for (idx, label) in labels.iter().enumerate() {
+            let label = label.to_ascii_lowercase();
+            let words: Vec<&str> = label
+                .split(|c: char| !c.is_ascii_alphanumeric())
+                .filter(|w| !w.is_empty())
+                .collect();
+            let slot = if words.contains(&"id") {
+                &mut columns.id
+            } else if words.contains(&"city") || words.contains(&"town") {
+                &mut columns.city
+            } else if words.contains(&"name") {
+                &mut columns.name
+            } else if words.contains(&"elev") || words.contains(&"elevation") {
+                &mut columns.elevation
+            } else if words.contains(&"24") {
+                &mut columns.past_24h
+            } else if words.contains(&"6") {
+                &mut columns.past_6h
+            } else if words.contains(&"zone") {
+                &mut columns.zone
+            } else if words.contains(&"location") {
+                &mut columns.location
+            } else {
+                continue;
+            };
+            slot.get_or_insert(idx);
+        }
+        columns
+    }
+
+    fn is_complete(&self) -> bool {
+        self.id.is_some() && self.name.is_some()
+    }
+}
+
+/// Cell text with whitespace (including &nbsp;) collapsed
+fn cell_text(cell: ElementRef) -> String {
+    cell.text()
+        .flat_map(str::split_whitespace)
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
 /// Extract station ID (4 or 5 digits) from a string that may contain additional text
-/// Delegates to shared utils::extract_station_id()
-fn extract_station_id(value: &str) -> Result<String, FetchError> {
-    utils::extract_station_id(value).map_err(|_| FetchError::ParseError)
+fn extract_station_id(value: &str) -> Result<StationId, ValueError> {
+    StationId::extract(value).map_err(|e| ValueError::new("station ID", value, e))
 }
 
 impl GaugeListFetcher {
@@ -36,19 +148,194 @@ impl GaugeListFetcher {
         Self {
             client: reqwest::Client::new(),
             url,
+            politeness: Politeness::default(),
+            archive: None,
         }
     }
 
+    /// Send requests with the shared User-Agent, robots.txt checks, and host delay
+    pub fn with_politeness(mut self, politeness: Politeness) -> Self {
+        self.politeness = politeness;
+        self
+    }
+
+    /// Keep each fetched gauge list in `archive` for replay
+    pub fn with_archive(mut self, archive: PayloadArchive) -> Self {
+        self.archive = Some(archive);
+        self
+    }
+
     #[instrument(skip(self), fields(url = %self.url))]
     pub async fn fetch_gauge_list(&self) -> Result<Vec<GaugeSummary>, FetchError> {
         debug!("Sending HTTP request to gauge list URL");
-        let response = self.client.get(&self.url).send().await?;
+        let response = self
+            .politeness
+            .send(&self.client, self.client.get(&self.url))
+            .await?;
         debug!("Received HTTP response with status: {}", response.status());
+        let response = FetchError::check_status(response)?;
 
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
         let text = response.text().await?;
         debug!("Retrieved text content, size: {} bytes", text.len());
+        if let Some(archive) = &self.archive {
+            archive
+                .record(
+                    PayloadSource::GaugeList,
+                    &self.url,
+                    content_type.as_deref(),
+                    &text,
+                )
+                .await;
+        }
 
-        self.parse_text(&text)
+        self.parse_body(content_type.as_deref(), &text)
+    }
+
+    /// Parse with the layout the response appears to use, falling back to the other one
+    ///
+    /// MCFCD occasionally serves the report as an HTML table instead of plain text. A
+    /// response that yields gauges under neither layout is an error rather than an
+    /// empty list, so the scheduler reports it instead of storing nothing.
+    pub fn parse_body(
+        &self,
+        content_type: Option<&str>,
+        body: &str,
+    ) -> Result<Vec<GaugeSummary>, FetchError> {
+        let format = ListFormat::detect(content_type, body);
+        for (attempt, format) in [format, format.other()].into_iter().enumerate() {
+            let gauges = match format {
+                ListFormat::Text => self.parse_text(body)?,
+                ListFormat::Html => self.parse_html(body),
+            };
+            if !gauges.is_empty() {
+                if attempt > 0 {
+                    warn!(%format, "Gauge list parsed only with the fallback layout");
+                }
+                return Ok(gauges);
+            }
+            debug!(%format, "No gauges found in gauge list layout");
+        }
+
+        error!(
+            content_type = content_type.unwrap_or("none"),
+            size = body.len(),
+            preview = %body.chars().take(200).collect::<String>(),
+            "Gauge list matched neither the text nor the HTML layout"
+        );
+        Err(FetchError::LayoutChanged(format!(
+            "gauge list ({} bytes, content type {}) has no gauges in text or HTML layout",
+            body.len(),
+            content_type.unwrap_or("none")
+        )))
+    }
+
+    /// Gauges from the first HTML table whose header names the gauge ID and name columns
+    #[instrument(skip(self, html), fields(html_size = html.len()))]
+    fn parse_html(&self, html: &str) -> Vec<GaugeSummary> {
+        debug!("Parsing gauge list HTML");
+        let document = Html::parse_document(html);
+        let table_selector = Selector::parse("table").unwrap();
+        let row_selector = Selector::parse("tr").unwrap();
+        let cell_selector = Selector::parse("th, td").unwrap();
+        let th_selector = Selector::parse("th").unwrap();
+
+        for table in document.select(&table_selector) {
+            let mut labels: Vec<String> = Vec::new();
+            let mut columns = HtmlColumns::default();
+            let mut gauges = Vec::new();
+            let mut skipped_rows = 0;
+
+            for (row_idx, row) in table.select(&row_selector).enumerate() {
+                let cells: Vec<String> = row.select(&cell_selector).map(cell_text).collect();
+                if cells.iter().all(|c| c.is_empty()) {
+                    continue;
+                }
+
+                // Header rows (th cells, or anything before the columns are known) are
+                // joined per column, as the report splits headers over two rows
+                if !columns.is_complete() || row.select(&th_selector).next().is_some() {
+                    if gauges.is_empty() {
+                        for (idx, cell) in cells.iter().enumerate() {
+                            match labels.get_mut(idx) {
+                                Some(label) => {
+                                    label.push(' ');
+                                    label.push_str(cell);
+                                }
+                                None => labels.push(cell.clone()),
+                            }
+                        }
+                        columns = HtmlColumns::from_labels(&labels);
+                    }
+                    continue;
+                }
+
+                match Self::parse_html_row(&columns, &cells) {
+                    Ok(gauge) => gauges.push(gauge),
+                    Err(e) => {
+                        warn!(
+                            "Failed to parse gauge table row: {} - {:?}",
+                            e.at_line(row_idx + 1),
+                            cells
+                        );
+                        skipped_rows += 1;
+                    }
+                }
+            }
+
+            if !gauges.is_empty() {
+                if skipped_rows > 0 {
+                    warn!("Skipped {} unparseable table rows", skipped_rows);
+                }
+                debug!("Successfully parsed {} gauges from HTML", gauges.len());
+                return gauges;
+            }
+        }
+
+        Vec::new()
+    }
+
+    fn parse_html_row(columns: &HtmlColumns, cells: &[String]) -> Result<GaugeSummary, ValueError> {
+        let cell = |idx: Option<usize>| {
+            idx.and_then(|i| cells.get(i))
+                .map(String::as_str)
+                .filter(|v| !v.is_empty())
+        };
+        // "----" marks missing data in the report
+        let value = |idx: Option<usize>| cell(idx).filter(|v| !v.starts_with("--"));
+        let inches =
+            |field, idx: Option<usize>| value(idx).map(|v| parse_inches(field, v)).transpose();
+        let missing = |field| ValueError::new(field, "", "empty cell");
+
+        let station_id =
+            extract_station_id(cell(columns.id).ok_or_else(|| missing("station ID"))?)?;
+        let gauge_name = cell(columns.name)
+            .ok_or_else(|| missing("gauge name"))?
+            .to_string();
+        let elevation_ft = value(columns.elevation)
+            .map(|v| {
+                v.replace(',', "")
+                    .parse::<i32>()
+                    .map_err(|e| ValueError::new("elevation", v, e))
+            })
+            .transpose()?;
+
+        Ok(GaugeSummary {
+            station_id,
+            gauge_name,
+            city_town: cell(columns.city).map(String::from),
+            elevation_ft,
+            rainfall_past_6h_inches: inches("6-hour rainfall", columns.past_6h)?,
+            rainfall_past_24h_inches: inches("24-hour rainfall", columns.past_24h)?,
+            msp_forecast_zone: cell(columns.zone)
+                .filter(|zone| *zone != "None")
+                .map(String::from),
+            general_location: cell(columns.location).map(String::from),
+        })
     }
 
     #[instrument(skip(self, text), fields(text_size = text.len()))]
@@ -59,7 +346,7 @@ impl GaugeListFetcher {
         let mut skipped_lines = 0;
         let mut found_gage_header = false;
 
-        for line in text.lines() {
+        for (line_idx, line) in text.lines().enumerate() {
             let trimmed = line.trim();
 
             // Skip empty lines
@@ -110,7 +397,11 @@ impl GaugeListFetcher {
                     gauges.push(gauge);
                 }
                 Err(e) => {
-                    warn!("Failed to parse gauge line: {} - {}", e, trimmed);
+                    warn!(
+                        "Failed to parse gauge line: {} - {}",
+                        e.at_line(line_idx + 1),
+                        trimmed
+                    );
                     skipped_lines += 1;
                 }
             }
@@ -124,7 +415,7 @@ impl GaugeListFetcher {
         Ok(gauges)
     }
 
-    fn parse_gauge_line(&self, line: &str) -> Result<GaugeSummary, FetchError> {
+    fn parse_gauge_line(&self, line: &str) -> Result<GaugeSummary, ValueError> {
         // Expected format (whitespace-delimited):
         // Gauge Name              City/Town       ID      Elev   6hr    24hr   Zone   Location
         // 4th of July Wash        Agua Caliente   41200   1120   0.00   0.00   None   21 mi. W of Old US80
@@ -132,7 +423,11 @@ impl GaugeListFetcher {
         let parts: Vec<&str> = line.split_whitespace().collect();
 
         if parts.len() < 7 {
-            return Err(FetchError::ParseError);
+            return Err(ValueError::new(
+                "gauge row",
+                line,
+                format!("{} columns, expected at least 7", parts.len()),
+            ));
         }
 
         // The challenge: gauge name and city/town can have multiple words
@@ -152,7 +447,9 @@ impl GaugeListFetcher {
             }
         }
 
-        let station_id_idx = station_id_idx.ok_or(FetchError::ParseError)?;
+        let station_id_idx = station_id_idx.ok_or_else(|| {
+            ValueError::new("gauge row", line, "no station ID followed by an elevation")
+        })?;
 
         // Parse fields based on station_id position
         // Before station_id: gauge_name and city_town
@@ -160,7 +457,11 @@ impl GaugeListFetcher {
         // After: elevation, 6hr, 24hr, zone, location...
 
         if station_id_idx < 2 || station_id_idx + 5 >= parts.len() {
-            return Err(FetchError::ParseError);
+            return Err(ValueError::new(
+                "gauge row",
+                line,
+                "too few columns around the station ID",
+            ));
         }
 
         // Extract station_id (5 digits only, ignore any text like "since 03/09/18")
@@ -169,17 +470,13 @@ impl GaugeListFetcher {
         // Elevation (next field after station_id)
         let elevation_ft = parts[station_id_idx + 1]
             .parse::<i32>()
-            .map_err(|e| FetchError::NumberError(e.to_string()))?;
+            .map_err(|e| ValueError::new("elevation", parts[station_id_idx + 1], e))?;
 
         // 6hr rainfall
-        let rainfall_past_6h = parts[station_id_idx + 2]
-            .parse::<f64>()
-            .map_err(|e| FetchError::NumberError(e.to_string()))?;
+        let rainfall_past_6h = parse_inches("6-hour rainfall", parts[station_id_idx + 2])?;
 
         // 24hr rainfall
-        let rainfall_past_24h = parts[station_id_idx + 3]
-            .parse::<f64>()
-            .map_err(|e| FetchError::NumberError(e.to_string()))?;
+        let rainfall_past_24h = parse_inches("24-hour rainfall", parts[station_id_idx + 3])?;
 
         // MSP Forecast Zone
         let msp_zone = parts.get(station_id_idx + 4).map(|s| s.to_string());
@@ -207,7 +504,7 @@ impl GaugeListFetcher {
         let gauge_name = if station_id_idx >= 2 {
             parts[0..station_id_idx - 1].join(" ")
         } else {
-            return Err(FetchError::ParseError);
+            return Err(ValueError::new("gauge name", line, "missing"));
         };
 
         Ok(GaugeSummary {
@@ -241,8 +538,8 @@ mod tests {
         assert_eq!(gauge.gauge_name, "4th of July Wash Agua");
         assert_eq!(gauge.city_town, Some("Caliente".to_string()));
         assert_eq!(gauge.elevation_ft, Some(1120));
-        assert_eq!(gauge.rainfall_past_6h_inches, Some(0.00));
-        assert_eq!(gauge.rainfall_past_24h_inches, Some(0.00));
+        assert_eq!(gauge.rainfall_past_6h_inches, Some(Inches::ZERO));
+        assert_eq!(gauge.rainfall_past_24h_inches, Some(Inches::ZERO));
         assert_eq!(
             gauge.general_location,
             Some("21 mi. W of Old US80 on Agua Caliente Road".to_string())
@@ -306,6 +603,88 @@ Test Gauge One          Phoenix         12345   1000   1.00   2.00   AZ001  Nort
         assert_eq!(gauges[0].gauge_name, "Test Gauge One");
     }
 
+    const HTML_REPORT: &str = r#"<!DOCTYPE html>
+<html><body>
+<h2>Precipitation Report for ALL FCDMC Rain Stations</h2>
+<table>
+<tr><th>Gage</th><th>In or Nearest</th><th>Gage</th><th>Elev.</th><th>Rainfall</th><th>Rainfall</th><th>MSP Forecast</th><th>General</th></tr>
+<tr><th>Name</th><th>City / Town</th><th>ID</th><th>(ft)</th><th>Past 6 hr</th><th>Past 24 hr</th><th>Zone</th><th>Location</th></tr>
+<tr><td>4th of July Wash</td><td>Agua Caliente</td><td>41200</td><td>1,120</td><td>0.00</td><td>0.04</td><td>None</td><td>21 mi. W of Old US80</td></tr>
+<tr><td>Columbus&nbsp;Wash</td><td>Agua Caliente</td><td>40800 since 03/09/18</td><td>705</td><td>----</td><td>0.00</td><td>AZ023</td><td>8 mi. N of Agua Caliente</td></tr>
+<tr><td>Broken Row</td><td>Nowhere</td><td>n/a</td><td>1</td><td>0.00</td><td>0.00</td><td>None</td><td>Here</td></tr>
+</table>
+</body></html>"#;
+
+    const TEXT_REPORT: &str = r#"
+     Gage                          In or Nearest      Gage    Elev.  Rainfall    Rainfall      MSP Forecast       General
+     Name                           City / Town        ID     (ft)   Past 6 hr  Past 24 hr         Zone           Location
+--------------------------------   ---------------   ------  ------  ---------  ----------  ------------------   --------------------------------------------
+4th of July Wash                   Agua Caliente      41200   1120      0.00       0.00     None                  21 mi. W of Old US80 on Agua Caliente Road
+"#;
+
+    #[test]
+    fn test_list_format_detection() {
+        assert_eq!(
+            ListFormat::detect(Some("text/html; charset=utf-8"), "plain"),
+            ListFormat::Html
+        );
+        assert_eq!(
+            ListFormat::detect(Some("text/plain"), TEXT_REPORT),
+            ListFormat::Text
+        );
+        assert_eq!(ListFormat::detect(None, HTML_REPORT), ListFormat::Html);
+        assert_eq!(ListFormat::detect(None, TEXT_REPORT), ListFormat::Text);
+    }
+
+    #[test]
+    fn test_parse_html_table() {
+        let fetcher = GaugeListFetcher::new("".to_string());
+        let gauges = fetcher.parse_html(HTML_REPORT);
+
+        assert_eq!(gauges.len(), 2);
+        assert_eq!(gauges[0].station_id, "41200");
+        assert_eq!(gauges[0].gauge_name, "4th of July Wash");
+        assert_eq!(gauges[0].city_town.as_deref(), Some("Agua Caliente"));
+        assert_eq!(gauges[0].elevation_ft, Some(1120));
+        assert_eq!(
+            gauges[0].rainfall_past_24h_inches.map(Inches::value),
+            Some(0.04)
+        );
+        assert_eq!(gauges[0].msp_forecast_zone, None);
+        assert_eq!(
+            gauges[0].general_location.as_deref(),
+            Some("21 mi. W of Old US80")
+        );
+
+        assert_eq!(gauges[1].station_id, "40800");
+        assert_eq!(gauges[1].gauge_name, "Columbus Wash");
+        assert_eq!(gauges[1].rainfall_past_6h_inches, None);
+        assert_eq!(gauges[1].msp_forecast_zone.as_deref(), Some("AZ023"));
+    }
+
+    #[test]
+    fn test_parse_body_falls_back_to_other_layout() {
+        let fetcher = GaugeListFetcher::new("".to_string());
+
+        // Mislabelled either way, the other parser still finds the gauges
+        let gauges = fetcher.parse_body(Some("text/plain"), HTML_REPORT).unwrap();
+        assert_eq!(gauges.len(), 2);
+        let gauges = fetcher.parse_body(Some("text/html"), TEXT_REPORT).unwrap();
+        assert_eq!(gauges.len(), 1);
+        assert_eq!(gauges[0].station_id, "41200");
+    }
+
+    #[test]
+    fn test_parse_body_rejects_unrecognized_layout() {
+        let fetcher = GaugeListFetcher::new("".to_string());
+        let body = "<html><body><p>Service temporarily unavailable</p></body></html>";
+
+        match fetcher.parse_body(Some("text/html"), body) {
+            Err(FetchError::LayoutChanged(msg)) => assert!(msg.contains("text/html")),
+            other => panic!("expected UnrecognizedFormat, got {other:?}"),
+        }
+    }
+
     #[test]
     fn test_extract_station_id_clean() {
         let result = extract_station_id("29200");
@@ -380,4 +759,78 @@ Test Gauge One          Phoenix         12345   1000   1.00   2.00   AZ001  Nort
         let result = extract_station_id("123456");
         assert!(result.is_err());
     }
+
+    mod properties {
+        use super::*;
+        use proptest::prelude::*;
+        use std::sync::LazyLock;
+
+        // Building a reqwest client per case dominates the run time otherwise
+        static FETCHER: LazyLock<GaugeListFetcher> =
+            LazyLock::new(|| GaugeListFetcher::new(String::new()));
+
+        proptest! {
+            #[test]
+            fn parse_gauge_line_never_panics(line in "\\PC{0,120}") {
+                let _ = FETCHER.parse_gauge_line(&line);
+            }
+
+            #[test]
+            fn parse_gauge_line_never_panics_on_numeric_soup(
+                tokens in prop::collection::vec("[0-9.\\-]{1,6}|[A-Za-z]{1,8}|NaN|inf", 0..14)
+            ) {
+                let _ = FETCHER.parse_gauge_line(&tokens.join(" "));
+            }
+
+            #[test]
+            fn parse_html_never_panics(rows in prop::collection::vec(prop::collection::vec("\\PC{0,20}", 0..10), 0..10)) {
+                let rows: String = rows
+                    .iter()
+                    .map(|cells| format!("<tr><td>{}</td></tr>", cells.join("</td><td>")))
+                    .collect();
+                let html = format!(
+                    "<table><tr><th>Gage Name</th><th>City</th><th>ID</th><th>Elev</th></tr>{rows}</table>"
+                );
+                let _ = FETCHER.parse_html(&html);
+            }
+
+            #[test]
+            fn parse_text_never_panics(body in prop::collection::vec("\\PC{0,80}", 0..20)) {
+                let text = format!(
+                    "Gage Elev Rainfall\nName City ID\n---------\n{}",
+                    body.join("\n")
+                );
+                prop_assert!(FETCHER.parse_text(&text).is_ok());
+            }
+
+            #[test]
+            fn well_formed_lines_round_trip(
+                name in "[A-Za-z][A-Za-z ]{0,20}[A-Za-z]",
+                city in "[A-Za-z]{1,12}",
+                station_id in 1000u32..100000,
+                elevation in 0i32..13000,
+                six_hour in 0u32..1000,
+                day in 0u32..1000,
+            ) {
+                let line = format!(
+                    "{name}   {city}   {station_id}   {elevation}   {}.{:02}   {}.{:02}   None   2 mi. N of Town",
+                    six_hour / 100, six_hour % 100, day / 100, day % 100
+                );
+
+                let gauge = FETCHER.parse_gauge_line(&line).unwrap();
+                prop_assert_eq!(gauge.station_id, station_id.to_string());
+                prop_assert_eq!(gauge.city_town.as_deref(), Some(city.as_str()));
+                prop_assert_eq!(gauge.elevation_ft, Some(elevation));
+                prop_assert_eq!(gauge.rainfall_past_6h_inches.map(Inches::value), Some(six_hour as f64 / 100.0));
+                prop_assert_eq!(gauge.rainfall_past_24h_inches.map(Inches::value), Some(day as f64 / 100.0));
+                prop_assert_eq!(gauge.msp_forecast_zone, None);
+            }
+
+            #[test]
+            fn non_finite_rainfall_is_rejected(amount in "NaN|inf|-inf|infinity") {
+                let line = format!("Some Wash   Town   41200   1120   {amount}   0.00   None   Here");
+                prop_assert!(FETCHER.parse_gauge_line(&line).is_err());
+            }
+        }
+    }
 }
@@ -0,0 +1,397 @@
+// Reverse geocoding of gauge coordinates
+//
+// Many gauges have coordinates but no city. Two providers can name the place a gauge sits
+// in:
+// - A Nominatim-compatible reverse endpoint (GEOCODE_URL, e.g.
+//   https://nominatim.openstreetmap.org/reverse), queried at most once per second as the
+//   public instance's usage policy requires.
+// - An offline places file (GEOCODE_PLACES_FILE), a CSV with `name,latitude,longitude,city`
+//   columns such as a county places export; the nearest place within
+//   MAX_PLACE_DISTANCE_MILES wins, and its `city` column (the municipality the place is
+//   in, empty for unincorporated places) fills the gauge's city.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Deserialize;
+use tracing::{debug, instrument};
+
+use crate::politeness::{PoliteError, Politeness, DEFAULT_USER_AGENT};
+
+/// How often the geocoding job looks for gauges to enrich
+pub const DEFAULT_GEOCODE_INTERVAL_MINUTES: u64 = 1440;
+
+/// Gauges looked up per run
+pub const DEFAULT_GEOCODE_BATCH_SIZE: usize = 50;
+
+/// Places farther than this from a gauge are not used to name it
+pub const MAX_PLACE_DISTANCE_MILES: f64 = 25.0;
+
+/// Spacing between requests to a remote provider (Nominatim allows one per second)
+const REMOTE_REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+
+const EARTH_RADIUS_MILES: f64 = 3958.8;
+
+#[derive(Debug, thiserror::Error)]
+pub enum GeocodeError {
+    #[error("HTTP request failed: {0}")]
+    Request(#[from] reqwest::Error),
+
+    #[error("Disallowed by robots.txt: {0}")]
+    Disallowed(String),
+
+    #[error("Paused after repeated failures from {0}")]
+    CircuitOpen(String),
+
+    #[error("Geocoder returned HTTP {0}")]
+    Status(u16),
+
+    #[error("Invalid geocoder response: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Failed to read places file {}: {source}", path.display())]
+    Places {
+        path: PathBuf,
+        #[source]
+        source: csv::Error,
+    },
+}
+
+impl From<PoliteError> for GeocodeError {
+    fn from(e: PoliteError) -> Self {
+        match e {
+            PoliteError::Request(e) => GeocodeError::Request(e),
+            PoliteError::Disallowed(url) => GeocodeError::Disallowed(url),
+            PoliteError::CircuitOpen(host) => GeocodeError::CircuitOpen(host),
+        }
+    }
+}
+
+/// Where the geocoding job looks up places
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GeocodeProvider {
+    /// Nominatim-compatible `/reverse` endpoint
+    Nominatim { url: String },
+    /// Offline places CSV
+    Places { path: PathBuf },
+}
+
+/// Settings for the optional geocoding job (disabled unless a provider is configured)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeocodeConfig {
+    pub provider: GeocodeProvider,
+    /// GEOCODE_INTERVAL_MINUTES, default 1440
+    pub interval_minutes: u64,
+    /// GEOCODE_BATCH_SIZE, default 50
+    pub batch_size: usize,
+}
+
+/// What a lookup found for a pair of coordinates
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Place {
+    /// Incorporated city or town containing the point
+    pub city: Option<String>,
+    /// Nearest named place (a neighborhood, village, or the city itself)
+    pub nearest_place: Option<String>,
+}
+
+/// Reverse geocoder for one configured provider
+#[derive(Clone)]
+pub enum ReverseGeocoder {
+    Nominatim(NominatimClient),
+    Places(Arc<PlacesDataset>),
+}
+
+impl ReverseGeocoder {
+    /// Build the geocoder, loading the places file for the offline provider
+    pub fn from_provider(provider: &GeocodeProvider) -> Result<Self, GeocodeError> {
+        match provider {
+            GeocodeProvider::Nominatim { url } => Ok(Self::Nominatim(NominatimClient::new(url))),
+            GeocodeProvider::Places { path } => {
+                Ok(Self::Places(Arc::new(PlacesDataset::load(path)?)))
+            }
+        }
+    }
+
+    /// Send remote lookups through the shared `politeness`
+    pub fn with_politeness(self, politeness: &Politeness) -> Self {
+        match self {
+            Self::Nominatim(client) => Self::Nominatim(client.with_politeness(politeness.clone())),
+            places => places,
+        }
+    }
+
+    /// Value recorded in `gauges.geocode_source`
+    pub fn source(&self) -> String {
+        match self {
+            Self::Nominatim(_) => "nominatim".to_string(),
+            Self::Places(dataset) => format!("places:{}", dataset.name),
+        }
+    }
+
+    /// Minimum spacing between lookups, for providers with a usage limit
+    pub fn request_interval(&self) -> Option<Duration> {
+        match self {
+            Self::Nominatim(_) => Some(REMOTE_REQUEST_INTERVAL),
+            Self::Places(_) => None,
+        }
+    }
+
+    /// Look up a point; Ok(None) when the provider knows no place there
+    pub async fn reverse(
+        &self,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<Option<Place>, GeocodeError> {
+        match self {
+            Self::Nominatim(client) => client.reverse(latitude, longitude).await,
+            Self::Places(dataset) => Ok(dataset.nearest(latitude, longitude)),
+        }
+    }
+}
+
+/// Client for a Nominatim-compatible reverse geocoding endpoint
+#[derive(Clone)]
+pub struct NominatimClient {
+    client: reqwest::Client,
+    url: String,
+    politeness: Politeness,
+}
+
+#[derive(Debug, Deserialize)]
+struct NominatimResponse {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    address: NominatimAddress,
+    /// Set instead of a place when nothing is found ("Unable to geocode")
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct NominatimAddress {
+    city: Option<String>,
+    town: Option<String>,
+    village: Option<String>,
+    hamlet: Option<String>,
+    suburb: Option<String>,
+    neighbourhood: Option<String>,
+}
+
+impl NominatimClient {
+    pub fn new(url: &str) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(30))
+                .user_agent(DEFAULT_USER_AGENT)
+                .build()
+                .expect("Failed to create HTTP client"),
+            url: url.to_string(),
+            politeness: Politeness::default(),
+        }
+    }
+
+    /// Send requests with the shared User-Agent, robots.txt checks, and host delay
+    pub fn with_politeness(mut self, politeness: Politeness) -> Self {
+        self.politeness = politeness;
+        self
+    }
+
+    #[instrument(skip(self))]
+    pub async fn reverse(
+        &self,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<Option<Place>, GeocodeError> {
+        let request = self.client.get(&self.url).query(&[
+            ("format", "jsonv2"),
+            ("lat", &latitude.to_string()),
+            ("lon", &longitude.to_string()),
+            ("zoom", "14"),
+            ("addressdetails", "1"),
+        ]);
+        let response = self.politeness.send(&self.client, request).await?;
+        if !response.status().is_success() {
+            return Err(GeocodeError::Status(response.status().as_u16()));
+        }
+
+        let body = response.text().await?;
+        debug!("Geocoder response: {} bytes", body.len());
+        parse_nominatim(&body)
+    }
+}
+
+/// Read a Nominatim `jsonv2` reverse response
+fn parse_nominatim(body: &str) -> Result<Option<Place>, GeocodeError> {
+    let response: NominatimResponse = serde_json::from_str(body)?;
+    if response.error.is_some() {
+        return Ok(None);
+    }
+
+    let address = response.address;
+    let city = address.city.or(address.town).or(address.village);
+    let nearest_place = response
+        .name
+        .filter(|name| !name.is_empty())
+        .or(address.neighbourhood)
+        .or(address.suburb)
+        .or(address.hamlet)
+        .or_else(|| city.clone());
+    if city.is_none() && nearest_place.is_none() {
+        return Ok(None);
+    }
+    Ok(Some(Place {
+        city,
+        nearest_place,
+    }))
+}
+
+/// One row of the offline places file
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PlaceRecord {
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Municipality the place is in; empty for unincorporated places
+    pub city: Option<String>,
+}
+
+/// Offline places searched for the nearest one to a gauge
+#[derive(Debug, Clone)]
+pub struct PlacesDataset {
+    /// File name, recorded as the geocode source
+    name: String,
+    places: Vec<PlaceRecord>,
+}
+
+impl PlacesDataset {
+    pub fn load(path: &Path) -> Result<Self, GeocodeError> {
+        let csv_error = |source| GeocodeError::Places {
+            path: path.to_path_buf(),
+            source,
+        };
+        let places = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_path(path)
+            .map_err(csv_error)?
+            .deserialize()
+            .collect::<Result<Vec<PlaceRecord>, _>>()
+            .map_err(csv_error)?;
+
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Ok(Self::new(name, places))
+    }
+
+    pub fn new(name: impl Into<String>, places: Vec<PlaceRecord>) -> Self {
+        Self {
+            name: name.into(),
+            places,
+        }
+    }
+
+    /// The nearest place within MAX_PLACE_DISTANCE_MILES
+    pub fn nearest(&self, latitude: f64, longitude: f64) -> Option<Place> {
+        self.places
+            .iter()
+            .map(|place| {
+                let distance = distance_miles(latitude, longitude, place.latitude, place.longitude);
+                (distance, place)
+            })
+            .filter(|(distance, _)| *distance <= MAX_PLACE_DISTANCE_MILES)
+            .min_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map(|(_, place)| Place {
+                city: place.city.clone(),
+                nearest_place: Some(place.name.clone()),
+            })
+    }
+}
+
+/// Great-circle distance between two points
+pub(crate) fn distance_miles(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (lat1, lat2) = (lat1.to_radians(), lat2.to_radians());
+    let d_lat = lat2 - lat1;
+    let d_lon = (lon2 - lon1).to_radians();
+    let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_MILES * a.sqrt().asin()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn place(name: &str, latitude: f64, longitude: f64, city: Option<&str>) -> PlaceRecord {
+        PlaceRecord {
+            name: name.to_string(),
+            latitude,
+            longitude,
+            city: city.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_distance_miles() {
+        // Phoenix to Tucson is about 106 miles
+        let distance = distance_miles(33.4484, -112.0740, 32.2226, -110.9747);
+        assert!((distance - 106.0).abs() < 2.0, "{distance}");
+    }
+
+    #[test]
+    fn test_nearest_place_within_range() {
+        let dataset = PlacesDataset::new(
+            "places.csv",
+            vec![
+                place("Sunnyslope", 33.58, -112.07, Some("Phoenix")),
+                place("New River", 33.92, -112.13, None),
+            ],
+        );
+
+        assert_eq!(
+            dataset.nearest(33.60, -112.06),
+            Some(Place {
+                city: Some("Phoenix".to_string()),
+                nearest_place: Some("Sunnyslope".to_string()),
+            })
+        );
+        assert_eq!(
+            dataset.nearest(33.90, -112.10).unwrap().city,
+            None,
+            "unincorporated places leave the city empty"
+        );
+        assert_eq!(dataset.nearest(35.2, -111.6), None);
+    }
+
+    #[test]
+    fn test_parse_nominatim() {
+        let body = r#"{
+            "name": "Dreamy Draw",
+            "address": {"suburb": "Sunnyslope", "city": "Phoenix", "county": "Maricopa County"}
+        }"#;
+        assert_eq!(
+            parse_nominatim(body).unwrap(),
+            Some(Place {
+                city: Some("Phoenix".to_string()),
+                nearest_place: Some("Dreamy Draw".to_string()),
+            })
+        );
+
+        let body =
+            r#"{"name": "", "address": {"hamlet": "Horseshoe", "county": "Maricopa County"}}"#;
+        assert_eq!(
+            parse_nominatim(body).unwrap(),
+            Some(Place {
+                city: None,
+                nearest_place: Some("Horseshoe".to_string()),
+            })
+        );
+
+        assert_eq!(
+            parse_nominatim(r#"{"error": "Unable to geocode"}"#).unwrap(),
+            None
+        );
+    }
+}
@@ -0,0 +1,287 @@
+// GIS export of the gauge network
+//
+// `historical-import export gis` writes every gauge with coordinates as a point, with its
+// metadata as attributes, for GIS tools that do not read the JSON API. Both formats are
+// written in Rust, without GDAL:
+// - Shapefile: <name>.shp, .shx, and .dbf, plus a WGS 84 .prj and a UTF-8 .cpg
+// - GeoPackage (`sqlite` builds): one <name>.gpkg holding every layer
+//
+// With forecast zones, gauges carry their assigned zone and the zone polygons are written
+// as a second layer: <name>_zones.shp, or a `forecast_zones` table in the GeoPackage.
+// Coordinates are longitude/latitude on WGS 84 (EPSG:4326). Shapefile attribute names are
+// limited to 10 characters and text to 254 bytes; longer values are cut at a character
+// boundary.
+
+#[cfg(feature = "sqlite")]
+mod gpkg;
+mod shp;
+
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::db::{DbError, GisGauge};
+use crate::zones::ZonePolygon;
+
+/// EPSG code of WGS 84 longitude/latitude
+pub const WGS84_SRS_ID: i32 = 4326;
+
+/// WGS 84 in the WKT that GIS tools expect in a .prj or GeoPackage SRS definition
+pub const WGS84_WKT: &str = concat!(
+    r#"GEOGCS["WGS 84",DATUM["WGS_1984",SPHEROID["WGS 84",6378137,298.257223563,"#,
+    r#"AUTHORITY["EPSG","7030"]],AUTHORITY["EPSG","6326"]],"#,
+    r#"PRIMEM["Greenwich",0,AUTHORITY["EPSG","8901"]],"#,
+    r#"UNIT["degree",0.0174532925199433,AUTHORITY["EPSG","9122"]],"#,
+    r#"AUTHORITY["EPSG","4326"]]"#
+);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum GisFormat {
+    Shapefile,
+    Geopackage,
+}
+
+impl GisFormat {
+    /// Extension of the main output file
+    pub fn extension(self) -> &'static str {
+        match self {
+            GisFormat::Shapefile => "shp",
+            GisFormat::Geopackage => "gpkg",
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GisError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Shapefile error: {0}")]
+    Shapefile(#[from] shapefile::Error),
+
+    #[error("Attribute table error: {0}")]
+    Attributes(#[from] shapefile::dbase::Error),
+
+    #[error("GeoPackage error: {0}")]
+    GeoPackage(#[source] sqlx::Error),
+
+    #[error("GeoPackage output requires a build with `--features sqlite`")]
+    Unsupported,
+
+    #[error("Zone {zone_id} has an invalid geometry: {message}")]
+    Geometry { zone_id: String, message: String },
+
+    #[error(transparent)]
+    Database(#[from] DbError),
+}
+
+/// A stored forecast zone with the number of gauges assigned to it
+#[derive(Debug, Clone)]
+pub struct ZoneFeature {
+    pub zone: ZonePolygon,
+    pub gauge_count: i64,
+}
+
+/// Files written and features in them
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct GisOutput {
+    pub files: Vec<PathBuf>,
+    pub gauges: usize,
+    /// Gauges left out because they have no coordinates
+    pub gauges_without_coordinates: usize,
+    /// Zone polygons written; None when zones were not requested
+    pub zones: Option<usize>,
+}
+
+/// Write gauges, and zones when given, to `path` in `format`
+///
+/// `path` gets the format's extension when it has none. Existing files are replaced.
+pub async fn write(
+    format: GisFormat,
+    path: &Path,
+    gauges: &[GisGauge],
+    zones: Option<&[ZoneFeature]>,
+) -> Result<GisOutput, GisError> {
+    let path = match path.extension() {
+        Some(_) => path.to_path_buf(),
+        None => path.with_extension(format.extension()),
+    };
+    let located: Vec<&GisGauge> = gauges.iter().filter(|g| position(g).is_some()).collect();
+
+    let files = match format {
+        GisFormat::Shapefile => shp::write(&path, &located, zones)?,
+        GisFormat::Geopackage => write_geopackage(&path, &located, zones).await?,
+    };
+
+    Ok(GisOutput {
+        files,
+        gauges: located.len(),
+        gauges_without_coordinates: gauges.len() - located.len(),
+        zones: zones.map(<[ZoneFeature]>::len),
+    })
+}
+
+#[cfg(feature = "sqlite")]
+async fn write_geopackage(
+    path: &Path,
+    gauges: &[&GisGauge],
+    zones: Option<&[ZoneFeature]>,
+) -> Result<Vec<PathBuf>, GisError> {
+    gpkg::write(path, gauges, zones).await
+}
+
+#[cfg(not(feature = "sqlite"))]
+async fn write_geopackage(
+    _path: &Path,
+    _gauges: &[&GisGauge],
+    _zones: Option<&[ZoneFeature]>,
+) -> Result<Vec<PathBuf>, GisError> {
+    Err(GisError::Unsupported)
+}
+
+/// A gauge's (longitude, latitude), when it has both
+fn position(gauge: &GisGauge) -> Option<(f64, f64)> {
+    gauge.longitude.zip(gauge.latitude)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn gauge(station_id: &str, position: Option<(f64, f64)>) -> GisGauge {
+        GisGauge {
+            station_id: station_id.to_string(),
+            station_name: Some("Aztec Park".to_string()),
+            city: Some("Scottsdale".to_string()),
+            county: Some("Maricopa".to_string()),
+            location_description: Some("Near Thunderbird & Frank Lloyd Wright".to_string()),
+            longitude: position.map(|p| p.0),
+            latitude: position.map(|p| p.1),
+            elevation_ft: Some(1465),
+            status: Some("Active".to_string()),
+            data_begins_date: NaiveDate::from_ymd_opt(1998, 6, 1),
+            data_ends_date: None,
+            forecast_zone: Some("E1".to_string()),
+        }
+    }
+
+    fn zone() -> ZoneFeature {
+        let geometry = serde_json::json!({
+            "type": "Polygon",
+            "coordinates": [
+                [[-112.5, 33.0], [-111.5, 33.0], [-111.5, 34.0], [-112.5, 34.0], [-112.5, 33.0]],
+                [[-112.0, 33.4], [-111.9, 33.4], [-111.9, 33.5], [-112.0, 33.5], [-112.0, 33.4]]
+            ]
+        });
+        ZoneFeature {
+            zone: ZonePolygon::from_geometry("E1", Some("East Valley".to_string()), geometry)
+                .unwrap(),
+            gauge_count: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_write_skips_gauges_without_coordinates() {
+        let dir = tempfile::tempdir().unwrap();
+        let gauges = [
+            gauge("59700", Some((-111.86545, 33.61006))),
+            gauge("41200", None),
+        ];
+
+        let output = write(
+            GisFormat::Shapefile,
+            &dir.path().join("gauges"),
+            &gauges,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(output.gauges, 1);
+        assert_eq!(output.gauges_without_coordinates, 1);
+        assert_eq!(output.zones, None);
+        assert_eq!(output.files[0], dir.path().join("gauges.shp"));
+    }
+
+    #[tokio::test]
+    async fn test_shapefile_round_trip_with_zones() {
+        use shapefile::dbase::{FieldValue, Record};
+        use shapefile::{Point, Polygon};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("network.shp");
+        let gauges = [gauge("59700", Some((-111.86545, 33.61006)))];
+        let zones = [zone()];
+
+        let output = write(GisFormat::Shapefile, &path, &gauges, Some(&zones))
+            .await
+            .unwrap();
+        assert_eq!(output.zones, Some(1));
+        assert_eq!(output.files.len(), 10);
+        assert!(std::fs::read_to_string(path.with_extension("prj"))
+            .unwrap()
+            .starts_with("GEOGCS[\"WGS 84\""));
+
+        let points = shapefile::read_as::<_, Point, Record>(&path).unwrap();
+        let (point, record) = &points[0];
+        assert_eq!((point.x, point.y), (-111.86545, 33.61006));
+        assert_eq!(
+            record.get("STATION"),
+            Some(&FieldValue::Character(Some("59700".to_string())))
+        );
+        assert_eq!(
+            record.get("ELEV_FT"),
+            Some(&FieldValue::Numeric(Some(1465.0)))
+        );
+        assert_eq!(
+            record.get("ZONE"),
+            Some(&FieldValue::Character(Some("E1".to_string())))
+        );
+
+        let polygons =
+            shapefile::read_as::<_, Polygon, Record>(dir.path().join("network_zones.shp")).unwrap();
+        let (polygon, record) = &polygons[0];
+        assert_eq!(polygon.rings().len(), 2, "outer ring and hole");
+        assert_eq!(record.get("GAUGES"), Some(&FieldValue::Numeric(Some(1.0))));
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_geopackage_layers() {
+        use sqlx::{Connection, Row, SqliteConnection};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("network.gpkg");
+        let gauges = [gauge("59700", Some((-111.86545, 33.61006)))];
+        let zones = [zone()];
+        write(GisFormat::Geopackage, &path, &gauges, Some(&zones))
+            .await
+            .unwrap();
+
+        let url = format!("sqlite:{}", path.display());
+        let mut conn = SqliteConnection::connect(&url).await.unwrap();
+        let application_id: i32 = sqlx::query_scalar("PRAGMA application_id")
+            .fetch_one(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(application_id, 0x4750_4B47);
+
+        let layers: Vec<String> =
+            sqlx::query_scalar("SELECT table_name FROM gpkg_geometry_columns ORDER BY table_name")
+                .fetch_all(&mut conn)
+                .await
+                .unwrap();
+        assert_eq!(layers, ["forecast_zones", "gauges"]);
+
+        let row = sqlx::query("SELECT geom, zone FROM gauges WHERE station_id = '59700'")
+            .fetch_one(&mut conn)
+            .await
+            .unwrap();
+        let geom: Vec<u8> = row.get("geom");
+        assert_eq!(&geom[..4], b"GP\0\x01");
+        assert_eq!(geom.len(), 8 + 21, "header and WKB point");
+        assert_eq!(row.get::<Option<String>, _>("zone").as_deref(), Some("E1"));
+    }
+}
@@ -0,0 +1,294 @@
+// GeoPackage output (OGC GeoPackage 1.3), written through the sqlx SQLite driver
+//
+// Geometries are GeoPackage binary: an 8-byte header naming the SRS, an optional
+// envelope, then little-endian WKB. Gauges are POINTs in a `gauges` table; zones are
+// MULTIPOLYGONs in `forecast_zones`.
+
+use std::path::{Path, PathBuf};
+
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{Connection, SqliteConnection};
+
+use super::{position, GisError, ZoneFeature, WGS84_SRS_ID, WGS84_WKT};
+use crate::db::GisGauge;
+use crate::zones::Polygon;
+
+/// "GPKG" as the SQLite application_id
+const APPLICATION_ID: i32 = 0x4750_4B47;
+
+/// GeoPackage 1.3.0 as the SQLite user_version
+const USER_VERSION: i32 = 10300;
+
+const WKB_POINT: u32 = 1;
+const WKB_POLYGON: u32 = 3;
+const WKB_MULTIPOLYGON: u32 = 6;
+
+/// Flags byte: little-endian, no envelope
+const FLAGS_NO_ENVELOPE: u8 = 0b0000_0001;
+
+/// Flags byte: little-endian, [min_x, max_x, min_y, max_y] envelope
+const FLAGS_XY_ENVELOPE: u8 = 0b0000_0011;
+
+const SCHEMA: &str = r#"
+CREATE TABLE gpkg_spatial_ref_sys (
+    srs_name TEXT NOT NULL,
+    srs_id INTEGER PRIMARY KEY,
+    organization TEXT NOT NULL,
+    organization_coordsys_id INTEGER NOT NULL,
+    definition TEXT NOT NULL,
+    description TEXT
+);
+CREATE TABLE gpkg_contents (
+    table_name TEXT NOT NULL PRIMARY KEY,
+    data_type TEXT NOT NULL,
+    identifier TEXT UNIQUE,
+    description TEXT DEFAULT '',
+    last_change DATETIME NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    min_x DOUBLE,
+    min_y DOUBLE,
+    max_x DOUBLE,
+    max_y DOUBLE,
+    srs_id INTEGER REFERENCES gpkg_spatial_ref_sys(srs_id)
+);
+CREATE TABLE gpkg_geometry_columns (
+    table_name TEXT NOT NULL UNIQUE REFERENCES gpkg_contents(table_name),
+    column_name TEXT NOT NULL,
+    geometry_type_name TEXT NOT NULL,
+    srs_id INTEGER NOT NULL REFERENCES gpkg_spatial_ref_sys(srs_id),
+    z TINYINT NOT NULL,
+    m TINYINT NOT NULL,
+    PRIMARY KEY (table_name, column_name)
+);
+CREATE TABLE gauges (
+    fid INTEGER PRIMARY KEY AUTOINCREMENT,
+    geom POINT NOT NULL,
+    station_id TEXT NOT NULL UNIQUE,
+    name TEXT,
+    city TEXT,
+    county TEXT,
+    location TEXT,
+    elevation_ft INTEGER,
+    status TEXT,
+    data_begins DATE,
+    data_ends DATE,
+    latitude DOUBLE NOT NULL,
+    longitude DOUBLE NOT NULL,
+    zone TEXT
+);
+"#;
+
+const ZONES_SCHEMA: &str = r#"
+CREATE TABLE forecast_zones (
+    fid INTEGER PRIMARY KEY AUTOINCREMENT,
+    geom MULTIPOLYGON NOT NULL,
+    zone_id TEXT NOT NULL UNIQUE,
+    name TEXT,
+    gauge_count INTEGER NOT NULL
+);
+"#;
+
+/// Write every layer to a new GeoPackage at `path`, replacing any file there
+pub async fn write(
+    path: &Path,
+    gauges: &[&GisGauge],
+    zones: Option<&[ZoneFeature]>,
+) -> Result<Vec<PathBuf>, GisError> {
+    if path.exists() {
+        std::fs::remove_file(path)?;
+    }
+    let options = SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(true);
+    let mut conn = SqliteConnection::connect_with(&options)
+        .await
+        .map_err(GisError::GeoPackage)?;
+    write_layers(&mut conn, gauges, zones)
+        .await
+        .map_err(GisError::GeoPackage)?;
+    conn.close().await.map_err(GisError::GeoPackage)?;
+
+    Ok(vec![path.to_path_buf()])
+}
+
+async fn write_layers(
+    conn: &mut SqliteConnection,
+    gauges: &[&GisGauge],
+    zones: Option<&[ZoneFeature]>,
+) -> Result<(), sqlx::Error> {
+    let mut tx = conn.begin().await?;
+    sqlx::raw_sql(&format!(
+        "PRAGMA application_id = {APPLICATION_ID}; PRAGMA user_version = {USER_VERSION};"
+    ))
+    .execute(&mut *tx)
+    .await?;
+    sqlx::raw_sql(SCHEMA).execute(&mut *tx).await?;
+
+    sqlx::query(
+        "INSERT INTO gpkg_spatial_ref_sys
+             (srs_name, srs_id, organization, organization_coordsys_id, definition)
+         VALUES ('Undefined Cartesian SRS', -1, 'NONE', -1, 'undefined'),
+                ('Undefined geographic SRS', 0, 'NONE', 0, 'undefined'),
+                ('WGS 84 geodetic', $1, 'EPSG', $1, $2)",
+    )
+    .bind(WGS84_SRS_ID)
+    .bind(WGS84_WKT)
+    .execute(&mut *tx)
+    .await?;
+
+    let points: Vec<(f64, f64)> = gauges.iter().filter_map(|g| position(g)).collect();
+    register_layer(&mut tx, "gauges", "Rain gauges", "POINT", envelope(&points)).await?;
+    for &gauge in gauges {
+        let Some((longitude, latitude)) = position(gauge) else {
+            continue;
+        };
+        sqlx::query(
+            "INSERT INTO gauges (geom, station_id, name, city, county, location, elevation_ft,
+                                 status, data_begins, data_ends, latitude, longitude, zone)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)",
+        )
+        .bind(point_geometry(longitude, latitude))
+        .bind(&gauge.station_id)
+        .bind(&gauge.station_name)
+        .bind(&gauge.city)
+        .bind(&gauge.county)
+        .bind(&gauge.location_description)
+        .bind(gauge.elevation_ft)
+        .bind(&gauge.status)
+        .bind(gauge.data_begins_date)
+        .bind(gauge.data_ends_date)
+        .bind(latitude)
+        .bind(longitude)
+        .bind(zones.and(gauge.forecast_zone.as_deref()))
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    if let Some(zones) = zones {
+        sqlx::raw_sql(ZONES_SCHEMA).execute(&mut *tx).await?;
+        let points: Vec<(f64, f64)> = zones
+            .iter()
+            .flat_map(|feature| feature.zone.polygons().iter().flatten().flatten())
+            .copied()
+            .collect();
+        let bounds = envelope(&points);
+        register_layer(
+            &mut tx,
+            "forecast_zones",
+            "Forecast zones",
+            "MULTIPOLYGON",
+            bounds,
+        )
+        .await?;
+        for feature in zones {
+            sqlx::query(
+                "INSERT INTO forecast_zones (geom, zone_id, name, gauge_count)
+                 VALUES ($1, $2, $3, $4)",
+            )
+            .bind(multipolygon_geometry(feature.zone.polygons()))
+            .bind(&feature.zone.zone_id)
+            .bind(&feature.zone.name)
+            .bind(feature.gauge_count)
+            .execute(&mut *tx)
+            .await?;
+        }
+    }
+
+    tx.commit().await
+}
+
+/// Add a feature table to gpkg_contents and gpkg_geometry_columns
+async fn register_layer(
+    conn: &mut SqliteConnection,
+    table: &str,
+    identifier: &str,
+    geometry_type: &str,
+    bounds: Option<[f64; 4]>,
+) -> Result<(), sqlx::Error> {
+    let [min_x, max_x, min_y, max_y] = bounds.map_or([None; 4], |b| b.map(Some));
+    sqlx::query(
+        "INSERT INTO gpkg_contents
+             (table_name, data_type, identifier, min_x, min_y, max_x, max_y, srs_id)
+         VALUES ($1, 'features', $2, $3, $4, $5, $6, $7)",
+    )
+    .bind(table)
+    .bind(identifier)
+    .bind(min_x)
+    .bind(min_y)
+    .bind(max_x)
+    .bind(max_y)
+    .bind(WGS84_SRS_ID)
+    .execute(&mut *conn)
+    .await?;
+    sqlx::query(
+        "INSERT INTO gpkg_geometry_columns
+             (table_name, column_name, geometry_type_name, srs_id, z, m)
+         VALUES ($1, 'geom', $2, $3, 0, 0)",
+    )
+    .bind(table)
+    .bind(geometry_type)
+    .bind(WGS84_SRS_ID)
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+/// [min_x, max_x, min_y, max_y] of the points, None when there are none
+fn envelope(points: &[(f64, f64)]) -> Option<[f64; 4]> {
+    let (&(x, y), rest) = points.split_first()?;
+    Some(
+        rest.iter()
+            .fold([x, x, y, y], |[min_x, max_x, min_y, max_y], &(x, y)| {
+                [min_x.min(x), max_x.max(x), min_y.min(y), max_y.max(y)]
+            }),
+    )
+}
+
+/// GeoPackage header: magic, version 0, flags, SRS ID, then the envelope if any
+fn header(flags: u8, envelope: Option<[f64; 4]>) -> Vec<u8> {
+    let mut blob = vec![b'G', b'P', 0, flags];
+    blob.extend(WGS84_SRS_ID.to_le_bytes());
+    for value in envelope.into_iter().flatten() {
+        blob.extend(value.to_le_bytes());
+    }
+    blob
+}
+
+fn point_geometry(x: f64, y: f64) -> Vec<u8> {
+    let mut blob = header(FLAGS_NO_ENVELOPE, None);
+    wkb_type(&mut blob, WKB_POINT);
+    blob.extend(x.to_le_bytes());
+    blob.extend(y.to_le_bytes());
+    blob
+}
+
+fn multipolygon_geometry(polygons: &[Polygon]) -> Vec<u8> {
+    let points: Vec<(f64, f64)> = polygons.iter().flatten().flatten().copied().collect();
+    let mut blob = match envelope(&points) {
+        Some(bounds) => header(FLAGS_XY_ENVELOPE, Some(bounds)),
+        None => header(FLAGS_NO_ENVELOPE, None),
+    };
+    wkb_type(&mut blob, WKB_MULTIPOLYGON);
+    wkb_count(&mut blob, polygons.len());
+    for rings in polygons {
+        wkb_type(&mut blob, WKB_POLYGON);
+        wkb_count(&mut blob, rings.len());
+        for ring in rings {
+            wkb_count(&mut blob, ring.len());
+            for &(x, y) in ring {
+                blob.extend(x.to_le_bytes());
+                blob.extend(y.to_le_bytes());
+            }
+        }
+    }
+    blob
+}
+
+/// Little-endian byte order marker and geometry type
+fn wkb_type(blob: &mut Vec<u8>, geometry_type: u32) {
+    blob.push(1);
+    blob.extend(geometry_type.to_le_bytes());
+}
+
+fn wkb_count(blob: &mut Vec<u8>, count: usize) {
+    blob.extend((count as u32).to_le_bytes());
+}
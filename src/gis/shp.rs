@@ -0,0 +1,178 @@
+// Shapefile output: a point layer of gauges and an optional polygon layer of zones
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{Datelike, NaiveDate};
+use shapefile::dbase::{self, FieldName, FieldWriter, TableWriterBuilder, WritableRecord};
+use shapefile::{Point, Polygon, PolygonRing, ShapeWriter};
+
+use super::{position, GisError, ZoneFeature, WGS84_WKT};
+use crate::db::GisGauge;
+
+/// Longest text a dBase character field holds
+const MAX_TEXT_LEN: u8 = 254;
+
+/// Write `<path>.shp` (and `<path>_zones.shp` with zones), returning every file written
+pub fn write(
+    path: &Path,
+    gauges: &[&GisGauge],
+    zones: Option<&[ZoneFeature]>,
+) -> Result<Vec<PathBuf>, GisError> {
+    let mut files = write_gauges(path, gauges, zones.is_some())?;
+    if let Some(zones) = zones {
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let zones_path = path.with_file_name(format!("{stem}_zones.shp"));
+        files.extend(write_zones(&zones_path, zones)?);
+    }
+    Ok(files)
+}
+
+fn write_gauges(
+    path: &Path,
+    gauges: &[&GisGauge],
+    with_zone: bool,
+) -> Result<Vec<PathBuf>, GisError> {
+    let mut table = TableWriterBuilder::new()
+        .add_character_field(field("STATION"), 10)
+        .add_character_field(field("NAME"), 100)
+        .add_character_field(field("CITY"), 100)
+        .add_character_field(field("COUNTY"), 100)
+        .add_character_field(field("LOCATION"), MAX_TEXT_LEN)
+        .add_numeric_field(field("ELEV_FT"), 6, 0)
+        .add_character_field(field("STATUS"), 20)
+        .add_date_field(field("BEGINS"))
+        .add_date_field(field("ENDS"))
+        .add_numeric_field(field("LATITUDE"), 11, 6)
+        .add_numeric_field(field("LONGITUDE"), 12, 6);
+    if with_zone {
+        table = table.add_character_field(field("ZONE"), 50);
+    }
+
+    let mut shapes = ShapeWriter::from_path(path)?;
+    let mut records = table.build_with_file_dest(path.with_extension("dbf"))?;
+    for &gauge in gauges {
+        let Some((longitude, latitude)) = position(gauge) else {
+            continue;
+        };
+        shapes.write_shape(&Point::new(longitude, latitude))?;
+        records.write_record(&GaugeRecord { gauge, with_zone })?;
+    }
+    shapes.finalize()?;
+    records.finalize()?;
+
+    write_sidecars(path)
+}
+
+fn write_zones(path: &Path, zones: &[ZoneFeature]) -> Result<Vec<PathBuf>, GisError> {
+    let table = TableWriterBuilder::new()
+        .add_character_field(field("ZONE"), 50)
+        .add_character_field(field("NAME"), 100)
+        .add_numeric_field(field("GAUGES"), 6, 0);
+
+    let mut shapes = ShapeWriter::from_path(path)?;
+    let mut records = table.build_with_file_dest(path.with_extension("dbf"))?;
+    for feature in zones {
+        // Shapefiles have no multipolygon: every part's rings go into one polygon
+        let rings = feature
+            .zone
+            .polygons()
+            .iter()
+            .flat_map(|rings| {
+                rings.iter().enumerate().map(|(index, ring)| {
+                    let points = ring.iter().map(|&(x, y)| Point::new(x, y)).collect();
+                    match index {
+                        0 => PolygonRing::Outer(points),
+                        _ => PolygonRing::Inner(points),
+                    }
+                })
+            })
+            .collect();
+        shapes.write_shape(&Polygon::with_rings(rings))?;
+        records.write_record(&ZoneRecord(feature))?;
+    }
+    shapes.finalize()?;
+    records.finalize()?;
+
+    write_sidecars(path)
+}
+
+/// Write the .prj and .cpg next to a .shp, returning the shapefile's files
+fn write_sidecars(path: &Path) -> Result<Vec<PathBuf>, GisError> {
+    fs::write(path.with_extension("prj"), WGS84_WKT)?;
+    fs::write(path.with_extension("cpg"), "UTF-8")?;
+    Ok(["shp", "shx", "dbf", "prj", "cpg"]
+        .into_iter()
+        .map(|extension| path.with_extension(extension))
+        .collect())
+}
+
+fn field(name: &str) -> FieldName {
+    FieldName::try_from(name).expect("field names are at most 10 characters")
+}
+
+/// Text cut to a character field's length without splitting a character
+fn text(value: Option<&str>, max_len: u8) -> Option<String> {
+    let value = value?;
+    let mut end = value.len().min(usize::from(max_len));
+    while !value.is_char_boundary(end) {
+        end -= 1;
+    }
+    Some(value[..end].to_string())
+}
+
+fn date(value: Option<NaiveDate>) -> Option<dbase::Date> {
+    let value = value?;
+    dbase::Date::new(
+        value.day(),
+        value.month(),
+        u32::try_from(value.year()).ok()?,
+    )
+    .ok()
+}
+
+struct GaugeRecord<'a> {
+    gauge: &'a GisGauge,
+    with_zone: bool,
+}
+
+impl WritableRecord for GaugeRecord<'_> {
+    fn write_using<W: Write>(
+        &self,
+        writer: &mut FieldWriter<'_, W>,
+    ) -> Result<(), dbase::FieldError> {
+        let gauge = self.gauge;
+        writer.write_next_field_value(&gauge.station_id.as_str())?;
+        writer.write_next_field_value(&text(gauge.station_name.as_deref(), 100))?;
+        writer.write_next_field_value(&text(gauge.city.as_deref(), 100))?;
+        writer.write_next_field_value(&text(gauge.county.as_deref(), 100))?;
+        writer
+            .write_next_field_value(&text(gauge.location_description.as_deref(), MAX_TEXT_LEN))?;
+        writer.write_next_field_value(&gauge.elevation_ft.map(f64::from))?;
+        writer.write_next_field_value(&text(gauge.status.as_deref(), 20))?;
+        writer.write_next_field_value(&date(gauge.data_begins_date))?;
+        writer.write_next_field_value(&date(gauge.data_ends_date))?;
+        writer.write_next_field_value(&gauge.latitude)?;
+        writer.write_next_field_value(&gauge.longitude)?;
+        if self.with_zone {
+            writer.write_next_field_value(&text(gauge.forecast_zone.as_deref(), 50))?;
+        }
+        Ok(())
+    }
+}
+
+struct ZoneRecord<'a>(&'a ZoneFeature);
+
+impl WritableRecord for ZoneRecord<'_> {
+    fn write_using<W: Write>(
+        &self,
+        writer: &mut FieldWriter<'_, W>,
+    ) -> Result<(), dbase::FieldError> {
+        let ZoneRecord(feature) = self;
+        writer.write_next_field_value(&text(Some(&feature.zone.zone_id), 50))?;
+        writer.write_next_field_value(&text(feature.zone.name.as_deref(), 100))?;
+        writer.write_next_field_value(&(feature.gauge_count as f64))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+// ESRI ASCII raster grids
+//
+// The plain-text raster format GDAL writes with `gdal_translate -of AAIGrid`: a header of
+// ncols, nrows, the lower-left corner (or center), cellsize, and an optional
+// NODATA_value, followed by cell values row by row from the north edge. Used for offline
+// elevation models and MRMS radar precipitation grids in geographic (lat/lon) coordinates.
+
+use std::collections::HashMap;
+
+/// An ESRI ASCII grid in geographic coordinates
+#[derive(Debug, Clone)]
+pub struct AsciiGrid {
+    /// File name, recorded as the source of sampled values
+    name: String,
+    ncols: usize,
+    nrows: usize,
+    /// Longitude of the grid's west edge
+    west: f64,
+    /// Latitude of the grid's south edge
+    south: f64,
+    cellsize: f64,
+    nodata: Option<f64>,
+    /// Row-major from the north edge, as in the file
+    values: Vec<f64>,
+}
+
+impl AsciiGrid {
+    /// Parse the text of an ESRI ASCII grid
+    pub fn parse(name: impl Into<String>, text: &str) -> Result<Self, String> {
+        let mut tokens = text.split_whitespace().peekable();
+        let mut header = HashMap::new();
+        while let Some(key) = tokens.next_if(|t| t.starts_with(|c: char| c.is_ascii_alphabetic())) {
+            let value: f64 = tokens
+                .next()
+                .and_then(|v| v.parse().ok())
+                .ok_or_else(|| format!("missing value for {key}"))?;
+            header.insert(key.to_ascii_lowercase(), value);
+        }
+        let field = |key: &str| {
+            header
+                .get(key)
+                .copied()
+                .ok_or_else(|| format!("missing {key} header"))
+        };
+
+        let ncols = field("ncols")? as usize;
+        let nrows = field("nrows")? as usize;
+        let cellsize = field("cellsize")?;
+        if ncols == 0 || nrows == 0 || cellsize <= 0.0 {
+            return Err("ncols, nrows, and cellsize must be positive".to_string());
+        }
+        // Corners name the grid's edge; centers name the middle of the corner cell
+        let (west, south) = match (header.get("xllcorner"), header.get("yllcorner")) {
+            (Some(x), Some(y)) => (*x, *y),
+            _ => (
+                field("xllcenter")? - cellsize / 2.0,
+                field("yllcenter")? - cellsize / 2.0,
+            ),
+        };
+
+        let values = tokens
+            .map(|t| t.parse().map_err(|_| format!("invalid cell value {t:?}")))
+            .collect::<Result<Vec<f64>, _>>()?;
+        if values.len() != ncols * nrows {
+            return Err(format!(
+                "expected {} cell values, found {}",
+                ncols * nrows,
+                values.len()
+            ));
+        }
+
+        Ok(Self {
+            name: name.into(),
+            ncols,
+            nrows,
+            west,
+            south,
+            cellsize,
+            nodata: header.get("nodata_value").copied(),
+            values,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Value of the cell containing the point; None outside the grid or at NODATA cells
+    pub fn sample(&self, latitude: f64, longitude: f64) -> Option<f64> {
+        let col = ((longitude - self.west) / self.cellsize).floor();
+        let row_from_south = ((latitude - self.south) / self.cellsize).floor();
+        if col < 0.0 || row_from_south < 0.0 {
+            return None;
+        }
+        let (col, row_from_south) = (col as usize, row_from_south as usize);
+        if col >= self.ncols || row_from_south >= self.nrows {
+            return None;
+        }
+
+        let value = self.values[(self.nrows - 1 - row_from_south) * self.ncols + col];
+        Some(value).filter(|v| Some(*v) != self.nodata)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRID: &str = "ncols 3
+nrows 2
+xllcorner -112.3
+yllcorner 33.4
+cellsize 0.1
+NODATA_value -9999
+400 410 -9999
+300 310 320
+";
+
+    #[test]
+    fn test_ascii_grid_sample() {
+        let grid = AsciiGrid::parse("phoenix.asc", GRID).unwrap();
+
+        // South row
+        assert_eq!(grid.sample(33.45, -112.25), Some(300.0));
+        assert_eq!(grid.sample(33.45, -112.05), Some(320.0));
+        // North row
+        assert_eq!(grid.sample(33.55, -112.15), Some(410.0));
+        assert_eq!(grid.sample(33.55, -112.05), None, "no data");
+        assert_eq!(grid.sample(33.65, -112.15), None, "north of grid");
+        assert_eq!(grid.sample(33.45, -111.95), None, "east of grid");
+    }
+
+    #[test]
+    fn test_ascii_grid_rejects_short_grid() {
+        let err = AsciiGrid::parse("short.asc", &GRID.replace(" 320", "")).unwrap_err();
+        assert_eq!(err, "expected 6 cell values, found 5");
+    }
+}
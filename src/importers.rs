@@ -2,7 +2,9 @@
 
 pub mod downloader;
 pub mod excel_importer;
+pub mod progress;
 
 // Re-export commonly used items
 pub use downloader::McfcdDownloader;
 pub use excel_importer::{ExcelImporter, HistoricalReading};
+pub use progress::{ImportPhase, ImportProgressEvent, ProgressReporter};
@@ -1,7 +1,14 @@
-use reqwest::Client;
+use chrono::{DateTime, Utc};
+use reqwest::header::{CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+use reqwest::{Client, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::io::Cursor;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
+
+use crate::politeness::{PoliteError, Politeness};
 
 #[derive(Error, Debug)]
 pub enum DownloadError {
@@ -16,6 +23,34 @@ pub enum DownloadError {
 
     #[error("Invalid URL: {0}")]
     InvalidUrl(String),
+
+    #[error("Incomplete download: {0}")]
+    Incomplete(String),
+
+    #[error("Disallowed by robots.txt: {0}")]
+    Disallowed(String),
+
+    #[error("Paused after repeated failures from {0}")]
+    CircuitOpen(String),
+}
+
+impl From<PoliteError> for DownloadError {
+    fn from(e: PoliteError) -> Self {
+        match e {
+            PoliteError::Request(e) => DownloadError::HttpError(e),
+            PoliteError::Disallowed(url) => DownloadError::Disallowed(url),
+            PoliteError::CircuitOpen(host) => DownloadError::CircuitOpen(host),
+        }
+    }
+}
+
+/// Outcome of a HEAD request for a remote file
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteFileStatus {
+    pub available: bool,
+    pub http_status: u16,
+    pub last_modified: Option<DateTime<Utc>>,
+    pub content_length: Option<i64>,
 }
 
 /// MCFCD data downloader for historical rainfall files
@@ -23,6 +58,9 @@ pub enum DownloadError {
 pub struct McfcdDownloader {
     pub(crate) client: Client,
     pub(crate) base_url: String,
+    /// Where interrupted FOPR downloads are kept so the next attempt can resume them
+    pub(crate) partial_dir: Option<PathBuf>,
+    pub(crate) politeness: Politeness,
 }
 
 impl McfcdDownloader {
@@ -35,6 +73,8 @@ impl McfcdDownloader {
                 .build()
                 .expect("Failed to create HTTP client"),
             base_url: "https://alert.fcd.maricopa.gov/alert/Rain/".to_string(),
+            partial_dir: None,
+            politeness: Politeness::default(),
         }
     }
 
@@ -49,9 +89,27 @@ impl McfcdDownloader {
                 .build()
                 .expect("Failed to create HTTP client"),
             base_url,
+            partial_dir: None,
+            politeness: Politeness::default(),
         }
     }
 
+    /// Send requests with the shared User-Agent, robots.txt checks, and host delay
+    pub fn with_politeness(mut self, politeness: Politeness) -> Self {
+        self.politeness = politeness;
+        self
+    }
+
+    /// Keep interrupted FOPR downloads in `dir` and resume them with HTTP Range requests
+    ///
+    /// Partial files are keyed by URL and checked against their recorded length and
+    /// SHA-256 before being resumed; the server's ETag or Last-Modified is sent as
+    /// If-Range so a file that changed in the meantime is downloaded from the start.
+    pub fn with_partial_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.partial_dir = Some(dir.into());
+        self
+    }
+
     /// Download Excel file for a water year
     /// Example: water_year=2023 downloads pcp_WY_2023.xlsx
     pub async fn download_excel(&self, water_year: i32) -> Result<Vec<u8>, DownloadError> {
@@ -114,12 +172,58 @@ impl McfcdDownloader {
         let url = format!("{}FOPR/{}", self.base_url, filename);
 
         info!("Downloading FOPR file for gauge {}: {}", gauge_id, url);
-        self.download_file(&url, &filename).await
+        match &self.partial_dir {
+            Some(dir) => self.download_resumable(dir, &url, &filename).await,
+            None => self.download_file(&url, &filename).await,
+        }
+    }
+
+    /// Check whether a gauge's FOPR file exists without downloading it (HEAD request)
+    ///
+    /// A 4xx response means the file is unavailable and is not an error; 5xx responses
+    /// and request failures are, since they say nothing about the file.
+    pub async fn head_fopr(&self, gauge_id: &str) -> Result<RemoteFileStatus, DownloadError> {
+        let filename = format!("{gauge_id}_FOPR.xlsx");
+        let url = format!("{}FOPR/{}", self.base_url, filename);
+
+        debug!("Checking FOPR file for gauge {}: {}", gauge_id, url);
+        let response = self
+            .politeness
+            .send(&self.client, self.client.head(&url))
+            .await?;
+        let status = response.status();
+
+        if status.is_server_error() {
+            return Err(DownloadError::ServerError(format!(
+                "Server error {status} while checking {filename}"
+            )));
+        }
+
+        let headers = response.headers();
+        let last_modified = headers
+            .get(reqwest::header::LAST_MODIFIED)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| DateTime::parse_from_rfc2822(v).ok())
+            .map(|dt| dt.with_timezone(&Utc));
+        let content_length = headers
+            .get(reqwest::header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok());
+
+        Ok(RemoteFileStatus {
+            available: status.is_success(),
+            http_status: status.as_u16(),
+            last_modified,
+            content_length,
+        })
     }
 
     /// Internal helper to download a file from a URL
     async fn download_file(&self, url: &str, filename: &str) -> Result<Vec<u8>, DownloadError> {
-        let response = self.client.get(url).send().await?;
+        let response = self
+            .politeness
+            .send(&self.client, self.client.get(url))
+            .await?;
 
         let status = response.status();
 
@@ -127,20 +231,220 @@ impl McfcdDownloader {
             let bytes = response.bytes().await?;
             debug!("Downloaded {filename} ({} bytes)", bytes.len());
             Ok(bytes.to_vec())
-        } else if status.as_u16() == 404 {
-            Err(DownloadError::NotFound(format!(
-                "{filename} not found on server"
-            )))
-        } else if status.is_server_error() {
-            Err(DownloadError::ServerError(format!(
-                "Server error {status} while downloading {filename}"
-            )))
         } else {
-            Err(DownloadError::HttpError(
-                response.error_for_status().unwrap_err(),
-            ))
+            Err(status_error(response, filename))
         }
     }
+
+    /// Download a file, continuing from the partial copy an interrupted attempt left in `dir`
+    ///
+    /// Whatever arrived before the connection failed is saved, so the next call only asks
+    /// for the remaining bytes. A partial copy the server won't continue (mismatched
+    /// Content-Range, 416) is discarded and the file downloaded from the start.
+    async fn download_resumable(
+        &self,
+        dir: &Path,
+        url: &str,
+        filename: &str,
+    ) -> Result<Vec<u8>, DownloadError> {
+        let partial = PartialDownload::new(dir, url);
+        let mut resume = partial.load().await;
+
+        loop {
+            let mut request = self.client.get(url);
+            if let Some((bytes, meta)) = &resume {
+                request = request.header(RANGE, format!("bytes={}-", bytes.len()));
+                if let Some(validator) = meta.etag.as_ref().or(meta.last_modified.as_ref()) {
+                    request = request.header(IF_RANGE, validator);
+                }
+            }
+
+            let mut response = self.politeness.send(&self.client, request).await?;
+            let status = response.status();
+            let (mut body, meta) = match resume.take() {
+                Some((bytes, meta)) if status == StatusCode::PARTIAL_CONTENT => {
+                    let (start, total) = content_range(&response).unwrap_or((u64::MAX, None));
+                    let same_total =
+                        total.is_none() || meta.total_len.is_none() || total == meta.total_len;
+                    if start != bytes.len() as u64 || !same_total {
+                        warn!("Server did not continue {filename} where it stopped, restarting");
+                        partial.discard().await;
+                        continue;
+                    }
+                    info!("Resuming {filename} at {} bytes", bytes.len());
+                    (bytes, meta)
+                }
+                Some(_) if status == StatusCode::RANGE_NOT_SATISFIABLE => {
+                    warn!("Server rejected resuming {filename}, restarting");
+                    partial.discard().await;
+                    continue;
+                }
+                _ if status.is_success() => {
+                    (Vec::new(), PartialMeta::from_response(url, &response))
+                }
+                _ => return Err(status_error(response, filename)),
+            };
+
+            loop {
+                match response.chunk().await {
+                    Ok(Some(chunk)) => body.extend_from_slice(&chunk),
+                    Ok(None) => break,
+                    Err(e) => {
+                        partial.save(&body, &meta).await;
+                        return Err(e.into());
+                    }
+                }
+            }
+
+            let received = body.len() as u64;
+            match meta.total_len {
+                Some(total) if received < total => {
+                    partial.save(&body, &meta).await;
+                    return Err(DownloadError::Incomplete(format!(
+                        "{filename}: received {received} of {total} bytes"
+                    )));
+                }
+                Some(total) if received > total => {
+                    partial.discard().await;
+                    return Err(DownloadError::Incomplete(format!(
+                        "{filename}: received {received} bytes, expected {total}"
+                    )));
+                }
+                _ => {}
+            }
+
+            partial.discard().await;
+            debug!("Downloaded {filename} ({received} bytes)");
+            return Ok(body);
+        }
+    }
+}
+
+/// Map an unsuccessful response to the matching DownloadError
+fn status_error(response: Response, filename: &str) -> DownloadError {
+    let status = response.status();
+    if status.as_u16() == 404 {
+        DownloadError::NotFound(format!("{filename} not found on server"))
+    } else if status.is_server_error() {
+        DownloadError::ServerError(format!(
+            "Server error {status} while downloading {filename}"
+        ))
+    } else {
+        DownloadError::HttpError(response.error_for_status().unwrap_err())
+    }
+}
+
+/// `(start, total)` of a `Content-Range: bytes start-end/total` header
+fn content_range(response: &Response) -> Option<(u64, Option<u64>)> {
+    let value = response.headers().get(CONTENT_RANGE)?.to_str().ok()?;
+    let (range, total) = value.strip_prefix("bytes ")?.split_once('/')?;
+    let start = range.split_once('-')?.0.trim().parse().ok()?;
+    Some((start, total.trim().parse().ok()))
+}
+
+/// What is known about a partially downloaded file, stored next to it as JSON
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct PartialMeta {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    /// Full size of the remote file, when the server reported it
+    total_len: Option<u64>,
+    /// Bytes saved in the partial file, and their SHA-256
+    received: u64,
+    sha256: String,
+}
+
+impl PartialMeta {
+    fn from_response(url: &str, response: &Response) -> Self {
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(String::from)
+        };
+        Self {
+            url: url.to_string(),
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+            total_len: response.content_length(),
+            received: 0,
+            sha256: String::new(),
+        }
+    }
+}
+
+/// `<sha256 of url>.part` and `.json` in the partial download directory
+struct PartialDownload {
+    data_path: PathBuf,
+    meta_path: PathBuf,
+}
+
+impl PartialDownload {
+    fn new(dir: &Path, url: &str) -> Self {
+        let key = format!("{:x}", Sha256::digest(url.as_bytes()));
+        Self {
+            data_path: dir.join(format!("{key}.part")),
+            meta_path: dir.join(format!("{key}.json")),
+        }
+    }
+
+    /// The saved bytes and metadata, if both exist and the bytes match the recorded
+    /// length and hash; anything else is discarded
+    async fn load(&self) -> Option<(Vec<u8>, PartialMeta)> {
+        let meta = tokio::fs::read(&self.meta_path).await.ok()?;
+        let bytes = tokio::fs::read(&self.data_path).await.ok();
+        let valid = serde_json::from_slice::<PartialMeta>(&meta)
+            .ok()
+            .zip(bytes)
+            .filter(|(meta, bytes)| {
+                meta.received == bytes.len() as u64
+                    && meta.sha256 == format!("{:x}", Sha256::digest(bytes))
+            });
+        match valid {
+            Some((meta, bytes)) if !bytes.is_empty() => Some((bytes, meta)),
+            _ => {
+                warn!(path = %self.data_path.display(), "Discarding invalid partial download");
+                self.discard().await;
+                None
+            }
+        }
+    }
+
+    /// Keep `bytes` for the next attempt; failures are logged, as the download can
+    /// always start over
+    async fn save(&self, bytes: &[u8], meta: &PartialMeta) {
+        if bytes.is_empty() {
+            return;
+        }
+        let meta = PartialMeta {
+            received: bytes.len() as u64,
+            sha256: format!("{:x}", Sha256::digest(bytes)),
+            ..meta.clone()
+        };
+        let result = async {
+            if let Some(dir) = self.data_path.parent() {
+                tokio::fs::create_dir_all(dir).await?;
+            }
+            tokio::fs::write(&self.data_path, bytes).await?;
+            tokio::fs::write(&self.meta_path, serde_json::to_vec(&meta)?).await
+        }
+        .await;
+        match result {
+            Ok(()) => info!(
+                url = %meta.url,
+                received = meta.received,
+                "Saved partial download for resumption"
+            ),
+            Err(e) => warn!(url = %meta.url, error = %e, "Failed to save partial download"),
+        }
+    }
+
+    async fn discard(&self) {
+        let _ = tokio::fs::remove_file(&self.meta_path).await;
+        let _ = tokio::fs::remove_file(&self.data_path).await;
+    }
 }
 
 impl Default for McfcdDownloader {
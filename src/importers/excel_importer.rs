@@ -1,10 +1,16 @@
 use calamine::{open_workbook, Data, Reader, Xlsx};
 use chrono::NaiveDate;
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::BufReader;
 use thiserror::Error;
 use tracing::{debug, info, warn};
 
+use crate::fopr::metadata_parser::{excel_datetime_to_date, excel_serial_to_date};
+use crate::ingest_guard::{IngestLimits, Screened};
+use crate::station_id::StationId;
+use crate::units::{Inches, UnitError};
+
 #[derive(Error, Debug)]
 pub enum ExcelImportError {
     #[error("Failed to open workbook: {0}")]
@@ -26,39 +32,80 @@ pub enum ExcelImportError {
 /// Represents a single rainfall reading from historical data files
 #[derive(Debug, Clone)]
 pub struct HistoricalReading {
-    pub station_id: String,
+    pub station_id: StationId,
     pub reading_date: NaiveDate,
-    pub rainfall_inches: f64,
+    pub rainfall_inches: Inches,
     /// Optional footnote marker from PDF (e.g., "1", "2") indicating a data quality note
     pub footnote_marker: Option<String>,
 }
 
+/// Month sheets of a water year Excel file, in water year order
+pub const MONTH_SHEETS: [&str; 12] = [
+    "OCT", "NOV", "DEC", "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP",
+];
+
+/// A gauge column found in a water year Excel file
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscoveredGauge {
+    pub station_id: StationId,
+    /// Month sheets whose header row lists the gauge, in water year order
+    pub months: Vec<String>,
+}
+
+/// List the gauges in a water year Excel file from the header row of every month sheet
+///
+/// Gauges are added and retired during a water year, so a gauge may appear in only some
+/// months. Missing sheets are skipped; a file with no gauge IDs in any sheet is an error.
+pub fn discover_gauges_from_water_year(
+    workbook_path: &str,
+) -> Result<Vec<DiscoveredGauge>, ExcelImportError> {
+    ExcelImporter::new(workbook_path).discover_gauges()
+}
+
 /// Parser for MCFCD Water Year Excel files (format: pcp_WY_YYYY.xlsx)
 pub struct ExcelImporter {
     workbook_path: String,
+    limits: IngestLimits,
 }
 
 impl ExcelImporter {
     pub fn new(workbook_path: impl Into<String>) -> Self {
         Self {
             workbook_path: workbook_path.into(),
+            limits: IngestLimits::default(),
         }
     }
 
-    /// Parse a single month sheet from the water year Excel file
+    /// Override the ingest limits daily values are screened against
+    pub fn with_limits(mut self, limits: IngestLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Parse a single month sheet, keeping only readings that pass the ingest guards
+    /// (see `screen_month_sheet` for the rejects)
+    pub fn parse_month_sheet(
+        &self,
+        sheet_name: &str,
+    ) -> Result<Vec<HistoricalReading>, ExcelImportError> {
+        Ok(self.screen_month_sheet(sheet_name)?.accepted)
+    }
+
+    /// Parse a single month sheet from the water year Excel file, returning the readings
+    /// and the values the ingest guards quarantined (negative or over the maximum)
     ///
     /// # Expected Sheet Structure:
-    /// ```
+    /// ```text
     /// Row 1: Header ("FCD of Maricopa County ALERT System")
     /// Row 2: Column numbers (1, 2, 3, ...)
     /// Row 3: Gage IDs (1000, 1200, 1500, ...)
     /// Row 4-34: Daily data (YYYY-MM-DD | rainfall values)
     /// Row 35: Monthly totals ("Totals:" | sum for each gauge)
     /// ```
-    pub fn parse_month_sheet(
+    pub fn screen_month_sheet(
         &self,
         sheet_name: &str,
-    ) -> Result<Vec<HistoricalReading>, ExcelImportError> {
+    ) -> Result<Screened<HistoricalReading>, ExcelImportError> {
         info!("Parsing sheet: {}", sheet_name);
 
         // Open workbook (this is synchronous, caller should use spawn_blocking)
@@ -73,7 +120,7 @@ impl ExcelImporter {
             Err(_) => return Err(ExcelImportError::SheetNotFound(sheet_name.to_string())),
         };
 
-        let mut readings = Vec::new();
+        let mut readings = Screened::default();
 
         // Row 3 (index 2) contains gauge IDs
         let gauge_ids = self.parse_gauge_ids(&range, 2)?;
@@ -107,15 +154,34 @@ impl ExcelImporter {
             for (col_idx, station_id) in gauge_ids.iter().enumerate() {
                 let data_col = col_idx + 1; // Offset by 1 since dates are in column 0
 
-                if let Some(rainfall) = self.parse_rainfall(&range, row_idx, data_col)? {
-                    // Only store non-zero values to save space
-                    if rainfall > 0.0 {
-                        readings.push(HistoricalReading {
-                            station_id: station_id.clone(),
-                            reading_date: date,
-                            rainfall_inches: rainfall,
-                            footnote_marker: None, // Excel files don't have footnotes
-                        });
+                let Some(rainfall) = self.parse_rainfall(&range, row_idx, data_col)? else {
+                    continue;
+                };
+                // Only store non-zero values to save space
+                if rainfall == 0.0 || rainfall.is_nan() {
+                    continue;
+                }
+                if rainfall.is_infinite() {
+                    return Err(ExcelImportError::InvalidData {
+                        row: row_idx,
+                        col: data_col,
+                        msg: UnitError::NotFinite(rainfall).to_string(),
+                    });
+                }
+
+                match self.limits.check_daily(station_id, date, rainfall) {
+                    Ok(rainfall_inches) => readings.accepted.push(HistoricalReading {
+                        station_id: station_id.clone(),
+                        reading_date: date,
+                        rainfall_inches,
+                        footnote_marker: None, // Excel files don't have footnotes
+                    }),
+                    Err(quarantined) => {
+                        warn!(
+                            "Quarantining {} inches for gauge {} on {} ({})",
+                            rainfall, station_id, date, quarantined.reason
+                        );
+                        readings.quarantined.push(quarantined);
                     }
                 }
             }
@@ -123,34 +189,40 @@ impl ExcelImporter {
 
         info!(
             "Parsed {} non-zero rainfall readings from sheet {}",
-            readings.len(),
+            readings.accepted.len(),
             sheet_name
         );
         Ok(readings)
     }
 
-    /// Parse all month sheets in a water year Excel file
-    ///
-    /// Returns readings for all months in the water year (Oct - Sep)
+    /// Parse all month sheets, keeping only readings that pass the ingest guards (see
+    /// `screen_all_months` for the rejects)
     pub fn parse_all_months(
         &self,
         water_year: i32,
     ) -> Result<Vec<HistoricalReading>, ExcelImportError> {
-        let months = [
-            "OCT", "NOV", "DEC", "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP",
-        ];
+        Ok(self.screen_all_months(water_year)?.accepted)
+    }
 
-        let mut all_readings = Vec::new();
+    /// Parse all month sheets in a water year Excel file
+    ///
+    /// Returns readings for all months in the water year (Oct - Sep), and the values the
+    /// ingest guards quarantined
+    pub fn screen_all_months(
+        &self,
+        water_year: i32,
+    ) -> Result<Screened<HistoricalReading>, ExcelImportError> {
+        let mut all_readings = Screened::default();
 
-        for month_name in months {
-            match self.parse_month_sheet(month_name) {
-                Ok(mut readings) => {
+        for month_name in MONTH_SHEETS {
+            match self.screen_month_sheet(month_name) {
+                Ok(readings) => {
                     info!(
                         "Successfully parsed {}: {} readings",
                         month_name,
-                        readings.len()
+                        readings.accepted.len()
                     );
-                    all_readings.append(&mut readings);
+                    all_readings.extend(readings);
                 }
                 Err(ExcelImportError::SheetNotFound(_)) => {
                     warn!(
@@ -164,33 +236,91 @@ impl ExcelImporter {
         }
 
         info!(
-            "Parsed total of {} readings from water year {}",
-            all_readings.len(),
-            water_year
+            "Parsed total of {} readings from water year {} ({} quarantined)",
+            all_readings.accepted.len(),
+            water_year,
+            all_readings.quarantined.len()
         );
         Ok(all_readings)
     }
 
+    /// Gauges listed in the header row of each month sheet (see [`discover_gauges_from_water_year`])
+    pub fn discover_gauges(&self) -> Result<Vec<DiscoveredGauge>, ExcelImportError> {
+        let mut workbook: Xlsx<BufReader<File>> = match open_workbook(&self.workbook_path) {
+            Ok(wb) => wb,
+            Err(e) => return Err(ExcelImportError::WorkbookOpen(e.to_string())),
+        };
+
+        let mut months_by_gauge: BTreeMap<StationId, Vec<String>> = BTreeMap::new();
+        for month_name in MONTH_SHEETS {
+            let Ok(range) = workbook.worksheet_range(month_name) else {
+                warn!("Sheet {} not found, skipping", month_name);
+                continue;
+            };
+
+            match self.parse_gauge_ids(&range, 2) {
+                Ok(gauge_ids) => {
+                    debug!(
+                        "Found {} gauge IDs in sheet {}",
+                        gauge_ids.len(),
+                        month_name
+                    );
+                    for station_id in gauge_ids {
+                        let months = months_by_gauge.entry(station_id).or_default();
+                        // A gauge listed twice in one sheet still counts once
+                        if months.last().map(String::as_str) != Some(month_name) {
+                            months.push(month_name.to_string());
+                        }
+                    }
+                }
+                Err(ExcelImportError::MissingGaugeIds) => {
+                    warn!("No gauge IDs in sheet {}, skipping", month_name);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        if months_by_gauge.is_empty() {
+            return Err(ExcelImportError::MissingGaugeIds);
+        }
+
+        info!(
+            "Discovered {} gauges in {}",
+            months_by_gauge.len(),
+            self.workbook_path
+        );
+        Ok(months_by_gauge
+            .into_iter()
+            .map(|(station_id, months)| DiscoveredGauge { station_id, months })
+            .collect())
+    }
+
     /// Parse gauge IDs from Row 3
     fn parse_gauge_ids(
         &self,
         range: &calamine::Range<Data>,
         row: usize,
-    ) -> Result<Vec<String>, ExcelImportError> {
+    ) -> Result<Vec<StationId>, ExcelImportError> {
         let mut gauge_ids = Vec::new();
+        let invalid =
+            |col: usize, e: crate::station_id::StationIdError| ExcelImportError::InvalidData {
+                row,
+                col,
+                msg: format!("invalid gauge ID: {e}"),
+            };
 
         // Start from column 1 (index 1) since column 0 is the date column header
         for col in 1..range.width() {
             match range.get((row, col)) {
                 Some(Data::Int(i)) => {
-                    gauge_ids.push(i.to_string());
+                    gauge_ids.push(StationId::parse(&i.to_string()).map_err(|e| invalid(col, e))?);
                 }
                 Some(Data::Float(f)) => {
-                    gauge_ids.push(format!("{f:.0}"));
+                    gauge_ids.push(StationId::from_number(*f).map_err(|e| invalid(col, e))?);
                 }
                 Some(Data::String(s)) => {
                     if !s.trim().is_empty() {
-                        gauge_ids.push(s.trim().to_string());
+                        gauge_ids.push(StationId::parse(s).map_err(|e| invalid(col, e))?);
                     } else {
                         break;
                     }
@@ -225,19 +355,21 @@ impl ExcelImporter {
             }
             Some(Data::DateTime(excel_date)) => {
                 // Excel DateTime - calamine provides direct conversion
-                let timestamp = excel_date.as_datetime();
-                Ok(timestamp.map(|dt| dt.date()))
+                excel_datetime_to_date(excel_date)
+                    .map(Some)
+                    .ok_or_else(|| ExcelImportError::InvalidDate(excel_date.to_string()))
             }
             Some(Data::Float(f)) => {
                 // Excel date serial number
-                let days = *f as i64;
-                let base_date = NaiveDate::from_ymd_opt(1899, 12, 30).unwrap();
-                Ok(Some(base_date + chrono::Duration::days(days)))
+                excel_serial_to_date(*f)
+                    .map(Some)
+                    .ok_or_else(|| ExcelImportError::InvalidDate(f.to_string()))
             }
             Some(Data::Int(i)) => {
                 // Excel date serial number
-                let base_date = NaiveDate::from_ymd_opt(1899, 12, 30).unwrap();
-                Ok(Some(base_date + chrono::Duration::days(*i)))
+                excel_serial_to_date(*i as f64)
+                    .map(Some)
+                    .ok_or_else(|| ExcelImportError::InvalidDate(i.to_string()))
             }
             Some(Data::Empty) | None => Ok(None),
             other => Err(ExcelImportError::InvalidData {
@@ -297,4 +429,66 @@ mod tests {
         let importer = ExcelImporter::new("test.xlsx");
         assert_eq!(importer.workbook_path, "test.xlsx");
     }
+
+    mod properties {
+        use super::*;
+        use calamine::{ExcelDateTime, ExcelDateTimeType, Range};
+        use proptest::prelude::*;
+
+        fn any_float() -> impl Strategy<Value = f64> {
+            prop_oneof![
+                any::<f64>(),
+                Just(f64::NAN),
+                Just(f64::INFINITY),
+                Just(f64::NEG_INFINITY),
+            ]
+        }
+
+        fn any_cell() -> impl Strategy<Value = Data> {
+            prop_oneof![
+                Just(Data::Empty),
+                any_float().prop_map(Data::Float),
+                any::<i64>().prop_map(Data::Int),
+                any::<bool>().prop_map(Data::Bool),
+                "\\PC{0,20}".prop_map(Data::String),
+                (any_float(), any::<bool>()).prop_map(|(value, is_1904)| {
+                    Data::DateTime(ExcelDateTime::new(
+                        value,
+                        ExcelDateTimeType::DateTime,
+                        is_1904,
+                    ))
+                }),
+            ]
+        }
+
+        fn single_cell(cell: Data) -> Range<Data> {
+            let mut range = Range::new((0, 0), (0, 0));
+            range.set_value((0, 0), cell);
+            range
+        }
+
+        proptest! {
+            #[test]
+            fn parse_date_never_panics(cell in any_cell()) {
+                let importer = ExcelImporter::new("test.xlsx");
+                let _ = importer.parse_date(&single_cell(cell), 0, 0);
+            }
+
+            #[test]
+            fn parse_rainfall_never_panics(cell in any_cell()) {
+                let importer = ExcelImporter::new("test.xlsx");
+                let _ = importer.parse_rainfall(&single_cell(cell), 0, 0);
+            }
+
+            #[test]
+            fn out_of_range_serials_are_invalid_dates(days in prop_oneof![
+                i64::MIN..0,
+                3_000_000..i64::MAX,
+            ]) {
+                let importer = ExcelImporter::new("test.xlsx");
+                let result = importer.parse_date(&single_cell(Data::Int(days)), 0, 0);
+                prop_assert!(matches!(result, Err(ExcelImportError::InvalidDate(_))));
+            }
+        }
+    }
 }
@@ -0,0 +1,191 @@
+// Structured progress events for long-running imports
+//
+// Every event is logged as a tracing event under the `import_progress` target and,
+// when a webhook URL is configured, POSTed to it as JSON. Webhook delivery is best
+// effort: failures are logged and never abort the import.
+
+use std::fmt;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Client;
+use serde::Serialize;
+use tracing::{info, warn};
+
+/// Tracing target for progress events (enable with `RUST_LOG=import_progress=info`)
+pub const PROGRESS_TARGET: &str = "import_progress";
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Pipeline stage an import is in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportPhase {
+    Downloading,
+    Parsing,
+    Inserting,
+    Recalculating,
+    Completed,
+    Failed,
+}
+
+impl fmt::Display for ImportPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ImportPhase::Downloading => "downloading",
+            ImportPhase::Parsing => "parsing",
+            ImportPhase::Inserting => "inserting",
+            ImportPhase::Recalculating => "recalculating",
+            ImportPhase::Completed => "completed",
+            ImportPhase::Failed => "failed",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Snapshot of an import's progress
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportProgressEvent {
+    /// Identifies one CLI invocation, so events from a bulk run can be grouped
+    pub run_id: String,
+    /// Data source being imported (e.g. `excel_WY_2023`)
+    pub source: String,
+    pub water_year: Option<i32>,
+    pub phase: ImportPhase,
+    pub readings_parsed: usize,
+    pub readings_inserted: usize,
+    pub duplicates_skipped: usize,
+    pub stations_processed: usize,
+    pub stations_total: usize,
+    pub error: Option<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Emits import progress events to tracing and an optional webhook
+#[derive(Debug, Clone)]
+pub struct ProgressReporter {
+    run_id: String,
+    webhook: Option<(Client, String)>,
+}
+
+impl ProgressReporter {
+    /// Reporter that only logs via tracing
+    pub fn new() -> Self {
+        Self {
+            run_id: format!("{}-{}", std::process::id(), Utc::now().timestamp_millis()),
+            webhook: None,
+        }
+    }
+
+    /// Also POST every event as JSON to `url`
+    pub fn with_webhook(mut self, url: impl Into<String>) -> Self {
+        let client = Client::builder()
+            .timeout(WEBHOOK_TIMEOUT)
+            .build()
+            .expect("Failed to create HTTP client");
+        self.webhook = Some((client, url.into()));
+        self
+    }
+
+    pub fn run_id(&self) -> &str {
+        &self.run_id
+    }
+
+    /// Start tracking an import; counts begin at zero in the `Downloading` phase
+    pub fn start(&self, source: impl Into<String>, water_year: Option<i32>) -> ImportProgressEvent {
+        ImportProgressEvent {
+            run_id: self.run_id.clone(),
+            source: source.into(),
+            water_year,
+            phase: ImportPhase::Downloading,
+            readings_parsed: 0,
+            readings_inserted: 0,
+            duplicates_skipped: 0,
+            stations_processed: 0,
+            stations_total: 0,
+            error: None,
+            timestamp: Utc::now(),
+        }
+    }
+
+    /// Timestamp and publish the event's current state
+    pub async fn emit(&self, event: &mut ImportProgressEvent) {
+        event.timestamp = Utc::now();
+
+        info!(
+            target: PROGRESS_TARGET,
+            run_id = %event.run_id,
+            source = %event.source,
+            water_year = event.water_year,
+            phase = %event.phase,
+            readings_parsed = event.readings_parsed,
+            readings_inserted = event.readings_inserted,
+            duplicates_skipped = event.duplicates_skipped,
+            stations_processed = event.stations_processed,
+            stations_total = event.stations_total,
+            error = event.error.as_deref(),
+            "Import progress"
+        );
+
+        if let Some((client, url)) = &self.webhook {
+            let body = match serde_json::to_vec(event) {
+                Ok(body) => body,
+                Err(e) => {
+                    warn!(error = %e, "Failed to serialize progress event");
+                    return;
+                }
+            };
+
+            let result = client
+                .post(url)
+                .header(CONTENT_TYPE, "application/json")
+                .body(body)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+
+            if let Err(e) = result {
+                warn!(url = %url, error = %e, "Progress webhook delivery failed");
+            }
+        }
+    }
+
+    /// Move to a new phase and publish
+    pub async fn phase(&self, event: &mut ImportProgressEvent, phase: ImportPhase) {
+        event.phase = phase;
+        self.emit(event).await;
+    }
+}
+
+impl Default for ProgressReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_start_event_has_zero_counts() {
+        let reporter = ProgressReporter::new();
+        let event = reporter.start("excel_WY_2023", Some(2023));
+
+        assert_eq!(event.run_id, reporter.run_id());
+        assert_eq!(event.phase, ImportPhase::Downloading);
+        assert_eq!(event.readings_parsed, 0);
+        assert_eq!(event.water_year, Some(2023));
+    }
+
+    #[test]
+    fn test_event_serializes_phase_as_snake_case() {
+        let mut event = ProgressReporter::new().start("excel_WY_2023", None);
+        event.phase = ImportPhase::Recalculating;
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["phase"], "recalculating");
+        assert_eq!(json["source"], "excel_WY_2023");
+    }
+}
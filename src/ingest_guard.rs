@@ -0,0 +1,226 @@
+// Ingest guards for rainfall readings
+//
+// One bad row (a negative amount, a cumulative counter that runs backwards within a day, a
+// mistyped 45.0) would otherwise be summed into the monthly summaries and every total built
+// on them. The fetcher and the importers screen readings as they parse them; rejects are
+// stored in `quarantined_readings` with the reason for review instead of in `rain_readings`.
+
+use std::fmt;
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+
+use crate::fetcher::RainReading;
+use crate::units::Inches;
+
+/// Largest single reading accepted by default
+pub const DEFAULT_MAX_READING_INCHES: f64 = 20.0;
+
+/// Limits a reading must fall within to be stored
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IngestLimits {
+    /// Largest incremental amount (a 15-minute reading or a daily total) accepted
+    pub max_reading_inches: f64,
+}
+
+impl Default for IngestLimits {
+    fn default() -> Self {
+        Self {
+            max_reading_inches: DEFAULT_MAX_READING_INCHES,
+        }
+    }
+}
+
+impl IngestLimits {
+    /// Check one parsed amount
+    pub fn check_amount(&self, value: f64) -> Result<Inches, QuarantineReason> {
+        if value < 0.0 {
+            return Err(QuarantineReason::NegativeRainfall);
+        }
+        if value > self.max_reading_inches {
+            return Err(QuarantineReason::ExceedsMaximum);
+        }
+        Inches::new(value).map_err(|_| QuarantineReason::ExceedsMaximum)
+    }
+
+    /// Check a daily total, quarantining it at midnight UTC like stored daily readings
+    pub fn check_daily(
+        &self,
+        station_id: &str,
+        date: NaiveDate,
+        value: f64,
+    ) -> Result<Inches, QuarantinedReading> {
+        self.check_amount(value)
+            .map_err(|reason| QuarantinedReading {
+                station_id: station_id.to_string(),
+                reading_datetime: date.and_time(NaiveTime::MIN).and_utc(),
+                cumulative_inches: None,
+                incremental_inches: value,
+                reason,
+            })
+    }
+}
+
+/// Why a reading was quarantined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QuarantineReason {
+    NegativeRainfall,
+    /// The cumulative total is below an earlier reading from the same day
+    CumulativeDecrease,
+    /// The incremental amount is over `IngestLimits::max_reading_inches`
+    ExceedsMaximum,
+}
+
+impl QuarantineReason {
+    /// Value stored in `quarantined_readings.reason`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::NegativeRainfall => "negative_rainfall",
+            Self::CumulativeDecrease => "cumulative_decrease",
+            Self::ExceedsMaximum => "exceeds_maximum",
+        }
+    }
+}
+
+impl fmt::Display for QuarantineReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A reading rejected by the guards, with the values as parsed
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuarantinedReading {
+    pub station_id: String,
+    pub reading_datetime: DateTime<Utc>,
+    /// None for daily totals, which have no cumulative counter
+    pub cumulative_inches: Option<f64>,
+    pub incremental_inches: f64,
+    pub reason: QuarantineReason,
+}
+
+/// Readings that passed the guards, and the ones that didn't
+#[derive(Debug, Clone, PartialEq)]
+pub struct Screened<T> {
+    pub accepted: Vec<T>,
+    pub quarantined: Vec<QuarantinedReading>,
+}
+
+impl<T> Default for Screened<T> {
+    fn default() -> Self {
+        Self {
+            accepted: Vec::new(),
+            quarantined: Vec::new(),
+        }
+    }
+}
+
+impl<T> Screened<T> {
+    /// Append another batch's results
+    pub fn extend(&mut self, other: Screened<T>) {
+        self.accepted.extend(other.accepted);
+        self.quarantined.extend(other.quarantined);
+    }
+}
+
+/// Quarantine live readings whose cumulative total falls within a day, oldest first
+///
+/// The counter only resets between days, so a reading below the last accepted one from
+/// the same (UTC) day is a bad row. Comparing against accepted readings keeps one low
+/// row from rejecting the rest of the day.
+pub fn screen_cumulative(
+    station_id: &str,
+    mut readings: Vec<RainReading>,
+) -> Screened<RainReading> {
+    readings.sort_by_key(|r| r.reading_datetime);
+
+    let mut screened = Screened::default();
+    for reading in readings {
+        let decreased = screened.accepted.last().is_some_and(|last: &RainReading| {
+            last.reading_datetime.date_naive() == reading.reading_datetime.date_naive()
+                && reading.cumulative_inches < last.cumulative_inches
+        });
+        if decreased {
+            screened.quarantined.push(QuarantinedReading {
+                station_id: station_id.to_string(),
+                reading_datetime: reading.reading_datetime,
+                cumulative_inches: Some(reading.cumulative_inches.value()),
+                incremental_inches: reading.incremental_inches.value(),
+                reason: QuarantineReason::CumulativeDecrease,
+            });
+        } else {
+            screened.accepted.push(reading);
+        }
+    }
+    screened
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn reading(day: u32, hour: u32, cumulative: f64, incremental: f64) -> RainReading {
+        RainReading {
+            reading_datetime: Utc.with_ymd_and_hms(2025, 1, day, hour, 0, 0).unwrap(),
+            cumulative_inches: Inches::new(cumulative).unwrap(),
+            incremental_inches: Inches::new(incremental).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_check_amount() {
+        let limits = IngestLimits::default();
+        assert_eq!(limits.check_amount(0.04).unwrap().value(), 0.04);
+        assert_eq!(limits.check_amount(20.0).unwrap().value(), 20.0);
+        assert_eq!(
+            limits.check_amount(-0.04),
+            Err(QuarantineReason::NegativeRainfall)
+        );
+        assert_eq!(
+            limits.check_amount(20.01),
+            Err(QuarantineReason::ExceedsMaximum)
+        );
+        assert_eq!(
+            IngestLimits {
+                max_reading_inches: 2.0
+            }
+            .check_amount(4.0),
+            Err(QuarantineReason::ExceedsMaximum)
+        );
+    }
+
+    #[test]
+    fn test_screen_cumulative_rejects_decrease_within_day() {
+        let screened = screen_cumulative(
+            "59700",
+            vec![
+                reading(2, 3, 1.20, 0.20),
+                reading(2, 1, 1.00, 0.00),
+                reading(2, 2, 0.40, 0.00),
+            ],
+        );
+
+        let accepted: Vec<f64> = screened
+            .accepted
+            .iter()
+            .map(|r| r.cumulative_inches.value())
+            .collect();
+        assert_eq!(accepted, [1.00, 1.20]);
+        assert_eq!(screened.quarantined.len(), 1);
+        assert_eq!(
+            screened.quarantined[0].reason,
+            QuarantineReason::CumulativeDecrease
+        );
+        assert_eq!(screened.quarantined[0].cumulative_inches, Some(0.40));
+    }
+
+    #[test]
+    fn test_screen_cumulative_allows_reset_between_days() {
+        let screened = screen_cumulative(
+            "59700",
+            vec![reading(1, 23, 1.50, 0.10), reading(2, 0, 0.00, 0.00)],
+        );
+        assert_eq!(screened.accepted.len(), 2);
+        assert!(screened.quarantined.is_empty());
+    }
+}
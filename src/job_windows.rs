@@ -0,0 +1,225 @@
+// Time-of-day windows for background jobs
+//
+// Operators can keep background work away from chosen times of day:
+// - JOB_BLACKOUT_WINDOWS: no MCFCD downloads (reading and gauge list scrapes, FOPR import
+//   claims), e.g. during MCFCD's nightly maintenance
+// - HEAVY_JOB_WINDOWS: heavy jobs (FOPR imports with their summary recalculation, gauge
+//   reconciliation, anomaly detection, analytics exports) only run inside these windows;
+//   unset means any time
+//
+// Windows are comma-separated `HH:MM-HH:MM` ranges in the JOB_WINDOW_UTC_OFFSET time zone
+// (default +00:00; Arizona is -07:00 all year). A range ending before it starts wraps past
+// midnight. Schedulers skip ticks that fall outside their window and FOPR workers leave
+// jobs unclaimed, so the work resumes at the next tick or poll once allowed.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, FixedOffset, NaiveTime, Utc};
+
+/// Start-inclusive, end-exclusive time-of-day range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeWindow {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl TimeWindow {
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            // Wraps past midnight
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+impl FromStr for TimeWindow {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |t: &str| NaiveTime::parse_from_str(t.trim(), "%H:%M");
+        let window = s
+            .split_once('-')
+            .and_then(|(start, end)| Some((parse(start).ok()?, parse(end).ok()?)))
+            .map(|(start, end)| TimeWindow { start, end });
+        match window {
+            Some(window) if window.start != window.end => Ok(window),
+            _ => Err(format!("expected HH:MM-HH:MM, got {s:?}")),
+        }
+    }
+}
+
+impl fmt::Display for TimeWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}-{}",
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M")
+        )
+    }
+}
+
+/// What a job does, which decides the windows that apply to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobKind {
+    /// Downloads from MCFCD; kept out of blackout windows
+    Download,
+    /// Long-running database work; kept inside heavy job windows
+    Heavy,
+    /// Both, like a FOPR import
+    HeavyDownload,
+    /// Unrestricted
+    Light,
+}
+
+/// Blackout and heavy job windows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JobWindows {
+    /// JOB_WINDOW_UTC_OFFSET, default +00:00
+    pub offset: FixedOffset,
+    /// JOB_BLACKOUT_WINDOWS
+    pub blackouts: Vec<TimeWindow>,
+    /// HEAVY_JOB_WINDOWS; empty lets heavy jobs run any time
+    pub heavy: Vec<TimeWindow>,
+    /// Settings that failed to parse, reported by Config::validate
+    pub invalid: Vec<String>,
+}
+
+impl Default for JobWindows {
+    fn default() -> Self {
+        Self {
+            offset: FixedOffset::east_opt(0).expect("zero offset"),
+            blackouts: Vec::new(),
+            heavy: Vec::new(),
+            invalid: Vec::new(),
+        }
+    }
+}
+
+impl JobWindows {
+    /// Build from the raw settings, recording any that fail to parse in `invalid`
+    pub fn parse(offset: Option<&str>, blackouts: Option<&str>, heavy: Option<&str>) -> Self {
+        let mut windows = JobWindows::default();
+        if let Some(offset) = offset {
+            match offset.trim().parse::<FixedOffset>() {
+                Ok(parsed) => windows.offset = parsed,
+                Err(_) => windows.invalid.push(format!(
+                    "JOB_WINDOW_UTC_OFFSET must look like -07:00, got {offset:?}"
+                )),
+            }
+        }
+        windows.blackouts = parse_list("JOB_BLACKOUT_WINDOWS", blackouts, &mut windows.invalid);
+        windows.heavy = parse_list("HEAVY_JOB_WINDOWS", heavy, &mut windows.invalid);
+        windows
+    }
+
+    /// Whether any window is configured
+    pub fn is_restricted(&self) -> bool {
+        !self.blackouts.is_empty() || !self.heavy.is_empty()
+    }
+
+    /// Whether a `kind` job may start at `now`
+    pub fn allows(&self, kind: JobKind, now: DateTime<Utc>) -> bool {
+        let time = now.with_timezone(&self.offset).time();
+        let download_ok = || !self.blackouts.iter().any(|w| w.contains(time));
+        let heavy_ok = || self.heavy.is_empty() || self.heavy.iter().any(|w| w.contains(time));
+        match kind {
+            JobKind::Download => download_ok(),
+            JobKind::Heavy => heavy_ok(),
+            JobKind::HeavyDownload => download_ok() && heavy_ok(),
+            JobKind::Light => true,
+        }
+    }
+}
+
+impl fmt::Display for JobWindows {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |windows: &[TimeWindow]| {
+            windows
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        write!(
+            f,
+            "downloads blacked out [{}], heavy jobs [{}] (UTC{})",
+            list(&self.blackouts),
+            if self.heavy.is_empty() {
+                "any time".to_string()
+            } else {
+                list(&self.heavy)
+            },
+            self.offset
+        )
+    }
+}
+
+fn parse_list(name: &str, value: Option<&str>, invalid: &mut Vec<String>) -> Vec<TimeWindow> {
+    let Some(value) = value else {
+        return Vec::new();
+    };
+    value
+        .split(',')
+        .filter(|w| !w.trim().is_empty())
+        .filter_map(|w| match w.parse() {
+            Ok(window) => Some(window),
+            Err(e) => {
+                invalid.push(format!("{name} entry {e}"));
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 2, 13, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_parse_windows() {
+        let windows = JobWindows::parse(Some("-07:00"), Some("02:00-04:00"), Some("22:00-05:00"));
+        assert_eq!(windows.invalid, Vec::<String>::new());
+        assert_eq!(windows.offset.local_minus_utc(), -7 * 3600);
+        assert_eq!(windows.blackouts[0].to_string(), "02:00-04:00");
+
+        let windows = JobWindows::parse(Some("Arizona"), Some("2am-4am,03:00-03:00"), None);
+        assert_eq!(windows.invalid.len(), 3, "{:?}", windows.invalid);
+        assert!(windows.invalid[0].starts_with("JOB_WINDOW_UTC_OFFSET"));
+        assert!(windows.invalid[1].starts_with("JOB_BLACKOUT_WINDOWS"));
+        assert!(windows.blackouts.is_empty());
+    }
+
+    #[test]
+    fn test_allows_in_local_time() {
+        // 02:00-04:00 in Arizona is 09:00-11:00 UTC
+        let windows = JobWindows::parse(Some("-07:00"), Some("02:00-04:00"), Some("22:00-05:00"));
+
+        assert!(windows.allows(JobKind::Download, at(8, 59)));
+        assert!(!windows.allows(JobKind::Download, at(9, 0)));
+        assert!(!windows.allows(JobKind::Download, at(10, 59)));
+        assert!(windows.allows(JobKind::Download, at(11, 0)));
+
+        // Heavy window wraps midnight: 22:00-05:00 local is 05:00-12:00 UTC
+        assert!(!windows.allows(JobKind::Heavy, at(4, 59)));
+        assert!(windows.allows(JobKind::Heavy, at(5, 0)));
+        assert!(
+            !windows.allows(JobKind::HeavyDownload, at(10, 0)),
+            "in the blackout"
+        );
+        assert!(windows.allows(JobKind::HeavyDownload, at(11, 30)));
+        assert!(!windows.allows(JobKind::Heavy, at(12, 0)));
+        assert!(windows.allows(JobKind::Light, at(10, 0)));
+
+        let unrestricted = JobWindows::default();
+        assert!(unrestricted.allows(JobKind::HeavyDownload, at(10, 0)));
+    }
+}
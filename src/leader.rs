@@ -0,0 +1,195 @@
+// Scheduler leader election
+//
+// Several replicas running schedulers (role `all` or `scheduler`, e.g. a Kubernetes
+// Deployment with replicas > 1) would each scrape on their own timer. With
+// LEADER_ELECTION_ENABLED=true the replicas contend for a PostgreSQL advisory lock (see
+// `db::leader_lock`) and only the holder's schedulers do work; the others' ticks are
+// skipped. The lock is released as soon as the leader exits or loses its connection, and
+// a follower takes over at its next attempt. The leader steps down itself when its
+// election connection stops answering.
+//
+// FOPR import workers do not take part: each job is claimed with FOR UPDATE SKIP LOCKED,
+// so any number of workers can run.
+
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tracing::{debug, info, warn};
+use utoipa::ToSchema;
+
+use crate::db::leader_lock::LeaderLock;
+
+/// Seconds between attempts to take or confirm leadership
+pub const DEFAULT_LEADER_RETRY_SECS: u64 = 10;
+
+/// Leader election settings, present when LEADER_ELECTION_ENABLED=true
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeaderElectionConfig {
+    /// LEADER_ELECTION_ID, default HOSTNAME (the pod name under Kubernetes)
+    pub instance_id: String,
+    /// LEADER_ELECTION_RETRY_SECS, default 10; also bounds how long a failover takes
+    pub retry_secs: u64,
+    /// Advisory lock key; every replica of one deployment must share it
+    pub lock_key: i64,
+}
+
+/// This replica's view of the election, reported by /api/v1/health
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct LeaderStatus {
+    /// This replica's identity
+    #[schema(example = "rain-tracker-7d9f8-x2k4q")]
+    pub instance_id: String,
+    /// This replica's schedulers are running
+    pub is_leader: bool,
+    /// Replica holding leadership, when known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub leader_id: Option<String>,
+    /// When this replica last became leader
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub leader_since: Option<DateTime<Utc>>,
+}
+
+/// Shared leadership state, cloned into the schedulers and the API state
+#[derive(Debug, Clone, Default)]
+pub struct Leadership {
+    /// None without an election: this process always leads
+    status: Option<Arc<RwLock<LeaderStatus>>>,
+}
+
+impl Leadership {
+    /// No election; schedulers always run
+    pub fn always() -> Self {
+        Self::default()
+    }
+
+    /// A follower until [`run_election`] wins the lock
+    pub fn elected(instance_id: impl Into<String>) -> Self {
+        Self {
+            status: Some(Arc::new(RwLock::new(LeaderStatus {
+                instance_id: instance_id.into(),
+                is_leader: false,
+                leader_id: None,
+                leader_since: None,
+            }))),
+        }
+    }
+
+    pub fn is_leader(&self) -> bool {
+        self.status().is_none_or(|s| s.is_leader)
+    }
+
+    /// Election state, or None when no election runs
+    pub fn status(&self) -> Option<LeaderStatus> {
+        self.status
+            .as_ref()
+            .map(|s| s.read().unwrap_or_else(|e| e.into_inner()).clone())
+    }
+
+    fn update(&self, f: impl FnOnce(&mut LeaderStatus)) {
+        if let Some(status) = &self.status {
+            f(&mut status.write().unwrap_or_else(|e| e.into_inner()));
+        }
+    }
+
+    fn lead(&self) {
+        self.update(|s| {
+            s.is_leader = true;
+            s.leader_id = Some(s.instance_id.clone());
+            s.leader_since = Some(Utc::now());
+        });
+    }
+
+    fn follow(&self, leader_id: Option<String>) {
+        self.update(|s| {
+            s.is_leader = false;
+            s.leader_id = leader_id;
+        });
+    }
+}
+
+/// Contend for leadership forever, updating `leadership`
+///
+/// Followers retry every `config.retry_secs`; the leader confirms its connection at the
+/// same interval and steps down (reconnecting as a follower) when it fails.
+pub async fn run_election(
+    database_url: String,
+    config: LeaderElectionConfig,
+    leadership: Leadership,
+) {
+    let retry = Duration::from_secs(config.retry_secs.max(1));
+    let mut lock: Option<LeaderLock> = None;
+
+    info!(
+        instance_id = %config.instance_id,
+        "Leader election started with {} second retry",
+        config.retry_secs
+    );
+
+    loop {
+        if lock.is_none() {
+            match LeaderLock::connect(&database_url, &config.instance_id, config.lock_key).await {
+                Ok(connected) => lock = Some(connected),
+                Err(e) => warn!(error = %e, "Leader election could not connect"),
+            }
+        }
+
+        if let Some(held) = lock.as_mut() {
+            if let Err(e) = step(held, &leadership).await {
+                if leadership.is_leader() {
+                    warn!(error = %e, "Lost the leader election connection; stepping down");
+                } else {
+                    warn!(error = %e, "Leader election attempt failed");
+                }
+                leadership.follow(None);
+                lock = None;
+            }
+        }
+
+        tokio::time::sleep(retry).await;
+    }
+}
+
+/// One round: confirm leadership, or try to take it and otherwise note the leader
+async fn step(lock: &mut LeaderLock, leadership: &Leadership) -> Result<(), crate::db::DbError> {
+    if leadership.is_leader() {
+        return lock.ping().await;
+    }
+
+    if lock.try_acquire().await? {
+        info!("Won the leader election; schedulers active");
+        leadership.lead();
+    } else {
+        let leader_id = lock.holder().await?;
+        debug!(leader_id = ?leader_id, "Following; schedulers idle");
+        leadership.follow(leader_id);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leadership_transitions() {
+        assert!(Leadership::always().is_leader());
+        assert_eq!(Leadership::always().status(), None);
+
+        let leadership = Leadership::elected("replica-a");
+        let shared = leadership.clone();
+        assert!(!leadership.is_leader());
+
+        shared.lead();
+        let status = leadership.status().unwrap();
+        assert!(status.is_leader);
+        assert_eq!(status.leader_id.as_deref(), Some("replica-a"));
+        assert!(status.leader_since.is_some());
+
+        shared.follow(Some("replica-b".to_string()));
+        let status = leadership.status().unwrap();
+        assert!(!status.is_leader);
+        assert_eq!(status.leader_id.as_deref(), Some("replica-b"));
+    }
+}
@@ -1,13 +1,48 @@
+pub mod analytics;
+pub mod anomaly;
 pub mod api;
 pub mod app;
+pub mod breaker;
+pub mod cli;
+pub mod clock;
 pub mod config;
 pub mod db;
+pub mod digest;
+pub mod elevation;
+pub mod error;
 pub mod fetch_error;
 pub mod fetcher;
+pub mod footprint;
 pub mod fopr;
+pub mod forecast;
+pub mod gauge_list_drift;
 pub mod gauge_list_fetcher;
+pub mod geocode;
+pub mod gis;
+pub mod grid;
 pub mod importers;
+pub mod ingest_guard;
+pub mod job_notify;
+pub mod job_windows;
+pub mod leader;
+pub mod loadgen;
+pub mod metrics;
+pub mod notify;
+pub mod pagination;
+pub mod payload_archive;
+pub mod politeness;
+pub mod radar;
+pub mod readiness;
 pub mod scheduler;
 pub mod services;
+pub mod share;
+#[cfg(feature = "sqlite")]
+pub mod snapshot;
+pub mod station_id;
+pub mod storage;
+pub mod tiles;
+pub mod units;
 pub mod utils;
+pub mod weather;
 pub mod workers;
+pub mod zones;
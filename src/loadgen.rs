@@ -0,0 +1,463 @@
+// Load generator for capacity planning
+//
+// Replays a dashboard-like mix of API requests (gauge list, latest readings, water year
+// summaries) against a running service at a fixed request rate, and reports latency
+// percentiles per endpoint. Requests go out on schedule whether or not earlier ones
+// have finished (open loop), so an overloaded server shows up as rising latency and,
+// beyond --max-in-flight, as dropped requests rather than a quietly lower rate.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use clap::Parser;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use serde::Serialize;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tokio::time::MissedTickBehavior;
+
+use crate::services::ReadingService;
+
+pub type LoadgenResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// Stations used when discovering them from the gauge list
+const DISCOVERED_STATIONS: usize = 50;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "loadgen",
+    version,
+    about = "Replay dashboard traffic against the API and report latency percentiles"
+)]
+pub struct LoadgenArgs {
+    /// Base URL of the service (e.g. http://localhost:8080)
+    #[arg(long, env = "LOADGEN_TARGET")]
+    pub target: String,
+
+    /// Requests started per second
+    #[arg(long, default_value_t = 20, value_parser = clap::value_parser!(u32).range(1..=10_000))]
+    pub rps: u32,
+
+    /// Seconds to send traffic for
+    #[arg(long, default_value_t = 60, value_parser = clap::value_parser!(u64).range(1..))]
+    pub duration: u64,
+
+    /// Requests allowed in flight at once; scheduled requests beyond this are dropped
+    #[arg(long, default_value_t = 200)]
+    pub max_in_flight: usize,
+
+    /// Stations to query (comma-separated); the first page of /api/v1/gauges when omitted
+    #[arg(long, value_delimiter = ',')]
+    pub stations: Vec<String>,
+
+    /// Water year for water year requests; the current one when omitted
+    #[arg(long)]
+    pub water_year: Option<i32>,
+
+    /// Relative weights of each request type
+    #[arg(long, default_value = "gauges=2,latest=5,water_year=3")]
+    pub mix: TrafficMix,
+
+    /// Per-request timeout in seconds
+    #[arg(long, default_value_t = 10)]
+    pub timeout: u64,
+
+    /// RNG seed for the request sequence; random when omitted
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Print the report as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Requests a dashboard makes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Endpoint {
+    /// GET /api/v1/gauges
+    Gauges,
+    /// GET /api/v1/readings/{station_id}/latest
+    Latest,
+    /// GET /api/v1/readings/{station_id}/water-year/{year}
+    WaterYear,
+}
+
+impl Endpoint {
+    pub const ALL: [Endpoint; 3] = [Endpoint::Gauges, Endpoint::Latest, Endpoint::WaterYear];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Endpoint::Gauges => "gauges",
+            Endpoint::Latest => "latest",
+            Endpoint::WaterYear => "water_year",
+        }
+    }
+
+    fn path(self, station_id: &str, water_year: i32) -> String {
+        match self {
+            Endpoint::Gauges => "/api/v1/gauges".to_string(),
+            Endpoint::Latest => format!("/api/v1/readings/{station_id}/latest"),
+            Endpoint::WaterYear => {
+                format!("/api/v1/readings/{station_id}/water-year/{water_year}")
+            }
+        }
+    }
+}
+
+/// Relative weight of each endpoint, parsed from "gauges=2,latest=5,water_year=3"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrafficMix {
+    weights: BTreeMap<Endpoint, u32>,
+}
+
+impl TrafficMix {
+    pub fn weight(&self, endpoint: Endpoint) -> u32 {
+        self.weights.get(&endpoint).copied().unwrap_or(0)
+    }
+}
+
+impl FromStr for TrafficMix {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut weights = BTreeMap::new();
+        for part in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (name, weight) = part
+                .split_once('=')
+                .ok_or_else(|| format!("expected name=weight, got '{part}'"))?;
+            let endpoint = Endpoint::ALL
+                .into_iter()
+                .find(|e| e.name() == name.trim())
+                .ok_or_else(|| {
+                    format!("unknown request type '{name}' (expected gauges, latest, water_year)")
+                })?;
+            let weight = weight
+                .trim()
+                .parse::<u32>()
+                .map_err(|_| format!("invalid weight for {name}: '{weight}'"))?;
+            weights.insert(endpoint, weight);
+        }
+        if weights.values().all(|w| *w == 0) {
+            return Err("at least one request type needs a weight above 0".to_string());
+        }
+        Ok(Self { weights })
+    }
+}
+
+/// Latency percentiles in milliseconds
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LatencySummary {
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+    pub mean_ms: f64,
+}
+
+impl LatencySummary {
+    /// Nearest-rank percentiles; None when there are no samples
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut ms: Vec<f64> = samples.iter().map(|d| d.as_secs_f64() * 1000.0).collect();
+        ms.sort_by(f64::total_cmp);
+        let rank = |p: f64| ms[((p * ms.len() as f64).ceil() as usize).clamp(1, ms.len()) - 1];
+
+        Some(Self {
+            p50_ms: rank(0.5),
+            p90_ms: rank(0.9),
+            p99_ms: rank(0.99),
+            max_ms: ms[ms.len() - 1],
+            mean_ms: ms.iter().sum::<f64>() / ms.len() as f64,
+        })
+    }
+}
+
+/// Results for one endpoint (or all of them)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EndpointReport {
+    /// Requests sent and completed (including failures)
+    pub requests: usize,
+    /// 2xx and 304 responses
+    pub succeeded: usize,
+    /// Other statuses, timeouts, and connection errors
+    pub failed: usize,
+    /// Scheduled but not sent because --max-in-flight requests were outstanding
+    pub dropped: usize,
+    /// Response counts by status code, with "error" for requests that got no response
+    pub status_codes: BTreeMap<String, usize>,
+    /// Over completed requests, failures included
+    pub latency: Option<LatencySummary>,
+}
+
+/// Summary of a load test run
+#[derive(Debug, Clone, Serialize)]
+pub struct LoadReport {
+    pub target: String,
+    pub requested_rps: u32,
+    /// Completed requests per second over the whole run
+    pub achieved_rps: f64,
+    pub duration_secs: f64,
+    pub stations: usize,
+    pub water_year: i32,
+    pub total: EndpointReport,
+    pub endpoints: BTreeMap<String, EndpointReport>,
+}
+
+impl fmt::Display for LoadReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Load test of {} for {:.1}s: {:.1} req/s achieved of {} requested ({} stations, water year {})",
+            self.target,
+            self.duration_secs,
+            self.achieved_rps,
+            self.requested_rps,
+            self.stations,
+            self.water_year
+        )?;
+        writeln!(
+            f,
+            "  {:<12} {:>8} {:>7} {:>8} {:>9} {:>9} {:>9} {:>9}",
+            "endpoint", "requests", "failed", "dropped", "p50 ms", "p90 ms", "p99 ms", "max ms"
+        )?;
+        let rows = self
+            .endpoints
+            .iter()
+            .map(|(name, report)| (name.as_str(), report))
+            .chain(std::iter::once(("total", &self.total)));
+        for (name, report) in rows {
+            write!(
+                f,
+                "  {name:<12} {:>8} {:>7} {:>8}",
+                report.requests, report.failed, report.dropped
+            )?;
+            match &report.latency {
+                Some(l) => writeln!(
+                    f,
+                    " {:>9.1} {:>9.1} {:>9.1} {:>9.1}",
+                    l.p50_ms, l.p90_ms, l.p99_ms, l.max_ms
+                )?,
+                None => writeln!(f)?,
+            }
+        }
+        if self.total.failed > 0 {
+            let codes: Vec<String> = self
+                .total
+                .status_codes
+                .iter()
+                .map(|(code, count)| format!("{code}: {count}"))
+                .collect();
+            write!(f, "Responses by status: {}", codes.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// One completed request
+struct Sample {
+    endpoint: Endpoint,
+    latency: Duration,
+    /// HTTP status, or None if the request failed without a response
+    status: Option<u16>,
+}
+
+/// Send traffic for `args.duration` seconds and summarize the responses
+pub async fn run(args: &LoadgenArgs) -> LoadgenResult<LoadReport> {
+    let target = args.target.trim_end_matches('/').to_string();
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(args.timeout))
+        .build()?;
+
+    let stations = if args.stations.is_empty() {
+        discover_stations(&client, &target).await?
+    } else {
+        args.stations.clone()
+    };
+    let water_year = args
+        .water_year
+        .unwrap_or_else(|| ReadingService::get_water_year(Utc::now()));
+
+    let endpoints: Vec<Endpoint> = Endpoint::ALL
+        .into_iter()
+        .filter(|e| args.mix.weight(*e) > 0)
+        .collect();
+    let choose = WeightedIndex::new(endpoints.iter().map(|e| args.mix.weight(*e)))?;
+    let mut rng = StdRng::seed_from_u64(args.seed.unwrap_or_else(rand::random));
+
+    let total_requests = args.rps as u64 * args.duration;
+    let mut ticker = tokio::time::interval(Duration::from_secs_f64(1.0 / args.rps as f64));
+    // Fall behind and catch up rather than silently lowering the rate
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Burst);
+    let in_flight = Arc::new(Semaphore::new(args.max_in_flight.max(1)));
+
+    let mut requests = JoinSet::new();
+    let mut samples = Vec::with_capacity(total_requests as usize);
+    let mut dropped: BTreeMap<Endpoint, usize> = BTreeMap::new();
+    let start = Instant::now();
+
+    for _ in 0..total_requests {
+        ticker.tick().await;
+        while let Some(done) = requests.try_join_next() {
+            samples.push(done?);
+        }
+
+        let endpoint = endpoints[choose.sample(&mut rng)];
+        let station = stations.choose(&mut rng).expect("stations is not empty");
+        let Ok(permit) = in_flight.clone().try_acquire_owned() else {
+            *dropped.entry(endpoint).or_default() += 1;
+            continue;
+        };
+
+        let client = client.clone();
+        let url = format!("{target}{}", endpoint.path(station, water_year));
+        requests.spawn(async move {
+            let sent = Instant::now();
+            let status = client
+                .get(&url)
+                .send()
+                .await
+                .ok()
+                .map(|response| response.status().as_u16());
+            drop(permit);
+            Sample {
+                endpoint,
+                latency: sent.elapsed(),
+                status,
+            }
+        });
+    }
+    while let Some(done) = requests.join_next().await {
+        samples.push(done?);
+    }
+    let elapsed = start.elapsed().as_secs_f64();
+
+    let mut endpoint_reports = BTreeMap::new();
+    for endpoint in &endpoints {
+        let endpoint_samples: Vec<&Sample> =
+            samples.iter().filter(|s| s.endpoint == *endpoint).collect();
+        let dropped = dropped.get(endpoint).copied().unwrap_or(0);
+        endpoint_reports.insert(
+            endpoint.name().to_string(),
+            summarize(&endpoint_samples, dropped),
+        );
+    }
+    let all: Vec<&Sample> = samples.iter().collect();
+
+    Ok(LoadReport {
+        target,
+        requested_rps: args.rps,
+        achieved_rps: samples.len() as f64 / elapsed,
+        duration_secs: elapsed,
+        stations: stations.len(),
+        water_year,
+        total: summarize(&all, dropped.values().sum()),
+        endpoints: endpoint_reports,
+    })
+}
+
+/// Station IDs from the first page of the gauge list
+async fn discover_stations(client: &reqwest::Client, target: &str) -> LoadgenResult<Vec<String>> {
+    let response = client
+        .get(format!("{target}/api/v1/gauges"))
+        .send()
+        .await?
+        .error_for_status()?;
+    let body: serde_json::Value = serde_json::from_str(&response.text().await?)?;
+
+    let stations: Vec<String> = body["gauges"]
+        .as_array()
+        .map(|gauges| {
+            gauges
+                .iter()
+                .filter_map(|g| g["station_id"].as_str().map(str::to_string))
+                .take(DISCOVERED_STATIONS)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if stations.is_empty() {
+        return Err(format!("{target}/api/v1/gauges listed no gauges; pass --stations").into());
+    }
+    Ok(stations)
+}
+
+fn summarize(samples: &[&Sample], dropped: usize) -> EndpointReport {
+    let mut report = EndpointReport {
+        requests: samples.len(),
+        dropped,
+        ..Default::default()
+    };
+    for sample in samples {
+        let code = sample.status.map_or("error".to_string(), |s| s.to_string());
+        *report.status_codes.entry(code).or_default() += 1;
+        match sample.status {
+            Some(status) if (200..300).contains(&status) || status == 304 => report.succeeded += 1,
+            _ => report.failed += 1,
+        }
+    }
+    let latencies: Vec<Duration> = samples.iter().map(|s| s.latency).collect();
+    report.latency = LatencySummary::from_samples(&latencies);
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_traffic_mix() {
+        let mix: TrafficMix = "gauges=1, latest=0,water_year=4".parse().unwrap();
+        assert_eq!(mix.weight(Endpoint::Gauges), 1);
+        assert_eq!(mix.weight(Endpoint::Latest), 0);
+        assert_eq!(mix.weight(Endpoint::WaterYear), 4);
+
+        let partial: TrafficMix = "latest=3".parse().unwrap();
+        assert_eq!(partial.weight(Endpoint::Gauges), 0);
+
+        assert!("latest".parse::<TrafficMix>().is_err());
+        assert!("calendar_year=1".parse::<TrafficMix>().is_err());
+        assert!("latest=-1".parse::<TrafficMix>().is_err());
+        assert!("gauges=0,latest=0".parse::<TrafficMix>().is_err());
+    }
+
+    #[test]
+    fn test_latency_percentiles() {
+        let samples: Vec<Duration> = (1..=100).map(Duration::from_millis).collect();
+        let latency = LatencySummary::from_samples(&samples).unwrap();
+
+        assert_eq!(latency.p50_ms, 50.0);
+        assert_eq!(latency.p90_ms, 90.0);
+        assert_eq!(latency.p99_ms, 99.0);
+        assert_eq!(latency.max_ms, 100.0);
+        assert_eq!(latency.mean_ms, 50.5);
+        assert!(LatencySummary::from_samples(&[]).is_none());
+    }
+
+    #[test]
+    fn test_endpoint_paths() {
+        assert_eq!(Endpoint::Gauges.path("59700", 2025), "/api/v1/gauges");
+        assert_eq!(
+            Endpoint::Latest.path("59700", 2025),
+            "/api/v1/readings/59700/latest"
+        );
+        assert_eq!(
+            Endpoint::WaterYear.path("59700", 2025),
+            "/api/v1/readings/59700/water-year/2025"
+        );
+    }
+
+    #[test]
+    fn test_cli_definition_is_valid() {
+        use clap::CommandFactory;
+        LoadgenArgs::command().debug_assert();
+    }
+}
@@ -1,12 +1,60 @@
-use sqlx::postgres::PgPoolOptions;
-use tracing::info;
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use tracing::{error, info};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
-use rain_tracker_service::app::Application;
-use rain_tracker_service::config::Config;
+use rain_tracker_service::app::{self, Application, ServiceRole};
+use rain_tracker_service::config::{Config, Profile};
+use rain_tracker_service::db::DbPool;
+use rain_tracker_service::error::Error;
+use rain_tracker_service::metrics::Metrics;
+
+/// Rain tracker service: HTTP API, scrape schedulers, and FOPR import workers
+#[derive(Parser)]
+#[command(version)]
+struct Args {
+    /// Run only part of the service, to scale the API apart from background work
+    #[arg(long, value_enum, env = "SERVICE_ROLE")]
+    role: Option<ServiceRole>,
+
+    /// Bundle of defaults for the deployment; overrides SERVICE_PROFILE, and picks the
+    /// defaults `config print-default` shows
+    #[arg(long, value_enum, global = true)]
+    profile: Option<Profile>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Inspect the service configuration
+    #[command(subcommand)]
+    Config(ConfigCommand),
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Print a commented .env template of every setting with its default
+    PrintDefault,
+}
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
+async fn main() -> ExitCode {
+    // Load environment variables before parsing, so SERVICE_ROLE may come from .env
+    dotenvy::dotenv().ok();
+    let args = Args::parse();
+
+    // Defaults come from code, so the template is the same whatever is set
+    if let Some(Command::Config(ConfigCommand::PrintDefault)) = args.command {
+        print!(
+            "{}",
+            Config::default_template(args.profile.unwrap_or_default())
+        );
+        return ExitCode::SUCCESS;
+    }
+
     // Initialize tracing with environment filter support
     tracing_subscriber::registry()
         .with(
@@ -21,29 +69,52 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         )
         .init();
 
-    // Load environment variables
-    dotenvy::dotenv().ok();
+    // Exit statuses follow the error's class (see `rain_tracker_service::error`)
+    match run(args).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            error!(class = e.class().as_str(), "Service stopped: {}", e);
+            e.exit_code()
+        }
+    }
+}
 
+async fn run(args: Args) -> Result<(), Error> {
     // Load configuration
-    let config = Config::from_env()?;
+    let lookup = |key: &str| match (key, args.profile) {
+        ("SERVICE_PROFILE", Some(profile)) => Some(profile.to_string()),
+        _ => std::env::var(key).ok(),
+    };
+    let mut config = Config::from_lookup(&lookup)?;
+    if let Some(role) = args.role {
+        config.server.role = role;
+    }
+    config.ensure_valid()?;
     info!("Starting rain tracker service with config: {:?}", config);
 
-    // Create database connection pool
-    info!("Connecting to database...");
-    let pool = PgPoolOptions::new()
-        .max_connections(5)
-        .connect(&config.database_url)
-        .await?;
-    info!("Database connection established");
-
-    // Run migrations
-    info!("Running database migrations...");
-    sqlx::migrate!("./migrations").run(&pool).await?;
-    info!("Database migrations completed");
+    let metrics = Metrics::new();
+    let pool = match &config.database.snapshot_dir {
+        Some(dir) => load_snapshot(dir).await?,
+        None => app::connect_database(&config, &metrics).await?,
+    };
 
     // Build and run application
-    let app = Application::build(config, pool).await?;
+    let app = Application::build(config, pool, metrics).await?;
     app.run_until_stopped().await?;
 
     Ok(())
 }
+
+#[cfg(feature = "sqlite")]
+async fn load_snapshot(dir: &str) -> Result<DbPool, Error> {
+    info!("Loading snapshot from {}...", dir);
+    let (pool, _stats) = rain_tracker_service::snapshot::load(std::path::Path::new(dir)).await?;
+    Ok(pool)
+}
+
+#[cfg(not(feature = "sqlite"))]
+async fn load_snapshot(_dir: &str) -> Result<DbPool, Error> {
+    Err(Error::Config(
+        "Snapshot mode (SNAPSHOT_DIR) requires a build with `--features sqlite`".to_string(),
+    ))
+}
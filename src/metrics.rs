@@ -0,0 +1,711 @@
+// Request metrics for operators
+//
+// Every HTTP request is recorded against its matched route template (so
+// /readings/59700/latest and /readings/1000/latest share one series), and successful
+// reads of a station-scoped route count toward that station. The same counters back
+// two views:
+// - GET /metrics: Prometheus text exposition for scraping
+// - GET /api/v1/admin/stats: a JSON summary of the slowest routes and hottest gauges
+//
+// Database connectivity is recorded here too: startup connection attempts, and the
+// periodic health check's view of whether the database is reachable and how often the
+// pool has recovered from losing it.
+//
+// So is the gauge list scheduler's drift check: the gauge count of the last scrape, the
+// trailing average it was compared with, and how many scrapes were rejected as suspect.
+//
+// Upstream hosts' circuit breakers (see crate::breaker) are read at scrape time: each
+// host's state, consecutive failures, and how often its breaker has opened.
+//
+// Counters live in process memory and reset on restart, like any Prometheus counter.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use axum::http::{Method, StatusCode};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::breaker::{BreakerState, CircuitBreakers, UpstreamStatus};
+use crate::db::PoolStats;
+
+/// Upper bounds (seconds) of the latency histogram buckets
+pub const LATENCY_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Route label for requests that matched no route (404 fallback)
+pub const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Per-route request counts and latency histogram
+#[derive(Debug, Clone, Default)]
+struct RouteStats {
+    /// Non-cumulative bucket counts; the last slot holds requests slower than every bound
+    buckets: [u64; LATENCY_BUCKETS.len() + 1],
+    count: u64,
+    sum_seconds: f64,
+    max_seconds: f64,
+    /// Requests by status class, indexed 1xx..5xx
+    by_class: [u64; 5],
+}
+
+impl RouteStats {
+    fn observe(&mut self, status: StatusCode, elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+        let bucket = LATENCY_BUCKETS
+            .iter()
+            .position(|&bound| seconds <= bound)
+            .unwrap_or(LATENCY_BUCKETS.len());
+        self.buckets[bucket] += 1;
+        self.count += 1;
+        self.sum_seconds += seconds;
+        self.max_seconds = self.max_seconds.max(seconds);
+        let class = (status.as_u16() / 100).clamp(1, 5) as usize;
+        self.by_class[class - 1] += 1;
+    }
+
+    /// Estimate a quantile as the upper bound of the bucket containing it, capped at
+    /// the slowest request seen
+    fn quantile(&self, q: f64) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        let rank = (q * self.count as f64).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (i, &n) in self.buckets.iter().enumerate() {
+            seen += n;
+            if seen >= rank {
+                return LATENCY_BUCKETS
+                    .get(i)
+                    .map_or(self.max_seconds, |&bound| bound.min(self.max_seconds));
+            }
+        }
+        self.max_seconds
+    }
+}
+
+/// Database connectivity as seen by startup and the health check
+#[derive(Debug, Clone, Default)]
+struct DbStats {
+    connect_successes: u64,
+    connect_failures: u64,
+    /// None until startup connects or the first health check runs
+    up: Option<bool>,
+    check_failures: u64,
+    /// Health checks that succeeded after the previous one failed
+    reconnects: u64,
+    pool: Option<PoolStats>,
+}
+
+/// Gauge counts seen by the gauge list drift check
+#[derive(Debug, Clone, Default)]
+struct GaugeListStats {
+    /// None until the first scrape parses
+    last_count: Option<usize>,
+    trailing_average: Option<f64>,
+    last_suspect: bool,
+    suspect_scrapes: u64,
+}
+
+#[derive(Debug)]
+struct Registry {
+    started_at: Instant,
+    routes: HashMap<(&'static str, String), RouteStats>,
+    station_reads: HashMap<String, u64>,
+    db: DbStats,
+    gauge_list: GaugeListStats,
+    /// Set once outbound clients are built
+    breakers: Option<CircuitBreakers>,
+}
+
+/// Shared request metrics, cloned into the API state
+#[derive(Debug, Clone)]
+pub struct Metrics {
+    registry: Arc<Mutex<Registry>>,
+}
+
+/// Latency and status summary for one route
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct RouteSummary {
+    #[schema(example = "GET")]
+    pub method: String,
+    /// Route template, or `unmatched` for requests no route matched
+    #[schema(example = "/api/v1/readings/{station_id}/latest")]
+    pub route: String,
+    pub requests: u64,
+    /// Responses with a 4xx status
+    pub client_errors: u64,
+    /// Responses with a 5xx status
+    pub server_errors: u64,
+    pub mean_ms: f64,
+    /// Bucket estimate; see LATENCY_BUCKETS
+    pub p50_ms: f64,
+    /// Bucket estimate; see LATENCY_BUCKETS
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Successful reads of one station's data
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct StationReads {
+    #[schema(example = "59700")]
+    pub station_id: String,
+    pub reads: u64,
+}
+
+/// Body of GET /api/v1/admin/stats
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct StatsSummary {
+    /// Seconds since counters started (process start)
+    pub uptime_seconds: u64,
+    pub total_requests: u64,
+    /// Routes by total time spent serving them, slowest first
+    pub routes: Vec<RouteSummary>,
+    /// Most-read stations, most reads first
+    pub hot_stations: Vec<StationReads>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self {
+            registry: Arc::new(Mutex::new(Registry {
+                started_at: Instant::now(),
+                routes: HashMap::new(),
+                station_reads: HashMap::new(),
+                db: DbStats::default(),
+                gauge_list: GaugeListStats::default(),
+                breakers: None,
+            })),
+        }
+    }
+
+    /// Record one finished request against its route template
+    pub fn record_request(
+        &self,
+        method: &Method,
+        route: &str,
+        status: StatusCode,
+        elapsed: Duration,
+    ) {
+        let key = (method_label(method), route.to_string());
+        self.lock()
+            .routes
+            .entry(key)
+            .or_default()
+            .observe(status, elapsed);
+    }
+
+    /// Count one successful read of a station's data
+    pub fn record_station_read(&self, station_id: &str) {
+        let mut registry = self.lock();
+        match registry.station_reads.get_mut(station_id) {
+            Some(reads) => *reads += 1,
+            None => {
+                registry.station_reads.insert(station_id.to_string(), 1);
+            }
+        }
+    }
+
+    /// Count one startup attempt to connect to the database (or run its migrations)
+    pub fn record_db_connect(&self, ok: bool) {
+        let mut registry = self.lock();
+        if ok {
+            registry.db.connect_successes += 1;
+            registry.db.up = Some(true);
+        } else {
+            registry.db.connect_failures += 1;
+        }
+    }
+
+    /// Record a database health check; returns true when reachability changed since the
+    /// last check, so the caller logs outages and recoveries once each
+    pub fn record_db_check(&self, ok: bool, pool: PoolStats) -> bool {
+        let mut registry = self.lock();
+        let db = &mut registry.db;
+        let previous = db.up.replace(ok);
+        db.pool = Some(pool);
+        if !ok {
+            db.check_failures += 1;
+        } else if previous == Some(false) {
+            db.reconnects += 1;
+        }
+        previous.is_some_and(|up| up != ok)
+    }
+
+    /// Record one parsed gauge list scrape and whether the drift check rejected it
+    pub fn record_gauge_list_scrape(
+        &self,
+        count: usize,
+        trailing_average: Option<f64>,
+        suspect: bool,
+    ) {
+        let mut registry = self.lock();
+        let stats = &mut registry.gauge_list;
+        stats.last_count = Some(count);
+        stats.trailing_average = trailing_average;
+        stats.last_suspect = suspect;
+        if suspect {
+            stats.suspect_scrapes += 1;
+        }
+    }
+
+    /// Report these upstream breakers from now on
+    pub fn watch_breakers(&self, breakers: CircuitBreakers) {
+        self.lock().breakers = Some(breakers);
+    }
+
+    /// Breaker state of every upstream host requested so far
+    pub fn upstreams(&self) -> Vec<UpstreamStatus> {
+        let breakers = self.lock().breakers.clone();
+        breakers.map(|b| b.statuses()).unwrap_or_default()
+    }
+
+    /// Summary for the admin stats endpoint, keeping the `top_stations` most-read stations
+    pub fn summary(&self, top_stations: usize) -> StatsSummary {
+        let registry = self.lock();
+
+        let mut routes: Vec<(&(&str, String), &RouteStats)> = registry.routes.iter().collect();
+        routes.sort_by(|a, b| {
+            b.1.sum_seconds
+                .total_cmp(&a.1.sum_seconds)
+                .then_with(|| a.0.cmp(b.0))
+        });
+        let routes = routes
+            .into_iter()
+            .map(|((method, route), stats)| RouteSummary {
+                method: method.to_string(),
+                route: route.clone(),
+                requests: stats.count,
+                client_errors: stats.by_class[3],
+                server_errors: stats.by_class[4],
+                mean_ms: millis(stats.sum_seconds / stats.count.max(1) as f64),
+                p50_ms: millis(stats.quantile(0.5)),
+                p95_ms: millis(stats.quantile(0.95)),
+                max_ms: millis(stats.max_seconds),
+            })
+            .collect();
+
+        let mut hot_stations: Vec<StationReads> = registry
+            .station_reads
+            .iter()
+            .map(|(station_id, &reads)| StationReads {
+                station_id: station_id.clone(),
+                reads,
+            })
+            .collect();
+        hot_stations.sort_by(|a, b| {
+            b.reads
+                .cmp(&a.reads)
+                .then_with(|| a.station_id.cmp(&b.station_id))
+        });
+        hot_stations.truncate(top_stations);
+
+        StatsSummary {
+            uptime_seconds: registry.started_at.elapsed().as_secs(),
+            total_requests: registry.routes.values().map(|s| s.count).sum(),
+            routes,
+            hot_stations,
+        }
+    }
+
+    /// Prometheus text exposition format (version 0.0.4)
+    pub fn render_prometheus(&self) -> String {
+        let registry = self.lock();
+        let mut routes: Vec<_> = registry.routes.iter().collect();
+        routes.sort_by(|a, b| a.0.cmp(b.0));
+        let mut out = String::new();
+
+        out.push_str(
+            "# HELP rain_tracker_http_requests_total HTTP requests by route and status class.\n",
+        );
+        out.push_str("# TYPE rain_tracker_http_requests_total counter\n");
+        for ((method, route), stats) in &routes {
+            for (i, &n) in stats.by_class.iter().enumerate() {
+                if n > 0 {
+                    let _ = writeln!(
+                        out,
+                        "rain_tracker_http_requests_total{{method=\"{method}\",route=\"{}\",status=\"{}xx\"}} {n}",
+                        escape(route),
+                        i + 1
+                    );
+                }
+            }
+        }
+
+        out.push_str(
+            "# HELP rain_tracker_http_request_duration_seconds HTTP request latency by route.\n",
+        );
+        out.push_str("# TYPE rain_tracker_http_request_duration_seconds histogram\n");
+        for ((method, route), stats) in &routes {
+            let labels = format!("method=\"{method}\",route=\"{}\"", escape(route));
+            let mut cumulative = 0;
+            for (bound, &n) in LATENCY_BUCKETS.iter().zip(&stats.buckets) {
+                cumulative += n;
+                let _ = writeln!(
+                    out,
+                    "rain_tracker_http_request_duration_seconds_bucket{{{labels},le=\"{bound}\"}} {cumulative}"
+                );
+            }
+            let _ = writeln!(
+                out,
+                "rain_tracker_http_request_duration_seconds_bucket{{{labels},le=\"+Inf\"}} {}",
+                stats.count
+            );
+            let _ = writeln!(
+                out,
+                "rain_tracker_http_request_duration_seconds_sum{{{labels}}} {}",
+                stats.sum_seconds
+            );
+            let _ = writeln!(
+                out,
+                "rain_tracker_http_request_duration_seconds_count{{{labels}}} {}",
+                stats.count
+            );
+        }
+
+        let mut stations: Vec<_> = registry.station_reads.iter().collect();
+        stations.sort();
+        out.push_str("# HELP rain_tracker_station_reads_total Successful reads of station-scoped routes by station.\n");
+        out.push_str("# TYPE rain_tracker_station_reads_total counter\n");
+        for (station_id, reads) in stations {
+            let _ = writeln!(
+                out,
+                "rain_tracker_station_reads_total{{station_id=\"{}\"}} {reads}",
+                escape(station_id)
+            );
+        }
+
+        let db = &registry.db;
+        out.push_str("# HELP rain_tracker_db_connect_attempts_total Startup attempts to connect to the database and run migrations.\n");
+        out.push_str("# TYPE rain_tracker_db_connect_attempts_total counter\n");
+        let _ = writeln!(
+            out,
+            "rain_tracker_db_connect_attempts_total{{result=\"success\"}} {}",
+            db.connect_successes
+        );
+        let _ = writeln!(
+            out,
+            "rain_tracker_db_connect_attempts_total{{result=\"failure\"}} {}",
+            db.connect_failures
+        );
+        if let Some(up) = db.up {
+            out.push_str(
+                "# HELP rain_tracker_db_up Whether the last database health check succeeded.\n",
+            );
+            out.push_str("# TYPE rain_tracker_db_up gauge\n");
+            let _ = writeln!(out, "rain_tracker_db_up {}", u8::from(up));
+        }
+        out.push_str(
+            "# HELP rain_tracker_db_health_check_failures_total Failed database health checks.\n",
+        );
+        out.push_str("# TYPE rain_tracker_db_health_check_failures_total counter\n");
+        let _ = writeln!(
+            out,
+            "rain_tracker_db_health_check_failures_total {}",
+            db.check_failures
+        );
+        out.push_str("# HELP rain_tracker_db_reconnects_total Recoveries after a failed database health check.\n");
+        out.push_str("# TYPE rain_tracker_db_reconnects_total counter\n");
+        let _ = writeln!(out, "rain_tracker_db_reconnects_total {}", db.reconnects);
+        if let Some(pool) = db.pool {
+            out.push_str(
+                "# HELP rain_tracker_db_pool_connections Open database connections by state.\n",
+            );
+            out.push_str("# TYPE rain_tracker_db_pool_connections gauge\n");
+            let _ = writeln!(
+                out,
+                "rain_tracker_db_pool_connections{{state=\"idle\"}} {}",
+                pool.idle
+            );
+            let _ = writeln!(
+                out,
+                "rain_tracker_db_pool_connections{{state=\"in_use\"}} {}",
+                pool.size.saturating_sub(pool.idle)
+            );
+        }
+
+        let gauge_list = &registry.gauge_list;
+        if let Some(count) = gauge_list.last_count {
+            out.push_str(
+                "# HELP rain_tracker_gauge_list_gauges Gauges parsed from the last gauge list scrape.\n",
+            );
+            out.push_str("# TYPE rain_tracker_gauge_list_gauges gauge\n");
+            let _ = writeln!(out, "rain_tracker_gauge_list_gauges {count}");
+            out.push_str("# HELP rain_tracker_gauge_list_suspect Whether the last gauge list scrape was rejected as parser drift.\n");
+            out.push_str("# TYPE rain_tracker_gauge_list_suspect gauge\n");
+            let _ = writeln!(
+                out,
+                "rain_tracker_gauge_list_suspect {}",
+                u8::from(gauge_list.last_suspect)
+            );
+        }
+        if let Some(average) = gauge_list.trailing_average {
+            out.push_str("# HELP rain_tracker_gauge_list_trailing_average Average gauge count of recent accepted scrapes.\n");
+            out.push_str("# TYPE rain_tracker_gauge_list_trailing_average gauge\n");
+            let _ = writeln!(out, "rain_tracker_gauge_list_trailing_average {average}");
+        }
+        out.push_str("# HELP rain_tracker_gauge_list_suspect_scrapes_total Gauge list scrapes not stored because the gauge count dropped sharply.\n");
+        out.push_str("# TYPE rain_tracker_gauge_list_suspect_scrapes_total counter\n");
+        let _ = writeln!(
+            out,
+            "rain_tracker_gauge_list_suspect_scrapes_total {}",
+            gauge_list.suspect_scrapes
+        );
+
+        let upstreams = registry
+            .breakers
+            .as_ref()
+            .map(CircuitBreakers::statuses)
+            .unwrap_or_default();
+        if !upstreams.is_empty() {
+            out.push_str("# HELP rain_tracker_upstream_breaker_state Upstream circuit breaker state by host (1 for the current state).\n");
+            out.push_str("# TYPE rain_tracker_upstream_breaker_state gauge\n");
+            for upstream in &upstreams {
+                for state in [
+                    BreakerState::Closed,
+                    BreakerState::Open,
+                    BreakerState::HalfOpen,
+                ] {
+                    let _ = writeln!(
+                        out,
+                        "rain_tracker_upstream_breaker_state{{host=\"{}\",state=\"{}\"}} {}",
+                        escape(&upstream.host),
+                        state.as_str(),
+                        u8::from(upstream.state == state)
+                    );
+                }
+            }
+            out.push_str("# HELP rain_tracker_upstream_consecutive_failures Failed requests to the host since its last success.\n");
+            out.push_str("# TYPE rain_tracker_upstream_consecutive_failures gauge\n");
+            for upstream in &upstreams {
+                let _ = writeln!(
+                    out,
+                    "rain_tracker_upstream_consecutive_failures{{host=\"{}\"}} {}",
+                    escape(&upstream.host),
+                    upstream.consecutive_failures
+                );
+            }
+            out.push_str("# HELP rain_tracker_upstream_breaker_trips_total Times the host's circuit breaker has opened.\n");
+            out.push_str("# TYPE rain_tracker_upstream_breaker_trips_total counter\n");
+            for upstream in &upstreams {
+                let _ = writeln!(
+                    out,
+                    "rain_tracker_upstream_breaker_trips_total{{host=\"{}\"}} {}",
+                    escape(&upstream.host),
+                    upstream.trips
+                );
+            }
+        }
+
+        out
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Registry> {
+        self.registry.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Standard methods keep their name; anything else shares one label to bound cardinality
+fn method_label(method: &Method) -> &'static str {
+    match *method {
+        Method::GET => "GET",
+        Method::HEAD => "HEAD",
+        Method::POST => "POST",
+        Method::PUT => "PUT",
+        Method::PATCH => "PATCH",
+        Method::DELETE => "DELETE",
+        Method::OPTIONS => "OPTIONS",
+        _ => "OTHER",
+    }
+}
+
+fn millis(seconds: f64) -> f64 {
+    (seconds * 1_000_000.0).round() / 1_000.0
+}
+
+/// Escape a Prometheus label value
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROUTE: &str = "/api/v1/readings/{station_id}/latest";
+
+    #[test]
+    fn test_summary_orders_routes_and_stations() {
+        let metrics = Metrics::new();
+        for ms in [2, 4, 30] {
+            metrics.record_request(
+                &Method::GET,
+                ROUTE,
+                StatusCode::OK,
+                Duration::from_millis(ms),
+            );
+        }
+        metrics.record_request(
+            &Method::GET,
+            "/api/v1/health",
+            StatusCode::OK,
+            Duration::from_millis(1),
+        );
+        metrics.record_request(
+            &Method::GET,
+            ROUTE,
+            StatusCode::NOT_FOUND,
+            Duration::from_millis(1),
+        );
+        metrics.record_station_read("59700");
+        metrics.record_station_read("1000");
+        metrics.record_station_read("59700");
+
+        let summary = metrics.summary(1);
+        assert_eq!(summary.total_requests, 5);
+        let slowest = &summary.routes[0];
+        assert_eq!(slowest.route, ROUTE);
+        assert_eq!(slowest.requests, 4);
+        assert_eq!(slowest.client_errors, 1);
+        assert_eq!(slowest.server_errors, 0);
+        assert_eq!(slowest.p50_ms, 5.0);
+        // Slowest request sits in the 50ms bucket; the estimate is capped at the max
+        assert_eq!(slowest.p95_ms, 30.0);
+        assert_eq!(slowest.max_ms, 30.0);
+        assert_eq!(summary.routes[1].route, "/api/v1/health");
+        assert_eq!(
+            summary.hot_stations,
+            [StationReads {
+                station_id: "59700".to_string(),
+                reads: 2
+            }]
+        );
+    }
+
+    #[test]
+    fn test_render_prometheus() {
+        let metrics = Metrics::new();
+        metrics.record_request(
+            &Method::GET,
+            ROUTE,
+            StatusCode::OK,
+            Duration::from_millis(20),
+        );
+        metrics.record_request(
+            &Method::from_bytes(b"PURGE").unwrap(),
+            UNMATCHED_ROUTE,
+            StatusCode::NOT_FOUND,
+            Duration::from_secs(30),
+        );
+        metrics.record_station_read("59700");
+
+        let text = metrics.render_prometheus();
+        let labels = format!("method=\"GET\",route=\"{ROUTE}\"");
+        assert!(text.contains(&format!(
+            "rain_tracker_http_requests_total{{{labels},status=\"2xx\"}} 1\n"
+        )));
+        assert!(text.contains(&format!(
+            "rain_tracker_http_request_duration_seconds_bucket{{{labels},le=\"0.01\"}} 0\n"
+        )));
+        assert!(text.contains(&format!(
+            "rain_tracker_http_request_duration_seconds_bucket{{{labels},le=\"0.025\"}} 1\n"
+        )));
+        assert!(text.contains(&format!(
+            "rain_tracker_http_request_duration_seconds_count{{{labels}}} 1\n"
+        )));
+        // Slower than every bound: only +Inf counts it
+        assert!(text.contains(
+            "rain_tracker_http_request_duration_seconds_bucket{method=\"OTHER\",route=\"unmatched\",le=\"10\"} 0\n"
+        ));
+        assert!(text.contains(
+            "rain_tracker_http_request_duration_seconds_bucket{method=\"OTHER\",route=\"unmatched\",le=\"+Inf\"} 1\n"
+        ));
+        assert!(text.contains("rain_tracker_station_reads_total{station_id=\"59700\"} 1\n"));
+    }
+
+    #[test]
+    fn test_db_connectivity() {
+        let metrics = Metrics::new();
+        let pool = PoolStats { size: 5, idle: 2 };
+        metrics.record_db_connect(false);
+        metrics.record_db_connect(true);
+
+        assert!(!metrics.record_db_check(true, pool));
+        assert!(metrics.record_db_check(false, pool), "outage starts");
+        assert!(!metrics.record_db_check(false, pool));
+        assert!(metrics.record_db_check(true, pool), "recovered");
+
+        let text = metrics.render_prometheus();
+        assert!(text.contains("rain_tracker_db_connect_attempts_total{result=\"success\"} 1\n"));
+        assert!(text.contains("rain_tracker_db_connect_attempts_total{result=\"failure\"} 1\n"));
+        assert!(text.contains("rain_tracker_db_up 1\n"));
+        assert!(text.contains("rain_tracker_db_health_check_failures_total 2\n"));
+        assert!(text.contains("rain_tracker_db_reconnects_total 1\n"));
+        assert!(text.contains("rain_tracker_db_pool_connections{state=\"in_use\"} 3\n"));
+    }
+
+    #[test]
+    fn test_gauge_list_drift() {
+        let metrics = Metrics::new();
+        assert!(!metrics
+            .render_prometheus()
+            .contains("rain_tracker_gauge_list_gauges"));
+
+        metrics.record_gauge_list_scrape(310, Some(305.5), false);
+        metrics.record_gauge_list_scrape(12, Some(305.5), true);
+
+        let text = metrics.render_prometheus();
+        assert!(text.contains("rain_tracker_gauge_list_gauges 12\n"));
+        assert!(text.contains("rain_tracker_gauge_list_suspect 1\n"));
+        assert!(text.contains("rain_tracker_gauge_list_trailing_average 305.5\n"));
+        assert!(text.contains("rain_tracker_gauge_list_suspect_scrapes_total 1\n"));
+    }
+
+    #[test]
+    fn test_upstream_breakers() {
+        let metrics = Metrics::new();
+        assert!(metrics.upstreams().is_empty());
+
+        let breakers = CircuitBreakers::new(crate::breaker::BreakerConfig {
+            threshold: 2,
+            cooldown: Duration::from_secs(60),
+            max_cooldown: Duration::from_secs(600),
+        });
+        metrics.watch_breakers(breakers.clone());
+        breakers.record_success("api.weather.gov");
+        breakers.record_failure("alert.fcd.maricopa.gov");
+        breakers.record_failure("alert.fcd.maricopa.gov");
+
+        let upstreams = metrics.upstreams();
+        assert_eq!(upstreams.len(), 2);
+        assert_eq!(upstreams[0].state, BreakerState::Open);
+
+        let text = metrics.render_prometheus();
+        for line in [
+            "rain_tracker_upstream_breaker_state{host=\"alert.fcd.maricopa.gov\",state=\"open\"} 1\n",
+            "rain_tracker_upstream_breaker_state{host=\"api.weather.gov\",state=\"closed\"} 1\n",
+            "rain_tracker_upstream_breaker_state{host=\"api.weather.gov\",state=\"open\"} 0\n",
+            "rain_tracker_upstream_consecutive_failures{host=\"alert.fcd.maricopa.gov\"} 2\n",
+            "rain_tracker_upstream_breaker_trips_total{host=\"alert.fcd.maricopa.gov\"} 1\n",
+        ] {
+            assert!(text.contains(line), "missing {line}");
+        }
+    }
+
+    #[test]
+    fn test_escape_label_values() {
+        assert_eq!(escape(r#"a"b\c"#), r#"a\"b\\c"#);
+        assert_eq!(escape("a\nb"), "a\\nb");
+    }
+}
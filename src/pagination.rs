@@ -0,0 +1,94 @@
+// Page-number pagination for list endpoints
+//
+// Gauges, readings, radar storms, job runs, and threshold events page the same way:
+// `page` (from 1) and `page_size` query parameters, a `pagination` object next to the
+// items, and RFC 5988 `Link` headers with rel first, prev, next, and last (see
+// `crate::api::pagination`), so a generated client can walk any of them with one loop. An
+// empty list is a single empty page.
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Where a page sits in its list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+pub struct PageInfo {
+    /// Current page, starting at 1
+    #[schema(example = 1)]
+    pub page: u32,
+    #[schema(example = 50)]
+    pub page_size: u32,
+    /// Items across all pages
+    #[schema(example = 352)]
+    pub total_items: u64,
+    #[schema(example = 8)]
+    pub total_pages: u32,
+    #[schema(example = true)]
+    pub has_next_page: bool,
+    #[schema(example = false)]
+    pub has_prev_page: bool,
+}
+
+impl PageInfo {
+    pub fn new(page: u32, page_size: u32, total_items: u64) -> Self {
+        let total_pages = total_items.div_ceil(u64::from(page_size.max(1))).max(1);
+        let total_pages = u32::try_from(total_pages).unwrap_or(u32::MAX);
+        Self {
+            page,
+            page_size,
+            total_items,
+            total_pages,
+            has_next_page: page < total_pages,
+            has_prev_page: page > 1,
+        }
+    }
+
+    /// A whole list returned as one page
+    pub fn single(total_items: u64) -> Self {
+        let page_size = u32::try_from(total_items).unwrap_or(u32::MAX);
+        Self {
+            page_size,
+            ..Self::new(1, page_size, total_items)
+        }
+    }
+}
+
+/// Rows to skip to reach `page`
+pub fn offset(page: u32, page_size: u32) -> i64 {
+    i64::from(page.saturating_sub(1)) * i64::from(page_size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_info() {
+        let first = PageInfo::new(1, 50, 352);
+        assert_eq!(first.total_pages, 8);
+        assert!(first.has_next_page && !first.has_prev_page);
+
+        let last = PageInfo::new(8, 50, 352);
+        assert!(!last.has_next_page && last.has_prev_page);
+
+        let beyond = PageInfo::new(9, 50, 352);
+        assert!(!beyond.has_next_page && beyond.has_prev_page);
+
+        assert_eq!(PageInfo::new(1, 50, 0).total_pages, 1);
+        assert_eq!(PageInfo::new(1, 50, 50).total_pages, 1);
+        assert_eq!(PageInfo::new(1, 50, 51).total_pages, 2);
+    }
+
+    #[test]
+    fn test_single_page() {
+        let page = PageInfo::single(1650);
+        assert_eq!((page.page, page.page_size, page.total_pages), (1, 1650, 1));
+        assert!(!page.has_next_page && !page.has_prev_page);
+        assert_eq!(PageInfo::single(0).total_pages, 1);
+    }
+
+    #[test]
+    fn test_offset() {
+        assert_eq!(offset(1, 50), 0);
+        assert_eq!(offset(3, 50), 100);
+    }
+}
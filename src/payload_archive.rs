@@ -0,0 +1,395 @@
+// Raw upstream payload archive
+//
+// Each gauge page and gauge list response is kept exactly as fetched, so a parser bug
+// or an upstream format change can be replayed against real payloads
+// (`historical-import replay`) instead of a hand-made fixture. Payloads live under
+// `<dir>/<source>/` as `<fetched_at>-<sha256 prefix>.zst` (zstd-compressed body) next
+// to a `.json` sidecar holding the URL, Content-Type, size, and full SHA-256. File
+// names sort by fetch time. Payloads older than PAYLOAD_ARCHIVE_DAYS are pruned daily.
+//
+// Archiving is best effort: a failed write is logged and the scrape carries on.
+
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::fs;
+use tracing::{debug, info, instrument, warn};
+
+use crate::clock::{self, SharedClock};
+
+/// Days payloads are kept
+pub const DEFAULT_PAYLOAD_ARCHIVE_DAYS: u32 = 30;
+
+/// zstd level for payloads (the library default)
+const COMPRESSION_LEVEL: i32 = 0;
+
+/// Layout of archived file names' timestamp
+const STAMP_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// Which scrape a payload came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum PayloadSource {
+    /// Gauge list report (GAUGE_LIST_URL)
+    GaugeList,
+    /// Live gauge readings page (GAUGE_URL)
+    Readings,
+}
+
+impl PayloadSource {
+    pub const ALL: [PayloadSource; 2] = [PayloadSource::GaugeList, PayloadSource::Readings];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PayloadSource::GaugeList => "gauge_list",
+            PayloadSource::Readings => "readings",
+        }
+    }
+}
+
+impl fmt::Display for PayloadSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ArchiveError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Invalid payload metadata: {0}")]
+    Metadata(#[from] serde_json::Error),
+
+    #[error("Payload {0} does not match its recorded SHA-256")]
+    Corrupt(PathBuf),
+}
+
+/// Sidecar describing one archived payload
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PayloadMeta {
+    pub source: PayloadSource,
+    pub url: String,
+    pub fetched_at: DateTime<Utc>,
+    pub content_type: Option<String>,
+    /// Uncompressed size
+    pub bytes: usize,
+    /// Hex SHA-256 of the uncompressed body
+    pub sha256: String,
+}
+
+/// An archived payload's metadata and where its body is stored
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredPayload {
+    pub meta: PayloadMeta,
+    pub path: PathBuf,
+}
+
+/// Directory of archived upstream payloads, cloned into each fetcher that archives
+#[derive(Debug, Clone)]
+pub struct PayloadArchive {
+    dir: PathBuf,
+    retention_days: u32,
+    clock: SharedClock,
+}
+
+impl PayloadArchive {
+    pub fn new(dir: impl Into<PathBuf>, retention_days: u32) -> Self {
+        Self {
+            dir: dir.into(),
+            retention_days,
+            clock: clock::system_clock(),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn retention_days(&self) -> u32 {
+        self.retention_days
+    }
+
+    /// Archive a fetched body, logging rather than returning a failure
+    pub async fn record(
+        &self,
+        source: PayloadSource,
+        url: &str,
+        content_type: Option<&str>,
+        body: &str,
+    ) {
+        if let Err(e) = self.store(source, url, content_type, body).await {
+            warn!(error = %e, %source, "Failed to archive upstream payload");
+        }
+    }
+
+    /// Compress and store a fetched body with its sidecar
+    #[instrument(skip(self, body), fields(size = body.len()))]
+    pub async fn store(
+        &self,
+        source: PayloadSource,
+        url: &str,
+        content_type: Option<&str>,
+        body: &str,
+    ) -> Result<StoredPayload, ArchiveError> {
+        let meta = PayloadMeta {
+            source,
+            url: url.to_string(),
+            fetched_at: self.clock.now(),
+            content_type: content_type.map(String::from),
+            bytes: body.len(),
+            sha256: sha256_hex(body.as_bytes()),
+        };
+        let dir = self.dir.join(source.as_str());
+        fs::create_dir_all(&dir).await?;
+
+        let stem = format!(
+            "{}-{}",
+            meta.fetched_at.format(STAMP_FORMAT),
+            &meta.sha256[..16]
+        );
+        let path = dir.join(format!("{stem}.zst"));
+        let compressed = zstd::encode_all(body.as_bytes(), COMPRESSION_LEVEL)?;
+        fs::write(&path, compressed).await?;
+        // The sidecar goes last: listings only see payloads whose body is complete
+        fs::write(
+            dir.join(format!("{stem}.json")),
+            serde_json::to_vec_pretty(&meta)?,
+        )
+        .await?;
+
+        debug!(path = %path.display(), "Archived upstream payload");
+        Ok(StoredPayload { meta, path })
+    }
+
+    /// Archived payloads of `source` (or every source) fetched at or after `since`,
+    /// oldest first
+    pub async fn list(
+        &self,
+        source: Option<PayloadSource>,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<Vec<StoredPayload>, ArchiveError> {
+        let sources = match source {
+            Some(source) => vec![source],
+            None => PayloadSource::ALL.to_vec(),
+        };
+
+        let mut payloads = Vec::new();
+        for source in sources {
+            for sidecar in sidecars(&self.dir.join(source.as_str())).await? {
+                let meta: PayloadMeta = serde_json::from_slice(&fs::read(&sidecar).await?)?;
+                if since.is_some_and(|since| meta.fetched_at < since) {
+                    continue;
+                }
+                payloads.push(StoredPayload {
+                    meta,
+                    path: sidecar.with_extension("zst"),
+                });
+            }
+        }
+        payloads.sort_by(|a, b| {
+            a.meta
+                .fetched_at
+                .cmp(&b.meta.fetched_at)
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        Ok(payloads)
+    }
+
+    /// Decompress a payload, checking it against its recorded hash
+    pub async fn load(&self, payload: &StoredPayload) -> Result<String, ArchiveError> {
+        let body = zstd::decode_all(fs::read(&payload.path).await?.as_slice())?;
+        if sha256_hex(&body) != payload.meta.sha256 {
+            return Err(ArchiveError::Corrupt(payload.path.clone()));
+        }
+        String::from_utf8(body).map_err(|_| ArchiveError::Corrupt(payload.path.clone()))
+    }
+
+    /// Delete payloads fetched more than `retention_days` ago; 0 keeps everything
+    #[instrument(skip(self))]
+    pub async fn prune(&self) -> Result<usize, ArchiveError> {
+        if self.retention_days == 0 {
+            return Ok(0);
+        }
+        let cutoff = self.clock.now() - Duration::days(self.retention_days as i64);
+
+        let mut deleted = 0;
+        for source in PayloadSource::ALL {
+            for sidecar in sidecars(&self.dir.join(source.as_str())).await? {
+                let expired = stamp(&sidecar).is_some_and(|fetched_at| fetched_at < cutoff);
+                if expired {
+                    remove_if_exists(&sidecar.with_extension("zst")).await?;
+                    remove_if_exists(&sidecar).await?;
+                    deleted += 1;
+                }
+            }
+        }
+        if deleted > 0 {
+            info!(deleted, %cutoff, "Pruned archived upstream payloads");
+        }
+        Ok(deleted)
+    }
+}
+
+/// Sidecar files in a source directory; none when it doesn't exist yet
+async fn sidecars(dir: &Path) -> Result<Vec<PathBuf>, ArchiveError> {
+    let mut entries = match fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut paths = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            paths.push(path);
+        }
+    }
+    Ok(paths)
+}
+
+/// Fetch time encoded in an archived file name
+fn stamp(path: &Path) -> Option<DateTime<Utc>> {
+    let stem = path.file_stem()?.to_str()?;
+    let (stamp, _hash) = stem.split_once('-')?;
+    NaiveDateTime::parse_from_str(stamp, STAMP_FORMAT)
+        .ok()
+        .map(|t| t.and_utc())
+}
+
+async fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path).await {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::FixedClock;
+    use chrono::TimeZone;
+    use std::sync::Arc;
+
+    fn archive_at(dir: &Path, day: u32) -> PayloadArchive {
+        let now = Utc.with_ymd_and_hms(2025, 2, day, 6, 15, 0).unwrap();
+        PayloadArchive::new(dir, 7).with_clock(Arc::new(FixedClock(now)))
+    }
+
+    #[tokio::test]
+    async fn test_store_list_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = archive_at(dir.path(), 13);
+        let stored = archive
+            .store(
+                PayloadSource::GaugeList,
+                "https://alert.fcd.maricopa.gov/alert/Rain/ev_rain.txt",
+                Some("text/plain"),
+                "  59700  Aztec Park ...",
+            )
+            .await
+            .unwrap();
+        assert!(stored.path.ends_with(format!(
+            "gauge_list/20250213T061500Z-{}.zst",
+            &stored.meta.sha256[..16]
+        )));
+        archive_at(dir.path(), 14)
+            .store(
+                PayloadSource::Readings,
+                "https://example.com",
+                None,
+                "<pre>",
+            )
+            .await
+            .unwrap();
+
+        let all = archive.list(None, None).await.unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0], stored);
+        assert_eq!(
+            archive.load(&all[0]).await.unwrap(),
+            "  59700  Aztec Park ..."
+        );
+
+        let since = Utc.with_ymd_and_hms(2025, 2, 14, 0, 0, 0).unwrap();
+        let recent = archive.list(None, Some(since)).await.unwrap();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].meta.source, PayloadSource::Readings);
+        assert!(archive
+            .list(Some(PayloadSource::GaugeList), Some(since))
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_load_rejects_modified_payload() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = archive_at(dir.path(), 13);
+        let stored = archive
+            .store(
+                PayloadSource::Readings,
+                "https://example.com",
+                None,
+                "<pre>",
+            )
+            .await
+            .unwrap();
+        std::fs::write(&stored.path, zstd::encode_all(&b"<html>"[..], 0).unwrap()).unwrap();
+
+        assert!(matches!(
+            archive.load(&stored).await,
+            Err(ArchiveError::Corrupt(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_prune_deletes_expired_payloads() {
+        let dir = tempfile::tempdir().unwrap();
+        archive_at(dir.path(), 1)
+            .store(PayloadSource::Readings, "https://example.com", None, "old")
+            .await
+            .unwrap();
+        let archive = archive_at(dir.path(), 13);
+        archive
+            .store(PayloadSource::Readings, "https://example.com", None, "new")
+            .await
+            .unwrap();
+
+        assert_eq!(archive.prune().await.unwrap(), 1);
+        let left = archive.list(None, None).await.unwrap();
+        assert_eq!(left.len(), 1);
+        assert_eq!(archive.load(&left[0]).await.unwrap(), "new");
+        assert_eq!(
+            std::fs::read_dir(dir.path().join("readings"))
+                .unwrap()
+                .count(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_missing_directory_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = archive_at(&dir.path().join("missing"), 13);
+        assert!(archive.list(None, None).await.unwrap().is_empty());
+        assert_eq!(archive.prune().await.unwrap(), 0);
+    }
+}
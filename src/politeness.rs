@@ -0,0 +1,558 @@
+// Good-citizen behaviour for outbound HTTP
+//
+// Every request to MCFCD and the third-party APIs (NWS, AZMET, Nominatim, USGS) goes
+// through one shared Politeness, which:
+// - identifies the service with a User-Agent carrying the project URL and, when
+//   HTTP_CONTACT_EMAIL is set, a contact address (also sent as the From header)
+// - honours robots.txt (RESPECT_ROBOTS_TXT, default true): each host's rules are fetched
+//   at most once a day and disallowed URLs are never requested
+// - spaces requests to the same host by HTTP_HOST_DELAY_MS, per-host HTTP_HOST_DELAYS
+//   overrides, or the host's robots.txt Crawl-delay, whichever is longest. The spacing is
+//   shared by every client, scheduler, and worker in the process.
+// - stops requesting a host for a cooldown after repeated network errors or 5xx, 429, or
+//   408 responses (see `crate::breaker`)
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use reqwest::header::{HeaderValue, FROM, USER_AGENT};
+use reqwest::{Client, RequestBuilder, Response, StatusCode, Url};
+use thiserror::Error;
+use tokio::time::Instant;
+use tracing::{debug, info, warn};
+
+use crate::breaker::{BreakerConfig, CircuitBreakers};
+use crate::fetch_error::is_transient_status;
+
+/// Product token matched against robots.txt User-agent lines
+pub const ROBOTS_AGENT: &str = "rain-tracker-service";
+
+/// User-Agent sent when HTTP_USER_AGENT is unset (the contact address is appended)
+pub const DEFAULT_USER_AGENT: &str = concat!(
+    "rain-tracker-service/",
+    env!("CARGO_PKG_VERSION"),
+    " (+https://github.com/tdrozdowski/rain-tracker-service)"
+);
+
+/// How long a host's robots.txt is trusted before it is fetched again
+const ROBOTS_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// How long to wait before retrying a robots.txt that could not be fetched
+const ROBOTS_RETRY: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Error)]
+pub enum PoliteError {
+    #[error(transparent)]
+    Request(#[from] reqwest::Error),
+
+    #[error("{0} is disallowed by robots.txt")]
+    Disallowed(String),
+
+    #[error("Requests to {0} are paused after repeated failures")]
+    CircuitOpen(String),
+}
+
+/// Outbound HTTP identity, robots.txt, and spacing settings
+#[derive(Debug, Clone, Default)]
+pub struct PolitenessConfig {
+    /// HTTP_USER_AGENT; replaces DEFAULT_USER_AGENT
+    pub user_agent: Option<String>,
+    /// HTTP_CONTACT_EMAIL
+    pub contact_email: Option<String>,
+    /// RESPECT_ROBOTS_TXT
+    pub respect_robots: bool,
+    /// HTTP_HOST_DELAY_MS: minimum time between requests to one host
+    pub host_delay: Duration,
+    /// HTTP_HOST_DELAYS: `host=ms` overrides of `host_delay`, keyed by lowercase host
+    pub host_delays: HashMap<String, Duration>,
+    /// FETCH_BREAKER_THRESHOLD, FETCH_BREAKER_COOLDOWN_SECS, FETCH_BREAKER_MAX_COOLDOWN_SECS
+    pub breaker: BreakerConfig,
+    /// Descriptions of settings that could not be parsed
+    pub invalid: Vec<String>,
+}
+
+impl PolitenessConfig {
+    /// Build from the raw environment values; `host_delays` is comma-separated `host=ms`
+    pub fn parse(
+        user_agent: Option<&str>,
+        contact_email: Option<&str>,
+        respect_robots: bool,
+        host_delay_ms: u64,
+        host_delays: Option<&str>,
+    ) -> Self {
+        let non_empty =
+            |v: Option<&str>| v.map(str::trim).filter(|v| !v.is_empty()).map(String::from);
+        let mut config = Self {
+            user_agent: non_empty(user_agent),
+            contact_email: non_empty(contact_email),
+            respect_robots,
+            host_delay: Duration::from_millis(host_delay_ms),
+            ..Self::default()
+        };
+
+        for entry in host_delays.unwrap_or_default().split(',').map(str::trim) {
+            if entry.is_empty() {
+                continue;
+            }
+            let parsed = entry
+                .split_once('=')
+                .and_then(|(host, ms)| Some((host.trim(), ms.trim().parse::<u64>().ok()?)))
+                .filter(|(host, _)| !host.is_empty());
+            match parsed {
+                Some((host, ms)) => {
+                    config
+                        .host_delays
+                        .insert(host.to_ascii_lowercase(), Duration::from_millis(ms));
+                }
+                None => config.invalid.push(format!(
+                    "HTTP_HOST_DELAYS entries must be host=milliseconds, got {entry:?}"
+                )),
+            }
+        }
+
+        if let Some(email) = &config.contact_email {
+            if !email.contains('@') || email.contains(char::is_whitespace) {
+                config.invalid.push(format!(
+                    "HTTP_CONTACT_EMAIL must be an email address, got {email:?}"
+                ));
+            }
+        }
+        config
+    }
+
+    /// The User-Agent header sent with every request
+    pub fn user_agent(&self) -> String {
+        match (&self.user_agent, &self.contact_email) {
+            (Some(agent), _) => agent.clone(),
+            (None, Some(email)) => {
+                format!("{}; {email})", DEFAULT_USER_AGENT.trim_end_matches(')'))
+            }
+            (None, None) => DEFAULT_USER_AGENT.to_string(),
+        }
+    }
+
+    /// Minimum spacing for `host` before any robots.txt Crawl-delay
+    pub fn delay_for(&self, host: &str) -> Duration {
+        self.host_delays
+            .get(&host.to_ascii_lowercase())
+            .copied()
+            .unwrap_or(self.host_delay)
+    }
+}
+
+/// Rules from one robots.txt that apply to this service
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RobotsRules {
+    /// `(allow, pattern)` in file order
+    rules: Vec<(bool, String)>,
+    pub crawl_delay: Option<Duration>,
+}
+
+impl RobotsRules {
+    /// Parse `body`, keeping the groups naming `agent`, or the `*` groups when none do
+    pub fn parse(body: &str, agent: &str) -> Self {
+        let agent = agent.to_ascii_lowercase();
+        let mut specific = Self::default();
+        let mut wildcard = Self::default();
+        let mut matched_specific = false;
+        // Agents of the group being read, and whether its rules have started
+        let mut group_agents: Vec<String> = Vec::new();
+        let mut in_rules = false;
+
+        for line in body.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let key = key.trim().to_ascii_lowercase();
+            let value = value.trim();
+
+            if key == "user-agent" {
+                if in_rules {
+                    group_agents.clear();
+                    in_rules = false;
+                }
+                group_agents.push(value.to_ascii_lowercase());
+                continue;
+            }
+            in_rules = true;
+
+            let is_specific = group_agents.contains(&agent);
+            let is_wildcard = group_agents.iter().any(|a| a == "*");
+            let target = if is_specific {
+                matched_specific = true;
+                &mut specific
+            } else if is_wildcard {
+                &mut wildcard
+            } else {
+                continue;
+            };
+
+            match key.as_str() {
+                "allow" | "disallow" if !value.is_empty() => {
+                    target.rules.push((key == "allow", value.to_string()));
+                }
+                "crawl-delay" => {
+                    target.crawl_delay = value
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|secs| secs.is_finite() && *secs >= 0.0)
+                        .map(Duration::from_secs_f64);
+                }
+                _ => {}
+            }
+        }
+
+        if matched_specific {
+            specific
+        } else {
+            wildcard
+        }
+    }
+
+    /// Whether `path` (with any query string) may be fetched: the longest matching rule
+    /// wins, Allow winning ties, and anything unmatched is allowed
+    pub fn allows(&self, path: &str) -> bool {
+        self.rules
+            .iter()
+            .filter(|(_, pattern)| pattern_matches(pattern, path))
+            .max_by_key(|(allow, pattern)| (pattern.len(), *allow))
+            .is_none_or(|(allow, _)| *allow)
+    }
+}
+
+/// robots.txt path matching: `*` matches any run of characters, a trailing `$` anchors
+/// the end, and otherwise the pattern is a prefix
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = path.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return !anchored || rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    if anchored {
+        rest.ends_with(last)
+    } else {
+        rest.contains(last)
+    }
+}
+
+struct CachedRobots {
+    fetched_at: Instant,
+    ttl: Duration,
+    rules: Arc<RobotsRules>,
+}
+
+struct Inner {
+    config: PolitenessConfig,
+    user_agent: HeaderValue,
+    from: Option<HeaderValue>,
+    robots_client: Client,
+    /// Keyed by origin (`https://host:port`)
+    robots: tokio::sync::Mutex<HashMap<String, CachedRobots>>,
+    /// Earliest time the next request to each host may start
+    next_slot: Mutex<HashMap<String, Instant>>,
+    breakers: CircuitBreakers,
+}
+
+/// Shared handle, cloned into each outbound client
+#[derive(Clone)]
+pub struct Politeness {
+    inner: Arc<Inner>,
+}
+
+impl fmt::Debug for Politeness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Politeness")
+            .field("config", &self.inner.config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for Politeness {
+    /// The default User-Agent with no robots.txt checks, spacing, or circuit breaking
+    fn default() -> Self {
+        Self::new(&PolitenessConfig::default())
+    }
+}
+
+impl Politeness {
+    pub fn new(config: &PolitenessConfig) -> Self {
+        let user_agent = config.user_agent();
+        let user_agent = HeaderValue::from_str(&user_agent)
+            .unwrap_or_else(|_| HeaderValue::from_static(DEFAULT_USER_AGENT));
+        let from = config
+            .contact_email
+            .as_deref()
+            .and_then(|email| HeaderValue::from_str(email).ok());
+        Self {
+            inner: Arc::new(Inner {
+                config: config.clone(),
+                robots_client: Client::builder()
+                    .timeout(Duration::from_secs(10))
+                    .user_agent(user_agent.clone())
+                    .build()
+                    .expect("Failed to create HTTP client"),
+                user_agent,
+                from,
+                robots: tokio::sync::Mutex::default(),
+                next_slot: Mutex::default(),
+                breakers: CircuitBreakers::new(config.breaker),
+            }),
+        }
+    }
+
+    /// The per-host breakers, for health and metrics reporting
+    pub fn breakers(&self) -> CircuitBreakers {
+        self.inner.breakers.clone()
+    }
+
+    /// Send `request` once robots.txt allows it and the host's delay has passed, with the
+    /// configured User-Agent and From headers; fails without sending while the host's
+    /// breaker is open
+    pub async fn send(
+        &self,
+        client: &Client,
+        request: RequestBuilder,
+    ) -> Result<Response, PoliteError> {
+        let mut request = request.build()?;
+        let url = request.url().clone();
+
+        let mut delay = url
+            .host_str()
+            .map(|host| self.inner.config.delay_for(host))
+            .unwrap_or_default();
+        if self.inner.config.respect_robots {
+            let rules = self.robots(&url).await;
+            if !rules.allows(&path_and_query(&url)) {
+                warn!(url = %url, "Skipping request disallowed by robots.txt");
+                return Err(PoliteError::Disallowed(url.to_string()));
+            }
+            delay = delay.max(rules.crawl_delay.unwrap_or_default());
+        }
+        let host = url.host_str().unwrap_or_default().to_string();
+        if !self.inner.breakers.allow(&host) {
+            debug!(url = %url, "Skipping request while the host's breaker is open");
+            return Err(PoliteError::CircuitOpen(host));
+        }
+        self.wait_turn(&host, delay).await;
+
+        let headers = request.headers_mut();
+        headers.insert(USER_AGENT, self.inner.user_agent.clone());
+        if let Some(from) = &self.inner.from {
+            headers.insert(FROM, from.clone());
+        }
+        let result = client.execute(request).await;
+        match &result {
+            Ok(response) if !is_transient_status(response.status()) => {
+                self.inner.breakers.record_success(&host)
+            }
+            // A request that could not be built says nothing about the host
+            Err(e) if e.is_builder() => {}
+            _ => self.inner.breakers.record_failure(&host),
+        }
+        Ok(result?)
+    }
+
+    /// Wait until `host` may be requested again, reserving the slot after it
+    async fn wait_turn(&self, host: &str, delay: Duration) {
+        if delay.is_zero() || host.is_empty() {
+            return;
+        }
+        let wait = {
+            let mut slots = self.inner.next_slot.lock().unwrap();
+            let now = Instant::now();
+            let slot = slots.entry(host.to_ascii_lowercase()).or_insert(now);
+            let start = (*slot).max(now);
+            *slot = start + delay;
+            start - now
+        };
+        if !wait.is_zero() {
+            debug!(
+                host,
+                wait_ms = wait.as_millis() as u64,
+                "Waiting for host delay"
+            );
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// The cached rules for `url`'s origin, fetching robots.txt when stale
+    async fn robots(&self, url: &Url) -> Arc<RobotsRules> {
+        let origin = url.origin().ascii_serialization();
+        let mut cache = self.inner.robots.lock().await;
+        if let Some(cached) = cache.get(&origin) {
+            if cached.fetched_at.elapsed() < cached.ttl {
+                return cached.rules.clone();
+            }
+        }
+
+        let (rules, ttl) = self.fetch_robots(&origin).await;
+        let rules = Arc::new(rules);
+        cache.insert(
+            origin,
+            CachedRobots {
+                fetched_at: Instant::now(),
+                ttl,
+                rules: rules.clone(),
+            },
+        );
+        rules
+    }
+
+    /// A missing robots.txt (4xx) allows everything; so does one that can't be fetched,
+    /// which is retried sooner rather than blocking the scrapers on the host's outage
+    async fn fetch_robots(&self, origin: &str) -> (RobotsRules, Duration) {
+        let url = format!("{origin}/robots.txt");
+        let response = self.inner.robots_client.get(&url).send().await;
+        match response {
+            Ok(response) if response.status().is_success() => match response.text().await {
+                Ok(body) => {
+                    info!(url = %url, "Loaded robots.txt");
+                    (RobotsRules::parse(&body, ROBOTS_AGENT), ROBOTS_TTL)
+                }
+                Err(e) => {
+                    warn!(url = %url, error = %e, "Failed to read robots.txt");
+                    (RobotsRules::default(), ROBOTS_RETRY)
+                }
+            },
+            Ok(response) if response.status().is_client_error() => {
+                debug!(url = %url, status = %response.status(), "No robots.txt");
+                (RobotsRules::default(), ROBOTS_TTL)
+            }
+            Ok(response) => {
+                let status: StatusCode = response.status();
+                warn!(url = %url, status = %status, "robots.txt unavailable");
+                (RobotsRules::default(), ROBOTS_RETRY)
+            }
+            Err(e) => {
+                warn!(url = %url, error = %e, "robots.txt unavailable");
+                (RobotsRules::default(), ROBOTS_RETRY)
+            }
+        }
+    }
+}
+
+fn path_and_query(url: &Url) -> String {
+    match url.query() {
+        Some(query) => format!("{}?{query}", url.path()),
+        None => url.path().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTS: &str = "\
+# MCFCD
+User-agent: *
+Disallow: /private/
+Allow: /private/public.html
+Disallow: /*.pdf$
+Crawl-delay: 2
+
+User-agent: BadBot
+User-agent: OtherBot
+Disallow: /
+";
+
+    #[test]
+    fn test_robots_rules_wildcard_group() {
+        let rules = RobotsRules::parse(ROBOTS, ROBOTS_AGENT);
+        assert!(rules.allows("/alert/Rain/FOPR/59700_FOPR.xlsx"));
+        assert!(!rules.allows("/private/data.txt"));
+        assert!(rules.allows("/private/public.html"));
+        assert!(!rules.allows("/alert/Rain/pcp1119.pdf"));
+        assert!(rules.allows("/alert/Rain/pcp1119.pdf?download=1"));
+        assert_eq!(rules.crawl_delay, Some(Duration::from_secs(2)));
+
+        let bad = RobotsRules::parse(ROBOTS, "badbot");
+        assert!(!bad.allows("/alert/Rain/FOPR/59700_FOPR.xlsx"));
+        assert_eq!(bad.crawl_delay, None);
+    }
+
+    #[test]
+    fn test_robots_rules_specific_group_wins() {
+        let body =
+            "User-agent: *\nDisallow: /\n\nUser-agent: Rain-Tracker-Service\nDisallow: /tmp\n";
+        let rules = RobotsRules::parse(body, ROBOTS_AGENT);
+        assert!(rules.allows("/alert/Rain/"));
+        assert!(!rules.allows("/tmp/x"));
+
+        // An empty Disallow allows everything
+        let open = RobotsRules::parse("User-agent: *\nDisallow:\n", ROBOTS_AGENT);
+        assert!(open.allows("/anything"));
+    }
+
+    #[test]
+    fn test_pattern_matches() {
+        assert!(pattern_matches("/a", "/abc"));
+        assert!(!pattern_matches("/a$", "/abc"));
+        assert!(pattern_matches("/a*c", "/abbbc/d"));
+        assert!(pattern_matches("/*.xlsx$", "/FOPR/1_FOPR.xlsx"));
+        assert!(!pattern_matches("/*.xlsx$", "/FOPR/1_FOPR.xlsx.bak"));
+        assert!(pattern_matches("*", "/"));
+    }
+
+    #[test]
+    fn test_config_parse() {
+        let config = PolitenessConfig::parse(
+            None,
+            Some("ops@example.com"),
+            true,
+            500,
+            Some("alert.fcd.maricopa.gov=2000, api.weather.gov=0"),
+        );
+        assert!(config.invalid.is_empty(), "{:?}", config.invalid);
+        assert_eq!(
+            config.user_agent(),
+            format!(
+                "rain-tracker-service/{} (+https://github.com/tdrozdowski/rain-tracker-service; ops@example.com)",
+                env!("CARGO_PKG_VERSION")
+            )
+        );
+        assert_eq!(
+            config.delay_for("ALERT.fcd.maricopa.gov"),
+            Duration::from_secs(2)
+        );
+        assert_eq!(config.delay_for("api.weather.gov"), Duration::ZERO);
+        assert_eq!(config.delay_for("example.com"), Duration::from_millis(500));
+
+        let config =
+            PolitenessConfig::parse(Some("custom/1.0"), Some("nope"), true, 0, Some("a.com"));
+        assert_eq!(config.user_agent(), "custom/1.0");
+        assert_eq!(config.invalid.len(), 2, "{:?}", config.invalid);
+    }
+
+    #[tokio::test]
+    async fn test_wait_turn_spaces_requests() {
+        let politeness = Politeness::default();
+        let delay = Duration::from_millis(50);
+        let start = Instant::now();
+        for _ in 0..3 {
+            politeness.wait_turn("alert.fcd.maricopa.gov", delay).await;
+        }
+        assert!(start.elapsed() >= delay * 2);
+
+        // Other hosts have their own slots
+        let other = Instant::now();
+        politeness.wait_turn("api.weather.gov", delay).await;
+        assert!(other.elapsed() < delay);
+    }
+}
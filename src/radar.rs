@@ -0,0 +1,205 @@
+// MRMS radar-estimated rainfall at gauge locations
+//
+// NOAA's Multi-Radar Multi-Sensor (MRMS) system publishes quantitative precipitation
+// estimates (QPE) as GRIB2 grids of millimeters at 0.01° resolution. `radar import` reads
+// QPE grids converted to ESRI ASCII (`gdal_translate -of AAIGrid`) that together cover a
+// storm window, e.g. consecutive hourly MultiSensor_QPE_01H_Pass2 grids or one 24-hour
+// accumulation, and sums the cells containing each gauge. Comparing the sum with the
+// gauge's own readings over the window flags gauges that under-catch (clogged funnels,
+// wind loss) or report rain radar did not see.
+//
+// MRMS grids use 0–360° longitudes and negative values for missing data; both are handled
+// when sampling.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::grid::AsciiGrid;
+
+/// Product recorded when `radar import` is not told which one the grids are
+pub const DEFAULT_RADAR_PRODUCT: &str = "MultiSensor_QPE_01H_Pass2";
+
+/// Gauge-to-radar ratio below which a gauge is flagged as under-catching
+pub const UNDER_CATCH_RATIO: f64 = 0.5;
+
+/// Gauge-to-radar ratio above which a gauge is flagged as over-reporting
+pub const OVER_CATCH_RATIO: f64 = 2.0;
+
+/// Rainfall below which neither source is compared; light totals are mostly noise
+pub const MIN_COMPARABLE_INCHES: f64 = 0.1;
+
+const MM_PER_INCH: f64 = 25.4;
+
+#[derive(Debug, thiserror::Error)]
+pub enum RadarError {
+    #[error("Failed to read QPE grid {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Invalid QPE grid {}: {message}", path.display())]
+    Grid { path: PathBuf, message: String },
+
+    #[error("At least one QPE grid is required")]
+    NoGrids,
+}
+
+/// QPE grids in millimeters covering one storm window
+#[derive(Debug, Clone)]
+pub struct RadarQpe {
+    grids: Vec<AsciiGrid>,
+}
+
+impl RadarQpe {
+    pub fn load(paths: &[PathBuf]) -> Result<Self, RadarError> {
+        let grids = paths
+            .iter()
+            .map(|path| load_grid(path))
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::new(grids)
+    }
+
+    pub fn new(grids: Vec<AsciiGrid>) -> Result<Self, RadarError> {
+        if grids.is_empty() {
+            return Err(RadarError::NoGrids);
+        }
+        Ok(Self { grids })
+    }
+
+    /// File names of the grids, recorded as the estimate source
+    pub fn source(&self) -> String {
+        self.grids
+            .iter()
+            .map(AsciiGrid::name)
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    /// Radar rainfall in inches at a point, summed over every grid; None when any grid
+    /// lacks a valid value there
+    pub fn sample_inches(&self, latitude: f64, longitude: f64) -> Option<f64> {
+        let mut millimeters = 0.0;
+        for grid in &self.grids {
+            let value = grid
+                .sample(latitude, longitude)
+                .or_else(|| grid.sample(latitude, longitude + 360.0))
+                .filter(|mm| *mm >= 0.0)?;
+            millimeters += value;
+        }
+        Some(millimeters / MM_PER_INCH)
+    }
+}
+
+fn load_grid(path: &Path) -> Result<AsciiGrid, RadarError> {
+    let text = std::fs::read_to_string(path).map_err(|source| RadarError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    AsciiGrid::parse(name, &text).map_err(|message| RadarError::Grid {
+        path: path.to_path_buf(),
+        message,
+    })
+}
+
+/// How a gauge's storm total compares with the radar estimate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RadarAgreement {
+    /// Gauge total within UNDER_CATCH_RATIO–OVER_CATCH_RATIO of radar
+    Agrees,
+    /// Gauge caught less than half of what radar estimated
+    UnderCatch,
+    /// Gauge reported more than twice what radar estimated
+    OverCatch,
+    /// Radar saw rain but the gauge reported nothing for the window
+    NoReadings,
+    /// Both totals are below MIN_COMPARABLE_INCHES
+    TooLight,
+}
+
+impl RadarAgreement {
+    pub fn assess(gauge_inches: f64, radar_inches: f64, reading_count: i64) -> Self {
+        if gauge_inches < MIN_COMPARABLE_INCHES && radar_inches < MIN_COMPARABLE_INCHES {
+            return Self::TooLight;
+        }
+        if reading_count == 0 {
+            return Self::NoReadings;
+        }
+        // Radar near zero with a wet gauge has no meaningful ratio
+        if radar_inches < MIN_COMPARABLE_INCHES {
+            return Self::OverCatch;
+        }
+        match gauge_inches / radar_inches {
+            ratio if ratio < UNDER_CATCH_RATIO => Self::UnderCatch,
+            ratio if ratio > OVER_CATCH_RATIO => Self::OverCatch,
+            _ => Self::Agrees,
+        }
+    }
+
+    /// Whether the gauge deserves a look from field staff
+    pub fn is_suspect(&self) -> bool {
+        matches!(self, Self::UnderCatch | Self::OverCatch | Self::NoReadings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 25.4 mm in the west cell, MRMS "no coverage" in the east cell, 0–360° longitudes
+    const HOUR_1: &str = "ncols 2
+nrows 1
+xllcorner 247.8
+yllcorner 33.4
+cellsize 0.1
+NODATA_value -999
+25.4 -3
+";
+
+    #[test]
+    fn test_sample_inches_sums_grids() {
+        let hour_1 = AsciiGrid::parse("hour_1.asc", HOUR_1).unwrap();
+        let hour_2 = AsciiGrid::parse("hour_2.asc", &HOUR_1.replace("25.4", "12.7")).unwrap();
+        let qpe = RadarQpe::new(vec![hour_1, hour_2]).unwrap();
+
+        assert_eq!(qpe.source(), "hour_1.asc,hour_2.asc");
+        let inches = qpe.sample_inches(33.45, -112.15).unwrap();
+        assert!((inches - 1.5).abs() < 1e-9, "{inches}");
+        assert_eq!(qpe.sample_inches(33.45, -112.05), None, "missing data");
+        assert_eq!(qpe.sample_inches(33.55, -112.15), None, "outside grid");
+    }
+
+    #[test]
+    fn test_radar_agreement() {
+        assert_eq!(RadarAgreement::assess(1.0, 1.2, 12), RadarAgreement::Agrees);
+        assert_eq!(
+            RadarAgreement::assess(0.3, 1.2, 12),
+            RadarAgreement::UnderCatch
+        );
+        assert_eq!(
+            RadarAgreement::assess(2.6, 1.2, 12),
+            RadarAgreement::OverCatch
+        );
+        assert_eq!(
+            RadarAgreement::assess(0.4, 0.0, 3),
+            RadarAgreement::OverCatch
+        );
+        assert_eq!(
+            RadarAgreement::assess(0.0, 1.2, 0),
+            RadarAgreement::NoReadings
+        );
+        assert_eq!(
+            RadarAgreement::assess(0.04, 0.05, 2),
+            RadarAgreement::TooLight
+        );
+        assert!(!RadarAgreement::TooLight.is_suspect());
+    }
+}
@@ -0,0 +1,192 @@
+// Startup readiness for /api/v1/health/ready
+//
+// The HTTP server starts before startup work has finished, so load balancers need a
+// signal separate from liveness (/api/v1/health). Application::build records each startup
+// check here, and the service reports ready once every check has passed:
+// - config: Config::validate found no problems
+// - migrations: the database schema matches this build
+// - gauge_cache: current conditions were rebuilt, so the dashboard has data
+//
+// Until then /health/ready answers 503 `not_ready` with one `errors` entry per check
+// that has not passed.
+
+use std::sync::{Arc, RwLock};
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// A startup check that gates readiness
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadinessCheck {
+    Config,
+    Migrations,
+    GaugeCache,
+}
+
+impl ReadinessCheck {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReadinessCheck::Config => "config",
+            ReadinessCheck::Migrations => "migrations",
+            ReadinessCheck::GaugeCache => "gauge_cache",
+        }
+    }
+
+    pub const ALL: [ReadinessCheck; 3] = [
+        ReadinessCheck::Config,
+        ReadinessCheck::Migrations,
+        ReadinessCheck::GaugeCache,
+    ];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckState {
+    Pending,
+    Passed,
+    /// Failed on the last attempt; startup checks are retried unless noted in `message`
+    Failed,
+}
+
+impl CheckState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CheckState::Pending => "pending",
+            CheckState::Passed => "passed",
+            CheckState::Failed => "failed",
+        }
+    }
+}
+
+/// State of one check in the readiness response
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct CheckStatus {
+    pub check: ReadinessCheck,
+    pub state: CheckState,
+    /// Why the check failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Body of GET /api/v1/health/ready
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct ReadinessReport {
+    pub ready: bool,
+    pub checks: Vec<CheckStatus>,
+}
+
+/// Shared readiness state, cloned into the API state and the startup task
+#[derive(Debug, Clone)]
+pub struct Readiness {
+    checks: Arc<RwLock<Vec<CheckStatus>>>,
+}
+
+impl Readiness {
+    /// Every check pending
+    pub fn new() -> Self {
+        Self::with_state(CheckState::Pending)
+    }
+
+    /// Every check passed, for routers built without a startup sequence (tests, tools)
+    pub fn ready() -> Self {
+        Self::with_state(CheckState::Passed)
+    }
+
+    fn with_state(state: CheckState) -> Self {
+        let checks = ReadinessCheck::ALL
+            .iter()
+            .map(|&check| CheckStatus {
+                check,
+                state,
+                message: None,
+            })
+            .collect();
+        Self {
+            checks: Arc::new(RwLock::new(checks)),
+        }
+    }
+
+    pub fn pass(&self, check: ReadinessCheck) {
+        self.set(check, CheckState::Passed, None);
+    }
+
+    pub fn fail(&self, check: ReadinessCheck, message: impl Into<String>) {
+        self.set(check, CheckState::Failed, Some(message.into()));
+    }
+
+    pub fn is_passed(&self, check: ReadinessCheck) -> bool {
+        self.read()
+            .iter()
+            .any(|c| c.check == check && c.state == CheckState::Passed)
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.read().iter().all(|c| c.state == CheckState::Passed)
+    }
+
+    pub fn report(&self) -> ReadinessReport {
+        let checks = self.read().clone();
+        ReadinessReport {
+            ready: checks.iter().all(|c| c.state == CheckState::Passed),
+            checks,
+        }
+    }
+
+    fn set(&self, check: ReadinessCheck, state: CheckState, message: Option<String>) {
+        let mut checks = self.checks.write().unwrap_or_else(|e| e.into_inner());
+        if let Some(status) = checks.iter_mut().find(|c| c.check == check) {
+            status.state = state;
+            status.message = message;
+        }
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, Vec<CheckStatus>> {
+        self.checks.read().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for Readiness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ready_only_after_every_check_passes() {
+        let readiness = Readiness::new();
+        assert!(!readiness.is_ready());
+
+        readiness.pass(ReadinessCheck::Config);
+        readiness.pass(ReadinessCheck::Migrations);
+        assert!(!readiness.is_ready());
+
+        readiness.fail(ReadinessCheck::GaugeCache, "connection refused");
+        let report = readiness.report();
+        assert!(!report.ready);
+        assert_eq!(report.checks[2].state, CheckState::Failed);
+        assert_eq!(
+            report.checks[2].message.as_deref(),
+            Some("connection refused")
+        );
+
+        readiness.pass(ReadinessCheck::GaugeCache);
+        assert!(readiness.is_ready());
+        assert_eq!(readiness.report().checks[2].message, None);
+    }
+
+    #[test]
+    fn test_clones_share_state() {
+        let readiness = Readiness::new();
+        let startup = readiness.clone();
+        for check in ReadinessCheck::ALL {
+            startup.pass(check);
+        }
+        assert!(readiness.is_ready());
+        assert!(Readiness::ready().is_ready());
+    }
+}
@@ -1,19 +1,52 @@
-use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use chrono::{DateTime, Datelike, DurationRound, NaiveDate, TimeDelta, Utc};
+use std::collections::BTreeSet;
 use std::time::Duration;
 use tokio::time;
 use tracing::{debug, error, info, instrument, warn};
 
-use crate::db::{MonthlyRainfallRepository, ReadingRepository};
-use crate::fetcher::RainGaugeFetcher;
+use crate::anomaly::AnomalyRules;
+use crate::clock::SharedClock;
+use crate::db::{
+    DbPool, HourlyRainfallRepository, JobRunKind, MonthlyRainfallRepository, QuarantineRepository,
+    ReadingRepository,
+};
+use crate::fetch_error::retry_transient;
+use crate::fetcher::{RainGaugeFetcher, LIVE_DATA_SOURCE, LIVE_STATION_ID};
+use crate::gauge_list_drift::{DriftConfig, DriftMonitor, DriftVerdict};
 use crate::gauge_list_fetcher::GaugeListFetcher;
+use crate::job_windows::{JobKind, JobWindows};
+use crate::leader::Leadership;
+use crate::metrics::Metrics;
+use crate::payload_archive::PayloadArchive;
 use crate::services::gauge_service::GaugeService;
+use crate::services::job_run_service::JobRunTimer;
+use crate::services::{
+    AnalyticsExportService, AnomalyService, CurrentConditionsService, DigestService,
+    ElevationService, GeocodeService, JobRunService, SummaryViewService, ThresholdService,
+    WeatherService, ZoneService,
+};
+use crate::weather::WeatherSource;
 
-#[instrument(skip(fetcher, reading_repo, monthly_repo), fields(interval_minutes = %interval_minutes))]
+/// Attempts per scheduled scrape while it fails with a transient error
+const FETCH_ATTEMPTS: u32 = 3;
+
+/// Wait before the first retry of a scrape, doubled before the next
+const FETCH_RETRY_DELAY: Duration = Duration::from_secs(15);
+
+#[allow(clippy::too_many_arguments)]
+#[instrument(skip(fetcher, reading_repo, quarantine_repo, monthly_repo, hourly_repo, current_conditions_service, job_runs, clock, gate), fields(interval_minutes = %interval_minutes))]
 pub async fn start_fetch_scheduler(
     fetcher: RainGaugeFetcher,
     reading_repo: ReadingRepository,
+    quarantine_repo: QuarantineRepository,
     monthly_repo: MonthlyRainfallRepository,
+    hourly_repo: HourlyRainfallRepository,
+    current_conditions_service: CurrentConditionsService,
+    job_runs: JobRunService,
+    clock: SharedClock,
     interval_minutes: u64,
+    late_data_window_hours: u32,
+    gate: JobGate,
 ) {
     let mut interval = time::interval(Duration::from_secs(interval_minutes * 60));
 
@@ -23,86 +56,155 @@ pub async fn start_fetch_scheduler(
     );
 
     loop {
-        interval.tick().await;
+        next_tick(&mut interval, &gate, JobKind::Download).await;
         debug!("Scheduler tick - initiating fetch");
 
-        match fetch_and_store(&fetcher, &reading_repo, &monthly_repo).await {
-            Ok(inserted) => {
-                if inserted > 0 {
-                    info!("Successfully fetched and stored {} new readings", inserted);
+        let timer = JobRunTimer::start(JobRunKind::Fetch);
+        let revisable_from = clock.now() - chrono::Duration::hours(late_data_window_hours as i64);
+        let result = fetch_and_store(
+            &fetcher,
+            &reading_repo,
+            &quarantine_repo,
+            &monthly_repo,
+            &hourly_repo,
+            revisable_from,
+        )
+        .await
+        .map_err(|e| e.to_string());
+        job_runs
+            .record(timer.finish(result.clone().map(|n| n as i64)))
+            .await;
+        let written = match result {
+            Ok(written) => {
+                if written > 0 {
+                    info!("Successfully fetched and stored {} readings", written);
                 } else {
-                    debug!("No new readings to store (all duplicates)");
+                    debug!("No new or revised readings to store");
                 }
+                written
             }
             Err(e) => {
                 error!("Failed to fetch and store readings: {}", e);
+                0
             }
+        };
+
+        if written > 0 {
+            refresh_current_conditions(&current_conditions_service, &clock).await;
         }
     }
 }
 
-#[instrument(skip(fetcher, reading_repo, monthly_repo))]
+/// Store a scrape's readings, returning how many were inserted or revised
+///
+/// Readings at or after `revisable_from` that the gauge has corrected since they were
+/// stored are updated rather than skipped; monthly and hourly summaries are recalculated
+/// for every month and hour with an inserted or revised reading.
+#[instrument(skip(fetcher, reading_repo, quarantine_repo, monthly_repo, hourly_repo))]
 async fn fetch_and_store(
     fetcher: &RainGaugeFetcher,
     reading_repo: &ReadingRepository,
+    quarantine_repo: &QuarantineRepository,
     monthly_repo: &MonthlyRainfallRepository,
+    hourly_repo: &HourlyRainfallRepository,
+    revisable_from: DateTime<Utc>,
 ) -> Result<usize, Box<dyn std::error::Error>> {
     debug!("Fetching readings from gauge");
-    let readings = fetcher.fetch_readings().await?;
-    info!("Fetched {} readings from gauge", readings.len());
+    let fetched = retry_transient(FETCH_ATTEMPTS, FETCH_RETRY_DELAY, || {
+        fetcher.fetch_readings()
+    })
+    .await?;
+    info!("Fetched {} readings from gauge", fetched.accepted.len());
+
+    // Losing these only loses the review trail; the readings are kept out either way
+    if let Err(e) = quarantine_repo
+        .insert(LIVE_DATA_SOURCE, &fetched.quarantined)
+        .await
+    {
+        error!("Failed to store quarantined readings: {}", e);
+    }
+
+    let readings = fetched.accepted;
 
     if readings.is_empty() {
         warn!("No readings returned from gauge");
         return Ok(0);
     }
 
-    debug!("Inserting readings into database");
-    let inserted = reading_repo.insert_readings(&readings).await?;
+    debug!("Upserting readings into database");
+    let written = reading_repo
+        .upsert_readings(&readings, revisable_from)
+        .await?;
+    if written.updated > 0 {
+        info!(
+            revised = written.updated,
+            "Revised readings the gauge corrected after they were stored"
+        );
+    }
 
-    if inserted > 0 {
-        // Update monthly aggregates for affected months
-        // Group readings by month and recalculate
-        use std::collections::HashMap;
-        let mut months_to_update: HashMap<(i32, i32), ()> = HashMap::new();
+    // Update monthly aggregates for months with new or revised readings
+    let months_to_update: BTreeSet<(i32, u32)> = written
+        .changed
+        .iter()
+        .map(|datetime| (datetime.year(), datetime.month()))
+        .collect();
+    debug!(
+        "Updating {} affected monthly summaries",
+        months_to_update.len()
+    );
+    for (year, month) in months_to_update {
+        // Calculate month boundaries for recalculation
+        let (start, end) = month_date_range(year, month);
 
-        for reading in &readings {
-            let year = reading.reading_datetime.year();
-            let month = reading.reading_datetime.month() as i32;
-            months_to_update.insert((year, month), ());
+        if let Err(e) = monthly_repo
+            .recalculate_monthly_summary(LIVE_STATION_ID, year, month as i32, start, end)
+            .await
+        {
+            error!(
+                "Failed to update monthly summary for {}-{:02}: {}",
+                year, month, e
+            );
         }
+    }
 
-        debug!(
-            "Updating {} affected monthly summaries",
-            months_to_update.len()
-        );
-        for ((year, month), _) in months_to_update {
-            // Calculate month boundaries for recalculation
-            let (start, end) = month_date_range(year, month as u32);
-
-            // Use the default station_id (59700) since RainGaugeFetcher doesn't expose station_id
-            // TODO: Make station_id configurable in fetcher
-            if let Err(e) = monthly_repo
-                .recalculate_monthly_summary("59700", year, month, start, end)
-                .await
-            {
-                error!(
-                    "Failed to update monthly summary for {}-{:02}: {}",
-                    year, month, e
-                );
-            }
+    // Hours between the first and last change are rebuilt too; unchanged ones come out the same
+    if let (Some(first), Some(last)) = (written.changed.first(), written.changed.last()) {
+        let hour = TimeDelta::hours(1);
+        let start = first.duration_trunc(hour)?;
+        let end = last.duration_trunc(hour)? + hour;
+        if let Err(e) = hourly_repo
+            .recalculate_hours(Some(LIVE_STATION_ID), Some(start), Some(end))
+            .await
+        {
+            error!("Failed to update hourly summaries from {}: {}", start, e);
         }
     }
 
-    Ok(inserted)
+    Ok(written.inserted + written.updated)
 }
 
-#[instrument(skip(fetcher, gauge_service), fields(interval_minutes = %interval_minutes))]
+#[allow(clippy::too_many_arguments)]
+#[instrument(skip(fetcher, gauge_service, threshold_service, current_conditions_service, job_runs, metrics, clock, drift, gate), fields(interval_minutes = %interval_minutes))]
 pub async fn start_gauge_list_scheduler(
     fetcher: GaugeListFetcher,
     gauge_service: GaugeService,
+    threshold_service: ThresholdService,
+    current_conditions_service: CurrentConditionsService,
+    job_runs: JobRunService,
+    metrics: Metrics,
+    clock: SharedClock,
     interval_minutes: u64,
+    inactive_after_days: u32,
+    drift: DriftConfig,
+    gate: JobGate,
 ) {
     let mut interval = time::interval(Duration::from_secs(interval_minutes * 60));
+    // Recorded runs carry the baseline across restarts
+    let mut drift = DriftMonitor::new(drift).with_history(
+        job_runs
+            .recent_row_counts(JobRunKind::GaugeList, drift.window)
+            .await,
+    );
 
     info!(
         "Gauge list scheduler started with {} minute interval",
@@ -110,10 +212,24 @@ pub async fn start_gauge_list_scheduler(
     );
 
     loop {
-        interval.tick().await;
+        next_tick(&mut interval, &gate, JobKind::Download).await;
         debug!("Gauge list scheduler tick - initiating fetch");
 
-        match fetch_and_store_gauge_list(&fetcher, &gauge_service).await {
+        let timer = JobRunTimer::start(JobRunKind::GaugeList);
+        let result = fetch_and_store_gauge_list(
+            &fetcher,
+            &gauge_service,
+            &threshold_service,
+            &mut drift,
+            &metrics,
+            &clock,
+        )
+        .await
+        .map_err(|e| e.to_string());
+        job_runs
+            .record(timer.finish(result.clone().map(|n| n as i64)))
+            .await;
+        match result {
             Ok(count) => {
                 info!(
                     gauge_count = count,
@@ -125,20 +241,85 @@ pub async fn start_gauge_list_scheduler(
                     error = %e,
                     "Failed to fetch and store gauge list"
                 );
+                // Without a fresh scrape, every gauge would look missing
+                continue;
+            }
+        }
+
+        match gauge_service
+            .detect_status_changes(inactive_after_days)
+            .await
+        {
+            Ok(stats) if stats.deactivated > 0 || stats.reactivated > 0 => {
+                info!(
+                    deactivated = stats.deactivated,
+                    reactivated = stats.reactivated,
+                    "Updated gauge statuses from gauge list"
+                );
+            }
+            Ok(_) => {}
+            Err(e) => {
+                error!(
+                    error = %e,
+                    "Failed to detect gauge status changes"
+                );
             }
         }
+
+        refresh_current_conditions(&current_conditions_service, &clock).await;
     }
 }
 
-#[instrument(skip(fetcher, gauge_service))]
+/// Rebuild the current conditions table; a failure leaves the previous snapshot
+async fn refresh_current_conditions(
+    current_conditions_service: &CurrentConditionsService,
+    clock: &SharedClock,
+) {
+    if let Err(e) = current_conditions_service.refresh(clock.now()).await {
+        error!(error = %e, "Failed to refresh current conditions");
+    }
+}
+
+#[instrument(skip(fetcher, gauge_service, threshold_service, drift, metrics, clock))]
 async fn fetch_and_store_gauge_list(
     fetcher: &GaugeListFetcher,
     gauge_service: &GaugeService,
+    threshold_service: &ThresholdService,
+    drift: &mut DriftMonitor,
+    metrics: &Metrics,
+    clock: &SharedClock,
 ) -> Result<usize, Box<dyn std::error::Error>> {
     debug!("Fetching gauge list from remote source");
-    let gauges = fetcher.fetch_gauge_list().await?;
+    let gauges = retry_transient(FETCH_ATTEMPTS, FETCH_RETRY_DELAY, || {
+        fetcher.fetch_gauge_list()
+    })
+    .await?;
     info!(gauge_count = gauges.len(), "Fetched gauges from list");
 
+    // A sharp drop usually means the upstream format changed under the parser; storing
+    // the scrape would overwrite good summaries, so it is rejected instead
+    let verdict = drift.observe(gauges.len());
+    metrics.record_gauge_list_scrape(gauges.len(), drift.trailing_average(), verdict.is_suspect());
+    match verdict {
+        DriftVerdict::Suspect {
+            trailing_average,
+            consecutive,
+        } => {
+            return Err(format!(
+                "Suspect gauge list scrape ({consecutive} in a row): {} gauges against a trailing average of {trailing_average:.0}; not stored",
+                gauges.len()
+            )
+            .into());
+        }
+        DriftVerdict::Accepted { previous_average } => {
+            warn!(
+                gauge_count = gauges.len(),
+                previous_average, "Gauge count stayed low; accepting it as the new baseline"
+            );
+        }
+        DriftVerdict::Learning | DriftVerdict::Normal { .. } => {}
+    }
+
     // Handle new gauge discovery
     let mut new_jobs_created = 0;
     for gauge in &gauges {
@@ -178,9 +359,352 @@ async fn fetch_and_store_gauge_list(
         "Upserting gauge summaries into database"
     );
     let upserted = gauge_service.upsert_summaries(&gauges).await?;
+
+    // A failure here only delays events until the next scrape
+    if let Err(e) = threshold_service
+        .record_crossings(&gauges, clock.now())
+        .await
+    {
+        error!(error = %e, "Failed to record rainfall threshold crossings");
+    }
+
     Ok(upserted)
 }
 
+#[instrument(skip(gauge_service, zone_service, gate), fields(interval_minutes = %interval_minutes))]
+pub async fn start_reconciliation_scheduler(
+    gauge_service: GaugeService,
+    zone_service: ZoneService,
+    interval_minutes: u64,
+    gate: JobGate,
+) {
+    let mut interval = time::interval(Duration::from_secs(interval_minutes * 60));
+
+    info!(
+        "Gauge reconciliation scheduler started with {} minute interval",
+        interval_minutes
+    );
+
+    loop {
+        next_tick(&mut interval, &gate, JobKind::Heavy).await;
+        debug!("Reconciliation scheduler tick - comparing gauge tables");
+
+        if let Err(e) = gauge_service.reconcile().await {
+            error!(
+                error = %e,
+                "Failed to reconcile gauge summaries with gauge metadata"
+            );
+        }
+
+        // Gauges move or gain coordinates between runs; keep their zones current
+        if let Err(e) = zone_service.assign_gauges().await {
+            error!(
+                error = %e,
+                "Failed to assign gauges to forecast zones"
+            );
+        }
+    }
+}
+
+pub async fn start_geocode_scheduler(
+    geocode_service: GeocodeService,
+    interval_minutes: u64,
+    gate: JobGate,
+) {
+    let mut interval = time::interval(Duration::from_secs(interval_minutes * 60));
+
+    info!(
+        "Geocoding scheduler started with {} minute interval",
+        interval_minutes
+    );
+
+    loop {
+        next_tick(&mut interval, &gate, JobKind::Light).await;
+        debug!("Geocoding scheduler tick - enriching gauges without a city");
+
+        if let Err(e) = geocode_service.enrich().await {
+            error!(
+                error = %e,
+                "Failed to reverse geocode gauges"
+            );
+        }
+    }
+}
+
+pub async fn start_elevation_scheduler(
+    elevation_service: ElevationService,
+    interval_minutes: u64,
+    gate: JobGate,
+) {
+    let mut interval = time::interval(Duration::from_secs(interval_minutes * 60));
+
+    info!(
+        "Elevation scheduler started with {} minute interval",
+        interval_minutes
+    );
+
+    loop {
+        next_tick(&mut interval, &gate, JobKind::Light).await;
+        debug!("Elevation scheduler tick - sampling gauges without elevation");
+
+        if let Err(e) = elevation_service.enrich().await {
+            error!(
+                error = %e,
+                "Failed to sample gauge elevations"
+            );
+        }
+    }
+}
+
+pub async fn start_weather_scheduler(
+    weather_service: WeatherService,
+    source: WeatherSource,
+    history_days: u32,
+    interval_minutes: u64,
+    gate: JobGate,
+) {
+    let mut interval = time::interval(Duration::from_secs(interval_minutes * 60));
+
+    info!(
+        "Weather scheduler started with {} minute interval",
+        interval_minutes
+    );
+
+    loop {
+        next_tick(&mut interval, &gate, JobKind::Light).await;
+        debug!("Weather scheduler tick - fetching daily temperature and ET");
+
+        if let Err(e) = weather_service.refresh(&source, history_days).await {
+            error!(
+                error = %e,
+                "Failed to refresh gauge weather"
+            );
+        }
+    }
+}
+
+pub async fn start_anomaly_scheduler(
+    anomaly_service: AnomalyService,
+    rules: AnomalyRules,
+    interval_minutes: u64,
+    gate: JobGate,
+) {
+    let mut interval = time::interval(Duration::from_secs(interval_minutes * 60));
+
+    info!(
+        "Anomaly scheduler started with {} minute interval",
+        interval_minutes
+    );
+
+    loop {
+        next_tick(&mut interval, &gate, JobKind::Heavy).await;
+        debug!("Anomaly scheduler tick - checking gauges for malfunctions");
+
+        if let Err(e) = anomaly_service.detect(&rules).await {
+            error!(
+                error = %e,
+                "Failed to check gauges for anomalies"
+            );
+        }
+    }
+}
+
+pub async fn start_digest_scheduler(
+    digest_service: DigestService,
+    interval_minutes: u64,
+    gate: JobGate,
+) {
+    let mut interval = time::interval(Duration::from_secs(interval_minutes * 60));
+
+    info!(
+        "Digest scheduler started with {} minute interval",
+        interval_minutes
+    );
+
+    loop {
+        next_tick(&mut interval, &gate, JobKind::Light).await;
+        debug!("Digest scheduler tick - delivering due rainfall digests");
+
+        if let Err(e) = digest_service.send_due().await {
+            error!(
+                error = %e,
+                "Failed to deliver rainfall digests"
+            );
+        }
+    }
+}
+
+pub async fn start_summary_view_scheduler(
+    summary_view_service: SummaryViewService,
+    interval_minutes: u64,
+    gate: JobGate,
+) {
+    let mut interval = time::interval(Duration::from_secs(interval_minutes * 60));
+
+    info!(
+        "Summary view scheduler started with {} minute interval",
+        interval_minutes
+    );
+
+    loop {
+        next_tick(&mut interval, &gate, JobKind::Light).await;
+        debug!("Summary view scheduler tick - refreshing materialized views");
+
+        if let Err(e) = summary_view_service.refresh().await {
+            error!(
+                error = %e,
+                "Failed to refresh summary views"
+            );
+        }
+    }
+}
+
+/// Publish a Parquet export of readings, summaries, and gauges every `interval_minutes`
+#[instrument(skip(service, gate), fields(interval_minutes = %interval_minutes))]
+pub async fn start_analytics_export_scheduler(
+    service: AnalyticsExportService,
+    interval_minutes: u64,
+    gate: JobGate,
+) {
+    let mut interval = time::interval(Duration::from_secs(interval_minutes * 60));
+
+    info!(
+        "Analytics export scheduler started with {} minute interval",
+        interval_minutes
+    );
+
+    loop {
+        next_tick(&mut interval, &gate, JobKind::Heavy).await;
+        debug!("Analytics export tick - exporting readings and summaries");
+
+        if let Err(e) = service.export().await {
+            error!(
+                error = %e,
+                "Failed to publish analytics export"
+            );
+        }
+    }
+}
+
+/// Delete job run history older than `retention_days`, once a day
+pub async fn start_job_run_retention_scheduler(
+    job_runs: JobRunService,
+    retention_days: u32,
+    gate: JobGate,
+) {
+    let mut interval = time::interval(Duration::from_secs(24 * 60 * 60));
+
+    info!(
+        "Job run retention scheduler started, keeping {} days",
+        retention_days
+    );
+
+    loop {
+        next_tick(&mut interval, &gate, JobKind::Light).await;
+        debug!("Job run retention tick - pruning old runs");
+
+        if let Err(e) = job_runs.prune(retention_days).await {
+            error!(
+                error = %e,
+                "Failed to prune job run history"
+            );
+        }
+    }
+}
+
+/// Delete archived upstream payloads older than the archive's retention, once a day
+pub async fn start_payload_archive_retention_scheduler(archive: PayloadArchive, gate: JobGate) {
+    let mut interval = time::interval(Duration::from_secs(24 * 60 * 60));
+
+    info!(
+        "Payload archive retention scheduler started, keeping {} days",
+        archive.retention_days()
+    );
+
+    loop {
+        next_tick(&mut interval, &gate, JobKind::Light).await;
+        debug!("Payload archive retention tick - pruning old payloads");
+
+        if let Err(e) = archive.prune().await {
+            error!(
+                error = %e,
+                "Failed to prune archived payloads"
+            );
+        }
+    }
+}
+
+/// Decides whether a scheduler tick does its work: this replica must lead (see
+/// `crate::leader`) and the job's kind must be allowed at this time of day (see
+/// `crate::job_windows`)
+#[derive(Debug, Clone)]
+pub struct JobGate {
+    leadership: Leadership,
+    windows: JobWindows,
+    clock: SharedClock,
+}
+
+impl JobGate {
+    pub fn new(leadership: Leadership, windows: JobWindows, clock: SharedClock) -> Self {
+        Self {
+            leadership,
+            windows,
+            clock,
+        }
+    }
+
+    pub fn allows(&self, kind: JobKind) -> bool {
+        if !self.leadership.is_leader() {
+            debug!("Scheduler tick skipped; another replica leads");
+            return false;
+        }
+        if !self.windows.allows(kind, self.clock.now()) {
+            debug!(?kind, "Scheduler tick skipped; outside the job window");
+            return false;
+        }
+        true
+    }
+}
+
+/// Wait for the next tick at which a `kind` job may run
+///
+/// Skipped ticks are not made up, so a replica that takes over leadership, or a window
+/// that opens, resumes work at the next interval.
+async fn next_tick(interval: &mut time::Interval, gate: &JobGate, kind: JobKind) {
+    loop {
+        interval.tick().await;
+        if gate.allows(kind) {
+            return;
+        }
+    }
+}
+
+/// Ping the database every `interval_secs`, recording reachability and pool usage
+///
+/// sqlx replaces broken connections on its own; this makes outages and recoveries
+/// visible in the db_* metrics and logs each transition once.
+pub async fn start_db_health_monitor(pool: DbPool, metrics: Metrics, interval_secs: u64) {
+    let mut interval = time::interval(Duration::from_secs(interval_secs.max(1)));
+
+    info!(
+        "Database health monitor started with {} second interval",
+        interval_secs
+    );
+
+    loop {
+        interval.tick().await;
+
+        let result = pool.ping().await;
+        let changed = metrics.record_db_check(result.is_ok(), pool.stats());
+        match result {
+            Err(e) if changed => error!(error = %e, "Database unreachable"),
+            Err(e) => debug!(error = %e, "Database still unreachable"),
+            Ok(()) if changed => info!("Database reachable again"),
+            Ok(()) => {}
+        }
+    }
+}
+
 /// Calculate date range for a specific month (helper for scheduler)
 ///
 /// Returns (start_of_month, start_of_next_month)
@@ -1,7 +1,67 @@
+pub mod analytics_export_service;
+pub mod annotation_service;
+pub mod anomaly_service;
+pub mod attachment_service;
+pub mod backup_service;
+pub mod bench_service;
+pub mod bootstrap_service;
+pub mod current_conditions_service;
+pub mod digest_service;
+pub mod elevation_service;
+pub mod fopr_availability_service;
 pub mod fopr_import_service;
+pub mod forecast_service;
+pub mod gauge_edit_service;
 pub mod gauge_service;
+pub mod geocode_service;
+pub mod gis_export_service;
+pub mod historical_import_service;
+pub mod idempotency_service;
+pub mod job_run_service;
+pub mod radar_service;
 pub mod reading_service;
+pub mod seed_service;
+pub mod share_service;
+pub mod slow_query_service;
+pub mod summary_service;
+pub mod summary_view_service;
+pub mod threshold_service;
+pub mod usage_service;
+pub mod user_service;
+pub mod weather_service;
+pub mod zone_service;
 
+pub use analytics_export_service::AnalyticsExportService;
+pub use annotation_service::AnnotationService;
+pub use anomaly_service::AnomalyService;
+pub use attachment_service::AttachmentService;
+pub use backup_service::BackupService;
+pub use bench_service::BenchService;
+pub use bootstrap_service::BootstrapService;
+pub use current_conditions_service::CurrentConditionsService;
+pub use digest_service::DigestService;
+pub use elevation_service::ElevationService;
+pub use fopr_availability_service::FoprAvailabilityService;
 pub use fopr_import_service::FoprImportService;
+pub use forecast_service::ForecastService;
+pub use gauge_edit_service::GaugeEditService;
 pub use gauge_service::GaugeService;
-pub use reading_service::ReadingService;
+pub use geocode_service::GeocodeService;
+pub use gis_export_service::GisExportService;
+pub use historical_import_service::HistoricalImportService;
+pub use idempotency_service::IdempotencyService;
+pub use job_run_service::JobRunService;
+pub use radar_service::RadarService;
+pub use reading_service::{
+    ReadingQueryError, ReadingQueryLimits, ReadingService, YearSummaryParams,
+};
+pub use seed_service::SeedService;
+pub use share_service::ShareService;
+pub use slow_query_service::SlowQueryService;
+pub use summary_service::SummaryService;
+pub use summary_view_service::SummaryViewService;
+pub use threshold_service::ThresholdService;
+pub use usage_service::UsageService;
+pub use user_service::UserService;
+pub use weather_service::WeatherService;
+pub use zone_service::ZoneService;
@@ -0,0 +1,118 @@
+use futures::TryStreamExt;
+use tracing::{info, instrument};
+
+use crate::analytics::{
+    self, AnalyticsError, AnalyticsManifest, AnalyticsStore, ReadingPartitions,
+    ANALYTICS_FORMAT_VERSION, GAUGES_TABLE, MONTHLY_SUMMARIES_TABLE, READINGS_TABLE,
+};
+use crate::clock::{self, SharedClock};
+use crate::db::{DbPool, GaugeRepository, MonthlyRainfallRepository, ReadingRepository};
+
+/// Publishes readings, monthly summaries, and gauges as Parquet for offline analysis
+///
+/// Exports and downloads are refused with `AnalyticsError::Disabled` until `with_store`
+/// names where exports are kept.
+#[derive(Clone)]
+pub struct AnalyticsExportService {
+    reading_repo: ReadingRepository,
+    monthly_repo: MonthlyRainfallRepository,
+    gauge_repo: GaugeRepository,
+    backend: &'static str,
+    store: Option<AnalyticsStore>,
+    clock: SharedClock,
+}
+
+impl AnalyticsExportService {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        let pool = pool.into();
+        Self {
+            reading_repo: ReadingRepository::new(pool.clone()),
+            monthly_repo: MonthlyRainfallRepository::new(pool.clone()),
+            gauge_repo: GaugeRepository::new(pool.clone()),
+            backend: pool.backend(),
+            store: None,
+            clock: clock::system_clock(),
+        }
+    }
+
+    pub fn with_store(mut self, store: AnalyticsStore) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Write and publish a new export of every table
+    #[instrument(skip(self))]
+    pub async fn export(&self) -> Result<AnalyticsManifest, AnalyticsError> {
+        let store = self.store()?;
+        let created_at = self.clock.now();
+        let export_id = analytics::export_id(created_at);
+        let staging = store.stage(&export_id)?;
+
+        let gauges = self.gauge_repo.find_gis_gauges().await?;
+        let gauge_file = analytics::write_gauges(&staging, &gauges)?;
+
+        let summaries = self.monthly_repo.find_all_summaries().await?;
+        let summary_file = analytics::write_monthly_summaries(&staging, &summaries)?;
+
+        let mut partitions = ReadingPartitions::new(&staging);
+        let mut readings = self.reading_repo.stream_all_for_export();
+        while let Some(reading) = readings.try_next().await? {
+            partitions.push(&reading)?;
+        }
+        let reading_files = partitions.finish()?;
+
+        let manifest = AnalyticsManifest {
+            format_version: ANALYTICS_FORMAT_VERSION,
+            export_id,
+            created_at,
+            backend: self.backend.to_string(),
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            tables: vec![
+                analytics::table(GAUGES_TABLE, vec![gauge_file]),
+                analytics::table(MONTHLY_SUMMARIES_TABLE, vec![summary_file]),
+                analytics::table(READINGS_TABLE, reading_files),
+            ],
+        };
+        store.publish(&staging, &manifest).await?;
+
+        let rows: Vec<String> = manifest
+            .tables
+            .iter()
+            .map(|table| format!("{} {}", table.rows, table.name))
+            .collect();
+        info!(
+            "Analytics export {} written: {}",
+            manifest.export_id,
+            rows.join(", ")
+        );
+        Ok(manifest)
+    }
+
+    /// Manifest of the newest published export
+    pub async fn latest(&self) -> Result<AnalyticsManifest, AnalyticsError> {
+        self.store()?
+            .latest()
+            .await?
+            .ok_or(AnalyticsError::NotPublished)
+    }
+
+    /// Contents of a file of a published export
+    pub async fn file(&self, export_id: &str, path: &str) -> Result<Vec<u8>, AnalyticsError> {
+        self.store()?
+            .read(export_id, path)
+            .await?
+            .ok_or_else(|| AnalyticsError::FileNotFound {
+                export_id: export_id.to_string(),
+                path: path.to_string(),
+            })
+    }
+
+    fn store(&self) -> Result<&AnalyticsStore, AnalyticsError> {
+        self.store.as_ref().ok_or(AnalyticsError::Disabled)
+    }
+}
@@ -0,0 +1,143 @@
+use std::borrow::Cow;
+
+use chrono::NaiveDate;
+use serde::Deserialize;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use validator::{Validate, ValidationError};
+
+use crate::db::{AnnotationRepository, DbError, GaugeAnnotation};
+
+/// A note to attach to a gauge
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+#[validate(schema(function = "validate_annotation_range"))]
+pub struct NewAnnotation {
+    #[validate(length(min = 1, max = 2000, message = "must be 1-2000 characters"))]
+    #[schema(example = "Sensor clogged with debris; readings undercount")]
+    pub note: String,
+    /// First day covered (inclusive); omit both dates for a note about the whole gauge
+    #[schema(example = "2025-03-02")]
+    pub start_date: Option<NaiveDate>,
+    /// Last day covered (inclusive); omit for an ongoing issue
+    #[schema(example = "2025-03-05")]
+    pub end_date: Option<NaiveDate>,
+    /// Who wrote the note (the admin key is shared, so this is self-reported)
+    #[validate(length(min = 1, max = 100, message = "must be 1-100 characters"))]
+    #[schema(example = "jsmith")]
+    pub author: String,
+}
+
+fn validate_annotation_range(annotation: &NewAnnotation) -> Result<(), ValidationError> {
+    match (annotation.start_date, annotation.end_date) {
+        (None, Some(_)) => Err(ValidationError::new("date_order")
+            .with_message(Cow::Borrowed("end_date requires start_date"))),
+        (Some(start), Some(end)) if start > end => Err(ValidationError::new("date_order")
+            .with_message(Cow::Borrowed("start_date must not be after end_date"))),
+        _ => Ok(()),
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AnnotationError {
+    #[error("Gauge not found: {0}")]
+    GaugeNotFound(String),
+
+    #[error("Annotation {0} not found")]
+    AnnotationNotFound(i64),
+
+    #[error("Database error: {0}")]
+    Database(#[from] DbError),
+}
+
+#[derive(Clone)]
+pub struct AnnotationService {
+    repo: AnnotationRepository,
+}
+
+impl AnnotationService {
+    pub fn new(repo: AnnotationRepository) -> Self {
+        Self { repo }
+    }
+
+    /// Attach a note to a gauge (the annotation must already be validated)
+    #[instrument(skip(self, annotation))]
+    pub async fn create(
+        &self,
+        station_id: &str,
+        annotation: &NewAnnotation,
+    ) -> Result<GaugeAnnotation, AnnotationError> {
+        let created = self
+            .repo
+            .insert(
+                station_id,
+                annotation.start_date,
+                annotation.end_date,
+                annotation.note.trim(),
+                annotation.author.trim(),
+            )
+            .await?
+            .ok_or_else(|| AnnotationError::GaugeNotFound(station_id.to_string()))?;
+
+        info!(
+            "{} annotated gauge {} (annotation {})",
+            created.author, station_id, created.id
+        );
+        Ok(created)
+    }
+
+    /// All annotations for a gauge, newest first
+    pub async fn list(&self, station_id: &str) -> Result<Vec<GaugeAnnotation>, AnnotationError> {
+        if !self.repo.gauge_exists(station_id).await? {
+            return Err(AnnotationError::GaugeNotFound(station_id.to_string()));
+        }
+        Ok(self.repo.find_by_station(station_id).await?)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn delete(&self, station_id: &str, id: i64) -> Result<(), AnnotationError> {
+        if !self.repo.delete(station_id, id).await? {
+            return Err(AnnotationError::AnnotationNotFound(id));
+        }
+        info!("Deleted annotation {} from gauge {}", id, station_id);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn annotation(start: Option<(i32, u32, u32)>, end: Option<(i32, u32, u32)>) -> NewAnnotation {
+        let date = |(y, m, d)| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        NewAnnotation {
+            note: "Sensor clogged".to_string(),
+            start_date: start.map(date),
+            end_date: end.map(date),
+            author: "jsmith".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_annotation_date_rules() {
+        assert!(annotation(None, None).validate().is_ok());
+        assert!(annotation(Some((2025, 3, 2)), None).validate().is_ok());
+        assert!(annotation(Some((2025, 3, 2)), Some((2025, 3, 2)))
+            .validate()
+            .is_ok());
+        assert!(annotation(None, Some((2025, 3, 5))).validate().is_err());
+        assert!(annotation(Some((2025, 3, 5)), Some((2025, 3, 2)))
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_annotation_text_rules() {
+        let mut empty_note = annotation(None, None);
+        empty_note.note = String::new();
+        assert!(empty_note.validate().is_err());
+
+        let mut long_author = annotation(None, None);
+        long_author.author = "a".repeat(101);
+        assert!(long_author.validate().is_err());
+    }
+}
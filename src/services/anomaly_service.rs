@@ -0,0 +1,200 @@
+use chrono::{Days, Duration};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::anomaly::{
+    flatline_finding, neighbors, AnomalyAlerter, AnomalyKind, AnomalyRules, AnomalyStatus,
+    OFFLINE_AFTER_DAYS,
+};
+use crate::clock::{self, SharedClock};
+use crate::db::{AnomalyRepository, DbError, DbPool, GaugeAnomaly, GaugeRepository};
+use crate::station_id::StationId;
+
+/// Anomaly list filters (used by API)
+#[derive(Debug, Clone, Deserialize, IntoParams, Validate)]
+pub struct AnomalyParams {
+    /// Only anomalies with this review status (open, confirmed, dismissed)
+    pub status: Option<AnomalyStatus>,
+    /// Only anomalies of this kind (flatline, spike, reset)
+    pub kind: Option<AnomalyKind>,
+    /// Only anomalies for this gauge
+    #[param(value_type = Option<String>)]
+    pub station_id: Option<StationId>,
+    /// Maximum anomalies to return (default 100, max 500)
+    #[serde(default = "default_anomaly_limit")]
+    #[validate(range(min = 1, max = 500, message = "must be between 1 and 500"))]
+    pub limit: u32,
+}
+
+fn default_anomaly_limit() -> u32 {
+    100
+}
+
+/// An admin's verdict on an anomaly
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+pub struct AnomalyReview {
+    /// `open` puts a reviewed anomaly back in the queue
+    pub status: AnomalyStatus,
+    #[validate(length(max = 2000, message = "must be at most 2000 characters"))]
+    #[schema(example = "Funnel packed with leaves; cleaned 1/24")]
+    pub note: Option<String>,
+    /// Who reviewed it (the admin key is shared, so this is self-reported)
+    #[validate(length(min = 1, max = 100, message = "must be 1-100 characters"))]
+    #[schema(example = "jsmith")]
+    pub reviewed_by: String,
+}
+
+/// Outcome of one anomaly run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct AnomalyStats {
+    /// Gauges reporting only zeros for the flat-line period
+    pub dry_gauges: usize,
+    pub flatlines: usize,
+    pub spikes: usize,
+    pub resets: usize,
+    /// Findings not recorded by an earlier run
+    pub new_anomalies: usize,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AnomalyError {
+    #[error("Anomaly {0} not found")]
+    AnomalyNotFound(i64),
+
+    #[error("Database error: {0}")]
+    Database(#[from] DbError),
+}
+
+/// Finds suspected gauge malfunctions and tracks their review
+#[derive(Clone)]
+pub struct AnomalyService {
+    repo: AnomalyRepository,
+    gauge_repo: GaugeRepository,
+    alerter: Option<AnomalyAlerter>,
+    clock: SharedClock,
+}
+
+impl AnomalyService {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        let pool = pool.into();
+        Self {
+            repo: AnomalyRepository::new(pool.clone()),
+            gauge_repo: GaugeRepository::new(pool),
+            alerter: None,
+            clock: clock::system_clock(),
+        }
+    }
+
+    /// POST new findings to a webhook after each run
+    pub fn with_alerter(mut self, alerter: AnomalyAlerter) -> Self {
+        self.alerter = Some(alerter);
+        self
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Look for flat-lines, spikes, and resets, record them, and alert on new ones
+    #[instrument(skip(self))]
+    pub async fn detect(&self, rules: &AnomalyRules) -> Result<AnomalyStats, DbError> {
+        let now = self.clock.now();
+        let since = now - Days::new(rules.lookback_days as u64);
+
+        let runs = self
+            .repo
+            .find_dry_runs(
+                now - Duration::days(OFFLINE_AFTER_DAYS),
+                rules.flatline_days as i32,
+            )
+            .await?;
+        let mut findings = Vec::new();
+        if !runs.is_empty() {
+            let gauges = self.gauge_repo.find_locations().await?;
+            for run in &runs {
+                let nearby = neighbors(run, &gauges);
+                if nearby.is_empty() {
+                    continue;
+                }
+                let totals = self
+                    .repo
+                    .find_rainfall_totals(&nearby, run.dry_since, run.last_reading)
+                    .await?;
+                findings.extend(flatline_finding(run, &totals));
+            }
+        }
+        let flatlines = findings.len();
+
+        let spikes = self.repo.find_spikes(since, rules.spike_inches).await?;
+        let resets = self.repo.find_resets(since).await?;
+        let stats = AnomalyStats {
+            dry_gauges: runs.len(),
+            flatlines,
+            spikes: spikes.len(),
+            resets: resets.len(),
+            new_anomalies: 0,
+        };
+        findings.extend(spikes);
+        findings.extend(resets);
+
+        let new = self.repo.upsert_findings(&findings).await?;
+        if let Some(alerter) = &self.alerter {
+            alerter.send(&new).await;
+        }
+
+        info!(
+            flatlines = stats.flatlines,
+            spikes = stats.spikes,
+            resets = stats.resets,
+            new_anomalies = new.len(),
+            "Checked gauges for anomalies"
+        );
+        Ok(AnomalyStats {
+            new_anomalies: new.len(),
+            ..stats
+        })
+    }
+
+    /// Recorded anomalies, newest first
+    pub async fn list(&self, params: &AnomalyParams) -> Result<Vec<GaugeAnomaly>, DbError> {
+        self.repo
+            .find(
+                params.status,
+                params.kind,
+                params.station_id.as_deref(),
+                params.limit as i64,
+            )
+            .await
+    }
+
+    /// Record an admin's review (the review must already be validated)
+    #[instrument(skip(self, review), fields(status = ?review.status))]
+    pub async fn review(
+        &self,
+        id: i64,
+        review: &AnomalyReview,
+    ) -> Result<GaugeAnomaly, AnomalyError> {
+        let note = review
+            .note
+            .as_deref()
+            .map(str::trim)
+            .filter(|n| !n.is_empty());
+        let reviewed = self
+            .repo
+            .review(id, review.status, note, review.reviewed_by.trim())
+            .await?
+            .ok_or(AnomalyError::AnomalyNotFound(id))?;
+
+        info!(
+            "{} marked anomaly {} on gauge {} {}",
+            reviewed.reviewed_by.as_deref().unwrap_or_default(),
+            id,
+            reviewed.station_id,
+            reviewed.status.as_str()
+        );
+        Ok(reviewed)
+    }
+}
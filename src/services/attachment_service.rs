@@ -0,0 +1,224 @@
+use std::borrow::Cow;
+
+use sha2::{Digest, Sha256};
+use tracing::{info, instrument};
+use utoipa::IntoParams;
+use validator::{Validate, ValidationError};
+
+use crate::db::attachment_repository::NewGaugeAttachment;
+use crate::db::{AttachmentRepository, DbError, GaugeAttachment};
+use crate::storage::ObjectStore;
+
+/// Largest accepted attachment (matches the Idempotency-Key body buffer)
+pub const MAX_ATTACHMENT_BYTES: usize = 10 * 1024 * 1024;
+
+/// Media types accepted for upload: site photos and FOPR PDFs
+pub const ALLOWED_CONTENT_TYPES: &[&str] =
+    &["image/jpeg", "image/png", "image/webp", "application/pdf"];
+
+/// Query parameters describing an uploaded file (the body is the raw file)
+#[derive(Debug, Clone, serde::Deserialize, IntoParams, Validate)]
+pub struct AttachmentUploadParams {
+    /// File name shown to clients and used for downloads
+    #[validate(custom(function = "validate_filename"))]
+    pub filename: String,
+    /// Optional caption (max 1000 characters)
+    #[validate(length(max = 1000, message = "must be at most 1000 characters"))]
+    pub description: Option<String>,
+}
+
+/// File names are 1-255 characters without path separators, quotes, or control characters
+pub fn validate_filename(filename: &str) -> Result<(), ValidationError> {
+    let valid = !filename.trim().is_empty()
+        && filename.len() <= 255
+        && !filename
+            .chars()
+            .any(|c| c.is_control() || matches!(c, '/' | '\\' | '"'));
+
+    if valid {
+        Ok(())
+    } else {
+        Err(
+            ValidationError::new("filename_format").with_message(Cow::Borrowed(
+                "must be 1-255 characters without '/', '\\', '\"' or control characters",
+            )),
+        )
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AttachmentError {
+    #[error("Gauge not found: {0}")]
+    GaugeNotFound(String),
+
+    #[error("Attachment {0} not found")]
+    AttachmentNotFound(i64),
+
+    #[error("Unsupported content type '{0}'")]
+    UnsupportedContentType(String),
+
+    #[error("Attachment is empty")]
+    Empty,
+
+    #[error("Object storage error: {0}")]
+    Storage(#[from] std::io::Error),
+
+    #[error("Database error: {0}")]
+    Database(#[from] DbError),
+}
+
+/// A file to attach to a gauge
+#[derive(Debug, Clone)]
+pub struct AttachmentUpload {
+    pub filename: String,
+    pub content_type: String,
+    pub description: Option<String>,
+    pub bytes: Vec<u8>,
+}
+
+#[derive(Clone)]
+pub struct AttachmentService {
+    repo: AttachmentRepository,
+    store: ObjectStore,
+}
+
+impl AttachmentService {
+    pub fn new(repo: AttachmentRepository, store: ObjectStore) -> Self {
+        Self { repo, store }
+    }
+
+    /// Normalize a Content-Type header (drop parameters, lowercase) and check it is allowed
+    pub fn allowed_content_type(content_type: &str) -> Option<String> {
+        let essence = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        ALLOWED_CONTENT_TYPES
+            .contains(&essence.as_str())
+            .then_some(essence)
+    }
+
+    /// Content-addressed object key, so re-uploads of the same file share one object
+    pub fn storage_key(station_id: &str, sha256: &str) -> String {
+        format!("gauges/{station_id}/{sha256}")
+    }
+
+    /// Store the file and record it against the gauge
+    #[instrument(skip(self, upload), fields(filename = %upload.filename, size = upload.bytes.len()))]
+    pub async fn upload(
+        &self,
+        station_id: &str,
+        upload: AttachmentUpload,
+    ) -> Result<GaugeAttachment, AttachmentError> {
+        let content_type = Self::allowed_content_type(&upload.content_type)
+            .ok_or_else(|| AttachmentError::UnsupportedContentType(upload.content_type.clone()))?;
+        if upload.bytes.is_empty() {
+            return Err(AttachmentError::Empty);
+        }
+        // Check first so unknown gauges never leave objects behind
+        if !self.repo.gauge_exists(station_id).await? {
+            return Err(AttachmentError::GaugeNotFound(station_id.to_string()));
+        }
+
+        let sha256 = format!("{:x}", Sha256::digest(&upload.bytes));
+        let storage_key = Self::storage_key(station_id, &sha256);
+        self.store.put(&storage_key, &upload.bytes).await?;
+
+        let attachment = self
+            .repo
+            .insert(&NewGaugeAttachment {
+                station_id,
+                filename: &upload.filename,
+                content_type: &content_type,
+                size_bytes: upload.bytes.len() as i64,
+                sha256: &sha256,
+                storage_key: &storage_key,
+                description: upload.description.as_deref(),
+            })
+            .await?
+            .ok_or_else(|| AttachmentError::GaugeNotFound(station_id.to_string()))?;
+
+        info!(
+            "Attached {} ({} bytes) to gauge {} as attachment {}",
+            attachment.filename, attachment.size_bytes, station_id, attachment.id
+        );
+        Ok(attachment)
+    }
+
+    /// Attachments for a gauge, newest first
+    pub async fn list(&self, station_id: &str) -> Result<Vec<GaugeAttachment>, AttachmentError> {
+        if !self.repo.gauge_exists(station_id).await? {
+            return Err(AttachmentError::GaugeNotFound(station_id.to_string()));
+        }
+        Ok(self.repo.find_by_station(station_id).await?)
+    }
+
+    /// Attachment metadata and contents
+    #[instrument(skip(self))]
+    pub async fn download(
+        &self,
+        station_id: &str,
+        id: i64,
+    ) -> Result<(GaugeAttachment, Vec<u8>), AttachmentError> {
+        let attachment = self
+            .repo
+            .find(station_id, id)
+            .await?
+            .ok_or(AttachmentError::AttachmentNotFound(id))?;
+
+        let bytes = self
+            .store
+            .get(&attachment.storage_key)
+            .await?
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("object {} is missing", attachment.storage_key),
+                )
+            })?;
+
+        Ok((attachment, bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allowed_content_type() {
+        assert_eq!(
+            AttachmentService::allowed_content_type("image/jpeg").as_deref(),
+            Some("image/jpeg")
+        );
+        assert_eq!(
+            AttachmentService::allowed_content_type("Application/PDF; name=fopr.pdf").as_deref(),
+            Some("application/pdf")
+        );
+        assert_eq!(AttachmentService::allowed_content_type("text/html"), None);
+        assert_eq!(AttachmentService::allowed_content_type(""), None);
+    }
+
+    #[test]
+    fn test_validate_filename() {
+        assert!(validate_filename("site-north.jpg").is_ok());
+        assert!(validate_filename("FOPR 59700 (2024).pdf").is_ok());
+        assert!(validate_filename("").is_err());
+        assert!(validate_filename("   ").is_err());
+        assert!(validate_filename("../secret.pdf").is_err());
+        assert!(validate_filename("a\\b.jpg").is_err());
+        assert!(validate_filename("say\"hi\".jpg").is_err());
+        assert!(validate_filename("line\nbreak.jpg").is_err());
+        assert!(validate_filename(&"a".repeat(256)).is_err());
+    }
+
+    #[test]
+    fn test_storage_key() {
+        assert_eq!(
+            AttachmentService::storage_key("59700", "abc123"),
+            "gauges/59700/abc123"
+        );
+    }
+}
@@ -0,0 +1,294 @@
+// Portable backups of gauges, readings, summaries, and FOPR jobs
+//
+// A backup is a zstd-compressed tar archive holding `manifest.json` and one JSON Lines
+// file per table (`<table>.jsonl`, one object per row). Rows carry no surrogate ids and use
+// one representation on every backend, so a PostgreSQL backup restores into SQLite and
+// vice versa (FOPR jobs are skipped on SQLite, which has no job queue).
+//
+// The manifest records the archive format version and the schema version (latest applied
+// migration) of the source database. Restores refuse other format versions, and backups
+// from a newer schema on the same backend.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tracing::{info, instrument};
+
+use crate::db::backup_repository::{BackupTable, BACKUP_TABLES};
+use crate::db::{BackupRepository, DbError};
+
+/// Version of the archive layout; bump when tables or columns change
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Rows inserted per statement during restore
+const RESTORE_BATCH_SIZE: usize = 1000;
+
+/// zstd level for archives (the library default)
+const COMPRESSION_LEVEL: i32 = 3;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BackupError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid backup data: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Database error: {0}")]
+    Database(#[from] DbError),
+
+    #[error("Not a backup archive: {MANIFEST_FILE} is missing")]
+    MissingManifest,
+
+    #[error(
+        "Backup format version {0} is not supported (this version reads {BACKUP_FORMAT_VERSION})"
+    )]
+    UnsupportedFormat(u32),
+
+    #[error("Backup is from schema version {backup}, newer than this database ({database}); upgrade before restoring")]
+    SchemaTooNew { backup: i64, database: i64 },
+
+    #[error(
+        "Database already has {0} gauges; restore into an empty database or pass --force to merge"
+    )]
+    NotEmpty(i64),
+}
+
+/// Contents of `manifest.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format_version: u32,
+    pub created_at: DateTime<Utc>,
+    /// Backend the backup was taken from ("postgres" or "sqlite")
+    pub backend: String,
+    /// Latest migration applied to the source database
+    pub schema_version: Option<i64>,
+    /// rain-tracker-service version that wrote the backup
+    pub tool_version: String,
+    pub tables: Vec<BackupTableEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupTableEntry {
+    pub name: String,
+    pub rows: u64,
+}
+
+/// Outcome of restoring one table
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RestoredTable {
+    pub name: String,
+    pub rows: u64,
+    /// Rows not already present in the database
+    pub inserted: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreStats {
+    pub tables: Vec<RestoredTable>,
+    /// Tables in the backup that this backend has no table for
+    pub skipped_tables: Vec<String>,
+    pub duration_secs: f64,
+}
+
+#[derive(Clone)]
+pub struct BackupService {
+    repo: BackupRepository,
+}
+
+impl BackupService {
+    pub fn new(repo: BackupRepository) -> Self {
+        Self { repo }
+    }
+
+    /// Write a backup archive of every table this backend has to `out`
+    #[instrument(skip(self))]
+    pub async fn backup(&self, out: &Path) -> Result<BackupManifest, BackupError> {
+        let staging = tempfile::tempdir()?;
+        let mut tables = Vec::new();
+
+        for table in BACKUP_TABLES.iter().filter(|t| self.repo.supports(t)) {
+            let mut writer = BufWriter::new(File::create(staging.path().join(file_name(table)))?);
+            let rows = self
+                .repo
+                .for_each_row(table, |row| {
+                    serde_json::to_writer(&mut writer, &row)?;
+                    writer.write_all(b"\n")?;
+                    Ok::<_, BackupError>(())
+                })
+                .await?;
+            writer.flush()?;
+
+            info!("Backed up {} rows from {}", rows, table.name);
+            tables.push(BackupTableEntry {
+                name: table.name.to_string(),
+                rows,
+            });
+        }
+
+        let manifest = BackupManifest {
+            format_version: BACKUP_FORMAT_VERSION,
+            created_at: Utc::now(),
+            backend: self.repo.backend().to_string(),
+            schema_version: self.repo.schema_version().await?,
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            tables,
+        };
+        serde_json::to_writer_pretty(File::create(staging.path().join(MANIFEST_FILE))?, &manifest)?;
+
+        let files: Vec<String> = std::iter::once(MANIFEST_FILE.to_string())
+            .chain(manifest.tables.iter().map(|t| format!("{}.jsonl", t.name)))
+            .collect();
+        let out = out.to_path_buf();
+        tokio::task::spawn_blocking(move || write_archive(staging.path(), &files, &out))
+            .await
+            .expect("archive task panicked")?;
+
+        Ok(manifest)
+    }
+
+    /// Restore a backup archive into this database
+    ///
+    /// The database must be migrated and, unless `force` is set, have no gauges. With
+    /// `force`, rows whose natural key already exists are left untouched.
+    #[instrument(skip(self))]
+    pub async fn restore(&self, archive: &Path, force: bool) -> Result<RestoreStats, BackupError> {
+        let start = Instant::now();
+        let staging = unpack(archive).await?;
+        let manifest = load_manifest(staging.path())?;
+        self.check_compatible(&manifest).await?;
+
+        if !force {
+            let gauges = self.repo.count_rows(&BACKUP_TABLES[0]).await?;
+            if gauges > 0 {
+                return Err(BackupError::NotEmpty(gauges));
+            }
+        }
+
+        let mut stats = RestoreStats {
+            tables: Vec::new(),
+            skipped_tables: Vec::new(),
+            duration_secs: 0.0,
+        };
+        for table in BACKUP_TABLES {
+            if !manifest.tables.iter().any(|t| t.name == table.name) {
+                continue;
+            }
+            if !self.repo.supports(table) {
+                info!(
+                    "Skipping {}: not supported by the {} backend",
+                    table.name,
+                    self.repo.backend()
+                );
+                stats.skipped_tables.push(table.name.to_string());
+                continue;
+            }
+
+            let restored = self
+                .restore_table(table, &staging.path().join(file_name(table)))
+                .await?;
+            info!(
+                "Restored {} of {} rows into {}",
+                restored.inserted, restored.rows, table.name
+            );
+            stats.tables.push(restored);
+        }
+
+        stats.duration_secs = start.elapsed().as_secs_f64();
+        Ok(stats)
+    }
+
+    async fn check_compatible(&self, manifest: &BackupManifest) -> Result<(), BackupError> {
+        if manifest.format_version != BACKUP_FORMAT_VERSION {
+            return Err(BackupError::UnsupportedFormat(manifest.format_version));
+        }
+
+        // Migration versions are only comparable within a backend; across backends the
+        // format version pins the columns
+        if manifest.backend == self.repo.backend() {
+            let database = self.repo.schema_version().await?.unwrap_or(0);
+            if let Some(backup) = manifest.schema_version.filter(|v| *v > database) {
+                return Err(BackupError::SchemaTooNew { backup, database });
+            }
+        }
+        Ok(())
+    }
+
+    async fn restore_table(
+        &self,
+        table: &BackupTable,
+        path: &Path,
+    ) -> Result<RestoredTable, BackupError> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut restored = RestoredTable {
+            name: table.name.to_string(),
+            rows: 0,
+            inserted: 0,
+        };
+
+        let mut batch: Vec<Map<String, Value>> = Vec::with_capacity(RESTORE_BATCH_SIZE);
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            batch.push(serde_json::from_str(&line)?);
+
+            if batch.len() == RESTORE_BATCH_SIZE {
+                restored.inserted += self.repo.insert_rows(table, &batch).await?;
+                restored.rows += batch.len() as u64;
+                batch.clear();
+            }
+        }
+        if !batch.is_empty() {
+            restored.inserted += self.repo.insert_rows(table, &batch).await?;
+            restored.rows += batch.len() as u64;
+        }
+
+        Ok(restored)
+    }
+}
+
+fn file_name(table: &BackupTable) -> String {
+    format!("{}.jsonl", table.name)
+}
+
+fn load_manifest(dir: &Path) -> Result<BackupManifest, BackupError> {
+    let path = dir.join(MANIFEST_FILE);
+    if !path.exists() {
+        return Err(BackupError::MissingManifest);
+    }
+    Ok(serde_json::from_reader(BufReader::new(File::open(path)?))?)
+}
+
+fn write_archive(dir: &Path, files: &[String], out: &Path) -> std::io::Result<()> {
+    let encoder = zstd::Encoder::new(File::create(out)?, COMPRESSION_LEVEL)?;
+    let mut builder = tar::Builder::new(encoder);
+    for file in files {
+        builder.append_path_with_name(dir.join(file), file)?;
+    }
+    builder.into_inner()?.finish()?.sync_all()
+}
+
+async fn unpack(archive: &Path) -> Result<tempfile::TempDir, BackupError> {
+    let archive = archive.to_path_buf();
+    let staging = tempfile::tempdir()?;
+    let dir = staging.path().to_path_buf();
+
+    tokio::task::spawn_blocking(move || -> std::io::Result<()> {
+        let decoder = zstd::Decoder::new(File::open(archive)?)?;
+        // `unpack` refuses entries that would land outside `dir`
+        tar::Archive::new(decoder).unpack(dir)
+    })
+    .await
+    .expect("unpack task panicked")?;
+
+    Ok(staging)
+}
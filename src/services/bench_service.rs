@@ -0,0 +1,185 @@
+// Workloads for the `bench` command and the criterion benchmarks
+//
+// Each one exercises a path that performance work targets: parsing a water year workbook,
+// parsing an FOPR workbook, bulk inserting historical readings, and building a water year
+// summary. Bulk inserts run in a transaction that is rolled back, so benchmarking leaves
+// a seeded database as it was.
+
+use std::time::Duration;
+
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::db::{
+    AnnotationRepository, DbError, GaugeRepository, HourlyRainfallRepository,
+    MonthlyRainfallRepository, ReadingRepository,
+};
+use crate::fopr::{FoprDailyDataParser, FoprParseError};
+use crate::importers::excel_importer::{ExcelImportError, ExcelImporter, HistoricalReading};
+use crate::services::reading_service::{ReadingQueryError, ReadingService, YearSummaryParams};
+use crate::station_id::StationId;
+use crate::units::Inches;
+
+/// First gauge created by `seed`, benchmarked unless another station is given
+pub const DEFAULT_BENCH_STATION: &str = "99001";
+
+/// Tags rows inserted by the bulk insert workload (always rolled back)
+pub const BENCH_DATA_SOURCE: &str = "benchmark";
+
+/// Readings inserted per bulk insert run unless another count is given
+pub const DEFAULT_BULK_INSERT_READINGS: usize = 1000;
+
+/// Timing statistics for one workload, in milliseconds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchTiming {
+    pub iterations: usize,
+    pub min_ms: f64,
+    pub median_ms: f64,
+    pub p95_ms: f64,
+    pub mean_ms: f64,
+}
+
+impl BenchTiming {
+    /// Summarize samples; None when there are none
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut ms: Vec<f64> = samples.iter().map(|d| d.as_secs_f64() * 1000.0).collect();
+        ms.sort_by(f64::total_cmp);
+
+        // Nearest-rank percentile
+        let rank = |p: f64| ms[((p * ms.len() as f64).ceil() as usize).clamp(1, ms.len()) - 1];
+
+        Some(Self {
+            iterations: ms.len(),
+            min_ms: ms[0],
+            median_ms: rank(0.5),
+            p95_ms: rank(0.95),
+            mean_ms: ms.iter().sum::<f64>() / ms.len() as f64,
+        })
+    }
+}
+
+/// Parse a water year workbook, returning the number of readings
+pub fn parse_water_year_excel(path: &str, water_year: i32) -> Result<usize, ExcelImportError> {
+    Ok(ExcelImporter::new(path).parse_all_months(water_year)?.len())
+}
+
+/// Parse every year sheet of an FOPR workbook, returning the number of readings
+pub fn parse_fopr_daily(path: &str, station_id: &StationId) -> Result<usize, FoprParseError> {
+    Ok(FoprDailyDataParser::new(path, station_id.clone())
+        .parse_all_years()?
+        .len())
+}
+
+/// `count` consecutive daily readings starting Oct 1, 1900
+///
+/// Seeded and imported data is far more recent, so inserting these never hits the
+/// ON CONFLICT path and every run measures the same amount of work.
+pub fn synthetic_readings(station_id: &StationId, count: usize) -> Vec<HistoricalReading> {
+    let start = NaiveDate::from_ymd_opt(1900, 10, 1).expect("valid date");
+    (0..count)
+        .map(|day| HistoricalReading {
+            station_id: station_id.clone(),
+            reading_date: start + Days::new(day as u64),
+            rainfall_inches: Inches::new(0.04 * (1 + day % 5) as f64).expect("positive amount"),
+            footnote_marker: None,
+        })
+        .collect()
+}
+
+/// Database workloads against a (seeded) PostgreSQL database
+pub struct BenchService {
+    pool: PgPool,
+    reading_repo: ReadingRepository,
+    reading_service: ReadingService,
+}
+
+impl BenchService {
+    pub fn new(pool: PgPool) -> Self {
+        let reading_repo = ReadingRepository::new(pool.clone());
+        let reading_service = ReadingService::new(
+            reading_repo.clone(),
+            MonthlyRainfallRepository::new(pool.clone()),
+            HourlyRainfallRepository::new(pool.clone()),
+            AnnotationRepository::new(pool.clone()),
+            GaugeRepository::new(pool.clone()),
+        );
+        Self {
+            pool,
+            reading_repo,
+            reading_service,
+        }
+    }
+
+    /// Insert readings through the historical bulk insert path, then roll back
+    ///
+    /// Returns the number of rows inserted before the rollback.
+    pub async fn bulk_insert_rolled_back(
+        &self,
+        station_id: &str,
+        readings: &[HistoricalReading],
+    ) -> Result<usize, DbError> {
+        let mut tx = self.pool.begin().await?;
+        let (inserted, _, _) = self
+            .reading_repo
+            .bulk_insert_historical_readings_tx(&mut tx, station_id, BENCH_DATA_SOURCE, readings)
+            .await?;
+        tx.rollback().await?;
+        Ok(inserted)
+    }
+
+    /// Build a water year summary the way the API does, returning the number of readings
+    pub async fn water_year_summary(
+        &self,
+        station_id: &str,
+        water_year: i32,
+    ) -> Result<usize, ReadingQueryError> {
+        let summary = self
+            .reading_service
+            .get_water_year_summary(station_id, water_year, &YearSummaryParams::default())
+            .await?;
+        Ok(summary.readings.len())
+    }
+
+    /// The water year before the current one, fully covered by a default `seed` run
+    pub fn default_water_year(&self) -> i32 {
+        self.reading_service.current_water_year() - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timing_from_samples() {
+        let samples: Vec<Duration> = (1..=20).map(Duration::from_millis).collect();
+        let timing = BenchTiming::from_samples(&samples).unwrap();
+
+        assert_eq!(timing.iterations, 20);
+        assert_eq!(timing.min_ms, 1.0);
+        assert_eq!(timing.median_ms, 10.0);
+        assert_eq!(timing.p95_ms, 19.0);
+        assert_eq!(timing.mean_ms, 10.5);
+        assert!(BenchTiming::from_samples(&[]).is_none());
+    }
+
+    #[test]
+    fn test_synthetic_readings_are_consecutive_days() {
+        let readings = synthetic_readings(&"99001".parse().unwrap(), 400);
+
+        assert_eq!(readings.len(), 400);
+        assert_eq!(
+            readings[0].reading_date,
+            NaiveDate::from_ymd_opt(1900, 10, 1).unwrap()
+        );
+        assert_eq!(
+            readings[399].reading_date,
+            NaiveDate::from_ymd_opt(1901, 11, 4).unwrap()
+        );
+        assert!(readings.iter().all(|r| r.rainfall_inches.value() > 0.0));
+    }
+}
@@ -0,0 +1,222 @@
+// Building blocks for the `bootstrap` command
+//
+// A new deployment is backfilled in stages: scrape the gauge list, import each gauge's
+// FOPR workbook, import water year workbooks for the years FOPR doesn't cover, rebuild
+// summaries, and report per-gauge coverage. Sequencing and checkpointing live in the CLI;
+// this service wraps the existing fetchers, importers, and repositories for each stage.
+
+use std::collections::BTreeSet;
+
+use chrono::{Datelike, NaiveDate};
+use serde::Serialize;
+
+use crate::db::fopr_import_job_repository::ImportStats;
+use crate::db::{
+    DbError, DbPool, FoprAvailabilityRepository, GaugeRepository, MonthlyRainfallRepository,
+    ReadingRepository, SummaryViewRepository,
+};
+use crate::fetch_error::FetchError;
+use crate::gauge_list_fetcher::{GaugeListFetcher, GaugeSummary as FetchedGauge};
+use crate::importers::downloader::McfcdDownloader;
+use crate::politeness::Politeness;
+use crate::services::fopr_import_service::{FoprImportError, FoprImportService};
+use crate::services::historical_import_service::HistoricalImportService;
+use crate::services::summary_service::{RecalcScope, RecalcStats, SummaryService};
+
+/// Earliest water year considered for water year workbook backfill unless another is given
+pub const DEFAULT_FIRST_WATER_YEAR: i32 = 2022;
+
+/// What a gauge's readings cover once the backfill is done
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StationCoverage {
+    pub station_id: String,
+    pub first_reading: NaiveDate,
+    pub last_reading: NaiveDate,
+    pub readings: i64,
+    /// Water years with at least one reading
+    pub water_years: usize,
+    pub data_sources: Vec<String>,
+}
+
+#[derive(Clone)]
+pub struct BootstrapService {
+    fetcher: GaugeListFetcher,
+    gauge_repo: GaugeRepository,
+    reading_repo: ReadingRepository,
+    availability_repo: FoprAvailabilityRepository,
+    fopr: FoprImportService,
+    historical: HistoricalImportService,
+    summary: SummaryService,
+}
+
+impl BootstrapService {
+    pub fn new(pool: impl Into<DbPool>, gauge_list_url: &str) -> Self {
+        let pool = pool.into();
+        Self {
+            fetcher: GaugeListFetcher::new(gauge_list_url.to_string()),
+            gauge_repo: GaugeRepository::new(pool.clone()),
+            reading_repo: ReadingRepository::new(pool.clone()),
+            availability_repo: FoprAvailabilityRepository::new(pool.clone()),
+            fopr: FoprImportService::new(pool.clone()),
+            historical: HistoricalImportService::new(pool.clone()),
+            summary: SummaryService::new(MonthlyRainfallRepository::new(pool.clone()))
+                .with_views(SummaryViewRepository::new(pool)),
+        }
+    }
+
+    /// Send MCFCD requests through the shared `politeness`
+    pub fn with_politeness(mut self, politeness: Politeness) -> Self {
+        let downloader = || McfcdDownloader::new().with_politeness(politeness.clone());
+        self.fetcher = self.fetcher.with_politeness(politeness.clone());
+        self.fopr = self.fopr.with_downloader(downloader());
+        self.historical = self.historical.with_downloader(downloader());
+        self
+    }
+
+    /// Set how many station-months are recalculated concurrently
+    pub fn with_recalc_concurrency(mut self, concurrency: usize) -> Self {
+        self.summary = self.summary.with_concurrency(concurrency);
+        self
+    }
+
+    /// Water year workbook imports reuse the `import excel` path
+    pub fn historical(&self) -> &HistoricalImportService {
+        &self.historical
+    }
+
+    /// Scrape the gauge list and store its summaries, returning the gauges found
+    ///
+    /// Unlike the scheduler, no FOPR import jobs are queued: bootstrap imports FOPR itself.
+    pub async fn discover_gauges(&self) -> Result<Vec<FetchedGauge>, DiscoveryError> {
+        let gauges = self.fetcher.fetch_gauge_list().await?;
+        self.gauge_repo.upsert_summaries(&gauges).await?;
+        Ok(gauges)
+    }
+
+    /// Stations whose last `probe fopr` found no FOPR workbook (empty if never probed)
+    pub async fn fopr_unavailable(&self) -> Result<Vec<String>, DbError> {
+        self.availability_repo.find_unavailable_station_ids().await
+    }
+
+    /// Download and import one gauge's FOPR workbook
+    pub async fn import_fopr(&self, station_id: &str) -> Result<ImportStats, FoprImportError> {
+        self.fopr.import_fopr(station_id).await
+    }
+
+    /// Rebuild every monthly summary
+    pub async fn recalculate<F>(&self, on_progress: F) -> Result<RecalcStats, DbError>
+    where
+        F: FnMut(usize, usize),
+    {
+        self.summary
+            .recalculate(&RecalcScope::default(), on_progress)
+            .await
+    }
+
+    /// Coverage of a gauge's readings; None when it has none
+    pub async fn station_coverage(
+        &self,
+        station_id: &str,
+    ) -> Result<Option<StationCoverage>, DbError> {
+        let rows = self.reading_repo.find_coverage(station_id).await?;
+        let (Some(first), Some(last)) = (
+            rows.iter().map(|r| r.first_reading).min(),
+            rows.iter().map(|r| r.last_reading).max(),
+        ) else {
+            return Ok(None);
+        };
+
+        let water_years: BTreeSet<i32> = rows
+            .iter()
+            .filter_map(|r| NaiveDate::from_ymd_opt(r.year, r.month as u32, 1))
+            .map(water_year_of)
+            .collect();
+        let data_sources: BTreeSet<String> = rows.iter().map(|r| r.data_source.clone()).collect();
+
+        Ok(Some(StationCoverage {
+            station_id: station_id.to_string(),
+            first_reading: first.date_naive(),
+            last_reading: last.date_naive(),
+            readings: rows.iter().map(|r| r.reading_count).sum(),
+            water_years: water_years.len(),
+            data_sources: data_sources.into_iter().collect(),
+        }))
+    }
+}
+
+/// Failure scraping or storing the gauge list
+#[derive(Debug, thiserror::Error)]
+pub enum DiscoveryError {
+    #[error("Failed to fetch gauge list: {0}")]
+    Fetch(#[from] FetchError),
+    #[error("Failed to store gauge list: {0}")]
+    Database(#[from] DbError),
+}
+
+/// Water year of a day (Oct 1 starts the next water year)
+pub fn water_year_of(date: NaiveDate) -> i32 {
+    if date.month() >= 10 {
+        date.year() + 1
+    } else {
+        date.year()
+    }
+}
+
+/// Water years in `first..=last` that some gauge's FOPR data doesn't reach
+///
+/// `last_readings` holds each gauge's latest reading after the FOPR stage (None when it
+/// has no readings at all). FOPR workbooks are published with a lag, so a gauge lacks
+/// every water year after the one holding its latest reading; a gauge without readings
+/// lacks the whole range.
+pub fn missing_water_years(last_readings: &[Option<NaiveDate>], first: i32, last: i32) -> Vec<i32> {
+    let earliest_gap = last_readings
+        .iter()
+        .map(|reading| reading.map_or(first, |date| water_year_of(date) + 1))
+        .min();
+
+    match earliest_gap {
+        Some(gap) => (gap.max(first)..=last).collect(),
+        None => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(y: i32, m: u32, d: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(y, m, d)
+    }
+
+    #[test]
+    fn test_water_year_of() {
+        assert_eq!(water_year_of(day(2023, 9, 30).unwrap()), 2023);
+        assert_eq!(water_year_of(day(2023, 10, 1).unwrap()), 2024);
+    }
+
+    #[test]
+    fn test_missing_water_years_after_latest_fopr_reading() {
+        // FOPR for one gauge ends in WY 2023, the other in WY 2024
+        let last = [day(2023, 6, 1), day(2024, 2, 15)];
+        assert_eq!(missing_water_years(&last, 2022, 2025), vec![2024, 2025]);
+
+        // Everything is covered
+        assert!(missing_water_years(&[day(2025, 9, 1)], 2022, 2025).is_empty());
+    }
+
+    #[test]
+    fn test_missing_water_years_without_readings_covers_whole_range() {
+        let last = [day(2024, 2, 15), None];
+        assert_eq!(
+            missing_water_years(&last, 2022, 2024),
+            vec![2022, 2023, 2024]
+        );
+
+        // Gaps before the range are clamped to it
+        assert_eq!(
+            missing_water_years(&[day(2010, 1, 1)], 2022, 2023),
+            vec![2022, 2023]
+        );
+        assert!(missing_water_years(&[], 2022, 2024).is_empty());
+    }
+}
@@ -0,0 +1,65 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+
+use crate::db::{CurrentCondition, CurrentConditionsRepository, DbError};
+use crate::services::gauge_service::GaugeFilterParams;
+use crate::services::ReadingService;
+
+/// Every gauge's current conditions, read from the precomputed table
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CurrentConditionsResponse {
+    #[schema(example = 352)]
+    pub total_gauges: usize,
+    /// Oldest refresh among the returned rows; null when there are none yet
+    #[schema(example = "2025-01-15T14:31:02Z")]
+    pub refreshed_at: Option<DateTime<Utc>>,
+    pub gauges: Vec<CurrentCondition>,
+}
+
+#[derive(Clone)]
+pub struct CurrentConditionsService {
+    repo: CurrentConditionsRepository,
+}
+
+impl CurrentConditionsService {
+    pub fn new(repo: CurrentConditionsRepository) -> Self {
+        Self { repo }
+    }
+
+    /// Rebuild the table for the water year containing `now`
+    ///
+    /// Called by the schedulers after each scrape; returns the number of gauges.
+    #[instrument(skip(self))]
+    pub async fn refresh(&self, now: DateTime<Utc>) -> Result<u64, DbError> {
+        let water_year = ReadingService::get_water_year(now);
+        let refreshed = self.repo.refresh(water_year, now).await?;
+        info!(
+            "Refreshed current conditions for {} gauges (WY {})",
+            refreshed, water_year
+        );
+        Ok(refreshed)
+    }
+
+    /// `refreshed_at` of the matching response, without reading the rows
+    pub async fn get_refreshed_at(
+        &self,
+        filter: &GaugeFilterParams,
+    ) -> Result<Option<DateTime<Utc>>, DbError> {
+        self.repo.find_refreshed_at(&filter.statuses()).await
+    }
+
+    pub async fn get_current(
+        &self,
+        filter: &GaugeFilterParams,
+    ) -> Result<CurrentConditionsResponse, DbError> {
+        let gauges = self.repo.find_by_status(&filter.statuses()).await?;
+
+        Ok(CurrentConditionsResponse {
+            total_gauges: gauges.len(),
+            refreshed_at: gauges.iter().map(|g| g.refreshed_at).min(),
+            gauges,
+        })
+    }
+}
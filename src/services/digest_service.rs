@@ -0,0 +1,282 @@
+use std::borrow::Cow;
+
+use lettre::message::Mailbox;
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument, warn};
+use utoipa::{IntoParams, ToSchema};
+use validator::{Validate, ValidationError};
+
+use crate::clock::{self, SharedClock};
+use crate::db::{DbError, DbPool, Digest, DigestRepository, DigestSubscription, User};
+use crate::digest::{DigestFrequency, DigestSender};
+use crate::notify;
+
+/// A digest subscription to create or replace
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+#[validate(schema(function = "validate_channels"))]
+pub struct DigestSubscriptionRequest {
+    pub frequency: DigestFrequency,
+    /// Where to email the digest
+    #[validate(custom(function = "validate_email"))]
+    #[schema(example = "jsmith@example.com")]
+    pub email: Option<String>,
+    /// Where to POST the digest as JSON; must not be a loopback, link-local or private host
+    #[validate(custom(function = "validate_webhook_url"))]
+    #[schema(example = "https://hooks.example.com/rain")]
+    pub webhook_url: Option<String>,
+}
+
+fn validate_channels(request: &DigestSubscriptionRequest) -> Result<(), ValidationError> {
+    if request.email.is_none() && request.webhook_url.is_none() {
+        return Err(ValidationError::new("channel")
+            .with_message(Cow::Borrowed("email or webhook_url is required")));
+    }
+    Ok(())
+}
+
+fn validate_email(email: &str) -> Result<(), ValidationError> {
+    if email.len() > 254 || email.parse::<Mailbox>().is_err() {
+        return Err(
+            ValidationError::new("email").with_message(Cow::Borrowed("must be an email address"))
+        );
+    }
+    Ok(())
+}
+
+fn validate_webhook_url(url: &str) -> Result<(), ValidationError> {
+    let parsed = reqwest::Url::parse(url)
+        .ok()
+        .filter(|u| url.len() <= 2000 && matches!(u.scheme(), "http" | "https"));
+    let Some(parsed) = parsed else {
+        return Err(
+            ValidationError::new("url").with_message(Cow::Borrowed("must be an http(s) URL"))
+        );
+    };
+    // Loopback, link-local and private hosts would let any user reach internal services
+    if !notify::is_public_host(&parsed) {
+        return Err(ValidationError::new("url").with_message(Cow::Borrowed(
+            "must not point to a private or local address",
+        )));
+    }
+    Ok(())
+}
+
+/// Digest preview options (used by API)
+#[derive(Debug, Clone, Deserialize, IntoParams, Validate)]
+pub struct DigestPreviewParams {
+    /// Period to summarize (daily or weekly; default daily)
+    #[serde(default = "default_preview_frequency")]
+    pub frequency: DigestFrequency,
+}
+
+fn default_preview_frequency() -> DigestFrequency {
+    DigestFrequency::Daily
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DigestError {
+    #[error("User {0} has no digest subscription")]
+    NotSubscribed(i64),
+
+    #[error("Email digests are not enabled")]
+    EmailDisabled,
+
+    #[error("Database error: {0}")]
+    Database(#[from] DbError),
+}
+
+/// Outcome of one digest run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DigestStats {
+    /// Subscriptions whose latest period had not been delivered
+    pub due: usize,
+    pub sent: usize,
+    /// Deliveries that failed; retried at the next run
+    pub failed: usize,
+}
+
+/// Daily and weekly rainfall digests for users' favorite gauges
+#[derive(Clone)]
+pub struct DigestService {
+    repo: DigestRepository,
+    sender: DigestSender,
+    clock: SharedClock,
+}
+
+impl DigestService {
+    /// Webhook delivery only until `with_sender` adds email
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        Self {
+            repo: DigestRepository::new(pool),
+            sender: DigestSender::default(),
+            clock: clock::system_clock(),
+        }
+    }
+
+    pub fn with_sender(mut self, sender: DigestSender) -> Self {
+        self.sender = sender;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub async fn subscription(&self, user: &User) -> Result<DigestSubscription, DigestError> {
+        self.repo
+            .find(user.id)
+            .await?
+            .ok_or(DigestError::NotSubscribed(user.id))
+    }
+
+    /// Create or replace the user's subscription (the request must already be validated)
+    #[instrument(skip(self, user, request), fields(user_id = user.id, frequency = ?request.frequency))]
+    pub async fn subscribe(
+        &self,
+        user: &User,
+        request: &DigestSubscriptionRequest,
+    ) -> Result<DigestSubscription, DigestError> {
+        if request.email.is_some() && !self.sender.email_enabled() {
+            return Err(DigestError::EmailDisabled);
+        }
+
+        let subscription = self
+            .repo
+            .upsert(
+                user.id,
+                request.frequency,
+                request.email.as_deref().map(str::trim),
+                request.webhook_url.as_deref(),
+            )
+            .await?;
+
+        info!(
+            "User {} subscribed to {} digests",
+            user.id,
+            request.frequency.as_str()
+        );
+        Ok(subscription)
+    }
+
+    /// Cancel the user's subscription; cancelling twice is not an error
+    #[instrument(skip(self, user), fields(user_id = user.id))]
+    pub async fn unsubscribe(&self, user: &User) -> Result<(), DigestError> {
+        if self.repo.delete(user.id).await? {
+            info!("User {} unsubscribed from digests", user.id);
+        }
+        Ok(())
+    }
+
+    /// The digest the user would receive for the latest complete period, without
+    /// sending it
+    pub async fn preview(
+        &self,
+        user: &User,
+        params: &DigestPreviewParams,
+    ) -> Result<Digest, DigestError> {
+        Ok(self.build(user.id, params.frequency).await?)
+    }
+
+    /// Deliver every subscription's latest complete period not delivered yet
+    #[instrument(skip(self))]
+    pub async fn send_due(&self) -> Result<DigestStats, DbError> {
+        let mut stats = DigestStats::default();
+        for frequency in [DigestFrequency::Daily, DigestFrequency::Weekly] {
+            let (_, last_day) = frequency.last_period(self.clock.now().date_naive());
+            let due = self.repo.find_due(frequency, last_day).await?;
+            stats.due += due.len();
+
+            for subscription in due {
+                let digest = self.build(subscription.user_id, frequency).await?;
+                let result = self
+                    .sender
+                    .deliver(
+                        &digest,
+                        subscription.webhook_url.as_deref(),
+                        subscription.email.as_deref(),
+                    )
+                    .await;
+                match result {
+                    Ok(()) => {
+                        self.repo
+                            .mark_sent(subscription.user_id, digest.last_day)
+                            .await?;
+                        stats.sent += 1;
+                    }
+                    Err(e) => {
+                        warn!(
+                            user_id = subscription.user_id,
+                            error = %e,
+                            "Digest delivery failed"
+                        );
+                        stats.failed += 1;
+                    }
+                }
+            }
+        }
+
+        if stats.due > 0 {
+            info!(
+                sent = stats.sent,
+                failed = stats.failed,
+                "Delivered rainfall digests"
+            );
+        }
+        Ok(stats)
+    }
+
+    async fn build(&self, user_id: i64, frequency: DigestFrequency) -> Result<Digest, DbError> {
+        let now = self.clock.now();
+        let (first_day, last_day) = frequency.last_period(now.date_naive());
+        let gauges = self.repo.find_gauges(user_id, first_day, last_day).await?;
+        let events = self.repo.find_events(user_id, first_day, last_day).await?;
+
+        Ok(Digest {
+            frequency,
+            first_day,
+            last_day,
+            gauges,
+            events,
+            generated_at: now,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(email: Option<&str>, webhook_url: Option<&str>) -> DigestSubscriptionRequest {
+        DigestSubscriptionRequest {
+            frequency: DigestFrequency::Weekly,
+            email: email.map(String::from),
+            webhook_url: webhook_url.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_subscription_request_rules() {
+        assert!(request(Some("jsmith@example.com"), None).validate().is_ok());
+        assert!(request(None, Some("https://hooks.example.com/rain"))
+            .validate()
+            .is_ok());
+
+        let errors = request(None, None).validate().unwrap_err();
+        assert!(errors.errors().contains_key("__all__"));
+        let errors = request(Some("not an address"), Some("ftp://example.com"))
+            .validate()
+            .unwrap_err();
+        assert!(errors.field_errors().contains_key("email"));
+        assert!(errors.field_errors().contains_key("webhook_url"));
+
+        for url in [
+            "http://169.254.169.254/latest/meta-data/",
+            "http://localhost:8080/hook",
+            "http://10.1.2.3/hook",
+        ] {
+            let errors = request(None, Some(url)).validate().unwrap_err();
+            assert!(errors.field_errors().contains_key("webhook_url"), "{url}");
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use serde::Serialize;
+use tracing::{debug, info, instrument, warn};
+
+use crate::db::{DbError, GaugeRepository};
+use crate::elevation::ElevationSampler;
+
+/// Outcome of one elevation run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ElevationStats {
+    /// Gauges sampled
+    pub examined: usize,
+    pub elevations_filled: usize,
+    /// Gauges outside the model's coverage (recorded, so they are not sampled again)
+    pub not_covered: usize,
+    /// Samples that failed (not recorded, so they are retried next run)
+    pub failed: usize,
+}
+
+/// Fills missing gauge elevations from a digital elevation model
+#[derive(Clone)]
+pub struct ElevationService {
+    gauge_repo: GaugeRepository,
+    sampler: ElevationSampler,
+    batch_size: usize,
+}
+
+impl ElevationService {
+    pub fn new(gauge_repo: GaugeRepository, sampler: ElevationSampler, batch_size: usize) -> Self {
+        Self {
+            gauge_repo,
+            sampler,
+            batch_size,
+        }
+    }
+
+    /// Sample the next batch of gauges with coordinates but no elevation
+    ///
+    /// Filled elevations are marked with the model as their elevation source.
+    #[instrument(skip(self))]
+    pub async fn enrich(&self) -> Result<ElevationStats, DbError> {
+        let candidates = self
+            .gauge_repo
+            .find_elevation_candidates(self.batch_size as i64)
+            .await?;
+        let source = self.sampler.source();
+        let mut stats = ElevationStats::default();
+
+        for (i, gauge) in candidates.iter().enumerate() {
+            if let (Some(interval), true) = (self.sampler.request_interval(), i > 0) {
+                tokio::time::sleep(interval).await;
+            }
+            stats.examined += 1;
+
+            let elevation_ft = match self.sampler.sample(gauge.latitude, gauge.longitude).await {
+                Ok(elevation_ft) => elevation_ft,
+                Err(e) => {
+                    warn!(station_id = %gauge.station_id, error = %e, "Elevation sampling failed");
+                    stats.failed += 1;
+                    continue;
+                }
+            };
+            match elevation_ft {
+                Some(_) => stats.elevations_filled += 1,
+                None => {
+                    debug!(station_id = %gauge.station_id, "Gauge is outside the elevation model");
+                    stats.not_covered += 1;
+                }
+            }
+
+            self.gauge_repo
+                .record_elevation_sample(&gauge.station_id, elevation_ft, &source)
+                .await?;
+        }
+
+        if stats.examined > 0 {
+            info!(
+                examined = stats.examined,
+                elevations_filled = stats.elevations_filled,
+                not_covered = stats.not_covered,
+                failed = stats.failed,
+                "Sampled gauge elevations"
+            );
+        }
+        Ok(stats)
+    }
+}
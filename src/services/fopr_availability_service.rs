@@ -0,0 +1,165 @@
+// FOPR availability probing
+//
+// Not every gauge has an FOPR workbook on the MCFCD site, and downloading one just to get
+// a 404 is slow. The prober sends rate-limited HEAD requests and records availability and
+// Last-Modified per gauge, so bulk imports can skip missing files and refreshes can tell
+// which files were republished.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::time::MissedTickBehavior;
+use tracing::{info, instrument, warn};
+use utoipa::IntoParams;
+use validator::Validate;
+
+use crate::db::{DbError, DbPool, FoprAvailability, FoprAvailabilityRepository, GaugeRepository};
+use crate::importers::downloader::McfcdDownloader;
+
+/// HEAD requests per second unless another rate is given
+pub const DEFAULT_PROBE_RATE: f64 = 2.0;
+
+// FOPR availability listing parameters (used by API)
+#[derive(Debug, Clone, Default, Deserialize, IntoParams, Validate)]
+pub struct FoprAvailabilityParams {
+    /// Only gauges whose file was (true) or wasn't (false) found
+    pub available: Option<bool>,
+    /// Only gauges whose availability or Last-Modified changed at or after this time (RFC 3339)
+    pub changed_since: Option<DateTime<Utc>>,
+}
+
+/// Result of probing one gauge
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProbeOutcome {
+    pub station_id: String,
+    /// None when the probe failed
+    pub available: Option<bool>,
+    pub http_status: Option<u16>,
+    pub last_modified: Option<DateTime<Utc>>,
+    /// Availability or Last-Modified differs from the previous probe (or first probe)
+    pub changed: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct FoprAvailabilityService {
+    repo: FoprAvailabilityRepository,
+    gauge_repo: GaugeRepository,
+    downloader: McfcdDownloader,
+    rate: f64,
+}
+
+impl FoprAvailabilityService {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        let pool = pool.into();
+        Self {
+            repo: FoprAvailabilityRepository::new(pool.clone()),
+            gauge_repo: GaugeRepository::new(pool),
+            downloader: McfcdDownloader::new(),
+            rate: DEFAULT_PROBE_RATE,
+        }
+    }
+
+    /// Use a custom downloader (primarily for testing with mock servers)
+    pub fn with_downloader(mut self, downloader: McfcdDownloader) -> Self {
+        self.downloader = downloader;
+        self
+    }
+
+    /// Set the maximum HEAD requests per second (non-positive values fall back to the default)
+    pub fn with_rate(mut self, requests_per_sec: f64) -> Self {
+        self.rate = if requests_per_sec > 0.0 {
+            requests_per_sec
+        } else {
+            DEFAULT_PROBE_RATE
+        };
+        self
+    }
+
+    /// Every station in the gauge list scrape or the gauges table
+    pub async fn known_station_ids(&self) -> Result<Vec<String>, DbError> {
+        Ok(self
+            .gauge_repo
+            .find_source_pairs()
+            .await?
+            .into_iter()
+            .map(|pair| pair.station_id)
+            .collect())
+    }
+
+    /// Probe each station's FOPR file, recording the results
+    ///
+    /// Requests are spaced to stay under the configured rate. A failed probe (network
+    /// error or 5xx) is reported but not recorded, so the previous result stands.
+    /// `on_result` is called after each station.
+    #[instrument(skip(self, station_ids, on_result), fields(count = station_ids.len()))]
+    pub async fn probe<F>(
+        &self,
+        station_ids: &[String],
+        mut on_result: F,
+    ) -> Result<Vec<ProbeOutcome>, DbError>
+    where
+        F: FnMut(&ProbeOutcome),
+    {
+        let mut ticker = tokio::time::interval(Duration::from_secs_f64(1.0 / self.rate));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        let mut outcomes = Vec::with_capacity(station_ids.len());
+        for station_id in station_ids {
+            ticker.tick().await;
+
+            let outcome = match self.downloader.head_fopr(station_id).await {
+                Ok(status) => {
+                    let changed = self.repo.record(station_id, &status).await?;
+                    ProbeOutcome {
+                        station_id: station_id.clone(),
+                        available: Some(status.available),
+                        http_status: Some(status.http_status),
+                        last_modified: status.last_modified,
+                        changed,
+                        error: None,
+                    }
+                }
+                Err(e) => {
+                    warn!(station_id = %station_id, error = %e, "FOPR probe failed");
+                    ProbeOutcome {
+                        station_id: station_id.clone(),
+                        available: None,
+                        http_status: None,
+                        last_modified: None,
+                        changed: false,
+                        error: Some(e.to_string()),
+                    }
+                }
+            };
+            on_result(&outcome);
+            outcomes.push(outcome);
+        }
+
+        info!(
+            probed = outcomes.len(),
+            available = outcomes
+                .iter()
+                .filter(|o| o.available == Some(true))
+                .count(),
+            "FOPR availability probe finished"
+        );
+        Ok(outcomes)
+    }
+
+    /// Recorded probe results, ordered by station
+    pub async fn list(
+        &self,
+        params: &FoprAvailabilityParams,
+    ) -> Result<Vec<FoprAvailability>, DbError> {
+        self.repo
+            .find_all(params.available, params.changed_since)
+            .await
+    }
+
+    /// Stations whose last probe found no FOPR file
+    pub async fn unavailable_station_ids(&self) -> Result<Vec<String>, DbError> {
+        self.repo.find_unavailable_station_ids().await
+    }
+}
@@ -1,20 +1,26 @@
-use chrono::{DateTime, NaiveDate, Utc};
-use sqlx::PgPool;
-use std::collections::HashSet;
 use std::io::Write;
 use std::time::Instant;
 use tracing::{debug, error, info, instrument, warn};
 
 use crate::db::fopr_import_job_repository::{FoprImportJobRepository, ImportStats};
-use crate::db::{DbError, GaugeRepository, MonthlyRainfallRepository, ReadingRepository};
+use crate::db::import_chunk_repository::ChunkedImport;
+use crate::db::{
+    ConflictPolicy, DbError, DbPool, GaugeRepository, ImportChunkRepository, QuarantineRepository,
+    SummaryViewRepository,
+};
 use crate::fopr::daily_data_parser::FoprDailyDataParser;
 use crate::fopr::metadata_parser::MetaStatsData;
+use crate::fopr::validation::ValidationBounds;
 use crate::importers::downloader::McfcdDownloader;
-use crate::importers::excel_importer::HistoricalReading;
+use crate::ingest_guard::{IngestLimits, Screened};
+use crate::station_id::{StationId, StationIdError};
 
 /// Error types for FOPR import operations
 #[derive(Debug, thiserror::Error)]
 pub enum FoprImportError {
+    #[error("Invalid station ID {0:?}: {1}")]
+    InvalidStationId(String, StationIdError),
+
     #[error("Download failed: {0}")]
     Download(String),
 
@@ -22,7 +28,7 @@ pub enum FoprImportError {
     Parse(String),
 
     #[error("Database error: {0}")]
-    Database(#[from] sqlx::Error),
+    Database(#[from] DbError),
 
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
@@ -34,36 +40,79 @@ pub enum FoprImportError {
     NoReadings,
 }
 
+impl From<sqlx::Error> for FoprImportError {
+    fn from(e: sqlx::Error) -> Self {
+        FoprImportError::Database(e.into())
+    }
+}
+
 /// Service for importing FOPR (Full Operational Period of Record) data
 #[derive(Clone)]
 pub struct FoprImportService {
     downloader: McfcdDownloader,
     gauge_repo: GaugeRepository,
-    reading_repo: ReadingRepository,
-    monthly_repo: MonthlyRainfallRepository,
+    chunk_repo: ImportChunkRepository,
+    quarantine_repo: QuarantineRepository,
     job_repo: FoprImportJobRepository,
+    view_repo: SummaryViewRepository,
+    validation_bounds: ValidationBounds,
+    ingest_limits: IngestLimits,
 }
 
 impl FoprImportService {
-    pub fn new(pool: PgPool) -> Self {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        let pool = pool.into();
         Self {
             gauge_repo: GaugeRepository::new(pool.clone()),
-            reading_repo: ReadingRepository::new(pool.clone()),
-            monthly_repo: MonthlyRainfallRepository::new(pool.clone()),
+            chunk_repo: ImportChunkRepository::new(pool.clone()).with_derived_cumulative(),
+            quarantine_repo: QuarantineRepository::new(pool.clone()),
             job_repo: FoprImportJobRepository::new(pool.clone()),
+            view_repo: SummaryViewRepository::new(pool.clone()),
             downloader: McfcdDownloader::new(),
+            validation_bounds: ValidationBounds::default(),
+            ingest_limits: IngestLimits::default(),
         }
     }
 
+    /// Use a custom downloader (mock servers, resumable downloads)
+    pub fn with_downloader(mut self, downloader: McfcdDownloader) -> Self {
+        self.downloader = downloader;
+        self
+    }
+
+    /// Override the bounds used to validate gauge metadata (defaults to MCFCD)
+    pub fn with_validation_bounds(mut self, bounds: ValidationBounds) -> Self {
+        self.validation_bounds = bounds;
+        self
+    }
+
+    /// Choose what happens to readings already stored for a gauge (defaults to Skip)
+    pub fn with_conflict_policy(mut self, policy: ConflictPolicy) -> Self {
+        self.chunk_repo = self.chunk_repo.with_conflict_policy(policy);
+        self
+    }
+
+    /// Override the limits daily totals are screened against (defaults to IngestLimits)
+    pub fn with_ingest_limits(mut self, limits: IngestLimits) -> Self {
+        self.ingest_limits = limits;
+        self
+    }
+
+    /// `data_source` of a gauge's FOPR readings
+    fn data_source(station_id: &str) -> String {
+        format!("fopr_import_{station_id}")
+    }
+
     /// Import FOPR data for a gauge
     ///
     /// This is the main business logic method that:
     /// 1. Downloads FOPR file
     /// 2. Parses metadata and upserts gauge
-    /// 3. Parses all year sheets
-    /// 4. Inserts readings with deduplication
-    /// 5. Recalculates monthly summaries
-    /// 6. Returns import statistics
+    /// 3. Parses all year sheets, quarantining totals the ingest guards reject
+    /// 4. Commits the readings one month at a time, each with its monthly summary and
+    ///    the water year cumulative totals FOPR files don't carry, skipping months a
+    ///    previous run already committed
+    /// 5. Returns import statistics
     #[instrument(skip(self), fields(station_id = %station_id))]
     pub async fn import_fopr(&self, station_id: &str) -> Result<ImportStats, FoprImportError> {
         let start_time = Instant::now();
@@ -71,6 +120,8 @@ impl FoprImportService {
             station_id = %station_id,
             "Starting FOPR import"
         );
+        let station_id = &StationId::parse(station_id)
+            .map_err(|e| FoprImportError::InvalidStationId(station_id.to_string(), e))?;
 
         // 1. Download FOPR file
         debug!(
@@ -129,14 +180,15 @@ impl FoprImportService {
                 FoprImportError::Parse(format!("Failed to read Meta_Stats sheet: {e:?}"))
             })?;
 
-            MetaStatsData::from_worksheet_range(&range).map_err(|e| {
-                error!(
-                    station_id = %station_id,
-                    error = %e,
-                    "Metadata parse error"
-                );
-                FoprImportError::Parse(format!("Metadata parse error: {e}"))
-            })?
+            MetaStatsData::from_worksheet_range_with_bounds(&range, &self.validation_bounds)
+                .map_err(|e| {
+                    error!(
+                        station_id = %station_id,
+                        error = %e,
+                        "Metadata parse error"
+                    );
+                    FoprImportError::Parse(format!("Metadata parse error: {e}"))
+                })?
         };
 
         info!(
@@ -149,13 +201,12 @@ impl FoprImportService {
             .upsert_gauge_metadata(&metadata)
             .await
             .map_err(|e| {
-                let DbError::SqlxError(sqlx_err) = e;
                 error!(
                     station_id = %station_id,
-                    error = %sqlx_err,
+                    error = %e,
                     "Failed to upsert gauge metadata"
                 );
-                FoprImportError::Database(sqlx_err)
+                FoprImportError::Database(e)
             })?;
 
         debug!(
@@ -168,8 +219,12 @@ impl FoprImportService {
             station_id = %station_id,
             "Parsing daily rainfall data from year sheets"
         );
-        let data_parser = FoprDailyDataParser::new(&temp_path, station_id);
-        let readings = data_parser.parse_all_years().map_err(|e| {
+        let data_parser = FoprDailyDataParser::new(&temp_path, station_id.clone())
+            .with_limits(self.ingest_limits);
+        let Screened {
+            accepted: readings,
+            quarantined,
+        } = data_parser.screen_all_years().map_err(|e| {
             error!(
                 station_id = %station_id,
                 error = %e,
@@ -178,6 +233,11 @@ impl FoprImportService {
             FoprImportError::Parse(format!("Daily data parse error: {e}"))
         })?;
 
+        // Rejected totals are recorded even when nothing else in the file is usable
+        self.quarantine_repo
+            .insert(&Self::data_source(station_id), &quarantined)
+            .await?;
+
         if readings.is_empty() {
             warn!(
                 station_id = %station_id,
@@ -192,25 +252,38 @@ impl FoprImportService {
             "Parsed daily rainfall readings"
         );
 
-        // 5. Insert readings with deduplication
-        let (inserted, duplicates, months_to_recalc) =
-            self.insert_readings_bulk(station_id, readings).await?;
+        // 5. Commit month by month, with summaries and cumulative totals
+        let ChunkedImport {
+            inserted,
+            updated,
+            duplicates,
+            months_committed,
+            months_resumed,
+        } = self
+            .chunk_repo
+            .import_by_month(station_id, &Self::data_source(station_id), &readings)
+            .await
+            .map_err(|e| {
+                error!(
+                    station_id = %station_id,
+                    error = %e,
+                    "Failed to import readings"
+                );
+                FoprImportError::Database(e)
+            })?;
 
         info!(
             station_id = %station_id,
             inserted = inserted,
+            updated = updated,
             duplicates = duplicates,
+            months_committed = months_committed.len(),
+            months_resumed = months_resumed,
             "Inserted readings into database"
         );
-
-        // 6. Recalculate monthly summaries
-        if !months_to_recalc.is_empty() {
-            debug!(
-                station_id = %station_id,
-                month_count = months_to_recalc.len(),
-                "Recalculating monthly summaries"
-            );
-            self.recalculate_monthly_summaries(&months_to_recalc)
+        if !months_committed.is_empty() {
+            self.view_repo
+                .mark_stale(&format!("FOPR readings imported for {station_id}"))
                 .await?;
         }
 
@@ -225,6 +298,8 @@ impl FoprImportService {
         // Build statistics
         let stats = ImportStats {
             readings_imported: inserted as i64,
+            readings_updated: updated as i64,
+            readings_quarantined: quarantined.len() as i64,
             start_date: None, // Could calculate from readings if needed
             end_date: None,
             duration_secs: duration.as_secs_f64(),
@@ -233,126 +308,9 @@ impl FoprImportService {
         Ok(stats)
     }
 
-    /// Insert readings in bulk with deduplication
-    ///
-    /// Business logic: Creates data_source identifier and coordinates with repository.
-    /// Returns: (inserted_count, duplicate_count, months_to_recalculate)
-    #[instrument(skip(self, readings), fields(station_id = %station_id, reading_count = readings.len()))]
-    #[allow(clippy::type_complexity)]
-    async fn insert_readings_bulk(
-        &self,
-        station_id: &str,
-        readings: Vec<HistoricalReading>,
-    ) -> Result<(usize, usize, HashSet<(String, i32, u32)>), FoprImportError> {
-        debug!(
-            station_id = %station_id,
-            reading_count = readings.len(),
-            "Inserting readings into database"
-        );
-
-        // Business logic: Create data_source identifier for FOPR imports
-        let data_source = format!("fopr_import_{station_id}");
-
-        // Delegate to repository for data access
-        let (inserted, duplicates, affected_months) = self
-            .reading_repo
-            .bulk_insert_historical_readings(station_id, &data_source, &readings)
-            .await
-            .map_err(|e| {
-                let DbError::SqlxError(sqlx_err) = e;
-                error!(
-                    station_id = %station_id,
-                    error = %sqlx_err,
-                    "Failed to insert readings"
-                );
-                FoprImportError::Database(sqlx_err)
-            })?;
-
-        // Business logic: Convert Vec<(year, month)> to HashSet<(station_id, year, month)>
-        // for coordination with MonthlyRainfallRepository
-        let months_to_recalculate: HashSet<(String, i32, u32)> = affected_months
-            .into_iter()
-            .map(|(year, month)| (station_id.to_string(), year, month))
-            .collect();
-
-        debug!(
-            station_id = %station_id,
-            inserted = inserted,
-            duplicates = duplicates,
-            affected_months = months_to_recalculate.len(),
-            "Bulk insert complete"
-        );
-
-        Ok((inserted, duplicates, months_to_recalculate))
-    }
-
-    /// Recalculate monthly summaries for affected station-months
-    #[instrument(skip(self, months), fields(month_count = months.len()))]
-    async fn recalculate_monthly_summaries(
-        &self,
-        months: &HashSet<(String, i32, u32)>,
-    ) -> Result<(), FoprImportError> {
-        debug!(
-            month_count = months.len(),
-            "Recalculating monthly summaries"
-        );
-
-        for (station_id, year, month) in months {
-            // Business logic: Calculate month boundaries (first day of month to first day of next month)
-            let (start, end) = Self::month_date_range(*year, *month);
-
-            self.monthly_repo
-                .recalculate_monthly_summary(station_id, *year, *month as i32, start, end)
-                .await
-                .map_err(|e| {
-                    let DbError::SqlxError(sqlx_err) = e;
-                    error!(
-                        station_id = %station_id,
-                        year = year,
-                        month = month,
-                        error = %sqlx_err,
-                        "Failed to recalculate monthly summary"
-                    );
-                    FoprImportError::Database(sqlx_err)
-                })?;
-        }
-
-        debug!("Monthly summaries recalculated");
-        Ok(())
-    }
-
-    /// Calculate date range for a specific month
-    ///
-    /// Returns (start_of_month, start_of_next_month)
-    fn month_date_range(year: i32, month: u32) -> (DateTime<Utc>, DateTime<Utc>) {
-        let start_date = NaiveDate::from_ymd_opt(year, month, 1)
-            .unwrap()
-            .and_hms_opt(0, 0, 0)
-            .unwrap();
-
-        let (next_year, next_month) = if month == 12 {
-            (year + 1, 1)
-        } else {
-            (year, month + 1)
-        };
-
-        let end_date = NaiveDate::from_ymd_opt(next_year, next_month, 1)
-            .unwrap()
-            .and_hms_opt(0, 0, 0)
-            .unwrap();
-
-        let start_dt = DateTime::<Utc>::from_naive_utc_and_offset(start_date, Utc);
-        let end_dt = DateTime::<Utc>::from_naive_utc_and_offset(end_date, Utc);
-
-        (start_dt, end_dt)
-    }
-
     /// Check if FOPR import job already exists for a station
     #[instrument(skip(self), fields(station_id = %station_id))]
     pub async fn job_exists(&self, station_id: &str) -> Result<bool, FoprImportError> {
-        self.job_repo.job_exists(station_id).await.map_err(|e| {
-            let DbError::SqlxError(sqlx_err) = e;
-            FoprImportError::Database(sqlx_err)
-        })
+        Ok(self.job_repo.job_exists(station_id).await?)
     }
 }
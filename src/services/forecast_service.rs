@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use tracing::{debug, instrument};
+use utoipa::ToSchema;
+
+use crate::clock::{self, SharedClock};
+use crate::db::{DbError, GaugeRepository, WaterYearTotal};
+use crate::forecast::{
+    ForecastConfig, ForecastError, ForecastPeriod, GridCell, GridForecast, NwsClient,
+    FORECAST_HOURS,
+};
+use crate::politeness::Politeness;
+use crate::services::ReadingService;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ForecastServiceError {
+    #[error("Gauge {0} has no coordinates")]
+    NoCoordinates(String),
+
+    #[error(transparent)]
+    Forecast(#[from] ForecastError),
+
+    #[error(transparent)]
+    Database(#[from] DbError),
+}
+
+/// Observed water-year-to-date rainfall with the forecast for the next 72 hours
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct GaugeForecast {
+    #[schema(example = "59700")]
+    pub station_id: String,
+    /// NWS grid cell containing the gauge
+    pub grid: GridCell,
+    /// When NWS last updated the cell's forecast
+    pub forecast_updated_at: Option<DateTime<Utc>>,
+    /// When the forecast was fetched; forecasts are reused per grid cell for
+    /// FORECAST_CACHE_MINUTES
+    pub fetched_at: DateTime<Utc>,
+    /// Start of the forecast window (now)
+    pub start: DateTime<Utc>,
+    /// End of the forecast window, 72 hours after `start`
+    pub end: DateTime<Utc>,
+    /// Observed rainfall for the current water year
+    pub observed: WaterYearTotal,
+    /// Forecast precipitation over the window
+    #[schema(example = 0.42)]
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub forecast_inches: f64,
+    /// Observed water-year total plus the forecast
+    #[schema(example = 2.78)]
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub projected_water_year_inches: f64,
+    /// Forecast intervals within the window, oldest first
+    pub periods: Vec<ForecastPeriod>,
+}
+
+#[derive(Clone)]
+struct CachedForecast {
+    fetched_at: DateTime<Utc>,
+    forecast: Arc<GridForecast>,
+}
+
+/// Combines observed rainfall with NWS gridpoint precipitation forecasts
+#[derive(Clone)]
+pub struct ForecastService {
+    client: NwsClient,
+    gauge_repo: GaugeRepository,
+    reading_service: ReadingService,
+    cache_ttl: Duration,
+    /// Grid cell of each looked-up point, keyed by rounded coordinates
+    cells: Arc<Mutex<HashMap<String, GridCell>>>,
+    forecasts: Arc<Mutex<HashMap<GridCell, CachedForecast>>>,
+    clock: SharedClock,
+}
+
+impl ForecastService {
+    pub fn new(
+        config: &ForecastConfig,
+        gauge_repo: GaugeRepository,
+        reading_service: ReadingService,
+    ) -> Self {
+        Self {
+            client: NwsClient::new(&config.api_url),
+            gauge_repo,
+            reading_service,
+            cache_ttl: Duration::minutes(config.cache_minutes as i64),
+            cells: Arc::default(),
+            forecasts: Arc::default(),
+            clock: clock::system_clock(),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Send NWS requests through the shared `politeness`
+    pub fn with_politeness(mut self, politeness: Politeness) -> Self {
+        self.client = self.client.with_politeness(politeness);
+        self
+    }
+
+    /// Observed water-year rainfall and the next 72 hours of forecast for a gauge
+    ///
+    /// Returns None when the gauge does not exist.
+    #[instrument(skip(self))]
+    pub async fn get_gauge_forecast(
+        &self,
+        station_id: &str,
+    ) -> Result<Option<GaugeForecast>, ForecastServiceError> {
+        let Some(gauge) = self.gauge_repo.find_detail(station_id).await? else {
+            return Ok(None);
+        };
+        let (Some(latitude), Some(longitude)) = (gauge.latitude, gauge.longitude) else {
+            return Err(ForecastServiceError::NoCoordinates(station_id.to_string()));
+        };
+
+        let cell = self.grid_cell(latitude, longitude).await?;
+        let cached = self.forecast(&cell).await?;
+        let water_year = self.reading_service.current_water_year();
+        let observed = self
+            .reading_service
+            .get_water_year_total(station_id, water_year)
+            .await?;
+
+        let start = self.clock.now();
+        let end = start + Duration::hours(FORECAST_HOURS);
+        let periods = cached.forecast.window(start, end);
+        let forecast_inches: f64 = periods.iter().map(|p| p.inches).sum();
+
+        Ok(Some(GaugeForecast {
+            station_id: station_id.to_string(),
+            grid: cell,
+            forecast_updated_at: cached.forecast.updated_at,
+            fetched_at: cached.fetched_at,
+            start,
+            end,
+            projected_water_year_inches: observed.total_rainfall_inches + forecast_inches,
+            observed,
+            forecast_inches,
+            periods,
+        }))
+    }
+
+    /// The grid cell containing a point; cells never change, so lookups are kept
+    async fn grid_cell(&self, latitude: f64, longitude: f64) -> Result<GridCell, ForecastError> {
+        let key = format!("{latitude:.4},{longitude:.4}");
+        if let Some(cell) = self.cells.lock().unwrap().get(&key) {
+            return Ok(cell.clone());
+        }
+
+        let cell = self.client.grid_cell(latitude, longitude).await?;
+        self.cells.lock().unwrap().insert(key, cell.clone());
+        Ok(cell)
+    }
+
+    /// The cell's forecast, fetched again once the cached copy is older than the TTL
+    async fn forecast(&self, cell: &GridCell) -> Result<CachedForecast, ForecastError> {
+        let now = self.clock.now();
+        if let Some(cached) = self.forecasts.lock().unwrap().get(cell) {
+            if now - cached.fetched_at < self.cache_ttl {
+                debug!(?cell, "Using cached forecast");
+                return Ok(cached.clone());
+            }
+        }
+
+        let cached = CachedForecast {
+            fetched_at: now,
+            forecast: Arc::new(self.client.precipitation(cell).await?),
+        };
+        self.forecasts
+            .lock()
+            .unwrap()
+            .insert(cell.clone(), cached.clone());
+        Ok(cached)
+    }
+}
@@ -0,0 +1,427 @@
+// Bulk corrections to gauge metadata through a CSV round trip
+//
+// `export_csv` writes the editable columns of every gauge; an operator fixes names,
+// cities, or coordinates in a spreadsheet, and `plan` reads the file back, validates the
+// changed values, and diffs them against the database. Nothing is written until `apply`,
+// which updates every changed gauge in one transaction. Gauges left out of the file are
+// not touched.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display};
+use std::io::{Read, Write};
+
+use serde::Serialize;
+use tracing::{info, instrument};
+
+use crate::db::{DbError, DbPool, EditableGaugeMetadata, GaugeRepository};
+use crate::fopr::validation::ValidationBounds;
+
+/// Longest station name the gauges table accepts
+const MAX_NAME_LEN: usize = 255;
+
+/// Longest city or county the gauges table accepts
+const MAX_PLACE_LEN: usize = 100;
+
+#[derive(Debug, thiserror::Error)]
+pub enum GaugeEditError {
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Database error: {0}")]
+    Database(#[from] DbError),
+
+    #[error("{0} rows failed validation; no gauges were updated")]
+    Invalid(usize),
+}
+
+/// One changed column of a gauge
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+/// A gauge whose row in the file differs from the database
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GaugeEdit {
+    pub station_id: String,
+    pub changes: Vec<FieldChange>,
+    #[serde(skip)]
+    pub metadata: EditableGaugeMetadata,
+}
+
+/// A row that can't be applied
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GaugeEditIssue {
+    /// Line in the CSV file (the header is line 1)
+    pub line: u64,
+    pub station_id: Option<String>,
+    pub message: String,
+}
+
+/// Differences between an edited CSV file and the database
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct GaugeEditPlan {
+    pub rows_read: usize,
+    pub unchanged: usize,
+    pub edits: Vec<GaugeEdit>,
+    pub issues: Vec<GaugeEditIssue>,
+}
+
+impl GaugeEditPlan {
+    /// Build a plan from the file's rows, validating only the values that changed
+    ///
+    /// Values already in the database are never rejected, so a gauge with old
+    /// out-of-range coordinates doesn't block edits to its name.
+    pub fn build(
+        current: &[EditableGaugeMetadata],
+        rows: Vec<(u64, Result<EditableGaugeMetadata, csv::Error>)>,
+        bounds: &ValidationBounds,
+    ) -> Self {
+        let current: HashMap<&str, &EditableGaugeMetadata> = current
+            .iter()
+            .map(|gauge| (gauge.station_id.as_str(), gauge))
+            .collect();
+        let mut seen = HashSet::new();
+        let mut plan = GaugeEditPlan {
+            rows_read: rows.len(),
+            ..Default::default()
+        };
+
+        for (line, row) in rows {
+            let edited = match row {
+                Ok(edited) => edited,
+                Err(e) => {
+                    plan.issues.push(GaugeEditIssue {
+                        line,
+                        station_id: None,
+                        message: e.to_string(),
+                    });
+                    continue;
+                }
+            };
+            let issue = |message: String| GaugeEditIssue {
+                line,
+                station_id: Some(edited.station_id.clone()),
+                message,
+            };
+
+            if !seen.insert(edited.station_id.clone()) {
+                plan.issues
+                    .push(issue("station appears more than once".to_string()));
+                continue;
+            }
+            let Some(existing) = current.get(edited.station_id.as_str()) else {
+                plan.issues.push(issue("unknown station".to_string()));
+                continue;
+            };
+
+            let changes = diff(existing, &edited);
+            if changes.is_empty() {
+                plan.unchanged += 1;
+                continue;
+            }
+            let problems = validate(&changes, &edited, bounds);
+            if problems.is_empty() {
+                plan.edits.push(GaugeEdit {
+                    station_id: edited.station_id.clone(),
+                    changes,
+                    metadata: edited,
+                });
+            } else {
+                plan.issues.extend(problems.into_iter().map(issue));
+            }
+        }
+        plan
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl Display for GaugeEditPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |value: &Option<String>| match value {
+            Some(value) => format!("{value:?}"),
+            None => "(empty)".to_string(),
+        };
+        for edit in &self.edits {
+            writeln!(f, "{}:", edit.station_id)?;
+            for change in &edit.changes {
+                writeln!(
+                    f,
+                    "  {}: {} → {}",
+                    change.field,
+                    show(&change.from),
+                    show(&change.to)
+                )?;
+            }
+        }
+        for issue in &self.issues {
+            match &issue.station_id {
+                Some(station_id) => writeln!(
+                    f,
+                    "✗ line {} ({}): {}",
+                    issue.line, station_id, issue.message
+                )?,
+                None => writeln!(f, "✗ line {}: {}", issue.line, issue.message)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Columns that differ between the stored and edited metadata, in CSV order
+fn diff(current: &EditableGaugeMetadata, edited: &EditableGaugeMetadata) -> Vec<FieldChange> {
+    fn field<T: PartialEq + ToString>(
+        changes: &mut Vec<FieldChange>,
+        name: &'static str,
+        from: &Option<T>,
+        to: &Option<T>,
+    ) {
+        if from != to {
+            changes.push(FieldChange {
+                field: name,
+                from: from.as_ref().map(T::to_string),
+                to: to.as_ref().map(T::to_string),
+            });
+        }
+    }
+
+    let mut changes = Vec::new();
+    field(
+        &mut changes,
+        "station_name",
+        &current.station_name,
+        &edited.station_name,
+    );
+    field(&mut changes, "city", &current.city, &edited.city);
+    field(&mut changes, "county", &current.county, &edited.county);
+    field(
+        &mut changes,
+        "location_description",
+        &current.location_description,
+        &edited.location_description,
+    );
+    field(
+        &mut changes,
+        "latitude",
+        &current.latitude,
+        &edited.latitude,
+    );
+    field(
+        &mut changes,
+        "longitude",
+        &current.longitude,
+        &edited.longitude,
+    );
+    field(
+        &mut changes,
+        "elevation_ft",
+        &current.elevation_ft,
+        &edited.elevation_ft,
+    );
+    changes
+}
+
+/// Problems with the changed values of one row
+fn validate(
+    changes: &[FieldChange],
+    edited: &EditableGaugeMetadata,
+    bounds: &ValidationBounds,
+) -> Vec<String> {
+    let mut problems = Vec::new();
+    for change in changes {
+        let problem = match change.field {
+            "station_name" => match &edited.station_name {
+                None => Some("station_name must not be empty".to_string()),
+                Some(name) if name.chars().count() > MAX_NAME_LEN => Some(format!(
+                    "station_name is longer than {MAX_NAME_LEN} characters"
+                )),
+                Some(_) => None,
+            },
+            "city" | "county" => change
+                .to
+                .as_ref()
+                .filter(|value| value.chars().count() > MAX_PLACE_LEN)
+                .map(|_| format!("{} is longer than {MAX_PLACE_LEN} characters", change.field)),
+            "latitude" => edited
+                .latitude
+                .and_then(|lat| bounds.validate_latitude(lat).err())
+                .map(|e| e.to_string()),
+            "longitude" => edited
+                .longitude
+                .and_then(|lon| bounds.validate_longitude(lon).err())
+                .map(|e| e.to_string()),
+            "elevation_ft" => edited
+                .elevation_ft
+                .and_then(|elev| bounds.validate_elevation(elev).err())
+                .map(|e| e.to_string()),
+            _ => None,
+        };
+        problems.extend(problem);
+    }
+    if edited.latitude.is_some() != edited.longitude.is_some() {
+        problems.push("latitude and longitude must both be set or both be empty".to_string());
+    }
+    problems
+}
+
+/// Service backing the `gauges export` and `gauges import` commands
+#[derive(Clone)]
+pub struct GaugeEditService {
+    gauge_repo: GaugeRepository,
+    validation_bounds: ValidationBounds,
+}
+
+impl GaugeEditService {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        Self {
+            gauge_repo: GaugeRepository::new(pool),
+            validation_bounds: ValidationBounds::default(),
+        }
+    }
+
+    /// Override the bounds edited coordinates and elevations are checked against
+    pub fn with_validation_bounds(mut self, bounds: ValidationBounds) -> Self {
+        self.validation_bounds = bounds;
+        self
+    }
+
+    /// Write every gauge's editable metadata as CSV; returns the number of rows
+    #[instrument(skip(self, writer))]
+    pub async fn export_csv<W: Write>(&self, writer: W) -> Result<usize, GaugeEditError> {
+        let gauges = self.gauge_repo.find_editable_metadata().await?;
+        let mut csv = csv::Writer::from_writer(writer);
+        for gauge in &gauges {
+            csv.serialize(gauge)?;
+        }
+        csv.flush()?;
+        Ok(gauges.len())
+    }
+
+    /// Read an edited CSV file and compare it with the database
+    #[instrument(skip(self, reader))]
+    pub async fn plan<R: Read>(&self, reader: R) -> Result<GaugeEditPlan, GaugeEditError> {
+        let mut csv = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(reader);
+        let rows = csv
+            .deserialize()
+            .enumerate()
+            .map(|(i, row)| (i as u64 + 2, row))
+            .collect();
+        let current = self.gauge_repo.find_editable_metadata().await?;
+
+        Ok(GaugeEditPlan::build(
+            &current,
+            rows,
+            &self.validation_bounds,
+        ))
+    }
+
+    /// Write a plan's edits; refuses a plan with validation issues
+    #[instrument(skip(self, plan), fields(edits = plan.edits.len()))]
+    pub async fn apply(&self, plan: &GaugeEditPlan) -> Result<u64, GaugeEditError> {
+        if !plan.is_valid() {
+            return Err(GaugeEditError::Invalid(plan.issues.len()));
+        }
+        let edits: Vec<EditableGaugeMetadata> = plan
+            .edits
+            .iter()
+            .map(|edit| edit.metadata.clone())
+            .collect();
+        let updated = self.gauge_repo.update_editable_metadata(&edits).await?;
+        info!("Updated metadata for {} gauges", updated);
+        Ok(updated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gauge(station_id: &str, name: &str, latitude: f64) -> EditableGaugeMetadata {
+        EditableGaugeMetadata {
+            station_id: station_id.to_string(),
+            station_name: Some(name.to_string()),
+            city: Some("Phoenix".to_string()),
+            county: Some("Maricopa".to_string()),
+            location_description: None,
+            latitude: Some(latitude),
+            longitude: Some(-112.0),
+            elevation_ft: Some(1100),
+        }
+    }
+
+    fn rows(csv_text: &str) -> Vec<(u64, Result<EditableGaugeMetadata, csv::Error>)> {
+        csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(csv_text.as_bytes())
+            .deserialize()
+            .enumerate()
+            .map(|(i, row)| (i as u64 + 2, row))
+            .collect()
+    }
+
+    const HEADER: &str =
+        "station_id,station_name,city,county,location_description,latitude,longitude,elevation_ft\n";
+
+    #[test]
+    fn test_build_plan_diffs_changed_columns() {
+        let current = [
+            gauge("59700", "Dreamy Draw", 33.5),
+            gauge("4695", "Cave Creek", 33.8),
+        ];
+        let plan = GaugeEditPlan::build(
+            &current,
+            rows(&format!(
+                "{HEADER}59700,Dreamy Draw Dam,Phoenix,Maricopa,,33.55,-112.0,1100\n\
+                 4695,Cave Creek,Phoenix,Maricopa,,33.8,-112.0,1100\n"
+            )),
+            &ValidationBounds::default(),
+        );
+
+        assert!(plan.is_valid());
+        assert_eq!(plan.rows_read, 2);
+        assert_eq!(plan.unchanged, 1);
+        assert_eq!(plan.edits.len(), 1);
+        let fields: Vec<&str> = plan.edits[0].changes.iter().map(|c| c.field).collect();
+        assert_eq!(fields, ["station_name", "latitude"]);
+        assert_eq!(plan.edits[0].changes[1].from.as_deref(), Some("33.5"));
+        assert_eq!(plan.edits[0].changes[1].to.as_deref(), Some("33.55"));
+    }
+
+    #[test]
+    fn test_build_plan_reports_invalid_rows() {
+        let current = [gauge("59700", "Dreamy Draw", 33.5)];
+        let plan = GaugeEditPlan::build(
+            &current,
+            rows(&format!(
+                "{HEADER}59700,,Phoenix,Maricopa,,45.0,-112.0,1100\n\
+                 99999,New Gauge,Phoenix,Maricopa,,33.5,-112.0,1100\n\
+                 59700,Dreamy Draw,Phoenix,Maricopa,,33.5,-112.0,1100\n\
+                 4695,Cave Creek,Phoenix,Maricopa,,north,-112.0,1100\n"
+            )),
+            &ValidationBounds::default(),
+        );
+
+        assert!(plan.edits.is_empty());
+        let lines: Vec<(u64, &str)> = plan
+            .issues
+            .iter()
+            .map(|issue| (issue.line, issue.message.as_str()))
+            .collect();
+        assert_eq!(lines[0], (2, "station_name must not be empty"));
+        assert_eq!(lines[1].0, 2);
+        assert!(lines[1].1.contains("Latitude 45"));
+        assert_eq!(lines[2], (3, "unknown station"));
+        assert_eq!(lines[3], (4, "station appears more than once"));
+        assert_eq!(lines[4].0, 5);
+    }
+}
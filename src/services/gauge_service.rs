@@ -1,17 +1,34 @@
 use crate::db::fopr_import_job_repository::FoprImportJobRepository;
-use crate::db::{DbError, GaugeRepository, GaugeSummary};
+use crate::db::{
+    DbError, GaugeDetail, GaugeMetadata, GaugeRepository, GaugeSourcePair, GaugeStatus,
+    GaugeStatusChange, GaugeSummary, WaterYearTotal,
+};
 use crate::gauge_list_fetcher::GaugeSummary as FetchedGauge;
-use chrono::{DateTime, Utc};
-use serde::Serialize;
-use tracing::{debug, error, info, instrument};
+use crate::pagination::{self, PageInfo};
+use crate::services::ReadingService;
+use crate::station_id::StationId;
+use crate::tiles::{encode_gauge_tile, TileCoord};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use tracing::{debug, error, info, instrument, warn};
 use utoipa::{IntoParams, ToSchema};
+use validator::{Validate, ValidationError};
+
+/// Largest page a client may request
+pub const MAX_PAGE_SIZE: u32 = 100;
 
 // Pagination types (used by API)
-#[derive(Debug, Clone, serde::Deserialize, IntoParams)]
+#[derive(Debug, Clone, serde::Deserialize, IntoParams, Validate)]
 pub struct PaginationParams {
+    /// Page number, starting at 1
     #[serde(default = "default_page")]
+    #[validate(range(min = 1, message = "must be at least 1"))]
     pub page: u32,
+    /// Gauges per page (default 50, max 100)
     #[serde(default = "default_page_size")]
+    #[validate(range(min = 1, max = MAX_PAGE_SIZE, message = "must be between 1 and 100"))]
     pub page_size: u32,
 }
 
@@ -25,24 +42,305 @@ fn default_page_size() -> u32 {
 
 impl PaginationParams {
     pub fn offset(&self) -> i64 {
-        ((self.page - 1) * self.page_size) as i64
+        pagination::offset(self.page, self.page_size)
     }
 
     pub fn limit(&self) -> i64 {
-        self.page_size.min(100) as i64
+        self.page_size.min(MAX_PAGE_SIZE) as i64
+    }
+}
+
+/// Default number of days a gauge may be missing from the gauge list before it is
+/// marked Inactive
+pub const DEFAULT_INACTIVE_AFTER_DAYS: u32 = 14;
+
+/// `changed_by` for transitions made by the status detection job
+pub const AUTO_DETECTION: &str = "auto_detection";
+
+/// `changed_by` for transitions made through the admin API
+pub const ADMIN: &str = "admin";
+
+// Gauge list filters (used by API)
+#[derive(Debug, Clone, Default, Deserialize, IntoParams, Validate)]
+pub struct GaugeFilterParams {
+    /// Only gauges with this status; by default Decommissioned gauges are hidden
+    #[param(inline)]
+    pub status: Option<GaugeStatus>,
+}
+
+impl GaugeFilterParams {
+    /// Status values to include
+    pub fn statuses(&self) -> Vec<String> {
+        match self.status {
+            Some(status) => vec![status.as_str().to_string()],
+            None => vec![
+                GaugeStatus::Active.as_str().to_string(),
+                GaugeStatus::Inactive.as_str().to_string(),
+            ],
+        }
+    }
+}
+
+/// Most station IDs a single `ids` request may list
+pub const MAX_IDS: usize = 100;
+
+// Gauge lookup by ID for hydrating a saved list (used by API)
+#[derive(Debug, Clone, Default, Deserialize, IntoParams, Validate)]
+pub struct GaugeIdsParams {
+    /// Comma-separated station IDs (max 100); only these gauges are returned, in the
+    /// order given, on a single page
+    #[validate(custom(function = "validate_ids"))]
+    #[param(example = "41200,40800,59700")]
+    pub ids: Option<String>,
+}
+
+impl GaugeIdsParams {
+    /// Normalized IDs in request order, without repeats
+    pub fn station_ids(&self) -> Option<Vec<String>> {
+        let ids = self.ids.as_deref()?;
+        let mut station_ids: Vec<String> = Vec::new();
+        for id in ids
+            .split(',')
+            .filter_map(|part| StationId::parse(part).ok())
+        {
+            if !station_ids.iter().any(|seen| seen == id.as_str()) {
+                station_ids.push(id.to_string());
+            }
+        }
+        Some(station_ids)
     }
 }
 
+fn validate_ids(ids: &str) -> Result<(), ValidationError> {
+    let parts: Vec<&str> = ids.split(',').collect();
+    if parts.len() > MAX_IDS {
+        return Err(ValidationError::new("ids")
+            .with_message(Cow::Borrowed("must list at most 100 station IDs")));
+    }
+    match parts.iter().find(|part| StationId::parse(part).is_err()) {
+        Some(part) => Err(ValidationError::new("ids")
+            .with_message(Cow::Owned(format!("{:?} is not a station ID", part.trim())))),
+        None => Ok(()),
+    }
+}
+
+/// `include` value that adds each gauge's water-year-to-date total
+pub const INCLUDE_WYTD: &str = "wytd";
+
+// Optional extras for the gauge list (used by API)
+#[derive(Debug, Clone, Default, Deserialize, IntoParams, Validate)]
+pub struct GaugeIncludeParams {
+    /// Comma-separated extras to add to each gauge; `wytd` adds `water_year_to_date`
+    #[validate(custom(function = "validate_include"))]
+    #[param(example = "wytd")]
+    pub include: Option<String>,
+}
+
+impl GaugeIncludeParams {
+    fn includes(&self, name: &str) -> bool {
+        self.include
+            .as_deref()
+            .is_some_and(|include| include.split(',').any(|part| part.trim() == name))
+    }
+
+    pub fn wytd(&self) -> bool {
+        self.includes(INCLUDE_WYTD)
+    }
+}
+
+fn validate_include(include: &str) -> Result<(), ValidationError> {
+    if include.split(',').all(|part| part.trim() == INCLUDE_WYTD) {
+        Ok(())
+    } else {
+        Err(ValidationError::new("include").with_message(Cow::Borrowed("supported values: wytd")))
+    }
+}
+
+/// Requested status transition
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct GaugeStatusUpdate {
+    pub status: GaugeStatus,
+    /// Date the new status took effect (default: today, UTC)
+    #[schema(example = "2025-01-02")]
+    pub effective_date: Option<NaiveDate>,
+    #[schema(example = "Site removed for road widening")]
+    pub reason: Option<String>,
+}
+
+/// Why a status change was refused
+#[derive(Debug, thiserror::Error)]
+pub enum GaugeStatusError {
+    #[error("Gauge not found: {0}")]
+    GaugeNotFound(String),
+
+    #[error("Cannot change gauge status from {from} to {to}")]
+    InvalidTransition { from: String, to: String },
+
+    #[error("Database error: {0}")]
+    Database(#[from] DbError),
+}
+
+/// Transitions made by one run of status detection
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StatusDetectionStats {
+    pub deactivated: usize,
+    pub reactivated: usize,
+}
+
+/// One page of gauges plus pagination metadata
 #[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct GaugeListResponse {
-    pub total_gauges: usize,
-    pub page: u32,
-    pub page_size: u32,
-    pub total_pages: u32,
-    pub has_next_page: bool,
-    pub has_prev_page: bool,
+    pub pagination: PageInfo,
+    /// Most recent scrape among the gauges on this page
+    #[schema(example = "2025-01-15T14:30:00Z")]
     pub last_scraped_at: Option<DateTime<Utc>>,
-    pub gauges: Vec<GaugeSummary>,
+    pub gauges: Vec<GaugeListItem>,
+}
+
+/// A gauge in the list, plus any extras requested with `include`
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct GaugeListItem {
+    #[serde(flatten)]
+    pub gauge: GaugeSummary,
+    /// Current water year's total so far; only with `include=wytd`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub water_year_to_date: Option<WaterYearTotal>,
+}
+
+/// How a gauge differs between the gauge list scrape and FOPR metadata
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GaugeMismatchKind {
+    /// Scraped gauge with no row in gauges (FOPR import pending or failed)
+    MissingMetadata,
+    /// Gauge with metadata that the latest scrape did not list
+    MissingFromGaugeList,
+    NameMismatch,
+    CityMismatch,
+    ElevationMismatch,
+}
+
+/// One difference found by reconciliation
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct GaugeMismatch {
+    #[schema(example = "59700")]
+    pub station_id: String,
+    pub kind: GaugeMismatchKind,
+    /// Value in gauge_summaries (gauge list scrape)
+    #[schema(example = "Aztec Park")]
+    pub gauge_list_value: Option<String>,
+    /// Value in gauges (FOPR metadata)
+    #[schema(example = "Aztec Park Basin")]
+    pub metadata_value: Option<String>,
+}
+
+/// Differences between gauge_summaries and gauges
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct GaugeReconciliationReport {
+    pub generated_at: DateTime<Utc>,
+    /// Stations in the latest gauge list scrape
+    #[schema(example = 352)]
+    pub gauge_list_count: usize,
+    /// Stations with FOPR metadata
+    #[schema(example = 349)]
+    pub metadata_count: usize,
+    /// Stations in both tables with no differences
+    #[schema(example = 340)]
+    pub matched_count: usize,
+    /// Ordered by station ID
+    pub mismatches: Vec<GaugeMismatch>,
+}
+
+impl GaugeReconciliationReport {
+    /// Compare both sides of every station
+    ///
+    /// Names and cities are compared ignoring case and repeated whitespace; a value
+    /// missing on either side is not a mismatch.
+    pub fn from_pairs(pairs: &[GaugeSourcePair], generated_at: DateTime<Utc>) -> Self {
+        let mut mismatches = Vec::new();
+        let mut matched_count = 0;
+
+        for pair in pairs {
+            let mismatch = |kind, gauge_list_value: Option<String>, metadata_value| GaugeMismatch {
+                station_id: pair.station_id.clone(),
+                kind,
+                gauge_list_value,
+                metadata_value,
+            };
+
+            match (pair.in_gauge_list, pair.has_metadata) {
+                (true, false) => {
+                    mismatches.push(mismatch(
+                        GaugeMismatchKind::MissingMetadata,
+                        pair.summary_name.clone(),
+                        None,
+                    ));
+                    continue;
+                }
+                (false, _) => {
+                    mismatches.push(mismatch(
+                        GaugeMismatchKind::MissingFromGaugeList,
+                        None,
+                        pair.metadata_name.clone(),
+                    ));
+                    continue;
+                }
+                (true, true) => {}
+            }
+
+            let before = mismatches.len();
+            if differs(&pair.summary_name, &pair.metadata_name) {
+                mismatches.push(mismatch(
+                    GaugeMismatchKind::NameMismatch,
+                    pair.summary_name.clone(),
+                    pair.metadata_name.clone(),
+                ));
+            }
+            if differs(&pair.summary_city, &pair.metadata_city) {
+                mismatches.push(mismatch(
+                    GaugeMismatchKind::CityMismatch,
+                    pair.summary_city.clone(),
+                    pair.metadata_city.clone(),
+                ));
+            }
+            if let (Some(scraped), Some(metadata)) =
+                (pair.summary_elevation_ft, pair.metadata_elevation_ft)
+            {
+                if scraped != metadata {
+                    mismatches.push(mismatch(
+                        GaugeMismatchKind::ElevationMismatch,
+                        Some(scraped.to_string()),
+                        Some(metadata.to_string()),
+                    ));
+                }
+            }
+            if mismatches.len() == before {
+                matched_count += 1;
+            }
+        }
+
+        Self {
+            generated_at,
+            gauge_list_count: pairs.iter().filter(|p| p.in_gauge_list).count(),
+            metadata_count: pairs.iter().filter(|p| p.has_metadata).count(),
+            matched_count,
+            mismatches,
+        }
+    }
+}
+
+/// True when both values are present and differ beyond case and spacing
+fn differs(a: &Option<String>, b: &Option<String>) -> bool {
+    let normalize = |s: &str| {
+        s.split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase()
+    };
+    match (a, b) {
+        (Some(a), Some(b)) => normalize(a) != normalize(b),
+        _ => false,
+    }
 }
 
 #[derive(Clone)]
@@ -63,38 +361,317 @@ impl GaugeService {
     pub async fn get_gauges_paginated(
         &self,
         params: &PaginationParams,
+        filter: &GaugeFilterParams,
+        include: &GaugeIncludeParams,
     ) -> Result<GaugeListResponse, DbError> {
         // Get data from repository
-        let total_gauges = self.gauge_repo.count().await?;
+        let statuses = filter.statuses();
+        let total_gauges = self.gauge_repo.count_by_status(&statuses).await?;
         let gauges = self
             .gauge_repo
-            .find_paginated(params.offset(), params.limit())
+            .find_paginated_by_status(&statuses, params.offset(), params.limit())
             .await?;
 
-        // Calculate pagination metadata (business logic)
-        let total_pages = ((total_gauges as f64) / (params.page_size as f64)).ceil() as u32;
-        let has_next_page = params.page < total_pages;
-        let has_prev_page = params.page > 1;
+        let pagination = PageInfo::new(params.page, params.page_size, total_gauges as u64);
+
+        self.list_response(pagination, gauges, include).await
+    }
 
+    /// The requested gauges, in request order, as a single page
+    ///
+    /// IDs with no gauge are left out. Gauges of any status are returned unless `filter`
+    /// names one, so a saved list still shows its decommissioned gauges.
+    pub async fn get_gauges_by_ids(
+        &self,
+        station_ids: &[String],
+        filter: &GaugeFilterParams,
+        include: &GaugeIncludeParams,
+    ) -> Result<GaugeListResponse, DbError> {
+        let statuses = match filter.status {
+            Some(_) => filter.statuses(),
+            None => [
+                GaugeStatus::Active,
+                GaugeStatus::Inactive,
+                GaugeStatus::Decommissioned,
+            ]
+            .iter()
+            .map(|status| status.as_str().to_string())
+            .collect(),
+        };
+        let mut found: HashMap<String, GaugeSummary> = self
+            .gauge_repo
+            .find_by_station_ids(station_ids, &statuses)
+            .await?
+            .into_iter()
+            .map(|gauge| (gauge.station_id.clone(), gauge))
+            .collect();
+        let gauges: Vec<GaugeSummary> = station_ids
+            .iter()
+            .filter_map(|station_id| found.remove(station_id))
+            .collect();
+
+        let pagination = PageInfo::single(gauges.len() as u64);
+        self.list_response(pagination, gauges, include).await
+    }
+
+    async fn list_response(
+        &self,
+        pagination: PageInfo,
+        gauges: Vec<GaugeSummary>,
+        include: &GaugeIncludeParams,
+    ) -> Result<GaugeListResponse, DbError> {
         let last_scraped_at = gauges.iter().map(|g| g.last_scraped_at).max();
 
+        let mut water_year_to_date = if include.wytd() {
+            self.water_year_to_date(&gauges, Utc::now()).await?
+        } else {
+            HashMap::new()
+        };
+        let gauges = gauges
+            .into_iter()
+            .map(|gauge| GaugeListItem {
+                water_year_to_date: water_year_to_date.remove(&gauge.station_id),
+                gauge,
+            })
+            .collect();
+
         Ok(GaugeListResponse {
-            total_gauges,
-            page: params.page,
-            page_size: params.page_size,
-            total_pages,
-            has_next_page,
-            has_prev_page,
+            pagination,
             last_scraped_at,
             gauges,
         })
     }
 
+    /// Water-year-to-date totals for a page of gauges, keyed by station ID
+    ///
+    /// Completed months come from monthly summaries; the current month is summed from
+    /// raw readings, since its summary may lag behind the latest scrape.
+    async fn water_year_to_date(
+        &self,
+        gauges: &[GaugeSummary],
+        now: DateTime<Utc>,
+    ) -> Result<HashMap<String, WaterYearTotal>, DbError> {
+        let water_year = ReadingService::get_water_year(now);
+        let station_ids: Vec<String> = gauges.iter().map(|g| g.station_id.clone()).collect();
+        let rows = self
+            .gauge_repo
+            .find_water_year_to_date(&station_ids, water_year, now.year(), now.month())
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                (
+                    row.station_id,
+                    WaterYearTotal {
+                        water_year,
+                        total_rainfall_inches: row.total_rainfall_inches,
+                        total_readings: row.total_readings,
+                        as_of: row.as_of,
+                    },
+                )
+            })
+            .collect())
+    }
+
     /// Get single gauge by ID
     pub async fn get_gauge_by_id(&self, station_id: &str) -> Result<Option<GaugeSummary>, DbError> {
         self.gauge_repo.find_by_id(station_id).await
     }
 
+    /// Get a gauge merged from the gauge list scrape and FOPR metadata
+    pub async fn get_gauge_detail(&self, station_id: &str) -> Result<Option<GaugeDetail>, DbError> {
+        self.gauge_repo.find_detail(station_id).await
+    }
+
+    /// Get FOPR metadata and statistics (None if the gauge was never imported)
+    pub async fn get_gauge_metadata(
+        &self,
+        station_id: &str,
+    ) -> Result<Option<GaugeMetadata>, DbError> {
+        self.gauge_repo.find_metadata(station_id).await
+    }
+
+    /// Apply a status transition and record it in the status history
+    #[instrument(skip(self, update), fields(status = ?update.status))]
+    pub async fn change_status(
+        &self,
+        station_id: &str,
+        update: &GaugeStatusUpdate,
+        changed_by: &str,
+    ) -> Result<GaugeStatusChange, GaugeStatusError> {
+        let current = self
+            .gauge_repo
+            .find_status(station_id)
+            .await?
+            .ok_or_else(|| GaugeStatusError::GaugeNotFound(station_id.to_string()))?;
+
+        let invalid = || GaugeStatusError::InvalidTransition {
+            from: current.clone(),
+            to: update.status.as_str().to_string(),
+        };
+        let from = GaugeStatus::parse(&current).ok_or_else(invalid)?;
+        if !from.can_transition_to(update.status) {
+            return Err(invalid());
+        }
+
+        let effective_date = update
+            .effective_date
+            .unwrap_or_else(|| Utc::now().date_naive());
+        let change = self
+            .gauge_repo
+            .change_status(
+                station_id,
+                from.as_str(),
+                update.status.as_str(),
+                effective_date,
+                update.reason.as_deref(),
+                changed_by,
+            )
+            .await?
+            // Status changed between the read and the update
+            .ok_or_else(invalid)?;
+
+        info!(
+            station_id = %station_id,
+            from = from.as_str(),
+            to = update.status.as_str(),
+            changed_by = changed_by,
+            "Gauge status changed"
+        );
+        Ok(change)
+    }
+
+    /// Status transitions for a gauge, newest first
+    pub async fn get_status_history(
+        &self,
+        station_id: &str,
+    ) -> Result<Vec<GaugeStatusChange>, DbError> {
+        self.gauge_repo.find_status_history(station_id).await
+    }
+
+    /// Mark gauges missing from the gauge list for `inactive_after_days` as Inactive
+    ///
+    /// Gauges this job deactivated are made Active again once they reappear. The
+    /// effective date of a deactivation is the last day the gauge was seen. Gauges made
+    /// Inactive or Decommissioned by an admin are left alone.
+    #[instrument(skip(self))]
+    pub async fn detect_status_changes(
+        &self,
+        inactive_after_days: u32,
+    ) -> Result<StatusDetectionStats, DbError> {
+        let now = Utc::now();
+        let cutoff = now - chrono::Duration::days(inactive_after_days as i64);
+        let mut stats = StatusDetectionStats::default();
+
+        for gauge in self.gauge_repo.find_active_not_seen_since(cutoff).await? {
+            let reason = format!("Not in gauge list for {inactive_after_days} days");
+            let changed = self
+                .gauge_repo
+                .change_status(
+                    &gauge.station_id,
+                    GaugeStatus::Active.as_str(),
+                    GaugeStatus::Inactive.as_str(),
+                    gauge.last_scraped_at.date_naive(),
+                    Some(&reason),
+                    AUTO_DETECTION,
+                )
+                .await?;
+            if changed.is_some() {
+                warn!(
+                    station_id = %gauge.station_id,
+                    last_seen = %gauge.last_scraped_at,
+                    "Gauge missing from gauge list, marked Inactive"
+                );
+                stats.deactivated += 1;
+            }
+        }
+
+        // Anything scraped after the cutoff is back in the list
+        for gauge in self
+            .gauge_repo
+            .find_inactive_seen_since(cutoff, AUTO_DETECTION)
+            .await?
+        {
+            let changed = self
+                .gauge_repo
+                .change_status(
+                    &gauge.station_id,
+                    GaugeStatus::Inactive.as_str(),
+                    GaugeStatus::Active.as_str(),
+                    gauge.last_scraped_at.date_naive(),
+                    Some("Reappeared in gauge list"),
+                    AUTO_DETECTION,
+                )
+                .await?;
+            if changed.is_some() {
+                info!(
+                    station_id = %gauge.station_id,
+                    "Gauge reappeared in gauge list, marked Active"
+                );
+                stats.reactivated += 1;
+            }
+        }
+
+        Ok(stats)
+    }
+
+    /// Compare gauge_summaries against gauges without changing either
+    #[instrument(skip(self))]
+    pub async fn reconciliation_report(&self) -> Result<GaugeReconciliationReport, DbError> {
+        let pairs = self.gauge_repo.find_source_pairs().await?;
+        Ok(GaugeReconciliationReport::from_pairs(&pairs, Utc::now()))
+    }
+
+    /// Reconciliation job: backfill missing metadata, then report what still differs
+    ///
+    /// Empty name/city/elevation columns in gauges are filled from the scrape. Conflicting
+    /// values are only reported, since neither source is authoritative for them.
+    #[instrument(skip(self))]
+    pub async fn reconcile(&self) -> Result<GaugeReconciliationReport, DbError> {
+        let backfilled = self.gauge_repo.backfill_metadata_from_summaries().await?;
+        if backfilled > 0 {
+            info!(
+                gauges_backfilled = backfilled,
+                "Backfilled gauge metadata from gauge list"
+            );
+        }
+
+        let report = self.reconciliation_report().await?;
+        if report.mismatches.is_empty() {
+            debug!(
+                matched = report.matched_count,
+                "Gauge tables are reconciled"
+            );
+        } else {
+            warn!(
+                mismatches = report.mismatches.len(),
+                matched = report.matched_count,
+                "Gauge list and gauge metadata disagree"
+            );
+        }
+        Ok(report)
+    }
+
+    /// Render a vector tile of gauges with current rainfall totals
+    ///
+    /// Returns an empty body when no gauges fall inside the tile.
+    #[instrument(skip(self))]
+    pub async fn get_gauge_tile(&self, tile: TileCoord) -> Result<Vec<u8>, DbError> {
+        let bounds = tile.bounds();
+        let points = self
+            .gauge_repo
+            .find_map_points(
+                bounds.min_lat,
+                bounds.max_lat,
+                bounds.min_lon,
+                bounds.max_lon,
+            )
+            .await?;
+
+        Ok(encode_gauge_tile(tile, &points))
+    }
+
     /// Handle discovery of a new gauge from scraper
     ///
     /// This method is called when the gauge list scraper discovers a gauge.
@@ -133,8 +710,22 @@ impl GaugeService {
             "New gauge discovered, checking for existing import job"
         );
 
-        // Check if import job already exists
-        let job_exists = self.job_repo.job_exists(station_id).await?;
+        // Check if import job already exists. Backends without the FOPR import queue
+        // (SQLite) register the gauge from its list entry instead.
+        let job_exists = match self.job_repo.job_exists(station_id).await {
+            Err(DbError::Unsupported(backend)) => {
+                let registered = self.gauge_repo.register_from_list(gauge_summary).await?;
+                if registered {
+                    info!(
+                        station_id = %station_id,
+                        backend,
+                        "Registered gauge from gauge list (FOPR import unavailable)"
+                    );
+                }
+                return Ok(false);
+            }
+            result => result?,
+        };
 
         if job_exists {
             debug!(
@@ -196,3 +787,138 @@ impl GaugeService {
             })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(station_id: &str, in_gauge_list: bool, has_metadata: bool) -> GaugeSourcePair {
+        GaugeSourcePair {
+            station_id: station_id.to_string(),
+            in_gauge_list,
+            has_metadata,
+            summary_name: in_gauge_list.then(|| "Aztec Park".to_string()),
+            metadata_name: has_metadata.then(|| "Aztec Park".to_string()),
+            summary_city: in_gauge_list.then(|| "Scottsdale".to_string()),
+            metadata_city: has_metadata.then(|| "Scottsdale".to_string()),
+            summary_elevation_ft: in_gauge_list.then_some(1465),
+            metadata_elevation_ft: has_metadata.then_some(1465),
+        }
+    }
+
+    #[test]
+    fn test_status_transitions() {
+        use GaugeStatus::*;
+        assert!(Active.can_transition_to(Inactive));
+        assert!(Active.can_transition_to(Decommissioned));
+        assert!(Inactive.can_transition_to(Active));
+        assert!(Decommissioned.can_transition_to(Active));
+        assert!(!Decommissioned.can_transition_to(Inactive));
+        assert!(!Active.can_transition_to(Active));
+    }
+
+    #[test]
+    fn test_include_params() {
+        let include = |value: &str| GaugeIncludeParams {
+            include: Some(value.to_string()),
+        };
+
+        assert!(!GaugeIncludeParams::default().wytd());
+        assert!(GaugeIncludeParams::default().validate().is_ok());
+        assert!(include("wytd").wytd());
+        assert!(include("wytd, wytd").validate().is_ok());
+        assert!(include("wytd,normals").validate().is_err());
+        assert!(include("").validate().is_err());
+    }
+
+    #[test]
+    fn test_default_filter_hides_decommissioned() {
+        assert_eq!(
+            GaugeFilterParams::default().statuses(),
+            vec!["Active", "Inactive"]
+        );
+        let filter = GaugeFilterParams {
+            status: Some(GaugeStatus::Decommissioned),
+        };
+        assert_eq!(filter.statuses(), vec!["Decommissioned"]);
+    }
+
+    #[test]
+    fn test_ids_params() {
+        let ids = |value: &str| GaugeIdsParams {
+            ids: Some(value.to_string()),
+        };
+
+        assert_eq!(GaugeIdsParams::default().station_ids(), None);
+        assert_eq!(
+            ids("41200, 040800,59700,41200").station_ids(),
+            Some(vec![
+                "41200".to_string(),
+                "40800".to_string(),
+                "59700".to_string()
+            ])
+        );
+        assert!(ids("41200,40800").validate().is_ok());
+        assert!(ids("41200,not an id").validate().is_err());
+        assert!(ids("").validate().is_err());
+
+        let too_many: Vec<String> = (0..=MAX_IDS).map(|i| (10000 + i).to_string()).collect();
+        assert!(ids(&too_many.join(",")).validate().is_err());
+    }
+
+    #[test]
+    fn test_report_counts_matched_and_missing() {
+        let pairs = [
+            pair("1000", true, true),
+            pair("2000", true, false),
+            pair("3000", false, true),
+        ];
+        let report = GaugeReconciliationReport::from_pairs(&pairs, Utc::now());
+
+        assert_eq!(report.gauge_list_count, 2);
+        assert_eq!(report.metadata_count, 2);
+        assert_eq!(report.matched_count, 1);
+        let kinds: Vec<_> = report.mismatches.iter().map(|m| m.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                GaugeMismatchKind::MissingMetadata,
+                GaugeMismatchKind::MissingFromGaugeList
+            ]
+        );
+    }
+
+    #[test]
+    fn test_report_field_mismatches() {
+        let mut drifted = pair("1000", true, true);
+        drifted.metadata_name = Some("Aztec Park Basin".to_string());
+        drifted.metadata_city = None;
+        drifted.metadata_elevation_ft = Some(1470);
+
+        let report = GaugeReconciliationReport::from_pairs(&[drifted], Utc::now());
+
+        assert_eq!(report.matched_count, 0);
+        assert_eq!(report.mismatches.len(), 2);
+        assert_eq!(report.mismatches[0].kind, GaugeMismatchKind::NameMismatch);
+        assert_eq!(
+            report.mismatches[0].metadata_value.as_deref(),
+            Some("Aztec Park Basin")
+        );
+        assert_eq!(
+            report.mismatches[1].kind,
+            GaugeMismatchKind::ElevationMismatch
+        );
+        assert_eq!(
+            report.mismatches[1].gauge_list_value.as_deref(),
+            Some("1465")
+        );
+    }
+
+    #[test]
+    fn test_differs_ignores_case_and_spacing() {
+        let some = |s: &str| Some(s.to_string());
+        assert!(!differs(&some("Aztec  Park"), &some("aztec park ")));
+        assert!(differs(&some("Aztec Park"), &some("Aztec Pk")));
+        assert!(!differs(&some("Aztec Park"), &None));
+    }
+}
@@ -0,0 +1,97 @@
+use serde::Serialize;
+use tracing::{debug, info, instrument, warn};
+
+use crate::db::{DbError, GaugeRepository};
+use crate::geocode::ReverseGeocoder;
+
+/// Outcome of one geocoding run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct GeocodeStats {
+    /// Gauges looked up
+    pub examined: usize,
+    pub cities_filled: usize,
+    pub places_filled: usize,
+    /// Gauges the provider could not name (recorded, so they are not looked up again)
+    pub not_found: usize,
+    /// Lookups that failed (not recorded, so they are retried next run)
+    pub failed: usize,
+}
+
+/// Enriches gauges missing a city with reverse geocoded places
+#[derive(Clone)]
+pub struct GeocodeService {
+    gauge_repo: GaugeRepository,
+    geocoder: ReverseGeocoder,
+    batch_size: usize,
+}
+
+impl GeocodeService {
+    pub fn new(gauge_repo: GaugeRepository, geocoder: ReverseGeocoder, batch_size: usize) -> Self {
+        Self {
+            gauge_repo,
+            geocoder,
+            batch_size,
+        }
+    }
+
+    /// Look up the next batch of gauges missing a city or nearest place
+    ///
+    /// Existing cities are kept; each gauge looked up is marked with the provider as its
+    /// geocode source.
+    #[instrument(skip(self))]
+    pub async fn enrich(&self) -> Result<GeocodeStats, DbError> {
+        let candidates = self
+            .gauge_repo
+            .find_geocode_candidates(self.batch_size as i64)
+            .await?;
+        let source = self.geocoder.source();
+        let mut stats = GeocodeStats::default();
+
+        for (i, gauge) in candidates.iter().enumerate() {
+            if let (Some(interval), true) = (self.geocoder.request_interval(), i > 0) {
+                tokio::time::sleep(interval).await;
+            }
+            stats.examined += 1;
+
+            let place = match self.geocoder.reverse(gauge.latitude, gauge.longitude).await {
+                Ok(place) => place.unwrap_or_default(),
+                Err(e) => {
+                    warn!(station_id = %gauge.station_id, error = %e, "Reverse geocoding failed");
+                    stats.failed += 1;
+                    continue;
+                }
+            };
+            if place.city.is_none() && place.nearest_place.is_none() {
+                debug!(station_id = %gauge.station_id, "No place found for gauge");
+                stats.not_found += 1;
+            }
+            if gauge.city.is_none() && place.city.is_some() {
+                stats.cities_filled += 1;
+            }
+            if place.nearest_place.is_some() {
+                stats.places_filled += 1;
+            }
+
+            self.gauge_repo
+                .record_geocode(
+                    &gauge.station_id,
+                    place.city.as_deref(),
+                    place.nearest_place.as_deref(),
+                    &source,
+                )
+                .await?;
+        }
+
+        if stats.examined > 0 {
+            info!(
+                examined = stats.examined,
+                cities_filled = stats.cities_filled,
+                places_filled = stats.places_filled,
+                not_found = stats.not_found,
+                failed = stats.failed,
+                "Reverse geocoded gauges"
+            );
+        }
+        Ok(stats)
+    }
+}
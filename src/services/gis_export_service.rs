@@ -0,0 +1,68 @@
+use std::path::Path;
+
+use tracing::{info, instrument};
+
+use crate::db::{DbPool, ForecastZoneRepository, GaugeRepository};
+use crate::gis::{self, GisError, GisFormat, GisOutput, ZoneFeature};
+use crate::zones::ZonePolygon;
+
+/// Exports the gauge network, and optionally forecast zones, as GIS layers
+#[derive(Clone)]
+pub struct GisExportService {
+    gauge_repo: GaugeRepository,
+    zone_repo: ForecastZoneRepository,
+}
+
+impl GisExportService {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        let pool = pool.into();
+        Self {
+            gauge_repo: GaugeRepository::new(pool.clone()),
+            zone_repo: ForecastZoneRepository::new(pool),
+        }
+    }
+
+    /// Write every gauge with coordinates to `path`
+    ///
+    /// With `with_zones`, gauges carry their assigned forecast zone and the zone polygons
+    /// are written as a second layer.
+    #[instrument(skip(self))]
+    pub async fn export(
+        &self,
+        format: GisFormat,
+        path: &Path,
+        with_zones: bool,
+    ) -> Result<GisOutput, GisError> {
+        let gauges = self.gauge_repo.find_gis_gauges().await?;
+        let zones = if with_zones {
+            Some(self.load_zones().await?)
+        } else {
+            None
+        };
+
+        let output = gis::write(format, path, &gauges, zones.as_deref()).await?;
+        info!(
+            gauges = output.gauges,
+            zones = output.zones,
+            "Exported gauge network for GIS"
+        );
+        Ok(output)
+    }
+
+    async fn load_zones(&self) -> Result<Vec<ZoneFeature>, GisError> {
+        self.zone_repo
+            .find_all()
+            .await?
+            .into_iter()
+            .map(|record| {
+                let zone_id = record.zone_id.clone();
+                let zone = ZonePolygon::from_geometry(record.zone_id, record.name, record.geometry)
+                    .map_err(|message| GisError::Geometry { zone_id, message })?;
+                Ok(ZoneFeature {
+                    zone,
+                    gauge_count: record.gauge_count,
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,351 @@
+use std::collections::{BTreeMap, HashSet};
+use std::io::Write;
+
+use serde::Serialize;
+use tracing::{debug, instrument, warn};
+use utoipa::ToSchema;
+
+use crate::db::{
+    ConflictPolicy, DbError, DbPool, GaugeRepository, ImportChunkRepository,
+    MonthlyRainfallRepository, QuarantineRepository, Reading, ReadingRepository,
+    SummaryDiscrepancy, SummaryViewRepository,
+};
+use crate::importers::downloader::{DownloadError, McfcdDownloader};
+use crate::importers::excel_importer::{self, ExcelImporter, HistoricalReading};
+use crate::ingest_guard::{QuarantinedReading, Screened};
+use crate::services::SummaryService;
+use crate::station_id::StationId;
+use crate::utils;
+
+/// Error types for historical (water year Excel) import operations
+#[derive(Debug, thiserror::Error)]
+pub enum HistoricalImportError {
+    #[error("Download failed: {0}")]
+    Download(String),
+
+    #[error("No water year {0} file is published")]
+    NotPublished(i32),
+
+    #[error("Parse failed: {0}")]
+    Parse(String),
+
+    #[error("Database error: {0}")]
+    Database(#[from] DbError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Result of inserting one station's readings
+#[derive(Debug, Clone, Default)]
+pub struct StationImportResult {
+    pub inserted: usize,
+    /// Stored readings overwritten (ConflictPolicy::Update only)
+    pub updated: usize,
+    pub duplicates: usize,
+    /// Station-months committed (readings and summary) by this run
+    pub affected_months: HashSet<(String, i32, u32)>,
+    /// Months an earlier run already committed with the same readings
+    pub months_resumed: usize,
+    /// True when the station is not in the gauges table and was skipped
+    pub skipped: bool,
+}
+
+/// Gauges listed in a water year Excel file
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WaterYearGauges {
+    #[schema(example = 2019)]
+    pub water_year: i32,
+    pub gauge_count: usize,
+    /// Ordered by station ID
+    pub gauges: Vec<WaterYearGauge>,
+}
+
+/// A gauge column in a water year Excel file
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WaterYearGauge {
+    #[schema(example = "59700")]
+    pub station_id: String,
+    /// Month sheets listing the gauge, in water year order (OCT through SEP)
+    #[schema(example = json!(["OCT", "NOV", "DEC"]))]
+    pub months: Vec<String>,
+    /// Whether the gauge has metadata in the gauges table; imports skip gauges without it
+    pub registered: bool,
+}
+
+/// Service backing the historical import CLI
+///
+/// Handles MCFCD water year Excel files (pcp_WY_YYYY.xlsx) plus the maintenance
+/// operations run around imports: summary recalculation, verification, and export.
+#[derive(Clone)]
+pub struct HistoricalImportService {
+    downloader: McfcdDownloader,
+    gauge_repo: GaugeRepository,
+    reading_repo: ReadingRepository,
+    chunk_repo: ImportChunkRepository,
+    quarantine_repo: QuarantineRepository,
+    monthly_repo: MonthlyRainfallRepository,
+    summary: SummaryService,
+    view_repo: SummaryViewRepository,
+}
+
+impl HistoricalImportService {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        let pool = pool.into();
+        Self {
+            gauge_repo: GaugeRepository::new(pool.clone()),
+            reading_repo: ReadingRepository::new(pool.clone()),
+            chunk_repo: ImportChunkRepository::new(pool.clone()),
+            quarantine_repo: QuarantineRepository::new(pool.clone()),
+            monthly_repo: MonthlyRainfallRepository::new(pool.clone()),
+            summary: SummaryService::new(MonthlyRainfallRepository::new(pool.clone())),
+            view_repo: SummaryViewRepository::new(pool),
+            downloader: McfcdDownloader::new(),
+        }
+    }
+
+    /// Choose what happens to readings already stored for a gauge (defaults to Skip)
+    pub fn with_conflict_policy(mut self, policy: ConflictPolicy) -> Self {
+        self.chunk_repo = self.chunk_repo.with_conflict_policy(policy);
+        self
+    }
+
+    /// Set how many stations `recalculate_months` rebuilds concurrently
+    pub fn with_recalc_concurrency(mut self, concurrency: usize) -> Self {
+        self.summary = self.summary.with_concurrency(concurrency);
+        self
+    }
+
+    /// Use a custom downloader (primarily for testing with mock servers)
+    pub fn with_downloader(mut self, downloader: McfcdDownloader) -> Self {
+        self.downloader = downloader;
+        self
+    }
+
+    /// Data source tag recorded on readings imported from a water year Excel file
+    pub fn excel_data_source(water_year: i32) -> String {
+        format!("excel_WY_{water_year}")
+    }
+
+    /// Download the water year Excel file from MCFCD
+    #[instrument(skip(self))]
+    pub async fn download_water_year(
+        &self,
+        water_year: i32,
+    ) -> Result<Vec<u8>, HistoricalImportError> {
+        self.downloader
+            .download_excel(water_year)
+            .await
+            .map_err(|e| match e {
+                DownloadError::NotFound(_) => HistoricalImportError::NotPublished(water_year),
+                e => HistoricalImportError::Download(e.to_string()),
+            })
+    }
+
+    /// Download a water year Excel file and list the gauges in its month sheets
+    #[instrument(skip(self))]
+    pub async fn discover_water_year_gauges(
+        &self,
+        water_year: i32,
+    ) -> Result<WaterYearGauges, HistoricalImportError> {
+        let bytes = self.download_water_year(water_year).await?;
+
+        // calamine needs a file path and parses synchronously
+        let discovered = tokio::task::spawn_blocking(move || {
+            let mut file = tempfile::NamedTempFile::new()?;
+            file.write_all(&bytes)?;
+            excel_importer::discover_gauges_from_water_year(&file.path().to_string_lossy())
+                .map_err(|e| HistoricalImportError::Parse(e.to_string()))
+        })
+        .await
+        .expect("gauge discovery task panicked")?;
+
+        let mut gauges = Vec::with_capacity(discovered.len());
+        for gauge in discovered {
+            gauges.push(WaterYearGauge {
+                registered: self.gauge_repo.gauge_exists(&gauge.station_id).await?,
+                station_id: gauge.station_id.into_string(),
+                months: gauge.months,
+            });
+        }
+
+        Ok(WaterYearGauges {
+            water_year,
+            gauge_count: gauges.len(),
+            gauges,
+        })
+    }
+
+    /// Parse all monthly sheets of a water year Excel file, separating the values the
+    /// ingest guards reject
+    #[instrument(skip(self))]
+    pub fn parse_excel(
+        &self,
+        path: &str,
+        water_year: i32,
+    ) -> Result<Screened<HistoricalReading>, HistoricalImportError> {
+        ExcelImporter::new(path)
+            .screen_all_months(water_year)
+            .map_err(|e| HistoricalImportError::Parse(e.to_string()))
+    }
+
+    /// Record readings the ingest guards rejected, returning how many were new
+    #[instrument(skip(self, readings), fields(count = readings.len()))]
+    pub async fn quarantine_readings(
+        &self,
+        data_source: &str,
+        readings: &[QuarantinedReading],
+    ) -> Result<usize, HistoricalImportError> {
+        Ok(self.quarantine_repo.insert(data_source, readings).await?)
+    }
+
+    /// Group parsed readings by station, ordered by station ID
+    pub fn group_by_station(
+        readings: Vec<HistoricalReading>,
+    ) -> BTreeMap<StationId, Vec<HistoricalReading>> {
+        let mut by_station: BTreeMap<StationId, Vec<HistoricalReading>> = BTreeMap::new();
+        for reading in readings {
+            by_station
+                .entry(reading.station_id.clone())
+                .or_default()
+                .push(reading);
+        }
+        by_station
+    }
+
+    /// Insert one station's readings with deduplication
+    ///
+    /// Each month is committed with its monthly summary in one transaction, and months
+    /// an interrupted earlier run committed are skipped. Stations missing from the gauges
+    /// table are skipped (readings reference gauges by foreign key); run `import fopr`
+    /// for them first.
+    #[instrument(skip(self, readings), fields(station_id = %station_id, count = readings.len()))]
+    pub async fn import_station_readings(
+        &self,
+        station_id: &str,
+        data_source: &str,
+        readings: &[HistoricalReading],
+    ) -> Result<StationImportResult, HistoricalImportError> {
+        if !self.gauge_repo.gauge_exists(station_id).await? {
+            warn!(
+                station_id = %station_id,
+                "Gauge not found in gauges table, skipping readings"
+            );
+            return Ok(StationImportResult {
+                skipped: true,
+                ..Default::default()
+            });
+        }
+
+        let imported = self
+            .chunk_repo
+            .import_by_month(station_id, data_source, readings)
+            .await?;
+        if !imported.months_committed.is_empty() {
+            self.view_repo
+                .mark_stale("Water year readings imported")
+                .await?;
+        }
+
+        Ok(StationImportResult {
+            inserted: imported.inserted,
+            updated: imported.updated,
+            duplicates: imported.duplicates,
+            affected_months: imported
+                .months_committed
+                .into_iter()
+                .map(|(year, month)| (station_id.to_string(), year, month))
+                .collect(),
+            months_resumed: imported.months_resumed,
+            skipped: false,
+        })
+    }
+
+    /// Recalculate monthly summaries for a set of station-months
+    ///
+    /// Stations are rebuilt in parallel (see `SummaryService::recalculate_months`).
+    /// Returns the number of months summarized.
+    #[instrument(skip(self, months), fields(month_count = months.len()))]
+    pub async fn recalculate_months(
+        &self,
+        months: &HashSet<(String, i32, u32)>,
+    ) -> Result<usize, HistoricalImportError> {
+        let months: Vec<_> = months.iter().cloned().collect();
+        let summarized = self.summary.recalculate_months(&months, |_, _| {}).await?;
+        if summarized > 0 {
+            self.view_repo
+                .mark_stale("Water year readings imported")
+                .await?;
+        }
+
+        debug!(month_count = summarized, "Monthly summaries recalculated");
+        Ok(summarized)
+    }
+
+    /// Compare monthly summaries to raw readings for a water year
+    #[instrument(skip(self))]
+    pub async fn verify_water_year(
+        &self,
+        station_id: Option<&str>,
+        water_year: i32,
+    ) -> Result<Vec<SummaryDiscrepancy>, HistoricalImportError> {
+        let (start, end) = utils::water_year_date_range(water_year);
+        Ok(self
+            .monthly_repo
+            .find_summary_discrepancies(station_id, start, end)
+            .await?)
+    }
+
+    /// Fetch a station's readings for a water year, oldest first
+    #[instrument(skip(self))]
+    pub async fn export_water_year(
+        &self,
+        station_id: &str,
+        water_year: i32,
+    ) -> Result<Vec<Reading>, HistoricalImportError> {
+        let (start, end) = utils::water_year_date_range(water_year);
+        let mut readings = self
+            .reading_repo
+            .find_by_date_range(station_id, start, end)
+            .await?;
+        readings.reverse();
+        Ok(readings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::units::Inches;
+    use chrono::NaiveDate;
+
+    fn reading(station_id: &str, day: u32) -> HistoricalReading {
+        HistoricalReading {
+            station_id: station_id.parse().unwrap(),
+            reading_date: NaiveDate::from_ymd_opt(2023, 1, day).unwrap(),
+            rainfall_inches: Inches::new(0.1).unwrap(),
+            footnote_marker: None,
+        }
+    }
+
+    #[test]
+    fn test_excel_data_source() {
+        assert_eq!(
+            HistoricalImportService::excel_data_source(2023),
+            "excel_WY_2023"
+        );
+    }
+
+    #[test]
+    fn test_group_by_station() {
+        let grouped = HistoricalImportService::group_by_station(vec![
+            reading("59700", 1),
+            reading("1000", 1),
+            reading("59700", 2),
+        ]);
+
+        assert_eq!(grouped.len(), 2);
+        assert_eq!(grouped["59700"].len(), 2);
+        assert_eq!(grouped.keys().next().map(StationId::as_str), Some("1000"));
+    }
+}
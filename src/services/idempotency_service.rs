@@ -0,0 +1,182 @@
+use chrono::{Duration, Utc};
+use sha2::{Digest, Sha256};
+use tracing::{debug, instrument};
+
+use crate::db::{DbError, IdempotencyRecord, IdempotencyRepository};
+
+/// How long a key's stored response is replayed
+pub const IDEMPOTENCY_KEY_TTL_HOURS: i64 = 24;
+
+/// Longest accepted Idempotency-Key value
+pub const MAX_IDEMPOTENCY_KEY_LEN: usize = 255;
+
+/// A completed response captured for replay
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredResponse {
+    pub status_code: u16,
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+}
+
+/// What to do with a request carrying an Idempotency-Key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdempotencyOutcome {
+    /// First use of the key: run the request, then `complete` or `release` it
+    Reserved,
+    /// Same request seen before: return the stored response
+    Replay(StoredResponse),
+    /// Same request is still being processed
+    InProgress,
+    /// Key was already used for a different request
+    Mismatch,
+}
+
+#[derive(Clone)]
+pub struct IdempotencyService {
+    repo: IdempotencyRepository,
+}
+
+impl IdempotencyService {
+    pub fn new(repo: IdempotencyRepository) -> Self {
+        Self { repo }
+    }
+
+    /// Keys must be 1-255 visible ASCII characters
+    pub fn is_valid_key(key: &str) -> bool {
+        !key.is_empty()
+            && key.len() <= MAX_IDEMPOTENCY_KEY_LEN
+            && key.bytes().all(|b| b.is_ascii_graphic())
+    }
+
+    /// Fingerprint of a request, so a reused key with a different body is detected
+    pub fn request_hash(method: &str, path: &str, body: &[u8]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(method.as_bytes());
+        hasher.update([0]);
+        hasher.update(path.as_bytes());
+        hasher.update([0]);
+        hasher.update(body);
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Claim the key or decide how to answer a repeat
+    #[instrument(skip(self, body))]
+    pub async fn begin(
+        &self,
+        key: &str,
+        method: &str,
+        path: &str,
+        body: &[u8],
+    ) -> Result<IdempotencyOutcome, DbError> {
+        let hash = Self::request_hash(method, path, body);
+        let expired_before = Utc::now() - Duration::hours(IDEMPOTENCY_KEY_TTL_HOURS);
+
+        let existing = self
+            .repo
+            .reserve(key, method, path, &hash, expired_before)
+            .await?;
+
+        let outcome = match existing {
+            // Claimed, or the previous claim was released between the two statements
+            None => IdempotencyOutcome::Reserved,
+            Some(record) => Self::classify(record, &hash),
+        };
+        debug!(
+            "Idempotency key {} for {} {}: {:?}",
+            key, method, path, outcome
+        );
+        Ok(outcome)
+    }
+
+    fn classify(record: IdempotencyRecord, hash: &str) -> IdempotencyOutcome {
+        if record.request_hash != hash {
+            return IdempotencyOutcome::Mismatch;
+        }
+        match record.status_code {
+            Some(status_code) => IdempotencyOutcome::Replay(StoredResponse {
+                status_code: status_code as u16,
+                content_type: record.content_type,
+                body: record.response_body.unwrap_or_default(),
+            }),
+            None => IdempotencyOutcome::InProgress,
+        }
+    }
+
+    /// Store the response for replay
+    pub async fn complete(
+        &self,
+        key: &str,
+        method: &str,
+        path: &str,
+        response: &StoredResponse,
+    ) -> Result<(), DbError> {
+        self.repo
+            .complete(
+                key,
+                method,
+                path,
+                response.status_code as i16,
+                response.content_type.as_deref(),
+                &response.body,
+            )
+            .await
+    }
+
+    /// Give the key back (e.g. after a server error) so a retry runs again
+    pub async fn release(&self, key: &str, method: &str, path: &str) -> Result<(), DbError> {
+        self.repo.release(key, method, path).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(hash: &str, status_code: Option<i16>) -> IdempotencyRecord {
+        IdempotencyRecord {
+            request_hash: hash.to_string(),
+            status_code,
+            content_type: Some("application/json".to_string()),
+            response_body: status_code.map(|_| b"{}".to_vec()),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_request_hash_covers_method_path_and_body() {
+        let hash = IdempotencyService::request_hash("POST", "/a", b"{}");
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, IdempotencyService::request_hash("POST", "/a", b"{}"));
+        assert_ne!(hash, IdempotencyService::request_hash("POST", "/b", b"{}"));
+        assert_ne!(hash, IdempotencyService::request_hash("POST", "/a", b"{ }"));
+        assert_ne!(hash, IdempotencyService::request_hash("PUT", "/a", b"{}"));
+    }
+
+    #[test]
+    fn test_is_valid_key() {
+        assert!(IdempotencyService::is_valid_key("retry-7f3c"));
+        assert!(!IdempotencyService::is_valid_key(""));
+        assert!(!IdempotencyService::is_valid_key("has space"));
+        assert!(!IdempotencyService::is_valid_key(&"k".repeat(256)));
+    }
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(
+            IdempotencyService::classify(record("abc", None), "abc"),
+            IdempotencyOutcome::InProgress
+        );
+        assert_eq!(
+            IdempotencyService::classify(record("abc", Some(200)), "xyz"),
+            IdempotencyOutcome::Mismatch
+        );
+        assert_eq!(
+            IdempotencyService::classify(record("abc", Some(200)), "abc"),
+            IdempotencyOutcome::Replay(StoredResponse {
+                status_code: 200,
+                content_type: Some("application/json".to_string()),
+                body: b"{}".to_vec(),
+            })
+        );
+    }
+}
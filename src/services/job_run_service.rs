@@ -0,0 +1,244 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use tracing::{debug, info, instrument, warn};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::clock::{self, SharedClock};
+use crate::db::{
+    DbError, DbPool, JobRun, JobRunKind, JobRunOutcome, JobRunRepository, JobRunTrend, NewJobRun,
+};
+use crate::pagination::{self, PageInfo};
+use crate::station_id::StationId;
+
+/// Days of job run history kept
+pub const DEFAULT_JOB_RUN_RETENTION_DAYS: u32 = 90;
+
+/// Job run listing filters (used by API)
+#[derive(Debug, Clone, Deserialize, IntoParams, Validate)]
+pub struct JobRunParams {
+    /// Only runs of this job (fetch, gauge_list, fopr_import)
+    pub job: Option<JobRunKind>,
+    /// Only FOPR imports of this gauge
+    #[param(value_type = Option<String>)]
+    pub station_id: Option<StationId>,
+    /// Page number, starting at 1
+    #[serde(default = "default_page")]
+    #[validate(range(min = 1, message = "must be at least 1"))]
+    pub page: u32,
+    /// Runs per page (default 50, max 500)
+    #[serde(default = "default_page_size")]
+    #[validate(range(min = 1, max = 500, message = "must be between 1 and 500"))]
+    pub page_size: u32,
+}
+
+fn default_page() -> u32 {
+    1
+}
+
+fn default_page_size() -> u32 {
+    50
+}
+
+/// One page of job runs, newest first
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct JobRunPage {
+    pub pagination: PageInfo,
+    pub runs: Vec<JobRun>,
+}
+
+/// Job run trend parameters (used by API)
+#[derive(Debug, Clone, Deserialize, IntoParams, Validate)]
+pub struct JobRunTrendParams {
+    /// Only runs of this job (fetch, gauge_list, fopr_import)
+    pub job: Option<JobRunKind>,
+    /// Days of history, counting today (default 30, max 365)
+    #[serde(default = "default_trend_days")]
+    #[validate(range(min = 1, max = 365, message = "must be between 1 and 365"))]
+    pub days: u32,
+}
+
+fn default_trend_days() -> u32 {
+    30
+}
+
+/// Times a job run from start to finish
+#[derive(Debug, Clone)]
+pub struct JobRunTimer {
+    job: JobRunKind,
+    station_id: Option<String>,
+    fopr_job_id: Option<i32>,
+    started_at: DateTime<Utc>,
+    started: Instant,
+}
+
+impl JobRunTimer {
+    pub fn start(job: JobRunKind) -> Self {
+        Self {
+            job,
+            station_id: None,
+            fopr_job_id: None,
+            started_at: Utc::now(),
+            started: Instant::now(),
+        }
+    }
+
+    /// Attribute the run to a FOPR import job
+    pub fn for_fopr_job(mut self, job_id: i32, station_id: &str) -> Self {
+        self.fopr_job_id = Some(job_id);
+        self.station_id = Some(station_id.to_string());
+        self
+    }
+
+    /// The finished run: rows written, or why it failed
+    pub fn finish(self, result: Result<i64, String>) -> NewJobRun {
+        let (row_count, error) = match result {
+            Ok(rows) => (rows, None),
+            Err(e) => (0, Some(e)),
+        };
+        NewJobRun {
+            job: self.job,
+            station_id: self.station_id,
+            fopr_job_id: self.fopr_job_id,
+            started_at: self.started_at,
+            duration_ms: self.started.elapsed().as_secs_f64() * 1000.0,
+            row_count,
+            error,
+        }
+    }
+}
+
+/// History of fetch, gauge list, and FOPR import runs
+///
+/// Recording is best effort: a failed insert is logged and the job carries on. On the
+/// SQLite backend, which has no job_runs table, nothing is recorded.
+#[derive(Clone)]
+pub struct JobRunService {
+    repo: JobRunRepository,
+    enabled: bool,
+    clock: SharedClock,
+}
+
+impl JobRunService {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        let pool = pool.into();
+        Self {
+            enabled: pool.postgres().is_ok(),
+            repo: JobRunRepository::new(pool),
+            clock: clock::system_clock(),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Store a finished run
+    pub async fn record(&self, run: NewJobRun) {
+        if !self.enabled {
+            return;
+        }
+        match self.repo.insert(&run).await {
+            Ok(id) => debug!(id, job = run.job.as_str(), "Recorded job run"),
+            Err(e) => warn!(error = %e, job = run.job.as_str(), "Failed to record job run"),
+        }
+    }
+
+    /// A page of recorded runs, newest first
+    pub async fn recent(&self, params: &JobRunParams) -> Result<JobRunPage, DbError> {
+        let station_id = params.station_id.as_deref();
+        let total = self.repo.count(params.job, station_id).await?;
+        let runs = self
+            .repo
+            .find_recent(
+                params.job,
+                station_id,
+                pagination::offset(params.page, params.page_size),
+                params.page_size as i64,
+            )
+            .await?;
+
+        Ok(JobRunPage {
+            pagination: PageInfo::new(params.page, params.page_size, total as u64),
+            runs,
+        })
+    }
+
+    /// Row counts of up to `limit` recent successful runs of `job`, oldest first
+    ///
+    /// Empty when history isn't recorded (SQLite) or can't be read.
+    pub async fn recent_row_counts(&self, job: JobRunKind, limit: usize) -> Vec<usize> {
+        if !self.enabled {
+            return Vec::new();
+        }
+        // Failed runs are interleaved with the successful ones, so look a little further back
+        match self
+            .repo
+            .find_recent(Some(job), None, 0, limit as i64 * 2)
+            .await
+        {
+            Ok(runs) => {
+                let mut counts: Vec<usize> = runs
+                    .into_iter()
+                    .filter(|run| run.outcome == JobRunOutcome::Succeeded)
+                    .take(limit)
+                    .map(|run| run.row_count.max(0) as usize)
+                    .collect();
+                counts.reverse();
+                counts
+            }
+            Err(e) => {
+                warn!(error = %e, job = job.as_str(), "Failed to load job run history");
+                Vec::new()
+            }
+        }
+    }
+
+    /// Daily totals over the last `params.days` UTC days, oldest first
+    pub async fn trend(&self, params: &JobRunTrendParams) -> Result<Vec<JobRunTrend>, DbError> {
+        let today = self
+            .clock
+            .now()
+            .date_naive()
+            .and_hms_opt(0, 0, 0)
+            .expect("midnight is valid")
+            .and_utc();
+        let since = today - Duration::days(params.days as i64 - 1);
+        self.repo.daily_trend(params.job, since).await
+    }
+
+    /// Delete runs older than `retention_days`
+    #[instrument(skip(self))]
+    pub async fn prune(&self, retention_days: u32) -> Result<u64, DbError> {
+        let cutoff = self.clock.now() - Duration::days(retention_days as i64);
+        let deleted = self.repo.delete_before(cutoff).await?;
+        if deleted > 0 {
+            info!(deleted, %cutoff, "Pruned job run history");
+        }
+        Ok(deleted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timer_finish() {
+        let run = JobRunTimer::start(JobRunKind::FoprImport)
+            .for_fopr_job(7, "59700")
+            .finish(Err("timed out".to_string()));
+        assert_eq!(run.job, JobRunKind::FoprImport);
+        assert_eq!(run.fopr_job_id, Some(7));
+        assert_eq!(run.station_id.as_deref(), Some("59700"));
+        assert_eq!(run.row_count, 0);
+        assert_eq!(run.error.as_deref(), Some("timed out"));
+        assert!(run.duration_ms >= 0.0);
+
+        let run = JobRunTimer::start(JobRunKind::Fetch).finish(Ok(96));
+        assert_eq!((run.row_count, run.error), (96, None));
+        assert_eq!(run.station_id, None);
+    }
+}
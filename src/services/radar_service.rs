@@ -0,0 +1,242 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, instrument};
+use utoipa::{IntoParams, ToSchema};
+use validator::{Validate, ValidationError};
+
+use crate::db::{DbError, DbPool, GaugeRepository, RadarEstimateRepository, RadarStorm};
+use crate::pagination::{self, PageInfo};
+use crate::radar::{RadarAgreement, RadarQpe, DEFAULT_RADAR_PRODUCT};
+
+/// Storm window of a radar comparison (used by API)
+#[derive(Debug, Clone, Deserialize, IntoParams, Validate)]
+#[validate(schema(function = "validate_storm_window"))]
+pub struct RadarComparisonParams {
+    /// Storm start as imported (RFC 3339)
+    pub start: DateTime<Utc>,
+    /// Storm end as imported (RFC 3339)
+    pub end: DateTime<Utc>,
+    /// MRMS product (default MultiSensor_QPE_01H_Pass2)
+    pub product: Option<String>,
+}
+
+fn validate_storm_window(params: &RadarComparisonParams) -> Result<(), ValidationError> {
+    if params.start >= params.end {
+        return Err(ValidationError::new("storm_order")
+            .with_message(std::borrow::Cow::Borrowed("start must be before end")));
+    }
+    Ok(())
+}
+
+/// Radar storm list parameters (used by API)
+#[derive(Debug, Clone, Deserialize, IntoParams, Validate)]
+pub struct RadarStormParams {
+    /// Page number, starting at 1
+    #[serde(default = "default_page")]
+    #[validate(range(min = 1, message = "must be at least 1"))]
+    pub page: u32,
+    /// Storm windows per page (default 50, max 100)
+    #[serde(default = "default_page_size")]
+    #[validate(range(min = 1, max = 100, message = "must be between 1 and 100"))]
+    pub page_size: u32,
+}
+
+fn default_page() -> u32 {
+    1
+}
+
+fn default_page_size() -> u32 {
+    50
+}
+
+/// One page of imported storm windows, newest first
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RadarStormPage {
+    pub pagination: PageInfo,
+    pub storms: Vec<RadarStorm>,
+}
+
+/// Outcome of sampling QPE grids for a storm
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RadarImportReport {
+    pub storm_start: DateTime<Utc>,
+    pub storm_end: DateTime<Utc>,
+    pub product: String,
+    /// Gauges with coordinates
+    pub gauges_located: usize,
+    /// Gauges with an estimate stored
+    pub gauges_sampled: usize,
+    /// Gauges outside the grids or at cells without data
+    pub gauges_not_covered: usize,
+}
+
+/// A gauge's storm total against the radar estimate
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct GaugeRadarComparison {
+    #[schema(example = "59700")]
+    pub station_id: String,
+    #[schema(example = "Aztec Park")]
+    pub station_name: Option<String>,
+    /// Rainfall the gauge reported for the window
+    #[schema(example = 0.71)]
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub gauge_inches: f64,
+    /// MRMS estimate for the gauge's grid cell
+    #[schema(example = 1.64)]
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub radar_inches: f64,
+    /// Gauge total divided by radar estimate (null when radar saw no rain)
+    #[schema(example = 0.43)]
+    #[serde(serialize_with = "crate::units::serialize_rounded_opt")]
+    pub ratio: Option<f64>,
+    /// Readings the gauge reported for the window
+    #[schema(example = 96)]
+    pub reading_count: i64,
+    pub agreement: RadarAgreement,
+}
+
+/// Gauge-vs-radar comparison for one storm
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RadarComparison {
+    #[schema(example = "2025-01-06T00:00:00Z")]
+    pub storm_start: DateTime<Utc>,
+    #[schema(example = "2025-01-07T00:00:00Z")]
+    pub storm_end: DateTime<Utc>,
+    #[schema(example = "MultiSensor_QPE_01H_Pass2")]
+    pub product: String,
+    /// Gauges flagged under_catch, over_catch, or no_readings
+    #[schema(example = 7)]
+    pub suspect_count: usize,
+    /// Suspect gauges first, each group by ratio (lowest first), then by station
+    pub gauges: Vec<GaugeRadarComparison>,
+}
+
+/// Samples MRMS radar estimates at gauges and compares them with gauge readings
+#[derive(Clone)]
+pub struct RadarService {
+    radar_repo: RadarEstimateRepository,
+    gauge_repo: GaugeRepository,
+}
+
+impl RadarService {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        let pool = pool.into();
+        Self {
+            radar_repo: RadarEstimateRepository::new(pool.clone()),
+            gauge_repo: GaugeRepository::new(pool),
+        }
+    }
+
+    /// Sample the QPE grids at every gauge with coordinates and store the storm's
+    /// estimates, replacing any earlier import of the same window and product
+    #[instrument(skip(self, qpe))]
+    pub async fn import_storm(
+        &self,
+        qpe: &RadarQpe,
+        storm_start: DateTime<Utc>,
+        storm_end: DateTime<Utc>,
+        product: &str,
+    ) -> Result<RadarImportReport, DbError> {
+        let gauges = self.gauge_repo.find_locations().await?;
+        let estimates: Vec<(String, f64)> = gauges
+            .iter()
+            .filter_map(|gauge| {
+                let inches = qpe.sample_inches(gauge.latitude, gauge.longitude);
+                if inches.is_none() {
+                    debug!("No radar estimate at gauge {}", gauge.station_id);
+                }
+                inches.map(|inches| (gauge.station_id.clone(), inches))
+            })
+            .collect();
+
+        self.radar_repo
+            .replace_storm(storm_start, storm_end, product, &qpe.source(), &estimates)
+            .await?;
+        info!(
+            "Sampled radar estimates for {} of {} gauges",
+            estimates.len(),
+            gauges.len()
+        );
+
+        Ok(RadarImportReport {
+            storm_start,
+            storm_end,
+            product: product.to_string(),
+            gauges_located: gauges.len(),
+            gauges_sampled: estimates.len(),
+            gauges_not_covered: gauges.len() - estimates.len(),
+        })
+    }
+
+    /// A page of imported storm windows, newest first
+    pub async fn storms(&self, params: &RadarStormParams) -> Result<RadarStormPage, DbError> {
+        let total = self.radar_repo.count_storms().await?;
+        let storms = self
+            .radar_repo
+            .find_storms(
+                pagination::offset(params.page, params.page_size),
+                params.page_size as i64,
+            )
+            .await?;
+
+        Ok(RadarStormPage {
+            pagination: PageInfo::new(params.page, params.page_size, total as u64),
+            storms,
+        })
+    }
+
+    /// Compare each gauge's readings with its radar estimate for an imported storm
+    ///
+    /// Returns None when the window and product were never imported.
+    #[instrument(skip(self))]
+    pub async fn compare(
+        &self,
+        params: &RadarComparisonParams,
+    ) -> Result<Option<RadarComparison>, DbError> {
+        let product = params.product.as_deref().unwrap_or(DEFAULT_RADAR_PRODUCT);
+        let rows = self
+            .radar_repo
+            .find_comparison(params.start, params.end, product)
+            .await?;
+        if rows.is_empty() {
+            return Ok(None);
+        }
+
+        let mut gauges: Vec<GaugeRadarComparison> = rows
+            .into_iter()
+            .map(|row| GaugeRadarComparison {
+                ratio: (row.radar_inches > 0.0).then(|| row.gauge_inches / row.radar_inches),
+                agreement: RadarAgreement::assess(
+                    row.gauge_inches,
+                    row.radar_inches,
+                    row.reading_count,
+                ),
+                station_id: row.station_id,
+                station_name: row.station_name,
+                gauge_inches: row.gauge_inches,
+                radar_inches: row.radar_inches,
+                reading_count: row.reading_count,
+            })
+            .collect();
+        // Rows arrive ordered by station, and the sort is stable
+        gauges.sort_by(|a, b| {
+            b.agreement
+                .is_suspect()
+                .cmp(&a.agreement.is_suspect())
+                .then_with(|| a.ratio.is_none().cmp(&b.ratio.is_none()))
+                .then_with(|| {
+                    a.ratio
+                        .unwrap_or_default()
+                        .total_cmp(&b.ratio.unwrap_or_default())
+                })
+        });
+
+        Ok(Some(RadarComparison {
+            storm_start: params.start,
+            storm_end: params.end,
+            product: product.to_string(),
+            suspect_count: gauges.iter().filter(|g| g.agreement.is_suspect()).count(),
+            gauges,
+        }))
+    }
+}
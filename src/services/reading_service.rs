@@ -1,63 +1,411 @@
-use chrono::{DateTime, Datelike, NaiveDate, Utc};
-use std::collections::HashMap;
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, Utc};
+use futures::StreamExt;
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use tokio::sync::mpsc;
+use tracing::debug;
+use utoipa::IntoParams;
+use validator::{Validate, ValidationError};
 
+use crate::clock::{self, SharedClock};
 use crate::db::{
-    CalendarYearSummary, DbError, MonthlyRainfallRepository, MonthlySummary, Reading,
-    ReadingRepository, WaterYearSummary,
+    AnnotationRepository, CalendarDay, CalendarYearSummary, CoverageRow, DailyRainfallIntensity,
+    DbError, GaugeAnnotation, GaugeCoverage, GaugeMetadata, GaugeRanking, GaugeRepository,
+    HistogramBin, HourlyRainfall, HourlyRainfallRepository, MonthCoverage, MonthFill,
+    MonthPercentileRow, MonthlyNormal, MonthlyNormals, MonthlyRainfallRepository,
+    MonthlyRainfallSummary, MonthlySummary, QualityGrade, RainfallCalendar, RainfallHistogram,
+    RankingOrder, RankingPeriod, RankingResponse, RankingRow, Reading, ReadingRange,
+    ReadingRepository, SourceCoverage, WaterYearSummary, WaterYearTotal, YearCoverage,
+    ZoneRainfall, ZoneRainfallResponse,
 };
+use crate::pagination::{self, PageInfo};
+use crate::services::SummaryViewService;
+use crate::units::round_inches;
+use crate::utils;
+
+/// Smallest allowed histogram bin, matching the 0.01" gauge resolution
+pub const MIN_HISTOGRAM_BIN_INCHES: f64 = 0.01;
+
+/// Readings buffered between the database cursor and a slow streaming client
+const STREAM_BUFFER_ROWS: usize = 256;
+
+/// Largest page of raw readings a client may request
+pub const MAX_READING_PAGE_SIZE: u32 = 10_000;
+
+/// Page size when `page` is given without `page_size`
+const DEFAULT_READING_PAGE_SIZE: u32 = 1000;
+
+/// Caps on queries that scan raw readings, so a naive client can't request a full history
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadingQueryLimits {
+    /// Longest date range, in days, a raw-readings query may cover (READINGS_MAX_SPAN_DAYS)
+    pub max_span_days: u32,
+    /// Most readings a single response may return (READINGS_MAX_ROWS)
+    pub max_rows: u32,
+}
+
+impl Default for ReadingQueryLimits {
+    fn default() -> Self {
+        Self {
+            // Five years, leap days included
+            max_span_days: 1827,
+            max_rows: 100_000,
+        }
+    }
+}
+
+impl ReadingQueryLimits {
+    /// Refuse to load more than `max_rows` readings
+    pub fn check_rows(&self, rows: i64) -> Result<(), ReadingQueryError> {
+        if rows > self.max_rows as i64 {
+            return Err(ReadingQueryError::TooManyRows {
+                rows,
+                max_rows: self.max_rows,
+            });
+        }
+        Ok(())
+    }
+
+    /// Refuse an inclusive date range longer than `max_span_days`
+    pub fn check_span(&self, start: NaiveDate, end: NaiveDate) -> Result<(), ReadingQueryError> {
+        let span_days = (end - start).num_days() + 1;
+        if span_days > self.max_span_days as i64 {
+            return Err(ReadingQueryError::SpanTooLarge {
+                span_days,
+                max_days: self.max_span_days,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Why a raw-readings query was refused or failed
+#[derive(Debug, thiserror::Error)]
+pub enum ReadingQueryError {
+    #[error("Range of {span_days} days exceeds the limit of {max_days} days")]
+    SpanTooLarge { span_days: i64, max_days: u32 },
+
+    #[error("{rows} readings exceed the limit of {max_rows} per response")]
+    TooManyRows { rows: i64, max_rows: u32 },
+
+    #[error("Database error: {0}")]
+    Database(#[from] DbError),
+}
+
+// Histogram query parameters (used by API)
+#[derive(Debug, Clone, Deserialize, IntoParams, Validate)]
+#[validate(schema(function = "validate_histogram_range"))]
+pub struct HistogramParams {
+    /// Bin width in inches (default 0.1, minimum 0.01)
+    #[serde(default = "default_bin")]
+    #[validate(custom(function = "validate_bin_width"))]
+    pub bin: f64,
+    /// First day to include (YYYY-MM-DD, inclusive)
+    pub start: Option<NaiveDate>,
+    /// Last day to include (YYYY-MM-DD, inclusive)
+    pub end: Option<NaiveDate>,
+}
+
+// Calendar heat map query parameters (used by API)
+#[derive(Debug, Clone, Deserialize, IntoParams, Validate)]
+pub struct CalendarParams {
+    /// Calendar year to chart (1900-2200)
+    #[validate(range(min = 1900, max = 2200, message = "must be between 1900 and 2200"))]
+    pub year: i32,
+}
+
+// Year summary query parameters (used by API)
+#[derive(Debug, Clone, Default, Deserialize, IntoParams, Validate)]
+pub struct YearSummaryParams {
+    /// List all 12 months, with `zero` or `null` readings and rainfall for months without
+    /// summary rows; omitted, only months with summary rows are listed
+    #[param(inline)]
+    pub fill: Option<MonthFill>,
+}
+
+// Date range query parameters for raw readings (used by API)
+#[derive(Debug, Clone, Deserialize, IntoParams, Validate)]
+#[validate(schema(function = "validate_reading_range"))]
+pub struct ReadingRangeParams {
+    /// First day to include (YYYY-MM-DD, inclusive)
+    pub start: NaiveDate,
+    /// Last day to include (YYYY-MM-DD, inclusive)
+    pub end: NaiveDate,
+}
+
+// Paging for raw readings (used by API)
+#[derive(Debug, Clone, Default, Deserialize, IntoParams, Validate)]
+pub struct ReadingPageParams {
+    /// Page number, starting at 1
+    #[validate(range(min = 1, message = "must be at least 1"))]
+    pub page: Option<u32>,
+    /// Readings per page (default 1000 when `page` is given, max 10000). With neither,
+    /// the whole range is returned as one page, up to READINGS_MAX_ROWS readings
+    #[validate(range(
+        min = 1,
+        max = MAX_READING_PAGE_SIZE,
+        message = "must be between 1 and 10000"
+    ))]
+    pub page_size: Option<u32>,
+}
+
+impl ReadingPageParams {
+    /// Page number and size, or None for the whole range
+    fn paging(&self) -> Option<(u32, u32)> {
+        if self.page.is_none() && self.page_size.is_none() {
+            return None;
+        }
+        Some((
+            self.page.unwrap_or(1),
+            self.page_size.unwrap_or(DEFAULT_READING_PAGE_SIZE),
+        ))
+    }
+}
+
+fn validate_reading_range(params: &ReadingRangeParams) -> Result<(), ValidationError> {
+    if params.start > params.end {
+        return Err(ValidationError::new("date_order")
+            .with_message(Cow::Borrowed("start must not be after end")));
+    }
+    Ok(())
+}
+
+impl ReadingRangeParams {
+    /// Half-open UTC range `[start 00:00, end + 1 day 00:00)`
+    pub fn datetime_range(&self) -> (DateTime<Utc>, DateTime<Utc>) {
+        let midnight = |d: NaiveDate| d.and_hms_opt(0, 0, 0).unwrap().and_utc();
+        let after_end = self.end.checked_add_days(Days::new(1)).unwrap_or(self.end);
+        (midnight(self.start), midnight(after_end))
+    }
+}
+
+fn default_bin() -> f64 {
+    0.1
+}
+
+fn validate_bin_width(bin: f64) -> Result<(), ValidationError> {
+    // Written so NaN fails too
+    if bin.is_finite() && bin >= MIN_HISTOGRAM_BIN_INCHES {
+        Ok(())
+    } else {
+        Err(
+            ValidationError::new("range").with_message(Cow::Owned(format!(
+                "must be at least {MIN_HISTOGRAM_BIN_INCHES} inches"
+            ))),
+        )
+    }
+}
+
+fn validate_histogram_range(params: &HistogramParams) -> Result<(), ValidationError> {
+    match (params.start, params.end) {
+        (Some(start), Some(end)) if start > end => Err(ValidationError::new("date_order")
+            .with_message(Cow::Borrowed("start must not be after end"))),
+        _ => Ok(()),
+    }
+}
 
 #[derive(Clone)]
 pub struct ReadingService {
     reading_repo: ReadingRepository,
     monthly_rainfall_repo: MonthlyRainfallRepository,
+    hourly_rainfall_repo: HourlyRainfallRepository,
+    annotation_repo: AnnotationRepository,
+    gauge_repo: GaugeRepository,
+    summary_views: Option<SummaryViewService>,
+    limits: ReadingQueryLimits,
+    clock: SharedClock,
+}
+
+// Ranking query parameters (used by API)
+#[derive(Debug, Clone, Deserialize, IntoParams, Validate)]
+pub struct RankingParams {
+    /// Period to rank: 24h, month, or water-year
+    #[param(inline)]
+    pub period: RankingPeriod,
+    /// wettest (default) or driest first
+    #[serde(default)]
+    #[param(inline)]
+    pub order: RankingOrder,
+    /// Number of gauges to return (default 20, max 100)
+    #[serde(default = "default_ranking_limit")]
+    #[validate(range(min = 1, max = 100, message = "must be between 1 and 100"))]
+    pub limit: u32,
+    /// Also rank Inactive and Decommissioned gauges (default false)
+    #[serde(default)]
+    pub include_inactive: bool,
+    /// Rank the period ending at this time instead of now (RFC 3339)
+    pub as_of: Option<DateTime<Utc>>,
+}
+
+fn default_ranking_limit() -> u32 {
+    20
+}
+
+impl RankingParams {
+    pub fn limit(&self) -> i64 {
+        self.limit.clamp(1, 100) as i64
+    }
+}
+
+// Zone rainfall query parameters (used by API)
+#[derive(Debug, Clone, Deserialize, IntoParams, Validate)]
+pub struct ZoneRainfallParams {
+    /// Period to aggregate: 24h, month, or water-year
+    #[param(inline)]
+    pub period: RankingPeriod,
+    /// Also count Inactive and Decommissioned gauges (default false)
+    #[serde(default)]
+    pub include_inactive: bool,
+    /// Aggregate the period ending at this time instead of now (RFC 3339)
+    pub as_of: Option<DateTime<Utc>>,
 }
 
 impl ReadingService {
     pub fn new(
         reading_repo: ReadingRepository,
         monthly_rainfall_repo: MonthlyRainfallRepository,
+        hourly_rainfall_repo: HourlyRainfallRepository,
+        annotation_repo: AnnotationRepository,
+        gauge_repo: GaugeRepository,
     ) -> Self {
         Self {
             reading_repo,
             monthly_rainfall_repo,
+            hourly_rainfall_repo,
+            annotation_repo,
+            gauge_repo,
+            summary_views: None,
+            limits: ReadingQueryLimits::default(),
+            clock: clock::system_clock(),
         }
     }
 
+    pub fn with_query_limits(mut self, limits: ReadingQueryLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Serve current month and water-year rankings from summary views while they are fresh
+    pub fn with_summary_views(mut self, summary_views: SummaryViewService) -> Self {
+        self.summary_views = Some(summary_views);
+        self
+    }
+
+    /// The water year in progress according to the service's clock
+    pub fn current_water_year(&self) -> i32 {
+        Self::get_water_year(self.clock.now())
+    }
+
     /// Get water year summary with business logic
     pub async fn get_water_year_summary(
         &self,
         station_id: &str,
         water_year: i32,
-    ) -> Result<WaterYearSummary, DbError> {
+        params: &YearSummaryParams,
+    ) -> Result<WaterYearSummary, ReadingQueryError> {
         // Business logic: Calculate water year date range (Oct prev year - Sep current year)
         let (start, end) = Self::water_year_date_range(water_year);
 
         // Fetch monthly summaries for the water year
-        let monthly_summaries = self
+        let monthly_summaries_db = self
             .monthly_rainfall_repo
             .get_summaries_by_date_range(station_id, start, end)
             .await?;
 
         // Calculate total rainfall by summing monthly totals
-        let total_rainfall: f64 = monthly_summaries
+        let total_rainfall: f64 = monthly_summaries_db
             .iter()
             .map(|m| m.total_rainfall_inches)
             .sum();
 
         // Calculate total readings count
-        let total_readings: i32 = monthly_summaries.iter().map(|m| m.reading_count).sum();
+        let total_readings: i32 = monthly_summaries_db.iter().map(|m| m.reading_count).sum();
+        self.limits.check_rows(total_readings as i64)?;
 
-        // Fetch actual readings for detailed view (reuse same date range)
+        // Fetch actual readings for detailed view
         let readings = self
             .reading_repo
-            .find_by_date_range(station_id, start, end)
+            .find_by_water_year(station_id, water_year)
+            .await?;
+
+        let monthly_summaries = self
+            .build_year_months(
+                station_id,
+                &utils::water_year_months(water_year),
+                &monthly_summaries_db,
+                params.fill,
+            )
             .await?;
 
+        let annotations = self.find_annotations(station_id, start, end).await?;
+
         Ok(WaterYearSummary {
             water_year,
+            quality_grade: Self::worst_grade(&monthly_summaries),
+            as_of: Self::latest_reading(&monthly_summaries_db),
+            is_partial: Self::range_is_partial(start, end, self.clock.now()),
             total_readings: total_readings as usize,
-            total_rainfall_inches: Self::normalize_zero(total_rainfall),
+            total_rainfall_inches: round_inches(total_rainfall),
+            monthly_summaries,
             readings,
+            annotations,
+        })
+    }
+
+    /// When a water year's readings last changed, without loading them
+    pub async fn get_water_year_last_modified(
+        &self,
+        station_id: &str,
+        water_year: i32,
+    ) -> Result<Option<DateTime<Utc>>, DbError> {
+        self.reading_repo
+            .find_water_year_last_modified(station_id, water_year)
+            .await
+    }
+
+    /// When a calendar year's readings last changed, without loading them
+    pub async fn get_calendar_year_last_modified(
+        &self,
+        station_id: &str,
+        year: i32,
+    ) -> Result<Option<DateTime<Utc>>, DbError> {
+        let (start, end) = Self::calendar_year_date_range_only(year);
+        self.reading_repo
+            .find_last_modified(station_id, start, end)
+            .await
+    }
+
+    /// Water-year total from monthly summaries, without loading readings
+    pub async fn get_water_year_total(
+        &self,
+        station_id: &str,
+        water_year: i32,
+    ) -> Result<WaterYearTotal, DbError> {
+        let (start, end) = Self::water_year_date_range(water_year);
+        let monthly_summaries = self
+            .monthly_rainfall_repo
+            .get_summaries_by_date_range(station_id, start, end)
+            .await?;
+
+        let total_rainfall: f64 = monthly_summaries
+            .iter()
+            .map(|m| m.total_rainfall_inches)
+            .sum();
+
+        Ok(WaterYearTotal {
+            water_year,
+            total_rainfall_inches: round_inches(total_rainfall),
+            total_readings: monthly_summaries
+                .iter()
+                .map(|m| m.reading_count as i64)
+                .sum(),
+            as_of: Self::latest_reading(&monthly_summaries),
         })
     }
 
@@ -66,7 +414,8 @@ impl ReadingService {
         &self,
         station_id: &str,
         year: i32,
-    ) -> Result<CalendarYearSummary, DbError> {
+        params: &YearSummaryParams,
+    ) -> Result<CalendarYearSummary, ReadingQueryError> {
         // Business logic: Calculate calendar year date range (Jan 1 - Dec 31)
         let (start, end) = Self::calendar_year_date_range_only(year);
 
@@ -81,6 +430,12 @@ impl ReadingService {
             .iter()
             .map(|m| m.total_rainfall_inches)
             .sum();
+        self.limits.check_rows(
+            monthly_summaries_db
+                .iter()
+                .map(|m| m.reading_count as i64)
+                .sum(),
+        )?;
 
         // Fetch actual readings for detailed view (reuse same date range)
         let mut readings = self
@@ -89,33 +444,454 @@ impl ReadingService {
             .await?;
 
         // Convert database monthly summaries to API format with cumulative YTD
-        let monthly_summaries = Self::build_monthly_summaries(&monthly_summaries_db);
+        let months: Vec<(i32, u32)> = (1..=12).map(|m| (year, m)).collect();
+        let monthly_summaries = self
+            .build_year_months(station_id, &months, &monthly_summaries_db, params.fill)
+            .await?;
 
         readings.reverse(); // Desc for API
 
+        let annotations = self.find_annotations(station_id, start, end).await?;
+
         Ok(CalendarYearSummary {
             calendar_year: year,
+            quality_grade: Self::worst_grade(&monthly_summaries),
+            as_of: Self::latest_reading(&monthly_summaries_db),
+            is_partial: Self::range_is_partial(start, end, self.clock.now()),
             total_readings: readings.len(),
-            year_to_date_rainfall_inches: Self::normalize_zero(year_to_date_rainfall),
+            year_to_date_rainfall_inches: round_inches(year_to_date_rainfall),
             monthly_summaries,
             readings,
+            annotations,
         })
     }
 
+    /// Readings for a date range, oldest first, loaded into one response
+    pub async fn get_reading_range(
+        &self,
+        station_id: &str,
+        params: &ReadingRangeParams,
+        page: &ReadingPageParams,
+    ) -> Result<ReadingRange, ReadingQueryError> {
+        self.limits.check_span(params.start, params.end)?;
+        let (start, end) = params.datetime_range();
+        let total = self
+            .reading_repo
+            .count_by_date_range(station_id, start, end)
+            .await?;
+
+        let (readings, pagination) = match page.paging() {
+            None => {
+                self.limits.check_rows(total)?;
+                let mut readings = self
+                    .reading_repo
+                    .find_by_date_range(station_id, start, end)
+                    .await?;
+                readings.reverse(); // Oldest first, matching the stream
+                (readings, PageInfo::single(total as u64))
+            }
+            Some((page, page_size)) => {
+                let offset = pagination::offset(page, page_size);
+                self.limits
+                    .check_rows((total - offset).clamp(0, page_size as i64))?;
+                let readings = self
+                    .reading_repo
+                    .find_page_by_date_range(station_id, start, end, offset, page_size as i64)
+                    .await?;
+                (readings, PageInfo::new(page, page_size, total as u64))
+            }
+        };
+
+        Ok(ReadingRange {
+            station_id: station_id.to_string(),
+            start_date: params.start,
+            end_date: params.end,
+            total_readings: readings.len(),
+            pagination,
+            readings,
+        })
+    }
+
+    /// Hourly rainfall for a date range from the hourly summaries, oldest first
+    ///
+    /// Only sub-daily readings are summarized by hour, so days covered by daily imports
+    /// alone have no hours.
+    pub async fn get_hourly_rainfall(
+        &self,
+        station_id: &str,
+        params: &ReadingRangeParams,
+    ) -> Result<HourlyRainfall, ReadingQueryError> {
+        self.limits.check_span(params.start, params.end)?;
+        let (start, end) = params.datetime_range();
+        let hours = self
+            .hourly_rainfall_repo
+            .get_summaries_by_date_range(station_id, start, end)
+            .await?;
+
+        Ok(HourlyRainfall {
+            station_id: station_id.to_string(),
+            start_date: params.start,
+            end_date: params.end,
+            total_inches: hours.iter().map(|h| h.total_rainfall_inches).sum(),
+            hours,
+        })
+    }
+
+    /// Readings for a date range, oldest first, streamed from a database cursor
+    ///
+    /// A background task forwards rows through a bounded channel, so the cursor only
+    /// advances as fast as the receiver drains it and memory stays flat for any range.
+    /// The row cap doesn't apply; the span cap still does. The task stops when the
+    /// receiver is dropped, and a database error is forwarded as the last item.
+    pub fn stream_reading_range(
+        &self,
+        station_id: &str,
+        params: &ReadingRangeParams,
+    ) -> Result<mpsc::Receiver<Result<Reading, DbError>>, ReadingQueryError> {
+        self.limits.check_span(params.start, params.end)?;
+        let (start, end) = params.datetime_range();
+
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER_ROWS);
+        let repo = self.reading_repo.clone();
+        let station_id = station_id.to_string();
+        tokio::spawn(async move {
+            let mut rows = repo.stream_by_date_range(&station_id, start, end);
+            while let Some(row) = rows.next().await {
+                let failed = row.is_err();
+                if tx.send(row).await.is_err() {
+                    debug!("Reading stream for gauge {} closed by client", station_id);
+                    break;
+                }
+                if failed {
+                    break;
+                }
+            }
+        });
+
+        Ok(rx)
+    }
+
+    /// Annotations overlapping the half-open range `[start, end)`
+    async fn find_annotations(
+        &self,
+        station_id: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<GaugeAnnotation>, DbError> {
+        let last_day = end.date_naive().pred_opt().unwrap_or(end.date_naive());
+        self.annotation_repo
+            .find_overlapping(station_id, start.date_naive(), last_day)
+            .await
+    }
+
     /// Get latest reading for a specific gauge
     pub async fn get_latest_reading(&self, station_id: &str) -> Result<Option<Reading>, DbError> {
         self.reading_repo.find_latest(station_id).await
     }
 
+    /// Summarize which months and data sources have readings for a gauge
+    pub async fn get_coverage(&self, station_id: &str) -> Result<GaugeCoverage, DbError> {
+        let rows = self.reading_repo.find_coverage(station_id).await?;
+        Ok(Self::build_coverage(station_id, &rows))
+    }
+
+    /// Histogram of daily rainfall totals, with empty bins filled in
+    ///
+    /// An open-ended range is measured to the gauge's first or last reading, so omitting
+    /// `start` on a long-running gauge is refused like any other span over the limit.
+    pub async fn get_daily_histogram(
+        &self,
+        station_id: &str,
+        params: &HistogramParams,
+    ) -> Result<RainfallHistogram, ReadingQueryError> {
+        let (first, last) = match (params.start, params.end) {
+            (Some(start), Some(end)) => (Some(start), Some(end)),
+            (start, end) => match self.reading_repo.find_date_extent(station_id).await? {
+                Some((first, last)) => (start.or(Some(first)), end.or(Some(last))),
+                None => (None, None),
+            },
+        };
+        if let (Some(first), Some(last)) = (first, last) {
+            self.limits.check_span(first, last)?;
+        }
+
+        let start = params
+            .start
+            .map(|d| d.and_hms_opt(0, 0, 0).unwrap().and_utc());
+        let end = params
+            .end
+            .and_then(|d| d.checked_add_days(Days::new(1)))
+            .map(|d| d.and_hms_opt(0, 0, 0).unwrap().and_utc());
+
+        let counts = self
+            .reading_repo
+            .daily_total_histogram(station_id, start, end, params.bin)
+            .await?;
+
+        Ok(RainfallHistogram {
+            station_id: station_id.to_string(),
+            bin_width_inches: params.bin,
+            start_date: params.start,
+            end_date: params.end,
+            total_days: counts.iter().map(|(_, count)| count).sum(),
+            bins: Self::build_histogram_bins(&counts, params.bin),
+        })
+    }
+
+    /// Every day of a calendar year with its rainfall total and heat-map cell
+    pub async fn get_rainfall_calendar(
+        &self,
+        station_id: &str,
+        params: &CalendarParams,
+    ) -> Result<RainfallCalendar, DbError> {
+        let first = NaiveDate::from_ymd_opt(params.year, 1, 1).unwrap();
+        let last = NaiveDate::from_ymd_opt(params.year, 12, 31).unwrap();
+        let totals = self
+            .reading_repo
+            .daily_totals(station_id, first, last)
+            .await?;
+
+        let intensities: HashMap<NaiveDate, DailyRainfallIntensity> = self
+            .hourly_rainfall_repo
+            .get_daily_intensity(station_id, first, last)
+            .await?
+            .into_iter()
+            .map(|day| (day.date, day))
+            .collect();
+
+        let mut days = Self::build_calendar_days(&totals);
+        for day in &mut days {
+            if let Some(intensity) = intensities.get(&day.date) {
+                day.max_15min_intensity_in_per_hr = Some(intensity.max_15min_intensity_in_per_hr);
+                day.max_1h_intensity_in_per_hr = Some(intensity.max_1h_intensity_in_per_hr);
+            }
+        }
+        Ok(RainfallCalendar {
+            station_id: station_id.to_string(),
+            year: params.year,
+            total_inches: totals.iter().map(|(_, inches)| inches).sum(),
+            max_daily_inches: totals.iter().map(|&(_, inches)| inches).fold(0.0, f64::max),
+            rainy_days: days.iter().filter(|d| d.level > 0).count() as i64,
+            days,
+        })
+    }
+
+    /// Per-month percentiles from a gauge's complete monthly history, alongside
+    /// this year's monthly totals
+    pub async fn get_monthly_normals(&self, station_id: &str) -> Result<MonthlyNormals, DbError> {
+        let now = self.clock.now();
+        let current_month_start = utils::month_date_range(now.year(), now.month()).0;
+
+        let percentiles = self
+            .monthly_rainfall_repo
+            .monthly_percentiles(station_id, current_month_start)
+            .await?;
+
+        let (year_start, year_end) = Self::calendar_year_date_range_only(now.year());
+        let current_year = self
+            .monthly_rainfall_repo
+            .get_summaries_by_date_range(station_id, year_start, year_end)
+            .await?;
+
+        Ok(MonthlyNormals {
+            station_id: station_id.to_string(),
+            current_year: now.year(),
+            as_of: Self::latest_reading(&current_year),
+            months: Self::build_monthly_normals(&percentiles, &current_year, now.month()),
+        })
+    }
+
+    /// Rank gauges by rainfall over a period ending now (or at `as_of`)
+    ///
+    /// The 24h window sums raw readings; month and water year periods sum monthly
+    /// summaries, which cover the current month to date, or their materialized totals
+    /// while fresh (see `with_summary_views`). Under `as_of`, those periods include the
+    /// whole of the month it falls in and always run live.
+    pub async fn get_rankings(&self, params: &RankingParams) -> Result<RankingResponse, DbError> {
+        let now = params.as_of.unwrap_or_else(|| self.clock.now());
+        let driest_first = params.order == RankingOrder::Driest;
+        let (start, rows) = self
+            .ranking_rows(
+                params.period,
+                now,
+                params.as_of.is_none(),
+                driest_first,
+                params.include_inactive,
+                params.limit(),
+            )
+            .await?;
+
+        Ok(RankingResponse {
+            period: params.period,
+            order: params.order,
+            start,
+            end: now,
+            rankings: Self::build_rankings(rows),
+        })
+    }
+
+    /// Rainfall at a set of gauges over a period ending now (or at `as_of`), wettest first
+    ///
+    /// Totals come from the same sources as `get_rankings`, inactive gauges included;
+    /// gauges without readings in the period are left out, and ranks are within the set.
+    pub async fn get_gauge_set_rainfall(
+        &self,
+        station_ids: &[String],
+        period: RankingPeriod,
+        as_of: Option<DateTime<Utc>>,
+    ) -> Result<RankingResponse, DbError> {
+        let now = as_of.unwrap_or_else(|| self.clock.now());
+        let (start, rows) = self
+            .ranking_rows(period, now, as_of.is_none(), false, true, i64::MAX)
+            .await?;
+        let rows = rows
+            .into_iter()
+            .filter(|row| station_ids.contains(&row.station_id))
+            .collect();
+
+        Ok(RankingResponse {
+            period,
+            order: RankingOrder::Wettest,
+            start,
+            end: now,
+            rankings: Self::build_rankings(rows),
+        })
+    }
+
+    /// Aggregate rainfall over a period by forecast zone
+    ///
+    /// Gauges count toward the zone their coordinates fall in, or the zone named by the
+    /// gauge list when they have none assigned. Periods match `get_rankings`.
+    pub async fn get_zone_rainfall(
+        &self,
+        params: &ZoneRainfallParams,
+    ) -> Result<ZoneRainfallResponse, DbError> {
+        let now = params.as_of.unwrap_or_else(|| self.clock.now());
+        let (start, rows) = self
+            .ranking_rows(
+                params.period,
+                now,
+                params.as_of.is_none(),
+                false,
+                params.include_inactive,
+                i64::MAX,
+            )
+            .await?;
+        let (zones, unzoned_gauges) = Self::aggregate_zones(rows);
+
+        Ok(ZoneRainfallResponse {
+            period: params.period,
+            start,
+            end: now,
+            zones,
+            unzoned_gauges,
+        })
+    }
+
+    /// Rank gauges by rainfall over the period, wettest or driest first
+    ///
+    /// `current` is false for periods ending at a requested `as_of`, which summary
+    /// views do not cover.
+    async fn ranking_rows(
+        &self,
+        period: RankingPeriod,
+        now: DateTime<Utc>,
+        current: bool,
+        driest_first: bool,
+        include_inactive: bool,
+        limit: i64,
+    ) -> Result<(DateTime<Utc>, Vec<RankingRow>), DbError> {
+        let (start, rows) = match period {
+            RankingPeriod::Last24Hours => {
+                let start = now - chrono::Duration::hours(24);
+                let rows = self
+                    .reading_repo
+                    .rank_stations_by_rainfall(start, now, driest_first, include_inactive, limit)
+                    .await?;
+                (start, rows)
+            }
+            RankingPeriod::Month | RankingPeriod::WaterYear => {
+                let start = if period == RankingPeriod::Month {
+                    utils::month_date_range(now.year(), now.month()).0
+                } else {
+                    utils::water_year_date_range(Self::get_water_year(now)).0
+                };
+                if let Some(views) = self.summary_views.as_ref().filter(|_| current) {
+                    if let Some(rows) = views
+                        .rank_stations(period, now, driest_first, include_inactive, limit)
+                        .await?
+                    {
+                        return Ok((start, rows));
+                    }
+                }
+                let rows = self
+                    .monthly_rainfall_repo
+                    .rank_stations_by_monthly_totals(
+                        start,
+                        now,
+                        driest_first,
+                        include_inactive,
+                        limit,
+                    )
+                    .await?;
+                (start, rows)
+            }
+        };
+        Ok((start, rows))
+    }
+
     // Business logic helpers (private)
 
-    /// Normalize -0.0 to 0.0 for cleaner API responses
-    fn normalize_zero(value: f64) -> f64 {
-        if value == 0.0 {
-            0.0 // Converts both 0.0 and -0.0 to 0.0
-        } else {
-            value
+    /// Number ranking rows from 1 in the order given
+    fn build_rankings(rows: Vec<RankingRow>) -> Vec<GaugeRanking> {
+        rows.into_iter()
+            .enumerate()
+            .map(|(i, row)| GaugeRanking {
+                rank: i + 1,
+                station_id: row.station_id,
+                gauge_name: row.gauge_name,
+                city_town: row.city_town,
+                msp_forecast_zone: row.msp_forecast_zone,
+                general_location: row.general_location,
+                rainfall_inches: round_inches(row.rainfall_inches),
+                reading_count: row.reading_count,
+            })
+            .collect()
+    }
+
+    /// Group gauge totals by zone, wettest mean first; returns the zones and the number
+    /// of gauges without one
+    fn aggregate_zones(rows: Vec<RankingRow>) -> (Vec<ZoneRainfall>, usize) {
+        let mut unzoned = 0;
+        let mut by_zone: BTreeMap<String, Vec<RankingRow>> = BTreeMap::new();
+        for row in rows {
+            match row.msp_forecast_zone.clone() {
+                Some(zone_id) => by_zone.entry(zone_id).or_default().push(row),
+                None => unzoned += 1,
+            }
         }
+
+        let mut zones: Vec<ZoneRainfall> = by_zone
+            .into_iter()
+            .map(|(zone_id, rows)| {
+                let total: f64 = rows.iter().map(|row| row.rainfall_inches).sum();
+                // Rows arrive wettest first, so the first is the zone's wettest gauge
+                let wettest = &rows[0];
+                ZoneRainfall {
+                    zone_id,
+                    gauge_count: rows.len(),
+                    mean_rainfall_inches: total / rows.len() as f64,
+                    max_rainfall_inches: wettest.rainfall_inches,
+                    wettest_station_id: wettest.station_id.clone(),
+                }
+            })
+            .collect();
+        zones.sort_by(|a, b| {
+            b.mean_rainfall_inches
+                .total_cmp(&a.mean_rainfall_inches)
+                .then_with(|| a.zone_id.cmp(&b.zone_id))
+        });
+        (zones, unzoned)
     }
 
     fn water_year_date_range(water_year: i32) -> (DateTime<Utc>, DateTime<Utc>) {
@@ -150,39 +926,272 @@ impl ReadingService {
         (start_dt, end_dt)
     }
 
+    /// Monthly breakdown of a year, judging completeness against the gauge's FOPR metadata
+    async fn build_year_months(
+        &self,
+        station_id: &str,
+        months: &[(i32, u32)],
+        monthly_summaries_db: &[MonthlyRainfallSummary],
+        fill: Option<MonthFill>,
+    ) -> Result<Vec<MonthlySummary>, DbError> {
+        let metadata = self.gauge_repo.find_metadata(station_id).await?;
+        Ok(Self::build_monthly_summaries(
+            months,
+            monthly_summaries_db,
+            fill,
+            metadata.as_ref(),
+            self.clock.now().date_naive(),
+        ))
+    }
+
+    /// One entry per month in `months` that has a summary row, or per month when filling
     fn build_monthly_summaries(
-        monthly_summaries_db: &[crate::db::MonthlyRainfallSummary],
+        months: &[(i32, u32)],
+        monthly_summaries_db: &[MonthlyRainfallSummary],
+        fill: Option<MonthFill>,
+        metadata: Option<&GaugeMetadata>,
+        today: NaiveDate,
     ) -> Vec<MonthlySummary> {
         let mut summaries = Vec::new();
         let mut cumulative_ytd = 0.0;
 
         // Create a map for quick lookup
-        let db_map: HashMap<i32, &crate::db::MonthlyRainfallSummary> =
-            monthly_summaries_db.iter().map(|s| (s.month, s)).collect();
-
-        for month in 1..=12 {
-            if let Some(db_summary) = db_map.get(&month) {
-                cumulative_ytd += db_summary.total_rainfall_inches;
-                summaries.push(MonthlySummary {
-                    month: month as u32,
-                    month_name: Self::get_month_name(month as u32),
-                    readings_count: db_summary.reading_count as usize,
-                    monthly_rainfall_inches: db_summary.total_rainfall_inches,
-                    cumulative_ytd_inches: cumulative_ytd,
-                });
-            } else {
+        let db_map: HashMap<(i32, u32), &MonthlyRainfallSummary> = monthly_summaries_db
+            .iter()
+            .map(|s| ((s.year, s.month as u32), s))
+            .collect();
+
+        for &(year, month) in months {
+            let db_summary = db_map.get(&(year, month)).copied();
+            let (readings_count, monthly_rainfall_inches) = match (db_summary, fill) {
+                (Some(db_summary), _) => {
+                    cumulative_ytd += db_summary.total_rainfall_inches;
+                    (
+                        Some(db_summary.reading_count as usize),
+                        Some(db_summary.total_rainfall_inches),
+                    )
+                }
                 // Month with no data - rainfall is 0, but maintain cumulative
-                summaries.push(MonthlySummary {
-                    month: month as u32,
-                    month_name: Self::get_month_name(month as u32),
-                    readings_count: 0,
-                    monthly_rainfall_inches: 0.0,
-                    cumulative_ytd_inches: cumulative_ytd,
+                (None, Some(MonthFill::Zero)) => (Some(0), Some(0.0)),
+                (None, Some(MonthFill::Null)) => (None, None),
+                (None, None) => continue,
+            };
+
+            summaries.push(MonthlySummary {
+                year,
+                month,
+                month_name: Self::get_month_name(month),
+                readings_count,
+                monthly_rainfall_inches,
+                cumulative_ytd_inches: cumulative_ytd,
+                flagged_count: db_summary.and_then(|m| m.flagged_count),
+                estimated_count: db_summary.and_then(|m| m.estimated_count),
+                footnoted_count: db_summary.and_then(|m| m.footnoted_count),
+                quality_grade: db_summary.and_then(MonthlyRainfallSummary::quality_grade),
+                is_complete: Self::month_is_complete(
+                    year,
+                    month,
+                    readings_count.unwrap_or(0),
+                    metadata,
+                    today,
+                ),
+                is_partial: Self::month_is_partial(year, month, today),
+            });
+        }
+
+        summaries
+    }
+
+    /// A year is only as trustworthy as its worst graded month
+    fn worst_grade(months: &[MonthlySummary]) -> Option<QualityGrade> {
+        months.iter().filter_map(|m| m.quality_grade).max()
+    }
+
+    /// Latest reading behind a set of monthly summaries, which is what their totals
+    /// run through
+    fn latest_reading(months: &[MonthlyRainfallSummary]) -> Option<DateTime<Utc>> {
+        months.iter().filter_map(|m| m.last_reading_date).max()
+    }
+
+    /// Whether `now` falls inside the half-open range `[start, end)`
+    fn range_is_partial(start: DateTime<Utc>, end: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        start <= now && now < end
+    }
+
+    /// Whether `today` falls inside the month
+    fn month_is_partial(year: i32, month: u32, today: NaiveDate) -> bool {
+        NaiveDate::from_ymd_opt(year, month, 1)
+            .is_some_and(|first_day| first_day <= today && today < first_day + Months::new(1))
+    }
+
+    /// Whether a month's record is final
+    ///
+    /// Dry months leave no readings, so a month without any only counts as complete when
+    /// the gauge's FOPR record reports no missing months. Months still in progress, or
+    /// only partly inside the gauge's record (from data_begins_date to data_ends_date),
+    /// never are.
+    fn month_is_complete(
+        year: i32,
+        month: u32,
+        readings_count: usize,
+        metadata: Option<&GaugeMetadata>,
+        today: NaiveDate,
+    ) -> bool {
+        let Some(first_day) = NaiveDate::from_ymd_opt(year, month, 1) else {
+            return false;
+        };
+        let last_day = first_day + Months::new(1) - Days::new(1);
+        if last_day >= today {
+            return false;
+        }
+
+        if let Some(metadata) = metadata {
+            if metadata.data_begins_date.is_some_and(|d| d > first_day)
+                || metadata.data_ends_date.is_some_and(|d| d < last_day)
+            {
+                return false;
+            }
+        }
+
+        readings_count > 0 || metadata.is_some_and(|m| m.missing_months_count == Some(0))
+    }
+
+    /// Roll per-month, per-source counts up into month, year, and source totals
+    fn build_coverage(station_id: &str, rows: &[CoverageRow]) -> GaugeCoverage {
+        let mut sources: BTreeMap<&str, SourceCoverage> = BTreeMap::new();
+        let mut years: Vec<YearCoverage> = Vec::new();
+
+        // Rows arrive ordered by year, month, data_source
+        for row in rows {
+            sources
+                .entry(row.data_source.as_str())
+                .and_modify(|s| {
+                    s.reading_count += row.reading_count;
+                    s.earliest_reading = s.earliest_reading.min(row.first_reading);
+                    s.latest_reading = s.latest_reading.max(row.last_reading);
+                })
+                .or_insert_with(|| SourceCoverage {
+                    data_source: row.data_source.clone(),
+                    reading_count: row.reading_count,
+                    earliest_reading: row.first_reading,
+                    latest_reading: row.last_reading,
+                });
+
+            if years.last().map(|y| y.year) != Some(row.year) {
+                years.push(YearCoverage {
+                    year: row.year,
+                    reading_count: 0,
+                    months: Vec::new(),
                 });
             }
+            let year = years.last_mut().expect("year just pushed");
+            year.reading_count += row.reading_count;
+
+            match year.months.last_mut() {
+                Some(month) if month.month == row.month as u32 => {
+                    month.reading_count += row.reading_count;
+                    month.earliest_reading = month.earliest_reading.min(row.first_reading);
+                    month.latest_reading = month.latest_reading.max(row.last_reading);
+                    month.data_sources.push(row.data_source.clone());
+                }
+                _ => year.months.push(MonthCoverage {
+                    month: row.month as u32,
+                    reading_count: row.reading_count,
+                    earliest_reading: row.first_reading,
+                    latest_reading: row.last_reading,
+                    data_sources: vec![row.data_source.clone()],
+                }),
+            }
         }
 
-        summaries
+        GaugeCoverage {
+            station_id: station_id.to_string(),
+            total_readings: years.iter().map(|y| y.reading_count).sum(),
+            earliest_reading: rows.iter().map(|r| r.first_reading).min(),
+            latest_reading: rows.iter().map(|r| r.last_reading).max(),
+            data_sources: sources.into_values().collect(),
+            years,
+        }
+    }
+
+    /// Expand sparse `(bin_index, count)` pairs into contiguous bins from zero
+    fn build_histogram_bins(counts: &[(i32, i64)], bin_width: f64) -> Vec<HistogramBin> {
+        let counts: HashMap<i32, i64> = counts.iter().copied().collect();
+        let Some(&last) = counts.keys().max() else {
+            return Vec::new();
+        };
+
+        // Round bounds to avoid 0.30000000000000004 in responses
+        let bound = |i: i32| (i as f64 * bin_width * 1e6).round() / 1e6;
+
+        (0..=last)
+            .map(|i| HistogramBin {
+                min_inches: bound(i),
+                max_inches: bound(i + 1),
+                day_count: counts.get(&i).copied().unwrap_or(0),
+            })
+            .collect()
+    }
+
+    /// Place daily totals on a Sunday-first week grid and scale them to levels 0-4
+    fn build_calendar_days(totals: &[(NaiveDate, f64)]) -> Vec<CalendarDay> {
+        let Some(&(first, _)) = totals.first() else {
+            return Vec::new();
+        };
+        // Days hidden by rounding count as dry, so a 0.00 cell never gets a color
+        let max = round_inches(totals.iter().map(|&(_, inches)| inches).fold(0.0, f64::max));
+        let lead = first.weekday().num_days_from_sunday();
+
+        totals
+            .iter()
+            .map(|&(date, inches)| {
+                let inches = round_inches(inches);
+                let level = if inches <= 0.0 {
+                    0
+                } else {
+                    (inches / max * 4.0).ceil().clamp(1.0, 4.0) as u8
+                };
+                CalendarDay {
+                    date,
+                    rainfall_inches: inches,
+                    level,
+                    week: (date.ordinal0() + lead) / 7,
+                    weekday: date.weekday().num_days_from_sunday(),
+                    max_15min_intensity_in_per_hr: None,
+                    max_1h_intensity_in_per_hr: None,
+                }
+            })
+            .collect()
+    }
+
+    fn build_monthly_normals(
+        percentiles: &[MonthPercentileRow],
+        current_year: &[MonthlyRainfallSummary],
+        current_month: u32,
+    ) -> Vec<MonthlyNormal> {
+        let current: HashMap<i32, f64> = current_year
+            .iter()
+            .map(|s| (s.month, s.total_rainfall_inches))
+            .collect();
+
+        percentiles
+            .iter()
+            .map(|p| MonthlyNormal {
+                month: p.month as u32,
+                month_name: Self::get_month_name(p.month as u32),
+                years_of_record: p.years_of_record,
+                mean_inches: p.mean_inches,
+                min_inches: p.min_inches,
+                p10_inches: p.p10_inches,
+                p25_inches: p.p25_inches,
+                p50_inches: p.p50_inches,
+                p75_inches: p.p75_inches,
+                p90_inches: p.p90_inches,
+                max_inches: p.max_inches,
+                current_year_inches: current.get(&p.month).copied(),
+                is_partial: p.month as u32 == current_month,
+            })
+            .collect()
     }
 
     fn get_month_name(month: u32) -> String {
@@ -233,4 +1242,439 @@ mod tests {
         let date3 = Utc.with_ymd_and_hms(2025, 10, 1, 0, 0, 0).unwrap();
         assert_eq!(ReadingService::get_water_year(date3), 2026);
     }
+
+    fn coverage_row(year: i32, month: i32, source: &str, count: i64, day: u32) -> CoverageRow {
+        CoverageRow {
+            year,
+            month,
+            data_source: source.to_string(),
+            reading_count: count,
+            first_reading: Utc
+                .with_ymd_and_hms(year, month as u32, day, 0, 0, 0)
+                .unwrap(),
+            last_reading: Utc
+                .with_ymd_and_hms(year, month as u32, day + 1, 0, 0, 0)
+                .unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_build_coverage_groups_by_year_month_and_source() {
+        let rows = vec![
+            coverage_row(2023, 12, "excel_WY_2024", 4, 10),
+            coverage_row(2024, 1, "excel_WY_2024", 3, 5),
+            coverage_row(2024, 1, "live_scrape", 2, 20),
+            coverage_row(2024, 3, "live_scrape", 1, 1),
+        ];
+
+        let coverage = ReadingService::build_coverage("59700", &rows);
+
+        assert_eq!(coverage.total_readings, 10);
+        assert_eq!(
+            coverage.earliest_reading,
+            Some(Utc.with_ymd_and_hms(2023, 12, 10, 0, 0, 0).unwrap())
+        );
+        assert_eq!(
+            coverage.latest_reading,
+            Some(Utc.with_ymd_and_hms(2024, 3, 2, 0, 0, 0).unwrap())
+        );
+
+        assert_eq!(coverage.data_sources.len(), 2);
+        assert_eq!(coverage.data_sources[0].data_source, "excel_WY_2024");
+        assert_eq!(coverage.data_sources[0].reading_count, 7);
+        assert_eq!(coverage.data_sources[1].reading_count, 3);
+
+        assert_eq!(coverage.years.len(), 2);
+        let y2024 = &coverage.years[1];
+        assert_eq!(y2024.reading_count, 6);
+        assert_eq!(y2024.months.len(), 2);
+        assert_eq!(y2024.months[0].month, 1);
+        assert_eq!(y2024.months[0].reading_count, 5);
+        assert_eq!(
+            y2024.months[0].data_sources,
+            vec!["excel_WY_2024".to_string(), "live_scrape".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_build_coverage_empty() {
+        let coverage = ReadingService::build_coverage("59700", &[]);
+        assert_eq!(coverage.total_readings, 0);
+        assert!(coverage.earliest_reading.is_none());
+        assert!(coverage.years.is_empty());
+        assert!(coverage.data_sources.is_empty());
+    }
+
+    fn month_row(year: i32, month: i32, total: f64, count: i32) -> MonthlyRainfallSummary {
+        let now = Utc::now();
+        MonthlyRainfallSummary {
+            id: 0,
+            station_id: "59700".to_string(),
+            year,
+            month,
+            total_rainfall_inches: total,
+            reading_count: count,
+            first_reading_date: None,
+            last_reading_date: None,
+            min_cumulative_inches: None,
+            max_cumulative_inches: None,
+            flagged_count: Some(0),
+            estimated_count: Some(0),
+            footnoted_count: Some(0),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn fopr_metadata(missing_months_count: Option<i32>) -> GaugeMetadata {
+        GaugeMetadata {
+            station_type: None,
+            county: None,
+            previous_station_ids: None,
+            installation_date: None,
+            data_begins_date: NaiveDate::from_ymd_opt(2023, 11, 15),
+            data_ends_date: None,
+            avg_annual_precipitation_inches: None,
+            complete_years_count: None,
+            incomplete_months_count: None,
+            missing_months_count,
+            data_quality_remarks: None,
+            fopr_available: Some(true),
+            fopr_last_import_date: None,
+        }
+    }
+
+    #[test]
+    fn test_build_monthly_summaries_fill() {
+        let rows = vec![month_row(2023, 12, 1.0, 4), month_row(2024, 2, 0.5, 2)];
+        let months = utils::water_year_months(2024);
+        let today = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let build =
+            |fill| ReadingService::build_monthly_summaries(&months, &rows, fill, None, today);
+
+        let listed = build(None);
+        assert_eq!(listed.len(), 2);
+        assert_eq!((listed[0].year, listed[0].month), (2023, 12));
+        assert_eq!((listed[1].year, listed[1].month), (2024, 2));
+        assert_eq!(listed[1].cumulative_ytd_inches, 1.5);
+        assert!(listed.iter().all(|m| !m.is_partial));
+
+        let zeros = build(Some(MonthFill::Zero));
+        assert_eq!(zeros.len(), 12);
+        assert_eq!((zeros[0].year, zeros[0].month), (2023, 10));
+        assert_eq!(zeros[3].readings_count, Some(0));
+        assert_eq!(zeros[3].monthly_rainfall_inches, Some(0.0));
+        assert_eq!(zeros[3].cumulative_ytd_inches, 1.0);
+
+        assert_eq!(listed[0].quality_grade, Some(QualityGrade::A));
+        assert_eq!(zeros[3].quality_grade, None);
+
+        let nulls = build(Some(MonthFill::Null));
+        assert_eq!(nulls.len(), 12);
+        assert_eq!(nulls[3].readings_count, None);
+        assert_eq!(nulls[3].monthly_rainfall_inches, None);
+        assert_eq!(nulls[4].monthly_rainfall_inches, Some(0.5));
+        assert_eq!(nulls[11].cumulative_ytd_inches, 1.5);
+    }
+
+    #[test]
+    fn test_quality_grade_from_counts() {
+        assert_eq!(
+            QualityGrade::from_counts(20, 0, 0, 0),
+            Some(QualityGrade::A)
+        );
+        assert_eq!(
+            QualityGrade::from_counts(20, 0, 0, 2),
+            Some(QualityGrade::A)
+        );
+        assert_eq!(
+            QualityGrade::from_counts(20, 0, 0, 3),
+            Some(QualityGrade::B)
+        );
+        assert_eq!(
+            QualityGrade::from_counts(20, 1, 0, 0),
+            Some(QualityGrade::B)
+        );
+        assert_eq!(
+            QualityGrade::from_counts(20, 2, 2, 1),
+            Some(QualityGrade::B)
+        );
+        assert_eq!(
+            QualityGrade::from_counts(20, 0, 6, 0),
+            Some(QualityGrade::C)
+        );
+        assert_eq!(QualityGrade::from_counts(0, 0, 0, 0), None);
+
+        let mut month = month_row(2024, 1, 0.2, 20);
+        month.estimated_count = Some(10);
+        assert_eq!(month.quality_grade(), Some(QualityGrade::C));
+        month.flagged_count = None;
+        assert_eq!(month.quality_grade(), None, "unknown counts aren't graded");
+        assert_eq!(
+            ReadingService::worst_grade(&ReadingService::build_monthly_summaries(
+                &[(2024, 1), (2024, 2)],
+                &[month_row(2024, 1, 0.2, 20), {
+                    let mut m = month_row(2024, 2, 0.1, 4);
+                    m.footnoted_count = Some(1);
+                    m
+                }],
+                None,
+                None,
+                NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+            )),
+            Some(QualityGrade::B)
+        );
+    }
+
+    #[test]
+    fn test_month_is_complete() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 31).unwrap();
+        let clean = fopr_metadata(Some(0));
+        let gaps = fopr_metadata(Some(3));
+        let complete = |year, month, count, metadata| {
+            ReadingService::month_is_complete(year, month, count, metadata, today)
+        };
+
+        // Months with readings are complete once they end
+        assert!(complete(2024, 2, 5, None));
+        assert!(!complete(2024, 3, 5, None), "still in progress");
+        // Without readings, only a gauge reporting no missing months vouches for a dry month
+        assert!(!complete(2024, 1, 0, None));
+        assert!(!complete(2024, 1, 0, Some(&gaps)));
+        assert!(complete(2024, 1, 0, Some(&clean)));
+        // Data began mid-November 2023
+        assert!(!complete(2023, 11, 5, Some(&clean)));
+        assert!(complete(2023, 12, 0, Some(&clean)));
+
+        let ended = GaugeMetadata {
+            data_ends_date: NaiveDate::from_ymd_opt(2024, 1, 31),
+            ..fopr_metadata(Some(0))
+        };
+        assert!(complete(2024, 1, 0, Some(&ended)));
+        assert!(!complete(2024, 2, 0, Some(&ended)));
+    }
+
+    #[test]
+    fn test_month_is_partial() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        assert!(ReadingService::month_is_partial(2024, 3, today));
+        assert!(!ReadingService::month_is_partial(2024, 2, today));
+        assert!(!ReadingService::month_is_partial(2024, 4, today));
+        assert!(!ReadingService::month_is_partial(2023, 3, today));
+
+        let last_day = NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
+        assert!(ReadingService::month_is_partial(2024, 12, last_day));
+    }
+
+    #[test]
+    fn test_year_as_of_and_partial() {
+        let at = |y, m, d| {
+            NaiveDate::from_ymd_opt(y, m, d)
+                .unwrap()
+                .and_hms_opt(12, 0, 0)
+                .unwrap()
+                .and_utc()
+        };
+        let mut december = month_row(2023, 12, 1.0, 4);
+        december.last_reading_date = Some(at(2023, 12, 20));
+        let mut february = month_row(2024, 2, 0.5, 2);
+        february.last_reading_date = Some(at(2024, 2, 9));
+
+        assert_eq!(
+            ReadingService::latest_reading(&[february.clone(), december]),
+            Some(at(2024, 2, 9))
+        );
+        assert_eq!(ReadingService::latest_reading(&[]), None);
+
+        let (start, end) = ReadingService::water_year_date_range(2024);
+        assert!(ReadingService::range_is_partial(start, end, at(2024, 2, 9)));
+        assert!(!ReadingService::range_is_partial(start, end, end));
+        assert!(!ReadingService::range_is_partial(
+            start,
+            end,
+            at(2023, 9, 30)
+        ));
+    }
+
+    #[test]
+    fn test_build_histogram_bins_fills_gaps() {
+        let bins = ReadingService::build_histogram_bins(&[(0, 10), (3, 2)], 0.1);
+
+        assert_eq!(bins.len(), 4);
+        assert_eq!(bins[0].day_count, 10);
+        assert_eq!(bins[1].day_count, 0);
+        assert_eq!(bins[3].min_inches, 0.3);
+        assert_eq!(bins[3].max_inches, 0.4);
+        assert_eq!(bins[3].day_count, 2);
+    }
+
+    #[test]
+    fn test_build_histogram_bins_empty() {
+        assert!(ReadingService::build_histogram_bins(&[], 0.1).is_empty());
+    }
+
+    #[test]
+    fn test_build_calendar_days_levels_and_grid() {
+        // 2025-01-01 is a Wednesday, so January 4 ends week 0
+        let day = |d: u32| NaiveDate::from_ymd_opt(2025, 1, d).unwrap();
+        let totals = [
+            (day(1), 0.0),
+            (day(2), 0.001),
+            (day(3), 0.25),
+            (day(4), 1.0),
+            (day(5), 0.26),
+        ];
+        let days = ReadingService::build_calendar_days(&totals);
+
+        let levels: Vec<u8> = days.iter().map(|d| d.level).collect();
+        assert_eq!(levels, vec![0, 0, 1, 4, 2]);
+        assert_eq!(days[1].rainfall_inches, 0.0);
+        assert_eq!((days[0].week, days[0].weekday), (0, 3));
+        assert_eq!((days[3].week, days[3].weekday), (0, 6));
+        assert_eq!((days[4].week, days[4].weekday), (1, 0));
+    }
+
+    #[test]
+    fn test_histogram_params_validation() {
+        let params = |bin: f64, start: Option<NaiveDate>, end: Option<NaiveDate>| HistogramParams {
+            bin,
+            start,
+            end,
+        };
+        let jan = NaiveDate::from_ymd_opt(2024, 1, 1);
+        let feb = NaiveDate::from_ymd_opt(2024, 2, 1);
+
+        assert!(params(0.1, jan, feb).validate().is_ok());
+        assert!(params(0.0, None, None).validate().is_err());
+        assert!(params(-0.5, None, None).validate().is_err());
+        assert!(params(f64::NAN, None, None).validate().is_err());
+        assert!(params(0.1, feb, jan).validate().is_err());
+    }
+
+    #[test]
+    fn test_query_limits() {
+        let limits = ReadingQueryLimits {
+            max_span_days: 31,
+            max_rows: 100,
+        };
+        let day = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+
+        assert!(limits.check_span(day(1, 1), day(1, 31)).is_ok());
+        assert!(matches!(
+            limits.check_span(day(1, 1), day(2, 1)),
+            Err(ReadingQueryError::SpanTooLarge {
+                span_days: 32,
+                max_days: 31
+            })
+        ));
+        assert!(limits.check_rows(100).is_ok());
+        assert!(matches!(
+            limits.check_rows(101),
+            Err(ReadingQueryError::TooManyRows { rows: 101, .. })
+        ));
+    }
+
+    #[test]
+    fn test_build_monthly_normals_attaches_current_year() {
+        let percentile = |month: i32| MonthPercentileRow {
+            month,
+            years_of_record: 10,
+            mean_inches: 1.0,
+            min_inches: 0.0,
+            p10_inches: 0.1,
+            p25_inches: 0.4,
+            p50_inches: 0.9,
+            p75_inches: 1.4,
+            p90_inches: 2.0,
+            max_inches: 3.5,
+        };
+        let now = Utc::now();
+        let current = MonthlyRainfallSummary {
+            id: 1,
+            station_id: "59700".to_string(),
+            year: 2025,
+            month: 8,
+            total_rainfall_inches: 2.2,
+            reading_count: 12,
+            first_reading_date: None,
+            last_reading_date: None,
+            min_cumulative_inches: None,
+            max_cumulative_inches: None,
+            flagged_count: Some(0),
+            estimated_count: Some(0),
+            footnoted_count: Some(0),
+            created_at: now,
+            updated_at: now,
+        };
+
+        let normals =
+            ReadingService::build_monthly_normals(&[percentile(7), percentile(8)], &[current], 8);
+
+        assert_eq!(normals.len(), 2);
+        assert_eq!(normals[0].month_name, "July");
+        assert_eq!(normals[0].current_year_inches, None);
+        assert_eq!(normals[1].p50_inches, 0.9);
+        assert_eq!(normals[1].current_year_inches, Some(2.2));
+        assert!(!normals[0].is_partial);
+        assert!(normals[1].is_partial);
+    }
+
+    #[test]
+    fn test_ranking_params_limit_is_clamped() {
+        let params = |limit: u32| RankingParams {
+            period: RankingPeriod::Month,
+            order: RankingOrder::Wettest,
+            limit,
+            include_inactive: false,
+            as_of: None,
+        };
+        assert_eq!(params(0).limit(), 1);
+        assert_eq!(params(20).limit(), 20);
+        assert_eq!(params(500).limit(), 100);
+    }
+
+    #[test]
+    fn test_ranking_period_deserializes_api_names() {
+        let period: RankingPeriod = serde_json::from_str(r#""24h""#).unwrap();
+        assert_eq!(period, RankingPeriod::Last24Hours);
+        let period: RankingPeriod = serde_json::from_str(r#""water-year""#).unwrap();
+        assert_eq!(period.as_str(), "water-year");
+    }
+
+    #[test]
+    fn test_aggregate_zones() {
+        let row = |station_id: &str, zone: Option<&str>, inches: f64| RankingRow {
+            station_id: station_id.to_string(),
+            gauge_name: station_id.to_string(),
+            city_town: None,
+            msp_forecast_zone: zone.map(str::to_string),
+            general_location: None,
+            rainfall_inches: inches,
+            reading_count: 1,
+        };
+        let rows = vec![
+            row("1", Some("E1"), 2.0),
+            row("2", Some("W1"), 1.5),
+            row("3", None, 1.2),
+            row("4", Some("W1"), 1.1),
+            row("5", Some("E1"), 0.2),
+        ];
+
+        let (zones, unzoned) = ReadingService::aggregate_zones(rows);
+
+        assert_eq!(unzoned, 1);
+        let summary: Vec<_> = zones
+            .iter()
+            .map(|z| {
+                (
+                    z.zone_id.as_str(),
+                    z.gauge_count,
+                    z.wettest_station_id.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(summary, [("W1", 2, "2"), ("E1", 2, "1")]);
+        assert!((zones[0].mean_rainfall_inches - 1.3).abs() < 1e-9);
+        assert_eq!(zones[1].max_rainfall_inches, 2.0);
+    }
 }
@@ -0,0 +1,337 @@
+use std::collections::HashSet;
+
+use chrono::{Datelike, Days, NaiveDate, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Serialize;
+use sqlx::PgPool;
+use tracing::{info, instrument};
+
+use crate::db::{DbError, GaugeRepository, MonthlyRainfallRepository, ReadingRepository};
+use crate::fopr::MetaStatsData;
+use crate::gauge_list_fetcher::GaugeSummary as FetchedGauge;
+use crate::importers::excel_importer::HistoricalReading;
+use crate::station_id::StationId;
+use crate::units::Inches;
+use crate::utils;
+
+/// Data source tag for synthetic readings
+pub const SEED_DATA_SOURCE: &str = "synthetic_seed";
+
+/// Synthetic station IDs are 99001, 99002, ... to stay clear of real MCFCD IDs
+const SEED_STATION_BASE: u32 = 99000;
+
+/// Maximum number of synthetic gauges (keeps IDs within the 99xxx range)
+pub const MAX_SEED_GAUGES: u32 = 999;
+
+/// Daily chance of rain by month (Jan..Dec), shaped like the Phoenix area:
+/// a winter storm season and the Jul-Sep monsoon, with a dry late spring.
+const RAIN_DAY_PROBABILITY: [f64; 12] = [
+    0.10, 0.10, 0.09, 0.04, 0.02, 0.02, 0.12, 0.14, 0.08, 0.05, 0.05, 0.09,
+];
+
+/// Mean rainfall (inches) on a rainy day by month (Jan..Dec)
+const MEAN_RAIN_DAY_INCHES: [f64; 12] = [
+    0.25, 0.25, 0.22, 0.15, 0.10, 0.10, 0.35, 0.35, 0.30, 0.25, 0.20, 0.25,
+];
+
+/// ALERT tipping-bucket resolution
+const BUCKET_INCHES: f64 = 0.04;
+
+const CITIES: [&str; 8] = [
+    "Phoenix",
+    "Mesa",
+    "Scottsdale",
+    "Chandler",
+    "Glendale",
+    "Tempe",
+    "Peoria",
+    "Cave Creek",
+];
+
+/// Options for generating synthetic data
+#[derive(Debug, Clone)]
+pub struct SeedOptions {
+    pub gauges: u32,
+    /// Number of water years of history, ending with the current water year
+    pub years: u32,
+    /// RNG seed for reproducible output
+    pub rng_seed: u64,
+    /// Last day to generate (normally today)
+    pub end_date: NaiveDate,
+}
+
+impl SeedOptions {
+    pub fn new(gauges: u32, years: u32, rng_seed: u64) -> Self {
+        Self {
+            gauges,
+            years,
+            rng_seed,
+            end_date: Utc::now().date_naive(),
+        }
+    }
+
+    /// First day of the oldest water year in range
+    pub fn start_date(&self) -> NaiveDate {
+        let current_wy = if self.end_date.month() >= 10 {
+            self.end_date.year() + 1
+        } else {
+            self.end_date.year()
+        };
+        let first_wy = current_wy - self.years.max(1) as i32 + 1;
+        utils::water_year_date_range(first_wy).0.date_naive()
+    }
+}
+
+/// A generated gauge with its readings
+#[derive(Debug, Clone)]
+pub struct SyntheticGauge {
+    pub metadata: MetaStatsData,
+    pub summary: FetchedGauge,
+    pub readings: Vec<HistoricalReading>,
+}
+
+/// Totals from a seed run
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SeedStats {
+    pub gauges_created: usize,
+    pub readings_inserted: usize,
+    pub duplicates_skipped: usize,
+    pub months_summarized: usize,
+}
+
+impl SeedStats {
+    pub fn add(&mut self, other: &SeedStats) {
+        self.gauges_created += other.gauges_created;
+        self.readings_inserted += other.readings_inserted;
+        self.duplicates_skipped += other.duplicates_skipped;
+        self.months_summarized += other.months_summarized;
+    }
+}
+
+/// Deterministic generator of synthetic gauges and rainfall
+pub struct SyntheticDataGenerator {
+    rng: StdRng,
+    options: SeedOptions,
+}
+
+impl SyntheticDataGenerator {
+    pub fn new(options: SeedOptions) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(options.rng_seed),
+            options,
+        }
+    }
+
+    /// Generate all gauges and their daily readings
+    pub fn generate(&mut self) -> Vec<SyntheticGauge> {
+        (1..=self.options.gauges.min(MAX_SEED_GAUGES))
+            .map(|n| self.generate_gauge(n))
+            .collect()
+    }
+
+    fn generate_gauge(&mut self, n: u32) -> SyntheticGauge {
+        let station_id = StationId::parse(&(SEED_STATION_BASE + n).to_string())
+            .expect("seed station IDs are 5 digits");
+        let latitude = self.rng.gen_range(33.25..33.95);
+        let longitude = self.rng.gen_range(-112.60..-111.60);
+        let elevation_ft = self.rng.gen_range(1000..5000);
+        let city = CITIES[self.rng.gen_range(0..CITIES.len())].to_string();
+
+        // Higher gauges catch more rain (~+10% per 1000 ft above the valley floor)
+        let elevation_factor = 1.0 + (elevation_ft - 1000) as f64 / 10_000.0;
+        let readings = self.generate_readings(&station_id, elevation_factor);
+
+        let start_date = self.options.start_date();
+        let station_name = format!("Synthetic Gauge {n:03}");
+
+        let mut fopr_metadata = serde_json::Map::new();
+        fopr_metadata.insert("synthetic".to_string(), serde_json::Value::Bool(true));
+
+        let metadata = MetaStatsData {
+            station_id: station_id.clone(),
+            station_name: station_name.clone(),
+            previous_station_ids: vec![],
+            station_type: "Rain".to_string(),
+            latitude,
+            longitude,
+            elevation_ft: Some(elevation_ft),
+            county: "Maricopa".to_string(),
+            city: Some(city.clone()),
+            location_description: Some(format!("Synthetic gauge near {city}")),
+            installation_date: Some(start_date),
+            data_begins_date: Some(start_date),
+            status: "Active".to_string(),
+            avg_annual_precipitation_inches: Some(8.0 * elevation_factor),
+            complete_years_count: Some(self.options.years as i32),
+            incomplete_months_count: 0,
+            missing_months_count: 0,
+            data_quality_remarks: Some("Synthetic data generated by the seed command".to_string()),
+            fopr_metadata,
+        };
+
+        let summary = FetchedGauge {
+            station_id,
+            gauge_name: station_name,
+            city_town: Some(city),
+            elevation_ft: Some(elevation_ft),
+            rainfall_past_6h_inches: Some(Inches::ZERO),
+            rainfall_past_24h_inches: Some(Inches::ZERO),
+            msp_forecast_zone: None,
+            general_location: metadata.location_description.clone(),
+        };
+
+        SyntheticGauge {
+            metadata,
+            summary,
+            readings,
+        }
+    }
+
+    /// Daily readings on rainy days only, with seasonal probability and amounts
+    fn generate_readings(&mut self, station_id: &StationId, factor: f64) -> Vec<HistoricalReading> {
+        let mut readings = Vec::new();
+        let mut date = self.options.start_date();
+
+        while date <= self.options.end_date {
+            let month_idx = date.month0() as usize;
+            if self.rng.gen::<f64>() < RAIN_DAY_PROBABILITY[month_idx] {
+                // Exponentially distributed amounts: many light days, occasional heavy ones
+                let u: f64 = self.rng.gen();
+                let inches = -MEAN_RAIN_DAY_INCHES[month_idx] * factor * (1.0 - u).ln();
+                let buckets = (inches / BUCKET_INCHES).round().max(1.0);
+
+                readings.push(HistoricalReading {
+                    station_id: station_id.clone(),
+                    reading_date: date,
+                    rainfall_inches: Inches::new((buckets * BUCKET_INCHES * 100.0).round() / 100.0)
+                        .expect("at least one bucket"),
+                    footnote_marker: None,
+                });
+            }
+
+            date = match date.checked_add_days(Days::new(1)) {
+                Some(next) => next,
+                None => break,
+            };
+        }
+
+        readings
+    }
+}
+
+/// Service that writes synthetic gauges and readings into a database
+///
+/// For development and load testing only; readings are tagged with
+/// `data_source = 'synthetic_seed'`.
+#[derive(Clone)]
+pub struct SeedService {
+    gauge_repo: GaugeRepository,
+    reading_repo: ReadingRepository,
+    monthly_repo: MonthlyRainfallRepository,
+}
+
+impl SeedService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            gauge_repo: GaugeRepository::new(pool.clone()),
+            reading_repo: ReadingRepository::new(pool.clone()),
+            monthly_repo: MonthlyRainfallRepository::new(pool),
+        }
+    }
+
+    /// Insert one synthetic gauge, its readings, and monthly summaries
+    #[instrument(skip(self, gauge), fields(station_id = %gauge.metadata.station_id))]
+    pub async fn seed_gauge(&self, gauge: &SyntheticGauge) -> Result<SeedStats, DbError> {
+        let station_id = &gauge.metadata.station_id;
+
+        self.gauge_repo
+            .upsert_gauge_metadata(&gauge.metadata)
+            .await?;
+        self.gauge_repo
+            .upsert_summaries(std::slice::from_ref(&gauge.summary))
+            .await?;
+
+        let (inserted, duplicates, affected_months) = self
+            .reading_repo
+            .bulk_insert_historical_readings(station_id, SEED_DATA_SOURCE, &gauge.readings)
+            .await?;
+
+        let months: HashSet<(i32, u32)> = affected_months.into_iter().collect();
+        if let (Some(first), Some(last)) = (months.iter().min(), months.iter().max()) {
+            self.monthly_repo
+                .recalculate_station_summaries(
+                    station_id,
+                    utils::month_date_range(first.0, first.1).0,
+                    utils::month_date_range(last.0, last.1).1,
+                )
+                .await?;
+        }
+
+        info!(
+            station_id = %station_id,
+            inserted = inserted,
+            "Seeded synthetic gauge"
+        );
+
+        Ok(SeedStats {
+            gauges_created: 1,
+            readings_inserted: inserted,
+            duplicates_skipped: duplicates,
+            months_summarized: months.len(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> SeedOptions {
+        SeedOptions {
+            gauges: 3,
+            years: 2,
+            rng_seed: 42,
+            end_date: NaiveDate::from_ymd_opt(2025, 3, 15).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_start_date_covers_requested_water_years() {
+        // March 2025 is in WY 2025, so 2 years starts Oct 1, 2023
+        assert_eq!(
+            options().start_date(),
+            NaiveDate::from_ymd_opt(2023, 10, 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_generation_is_deterministic() {
+        let a = SyntheticDataGenerator::new(options()).generate();
+        let b = SyntheticDataGenerator::new(options()).generate();
+
+        assert_eq!(a.len(), 3);
+        assert_eq!(a[0].metadata.station_id, "99001");
+        assert_eq!(a[2].metadata.station_id, "99003");
+        assert_eq!(a[0].readings.len(), b[0].readings.len());
+        assert_eq!(a[1].metadata.latitude, b[1].metadata.latitude);
+    }
+
+    #[test]
+    fn test_generated_data_is_plausible() {
+        let bounds = crate::fopr::ValidationBounds::default();
+        let gauges = SyntheticDataGenerator::new(options()).generate();
+
+        for gauge in &gauges {
+            assert!(bounds.validate_latitude(gauge.metadata.latitude).is_ok());
+            assert!(bounds.validate_longitude(gauge.metadata.longitude).is_ok());
+            assert!(!gauge.readings.is_empty());
+
+            for reading in &gauge.readings {
+                assert!(reading.rainfall_inches.value() >= BUCKET_INCHES - f64::EPSILON);
+                assert!(reading.reading_date >= options().start_date());
+                assert!(reading.reading_date <= options().end_date);
+            }
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::clock::{self, SharedClock};
+use crate::db::{
+    DbError, DbPool, RankingPeriod, ShareLink, ShareRepository, SharedSnapshot, User,
+    UserRepository,
+};
+use crate::services::user_service::unique_station_ids;
+use crate::services::ReadingService;
+use crate::share::ShareSigner;
+use crate::station_id::StationId;
+
+/// A gauge set and period to freeze behind a share link
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+pub struct ShareRequest {
+    /// Gauges in display order; repeats are dropped
+    #[validate(length(min = 1, max = 100, message = "must list 1-100 gauges"))]
+    #[schema(value_type = Vec<String>, example = json!(["59700", "11000"]))]
+    pub station_ids: Vec<StationId>,
+    pub period: RankingPeriod,
+    /// Freeze the period ending at this time instead of now (RFC 3339)
+    pub as_of: Option<DateTime<Utc>>,
+    /// Hours until the link expires (default 24, max 168)
+    #[serde(default = "default_expires_in_hours")]
+    #[validate(range(min = 1, max = 168, message = "must be between 1 and 168"))]
+    #[schema(example = 24)]
+    pub expires_in_hours: u32,
+}
+
+fn default_expires_in_hours() -> u32 {
+    24
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ShareError {
+    #[error("Share links are disabled; SHARE_SIGNING_KEY is not set")]
+    Disabled,
+
+    #[error("Gauges not found: {}", .0.join(", "))]
+    GaugesNotFound(Vec<String>),
+
+    #[error("Share link not found")]
+    NotFound,
+
+    #[error("Share link expired at {0}")]
+    Expired(DateTime<Utc>),
+
+    #[error("Database error: {0}")]
+    Database(#[from] DbError),
+}
+
+/// Frozen rainfall snapshots behind signed, expiring share links
+#[derive(Clone)]
+pub struct ShareService {
+    repo: ShareRepository,
+    users: UserRepository,
+    reading_service: ReadingService,
+    signer: Option<ShareSigner>,
+    clock: SharedClock,
+}
+
+impl ShareService {
+    /// Sharing stays disabled until `with_signer` provides a key
+    pub fn new(pool: impl Into<DbPool>, reading_service: ReadingService) -> Self {
+        let pool = pool.into();
+        Self {
+            repo: ShareRepository::new(pool.clone()),
+            users: UserRepository::new(pool),
+            reading_service,
+            signer: None,
+            clock: clock::system_clock(),
+        }
+    }
+
+    pub fn with_signer(mut self, signer: ShareSigner) -> Self {
+        self.signer = Some(signer);
+        self
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Freeze the request's rainfall and sign a link to it (the request must already be
+    /// validated)
+    ///
+    /// Expired snapshots are deleted before the new one is stored, so the table only holds
+    /// live links.
+    #[instrument(skip(self, user, request), fields(user_id = user.id, period = ?request.period))]
+    pub async fn create(
+        &self,
+        user: &User,
+        request: &ShareRequest,
+    ) -> Result<ShareLink, ShareError> {
+        let signer = self.signer.as_ref().ok_or(ShareError::Disabled)?;
+        let station_ids = unique_station_ids(&request.station_ids);
+        let missing = self.users.find_missing_gauges(&station_ids).await?;
+        if !missing.is_empty() {
+            return Err(ShareError::GaugesNotFound(missing));
+        }
+
+        let now = self.clock.now();
+        // Tokens carry whole seconds
+        let expires_at = DateTime::from_timestamp(
+            now.timestamp() + i64::from(request.expires_in_hours) * 3600,
+            0,
+        )
+        .unwrap_or(DateTime::<Utc>::MAX_UTC);
+        let rainfall = self
+            .reading_service
+            .get_gauge_set_rainfall(&station_ids, request.period, request.as_of)
+            .await?;
+        let snapshot = SharedSnapshot {
+            station_ids,
+            period: rainfall.period,
+            start: rainfall.start,
+            end: rainfall.end,
+            rankings: rainfall.rankings,
+            created_at: now,
+            expires_at,
+        };
+
+        let expired = self.repo.delete_expired(now).await?;
+        if expired > 0 {
+            info!("Deleted {} expired share snapshots", expired);
+        }
+        // Strings, numbers, and timestamps only; serializing cannot fail
+        let json = serde_json::to_value(&snapshot).expect("snapshot serializes to JSON");
+        let id = self
+            .repo
+            .insert(
+                user.id,
+                &snapshot.station_ids,
+                snapshot.period,
+                &json,
+                expires_at,
+            )
+            .await?;
+
+        let token = signer.sign(id, expires_at);
+        info!(
+            "User {} shared {} gauges until {}",
+            user.id,
+            snapshot.station_ids.len(),
+            expires_at
+        );
+        Ok(ShareLink {
+            path: format!("/share/{token}"),
+            token,
+            expires_at,
+        })
+    }
+
+    /// The snapshot behind a share link, as stored
+    pub async fn open(&self, token: &str) -> Result<serde_json::Value, ShareError> {
+        let signer = self.signer.as_ref().ok_or(ShareError::Disabled)?;
+        let (id, expires_at) = signer.verify(token).ok_or(ShareError::NotFound)?;
+        let now = self.clock.now();
+        if expires_at <= now {
+            return Err(ShareError::Expired(expires_at));
+        }
+
+        self.repo.find(id, now).await?.ok_or(ShareError::NotFound)
+    }
+}
@@ -0,0 +1,35 @@
+use serde::Deserialize;
+use utoipa::IntoParams;
+use validator::Validate;
+
+use crate::db::{DbError, SlowQueryCapture, SlowQueryRepository};
+
+// Slow query capture listing parameters (used by API)
+#[derive(Debug, Clone, Deserialize, IntoParams, Validate)]
+pub struct SlowQueryParams {
+    /// Maximum captures to return (default 20, max 100)
+    #[serde(default = "default_capture_limit")]
+    #[validate(range(min = 1, max = 100, message = "must be between 1 and 100"))]
+    pub limit: u32,
+}
+
+fn default_capture_limit() -> u32 {
+    20
+}
+
+/// EXPLAIN captures recorded by db::SlowQueryLog
+#[derive(Clone)]
+pub struct SlowQueryService {
+    repo: SlowQueryRepository,
+}
+
+impl SlowQueryService {
+    pub fn new(repo: SlowQueryRepository) -> Self {
+        Self { repo }
+    }
+
+    /// Most recent captures first
+    pub async fn recent(&self, params: &SlowQueryParams) -> Result<Vec<SlowQueryCapture>, DbError> {
+        self.repo.find_recent(params.limit as i64).await
+    }
+}
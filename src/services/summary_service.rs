@@ -0,0 +1,275 @@
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinSet;
+use tracing::{debug, info, instrument};
+use utoipa::ToSchema;
+
+use crate::db::{
+    DbError, HourlyRainfallRepository, MonthlyRainfallRepository, SummaryViewRepository,
+};
+use crate::utils;
+
+/// Default number of stations recalculated concurrently
+pub const DEFAULT_RECALC_CONCURRENCY: usize = 8;
+
+/// Which summaries to rebuild
+///
+/// All fields are optional; an empty scope means the whole database.
+/// Dates are inclusive calendar days (UTC).
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct RecalcScope {
+    /// Restrict to one station
+    pub station_id: Option<String>,
+    /// First day to include (inclusive)
+    pub start_date: Option<NaiveDate>,
+    /// Last day to include (inclusive)
+    pub end_date: Option<NaiveDate>,
+}
+
+impl RecalcScope {
+    /// Scope covering one water year, optionally for a single station
+    pub fn water_year(station_id: Option<String>, water_year: i32) -> Self {
+        let (start, end) = utils::water_year_date_range(water_year);
+        Self {
+            station_id,
+            start_date: Some(start.date_naive()),
+            end_date: end.date_naive().checked_sub_days(Days::new(1)),
+        }
+    }
+
+    /// Convert the inclusive date range into a half-open UTC timestamp range
+    fn bounds(&self) -> (Option<DateTime<Utc>>, Option<DateTime<Utc>>) {
+        let start = self
+            .start_date
+            .map(|d| d.and_hms_opt(0, 0, 0).unwrap().and_utc());
+        let end = self
+            .end_date
+            .and_then(|d| d.checked_add_days(Days::new(1)))
+            .map(|d| d.and_hms_opt(0, 0, 0).unwrap().and_utc());
+        (start, end)
+    }
+}
+
+/// Outcome of a recalculation run
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RecalcStats {
+    /// Station-months whose monthly summary was rebuilt from readings
+    pub months_recalculated: usize,
+    /// Summaries deleted because no readings remain for that month
+    pub orphaned_summaries_deleted: u64,
+    /// Station-hours whose hourly summary was rebuilt from sub-daily readings
+    pub hours_recalculated: u64,
+    pub duration_secs: f64,
+}
+
+/// Service that rebuilds pre-aggregated summaries from raw readings
+///
+/// Needed after corrections, rollbacks, or schema changes. Rebuilds monthly
+/// summaries, and hourly ones when given an hourly repository; other summary tables
+/// should be added here as they appear.
+/// Summary views derived from them are flagged stale rather than rebuilt (see
+/// `SummaryViewService`).
+#[derive(Clone)]
+pub struct SummaryService {
+    monthly_repo: MonthlyRainfallRepository,
+    hourly_repo: Option<HourlyRainfallRepository>,
+    view_repo: Option<SummaryViewRepository>,
+    concurrency: usize,
+}
+
+impl SummaryService {
+    pub fn new(monthly_repo: MonthlyRainfallRepository) -> Self {
+        Self {
+            monthly_repo,
+            hourly_repo: None,
+            view_repo: None,
+            concurrency: DEFAULT_RECALC_CONCURRENCY,
+        }
+    }
+
+    /// Rebuild hourly summaries alongside the monthly ones
+    pub fn with_hourly(mut self, hourly_repo: HourlyRainfallRepository) -> Self {
+        self.hourly_repo = Some(hourly_repo);
+        self
+    }
+
+    /// Flag summary views stale after each recalculation
+    pub fn with_views(mut self, view_repo: SummaryViewRepository) -> Self {
+        self.view_repo = Some(view_repo);
+        self
+    }
+
+    /// Set how many stations are recalculated concurrently (minimum 1)
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// List the station-months in scope that have readings
+    #[instrument(skip(self))]
+    pub async fn station_months(
+        &self,
+        scope: &RecalcScope,
+    ) -> Result<Vec<(String, i32, u32)>, DbError> {
+        let (start, end) = scope.bounds();
+        self.monthly_repo
+            .find_station_months(scope.station_id.as_deref(), start, end)
+            .await
+    }
+
+    /// Rebuild monthly summaries in scope, several stations at a time, then hourly ones
+    ///
+    /// `on_progress(done, total)` is called in station-months as each station completes.
+    #[instrument(skip(self, on_progress))]
+    pub async fn recalculate<F>(
+        &self,
+        scope: &RecalcScope,
+        on_progress: F,
+    ) -> Result<RecalcStats, DbError>
+    where
+        F: FnMut(usize, usize),
+    {
+        let start_time = Instant::now();
+        let months = self.station_months(scope).await?;
+        let total = months.len();
+
+        info!(
+            month_count = total,
+            concurrency = self.concurrency,
+            "Recalculating monthly summaries"
+        );
+
+        self.recalculate_months(&months, on_progress).await?;
+
+        let (start, end) = scope.bounds();
+        let orphaned_summaries_deleted = self
+            .monthly_repo
+            .delete_orphaned_summaries(scope.station_id.as_deref(), start, end)
+            .await?;
+        let hours_recalculated = match &self.hourly_repo {
+            Some(hourly_repo) => {
+                hourly_repo
+                    .recalculate_hours(scope.station_id.as_deref(), start, end)
+                    .await?
+            }
+            None => 0,
+        };
+        if let Some(view_repo) = &self.view_repo {
+            if total > 0 || orphaned_summaries_deleted > 0 {
+                view_repo
+                    .mark_stale("Monthly summaries recalculated")
+                    .await?;
+            }
+        }
+
+        let stats = RecalcStats {
+            months_recalculated: total,
+            orphaned_summaries_deleted,
+            hours_recalculated,
+            duration_secs: start_time.elapsed().as_secs_f64(),
+        };
+
+        info!(
+            months_recalculated = stats.months_recalculated,
+            orphaned_deleted = stats.orphaned_summaries_deleted,
+            hours_recalculated = stats.hours_recalculated,
+            duration_secs = %format!("{:.2}", stats.duration_secs),
+            "Monthly summary recalculation complete"
+        );
+
+        Ok(stats)
+    }
+
+    /// Rebuild the monthly summaries of the given station-months
+    ///
+    /// Each station's months are rebuilt by one set-based statement spanning its first
+    /// to last month, with up to `concurrency` stations in flight. Months in between that
+    /// have readings are rebuilt too. Returns the number of months summarized.
+    #[instrument(skip(self, months, on_progress), fields(month_count = months.len()))]
+    pub async fn recalculate_months<F>(
+        &self,
+        months: &[(String, i32, u32)],
+        mut on_progress: F,
+    ) -> Result<usize, DbError>
+    where
+        F: FnMut(usize, usize),
+    {
+        let mut by_station: BTreeMap<&str, Vec<(i32, u32)>> = BTreeMap::new();
+        for (station_id, year, month) in months {
+            by_station
+                .entry(station_id)
+                .or_default()
+                .push((*year, *month));
+        }
+
+        let total = months.len();
+        let mut stations = by_station.into_iter();
+        let mut tasks = JoinSet::new();
+        let (mut done, mut summarized) = (0, 0);
+        loop {
+            while tasks.len() < self.concurrency {
+                let Some((station_id, station_months)) = stations.next() else {
+                    break;
+                };
+                let repo = self.monthly_repo.clone();
+                let station_id = station_id.to_string();
+                let first = station_months.iter().min().copied().unwrap();
+                let last = station_months.iter().max().copied().unwrap();
+                tasks.spawn(async move {
+                    let start = utils::month_date_range(first.0, first.1).0;
+                    let end = utils::month_date_range(last.0, last.1).1;
+                    let summarized = repo
+                        .recalculate_station_summaries(&station_id, start, end)
+                        .await?;
+                    Ok::<_, DbError>((station_months.len(), summarized))
+                });
+            }
+
+            let Some(result) = tasks.join_next().await else {
+                break;
+            };
+            let (station_months, station_summarized) =
+                result.expect("summary recalculation task panicked")?;
+            done += station_months;
+            summarized += station_summarized;
+            debug!(done = done, total = total, "Station recalculation complete");
+            on_progress(done, total);
+        }
+
+        Ok(summarized)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_water_year_scope_dates() {
+        let scope = RecalcScope::water_year(Some("59700".to_string()), 2025);
+        assert_eq!(scope.start_date, NaiveDate::from_ymd_opt(2024, 10, 1));
+        assert_eq!(scope.end_date, NaiveDate::from_ymd_opt(2025, 9, 30));
+    }
+
+    #[test]
+    fn test_scope_bounds_end_is_exclusive_next_day() {
+        let scope = RecalcScope {
+            station_id: None,
+            start_date: NaiveDate::from_ymd_opt(2024, 1, 1),
+            end_date: NaiveDate::from_ymd_opt(2024, 1, 31),
+        };
+        let (start, end) = scope.bounds();
+        assert_eq!(start.unwrap().to_rfc3339(), "2024-01-01T00:00:00+00:00");
+        assert_eq!(end.unwrap().to_rfc3339(), "2024-02-01T00:00:00+00:00");
+    }
+
+    #[test]
+    fn test_empty_scope_is_unbounded() {
+        let (start, end) = RecalcScope::default().bounds();
+        assert!(start.is_none());
+        assert!(end.is_none());
+    }
+}
@@ -0,0 +1,195 @@
+use chrono::{DateTime, Datelike, Duration, Utc};
+use serde::Serialize;
+use tracing::{debug, info, instrument};
+use utoipa::ToSchema;
+
+use crate::clock::{self, SharedClock};
+use crate::db::{DbError, DbPool, RankingPeriod, RankingRow, SummaryView, SummaryViewRepository};
+
+/// How often the summary views are refreshed
+pub const DEFAULT_VIEW_REFRESH_MINUTES: u64 = 15;
+
+/// Views refreshed longer ago than this are not read
+pub const DEFAULT_VIEW_MAX_AGE_MINUTES: u64 = 60;
+
+/// Settings for serving rankings and zone aggregates from summary views
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SummaryViewConfig {
+    /// SUMMARY_VIEW_REFRESH_MINUTES, default 15
+    pub refresh_minutes: u64,
+    /// SUMMARY_VIEW_MAX_AGE_MINUTES, default 60
+    pub max_age_minutes: u64,
+}
+
+impl Default for SummaryViewConfig {
+    fn default() -> Self {
+        Self {
+            refresh_minutes: DEFAULT_VIEW_REFRESH_MINUTES,
+            max_age_minutes: DEFAULT_VIEW_MAX_AGE_MINUTES,
+        }
+    }
+}
+
+impl SummaryViewConfig {
+    /// Whether `view` can stand in for the live query at `now`
+    ///
+    /// It must not be flagged stale, must be younger than the max age, and must have
+    /// been refreshed in the same UTC month as `now` so its periods are the current ones.
+    pub fn is_fresh(&self, view: &SummaryView, now: DateTime<Utc>) -> bool {
+        let Some(refreshed_at) = view.refreshed_at else {
+            return false;
+        };
+        !view.stale
+            && refreshed_at <= now
+            && now - refreshed_at <= Duration::minutes(self.max_age_minutes as i64)
+            && (refreshed_at.year(), refreshed_at.month()) == (now.year(), now.month())
+    }
+}
+
+/// Summary views and whether queries are reading them
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SummaryViewReport {
+    /// SUMMARY_VIEWS_ENABLED; when false every query runs live
+    pub enabled: bool,
+    pub views: Vec<SummaryViewStatus>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SummaryViewStatus {
+    #[serde(flatten)]
+    pub view: SummaryView,
+    /// Queries read this view instead of running live
+    pub in_use: bool,
+}
+
+/// Materialized views behind rankings and zone aggregates
+///
+/// Month and water-year rankings read gauge_period_rainfall while it is fresh (see
+/// [`SummaryViewConfig::is_fresh`]) and run live otherwise.
+#[derive(Clone)]
+pub struct SummaryViewService {
+    repo: SummaryViewRepository,
+    config: Option<SummaryViewConfig>,
+    clock: SharedClock,
+}
+
+impl SummaryViewService {
+    /// Views are only read with a `config`; without one every query runs live
+    pub fn new(pool: impl Into<DbPool>, config: Option<SummaryViewConfig>) -> Self {
+        Self {
+            repo: SummaryViewRepository::new(pool),
+            config,
+            clock: clock::system_clock(),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Every view's refresh state
+    pub async fn status(&self) -> Result<SummaryViewReport, DbError> {
+        let now = self.clock.now();
+        let views = self.repo.find_all().await?;
+
+        Ok(SummaryViewReport {
+            enabled: self.config.is_some(),
+            views: views
+                .into_iter()
+                .map(|view| SummaryViewStatus {
+                    in_use: self.config.is_some_and(|c| c.is_fresh(&view, now)),
+                    view,
+                })
+                .collect(),
+        })
+    }
+
+    /// Recompute every view now
+    #[instrument(skip(self))]
+    pub async fn refresh(&self) -> Result<SummaryViewReport, DbError> {
+        let view = self.repo.refresh_period_rainfall().await?;
+        info!(
+            duration_ms = view.refresh_duration_ms,
+            still_stale = view.stale,
+            "Refreshed summary views"
+        );
+
+        self.status().await
+    }
+
+    /// Gauge totals for a current month or water-year ranking from the view, or None
+    /// when the live query must run (views disabled or not fresh, or a 24h ranking)
+    pub async fn rank_stations(
+        &self,
+        period: RankingPeriod,
+        now: DateTime<Utc>,
+        driest_first: bool,
+        include_inactive: bool,
+        limit: i64,
+    ) -> Result<Option<Vec<RankingRow>>, DbError> {
+        let Some(config) = self.config else {
+            return Ok(None);
+        };
+        let month = match period {
+            RankingPeriod::Month => true,
+            RankingPeriod::WaterYear => false,
+            RankingPeriod::Last24Hours => return Ok(None),
+        };
+
+        let view = self.repo.find_period_rainfall().await?;
+        if !config.is_fresh(&view, now) {
+            debug!(
+                stale = view.stale,
+                refreshed_at = ?view.refreshed_at,
+                "gauge_period_rainfall is not fresh; ranking live"
+            );
+            return Ok(None);
+        }
+
+        self.repo
+            .rank_stations_by_period(month, driest_first, include_inactive, limit)
+            .await
+            .map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn view(refreshed_at: Option<DateTime<Utc>>, stale: bool) -> SummaryView {
+        SummaryView {
+            view_name: "gauge_period_rainfall".to_string(),
+            refreshed_at,
+            refresh_duration_ms: Some(10),
+            stale,
+            stale_reason: None,
+            marked_stale_at: None,
+        }
+    }
+
+    #[test]
+    fn test_is_fresh() {
+        let config = SummaryViewConfig::default();
+        let now = Utc.with_ymd_and_hms(2025, 2, 13, 6, 30, 0).unwrap();
+        let minutes_ago = |m| Some(now - Duration::minutes(m));
+
+        assert!(config.is_fresh(&view(minutes_ago(10), false), now));
+        assert!(!config.is_fresh(&view(minutes_ago(10), true), now));
+        assert!(!config.is_fresh(&view(minutes_ago(61), false), now));
+        assert!(!config.is_fresh(&view(None, false), now));
+        assert!(
+            !config.is_fresh(&view(minutes_ago(-5), false), now),
+            "refreshed after the ranked time"
+        );
+
+        // Refreshed in January, so its month is not the current one
+        let first_of_month = Utc.with_ymd_and_hms(2025, 2, 1, 0, 10, 0).unwrap();
+        assert!(!config.is_fresh(
+            &view(Some(first_of_month - Duration::minutes(20)), false),
+            first_of_month
+        ));
+    }
+}
@@ -0,0 +1,445 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::{IntoParams, ToSchema};
+use validator::{Validate, ValidationError};
+
+use crate::db::threshold_event_repository::{NewThresholdEvent, ThresholdChanges};
+use crate::db::{
+    DbError, GaugeThresholdEvent, OpenThresholdEvent, StormFootprint, StormTimeline,
+    StormTimelineFrame, StormTimelineGauge, ThresholdEventRepository,
+};
+use crate::footprint::hull_geometry;
+use crate::gauge_list_fetcher::GaugeSummary as FetchedGauge;
+use crate::pagination::{self, PageInfo};
+use crate::units::Inches;
+
+/// 24h rainfall thresholds tracked when RAINFALL_THRESHOLDS_INCHES is unset
+pub const DEFAULT_THRESHOLDS_INCHES: [f64; 3] = [0.5, 1.0, 2.0];
+
+/// How far before a crossing a storm starts: the 24h total that crossed began then
+const STORM_LOOKBACK_HOURS: i64 = 24;
+
+/// Thresholds closer than this are treated as the same threshold
+const THRESHOLD_EPSILON: f64 = 0.0001;
+
+// Threshold event query parameters (used by API)
+#[derive(Debug, Clone, Deserialize, IntoParams, Validate)]
+pub struct ThresholdEventParams {
+    /// Only events for this threshold in inches (e.g. 1.0)
+    #[validate(range(exclusive_min = 0.0, message = "must be greater than 0"))]
+    pub threshold: Option<f64>,
+    /// Page number, starting at 1
+    #[serde(default = "default_page")]
+    #[validate(range(min = 1, message = "must be at least 1"))]
+    pub page: u32,
+    /// Events per page (default 50, max 500)
+    #[serde(default = "default_page_size")]
+    #[validate(range(min = 1, max = 500, message = "must be between 1 and 500"))]
+    pub page_size: u32,
+}
+
+fn default_page() -> u32 {
+    1
+}
+
+fn default_page_size() -> u32 {
+    50
+}
+
+/// One page of a gauge's threshold events, newest first
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ThresholdEventPage {
+    pub pagination: PageInfo,
+    pub events: Vec<GaugeThresholdEvent>,
+}
+
+/// Most frames a storm timeline returns; longer storms need a coarser step
+pub const MAX_TIMELINE_FRAMES: i64 = 2000;
+
+// Storm timeline query parameters (used by API)
+#[derive(Debug, Clone, Deserialize, IntoParams, Validate)]
+pub struct StormTimelineParams {
+    /// Frame length in minutes or hours, from 5m to 24h (default 15m)
+    #[serde(default = "default_timeline_step")]
+    #[param(example = "15m")]
+    #[validate(custom(function = "validate_timeline_step"))]
+    pub step: String,
+}
+
+fn default_timeline_step() -> String {
+    "15m".to_string()
+}
+
+impl StormTimelineParams {
+    /// The step as a duration; None when it does not validate
+    pub fn step(&self) -> Option<TimeDelta> {
+        parse_step(&self.step)
+    }
+}
+
+/// Parse a `<n>m` or `<n>h` step between 5 minutes and 24 hours
+fn parse_step(step: &str) -> Option<TimeDelta> {
+    let step = match step.strip_suffix('m') {
+        Some(minutes) => TimeDelta::try_minutes(minutes.parse().ok()?)?,
+        None => TimeDelta::try_hours(step.strip_suffix('h')?.parse().ok()?)?,
+    };
+    (TimeDelta::minutes(5)..=TimeDelta::hours(24))
+        .contains(&step)
+        .then_some(step)
+}
+
+fn validate_timeline_step(step: &str) -> Result<(), ValidationError> {
+    match parse_step(step) {
+        Some(_) => Ok(()),
+        None => Err(ValidationError::new("step").with_message(Cow::Borrowed(
+            "must be minutes or hours such as 15m or 1h, from 5m to 24h",
+        ))),
+    }
+}
+
+/// Why a storm could not be returned
+#[derive(Debug, thiserror::Error)]
+pub enum StormError {
+    #[error("Storm {0} not found")]
+    StormNotFound(i64),
+
+    #[error("{frames} frames exceed the limit of {max} per timeline")]
+    TooManyFrames { frames: i64, max: i64 },
+
+    #[error("Database error: {0}")]
+    Database(#[from] DbError),
+}
+
+#[derive(Clone)]
+pub struct ThresholdService {
+    repo: ThresholdEventRepository,
+    thresholds: Vec<f64>,
+}
+
+impl ThresholdService {
+    pub fn new(repo: ThresholdEventRepository, thresholds: &[f64]) -> Self {
+        Self {
+            repo,
+            thresholds: Self::normalize_thresholds(thresholds),
+        }
+    }
+
+    /// Sort ascending, dropping non-positive and duplicate thresholds
+    fn normalize_thresholds(thresholds: &[f64]) -> Vec<f64> {
+        let mut thresholds: Vec<f64> = thresholds
+            .iter()
+            .copied()
+            .filter(|t| t.is_finite() && *t > 0.0)
+            .collect();
+        thresholds.sort_by(f64::total_cmp);
+        thresholds.dedup_by(|a, b| (*a - *b).abs() < THRESHOLD_EPSILON);
+        thresholds
+    }
+
+    pub fn thresholds(&self) -> &[f64] {
+        &self.thresholds
+    }
+
+    /// Compare a scrape's 24h totals with the thresholds and update events
+    ///
+    /// Only gauges in `gauges` are touched; a gauge missing from the scrape keeps its
+    /// open events until it is seen again. A missing 24h total counts as below.
+    #[instrument(skip(self, gauges), fields(count = gauges.len()))]
+    pub async fn record_crossings(
+        &self,
+        gauges: &[FetchedGauge],
+        observed_at: DateTime<Utc>,
+    ) -> Result<ThresholdChanges, DbError> {
+        let station_ids: Vec<String> = gauges.iter().map(|g| g.station_id.to_string()).collect();
+        let open = self.repo.find_open(&station_ids).await?;
+
+        let changes = Self::plan(&self.thresholds, &open, gauges);
+        if !changes.is_empty() {
+            self.repo.apply(&changes, observed_at).await?;
+        }
+        if !changes.opened.is_empty() {
+            info!("{} new rainfall threshold crossings", changes.opened.len());
+        }
+        Ok(changes)
+    }
+
+    /// Work out which events open, rise, or close
+    fn plan(
+        thresholds: &[f64],
+        open: &[OpenThresholdEvent],
+        gauges: &[FetchedGauge],
+    ) -> ThresholdChanges {
+        let mut changes = ThresholdChanges::default();
+
+        for gauge in gauges {
+            let total = gauge.rainfall_past_24h_inches.map(Inches::value);
+            let gauge_open: Vec<&OpenThresholdEvent> = open
+                .iter()
+                .filter(|e| e.station_id == gauge.station_id)
+                .collect();
+
+            // Open events for thresholds no longer configured are still closed normally
+            for event in &gauge_open {
+                match total {
+                    Some(total) if total >= event.threshold_inches => {
+                        if total > event.peak_rainfall_24h_inches {
+                            changes.raised_peaks.push((event.id, total));
+                        }
+                    }
+                    _ => changes.closed.push(event.id),
+                }
+            }
+
+            let Some(total) = total else { continue };
+            for &threshold in thresholds {
+                let already_open = gauge_open
+                    .iter()
+                    .any(|e| (e.threshold_inches - threshold).abs() < THRESHOLD_EPSILON);
+                if total >= threshold && !already_open {
+                    changes.opened.push(NewThresholdEvent {
+                        station_id: gauge.station_id.to_string(),
+                        threshold_inches: threshold,
+                        rainfall_24h_inches: total,
+                    });
+                }
+            }
+        }
+
+        changes
+    }
+
+    /// A page of a gauge's threshold events, newest first
+    pub async fn get_events(
+        &self,
+        station_id: &str,
+        params: &ThresholdEventParams,
+    ) -> Result<ThresholdEventPage, DbError> {
+        let total = self
+            .repo
+            .count_by_station(station_id, params.threshold)
+            .await?;
+        let events = self
+            .repo
+            .find_by_station(
+                station_id,
+                params.threshold,
+                pagination::offset(params.page, params.page_size),
+                params.page_size as i64,
+            )
+            .await?;
+
+        Ok(ThresholdEventPage {
+            pagination: PageInfo::new(params.page, params.page_size, total as u64),
+            events,
+        })
+    }
+
+    /// Gauges that recorded rain during a threshold event, with the area they cover
+    ///
+    /// The window runs from 24h before the crossing to the end of the event, or to
+    /// `now` while it is open.
+    #[instrument(skip(self))]
+    pub async fn get_footprint(
+        &self,
+        event_id: i64,
+        now: DateTime<Utc>,
+    ) -> Result<StormFootprint, StormError> {
+        let (storm, window_start, window_end) = self.find_storm(event_id, now).await?;
+        let gauges = self.repo.find_footprint(window_start, window_end).await?;
+
+        let points: Vec<(f64, f64)> = gauges
+            .iter()
+            .filter_map(|g| Some((g.longitude?, g.latitude?)))
+            .collect();
+        Ok(StormFootprint {
+            storm,
+            window_start,
+            window_end,
+            gauge_count: gauges.len(),
+            hull: hull_geometry(&points),
+            gauges,
+        })
+    }
+
+    /// Per-gauge rainfall in fixed steps across a threshold event, for map playback
+    ///
+    /// Frames line up with multiples of `step` since the Unix epoch (so a 15m step
+    /// starts frames on the quarter hour) and cover the footprint's window, dry frames
+    /// included.
+    #[instrument(skip(self))]
+    pub async fn get_timeline(
+        &self,
+        event_id: i64,
+        step: TimeDelta,
+        now: DateTime<Utc>,
+    ) -> Result<StormTimeline, StormError> {
+        let (storm, window_start, window_end) = self.find_storm(event_id, now).await?;
+        let step_seconds = step.num_seconds();
+        let first = window_start.timestamp().div_euclid(step_seconds);
+        let last = window_end.timestamp().div_euclid(step_seconds);
+        let frames = last - first + 1;
+        if frames > MAX_TIMELINE_FRAMES {
+            return Err(StormError::TooManyFrames {
+                frames,
+                max: MAX_TIMELINE_FRAMES,
+            });
+        }
+
+        let gauges = self.repo.find_footprint(window_start, window_end).await?;
+        let mut buckets: HashMap<DateTime<Utc>, Vec<(String, f64)>> = HashMap::new();
+        for row in self
+            .repo
+            .find_timeline_buckets(window_start, window_end, step_seconds)
+            .await?
+        {
+            buckets
+                .entry(row.bucket_start)
+                .or_default()
+                .push((row.station_id, row.rainfall_inches));
+        }
+
+        let mut cumulative: HashMap<String, f64> = HashMap::new();
+        let frames = (first..=last)
+            .filter_map(|bucket| DateTime::from_timestamp(bucket * step_seconds, 0))
+            .map(|start| {
+                let mut gauges: Vec<StormTimelineGauge> = buckets
+                    .remove(&start)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(station_id, inches)| {
+                        let total = cumulative.entry(station_id.clone()).or_default();
+                        *total += inches;
+                        StormTimelineGauge {
+                            station_id,
+                            rainfall_inches: inches,
+                            cumulative_inches: *total,
+                        }
+                    })
+                    .collect();
+                gauges.sort_by(|a, b| a.station_id.cmp(&b.station_id));
+                StormTimelineFrame { start, gauges }
+            })
+            .collect();
+
+        Ok(StormTimeline {
+            storm,
+            window_start,
+            window_end,
+            step_minutes: step.num_minutes(),
+            gauges,
+            frames,
+        })
+    }
+
+    /// A threshold event with its storm window
+    async fn find_storm(
+        &self,
+        event_id: i64,
+        now: DateTime<Utc>,
+    ) -> Result<(GaugeThresholdEvent, DateTime<Utc>, DateTime<Utc>), StormError> {
+        let storm = self
+            .repo
+            .find_by_id(event_id)
+            .await?
+            .ok_or(StormError::StormNotFound(event_id))?;
+        let window_start = storm.crossed_at - TimeDelta::hours(STORM_LOOKBACK_HOURS);
+        let window_end = storm.ended_at.unwrap_or(now);
+        Ok((storm, window_start, window_end))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gauge(station_id: &str, total: Option<f64>) -> FetchedGauge {
+        FetchedGauge {
+            station_id: station_id.parse().unwrap(),
+            gauge_name: format!("Gauge {station_id}"),
+            city_town: None,
+            elevation_ft: None,
+            rainfall_past_6h_inches: None,
+            rainfall_past_24h_inches: total.map(|total| Inches::new(total).unwrap()),
+            msp_forecast_zone: None,
+            general_location: None,
+        }
+    }
+
+    fn open(id: i64, station_id: &str, threshold: f64, peak: f64) -> OpenThresholdEvent {
+        OpenThresholdEvent {
+            id,
+            station_id: station_id.to_string(),
+            threshold_inches: threshold,
+            peak_rainfall_24h_inches: peak,
+        }
+    }
+
+    #[test]
+    fn test_normalize_thresholds() {
+        assert_eq!(
+            ThresholdService::normalize_thresholds(&[2.0, 0.5, -1.0, f64::NAN, 1.0, 0.5]),
+            vec![0.5, 1.0, 2.0]
+        );
+    }
+
+    #[test]
+    fn test_plan_opens_each_crossed_threshold() {
+        let changes = ThresholdService::plan(
+            &DEFAULT_THRESHOLDS_INCHES,
+            &[],
+            &[gauge("1000", Some(1.2)), gauge("2000", Some(0.2))],
+        );
+
+        let opened: Vec<f64> = changes.opened.iter().map(|e| e.threshold_inches).collect();
+        assert_eq!(opened, vec![0.5, 1.0]);
+        assert!(changes.opened.iter().all(|e| e.station_id == "1000"));
+        assert!(changes.raised_peaks.is_empty());
+        assert!(changes.closed.is_empty());
+    }
+
+    #[test]
+    fn test_plan_tracks_open_events() {
+        let open_events = [
+            open(1, "1000", 0.5, 1.2),
+            open(2, "1000", 1.0, 1.2),
+            open(3, "2000", 0.5, 0.6),
+        ];
+        let changes = ThresholdService::plan(
+            &DEFAULT_THRESHOLDS_INCHES,
+            &open_events,
+            &[gauge("1000", Some(0.8)), gauge("2000", Some(0.9))],
+        );
+
+        // 1000 fell below 1", still above 0.5"; 2000 rose but stayed under 1"
+        assert!(changes.opened.is_empty());
+        assert_eq!(changes.closed, vec![2]);
+        assert_eq!(changes.raised_peaks, vec![(3, 0.9)]);
+    }
+
+    #[test]
+    fn test_parse_step() {
+        assert_eq!(parse_step("15m"), Some(TimeDelta::minutes(15)));
+        assert_eq!(parse_step("2h"), Some(TimeDelta::hours(2)));
+        assert_eq!(parse_step("24h"), Some(TimeDelta::hours(24)));
+        for invalid in ["4m", "25h", "15", "m", "-15m", "1.5h", "15s", "1é", ""] {
+            assert_eq!(parse_step(invalid), None, "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_plan_missing_total_closes_and_absent_gauge_is_untouched() {
+        let open_events = [open(1, "1000", 0.5, 0.7), open(2, "3000", 0.5, 0.7)];
+        let changes = ThresholdService::plan(
+            &DEFAULT_THRESHOLDS_INCHES,
+            &open_events,
+            &[gauge("1000", None)],
+        );
+
+        assert_eq!(changes.closed, vec![1]);
+        assert!(changes.opened.is_empty());
+    }
+}
@@ -0,0 +1,100 @@
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use tracing::{instrument, warn};
+
+use crate::clock::{self, SharedClock};
+use crate::db::{DbError, DbPool, MonthlyUsage, UsageReport, UsageRepository, User};
+use crate::utils::month_date_range;
+
+#[derive(Debug, thiserror::Error)]
+pub enum UsageError {
+    #[error("Monthly quota exceeded until {}", .0.resets_at)]
+    QuotaExceeded(Box<UsageReport>),
+
+    #[error("Database error: {0}")]
+    Database(#[from] DbError),
+}
+
+/// Per-user request and byte counts by UTC month, checked against the user's quotas
+#[derive(Clone)]
+pub struct UsageService {
+    repo: UsageRepository,
+    clock: SharedClock,
+}
+
+impl UsageService {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        Self {
+            repo: UsageRepository::new(pool),
+            clock: clock::system_clock(),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// First day of the current UTC month
+    fn current_month(&self) -> NaiveDate {
+        let now = self.clock.now();
+        NaiveDate::from_ymd_opt(now.year(), now.month(), 1).expect("day 1 exists")
+    }
+
+    fn report(&self, user: &User, month: NaiveDate, usage: MonthlyUsage) -> UsageReport {
+        let (_, resets_at): (DateTime<Utc>, _) = month_date_range(month.year(), month.month());
+        UsageReport {
+            month,
+            request_count: usage.request_count,
+            response_bytes: usage.response_bytes,
+            monthly_request_quota: user.monthly_request_quota,
+            monthly_byte_quota: user.monthly_byte_quota,
+            resets_at,
+        }
+    }
+
+    /// Count a request by `user`; returns the month it was counted in, for
+    /// `record_bytes`
+    #[instrument(skip(self, user), fields(user_id = user.id))]
+    pub async fn claim(&self, user: &User) -> Result<NaiveDate, UsageError> {
+        let month = self.current_month();
+        let claimed = self
+            .repo
+            .claim(
+                user.id,
+                month,
+                user.monthly_request_quota,
+                user.monthly_byte_quota,
+            )
+            .await?;
+        if claimed.is_some() {
+            return Ok(month);
+        }
+
+        let usage = self.repo.find(user.id, month).await?;
+        warn!(
+            "User {} is over quota: {} requests, {} bytes",
+            user.id, usage.request_count, usage.response_bytes
+        );
+        Err(UsageError::QuotaExceeded(Box::new(
+            self.report(user, month, usage),
+        )))
+    }
+
+    /// Add a response's body size to the month its request was claimed in
+    pub async fn record_bytes(
+        &self,
+        user_id: i64,
+        month: NaiveDate,
+        bytes: u64,
+    ) -> Result<(), DbError> {
+        let bytes = i64::try_from(bytes).unwrap_or(i64::MAX);
+        self.repo.add_bytes(user_id, month, bytes).await
+    }
+
+    /// The user's usage so far this month
+    pub async fn usage(&self, user: &User) -> Result<UsageReport, UsageError> {
+        let month = self.current_month();
+        let usage = self.repo.find(user.id, month).await?;
+        Ok(self.report(user, month, usage))
+    }
+}
@@ -0,0 +1,296 @@
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::db::{DbError, DbPool, FavoriteGauge, RankingPeriod, SavedView, User, UserRepository};
+use crate::station_id::StationId;
+
+/// Random bytes in a user key (hex-encoded, so keys are twice this long)
+const USER_KEY_BYTES: usize = 32;
+
+/// A user to create
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+pub struct NewUser {
+    #[validate(length(min = 1, max = 100, message = "must be 1-100 characters"))]
+    #[schema(example = "jsmith")]
+    pub name: String,
+    /// Requests allowed per UTC month; omit for unlimited
+    #[validate(range(min = 1, message = "must be at least 1"))]
+    #[schema(example = 10000)]
+    pub monthly_request_quota: Option<i64>,
+    /// Response bytes allowed per UTC month; omit for unlimited
+    #[validate(range(min = 1, message = "must be at least 1"))]
+    #[schema(example = 1073741824)]
+    pub monthly_byte_quota: Option<i64>,
+}
+
+/// A user's new monthly quotas; null or omitted means unlimited
+#[derive(Debug, Clone, Default, Deserialize, ToSchema, Validate)]
+pub struct UserQuotas {
+    #[validate(range(min = 1, message = "must be at least 1"))]
+    #[schema(example = 10000)]
+    pub monthly_request_quota: Option<i64>,
+    #[validate(range(min = 1, message = "must be at least 1"))]
+    #[schema(example = 1073741824)]
+    pub monthly_byte_quota: Option<i64>,
+}
+
+/// A newly created user with their key; the key is never shown again
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CreatedUser {
+    pub user: User,
+    /// Send as X-User-Key; only its hash is stored
+    #[schema(example = "3f6c0f5e9a0b4d1c8e7f2a6b5c4d3e2f1a0b9c8d7e6f5a4b3c2d1e0f9a8b7c6d")]
+    pub key: String,
+}
+
+/// A view to save, or a view's new contents
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+pub struct SavedViewRequest {
+    #[validate(length(min = 1, max = 100, message = "must be 1-100 characters"))]
+    #[schema(example = "North Scottsdale")]
+    pub name: String,
+    /// Gauges in display order; repeats are dropped
+    #[validate(length(min = 1, max = 100, message = "must list 1-100 gauges"))]
+    #[schema(value_type = Vec<String>, example = json!(["59700", "11000"]))]
+    pub station_ids: Vec<StationId>,
+    pub period: RankingPeriod,
+}
+
+impl SavedViewRequest {
+    fn unique_station_ids(&self) -> Vec<String> {
+        unique_station_ids(&self.station_ids)
+    }
+}
+
+/// Station IDs with repeats removed, keeping the first occurrence
+pub(crate) fn unique_station_ids(station_ids: &[StationId]) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::with_capacity(station_ids.len());
+    for id in station_ids {
+        if !ids.iter().any(|seen| seen == id.as_ref()) {
+            ids.push(id.to_string());
+        }
+    }
+    ids
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum UserError {
+    #[error("Gauge not found: {0}")]
+    GaugeNotFound(String),
+
+    #[error("Gauges not found: {}", .0.join(", "))]
+    ViewGaugesNotFound(Vec<String>),
+
+    #[error("User {0} not found")]
+    UserNotFound(i64),
+
+    #[error("Saved view {0} not found")]
+    ViewNotFound(i64),
+
+    #[error("Name already in use: {0}")]
+    NameTaken(String),
+
+    #[error("Database error: {0}")]
+    Database(#[from] DbError),
+}
+
+/// Map a unique violation on a name column to NameTaken
+fn name_conflict(name: &str) -> impl FnOnce(DbError) -> UserError + '_ {
+    move |e| match &e {
+        DbError::SqlxError(sqlx::Error::Database(db)) if db.is_unique_violation() => {
+            UserError::NameTaken(name.to_string())
+        }
+        _ => UserError::Database(e),
+    }
+}
+
+/// Hex SHA-256 of a user key, as stored
+fn hash_key(key: &str) -> String {
+    format!("{:x}", Sha256::digest(key.as_bytes()))
+}
+
+/// User accounts keyed by X-User-Key, with favorite gauges and saved views
+#[derive(Clone)]
+pub struct UserService {
+    repo: UserRepository,
+}
+
+impl UserService {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        Self {
+            repo: UserRepository::new(pool),
+        }
+    }
+
+    /// Create a user with a fresh random key (the user must already be validated)
+    #[instrument(skip(self, new_user), fields(name = %new_user.name))]
+    pub async fn create(&self, new_user: &NewUser) -> Result<CreatedUser, UserError> {
+        let mut bytes = [0u8; USER_KEY_BYTES];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        let key: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+
+        let name = new_user.name.trim();
+        let user = self
+            .repo
+            .insert(
+                name,
+                &hash_key(&key),
+                new_user.monthly_request_quota,
+                new_user.monthly_byte_quota,
+            )
+            .await
+            .map_err(name_conflict(name))?;
+
+        info!("Created user {} ({})", user.name, user.id);
+        Ok(CreatedUser { user, key })
+    }
+
+    /// Every user, ordered by name
+    pub async fn list(&self) -> Result<Vec<User>, UserError> {
+        Ok(self.repo.find_all().await?)
+    }
+
+    /// Replace a user's monthly quotas (the quotas must already be validated)
+    #[instrument(skip(self))]
+    pub async fn set_quotas(&self, id: i64, quotas: &UserQuotas) -> Result<User, UserError> {
+        let user = self
+            .repo
+            .set_quotas(id, quotas.monthly_request_quota, quotas.monthly_byte_quota)
+            .await?
+            .ok_or(UserError::UserNotFound(id))?;
+        info!(
+            "Set quotas for user {}: {:?} requests, {:?} bytes",
+            id, user.monthly_request_quota, user.monthly_byte_quota
+        );
+        Ok(user)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn delete(&self, id: i64) -> Result<(), UserError> {
+        if !self.repo.delete(id).await? {
+            return Err(UserError::UserNotFound(id));
+        }
+        info!("Deleted user {}", id);
+        Ok(())
+    }
+
+    /// The user holding a key; None when no user has it
+    pub async fn authenticate(&self, key: &str) -> Result<Option<User>, UserError> {
+        Ok(self.repo.authenticate(&hash_key(key)).await?)
+    }
+
+    pub async fn favorites(&self, user: &User) -> Result<Vec<FavoriteGauge>, UserError> {
+        Ok(self.repo.find_favorites(user.id).await?)
+    }
+
+    #[instrument(skip(self, user), fields(user_id = user.id))]
+    pub async fn add_favorite(&self, user: &User, station_id: &str) -> Result<(), UserError> {
+        if !self.repo.add_favorite(user.id, station_id).await? {
+            return Err(UserError::GaugeNotFound(station_id.to_string()));
+        }
+        Ok(())
+    }
+
+    #[instrument(skip(self, user), fields(user_id = user.id))]
+    pub async fn remove_favorite(&self, user: &User, station_id: &str) -> Result<(), UserError> {
+        Ok(self.repo.remove_favorite(user.id, station_id).await?)
+    }
+
+    pub async fn views(&self, user: &User) -> Result<Vec<SavedView>, UserError> {
+        Ok(self.repo.find_views(user.id).await?)
+    }
+
+    /// Save a new view (the request must already be validated)
+    #[instrument(skip(self, user, request), fields(user_id = user.id))]
+    pub async fn create_view(
+        &self,
+        user: &User,
+        request: &SavedViewRequest,
+    ) -> Result<SavedView, UserError> {
+        let station_ids = self.existing_gauges(request).await?;
+        let name = request.name.trim();
+        let view = self
+            .repo
+            .insert_view(user.id, name, &station_ids, request.period)
+            .await
+            .map_err(name_conflict(name))?;
+
+        info!("User {} saved view {}", user.id, view.id);
+        Ok(view)
+    }
+
+    /// Replace a view's name, gauges, and period (the request must already be validated)
+    #[instrument(skip(self, user, request), fields(user_id = user.id))]
+    pub async fn update_view(
+        &self,
+        user: &User,
+        id: i64,
+        request: &SavedViewRequest,
+    ) -> Result<SavedView, UserError> {
+        let station_ids = self.existing_gauges(request).await?;
+        let name = request.name.trim();
+        self.repo
+            .update_view(user.id, id, name, &station_ids, request.period)
+            .await
+            .map_err(name_conflict(name))?
+            .ok_or(UserError::ViewNotFound(id))
+    }
+
+    #[instrument(skip(self, user), fields(user_id = user.id))]
+    pub async fn delete_view(&self, user: &User, id: i64) -> Result<(), UserError> {
+        if !self.repo.delete_view(user.id, id).await? {
+            return Err(UserError::ViewNotFound(id));
+        }
+        Ok(())
+    }
+
+    /// The request's unique station IDs, rejecting any without a gauge
+    async fn existing_gauges(&self, request: &SavedViewRequest) -> Result<Vec<String>, UserError> {
+        let station_ids = request.unique_station_ids();
+        let missing = self.repo.find_missing_gauges(&station_ids).await?;
+        if !missing.is_empty() {
+            return Err(UserError::ViewGaugesNotFound(missing));
+        }
+        Ok(station_ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view(station_ids: &[&str]) -> SavedViewRequest {
+        SavedViewRequest {
+            name: "North Scottsdale".to_string(),
+            station_ids: station_ids.iter().map(|id| id.parse().unwrap()).collect(),
+            period: RankingPeriod::Month,
+        }
+    }
+
+    #[test]
+    fn test_saved_view_rules() {
+        assert!(view(&["59700"]).validate().is_ok());
+        assert!(view(&[]).validate().is_err());
+
+        let mut unnamed = view(&["59700"]);
+        unnamed.name = String::new();
+        assert!(unnamed.validate().is_err());
+
+        assert_eq!(
+            view(&["59700", "11000", "59700"]).unique_station_ids(),
+            vec!["59700", "11000"]
+        );
+    }
+
+    #[test]
+    fn test_hash_key_is_hex_sha256() {
+        assert_eq!(
+            hash_key("test"),
+            "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"
+        );
+    }
+}
@@ -0,0 +1,260 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use chrono::{Days, NaiveDate};
+use serde::Serialize;
+use tracing::{debug, info, instrument, warn};
+use utoipa::ToSchema;
+
+use crate::clock::{self, SharedClock};
+use crate::db::{DailyWeatherRepository, DbError, DbPool, GaugeRepository, GaugeWeatherDay};
+use crate::forecast::{ForecastError, GridCell, NwsClient};
+use crate::services::reading_service::{ReadingQueryError, ReadingQueryLimits, ReadingRangeParams};
+use crate::weather::{daily_from_forecast, DailyWeather, WeatherSource};
+
+/// Outcome of one weather run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct WeatherStats {
+    /// Gauges with coordinates
+    pub gauges_located: usize,
+    /// Gauges with days stored this run
+    pub gauges_updated: usize,
+    /// Gauges without an AZMET station nearby or outside NWS coverage
+    pub gauges_not_covered: usize,
+    /// Stations or grid cells whose fetch failed (retried next run)
+    pub failed: usize,
+    /// Gauge-days written
+    pub days_stored: u64,
+}
+
+/// A gauge's daily rainfall beside temperature and reference ET
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct GaugeWeather {
+    #[schema(example = "59700")]
+    pub station_id: String,
+    #[schema(example = "2025-01-01")]
+    pub start: NaiveDate,
+    #[schema(example = "2025-01-31")]
+    pub end: NaiveDate,
+    #[schema(example = 1.21)]
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub total_rainfall_inches: f64,
+    /// Reference ET summed over the days that have it
+    #[schema(example = 2.48)]
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub total_et_inches: f64,
+    /// Days in the range with reference ET
+    #[schema(example = 31)]
+    pub et_days: usize,
+    /// Rainfall minus ET over the days with ET; negative is a water deficit
+    #[schema(example = -1.27)]
+    #[serde(serialize_with = "crate::units::serialize_rounded")]
+    pub net_inches: f64,
+    /// Every day of the range, oldest first
+    pub days: Vec<GaugeWeatherDay>,
+}
+
+/// Fetches daily temperature and ET for gauges and combines it with their rainfall
+#[derive(Clone)]
+pub struct WeatherService {
+    weather_repo: DailyWeatherRepository,
+    gauge_repo: GaugeRepository,
+    limits: ReadingQueryLimits,
+    /// NWS grid cell of each looked-up point, keyed by rounded coordinates
+    cells: Arc<Mutex<HashMap<String, GridCell>>>,
+    clock: SharedClock,
+}
+
+impl WeatherService {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        let pool = pool.into();
+        Self {
+            weather_repo: DailyWeatherRepository::new(pool.clone()),
+            gauge_repo: GaugeRepository::new(pool),
+            limits: ReadingQueryLimits::default(),
+            cells: Arc::default(),
+            clock: clock::system_clock(),
+        }
+    }
+
+    pub fn with_query_limits(mut self, limits: ReadingQueryLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Fetch recent daily weather for every gauge with coordinates
+    ///
+    /// A failed station or grid cell is logged and skipped; database errors end the run.
+    #[instrument(skip(self, source))]
+    pub async fn refresh(
+        &self,
+        source: &WeatherSource,
+        history_days: u32,
+    ) -> Result<WeatherStats, DbError> {
+        let gauges = self.gauge_repo.find_locations().await?;
+        let mut stats = WeatherStats {
+            gauges_located: gauges.len(),
+            ..WeatherStats::default()
+        };
+
+        match source {
+            WeatherSource::Azmet { client, stations } => {
+                let mut by_station: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+                for gauge in &gauges {
+                    match stations.nearest(gauge.latitude, gauge.longitude) {
+                        Some(station) => by_station
+                            .entry(&station.id)
+                            .or_default()
+                            .push(gauge.station_id.clone()),
+                        None => {
+                            debug!(station_id = %gauge.station_id, "No AZMET station near gauge");
+                            stats.gauges_not_covered += 1;
+                        }
+                    }
+                }
+
+                let today = self.clock.now().date_naive();
+                let start = today - Days::new(history_days as u64);
+                for (station, station_ids) in by_station {
+                    match client.daily(station, start).await {
+                        Ok(days) => {
+                            let source = format!("azmet:{station}");
+                            self.store(&mut stats, &station_ids, &days, &source).await?;
+                        }
+                        Err(e) => {
+                            warn!(station, error = %e, "AZMET observations failed");
+                            stats.failed += 1;
+                        }
+                    }
+                }
+            }
+            WeatherSource::Nws(client) => {
+                // Latitude of the cell's first gauge, for the ET estimate
+                let mut by_cell: HashMap<GridCell, (f64, Vec<String>)> = HashMap::new();
+                for gauge in &gauges {
+                    match self
+                        .grid_cell(client, gauge.latitude, gauge.longitude)
+                        .await
+                    {
+                        Ok(cell) => by_cell
+                            .entry(cell)
+                            .or_insert_with(|| (gauge.latitude, Vec::new()))
+                            .1
+                            .push(gauge.station_id.clone()),
+                        Err(ForecastError::OutsideCoverage) => {
+                            debug!(station_id = %gauge.station_id, "Gauge is outside NWS coverage");
+                            stats.gauges_not_covered += 1;
+                        }
+                        Err(e) => {
+                            warn!(station_id = %gauge.station_id, error = %e, "NWS point lookup failed");
+                            stats.failed += 1;
+                        }
+                    }
+                }
+
+                for (cell, (latitude, station_ids)) in by_cell {
+                    match client.temperatures(&cell).await {
+                        Ok(temperatures) => {
+                            let days = daily_from_forecast(&temperatures, latitude);
+                            let source = format!("nws:{}/{},{}", cell.office, cell.x, cell.y);
+                            self.store(&mut stats, &station_ids, &days, &source).await?;
+                        }
+                        Err(e) => {
+                            warn!(?cell, error = %e, "NWS temperature forecast failed");
+                            stats.failed += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        info!(
+            gauges_updated = stats.gauges_updated,
+            gauges_not_covered = stats.gauges_not_covered,
+            failed = stats.failed,
+            days_stored = stats.days_stored,
+            "Refreshed gauge weather"
+        );
+        Ok(stats)
+    }
+
+    async fn store(
+        &self,
+        stats: &mut WeatherStats,
+        station_ids: &[String],
+        days: &[DailyWeather],
+        source: &str,
+    ) -> Result<(), DbError> {
+        if days.is_empty() {
+            return Ok(());
+        }
+        stats.days_stored += self
+            .weather_repo
+            .upsert_days(station_ids, days, source)
+            .await?;
+        stats.gauges_updated += station_ids.len();
+        Ok(())
+    }
+
+    /// The NWS grid cell containing a point; cells never change, so lookups are kept
+    async fn grid_cell(
+        &self,
+        client: &NwsClient,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<GridCell, ForecastError> {
+        let key = format!("{latitude:.4},{longitude:.4}");
+        if let Some(cell) = self.cells.lock().unwrap().get(&key) {
+            return Ok(cell.clone());
+        }
+
+        let cell = client.grid_cell(latitude, longitude).await?;
+        self.cells.lock().unwrap().insert(key, cell.clone());
+        Ok(cell)
+    }
+
+    /// Daily rainfall, temperature, and ET for a gauge over an inclusive date range
+    ///
+    /// Returns None when the gauge does not exist.
+    #[instrument(skip(self))]
+    pub async fn get_gauge_weather(
+        &self,
+        station_id: &str,
+        params: &ReadingRangeParams,
+    ) -> Result<Option<GaugeWeather>, ReadingQueryError> {
+        self.limits.check_span(params.start, params.end)?;
+        if !self.gauge_repo.gauge_exists(station_id).await? {
+            return Ok(None);
+        }
+
+        let (start, end) = params.datetime_range();
+        let days = self
+            .weather_repo
+            .find_days(station_id, params.start, params.end, start, end)
+            .await?;
+
+        let with_et = days
+            .iter()
+            .filter_map(|d| d.et_inches.map(|et| (d.rainfall_inches, et)));
+        let (et_days, total_et_inches, net_inches) =
+            with_et.fold((0, 0.0, 0.0), |(count, total_et, net), (rainfall, et)| {
+                (count + 1, total_et + et, net + rainfall - et)
+            });
+
+        Ok(Some(GaugeWeather {
+            station_id: station_id.to_string(),
+            start: params.start,
+            end: params.end,
+            total_rainfall_inches: days.iter().map(|d| d.rainfall_inches).sum(),
+            total_et_inches,
+            et_days,
+            net_inches,
+            days,
+        }))
+    }
+}
@@ -0,0 +1,200 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tracing::{info, instrument, warn};
+use utoipa::ToSchema;
+
+use crate::db::{DbError, DbPool, ForecastZoneRepository};
+use crate::zones::{self, ZoneError, ZonePolygon};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ZoneImportError {
+    #[error(transparent)]
+    Zones(#[from] ZoneError),
+
+    #[error(transparent)]
+    Database(#[from] DbError),
+}
+
+/// Result of assigning gauges to the zones containing them
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ZoneAssignment {
+    /// Stored zones gauges were tested against
+    pub zones: usize,
+    /// Gauges with coordinates
+    pub gauges_located: usize,
+    /// Gauges inside a zone
+    pub gauges_in_zones: usize,
+    /// Gauges whose zone changed
+    pub gauges_changed: u64,
+}
+
+/// Result of importing zone polygons
+#[derive(Debug, Clone, Serialize)]
+pub struct ZoneImportReport {
+    pub zones_imported: usize,
+    /// Stored zones missing from the file, now deleted
+    pub zones_removed: u64,
+    pub assignment: ZoneAssignment,
+}
+
+/// Forecast zones as a GeoJSON FeatureCollection
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ZoneCollection {
+    /// Always "FeatureCollection"
+    #[serde(rename = "type")]
+    #[schema(example = "FeatureCollection")]
+    pub kind: String,
+    pub features: Vec<ZoneFeature>,
+}
+
+/// A forecast zone as a GeoJSON Feature
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ZoneFeature {
+    /// Always "Feature"
+    #[serde(rename = "type")]
+    #[schema(example = "Feature")]
+    pub kind: String,
+    #[schema(example = "E1")]
+    pub id: String,
+    /// GeoJSON Polygon or MultiPolygon, longitude/latitude
+    #[schema(value_type = Object)]
+    pub geometry: serde_json::Value,
+    pub properties: ZoneProperties,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ZoneProperties {
+    #[schema(example = "E1")]
+    pub zone_id: String,
+    #[schema(example = "East Valley")]
+    pub name: Option<String>,
+    /// Gauges whose coordinates fall inside the zone
+    #[schema(example = 12)]
+    pub gauge_count: i64,
+    /// File the zone was imported from
+    #[schema(example = "msp_zones.geojson")]
+    pub source: Option<String>,
+    pub imported_at: DateTime<Utc>,
+}
+
+/// Imports forecast zone polygons and assigns gauges to them by location
+#[derive(Clone)]
+pub struct ZoneService {
+    zone_repo: ForecastZoneRepository,
+}
+
+impl ZoneService {
+    pub fn new(pool: impl Into<DbPool>) -> Self {
+        Self {
+            zone_repo: ForecastZoneRepository::new(pool),
+        }
+    }
+
+    /// Replace the stored zones with a GeoJSON FeatureCollection, then reassign gauges
+    ///
+    /// Nothing is stored when any feature is invalid.
+    #[instrument(skip(self, geojson))]
+    pub async fn import_geojson(
+        &self,
+        geojson: &str,
+        id_property: &str,
+        name_property: &str,
+        source: &str,
+    ) -> Result<ZoneImportReport, ZoneImportError> {
+        let zones = zones::parse_feature_collection(geojson, id_property, name_property)?;
+        let zones_removed = self.zone_repo.replace_all(&zones, source).await?;
+        let assignment = self.assign_gauges().await?;
+
+        Ok(ZoneImportReport {
+            zones_imported: zones.len(),
+            zones_removed,
+            assignment,
+        })
+    }
+
+    /// Assign every gauge with coordinates to the zone containing it
+    ///
+    /// Gauges outside every zone, or without coordinates, have their zone cleared. Does
+    /// nothing until zones are imported.
+    #[instrument(skip(self))]
+    pub async fn assign_gauges(&self) -> Result<ZoneAssignment, DbError> {
+        let zones = self.load_polygons().await?;
+        if zones.is_empty() {
+            return Ok(ZoneAssignment::default());
+        }
+
+        let mut assignment = ZoneAssignment {
+            zones: zones.len(),
+            ..Default::default()
+        };
+        let mut changes = Vec::new();
+        for gauge in self.zone_repo.find_gauge_locations().await? {
+            let zone_id = match (gauge.latitude, gauge.longitude) {
+                (Some(latitude), Some(longitude)) => {
+                    assignment.gauges_located += 1;
+                    zones::zone_for_point(&zones, latitude, longitude).map(str::to_string)
+                }
+                _ => None,
+            };
+            if zone_id.is_some() {
+                assignment.gauges_in_zones += 1;
+            }
+            if zone_id != gauge.forecast_zone {
+                changes.push((gauge.station_id, zone_id));
+            }
+        }
+
+        assignment.gauges_changed = self.zone_repo.assign_gauges(&changes).await?;
+        if assignment.gauges_changed > 0 {
+            info!(
+                gauges_changed = assignment.gauges_changed,
+                gauges_in_zones = assignment.gauges_in_zones,
+                "Reassigned gauges to forecast zones"
+            );
+        }
+        Ok(assignment)
+    }
+
+    /// All zones with their geometry and gauge counts
+    #[instrument(skip(self))]
+    pub async fn zone_collection(&self) -> Result<ZoneCollection, DbError> {
+        let features = self
+            .zone_repo
+            .find_all()
+            .await?
+            .into_iter()
+            .map(|zone| ZoneFeature {
+                kind: "Feature".to_string(),
+                id: zone.zone_id.clone(),
+                geometry: zone.geometry,
+                properties: ZoneProperties {
+                    zone_id: zone.zone_id,
+                    name: zone.name,
+                    gauge_count: zone.gauge_count,
+                    source: zone.source,
+                    imported_at: zone.imported_at,
+                },
+            })
+            .collect();
+
+        Ok(ZoneCollection {
+            kind: "FeatureCollection".to_string(),
+            features,
+        })
+    }
+
+    /// Stored zones as polygons, sorted by zone ID; unreadable geometries are skipped
+    async fn load_polygons(&self) -> Result<Vec<ZonePolygon>, DbError> {
+        Ok(self
+            .zone_repo
+            .find_all()
+            .await?
+            .into_iter()
+            .filter_map(|zone| {
+                ZonePolygon::from_geometry(zone.zone_id.clone(), zone.name, zone.geometry)
+                    .inspect_err(|e| warn!(zone_id = %zone.zone_id, "Skipping zone: {}", e))
+                    .ok()
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,114 @@
+// Public shareable snapshot links
+//
+// A user POSTs a gauge set and period to /api/v1/share. The rainfall totals at that moment
+// are stored as a snapshot and the response carries a signed token; GET /share/{token}
+// serves the stored snapshot to anyone holding the link, without a key, until it expires.
+// Later readings and revisions do not change what recipients see.
+//
+// Tokens are `<snapshot id>.<expiry, Unix seconds>.<hex HMAC-SHA256 of the two>`, signed
+// with SHARE_SIGNING_KEY; sharing is disabled when the key is unset. Rotating the key
+// invalidates every outstanding link. Expired snapshots are deleted as new ones are
+// created. PostgreSQL only.
+
+use chrono::{DateTime, TimeZone, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// Shortest SHARE_SIGNING_KEY accepted at startup
+pub const MIN_SIGNING_KEY_LEN: usize = 32;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Signs and verifies share link tokens
+#[derive(Clone)]
+pub struct ShareSigner {
+    key: Vec<u8>,
+}
+
+impl std::fmt::Debug for ShareSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ShareSigner(***)")
+    }
+}
+
+impl ShareSigner {
+    pub fn new(key: &str) -> Self {
+        Self {
+            key: key.as_bytes().to_vec(),
+        }
+    }
+
+    fn mac(&self, payload: &str) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(&self.key).expect("HMAC accepts any key length");
+        mac.update(payload.as_bytes());
+        mac
+    }
+
+    /// Token for a snapshot that expires at `expires_at` (whole seconds)
+    pub fn sign(&self, id: i64, expires_at: DateTime<Utc>) -> String {
+        let payload = format!("{id}.{}", expires_at.timestamp());
+        let signature = self.mac(&payload).finalize().into_bytes();
+        format!("{payload}.{signature:x}")
+    }
+
+    /// The snapshot ID and expiry a token was signed for; None when it is malformed or
+    /// the signature does not match. Expiry is left to the caller
+    pub fn verify(&self, token: &str) -> Option<(i64, DateTime<Utc>)> {
+        let (payload, signature) = token.rsplit_once('.')?;
+        let (id, expires) = payload.split_once('.')?;
+        let id: i64 = id.parse().ok()?;
+        let expires_at = Utc.timestamp_opt(expires.parse().ok()?, 0).single()?;
+
+        let signature = decode_hex(signature)?;
+        self.mac(payload).verify_slice(&signature).ok()?;
+        Some((id, expires_at))
+    }
+}
+
+/// Hex digits to bytes; None on odd length or anything but a hex digit
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    // from_str_radix alone would accept a `+` sign
+    if !hex.len().is_multiple_of(2) || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signer() -> ShareSigner {
+        ShareSigner::new("0123456789abcdef0123456789abcdef")
+    }
+
+    #[test]
+    fn test_token_round_trip() {
+        let expires_at = Utc.with_ymd_and_hms(2025, 2, 12, 8, 0, 0).unwrap();
+        let token = signer().sign(42, expires_at);
+
+        assert!(token.starts_with("42.1739347200."), "{token}");
+        assert_eq!(signer().verify(&token), Some((42, expires_at)));
+    }
+
+    #[test]
+    fn test_verify_rejects_tampered_tokens() {
+        let expires_at = Utc.with_ymd_and_hms(2025, 2, 12, 8, 0, 0).unwrap();
+        let token = signer().sign(42, expires_at);
+        let signature = token.rsplit_once('.').unwrap().1;
+
+        // Another snapshot, a later expiry, or another key
+        assert_eq!(signer().verify(&format!("43.1739347200.{signature}")), None);
+        assert_eq!(signer().verify(&format!("42.1739433600.{signature}")), None);
+        assert_eq!(
+            ShareSigner::new("another key, also thirty-two chars").verify(&token),
+            None
+        );
+        for malformed in ["", "42", "42.1739347200", "x.1739347200.00", "42.1.zz"] {
+            assert_eq!(signer().verify(malformed), None, "{malformed:?}");
+        }
+    }
+}
@@ -0,0 +1,155 @@
+// Read-only snapshot mode (`sqlite` feature)
+//
+// SNAPSHOT_DIR points at a published snapshot: CSV files that are loaded into an in-memory
+// SQLite database at startup, so the read API can serve demos or archived data without a
+// database server. Nothing is scraped in this mode and the admin API is disabled.
+//
+// Snapshot layout:
+//   gauges.csv    station_id,gauge_name,city_town,elevation_ft,general_location,
+//                 msp_forecast_zone,rainfall_past_6h_inches,rainfall_past_24h_inches
+//   readings.csv  station_id,reading_datetime,cumulative_inches,incremental_inches
+//                 (the `historical-import export --format csv` format; optional)
+//
+// Monthly summaries and current conditions are rebuilt from the readings after loading.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use sqlx::sqlite::SqlitePoolOptions;
+use tracing::{info, instrument};
+
+use crate::db::{
+    CurrentConditionsRepository, DbError, DbPool, GaugeRepository, MonthlyRainfallRepository,
+    ReadingRepository,
+};
+use crate::fetcher::RainReading;
+use crate::gauge_list_fetcher::GaugeSummary as FetchedGauge;
+use crate::services::summary_service::RecalcScope;
+use crate::services::{CurrentConditionsService, SummaryService};
+use crate::units::Inches;
+
+pub const GAUGES_FILE: &str = "gauges.csv";
+pub const READINGS_FILE: &str = "readings.csv";
+
+/// `data_source` recorded for readings loaded from a snapshot
+pub const SNAPSHOT_DATA_SOURCE: &str = "snapshot";
+
+#[derive(Debug, thiserror::Error)]
+pub enum SnapshotError {
+    #[error("Failed to read {}: {source}", path.display())]
+    Csv {
+        path: PathBuf,
+        #[source]
+        source: csv::Error,
+    },
+
+    #[error("{READINGS_FILE} has readings for station {0}, which is not in {GAUGES_FILE}")]
+    UnknownStation(String),
+
+    #[error("Database error: {0}")]
+    Database(#[from] DbError),
+}
+
+/// What was loaded from a snapshot
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotStats {
+    pub gauges: usize,
+    pub readings: usize,
+    pub months_summarized: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReadingRow {
+    station_id: String,
+    reading_datetime: DateTime<Utc>,
+    cumulative_inches: Inches,
+    incremental_inches: Inches,
+}
+
+/// Load the snapshot in `dir` into a new in-memory database
+#[instrument]
+pub async fn load(dir: &Path) -> Result<(DbPool, SnapshotStats), SnapshotError> {
+    let gauges: Vec<FetchedGauge> = read_csv(&dir.join(GAUGES_FILE))?;
+    let readings_path = dir.join(READINGS_FILE);
+    let readings: Vec<ReadingRow> = if readings_path.exists() {
+        read_csv(&readings_path)?
+    } else {
+        Vec::new()
+    };
+
+    // The database lives as long as one connection stays open, so never close the last
+    let pool = SqlitePoolOptions::new()
+        .max_connections(4)
+        .min_connections(1)
+        .idle_timeout(None)
+        .max_lifetime(None)
+        .connect("sqlite::memory:")
+        .await
+        .map_err(DbError::from)?;
+    let db = DbPool::from(pool);
+    db.migrate().await?;
+
+    let gauge_repo = GaugeRepository::new(db.clone());
+    for gauge in &gauges {
+        gauge_repo.register_from_list(gauge).await?;
+    }
+    gauge_repo.upsert_summaries(&gauges).await?;
+
+    let mut by_station: BTreeMap<String, Vec<RainReading>> = BTreeMap::new();
+    for row in readings {
+        by_station
+            .entry(row.station_id)
+            .or_default()
+            .push(RainReading {
+                reading_datetime: row.reading_datetime,
+                cumulative_inches: row.cumulative_inches,
+                incremental_inches: row.incremental_inches,
+            });
+    }
+
+    let reading_repo = ReadingRepository::new(db.clone());
+    let mut reading_count = 0;
+    for (station_id, station_readings) in &by_station {
+        if !gauges.iter().any(|g| &g.station_id == station_id) {
+            return Err(SnapshotError::UnknownStation(station_id.clone()));
+        }
+        reading_count += reading_repo
+            .insert_station_readings(station_id, SNAPSHOT_DATA_SOURCE, station_readings)
+            .await?;
+    }
+
+    let recalc = SummaryService::new(MonthlyRainfallRepository::new(db.clone()))
+        .recalculate(&RecalcScope::default(), |_, _| {})
+        .await?;
+    CurrentConditionsService::new(CurrentConditionsRepository::new(db.clone()))
+        .refresh(Utc::now())
+        .await?;
+
+    let stats = SnapshotStats {
+        gauges: gauges.len(),
+        readings: reading_count,
+        months_summarized: recalc.months_recalculated,
+    };
+    info!(
+        "Loaded snapshot from {}: {} gauges, {} readings",
+        dir.display(),
+        stats.gauges,
+        stats.readings
+    );
+    Ok((db, stats))
+}
+
+fn read_csv<T: for<'de> Deserialize<'de>>(path: &Path) -> Result<Vec<T>, SnapshotError> {
+    let csv_error = |source| SnapshotError::Csv {
+        path: path.to_path_buf(),
+        source,
+    };
+
+    csv::Reader::from_path(path)
+        .map_err(csv_error)?
+        .deserialize()
+        .collect::<Result<Vec<T>, _>>()
+        .map_err(csv_error)
+}
@@ -0,0 +1,311 @@
+// Station ID parsing and normalization
+//
+// MCFCD station IDs are 4 or 5 digit numbers, but they reach us in many shapes: integer
+// and float cells in the water year and FOPR workbooks ("59700.0"), zero-padded strings
+// ("01800" for gauge 1800), and scraped text with notes attached ("29200 since 03/09/18").
+// Every source converts through StationId so the same gauge always gets the same ID, and
+// anything that isn't an ID is rejected with the same error wherever it came from.
+
+use std::borrow::Borrow;
+use std::fmt;
+use std::ops::Deref;
+use std::str::FromStr;
+
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize};
+
+/// Station IDs are stored as VARCHAR(20)
+pub const MAX_STATION_ID_LEN: usize = 20;
+
+/// Fewest digits in an MCFCD station ID; shorter all-digit IDs are never zero-padded
+const MIN_NUMERIC_DIGITS: usize = 4;
+
+/// A normalized station ID: 1-20 ASCII letters, digits, `_`, or `-`
+///
+/// All-digit IDs have no zero padding beyond four digits, so "01800" and "1800" are the
+/// same gauge. Test and synthetic gauges ("TEST_API-001") keep their letters.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, sqlx::Type)]
+#[serde(transparent)]
+#[sqlx(transparent)]
+pub struct StationId(String);
+
+/// Why a value isn't a station ID
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum StationIdError {
+    #[error("must be 1-{MAX_STATION_ID_LEN} letters, digits, '_' or '-'")]
+    Format,
+    #[error("no 4-5 digit station ID in {0:?}")]
+    NotFound(String),
+    #[error("station ID must be a whole non-negative number, got {0}")]
+    NotWholeNumber(f64),
+}
+
+impl StationId {
+    /// Parse an ID given on its own (path segment, CLI argument, workbook header)
+    ///
+    /// Surrounding whitespace and a zero fractional part ("59700.0") are dropped, and
+    /// zero padding is removed from all-digit IDs.
+    pub fn parse(value: &str) -> Result<Self, StationIdError> {
+        let value = value.trim();
+        let value = strip_zero_fraction(value).unwrap_or(value);
+
+        let valid = !value.is_empty()
+            && value.len() <= MAX_STATION_ID_LEN
+            && value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if !valid {
+            return Err(StationIdError::Format);
+        }
+
+        if value.chars().all(|c| c.is_ascii_digit()) {
+            Ok(Self(unpad(value).to_string()))
+        } else {
+            Ok(Self(value.to_string()))
+        }
+    }
+
+    /// Find the 4-5 digit ID in scraped text such as "29200 since 03/09/18"
+    ///
+    /// The first whitespace-separated token of 4-5 digits wins; failing that, 4-5
+    /// leading digits ("1234A" gives 1234).
+    pub fn extract(text: &str) -> Result<Self, StationIdError> {
+        let is_id = |s: &str| (4..=5).contains(&s.len()) && s.chars().all(|c| c.is_ascii_digit());
+
+        if let Some(token) = text
+            .split_whitespace()
+            .map(|token| strip_zero_fraction(token).unwrap_or(token))
+            .find(|token| is_id(token))
+        {
+            return Ok(Self(unpad(token).to_string()));
+        }
+
+        let end = text
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(text.len());
+        if is_id(&text[..end]) {
+            return Ok(Self(unpad(&text[..end]).to_string()));
+        }
+
+        Err(StationIdError::NotFound(text.to_string()))
+    }
+
+    /// Convert a numeric spreadsheet cell (59700 or 59700.0)
+    pub fn from_number(value: f64) -> Result<Self, StationIdError> {
+        if !value.is_finite() || value < 0.0 || value.fract() != 0.0 || value >= 1e20 {
+            return Err(StationIdError::NotWholeNumber(value));
+        }
+        Self::parse(&format!("{value:.0}"))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+/// "59700.0" and "59700.00" without the fraction; None when there isn't a zero fraction
+fn strip_zero_fraction(value: &str) -> Option<&str> {
+    let (whole, fraction) = value.split_once('.')?;
+    let numeric = !whole.is_empty() && whole.chars().all(|c| c.is_ascii_digit());
+    (numeric && !fraction.is_empty() && fraction.chars().all(|c| c == '0')).then_some(whole)
+}
+
+/// Drop leading zeros from an all-digit ID, keeping at least four digits
+fn unpad(digits: &str) -> &str {
+    let zeros = digits.len() - digits.trim_start_matches('0').len();
+    let removable = zeros.min(digits.len().saturating_sub(MIN_NUMERIC_DIGITS));
+    &digits[removable..]
+}
+
+impl FromStr for StationId {
+    type Err = StationIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<String> for StationId {
+    type Error = StationIdError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value)
+    }
+}
+
+impl TryFrom<&str> for StationId {
+    type Error = StationIdError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+}
+
+impl From<StationId> for String {
+    fn from(id: StationId) -> Self {
+        id.0
+    }
+}
+
+impl Deref for StationId {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for StationId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+// Parses inside the visitor (rather than via `try_from`) so axum's path extractor
+// reports which segment held the bad ID
+impl<'de> Deserialize<'de> for StationId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct StationIdVisitor;
+
+        impl Visitor<'_> for StationIdVisitor {
+            type Value = StationId;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a station ID")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<StationId, E> {
+                StationId::parse(value).map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(StationIdVisitor)
+    }
+}
+
+// Lets maps keyed by StationId be looked up with a &str
+impl Borrow<str> for StationId {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for StationId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl PartialEq<str> for StationId {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for StationId {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<String> for StationId {
+    fn eq(&self, other: &String) -> bool {
+        &self.0 == other
+    }
+}
+
+impl PartialEq<StationId> for String {
+    fn eq(&self, other: &StationId) -> bool {
+        self == &other.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_normalizes_numeric_forms() {
+        for raw in ["59700", " 59700 ", "59700.0", "59700.00", "059700"] {
+            assert_eq!(StationId::parse(raw).unwrap(), "59700", "{raw:?}");
+        }
+        assert_eq!(StationId::parse("01800").unwrap(), "1800");
+        assert_eq!(StationId::parse("1800.0").unwrap(), "1800");
+        // Padding is removed only down to four digits
+        assert_eq!(StationId::parse("00123").unwrap(), "0123");
+        assert_eq!(StationId::parse("123").unwrap(), "123");
+    }
+
+    #[test]
+    fn test_parse_keeps_test_ids() {
+        assert_eq!(StationId::parse("TEST_API-001").unwrap(), "TEST_API-001");
+        assert_eq!(StationId::parse("99001").unwrap(), "99001");
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_ids() {
+        for raw in [
+            "",
+            "   ",
+            "bad id",
+            "59700.5",
+            "59700;",
+            "ñ",
+            &"9".repeat(21),
+        ] {
+            assert_eq!(
+                StationId::parse(raw),
+                Err(StationIdError::Format),
+                "{raw:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_extract_from_text() {
+        for (text, expected) in [
+            ("29200", "29200"),
+            ("1800", "1800"),
+            ("29200 since 03/09/18", "29200"),
+            ("1800 since 03/27/18", "1800"),
+            ("37300 since installation", "37300"),
+            ("4695 prior to 2/20/2018", "4695"),
+            ("Gauge 59700.0", "59700"),
+            ("1234A", "1234"),
+            ("01800", "1800"),
+        ] {
+            assert_eq!(StationId::extract(text).unwrap(), expected, "{text:?}");
+        }
+    }
+
+    #[test]
+    fn test_extract_requires_four_or_five_digits() {
+        for text in ["123", "123456", "ABCDE", ""] {
+            assert!(
+                matches!(StationId::extract(text), Err(StationIdError::NotFound(_))),
+                "{text:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_number() {
+        assert_eq!(StationId::from_number(59700.0).unwrap(), "59700");
+        assert_eq!(StationId::from_number(1800.0).unwrap(), "1800");
+        for value in [59700.5, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(StationId::from_number(value).is_err(), "{value}");
+        }
+    }
+
+    #[test]
+    fn test_serde_round_trip_validates() {
+        let id: StationId = serde_json::from_str("\"59700.0\"").unwrap();
+        assert_eq!(id, "59700");
+        assert_eq!(serde_json::to_string(&id).unwrap(), "\"59700\"");
+        assert!(serde_json::from_str::<StationId>("\"bad id\"").is_err());
+    }
+}
@@ -0,0 +1,109 @@
+// Object storage for gauge attachments
+//
+// Objects are addressed by slash-separated keys (`gauges/59700/<sha256>`) and stored as
+// files under a root directory. Pointing the root at a mounted bucket (e.g. an S3 or GCS
+// FUSE mount, or a Kubernetes persistent volume) keeps the layout identical across
+// deployments. Writes go to a temporary file that is renamed into place, so readers
+// never see a partially written object.
+
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use tokio::fs;
+use tracing::{debug, instrument};
+
+#[derive(Debug, Clone)]
+pub struct ObjectStore {
+    root: PathBuf,
+}
+
+impl ObjectStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Store an object, replacing any existing object with the same key
+    #[instrument(skip(self, bytes), fields(size = bytes.len()))]
+    pub async fn put(&self, key: &str, bytes: &[u8]) -> io::Result<()> {
+        let path = self.path_for(key)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        let tmp = path.with_extension("partial");
+        fs::write(&tmp, bytes).await?;
+        if let Err(e) = fs::rename(&tmp, &path).await {
+            let _ = fs::remove_file(&tmp).await;
+            return Err(e);
+        }
+
+        debug!("Stored object {} ({} bytes)", key, bytes.len());
+        Ok(())
+    }
+
+    /// Read an object; None when the key does not exist
+    #[instrument(skip(self))]
+    pub async fn get(&self, key: &str) -> io::Result<Option<Vec<u8>>> {
+        match fs::read(self.path_for(key)?).await {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Map a key to a path under the root, rejecting keys that could escape it
+    fn path_for(&self, key: &str) -> io::Result<PathBuf> {
+        let relative = Path::new(key);
+        let valid = !key.is_empty()
+            && relative
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)));
+
+        if valid {
+            Ok(self.root.join(relative))
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid object key '{key}'"),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_for_rejects_escaping_keys() {
+        let store = ObjectStore::new("/data");
+        assert_eq!(
+            store.path_for("gauges/59700/abc").unwrap(),
+            PathBuf::from("/data/gauges/59700/abc")
+        );
+        assert!(store.path_for("").is_err());
+        assert!(store.path_for("../etc/passwd").is_err());
+        assert!(store.path_for("/etc/passwd").is_err());
+        assert!(store.path_for("gauges/../../x").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_put_and_get() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ObjectStore::new(dir.path());
+
+        assert_eq!(store.get("gauges/1/a").await.unwrap(), None);
+
+        store.put("gauges/1/a", b"first").await.unwrap();
+        store.put("gauges/1/a", b"second").await.unwrap();
+        assert_eq!(
+            store.get("gauges/1/a").await.unwrap(),
+            Some(b"second".to_vec())
+        );
+        assert!(!dir.path().join("gauges/1/a.partial").exists());
+    }
+}
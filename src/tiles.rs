@@ -0,0 +1,342 @@
+// Mapbox Vector Tile (MVT 2.1) encoding for gauge map layers
+//
+// Tiles are addressed with the standard XYZ scheme in Web Mercator (EPSG:3857), as used
+// by Leaflet and MapLibre. Each tile has one point layer named `gauges`. The protobuf
+// encoding is written by hand: the MVT schema is small and we only emit points.
+//
+// Spec: https://github.com/mapbox/vector-tile-spec/tree/master/2.1
+
+use std::f64::consts::PI;
+
+use crate::db::GaugeMapPoint;
+
+/// Coordinate resolution within a tile
+pub const TILE_EXTENT: u32 = 4096;
+
+/// Highest zoom level served
+pub const MAX_ZOOM: u32 = 22;
+
+/// Layer name in every gauge tile
+pub const GAUGE_LAYER: &str = "gauges";
+
+/// Points this far outside the tile (in tile units) are still included, so
+/// markers on a tile edge are not clipped by the renderer
+const TILE_BUFFER: f64 = 64.0;
+
+/// Web Mercator latitude limit
+const MAX_LATITUDE: f64 = 85.051_128_78;
+
+/// An XYZ tile address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TileCoord {
+    pub z: u32,
+    pub x: u32,
+    pub y: u32,
+}
+
+/// Geographic bounding box in degrees
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LatLonBounds {
+    pub min_lat: f64,
+    pub max_lat: f64,
+    pub min_lon: f64,
+    pub max_lon: f64,
+}
+
+impl TileCoord {
+    /// Validate zoom and x/y ranges for the zoom level
+    pub fn new(z: u32, x: u32, y: u32) -> Option<Self> {
+        if z > MAX_ZOOM {
+            return None;
+        }
+        let n = 1u32 << z;
+        (x < n && y < n).then_some(Self { z, x, y })
+    }
+
+    fn tiles_per_side(&self) -> f64 {
+        (1u64 << self.z) as f64
+    }
+
+    /// Bounds of the tile including the edge buffer
+    pub fn bounds(&self) -> LatLonBounds {
+        let n = self.tiles_per_side();
+        let buffer = TILE_BUFFER / TILE_EXTENT as f64;
+
+        let lon = |x: f64| x / n * 360.0 - 180.0;
+        let lat = |y: f64| (PI * (1.0 - 2.0 * y / n)).sinh().atan().to_degrees();
+
+        LatLonBounds {
+            min_lat: lat(self.y as f64 + 1.0 + buffer),
+            max_lat: lat(self.y as f64 - buffer),
+            min_lon: lon(self.x as f64 - buffer),
+            max_lon: lon(self.x as f64 + 1.0 + buffer),
+        }
+    }
+
+    /// Project a position to integer tile coordinates (origin top-left)
+    ///
+    /// Returns None when the point falls outside the buffered tile.
+    pub fn project(&self, lat: f64, lon: f64) -> Option<(i32, i32)> {
+        let n = self.tiles_per_side();
+        let lat = lat.clamp(-MAX_LATITUDE, MAX_LATITUDE).to_radians();
+
+        let world_x = (lon + 180.0) / 360.0 * n;
+        let world_y = (1.0 - (lat.tan() + 1.0 / lat.cos()).ln() / PI) / 2.0 * n;
+
+        let extent = TILE_EXTENT as f64;
+        let tile_x = ((world_x - self.x as f64) * extent).round();
+        let tile_y = ((world_y - self.y as f64) * extent).round();
+
+        let in_range = |v: f64| (-TILE_BUFFER..extent + TILE_BUFFER).contains(&v);
+        (in_range(tile_x) && in_range(tile_y)).then_some((tile_x as i32, tile_y as i32))
+    }
+}
+
+/// Attribute value types used by gauge features
+#[derive(Debug, Clone, PartialEq)]
+enum TileValue {
+    String(String),
+    Double(f64),
+    Int(i64),
+}
+
+/// Encode gauges as a single-layer vector tile
+///
+/// Feature attributes: `station_id`, `gauge_name`, `city_town`, `elevation_ft`,
+/// `rainfall_past_6h_inches`, `rainfall_past_24h_inches` (missing values are omitted).
+/// Returns an empty tile (zero bytes) when no gauges fall inside it.
+pub fn encode_gauge_tile(tile: TileCoord, gauges: &[GaugeMapPoint]) -> Vec<u8> {
+    let mut keys: Vec<&'static str> = Vec::new();
+    let mut values: Vec<TileValue> = Vec::new();
+    let mut features: Vec<Vec<u8>> = Vec::new();
+
+    for gauge in gauges {
+        let Some((x, y)) = tile.project(gauge.latitude, gauge.longitude) else {
+            continue;
+        };
+
+        let attributes = [
+            (
+                "station_id",
+                Some(TileValue::String(gauge.station_id.clone())),
+            ),
+            (
+                "gauge_name",
+                Some(TileValue::String(gauge.gauge_name.clone())),
+            ),
+            ("city_town", gauge.city_town.clone().map(TileValue::String)),
+            (
+                "elevation_ft",
+                gauge.elevation_ft.map(|e| TileValue::Int(e as i64)),
+            ),
+            (
+                "rainfall_past_6h_inches",
+                gauge.rainfall_past_6h_inches.map(TileValue::Double),
+            ),
+            (
+                "rainfall_past_24h_inches",
+                gauge.rainfall_past_24h_inches.map(TileValue::Double),
+            ),
+        ];
+
+        let mut tags = Vec::new();
+        for (key, value) in attributes {
+            let Some(value) = value else { continue };
+            tags.push(intern(&mut keys, key));
+            tags.push(intern(&mut values, value));
+        }
+
+        let mut feature = Vec::new();
+        if let Ok(id) = gauge.station_id.parse::<u64>() {
+            write_varint_field(&mut feature, 1, id);
+        }
+        write_packed_field(&mut feature, 2, &tags);
+        write_varint_field(&mut feature, 3, 1); // GeomType POINT
+        write_packed_field(
+            &mut feature,
+            4,
+            &[command(1, 1), zigzag(x), zigzag(y)], // MoveTo(1) x y
+        );
+        features.push(feature);
+    }
+
+    if features.is_empty() {
+        return Vec::new();
+    }
+
+    let mut layer = Vec::new();
+    write_varint_field(&mut layer, 15, 2); // version
+    write_bytes_field(&mut layer, 1, GAUGE_LAYER.as_bytes());
+    for feature in &features {
+        write_bytes_field(&mut layer, 2, feature);
+    }
+    for key in &keys {
+        write_bytes_field(&mut layer, 3, key.as_bytes());
+    }
+    for value in &values {
+        write_bytes_field(&mut layer, 4, &encode_value(value));
+    }
+    write_varint_field(&mut layer, 5, TILE_EXTENT as u64);
+
+    let mut tile_bytes = Vec::new();
+    write_bytes_field(&mut tile_bytes, 3, &layer);
+    tile_bytes
+}
+
+/// Index of `item` in the table, appending it if new
+fn intern<T: PartialEq>(table: &mut Vec<T>, item: T) -> u32 {
+    match table.iter().position(|existing| *existing == item) {
+        Some(index) => index as u32,
+        None => {
+            table.push(item);
+            (table.len() - 1) as u32
+        }
+    }
+}
+
+fn encode_value(value: &TileValue) -> Vec<u8> {
+    let mut buf = Vec::new();
+    match value {
+        TileValue::String(s) => write_bytes_field(&mut buf, 1, s.as_bytes()),
+        TileValue::Double(d) => {
+            write_key(&mut buf, 3, 1); // 64-bit
+            buf.extend_from_slice(&d.to_le_bytes());
+        }
+        TileValue::Int(i) => write_varint_field(&mut buf, 4, *i as u64),
+    }
+    buf
+}
+
+fn command(id: u32, count: u32) -> u32 {
+    (id & 0x7) | (count << 3)
+}
+
+fn zigzag(n: i32) -> u32 {
+    ((n << 1) ^ (n >> 31)) as u32
+}
+
+fn write_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+fn write_key(buf: &mut Vec<u8>, field: u32, wire_type: u32) {
+    write_varint(buf, ((field << 3) | wire_type) as u64);
+}
+
+fn write_varint_field(buf: &mut Vec<u8>, field: u32, value: u64) {
+    write_key(buf, field, 0);
+    write_varint(buf, value);
+}
+
+fn write_bytes_field(buf: &mut Vec<u8>, field: u32, bytes: &[u8]) {
+    write_key(buf, field, 2);
+    write_varint(buf, bytes.len() as u64);
+    buf.extend_from_slice(bytes);
+}
+
+fn write_packed_field(buf: &mut Vec<u8>, field: u32, values: &[u32]) {
+    let mut packed = Vec::new();
+    for value in values {
+        write_varint(&mut packed, *value as u64);
+    }
+    write_bytes_field(buf, field, &packed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gauge(station_id: &str, latitude: f64, longitude: f64) -> GaugeMapPoint {
+        GaugeMapPoint {
+            station_id: station_id.to_string(),
+            gauge_name: format!("Gauge {station_id}"),
+            city_town: Some("Phoenix".to_string()),
+            latitude,
+            longitude,
+            elevation_ft: Some(1100),
+            rainfall_past_6h_inches: None,
+            rainfall_past_24h_inches: Some(0.47),
+        }
+    }
+
+    #[test]
+    fn test_tile_coord_validation() {
+        assert!(TileCoord::new(0, 0, 0).is_some());
+        assert!(TileCoord::new(1, 1, 1).is_some());
+        assert!(TileCoord::new(1, 2, 0).is_none());
+        assert!(TileCoord::new(MAX_ZOOM + 1, 0, 0).is_none());
+    }
+
+    #[test]
+    fn test_project_world_tile() {
+        let tile = TileCoord::new(0, 0, 0).unwrap();
+        assert_eq!(tile.project(0.0, 0.0), Some((2048, 2048)));
+        assert_eq!(tile.project(0.0, -180.0), Some((0, 2048)));
+    }
+
+    #[test]
+    fn test_project_phoenix_tile() {
+        // Downtown Phoenix (33.45, -112.07) is in z10 tile 193/410
+        let tile = TileCoord::new(10, 193, 410).unwrap();
+        let (x, y) = tile.project(33.45, -112.07).unwrap();
+        assert!((0..4096).contains(&x));
+        assert!((0..4096).contains(&y));
+
+        let neighbor = TileCoord::new(10, 195, 410).unwrap();
+        assert!(neighbor.project(33.45, -112.07).is_none());
+    }
+
+    #[test]
+    fn test_bounds_contain_projected_points() {
+        let tile = TileCoord::new(10, 193, 410).unwrap();
+        let bounds = tile.bounds();
+        assert!(bounds.min_lat < 33.45 && 33.45 < bounds.max_lat);
+        assert!(bounds.min_lon < -112.07 && -112.07 < bounds.max_lon);
+    }
+
+    #[test]
+    fn test_zigzag() {
+        assert_eq!(zigzag(0), 0);
+        assert_eq!(zigzag(-1), 1);
+        assert_eq!(zigzag(1), 2);
+        assert_eq!(zigzag(-2), 3);
+    }
+
+    #[test]
+    fn test_empty_tile_has_no_bytes() {
+        let tile = TileCoord::new(10, 0, 0).unwrap();
+        assert!(encode_gauge_tile(tile, &[gauge("59700", 33.45, -112.07)]).is_empty());
+    }
+
+    #[test]
+    fn test_encode_gauge_tile_structure() {
+        let tile = TileCoord::new(0, 0, 0).unwrap();
+        let bytes = encode_gauge_tile(
+            tile,
+            &[gauge("59700", 33.45, -112.07), gauge("1000", 33.5, -112.0)],
+        );
+
+        // Tile.layers (field 3, length-delimited)
+        assert_eq!(bytes[0], 0x1a);
+        // Layer starts with version = 2 (field 15, varint)
+        let layer_start = 1 + varint_len(&bytes[1..]);
+        assert_eq!(&bytes[layer_start..layer_start + 2], &[0x78, 0x02]);
+        // Layer name follows
+        assert_eq!(bytes[layer_start + 2], 0x0a);
+        assert_eq!(bytes[layer_start + 3] as usize, GAUGE_LAYER.len());
+
+        // Shared keys are interned once
+        let text = String::from_utf8_lossy(&bytes);
+        assert_eq!(text.matches("rainfall_past_24h_inches").count(), 1);
+        assert_eq!(text.matches("Phoenix").count(), 1);
+        assert!(!text.contains("rainfall_past_6h_inches"));
+    }
+
+    fn varint_len(bytes: &[u8]) -> usize {
+        bytes.iter().position(|b| b & 0x80 == 0).unwrap() + 1
+    }
+}
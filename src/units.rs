@@ -0,0 +1,242 @@
+// Rainfall depth units
+//
+// MCFCD publishes every amount in inches, and the database and API still store and
+// serialize bare inches. Parsed amounts are carried as Inches so a millimeter value can't
+// be added to an inch total by accident once metric output lands; convert explicitly with
+// `to_millimeters` / `to_inches`. Both types serialize as plain numbers; there's no
+// Display impl, so formatting one for output goes through `value()` deliberately.
+//
+// Totals are sums of float readings and drift (0.1 + 0.2 = 0.30000000000000004). Stored
+// aggregates are rounded with `round_inches` when they're computed, and API models
+// serialize derived amounts through `serialize_rounded` so no response shows the drift.
+
+use serde::{Deserialize, Serialize, Serializer};
+
+/// Millimeters in one inch (exact by definition)
+pub const MM_PER_INCH: f64 = 25.4;
+
+/// Decimal places rainfall is reported to; MCFCD gauges measure hundredths of an inch
+pub const INCH_DECIMALS: i32 = 2;
+
+/// Round an inch amount to hundredths, with -0.0 coming back as 0.0
+pub fn round_inches(value: f64) -> f64 {
+    let scale = 10f64.powi(INCH_DECIMALS);
+    (value * scale).round() / scale + 0.0
+}
+
+/// `serialize_with` for derived inch amounts (totals, means, percentiles) in API models
+pub fn serialize_rounded<S: Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(round_inches(*value))
+}
+
+/// `serialize_with` for optional derived inch amounts
+pub fn serialize_rounded_opt<S: Serializer>(
+    value: &Option<f64>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    value.map(round_inches).serialize(serializer)
+}
+
+/// Why a value isn't a rainfall depth
+#[derive(Debug, Clone, Copy, PartialEq, thiserror::Error)]
+pub enum UnitError {
+    #[error("rainfall must be a finite number, got {0}")]
+    NotFinite(f64),
+    #[error("rainfall can't be negative, got {0}")]
+    Negative(f64),
+}
+
+fn check_depth(value: f64) -> Result<f64, UnitError> {
+    if !value.is_finite() {
+        Err(UnitError::NotFinite(value))
+    } else if value < 0.0 {
+        Err(UnitError::Negative(value))
+    } else {
+        // -0.0 passes the sign check above; store it as 0.0 so it never serializes as "-0.0"
+        Ok(value + 0.0)
+    }
+}
+
+/// A rainfall depth in inches: finite and non-negative
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, PartialOrd, Serialize, Deserialize, sqlx::Type,
+)]
+#[serde(try_from = "f64", into = "f64")]
+#[sqlx(transparent)]
+pub struct Inches(f64);
+
+impl Inches {
+    pub const ZERO: Inches = Inches(0.0);
+
+    pub fn new(value: f64) -> Result<Self, UnitError> {
+        check_depth(value).map(Self)
+    }
+
+    pub fn value(self) -> f64 {
+        self.0
+    }
+
+    /// Sum of two depths; None if the total overflows
+    pub fn checked_add(self, rhs: Inches) -> Option<Inches> {
+        Self::new(self.0 + rhs.0).ok()
+    }
+
+    /// Difference of two depths; None if `rhs` is larger
+    pub fn checked_sub(self, rhs: Inches) -> Option<Inches> {
+        Self::new(self.0 - rhs.0).ok()
+    }
+
+    /// Total of many depths; None if the total overflows
+    pub fn checked_sum(depths: impl IntoIterator<Item = Inches>) -> Option<Inches> {
+        depths
+            .into_iter()
+            .try_fold(Inches::ZERO, |total, depth| total.checked_add(depth))
+    }
+
+    pub fn to_millimeters(self) -> Millimeters {
+        Millimeters(self.0 * MM_PER_INCH)
+    }
+}
+
+impl TryFrom<f64> for Inches {
+    type Error = UnitError;
+
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl From<Inches> for f64 {
+    fn from(inches: Inches) -> Self {
+        inches.0
+    }
+}
+
+impl From<Millimeters> for Inches {
+    fn from(mm: Millimeters) -> Self {
+        mm.to_inches()
+    }
+}
+
+/// A rainfall depth in millimeters: finite and non-negative
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(try_from = "f64", into = "f64")]
+pub struct Millimeters(f64);
+
+impl Millimeters {
+    pub fn new(value: f64) -> Result<Self, UnitError> {
+        check_depth(value).map(Self)
+    }
+
+    pub fn value(self) -> f64 {
+        self.0
+    }
+
+    pub fn to_inches(self) -> Inches {
+        Inches(self.0 / MM_PER_INCH)
+    }
+}
+
+impl TryFrom<f64> for Millimeters {
+    type Error = UnitError;
+
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl From<Millimeters> for f64 {
+    fn from(mm: Millimeters) -> Self {
+        mm.0
+    }
+}
+
+impl From<Inches> for Millimeters {
+    fn from(inches: Inches) -> Self {
+        inches.to_millimeters()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inches(value: f64) -> Inches {
+        Inches::new(value).unwrap()
+    }
+
+    #[test]
+    fn test_new_rejects_non_depths() {
+        assert!(matches!(
+            Inches::new(f64::NAN),
+            Err(UnitError::NotFinite(_))
+        ));
+        assert!(matches!(
+            Inches::new(f64::INFINITY),
+            Err(UnitError::NotFinite(_))
+        ));
+        assert_eq!(Inches::new(-0.01), Err(UnitError::Negative(-0.01)));
+        assert!(Millimeters::new(-1.0).is_err());
+        assert_eq!(inches(-0.0).value().to_bits(), 0.0f64.to_bits());
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        assert_eq!(inches(1.25).checked_add(inches(0.5)), Some(inches(1.75)));
+        assert_eq!(inches(1.25).checked_sub(inches(0.25)), Some(inches(1.0)));
+        assert_eq!(inches(0.25).checked_sub(inches(1.0)), None);
+        assert_eq!(inches(f64::MAX).checked_add(inches(f64::MAX)), None);
+        assert_eq!(
+            Inches::checked_sum([inches(0.5), inches(0.25), inches(0.25)]),
+            Some(inches(1.0))
+        );
+        assert_eq!(Inches::checked_sum([]), Some(Inches::ZERO));
+    }
+
+    #[test]
+    fn test_millimeter_conversion() {
+        assert_eq!(inches(1.0).to_millimeters().value(), 25.4);
+        assert_eq!(Millimeters::new(50.8).unwrap().to_inches(), inches(2.0));
+        assert_eq!(Inches::from(inches(0.04).to_millimeters()).value(), 0.04);
+    }
+
+    #[test]
+    fn test_round_inches() {
+        assert_eq!(round_inches(5.299999999), 5.3);
+        assert_eq!(round_inches(0.1 + 0.2), 0.3);
+        assert_eq!(round_inches(1.006), 1.01);
+        assert_eq!(round_inches(-0.001).to_bits(), 0.0f64.to_bits());
+    }
+
+    #[test]
+    fn test_serialize_rounded() {
+        #[derive(Serialize)]
+        struct Total {
+            #[serde(serialize_with = "serialize_rounded")]
+            total: f64,
+            #[serde(serialize_with = "serialize_rounded_opt")]
+            month: Option<f64>,
+        }
+
+        let json = serde_json::to_string(&Total {
+            total: 0.1 + 0.2,
+            month: Some(5.299999999),
+        })
+        .unwrap();
+        assert_eq!(json, r#"{"total":0.3,"month":5.3}"#);
+
+        let json = serde_json::to_string(&Total {
+            total: 0.0,
+            month: None,
+        })
+        .unwrap();
+        assert_eq!(json, r#"{"total":0.0,"month":null}"#);
+    }
+
+    #[test]
+    fn test_serializes_as_plain_number() {
+        assert_eq!(serde_json::to_string(&inches(0.04)).unwrap(), "0.04");
+        assert_eq!(serde_json::from_str::<Inches>("1.5").unwrap(), inches(1.5));
+        assert!(serde_json::from_str::<Inches>("-1.5").is_err());
+    }
+}
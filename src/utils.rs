@@ -1,98 +1,81 @@
 /// Shared utility functions for the rain tracker service
 ///
-/// Extract 4-5 digit station ID from a string that may contain additional text
-///
-/// Station IDs in the MCFCD system are either 4 or 5 digits. Sometimes they appear
-/// with additional text like "29200 since 03/09/18" or "40700 since 6/30/20".
-/// This function extracts just the numeric ID portion.
-///
-/// # Examples
-///
-/// ```
-/// use rain_tracker_service::utils::extract_station_id;
-///
-/// assert_eq!(extract_station_id("29200").unwrap(), "29200");
-/// assert_eq!(extract_station_id("1800 since 03/27/18").unwrap(), "1800");
-/// assert_eq!(extract_station_id("40700 since 6/30/20").unwrap(), "40700");
-/// assert_eq!(extract_station_id("37300 since installation").unwrap(), "37300");
-/// ```
-pub fn extract_station_id(value: &str) -> Result<String, &'static str> {
-    // Find first whitespace-delimited token that is 4-5 digits
-    for part in value.split_whitespace() {
-        let len = part.len();
-        if (len == 4 || len == 5) && part.chars().all(|c| c.is_ascii_digit()) {
-            return Ok(part.to_string());
-        }
-    }
-
-    // Fallback: extract leading digits if they are 4-5 in length
-    let digits: String = value.chars().take_while(|c| c.is_ascii_digit()).collect();
-    let len = digits.len();
-    if len == 4 || len == 5 {
-        return Ok(digits);
-    }
-
-    Err("No valid 4-5 digit station ID found")
-}
+use std::collections::BTreeSet;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
 
-    #[test]
-    fn test_extract_station_id_5digit_clean() {
-        assert_eq!(extract_station_id("29200").unwrap(), "29200");
-    }
+/// Calculate the UTC date range for a calendar month
+///
+/// Returns (start_of_month, start_of_next_month)
+pub fn month_date_range(year: i32, month: u32) -> (DateTime<Utc>, DateTime<Utc>) {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
 
-    #[test]
-    fn test_extract_station_id_4digit_clean() {
-        assert_eq!(extract_station_id("1800").unwrap(), "1800");
-    }
+    (
+        midnight_utc(year, month, 1),
+        midnight_utc(next_year, next_month, 1),
+    )
+}
 
-    #[test]
-    fn test_extract_station_id_with_since_5digit() {
-        assert_eq!(extract_station_id("29200 since 03/09/18").unwrap(), "29200");
-    }
+/// Distinct (year, month) pairs of some UTC timestamps, oldest first
+pub fn distinct_months(datetimes: impl IntoIterator<Item = DateTime<Utc>>) -> Vec<(i32, u32)> {
+    let months: BTreeSet<(i32, u32)> = datetimes
+        .into_iter()
+        .map(|dt| (dt.year(), dt.month()))
+        .collect();
+    months.into_iter().collect()
+}
 
-    #[test]
-    fn test_extract_station_id_with_since_4digit() {
-        assert_eq!(extract_station_id("1800 since 03/27/18").unwrap(), "1800");
-    }
+/// List the (year, month) pairs of a water year, Oct of the prior year through Sep
+pub fn water_year_months(water_year: i32) -> Vec<(i32, u32)> {
+    (10..=12)
+        .map(|m| (water_year - 1, m))
+        .chain((1..=9).map(|m| (water_year, m)))
+        .collect()
+}
 
-    #[test]
-    fn test_extract_station_id_with_installation() {
-        assert_eq!(
-            extract_station_id("37300 since installation").unwrap(),
-            "37300"
-        );
-    }
+/// Calculate the UTC date range for a water year (Oct 1 of year-1 to Oct 1 of year)
+pub fn water_year_date_range(water_year: i32) -> (DateTime<Utc>, DateTime<Utc>) {
+    (
+        midnight_utc(water_year - 1, 10, 1),
+        midnight_utc(water_year, 10, 1),
+    )
+}
 
-    #[test]
-    fn test_extract_station_id_with_prior() {
-        assert_eq!(
-            extract_station_id("4695 prior to 2/20/2018").unwrap(),
-            "4695"
-        );
-    }
+fn midnight_utc(year: i32, month: u32, day: u32) -> DateTime<Utc> {
+    let date = NaiveDate::from_ymd_opt(year, month, day)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap();
+    DateTime::<Utc>::from_naive_utc_and_offset(date, Utc)
+}
 
-    #[test]
-    fn test_extract_station_id_too_short() {
-        assert!(extract_station_id("123").is_err());
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
     #[test]
-    fn test_extract_station_id_too_long() {
-        assert!(extract_station_id("123456").is_err());
+    fn test_month_date_range_december_rolls_over() {
+        let (start, end) = month_date_range(2024, 12);
+        assert_eq!(start.to_rfc3339(), "2024-12-01T00:00:00+00:00");
+        assert_eq!(end.to_rfc3339(), "2025-01-01T00:00:00+00:00");
     }
 
     #[test]
-    fn test_extract_station_id_non_numeric() {
-        assert!(extract_station_id("ABCDE").is_err());
+    fn test_water_year_months() {
+        let months = water_year_months(2025);
+        assert_eq!(months.len(), 12);
+        assert_eq!(months.first(), Some(&(2024, 10)));
+        assert_eq!(months.last(), Some(&(2025, 9)));
     }
 
     #[test]
-    fn test_extract_station_id_leading_digits() {
-        // "1234A" should extract "1234" as leading digits
-        assert_eq!(extract_station_id("1234A").unwrap(), "1234");
+    fn test_water_year_date_range() {
+        let (start, end) = water_year_date_range(2025);
+        assert_eq!(start.to_rfc3339(), "2024-10-01T00:00:00+00:00");
+        assert_eq!(end.to_rfc3339(), "2025-10-01T00:00:00+00:00");
     }
 }
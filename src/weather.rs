@@ -0,0 +1,456 @@
+// Daily temperature and reference evapotranspiration (ET) at gauges
+//
+// Landscape and irrigation users weigh rainfall against the water plants lose. The
+// optional weather job stores a daily high, low, and reference ET for every gauge with
+// coordinates, from one of two sources:
+// - AZMET, the University of Arizona's agricultural weather network (AZMET_STATIONS_FILE,
+//   a CSV with `id,name,latitude,longitude` columns such as `az06,Phoenix Greenway,...`).
+//   Each gauge uses the nearest listed station within MAX_STATION_DISTANCE_MILES, whose
+//   daily observations (`/observations/daily/{station}/{start}/*`) include the measured
+//   reference ET `eto_azmet_in`.
+// - NWS gridpoint forecasts (WEATHER_FROM_NWS) for the gauge's grid cell. NWS publishes
+//   no ET, so it is estimated with the Hargreaves equation from the day's high, low, and
+//   extraterrestrial radiation. Each run stores the coming days; later runs overwrite
+//   them, so a past day keeps the last forecast made for it.
+//
+// Weather days are the source's own days (Arizona local days for AZMET); rainfall days
+// are UTC days like every other daily total.
+
+use std::collections::BTreeMap;
+use std::f64::consts::PI;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Deserializer};
+use tracing::{debug, instrument};
+
+use crate::forecast::{GridTemperatures, NwsClient, TemperaturePeriod};
+use crate::geocode::distance_miles;
+use crate::politeness::{PoliteError, Politeness, DEFAULT_USER_AGENT};
+
+/// AZMET API base URL when AZMET_URL is unset
+pub const DEFAULT_AZMET_URL: &str = "https://api.azmet.arizona.edu/v1";
+
+/// How often the weather job runs
+pub const DEFAULT_WEATHER_INTERVAL_MINUTES: u64 = 1440;
+
+/// Days of past AZMET observations fetched again each run, so late or corrected
+/// observations replace earlier ones
+pub const DEFAULT_WEATHER_HISTORY_DAYS: u32 = 7;
+
+/// Stations farther than this from a gauge are not used for it
+pub const MAX_STATION_DISTANCE_MILES: f64 = 30.0;
+
+/// AZMET marks missing observations with large negative sentinels (e.g. -99999)
+const AZMET_MISSING_BELOW: f64 = -99.0;
+
+const MM_PER_INCH: f64 = 25.4;
+
+#[derive(Debug, thiserror::Error)]
+pub enum WeatherError {
+    #[error("HTTP request failed: {0}")]
+    Request(#[from] reqwest::Error),
+
+    #[error("Disallowed by robots.txt: {0}")]
+    Disallowed(String),
+
+    #[error("Paused after repeated failures from {0}")]
+    CircuitOpen(String),
+
+    #[error("AZMET API returned HTTP {0}")]
+    Status(u16),
+
+    #[error("Invalid AZMET API response: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Failed to read AZMET stations file {}: {source}", path.display())]
+    Stations {
+        path: PathBuf,
+        #[source]
+        source: csv::Error,
+    },
+}
+
+impl From<PoliteError> for WeatherError {
+    fn from(e: PoliteError) -> Self {
+        match e {
+            PoliteError::Request(e) => WeatherError::Request(e),
+            PoliteError::Disallowed(url) => WeatherError::Disallowed(url),
+            PoliteError::CircuitOpen(host) => WeatherError::CircuitOpen(host),
+        }
+    }
+}
+
+/// Where the weather job gets daily temperatures and ET
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WeatherProvider {
+    /// AZMET daily observations from the stations in a CSV
+    Azmet { url: String, stations_file: PathBuf },
+    /// NWS gridpoint temperature forecasts, with ET estimated (uses NWS_API_URL)
+    Nws,
+}
+
+/// Settings for the optional weather job (disabled unless a provider is configured)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeatherConfig {
+    pub provider: WeatherProvider,
+    /// WEATHER_INTERVAL_MINUTES, default 1440
+    pub interval_minutes: u64,
+    /// WEATHER_HISTORY_DAYS, default 7 (AZMET only; NWS has no past days)
+    pub history_days: u32,
+}
+
+/// One day's weather at a gauge
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyWeather {
+    pub date: NaiveDate,
+    pub max_temp_f: Option<f64>,
+    pub min_temp_f: Option<f64>,
+    /// Reference evapotranspiration
+    pub et_inches: Option<f64>,
+}
+
+/// One row of the AZMET stations file
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AzmetStation {
+    /// AZMET station ID, e.g. az06
+    pub id: String,
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// AZMET stations searched for the nearest one to a gauge
+#[derive(Debug, Clone)]
+pub struct AzmetStations {
+    stations: Vec<AzmetStation>,
+}
+
+impl AzmetStations {
+    pub fn load(path: &Path) -> Result<Self, WeatherError> {
+        let csv_error = |source| WeatherError::Stations {
+            path: path.to_path_buf(),
+            source,
+        };
+        let stations = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_path(path)
+            .map_err(csv_error)?
+            .deserialize()
+            .collect::<Result<Vec<AzmetStation>, _>>()
+            .map_err(csv_error)?;
+        Ok(Self::new(stations))
+    }
+
+    pub fn new(stations: Vec<AzmetStation>) -> Self {
+        Self { stations }
+    }
+
+    /// The nearest station within MAX_STATION_DISTANCE_MILES
+    pub fn nearest(&self, latitude: f64, longitude: f64) -> Option<&AzmetStation> {
+        self.stations
+            .iter()
+            .map(|station| {
+                let distance =
+                    distance_miles(latitude, longitude, station.latitude, station.longitude);
+                (distance, station)
+            })
+            .filter(|(distance, _)| *distance <= MAX_STATION_DISTANCE_MILES)
+            .min_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map(|(_, station)| station)
+    }
+}
+
+/// Client for the AZMET API
+#[derive(Clone)]
+pub struct AzmetClient {
+    client: reqwest::Client,
+    url: String,
+    politeness: Politeness,
+}
+
+impl AzmetClient {
+    pub fn new(url: &str) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(30))
+                .user_agent(DEFAULT_USER_AGENT)
+                .build()
+                .expect("Failed to create HTTP client"),
+            url: url.trim_end_matches('/').to_string(),
+            politeness: Politeness::default(),
+        }
+    }
+
+    /// Send requests with the shared User-Agent, robots.txt checks, and host delay
+    pub fn with_politeness(mut self, politeness: Politeness) -> Self {
+        self.politeness = politeness;
+        self
+    }
+
+    /// A station's daily observations from `start` through the latest day
+    #[instrument(skip(self))]
+    pub async fn daily(
+        &self,
+        station_id: &str,
+        start: NaiveDate,
+    ) -> Result<Vec<DailyWeather>, WeatherError> {
+        let url = format!("{}/observations/daily/{station_id}/{start}/*", self.url);
+        let response = self
+            .politeness
+            .send(&self.client, self.client.get(&url))
+            .await?;
+        if !response.status().is_success() {
+            return Err(WeatherError::Status(response.status().as_u16()));
+        }
+
+        let body = response.text().await?;
+        debug!("AZMET API response from {}: {} bytes", url, body.len());
+        parse_azmet_daily(&body)
+    }
+}
+
+/// Source the weather job reads, built from the configured provider
+#[derive(Clone)]
+pub enum WeatherSource {
+    Azmet {
+        client: AzmetClient,
+        stations: Arc<AzmetStations>,
+    },
+    Nws(NwsClient),
+}
+
+impl WeatherSource {
+    /// Build the source, loading the stations file for AZMET
+    pub fn from_provider(provider: &WeatherProvider, nws_url: &str) -> Result<Self, WeatherError> {
+        match provider {
+            WeatherProvider::Azmet { url, stations_file } => Ok(Self::Azmet {
+                client: AzmetClient::new(url),
+                stations: Arc::new(AzmetStations::load(stations_file)?),
+            }),
+            WeatherProvider::Nws => Ok(Self::Nws(NwsClient::new(nws_url))),
+        }
+    }
+
+    /// Send requests through the shared `politeness`
+    pub fn with_politeness(self, politeness: &Politeness) -> Self {
+        match self {
+            Self::Azmet { client, stations } => Self::Azmet {
+                client: client.with_politeness(politeness.clone()),
+                stations,
+            },
+            Self::Nws(client) => Self::Nws(client.with_politeness(politeness.clone())),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct AzmetResponse {
+    #[serde(default)]
+    data: Vec<AzmetDay>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AzmetDay {
+    datetime: NaiveDate,
+    #[serde(rename = "temp_air_maxF", default, deserialize_with = "azmet_value")]
+    temp_air_max_f: Option<f64>,
+    #[serde(rename = "temp_air_minF", default, deserialize_with = "azmet_value")]
+    temp_air_min_f: Option<f64>,
+    #[serde(default, deserialize_with = "azmet_value")]
+    eto_azmet_in: Option<f64>,
+}
+
+/// AZMET sends values as numbers or numeric strings, with sentinels for missing data
+fn azmet_value<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<f64>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Value {
+        Number(f64),
+        Text(String),
+    }
+
+    let value = match Option::<Value>::deserialize(deserializer)? {
+        Some(Value::Number(number)) => Some(number),
+        Some(Value::Text(text)) => text.trim().parse().ok(),
+        None => None,
+    };
+    Ok(value.filter(|v: &f64| v.is_finite() && *v > AZMET_MISSING_BELOW))
+}
+
+/// Read a daily observations response
+fn parse_azmet_daily(body: &str) -> Result<Vec<DailyWeather>, WeatherError> {
+    let response: AzmetResponse = serde_json::from_str(body)?;
+    let mut days: Vec<DailyWeather> = response
+        .data
+        .into_iter()
+        .map(|day| DailyWeather {
+            date: day.datetime,
+            max_temp_f: day.temp_air_max_f,
+            min_temp_f: day.temp_air_min_f,
+            et_inches: day.eto_azmet_in.map(|et| et.max(0.0)),
+        })
+        .collect();
+    days.sort_by_key(|d| d.date);
+    Ok(days)
+}
+
+/// Daily weather from a grid cell's temperature forecasts, with Hargreaves ET
+///
+/// Each high or low belongs to the UTC date of its interval's midpoint: mid-afternoon
+/// for highs and early morning for lows across US time zones.
+pub fn daily_from_forecast(temperatures: &GridTemperatures, latitude: f64) -> Vec<DailyWeather> {
+    let midpoint_date = |p: &TemperaturePeriod| (p.start + (p.end - p.start) / 2).date_naive();
+    let mut days: BTreeMap<NaiveDate, (Option<f64>, Option<f64>)> = BTreeMap::new();
+    for high in &temperatures.highs {
+        let day = days.entry(midpoint_date(high)).or_default();
+        day.0.get_or_insert(high.fahrenheit);
+    }
+    for low in &temperatures.lows {
+        let day = days.entry(midpoint_date(low)).or_default();
+        day.1.get_or_insert(low.fahrenheit);
+    }
+
+    days.into_iter()
+        .map(|(date, (max_temp_f, min_temp_f))| DailyWeather {
+            date,
+            max_temp_f,
+            min_temp_f,
+            et_inches: max_temp_f
+                .zip(min_temp_f)
+                .and_then(|(max, min)| hargreaves_et_inches(latitude, date, max, min)),
+        })
+        .collect()
+}
+
+/// Hargreaves reference ET (FAO-56 eq. 52) from the day's high and low
+///
+/// None when the low is above the high.
+pub fn hargreaves_et_inches(
+    latitude: f64,
+    date: NaiveDate,
+    max_temp_f: f64,
+    min_temp_f: f64,
+) -> Option<f64> {
+    let to_celsius = |f: f64| (f - 32.0) * 5.0 / 9.0;
+    let (max, min) = (to_celsius(max_temp_f), to_celsius(min_temp_f));
+    if min > max {
+        return None;
+    }
+    let mean = (max + min) / 2.0;
+    // 0.408 converts MJ/m²/day of radiation to mm/day of evaporation
+    let radiation_mm = 0.408 * extraterrestrial_radiation(latitude, date.ordinal());
+    let et_mm = 0.0023 * radiation_mm * (mean + 17.8) * (max - min).sqrt();
+    Some(et_mm.max(0.0) / MM_PER_INCH)
+}
+
+/// Daily extraterrestrial radiation in MJ/m²/day (FAO-56 eq. 21)
+fn extraterrestrial_radiation(latitude: f64, day_of_year: u32) -> f64 {
+    const SOLAR_CONSTANT: f64 = 0.0820;
+    let latitude = latitude.to_radians();
+    let angle = 2.0 * PI * day_of_year as f64 / 365.0;
+    let inverse_distance = 1.0 + 0.033 * angle.cos();
+    let declination = 0.409 * (angle - 1.39).sin();
+    let sunset_angle = (-latitude.tan() * declination.tan())
+        .clamp(-1.0, 1.0)
+        .acos();
+    24.0 * 60.0 / PI
+        * SOLAR_CONSTANT
+        * inverse_distance
+        * (sunset_angle * latitude.sin() * declination.sin()
+            + latitude.cos() * declination.cos() * sunset_angle.sin())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_parse_azmet_daily() {
+        let body = r#"{"data": [
+            {"datetime": "2025-01-15", "station_id": "az06", "temp_air_maxF": "68.2",
+             "temp_air_minF": 41.5, "eto_azmet_in": "0.08"},
+            {"datetime": "2025-01-14", "station_id": "az06", "temp_air_maxF": -99999,
+             "temp_air_minF": "", "eto_azmet_in": null}
+        ], "errors": []}"#;
+        let days = parse_azmet_daily(body).unwrap();
+
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[0].date, NaiveDate::from_ymd_opt(2025, 1, 14).unwrap());
+        assert_eq!(
+            (days[0].max_temp_f, days[0].min_temp_f, days[0].et_inches),
+            (None, None, None),
+            "sentinels and blanks are missing"
+        );
+        assert_eq!(days[1].max_temp_f, Some(68.2));
+        assert_eq!(days[1].min_temp_f, Some(41.5));
+        assert_eq!(days[1].et_inches, Some(0.08));
+    }
+
+    #[test]
+    fn test_nearest_station() {
+        let station = |id: &str, latitude, longitude| AzmetStation {
+            id: id.to_string(),
+            name: id.to_string(),
+            latitude,
+            longitude,
+        };
+        let stations = AzmetStations::new(vec![
+            station("az06", 33.49, -112.10),
+            station("az15", 33.07, -111.97),
+        ]);
+
+        assert_eq!(stations.nearest(33.5, -112.0).unwrap().id, "az06");
+        assert_eq!(stations.nearest(33.1, -112.0).unwrap().id, "az15");
+        assert!(
+            stations.nearest(35.2, -111.6).is_none(),
+            "Flagstaff is too far"
+        );
+    }
+
+    #[test]
+    fn test_hargreaves_et_follows_the_seasons() {
+        let date = |m| NaiveDate::from_ymd_opt(2025, m, 15).unwrap();
+        let january = hargreaves_et_inches(33.5, date(1), 68.0, 41.0).unwrap();
+        let july = hargreaves_et_inches(33.5, date(7), 106.0, 84.0).unwrap();
+
+        // Phoenix reference ET runs near 0.08" a day in January and 0.3" in July
+        assert!((0.05..0.11).contains(&january), "{january}");
+        assert!((0.22..0.35).contains(&july), "{july}");
+        assert_eq!(hargreaves_et_inches(33.5, date(1), 40.0, 50.0), None);
+    }
+
+    #[test]
+    fn test_daily_from_forecast_pairs_highs_and_lows() {
+        let at = |d, h| Utc.with_ymd_and_hms(2025, 1, d, h, 0, 0).unwrap();
+        let period = |start, end, fahrenheit| TemperaturePeriod {
+            start,
+            end,
+            fahrenheit,
+        };
+        // Phoenix: highs 7 am-8 pm MST, lows 5 pm-9 am MST
+        let temperatures = GridTemperatures {
+            highs: vec![
+                period(at(15, 14), at(16, 3), 68.0),
+                period(at(16, 14), at(17, 3), 70.0),
+            ],
+            lows: vec![period(at(16, 0), at(16, 16), 41.0)],
+        };
+        let days = daily_from_forecast(&temperatures, 33.5);
+
+        let date = |d| NaiveDate::from_ymd_opt(2025, 1, d).unwrap();
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[0].date, date(15));
+        assert_eq!((days[0].max_temp_f, days[0].min_temp_f), (Some(68.0), None));
+        assert_eq!(days[0].et_inches, None, "ET needs both temperatures");
+        assert_eq!(days[1].date, date(16));
+        assert_eq!(
+            (days[1].max_temp_f, days[1].min_temp_f),
+            (Some(70.0), Some(41.0))
+        );
+        assert!(days[1].et_inches.unwrap() > 0.0);
+    }
+}
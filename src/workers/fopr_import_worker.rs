@@ -2,10 +2,14 @@ use backon::{BackoffBuilder, ExponentialBuilder};
 use chrono::Utc;
 use std::time::Duration;
 use tokio::time::interval;
-use tracing::{error, info, instrument, warn};
+use tracing::{debug, error, info, instrument, warn};
 
 use crate::db::fopr_import_job_repository::{ErrorHistoryEntry, FoprImportJobRepository};
+use crate::db::JobRunKind;
+use crate::job_notify::{JobNotification, JobNotifier};
+use crate::job_windows::{JobKind, JobWindows};
 use crate::services::fopr_import_service::FoprImportService;
+use crate::services::job_run_service::{JobRunService, JobRunTimer};
 
 /// FOPR Import Worker
 ///
@@ -17,6 +21,9 @@ pub struct FoprImportWorker {
     import_service: FoprImportService,
     poll_interval_secs: u64,
     worker_id: usize,
+    windows: JobWindows,
+    job_runs: Option<JobRunService>,
+    notifier: Option<JobNotifier>,
 }
 
 impl FoprImportWorker {
@@ -31,9 +38,30 @@ impl FoprImportWorker {
             import_service,
             poll_interval_secs,
             worker_id,
+            windows: JobWindows::default(),
+            job_runs: None,
+            notifier: None,
         }
     }
 
+    /// Claim jobs only outside download blackouts and inside heavy job windows
+    pub fn with_job_windows(mut self, windows: JobWindows) -> Self {
+        self.windows = windows;
+        self
+    }
+
+    /// Record each import attempt in the job run history
+    pub fn with_job_runs(mut self, job_runs: JobRunService) -> Self {
+        self.job_runs = Some(job_runs);
+        self
+    }
+
+    /// Notify operators when a job completes or runs out of retries
+    pub fn with_notifier(mut self, notifier: JobNotifier) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
     /// Start the worker loop
     ///
     /// This runs indefinitely, polling for jobs at the configured interval.
@@ -67,6 +95,16 @@ impl FoprImportWorker {
     /// Process a single job (if available)
     #[instrument(skip(self), fields(worker_id = %self.worker_id))]
     async fn process_next_job(&self) -> Result<(), Box<dyn std::error::Error>> {
+        // FOPR imports download from MCFCD and recalculate summaries; leave jobs queued
+        // until both are allowed
+        if !self.windows.allows(JobKind::HeavyDownload, Utc::now()) {
+            debug!(
+                worker_id = self.worker_id,
+                "Outside the job window; not claiming"
+            );
+            return Ok(());
+        }
+
         // Atomically claim next job
         let job = match self.job_repo.claim_next_job().await? {
             Some(j) => j,
@@ -84,7 +122,17 @@ impl FoprImportWorker {
         );
 
         // Execute import
+        let timer =
+            JobRunTimer::start(JobRunKind::FoprImport).for_fopr_job(job.id, &job.station_id);
         let result = self.import_service.import_fopr(&job.station_id).await;
+        if let Some(job_runs) = &self.job_runs {
+            let rows = result
+                .as_ref()
+                .map(|s| s.readings_imported + s.readings_updated);
+            job_runs
+                .record(timer.finish(rows.map_err(|e| e.to_string())))
+                .await;
+        }
 
         // Update job based on result
         match result {
@@ -98,6 +146,11 @@ impl FoprImportWorker {
                     "Job completed successfully"
                 );
                 self.job_repo.mark_completed(job.id, &stats).await?;
+                if let Some(notifier) = &self.notifier {
+                    notifier
+                        .notify(&JobNotification::completed(&job, &stats))
+                        .await;
+                }
             }
             Err(e) => {
                 let error_msg = e.to_string();
@@ -156,6 +209,11 @@ impl FoprImportWorker {
                         max_retries = job.max_retries,
                         "Job exceeded max retries, giving up"
                     );
+                    if let Some(notifier) = &self.notifier {
+                        notifier
+                            .notify(&JobNotification::failed(&job, &error_msg, new_retry_count))
+                            .await;
+                    }
                 } else {
                     info!(
                         worker_id = self.worker_id,
@@ -0,0 +1,314 @@
+// MSP forecast zone polygons
+//
+// Zones are imported from a GeoJSON FeatureCollection of Polygon or MultiPolygon features
+// (WGS 84 longitude/latitude, as exported by most GIS tools). Each feature's zone ID and
+// name come from configurable properties. A gauge belongs to the zone whose polygon
+// contains its coordinates; holes are honored, and when polygons overlap the first zone
+// in ID order wins so assignment is deterministic.
+
+use std::collections::HashSet;
+
+use serde_json::Value;
+
+/// Closed ring of (longitude, latitude) positions
+pub type Ring = Vec<(f64, f64)>;
+
+/// Outer ring followed by its holes
+pub type Polygon = Vec<Ring>;
+
+/// Feature property holding the zone ID when none is given
+pub const DEFAULT_ZONE_ID_PROPERTY: &str = "zone";
+
+/// Feature property holding the zone name when none is given
+pub const DEFAULT_ZONE_NAME_PROPERTY: &str = "name";
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum ZoneError {
+    #[error("Invalid GeoJSON: {0}")]
+    Json(String),
+
+    #[error("Feature {index}: {message}")]
+    Feature { index: usize, message: String },
+
+    #[error("Zone {0} appears more than once")]
+    Duplicate(String),
+
+    #[error("No zone features found")]
+    Empty,
+}
+
+/// A forecast zone polygon ready to store
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZonePolygon {
+    pub zone_id: String,
+    pub name: Option<String>,
+    /// GeoJSON geometry as imported
+    pub geometry: Value,
+    pub bounds: Bounds,
+    /// Polygons of the geometry
+    polygons: Vec<Polygon>,
+}
+
+/// Latitude/longitude bounding box
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    pub min_latitude: f64,
+    pub max_latitude: f64,
+    pub min_longitude: f64,
+    pub max_longitude: f64,
+}
+
+impl Bounds {
+    pub fn contains(&self, latitude: f64, longitude: f64) -> bool {
+        (self.min_latitude..=self.max_latitude).contains(&latitude)
+            && (self.min_longitude..=self.max_longitude).contains(&longitude)
+    }
+}
+
+impl ZonePolygon {
+    /// Build a zone from a stored GeoJSON geometry
+    pub fn from_geometry(
+        zone_id: impl Into<String>,
+        name: Option<String>,
+        geometry: Value,
+    ) -> Result<Self, String> {
+        let polygons = parse_polygons(&geometry)?;
+        let points = polygons.iter().flatten().flatten();
+        let mut bounds = Bounds {
+            min_latitude: f64::INFINITY,
+            max_latitude: f64::NEG_INFINITY,
+            min_longitude: f64::INFINITY,
+            max_longitude: f64::NEG_INFINITY,
+        };
+        for &(longitude, latitude) in points {
+            bounds.min_latitude = bounds.min_latitude.min(latitude);
+            bounds.max_latitude = bounds.max_latitude.max(latitude);
+            bounds.min_longitude = bounds.min_longitude.min(longitude);
+            bounds.max_longitude = bounds.max_longitude.max(longitude);
+        }
+
+        Ok(Self {
+            zone_id: zone_id.into(),
+            name,
+            geometry,
+            bounds,
+            polygons,
+        })
+    }
+
+    /// Polygons of the geometry, each an outer ring followed by its holes
+    pub fn polygons(&self) -> &[Polygon] {
+        &self.polygons
+    }
+
+    /// Whether the point is inside the zone (outside its holes)
+    pub fn contains(&self, latitude: f64, longitude: f64) -> bool {
+        self.bounds.contains(latitude, longitude)
+            && self.polygons.iter().any(|rings| {
+                let mut rings = rings.iter();
+                rings
+                    .next()
+                    .is_some_and(|outer| ring_contains(outer, latitude, longitude))
+                    && !rings.any(|hole| ring_contains(hole, latitude, longitude))
+            })
+    }
+}
+
+/// The zone containing a point, from zones sorted by ID
+pub fn zone_for_point(zones: &[ZonePolygon], latitude: f64, longitude: f64) -> Option<&str> {
+    zones
+        .iter()
+        .find(|zone| zone.contains(latitude, longitude))
+        .map(|zone| zone.zone_id.as_str())
+}
+
+/// Parse a GeoJSON FeatureCollection into zones, sorted by zone ID
+///
+/// Every feature must have a Polygon or MultiPolygon geometry and a non-empty
+/// `id_property`; `name_property` is optional.
+pub fn parse_feature_collection(
+    text: &str,
+    id_property: &str,
+    name_property: &str,
+) -> Result<Vec<ZonePolygon>, ZoneError> {
+    let collection: Value =
+        serde_json::from_str(text).map_err(|e| ZoneError::Json(e.to_string()))?;
+    let features = collection
+        .get("features")
+        .and_then(Value::as_array)
+        .ok_or_else(|| ZoneError::Json("expected a FeatureCollection".to_string()))?;
+
+    let mut seen = HashSet::new();
+    let mut zones = Vec::with_capacity(features.len());
+    for (index, feature) in features.iter().enumerate() {
+        let invalid = |message: String| ZoneError::Feature { index, message };
+        let properties = &feature["properties"];
+        let zone_id = property_text(&properties[id_property])
+            .ok_or_else(|| invalid(format!("missing {id_property:?} property")))?;
+        let name = property_text(&properties[name_property]);
+        if !seen.insert(zone_id.clone()) {
+            return Err(ZoneError::Duplicate(zone_id));
+        }
+
+        let zone = ZonePolygon::from_geometry(zone_id, name, feature["geometry"].clone())
+            .map_err(invalid)?;
+        zones.push(zone);
+    }
+    if zones.is_empty() {
+        return Err(ZoneError::Empty);
+    }
+
+    zones.sort_by(|a, b| a.zone_id.cmp(&b.zone_id));
+    Ok(zones)
+}
+
+/// A string or number property as trimmed text; None when missing or empty
+fn property_text(value: &Value) -> Option<String> {
+    let text = match value {
+        Value::String(s) => s.trim().to_string(),
+        Value::Number(n) => n.to_string(),
+        _ => return None,
+    };
+    Some(text).filter(|t| !t.is_empty())
+}
+
+fn parse_polygons(geometry: &Value) -> Result<Vec<Polygon>, String> {
+    let coordinates = &geometry["coordinates"];
+    let polygons = match geometry["type"].as_str() {
+        Some("Polygon") => vec![parse_polygon(coordinates)?],
+        Some("MultiPolygon") => coordinates
+            .as_array()
+            .ok_or("MultiPolygon coordinates must be an array")?
+            .iter()
+            .map(parse_polygon)
+            .collect::<Result<_, _>>()?,
+        other => {
+            return Err(format!(
+                "geometry must be a Polygon or MultiPolygon, got {}",
+                other.unwrap_or("none")
+            ))
+        }
+    };
+    if polygons.is_empty() {
+        return Err("geometry has no polygons".to_string());
+    }
+    Ok(polygons)
+}
+
+fn parse_polygon(rings: &Value) -> Result<Polygon, String> {
+    let rings = rings
+        .as_array()
+        .filter(|rings| !rings.is_empty())
+        .ok_or("polygon must have at least one ring")?;
+    rings
+        .iter()
+        .map(|ring| {
+            let points = ring
+                .as_array()
+                .ok_or("ring must be an array of positions")?
+                .iter()
+                .map(
+                    |position| match (position[0].as_f64(), position[1].as_f64()) {
+                        (Some(longitude), Some(latitude)) => Ok((longitude, latitude)),
+                        _ => Err(format!("invalid position {position}")),
+                    },
+                )
+                .collect::<Result<Ring, _>>()?;
+            if points.len() < 4 {
+                return Err("ring must have at least 4 positions".to_string());
+            }
+            Ok(points)
+        })
+        .collect()
+}
+
+/// Even-odd ray casting test of a point against one ring of (longitude, latitude)
+fn ring_contains(ring: &[(f64, f64)], latitude: f64, longitude: f64) -> bool {
+    let mut inside = false;
+    let mut previous = ring[ring.len() - 1];
+    for &current in ring {
+        let ((x1, y1), (x2, y2)) = (previous, current);
+        if (y1 > latitude) != (y2 > latitude)
+            && longitude < x1 + (latitude - y1) * (x2 - x1) / (y2 - y1)
+        {
+            inside = !inside;
+        }
+        previous = current;
+    }
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// E1: a square with a square hole; W1: a square to its west
+    const ZONES: &str = r#"{
+        "type": "FeatureCollection",
+        "features": [
+            {
+                "type": "Feature",
+                "properties": {"zone": "W1", "name": "West Valley"},
+                "geometry": {"type": "Polygon", "coordinates": [
+                    [[-113.0, 33.0], [-112.5, 33.0], [-112.5, 34.0], [-113.0, 34.0], [-113.0, 33.0]]
+                ]}
+            },
+            {
+                "type": "Feature",
+                "properties": {"zone": "E1", "name": "East Valley"},
+                "geometry": {"type": "MultiPolygon", "coordinates": [[
+                    [[-112.5, 33.0], [-111.5, 33.0], [-111.5, 34.0], [-112.5, 34.0], [-112.5, 33.0]],
+                    [[-112.0, 33.4], [-111.9, 33.4], [-111.9, 33.5], [-112.0, 33.5], [-112.0, 33.4]]
+                ]]}
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn test_parse_feature_collection() {
+        let zones = parse_feature_collection(ZONES, "zone", "name").unwrap();
+        let ids: Vec<&str> = zones.iter().map(|z| z.zone_id.as_str()).collect();
+        assert_eq!(ids, ["E1", "W1"]);
+        assert_eq!(zones[0].name.as_deref(), Some("East Valley"));
+        assert_eq!(
+            zones[0].bounds,
+            Bounds {
+                min_latitude: 33.0,
+                max_latitude: 34.0,
+                min_longitude: -112.5,
+                max_longitude: -111.5,
+            }
+        );
+    }
+
+    #[test]
+    fn test_zone_for_point() {
+        let zones = parse_feature_collection(ZONES, "zone", "name").unwrap();
+
+        assert_eq!(zone_for_point(&zones, 33.6, -112.8), Some("W1"));
+        assert_eq!(zone_for_point(&zones, 33.6, -111.7), Some("E1"));
+        assert_eq!(zone_for_point(&zones, 33.45, -111.95), None, "in the hole");
+        assert_eq!(zone_for_point(&zones, 35.0, -112.0), None, "north of both");
+    }
+
+    #[test]
+    fn test_parse_feature_collection_errors() {
+        assert_eq!(
+            parse_feature_collection(ZONES, "ZONE_ID", "name").unwrap_err(),
+            ZoneError::Feature {
+                index: 0,
+                message: "missing \"ZONE_ID\" property".to_string()
+            }
+        );
+        assert_eq!(
+            parse_feature_collection(&ZONES.replace("W1", "E1"), "zone", "name").unwrap_err(),
+            ZoneError::Duplicate("E1".to_string())
+        );
+        let point = r#"{"features": [{"properties": {"zone": "P"},
+            "geometry": {"type": "Point", "coordinates": [-112.0, 33.5]}}]}"#;
+        assert!(matches!(
+            parse_feature_collection(point, "zone", "name").unwrap_err(),
+            ZoneError::Feature { index: 0, .. }
+        ));
+    }
+}
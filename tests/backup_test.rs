@@ -0,0 +1,334 @@
+// Tests for the backup and restore service
+// PostgreSQL round trips use the test database; SQLite tests need `--features sqlite`
+
+mod common;
+
+use std::fs::File;
+use std::path::Path;
+
+use chrono::{TimeZone, Utc};
+use rain_tracker_service::db::{
+    BackupRepository, DbPool, GaugeRepository, MonthlyRainfallRepository, ReadingRepository,
+};
+use rain_tracker_service::fetcher::RainReading;
+use rain_tracker_service::gauge_list_fetcher::GaugeSummary as FetchedGauge;
+use rain_tracker_service::services::backup_service::{
+    BackupError, BackupManifest, BackupService, BACKUP_FORMAT_VERSION, MANIFEST_FILE,
+};
+use rain_tracker_service::units::Inches;
+use serial_test::serial;
+use sqlx::postgres::PgPoolOptions;
+
+mod backup_fixtures {
+    use super::*;
+
+    pub const STATION_ID: &str = "98801";
+
+    pub async fn postgres_db() -> DbPool {
+        let database_url = crate::common::database_url().await;
+        let pool = PgPoolOptions::new()
+            .max_connections(5)
+            .connect(&database_url)
+            .await
+            .expect("Failed to connect to test database");
+
+        let db = DbPool::from(pool);
+        db.migrate().await.expect("Failed to run migrations");
+        db
+    }
+
+    pub async fn cleanup(db: &DbPool) {
+        let pool = db.postgres().unwrap();
+        for table in [
+            "daily_rainfall_intensity",
+            "hourly_rainfall_summary",
+            "monthly_rainfall_summary",
+            "rain_readings",
+            "gauge_summaries",
+            "gauges",
+        ] {
+            sqlx::query(&format!("DELETE FROM {table} WHERE station_id = $1"))
+                .bind(STATION_ID)
+                .execute(pool)
+                .await
+                .ok();
+        }
+    }
+
+    pub fn readings() -> Vec<RainReading> {
+        vec![
+            RainReading {
+                reading_datetime: Utc.with_ymd_and_hms(2089, 11, 3, 6, 0, 0).unwrap(),
+                cumulative_inches: Inches::new(0.12).unwrap(),
+                incremental_inches: Inches::new(0.12).unwrap(),
+            },
+            RainReading {
+                reading_datetime: Utc.with_ymd_and_hms(2089, 11, 3, 6, 15, 30).unwrap()
+                    + chrono::Duration::milliseconds(250),
+                cumulative_inches: Inches::new(0.2).unwrap(),
+                incremental_inches: Inches::new(0.08).unwrap(),
+            },
+        ]
+    }
+
+    /// One gauge with a list summary, readings, and a monthly summary
+    pub async fn populate(db: &DbPool) {
+        let gauge = FetchedGauge {
+            station_id: STATION_ID.parse().unwrap(),
+            gauge_name: "Backup Test Gauge".to_string(),
+            city_town: Some("Phoenix".to_string()),
+            elevation_ft: Some(1100),
+            general_location: Some("Test Location".to_string()),
+            msp_forecast_zone: None,
+            rainfall_past_6h_inches: Some(Inches::new(0.0).unwrap()),
+            rainfall_past_24h_inches: Some(Inches::new(0.2).unwrap()),
+        };
+        let gauge_repo = GaugeRepository::new(db.clone());
+        gauge_repo.register_from_list(&gauge).await.unwrap();
+        gauge_repo.upsert_summaries(&[gauge]).await.unwrap();
+
+        ReadingRepository::new(db.clone())
+            .insert_station_readings(STATION_ID, "live_scrape", &readings())
+            .await
+            .unwrap();
+        let start = Utc.with_ymd_and_hms(2089, 11, 1, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2089, 12, 1, 0, 0, 0).unwrap();
+        MonthlyRainfallRepository::new(db.clone())
+            .recalculate_monthly_summary(STATION_ID, 2089, 11, start, end)
+            .await
+            .unwrap();
+    }
+
+    pub async fn stored_readings(db: &DbPool) -> Vec<RainReading> {
+        let start = Utc.with_ymd_and_hms(2089, 11, 1, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2089, 12, 1, 0, 0, 0).unwrap();
+        let mut readings: Vec<RainReading> = ReadingRepository::new(db.clone())
+            .find_by_date_range(STATION_ID, start, end)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| RainReading {
+                reading_datetime: r.reading_datetime,
+                cumulative_inches: r.cumulative_inches,
+                incremental_inches: r.incremental_inches,
+            })
+            .collect();
+        readings.reverse();
+        readings
+    }
+
+    /// A hand-built archive holding only `manifest`
+    pub fn write_manifest_archive(path: &Path, manifest: &BackupManifest) {
+        let dir = tempfile::tempdir().unwrap();
+        serde_json::to_writer(
+            File::create(dir.path().join(MANIFEST_FILE)).unwrap(),
+            manifest,
+        )
+        .unwrap();
+
+        let encoder = zstd::Encoder::new(File::create(path).unwrap(), 3).unwrap();
+        let mut builder = tar::Builder::new(encoder);
+        builder
+            .append_path_with_name(dir.path().join(MANIFEST_FILE), MANIFEST_FILE)
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+}
+
+use backup_fixtures::*;
+
+#[tokio::test]
+#[serial]
+async fn test_postgres_backup_and_restore() {
+    let db = postgres_db().await;
+    cleanup(&db).await;
+    populate(&db).await;
+    let service = BackupService::new(BackupRepository::new(db.clone()));
+    let dir = tempfile::tempdir().unwrap();
+    let archive = dir.path().join("backup.tar.zst");
+
+    let manifest = service.backup(&archive).await.unwrap();
+    assert_eq!(manifest.format_version, BACKUP_FORMAT_VERSION);
+    assert_eq!(manifest.backend, "postgres");
+    assert!(manifest.schema_version.is_some());
+    let table_names: Vec<&str> = manifest.tables.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(
+        table_names,
+        [
+            "gauges",
+            "gauge_summaries",
+            "rain_readings",
+            "monthly_rainfall_summary",
+            "hourly_rainfall_summary",
+            "daily_rainfall_intensity",
+            "fopr_import_jobs",
+            "forecast_zones"
+        ]
+    );
+
+    // The test database has gauges, so a plain restore is refused
+    let err = service.restore(&archive, false).await.unwrap_err();
+    assert!(matches!(err, BackupError::NotEmpty(_)), "{err}");
+
+    // Lose the readings, then merge the backup back in
+    sqlx::query("DELETE FROM rain_readings WHERE station_id = $1")
+        .bind(STATION_ID)
+        .execute(db.postgres().unwrap())
+        .await
+        .unwrap();
+    let stats = service.restore(&archive, true).await.unwrap();
+    let readings_table = stats
+        .tables
+        .iter()
+        .find(|t| t.name == "rain_readings")
+        .unwrap();
+    assert!(readings_table.inserted >= 2);
+    let gauges_table = stats.tables.iter().find(|t| t.name == "gauges").unwrap();
+    assert_eq!(gauges_table.inserted, 0);
+    assert!(stats.skipped_tables.is_empty());
+
+    let restored = stored_readings(&db).await;
+    let expected = readings();
+    assert_eq!(restored.len(), expected.len());
+    for (restored, expected) in restored.iter().zip(&expected) {
+        assert_eq!(restored.reading_datetime, expected.reading_datetime);
+        assert_eq!(restored.cumulative_inches, expected.cumulative_inches);
+    }
+
+    cleanup(&db).await;
+}
+
+#[tokio::test]
+#[serial]
+async fn test_restore_rejects_other_format_versions() {
+    let db = postgres_db().await;
+    let service = BackupService::new(BackupRepository::new(db));
+    let dir = tempfile::tempdir().unwrap();
+    let archive = dir.path().join("future.tar.zst");
+    write_manifest_archive(
+        &archive,
+        &BackupManifest {
+            format_version: BACKUP_FORMAT_VERSION + 1,
+            created_at: Utc::now(),
+            backend: "postgres".to_string(),
+            schema_version: None,
+            tool_version: "99.0.0".to_string(),
+            tables: vec![],
+        },
+    );
+
+    let err = service.restore(&archive, true).await.unwrap_err();
+    assert!(matches!(err, BackupError::UnsupportedFormat(v) if v == BACKUP_FORMAT_VERSION + 1));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_restore_rejects_newer_schema() {
+    let db = postgres_db().await;
+    let service = BackupService::new(BackupRepository::new(db));
+    let dir = tempfile::tempdir().unwrap();
+    let archive = dir.path().join("newer.tar.zst");
+    write_manifest_archive(
+        &archive,
+        &BackupManifest {
+            format_version: BACKUP_FORMAT_VERSION,
+            created_at: Utc::now(),
+            backend: "postgres".to_string(),
+            schema_version: Some(99991231000000),
+            tool_version: "99.0.0".to_string(),
+            tables: vec![],
+        },
+    );
+
+    let err = service.restore(&archive, true).await.unwrap_err();
+    assert!(matches!(
+        err,
+        BackupError::SchemaTooNew {
+            backup: 99991231000000,
+            ..
+        }
+    ));
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite_backend {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn sqlite_db() -> DbPool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = DbPool::from(pool);
+        db.migrate().await.unwrap();
+        db
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_backup_restores_into_empty_sqlite() {
+        let source = sqlite_db().await;
+        populate(&source).await;
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("backup.tar.zst");
+
+        let manifest = BackupService::new(BackupRepository::new(source.clone()))
+            .backup(&archive)
+            .await
+            .unwrap();
+        assert_eq!(manifest.backend, "sqlite");
+        assert!(!manifest.tables.iter().any(|t| t.name == "fopr_import_jobs"));
+
+        let target = sqlite_db().await;
+        let service = BackupService::new(BackupRepository::new(target.clone()));
+        let stats = service.restore(&archive, false).await.unwrap();
+        assert!(stats.tables.iter().all(|t| t.inserted == t.rows));
+
+        let restored = stored_readings(&target).await;
+        assert_eq!(restored.len(), 2);
+        assert_eq!(restored[1].reading_datetime, readings()[1].reading_datetime);
+        let gauge = GaugeRepository::new(target.clone())
+            .find_by_id(STATION_ID)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(gauge.gauge_name, "Backup Test Gauge");
+
+        // Merging the same backup again adds nothing
+        let again = service.restore(&archive, true).await.unwrap();
+        assert!(again.tables.iter().all(|t| t.inserted == 0));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_postgres_backup_restores_into_sqlite() {
+        let postgres = postgres_db().await;
+        cleanup(&postgres).await;
+        populate(&postgres).await;
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("backup.tar.zst");
+        BackupService::new(BackupRepository::new(postgres.clone()))
+            .backup(&archive)
+            .await
+            .unwrap();
+        cleanup(&postgres).await;
+
+        let target = sqlite_db().await;
+        let stats = BackupService::new(BackupRepository::new(target.clone()))
+            .restore(&archive, false)
+            .await
+            .unwrap();
+        assert_eq!(stats.skipped_tables, ["fopr_import_jobs"]);
+
+        let restored = stored_readings(&target).await;
+        assert_eq!(restored.len(), 2);
+        assert_eq!(restored[1].reading_datetime, readings()[1].reading_datetime);
+        let metadata = GaugeRepository::new(target)
+            .find_detail(STATION_ID)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(metadata.gauge_name, "Backup Test Gauge");
+    }
+}
@@ -0,0 +1,275 @@
+// Tests for DigestService: building digests from the summaries and delivering them once
+
+mod common;
+
+use std::sync::Arc;
+
+use chrono::{DateTime, TimeZone, Utc};
+use mockito::{Matcher, Server};
+use rain_tracker_service::clock::FixedClock;
+use rain_tracker_service::db::{User, UserRepository};
+use rain_tracker_service::digest::{DigestFrequency, DigestSender};
+use rain_tracker_service::services::digest_service::{
+    DigestPreviewParams, DigestSubscriptionRequest,
+};
+use rain_tracker_service::services::DigestService;
+use sqlx::PgPool;
+
+const WET: &str = "DIGEST_WET";
+const DRY: &str = "DIGEST_DRY";
+
+fn at(day: u32, hour: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 2, day, hour, 0, 0).unwrap()
+}
+
+/// A user with both test gauges as favorites, rain at WET on Feb 4 and Feb 11, and
+/// readings at DRY for all but 12 hours of the week of Feb 3
+async fn setup() -> (PgPool, User) {
+    let pool = common::isolated_db().await;
+    for (station_id, name) in [(WET, "Digest Wet"), (DRY, "Digest Dry")] {
+        sqlx::query!(
+            r#"
+            INSERT INTO gauges (station_id, station_name, station_type, latitude, longitude, county, status)
+            VALUES ($1, $2, 'Rain', 33.5, -112.0, 'Test County', 'Active')
+            "#,
+            station_id,
+            name
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+
+    let hours = [
+        (WET, at(4, 6), 0.4),
+        (WET, at(4, 7), 0.8),
+        // The following week, outside the digest
+        (WET, at(11, 6), 2.0),
+    ];
+    for (station_id, hour_start, inches) in hours {
+        sqlx::query!(
+            r#"
+            INSERT INTO hourly_rainfall_summary
+                (station_id, hour_start, total_rainfall_inches, max_incremental_inches, reading_count)
+            VALUES ($1, $2, $3, $3, 4)
+            "#,
+            station_id,
+            hour_start,
+            inches
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+    sqlx::query!(
+        r#"
+        INSERT INTO hourly_rainfall_summary
+            (station_id, hour_start, total_rainfall_inches, max_incremental_inches, reading_count)
+        SELECT $1, hour_start, 0, 0, 4
+        FROM generate_series($2::TIMESTAMPTZ, $3::TIMESTAMPTZ, INTERVAL '1 hour') AS hour_start
+        "#,
+        DRY,
+        at(3, 12),
+        at(9, 23)
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    sqlx::query!(
+        r#"
+        INSERT INTO gauge_threshold_events
+            (station_id, threshold_inches, crossed_at, ended_at, rainfall_24h_inches,
+             peak_rainfall_24h_inches)
+        VALUES ($1, 1.0, $2, $3, 1.2, 1.2)
+        "#,
+        WET,
+        at(4, 7),
+        at(5, 8)
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let users = UserRepository::new(pool.clone());
+    let user = users
+        .insert("digest-test", &"0".repeat(64), None, None)
+        .await
+        .unwrap();
+    for station_id in [WET, DRY] {
+        assert!(users.add_favorite(user.id, station_id).await.unwrap());
+    }
+    (pool, user)
+}
+
+fn service(pool: &PgPool) -> DigestService {
+    // Wednesday, so the latest complete week is Feb 3-9
+    DigestService::new(pool.clone())
+        .with_sender(DigestSender::default().with_private_webhooks())
+        .with_clock(Arc::new(FixedClock(at(12, 8))))
+}
+
+fn webhook(url: String) -> DigestSubscriptionRequest {
+    DigestSubscriptionRequest {
+        frequency: DigestFrequency::Weekly,
+        email: None,
+        webhook_url: Some(url),
+    }
+}
+
+#[tokio::test]
+async fn test_preview_summarizes_favorites_over_the_period() {
+    let (pool, user) = setup().await;
+
+    let digest = service(&pool)
+        .preview(
+            &user,
+            &DigestPreviewParams {
+                frequency: DigestFrequency::Weekly,
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(digest.first_day.to_string(), "2025-02-03");
+    assert_eq!(digest.last_day.to_string(), "2025-02-09");
+    let stations: Vec<&str> = digest
+        .gauges
+        .iter()
+        .map(|g| g.station_id.as_str())
+        .collect();
+    assert_eq!(stations, [WET, DRY], "wettest first");
+
+    let wet = &digest.gauges[0];
+    assert!((wet.total_inches - 1.2).abs() < 1e-9);
+    assert_eq!(wet.wettest_hour_inches, Some(0.8));
+    assert_eq!((wet.reported_hours, wet.missing_hours), (2, 166));
+    let dry = &digest.gauges[1];
+    assert_eq!(dry.total_inches, 0.0);
+    assert_eq!((dry.reported_hours, dry.missing_hours), (156, 12));
+
+    assert_eq!(digest.events.len(), 1);
+    assert_eq!(digest.events[0].station_id, WET);
+}
+
+#[tokio::test]
+async fn test_send_due_delivers_each_period_once() {
+    let (pool, user) = setup().await;
+    let service = service(&pool);
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/digest")
+        .match_header("content-type", "application/json")
+        .match_body(Matcher::PartialJson(serde_json::json!({
+            "frequency": "weekly",
+            "first_day": "2025-02-03",
+            "last_day": "2025-02-09",
+            "gauges": [{"station_id": WET, "total_inches": 1.2}, {"station_id": DRY}]
+        })))
+        .with_status(204)
+        .expect(1)
+        .create_async()
+        .await;
+
+    service
+        .subscribe(&user, &webhook(format!("{}/digest", server.url())))
+        .await
+        .unwrap();
+
+    let stats = service.send_due().await.unwrap();
+    assert_eq!((stats.due, stats.sent, stats.failed), (1, 1, 0));
+    let subscription = service.subscription(&user).await.unwrap();
+    assert_eq!(
+        subscription.last_sent_through.unwrap().to_string(),
+        "2025-02-09"
+    );
+
+    // The week was delivered, so the next run has nothing to send
+    let stats = service.send_due().await.unwrap();
+    assert_eq!(stats.due, 0);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_failed_delivery_is_retried() {
+    let (pool, user) = setup().await;
+    let service = service(&pool);
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/digest")
+        .with_status(500)
+        .expect(2)
+        .create_async()
+        .await;
+
+    service
+        .subscribe(&user, &webhook(format!("{}/digest", server.url())))
+        .await
+        .unwrap();
+
+    for _ in 0..2 {
+        let stats = service.send_due().await.unwrap();
+        assert_eq!((stats.due, stats.sent, stats.failed), (1, 0, 1));
+    }
+    assert!(service
+        .subscription(&user)
+        .await
+        .unwrap()
+        .last_sent_through
+        .is_none());
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_webhooks_to_private_hosts_and_redirects_are_refused() {
+    let (pool, user) = setup().await;
+    let mut server = Server::new_async().await;
+    let private = server
+        .mock("POST", "/digest")
+        .expect(0)
+        .create_async()
+        .await;
+    let redirect = server
+        .mock("POST", "/moved")
+        .with_status(307)
+        .with_header("location", "/digest")
+        .expect(1)
+        .create_async()
+        .await;
+
+    // The default sender only reaches public hosts, so the local server is never called
+    let public_only = DigestService::new(pool.clone()).with_clock(Arc::new(FixedClock(at(12, 8))));
+    public_only
+        .subscribe(&user, &webhook(format!("{}/digest", server.url())))
+        .await
+        .unwrap();
+    let stats = public_only.send_due().await.unwrap();
+    assert_eq!((stats.due, stats.sent, stats.failed), (1, 0, 1));
+
+    // Redirects are not followed, even to a host deliveries may reach
+    let service = service(&pool);
+    service
+        .subscribe(&user, &webhook(format!("{}/moved", server.url())))
+        .await
+        .unwrap();
+    let stats = service.send_due().await.unwrap();
+    assert_eq!((stats.due, stats.sent, stats.failed), (1, 0, 1));
+
+    private.assert_async().await;
+    redirect.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_email_needs_smtp() {
+    let (pool, user) = setup().await;
+    let request = DigestSubscriptionRequest {
+        frequency: DigestFrequency::Daily,
+        email: Some("jsmith@example.com".to_string()),
+        webhook_url: None,
+    };
+
+    let result = service(&pool).subscribe(&user, &request).await;
+    assert!(matches!(
+        result,
+        Err(rain_tracker_service::services::digest_service::DigestError::EmailDisabled)
+    ));
+}
@@ -175,6 +175,168 @@ async fn test_download_fopr_404() {
     mock.assert_async().await;
 }
 
+#[tokio::test]
+async fn test_head_fopr_available() {
+    let mut server = Server::new_async().await;
+
+    let mock = server
+        .mock("HEAD", "/FOPR/59700_FOPR.xlsx")
+        .with_status(200)
+        .with_header("last-modified", "Mon, 06 Jan 2025 17:42:00 GMT")
+        .with_header("content-length", "326073")
+        .create_async()
+        .await;
+
+    let downloader = create_test_downloader(server.url() + "/");
+    let status = downloader.head_fopr("59700").await.unwrap();
+
+    assert!(status.available);
+    assert_eq!(status.http_status, 200);
+    assert_eq!(
+        status.last_modified.unwrap().to_rfc3339(),
+        "2025-01-06T17:42:00+00:00"
+    );
+    assert_eq!(status.content_length, Some(326073));
+
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_head_fopr_missing_is_not_an_error() {
+    let mut server = Server::new_async().await;
+
+    let mock = server
+        .mock("HEAD", "/FOPR/99999_FOPR.xlsx")
+        .with_status(404)
+        .create_async()
+        .await;
+
+    let downloader = create_test_downloader(server.url() + "/");
+    let status = downloader.head_fopr("99999").await.unwrap();
+
+    assert!(!status.available);
+    assert_eq!(status.http_status, 404);
+    assert_eq!(status.last_modified, None);
+
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_head_fopr_server_error() {
+    let mut server = Server::new_async().await;
+
+    let mock = server
+        .mock("HEAD", "/FOPR/59700_FOPR.xlsx")
+        .with_status(503)
+        .create_async()
+        .await;
+
+    let downloader = create_test_downloader(server.url() + "/");
+    match downloader.head_fopr("59700").await {
+        Err(DownloadError::ServerError(msg)) => assert!(msg.contains("59700_FOPR.xlsx")),
+        other => panic!("Expected ServerError, got {other:?}"),
+    }
+
+    mock.assert_async().await;
+}
+
+/// Serve one scripted raw HTTP response per connection, returning each request's head
+///
+/// mockito can't drop a connection partway through a body, which is what resumption is for.
+async fn serve_raw(responses: Vec<Vec<u8>>) -> (String, tokio::task::JoinHandle<Vec<String>>) {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    let handle = tokio::spawn(async move {
+        let mut requests = Vec::new();
+        for response in responses {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut head = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !head.ends_with(b"\r\n\r\n") {
+                let n = socket.read(&mut buf).await.unwrap();
+                head.extend_from_slice(&buf[..n]);
+            }
+            requests.push(String::from_utf8_lossy(&head).to_lowercase());
+            socket.write_all(&response).await.unwrap();
+            socket.shutdown().await.unwrap();
+        }
+        requests
+    });
+    (url, handle)
+}
+
+#[tokio::test]
+async fn test_download_fopr_resumes_interrupted_download() {
+    let partial_dir = tempfile::tempdir().unwrap();
+    let (url, server) = serve_raw(vec![
+        // Promises 12 bytes, sends 6, then hangs up
+        b"HTTP/1.1 200 OK\r\ncontent-length: 12\r\netag: \"v1\"\r\n\r\nhello ".to_vec(),
+        b"HTTP/1.1 206 Partial Content\r\ncontent-length: 6\r\ncontent-range: bytes 6-11/12\r\n\r\nworld!".to_vec(),
+    ])
+    .await;
+    let downloader = create_test_downloader(url).with_partial_dir(partial_dir.path());
+
+    assert!(downloader.download_fopr("59700").await.is_err());
+    assert_eq!(std::fs::read_dir(partial_dir.path()).unwrap().count(), 2);
+
+    let bytes = downloader.download_fopr("59700").await.unwrap();
+    assert_eq!(bytes, b"hello world!");
+    // The partial copy is removed once the file is complete
+    assert_eq!(std::fs::read_dir(partial_dir.path()).unwrap().count(), 0);
+
+    let requests = server.await.unwrap();
+    assert!(!requests[0].contains("range:"));
+    assert!(requests[1].contains("range: bytes=6-"));
+    assert!(requests[1].contains("if-range: \"v1\""));
+}
+
+#[tokio::test]
+async fn test_download_fopr_restarts_when_file_changed() {
+    let partial_dir = tempfile::tempdir().unwrap();
+    let (url, server) = serve_raw(vec![
+        b"HTTP/1.1 200 OK\r\ncontent-length: 12\r\netag: \"v1\"\r\n\r\nhello ".to_vec(),
+        // If-Range no longer matches, so the server sends the whole new file
+        b"HTTP/1.1 200 OK\r\ncontent-length: 8\r\netag: \"v2\"\r\n\r\nnew file".to_vec(),
+    ])
+    .await;
+    let downloader = create_test_downloader(url).with_partial_dir(partial_dir.path());
+
+    assert!(downloader.download_fopr("59700").await.is_err());
+    let bytes = downloader.download_fopr("59700").await.unwrap();
+    assert_eq!(bytes, b"new file");
+    assert_eq!(std::fs::read_dir(partial_dir.path()).unwrap().count(), 0);
+
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_download_fopr_discards_corrupted_partial() {
+    let partial_dir = tempfile::tempdir().unwrap();
+    let (url, server) = serve_raw(vec![
+        b"HTTP/1.1 200 OK\r\ncontent-length: 12\r\n\r\nhello ".to_vec(),
+        b"HTTP/1.1 200 OK\r\ncontent-length: 12\r\n\r\nhello world!".to_vec(),
+    ])
+    .await;
+    let downloader = create_test_downloader(url).with_partial_dir(partial_dir.path());
+
+    assert!(downloader.download_fopr("59700").await.is_err());
+    for entry in std::fs::read_dir(partial_dir.path()).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_some_and(|ext| ext == "part") {
+            std::fs::write(&path, b"HELLO ").unwrap();
+        }
+    }
+
+    // Same length, different hash: downloaded again from the start
+    let bytes = downloader.download_fopr("59700").await.unwrap();
+    assert_eq!(bytes, b"hello world!");
+
+    let requests = server.await.unwrap();
+    assert!(!requests[1].contains("range:"));
+}
+
 #[tokio::test]
 async fn test_download_water_year_pdfs_partial() {
     // Test downloading first 3 PDFs of a water year (Oct-Dec)
@@ -1,8 +1,13 @@
 // Tests for ExcelImporter to improve coverage
 // Tests parsing Excel files with rain gauge data
 
+use std::collections::HashSet;
+
 use chrono::Datelike;
-use rain_tracker_service::importers::excel_importer::{ExcelImportError, ExcelImporter};
+use rain_tracker_service::importers::excel_importer::{
+    discover_gauges_from_water_year, ExcelImportError, ExcelImporter, MONTH_SHEETS,
+};
+use rain_tracker_service::units::Inches;
 
 #[test]
 fn test_excel_importer_creation() {
@@ -60,7 +65,7 @@ fn test_parse_month_sheet_valid() {
     // Verify structure of a reading
     if let Some(reading) = readings.first() {
         assert!(!reading.station_id.is_empty());
-        assert!(reading.rainfall_inches >= 0.0);
+        assert!(reading.rainfall_inches.value() >= 0.0);
         // October 2023 dates should be in October (month 10)
         assert_eq!(reading.reading_date.month(), 10);
     }
@@ -123,7 +128,7 @@ fn test_historical_reading_structure() {
         assert_eq!(reading.footnote_marker, None);
 
         // Rainfall should be positive (we only store non-zero values)
-        assert!(reading.rainfall_inches > 0.0);
+        assert!(reading.rainfall_inches.value() > 0.0);
     }
 }
 
@@ -139,7 +144,7 @@ fn test_parse_multiple_gauges() {
     // Collect unique station IDs
     let mut station_ids: Vec<String> = readings
         .iter()
-        .map(|r| r.station_id.clone())
+        .map(|r| r.station_id.to_string())
         .collect::<std::collections::HashSet<_>>()
         .into_iter()
         .collect();
@@ -167,7 +172,7 @@ fn test_date_ordering() {
 
     for reading in readings {
         dates_by_station
-            .entry(reading.station_id.clone())
+            .entry(reading.station_id.to_string())
             .or_default()
             .push(reading.reading_date);
     }
@@ -213,9 +218,9 @@ fn test_clone_historical_reading() {
     use rain_tracker_service::importers::excel_importer::HistoricalReading;
 
     let reading = HistoricalReading {
-        station_id: "12345".to_string(),
+        station_id: "12345".parse().unwrap(),
         reading_date: NaiveDate::from_ymd_opt(2023, 1, 15).unwrap(),
-        rainfall_inches: 1.5,
+        rainfall_inches: Inches::new(1.5).unwrap(),
         footnote_marker: Some("1".to_string()),
     };
 
@@ -232,9 +237,9 @@ fn test_debug_historical_reading() {
     use rain_tracker_service::importers::excel_importer::HistoricalReading;
 
     let reading = HistoricalReading {
-        station_id: "12345".to_string(),
+        station_id: "12345".parse().unwrap(),
         reading_date: NaiveDate::from_ymd_opt(2023, 1, 15).unwrap(),
-        rainfall_inches: 1.5,
+        rainfall_inches: Inches::new(1.5).unwrap(),
         footnote_marker: None,
     };
 
@@ -301,7 +306,7 @@ fn test_only_nonzero_rainfall_stored() {
     // All stored readings should have positive rainfall
     for reading in readings {
         assert!(
-            reading.rainfall_inches > 0.0,
+            reading.rainfall_inches.value() > 0.0,
             "Only non-zero rainfall should be stored"
         );
     }
@@ -384,13 +389,13 @@ fn test_rainfall_values_are_reasonable() {
 
     for reading in readings {
         // Rainfall should be positive (we only store non-zero)
-        assert!(reading.rainfall_inches > 0.0);
+        assert!(reading.rainfall_inches.value() > 0.0);
 
         // Rainfall should be reasonable (< 20 inches in a day is extreme but possible)
         assert!(
-            reading.rainfall_inches < 50.0,
+            reading.rainfall_inches.value() < 50.0,
             "Rainfall value {} seems unreasonable for a single day",
-            reading.rainfall_inches
+            reading.rainfall_inches.value()
         );
     }
 }
@@ -415,3 +420,43 @@ fn test_parse_all_months_returns_chronological_data() {
         "Should have data from water year 2023 (Oct 2022 - Sep 2023)"
     );
 }
+
+#[test]
+fn test_discover_gauges_from_water_year_scans_every_month() {
+    let gauges = discover_gauges_from_water_year("sample-data-files/pcp_WY_2023.xlsx").unwrap();
+
+    assert!(!gauges.is_empty());
+    assert!(gauges.windows(2).all(|w| w[0].station_id < w[1].station_id));
+    assert!(gauges.iter().all(|g| !g.months.is_empty()));
+
+    // Every gauge with October readings is listed, with OCT first
+    let october: HashSet<String> = ExcelImporter::new("sample-data-files/pcp_WY_2023.xlsx")
+        .parse_month_sheet("OCT")
+        .unwrap()
+        .into_iter()
+        .map(|r| r.station_id.into_string())
+        .collect();
+    for station_id in &october {
+        let gauge = gauges
+            .iter()
+            .find(|g| &g.station_id == station_id)
+            .unwrap_or_else(|| panic!("gauge {station_id} not discovered"));
+        assert_eq!(gauge.months[0], "OCT");
+    }
+
+    // Months follow the water year order
+    for gauge in &gauges {
+        let positions: Vec<usize> = gauge
+            .months
+            .iter()
+            .map(|m| MONTH_SHEETS.iter().position(|s| s == m).unwrap())
+            .collect();
+        assert!(positions.windows(2).all(|w| w[0] < w[1]));
+    }
+}
+
+#[test]
+fn test_discover_gauges_missing_workbook() {
+    let result = discover_gauges_from_water_year("/nonexistent/path/to/file.xlsx");
+    assert!(matches!(result, Err(ExcelImportError::WorkbookOpen(_))));
+}
@@ -0,0 +1,168 @@
+// Integration tests for FoprAvailabilityService
+// Covers HEAD probing against a mock MCFCD site, change detection, and listing filters
+
+mod common;
+
+use mockito::Server;
+use rain_tracker_service::importers::downloader::McfcdDownloader;
+use rain_tracker_service::services::fopr_availability_service::FoprAvailabilityParams;
+use rain_tracker_service::services::FoprAvailabilityService;
+use serial_test::serial;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+
+async fn setup_test_db() -> PgPool {
+    let database_url = common::database_url().await;
+
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&database_url)
+        .await
+        .expect("Failed to connect to test database");
+
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("Failed to run migrations");
+
+    sqlx::query("DELETE FROM fopr_availability WHERE station_id LIKE 'FA%'")
+        .execute(&pool)
+        .await
+        .expect("Failed to clean up fopr_availability");
+
+    pool
+}
+
+fn station_ids(ids: &[&str]) -> Vec<String> {
+    ids.iter().map(|id| id.to_string()).collect()
+}
+
+#[tokio::test]
+#[serial]
+async fn test_probe_records_availability_and_changes() {
+    let pool = setup_test_db().await;
+    let mut server = Server::new_async().await;
+
+    let available = server
+        .mock("HEAD", "/FOPR/FA001_FOPR.xlsx")
+        .with_status(200)
+        .with_header("last-modified", "Mon, 06 Jan 2025 17:42:00 GMT")
+        .expect(2)
+        .create_async()
+        .await;
+    let missing = server
+        .mock("HEAD", "/FOPR/FA002_FOPR.xlsx")
+        .with_status(404)
+        .expect(2)
+        .create_async()
+        .await;
+    let failing = server
+        .mock("HEAD", "/FOPR/FA003_FOPR.xlsx")
+        .with_status(500)
+        .create_async()
+        .await;
+
+    let service = FoprAvailabilityService::new(pool.clone())
+        .with_downloader(McfcdDownloader::with_base_url(server.url() + "/"))
+        .with_rate(1000.0);
+
+    let mut seen = Vec::new();
+    let first = service
+        .probe(&station_ids(&["FA001", "FA002", "FA003"]), |outcome| {
+            seen.push(outcome.station_id.clone())
+        })
+        .await
+        .unwrap();
+    assert_eq!(seen, vec!["FA001", "FA002", "FA003"]);
+
+    assert_eq!(first[0].available, Some(true));
+    assert!(first[0].last_modified.is_some());
+    assert!(first[0].changed, "First probe counts as a change");
+    assert_eq!(first[1].available, Some(false));
+    assert_eq!(first[1].http_status, Some(404));
+    assert_eq!(first[2].available, None);
+    assert!(first[2].error.is_some());
+    assert!(!first[2].changed);
+
+    // Nothing changed on the site, so the second probe reports no changes
+    let second = service
+        .probe(&station_ids(&["FA001", "FA002"]), |_| {})
+        .await
+        .unwrap();
+    assert!(second.iter().all(|outcome| !outcome.changed));
+
+    available.assert_async().await;
+    missing.assert_async().await;
+    failing.assert_async().await;
+
+    // Failed probes aren't recorded
+    let recorded = service
+        .list(&FoprAvailabilityParams::default())
+        .await
+        .unwrap();
+    let recorded: Vec<_> = recorded
+        .iter()
+        .filter(|row| row.station_id.starts_with("FA"))
+        .map(|row| row.station_id.as_str())
+        .collect();
+    assert_eq!(recorded, vec!["FA001", "FA002"]);
+
+    let unavailable = service.unavailable_station_ids().await.unwrap();
+    assert!(unavailable.contains(&"FA002".to_string()));
+    assert!(!unavailable.contains(&"FA001".to_string()));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_republished_file_is_reported_as_changed() {
+    let pool = setup_test_db().await;
+    let mut server = Server::new_async().await;
+    let downloader = McfcdDownloader::with_base_url(server.url() + "/");
+    let service = FoprAvailabilityService::new(pool.clone())
+        .with_downloader(downloader)
+        .with_rate(1000.0);
+    let stations = station_ids(&["FA010"]);
+
+    let original = server
+        .mock("HEAD", "/FOPR/FA010_FOPR.xlsx")
+        .with_status(200)
+        .with_header("last-modified", "Mon, 06 Jan 2025 17:42:00 GMT")
+        .create_async()
+        .await;
+    service.probe(&stations, |_| {}).await.unwrap();
+    original.remove_async().await;
+
+    let before = service
+        .list(&FoprAvailabilityParams {
+            available: Some(true),
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|row| row.station_id == "FA010")
+        .expect("FA010 recorded as available");
+
+    let _republished = server
+        .mock("HEAD", "/FOPR/FA010_FOPR.xlsx")
+        .with_status(200)
+        .with_header("last-modified", "Tue, 04 Feb 2025 09:00:00 GMT")
+        .create_async()
+        .await;
+    let outcomes = service.probe(&stations, |_| {}).await.unwrap();
+    assert!(outcomes[0].changed);
+
+    let changed = service
+        .list(&FoprAvailabilityParams {
+            changed_since: Some(before.checked_at),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let row = changed
+        .iter()
+        .find(|row| row.station_id == "FA010")
+        .expect("FA010 changed since the first probe");
+    assert!(row.changed_at > before.changed_at);
+    assert!(row.last_modified > before.last_modified);
+}
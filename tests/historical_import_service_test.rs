@@ -0,0 +1,283 @@
+// Integration tests for HistoricalImportService
+// Covers station import (including unknown gauges), recalculation, verification, and export
+
+mod common;
+
+use std::collections::HashSet;
+
+use chrono::NaiveDate;
+use mockito::Server;
+use rain_tracker_service::importers::downloader::McfcdDownloader;
+use rain_tracker_service::importers::excel_importer::HistoricalReading;
+use rain_tracker_service::ingest_guard::{IngestLimits, QuarantinedReading};
+use rain_tracker_service::services::historical_import_service::HistoricalImportError;
+use rain_tracker_service::services::HistoricalImportService;
+use rain_tracker_service::units::Inches;
+use serial_test::serial;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+
+mod historical_import_fixtures {
+    use super::*;
+
+    pub async fn setup_test_db() -> PgPool {
+        let database_url = crate::common::database_url().await;
+
+        let pool = PgPoolOptions::new()
+            .max_connections(5)
+            .connect(&database_url)
+            .await
+            .expect("Failed to connect to test database");
+
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    pub async fn create_test_gauge(pool: &PgPool, station_id: &str) {
+        sqlx::query!(
+            r#"
+            INSERT INTO gauges (station_id, station_name, station_type, latitude, longitude, county, status)
+            VALUES ($1, $2, 'Rain', 33.5, -112.0, 'Test County', 'Active')
+            ON CONFLICT (station_id) DO NOTHING
+            "#,
+            station_id,
+            format!("Test Gauge {}", station_id)
+        )
+        .execute(pool)
+        .await
+        .ok();
+    }
+
+    pub async fn cleanup(pool: &PgPool, station_id: &str) {
+        sqlx::query!(
+            "DELETE FROM monthly_rainfall_summary WHERE station_id = $1",
+            station_id
+        )
+        .execute(pool)
+        .await
+        .ok();
+        sqlx::query!(
+            "DELETE FROM rain_readings WHERE station_id = $1",
+            station_id
+        )
+        .execute(pool)
+        .await
+        .ok();
+        sqlx::query!("DELETE FROM gauges WHERE station_id = $1", station_id)
+            .execute(pool)
+            .await
+            .ok();
+    }
+
+    pub fn readings(station_id: &str) -> Vec<HistoricalReading> {
+        vec![
+            HistoricalReading {
+                station_id: station_id.parse().unwrap(),
+                reading_date: NaiveDate::from_ymd_opt(2124, 10, 5).unwrap(),
+                rainfall_inches: Inches::new(0.25).unwrap(),
+                footnote_marker: None,
+            },
+            HistoricalReading {
+                station_id: station_id.parse().unwrap(),
+                reading_date: NaiveDate::from_ymd_opt(2124, 10, 6).unwrap(),
+                rainfall_inches: Inches::new(0.5).unwrap(),
+                footnote_marker: None,
+            },
+            HistoricalReading {
+                station_id: station_id.parse().unwrap(),
+                reading_date: NaiveDate::from_ymd_opt(2125, 2, 1).unwrap(),
+                rainfall_inches: Inches::new(1.0).unwrap(),
+                footnote_marker: None,
+            },
+        ]
+    }
+}
+
+use historical_import_fixtures::*;
+
+#[tokio::test]
+#[serial]
+async fn test_import_station_readings_skips_unknown_gauge() {
+    let pool = setup_test_db().await;
+    let station_id = "TEST_HIST_UNKNOWN";
+    cleanup(&pool, station_id).await;
+
+    let service = HistoricalImportService::new(pool.clone());
+    let result = service
+        .import_station_readings(station_id, "excel_WY_2125", &readings(station_id))
+        .await
+        .expect("Import should not fail for unknown gauge");
+
+    assert!(result.skipped);
+    assert_eq!(result.inserted, 0);
+    assert!(result.affected_months.is_empty());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_import_recalc_verify_and_export_water_year() {
+    let pool = setup_test_db().await;
+    let station_id = "TEST_HIST_001";
+    cleanup(&pool, station_id).await;
+    create_test_gauge(&pool, station_id).await;
+
+    let service = HistoricalImportService::new(pool.clone());
+
+    // Each month is committed with its summary
+    let result = service
+        .import_station_readings(station_id, "excel_WY_2125", &readings(station_id))
+        .await
+        .expect("Import failed");
+    assert!(!result.skipped);
+    assert_eq!(result.inserted, 3);
+    let expected_months: HashSet<(String, i32, u32)> = [
+        (station_id.to_string(), 2124, 10),
+        (station_id.to_string(), 2125, 2),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(result.affected_months, expected_months);
+    assert_eq!(result.months_resumed, 0);
+
+    let discrepancies = service
+        .verify_water_year(Some(station_id), 2125)
+        .await
+        .expect("Verify failed");
+    assert!(discrepancies.is_empty(), "Unexpected: {discrepancies:?}");
+
+    // A rerun skips the months already committed
+    let rerun = service
+        .import_station_readings(station_id, "excel_WY_2125", &readings(station_id))
+        .await
+        .expect("Import failed");
+    assert_eq!(rerun.inserted, 0);
+    assert_eq!(rerun.duplicates, 0);
+    assert!(rerun.affected_months.is_empty());
+    assert_eq!(rerun.months_resumed, 2);
+
+    // Recalculating the same months leaves verification passing
+    let months = service
+        .recalculate_months(&result.affected_months)
+        .await
+        .expect("Recalc failed");
+    assert_eq!(months, 2);
+
+    let discrepancies = service
+        .verify_water_year(Some(station_id), 2125)
+        .await
+        .expect("Verify failed");
+    assert!(discrepancies.is_empty(), "Unexpected: {discrepancies:?}");
+
+    // Export returns readings oldest first
+    let exported = service
+        .export_water_year(station_id, 2125)
+        .await
+        .expect("Export failed");
+    assert_eq!(exported.len(), 3);
+    assert!(exported[0].reading_datetime < exported[2].reading_datetime);
+
+    cleanup(&pool, station_id).await;
+}
+
+#[tokio::test]
+async fn test_discover_water_year_gauges() {
+    let pool = setup_test_db().await;
+    let mut server = Server::new_async().await;
+    let _file = server
+        .mock("GET", "/pcp_WY_2023.xlsx")
+        .with_status(200)
+        .with_body(std::fs::read("sample-data-files/pcp_WY_2023.xlsx").unwrap())
+        .create_async()
+        .await;
+    let _missing = server
+        .mock("GET", "/pcp_WY_1990.xlsx")
+        .with_status(404)
+        .create_async()
+        .await;
+    let service = HistoricalImportService::new(pool.clone())
+        .with_downloader(McfcdDownloader::with_base_url(format!("{}/", server.url())));
+
+    let discovered = service
+        .discover_water_year_gauges(2023)
+        .await
+        .expect("Discovery failed");
+    assert_eq!(discovered.water_year, 2023);
+    assert_eq!(discovered.gauge_count, discovered.gauges.len());
+    assert!(discovered.gauge_count > 0);
+
+    let gauge = &discovered.gauges[0];
+    let exists = sqlx::query_scalar!(
+        r#"SELECT EXISTS(SELECT 1 FROM gauges WHERE station_id = $1) AS "exists!""#,
+        gauge.station_id
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(gauge.registered, exists);
+
+    let err = service.discover_water_year_gauges(1990).await.unwrap_err();
+    assert!(matches!(err, HistoricalImportError::NotPublished(1990)));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_quarantine_readings_records_each_reading_once() {
+    let pool = setup_test_db().await;
+    let station_id = "TEST_HIST_QUARANTINE";
+    sqlx::query("DELETE FROM quarantined_readings WHERE station_id = $1")
+        .bind(station_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let limits = IngestLimits::default();
+    let date = NaiveDate::from_ymd_opt(2124, 12, 3).unwrap();
+    let quarantined: Vec<QuarantinedReading> = [-0.25, 45.0]
+        .into_iter()
+        .enumerate()
+        .map(|(i, value)| {
+            limits
+                .check_daily(station_id, date + chrono::Days::new(i as u64), value)
+                .unwrap_err()
+        })
+        .collect();
+
+    let service = HistoricalImportService::new(pool.clone());
+    let inserted = service
+        .quarantine_readings("excel_WY_2125", &quarantined)
+        .await
+        .unwrap();
+    assert_eq!(inserted, 2);
+    // Re-importing the same file doesn't duplicate them
+    let inserted = service
+        .quarantine_readings("excel_WY_2125", &quarantined)
+        .await
+        .unwrap();
+    assert_eq!(inserted, 0);
+
+    let rows: Vec<(String, f64, Option<f64>)> = sqlx::query_as(
+        "SELECT reason, incremental_inches, cumulative_inches FROM quarantined_readings
+         WHERE station_id = $1 ORDER BY reading_datetime",
+    )
+    .bind(station_id)
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        rows,
+        [
+            ("negative_rainfall".to_string(), -0.25, None),
+            ("exceeds_maximum".to_string(), 45.0, None),
+        ]
+    );
+
+    sqlx::query("DELETE FROM quarantined_readings WHERE station_id = $1")
+        .bind(station_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
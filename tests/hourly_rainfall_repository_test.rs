@@ -0,0 +1,144 @@
+// Tests for HourlyRainfallRepository: rebuilding hours from sub-daily readings
+
+mod common;
+
+use chrono::{DateTime, TimeZone, Utc};
+use rain_tracker_service::db::HourlyRainfallRepository;
+use sqlx::PgPool;
+
+const STATION_ID: &str = "HOURLY_TEST";
+
+fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 8, day, hour, minute, 0).unwrap()
+}
+
+async fn insert_reading(pool: &PgPool, datetime: DateTime<Utc>, inches: f64, source: &str) {
+    sqlx::query!(
+        r#"
+        INSERT INTO rain_readings
+            (station_id, reading_datetime, cumulative_inches, incremental_inches, data_source)
+        VALUES ($1, $2, 0.0, $3, $4)
+        "#,
+        STATION_ID,
+        datetime,
+        inches,
+        source
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+async fn setup() -> PgPool {
+    let pool = common::isolated_db().await;
+    sqlx::query!(
+        r#"
+        INSERT INTO gauges (station_id, station_name, station_type, latitude, longitude, county, status)
+        VALUES ($1, 'Hourly Test', 'Rain', 33.5, -112.0, 'Test County', 'Active')
+        "#,
+        STATION_ID
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    pool
+}
+
+#[tokio::test]
+async fn test_recalculate_hours_buckets_sub_daily_readings() {
+    let pool = setup().await;
+    let repo = HourlyRainfallRepository::new(pool.clone());
+
+    insert_reading(&pool, at(1, 14, 5), 0.12, "live_scrape").await;
+    insert_reading(&pool, at(1, 14, 20), 0.36, "live_scrape").await;
+    insert_reading(&pool, at(1, 15, 0), 0.04, "live_scrape").await;
+    // A daily import total would read as one very wet hour
+    insert_reading(&pool, at(2, 0, 0), 1.5, "excel_WY_2024").await;
+
+    assert_eq!(repo.recalculate_hours(None, None, None).await.unwrap(), 2);
+
+    let hours = repo
+        .get_summaries_by_date_range(STATION_ID, at(1, 0, 0), at(3, 0, 0))
+        .await
+        .unwrap();
+    assert_eq!(hours.len(), 2);
+    assert_eq!(hours[0].hour_start, at(1, 14, 0));
+    assert_eq!(hours[0].total_rainfall_inches, 0.48);
+    assert_eq!(hours[0].max_incremental_inches, 0.36);
+    assert_eq!(hours[0].reading_count, 2);
+    assert_eq!(hours[1].hour_start, at(1, 15, 0));
+}
+
+#[tokio::test]
+async fn test_recalculate_hours_in_range_drops_emptied_hours() {
+    let pool = setup().await;
+    let repo = HourlyRainfallRepository::new(pool.clone());
+
+    insert_reading(&pool, at(1, 14, 5), 0.12, "live_scrape").await;
+    insert_reading(&pool, at(1, 16, 5), 0.2, "live_scrape").await;
+    repo.recalculate_hours(Some(STATION_ID), None, None)
+        .await
+        .unwrap();
+
+    sqlx::query!(
+        "DELETE FROM rain_readings WHERE station_id = $1 AND reading_datetime < $2",
+        STATION_ID,
+        at(1, 15, 0)
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    insert_reading(&pool, at(1, 16, 35), 0.1, "live_scrape").await;
+
+    let rebuilt = repo
+        .recalculate_hours(Some(STATION_ID), Some(at(1, 14, 0)), Some(at(1, 17, 0)))
+        .await
+        .unwrap();
+    assert_eq!(rebuilt, 1);
+
+    let hours = repo
+        .get_summaries_by_date_range(STATION_ID, at(1, 0, 0), at(2, 0, 0))
+        .await
+        .unwrap();
+    assert_eq!(hours.len(), 1);
+    assert_eq!(hours[0].hour_start, at(1, 16, 0));
+    assert_eq!(hours[0].total_rainfall_inches, 0.3);
+    assert_eq!(hours[0].reading_count, 2);
+}
+
+#[tokio::test]
+async fn test_daily_intensity_windows_cross_midnight() {
+    let pool = setup().await;
+    let repo = HourlyRainfallRepository::new(pool.clone());
+
+    insert_reading(&pool, at(1, 23, 30), 0.2, "live_scrape").await;
+    insert_reading(&pool, at(1, 23, 50), 0.3, "live_scrape").await;
+    insert_reading(&pool, at(2, 0, 5), 0.25, "live_scrape").await;
+    insert_reading(&pool, at(2, 0, 40), 0.1, "live_scrape").await;
+    insert_reading(&pool, at(3, 0, 0), 1.5, "excel_WY_2024").await;
+    repo.recalculate_hours(None, None, None).await.unwrap();
+
+    let first = at(1, 0, 0).date_naive();
+    let last = at(3, 0, 0).date_naive();
+    let days = repo
+        .get_daily_intensity(STATION_ID, first, last)
+        .await
+        .unwrap();
+    assert_eq!(days.len(), 2);
+    assert_eq!(days[0].date, first);
+    assert_eq!(days[0].max_15min_intensity_in_per_hr, 1.2);
+    assert_eq!(days[0].max_1h_intensity_in_per_hr, 0.5);
+    // The 00:05 hour reaches back to 23:05; its 15 minutes leave out 23:50
+    assert_eq!(days[1].max_15min_intensity_in_per_hr, 1.0);
+    assert_eq!(days[1].max_1h_intensity_in_per_hr, 0.75);
+
+    // Rebuilding only the first hour of day 2 still sees the readings before midnight
+    repo.recalculate_hours(Some(STATION_ID), Some(at(2, 0, 0)), Some(at(2, 1, 0)))
+        .await
+        .unwrap();
+    let rebuilt = repo
+        .get_daily_intensity(STATION_ID, first, last)
+        .await
+        .unwrap();
+    assert_eq!(rebuilt, days);
+}
@@ -0,0 +1,252 @@
+// Tests for ImportChunkRepository: atomic month commits and resuming imports
+
+mod common;
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use rain_tracker_service::db::{ConflictPolicy, ImportChunkRepository, MonthlyRainfallRepository};
+use rain_tracker_service::importers::excel_importer::HistoricalReading;
+use rain_tracker_service::units::Inches;
+use serial_test::serial;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+
+mod import_chunk_fixtures {
+    use super::*;
+
+    pub async fn setup_test_db() -> PgPool {
+        let database_url = crate::common::database_url().await;
+
+        let pool = PgPoolOptions::new()
+            .max_connections(5)
+            .connect(&database_url)
+            .await
+            .expect("Failed to connect to test database");
+
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    pub async fn create_test_gauge(pool: &PgPool, station_id: &str) {
+        sqlx::query!(
+            r#"
+            INSERT INTO gauges (station_id, station_name, station_type, latitude, longitude, county, status)
+            VALUES ($1, $2, 'Rain', 33.5, -112.0, 'Test County', 'Active')
+            ON CONFLICT (station_id) DO NOTHING
+            "#,
+            station_id,
+            format!("Test Gauge {}", station_id)
+        )
+        .execute(pool)
+        .await
+        .ok();
+    }
+
+    /// Deleting the gauge removes its import_chunks rows too
+    pub async fn cleanup(pool: &PgPool, station_id: &str) {
+        sqlx::query!(
+            "DELETE FROM monthly_rainfall_summary WHERE station_id = $1",
+            station_id
+        )
+        .execute(pool)
+        .await
+        .ok();
+        sqlx::query!(
+            "DELETE FROM rain_readings WHERE station_id = $1",
+            station_id
+        )
+        .execute(pool)
+        .await
+        .ok();
+        sqlx::query!("DELETE FROM gauges WHERE station_id = $1", station_id)
+            .execute(pool)
+            .await
+            .ok();
+    }
+
+    pub fn daily(station_id: &str, month: u32, day: u32, inches: f64) -> HistoricalReading {
+        HistoricalReading {
+            station_id: station_id.parse().unwrap(),
+            reading_date: NaiveDate::from_ymd_opt(2124, month, day).unwrap(),
+            rainfall_inches: Inches::new(inches).unwrap(),
+            footnote_marker: None,
+        }
+    }
+}
+
+use import_chunk_fixtures::*;
+
+#[tokio::test]
+#[serial]
+async fn test_import_by_month_commits_summaries_and_resumes() {
+    let pool = setup_test_db().await;
+    let station_id = "CHUNK_TEST_001";
+    cleanup(&pool, station_id).await;
+    create_test_gauge(&pool, station_id).await;
+
+    let repo = ImportChunkRepository::new(pool.clone()).with_derived_cumulative();
+    let monthly_repo = MonthlyRainfallRepository::new(pool.clone());
+    let source = "fopr_import_chunk_test";
+    let (start, end) = (
+        Utc.with_ymd_and_hms(2124, 1, 1, 0, 0, 0).unwrap(),
+        Utc.with_ymd_and_hms(2124, 3, 1, 0, 0, 0).unwrap(),
+    );
+
+    let imported = repo
+        .import_by_month(
+            station_id,
+            source,
+            &[
+                daily(station_id, 2, 3, 0.5),
+                daily(station_id, 1, 10, 0.25),
+                daily(station_id, 1, 20, 0.25),
+            ],
+        )
+        .await
+        .unwrap();
+    assert_eq!(imported.inserted, 3);
+    assert_eq!(imported.months_committed, vec![(2124, 1), (2124, 2)]);
+
+    let summaries = monthly_repo
+        .get_summaries_by_date_range(station_id, start, end)
+        .await
+        .unwrap();
+    assert_eq!(summaries.len(), 2);
+    assert_eq!(summaries[0].total_rainfall_inches, 0.5);
+    assert_eq!(summaries[1].max_cumulative_inches, Some(1.0));
+
+    // Unchanged months are skipped; a month with a new day is committed again, and the
+    // later month's cumulative totals and summary move with it
+    let rerun = repo
+        .import_by_month(
+            station_id,
+            source,
+            &[
+                daily(station_id, 2, 3, 0.5),
+                daily(station_id, 1, 10, 0.25),
+                daily(station_id, 1, 20, 0.25),
+                daily(station_id, 1, 5, 0.5),
+            ],
+        )
+        .await
+        .unwrap();
+    assert_eq!(rerun.inserted, 1);
+    assert_eq!(rerun.duplicates, 2);
+    assert_eq!(rerun.months_committed, vec![(2124, 1)]);
+    assert_eq!(rerun.months_resumed, 1);
+
+    let summaries = monthly_repo
+        .get_summaries_by_date_range(station_id, start, end)
+        .await
+        .unwrap();
+    assert_eq!(summaries[0].total_rainfall_inches, 1.0);
+    assert_eq!(summaries[1].max_cumulative_inches, Some(1.5));
+
+    let committed = repo.committed_months(station_id, source).await.unwrap();
+    assert_eq!(committed.get(&(2124, 1)), Some(&3));
+    assert_eq!(committed.get(&(2124, 2)), Some(&1));
+
+    cleanup(&pool, station_id).await;
+}
+
+#[tokio::test]
+#[serial]
+async fn test_commit_month_rolls_back_on_failure() {
+    let pool = setup_test_db().await;
+    let station_id = "CHUNK_TEST_NOGAUGE";
+    cleanup(&pool, station_id).await;
+
+    // Without a gauge row the readings' foreign key fails, so nothing is committed
+    let repo = ImportChunkRepository::new(pool.clone());
+    let result = repo
+        .commit_month(
+            station_id,
+            "excel_WY_2124",
+            (2124, 1),
+            &[daily(station_id, 1, 10, 0.25)],
+        )
+        .await;
+    assert!(result.is_err());
+    assert!(repo
+        .committed_months(station_id, "excel_WY_2124")
+        .await
+        .unwrap()
+        .is_empty());
+
+    cleanup(&pool, station_id).await;
+}
+
+#[tokio::test]
+#[serial]
+async fn test_import_by_month_update_overwrites_and_records_revision() {
+    let pool = setup_test_db().await;
+    let station_id = "CHUNK_TEST_002";
+    cleanup(&pool, station_id).await;
+    create_test_gauge(&pool, station_id).await;
+
+    let source = "fopr_import_chunk_test";
+    let original = [daily(station_id, 1, 10, 0.25), daily(station_id, 2, 3, 0.5)];
+    let corrected = [daily(station_id, 1, 10, 0.75), daily(station_id, 2, 3, 0.5)];
+
+    let skip = ImportChunkRepository::new(pool.clone()).with_derived_cumulative();
+    skip.import_by_month(station_id, source, &original)
+        .await
+        .unwrap();
+
+    // The default policy keeps the stored value
+    let skipped = skip
+        .import_by_month(station_id, source, &corrected)
+        .await
+        .unwrap();
+    assert_eq!(skipped.updated, 0);
+
+    let update = skip.with_conflict_policy(ConflictPolicy::Update);
+    let updated = update
+        .import_by_month(station_id, source, &corrected)
+        .await
+        .unwrap();
+    assert_eq!(updated.inserted, 0);
+    assert_eq!(updated.updated, 1);
+    assert_eq!(updated.duplicates, 1);
+    assert_eq!(updated.months_resumed, 0);
+
+    let revision = sqlx::query!(
+        r#"
+        SELECT previous_incremental_inches, new_incremental_inches, previous_data_source
+        FROM reading_revisions
+        WHERE station_id = $1
+        "#,
+        station_id
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(revision.previous_incremental_inches, 0.25);
+    assert_eq!(revision.new_incremental_inches, 0.75);
+    assert_eq!(revision.previous_data_source, source);
+
+    // The corrected amount flows into the month's summary and later cumulative totals
+    let summaries = MonthlyRainfallRepository::new(pool.clone())
+        .get_summaries_by_date_range(
+            station_id,
+            Utc.with_ymd_and_hms(2124, 1, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2124, 3, 1, 0, 0, 0).unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(summaries[0].total_rainfall_inches, 0.75);
+    assert_eq!(summaries[1].max_cumulative_inches, Some(1.25));
+
+    // Re-running the same corrected file changes nothing
+    let rerun = update
+        .import_by_month(station_id, source, &corrected)
+        .await
+        .unwrap();
+    assert_eq!(rerun.updated, 0);
+    assert_eq!(rerun.duplicates, 2);
+
+    cleanup(&pool, station_id).await;
+}
@@ -4,11 +4,19 @@
 mod common;
 
 use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Utc};
-use rain_tracker_service::db::{GaugeRepository, MonthlyRainfallRepository, ReadingRepository};
+use rain_tracker_service::clock::FixedClock;
+use rain_tracker_service::db::{
+    AnnotationRepository, GaugeRepository, HourlyRainfallRepository, MonthFill,
+    MonthlyRainfallRepository, ReadingRepository,
+};
 use rain_tracker_service::fetcher::RainReading;
 use rain_tracker_service::fopr::MetaStatsData;
-use rain_tracker_service::services::ReadingService;
+use rain_tracker_service::services::{
+    ReadingQueryError, ReadingQueryLimits, ReadingService, YearSummaryParams,
+};
+use rain_tracker_service::units::Inches;
 use sqlx::{Postgres, Transaction};
+use std::sync::Arc;
 
 /// Helper to insert a test gauge using a transaction
 async fn insert_test_gauge(
@@ -20,7 +28,7 @@ async fn insert_test_gauge(
     let gauge_repo = GaugeRepository::new(pool.clone());
 
     let metadata = MetaStatsData {
-        station_id: station_id.to_string(),
+        station_id: station_id.parse().unwrap(),
         station_name: station_name.to_string(),
         previous_station_ids: vec![],
         station_type: "Rain".to_string(),
@@ -60,8 +68,8 @@ async fn insert_reading_tx(
         ON CONFLICT (reading_datetime, station_id) DO NOTHING
         "#,
         reading.reading_datetime,
-        reading.cumulative_inches,
-        reading.incremental_inches,
+        reading.cumulative_inches.value(),
+        reading.incremental_inches.value(),
         station_id
     )
     .execute(&mut **tx)
@@ -83,13 +91,13 @@ async fn test_insert_and_retrieve_readings() {
     let readings = vec![
         RainReading {
             reading_datetime: Utc::now(),
-            cumulative_inches: 1.85,
-            incremental_inches: 0.04,
+            cumulative_inches: Inches::new(1.85).unwrap(),
+            incremental_inches: Inches::new(0.04).unwrap(),
         },
         RainReading {
             reading_datetime: Utc::now(),
-            cumulative_inches: 1.81,
-            incremental_inches: 0.04,
+            cumulative_inches: Inches::new(1.81).unwrap(),
+            incremental_inches: Inches::new(0.04).unwrap(),
         },
     ];
 
@@ -105,7 +113,13 @@ async fn test_insert_and_retrieve_readings() {
     let pool = common::test_pool().await;
     let reading_repo = ReadingRepository::new(pool.clone());
     let monthly_rainfall_repo = MonthlyRainfallRepository::new(pool.clone());
-    let reading_service = ReadingService::new(reading_repo.clone(), monthly_rainfall_repo);
+    let reading_service = ReadingService::new(
+        reading_repo.clone(),
+        monthly_rainfall_repo,
+        HourlyRainfallRepository::new(pool.clone()),
+        AnnotationRepository::new(pool.clone()),
+        GaugeRepository::new(pool.clone()),
+    );
 
     // Retrieve latest reading
     let latest = reading_service
@@ -141,16 +155,69 @@ async fn test_water_year_queries() {
     let pool = common::test_pool().await;
     let reading_repo = ReadingRepository::new(pool.clone());
     let monthly_rainfall_repo = MonthlyRainfallRepository::new(pool.clone());
-    let reading_service = ReadingService::new(reading_repo, monthly_rainfall_repo);
+    let reading_service = ReadingService::new(
+        reading_repo,
+        monthly_rainfall_repo,
+        HourlyRainfallRepository::new(pool.clone()),
+        AnnotationRepository::new(pool.clone()),
+        GaugeRepository::new(pool.clone()),
+    );
 
     // Query for current rain year
     let current_water_year = ReadingService::get_water_year(Utc::now());
-    let _summary = reading_service
-        .get_water_year_summary(test_station_id, current_water_year)
+    let summary = reading_service
+        .get_water_year_summary(
+            test_station_id,
+            current_water_year,
+            &YearSummaryParams::default(),
+        )
         .await
         .unwrap();
 
-    // Test passes if query completes without error
+    // The current water year is still in progress, and has no readings to date
+    assert!(summary.is_partial);
+    assert_eq!(summary.as_of, None);
+}
+
+#[tokio::test]
+async fn test_water_year_rollover_with_fixed_clock() {
+    let pool = common::test_pool().await;
+    let service_at = |now: DateTime<Utc>| {
+        ReadingService::new(
+            ReadingRepository::new(pool.clone()),
+            MonthlyRainfallRepository::new(pool.clone()),
+            HourlyRainfallRepository::new(pool.clone()),
+            AnnotationRepository::new(pool.clone()),
+            GaugeRepository::new(pool.clone()),
+        )
+        .with_clock(Arc::new(FixedClock(now)))
+    };
+
+    let last_second = service_at(Utc.with_ymd_and_hms(2024, 9, 30, 23, 59, 59).unwrap());
+    let first_second = service_at(Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap());
+    assert_eq!(last_second.current_water_year(), 2024);
+    assert_eq!(first_second.current_water_year(), 2025);
+
+    // WY 2024 is in progress through Sep 30 and final from Oct 1
+    let params = YearSummaryParams {
+        fill: Some(MonthFill::Zero),
+    };
+    let summary = last_second
+        .get_water_year_summary("TEST_WY_ROLLOVER_001", 2024, &params)
+        .await
+        .unwrap();
+    assert!(summary.is_partial);
+    let september = summary.monthly_summaries.last().unwrap();
+    assert_eq!(september.month, 9);
+    assert!(september.is_partial);
+    assert!(!summary.monthly_summaries[0].is_partial);
+
+    let summary = first_second
+        .get_water_year_summary("TEST_WY_ROLLOVER_001", 2024, &params)
+        .await
+        .unwrap();
+    assert!(!summary.is_partial);
+    assert!(summary.monthly_summaries.iter().all(|m| !m.is_partial));
 }
 
 #[tokio::test]
@@ -165,7 +232,7 @@ async fn test_water_year_total_rainfall_calculation() {
 
         let gauge_repo = GaugeRepository::new(pool.clone());
         let metadata = MetaStatsData {
-            station_id: test_station_id.to_string(),
+            station_id: test_station_id.parse().unwrap(),
             station_name: "Test Water Year Calculation Gauge".to_string(),
             previous_station_ids: vec![],
             station_type: "Rain".to_string(),
@@ -194,18 +261,18 @@ async fn test_water_year_total_rainfall_calculation() {
         let readings = vec![
             RainReading {
                 reading_datetime: Utc.with_ymd_and_hms(2023, 10, 15, 12, 0, 0).unwrap(),
-                cumulative_inches: 0.5,
-                incremental_inches: 0.5,
+                cumulative_inches: Inches::new(0.5).unwrap(),
+                incremental_inches: Inches::new(0.5).unwrap(),
             },
             RainReading {
                 reading_datetime: Utc.with_ymd_and_hms(2024, 3, 15, 12, 0, 0).unwrap(),
-                cumulative_inches: 2.3,
-                incremental_inches: 1.8,
+                cumulative_inches: Inches::new(2.3).unwrap(),
+                incremental_inches: Inches::new(1.8).unwrap(),
             },
             RainReading {
                 reading_datetime: Utc.with_ymd_and_hms(2024, 9, 15, 12, 0, 0).unwrap(),
-                cumulative_inches: 5.75,
-                incremental_inches: 3.45,
+                cumulative_inches: Inches::new(5.75).unwrap(),
+                incremental_inches: Inches::new(3.45).unwrap(),
             },
         ];
 
@@ -229,11 +296,17 @@ async fn test_water_year_total_rainfall_calculation() {
             .unwrap();
     }
 
-    let reading_service = ReadingService::new(reading_repo, monthly_rainfall_repo.clone());
+    let reading_service = ReadingService::new(
+        reading_repo.clone(),
+        monthly_rainfall_repo.clone(),
+        HourlyRainfallRepository::new(pool.clone()),
+        AnnotationRepository::new(pool.clone()),
+        GaugeRepository::new(pool.clone()),
+    );
 
     // Get water year summary for 2024
     let summary = reading_service
-        .get_water_year_summary(test_station_id, 2024)
+        .get_water_year_summary(test_station_id, 2024, &YearSummaryParams::default())
         .await
         .unwrap();
 
@@ -243,6 +316,36 @@ async fn test_water_year_total_rainfall_calculation() {
         "Total rainfall should equal sum of monthly incremental values for the water year"
     );
     assert_eq!(summary.total_readings, 3);
+    // A past water year is final; its totals run through its last reading
+    assert!(!summary.is_partial);
+    assert_eq!(
+        summary.as_of,
+        Some(Utc.with_ymd_and_hms(2024, 9, 15, 12, 0, 0).unwrap())
+    );
+    assert!(summary.monthly_summaries.iter().all(|m| !m.is_partial));
+
+    // The row cap is checked against the monthly summary counts before loading readings
+    let capped_service = ReadingService::new(
+        reading_repo,
+        monthly_rainfall_repo.clone(),
+        HourlyRainfallRepository::new(pool.clone()),
+        AnnotationRepository::new(pool.clone()),
+        GaugeRepository::new(pool.clone()),
+    )
+    .with_query_limits(ReadingQueryLimits {
+        max_rows: 2,
+        ..ReadingQueryLimits::default()
+    });
+    let result = capped_service
+        .get_water_year_summary(test_station_id, 2024, &YearSummaryParams::default())
+        .await;
+    assert!(matches!(
+        result,
+        Err(ReadingQueryError::TooManyRows {
+            rows: 3,
+            max_rows: 2
+        })
+    ));
 
     // Cleanup
     sqlx::query!(
@@ -276,33 +379,33 @@ async fn test_calendar_year_total_rainfall_calculation() {
         let readings = vec![
             RainReading {
                 reading_datetime: Utc.with_ymd_and_hms(2024, 12, 31, 12, 0, 0).unwrap(),
-                cumulative_inches: 0.5,
-                incremental_inches: 0.1,
+                cumulative_inches: Inches::new(0.5).unwrap(),
+                incremental_inches: Inches::new(0.1).unwrap(),
             },
             RainReading {
                 reading_datetime: Utc.with_ymd_and_hms(2025, 1, 31, 12, 0, 0).unwrap(),
-                cumulative_inches: 1.0,
-                incremental_inches: 0.5,
+                cumulative_inches: Inches::new(1.0).unwrap(),
+                incremental_inches: Inches::new(0.5).unwrap(),
             },
             RainReading {
                 reading_datetime: Utc.with_ymd_and_hms(2025, 3, 31, 12, 0, 0).unwrap(),
-                cumulative_inches: 2.5,
-                incremental_inches: 1.5,
+                cumulative_inches: Inches::new(2.5).unwrap(),
+                incremental_inches: Inches::new(1.5).unwrap(),
             },
             RainReading {
                 reading_datetime: Utc.with_ymd_and_hms(2025, 9, 30, 12, 0, 0).unwrap(),
-                cumulative_inches: 5.0,
-                incremental_inches: 2.5,
+                cumulative_inches: Inches::new(5.0).unwrap(),
+                incremental_inches: Inches::new(2.5).unwrap(),
             },
             RainReading {
                 reading_datetime: Utc.with_ymd_and_hms(2025, 10, 31, 12, 0, 0).unwrap(),
-                cumulative_inches: 0.3,
-                incremental_inches: 0.3,
+                cumulative_inches: Inches::new(0.3).unwrap(),
+                incremental_inches: Inches::new(0.3).unwrap(),
             },
             RainReading {
                 reading_datetime: Utc.with_ymd_and_hms(2025, 12, 31, 12, 0, 0).unwrap(),
-                cumulative_inches: 0.8,
-                incremental_inches: 0.5,
+                cumulative_inches: Inches::new(0.8).unwrap(),
+                incremental_inches: Inches::new(0.5).unwrap(),
             },
         ];
 
@@ -333,11 +436,17 @@ async fn test_calendar_year_total_rainfall_calculation() {
             .unwrap();
     }
 
-    let reading_service = ReadingService::new(reading_repo, monthly_rainfall_repo.clone());
+    let reading_service = ReadingService::new(
+        reading_repo,
+        monthly_rainfall_repo.clone(),
+        HourlyRainfallRepository::new(pool.clone()),
+        AnnotationRepository::new(pool.clone()),
+        GaugeRepository::new(pool.clone()),
+    );
 
     // Get calendar year summary for 2025
     let summary = reading_service
-        .get_calendar_year_summary(test_station_id, 2025)
+        .get_calendar_year_summary(test_station_id, 2025, &YearSummaryParams::default())
         .await
         .unwrap();
 
@@ -347,6 +456,29 @@ async fn test_calendar_year_total_rainfall_calculation() {
         "Calendar year total should sum monthly rainfall for all months"
     );
 
+    // Only months with summary rows are listed unless asked to fill
+    let months: Vec<u32> = summary.monthly_summaries.iter().map(|m| m.month).collect();
+    assert_eq!(months, vec![1, 3, 9, 10, 12]);
+
+    let filled = reading_service
+        .get_calendar_year_summary(
+            test_station_id,
+            2025,
+            &YearSummaryParams {
+                fill: Some(MonthFill::Null),
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(filled.monthly_summaries.len(), 12);
+    let february = &filled.monthly_summaries[1];
+    assert_eq!(february.readings_count, None);
+    assert_eq!(february.monthly_rainfall_inches, None);
+    assert_eq!(february.cumulative_ytd_inches, 0.5);
+    // The test gauge's FOPR record reports no missing months, so a dry month is complete
+    assert!(february.is_complete);
+    assert!(filled.monthly_summaries[0].is_complete);
+
     // Cleanup
     sqlx::query!(
         "DELETE FROM monthly_rainfall_summary WHERE station_id = $1",
@@ -380,11 +512,17 @@ async fn test_calendar_year_queries() {
     // Test: Query with service layer
     let reading_repo = ReadingRepository::new(pool.clone());
     let monthly_rainfall_repo = MonthlyRainfallRepository::new(pool.clone());
-    let reading_service = ReadingService::new(reading_repo, monthly_rainfall_repo);
+    let reading_service = ReadingService::new(
+        reading_repo,
+        monthly_rainfall_repo,
+        HourlyRainfallRepository::new(pool.clone()),
+        AnnotationRepository::new(pool.clone()),
+        GaugeRepository::new(pool.clone()),
+    );
 
     let current_year = Utc::now().year();
     let _summary = reading_service
-        .get_calendar_year_summary(test_station_id, current_year)
+        .get_calendar_year_summary(test_station_id, current_year, &YearSummaryParams::default())
         .await
         .unwrap();
 
@@ -0,0 +1,108 @@
+// Webhook delivery of FOPR job notifications against a mock endpoint
+
+use chrono::Utc;
+use mockito::{Matcher, Server};
+use rain_tracker_service::db::fopr_import_job_repository::{FoprImportJob, ImportStats, JobStatus};
+use rain_tracker_service::job_notify::{JobNotification, JobNotifier, JobNotifyConfig};
+
+fn job() -> FoprImportJob {
+    FoprImportJob {
+        id: 7,
+        station_id: "59700".to_string(),
+        status: JobStatus::InProgress,
+        priority: 10,
+        created_at: Utc::now(),
+        started_at: Some(Utc::now()),
+        completed_at: None,
+        error_message: None,
+        error_history: serde_json::json!([]),
+        retry_count: 0,
+        max_retries: 3,
+        next_retry_at: None,
+        source: "gauge_discovery".to_string(),
+        gauge_summary: None,
+        import_stats: None,
+    }
+}
+
+fn stats() -> ImportStats {
+    ImportStats {
+        readings_imported: 1200,
+        readings_updated: 0,
+        readings_quarantined: 0,
+        start_date: Some("2019-10-01".to_string()),
+        end_date: Some("2024-09-30".to_string()),
+        duration_secs: 3.5,
+    }
+}
+
+fn notifier(url: String, on_completed: bool) -> JobNotifier {
+    JobNotifier::from_config(&JobNotifyConfig {
+        webhook_url: Some(url),
+        on_completed,
+        on_failed: true,
+        ..JobNotifyConfig::default()
+    })
+    .unwrap()
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_webhook_receives_completed_job_with_stats() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/hook")
+        .match_header("content-type", "application/json")
+        .match_body(Matcher::PartialJson(serde_json::json!({
+            "event": "completed",
+            "job_id": 7,
+            "station_id": "59700",
+            "stats": {"readings_imported": 1200, "duration_secs": 3.5}
+        })))
+        .with_status(204)
+        .create_async()
+        .await;
+
+    notifier(format!("{}/hook", server.url()), true)
+        .notify(&JobNotification::completed(&job(), &stats()))
+        .await;
+
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_webhook_skips_disabled_events_and_survives_errors() {
+    let mut server = Server::new_async().await;
+    let failed = server
+        .mock("POST", "/hook")
+        .match_body(Matcher::PartialJson(serde_json::json!({
+            "event": "failed",
+            "retry_count": 3,
+            "error": "HTTP 404"
+        })))
+        .with_status(500)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let completed = server
+        .mock("POST", "/hook")
+        .match_body(Matcher::PartialJson(
+            serde_json::json!({"event": "completed"}),
+        ))
+        .expect(0)
+        .create_async()
+        .await;
+
+    let notifier = notifier(format!("{}/hook", server.url()), false);
+    // Completed events are turned off, so only the failure is POSTed; the 500 is logged
+    notifier
+        .notify(&JobNotification::completed(&job(), &stats()))
+        .await;
+    notifier
+        .notify(&JobNotification::failed(&job(), "HTTP 404", 3))
+        .await;
+
+    failed.assert_async().await;
+    completed.assert_async().await;
+}
@@ -0,0 +1,121 @@
+// Tests for JobRunRepository: recording runs, daily trends, and retention
+
+mod common;
+
+use chrono::{Duration, Utc};
+use rain_tracker_service::db::{JobRunKind, JobRunOutcome, JobRunRepository, NewJobRun};
+
+fn run(
+    job: JobRunKind,
+    days_ago: i64,
+    duration_ms: f64,
+    rows: i64,
+    error: Option<&str>,
+) -> NewJobRun {
+    NewJobRun {
+        job,
+        station_id: None,
+        fopr_job_id: None,
+        started_at: Utc::now() - Duration::days(days_ago),
+        duration_ms,
+        row_count: rows,
+        error: error.map(String::from),
+    }
+}
+
+#[tokio::test]
+async fn test_find_recent_filters_by_job() {
+    let pool = common::isolated_db().await;
+    let repo = JobRunRepository::new(pool);
+
+    repo.insert(&run(JobRunKind::Fetch, 0, 800.0, 4, None))
+        .await
+        .unwrap();
+    repo.insert(&run(
+        JobRunKind::GaugeList,
+        0,
+        2000.0,
+        350,
+        Some("HTTP 503"),
+    ))
+    .await
+    .unwrap();
+
+    let all = repo.find_recent(None, None, 0, 10).await.unwrap();
+    assert_eq!(all.len(), 2);
+    assert_eq!(repo.count(None, None).await.unwrap(), 2);
+
+    let second_page = repo.find_recent(None, None, 1, 10).await.unwrap();
+    assert_eq!(second_page.len(), 1);
+    assert_eq!(second_page[0].id, all[1].id);
+
+    let gauge_list = repo
+        .find_recent(Some(JobRunKind::GaugeList), None, 0, 10)
+        .await
+        .unwrap();
+    assert_eq!(gauge_list.len(), 1);
+    assert_eq!(
+        repo.count(Some(JobRunKind::GaugeList), None).await.unwrap(),
+        1
+    );
+    assert_eq!(gauge_list[0].outcome, JobRunOutcome::Failed);
+    assert_eq!(gauge_list[0].error.as_deref(), Some("HTTP 503"));
+}
+
+#[tokio::test]
+async fn test_daily_trend_aggregates_each_day() {
+    let pool = common::isolated_db().await;
+    let repo = JobRunRepository::new(pool);
+
+    // Yesterday: two successful imports, 3000 rows in 3 seconds
+    repo.insert(&run(JobRunKind::FoprImport, 1, 1000.0, 1000, None))
+        .await
+        .unwrap();
+    repo.insert(&run(JobRunKind::FoprImport, 1, 2000.0, 2000, None))
+        .await
+        .unwrap();
+    // Today: one failure only
+    repo.insert(&run(JobRunKind::FoprImport, 0, 500.0, 0, Some("timeout")))
+        .await
+        .unwrap();
+    repo.insert(&run(JobRunKind::Fetch, 0, 100.0, 1, None))
+        .await
+        .unwrap();
+
+    let trend = repo
+        .daily_trend(Some(JobRunKind::FoprImport), Utc::now() - Duration::days(7))
+        .await
+        .unwrap();
+    assert_eq!(trend.len(), 2, "{trend:?}");
+
+    let yesterday = &trend[0];
+    assert_eq!((yesterday.runs, yesterday.failures), (2, 0));
+    assert_eq!(yesterday.row_count, 3000);
+    assert_eq!(yesterday.avg_duration_ms, 1500.0);
+    assert!(yesterday.p95_duration_ms > 1900.0 && yesterday.p95_duration_ms <= 2000.0);
+    assert_eq!(yesterday.rows_per_second, Some(1000.0));
+
+    let today = &trend[1];
+    assert_eq!((today.runs, today.failures), (1, 1));
+    assert_eq!(today.rows_per_second, None, "no successful runs");
+}
+
+#[tokio::test]
+async fn test_delete_before_prunes_old_runs() {
+    let pool = common::isolated_db().await;
+    let repo = JobRunRepository::new(pool);
+
+    repo.insert(&run(JobRunKind::Fetch, 120, 100.0, 1, None))
+        .await
+        .unwrap();
+    repo.insert(&run(JobRunKind::Fetch, 10, 100.0, 1, None))
+        .await
+        .unwrap();
+
+    let deleted = repo
+        .delete_before(Utc::now() - Duration::days(90))
+        .await
+        .unwrap();
+    assert_eq!(deleted, 1);
+    assert_eq!(repo.find_recent(None, None, 0, 10).await.unwrap().len(), 1);
+}
@@ -0,0 +1,56 @@
+// Scheduler leader election between two replicas against the test database
+
+mod common;
+
+use std::time::Duration;
+
+use rain_tracker_service::leader::{run_election, LeaderElectionConfig, Leadership};
+
+/// Distinct from the service's key so a locally running service does not interfere
+const TEST_LOCK_KEY: i64 = 0x7465_7374_6c65_6164;
+
+fn election(instance_id: &str) -> LeaderElectionConfig {
+    LeaderElectionConfig {
+        instance_id: instance_id.to_string(),
+        retry_secs: 1,
+        lock_key: TEST_LOCK_KEY,
+    }
+}
+
+/// Poll until `check` holds, for up to 10 seconds
+async fn eventually(what: &str, check: impl Fn() -> bool) {
+    for _ in 0..100 {
+        if check() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("timed out waiting for {what}");
+}
+
+#[tokio::test]
+async fn test_follower_takes_over_when_leader_exits() {
+    let url = common::database_url().await;
+
+    let a = Leadership::elected("replica-a");
+    let a_task = tokio::spawn(run_election(url.clone(), election("replica-a"), a.clone()));
+    eventually("replica-a to lead", || a.is_leader()).await;
+
+    let b = Leadership::elected("replica-b");
+    let b_task = tokio::spawn(run_election(url, election("replica-b"), b.clone()));
+    eventually("replica-b to see replica-a lead", || {
+        b.status().unwrap().leader_id.as_deref() == Some("replica-a")
+    })
+    .await;
+    assert!(!b.is_leader());
+    assert!(a.is_leader(), "leadership is exclusive");
+
+    // Dropping the task closes replica-a's connection, releasing the lock
+    a_task.abort();
+    eventually("replica-b to take over", || b.is_leader()).await;
+    let status = b.status().unwrap();
+    assert_eq!(status.leader_id.as_deref(), Some("replica-b"));
+    assert!(status.leader_since.is_some());
+
+    b_task.abort();
+}
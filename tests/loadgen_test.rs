@@ -0,0 +1,111 @@
+// Integration tests for the loadgen binary's runner
+// Uses mockito as the target service so no server or database is needed
+
+use clap::Parser;
+use mockito::{Matcher, Server};
+use rain_tracker_service::loadgen::{self, LoadgenArgs};
+
+fn args(target: &str, extra: &[&str]) -> LoadgenArgs {
+    let mut argv = vec![
+        "loadgen",
+        "--target",
+        target,
+        "--rps",
+        "50",
+        "--duration",
+        "1",
+        "--seed",
+        "7",
+    ];
+    argv.extend_from_slice(extra);
+    LoadgenArgs::try_parse_from(argv).unwrap()
+}
+
+#[tokio::test]
+async fn test_loadgen_discovers_stations_and_reports_each_endpoint() {
+    let mut server = Server::new_async().await;
+    let gauges = server
+        .mock("GET", "/api/v1/gauges")
+        .with_status(200)
+        .with_body(r#"{"gauges": [{"station_id": "59700"}, {"station_id": "11000"}]}"#)
+        .expect_at_least(1)
+        .create_async()
+        .await;
+    let _latest = server
+        .mock(
+            "GET",
+            Matcher::Regex(r"^/api/v1/readings/\d+/latest$".into()),
+        )
+        .with_status(200)
+        .with_body("{}")
+        .create_async()
+        .await;
+    let _water_year = server
+        .mock(
+            "GET",
+            Matcher::Regex(r"^/api/v1/readings/\d+/water-year/2024$".into()),
+        )
+        .with_status(503)
+        .create_async()
+        .await;
+
+    let report = loadgen::run(&args(&server.url(), &["--water-year", "2024"]))
+        .await
+        .unwrap();
+
+    gauges.assert_async().await;
+    assert_eq!(report.stations, 2);
+    assert_eq!(report.water_year, 2024);
+    assert_eq!(report.total.requests + report.total.dropped, 50);
+    assert_eq!(report.total.dropped, 0);
+
+    let latest = &report.endpoints["latest"];
+    assert_eq!(latest.failed, 0);
+    assert_eq!(latest.status_codes.get("200"), Some(&latest.requests));
+
+    let water_year = &report.endpoints["water_year"];
+    assert!(water_year.requests > 0);
+    assert_eq!(water_year.succeeded, 0);
+    assert_eq!(
+        water_year.status_codes.get("503"),
+        Some(&water_year.requests)
+    );
+    assert_eq!(report.total.failed, water_year.requests);
+    assert!(report.total.latency.is_some());
+}
+
+#[tokio::test]
+async fn test_loadgen_uses_given_stations_and_mix() {
+    let mut server = Server::new_async().await;
+    let latest = server
+        .mock("GET", "/api/v1/readings/12345/latest")
+        .with_status(200)
+        .expect(50)
+        .create_async()
+        .await;
+
+    let report = loadgen::run(&args(
+        &server.url(),
+        &["--stations", "12345", "--mix", "latest=1"],
+    ))
+    .await
+    .unwrap();
+
+    latest.assert_async().await;
+    assert_eq!(report.endpoints.len(), 1);
+    assert_eq!(report.endpoints["latest"].succeeded, 50);
+}
+
+#[tokio::test]
+async fn test_loadgen_fails_without_stations() {
+    let mut server = Server::new_async().await;
+    let _gauges = server
+        .mock("GET", "/api/v1/gauges")
+        .with_status(200)
+        .with_body(r#"{"gauges": []}"#)
+        .create_async()
+        .await;
+
+    let err = loadgen::run(&args(&server.url(), &[])).await.unwrap_err();
+    assert!(err.to_string().contains("--stations"));
+}
@@ -0,0 +1,96 @@
+// Migration status and controlled up/down against an isolated test database
+// SQLite tests need `--features sqlite`
+
+mod common;
+
+use rain_tracker_service::db::{DbError, DbPool};
+
+/// Newest migration; ships a down script
+const LATEST: i64 = 20250212000000;
+const BEFORE_LATEST: i64 = 20250211000000;
+/// Newest migration without a down script
+const IRREVERSIBLE: i64 = 20250118000000;
+
+/// Migrations run down and up on a database of their own, never the shared one
+async fn postgres_db() -> DbPool {
+    DbPool::from(crate::common::isolated_db().await)
+}
+
+#[tokio::test]
+async fn test_migrate_down_and_up() {
+    let db = postgres_db().await;
+    let status = db.migration_status().await.unwrap();
+    assert!(status.iter().all(|m| m.applied && !m.checksum_mismatch));
+    assert_eq!(status.last().unwrap().version, LATEST);
+    assert!(status.last().unwrap().reversible);
+    db.ensure_migrated().await.unwrap();
+
+    let reverted = db.migrate_down(BEFORE_LATEST).await.unwrap();
+    assert_eq!(reverted, [LATEST]);
+    let err = db.ensure_migrated().await.unwrap_err();
+    assert!(matches!(
+        err,
+        DbError::PendingMigrations {
+            count: 1,
+            latest: LATEST
+        }
+    ));
+
+    let applied = db.migrate_up(None).await.unwrap();
+    assert_eq!(applied, [LATEST]);
+    db.ensure_migrated().await.unwrap();
+    assert!(db.migrate_up(None).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_migrate_down_refuses_irreversible_migrations() {
+    let db = postgres_db().await;
+
+    // IRREVERSIBLE has no down script, so nothing is reverted, not even LATEST
+    let err = db.migrate_down(IRREVERSIBLE - 1_000_000).await.unwrap_err();
+    assert!(matches!(err, DbError::Irreversible(IRREVERSIBLE)), "{err}");
+    db.ensure_migrated().await.unwrap();
+
+    // The advisory lock was released, so later runs proceed
+    assert!(db.migrate_up(None).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_migrate_rejects_unknown_versions() {
+    let db = postgres_db().await;
+
+    assert!(db.migrate_up(Some(LATEST + 1)).await.is_err());
+    assert!(db.migrate_down(42).await.is_err());
+    db.ensure_migrated().await.unwrap();
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite_backend {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn test_sqlite_pending_migrations() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = DbPool::from(pool);
+
+        let status = db.migration_status().await.unwrap();
+        assert!(!status.is_empty());
+        assert!(status.iter().all(|m| !m.applied));
+        assert!(matches!(
+            db.ensure_migrated().await,
+            Err(DbError::PendingMigrations { .. })
+        ));
+
+        let applied = db.migrate_up(None).await.unwrap();
+        assert_eq!(applied.len(), status.len());
+        db.ensure_migrated().await.unwrap();
+
+        let err = db.migrate_down(0).await.unwrap_err();
+        assert!(matches!(err, DbError::Irreversible(_)));
+    }
+}
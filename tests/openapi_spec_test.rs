@@ -0,0 +1,59 @@
+// Spec snapshot tests
+// The committed openapi.json is what clients generate code from, so it must match the
+// spec the code produces. Regenerate with `cargo run --bin generate-openapi` (or `make openapi`).
+
+use rain_tracker_service::api::generate_openapi_spec;
+use serde_json::Value;
+
+fn generated_spec() -> Value {
+    serde_json::to_value(generate_openapi_spec()).expect("Failed to serialize OpenAPI spec")
+}
+
+#[test]
+fn test_committed_spec_matches_code() {
+    let committed = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/openapi.json"))
+        .expect("openapi.json is missing");
+    let committed: Value =
+        serde_json::from_str(&committed).expect("openapi.json is not valid JSON");
+
+    assert!(
+        committed == generated_spec(),
+        "openapi.json is out of date; run `cargo run --bin generate-openapi` and commit the result"
+    );
+}
+
+#[test]
+fn test_error_responses_use_problem_details() {
+    let spec = generated_spec();
+
+    for (path, operations) in spec["paths"].as_object().unwrap() {
+        for (method, operation) in operations.as_object().unwrap() {
+            for (status, response) in operation["responses"].as_object().unwrap() {
+                if !(status.starts_with('4') || status.starts_with('5')) {
+                    continue;
+                }
+                let schema = &response["content"]["application/problem+json"]["schema"]["$ref"];
+                assert_eq!(
+                    schema, "#/components/schemas/ProblemDetails",
+                    "{method} {path} {status} should document a ProblemDetails body"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_every_operation_documents_server_errors() {
+    let spec = generated_spec();
+
+    for (path, operations) in spec["paths"].as_object().unwrap() {
+        for (method, operation) in operations.as_object().unwrap() {
+            assert!(
+                operation["responses"].get("500").is_some()
+                    || path == "/api/v1/health"
+                    || path == "/api/v1/health/ready",
+                "{method} {path} should document a 500 response"
+            );
+        }
+    }
+}
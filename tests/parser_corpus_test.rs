@@ -0,0 +1,232 @@
+// Parser regression corpus
+//
+// Each tests/parser_corpus/*.json case names a parser and an input file checked in under
+// sample-data-files/, plus the canonical output that parser produced when the case was
+// recorded. Parsing every case and diffing against `expected` keeps a parser change from
+// silently altering how a known historical file is read.
+//
+// After an intentional change, rerun with UPDATE_PARSER_CORPUS=1 to rewrite the
+// expectations, and review the diff before committing it.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use calamine::{open_workbook_auto, Reader};
+use rain_tracker_service::fopr::{FoprDailyDataParser, MetaStatsData};
+use rain_tracker_service::importers::excel_importer::{ExcelImporter, HistoricalReading};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+const CORPUS_DIR: &str = "tests/parser_corpus";
+
+/// Differences listed per failing case before the rest are elided
+const MAX_REPORTED_DIFFS: usize = 20;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Case {
+    description: String,
+    parser: Parser,
+    /// Relative to the crate root
+    input: String,
+    /// Required by `water_year_excel`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    water_year: Option<i32>,
+    /// Required by `fopr_daily`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    station_id: Option<String>,
+    expected: Value,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Parser {
+    /// ExcelImporter::parse_all_months on a pcp_WY_YYYY.xlsx workbook
+    WaterYearExcel,
+    /// MetaStatsData from an FOPR workbook's Meta_Stats sheet
+    FoprMetaStats,
+    /// FoprDailyDataParser::parse_all_years on an FOPR workbook
+    FoprDaily,
+}
+
+#[test]
+fn test_parser_corpus() {
+    let update = std::env::var("UPDATE_PARSER_CORPUS").is_ok_and(|v| v == "1");
+    let mut paths: Vec<PathBuf> = fs::read_dir(CORPUS_DIR)
+        .expect("Failed to read parser corpus directory")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    assert!(!paths.is_empty(), "No cases in {CORPUS_DIR}");
+
+    let mut failures = Vec::new();
+    for path in &paths {
+        let mut case: Case = serde_json::from_str(&fs::read_to_string(path).unwrap())
+            .unwrap_or_else(|e| panic!("Invalid corpus case {}: {e}", path.display()));
+        let actual = parse(&case);
+
+        if update {
+            if actual != case.expected {
+                case.expected = actual;
+                let mut json = serde_json::to_string_pretty(&case).unwrap();
+                json.push('\n');
+                fs::write(path, json).unwrap();
+                println!("Updated {}", path.display());
+            }
+            continue;
+        }
+
+        let mut diffs = Vec::new();
+        diff_values("", &case.expected, &actual, &mut diffs);
+        if !diffs.is_empty() {
+            let total = diffs.len();
+            diffs.truncate(MAX_REPORTED_DIFFS);
+            let mut report = format!(
+                "{} ({}):\n  {}",
+                path.display(),
+                case.description,
+                diffs.join("\n  ")
+            );
+            if total > MAX_REPORTED_DIFFS {
+                report.push_str(&format!("\n  ... and {} more", total - MAX_REPORTED_DIFFS));
+            }
+            failures.push(report);
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "Parser output drifted from the corpus (UPDATE_PARSER_CORPUS=1 rewrites it):\n{}",
+        failures.join("\n")
+    );
+}
+
+/// Canonical, diffable output of the case's parser
+fn parse(case: &Case) -> Value {
+    let input = Path::new(&case.input);
+    assert!(input.exists(), "Corpus input {} is missing", case.input);
+
+    match case.parser {
+        Parser::WaterYearExcel => {
+            let water_year = case
+                .water_year
+                .expect("water_year_excel cases need water_year");
+            let readings = ExcelImporter::new(&case.input)
+                .parse_all_months(water_year)
+                .unwrap_or_else(|e| panic!("Failed to parse {}: {e}", case.input));
+            summarize_by(&readings, |r| r.station_id.to_string())
+        }
+        Parser::FoprMetaStats => {
+            let mut workbook = open_workbook_auto(input)
+                .unwrap_or_else(|e| panic!("Failed to open {}: {e}", case.input));
+            let range = workbook
+                .worksheet_range("Meta_Stats")
+                .unwrap_or_else(|e| panic!("No Meta_Stats sheet in {}: {e}", case.input));
+            let meta = MetaStatsData::from_worksheet_range(&range)
+                .unwrap_or_else(|e| panic!("Failed to parse {}: {e}", case.input));
+            serde_json::to_value(meta).unwrap()
+        }
+        Parser::FoprDaily => {
+            let station_id = case
+                .station_id
+                .as_deref()
+                .expect("fopr_daily cases need station_id")
+                .parse()
+                .unwrap_or_else(|e| panic!("Bad station_id in {}: {e}", case.input));
+            let readings = FoprDailyDataParser::new(&case.input, station_id)
+                .parse_all_years()
+                .unwrap_or_else(|e| panic!("Failed to parse {}: {e}", case.input));
+            summarize_by(&readings, |r| r.reading_date.format("%Y-%m").to_string())
+        }
+    }
+}
+
+/// Reading counts and totals overall and per group
+///
+/// Full reading lists would run to tens of thousands of lines; per-group counts, totals,
+/// and date bounds still pin down which cells were read and how they were interpreted.
+fn summarize_by(
+    readings: &[HistoricalReading],
+    group: impl Fn(&HistoricalReading) -> String,
+) -> Value {
+    let mut groups: BTreeMap<String, Vec<&HistoricalReading>> = BTreeMap::new();
+    for reading in readings {
+        groups.entry(group(reading)).or_default().push(reading);
+    }
+
+    let all: Vec<&HistoricalReading> = readings.iter().collect();
+    let mut summary = summarize(&all);
+    summary["groups"] = groups
+        .into_iter()
+        .map(|(key, readings)| (key, summarize(&readings)))
+        .collect::<serde_json::Map<_, _>>()
+        .into();
+    summary
+}
+
+fn summarize(readings: &[&HistoricalReading]) -> Value {
+    let total: f64 = readings.iter().map(|r| r.rainfall_inches.value()).sum();
+    let footnoted: BTreeMap<&str, usize> = readings
+        .iter()
+        .filter_map(|r| r.footnote_marker.as_deref())
+        .fold(BTreeMap::new(), |mut counts, marker| {
+            *counts.entry(marker).or_default() += 1;
+            counts
+        });
+
+    json!({
+        "readings": readings.len(),
+        "total_inches": (total * 100.0).round() / 100.0,
+        "first_date": readings.iter().map(|r| r.reading_date).min(),
+        "last_date": readings.iter().map(|r| r.reading_date).max(),
+        "footnote_markers": footnoted,
+    })
+}
+
+/// JSON paths where `expected` and `actual` differ
+fn diff_values(path: &str, expected: &Value, actual: &Value, diffs: &mut Vec<String>) {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            for (key, value) in expected {
+                let child = format!("{path}/{key}");
+                match actual.get(key) {
+                    Some(actual) => diff_values(&child, value, actual, diffs),
+                    None => diffs.push(format!("{child}: missing (expected {value})")),
+                }
+            }
+            for (key, value) in actual {
+                if !expected.contains_key(key) {
+                    diffs.push(format!("{path}/{key}: unexpected {value}"));
+                }
+            }
+        }
+        (Value::Array(expected), Value::Array(actual)) if expected.len() == actual.len() => {
+            for (i, (expected, actual)) in expected.iter().zip(actual).enumerate() {
+                diff_values(&format!("{path}/{i}"), expected, actual, diffs);
+            }
+        }
+        _ if expected != actual => {
+            let path = if path.is_empty() { "/" } else { path };
+            diffs.push(format!("{path}: expected {expected}, got {actual}"));
+        }
+        _ => {}
+    }
+}
+
+#[test]
+fn test_diff_values_reports_paths() {
+    let expected = json!({"a": 1, "b": {"c": [1, 2]}, "gone": true});
+    let actual = json!({"a": 1, "b": {"c": [1, 3]}, "new": null});
+    let mut diffs = Vec::new();
+    diff_values("", &expected, &actual, &mut diffs);
+
+    assert_eq!(
+        diffs,
+        [
+            "/b/c/1: expected 2, got 3",
+            "/gone: missing (expected true)",
+            "/new: unexpected null",
+        ]
+    );
+}
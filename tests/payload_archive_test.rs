@@ -0,0 +1,81 @@
+// Archiving scraped payloads from a mock MCFCD and replaying them with `historical-import replay`
+
+use mockito::Server;
+use rain_tracker_service::cli::replay::replay;
+use rain_tracker_service::cli::ReplayArgs;
+use rain_tracker_service::gauge_list_fetcher::GaugeListFetcher;
+use rain_tracker_service::payload_archive::{PayloadArchive, PayloadSource};
+
+const GAUGE_LIST: &str = r#"
+     Gage                          In or Nearest      Gage    Elev.  Rainfall    Rainfall      MSP Forecast       General
+     Name                           City / Town        ID     (ft)   Past 6 hr  Past 24 hr         Zone           Location
+--------------------------------   ---------------   ------  ------  ---------  ----------  ------------------   --------------------------------------------
+4th of July Wash                   Agua Caliente      41200   1120      0.00       0.00     None                  21 mi. W of Old US80 on Agua Caliente Road
+Columbus Wash                      Agua Caliente      40800    705      0.00       0.04     AZ023                 8 mi. N of Agua Caliente
+"#;
+
+fn replay_args(dir: &std::path::Path, source: Option<PayloadSource>) -> ReplayArgs {
+    ReplayArgs {
+        dir: dir.to_path_buf(),
+        source,
+        since: None,
+        last: None,
+        show_rows: false,
+    }
+}
+
+#[tokio::test]
+async fn test_scraped_gauge_list_is_archived_and_replayed() {
+    let mut server = Server::new_async().await;
+    let _list = server
+        .mock("GET", "/ev_rain.txt")
+        .with_status(200)
+        .with_header("content-type", "text/plain")
+        .with_body(GAUGE_LIST)
+        .create_async()
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let archive = PayloadArchive::new(dir.path(), 30);
+    let fetcher = GaugeListFetcher::new(format!("{}/ev_rain.txt", server.url()))
+        .with_archive(archive.clone());
+    assert_eq!(fetcher.fetch_gauge_list().await.unwrap().len(), 2);
+
+    let stored = archive.list(None, None).await.unwrap();
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].meta.source, PayloadSource::GaugeList);
+    assert_eq!(stored[0].meta.content_type.as_deref(), Some("text/plain"));
+    assert_eq!(stored[0].meta.bytes, GAUGE_LIST.len());
+
+    let report = replay(&replay_args(dir.path(), None), true).await.unwrap();
+    assert_eq!(report.failed(), 0);
+    assert_eq!(report.payloads.len(), 1);
+    assert_eq!(report.payloads[0].parsed, 2);
+
+    let report = replay(
+        &replay_args(dir.path(), Some(PayloadSource::Readings)),
+        true,
+    )
+    .await
+    .unwrap();
+    assert!(report.payloads.is_empty());
+}
+
+#[tokio::test]
+async fn test_replay_reports_payloads_that_no_longer_parse() {
+    let dir = tempfile::tempdir().unwrap();
+    let archive = PayloadArchive::new(dir.path(), 30);
+    archive
+        .store(
+            PayloadSource::Readings,
+            "https://alert.fcd.maricopa.gov/php/showdata4.php?ID=59700",
+            Some("text/html"),
+            "<html><body>Service unavailable</body></html>",
+        )
+        .await
+        .unwrap();
+
+    let report = replay(&replay_args(dir.path(), None), true).await.unwrap();
+    assert_eq!(report.failed(), 1);
+    assert!(report.payloads[0].error.is_some());
+}
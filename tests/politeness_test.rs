@@ -0,0 +1,169 @@
+// robots.txt, User-Agent, From, circuit breaker, and HTTP status handling of outbound
+// requests against a mock MCFCD
+
+use std::time::Duration;
+
+use mockito::{Matcher, Server};
+use rain_tracker_service::breaker::{BreakerConfig, BreakerState};
+use rain_tracker_service::fetch_error::FetchError;
+use rain_tracker_service::fetcher::RainGaugeFetcher;
+use rain_tracker_service::importers::downloader::{DownloadError, McfcdDownloader};
+use rain_tracker_service::politeness::{Politeness, PolitenessConfig};
+
+fn politeness(contact: Option<&str>) -> Politeness {
+    Politeness::new(&PolitenessConfig::parse(None, contact, true, 0, None))
+}
+
+#[tokio::test]
+async fn test_robots_txt_disallowed_files_are_not_requested() {
+    let mut server = Server::new_async().await;
+    let robots = server
+        .mock("GET", "/robots.txt")
+        .with_status(200)
+        .with_body("User-agent: *\nDisallow: /FOPR/\n")
+        .expect(1)
+        .create_async()
+        .await;
+    let fopr = server
+        .mock("GET", "/FOPR/59700_FOPR.xlsx")
+        .expect(0)
+        .create_async()
+        .await;
+    let excel = server
+        .mock("GET", "/pcp_WY_2023.xlsx")
+        .with_status(200)
+        .with_body(b"fake excel data")
+        .create_async()
+        .await;
+
+    let downloader =
+        McfcdDownloader::with_base_url(server.url() + "/").with_politeness(politeness(None));
+
+    match downloader.download_fopr("59700").await {
+        Err(DownloadError::Disallowed(url)) => assert!(url.ends_with("/FOPR/59700_FOPR.xlsx")),
+        other => panic!("Expected Disallowed, got {other:?}"),
+    }
+    // robots.txt is cached, so the second request doesn't fetch it again
+    assert_eq!(
+        downloader.download_excel(2023).await.unwrap(),
+        b"fake excel data"
+    );
+
+    robots.assert_async().await;
+    fopr.assert_async().await;
+    excel.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_requests_identify_the_service_and_contact() {
+    let mut server = Server::new_async().await;
+    let robots = server
+        .mock("GET", "/robots.txt")
+        .with_status(404)
+        .create_async()
+        .await;
+    let excel = server
+        .mock("GET", "/pcp_WY_2023.xlsx")
+        .match_header(
+            "user-agent",
+            Matcher::Regex(r"^rain-tracker-service/\S+ \(\+https://github.com/tdrozdowski/rain-tracker-service; ops@example.com\)$".to_string()),
+        )
+        .match_header("from", "ops@example.com")
+        .with_status(200)
+        .with_body(b"fake excel data")
+        .create_async()
+        .await;
+
+    let downloader = McfcdDownloader::with_base_url(server.url() + "/")
+        .with_politeness(politeness(Some("ops@example.com")));
+
+    // A missing robots.txt allows everything
+    assert!(downloader.download_excel(2023).await.is_ok());
+
+    robots.assert_async().await;
+    excel.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_breaker_stops_requests_after_repeated_server_errors() {
+    let mut server = Server::new_async().await;
+    let failing = server
+        .mock("GET", "/pcp_WY_2023.xlsx")
+        .with_status(503)
+        .expect(3)
+        .create_async()
+        .await;
+
+    let politeness = Politeness::new(&PolitenessConfig {
+        breaker: BreakerConfig {
+            threshold: 3,
+            cooldown: Duration::from_secs(60),
+            max_cooldown: Duration::from_secs(600),
+        },
+        ..PolitenessConfig::parse(None, None, false, 0, None)
+    });
+    let downloader =
+        McfcdDownloader::with_base_url(server.url() + "/").with_politeness(politeness.clone());
+
+    for _ in 0..3 {
+        assert!(matches!(
+            downloader.download_excel(2023).await,
+            Err(DownloadError::ServerError(_))
+        ));
+    }
+    // Open: refused without reaching the server
+    match downloader.download_excel(2023).await {
+        Err(DownloadError::CircuitOpen(host)) => assert_eq!(host, "127.0.0.1"),
+        other => panic!("Expected CircuitOpen, got {other:?}"),
+    }
+
+    let status = &politeness.breakers().statuses()[0];
+    assert_eq!(status.state, BreakerState::Open);
+    assert_eq!(status.consecutive_failures, 3);
+    assert_eq!(status.trips, 1);
+    failing.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_gauge_page_errors_report_status_and_retryability() {
+    let mut server = Server::new_async().await;
+    let _unavailable = server
+        .mock("GET", "/busy.html")
+        .with_status(503)
+        .with_body("<html>Service Unavailable</html>")
+        .create_async()
+        .await;
+    let _missing = server
+        .mock("GET", "/moved.html")
+        .with_status(404)
+        .create_async()
+        .await;
+    let _redesigned = server
+        .mock("GET", "/redesigned.html")
+        .with_status(200)
+        .with_body("<html><table><tr><td>1.85</td></tr></table></html>")
+        .create_async()
+        .await;
+
+    let fetch = |path: &str| {
+        RainGaugeFetcher::new(format!("{}{path}", server.url())).with_politeness(politeness(None))
+    };
+
+    let e = fetch("/busy.html").fetch_readings().await.unwrap_err();
+    assert!(matches!(
+        e,
+        FetchError::HttpStatus { status, .. } if status.as_u16() == 503
+    ));
+    assert!(e.is_retryable());
+
+    let e = fetch("/moved.html").fetch_readings().await.unwrap_err();
+    assert_eq!(e.kind(), "http_status");
+    assert!(!e.is_retryable());
+
+    let e = fetch("/redesigned.html")
+        .fetch_readings()
+        .await
+        .unwrap_err();
+    assert!(matches!(e, FetchError::LayoutChanged(_)));
+    assert!(!e.is_retryable());
+}
@@ -0,0 +1,148 @@
+// Integration tests for SeedService
+// Seeds a couple of synthetic gauges and checks gauges, readings, and summaries land
+
+mod common;
+
+use chrono::NaiveDate;
+use rain_tracker_service::services::seed_service::{
+    SeedOptions, SyntheticDataGenerator, SEED_DATA_SOURCE,
+};
+use rain_tracker_service::services::SeedService;
+use serial_test::serial;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+
+mod seed_fixtures {
+    use super::*;
+
+    pub async fn setup_test_db() -> PgPool {
+        let database_url = crate::common::database_url().await;
+
+        let pool = PgPoolOptions::new()
+            .max_connections(5)
+            .connect(&database_url)
+            .await
+            .expect("Failed to connect to test database");
+
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    pub async fn cleanup(pool: &PgPool, station_id: &str) {
+        sqlx::query!(
+            "DELETE FROM monthly_rainfall_summary WHERE station_id = $1",
+            station_id
+        )
+        .execute(pool)
+        .await
+        .ok();
+        sqlx::query!(
+            "DELETE FROM rain_readings WHERE station_id = $1",
+            station_id
+        )
+        .execute(pool)
+        .await
+        .ok();
+        sqlx::query!(
+            "DELETE FROM gauge_summaries WHERE station_id = $1",
+            station_id
+        )
+        .execute(pool)
+        .await
+        .ok();
+        sqlx::query!("DELETE FROM gauges WHERE station_id = $1", station_id)
+            .execute(pool)
+            .await
+            .ok();
+    }
+
+    pub fn options() -> SeedOptions {
+        SeedOptions {
+            gauges: 2,
+            years: 1,
+            rng_seed: 7,
+            end_date: NaiveDate::from_ymd_opt(2024, 9, 30).unwrap(),
+        }
+    }
+}
+
+use seed_fixtures::*;
+
+#[tokio::test]
+#[serial]
+async fn test_seed_inserts_gauges_readings_and_summaries() {
+    let pool = setup_test_db().await;
+    let gauges = SyntheticDataGenerator::new(options()).generate();
+    for gauge in &gauges {
+        cleanup(&pool, &gauge.metadata.station_id).await;
+    }
+
+    let service = SeedService::new(pool.clone());
+    let gauge = &gauges[0];
+    let station_id = gauge.metadata.station_id.as_str();
+    let stats = service.seed_gauge(gauge).await.unwrap();
+
+    assert_eq!(stats.gauges_created, 1);
+    assert_eq!(stats.readings_inserted, gauge.readings.len());
+    assert_eq!(stats.duplicates_skipped, 0);
+
+    let listed = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM gauge_summaries WHERE station_id = $1",
+        station_id
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(listed, Some(1));
+
+    let readings = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM rain_readings WHERE station_id = $1 AND data_source = $2",
+        station_id,
+        SEED_DATA_SOURCE
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(readings, Some(gauge.readings.len() as i64));
+
+    let expected_total: f64 = gauge
+        .readings
+        .iter()
+        .map(|r| r.rainfall_inches.value())
+        .sum();
+    let summarized_total = sqlx::query_scalar!(
+        "SELECT SUM(total_rainfall_inches) FROM monthly_rainfall_summary WHERE station_id = $1",
+        station_id
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap()
+    .unwrap();
+    assert!((summarized_total - expected_total).abs() < 0.001);
+
+    for gauge in &gauges {
+        cleanup(&pool, &gauge.metadata.station_id).await;
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn test_reseeding_skips_duplicates() {
+    let pool = setup_test_db().await;
+    let gauges = SyntheticDataGenerator::new(options()).generate();
+    let gauge = &gauges[1];
+    cleanup(&pool, &gauge.metadata.station_id).await;
+
+    let service = SeedService::new(pool.clone());
+    service.seed_gauge(gauge).await.unwrap();
+    let stats = service.seed_gauge(gauge).await.unwrap();
+
+    assert_eq!(stats.readings_inserted, 0);
+    assert_eq!(stats.duplicates_skipped, gauge.readings.len());
+
+    cleanup(&pool, &gauge.metadata.station_id).await;
+}
@@ -0,0 +1,181 @@
+// Tests for ShareService: snapshots stay frozen behind their links until they expire
+
+mod common;
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rain_tracker_service::clock::FixedClock;
+use rain_tracker_service::db::{
+    AnnotationRepository, GaugeRepository, HourlyRainfallRepository, MonthlyRainfallRepository,
+    RankingPeriod, ReadingRepository, User, UserRepository,
+};
+use rain_tracker_service::services::share_service::{ShareError, ShareRequest};
+use rain_tracker_service::services::{ReadingService, ShareService};
+use rain_tracker_service::share::ShareSigner;
+use rain_tracker_service::station_id::StationId;
+use sqlx::PgPool;
+
+const WET: &str = "SHARE_WET";
+const DRY: &str = "SHARE_DRY";
+/// Rains too, but is never shared
+const OTHER: &str = "SHARE_OTHER";
+
+fn now() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 2, 12, 8, 0, 0).unwrap()
+}
+
+async fn add_reading(pool: &PgPool, station_id: &str, at: DateTime<Utc>, inches: f64) {
+    sqlx::query!(
+        r#"
+        INSERT INTO rain_readings (reading_datetime, cumulative_inches, incremental_inches, station_id)
+        VALUES ($1, $2, $2, $3)
+        "#,
+        at,
+        inches,
+        station_id
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+/// Three gauges with rain at WET and OTHER in the 24 hours before `now`, and a user
+async fn setup() -> (PgPool, User) {
+    let pool = common::isolated_db().await;
+    for station_id in [WET, DRY, OTHER] {
+        sqlx::query!(
+            r#"
+            INSERT INTO gauges (station_id, station_name, station_type, latitude, longitude, county, status)
+            VALUES ($1, $1, 'Rain', 33.5, -112.0, 'Test County', 'Active')
+            "#,
+            station_id
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query!(
+            r#"
+            INSERT INTO gauge_summaries (station_id, gauge_name, last_scraped_at, updated_at)
+            VALUES ($1, $1, NOW(), NOW())
+            "#,
+            station_id
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+    add_reading(&pool, WET, now() - Duration::hours(3), 0.75).await;
+    add_reading(&pool, DRY, now() - Duration::hours(3), 0.0).await;
+    add_reading(&pool, OTHER, now() - Duration::hours(2), 2.0).await;
+
+    let user = UserRepository::new(pool.clone())
+        .insert("share-test", &"0".repeat(64), None, None)
+        .await
+        .unwrap();
+    (pool, user)
+}
+
+fn service(pool: &PgPool, at: DateTime<Utc>) -> ShareService {
+    let clock = Arc::new(FixedClock(at));
+    let reading_service = ReadingService::new(
+        ReadingRepository::new(pool.clone()),
+        MonthlyRainfallRepository::new(pool.clone()),
+        HourlyRainfallRepository::new(pool.clone()),
+        AnnotationRepository::new(pool.clone()),
+        GaugeRepository::new(pool.clone()),
+    )
+    .with_clock(clock.clone());
+    ShareService::new(pool.clone(), reading_service).with_clock(clock)
+}
+
+fn signed(pool: &PgPool, at: DateTime<Utc>) -> ShareService {
+    service(pool, at).with_signer(ShareSigner::new("share-test-signing-key-0123456789"))
+}
+
+fn request(expires_in_hours: u32) -> ShareRequest {
+    ShareRequest {
+        station_ids: [DRY, WET]
+            .iter()
+            .map(|id| StationId::parse(id).unwrap())
+            .collect(),
+        period: RankingPeriod::Last24Hours,
+        as_of: None,
+        expires_in_hours,
+    }
+}
+
+#[tokio::test]
+async fn test_snapshot_is_frozen_when_shared() {
+    let (pool, user) = setup().await;
+    let service = signed(&pool, now());
+
+    let link = service.create(&user, &request(24)).await.unwrap();
+    assert_eq!(link.expires_at, now() + Duration::hours(24));
+    assert_eq!(link.path, format!("/share/{}", link.token));
+
+    // Rain after the link was created does not change what it serves
+    add_reading(&pool, DRY, now() - Duration::hours(1), 1.5).await;
+
+    let snapshot = service.open(&link.token).await.unwrap();
+    assert_eq!(snapshot["station_ids"], serde_json::json!([DRY, WET]));
+    assert_eq!(snapshot["end"], "2025-02-12T08:00:00Z");
+    let rankings = snapshot["rankings"].as_array().unwrap();
+    let stations: Vec<&str> = rankings
+        .iter()
+        .map(|r| r["station_id"].as_str().unwrap())
+        .collect();
+    assert_eq!(stations, [WET, DRY], "wettest first, OTHER left out");
+    assert_eq!(rankings[0]["rank"], 1);
+    assert_eq!(rankings[0]["rainfall_inches"], 0.75);
+    assert_eq!(rankings[1]["rainfall_inches"], 0.0);
+}
+
+#[tokio::test]
+async fn test_links_expire() {
+    let (pool, user) = setup().await;
+    let link = signed(&pool, now())
+        .create(&user, &request(1))
+        .await
+        .unwrap();
+
+    let later = signed(&pool, now() + Duration::hours(2));
+    assert!(matches!(
+        later.open(&link.token).await,
+        Err(ShareError::Expired(at)) if at == link.expires_at
+    ));
+
+    // The expired snapshot is deleted when the next link is created
+    later.create(&user, &request(1)).await.unwrap();
+    let remaining = sqlx::query_scalar!("SELECT COUNT(*) FROM shared_snapshots")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(remaining, Some(1));
+}
+
+#[tokio::test]
+async fn test_sharing_needs_a_signing_key() {
+    let (pool, user) = setup().await;
+    let link = signed(&pool, now())
+        .create(&user, &request(24))
+        .await
+        .unwrap();
+
+    let unsigned = service(&pool, now());
+    assert!(matches!(
+        unsigned.create(&user, &request(24)).await,
+        Err(ShareError::Disabled)
+    ));
+    assert!(matches!(
+        unsigned.open(&link.token).await,
+        Err(ShareError::Disabled)
+    ));
+
+    // Rotating the key invalidates outstanding links
+    let rotated = service(&pool, now()).with_signer(ShareSigner::new(&"k".repeat(32)));
+    assert!(matches!(
+        rotated.open(&link.token).await,
+        Err(ShareError::NotFound)
+    ));
+}
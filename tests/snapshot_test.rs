@@ -0,0 +1,117 @@
+// Snapshot mode loading tests (`cargo test --features sqlite`)
+
+#![cfg(feature = "sqlite")]
+
+use std::fs;
+use std::path::Path;
+
+use chrono::{TimeZone, Utc};
+use rain_tracker_service::db::{GaugeRepository, MonthlyRainfallRepository, ReadingRepository};
+use rain_tracker_service::snapshot::{self, SnapshotError, SnapshotStats};
+
+mod snapshot_fixtures {
+    use super::*;
+
+    pub const GAUGES_CSV: &str = "\
+station_id,gauge_name,city_town,elevation_ft,general_location,msp_forecast_zone,rainfall_past_6h_inches,rainfall_past_24h_inches
+59700,Aztec Park,Scottsdale,1465,\"Near Thunderbird, Frank Lloyd Wright\",Zone 1,0,0.16
+11000,Cave Creek,,,,,,
+";
+
+    pub const READINGS_CSV: &str = "\
+station_id,reading_datetime,cumulative_inches,incremental_inches
+59700,2024-11-03T06:00:00+00:00,0.12,0.12
+59700,2024-11-03T12:00:00+00:00,0.16,0.04
+59700,2024-12-01T08:00:00+00:00,0.4,0.24
+";
+
+    pub fn write_snapshot(dir: &Path, gauges: &str, readings: Option<&str>) {
+        fs::write(dir.join(snapshot::GAUGES_FILE), gauges).unwrap();
+        if let Some(readings) = readings {
+            fs::write(dir.join(snapshot::READINGS_FILE), readings).unwrap();
+        }
+    }
+}
+
+use snapshot_fixtures::*;
+
+#[tokio::test]
+async fn test_load_snapshot() {
+    let dir = tempfile::tempdir().unwrap();
+    write_snapshot(dir.path(), GAUGES_CSV, Some(READINGS_CSV));
+
+    let (db, stats) = snapshot::load(dir.path()).await.unwrap();
+    assert_eq!(
+        stats,
+        SnapshotStats {
+            gauges: 2,
+            readings: 3,
+            months_summarized: 2,
+        }
+    );
+
+    let gauge_repo = GaugeRepository::new(db.clone());
+    let gauge = gauge_repo.find_by_id("59700").await.unwrap().unwrap();
+    assert_eq!(
+        gauge.general_location.as_deref(),
+        Some("Near Thunderbird, Frank Lloyd Wright")
+    );
+    let sparse = gauge_repo.find_by_id("11000").await.unwrap().unwrap();
+    assert_eq!(sparse.city_town, None);
+
+    let reading_repo = ReadingRepository::new(db.clone());
+    let latest = reading_repo.find_latest("59700").await.unwrap().unwrap();
+    assert_eq!(latest.cumulative_inches.value(), 0.4);
+
+    // Monthly summaries are rebuilt from the loaded readings
+    let start = Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap();
+    let end = Utc.with_ymd_and_hms(2025, 10, 1, 0, 0, 0).unwrap();
+    let summaries = MonthlyRainfallRepository::new(db)
+        .get_summaries_by_date_range("59700", start, end)
+        .await
+        .unwrap();
+    assert_eq!(summaries.len(), 2);
+    assert!((summaries[0].total_rainfall_inches - 0.16).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_load_snapshot_without_readings() {
+    let dir = tempfile::tempdir().unwrap();
+    write_snapshot(dir.path(), GAUGES_CSV, None);
+
+    let (_, stats) = snapshot::load(dir.path()).await.unwrap();
+    assert_eq!(stats.gauges, 2);
+    assert_eq!(stats.readings, 0);
+}
+
+#[tokio::test]
+async fn test_load_snapshot_rejects_unknown_station() {
+    let dir = tempfile::tempdir().unwrap();
+    write_snapshot(
+        dir.path(),
+        GAUGES_CSV,
+        Some("station_id,reading_datetime,cumulative_inches,incremental_inches\n99999,2024-11-03T06:00:00+00:00,0.1,0.1\n"),
+    );
+
+    let err = snapshot::load(dir.path()).await.unwrap_err();
+    assert!(matches!(err, SnapshotError::UnknownStation(ref id) if id == "99999"));
+}
+
+#[tokio::test]
+async fn test_load_snapshot_reports_bad_rows() {
+    let dir = tempfile::tempdir().unwrap();
+    write_snapshot(
+        dir.path(),
+        GAUGES_CSV,
+        Some("station_id,reading_datetime,cumulative_inches,incremental_inches\n59700,yesterday,0.1,0.1\n"),
+    );
+
+    let err = snapshot::load(dir.path()).await.unwrap_err();
+    assert!(matches!(err, SnapshotError::Csv { .. }));
+    assert!(err.to_string().contains(snapshot::READINGS_FILE));
+
+    let missing = snapshot::load(&dir.path().join("missing"))
+        .await
+        .unwrap_err();
+    assert!(matches!(missing, SnapshotError::Csv { .. }));
+}
@@ -0,0 +1,741 @@
+// Repository tests against the SQLite backend (`cargo test --features sqlite`)
+// Each test runs on its own in-memory database, so no server or cleanup is needed
+
+#![cfg(feature = "sqlite")]
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use rain_tracker_service::db::threshold_event_repository::{NewThresholdEvent, ThresholdChanges};
+use rain_tracker_service::db::{
+    AnnotationRepository, ConflictPolicy, CurrentConditionsRepository, DbError, DbPool,
+    FoprImportJobRepository, GaugeRepository, HourlyRainfallRepository, ImportChunkRepository,
+    MonthlyRainfallRepository, QualityGrade, ReadingRepository, ThresholdEventRepository,
+};
+use rain_tracker_service::fetcher::RainReading;
+use rain_tracker_service::gauge_list_fetcher::GaugeSummary as FetchedGauge;
+use rain_tracker_service::importers::excel_importer::HistoricalReading;
+use rain_tracker_service::services::ZoneService;
+use rain_tracker_service::units::Inches;
+use sqlx::sqlite::SqlitePoolOptions;
+
+mod sqlite_fixtures {
+    use super::*;
+
+    /// Live readings default to this station (see the rain_readings schema)
+    pub const STATION_ID: &str = "59700";
+
+    pub async fn setup_test_db() -> DbPool {
+        // One connection: every connection to `sqlite::memory:` opens a separate database
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to open in-memory SQLite database");
+
+        let db = DbPool::from(pool);
+        db.migrate().await.expect("Failed to run SQLite migrations");
+        db
+    }
+
+    pub fn fetched_gauge(station_id: &str, past_24h: f64) -> FetchedGauge {
+        FetchedGauge {
+            station_id: station_id.parse().unwrap(),
+            gauge_name: "Aztec Park".to_string(),
+            city_town: Some("Scottsdale".to_string()),
+            elevation_ft: Some(1465),
+            general_location: Some("Near Thunderbird & Frank Lloyd Wright".to_string()),
+            msp_forecast_zone: Some("Zone 1".to_string()),
+            rainfall_past_6h_inches: Some(Inches::new(0.2).unwrap()),
+            rainfall_past_24h_inches: Some(Inches::new(past_24h).unwrap()),
+        }
+    }
+
+    /// Register the gauge and its list summary, as gauge discovery does on SQLite
+    pub async fn register_gauge(db: &DbPool, station_id: &str, past_24h: f64) {
+        let gauge_repo = GaugeRepository::new(db.clone());
+        let gauge = fetched_gauge(station_id, past_24h);
+        assert!(gauge_repo.register_from_list(&gauge).await.unwrap());
+        gauge_repo.upsert_summaries(&[gauge]).await.unwrap();
+    }
+
+    pub fn reading(day: u32, hour: u32, cumulative: f64, incremental: f64) -> RainReading {
+        RainReading {
+            reading_datetime: Utc.with_ymd_and_hms(2024, 11, day, hour, 0, 0).unwrap(),
+            cumulative_inches: Inches::new(cumulative).unwrap(),
+            incremental_inches: Inches::new(incremental).unwrap(),
+        }
+    }
+}
+
+use sqlite_fixtures::*;
+
+#[tokio::test]
+async fn test_register_and_find_gauge() {
+    let db = setup_test_db().await;
+    register_gauge(&db, STATION_ID, 1.0).await;
+    let gauge_repo = GaugeRepository::new(db.clone());
+
+    // Registering again is a no-op
+    assert!(!gauge_repo
+        .register_from_list(&fetched_gauge(STATION_ID, 1.0))
+        .await
+        .unwrap());
+
+    assert!(gauge_repo.gauge_exists(STATION_ID).await.unwrap());
+    assert_eq!(gauge_repo.count().await.unwrap(), 1);
+
+    let summary = gauge_repo.find_by_id(STATION_ID).await.unwrap().unwrap();
+    assert_eq!(summary.gauge_name, "Aztec Park");
+    assert_eq!(summary.rainfall_past_24h_inches, Some(1.0));
+
+    let page = gauge_repo.find_paginated(0, 10).await.unwrap();
+    assert_eq!(page.len(), 1);
+
+    let detail = gauge_repo.find_detail(STATION_ID).await.unwrap().unwrap();
+    assert_eq!(detail.city_town.as_deref(), Some("Scottsdale"));
+    assert_eq!(
+        gauge_repo.find_status(STATION_ID).await.unwrap().as_deref(),
+        Some("Active")
+    );
+}
+
+#[tokio::test]
+async fn test_update_editable_metadata() {
+    let db = setup_test_db().await;
+    register_gauge(&db, STATION_ID, 1.0).await;
+    let gauge_repo = GaugeRepository::new(db.clone());
+
+    let mut gauge = gauge_repo.find_editable_metadata().await.unwrap().remove(0);
+    assert_eq!(gauge.city.as_deref(), Some("Scottsdale"));
+    gauge.city = Some("Paradise Valley".to_string());
+    gauge.latitude = Some(33.53);
+    gauge.longitude = Some(-111.94);
+    assert_eq!(
+        gauge_repo
+            .update_editable_metadata(std::slice::from_ref(&gauge))
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        gauge_repo.find_editable_metadata().await.unwrap(),
+        vec![gauge]
+    );
+}
+
+#[tokio::test]
+async fn test_record_geocode() {
+    let db = setup_test_db().await;
+    register_gauge(&db, STATION_ID, 1.0).await;
+    let gauge_repo = GaugeRepository::new(db.clone());
+    // Gauges registered from the list have no coordinates until metadata arrives
+    assert!(gauge_repo
+        .find_geocode_candidates(10)
+        .await
+        .unwrap()
+        .is_empty());
+    let mut gauge = gauge_repo.find_editable_metadata().await.unwrap().remove(0);
+    gauge.latitude = Some(33.53);
+    gauge.longitude = Some(-111.94);
+    gauge_repo
+        .update_editable_metadata(std::slice::from_ref(&gauge))
+        .await
+        .unwrap();
+
+    let candidates = gauge_repo.find_geocode_candidates(10).await.unwrap();
+    assert_eq!(candidates.len(), 1);
+    assert_eq!(candidates[0].city.as_deref(), Some("Scottsdale"));
+
+    gauge_repo
+        .record_geocode(
+            STATION_ID,
+            Some("Phoenix"),
+            Some("Pinnacle Peak"),
+            "nominatim",
+        )
+        .await
+        .unwrap();
+    assert!(gauge_repo
+        .find_geocode_candidates(10)
+        .await
+        .unwrap()
+        .is_empty());
+    let gauge = gauge_repo.find_editable_metadata().await.unwrap().remove(0);
+    assert_eq!(gauge.city.as_deref(), Some("Scottsdale"));
+}
+
+#[tokio::test]
+async fn test_record_elevation_sample() {
+    let db = setup_test_db().await;
+    register_gauge(&db, STATION_ID, 1.0).await;
+    let gauge_repo = GaugeRepository::new(db.clone());
+    let mut gauge = gauge_repo.find_editable_metadata().await.unwrap().remove(0);
+    gauge.latitude = Some(33.53);
+    gauge.longitude = Some(-111.94);
+    gauge.elevation_ft = None;
+    gauge_repo
+        .update_editable_metadata(std::slice::from_ref(&gauge))
+        .await
+        .unwrap();
+
+    let candidates = gauge_repo.find_elevation_candidates(10).await.unwrap();
+    assert_eq!(candidates.len(), 1);
+
+    gauge_repo
+        .record_elevation_sample(STATION_ID, Some(1342), "usgs-3dep")
+        .await
+        .unwrap();
+    assert!(gauge_repo
+        .find_elevation_candidates(10)
+        .await
+        .unwrap()
+        .is_empty());
+    let gauge = gauge_repo.find_editable_metadata().await.unwrap().remove(0);
+    assert_eq!(gauge.elevation_ft, Some(1342));
+}
+
+#[tokio::test]
+async fn test_forecast_zone_assignment() {
+    let db = setup_test_db().await;
+    register_gauge(&db, STATION_ID, 1.0).await;
+    let gauge_repo = GaugeRepository::new(db.clone());
+    let mut gauge = gauge_repo.find_editable_metadata().await.unwrap().remove(0);
+    gauge.latitude = Some(33.53);
+    gauge.longitude = Some(-111.94);
+    gauge_repo
+        .update_editable_metadata(std::slice::from_ref(&gauge))
+        .await
+        .unwrap();
+
+    let zones = |zone_id: &str| {
+        format!(
+            r#"{{"features": [{{"properties": {{"zone": "{zone_id}"}},
+                "geometry": {{"type": "Polygon", "coordinates": [
+                    [[-112.0, 33.5], [-111.9, 33.5], [-111.9, 33.6], [-112.0, 33.6], [-112.0, 33.5]]
+                ]}}}}]}}"#
+        )
+    };
+    let service = ZoneService::new(db.clone());
+    let report = service
+        .import_geojson(&zones("E1"), "zone", "name", "zones.geojson")
+        .await
+        .unwrap();
+    assert_eq!(report.assignment.gauges_in_zones, 1);
+    assert_eq!(report.assignment.gauges_changed, 1);
+
+    let collection = service.zone_collection().await.unwrap();
+    assert_eq!(collection.features.len(), 1);
+    assert_eq!(collection.features[0].geometry["type"], "Polygon");
+    assert_eq!(collection.features[0].properties.gauge_count, 1);
+
+    // Rankings prefer the polygon zone over the one named by the gauge list
+    let reading_repo = ReadingRepository::new(db.clone());
+    reading_repo
+        .insert_readings(&[reading(3, 6, 0.25, 0.25)])
+        .await
+        .unwrap();
+    let start = Utc.with_ymd_and_hms(2024, 11, 1, 0, 0, 0).unwrap();
+    let end = Utc.with_ymd_and_hms(2024, 12, 1, 0, 0, 0).unwrap();
+    let rows = reading_repo
+        .rank_stations_by_rainfall(start, end, false, false, 10)
+        .await
+        .unwrap();
+    assert_eq!(rows[0].msp_forecast_zone.as_deref(), Some("E1"));
+
+    // Replacing the zones drops E1, which clears the gauge's zone before reassignment
+    let report = service
+        .import_geojson(&zones("E2"), "zone", "name", "zones.geojson")
+        .await
+        .unwrap();
+    assert_eq!(report.zones_removed, 1);
+    assert_eq!(report.assignment.gauges_changed, 1);
+    let rows = reading_repo
+        .rank_stations_by_rainfall(start, end, false, false, 10)
+        .await
+        .unwrap();
+    assert_eq!(rows[0].msp_forecast_zone.as_deref(), Some("E2"));
+}
+
+#[tokio::test]
+async fn test_readings_and_monthly_summary() {
+    let db = setup_test_db().await;
+    register_gauge(&db, STATION_ID, 1.0).await;
+    let reading_repo = ReadingRepository::new(db.clone());
+    let monthly_repo = MonthlyRainfallRepository::new(db.clone());
+
+    let readings = vec![
+        reading(3, 6, 0.25, 0.25),
+        reading(3, 12, 0.75, 0.5),
+        reading(20, 8, 1.0, 0.25),
+    ];
+    assert_eq!(reading_repo.insert_readings(&readings).await.unwrap(), 3);
+    // Duplicates are skipped
+    assert_eq!(reading_repo.insert_readings(&readings).await.unwrap(), 0);
+
+    let start = Utc.with_ymd_and_hms(2024, 11, 1, 0, 0, 0).unwrap();
+    let end = Utc.with_ymd_and_hms(2024, 12, 1, 0, 0, 0).unwrap();
+    let found = reading_repo
+        .find_by_date_range(STATION_ID, start, end)
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 3);
+    assert_eq!(found[0].reading_datetime, readings[2].reading_datetime);
+    assert_eq!(
+        reading_repo
+            .count_by_date_range(STATION_ID, start, end)
+            .await
+            .unwrap(),
+        3
+    );
+    assert_eq!(
+        reading_repo.find_date_extent(STATION_ID).await.unwrap(),
+        Some((
+            NaiveDate::from_ymd_opt(2024, 11, 3).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 11, 20).unwrap()
+        ))
+    );
+
+    monthly_repo
+        .recalculate_monthly_summary(STATION_ID, 2024, 11, start, end)
+        .await
+        .unwrap();
+    let summaries = monthly_repo
+        .get_summaries_by_date_range(STATION_ID, start, end)
+        .await
+        .unwrap();
+    assert_eq!(summaries.len(), 1);
+    assert!((summaries[0].total_rainfall_inches - 1.0).abs() < 1e-9);
+    assert_eq!(summaries[0].reading_count, 3);
+    assert_eq!(summaries[0].flagged_count, Some(0));
+    assert_eq!(summaries[0].footnoted_count, Some(0));
+    assert_eq!(summaries[0].quality_grade(), Some(QualityGrade::A));
+
+    // Footnote markers stored as JSON text are counted too
+    let footnoted = HistoricalReading {
+        station_id: STATION_ID.parse().unwrap(),
+        reading_date: NaiveDate::from_ymd_opt(2024, 12, 2).unwrap(),
+        rainfall_inches: Inches::new(0.4).unwrap(),
+        footnote_marker: Some("2".to_string()),
+    };
+    reading_repo
+        .bulk_insert_historical_readings(STATION_ID, "test", &[footnoted])
+        .await
+        .unwrap();
+    // Daily totals get their water year running total as the cumulative value
+    assert_eq!(
+        reading_repo
+            .backfill_cumulative(STATION_ID, "test", 2025)
+            .await
+            .unwrap(),
+        vec![(2024, 12)]
+    );
+    let (dec_start, dec_end) = (end, Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap());
+    monthly_repo
+        .recalculate_monthly_summary(STATION_ID, 2024, 12, dec_start, dec_end)
+        .await
+        .unwrap();
+    let december = monthly_repo
+        .get_summaries_by_date_range(STATION_ID, dec_start, dec_end)
+        .await
+        .unwrap();
+    assert_eq!(december[0].footnoted_count, Some(1));
+    assert_eq!(december[0].quality_grade(), Some(QualityGrade::C));
+
+    let discrepancies = monthly_repo
+        .find_summary_discrepancies(Some(STATION_ID), start, end)
+        .await
+        .unwrap();
+    assert!(discrepancies.is_empty(), "{discrepancies:?}");
+
+    // A reading the summary has not seen shows up as a discrepancy
+    reading_repo
+        .insert_readings(&[reading(25, 9, 1.5, 0.5)])
+        .await
+        .unwrap();
+    let discrepancies = monthly_repo
+        .find_summary_discrepancies(Some(STATION_ID), start, end)
+        .await
+        .unwrap();
+    assert_eq!(discrepancies.len(), 1);
+    assert_eq!(discrepancies[0].actual_reading_count, Some(4));
+}
+
+#[tokio::test]
+async fn test_daily_totals_zero_fill() {
+    let db = setup_test_db().await;
+    register_gauge(&db, STATION_ID, 1.0).await;
+    let reading_repo = ReadingRepository::new(db.clone());
+
+    reading_repo
+        .insert_readings(&[
+            reading(2, 6, 0.25, 0.25),
+            reading(2, 18, 0.75, 0.5),
+            reading(4, 6, 1.0, 0.25),
+            reading(6, 6, 1.5, 0.5),
+        ])
+        .await
+        .unwrap();
+
+    let day = |d: u32| NaiveDate::from_ymd_opt(2024, 11, d).unwrap();
+    let totals = reading_repo
+        .daily_totals(STATION_ID, day(1), day(5))
+        .await
+        .unwrap();
+    assert_eq!(
+        totals,
+        vec![
+            (day(1), 0.0),
+            (day(2), 0.75),
+            (day(3), 0.0),
+            (day(4), 0.25),
+            (day(5), 0.0)
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_hourly_summaries() {
+    let db = setup_test_db().await;
+    register_gauge(&db, STATION_ID, 1.0).await;
+    let reading_repo = ReadingRepository::new(db.clone());
+    let hourly_repo = HourlyRainfallRepository::new(db.clone());
+
+    reading_repo
+        .insert_readings(&[
+            reading(3, 6, 0.25, 0.25),
+            reading(3, 7, 0.75, 0.5),
+            reading(4, 6, 1.0, 0.25),
+        ])
+        .await
+        .unwrap();
+    // Daily import totals are left out
+    let daily = HistoricalReading {
+        station_id: STATION_ID.parse().unwrap(),
+        reading_date: NaiveDate::from_ymd_opt(2024, 11, 10).unwrap(),
+        rainfall_inches: Inches::new(2.0).unwrap(),
+        footnote_marker: None,
+    };
+    reading_repo
+        .bulk_insert_historical_readings(STATION_ID, "test", &[daily])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        hourly_repo
+            .recalculate_hours(None, None, None)
+            .await
+            .unwrap(),
+        3
+    );
+
+    let start = Utc.with_ymd_and_hms(2024, 11, 1, 0, 0, 0).unwrap();
+    let end = Utc.with_ymd_and_hms(2024, 12, 1, 0, 0, 0).unwrap();
+    let hours = hourly_repo
+        .get_summaries_by_date_range(STATION_ID, start, end)
+        .await
+        .unwrap();
+    let starts: Vec<_> = hours.iter().map(|h| h.hour_start).collect();
+    assert_eq!(
+        starts,
+        vec![
+            reading(3, 6, 0.0, 0.0).reading_datetime,
+            reading(3, 7, 0.0, 0.0).reading_datetime,
+            reading(4, 6, 0.0, 0.0).reading_datetime
+        ]
+    );
+    assert_eq!(hours[1].total_rainfall_inches, 0.5);
+    assert_eq!(hours[1].reading_count, 1);
+
+    // Readings an hour apart never share a 1-hour window
+    let days = hourly_repo
+        .get_daily_intensity(STATION_ID, start.date_naive(), end.date_naive())
+        .await
+        .unwrap();
+    assert_eq!(days.len(), 2);
+    assert_eq!(days[0].date, NaiveDate::from_ymd_opt(2024, 11, 3).unwrap());
+    assert_eq!(days[0].max_15min_intensity_in_per_hr, 2.0);
+    assert_eq!(days[0].max_1h_intensity_in_per_hr, 0.5);
+    assert_eq!(days[1].max_15min_intensity_in_per_hr, 1.0);
+}
+
+#[tokio::test]
+async fn test_upsert_readings_revises_inside_window() {
+    let db = setup_test_db().await;
+    register_gauge(&db, STATION_ID, 1.0).await;
+    let reading_repo = ReadingRepository::new(db.clone());
+
+    reading_repo
+        .insert_readings(&[reading(3, 6, 0.25, 0.25), reading(4, 6, 0.5, 0.25)])
+        .await
+        .unwrap();
+
+    // Nov 3 is older than the window and stays; Nov 4 is revised; Nov 5 is new
+    let revisable_from = Utc.with_ymd_and_hms(2024, 11, 4, 0, 0, 0).unwrap();
+    let written = reading_repo
+        .upsert_readings(
+            &[
+                reading(3, 6, 0.3, 0.3),
+                reading(4, 6, 0.75, 0.45),
+                reading(5, 6, 1.0, 0.25),
+            ],
+            revisable_from,
+        )
+        .await
+        .unwrap();
+    assert_eq!((written.inserted, written.updated), (1, 1));
+    assert_eq!(
+        written.changed,
+        vec![
+            reading(4, 6, 0.0, 0.0).reading_datetime,
+            reading(5, 6, 0.0, 0.0).reading_datetime
+        ]
+    );
+
+    let start = Utc.with_ymd_and_hms(2024, 11, 1, 0, 0, 0).unwrap();
+    let end = Utc.with_ymd_and_hms(2024, 12, 1, 0, 0, 0).unwrap();
+    let found = reading_repo
+        .find_by_date_range(STATION_ID, start, end)
+        .await
+        .unwrap();
+    let cumulative: Vec<f64> = found.iter().map(|r| r.cumulative_inches.value()).collect();
+    assert_eq!(cumulative, vec![1.0, 0.75, 0.25]);
+
+    // Unchanged values are neither inserted nor revised
+    let written = reading_repo
+        .upsert_readings(&[reading(4, 6, 0.75, 0.45)], revisable_from)
+        .await
+        .unwrap();
+    assert_eq!((written.inserted, written.updated), (0, 0));
+}
+
+#[tokio::test]
+async fn test_import_by_month_commits_and_resumes() {
+    let db = setup_test_db().await;
+    register_gauge(&db, STATION_ID, 0.0).await;
+    let repo = ImportChunkRepository::new(db.clone()).with_derived_cumulative();
+    let daily = |month, day, inches| HistoricalReading {
+        station_id: STATION_ID.parse().unwrap(),
+        reading_date: NaiveDate::from_ymd_opt(2024, month, day).unwrap(),
+        rainfall_inches: Inches::new(inches).unwrap(),
+        footnote_marker: None,
+    };
+    let readings = [daily(10, 2, 0.5), daily(11, 4, 0.25)];
+
+    let imported = repo
+        .import_by_month(STATION_ID, "fopr_import_59700", &readings)
+        .await
+        .unwrap();
+    assert_eq!(imported.inserted, 2);
+    assert_eq!(imported.months_committed, vec![(2024, 10), (2024, 11)]);
+
+    let (start, end) = (
+        Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap(),
+        Utc.with_ymd_and_hms(2024, 12, 1, 0, 0, 0).unwrap(),
+    );
+    let summaries = MonthlyRainfallRepository::new(db.clone())
+        .get_summaries_by_date_range(STATION_ID, start, end)
+        .await
+        .unwrap();
+    assert_eq!(summaries.len(), 2);
+    assert_eq!(summaries[1].max_cumulative_inches, Some(0.75));
+
+    let rerun = repo
+        .import_by_month(STATION_ID, "fopr_import_59700", &readings)
+        .await
+        .unwrap();
+    assert_eq!(rerun.inserted, 0);
+    assert_eq!(rerun.months_resumed, 2);
+
+    // A corrected October overwrites the stored reading and carries into November
+    let corrected = repo
+        .with_conflict_policy(ConflictPolicy::Update)
+        .import_by_month(
+            STATION_ID,
+            "fopr_import_59700",
+            &[daily(10, 2, 1.0), daily(11, 4, 0.25)],
+        )
+        .await
+        .unwrap();
+    assert_eq!(corrected.updated, 1);
+    assert_eq!(corrected.duplicates, 1);
+    let summaries = MonthlyRainfallRepository::new(db.clone())
+        .get_summaries_by_date_range(STATION_ID, start, end)
+        .await
+        .unwrap();
+    assert_eq!(summaries[0].total_rainfall_inches, 1.0);
+    assert_eq!(summaries[1].max_cumulative_inches, Some(1.25));
+}
+
+#[tokio::test]
+async fn test_current_conditions_refresh() {
+    let db = setup_test_db().await;
+    register_gauge(&db, STATION_ID, 1.0).await;
+    ReadingRepository::new(db.clone())
+        .insert_readings(&[reading(3, 6, 0.25, 0.25)])
+        .await
+        .unwrap();
+    let start = Utc.with_ymd_and_hms(2024, 11, 1, 0, 0, 0).unwrap();
+    let end = Utc.with_ymd_and_hms(2024, 12, 1, 0, 0, 0).unwrap();
+    MonthlyRainfallRepository::new(db.clone())
+        .recalculate_monthly_summary(STATION_ID, 2024, 11, start, end)
+        .await
+        .unwrap();
+
+    let repo = CurrentConditionsRepository::new(db.clone());
+    let refreshed_at = Utc::now();
+    assert_eq!(repo.refresh(2025, refreshed_at).await.unwrap(), 1);
+
+    let active = vec!["Active".to_string()];
+    let conditions = repo.find_by_status(&active).await.unwrap();
+    assert_eq!(conditions.len(), 1);
+    assert_eq!(conditions[0].status, "Active");
+    assert!((conditions[0].water_year_to_date_inches - 0.25).abs() < 1e-9);
+    assert_eq!(
+        conditions[0].latest_reading_at,
+        Some(Utc.with_ymd_and_hms(2024, 11, 3, 6, 0, 0).unwrap())
+    );
+    assert!(repo.find_refreshed_at(&active).await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn test_threshold_events_open_raise_and_close() {
+    let db = setup_test_db().await;
+    register_gauge(&db, STATION_ID, 1.0).await;
+    let repo = ThresholdEventRepository::new(db.clone());
+    let stations = vec![STATION_ID.to_string()];
+    // Live readings before the crossing feed the event's peak intensities
+    ReadingRepository::new(db.clone())
+        .insert_readings(&[reading(3, 6, 0.25, 0.25), reading(3, 7, 0.75, 0.5)])
+        .await
+        .unwrap();
+    let observed_at = reading(3, 8, 0.0, 0.0).reading_datetime;
+
+    let opened = ThresholdChanges {
+        opened: vec![NewThresholdEvent {
+            station_id: STATION_ID.to_string(),
+            threshold_inches: 1.0,
+            rainfall_24h_inches: 1.2,
+        }],
+        ..Default::default()
+    };
+    repo.apply(&opened, observed_at).await.unwrap();
+    // A second open for the same threshold is ignored while the first is open
+    repo.apply(&opened, observed_at).await.unwrap();
+
+    let open = repo.find_open(&stations).await.unwrap();
+    assert_eq!(open.len(), 1);
+
+    let id = open[0].id;
+    repo.apply(
+        &ThresholdChanges {
+            raised_peaks: vec![(id, 2.5)],
+            ..Default::default()
+        },
+        observed_at,
+    )
+    .await
+    .unwrap();
+    repo.apply(
+        &ThresholdChanges {
+            closed: vec![id],
+            ..Default::default()
+        },
+        observed_at,
+    )
+    .await
+    .unwrap();
+
+    assert!(repo.find_open(&stations).await.unwrap().is_empty());
+    let events = repo
+        .find_by_station(STATION_ID, Some(1.0), 0, 10)
+        .await
+        .unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(
+        repo.count_by_station(STATION_ID, Some(1.0)).await.unwrap(),
+        1
+    );
+    assert_eq!(events[0].peak_rainfall_24h_inches, 2.5);
+    assert!(events[0].ended_at.is_some());
+    assert_eq!(events[0].max_15min_intensity_in_per_hr, Some(2.0));
+    assert_eq!(events[0].max_1h_intensity_in_per_hr, Some(0.5));
+
+    let storm = repo.find_by_id(id).await.unwrap().unwrap();
+    let gauges = repo
+        .find_footprint(storm.crossed_at - chrono::TimeDelta::hours(24), observed_at)
+        .await
+        .unwrap();
+    assert_eq!(gauges.len(), 1);
+    assert_eq!(gauges[0].station_id, STATION_ID);
+    assert_eq!(gauges[0].rainfall_inches, 0.75);
+    assert_eq!(gauges[0].reading_count, 2);
+    assert!(repo.find_by_id(id + 1).await.unwrap().is_none());
+
+    let buckets = repo
+        .find_timeline_buckets(
+            storm.crossed_at - chrono::TimeDelta::hours(24),
+            observed_at,
+            7200,
+        )
+        .await
+        .unwrap();
+    assert_eq!(buckets.len(), 1);
+    assert_eq!(
+        buckets[0].bucket_start,
+        reading(3, 6, 0.0, 0.0).reading_datetime
+    );
+    assert_eq!(buckets[0].rainfall_inches, 0.75);
+}
+
+#[tokio::test]
+async fn test_status_change_and_annotations() {
+    let db = setup_test_db().await;
+    register_gauge(&db, STATION_ID, 1.0).await;
+    let gauge_repo = GaugeRepository::new(db.clone());
+    let effective = NaiveDate::from_ymd_opt(2024, 12, 1).unwrap();
+
+    let change = gauge_repo
+        .change_status(STATION_ID, "Active", "Inactive", effective, None, "admin")
+        .await
+        .unwrap();
+    assert!(change.is_some());
+    // The expected current status no longer matches
+    assert!(gauge_repo
+        .change_status(STATION_ID, "Active", "Inactive", effective, None, "admin")
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(
+        gauge_repo
+            .find_status_history(STATION_ID)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+
+    let annotations = AnnotationRepository::new(db.clone());
+    let note = annotations
+        .insert(STATION_ID, Some(effective), None, "Gauge removed", "admin")
+        .await
+        .unwrap()
+        .expect("gauge exists");
+    assert!(annotations
+        .insert("00000", None, None, "Unknown gauge", "admin")
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(
+        annotations.find_by_station(STATION_ID).await.unwrap().len(),
+        1
+    );
+    assert!(annotations.delete(STATION_ID, note.id).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_fopr_jobs_are_postgres_only() {
+    let db = setup_test_db().await;
+    let job_repo = FoprImportJobRepository::new(db);
+
+    let result = job_repo.job_exists(STATION_ID).await;
+    assert!(matches!(result, Err(DbError::Unsupported("sqlite"))));
+}
@@ -0,0 +1,211 @@
+// Integration tests for SummaryService
+// Covers scoped recalculation and cleanup of orphaned monthly summaries
+
+mod common;
+
+use chrono::NaiveDate;
+use rain_tracker_service::db::{MonthlyRainfallRepository, ReadingRepository};
+use rain_tracker_service::importers::excel_importer::HistoricalReading;
+use rain_tracker_service::services::summary_service::RecalcScope;
+use rain_tracker_service::services::SummaryService;
+use rain_tracker_service::units::Inches;
+use serial_test::serial;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+
+mod summary_fixtures {
+    use super::*;
+
+    pub async fn setup_test_db() -> PgPool {
+        let database_url = crate::common::database_url().await;
+
+        let pool = PgPoolOptions::new()
+            .max_connections(5)
+            .connect(&database_url)
+            .await
+            .expect("Failed to connect to test database");
+
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    pub async fn create_test_gauge(pool: &PgPool, station_id: &str) {
+        sqlx::query!(
+            r#"
+            INSERT INTO gauges (station_id, station_name, station_type, latitude, longitude, county, status)
+            VALUES ($1, $2, 'Rain', 33.5, -112.0, 'Test County', 'Active')
+            ON CONFLICT (station_id) DO NOTHING
+            "#,
+            station_id,
+            format!("Test Gauge {}", station_id)
+        )
+        .execute(pool)
+        .await
+        .ok();
+    }
+
+    pub async fn cleanup(pool: &PgPool, station_id: &str) {
+        sqlx::query!(
+            "DELETE FROM monthly_rainfall_summary WHERE station_id = $1",
+            station_id
+        )
+        .execute(pool)
+        .await
+        .ok();
+        sqlx::query!(
+            "DELETE FROM rain_readings WHERE station_id = $1",
+            station_id
+        )
+        .execute(pool)
+        .await
+        .ok();
+        sqlx::query!("DELETE FROM gauges WHERE station_id = $1", station_id)
+            .execute(pool)
+            .await
+            .ok();
+    }
+
+    pub async fn insert_reading(pool: &PgPool, station_id: &str, date: NaiveDate, inches: f64) {
+        ReadingRepository::new(pool.clone())
+            .bulk_insert_historical_readings(
+                station_id,
+                "test",
+                &[HistoricalReading {
+                    station_id: station_id.parse().unwrap(),
+                    reading_date: date,
+                    rainfall_inches: Inches::new(inches).unwrap(),
+                    footnote_marker: None,
+                }],
+            )
+            .await
+            .expect("Failed to insert reading");
+    }
+
+    pub async fn summary_count(pool: &PgPool, station_id: &str) -> i64 {
+        sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!" FROM monthly_rainfall_summary WHERE station_id = $1"#,
+            station_id
+        )
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+}
+
+use summary_fixtures::*;
+
+#[tokio::test]
+#[serial]
+async fn test_recalculate_station_scope() {
+    let pool = setup_test_db().await;
+    let station_id = "TEST_SUMMARY_001";
+    cleanup(&pool, station_id).await;
+    create_test_gauge(&pool, station_id).await;
+
+    insert_reading(
+        &pool,
+        station_id,
+        NaiveDate::from_ymd_opt(2124, 1, 3).unwrap(),
+        0.2,
+    )
+    .await;
+    insert_reading(
+        &pool,
+        station_id,
+        NaiveDate::from_ymd_opt(2124, 1, 9).unwrap(),
+        0.3,
+    )
+    .await;
+    insert_reading(
+        &pool,
+        station_id,
+        NaiveDate::from_ymd_opt(2124, 4, 2).unwrap(),
+        1.0,
+    )
+    .await;
+
+    let service =
+        SummaryService::new(MonthlyRainfallRepository::new(pool.clone())).with_concurrency(2);
+    let scope = RecalcScope {
+        station_id: Some(station_id.to_string()),
+        ..Default::default()
+    };
+
+    let mut progress = Vec::new();
+    let stats = service
+        .recalculate(&scope, |done, total| progress.push((done, total)))
+        .await
+        .expect("Recalculation failed");
+
+    assert_eq!(stats.months_recalculated, 2);
+    assert_eq!(stats.orphaned_summaries_deleted, 0);
+    assert_eq!(progress.last(), Some(&(2, 2)));
+    assert_eq!(summary_count(&pool, station_id).await, 2);
+
+    cleanup(&pool, station_id).await;
+}
+
+#[tokio::test]
+#[serial]
+async fn test_recalculate_date_range_deletes_orphans() {
+    let pool = setup_test_db().await;
+    let station_id = "TEST_SUMMARY_002";
+    cleanup(&pool, station_id).await;
+    create_test_gauge(&pool, station_id).await;
+
+    insert_reading(
+        &pool,
+        station_id,
+        NaiveDate::from_ymd_opt(2124, 2, 10).unwrap(),
+        0.5,
+    )
+    .await;
+    insert_reading(
+        &pool,
+        station_id,
+        NaiveDate::from_ymd_opt(2124, 6, 10).unwrap(),
+        0.7,
+    )
+    .await;
+
+    let service = SummaryService::new(MonthlyRainfallRepository::new(pool.clone()));
+    let station_scope = RecalcScope {
+        station_id: Some(station_id.to_string()),
+        ..Default::default()
+    };
+    service
+        .recalculate(&station_scope, |_, _| {})
+        .await
+        .expect("Initial recalculation failed");
+    assert_eq!(summary_count(&pool, station_id).await, 2);
+
+    // Roll back February's readings; its summary becomes orphaned
+    sqlx::query!(
+        "DELETE FROM rain_readings WHERE station_id = $1 AND reading_datetime < '2124-03-01'",
+        station_id
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let feb_scope = RecalcScope {
+        station_id: Some(station_id.to_string()),
+        start_date: NaiveDate::from_ymd_opt(2124, 2, 1),
+        end_date: NaiveDate::from_ymd_opt(2124, 2, 29),
+    };
+    let stats = service
+        .recalculate(&feb_scope, |_, _| {})
+        .await
+        .expect("Range recalculation failed");
+
+    assert_eq!(stats.months_recalculated, 0);
+    assert_eq!(stats.orphaned_summaries_deleted, 1);
+    // June's summary is outside the range and must survive
+    assert_eq!(summary_count(&pool, station_id).await, 1);
+
+    cleanup(&pool, station_id).await;
+}
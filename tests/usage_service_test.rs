@@ -0,0 +1,82 @@
+// Tests for UsageService: requests and bytes are counted per UTC month against quotas
+
+mod common;
+
+use std::sync::Arc;
+
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use rain_tracker_service::clock::FixedClock;
+use rain_tracker_service::db::{User, UserRepository};
+use rain_tracker_service::services::usage_service::UsageError;
+use rain_tracker_service::services::UsageService;
+use sqlx::PgPool;
+
+fn feb() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 2, 12, 8, 0, 0).unwrap()
+}
+
+async fn user(pool: &PgPool, request_quota: Option<i64>, byte_quota: Option<i64>) -> User {
+    UserRepository::new(pool.clone())
+        .insert("usage-test", &"0".repeat(64), request_quota, byte_quota)
+        .await
+        .unwrap()
+}
+
+fn service(pool: &PgPool, at: DateTime<Utc>) -> UsageService {
+    UsageService::new(pool.clone()).with_clock(Arc::new(FixedClock(at)))
+}
+
+#[tokio::test]
+async fn test_request_quota_resets_each_month() {
+    let pool = common::isolated_db().await;
+    let user = user(&pool, Some(2), None).await;
+    let february = service(&pool, feb());
+
+    let month = NaiveDate::from_ymd_opt(2025, 2, 1).unwrap();
+    assert_eq!(february.claim(&user).await.unwrap(), month);
+    assert_eq!(february.claim(&user).await.unwrap(), month);
+    let Err(UsageError::QuotaExceeded(report)) = february.claim(&user).await else {
+        panic!("third request should be refused");
+    };
+    assert_eq!(report.request_count, 2, "refused requests are not counted");
+    assert_eq!(
+        report.resets_at,
+        Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap()
+    );
+
+    let march = service(&pool, Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap());
+    march.claim(&user).await.unwrap();
+    let report = march.usage(&user).await.unwrap();
+    assert_eq!(report.month, NaiveDate::from_ymd_opt(2025, 3, 1).unwrap());
+    assert_eq!(report.request_count, 1);
+}
+
+#[tokio::test]
+async fn test_byte_quota_refuses_the_next_request() {
+    let pool = common::isolated_db().await;
+    let user = user(&pool, None, Some(1000)).await;
+    let service = service(&pool, feb());
+
+    // The response that crosses the quota still completes
+    let month = service.claim(&user).await.unwrap();
+    service.record_bytes(user.id, month, 600).await.unwrap();
+    let month = service.claim(&user).await.unwrap();
+    service.record_bytes(user.id, month, 600).await.unwrap();
+
+    let Err(UsageError::QuotaExceeded(report)) = service.claim(&user).await else {
+        panic!("request after the byte quota should be refused");
+    };
+    assert_eq!(report.response_bytes, 1200);
+    assert_eq!(report.monthly_byte_quota, Some(1000));
+}
+
+#[tokio::test]
+async fn test_concurrent_requests_do_not_overshoot() {
+    let pool = common::isolated_db().await;
+    let user = user(&pool, Some(5), None).await;
+    let service = service(&pool, feb());
+
+    let claims = futures::future::join_all((0..20).map(|_| service.claim(&user))).await;
+    assert_eq!(claims.iter().filter(|c| c.is_ok()).count(), 5);
+    assert_eq!(service.usage(&user).await.unwrap().request_count, 5);
+}